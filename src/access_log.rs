@@ -0,0 +1,322 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+//! Per-request structured access log.
+//!
+//! [`access_log_middleware`] opens an [`AccessRecord`] for every HTTP request
+//! and writes it as one JSON line to the [`AccessLog`] sink once the response
+//! is ready. Code further down the stack fills in what only it knows through
+//! [`annotate`]: the auth middleware sets the client, the MCP handlers the
+//! session and JSON-RPC method, `gateway_invoke` the backend, tool, cache and
+//! idempotency outcome and the error class of a failed call.
+//!
+//! ```json
+//! {"timestamp":"2026-10-15T09:12:03.114Z","http_method":"POST","path":"/mcp",
+//!  "status":200,"client":"ci-bot","session":"3f2…","method":"tools/call",
+//!  "backend":"github","tool":"search_issues","latency_ms":182,
+//!  "result_bytes":5120,"cache":"miss","idempotency":null,"error_class":null}
+//! ```
+//!
+//! For a streamed response (SSE) `latency_ms` is the time to the response
+//! headers and `result_bytes` is `null`.
+//!
+//! The sink rotates by size: `access.jsonl` → `access.jsonl.1` → … →
+//! `access.jsonl.<max_files>`, the oldest dropped. It is kept apart from
+//! application tracing, so analytics never parse human-oriented log lines.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    body::HttpBody as _,
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::config::AccessLogConfig;
+
+tokio::task_local! {
+    /// The record of the request being served.
+    static RECORD: Arc<Mutex<AccessRecord>>;
+}
+
+/// Outcome of the response cache for a tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheStatus {
+    /// Served from the response cache.
+    Hit,
+    /// Looked up, not found; the backend was called.
+    Miss,
+}
+
+/// Outcome of duplicate suppression for a tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdempotencyStatus {
+    /// A completed result for the key was replayed.
+    Replayed,
+    /// The key was registered and the call went through.
+    Registered,
+}
+
+/// One access-log line.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccessRecord {
+    /// RFC 3339 time the request completed.
+    pub timestamp: String,
+    /// HTTP method.
+    pub http_method: String,
+    /// Request path (without the query string).
+    pub path: String,
+    /// HTTP status code.
+    pub status: u16,
+    /// Authenticated client name.
+    pub client: Option<String>,
+    /// MCP session ID.
+    pub session: Option<String>,
+    /// JSON-RPC method.
+    pub method: Option<String>,
+    /// Backend the request reached.
+    pub backend: Option<String>,
+    /// Tool invoked.
+    pub tool: Option<String>,
+    /// Time from receiving the request to the response headers.
+    pub latency_ms: u64,
+    /// Response body size, when known up front.
+    pub result_bytes: Option<u64>,
+    /// Response cache outcome.
+    pub cache: Option<CacheStatus>,
+    /// Duplicate suppression outcome.
+    pub idempotency: Option<IdempotencyStatus>,
+    /// Why the request failed: the gateway error kind, or `http_<status>`.
+    pub error_class: Option<String>,
+}
+
+/// Update the record of the current request. No-op outside a logged
+/// request.
+pub fn annotate(f: impl FnOnce(&mut AccessRecord)) {
+    let _ = RECORD.try_with(|record| f(&mut record.lock()));
+}
+
+/// Size-rotated JSONL sink.
+pub struct AccessLog {
+    path: PathBuf,
+    max_size: u64,
+    max_files: u32,
+    file: Mutex<Sink>,
+}
+
+struct Sink {
+    writer: BufWriter<File>,
+    size: u64,
+}
+
+impl std::fmt::Debug for AccessLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccessLog")
+            .field("path", &self.path)
+            .field("max_size", &self.max_size)
+            .field("max_files", &self.max_files)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AccessLog {
+    /// Open the sink configured by `config`, creating its directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` if the directory or file cannot be created.
+    pub fn open(config: &AccessLogConfig) -> io::Result<Self> {
+        let path = config.file_path();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let sink = Sink::open(&path)?;
+        Ok(Self {
+            path,
+            max_size: config.max_size_bytes,
+            max_files: config.max_files,
+            file: Mutex::new(sink),
+        })
+    }
+
+    /// Append `record` as one line, rotating first if it would overflow the
+    /// file. Failures are logged, never propagated to the request.
+    pub fn write(&self, record: &AccessRecord) {
+        let Ok(mut line) = serde_json::to_vec(record) else {
+            return;
+        };
+        line.push(b'\n');
+        let mut sink = self.file.lock();
+        if sink.size > 0
+            && sink.size + line.len() as u64 > self.max_size
+            && let Err(e) = self.rotate(&mut sink)
+        {
+            warn!(path = %self.path.display(), error = %e, "Access log rotation failed");
+        }
+        match sink
+            .writer
+            .write_all(&line)
+            .and_then(|()| sink.writer.flush())
+        {
+            Ok(()) => sink.size += line.len() as u64,
+            Err(e) => warn!(path = %self.path.display(), error = %e, "Access log write failed"),
+        }
+    }
+
+    fn rotate(&self, sink: &mut Sink) -> io::Result<()> {
+        sink.writer.flush()?;
+        if self.max_files == 0 {
+            *sink = Sink::create(&self.path)?;
+            return Ok(());
+        }
+        for n in (1..self.max_files).rev() {
+            let from = rotated(&self.path, n);
+            if from.exists() {
+                std::fs::rename(&from, rotated(&self.path, n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated(&self.path, 1))?;
+        *sink = Sink::open(&self.path)?;
+        Ok(())
+    }
+}
+
+impl Sink {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            writer: BufWriter::new(file),
+            size,
+        })
+    }
+
+    fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            size: 0,
+        })
+    }
+}
+
+/// `<path>.<n>`.
+fn rotated(path: &Path, n: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+/// Axum middleware writing one [`AccessRecord`] per request to `log`
+/// (pass-through when the access log is disabled).
+pub async fn access_log_middleware(
+    State(log): State<Option<Arc<AccessLog>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(log) = log else {
+        return next.run(request).await;
+    };
+    let started = Instant::now();
+    let record = Arc::new(Mutex::new(AccessRecord {
+        http_method: request.method().to_string(),
+        path: request.uri().path().to_string(),
+        ..Default::default()
+    }));
+    let response = RECORD.scope(Arc::clone(&record), next.run(request)).await;
+
+    let mut record = record.lock().clone();
+    record.timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    record.status = response.status().as_u16();
+    record.latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    record.result_bytes = response.body().size_hint().exact();
+    if record.error_class.is_none() && record.status >= 400 {
+        record.error_class = Some(format!("http_{}", record.status));
+    }
+    log.write(&record);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &Path, max_size_bytes: u64, max_files: u32) -> AccessLogConfig {
+        AccessLogConfig {
+            enabled: true,
+            path: dir.join("access.jsonl").to_string_lossy().into_owned(),
+            max_size_bytes,
+            max_files,
+        }
+    }
+
+    fn record(tool: &str) -> AccessRecord {
+        AccessRecord {
+            http_method: "POST".to_string(),
+            path: "/mcp".to_string(),
+            status: 200,
+            tool: Some(tool.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn writes_one_json_line_per_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AccessLog::open(&config(dir.path(), 1 << 20, 2)).unwrap();
+
+        log.write(&record("a"));
+        log.write(&record("b"));
+
+        let text = std::fs::read_to_string(dir.path().join("access.jsonl")).unwrap();
+        let tools: Vec<String> = text
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["tool"].to_string())
+            .collect();
+        assert_eq!(tools, ["\"a\"", "\"b\""]);
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_max_files() {
+        // GIVEN: a sink that fits one record per file and keeps two rotations
+        let dir = tempfile::tempdir().unwrap();
+        let log = AccessLog::open(&config(dir.path(), 10, 2)).unwrap();
+
+        // WHEN: four records are written
+        for tool in ["a", "b", "c", "d"] {
+            log.write(&record(tool));
+        }
+
+        // THEN: the newest is live, the two before it rotated, the oldest gone
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+        assert!(read("access.jsonl").contains("\"d\""));
+        assert!(read("access.jsonl.1").contains("\"c\""));
+        assert!(read("access.jsonl.2").contains("\"b\""));
+        assert!(!dir.path().join("access.jsonl.3").exists());
+    }
+
+    #[tokio::test]
+    async fn annotate_only_reaches_the_scoped_record() {
+        let record = Arc::new(Mutex::new(AccessRecord::default()));
+
+        RECORD
+            .scope(Arc::clone(&record), async {
+                annotate(|r| r.cache = Some(CacheStatus::Hit));
+            })
+            .await;
+        annotate(|r| r.cache = Some(CacheStatus::Miss));
+
+        assert_eq!(record.lock().cache, Some(CacheStatus::Hit));
+    }
+}
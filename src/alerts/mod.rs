@@ -0,0 +1,763 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Operator alerts for backends taken out of service.
+//!
+//! The error budget auto-kills backends, circuit breakers open, and the
+//! per-capability budget disables single tools — all silently apart from a
+//! log line. This module turns those transitions into [`OperatorAlert`]s and
+//! delivers each one to every configured sink:
+//!
+//! - a generic webhook (JSON POST, same envelope as SLO alerts),
+//! - a Slack-compatible incoming webhook (`{"text": …}`),
+//! - connected MCP clients as a `notifications/message` logging notification.
+//!
+//! Failed scheduled jobs ([`crate::scheduler`]) and failing background OAuth
+//! token refreshes are reported the same way, as are operator-defined
+//! threshold rules (`alerts.rules`, see [`RuleWatch`]).
+//!
+//! Error-budget events are pushed by the meta-MCP handler through an
+//! [`AlertNotifier`]; breaker trips, refresh failures and rule metrics are
+//! detected by polling each backend's counters, so the hot paths are
+//! untouched.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::backend::BackendRegistry;
+use crate::cache::ResponseCache;
+use crate::config::{AlertMetric, AlertRuleConfig, AlertsConfig};
+use crate::gateway::streaming::{NotificationMultiplexer, TaggedNotification};
+use crate::scheduler::ScheduleAction;
+
+/// Timeout for webhook deliveries.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Alerts buffered for the delivery task before the oldest are dropped.
+const CHANNEL_CAPACITY: usize = 256;
+/// MCP logger name on `notifications/message`.
+const MCP_LOGGER: &str = "mcp-gateway.alerts";
+
+/// What happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// The error budget killed a backend.
+    BackendAutoKilled,
+    /// An auto-killed backend passed its health probe and is serving again.
+    BackendRevived,
+    /// A backend's circuit breaker opened.
+    CircuitOpened,
+    /// The per-capability error budget disabled one tool.
+    CapabilityDisabled,
+    /// A scheduled playbook run or tool call failed.
+    ScheduledJobFailed,
+    /// Background renewal of a backend's OAuth token started failing.
+    TokenRefreshFailed,
+    /// An `alerts.rules` threshold has been breached for its `for` duration.
+    RuleFiring,
+    /// A firing rule's condition cleared.
+    RuleResolved,
+}
+
+impl AlertKind {
+    /// Whether the event takes something out of service (as opposed to
+    /// restoring it).
+    #[must_use]
+    pub fn is_outage(self) -> bool {
+        !matches!(self, Self::BackendRevived | Self::RuleResolved)
+    }
+}
+
+/// One operator alert.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperatorAlert {
+    /// Event type.
+    pub kind: AlertKind,
+    /// Affected backend (empty for scheduled playbook runs).
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub backend: String,
+    /// Affected tool, for [`AlertKind::CapabilityDisabled`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capability: Option<String>,
+    /// Human-readable summary.
+    pub message: String,
+    /// Event-specific fields (error rate, trip reason, …).
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub details: Value,
+    /// RFC 3339 time the event was observed.
+    pub timestamp: String,
+}
+
+impl OperatorAlert {
+    fn new(kind: AlertKind, backend: &str, capability: Option<&str>, message: String) -> Self {
+        Self {
+            kind,
+            backend: backend.to_string(),
+            capability: capability.map(str::to_string),
+            message,
+            details: Value::Null,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Backend killed by the error budget.
+    #[must_use]
+    pub fn backend_auto_killed(backend: &str, error_rate: f64) -> Self {
+        let mut alert = Self::new(
+            AlertKind::BackendAutoKilled,
+            backend,
+            None,
+            format!(
+                "Backend '{backend}' auto-killed by error budget ({:.0}% errors)",
+                error_rate * 100.0
+            ),
+        );
+        alert.details = json!({ "error_rate": error_rate });
+        alert
+    }
+
+    /// Auto-killed backend revived after a successful probe.
+    #[must_use]
+    pub fn backend_revived(backend: &str) -> Self {
+        Self::new(
+            AlertKind::BackendRevived,
+            backend,
+            None,
+            format!("Backend '{backend}' passed its health probe and was revived"),
+        )
+    }
+
+    /// Circuit breaker opened; `reason` is the failure that tripped it.
+    #[must_use]
+    pub fn circuit_opened(backend: &str, reason: Option<&str>, trips: u64) -> Self {
+        let message = match reason {
+            Some(reason) => format!("Circuit breaker for '{backend}' opened: {reason}"),
+            None => format!("Circuit breaker for '{backend}' opened"),
+        };
+        let mut alert = Self::new(AlertKind::CircuitOpened, backend, None, message);
+        alert.details = json!({ "reason": reason, "trips_count": trips });
+        alert
+    }
+
+    /// Scheduled job `job` failed while running `action`.
+    #[must_use]
+    pub fn scheduled_job_failed(job: &str, action: &ScheduleAction, error: &str) -> Self {
+        let backend = match action {
+            ScheduleAction::RunPlaybook(_) => "",
+            ScheduleAction::InvokeTool { server, .. } => server,
+        };
+        let target = action.target();
+        let mut alert = Self::new(
+            AlertKind::ScheduledJobFailed,
+            backend,
+            None,
+            format!("Scheduled job '{job}' ({target}) failed: {error}"),
+        );
+        alert.details = json!({ "job": job, "target": target, "error": error });
+        alert
+    }
+
+    /// Background OAuth token renewal for `backend` started failing.
+    #[must_use]
+    pub fn token_refresh_failed(backend: &str, error: Option<&str>) -> Self {
+        let message = match error {
+            Some(error) => format!("OAuth token refresh for '{backend}' is failing: {error}"),
+            None => format!("OAuth token refresh for '{backend}' is failing"),
+        };
+        let mut alert = Self::new(AlertKind::TokenRefreshFailed, backend, None, message);
+        alert.details = json!({ "error": error });
+        alert
+    }
+
+    /// Tool disabled by the per-capability error budget.
+    #[must_use]
+    pub fn capability_disabled(backend: &str, capability: &str, error_rate: f64) -> Self {
+        let mut alert = Self::new(
+            AlertKind::CapabilityDisabled,
+            backend,
+            Some(capability),
+            format!(
+                "Capability '{backend}:{capability}' disabled by error budget ({:.0}% errors)",
+                error_rate * 100.0
+            ),
+        );
+        alert.details = json!({ "error_rate": error_rate });
+        alert
+    }
+
+    /// Threshold rule `rule` started firing with the metric at `value`.
+    #[must_use]
+    pub fn rule_firing(rule: &AlertRuleConfig, backend: &str, value: f64) -> Self {
+        let subject = rule_subject(rule, backend);
+        let mut alert = Self::new(
+            AlertKind::RuleFiring,
+            backend,
+            None,
+            format!(
+                "Alert '{}' firing: {subject} {}",
+                rule.name,
+                describe_breach(rule, value)
+            ),
+        );
+        alert.details = rule_details(rule, value);
+        alert
+    }
+
+    /// Threshold rule `rule` stopped firing; the metric is back at `value`.
+    #[must_use]
+    pub fn rule_resolved(rule: &AlertRuleConfig, backend: &str, value: f64) -> Self {
+        let subject = rule_subject(rule, backend);
+        let mut alert = Self::new(
+            AlertKind::RuleResolved,
+            backend,
+            None,
+            format!(
+                "Alert '{}' resolved: {subject} {}",
+                rule.name,
+                describe_value(rule.metric, value)
+            ),
+        );
+        alert.details = rule_details(rule, value);
+        alert
+    }
+
+    /// Generic webhook body.
+    #[must_use]
+    pub fn webhook_payload(&self) -> Value {
+        json!({
+            "source": "mcp-gateway",
+            "type": "operator_alert",
+            "alert": self,
+        })
+    }
+
+    /// Slack incoming-webhook body.
+    #[must_use]
+    pub fn slack_payload(&self) -> Value {
+        let icon = if self.kind.is_outage() {
+            ":rotating_light:"
+        } else {
+            ":white_check_mark:"
+        };
+        json!({ "text": format!("{icon} *mcp-gateway*: {}", self.message) })
+    }
+
+    /// MCP `notifications/message` (logging) notification.
+    #[must_use]
+    pub fn mcp_notification(&self) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": {
+                "level": if self.kind.is_outage() { "warning" } else { "info" },
+                "logger": MCP_LOGGER,
+                "data": self,
+            }
+        })
+    }
+}
+
+fn rule_subject(rule: &AlertRuleConfig, backend: &str) -> String {
+    match rule.metric {
+        AlertMetric::CacheHitRate => "response cache".to_string(),
+        _ => format!("backend '{backend}'"),
+    }
+}
+
+fn describe_value(metric: AlertMetric, value: f64) -> String {
+    match metric {
+        AlertMetric::ErrorRate => format!("error rate {:.0}%", value * 100.0),
+        AlertMetric::CacheHitRate => format!("hit rate {:.0}%", value * 100.0),
+        AlertMetric::LatencyP99 => format!("p99 latency {value:.0}ms"),
+        AlertMetric::BackendDown if value > 0.0 => "is down".to_string(),
+        AlertMetric::BackendDown => "is up".to_string(),
+    }
+}
+
+fn describe_breach(rule: &AlertRuleConfig, value: f64) -> String {
+    let current = describe_value(rule.metric, value);
+    let held = format!("{}s", rule.for_duration.as_secs());
+    match (rule.above, rule.below) {
+        (Some(above), _) => format!(
+            "{current} above {} for {held}",
+            format_threshold(rule.metric, above)
+        ),
+        (_, Some(below)) => format!(
+            "{current} below {} for {held}",
+            format_threshold(rule.metric, below)
+        ),
+        _ => format!("{current} for {held}"),
+    }
+}
+
+fn format_threshold(metric: AlertMetric, threshold: f64) -> String {
+    if metric.is_rate() {
+        format!("{:.0}%", threshold * 100.0)
+    } else {
+        format!("{threshold:.0}ms")
+    }
+}
+
+fn rule_details(rule: &AlertRuleConfig, value: f64) -> Value {
+    json!({
+        "rule": rule.name,
+        "metric": rule.metric,
+        "value": value,
+        "above": rule.above,
+        "below": rule.below,
+        "for_secs": rule.for_duration.as_secs(),
+    })
+}
+
+// ============================================================================
+// Notifier
+// ============================================================================
+
+/// Cheap handle for raising alerts from the request path.
+///
+/// Sending never blocks; delivery happens on the task started by [`spawn`].
+#[derive(Debug)]
+pub struct AlertNotifier {
+    tx: broadcast::Sender<OperatorAlert>,
+}
+
+impl Default for AlertNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlertNotifier {
+    /// Create a notifier with no delivery task attached yet.
+    #[must_use]
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { tx }
+    }
+
+    /// Queue `alert` for delivery.
+    pub fn notify(&self, alert: OperatorAlert) {
+        let _ = self.tx.send(alert);
+    }
+
+    /// Receive every alert raised from now on.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<OperatorAlert> {
+        self.tx.subscribe()
+    }
+}
+
+// ============================================================================
+// Circuit trip detection
+// ============================================================================
+
+/// Remembers each backend's breaker trip counter between polls.
+#[derive(Debug, Default)]
+pub struct CircuitWatch {
+    trips: HashMap<String, u64>,
+}
+
+impl CircuitWatch {
+    /// Compare every backend's trip counter with the previous poll and return
+    /// an alert per backend that tripped since.
+    ///
+    /// The first sighting of a backend only records its counter, so trips
+    /// that happened before the watch started are not reported.
+    pub fn poll(&mut self, backends: &BackendRegistry) -> Vec<OperatorAlert> {
+        let mut alerts = Vec::new();
+        let mut seen = HashMap::new();
+        for backend in backends.all() {
+            let trips = backend.circuit_breaker_stats().trips_count;
+            if let Some(&previous) = self.trips.get(&backend.name)
+                && trips > previous
+            {
+                let reason = backend.last_circuit_open_event().map(|e| e.reason);
+                alerts.push(OperatorAlert::circuit_opened(
+                    &backend.name,
+                    reason.as_deref(),
+                    trips,
+                ));
+            }
+            seen.insert(backend.name.clone(), trips);
+        }
+        self.trips = seen;
+        alerts
+    }
+}
+
+/// Remembers each backend's OAuth refresh failure-streak counter between
+/// polls.
+#[derive(Debug, Default)]
+pub struct RefreshWatch {
+    streaks: HashMap<String, u64>,
+}
+
+impl RefreshWatch {
+    /// Return an alert per backend whose background token refresh started
+    /// failing since the previous poll.
+    ///
+    /// Like [`CircuitWatch`], the first sighting of a backend only records
+    /// its counter.
+    pub fn poll(&mut self, backends: &BackendRegistry) -> Vec<OperatorAlert> {
+        let mut alerts = Vec::new();
+        let mut seen = HashMap::new();
+        for backend in backends.all() {
+            let health = backend.oauth_refresh_health();
+            let streaks = health.failure_streaks();
+            if let Some(&previous) = self.streaks.get(&backend.name)
+                && streaks > previous
+            {
+                alerts.push(OperatorAlert::token_refresh_failed(
+                    &backend.name,
+                    health.last_error().as_deref(),
+                ));
+            }
+            seen.insert(backend.name.clone(), streaks);
+        }
+        self.streaks = seen;
+        alerts
+    }
+}
+
+// ============================================================================
+// Threshold rules
+// ============================================================================
+
+/// Counter pair a rate is computed from: failures/successes for a backend,
+/// hits/misses for the response cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    /// Calls counted in the rate's numerator.
+    pub matched: u64,
+    /// The remaining calls.
+    pub other: u64,
+}
+
+/// One backend's metrics at an evaluation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BackendSample {
+    /// Backend name.
+    pub name: String,
+    /// Cumulative failures (`matched`) and successes (`other`).
+    pub calls: Counters,
+    /// Current p99 latency, if any calls were timed.
+    pub latency_p99_ms: Option<u64>,
+    /// Circuit breaker open or health tracker unhealthy.
+    pub down: bool,
+}
+
+/// Everything [`RuleWatch`] evaluates against at one instant.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricSample {
+    /// Per-backend metrics.
+    pub backends: Vec<BackendSample>,
+    /// Cumulative response-cache hits (`matched`) and misses (`other`);
+    /// `None` when caching is off.
+    pub cache: Option<Counters>,
+}
+
+impl MetricSample {
+    /// Read the current metrics from `backends` and `cache`.
+    #[must_use]
+    pub fn collect(backends: &BackendRegistry, cache: Option<&ResponseCache>) -> Self {
+        Self {
+            backends: backends
+                .all()
+                .iter()
+                .map(|backend| {
+                    let health = backend.health_metrics();
+                    BackendSample {
+                        name: backend.name.clone(),
+                        calls: Counters {
+                            matched: health.failure_count,
+                            other: health.success_count,
+                        },
+                        latency_p99_ms: health.latency_p99_ms,
+                        down: backend.is_circuit_tripped() || !health.healthy,
+                    }
+                })
+                .collect(),
+            cache: cache.map(|cache| {
+                let stats = cache.stats();
+                Counters {
+                    matched: stats.hits,
+                    other: stats.misses,
+                }
+            }),
+        }
+    }
+}
+
+/// Per rule and target: when the condition started holding and whether the
+/// rule has fired.
+#[derive(Debug, Default)]
+struct RuleState {
+    breaching_since: Option<Instant>,
+    firing: bool,
+}
+
+/// Evaluates `alerts.rules`, firing each rule once per incident and
+/// resolving it when the condition clears.
+///
+/// Rates are computed from the change in cumulative counters over the
+/// rule's `for` window, so the watch keeps a short history of samples. A
+/// rate rule whose window holds fewer than `min_events` calls, or whose
+/// window is not yet covered by history, is left as it is.
+#[derive(Debug)]
+pub struct RuleWatch {
+    rules: Vec<AlertRuleConfig>,
+    min_events: u64,
+    retention: Duration,
+    /// Counter history per source; the response cache is keyed `""`.
+    history: HashMap<String, VecDeque<(Instant, Counters)>>,
+    states: HashMap<(usize, String), RuleState>,
+}
+
+impl RuleWatch {
+    /// Watch `rules`, ignoring rate windows with fewer than `min_events`
+    /// calls.
+    #[must_use]
+    pub fn new(rules: Vec<AlertRuleConfig>, min_events: u64) -> Self {
+        let retention = rules
+            .iter()
+            .filter(|rule| rule.metric.is_rate())
+            .map(|rule| rule.for_duration)
+            .max()
+            .unwrap_or_default();
+        Self {
+            rules,
+            min_events,
+            retention,
+            history: HashMap::new(),
+            states: HashMap::new(),
+        }
+    }
+
+    /// Sample `backends` and `cache` now and evaluate every rule.
+    pub fn poll(
+        &mut self,
+        backends: &BackendRegistry,
+        cache: Option<&ResponseCache>,
+    ) -> Vec<OperatorAlert> {
+        self.evaluate(&MetricSample::collect(backends, cache), Instant::now())
+    }
+
+    /// Evaluate every rule against `sample`, taken at `now`.
+    pub fn evaluate(&mut self, sample: &MetricSample, now: Instant) -> Vec<OperatorAlert> {
+        for backend in &sample.backends {
+            self.record(&backend.name, now, backend.calls);
+        }
+        if let Some(cache) = sample.cache {
+            self.record("", now, cache);
+        }
+
+        let mut alerts = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            let targets: Vec<(&str, Option<f64>)> = match rule.metric {
+                AlertMetric::CacheHitRate => sample
+                    .cache
+                    .map(|_| ("", self.rate("", rule.for_duration, now)))
+                    .into_iter()
+                    .collect(),
+                metric => sample
+                    .backends
+                    .iter()
+                    .filter(|b| rule.backend.as_ref().is_none_or(|name| *name == b.name))
+                    .map(|b| (b.name.as_str(), self.backend_value(metric, b, rule, now)))
+                    .collect(),
+            };
+            for (target, value) in targets {
+                // No data (idle window, no timed calls): leave the state alone.
+                let Some(value) = value else {
+                    continue;
+                };
+                let state = self.states.entry((index, target.to_string())).or_default();
+                let breaching = rule.breaches(value);
+                let held = if !breaching {
+                    state.breaching_since = None;
+                    false
+                } else if rule.metric.is_rate() {
+                    // The window itself already spans `for`.
+                    true
+                } else {
+                    let since = *state.breaching_since.get_or_insert(now);
+                    now.duration_since(since) >= rule.for_duration
+                };
+                if held && !state.firing {
+                    state.firing = true;
+                    alerts.push(OperatorAlert::rule_firing(rule, target, value));
+                } else if !breaching && state.firing {
+                    state.firing = false;
+                    alerts.push(OperatorAlert::rule_resolved(rule, target, value));
+                }
+            }
+        }
+        alerts
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn backend_value(
+        &self,
+        metric: AlertMetric,
+        backend: &BackendSample,
+        rule: &AlertRuleConfig,
+        now: Instant,
+    ) -> Option<f64> {
+        match metric {
+            AlertMetric::ErrorRate => self.rate(&backend.name, rule.for_duration, now),
+            AlertMetric::LatencyP99 => backend.latency_p99_ms.map(|ms| ms as f64),
+            AlertMetric::BackendDown => Some(if backend.down { 1.0 } else { 0.0 }),
+            AlertMetric::CacheHitRate => None,
+        }
+    }
+
+    fn record(&mut self, source: &str, now: Instant, counters: Counters) {
+        let history = self.history.entry(source.to_string()).or_default();
+        history.push_back((now, counters));
+        // Keep one sample at or before the longest window as its baseline.
+        if let Some(cutoff) = now.checked_sub(self.retention) {
+            while history.get(1).is_some_and(|(at, _)| *at <= cutoff) {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// `matched / total` over the trailing `window`, or `None` when history
+    /// does not reach back that far or too few calls happened.
+    #[allow(clippy::cast_precision_loss)]
+    fn rate(&self, source: &str, window: Duration, now: Instant) -> Option<f64> {
+        let history = self.history.get(source)?;
+        let cutoff = now.checked_sub(window)?;
+        let (_, base) = history.iter().rev().find(|(at, _)| *at <= cutoff)?;
+        let (_, latest) = history.back()?;
+        let matched = latest.matched.saturating_sub(base.matched);
+        let total = matched + latest.other.saturating_sub(base.other);
+        if total == 0 || total < self.min_events {
+            return None;
+        }
+        Some(matched as f64 / total as f64)
+    }
+}
+
+// ============================================================================
+// Delivery
+// ============================================================================
+
+/// Configured alert sinks.
+struct Sinks {
+    client: reqwest::Client,
+    webhook_url: Option<String>,
+    slack_webhook_url: Option<String>,
+    multiplexer: Option<Arc<NotificationMultiplexer>>,
+}
+
+impl Sinks {
+    async fn deliver(&self, alert: &OperatorAlert) {
+        if alert.kind.is_outage() {
+            warn!(
+                kind = ?alert.kind,
+                backend = %alert.backend,
+                capability = alert.capability.as_deref(),
+                "{}",
+                alert.message
+            );
+        } else {
+            info!(kind = ?alert.kind, backend = %alert.backend, "{}", alert.message);
+        }
+        if let Some(url) = &self.webhook_url {
+            self.post(url, &alert.webhook_payload(), "webhook").await;
+        }
+        if let Some(url) = &self.slack_webhook_url {
+            self.post(url, &alert.slack_payload(), "Slack webhook")
+                .await;
+        }
+        if let Some(mux) = &self.multiplexer {
+            mux.broadcast(TaggedNotification {
+                source: "gateway".to_string(),
+                event_type: "message".to_string(),
+                data: alert.mcp_notification(),
+                event_id: Some(mux.next_event_id()),
+            });
+        }
+    }
+
+    async fn post(&self, url: &str, body: &Value, sink: &str) {
+        match self.client.post(url).json(body).send().await {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => warn!(status = %response.status(), sink, "Alert delivery rejected"),
+            Err(e) => warn!(error = %e, sink, "Alert delivery failed"),
+        }
+    }
+}
+
+/// Spawn the delivery task: forwards alerts raised through `notifier`,
+/// polls `backends` for circuit breaker trips and OAuth refresh failures,
+/// and evaluates `alerts.rules` against `backends` and `cache`.
+///
+/// `multiplexer` is the streamable-HTTP notification fan-out; pass `None`
+/// (stdio mode) or leave `mcp_notifications` off to skip MCP delivery.
+pub fn spawn(
+    notifier: &AlertNotifier,
+    backends: Arc<BackendRegistry>,
+    cache: Option<Arc<ResponseCache>>,
+    multiplexer: Option<Arc<NotificationMultiplexer>>,
+    config: &AlertsConfig,
+    mut shutdown: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    let sinks = Sinks {
+        client: reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .unwrap_or_default(),
+        webhook_url: config.webhook_url.clone(),
+        slack_webhook_url: config.slack_webhook_url.clone(),
+        multiplexer: multiplexer.filter(|_| config.mcp_notifications),
+    };
+    let mut alerts = notifier.subscribe();
+    let period = config.circuit_poll_interval;
+    let mut rule_watch = RuleWatch::new(config.rules.clone(), config.min_events);
+    let has_rules = !config.rules.is_empty();
+    let evaluation_period = config.evaluation_interval;
+    tokio::spawn(async move {
+        let mut watch = CircuitWatch::default();
+        watch.poll(&backends);
+        let mut refresh_watch = RefreshWatch::default();
+        refresh_watch.poll(&backends);
+        let mut interval = tokio::time::interval(period);
+        interval.tick().await;
+        let mut rule_interval = tokio::time::interval(evaluation_period);
+        loop {
+            tokio::select! {
+                received = alerts.recv() => match received {
+                    Ok(alert) => sinks.deliver(&alert).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "Operator alerts dropped; delivery is falling behind");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = interval.tick() => {
+                    for alert in watch.poll(&backends).into_iter().chain(refresh_watch.poll(&backends)) {
+                        sinks.deliver(&alert).await;
+                    }
+                }
+                _ = rule_interval.tick(), if has_rules => {
+                    for alert in rule_watch.poll(&backends, cache.as_deref()) {
+                        sinks.deliver(&alert).await;
+                    }
+                }
+                _ = shutdown.recv() => {
+                    break;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,351 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use std::time::Duration;
+
+use super::*;
+use crate::backend::Backend;
+use crate::config::{BackendConfig, FailsafeConfig};
+
+fn registry_with(name: &str) -> (Arc<BackendRegistry>, Arc<Backend>) {
+    let registry = Arc::new(BackendRegistry::new());
+    let backend = Arc::new(Backend::new(
+        name,
+        BackendConfig::default(),
+        &FailsafeConfig::default(),
+        Duration::from_secs(60),
+    ));
+    registry.register(Arc::clone(&backend));
+    (registry, backend)
+}
+
+// ── Payloads ─────────────────────────────────────────────────────────
+
+#[test]
+fn webhook_payload_wraps_alert_in_envelope() {
+    let body = OperatorAlert::backend_auto_killed("github", 0.75).webhook_payload();
+    assert_eq!(body["source"], "mcp-gateway");
+    assert_eq!(body["type"], "operator_alert");
+    assert_eq!(body["alert"]["kind"], "backend_auto_killed");
+    assert_eq!(body["alert"]["backend"], "github");
+    assert_eq!(body["alert"]["details"]["error_rate"], 0.75);
+    assert!(body["alert"].get("capability").is_none());
+}
+
+#[test]
+fn slack_payload_is_plain_text() {
+    let alert = OperatorAlert::capability_disabled("erp", "create_invoice", 0.5);
+    let body = alert.slack_payload();
+    let text = body["text"].as_str().unwrap();
+    assert!(text.starts_with(":rotating_light:"), "{text}");
+    assert!(
+        text.contains("erp:create_invoice") && text.contains("50%"),
+        "{text}"
+    );
+    assert_eq!(body.as_object().unwrap().len(), 1);
+
+    let revived = OperatorAlert::backend_revived("erp").slack_payload();
+    assert!(
+        revived["text"]
+            .as_str()
+            .unwrap()
+            .starts_with(":white_check_mark:")
+    );
+}
+
+#[test]
+fn mcp_notification_is_a_logging_message() {
+    let msg = OperatorAlert::circuit_opened("docs", Some("timeout"), 3).mcp_notification();
+    assert_eq!(msg["jsonrpc"], "2.0");
+    assert_eq!(msg["method"], "notifications/message");
+    assert!(msg.get("id").is_none());
+    assert_eq!(msg["params"]["level"], "warning");
+    assert_eq!(msg["params"]["logger"], "mcp-gateway.alerts");
+    assert_eq!(msg["params"]["data"]["kind"], "circuit_opened");
+    assert_eq!(msg["params"]["data"]["details"]["reason"], "timeout");
+
+    let revived = OperatorAlert::backend_revived("docs").mcp_notification();
+    assert_eq!(revived["params"]["level"], "info");
+}
+
+#[test]
+fn scheduled_job_failure_names_job_and_target() {
+    let playbook = ScheduleAction::RunPlaybook("nightly-sync".to_string());
+    let body = OperatorAlert::scheduled_job_failed("nightly", &playbook, "boom").webhook_payload();
+    assert_eq!(body["alert"]["kind"], "scheduled_job_failed");
+    assert_eq!(body["alert"]["details"]["job"], "nightly");
+    assert_eq!(body["alert"]["details"]["target"], "playbook:nightly-sync");
+    assert!(body["alert"].get("backend").is_none());
+
+    let tool = ScheduleAction::InvokeTool {
+        server: "erp".to_string(),
+        tool: "close_books".to_string(),
+        arguments: std::collections::HashMap::new(),
+    };
+    let alert = OperatorAlert::scheduled_job_failed("month-end", &tool, "timeout");
+    assert_eq!(alert.backend, "erp");
+    assert!(
+        alert.message.contains("erp:close_books"),
+        "{}",
+        alert.message
+    );
+}
+
+// ── CircuitWatch ─────────────────────────────────────────────────────
+
+#[test]
+fn circuit_watch_reports_new_trips_once() {
+    // GIVEN: a watch that has seen the backend's breaker closed
+    let (registry, backend) = registry_with("docs");
+    let mut watch = CircuitWatch::default();
+    assert!(watch.poll(&registry).is_empty());
+
+    // WHEN: the breaker trips between polls
+    backend.trip_circuit_breaker_for_test();
+    let alerts = watch.poll(&registry);
+
+    // THEN: one alert carries the trip reason, and the next poll is quiet
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::CircuitOpened);
+    assert_eq!(alerts[0].backend, "docs");
+    assert_eq!(alerts[0].details["reason"], "test-trip");
+    assert!(watch.poll(&registry).is_empty());
+}
+
+#[test]
+fn circuit_watch_ignores_trips_before_first_poll() {
+    let (registry, backend) = registry_with("docs");
+    backend.trip_circuit_breaker_for_test();
+    assert!(CircuitWatch::default().poll(&registry).is_empty());
+}
+
+// ── RefreshWatch ─────────────────────────────────────────────────────
+
+#[test]
+fn refresh_watch_reports_each_failure_streak_once() {
+    // GIVEN: a watch that has seen the backend refreshing fine
+    let (registry, backend) = registry_with("crm");
+    let mut watch = RefreshWatch::default();
+    assert!(watch.poll(&registry).is_empty());
+
+    // WHEN: renewal fails twice between polls
+    let health = backend.oauth_refresh_health();
+    health.record_failure("invalid_grant".to_string());
+    health.record_failure("invalid_grant".to_string());
+    let alerts = watch.poll(&registry);
+
+    // THEN: one alert carries the error, and the next poll is quiet
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::TokenRefreshFailed);
+    assert_eq!(alerts[0].backend, "crm");
+    assert_eq!(alerts[0].details["error"], "invalid_grant");
+    assert!(watch.poll(&registry).is_empty());
+
+    // AND: a new streak after recovery alerts again
+    health.record_success();
+    health.record_failure("timeout".to_string());
+    assert_eq!(watch.poll(&registry).len(), 1);
+}
+
+// ── RuleWatch ────────────────────────────────────────────────────────
+
+fn rule(yaml: &str) -> AlertRuleConfig {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+fn backend_sample(name: &str, failures: u64, successes: u64) -> MetricSample {
+    MetricSample {
+        backends: vec![BackendSample {
+            name: name.to_string(),
+            calls: Counters {
+                matched: failures,
+                other: successes,
+            },
+            ..Default::default()
+        }],
+        cache: None,
+    }
+}
+
+#[test]
+fn rule_watch_fires_error_rate_once_and_resolves() {
+    // GIVEN: "github error rate > 20% over 5 min"
+    let rule = rule("{name: gh-errors, backend: github, metric: error_rate, above: 0.2, for: 5m}");
+    let mut watch = RuleWatch::new(vec![rule], 10);
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    assert!(
+        watch
+            .evaluate(&backend_sample("github", 0, 0), at(0))
+            .is_empty()
+    );
+
+    // WHEN: the window is not yet covered, nothing fires even at 50% errors
+    assert!(
+        watch
+            .evaluate(&backend_sample("github", 10, 10), at(120))
+            .is_empty()
+    );
+
+    // THEN: once 5 minutes of history show 30% errors, the rule fires once
+    let alerts = watch.evaluate(&backend_sample("github", 30, 70), at(300));
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::RuleFiring);
+    assert_eq!(alerts[0].backend, "github");
+    assert_eq!(alerts[0].details["rule"], "gh-errors");
+    assert!(alerts[0].message.contains("30%"), "{}", alerts[0].message);
+    assert!(
+        watch
+            .evaluate(&backend_sample("github", 40, 90), at(360))
+            .is_empty()
+    );
+
+    // AND: when the trailing window recovers, one resolve follows
+    let alerts = watch.evaluate(&backend_sample("github", 40, 400), at(660));
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::RuleResolved);
+    assert!(!alerts[0].kind.is_outage());
+    assert!(
+        watch
+            .evaluate(&backend_sample("github", 40, 500), at(720))
+            .is_empty()
+    );
+}
+
+#[test]
+fn rule_watch_ignores_windows_below_min_events() {
+    let rule = rule("{name: errors, metric: error_rate, above: 0.2, for: 1m}");
+    let mut watch = RuleWatch::new(vec![rule], 10);
+    let start = Instant::now();
+    watch.evaluate(&backend_sample("docs", 0, 0), start);
+    let alerts = watch.evaluate(
+        &backend_sample("docs", 3, 0),
+        start + Duration::from_secs(60),
+    );
+    assert!(alerts.is_empty());
+}
+
+#[test]
+fn rule_watch_requires_gauges_to_hold_for_the_duration() {
+    // GIVEN: a backend_down rule with a 2 minute hold
+    let rule = rule("{name: down, metric: backend_down, for: 2m}");
+    let mut watch = RuleWatch::new(vec![rule], 10);
+    let start = Instant::now();
+    let down = |down| MetricSample {
+        backends: vec![BackendSample {
+            name: "crm".to_string(),
+            down,
+            ..Default::default()
+        }],
+        cache: None,
+    };
+
+    // WHEN: the backend flaps back up before the hold elapses
+    assert!(watch.evaluate(&down(true), start).is_empty());
+    assert!(
+        watch
+            .evaluate(&down(false), start + Duration::from_secs(60))
+            .is_empty()
+    );
+    assert!(
+        watch
+            .evaluate(&down(true), start + Duration::from_secs(90))
+            .is_empty()
+    );
+    assert!(
+        watch
+            .evaluate(&down(true), start + Duration::from_secs(180))
+            .is_empty()
+    );
+
+    // THEN: it fires only after staying down for the full 2 minutes
+    let alerts = watch.evaluate(&down(true), start + Duration::from_secs(210));
+    assert_eq!(alerts.len(), 1);
+    assert!(
+        alerts[0].message.contains("is down"),
+        "{}",
+        alerts[0].message
+    );
+    let alerts = watch.evaluate(&down(false), start + Duration::from_secs(240));
+    assert_eq!(alerts[0].kind, AlertKind::RuleResolved);
+}
+
+#[test]
+fn rule_watch_alerts_on_cache_hit_rate_collapse() {
+    let rule = rule("{name: cache-collapse, metric: cache_hit_rate, below: 0.5, for: 1m}");
+    let mut watch = RuleWatch::new(vec![rule], 10);
+    let start = Instant::now();
+    let cache = |hits, misses| MetricSample {
+        backends: Vec::new(),
+        cache: Some(Counters {
+            matched: hits,
+            other: misses,
+        }),
+    };
+    watch.evaluate(&cache(100, 10), start);
+    let alerts = watch.evaluate(&cache(110, 90), start + Duration::from_secs(60));
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].backend, "");
+    assert!(
+        alerts[0].message.contains("response cache"),
+        "{}",
+        alerts[0].message
+    );
+}
+
+// ── Delivery ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn spawn_posts_alerts_to_webhook_and_slack() {
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    let (seen_tx, mut seen_rx) = tokio::sync::mpsc::unbounded_channel::<(String, Value)>();
+    let webhook_tx = seen_tx.clone();
+    let app = Router::new()
+        .route(
+            "/hook",
+            post(move |Json(body): Json<Value>| async move {
+                let _ = webhook_tx.send(("hook".to_string(), body));
+            }),
+        )
+        .route(
+            "/slack",
+            post(move |Json(body): Json<Value>| async move {
+                let _ = seen_tx.send(("slack".to_string(), body));
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let notifier = AlertNotifier::new();
+    let config = AlertsConfig {
+        webhook_url: Some(format!("http://{addr}/hook")),
+        slack_webhook_url: Some(format!("http://{addr}/slack")),
+        ..AlertsConfig::default()
+    };
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let (registry, _backend) = registry_with("docs");
+    let task = spawn(&notifier, registry, None, None, &config, shutdown_rx);
+    tokio::task::yield_now().await;
+
+    notifier.notify(OperatorAlert::backend_auto_killed("docs", 0.9));
+
+    let mut received = Vec::new();
+    for _ in 0..2 {
+        let (sink, body) = tokio::time::timeout(Duration::from_secs(5), seen_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        received.push(sink.clone());
+        match sink.as_str() {
+            "hook" => assert_eq!(body["alert"]["kind"], "backend_auto_killed"),
+            _ => assert!(body["text"].as_str().unwrap().contains("docs")),
+        }
+    }
+    received.sort();
+    assert_eq!(received, ["hook", "slack"]);
+
+    let _ = shutdown_tx.send(());
+    task.await.unwrap();
+}
@@ -12,6 +12,8 @@
 //! - Zero-allocation fast-path: returns the original string unchanged when no
 //!   keywords are extracted.
 //! - Top-7 keywords, preferring longer (more specific) words, deduplicated.
+//! - Configurable: [`AutotagRules`] built from the `autotag` config section adds
+//!   stopwords, domain keywords, regex extraction rules, and a different tag cap.
 //!
 //! # Example
 //!
@@ -57,16 +59,175 @@
 /// ```
 #[must_use]
 pub fn enrich_description(description: &str) -> String {
-    if description.contains("[keywords:") {
-        return description.to_string();
+    DEFAULT_RULES.enrich(description)
+}
+
+/// Keyword extraction rules: the built-in heuristics plus configured
+/// stopwords, domain keywords, regex rules, and tag cap.
+///
+/// [`AutotagRules::default`] reproduces [`enrich_description`].
+#[derive(Debug)]
+pub struct AutotagRules {
+    max_tags: usize,
+    min_word_len: usize,
+    default_stopwords: bool,
+    stopwords: HashSet<String>,
+    keywords: HashSet<String>,
+    rules: Vec<ExtractionRule>,
+}
+
+/// Compiled [`AutotagRuleConfig`].
+#[derive(Debug)]
+struct ExtractionRule {
+    pattern: Regex,
+    tags: Vec<String>,
+}
+
+impl AutotagRules {
+    /// Compile rules from the `autotag` config section.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a rule pattern is not a valid regular
+    /// expression.
+    pub fn from_config(config: &AutotagConfig) -> Result<Self> {
+        let lowercase = |words: &[String]| -> HashSet<String> {
+            words.iter().map(|w| w.trim().to_lowercase()).collect()
+        };
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let pattern = Regex::new(&rule.pattern).map_err(|e| {
+                    Error::Config(format!("Invalid autotag pattern '{}': {e}", rule.pattern))
+                })?;
+                Ok(ExtractionRule {
+                    pattern,
+                    tags: rule.tags.iter().map(|t| t.trim().to_lowercase()).collect(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            max_tags: config.max_tags.max(1),
+            min_word_len: config.min_word_length,
+            default_stopwords: !config.replace_default_stopwords,
+            stopwords: lowercase(&config.stopwords),
+            keywords: lowercase(&config.keywords),
+            rules,
+        })
+    }
+
+    /// Enrich a tool description with keyword tags (see [`enrich_description`]).
+    #[must_use]
+    pub fn enrich(&self, description: &str) -> String {
+        if description.contains("[keywords:") {
+            return description.to_string();
+        }
+
+        let tags = self.extract_keywords(description);
+        if tags.is_empty() {
+            return description.to_string();
+        }
+
+        format!("{} [keywords: {}]", description.trim_end(), tags.join(", "))
+    }
+
+    /// Extract up to `max_tags` meaningful keywords from `text`.
+    ///
+    /// Pipeline:
+    /// 1. Collect tags from matching regex rules
+    /// 2. Tokenise on non-alphabetic boundaries (hyphens treated as word separators)
+    /// 3. Lowercase
+    /// 4. Filter stopwords and tool-generic words
+    /// 5. Filter words shorter than the minimum length (in characters)
+    /// 6. Deduplicate, preserving first-occurrence order
+    /// 7. Sort stable by descending length (longer = more specific) within the deduped set
+    /// 8. Truncate to `max_tags`, rule tags and priority terms first
+    fn extract_keywords(&self, text: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        // Rule tags are explicit operator intent, so they lead.
+        let mut words: Vec<String> = Vec::new();
+        for rule in &self.rules {
+            for tag in rule.tags_for(text) {
+                if seen.insert(tag.clone()) {
+                    words.push(tag);
+                }
+            }
+        }
+        // High-signal short domain terms are kept ahead of the length-ranked fill so
+        // they survive truncation (the length heuristic otherwise drops "email",
+        // "inbox", etc. in favour of merely-longer generic words).
+        let mut priority: Vec<String> = Vec::new();
+        let mut rest: Vec<String> = Vec::new();
+
+        for raw_word in tokenize(text) {
+            let word = raw_word.to_lowercase();
+            if word.chars().count() < self.min_word_len {
+                continue;
+            }
+            if self.is_stopword(&word) {
+                continue;
+            }
+            if seen.insert(word.clone()) {
+                if self.is_priority(&word) {
+                    priority.push(word);
+                } else {
+                    rest.push(word);
+                }
+            }
+        }
+
+        // Stable sort descending by length — longer words are more specific.
+        rest.sort_by_key(|w| Reverse(w.chars().count()));
+
+        // Rule tags, then priority terms (first-occurrence order), then the
+        // length-ranked fill.
+        words.extend(priority);
+        words.extend(rest);
+        words.truncate(self.max_tags);
+        words
     }
 
-    let tags = extract_keywords(description);
-    if tags.is_empty() {
-        return description.to_string();
+    fn is_stopword(&self, word: &str) -> bool {
+        (self.default_stopwords && is_stopword(word)) || self.stopwords.contains(word)
     }
 
-    format!("{} [keywords: {}]", description.trim_end(), tags.join(", "))
+    fn is_priority(&self, word: &str) -> bool {
+        is_priority(word) || self.keywords.contains(word)
+    }
+}
+
+impl Default for AutotagRules {
+    fn default() -> Self {
+        Self {
+            max_tags: MAX_KEYWORDS,
+            min_word_len: MIN_WORD_LEN,
+            default_stopwords: true,
+            stopwords: HashSet::new(),
+            keywords: HashSet::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl ExtractionRule {
+    /// Tags contributed by this rule for `text`: the configured tags on a
+    /// match, or else every match (capture group 1 when present).
+    fn tags_for(&self, text: &str) -> Vec<String> {
+        if !self.tags.is_empty() {
+            return if self.pattern.is_match(text) {
+                self.tags.clone()
+            } else {
+                Vec::new()
+            };
+        }
+        self.pattern
+            .captures_iter(text)
+            .filter_map(|caps| caps.get(1).or_else(|| caps.get(0)))
+            .map(|m| m.as_str().trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
 }
 
 // ============================================================================
@@ -74,6 +235,13 @@ pub fn enrich_description(description: &str) -> String {
 // ============================================================================
 
 use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::config::AutotagConfig;
+use crate::{Error, Result};
 
 /// Maximum number of keywords appended per description.
 const MAX_KEYWORDS: usize = 7;
@@ -81,57 +249,22 @@ const MAX_KEYWORDS: usize = 7;
 /// Minimum word length to consider (inclusive).
 const MIN_WORD_LEN: usize = 3;
 
-/// Extract up to [`MAX_KEYWORDS`] meaningful keywords from `text`.
-///
-/// Pipeline:
-/// 1. Tokenise on non-alphabetic boundaries (hyphens treated as word separators)
-/// 2. Lowercase
-/// 3. Filter stopwords and tool-generic words
-/// 4. Filter words shorter than [`MIN_WORD_LEN`]
-/// 5. Deduplicate, preserving first-occurrence order
-/// 6. Sort stable by descending length (longer = more specific) within the deduped set
-/// 7. Truncate to [`MAX_KEYWORDS`]
-fn extract_keywords(text: &str) -> Vec<String> {
-    let mut seen = std::collections::HashSet::new();
-    // High-signal short domain terms are kept ahead of the length-ranked fill so
-    // they survive truncation (the length heuristic otherwise drops "email",
-    // "inbox", etc. in favour of merely-longer generic words).
-    let mut priority: Vec<String> = Vec::new();
-    let mut rest: Vec<String> = Vec::new();
-
-    for raw_word in tokenize(text) {
-        let word = raw_word.to_lowercase();
-        if word.len() < MIN_WORD_LEN {
-            continue;
-        }
-        if is_stopword(&word) {
-            continue;
-        }
-        if seen.insert(word.clone()) {
-            if is_priority(&word) {
-                priority.push(word);
-            } else {
-                rest.push(word);
-            }
-        }
-    }
-
-    // Stable sort descending by length — longer words are more specific.
-    rest.sort_by_key(|w| Reverse(w.len()));
+/// Built-in rules used by [`enrich_description`].
+static DEFAULT_RULES: LazyLock<AutotagRules> = LazyLock::new(AutotagRules::default);
 
-    // Priority terms first (first-occurrence order), then the length-ranked fill.
-    let mut words = priority;
-    words.extend(rest);
-    words.truncate(MAX_KEYWORDS);
-    words
+/// Extract up to [`MAX_KEYWORDS`] keywords from `text` with the built-in rules.
+#[cfg(test)]
+fn extract_keywords(text: &str) -> Vec<String> {
+    DEFAULT_RULES.extract_keywords(text)
 }
 
-/// Tokenise `text` by splitting on any non-ASCII-alphabetic character.
+/// Tokenise `text` by splitting on any non-alphabetic character.
 ///
 /// Hyphens and underscores are treated as separators, so `"entity-discovery"`
-/// yields `["entity", "discovery"]`.
+/// yields `["entity", "discovery"]`. Letters outside ASCII (`ä`, `ß`, `é`)
+/// are part of words, so non-English descriptions tokenise correctly.
 fn tokenize(text: &str) -> impl Iterator<Item = &str> {
-    text.split(|c: char| !c.is_ascii_alphabetic())
+    text.split(|c: char| !c.is_alphabetic())
         .filter(|s| !s.is_empty())
 }
 
@@ -661,9 +794,9 @@ mod tests {
 
     #[test]
     fn enrich_description_handles_unicode_text() {
-        // Non-ASCII chars are split boundaries; the ASCII portions should tokenize fine
+        // Non-ASCII letters are part of words, so "créer" stays whole
         let result = enrich_description("Manages calendar entries (créer).");
-        // "manages", "calendar", "entries" should be extracted; "cr" + "er" filtered by len
+        // "manages", "calendar", "entries" should be extracted
         assert!(result.contains("[keywords:"));
         let kws_part = result.split("[keywords:").nth(1).unwrap_or("");
         assert!(
@@ -688,4 +821,96 @@ mod tests {
             );
         }
     }
+
+    // ── AutotagRules ────────────────────────────────────────────────────
+
+    fn rules(yaml: &str) -> AutotagRules {
+        let config: AutotagConfig = serde_yaml::from_str(yaml).unwrap();
+        AutotagRules::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn tokenize_keeps_non_ascii_letters_in_words() {
+        let tokens: Vec<&str> = tokenize("Hakee säätiedot kaupungille").collect();
+        assert_eq!(tokens, ["Hakee", "säätiedot", "kaupungille"]);
+    }
+
+    #[test]
+    fn default_rules_match_enrich_description() {
+        let desc = "Update an email thread: mark done, move between Important and Other.";
+        assert_eq!(
+            AutotagRules::default().enrich(desc),
+            enrich_description(desc)
+        );
+    }
+
+    #[test]
+    fn configured_stopwords_extend_builtin_list() {
+        // GIVEN: Finnish filler words configured as stopwords
+        let rules = rules("stopwords: [Hakee, palauttaa]");
+        // WHEN
+        let keywords = rules.extract_keywords("Hakee ja palauttaa säätiedot from the API");
+        // THEN: both configured and built-in stopwords are dropped
+        assert!(keywords.contains(&"säätiedot".to_string()), "{keywords:?}");
+        assert!(!keywords.contains(&"hakee".to_string()));
+        assert!(!keywords.contains(&"palauttaa".to_string()));
+        assert!(!keywords.contains(&"from".to_string()));
+    }
+
+    #[test]
+    fn replace_default_stopwords_drops_builtin_list() {
+        let rules = rules("replace_default_stopwords: true\nstopwords: [und]");
+        let keywords = rules.extract_keywords("Returns weather und forecast");
+        assert!(keywords.contains(&"returns".to_string()), "{keywords:?}");
+        assert!(!keywords.contains(&"und".to_string()));
+    }
+
+    #[test]
+    fn configured_keywords_survive_truncation() {
+        let rules = rules("max_tags: 2\nkeywords: [lasku]");
+        let keywords = rules.extract_keywords("Luo lasku asiakkaalle järjestelmässä");
+        assert_eq!(keywords[0], "lasku");
+        assert_eq!(keywords.len(), 2);
+    }
+
+    #[test]
+    fn rule_tags_lead_and_matches_become_tags() {
+        // GIVEN: a tag rule and a capture rule
+        let rules = rules(
+            r#"
+max_tags: 3
+rules:
+  - pattern: "(?i)\\binvoice"
+    tags: [Billing]
+  - pattern: "\\b([A-Z]{2,}-\\d+)\\b"
+"#,
+        );
+        // WHEN
+        let result = rules.enrich("Attach invoice to ticket OPS-42 in the accounting system");
+        // THEN: rule tags come first, then the extracted words up to the cap
+        assert!(
+            result.ends_with("[keywords: billing, ops-42, accounting]"),
+            "{result}"
+        );
+    }
+
+    #[test]
+    fn rule_without_match_adds_nothing() {
+        let rules = rules("rules:\n  - pattern: \"(?i)invoice\"\n    tags: [billing]");
+        let keywords = rules.extract_keywords("Creates calendar events");
+        assert!(!keywords.contains(&"billing".to_string()));
+    }
+
+    #[test]
+    fn from_config_rejects_invalid_pattern() {
+        let config = AutotagConfig {
+            rules: vec![crate::config::AutotagRuleConfig {
+                pattern: "(".to_string(),
+                tags: Vec::new(),
+            }],
+            ..AutotagConfig::default()
+        };
+        assert!(AutotagRules::from_config(&config).is_err());
+        assert!(config.validate().is_err());
+    }
 }
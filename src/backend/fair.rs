@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Weighted-fair admission to a backend's concurrency slots.
+//!
+//! [`FairScheduler`] replaces a first-come-first-served semaphore with
+//! start-time fair queuing across sessions. Every request is tagged with a
+//! virtual start time `max(vtime, session's previous finish)` and a finish
+//! time `start + 1 / weight`; when the backend is saturated, a freed slot goes
+//! to the queued request with the smallest start tag. While several sessions
+//! are backlogged each one is served in proportion to its weight, so a session
+//! running a 200-step playbook cannot lock out one that arrives after it.
+//! Without contention requests are admitted immediately.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+use crate::{Error, Result};
+
+/// Session key used for requests that carry no session (health probes,
+/// metadata refreshes, direct-route traffic).
+pub(crate) const SHARED_SESSION: &str = "";
+
+/// Weighted-fair admission control for one backend.
+pub(crate) struct FairScheduler {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    /// Free concurrency slots.
+    available: usize,
+    /// Start tag of the most recently admitted request.
+    vtime: f64,
+    /// Finish tag of each session's latest request. Entries at or below
+    /// `vtime` carry no history and are pruned.
+    finish: HashMap<String, f64>,
+    /// Requests waiting for a slot, smallest start tag first.
+    queue: BinaryHeap<Waiter>,
+    /// Arrival counter breaking ties between equal tags (FIFO).
+    seq: u64,
+}
+
+struct Waiter {
+    start: f64,
+    seq: u64,
+    tx: oneshot::Sender<FairPermit>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    // Reversed so the max-heap pops the smallest (start, seq).
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .start
+            .total_cmp(&self.start)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// A held concurrency slot; released (and handed to the next waiter) on drop.
+pub(crate) struct FairPermit {
+    state: Arc<Mutex<State>>,
+    /// Cleared on a permit bounced back by a cancelled waiter, so dropping
+    /// it does not release the slot the handing-over permit still owns.
+    holds_slot: bool,
+}
+
+impl Drop for FairPermit {
+    fn drop(&mut self) {
+        if !self.holds_slot {
+            return;
+        }
+        // Hand the slot to the first waiter still listening. A waiter can be
+        // cancelled after the check and bounce the permit back; that permit
+        // gives up its claim and the next waiter is tried, all in this drop.
+        loop {
+            let waiter = {
+                let mut state = self.state.lock();
+                loop {
+                    match state.queue.pop() {
+                        None => {
+                            state.available += 1;
+                            return;
+                        }
+                        Some(waiter) if waiter.tx.is_closed() => {}
+                        Some(waiter) => {
+                            state.admit(waiter.start);
+                            break waiter;
+                        }
+                    }
+                }
+            };
+            let permit = FairPermit {
+                state: Arc::clone(&self.state),
+                holds_slot: true,
+            };
+            match waiter.tx.send(permit) {
+                Ok(()) => return,
+                Err(mut bounced) => bounced.holds_slot = false,
+            }
+        }
+    }
+}
+
+impl State {
+    /// Tag a request from `session` and return its start tag.
+    fn tag(&mut self, session: &str, weight: u32) -> f64 {
+        let previous = self.finish.get(session).copied().unwrap_or(0.0);
+        let start = self.vtime.max(previous);
+        self.finish
+            .insert(session.to_string(), start + 1.0 / f64::from(weight.max(1)));
+        start
+    }
+
+    /// Advance virtual time to an admitted request's start tag.
+    fn admit(&mut self, start: f64) {
+        self.vtime = self.vtime.max(start);
+        let vtime = self.vtime;
+        self.finish.retain(|_, finish| *finish > vtime);
+    }
+}
+
+impl FairScheduler {
+    /// Create a scheduler with `capacity` concurrent slots.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                available: capacity,
+                vtime: 0.0,
+                finish: HashMap::new(),
+                queue: BinaryHeap::new(),
+                seq: 0,
+            })),
+        }
+    }
+
+    /// Wait for a slot on behalf of `session`, weighted by `weight`
+    /// (0 is treated as 1).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BackendUnavailable`] if the scheduler is torn down
+    /// while the request is queued.
+    pub(crate) async fn acquire(&self, session: &str, weight: u32) -> Result<FairPermit> {
+        let rx = {
+            let mut state = self.state.lock();
+            let start = state.tag(session, weight);
+            if state.available > 0 && state.queue.is_empty() {
+                state.available -= 1;
+                state.admit(start);
+                return Ok(FairPermit {
+                    state: Arc::clone(&self.state),
+                    holds_slot: true,
+                });
+            }
+            let (tx, rx) = oneshot::channel();
+            state.seq += 1;
+            let seq = state.seq;
+            state.queue.push(Waiter { start, seq, tx });
+            rx
+        };
+        rx.await
+            .map_err(|_| Error::BackendUnavailable("Concurrency limit reached".to_string()))
+    }
+
+    /// Number of requests waiting for a slot.
+    #[cfg(test)]
+    pub(crate) fn queued(&self) -> usize {
+        self.state.lock().queue.len()
+    }
+}
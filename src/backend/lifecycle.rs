@@ -11,15 +11,17 @@ use std::time::Duration;
 
 use dashmap::DashMap;
 use reqwest::Client;
-use tokio::sync::Semaphore;
 use tracing::{info, warn};
 
 use super::Backend;
 use super::cached_metadata::CachedMetadata;
+use super::fair::FairScheduler;
 use super::pool::{PoolKey, PooledEntry, now_unix_secs};
-use crate::config::{BackendConfig, RuntimeConfig, TransportConfig};
+use crate::config::{BackendConfig, OAuthGrantType, RuntimeConfig, TransportConfig};
 use crate::oauth::{OAuthClient, OAuthClientConfig, TokenStorage};
+use crate::provider::transforms::{ArgumentTransform, FilterTransform, NamespaceTransform};
 use crate::runtime::{RuntimeLaunchCommand, RuntimeLaunchMode, RuntimePlan, RuntimeProviderKind};
+use crate::secrets::SecretResolver;
 use crate::transport::{HttpTransport, StdioTransport, Transport};
 use crate::{Error, Result};
 
@@ -119,6 +121,15 @@ impl Backend {
         cache_ttl: Duration,
         runtime_plan: Option<RuntimePlan>,
     ) -> Self {
+        let namespace = config.namespace.as_deref().map(NamespaceTransform::new);
+        let tool_filter = (!config.tools.is_empty())
+            .then(|| FilterTransform::new(config.tools.allow.clone(), config.tools.deny.clone()));
+        let argument_transforms = config
+            .argument_transforms
+            .iter()
+            .filter(|(_, rules)| !rules.is_empty())
+            .map(|(tool, rules)| (tool.clone(), ArgumentTransform::new(rules.clone())))
+            .collect();
         Self {
             name: name.to_string(),
             config,
@@ -138,8 +149,12 @@ impl Backend {
             prompts_cache: CachedMetadata::new(),
             cache_ttl,
             last_used: std::sync::atomic::AtomicU64::new(0),
-            semaphore: Semaphore::new(100), // Max concurrent requests
+            scheduler: FairScheduler::new(100), // Max concurrent requests
             request_count: std::sync::atomic::AtomicU64::new(0),
+            namespace,
+            tool_filter,
+            argument_transforms,
+            oauth_refresh: Arc::default(),
         }
     }
 
@@ -304,7 +319,7 @@ impl Backend {
                     &launch.command,
                     launch.env,
                     cwd.clone(),
-                    self.config.timeout,
+                    self.config.max_timeout(),
                     protocol_version.clone(),
                 );
                 transport.start().await?;
@@ -316,12 +331,26 @@ impl Backend {
                 protocol_version,
             } => {
                 // Create OAuth client if configured
-                let oauth_client = self.create_oauth_client(http_url)?;
+                let account = match key {
+                    PoolKey::Account { name } => Some(name.as_str()),
+                    _ => None,
+                };
+                let oauth_client = self.create_oauth_client(http_url, account)?;
+
+                // Header values may be `{env.VAR}` / `{keychain.SERVICE}` /
+                // `{vault.MOUNT/PATH#FIELD}` references
+                let resolver = SecretResolver::new();
+                let headers = self
+                    .config
+                    .headers
+                    .iter()
+                    .map(|(name, value)| Ok((name.clone(), resolver.resolve(value)?)))
+                    .collect::<Result<HashMap<_, _>>>()?;
 
                 let transport = HttpTransport::new_with_oauth(
                     http_url,
-                    self.config.headers.clone(),
-                    self.config.timeout,
+                    headers,
+                    self.config.max_timeout(),
                     *streamable_http,
                     oauth_client,
                     protocol_version.clone(),
@@ -358,8 +387,13 @@ impl Backend {
         Ok(transport)
     }
 
-    /// Create OAuth client if OAuth is configured for this backend
-    pub(super) fn create_oauth_client(&self, resource_url: &str) -> Result<Option<OAuthClient>> {
+    /// Create OAuth client if OAuth is configured for this backend, holding
+    /// the token of the named `account` (`None` = default login).
+    pub(super) fn create_oauth_client(
+        &self,
+        resource_url: &str,
+        account: Option<&str>,
+    ) -> Result<Option<OAuthClient>> {
         let oauth_config = match &self.config.oauth {
             Some(cfg) if cfg.enabled => cfg,
             _ => return Ok(None),
@@ -384,7 +418,16 @@ impl Backend {
             )));
         }
 
-        info!(backend = %self.name, "Initializing OAuth client");
+        if let Some(account) = account
+            && oauth_config.account(account).is_none()
+        {
+            return Err(Error::Config(format!(
+                "backend '{}' has no oauth account '{account}'",
+                self.name
+            )));
+        }
+
+        info!(backend = %self.name, account, "Initializing OAuth client");
 
         // Create HTTP client for OAuth requests
         let http_client = Client::builder()
@@ -398,6 +441,12 @@ impl Backend {
                 .map_err(|e| Error::OAuth(format!("Failed to create token storage: {e}")))?,
         );
 
+        // Client credentials may be `{env.VAR}` / `{keychain.SERVICE}` / `{vault.…}` references
+        let resolver = SecretResolver::new();
+        let resolve = |value: Option<&String>| value.map(|v| resolver.resolve(v)).transpose();
+        let client_id = resolve(oauth_config.client_id.as_ref())?;
+        let client_secret = resolve(oauth_config.client_secret.as_ref())?;
+
         // Create OAuth client
         let oauth = OAuthClient::new(
             http_client,
@@ -406,12 +455,15 @@ impl Backend {
             oauth_config.scopes.clone(),
             storage,
             OAuthClientConfig {
-                client_id: oauth_config.client_id.clone(),
-                client_secret: oauth_config.client_secret.clone(),
+                client_id,
+                client_secret,
                 callback_host: oauth_config.callback_host.clone(),
                 callback_port: oauth_config.callback_port,
                 callback_path: oauth_config.callback_path.clone(),
                 token_refresh_buffer_secs: oauth_config.token_refresh_buffer_secs,
+                client_credentials: oauth_config.grant_type == OAuthGrantType::ClientCredentials,
+                refresh_health: Some(Arc::clone(&self.oauth_refresh)),
+                account: account.map(str::to_string),
             },
         );
 
@@ -79,6 +79,29 @@ impl Backend {
             .unwrap_or_else(|| Arc::new(Vec::new()))
     }
 
+    /// Prefix tool names with this backend's configured namespace, if any.
+    ///
+    /// Runs after annotation inference so naming-convention hints are derived
+    /// from the backend's own tool names rather than the prefixed ones.
+    pub(crate) fn apply_tool_namespace(&self, tools: &mut [Tool]) {
+        let Some(namespace) = &self.namespace else {
+            return;
+        };
+        for tool in tools {
+            tool.name = namespace.prefixed(&tool.name);
+        }
+    }
+
+    /// Drop tools hidden by this backend's configured allow/deny filter.
+    ///
+    /// Runs after [`Self::apply_tool_namespace`], so patterns match the
+    /// exposed (prefixed) names.
+    pub(crate) fn apply_tool_filter(&self, tools: &mut Vec<Tool>) {
+        if let Some(filter) = &self.tool_filter {
+            tools.retain(|tool| filter.is_allowed(&tool.name));
+        }
+    }
+
     async fn get_cached_list_shared<T, F>(
         &self,
         cache: &CachedMetadata<Vec<T>>,
@@ -117,6 +140,8 @@ impl Backend {
         self.get_cached_list_shared(&self.tools_cache, "tools/list", "tools", |result| {
             let mut tools = serde_json::from_value::<ToolsListResult>(result)?.tools;
             normalize_tool_annotations(&self.name, &mut tools);
+            self.apply_tool_namespace(&mut tools);
+            self.apply_tool_filter(&mut tools);
             Ok(tools)
         })
         .await
@@ -2,19 +2,22 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Backend management
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 use std::time::Duration;
 
 use dashmap::DashMap;
-use tokio::sync::Semaphore;
 
 use crate::config::BackendConfig;
+use crate::oauth::RefreshHealth;
 use crate::protocol::{Prompt, Resource, ResourceTemplate, Tool};
+use crate::provider::transforms::{ArgumentTransform, FilterTransform, NamespaceTransform};
 use crate::runtime::RuntimePlan;
 
 mod annotations;
 mod cached_metadata;
+mod fair;
 mod lifecycle;
 mod metadata;
 mod ops;
@@ -22,9 +25,11 @@ mod pool;
 mod registry;
 
 use cached_metadata::CachedMetadata;
+use fair::FairScheduler;
 use pool::{PoolKey, PooledEntry};
 
 pub(crate) use annotations::normalize_tool_annotations;
+pub(crate) use fair::SHARED_SESSION;
 pub use lifecycle::runtime_plan_for_backend;
 pub use registry::{BackendRegistry, BackendRuntimeState, BackendRuntimeStatus, BackendStatus};
 
@@ -60,10 +65,22 @@ pub struct Backend {
     cache_ttl: Duration,
     /// Last used timestamp
     last_used: AtomicU64,
-    /// Concurrency limiter
-    semaphore: Semaphore,
+    /// Concurrency limiter, weighted-fair across sessions
+    scheduler: FairScheduler,
     /// Request counter
     request_count: AtomicU64,
+    /// Tool namespace prefix from `BackendConfig::namespace`, applied to
+    /// listed tool names and stripped from `tools/call` before forwarding.
+    namespace: Option<NamespaceTransform>,
+    /// Tool allow/deny filter from `BackendConfig::tools`, applied to listed
+    /// tools and enforced on `tools/call`.
+    tool_filter: Option<FilterTransform>,
+    /// Per-tool argument rewriting from `BackendConfig::argument_transforms`,
+    /// keyed by exposed tool name.
+    argument_transforms: HashMap<String, ArgumentTransform>,
+    /// Background OAuth token refresh outcomes, shared with every OAuth
+    /// client this backend creates and polled by operator alerts.
+    oauth_refresh: Arc<RefreshHealth>,
 }
 
 #[cfg(test)]
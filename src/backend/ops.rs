@@ -8,10 +8,12 @@ use std::sync::atomic::Ordering;
 use serde_json::Value;
 
 use super::Backend;
+use super::fair::SHARED_SESSION;
 use super::registry::{BackendRuntimeState, BackendRuntimeStatus, BackendStatus};
 use crate::config::TransportConfig;
 use crate::failsafe::with_retry;
-use crate::protocol::JsonRpcResponse;
+use crate::oauth::RefreshHealth;
+use crate::protocol::{JsonRpcResponse, compat};
 use crate::{Error, Result};
 
 impl Backend {
@@ -46,6 +48,12 @@ impl Backend {
         self.request_with_headers(method, params, &[], None).await
     }
 
+    /// This backend's OAuth config, if any.
+    #[must_use]
+    pub fn oauth_config(&self) -> Option<&crate::config::OAuthConfig> {
+        self.config.oauth.as_ref()
+    }
+
     /// This backend's end-user identity-propagation config, if configured
     /// (MIK-6704 / ADR-007). `None` -> static-credential behavior unchanged.
     #[must_use]
@@ -108,8 +116,48 @@ impl Backend {
         params: Option<Value>,
         extra_headers: &[(String, String)],
         identity_key: Option<&str>,
+    ) -> Result<JsonRpcResponse> {
+        self.request_for_session(
+            method,
+            params,
+            extra_headers,
+            identity_key,
+            None,
+            SHARED_SESSION,
+            1,
+        )
+        .await
+    }
+
+    /// Send a request on behalf of `session_id`, as
+    /// [`request_with_headers`](Self::request_with_headers).
+    ///
+    /// When the backend's concurrency slots are saturated, queued requests are
+    /// admitted weighted-fair across sessions (`weight` from the session's
+    /// routing profile) instead of first-come-first-served.
+    ///
+    /// `account` names one of the backend's OAuth accounts (`oauth.accounts`);
+    /// the request then runs on that account's own transport and token.
+    /// `None` uses the default login.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend is unavailable, the concurrency limit
+    /// is reached, or the request itself fails after retries.
+    #[allow(clippy::too_many_arguments)] // Caller context threaded explicitly (identity, account, session)
+    pub async fn request_for_session(
+        &self,
+        method: &str,
+        params: Option<Value>,
+        extra_headers: &[(String, String)],
+        identity_key: Option<&str>,
+        account: Option<&str>,
+        session_id: &str,
+        weight: u32,
     ) -> Result<JsonRpcResponse> {
         let start_time = std::time::Instant::now();
+        let timeout = self.request_timeout(method, params.as_ref());
+        let mut params = self.route_tool_call(method, params)?;
 
         // Derive the per-identity pool slot FIRST (MIK-6735 fix 1, adversarial
         // review of commit bfd62b91). Each slot owns its own circuit breaker +
@@ -121,8 +169,8 @@ impl Backend {
         // user backend, or a per-user backend request without a resolved
         // identity, collapses to the shared canonical slot (IDP.5); a per-user
         // request gets its own transport/session/failsafe so users never
-        // collide (IDP.7).
-        let key = self.pool_key_for(identity_key);
+        // collide (IDP.7). A named OAuth account gets its own slot too.
+        let key = self.pool_key_for_account(identity_key, account);
         let entry = self.pooled_entry(&key);
 
         // Check THIS slot's failsafe, not the backend's.
@@ -141,17 +189,25 @@ impl Backend {
         )
         .set(1.0_f64);
 
-        // Acquire semaphore
-        let _permit = self.semaphore.acquire().await.map_err(|_| {
-            tracing::warn!("Concurrency limit reached");
-            Error::BackendUnavailable("Concurrency limit reached".to_string())
-        })?;
+        // Wait for a concurrency slot, weighted-fair across sessions.
+        let _permit = self.scheduler.acquire(session_id, weight).await?;
 
         self.request_count.fetch_add(1, Ordering::Relaxed);
 
         // Ensure this slot's transport is live.
         let transport = self.ensure_entry_started(&key).await?;
 
+        // Speak the backend's protocol version (see `protocol::compat`).
+        let version = transport.protocol_version();
+        if let (Some(version), Some(params)) = (version.as_deref(), params.as_mut()) {
+            compat::downgrade_params(method, params, version);
+        }
+        // Tell the backend who is calling, if it opted in.
+        let mut extra_headers = extra_headers.to_vec();
+        if let Some(forward) = &self.config.forward_identity {
+            crate::gateway::caller::apply(forward, &mut params, &mut extra_headers);
+        }
+
         // Execute with retry
         let name = self.name.clone();
         // Own the identity key so the retry closure (Fn, invoked once per
@@ -162,12 +218,22 @@ impl Backend {
             let transport = std::sync::Arc::clone(&transport);
             let method = method.to_string();
             let params = params.clone();
-            let extra_headers = extra_headers.to_vec();
+            let extra_headers = extra_headers.clone();
             let identity_key = identity_key.clone();
+            let name = name.clone();
             async move {
-                transport
-                    .request_with_headers(&method, params, &extra_headers, identity_key.as_deref())
-                    .await
+                let call = transport.request_with_headers(
+                    &method,
+                    params,
+                    &extra_headers,
+                    identity_key.as_deref(),
+                );
+                tokio::time::timeout(timeout, call).await.map_err(|_| {
+                    Error::BackendTimeout(format!(
+                        "{name}: {method} did not complete within {}s",
+                        timeout.as_secs()
+                    ))
+                })?
             }
         })
         .await;
@@ -210,7 +276,12 @@ impl Backend {
         )
         .record(latency.as_secs_f64());
 
-        result
+        result.map(|mut response| {
+            if let (Some(version), Some(result)) = (version.as_deref(), response.result.as_mut()) {
+                compat::upgrade_result(method, result, version);
+            }
+            response
+        })
     }
 
     /// Send a notification to the backend via the canonical shared slot's
@@ -283,10 +354,7 @@ impl Backend {
         )
         .set(1.0_f64);
 
-        let _permit = self.semaphore.acquire().await.map_err(|_| {
-            tracing::warn!("Concurrency limit reached");
-            Error::BackendUnavailable("Concurrency limit reached".to_string())
-        })?;
+        let _permit = self.scheduler.acquire(SHARED_SESSION, 1).await?;
 
         self.request_count.fetch_add(1, Ordering::Relaxed);
 
@@ -331,6 +399,85 @@ impl Backend {
         result
     }
 
+    /// Deadline of one attempt of `method`: the called tool's `timeouts`
+    /// entry for `tools/call`, else the backend `timeout`.
+    fn request_timeout(&self, method: &str, params: Option<&Value>) -> std::time::Duration {
+        match params.and_then(|p| p.get("name")).and_then(Value::as_str) {
+            Some(tool) if method == "tools/call" => {
+                self.config.tool_timeout(&self.exposed_tool_name(tool))
+            }
+            _ => self.config.timeout,
+        }
+    }
+
+    /// The name a tool is listed under, whether the caller sent it with or
+    /// without the namespace prefix. Per-tool settings and the tool filter
+    /// are keyed by this name, so a bare name cannot slip past them.
+    fn exposed_tool_name(&self, tool: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => namespace.prefixed(&namespace.strip(tool)),
+            None => tool.to_string(),
+        }
+    }
+
+    /// Apply the configured tool filter, argument rewriting, and namespace to
+    /// a `tools/call`.
+    ///
+    /// A tool hidden by `tools.allow`/`tools.deny` is refused with
+    /// [`Error::ToolNotFound`] — it was never listed, so it does not exist as
+    /// far as the caller is concerned. Otherwise the tool's
+    /// `argument_transforms` rules rewrite its arguments and the namespace
+    /// prefix is stripped so the backend receives the name it advertised.
+    /// Both checks see the exposed name, also for a call by the bare name.
+    /// Other methods pass through unchanged.
+    fn route_tool_call(&self, method: &str, mut params: Option<Value>) -> Result<Option<Value>> {
+        if method != "tools/call"
+            || (self.namespace.is_none()
+                && self.tool_filter.is_none()
+                && self.argument_transforms.is_empty())
+        {
+            return Ok(params);
+        }
+        let Some(obj) = params.as_mut().and_then(Value::as_object_mut) else {
+            return Ok(params);
+        };
+        let Some(tool) = obj
+            .get("name")
+            .and_then(Value::as_str)
+            .map(|name| self.exposed_tool_name(name))
+        else {
+            return Ok(params);
+        };
+        if let Some(filter) = &self.tool_filter
+            && !filter.is_allowed(&tool)
+        {
+            return Err(Error::ToolNotFound(tool));
+        }
+        if let Some(rewrite) = self.argument_transforms.get(&tool) {
+            let args = obj.remove("arguments").unwrap_or(Value::Null);
+            obj.insert("arguments".to_string(), rewrite.rewrite(args)?);
+        }
+        if let Some(namespace) = &self.namespace {
+            obj.insert("name".to_string(), Value::String(namespace.strip(&tool)));
+        }
+        Ok(params)
+    }
+
+    /// The cost model used for spend attribution.
+    #[must_use]
+    pub fn cost_config(&self) -> &crate::config::BackendCostConfig {
+        &self.config.cost
+    }
+
+    /// The configured response transform for an exposed tool name, if any.
+    #[must_use]
+    pub fn response_transform(&self, tool: &str) -> Option<&crate::transform::TransformConfig> {
+        self.config
+            .response_transforms
+            .get(tool)
+            .filter(|config| !config.is_empty())
+    }
+
     /// Return `true` if this backend is configured for pass-through mode.
     ///
     /// When `true`, the direct `/mcp/{name}` endpoint skips tool policy
@@ -418,6 +565,22 @@ impl Backend {
         self.shared_entry().failsafe.circuit_breaker.stats()
     }
 
+    /// The most recent trip of this backend's canonical Shared-slot circuit
+    /// breaker, if it ever opened.
+    #[must_use]
+    pub fn last_circuit_open_event(&self) -> Option<crate::failsafe::BreakerOpenEvent> {
+        self.shared_entry()
+            .failsafe
+            .circuit_breaker
+            .last_open_event()
+    }
+
+    /// Background OAuth token refresh outcomes for this backend.
+    #[must_use]
+    pub fn oauth_refresh_health(&self) -> &RefreshHealth {
+        &self.oauth_refresh
+    }
+
     /// Force this backend's canonical Shared-slot circuit breaker back to
     /// `Closed` (MIK-5983; slot-scoped per MIK-6735 fix 1).
     ///
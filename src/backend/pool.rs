@@ -41,6 +41,9 @@ pub(crate) enum PoolKey {
     /// A per-user slot keyed by the caller's stable identity binding
     /// (`PropagatedCredential::cache_binding`, MIK-6784).
     PerUser { binding: String },
+    /// A slot for one of the backend's named OAuth accounts
+    /// (`oauth.accounts`); its transport holds that account's token.
+    Account { name: String },
 }
 
 /// One pooled transport slot: its lazily started transport, a start lock that
@@ -105,6 +108,21 @@ impl Backend {
         }
     }
 
+    /// Derive the pool slot for a request made as OAuth `account`; the default
+    /// account (`None`) falls back to [`pool_key_for`](Self::pool_key_for).
+    pub(super) fn pool_key_for_account(
+        &self,
+        identity_key: Option<&str>,
+        account: Option<&str>,
+    ) -> PoolKey {
+        match account {
+            Some(name) => PoolKey::Account {
+                name: name.to_string(),
+            },
+            None => self.pool_key_for(identity_key),
+        }
+    }
+
     /// Fetch (or lazily create) the pooled entry for `key`. The `Arc` is cloned
     /// out so the `DashMap` shard guard is released before any `.await`.
     ///
@@ -71,6 +71,7 @@ fn per_user_backend() -> Arc<Backend> {
         session_mode: crate::identity_propagation::SessionMode::PerUser,
         token_exchange_endpoint: None,
         token_exchange_scope: None,
+        token_exchange_client: None,
     };
     let cfg = BackendConfig {
         transport: TransportConfig::Http {
@@ -264,6 +265,39 @@ fn pool_key_collapses_to_shared_without_per_user_identity() {
     );
 }
 
+// A named OAuth account runs on its own slot, separate from the default
+// login's shared slot.
+#[tokio::test]
+async fn account_requests_route_to_their_own_slot() {
+    let backend = Backend::new(
+        "crm",
+        BackendConfig::default(),
+        &crate::config::FailsafeConfig::default(),
+        Duration::from_secs(60),
+    );
+    let account_key = PoolKey::Account {
+        name: "alice".to_string(),
+    };
+    backend.set_pooled_transport_for_test(&PoolKey::Shared, Arc::new(SessionMock::new("default")));
+    backend.set_pooled_transport_for_test(&account_key, Arc::new(SessionMock::new("alice")));
+
+    let as_alice = backend
+        .request_for_session("tools/list", None, &[], None, Some("alice"), "s1", 1)
+        .await
+        .unwrap();
+    let as_default = backend
+        .request_for_session("tools/list", None, &[], None, None, "s1", 1)
+        .await
+        .unwrap();
+
+    assert_eq!(as_alice.result.unwrap()["session"], json!("alice"));
+    assert_eq!(as_default.result.unwrap()["session"], json!("default"));
+    assert_eq!(
+        backend.pool_key_for_account(Some("userA"), Some("alice")),
+        account_key
+    );
+}
+
 // POOL.2: idle per-user slots are evicted and their transports closed, the
 // shared canonical slot is NEVER evicted, and a later request lazily
 // re-creates a fresh slot.
@@ -187,6 +187,8 @@ fn oauth_requires_per_user_isolation_reflects_config() {
     };
     let oauth = |enabled: bool, shared: bool| crate::config::OAuthConfig {
         enabled,
+        grant_type: crate::config::OAuthGrantType::AuthorizationCode,
+        accounts: Vec::new(),
         scopes: vec![],
         client_id: None,
         client_secret: None,
@@ -224,6 +226,8 @@ fn create_oauth_client_refuses_identity_propagation_backends() {
     let oauth_enabled = crate::config::OAuthConfig {
         enabled: true,
         scopes: vec![],
+        grant_type: crate::config::OAuthGrantType::AuthorizationCode,
+        accounts: Vec::new(),
         client_id: None,
         client_secret: None,
         callback_host: None,
@@ -240,6 +244,7 @@ fn create_oauth_client_refuses_identity_propagation_backends() {
             session_mode: crate::identity_propagation::SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }
     };
     let mk = |strategy| {
@@ -259,7 +264,7 @@ fn create_oauth_client_refuses_identity_propagation_backends() {
         crate::identity_propagation::PropagationStrategyKind::Passthrough,
     ] {
         let backend = mk(strategy);
-        match backend.create_oauth_client("https://backend.example") {
+        match backend.create_oauth_client("https://backend.example", None) {
             Err(Error::ConfigValidation(_)) => {}
             Err(other) => {
                 panic!("expected ConfigValidation, got {other:?} for {strategy:?}")
@@ -289,7 +294,7 @@ fn create_oauth_client_refuses_identity_propagation_backends() {
     );
     assert!(
         shared
-            .create_oauth_client("https://backend.example")
+            .create_oauth_client("https://backend.example", None)
             .is_err(),
         "shared_account=true must not exempt the F3 guard"
     );
@@ -306,7 +311,9 @@ fn create_oauth_client_refuses_identity_propagation_backends() {
         Duration::from_secs(60),
     );
     assert!(
-        plain.create_oauth_client("https://backend.example").is_ok(),
+        plain
+            .create_oauth_client("https://backend.example", None)
+            .is_ok(),
         "backend oauth without identity_propagation must still be allowed"
     );
 }
@@ -742,3 +749,374 @@ async fn get_tools_does_not_cache_json_rpc_error_response() {
     assert!(!backend.has_cached_tools());
     assert_eq!(transport.requests.load(Ordering::SeqCst), 1);
 }
+
+// Records the params of every request and answers `tools/list` with a single
+// `search` tool, so namespace prefixing and stripping can be observed.
+struct NamespaceRecorder {
+    calls: parking_lot::Mutex<Vec<(String, Option<Value>)>>,
+}
+
+#[async_trait]
+impl Transport for NamespaceRecorder {
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<JsonRpcResponse> {
+        self.calls.lock().push((method.to_string(), params));
+        let result = if method == "tools/list" {
+            serde_json::to_value(ToolsListResult {
+                tools: vec![sample_tool("search")],
+                next_cursor: None,
+            })
+            .unwrap()
+        } else {
+            json!({"content": []})
+        };
+        Ok(JsonRpcResponse::success_serialized(
+            RequestId::Number(1),
+            result,
+        ))
+    }
+
+    async fn notify(&self, _method: &str, _params: Option<Value>) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn namespace_prefixes_listed_tools_and_strips_on_call() {
+    let backend = Backend::new(
+        "docs",
+        BackendConfig {
+            namespace: Some("docs".to_string()),
+            ..BackendConfig::default()
+        },
+        &crate::config::FailsafeConfig::default(),
+        Duration::from_secs(60),
+    );
+    let transport = Arc::new(NamespaceRecorder {
+        calls: parking_lot::Mutex::new(Vec::new()),
+    });
+    let transport_dyn: Arc<dyn Transport> = transport.clone();
+    backend.set_transport_for_test(transport_dyn);
+
+    let tools = backend.get_tools().await.unwrap();
+    assert_eq!(tools[0].name, "docs_search");
+    assert!(backend.get_cached_tool("docs_search").is_some());
+
+    backend
+        .request(
+            "tools/call",
+            Some(json!({"name": "docs_search", "arguments": {"q": "x"}})),
+        )
+        .await
+        .unwrap();
+
+    let calls = transport.calls.lock();
+    let (method, params) = calls.last().unwrap();
+    assert_eq!(method, "tools/call");
+    let params = params.as_ref().unwrap();
+    assert_eq!(params["name"], "search");
+    assert_eq!(params["arguments"]["q"], "x");
+}
+
+#[tokio::test]
+async fn tool_filter_hides_denied_tools_and_refuses_their_calls() {
+    let backend = Backend::new(
+        "docs",
+        BackendConfig {
+            tools: crate::config::BackendToolFilter {
+                allow: Vec::new(),
+                deny: vec!["*search*".to_string()],
+            },
+            ..BackendConfig::default()
+        },
+        &crate::config::FailsafeConfig::default(),
+        Duration::from_secs(60),
+    );
+    let transport = Arc::new(NamespaceRecorder {
+        calls: parking_lot::Mutex::new(Vec::new()),
+    });
+    let transport_dyn: Arc<dyn Transport> = transport.clone();
+    backend.set_transport_for_test(transport_dyn);
+
+    assert!(backend.get_tools().await.unwrap().is_empty());
+
+    let err = backend
+        .request(
+            "tools/call",
+            Some(json!({"name": "search", "arguments": {}})),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::ToolNotFound(name) if name == "search"));
+    assert!(
+        transport
+            .calls
+            .lock()
+            .iter()
+            .all(|(method, _)| method != "tools/call"),
+        "a filtered call must never reach the backend"
+    );
+}
+
+#[tokio::test]
+async fn tool_filter_refuses_a_denied_tool_called_by_its_bare_name() {
+    // GIVEN: a namespaced backend denying its exposed `docs_search` tool
+    let backend = Backend::new(
+        "docs",
+        BackendConfig {
+            namespace: Some("docs".to_string()),
+            tools: crate::config::BackendToolFilter {
+                allow: Vec::new(),
+                deny: vec!["docs_search".to_string()],
+            },
+            ..BackendConfig::default()
+        },
+        &crate::config::FailsafeConfig::default(),
+        Duration::from_secs(60),
+    );
+    let transport = Arc::new(NamespaceRecorder {
+        calls: parking_lot::Mutex::new(Vec::new()),
+    });
+    let transport_dyn: Arc<dyn Transport> = transport.clone();
+    backend.set_transport_for_test(transport_dyn);
+
+    // WHEN: calling it without the namespace prefix
+    let err = backend
+        .request(
+            "tools/call",
+            Some(json!({"name": "search", "arguments": {}})),
+        )
+        .await
+        .unwrap_err();
+
+    // THEN: it is refused under its exposed name and never forwarded
+    assert!(matches!(err, Error::ToolNotFound(name) if name == "docs_search"));
+    assert!(
+        transport
+            .calls
+            .lock()
+            .iter()
+            .all(|(method, _)| method != "tools/call"),
+        "a filtered call must never reach the backend"
+    );
+}
+
+#[tokio::test]
+async fn argument_transforms_rewrite_call_arguments_before_forwarding() {
+    let rules: crate::provider::transforms::ArgumentRewriteConfig = serde_json::from_value(json!({
+        "rename": {"query": "q"},
+        "defaults": {"limit": 20}
+    }))
+    .unwrap();
+    let backend = Backend::new(
+        "docs",
+        BackendConfig {
+            namespace: Some("docs".to_string()),
+            argument_transforms: HashMap::from([("docs_search".to_string(), rules)]),
+            ..BackendConfig::default()
+        },
+        &crate::config::FailsafeConfig::default(),
+        Duration::from_secs(60),
+    );
+    let transport = Arc::new(NamespaceRecorder {
+        calls: parking_lot::Mutex::new(Vec::new()),
+    });
+    let transport_dyn: Arc<dyn Transport> = transport.clone();
+    backend.set_transport_for_test(transport_dyn);
+
+    backend
+        .request(
+            "tools/call",
+            Some(json!({"name": "docs_search", "arguments": {"query": "rust"}})),
+        )
+        .await
+        .unwrap();
+
+    let calls = transport.calls.lock();
+    let params = calls.last().unwrap().1.as_ref().unwrap();
+    assert_eq!(params["name"], "search");
+    assert_eq!(params["arguments"], json!({"q": "rust", "limit": 20}));
+}
+
+// Answers every request after `delay`.
+struct SlowTransport {
+    delay: Duration,
+}
+
+#[async_trait]
+impl Transport for SlowTransport {
+    async fn request(&self, _method: &str, _params: Option<Value>) -> Result<JsonRpcResponse> {
+        sleep(self.delay).await;
+        Ok(JsonRpcResponse::success_serialized(
+            RequestId::Number(1),
+            json!({"content": []}),
+        ))
+    }
+
+    async fn notify(&self, _method: &str, _params: Option<Value>) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tool_timeout_overrides_backend_timeout() {
+    // GIVEN: a 20ms backend timeout, 5s for `render_pdf`, and a backend
+    // that takes 100ms per call
+    let backend = Backend::new(
+        "docs",
+        BackendConfig {
+            timeout: Duration::from_millis(20),
+            timeouts: HashMap::from([("render_pdf".to_string(), Duration::from_secs(5))]),
+            ..BackendConfig::default()
+        },
+        &crate::config::FailsafeConfig::default(),
+        Duration::from_secs(60),
+    );
+    backend.set_transport_for_test(Arc::new(SlowTransport {
+        delay: Duration::from_millis(100),
+    }));
+    let call =
+        |tool: &str| backend.request("tools/call", Some(json!({"name": tool, "arguments": {}})));
+
+    // WHEN / THEN: the slow tool completes, any other tool times out
+    assert!(call("render_pdf").await.is_ok());
+    let err = call("search").await.unwrap_err();
+    assert!(matches!(err, Error::BackendTimeout(_)), "got {err:?}");
+}
+
+// ── Fair scheduling ─────────────────────────────────────────────────
+
+/// Queue an `acquire` for `session` on a saturated scheduler and wait until it
+/// is parked, so arrival order is deterministic. The task records its session
+/// in `order` once admitted and releases the slot immediately.
+async fn enqueue_fair(
+    scheduler: &Arc<super::fair::FairScheduler>,
+    session: &'static str,
+    weight: u32,
+    order: &Arc<parking_lot::Mutex<Vec<&'static str>>>,
+) -> tokio::task::JoinHandle<()> {
+    let queued = scheduler.queued();
+    let task_scheduler = Arc::clone(scheduler);
+    let task_order = Arc::clone(order);
+    let handle = tokio::spawn(async move {
+        let _permit = task_scheduler.acquire(session, weight).await.unwrap();
+        task_order.lock().push(session);
+    });
+    while scheduler.queued() == queued {
+        tokio::task::yield_now().await;
+    }
+    handle
+}
+
+#[tokio::test]
+async fn fair_scheduler_interleaves_sessions_instead_of_fifo() {
+    // GIVEN a saturated single-slot scheduler held by the heavy session
+    let scheduler = Arc::new(super::fair::FairScheduler::new(1));
+    let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    let holder = scheduler.acquire("heavy", 1).await.unwrap();
+
+    // WHEN heavy queues four more calls before light queues two
+    let mut handles = Vec::new();
+    for _ in 0..4 {
+        handles.push(enqueue_fair(&scheduler, "heavy", 1, &order).await);
+    }
+    for _ in 0..2 {
+        handles.push(enqueue_fair(&scheduler, "light", 1, &order).await);
+    }
+    drop(holder);
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    // THEN light is not stuck behind heavy's backlog
+    assert_eq!(
+        *order.lock(),
+        ["light", "heavy", "light", "heavy", "heavy", "heavy"]
+    );
+}
+
+#[tokio::test]
+async fn fair_scheduler_shares_slots_by_weight() {
+    // GIVEN sessions a (weight 2) and b (weight 1), both backlogged
+    let scheduler = Arc::new(super::fair::FairScheduler::new(1));
+    let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    let holder = scheduler.acquire("holder", 1).await.unwrap();
+    let mut handles = Vec::new();
+    for _ in 0..4 {
+        handles.push(enqueue_fair(&scheduler, "a", 2, &order).await);
+    }
+    for _ in 0..4 {
+        handles.push(enqueue_fair(&scheduler, "b", 1, &order).await);
+    }
+
+    // WHEN the slot is released
+    drop(holder);
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    // THEN a gets two dispatches for each of b's while both are queued
+    let order = order.lock();
+    assert_eq!(order[..6].iter().filter(|s| **s == "a").count(), 4);
+}
+
+#[tokio::test]
+async fn fair_scheduler_skips_a_long_run_of_cancelled_waiters() {
+    use futures::FutureExt as _;
+
+    // GIVEN a saturated scheduler whose queue holds 100k cancelled waiters
+    let scheduler = Arc::new(super::fair::FairScheduler::new(1));
+    let holder = scheduler.acquire("holder", 1).await.unwrap();
+    for _ in 0..100_000 {
+        assert!(scheduler.acquire("gone", 1).now_or_never().is_none());
+    }
+    assert_eq!(scheduler.queued(), 100_000);
+
+    // WHEN the slot is released
+    drop(holder);
+
+    // THEN every cancelled waiter is skipped and the slot is free again
+    assert_eq!(scheduler.queued(), 0);
+    tokio::time::timeout(Duration::from_secs(1), scheduler.acquire("later", 1))
+        .await
+        .expect("slot must be released")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn fair_scheduler_skips_cancelled_waiters() {
+    // GIVEN two queued waiters, the first of which is cancelled
+    let scheduler = Arc::new(super::fair::FairScheduler::new(1));
+    let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    let holder = scheduler.acquire("holder", 1).await.unwrap();
+    let cancelled = enqueue_fair(&scheduler, "gone", 1, &order).await;
+    let waiting = enqueue_fair(&scheduler, "next", 1, &order).await;
+    cancelled.abort();
+    assert!(cancelled.await.unwrap_err().is_cancelled());
+
+    // WHEN the slot is released
+    drop(holder);
+    waiting.await.unwrap();
+
+    // THEN the live waiter ran and the slot is free again
+    assert_eq!(*order.lock(), ["next"]);
+    tokio::time::timeout(Duration::from_secs(1), scheduler.acquire("later", 1))
+        .await
+        .expect("slot must be released")
+        .unwrap();
+}
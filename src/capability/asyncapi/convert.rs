@@ -0,0 +1,436 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! `AsyncAPI` -> webhook capability conversion engine.
+//!
+//! Single responsibility: read the channels, messages and HTTP bindings of
+//! a dereferenced document and emit one webhook-only capability.
+//!
+//! Both 2.x channels (`publish`/`subscribe` operations inline, keyed by
+//! address) and 3.0 channels (`messages` map, with operations alongside)
+//! are read. Every channel that carries a message becomes one endpoint,
+//! whatever the direction of its operations: the document describes the
+//! event source, and the gateway is always the receiving side.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::fs;
+
+use serde_json::{Value, json};
+use tracing::{debug, info};
+
+use crate::capability::GeneratedCapability;
+use crate::capability::openapi::refs::Documents;
+use crate::capability::openapi::sanitize::{sanitize_description, yaml_scalar};
+use crate::capability::openapi::schema::flatten_schema;
+use crate::{Error, Result};
+
+/// URL given to a document converted from a string; its relative refs
+/// resolve to documents that can never be loaded.
+const INLINE_DOCUMENT_URL: &str = "inline:///asyncapi";
+
+/// `AsyncAPI` to webhook capability converter
+pub struct AsyncApiConverter {
+    /// Prefix for the generated capability name
+    prefix: Option<String>,
+    /// Header carrying the HMAC signature of each delivery
+    signature_header: Option<String>,
+}
+
+/// One inbound endpoint, generated from a channel.
+struct Endpoint {
+    name: String,
+    description: Option<String>,
+    method: String,
+    event_type: Option<String>,
+    schema: Option<Value>,
+}
+
+impl AsyncApiConverter {
+    /// Create a new converter with default settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            prefix: None,
+            signature_header: None,
+        }
+    }
+
+    /// Set a prefix for the generated capability name
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Require an HMAC-SHA256 signature in `header` on every endpoint.
+    ///
+    /// The secret is read from `<CAPABILITY>_WEBHOOK_SECRET` in the
+    /// environment.
+    #[must_use]
+    pub fn with_signature_header(mut self, header: &str) -> Self {
+        self.signature_header = Some(header.to_string());
+        self
+    }
+
+    /// Convert an `AsyncAPI` document file to a webhook capability
+    ///
+    /// `$ref`s into other local files are resolved relative to the document;
+    /// refs to remote documents are left unresolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not an `AsyncAPI`
+    /// document, or defines no channel messages.
+    pub fn convert_file(&self, path: &str) -> Result<GeneratedCapability> {
+        let read = |path: &std::path::Path| {
+            fs::read_to_string(path)
+                .map_err(|e| Error::Config(format!("Failed to read AsyncAPI document: {e}")))
+        };
+        let path = fs::canonicalize(path)
+            .map_err(|e| Error::Config(format!("Failed to read AsyncAPI document: {e}")))?;
+        let root = url::Url::from_file_path(&path)
+            .map_err(|()| Error::Config(format!("Invalid document path {}", path.display())))?;
+        let mut documents = Documents::new(root, parse_document(&read(&path)?)?);
+
+        loop {
+            let missing = documents.missing();
+            if missing.is_empty() {
+                break;
+            }
+            for url in missing {
+                let document = match url.to_file_path() {
+                    Ok(path) if url.scheme() == "file" => {
+                        read(&path).and_then(|content| parse_document(&content))
+                    }
+                    _ => Err(Error::Config(
+                        "only local files are loaded for a document file".to_string(),
+                    )),
+                };
+                documents.add(&url, document);
+            }
+        }
+
+        self.convert_documents(&documents)
+    }
+
+    /// Convert an `AsyncAPI` document string to a webhook capability
+    ///
+    /// Only `$ref`s within the string itself can be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is not an `AsyncAPI` document in YAML
+    /// or JSON, or defines no channel messages.
+    pub fn convert_string(&self, content: &str) -> Result<GeneratedCapability> {
+        let root = url::Url::parse(INLINE_DOCUMENT_URL)
+            .map_err(|e| Error::Internal(format!("Invalid inline document URL: {e}")))?;
+        let mut documents = Documents::new(root, parse_document(content)?);
+        for url in documents.missing() {
+            documents.add(
+                &url,
+                Err(Error::Config(
+                    "a document given as a string cannot refer to other documents".to_string(),
+                )),
+            );
+        }
+
+        self.convert_documents(&documents)
+    }
+
+    /// Whether `content` is an `AsyncAPI` document (has a top-level
+    /// `asyncapi` version), as opposed to an `OpenAPI` spec.
+    #[must_use]
+    pub fn is_asyncapi(content: &str) -> bool {
+        parse_document(content).is_ok_and(|document| document.get("asyncapi").is_some())
+    }
+
+    fn convert_documents(&self, documents: &Documents) -> Result<GeneratedCapability> {
+        let root = documents.root();
+        let version = root
+            .get("asyncapi")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                Error::Config("Not an AsyncAPI document: no `asyncapi` version".to_string())
+            })?;
+        let title = root
+            .pointer("/info/title")
+            .and_then(Value::as_str)
+            .unwrap_or("asyncapi");
+        info!(title = %title, version = %version, "Converting AsyncAPI document");
+
+        let name = self.format_name(title);
+        let v3 = version.starts_with('3');
+        // 3.0 operations sit beside the channels; dereferenced, each holds
+        // its channel inline.
+        let operations: Vec<Value> = if v3 {
+            root.get("operations")
+                .and_then(Value::as_object)
+                .map(|ops| ops.values().map(|op| documents.dereference(op)).collect())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let mut endpoints: Vec<Endpoint> = Vec::new();
+        let mut names = HashSet::new();
+        for (key, channel) in root
+            .get("channels")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let channel = documents.dereference(channel);
+            let Some(mut endpoint) = channel_endpoint(&name, &channel, &operations, v3) else {
+                debug!(channel = %key, "Skipping channel without messages");
+                continue;
+            };
+            let base = match slug(key) {
+                webhook if webhook.is_empty() => "events".to_string(),
+                webhook => webhook,
+            };
+            endpoint.name.clone_from(&base);
+            let mut n = 2;
+            while !names.insert(endpoint.name.clone()) {
+                endpoint.name = format!("{base}_{n}");
+                n += 1;
+            }
+            endpoints.push(endpoint);
+        }
+
+        if endpoints.is_empty() {
+            return Err(Error::Config(
+                "AsyncAPI document defines no channel messages".to_string(),
+            ));
+        }
+        endpoints.sort_by(|a, b| a.name.cmp(&b.name));
+        info!(capability = %name, endpoints = endpoints.len(), "Generated webhook capability");
+
+        let description = root
+            .pointer("/info/description")
+            .and_then(Value::as_str)
+            .map(sanitize_description)
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| format!("Webhook events from {}", sanitize_description(title)));
+        let yaml = self.build_yaml(&name, title, &description, &endpoints);
+        Ok(GeneratedCapability { name, yaml })
+    }
+
+    /// Capability name: the document title in snake case, after the prefix.
+    fn format_name(&self, title: &str) -> String {
+        let mut name = slug(title);
+        if name.is_empty() {
+            name = "asyncapi_events".to_string();
+        }
+        match &self.prefix {
+            Some(prefix) => format!("{}_{name}", slug(prefix)),
+            None => name,
+        }
+    }
+
+    /// Build the capability YAML
+    fn build_yaml(
+        &self,
+        name: &str,
+        title: &str,
+        description: &str,
+        endpoints: &[Endpoint],
+    ) -> String {
+        let mut yaml = String::new();
+
+        let _ = writeln!(yaml, "# Auto-generated from AsyncAPI document");
+        let _ = writeln!(
+            yaml,
+            "# {}",
+            sanitize_description(title).lines().next().unwrap_or(name)
+        );
+        yaml.push('\n');
+
+        yaml.push_str("fulcrum: \"1.0\"\n");
+        let _ = writeln!(yaml, "name: {name}");
+        let _ = writeln!(yaml, "description: {}", yaml_scalar(description));
+        yaml.push('\n');
+
+        yaml.push_str("schema:\n  input:\n    type: object\n    properties: {}\n  output:\n    type: object\n\n");
+        yaml.push_str("providers: {}\n\n");
+
+        yaml.push_str("webhooks:\n");
+        for endpoint in endpoints {
+            let _ = writeln!(yaml, "  {}:", endpoint.name);
+            if let Some(description) = &endpoint.description
+                && let Some(line) = description.lines().next()
+            {
+                let _ = writeln!(yaml, "    # {line}");
+            }
+            let _ = writeln!(yaml, "    path: /{name}/{}", endpoint.name);
+            let _ = writeln!(yaml, "    method: {}", endpoint.method);
+            if let Some(header) = &self.signature_header {
+                let _ = writeln!(
+                    yaml,
+                    "    secret: \"{{env.{}_WEBHOOK_SECRET}}\"",
+                    name.to_uppercase()
+                );
+                let _ = writeln!(yaml, "    signature_header: {}", yaml_scalar(header));
+            }
+            yaml.push_str("    notify: true\n");
+            if let Some(event_type) = &endpoint.event_type {
+                let _ = writeln!(yaml, "    transform:\n      event_type: {event_type}");
+            }
+            if let Some(schema) = &endpoint.schema {
+                yaml.push_str("    schema:\n");
+                for line in serde_yaml::to_string(schema).unwrap_or_default().lines() {
+                    let _ = writeln!(yaml, "      {line}");
+                }
+            }
+            yaml.push('\n');
+        }
+
+        yaml.push_str("metadata:\n  category: integration\n  tags: [asyncapi, webhook, generated]\n  cost_category: free\n  execution_time: instant\n  read_only: false\n");
+
+        yaml
+    }
+}
+
+impl Default for AsyncApiConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Endpoint for a dereferenced `channel`, named by the caller; `None` when
+/// the channel carries no messages.
+///
+/// 2.x channels hold their operations; 3.0 `operations` refer to the
+/// channel they act on.
+fn channel_endpoint(
+    capability: &str,
+    channel: &Value,
+    operations: &[Value],
+    v3: bool,
+) -> Option<Endpoint> {
+    let channel_operations: Vec<&Value> = if v3 {
+        operations
+            .iter()
+            .filter(|op| op.get("channel") == Some(channel))
+            .collect()
+    } else {
+        ["publish", "subscribe"]
+            .into_iter()
+            .filter_map(|op| channel.get(op))
+            .collect()
+    };
+    let messages: Vec<Value> = if v3 {
+        channel
+            .get("messages")
+            .and_then(Value::as_object)
+            .map(|messages| messages.values().cloned().collect())
+            .unwrap_or_default()
+    } else {
+        channel_operations
+            .iter()
+            .filter_map(|op| op.get("message"))
+            .flat_map(|message| match message.get("oneOf") {
+                Some(Value::Array(variants)) => variants.clone(),
+                _ => vec![message.clone()],
+            })
+            .collect()
+    };
+    if messages.is_empty() {
+        return None;
+    }
+
+    Some(Endpoint {
+        name: String::new(),
+        description: channel
+            .get("description")
+            .or_else(|| {
+                messages
+                    .iter()
+                    .find_map(|m| m.get("summary").or_else(|| m.get("title")))
+            })
+            .or_else(|| channel_operations.iter().find_map(|op| op.get("summary")))
+            .and_then(Value::as_str)
+            .map(sanitize_description)
+            .filter(|d| !d.is_empty()),
+        method: channel_operations
+            .iter()
+            .find_map(|op| op.pointer("/bindings/http/method"))
+            .and_then(Value::as_str)
+            .map_or_else(|| "POST".to_string(), str::to_uppercase),
+        event_type: match messages.as_slice() {
+            [message] => message
+                .get("name")
+                .and_then(Value::as_str)
+                .map(|event| format!("{capability}.{}", event_name(event))),
+            _ => None,
+        },
+        schema: notification_schema(&messages),
+    })
+}
+
+/// Parse a document as JSON or, failing that, YAML.
+fn parse_document(content: &str) -> Result<Value> {
+    serde_json::from_str(content)
+        .or_else(|_| serde_yaml::from_str(content))
+        .map_err(|e| Error::Config(format!("Failed to parse AsyncAPI document: {e}")))
+}
+
+/// Schema of the notification data of a channel: its one message payload,
+/// or the payloads of all its messages merged. Payloads in a non-JSON
+/// Schema format (Avro, Protobuf, ...) are left out.
+fn notification_schema(messages: &[Value]) -> Option<Value> {
+    let mut schemas: Vec<Value> = messages.iter().filter_map(payload_schema).collect();
+    match schemas.len() {
+        0 => None,
+        1 => schemas.pop(),
+        _ => Some(flatten_schema(&json!({ "oneOf": schemas }))),
+    }
+}
+
+fn payload_schema(message: &Value) -> Option<Value> {
+    let mut payload = message.get("payload")?;
+    let mut format = message.get("schemaFormat").and_then(Value::as_str);
+    // 3.0 multi-format schema: `payload: {schemaFormat, schema}`.
+    if let (Some(inner_format), Some(schema)) = (
+        payload.get("schemaFormat").and_then(Value::as_str),
+        payload.get("schema"),
+    ) {
+        format = Some(inner_format);
+        payload = schema;
+    }
+    if let Some(format) = format
+        && !(format.starts_with("application/vnd.aai.asyncapi")
+            || format.starts_with("application/schema+json")
+            || format.starts_with("application/schema+yaml"))
+    {
+        debug!(format = %format, "Skipping payload in a non-JSON Schema format");
+        return None;
+    }
+    Some(flatten_schema(payload))
+}
+
+/// `raw` in snake case: alphanumerics kept, runs of anything else one `_`.
+fn slug(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+/// A message name usable in a literal `event_type`: no template braces or
+/// YAML-significant characters.
+fn event_name(raw: &str) -> String {
+    raw.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,23 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! `AsyncAPI` to webhook capability converter
+//!
+//! Generates a webhook-only capability from an `AsyncAPI` 2.x or 3.0
+//! document: each channel that carries messages becomes an inbound endpoint
+//! served by `gateway::webhooks`, and the channel's message payloads become
+//! the endpoint's notification schema.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! let converter = AsyncApiConverter::new().with_signature_header("X-Signature");
+//! let capability = converter.convert_file("events.yaml")?;
+//! capability.write_to_file("capabilities/")?;
+//! ```
+
+mod convert;
+
+pub use convert::AsyncApiConverter;
+
+#[cfg(test)]
+mod tests;
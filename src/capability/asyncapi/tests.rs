@@ -0,0 +1,248 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Unit tests for the `AsyncAPI` converter module.
+
+use serde_json::json;
+
+use super::*;
+use crate::capability::validator::{IssueSeverity, validate_capability_definition};
+use crate::capability::{CapabilityDefinition, parse_capability};
+use crate::config::WebhookConfig;
+use crate::gateway::webhooks::WebhookRegistry;
+
+const ASYNCAPI_2_FIXTURE: &str = r##"
+asyncapi: "2.6.0"
+info:
+  title: Acme Billing Events
+  version: "1.0"
+  description: Payment lifecycle events sent by Acme Billing.
+channels:
+  invoice/paid:
+    description: An invoice was paid in full.
+    subscribe:
+      bindings:
+        http:
+          type: request
+          method: put
+      message:
+        $ref: "#/components/messages/InvoicePaid"
+  customer/changed:
+    subscribe:
+      message:
+        oneOf:
+          - $ref: "#/components/messages/CustomerCreated"
+          - $ref: "#/components/messages/CustomerDeleted"
+  heartbeat:
+    description: Carries no messages.
+components:
+  messages:
+    InvoicePaid:
+      name: invoice.paid
+      payload:
+        type: object
+        required: [invoice_id, amount]
+        properties:
+          invoice_id: { type: string }
+          amount: { type: integer }
+    CustomerCreated:
+      name: customer.created
+      payload:
+        type: object
+        required: [customer_id, email]
+        properties:
+          customer_id: { type: string }
+          email: { type: string }
+    CustomerDeleted:
+      name: customer.deleted
+      payload:
+        type: object
+        required: [customer_id]
+        properties:
+          customer_id: { type: string }
+          deleted_at: { type: [string, "null"] }
+"##;
+
+const ASYNCAPI_3_FIXTURE: &str = r##"
+asyncapi: "3.0.0"
+info:
+  title: Shipping
+  version: "1.0"
+channels:
+  shipmentUpdated:
+    address: shipments/{shipmentId}/updated
+    messages:
+      shipmentUpdated:
+        $ref: "#/components/messages/ShipmentUpdated"
+  legacyFeed:
+    address: legacy
+    messages:
+      legacyRecord:
+        payload:
+          schemaFormat: application/vnd.apache.avro;version=1.9.0
+          schema:
+            type: record
+            name: Legacy
+operations:
+  onShipmentUpdated:
+    action: receive
+    channel:
+      $ref: "#/channels/shipmentUpdated"
+    bindings:
+      http:
+        method: PATCH
+components:
+  messages:
+    ShipmentUpdated:
+      name: shipment.updated
+      summary: Shipment status changed.
+      payload:
+        schemaFormat: application/vnd.aai.asyncapi+json;version=3.0.0
+        schema:
+          allOf:
+            - $ref: "#/components/schemas/Shipment"
+            - type: object
+              properties:
+                status: { type: string }
+  schemas:
+    Shipment:
+      type: object
+      required: [shipment_id]
+      properties:
+        shipment_id: { type: string }
+"##;
+
+fn converted(content: &str) -> CapabilityDefinition {
+    let generated = AsyncApiConverter::new()
+        .convert_string(content)
+        .expect("document converts");
+    parse_capability(&generated.yaml).unwrap_or_else(|e| panic!("{e}\n{}", generated.yaml))
+}
+
+#[test]
+fn asyncapi_2_channels_become_webhooks_with_payload_schemas() {
+    // GIVEN: a 2.x document with a single-message channel, a oneOf channel
+    //        and a channel without messages
+    // WHEN: converting it
+    let cap = converted(ASYNCAPI_2_FIXTURE);
+
+    // THEN: one webhook-only capability with an endpoint per message channel
+    assert_eq!(cap.name, "acme_billing_events");
+    assert!(cap.providers.is_empty());
+    assert_eq!(cap.webhooks.len(), 2, "{:?}", cap.webhooks.keys());
+
+    let paid = &cap.webhooks["invoice_paid"];
+    assert_eq!(paid.path, "/acme_billing_events/invoice_paid");
+    assert_eq!(paid.method, "PUT");
+    assert_eq!(
+        paid.transform.event_type.as_deref(),
+        Some("acme_billing_events.invoice.paid")
+    );
+    let schema = paid.schema.as_ref().expect("payload schema");
+    assert_eq!(schema["required"], json!(["invoice_id", "amount"]));
+
+    // AND: several messages share one endpoint whose schema unites them
+    let changed = &cap.webhooks["customer_changed"];
+    assert_eq!(changed.method, "POST");
+    assert!(changed.transform.event_type.is_none());
+    let schema = changed.schema.as_ref().expect("merged schema");
+    assert_eq!(schema["required"], json!(["customer_id"]));
+    assert_eq!(schema["properties"]["deleted_at"]["type"], "string");
+    assert!(schema["properties"].get("email").is_some());
+
+    // AND: the capability passes validation
+    let errors: Vec<_> = validate_capability_definition(&cap, None)
+        .into_iter()
+        .filter(|i| i.severity == IssueSeverity::Error)
+        .collect();
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn asyncapi_3_operations_set_the_method_and_foreign_formats_are_skipped() {
+    // GIVEN: a 3.0 document whose operation binds its channel to PATCH, and
+    //        a channel with an Avro payload
+    // WHEN: converting it
+    let cap = converted(ASYNCAPI_3_FIXTURE);
+
+    // THEN: the operation's binding applies and the allOf payload flattens
+    let updated = &cap.webhooks["shipmentupdated"];
+    assert_eq!(updated.method, "PATCH");
+    let schema = updated.schema.as_ref().expect("payload schema");
+    assert_eq!(schema["required"], json!(["shipment_id"]));
+    assert_eq!(schema["properties"]["status"]["type"], "string");
+
+    // AND: the Avro channel still gets an endpoint, without a schema
+    let legacy = &cap.webhooks["legacyfeed"];
+    assert!(legacy.schema.is_none());
+}
+
+#[test]
+fn generated_webhooks_register_with_their_notification_schema() {
+    // GIVEN: a capability converted from a 2.x document
+    let cap = converted(ASYNCAPI_2_FIXTURE);
+
+    // WHEN: the webhook registry loads it
+    let mut registry = WebhookRegistry::new(WebhookConfig::default());
+    registry.register_capability(&cap);
+
+    // THEN: the endpoints are served, and their status reports the schema
+    assert_eq!(registry.endpoint_count(), 2);
+    let endpoints = registry.list_endpoints();
+    let paid = endpoints
+        .iter()
+        .find(|e| e.webhook_name == "invoice_paid")
+        .expect("invoice_paid endpoint");
+    assert!(paid.path.ends_with("/acme_billing_events/invoice_paid"));
+    assert_eq!(
+        paid.schema
+            .as_ref()
+            .map(|s| &s["properties"]["amount"]["type"]),
+        Some(&json!("integer"))
+    );
+}
+
+#[test]
+fn signature_header_adds_secret_reference_to_every_endpoint() {
+    // GIVEN: a converter requiring signatures, with a prefix
+    let generated = AsyncApiConverter::new()
+        .with_prefix("acme")
+        .with_signature_header("X-Acme-Signature")
+        .convert_string(ASYNCAPI_2_FIXTURE)
+        .unwrap();
+
+    // WHEN: parsing the output
+    let cap = parse_capability(&generated.yaml).unwrap();
+
+    // THEN: each endpoint carries the env secret and the header
+    assert_eq!(cap.name, "acme_acme_billing_events");
+    for webhook in cap.webhooks.values() {
+        assert_eq!(
+            webhook.secret.as_deref(),
+            Some("{env.ACME_ACME_BILLING_EVENTS_WEBHOOK_SECRET}")
+        );
+        assert_eq!(
+            webhook.signature_header.as_deref(),
+            Some("X-Acme-Signature")
+        );
+    }
+}
+
+#[test]
+fn documents_that_are_not_asyncapi_are_rejected() {
+    let openapi = "openapi: \"3.0.0\"\ninfo:\n  title: X\n  version: \"1\"\npaths: {}\n";
+    assert!(!AsyncApiConverter::is_asyncapi(openapi));
+    assert!(AsyncApiConverter::is_asyncapi(ASYNCAPI_2_FIXTURE));
+    let err = AsyncApiConverter::new()
+        .convert_string(openapi)
+        .expect_err("not AsyncAPI");
+    assert!(
+        err.to_string().contains("Not an AsyncAPI document"),
+        "{err}"
+    );
+
+    let empty = "asyncapi: \"2.6.0\"\ninfo:\n  title: Empty\n  version: \"1\"\nchannels: {}\n";
+    let err = AsyncApiConverter::new()
+        .convert_string(empty)
+        .expect_err("no messages");
+    assert!(err.to_string().contains("no channel messages"), "{err}");
+}
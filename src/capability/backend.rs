@@ -10,7 +10,11 @@
 //!
 //! The backend supports hot-reloading of capabilities. When capability
 //! files change, call `reload()` to refresh the registry without
-//! restarting the gateway.
+//! restarting the gateway. The new tool set is swapped in under one write
+//! lock; a file that stops loading keeps its last good definition, and the
+//! failure is returned in the [`CapabilityReloadReport`]. When the tool list
+//! changed, the hook set with `set_tools_changed_hook` runs so the gateway
+//! can push `notifications/tools/list_changed`.
 //!
 //! # O(1) Lookup
 //!
@@ -18,8 +22,22 @@
 //! that maps tool names to positions in the ordered `Vec`.  The tool MCP
 //! representation is pre-built once and cached so `get_tools()` is a cheap
 //! `Vec::clone()` rather than N calls to `to_mcp_tool()`.
+//!
+//! # Versions
+//!
+//! Capabilities with a `version:` are kept side by side: the newest version
+//! of a name is registered under the name itself, so unversioned calls reach
+//! it, and older versions under their versioned name (`search_v1`).
+//!
+//! # Registry Trust
+//!
+//! With [`CapabilityBackend::set_registry_trust`], capabilities installed
+//! from a signature-checked registry are loaded only while they match the
+//! signed checksum in their directory's `capabilities.lock`; see
+//! [`crate::registry::trust`].
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use parking_lot::RwLock;
@@ -30,10 +48,13 @@ use super::hash::compute_capability_hash;
 use super::schema_validator::validate_arguments;
 use super::{
     CapabilityDefinition, CapabilityExecutionContext, CapabilityExecutor, CapabilityLoader,
-    validate_oauth_isolation, validate_personal_capability_identity,
+    DirectoryLoad, LoadFailure, compare_versions, validate_oauth_isolation,
+    validate_personal_capability_identity,
 };
 use crate::Result;
+use crate::config::RegistryConfig;
 use crate::protocol::{Content, Tool, ToolsCallResult};
+use crate::registry::trust::untrusted_capabilities;
 
 // ============================================================================
 // Indexed capability storage (O(1) lookup)
@@ -57,11 +78,21 @@ struct IndexedCapabilities {
     index: HashMap<String, usize>,
     /// Pre-built MCP `Tool` representations — rebuilt whenever `entries` changes.
     tools: Vec<Tool>,
+    /// Name shared by all versions of each entry (the `name` in its file);
+    /// an older version's entry is registered under its versioned name.
+    bases: Vec<String>,
 }
 
 impl IndexedCapabilities {
     /// Insert or replace a capability, maintaining index and tool cache consistency.
+    ///
+    /// A versioned capability replaces only the entry with the same `name`
+    /// and `version`; see [`Self::upsert_version`].
     fn upsert(&mut self, cap: CapabilityDefinition) {
+        if cap.version.is_some() || self.get(&cap.name).is_some_and(|c| c.version.is_some()) {
+            self.upsert_version(cap);
+            return;
+        }
         let tool = cap.to_mcp_tool();
         if let Some(&pos) = self.index.get(&cap.name) {
             self.entries[pos] = cap;
@@ -69,6 +100,7 @@ impl IndexedCapabilities {
         } else {
             let pos = self.entries.len();
             self.index.insert(cap.name.clone(), pos);
+            self.bases.push(cap.name.clone());
             self.entries.push(cap);
             self.tools.push(tool);
         }
@@ -76,18 +108,114 @@ impl IndexedCapabilities {
 
     /// Replace all entries atomically, rebuilding both index and tool cache.
     fn replace_all(&mut self, caps: Vec<CapabilityDefinition>) {
-        self.index.clear();
-        self.tools.clear();
         self.entries = Vec::with_capacity(caps.len());
         self.tools = Vec::with_capacity(caps.len());
+        self.bases = Vec::with_capacity(caps.len());
         self.index = HashMap::with_capacity(caps.len());
         for cap in caps {
-            let tool = cap.to_mcp_tool();
-            let pos = self.entries.len();
-            self.index.insert(cap.name.clone(), pos);
-            self.entries.push(cap);
+            self.upsert(cap);
+        }
+    }
+
+    /// Insert one version of a capability next to its other versions.
+    ///
+    /// The newest version of a name is registered under that name, so
+    /// unversioned calls reach it; older versions are registered under
+    /// [`CapabilityDefinition::versioned_name`]. An unversioned definition
+    /// of a name that has versions is dropped.
+    fn upsert_version(&mut self, cap: CapabilityDefinition) {
+        let base = cap.name.clone();
+        if cap.version.is_none() {
+            warn!(capability = %base, "Ignoring unversioned capability; versioned definitions of it are loaded");
+            return;
+        }
+        let entries = std::mem::take(&mut self.entries);
+        let tools = std::mem::take(&mut self.tools);
+        let bases = std::mem::take(&mut self.bases);
+        for ((entry, tool), entry_base) in entries.into_iter().zip(tools).zip(bases) {
+            if entry_base == base {
+                if entry.version.is_none() {
+                    warn!(capability = %base, "Unversioned capability replaced by a versioned definition");
+                    continue;
+                }
+                if entry.version == cap.version {
+                    continue;
+                }
+            }
+            self.entries.push(entry);
             self.tools.push(tool);
+            self.bases.push(entry_base);
+        }
+        self.tools.push(cap.to_mcp_tool());
+        self.entries.push(cap);
+        self.bases.push(base.clone());
+        self.rename_versions(&base);
+    }
+
+    /// Register the versions of `base` under their names (see
+    /// [`Self::upsert_version`]) and rebuild the index.
+    fn rename_versions(&mut self, base: &str) {
+        let latest = (0..self.entries.len())
+            .filter(|&pos| self.bases[pos] == base)
+            .max_by(|&a, &b| {
+                compare_versions(
+                    self.entries[a].version.as_deref().unwrap_or_default(),
+                    self.entries[b].version.as_deref().unwrap_or_default(),
+                )
+            });
+        for pos in 0..self.entries.len() {
+            if self.bases[pos] != base {
+                continue;
+            }
+            let entry = &mut self.entries[pos];
+            let previous = std::mem::replace(&mut entry.name, base.to_string());
+            if Some(pos) != latest {
+                entry.name = entry.versioned_name();
+            }
+            if entry.name != previous {
+                self.tools[pos] = entry.to_mcp_tool();
+            }
+        }
+        self.index = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(pos, entry)| (entry.name.clone(), pos))
+            .collect();
+    }
+
+    /// Remove the capability registered as `name`; when it was the newest
+    /// version, the next newest takes over the unversioned name.
+    fn remove(&mut self, name: &str) -> bool {
+        let Some(pos) = self.index.remove(name) else {
+            return false;
+        };
+        let removed = self.entries.remove(pos);
+        self.tools.remove(pos);
+        let base = self.bases.remove(pos);
+        // Shift remaining indices down.
+        for idx in self.index.values_mut() {
+            if *idx > pos {
+                *idx -= 1;
+            }
+        }
+        if removed.version.is_some() {
+            self.rename_versions(&base);
         }
+        true
+    }
+
+    /// Each served tool, by name, for detecting tool-list changes.
+    fn tool_snapshot(&self) -> HashMap<String, Value> {
+        self.tools
+            .iter()
+            .map(|tool| {
+                (
+                    tool.name.clone(),
+                    serde_json::to_value(tool).unwrap_or_default(),
+                )
+            })
+            .collect()
     }
 
     /// O(1) capability lookup by name.
@@ -128,6 +256,11 @@ pub struct CapabilityBackend {
     capabilities: RwLock<IndexedCapabilities>,
     /// Directories to load capabilities from
     directories: RwLock<Vec<String>>,
+    /// Last definition loaded from each capability file. A reload falls
+    /// back to it while the file fails to load.
+    sources: RwLock<BTreeMap<PathBuf, CapabilityDefinition>>,
+    /// Called after a reload or quarantine changed the served tool list.
+    tools_changed_hook: RwLock<Option<ToolsChangedHook>>,
     /// Capability names currently quarantined by a rug-pull detection event.
     ///
     /// Populated by the file watcher when an on-disk YAML's `sha256:` pin no
@@ -142,6 +275,26 @@ pub struct CapabilityBackend {
     /// they may be set in either order at startup. Read by
     /// [`validate_oauth_isolation`] inside `call_tool_with_context`.
     multi_user: std::sync::atomic::AtomicBool,
+    /// Registries whose trust policy is enforced at load time; `None` when
+    /// no registry checks signatures.
+    registry_trust: RwLock<Option<RegistryConfig>>,
+}
+
+/// Callback run when the capability tool list changed.
+pub type ToolsChangedHook = Arc<dyn Fn() + Send + Sync>;
+
+/// Outcome of [`CapabilityBackend::reload`].
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct CapabilityReloadReport {
+    /// Capabilities served after the reload.
+    pub total: usize,
+    /// Whether the served tool list differs from the one before the reload.
+    pub tools_changed: bool,
+    /// Capability files that failed to parse or validate.
+    pub failures: Vec<LoadFailure>,
+    /// Capabilities still served from their last good definition because
+    /// their file is among `failures`.
+    pub kept: Vec<String>,
 }
 
 /// Record of a detected rug-pull event for a single capability.
@@ -165,8 +318,11 @@ impl CapabilityBackend {
             executor,
             capabilities: RwLock::new(IndexedCapabilities::default()),
             directories: RwLock::new(Vec::new()),
+            sources: RwLock::new(BTreeMap::new()),
+            tools_changed_hook: RwLock::new(None),
             rug_pull_state: RwLock::new(HashMap::new()),
             multi_user: std::sync::atomic::AtomicBool::new(false),
+            registry_trust: RwLock::new(None),
         }
     }
 
@@ -179,6 +335,68 @@ impl CapabilityBackend {
             .store(multi_user, std::sync::atomic::Ordering::Relaxed);
     }
 
+    /// Enforce the trust policies of `registry` on every later load and
+    /// reload. Has no effect unless a source checks signatures.
+    pub fn set_registry_trust(&self, registry: &RegistryConfig) {
+        *self.registry_trust.write() = registry
+            .sources
+            .iter()
+            .any(crate::config::RegistrySourceConfig::verifies_signatures)
+            .then(|| registry.clone());
+    }
+
+    /// Move the capabilities of `dir` refused by the registry trust policy
+    /// from `load.loaded` to `load.failures`. An unreadable lockfile
+    /// refuses the whole directory.
+    fn reject_untrusted(&self, dir: &str, load: &mut DirectoryLoad) {
+        let Some(registry) = self.registry_trust.read().clone() else {
+            return;
+        };
+        let untrusted = match untrusted_capabilities(Path::new(dir), &registry) {
+            Ok(untrusted) => untrusted,
+            Err(e) => {
+                warn!(backend = %self.name, directory = %dir, error = %e, "Cannot check registry trust; refusing directory");
+                load.failures
+                    .extend(load.loaded.drain(..).map(|(path, _)| LoadFailure {
+                        path,
+                        error: e.to_string(),
+                    }));
+                return;
+            }
+        };
+        for refused in untrusted {
+            warn!(
+                backend = %self.name,
+                capability = %refused.name,
+                path = %refused.path.display(),
+                "Refusing untrusted capability: {}",
+                refused.reason,
+            );
+            let (rejected, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut load.loaded)
+                .into_iter()
+                .partition(|(_, cap)| cap.name == refused.name);
+            load.loaded = kept;
+            load.failures
+                .extend(rejected.into_iter().map(|(path, _)| LoadFailure {
+                    path,
+                    error: refused.reason.clone(),
+                }));
+        }
+    }
+
+    /// Run `hook` whenever a reload or a rug-pull quarantine changes the
+    /// served tool list.
+    pub fn set_tools_changed_hook(&self, hook: ToolsChangedHook) {
+        *self.tools_changed_hook.write() = Some(hook);
+    }
+
+    fn notify_tools_changed(&self) {
+        let hook = self.tools_changed_hook.read().clone();
+        if let Some(hook) = hook {
+            hook();
+        }
+    }
+
     /// Whether the capability backend is currently considered healthy by its
     /// outbound-transport health tracker (owned by the executor).
     #[must_use]
@@ -192,21 +410,7 @@ impl CapabilityBackend {
     /// tampered capability is no longer callable until the operator
     /// explicitly re-pins it.
     pub fn unload_capability(&self, name: &str) -> bool {
-        let mut caps = self.capabilities.write();
-        if let Some(&pos) = caps.index.get(name) {
-            caps.entries.remove(pos);
-            caps.tools.remove(pos);
-            caps.index.remove(name);
-            // Shift remaining indices down.
-            for idx in caps.index.values_mut() {
-                if *idx > pos {
-                    *idx -= 1;
-                }
-            }
-            true
-        } else {
-            false
-        }
+        self.capabilities.write().remove(name)
     }
 
     /// Mark a capability as quarantined by a rug-pull event.
@@ -254,8 +458,9 @@ impl CapabilityBackend {
     ///
     /// Returns an error if the directory cannot be loaded.
     pub async fn load_from_directory(&self, path: &str) -> Result<usize> {
-        let loaded = CapabilityLoader::load_directory(path).await?;
-        let count = loaded.len();
+        let mut load = CapabilityLoader::load_directory_with_failures(path).await?;
+        self.reject_untrusted(path, &mut load);
+        let count = load.loaded.len();
 
         // Register directory for future hot-reloads.
         {
@@ -266,7 +471,8 @@ impl CapabilityBackend {
         }
 
         // Upsert each capability into the indexed store.
-        for cap in loaded {
+        for (file, cap) in load.loaded {
+            self.sources.write().insert(file, cap.clone());
             {
                 let mut caps = self.capabilities.write();
                 caps.upsert(cap);
@@ -281,42 +487,86 @@ impl CapabilityBackend {
     /// Reload all capabilities from registered directories
     ///
     /// This is the hot-reload entry point. It re-reads all capability
-    /// files from the registered directories and updates the registry.
+    /// files from the registered directories and swaps the new set in under
+    /// one write lock. A file that fails to load keeps serving its last good
+    /// definition (unless the capability is quarantined), as does every
+    /// file of a directory that cannot be read; the failures are returned
+    /// in the report. Runs the tools-changed hook when the tool list changed.
     ///
     /// # Errors
     ///
-    /// Returns an error if reloading fails for all directories.
-    pub async fn reload(&self) -> Result<usize> {
+    /// Currently infallible; directory and file failures are reported, not
+    /// returned.
+    pub async fn reload(&self) -> Result<CapabilityReloadReport> {
         let dirs: Vec<String> = self.directories.read().clone();
 
         if dirs.is_empty() {
             debug!(backend = %self.name, "No directories to reload");
-            return Ok(0);
+            return Ok(CapabilityReloadReport::default());
         }
 
-        let mut all_caps = Vec::new();
-        let mut total = 0;
+        let previous = self.sources.read().clone();
+        let mut sources = BTreeMap::new();
+        let mut report = CapabilityReloadReport::default();
 
         for dir in &dirs {
-            match CapabilityLoader::load_directory(dir).await {
-                Ok(loaded) => {
-                    total += loaded.len();
-                    all_caps.extend(loaded);
+            match CapabilityLoader::load_directory_with_failures(dir).await {
+                Ok(mut load) => {
+                    self.reject_untrusted(dir, &mut load);
+                    sources.extend(load.loaded);
+                    report.failures.extend(load.failures);
                 }
                 Err(e) => {
-                    warn!(backend = %self.name, directory = %dir, error = %e, "Failed to reload directory");
+                    warn!(backend = %self.name, directory = %dir, error = %e, "Failed to reload directory; keeping its capabilities");
+                    sources.extend(
+                        previous
+                            .iter()
+                            .filter(|(file, _)| file.starts_with(dir))
+                            .map(|(file, cap)| (file.clone(), cap.clone())),
+                    );
                 }
             }
         }
 
+        for failure in &report.failures {
+            let Some(cap) = previous.get(&failure.path) else {
+                continue;
+            };
+            if self.is_rug_pulled(&cap.name) {
+                continue;
+            }
+            warn!(
+                backend = %self.name,
+                capability = %cap.name,
+                path = %failure.path.display(),
+                "Capability file no longer loads; keeping its last good definition",
+            );
+            report.kept.push(cap.name.clone());
+            sources.insert(failure.path.clone(), cap.clone());
+        }
+
         // Atomic swap: rebuild index and tool cache in one write lock.
         {
             let mut caps = self.capabilities.write();
-            caps.replace_all(all_caps);
+            let before = caps.tool_snapshot();
+            caps.replace_all(sources.values().cloned().collect());
+            report.tools_changed = caps.tool_snapshot() != before;
+            report.total = caps.len();
         }
-
-        info!(backend = %self.name, count = total, directories = dirs.len(), "Hot-reloaded capabilities");
-        Ok(total)
+        *self.sources.write() = sources;
+
+        info!(
+            backend = %self.name,
+            count = report.total,
+            failed = report.failures.len(),
+            directories = dirs.len(),
+            tools_changed = report.tools_changed,
+            "Hot-reloaded capabilities",
+        );
+        if report.tools_changed {
+            self.notify_tools_changed();
+        }
+        Ok(report)
     }
 
     /// Get all tools (pre-built MCP tool representations).
@@ -399,6 +649,9 @@ impl CapabilityBackend {
         let capability = self
             .get(name)
             .ok_or_else(|| crate::Error::Config(format!("Capability not found: {name}")))?;
+        if let Some(warning) = capability.deprecation_warning() {
+            warn!(capability = %name, "{warning}");
+        }
         validate_personal_capability_identity(&capability, &context)?;
         validate_oauth_isolation(
             &capability,
@@ -471,6 +724,7 @@ impl CapabilityBackend {
             healthy: health.healthy,
             consecutive_failures: health.consecutive_failures,
             latency_p95_ms: health.latency_p95_ms,
+            unhealthy_providers: self.executor.unhealthy_providers(),
         }
     }
 
@@ -508,6 +762,9 @@ impl CapabilityBackend {
             self.unload_capability(&record.capability);
             self.mark_rug_pull(record.clone());
         }
+        if !detected.is_empty() {
+            self.notify_tools_changed();
+        }
 
         detected
     }
@@ -552,8 +809,6 @@ fn build_success_tool_result(capability: &CapabilityDefinition, result: Value) -
     }
 }
 
-use std::path::Path;
-
 /// Recursively walk a directory and report any YAML file whose embedded
 /// `sha256:` pin does not match the file's current content.
 async fn detect_rug_pulls_in_dir(dir: &Path, out: &mut Vec<RugPullRecord>) {
@@ -627,6 +882,9 @@ pub struct CapabilityBackendStatus {
     pub consecutive_failures: u64,
     /// 95th percentile execution latency in milliseconds, if any samples exist.
     pub latency_p95_ms: Option<u64>,
+    /// `capability/provider` pairs marked unhealthy by provider failover.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unhealthy_providers: Vec<String>,
 }
 
 // ============================================================================
@@ -746,6 +1004,60 @@ providers:
         assert!(result.is_none());
     }
 
+    fn make_versioned_cap(name: &str, version: &str) -> CapabilityDefinition {
+        let mut cap = make_cap(name);
+        cap.version = Some(version.to_string());
+        cap.description = format!("Version {version}");
+        cap
+    }
+
+    #[test]
+    fn indexed_capabilities_keep_versions_side_by_side_with_latest_unversioned() {
+        // GIVEN: versions 1.9, 1.10 and 1.2 of one capability, in that order
+        let mut idx = IndexedCapabilities::default();
+        idx.upsert(make_versioned_cap("search", "1.9"));
+        idx.upsert(make_versioned_cap("search", "1.10"));
+        idx.upsert(make_versioned_cap("search", "1.2"));
+
+        // THEN: the newest answers to the plain name, the others to theirs
+        assert_eq!(idx.len(), 3);
+        assert_eq!(idx.get("search").unwrap().description, "Version 1.10");
+        assert_eq!(idx.get("search_v1_9").unwrap().description, "Version 1.9");
+        assert_eq!(idx.get("search_v1_2").unwrap().description, "Version 1.2");
+        let mut tools: Vec<&str> = idx.tools.iter().map(|t| t.name.as_str()).collect();
+        tools.sort_unstable();
+        assert_eq!(tools, ["search", "search_v1_2", "search_v1_9"]);
+
+        // WHEN: the same version is loaded again
+        let mut updated = make_versioned_cap("search", "1.9");
+        updated.description = "Updated".to_string();
+        idx.upsert(updated);
+
+        // THEN: it replaces that version only
+        assert_eq!(idx.len(), 3);
+        assert_eq!(idx.get("search_v1_9").unwrap().description, "Updated");
+    }
+
+    #[test]
+    fn indexed_capabilities_removing_latest_promotes_previous_version() {
+        // GIVEN: versions 1 and 2, with an unversioned one loaded first
+        let mut idx = IndexedCapabilities::default();
+        idx.upsert(make_cap("search"));
+        idx.upsert(make_versioned_cap("search", "1"));
+        idx.upsert(make_versioned_cap("search", "2"));
+        // THEN: the unversioned definition gave way to the versions
+        assert_eq!(idx.len(), 2);
+
+        // WHEN: the latest version is removed
+        assert!(idx.remove("search"));
+
+        // THEN: version 1 takes over the plain name
+        assert_eq!(idx.len(), 1);
+        assert_eq!(idx.get("search").unwrap().description, "Version 1");
+        assert!(!idx.contains("search_v1"));
+        assert_eq!(idx.tools[0].name, "search");
+    }
+
     // ── CapabilityBackend public API ──────────────────────────────────────
 
     #[test]
@@ -934,14 +1246,74 @@ providers:
         assert_eq!(backend.get_tools().len(), 1);
 
         // WHEN: reloading
-        let reload_count = backend.reload().await.unwrap();
+        let report = backend.reload().await.unwrap();
 
         // THEN: consistency is maintained
-        assert_eq!(reload_count, 1);
+        assert_eq!(report.total, 1);
+        assert!(!report.tools_changed);
         assert!(backend.has_capability("alpha"));
         assert_eq!(backend.get_tools().len(), 1);
     }
 
+    #[tokio::test]
+    async fn reload_applies_file_changes_keeps_broken_files_and_notifies() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tempfile::TempDir;
+
+        let cap_yaml = |name: &str, description: &str| {
+            format!(
+                "name: {name}\ndescription: {description}\nproviders:\n  primary:\n    \
+                 service: rest\n    config:\n      base_url: https://example.com\n      path: /x\n"
+            )
+        };
+
+        // GIVEN: a loaded directory with one capability and a tools-changed hook
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("alpha.yaml"), cap_yaml("alpha", "Alpha")).unwrap();
+        let backend = make_backend();
+        backend
+            .load_from_directory(dir.path().to_str().unwrap())
+            .await
+            .unwrap();
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&notified);
+        backend.set_tools_changed_hook(Arc::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        // WHEN: a file is added
+        std::fs::write(dir.path().join("beta.yaml"), cap_yaml("beta", "Beta")).unwrap();
+        let report = backend.reload().await.unwrap();
+
+        // THEN: it is served and clients are notified
+        assert_eq!(report.total, 2);
+        assert!(report.tools_changed);
+        assert!(backend.has_capability("beta"));
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+        // WHEN: a file is edited into invalid YAML
+        std::fs::write(dir.path().join("alpha.yaml"), "name: alpha\ndescription: [").unwrap();
+        let report = backend.reload().await.unwrap();
+
+        // THEN: the failure is reported and the last good definition is kept
+        assert_eq!(report.failures.len(), 1, "{report:?}");
+        assert_eq!(report.kept, ["alpha"]);
+        assert!(!report.tools_changed);
+        assert_eq!(backend.get("alpha").unwrap().description, "Alpha");
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+        // WHEN: the edit is fixed and the other file deleted
+        std::fs::write(dir.path().join("alpha.yaml"), cap_yaml("alpha", "Alpha v2")).unwrap();
+        std::fs::remove_file(dir.path().join("beta.yaml")).unwrap();
+        let report = backend.reload().await.unwrap();
+
+        // THEN: both changes apply in one swap
+        assert!(report.failures.is_empty());
+        assert_eq!(backend.get("alpha").unwrap().description, "Alpha v2");
+        assert!(!backend.has_capability("beta"));
+        assert_eq!(notified.load(Ordering::SeqCst), 2);
+    }
+
     #[test]
     fn build_success_tool_result_populates_structured_content_when_output_schema_exists() {
         let yaml = r#"
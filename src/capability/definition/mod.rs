@@ -26,6 +26,25 @@ pub struct CapabilityDefinition {
     #[serde(default)]
     pub description: String,
 
+    /// Version of this capability (e.g. `"2"` or `"1.3.0"`).
+    ///
+    /// Several versions of one `name` can be loaded side by side: the newest
+    /// (see [`compare_versions`]) is served under `name`, so unversioned
+    /// calls reach it, and each older one under
+    /// [`versioned_name`](Self::versioned_name), e.g. `search_v1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Whether this capability is deprecated. Its tool description and
+    /// search results then carry a deprecation warning.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+
+    /// Tool to use instead of this deprecated capability (e.g. `search`, or
+    /// a newer capability altogether).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+
     /// Input/output schema
     #[serde(default)]
     pub schema: SchemaDefinition,
@@ -115,6 +134,20 @@ pub struct CapabilityDefinition {
     /// ```
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub visible_in_states: Vec<String>,
+
+    /// Retries, deadline and upstream rate limit of this capability,
+    /// enforced by the executor on top of any backend-level failsafes.
+    ///
+    /// # Example (YAML)
+    ///
+    /// ```yaml
+    /// resilience:
+    ///   retries: 2
+    ///   timeout: 20
+    ///   rate_limit: 60/min
+    /// ```
+    #[serde(default, skip_serializing_if = "ResilienceConfig::is_empty")]
+    pub resilience: ResilienceConfig,
 }
 
 /// Provider configurations supporting both named and fallback arrays
@@ -124,6 +157,28 @@ pub struct ProvidersConfig {
     pub named: HashMap<String, ProviderConfig>,
     /// Fallback providers (ordered list)
     pub fallback: Vec<ProviderConfig>,
+    /// How the first provider of a call is chosen (the `strategy:` key).
+    #[serde(skip_serializing_if = "is_default_strategy")]
+    pub strategy: ProviderStrategy,
+}
+
+/// How a capability with several providers picks the one to call first.
+///
+/// Whichever is picked, a failing provider fails over to the next one in
+/// [`ProvidersConfig::ordered`] order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderStrategy {
+    /// Always start with the first provider in failover order.
+    #[default]
+    Failover,
+    /// Start with a provider chosen at random in proportion to its `weight`.
+    Weighted,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde's skip_serializing_if signature
+fn is_default_strategy(strategy: &ProviderStrategy) -> bool {
+    *strategy == ProviderStrategy::Failover
 }
 
 impl ProvidersConfig {
@@ -144,6 +199,27 @@ impl ProvidersConfig {
     pub fn get(&self, key: &str) -> Option<&ProviderConfig> {
         self.named.get(key)
     }
+
+    /// Every provider in failover order, with its name.
+    ///
+    /// Named providers come first, by `priority` (`primary` defaults to 0,
+    /// the others to 100) and then by name; `fallback` entries follow in list
+    /// order as `fallback[0]`, `fallback[1]`, ….
+    #[must_use]
+    pub fn ordered(&self) -> Vec<(String, &ProviderConfig)> {
+        let mut named: Vec<(&String, &ProviderConfig)> = self.named.iter().collect();
+        named.sort_by_key(|(name, provider)| (provider.priority_rank(name), *name));
+        named
+            .into_iter()
+            .map(|(name, provider)| (name.clone(), provider))
+            .chain(
+                self.fallback
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, provider)| (format!("fallback[{idx}]"), provider)),
+            )
+            .collect()
+    }
 }
 
 impl<'de> Deserialize<'de> for ProvidersConfig {
@@ -180,9 +256,12 @@ where
         {
             let mut named = HashMap::new();
             let mut fallback = Vec::new();
+            let mut strategy = ProviderStrategy::default();
 
             while let Some(key) = map.next_key::<String>()? {
-                if key == "fallback" {
+                if key == "strategy" {
+                    strategy = map.next_value()?;
+                } else if key == "fallback" {
                     // Try to deserialize as array first, then as single provider
                     let value: serde_json::Value = map.next_value()?;
                     if let Some(arr) = value.as_array() {
@@ -200,7 +279,11 @@ where
                 }
             }
 
-            Ok(ProvidersConfig { named, fallback })
+            Ok(ProvidersConfig {
+                named,
+                fallback,
+                strategy,
+            })
         }
     }
 
@@ -211,6 +294,37 @@ fn default_version() -> String {
     "1.0".to_string()
 }
 
+/// Order two capability `version`s.
+///
+/// Versions are split at `.`, `-` and `+`; parts that are both numbers
+/// compare as numbers (`1.10` is newer than `1.9`), others as text. A
+/// leading `v` is ignored and missing parts count as `0`, so `v2` equals
+/// `2.0`.
+#[must_use]
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parts = |version: &str| -> Vec<String> {
+        version
+            .trim()
+            .trim_start_matches(['v', 'V'])
+            .split(['.', '-', '+'])
+            .map(str::to_string)
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).map_or("0", String::as_str);
+        let y = b.get(i).map_or("0", String::as_str);
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if order.is_ne() {
+            return order;
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
 /// Schema definition for input/output
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SchemaDefinition {
@@ -238,12 +352,32 @@ pub struct ProviderConfig {
     #[serde(default = "default_timeout")]
     pub timeout: u64,
 
+    /// Failover position among named providers (lower first); see
+    /// [`ProvidersConfig::ordered`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+
+    /// Relative share of calls started on this provider under
+    /// `strategy: weighted` (0 = only as a failover target).
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+
     /// REST configuration
     #[serde(default)]
     pub config: RestConfig,
 }
 
+fn default_weight() -> u32 {
+    1
+}
+
 impl ProviderConfig {
+    /// Failover rank of the provider named `name`.
+    fn priority_rank(&self, name: &str) -> u32 {
+        self.priority
+            .unwrap_or(if name == "primary" { 0 } else { 100 })
+    }
+
     /// Derive the protocol-specific configuration from the `service` field.
     ///
     /// This is the bridge between the flat YAML structure (which uses a
@@ -257,58 +391,12 @@ impl ProviderConfig {
     pub fn protocol_config(&self) -> ProtocolConfig {
         match self.service.as_str() {
             "rest" | "" => ProtocolConfig::Rest(Box::new(self.config.clone())),
-            "graphql" => {
-                // Build GraphqlConfig from the flat RestConfig fields.
-                // The YAML `config:` block uses RestConfig for all service
-                // types — we map the relevant fields to GraphqlConfig here.
-                ProtocolConfig::Graphql(GraphqlConfig {
-                    endpoint: if self.config.endpoint.is_empty() {
-                        format!("{}{}", self.config.base_url, self.config.path)
-                    } else {
-                        self.config.endpoint.clone()
-                    },
-                    headers: self.config.headers.clone(),
-                    query: self
-                        .config
-                        .body
-                        .as_ref()
-                        .and_then(|b| b.as_str().map(ToString::to_string))
-                        .or_else(|| {
-                            // Also check for a `query` field in the body object
-                            self.config
-                                .body
-                                .as_ref()
-                                .and_then(|b| b.get("query"))
-                                .and_then(|q| q.as_str())
-                                .map(ToString::to_string)
-                        }),
-                    variables: self.config.static_params.clone(),
-                    response_path: self.config.response_path.clone(),
-                })
-            }
-            "jsonrpc" => {
-                // Build JsonRpcConfig from the flat RestConfig fields.
-                ProtocolConfig::Jsonrpc(JsonRpcConfig {
-                    endpoint: if self.config.endpoint.is_empty() {
-                        format!("{}{}", self.config.base_url, self.config.path)
-                    } else {
-                        self.config.endpoint.clone()
-                    },
-                    method: self.config.method.clone(),
-                    headers: self.config.headers.clone(),
-                    default_params: if self.config.static_params.is_empty() {
-                        serde_json::Value::Null
-                    } else {
-                        serde_json::Value::Object(
-                            self.config
-                                .static_params
-                                .iter()
-                                .map(|(k, v)| (k.clone(), v.clone()))
-                                .collect(),
-                        )
-                    },
-                })
-            }
+            "graphql" => ProtocolConfig::Graphql(self.graphql_config()),
+            "jsonrpc" => ProtocolConfig::Jsonrpc(self.jsonrpc_config()),
+            "grpc" => ProtocolConfig::Grpc(self.grpc_config()),
+            "sql" => ProtocolConfig::Sql(self.sql_config()),
+            "exec" => ProtocolConfig::Exec(self.exec_config()),
+            "soap" => ProtocolConfig::Rest(Box::new(self.soap_config())),
             // Unknown service → fall back to REST with a tracing warning.
             // This preserves backward compat if someone has a typo or
             // uses a service name that isn't implemented yet.
@@ -321,6 +409,124 @@ impl ProviderConfig {
             }
         }
     }
+
+    /// `endpoint`, or `fallback` when it is empty.
+    fn endpoint_or(&self, fallback: impl FnOnce() -> String) -> String {
+        if self.config.endpoint.is_empty() {
+            fallback()
+        } else {
+            self.config.endpoint.clone()
+        }
+    }
+
+    /// Build a [`GraphqlConfig`] from the flat `RestConfig` fields.
+    ///
+    /// The YAML `config:` block uses `RestConfig` for all service types — we
+    /// map the relevant fields to `GraphqlConfig` here.
+    fn graphql_config(&self) -> GraphqlConfig {
+        GraphqlConfig {
+            endpoint: self.endpoint_or(|| format!("{}{}", self.config.base_url, self.config.path)),
+            headers: self.config.headers.clone(),
+            query: self.config.query.clone().or_else(|| {
+                self.config
+                    .body
+                    .as_ref()
+                    .and_then(|b| b.as_str().map(ToString::to_string))
+                    .or_else(|| {
+                        // Also check for a `query` field in the body object
+                        self.config
+                            .body
+                            .as_ref()
+                            .and_then(|b| b.get("query"))
+                            .and_then(|q| q.as_str())
+                            .map(ToString::to_string)
+                    })
+            }),
+            operation_name: self.config.operation_name.clone(),
+            variables: self.config.static_params.clone(),
+            variable_map: self.config.param_map.clone(),
+            selection: self.config.selection.clone(),
+            response_path: self.config.response_path.clone(),
+        }
+    }
+
+    /// Build a [`JsonRpcConfig`] from the flat `RestConfig` fields.
+    fn jsonrpc_config(&self) -> JsonRpcConfig {
+        JsonRpcConfig {
+            endpoint: self.endpoint_or(|| format!("{}{}", self.config.base_url, self.config.path)),
+            method: self.config.method.clone(),
+            headers: self.config.headers.clone(),
+            default_params: if self.config.static_params.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::Value::Object(
+                    self.config
+                        .static_params
+                        .iter()
+                        .map(|(k, v)| (k.clone(), v.clone()))
+                        .collect(),
+                )
+            },
+        }
+    }
+
+    /// Build a [`GrpcConfig`] from the flat `RestConfig` fields.
+    fn grpc_config(&self) -> GrpcConfig {
+        GrpcConfig {
+            endpoint: self.endpoint_or(|| self.config.base_url.clone()),
+            method: self.config.method.clone(),
+            descriptor_set: self.config.descriptor_set.clone(),
+            headers: self.config.headers.clone(),
+            default_fields: self.config.static_params.clone(),
+            response_path: self.config.response_path.clone(),
+        }
+    }
+
+    /// Build a [`SqlConfig`] from the flat `RestConfig` fields.
+    fn sql_config(&self) -> SqlConfig {
+        SqlConfig {
+            url: self.endpoint_or(|| self.config.base_url.clone()),
+            query: self.config.query.clone().unwrap_or_default(),
+            bind: self.config.bind.clone(),
+            defaults: self.config.static_params.clone(),
+            max_rows: self.config.max_rows.unwrap_or(DEFAULT_SQL_MAX_ROWS),
+            shape: self.config.shape.unwrap_or_default(),
+            response_path: self.config.response_path.clone(),
+        }
+    }
+
+    /// Build an [`ExecConfig`] from the flat `RestConfig` fields.
+    fn exec_config(&self) -> ExecConfig {
+        ExecConfig {
+            command: self.config.command.clone(),
+            working_dir: self.config.working_dir.clone(),
+            env: self.config.env.clone(),
+            defaults: self.config.static_params.clone(),
+            max_output_bytes: self
+                .config
+                .max_output_bytes
+                .unwrap_or(DEFAULT_EXEC_MAX_OUTPUT_BYTES),
+            dangerous: self.config.dangerous,
+            response_format: self.config.response_format.clone(),
+            response_path: self.config.response_path.clone(),
+        }
+    }
+
+    /// SOAP is REST with XML defaults: POST an XML envelope and parse the
+    /// XML response.
+    fn soap_config(&self) -> RestConfig {
+        let mut config = self.config.clone();
+        if config.method.eq_ignore_ascii_case("GET") {
+            config.method = "POST".to_string();
+        }
+        if config.body_content_type.is_empty() {
+            config.body_content_type = "text/xml; charset=utf-8".to_string();
+        }
+        if config.response_format.is_empty() {
+            config.response_format = "xml".to_string();
+        }
+        config
+    }
 }
 
 fn default_service() -> String {
@@ -367,11 +573,14 @@ pub struct RestConfig {
     #[serde(default = "default_method")]
     pub method: String,
 
-    /// Headers to send (supports {param} and {env.VAR} substitution)
+    /// Headers to send (supports {param} and {env.VAR} substitution, or a
+    /// [template](crate::capability::template); a template rendering
+    /// nothing omits the header)
     #[serde(default)]
     pub headers: HashMap<String, String>,
 
-    /// Query parameters (supports substitution)
+    /// Query parameters (supports substitution and templates; empty values
+    /// are omitted)
     #[serde(default)]
     pub params: HashMap<String, String>,
 
@@ -404,6 +613,10 @@ pub struct RestConfig {
     pub static_params: HashMap<String, serde_json::Value>,
 
     /// Request body template (for POST/PUT)
+    ///
+    /// String values may be [templates](crate::capability::template): one
+    /// rendering nothing omits its field, and one rendering a JSON object or
+    /// array is inserted as that value.
     #[serde(default)]
     pub body: Option<serde_json::Value>,
 
@@ -432,6 +645,10 @@ pub struct RestConfig {
     /// (`"SELECT ..."`) — it is serialised without the outer quotes before
     /// sending.
     ///
+    /// An XML content type (`text/xml`, `application/soap+xml`, …) sends a
+    /// string `body` template as XML: substituted values are XML-escaped, so
+    /// arguments cannot inject markup.
+    ///
     /// # Example (YAML)
     ///
     /// ```yaml
@@ -444,6 +661,386 @@ pub struct RestConfig {
     /// ```
     #[serde(default)]
     pub body_content_type: String,
+
+    /// SOAP operation URI. Sent as the `SOAPAction` header (SOAP 1.1), or
+    /// as the `action` parameter of an `application/soap+xml` content type
+    /// (SOAP 1.2).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soap_action: Option<String>,
+
+    /// File parameters, keyed by input argument name; see
+    /// [`FileParamConfig`].
+    ///
+    /// With files declared, a POST/PUT/PATCH body is `multipart/form-data`
+    /// (the other arguments, or the substituted `body` object, become text
+    /// fields). Any other `body_content_type` sends the one file argument as
+    /// the raw body.
+    ///
+    /// # Example (YAML)
+    ///
+    /// ```yaml
+    /// config:
+    ///   endpoint: https://ocr.example.com/v1/documents
+    ///   method: POST
+    ///   files:
+    ///     document:
+    ///       field: file
+    ///       content_type: application/pdf
+    ///       max_bytes: 5242880
+    /// ```
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub files: HashMap<String, FileParamConfig>,
+
+    /// `XPath` selecting the part of an XML response to return, applied
+    /// before `response_path` (e.g. `//GetRatesResponse/Rate`). Namespace
+    /// prefixes are ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_xpath: Option<String>,
+
+    /// Follow further pages and merge their items into one result; see
+    /// [`PaginationConfig`].
+    ///
+    /// # Example (YAML)
+    ///
+    /// ```yaml
+    /// config:
+    ///   base_url: https://api.example.com
+    ///   path: /v1/orders
+    ///   pagination:
+    ///     strategy: cursor
+    ///     cursor_path: meta.next_cursor
+    ///     merge_path: data
+    ///     max_pages: 5
+    /// ```
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<PaginationConfig>,
+
+    /// Consume a streaming (SSE, line-delimited or chunked) response
+    /// incrementally; see [`ResponseStreamConfig`].
+    ///
+    /// # Example (YAML)
+    ///
+    /// ```yaml
+    /// config:
+    ///   endpoint: https://llm.example.com/v1/chat/completions
+    ///   method: POST
+    ///   stream:
+    ///     format: sse
+    ///     text_path: choices.0.delta.content
+    ///     partial: true
+    /// ```
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<ResponseStreamConfig>,
+
+    /// GraphQL document (`service: graphql`): a query or mutation template,
+    /// sent as the request's `query`. For `service: sql`, the SQL statement
+    /// (see [`SqlConfig`]).
+    ///
+    /// Takes precedence over the older `body: {query: …}` form. Variables
+    /// the document declares (`$first`) are filled from the caller argument
+    /// of the same name, or from the argument `param_map` renames to it.
+    ///
+    /// # Example (YAML)
+    ///
+    /// ```yaml
+    /// config:
+    ///   endpoint: https://api.github.com/graphql
+    ///   query: |
+    ///     query Repos($first: Int!) {
+    ///       viewer { repositories(first: $first) { nodes { {selection} } } }
+    ///     }
+    ///   selection: [name, stargazerCount, url]
+    ///   param_map:
+    ///     limit: first
+    /// ```
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    /// GraphQL operation to run when `query` defines several
+    /// (`service: graphql` only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_name: Option<String>,
+
+    /// Fields substituted for the `{selection}` placeholder of a GraphQL
+    /// `query` (`service: graphql` only).
+    ///
+    /// Callers can narrow the selection with a `fields` argument listing a
+    /// subset of these; any other field is rejected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selection: Vec<String>,
+
+    /// Compiled `FileDescriptorSet` describing the service (`service: grpc`
+    /// only), e.g. from `protoc --include_imports --descriptor_set_out`.
+    ///
+    /// Without it the method schema is fetched with gRPC server reflection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descriptor_set: Option<String>,
+
+    /// Caller arguments bound, in order, to the positional placeholders of
+    /// a SQL `query` (`service: sql` only): `$1, $2…` on Postgres and
+    /// `SQLite`, `?` on `MySQL`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bind: Vec<String>,
+
+    /// Maximum rows a SQL `query` returns (`service: sql` only; default
+    /// 100). Further rows are dropped and the result is marked truncated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rows: Option<usize>,
+
+    /// Shape of a SQL result (`service: sql` only; default `rows`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<SqlShape>,
+
+    /// Program and argument templates (`service: exec` only); see
+    /// [`ExecConfig`]. A single string is read as a one-element list, as
+    /// `service: cli` capabilities name their binary that way.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_command",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub command: Vec<String>,
+
+    /// Working directory of the command (`service: exec` only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+
+    /// Environment variables of the command (`service: exec` only).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+
+    /// Bytes kept from each of stdout and stderr (`service: exec` only;
+    /// default 64 KiB).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+
+    /// Acknowledges that the capability runs a local command
+    /// (`service: exec` only); required.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dangerous: bool,
+}
+
+/// Largest file argument accepted when `max_bytes` is not set (10 MiB).
+pub const DEFAULT_UPLOAD_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+/// A file parameter of a REST capability.
+///
+/// The argument is base64 (optionally a `data:` URL), an object
+/// `{data | uri, filename?, content_type?}`, or a resource URI: `http(s)://`
+/// URLs are downloaded, `file://` URIs are read only below `file_roots`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileParamConfig {
+    /// Multipart form field name (defaults to the argument name).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+
+    /// File name template (supports `{param}` substitution); defaults to the
+    /// caller's `filename`, the URI's last segment, or the argument name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+
+    /// Content type used when the caller and source give none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+
+    /// Largest accepted file, in bytes.
+    #[serde(default = "default_upload_max_bytes")]
+    pub max_bytes: usize,
+
+    /// Directories `file://` arguments may be read from; empty rejects
+    /// `file://` arguments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_roots: Vec<String>,
+}
+
+impl Default for FileParamConfig {
+    fn default() -> Self {
+        Self {
+            field: None,
+            filename: None,
+            content_type: None,
+            max_bytes: DEFAULT_UPLOAD_MAX_BYTES,
+            file_roots: Vec::new(),
+        }
+    }
+}
+
+/// Deserialize a command given as a single string or a list.
+fn deserialize_command<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(command) => vec![command],
+        OneOrMany::Many(command) => command,
+    })
+}
+
+fn default_upload_max_bytes() -> usize {
+    DEFAULT_UPLOAD_MAX_BYTES
+}
+
+/// Pages fetched when `max_pages` is not set.
+pub const DEFAULT_MAX_PAGES: usize = 10;
+
+/// How a paginated REST API points at its next page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaginationStrategy {
+    /// The response carries an opaque cursor (`cursor_path`) sent back as
+    /// the `param` query parameter (default `cursor`).
+    #[default]
+    Cursor,
+    /// Page numbers in the `param` query parameter (default `page`),
+    /// counting from `start_page`.
+    Page,
+    /// The next page's URL in the response's `Link: <…>; rel="next"` header.
+    LinkHeader,
+}
+
+/// Automatic pagination of a REST capability.
+///
+/// Pages are requested until there is no next page, a page has no items, or
+/// `max_pages` pages have been fetched. The items at `merge_path` of every
+/// page are concatenated into the first page's response, which then goes
+/// through `response_path` as usual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginationConfig {
+    /// How the next page is found.
+    #[serde(default)]
+    pub strategy: PaginationStrategy,
+
+    /// Dot path of the items array in each page (the whole response when
+    /// unset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_path: Option<String>,
+
+    /// Most pages fetched per call, the first included.
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+
+    /// Dot path of the next-page cursor (`cursor` strategy); a missing,
+    /// `null` or empty cursor ends pagination.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor_path: Option<String>,
+
+    /// Query parameter carrying the cursor or page number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param: Option<String>,
+
+    /// First page number (`page` strategy).
+    #[serde(default = "default_start_page")]
+    pub start_page: u64,
+}
+
+impl PaginationConfig {
+    /// Query parameter carrying the cursor or page number.
+    #[must_use]
+    pub fn param_name(&self) -> &str {
+        match (&self.param, self.strategy) {
+            (Some(param), _) => param,
+            (None, PaginationStrategy::Page) => "page",
+            (None, _) => "cursor",
+        }
+    }
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self {
+            strategy: PaginationStrategy::default(),
+            merge_path: None,
+            max_pages: DEFAULT_MAX_PAGES,
+            cursor_path: None,
+            param: None,
+            start_page: default_start_page(),
+        }
+    }
+}
+
+fn default_max_pages() -> usize {
+    DEFAULT_MAX_PAGES
+}
+
+fn default_start_page() -> u64 {
+    1
+}
+
+/// Largest streamed text kept when `max_bytes` is not set (8 MiB).
+pub const DEFAULT_STREAM_MAX_BYTES: usize = 8 * 1024 * 1024;
+
+/// Framing of a streaming response body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamFormat {
+    /// Server-sent events; each event's `data` is one item.
+    #[default]
+    Sse,
+    /// Newline-delimited items (e.g. NDJSON).
+    Lines,
+    /// Every received chunk of text is one item.
+    Chunks,
+}
+
+/// Incremental handling of a streaming provider response.
+///
+/// Each item is reported to the caller as an MCP progress notification (when
+/// the call carries a `progressToken`) instead of being buffered silently.
+/// The provider `timeout` applies between items rather than to the whole
+/// response. The result is `{text, events, truncated}`, where `text` joins
+/// the items (concatenated with `text_path`, one per line without), and then
+/// goes through `response_path` as usual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseStreamConfig {
+    /// Body framing.
+    #[serde(default)]
+    pub format: StreamFormat,
+
+    /// Dot path of the text in each JSON item (e.g.
+    /// `choices.0.delta.content`); the raw item when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_path: Option<String>,
+
+    /// Include each item's text in its progress notification, so the caller
+    /// sees partial output while the call runs.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+
+    /// Item that ends the stream.
+    #[serde(default = "default_stream_done")]
+    pub done: String,
+
+    /// Most bytes of text kept; longer output ends the stream and marks the
+    /// result truncated.
+    #[serde(default = "default_stream_max_bytes")]
+    pub max_bytes: usize,
+}
+
+impl Default for ResponseStreamConfig {
+    fn default() -> Self {
+        Self {
+            format: StreamFormat::default(),
+            text_path: None,
+            partial: false,
+            done: default_stream_done(),
+            max_bytes: DEFAULT_STREAM_MAX_BYTES,
+        }
+    }
+}
+
+fn default_stream_done() -> String {
+    "[DONE]".to_string()
+}
+
+fn default_stream_max_bytes() -> usize {
+    DEFAULT_STREAM_MAX_BYTES
 }
 
 /// Declarative selection of a REST path template from an input parameter.
@@ -541,6 +1138,10 @@ pub struct GraphqlConfig {
     #[serde(default)]
     pub query: Option<String>,
 
+    /// Operation to run when the document defines several (`operationName`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_name: Option<String>,
+
     /// Default GraphQL variables.
     ///
     /// These are merged with caller-supplied variables (caller wins on key
@@ -548,6 +1149,20 @@ pub struct GraphqlConfig {
     #[serde(default)]
     pub variables: HashMap<String, serde_json::Value>,
 
+    /// Caller argument → GraphQL variable renames.
+    ///
+    /// Arguments named like a variable the document declares are sent as
+    /// that variable without an entry here.
+    #[serde(default)]
+    pub variable_map: HashMap<String, String>,
+
+    /// Fields substituted for the `{selection}` placeholder in the query.
+    ///
+    /// A `fields` argument may narrow it to a subset; fields outside this
+    /// list are rejected so callers cannot widen the selection.
+    #[serde(default)]
+    pub selection: Vec<String>,
+
     /// Response path for extracting a nested field from the GraphQL `data`
     /// response (dot-separated, e.g. `"data.viewer"`).
     #[serde(default)]
@@ -592,6 +1207,264 @@ pub struct JsonRpcConfig {
     pub default_params: serde_json::Value,
 }
 
+/// gRPC service configuration.
+///
+/// Calls one unary method with a request message built from the caller's
+/// JSON arguments; the response message is returned as JSON (proto field
+/// names). Message schemas come from `descriptor_set`, or from the server's
+/// reflection service when unset. The provider `timeout` is sent as the
+/// call's deadline.
+///
+/// In YAML the method goes in `config.method` as `package.Service/Method`:
+///
+/// ```yaml
+/// providers:
+///   primary:
+///     service: grpc
+///     timeout: 5
+///     config:
+///       endpoint: https://orders.example.com:443
+///       method: shop.v1.OrderService/GetOrder
+///       descriptor_set: ~/.mcp-gateway/protos/orders.pb
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GrpcConfig {
+    /// Server URL (`http://` for plaintext HTTP/2, `https://` for TLS).
+    #[serde(default)]
+    pub endpoint: String,
+
+    /// Fully qualified method, `package.Service/Method`.
+    #[serde(default)]
+    pub method: String,
+
+    /// Path to a compiled `FileDescriptorSet`; `None` uses server reflection.
+    #[serde(default)]
+    pub descriptor_set: Option<String>,
+
+    /// Request metadata (supports `{env.VAR}` substitution for auth).
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Request fields merged under the caller's arguments (caller wins).
+    #[serde(default)]
+    pub default_fields: HashMap<String, serde_json::Value>,
+
+    /// Dot-separated path extracted from the response message.
+    #[serde(default)]
+    pub response_path: Option<String>,
+}
+
+/// Rows a SQL capability returns when `max_rows` is not set.
+pub const DEFAULT_SQL_MAX_ROWS: usize = 100;
+
+/// Result shape of a SQL capability.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlShape {
+    /// `{columns, rows: [{column: value}], row_count, truncated}`.
+    #[default]
+    Rows,
+    /// The first row as an object, or `null` when there is none.
+    Row,
+    /// The first column of the first row, or `null` when there is none.
+    Value,
+}
+
+/// Read-only SQL query configuration.
+///
+/// Runs the one query written in the capability YAML against Postgres,
+/// `MySQL` or `SQLite`; callers only supply the values bound to its
+/// placeholders, never SQL. Only single `SELECT`/`WITH`/`VALUES` statements
+/// are accepted, and connections are opened read-only. The provider
+/// `timeout` bounds the whole query.
+///
+/// ```yaml
+/// providers:
+///   primary:
+///     service: sql
+///     timeout: 10
+///     config:
+///       endpoint: "{env.REPORTING_DATABASE_URL}"
+///       query: |
+///         SELECT region, SUM(amount) AS revenue
+///         FROM orders WHERE created_at >= $1
+///         GROUP BY region ORDER BY revenue DESC
+///       bind: [since]
+///       max_rows: 50
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SqlConfig {
+    /// Connection URL (`postgres://`, `mysql://` or `sqlite:`); supports
+    /// `{env.VAR}` and secret references, never caller arguments.
+    #[serde(default)]
+    pub url: String,
+
+    /// The SQL statement.
+    #[serde(default)]
+    pub query: String,
+
+    /// Argument names bound to the statement's placeholders, in order.
+    #[serde(default)]
+    pub bind: Vec<String>,
+
+    /// Values used for bound arguments the caller omits.
+    #[serde(default)]
+    pub defaults: HashMap<String, serde_json::Value>,
+
+    /// Maximum rows returned.
+    #[serde(default)]
+    pub max_rows: usize,
+
+    /// Result shape.
+    #[serde(default)]
+    pub shape: SqlShape,
+
+    /// Dot-separated path extracted from the shaped result.
+    #[serde(default)]
+    pub response_path: Option<String>,
+}
+
+impl SqlConfig {
+    /// Check that `query` is a single read-only statement.
+    ///
+    /// This is a coarse guard for configuration mistakes; read-only
+    /// connections are what actually prevent writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if the query is empty, holds
+    /// more than one statement, or does not start with `SELECT`, `WITH` or
+    /// `VALUES`.
+    pub fn check_read_only(&self) -> std::result::Result<(), String> {
+        let body = strip_sql_comments(&self.query);
+        let statement = body.trim().trim_end_matches(';').trim_end();
+        if statement.is_empty() {
+            return Err("SQL query is empty".to_string());
+        }
+        if statement.contains(';') {
+            return Err("SQL query must be a single statement".to_string());
+        }
+        let keyword = statement
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        if !matches!(keyword.as_str(), "SELECT" | "WITH" | "VALUES") {
+            return Err(format!(
+                "SQL query must start with SELECT, WITH or VALUES, not '{keyword}'"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// `sql` without `--` line comments and `/* */` block comments.
+fn strip_sql_comments(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut rest = sql;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("--") {
+            rest = after.find('\n').map_or("", |i| &after[i..]);
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after.find("*/").map_or("", |i| &after[i + 2..]);
+            out.push(' ');
+        } else {
+            let mut chars = rest.chars();
+            out.extend(chars.next());
+            rest = chars.as_str();
+        }
+    }
+    out
+}
+
+/// Bytes of stdout and stderr an exec capability keeps when
+/// `max_output_bytes` is not set.
+pub const DEFAULT_EXEC_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Local command configuration.
+///
+/// Runs the program named by `command[0]` directly (no shell) with the
+/// remaining elements as arguments. Arguments may contain `{param}`
+/// placeholders filled from the caller's arguments (or `static_params`);
+/// the program itself is fixed. The child gets a cleared environment with
+/// only `PATH` and the configured `env`, the provider `timeout` kills it,
+/// and stdout/stderr are capped at `max_output_bytes` each.
+///
+/// Because the gateway's user runs the command, the provider refuses to
+/// run unless `dangerous: true` is set.
+///
+/// ```yaml
+/// providers:
+///   primary:
+///     service: exec
+///     timeout: 10
+///     config:
+///       dangerous: true
+///       command: [git, log, --oneline, -n, "{count}"]
+///       working_dir: ~/src/gateway
+///       env:
+///         GIT_PAGER: cat
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecConfig {
+    /// Program followed by argument templates.
+    #[serde(default)]
+    pub command: Vec<String>,
+
+    /// Working directory (`~` expands to the home directory).
+    #[serde(default)]
+    pub working_dir: Option<String>,
+
+    /// Environment variables (supports `{env.VAR}` and secret references,
+    /// never caller arguments).
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    /// Values used for placeholders the caller omits.
+    #[serde(default)]
+    pub defaults: HashMap<String, serde_json::Value>,
+
+    /// Bytes kept from each of stdout and stderr.
+    #[serde(default)]
+    pub max_output_bytes: usize,
+
+    /// Set to run the command at all.
+    #[serde(default)]
+    pub dangerous: bool,
+
+    /// `"json"` parses stdout as the result; otherwise the result is
+    /// `{exit_code, stdout, stderr, truncated}`.
+    #[serde(default)]
+    pub response_format: String,
+
+    /// Dot-separated path extracted from the result.
+    #[serde(default)]
+    pub response_path: Option<String>,
+}
+
+impl ExecConfig {
+    /// Check that the command can be run at all.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if `dangerous` is not set, the
+    /// command is empty, or the program name contains a placeholder.
+    pub fn check_runnable(&self) -> std::result::Result<(), String> {
+        if !self.dangerous {
+            return Err("exec provider requires 'dangerous: true'".to_string());
+        }
+        let Some(program) = self.command.first() else {
+            return Err("exec provider has no 'command'".to_string());
+        };
+        if program.is_empty() || program.contains('{') {
+            return Err(format!(
+                "exec program '{program}' must be a fixed, non-empty name"
+            ));
+        }
+        Ok(())
+    }
+}
+
 // Note: JsonRpcConfig and GraphqlConfig both use #[derive(Default)]
 // on their struct definitions above, so no manual impl is needed.
 
@@ -615,8 +1488,13 @@ pub enum ProtocolConfig {
     Graphql(GraphqlConfig),
     /// JSON-RPC 2.0 protocol — sends `{ jsonrpc, id, method, params }` as a POST.
     Jsonrpc(JsonRpcConfig),
+    /// gRPC protocol — one unary call with a dynamically built message.
+    Grpc(GrpcConfig),
+    /// Read-only SQL query against a database.
+    Sql(SqlConfig),
+    /// Local command run without a shell.
+    Exec(ExecConfig),
     // Future variants:
-    // Grpc(GrpcConfig),
     // Cli(CliConfig),
     // Wasm(WasmConfig),
 }
@@ -629,6 +1507,9 @@ impl ProtocolConfig {
             ProtocolConfig::Rest(_) => "rest",
             ProtocolConfig::Graphql(_) => "graphql",
             ProtocolConfig::Jsonrpc(_) => "jsonrpc",
+            ProtocolConfig::Grpc(_) => "grpc",
+            ProtocolConfig::Sql(_) => "sql",
+            ProtocolConfig::Exec(_) => "exec",
         }
     }
 
@@ -658,6 +1539,33 @@ impl ProtocolConfig {
             _ => None,
         }
     }
+
+    /// Extract the inner `GrpcConfig`, if this is a gRPC protocol.
+    #[must_use]
+    pub fn as_grpc(&self) -> Option<&GrpcConfig> {
+        match self {
+            ProtocolConfig::Grpc(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Extract the inner `SqlConfig`, if this is a SQL protocol.
+    #[must_use]
+    pub fn as_sql(&self) -> Option<&SqlConfig> {
+        match self {
+            ProtocolConfig::Sql(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Extract the inner `ExecConfig`, if this is a local command.
+    #[must_use]
+    pub fn as_exec(&self) -> Option<&ExecConfig> {
+        match self {
+            ProtocolConfig::Exec(c) => Some(c),
+            _ => None,
+        }
+    }
 }
 
 /// Authentication configuration
@@ -670,17 +1578,33 @@ impl ProtocolConfig {
 /// - `keychain:name` - macOS Keychain
 /// - `env:VAR_NAME` - Environment variable
 /// - `oauth:provider` - OAuth token from vault
+/// - `vault:MOUNT/PATH#FIELD` - Vault KV v2 field (needs gateway `vault:` config)
+/// - `aws-secrets:NAME#FIELD` - AWS Secrets Manager secret (or `NAME` for the whole string)
+/// - `aws-ssm:NAME` - AWS SSM Parameter Store value
+/// - `op:VAULT/ITEM/FIELD` - 1Password field, via the `op` CLI
+/// - `bw:ITEM#FIELD` - Bitwarden item field, via the `bw` CLI
+/// - `secret:KEY.PATH` - Encrypted secrets file entry (needs gateway `secrets_file:` config)
+///
+/// With `type: oauth2_client_credentials` the gateway obtains the access
+/// token itself: it POSTs the client credentials grant to `token_endpoint`,
+/// with `client_id` and the client secret referenced by `key`, and caches
+/// the token until shortly before it expires.
+///
+/// With `type: aws_sigv4` REST requests are signed with AWS Signature
+/// Version 4 for the region and service in `aws`.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AuthConfig {
     /// Whether authentication is required
     #[serde(default)]
     pub required: bool,
 
-    /// Authentication type (oauth, `api_key`, basic, bearer, none)
+    /// Authentication type (oauth, `oauth2_client_credentials`,
+    /// `aws_sigv4`, `api_key`, basic, bearer, none)
     #[serde(rename = "type", default)]
     pub auth_type: String,
 
-    /// OAuth scopes (for oauth type)
+    /// OAuth scopes (for oauth type; requested by the client credentials
+    /// grant)
     #[serde(default)]
     pub scopes: Vec<String>,
 
@@ -712,9 +1636,30 @@ pub struct AuthConfig {
     /// When `key` is `oauth:<provider>` and the stored token is expired,
     /// the executor will POST `grant_type=refresh_token` here when a
     /// refresh token is available.
+    ///
+    /// For `oauth2_client_credentials`, the endpoint of the client
+    /// credentials grant.
     #[serde(default)]
     pub token_endpoint: Option<String>,
 
+    /// OAuth client ID for `oauth2_client_credentials`: a literal, or an
+    /// `env:`, `keychain:` or `file:` reference like `key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+
+    /// How `oauth2_client_credentials` authenticates to the token endpoint.
+    #[serde(default, skip_serializing_if = "is_default_client_auth")]
+    pub client_auth: ClientAuthMethod,
+
+    /// `audience` sent with the client credentials grant, for servers that
+    /// issue tokens per API (e.g. Auth0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+
+    /// Region, service and credentials for `aws_sigv4`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aws: Option<AwsSigV4Config>,
+
     /// Operator-blessed escape hatch for a gateway-held `oauth:<provider>`
     /// credential that is intentionally shared across every caller (e.g. a
     /// team service account), mirroring `OAuthConfig.shared_account` for MCP
@@ -726,6 +1671,57 @@ pub struct AuthConfig {
     pub shared_account: bool,
 }
 
+/// `auth.type` of capabilities whose token the gateway obtains with the
+/// OAuth 2.0 client credentials grant.
+pub const OAUTH2_CLIENT_CREDENTIALS: &str = "oauth2_client_credentials";
+
+/// `auth.type` of capabilities whose requests are signed with AWS
+/// Signature Version 4.
+pub const AWS_SIGV4: &str = "aws_sigv4";
+
+/// AWS Signature Version 4 settings (`auth.aws`)
+///
+/// Without `access_key_id` and `secret_access_key` the credentials come from
+/// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`, then
+/// from the ECS container or EC2 instance profile credentials endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AwsSigV4Config {
+    /// AWS region, e.g. `eu-west-1`.
+    pub region: String,
+
+    /// Signing name of the service, e.g. `s3`, `execute-api`, `bedrock`.
+    pub service: String,
+
+    /// Access key ID reference (`env:`, `keychain:`, `file:`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_key_id: Option<String>,
+
+    /// Secret access key reference (`env:`, `keychain:`, `file:`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_access_key: Option<String>,
+
+    /// Session token reference, for temporary credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+}
+
+/// Client authentication at the token endpoint (RFC 6749 section 2.3.1)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientAuthMethod {
+    /// HTTP Basic with the client ID and secret (`client_secret_basic`).
+    #[default]
+    Basic,
+    /// `client_id` and `client_secret` in the form body
+    /// (`client_secret_post`).
+    Post,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde's skip_serializing_if signature
+fn is_default_client_auth(method: &ClientAuthMethod) -> bool {
+    *method == ClientAuthMethod::default()
+}
+
 /// Cache configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CacheConfig {
@@ -754,6 +1750,88 @@ impl CacheConfig {
     }
 }
 
+/// Default delay before the first retry of a capability call, in
+/// milliseconds.
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 200;
+
+/// Default upper bound of the delay between retries, in milliseconds.
+pub const DEFAULT_RETRY_MAX_BACKOFF_MS: u64 = 5_000;
+
+/// Per-capability retries, deadline and upstream rate limit (`resilience:`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResilienceConfig {
+    /// Further attempts on each provider after HTTP 429 or a request that
+    /// never reached it (0 = none). Transport errors, timeouts and 5xx are
+    /// retried only for idempotent calls (see `metadata.idempotent`).
+    #[serde(default)]
+    pub retries: u32,
+
+    /// Delay before the first retry in milliseconds, doubled per retry.
+    #[serde(default = "default_retry_backoff_ms")]
+    pub backoff_ms: u64,
+
+    /// Upper bound of the delay between retries in milliseconds.
+    #[serde(default = "default_retry_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+
+    /// Deadline of the whole call in seconds, across retries and provider
+    /// failover (unset = bounded only by the provider timeouts).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+
+    /// Upstream requests allowed per period, such as `60/min`. Every
+    /// attempt, retries included, takes one; calls beyond it are rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<crate::config::RateSpec>,
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    DEFAULT_RETRY_BACKOFF_MS
+}
+
+fn default_retry_max_backoff_ms() -> u64 {
+    DEFAULT_RETRY_MAX_BACKOFF_MS
+}
+
+impl Default for ResilienceConfig {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            backoff_ms: DEFAULT_RETRY_BACKOFF_MS,
+            max_backoff_ms: DEFAULT_RETRY_MAX_BACKOFF_MS,
+            timeout: None,
+            rate_limit: None,
+        }
+    }
+}
+
+impl ResilienceConfig {
+    /// Whether nothing is configured (the default).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Deadline of the whole call, if set.
+    #[must_use]
+    pub fn timeout_duration(&self) -> Option<std::time::Duration> {
+        self.timeout.map(std::time::Duration::from_secs)
+    }
+
+    /// Delay before retry number `retry` (1-based).
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> std::time::Duration {
+        let factor = 1_u64
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        std::time::Duration::from_millis(
+            self.backoff_ms
+                .saturating_mul(factor)
+                .min(self.max_backoff_ms),
+        )
+    }
+}
+
 /// Webhook transform configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WebhookTransform {
@@ -785,6 +1863,10 @@ pub struct WebhookDefinition {
     /// Payload transform configuration
     #[serde(default)]
     pub transform: WebhookTransform,
+    /// JSON Schema of the notification `data` (e.g. generated from an
+    /// `AsyncAPI` message payload); informational, reported in webhook status
+    #[serde(default)]
+    pub schema: Option<serde_json::Value>,
 }
 
 // Manual `Debug` that redacts the HMAC verification secret (CWE-532, mirrors
@@ -800,6 +1882,7 @@ impl std::fmt::Debug for WebhookDefinition {
             .field("signature_header", &self.signature_header)
             .field("notify", &self.notify)
             .field("transform", &self.transform)
+            .field("schema", &self.schema)
             .finish()
     }
 }
@@ -958,7 +2041,9 @@ impl CapabilityDefinition {
     /// - `[schema: field1, field2, ...]`
     ///
     /// Both are invisible to human readers but searchable by the gateway's
-    /// ranking engine and by LLMs reading the description.
+    /// ranking engine and by LLMs reading the description. A deprecated
+    /// capability's description starts with `[DEPRECATED: ...]`, so the
+    /// warning reaches every tool listing and search result.
     #[must_use]
     fn build_description(&self) -> String {
         let keyword_suffix = if self.metadata.tags.is_empty() {
@@ -974,7 +2059,48 @@ impl CapabilityDefinition {
             format!(" [schema: {}]", schema_fields.join(", "))
         };
 
-        format!("{}{keyword_suffix}{schema_suffix}", self.description)
+        let deprecation_prefix = self
+            .deprecation_warning()
+            .map_or_else(String::new, |warning| format!("[DEPRECATED: {warning}] "));
+
+        format!(
+            "{deprecation_prefix}{}{keyword_suffix}{schema_suffix}",
+            self.description
+        )
+    }
+
+    /// Tool name of this version while a newer version of the same `name`
+    /// is loaded: `<name>_v<version>`, with characters other than letters
+    /// and digits in the version replaced by `_` (`1.2` gives `search_v1_2`).
+    /// Unversioned capabilities keep `name`.
+    #[must_use]
+    pub fn versioned_name(&self) -> String {
+        match &self.version {
+            Some(version) => {
+                let version: String = version
+                    .trim_start_matches(['v', 'V'])
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect();
+                format!("{}_v{version}", self.name)
+            }
+            None => self.name.clone(),
+        }
+    }
+
+    /// Warning shown for a deprecated capability, naming its replacement
+    /// when one is set; `None` when it is not deprecated.
+    #[must_use]
+    pub fn deprecation_warning(&self) -> Option<String> {
+        if !self.deprecated {
+            return None;
+        }
+        Some(match &self.replaced_by {
+            Some(replacement) => {
+                format!("'{}' is deprecated; use '{replacement}' instead", self.name)
+            }
+            None => format!("'{}' is deprecated", self.name),
+        })
     }
 
     /// Collect all schema field tokens from input and output schemas combined,
@@ -1063,6 +2189,7 @@ mod cwe532_debug_redaction {
             signature_header: Some("X-Linear-Signature".to_string()),
             notify: true,
             transform: WebhookTransform::default(),
+            schema: None,
         };
         let dbg = format!("{w:?}");
         assert!(!dbg.contains(SENTINEL), "leaked webhook secret: {dbg}");
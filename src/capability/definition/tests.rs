@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2026 Mikko Parkkola
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 use super::*;
+use crate::capability::template::{self, Escape};
 
 #[test]
 fn test_default_values() {
@@ -28,6 +29,10 @@ fn make_capability(name: &str, description: &str, tags: Vec<&str>) -> Capability
         webhooks: HashMap::new(),
         sha256: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
     }
 }
 
@@ -119,6 +124,68 @@ fn build_description_with_tags_includes_all_tags() {
     assert_eq!(cap.build_description(), "Desc [keywords: a, b, c]");
 }
 
+#[test]
+fn build_description_of_deprecated_capability_leads_with_warning() {
+    let mut cap = make_capability("search", "Desc", vec![]);
+    cap.deprecated = true;
+    assert_eq!(
+        cap.build_description(),
+        "[DEPRECATED: 'search' is deprecated] Desc"
+    );
+
+    cap.replaced_by = Some("search_v2".to_string());
+    assert_eq!(
+        cap.deprecation_warning().as_deref(),
+        Some("'search' is deprecated; use 'search_v2' instead")
+    );
+}
+
+// ── versions ──────────────────────────────────────────────────────────
+
+#[test]
+fn compare_versions_orders_numeric_parts_numerically() {
+    use std::cmp::Ordering;
+    assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+    assert_eq!(compare_versions("v2", "2.0.0"), Ordering::Equal);
+    assert_eq!(compare_versions("1.2.0", "1.2.1"), Ordering::Less);
+    assert_eq!(compare_versions("2.0-rc", "2.0-beta"), Ordering::Greater);
+}
+
+#[test]
+fn versioned_name_suffixes_a_sanitized_version() {
+    let mut cap = make_capability("search", "Desc", vec![]);
+    assert_eq!(cap.versioned_name(), "search");
+    cap.version = Some("v1.2-beta".to_string());
+    assert_eq!(cap.versioned_name(), "search_v1_2_beta");
+}
+
+#[test]
+fn version_fields_parse_from_yaml() {
+    let yaml = r#"
+name: search
+description: Search things.
+version: "1.2"
+deprecated: true
+replaced_by: search_v2
+providers:
+  primary:
+    config: {base_url: 'https://api.example.com'}
+"#;
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(cap.version.as_deref(), Some("1.2"));
+    assert!(cap.deprecated);
+    assert_eq!(cap.replaced_by.as_deref(), Some("search_v2"));
+
+    // Unset metadata stays out of the serialized form
+    let plain = make_capability("plain", "Desc", vec![]);
+    let out = serde_yaml::to_string(&plain).unwrap();
+    assert!(
+        !out.lines()
+            .any(|line| line.starts_with("version:") || line.starts_with("deprecated:")),
+        "{out}"
+    );
+}
+
 // ── extract_schema_fields ─────────────────────────────────────────────
 
 #[test]
@@ -243,6 +310,10 @@ fn make_capability_with_schema(
         webhooks: HashMap::new(),
         sha256: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
     }
 }
 
@@ -466,6 +537,8 @@ fn provider_config_protocol_config_maps_rest_service() {
         service: "rest".to_string(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             base_url: "https://api.example.com".to_string(),
             path: "/users".to_string(),
@@ -489,6 +562,8 @@ fn provider_config_protocol_config_defaults_empty_service_to_rest() {
         service: String::new(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             base_url: "https://fallback.example.com".to_string(),
             ..Default::default()
@@ -505,13 +580,15 @@ fn provider_config_protocol_config_defaults_empty_service_to_rest() {
 
 #[test]
 fn provider_config_protocol_config_unknown_service_falls_back_to_rest() {
-    // GIVEN: ProviderConfig with unknown service = "grpc"
+    // GIVEN: ProviderConfig with unknown service = "carrier-pigeon"
     // WHEN: calling protocol_config()
     // THEN: falls back to REST (backward compat)
     let provider = ProviderConfig {
-        service: "grpc".to_string(),
+        service: "carrier-pigeon".to_string(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             base_url: "https://grpc.example.com".to_string(),
             ..Default::default()
@@ -524,6 +601,13 @@ fn provider_config_protocol_config_unknown_service_falls_back_to_rest() {
         proto.as_rest().unwrap().base_url,
         "https://grpc.example.com"
     );
+
+    // AND: "grpc" is a known service now, whether or not the feature is built
+    let provider = ProviderConfig {
+        service: "grpc".to_string(),
+        ..provider
+    };
+    assert_eq!(provider.protocol_config().protocol_name(), "grpc");
 }
 
 #[test]
@@ -586,6 +670,7 @@ fn protocol_config_graphql_round_trips_through_serde_json() {
             v
         },
         response_path: Some("data.viewer".to_string()),
+        ..Default::default()
     });
 
     let json = serde_json::to_string(&config).unwrap();
@@ -655,6 +740,8 @@ fn provider_config_graphql_service_maps_to_graphql_protocol() {
         service: "graphql".to_string(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             endpoint: "https://api.github.com/graphql".to_string(),
             headers: {
@@ -682,6 +769,8 @@ fn provider_config_graphql_uses_base_url_plus_path_when_no_endpoint() {
         service: "graphql".to_string(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             base_url: "https://api.example.com".to_string(),
             path: "/graphql".to_string(),
@@ -703,6 +792,8 @@ fn provider_config_graphql_extracts_query_from_body_string() {
         service: "graphql".to_string(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             endpoint: "https://api.example.com/graphql".to_string(),
             body: Some(serde_json::json!("{ viewer { login } }")),
@@ -724,6 +815,8 @@ fn provider_config_graphql_extracts_query_from_body_object() {
         service: "graphql".to_string(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             endpoint: "https://api.example.com/graphql".to_string(),
             body: Some(serde_json::json!({ "query": "{ users { id } }" })),
@@ -736,6 +829,39 @@ fn provider_config_graphql_extracts_query_from_body_object() {
     assert_eq!(gql.query.as_deref(), Some("{ users { id } }"));
 }
 
+#[test]
+fn provider_config_graphql_reads_query_selection_and_variable_map_from_yaml() {
+    // GIVEN: a GraphQL provider using the dedicated query fields
+    let provider: ProviderConfig = serde_yaml::from_str(
+        r"
+service: graphql
+config:
+  endpoint: https://api.example.com/graphql
+  query: 'query Users($first: Int) { users(first: $first) { {selection} } }'
+  operation_name: Users
+  selection: [id, name]
+  param_map:
+    limit: first
+  body:
+    query: '{ ignored }'
+",
+    )
+    .unwrap();
+
+    // WHEN: calling protocol_config()
+    let proto = provider.protocol_config();
+
+    // THEN: the dedicated fields win over the body and carry over
+    let gql = proto.as_graphql().unwrap();
+    assert!(gql.query.as_deref().unwrap().starts_with("query Users"));
+    assert_eq!(gql.operation_name.as_deref(), Some("Users"));
+    assert_eq!(gql.selection, ["id", "name"]);
+    assert_eq!(
+        gql.variable_map.get("limit").map(String::as_str),
+        Some("first")
+    );
+}
+
 #[test]
 fn provider_config_graphql_maps_static_params_to_variables() {
     // GIVEN: ProviderConfig with static_params
@@ -745,6 +871,8 @@ fn provider_config_graphql_maps_static_params_to_variables() {
         service: "graphql".to_string(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             endpoint: "https://api.example.com/graphql".to_string(),
             static_params: {
@@ -767,6 +895,8 @@ fn provider_config_graphql_preserves_response_path() {
         service: "graphql".to_string(),
         cost_per_call: 0.0,
         timeout: 30,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             endpoint: "https://api.example.com/graphql".to_string(),
             response_path: Some("data.viewer".to_string()),
@@ -834,6 +964,45 @@ fn github_graphql_sample_capability_loads() {
     assert!(gql.query.as_deref().unwrap().contains("viewer"));
 }
 
+// ── gRPC ProtocolConfig tests ───────────────────────────────────────
+
+#[test]
+fn provider_config_grpc_service_maps_to_grpc_protocol() {
+    // GIVEN: a gRPC provider in the flat YAML form
+    let provider: ProviderConfig = serde_yaml::from_str(
+        r"
+service: grpc
+timeout: 5
+config:
+  base_url: https://orders.example.com:443
+  method: shop.v1.OrderService/GetOrder
+  descriptor_set: ~/protos/orders.pb
+  headers:
+    authorization: 'Bearer {env.ORDERS_TOKEN}'
+  static_params:
+    include_items: true
+  response_path: order
+",
+    )
+    .unwrap();
+
+    // WHEN: calling protocol_config()
+    let proto = provider.protocol_config();
+
+    // THEN: the gRPC fields are carried over
+    assert_eq!(proto.protocol_name(), "grpc");
+    let grpc = proto.as_grpc().unwrap();
+    assert_eq!(grpc.endpoint, "https://orders.example.com:443");
+    assert_eq!(grpc.method, "shop.v1.OrderService/GetOrder");
+    assert_eq!(grpc.descriptor_set.as_deref(), Some("~/protos/orders.pb"));
+    assert_eq!(
+        grpc.default_fields["include_items"],
+        serde_json::json!(true)
+    );
+    assert_eq!(grpc.response_path.as_deref(), Some("order"));
+    assert!(proto.as_graphql().is_none());
+}
+
 // ── JSON-RPC ProtocolConfig tests ──────────────────────────────────
 
 #[test]
@@ -897,6 +1066,8 @@ fn provider_config_jsonrpc_service_maps_to_jsonrpc_protocol() {
         service: "jsonrpc".to_string(),
         cost_per_call: 0.0,
         timeout: 10,
+        priority: None,
+        weight: 1,
         config: RestConfig {
             endpoint: "http://localhost:8545".to_string(),
             method: "eth_getBalance".to_string(),
@@ -970,6 +1141,256 @@ fn jsonrpc_sample_capability_loads() {
     assert_eq!(jrpc.default_params["tag"], "latest");
 }
 
+// ── Sample gRPC capability YAML loads correctly ─────────────────────
+
+#[test]
+fn grpc_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/grpc_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    let provider = cap.providers.get("primary").unwrap();
+    let proto = provider.protocol_config();
+    let grpc = proto.as_grpc().unwrap();
+    assert_eq!(grpc.endpoint, "https://orders.example.com:443");
+    assert_eq!(grpc.method, "shop.v1.OrderService/GetOrder");
+    assert_eq!(provider.timeout, 5);
+}
+
+// ── Sample SQL capability YAML loads correctly ──────────────────────
+
+#[test]
+fn sql_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/sql_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    let proto = cap.providers.get("primary").unwrap().protocol_config();
+    let sql = proto.as_sql().unwrap();
+    assert_eq!(sql.url, "{env.REPORTING_DATABASE_URL}");
+    assert_eq!(sql.bind, vec!["since", "region"]);
+    assert_eq!(sql.defaults["region"], "%");
+    assert_eq!(sql.max_rows, 50);
+    assert_eq!(sql.shape, SqlShape::Rows);
+    assert!(sql.check_read_only().is_ok());
+}
+
+#[test]
+fn sql_check_read_only_ignores_comments_and_trailing_semicolon() {
+    let mut sql = SqlConfig {
+        query: "-- revenue\n/* by region */ select 1;".to_string(),
+        ..SqlConfig::default()
+    };
+    assert!(sql.check_read_only().is_ok());
+
+    sql.query = "/* select */ UPDATE orders SET amount = 0".to_string();
+    assert!(sql.check_read_only().unwrap_err().contains("UPDATE"));
+}
+
+// ── Sample exec capability YAML loads correctly ─────────────────────
+
+#[test]
+fn exec_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/exec_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    let proto = cap.providers.get("primary").unwrap().protocol_config();
+    let exec = proto.as_exec().unwrap();
+    assert_eq!(exec.command[0], "git");
+    assert_eq!(exec.command.last().unwrap(), "{count}");
+    assert_eq!(exec.working_dir.as_deref(), Some("~/src/mcp-gateway"));
+    assert_eq!(exec.env["GIT_PAGER"], "cat");
+    assert_eq!(exec.max_output_bytes, 32768);
+    assert!(exec.check_runnable().is_ok());
+}
+
+#[test]
+fn upload_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/upload_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    let proto = cap.providers.get("primary").unwrap().protocol_config();
+    let rest = proto.as_rest().unwrap();
+    let document = &rest.files["document"];
+    assert_eq!(document.field.as_deref(), Some("file"));
+    assert_eq!(document.max_bytes, 5_242_880);
+    assert_eq!(document.file_roots, vec!["~/Documents/scans"]);
+    assert!(rest.body_content_type.is_empty());
+}
+
+#[test]
+fn file_param_max_bytes_defaults_to_ten_mib() {
+    let config: RestConfig = serde_yaml::from_str("files:\n  document: {}\n").unwrap();
+    assert_eq!(config.files["document"].max_bytes, DEFAULT_UPLOAD_MAX_BYTES);
+}
+
+#[test]
+fn pagination_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/pagination_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    let proto = cap.providers.get("primary").unwrap().protocol_config();
+    let pagination = proto.as_rest().unwrap().pagination.as_ref().unwrap();
+    assert_eq!(pagination.strategy, PaginationStrategy::LinkHeader);
+    assert_eq!(pagination.max_pages, 5);
+    assert_eq!(pagination.merge_path, None);
+}
+
+#[test]
+fn pagination_param_defaults_follow_the_strategy() {
+    let cursor: PaginationConfig = serde_yaml::from_str("cursor_path: next").unwrap();
+    assert_eq!(cursor.param_name(), "cursor");
+    assert_eq!(cursor.max_pages, DEFAULT_MAX_PAGES);
+
+    let page: PaginationConfig = serde_yaml::from_str("strategy: page").unwrap();
+    assert_eq!((page.param_name(), page.start_page), ("page", 1));
+}
+
+#[test]
+fn streaming_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/streaming_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    let proto = cap.providers.get("primary").unwrap().protocol_config();
+    let stream = proto.as_rest().unwrap().stream.as_ref().unwrap();
+    assert_eq!(stream.format, StreamFormat::Sse);
+    assert_eq!(stream.text_path.as_deref(), Some("choices.0.delta.content"));
+    assert!(stream.partial);
+    assert_eq!(stream.done, "[DONE]");
+    assert_eq!(stream.max_bytes, 1_048_576);
+}
+
+#[test]
+fn failover_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/failover_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    assert_eq!(cap.providers.strategy, ProviderStrategy::Weighted);
+    let ordered: Vec<(String, u32)> = cap
+        .providers
+        .ordered()
+        .into_iter()
+        .map(|(name, provider)| (name, provider.weight))
+        .collect();
+    assert_eq!(
+        ordered,
+        vec![
+            ("primary".to_string(), 3),
+            ("secondary".to_string(), 1),
+            ("fallback[0]".to_string(), 0),
+        ]
+    );
+}
+
+#[test]
+fn providers_default_to_failover_with_unit_weights() {
+    // GIVEN: two named providers, the non-primary one with a lower priority
+    let yaml = r"
+name: my_tool
+providers:
+  primary:
+    config: {base_url: 'https://a.example.com'}
+  backup:
+    priority: 0
+    config: {base_url: 'https://b.example.com'}
+";
+
+    // WHEN: parsing
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    // THEN: failover strategy, weight 1, and ties on priority broken by name
+    assert_eq!(cap.providers.strategy, ProviderStrategy::Failover);
+    let names: Vec<String> = cap
+        .providers
+        .ordered()
+        .into_iter()
+        .map(|(n, _)| n)
+        .collect();
+    assert_eq!(names, vec!["backup", "primary"]);
+    assert!(cap.providers.named.values().all(|p| p.weight == 1));
+    assert!(
+        !serde_yaml::to_string(&cap.providers)
+            .unwrap()
+            .contains("strategy")
+    );
+}
+
+#[test]
+fn resilience_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/resilience_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    let resilience = &cap.resilience;
+    assert_eq!(resilience.retries, 3);
+    assert_eq!(
+        resilience.timeout_duration(),
+        Some(std::time::Duration::from_secs(15))
+    );
+    assert_eq!(resilience.rate_limit.unwrap().to_string(), "60/min");
+    // 250ms doubling, capped at 2s
+    let waits: Vec<u128> = (1..=5).map(|n| resilience.backoff(n).as_millis()).collect();
+    assert_eq!(waits, vec![250, 500, 1000, 2000, 2000]);
+}
+
+#[test]
+fn resilience_defaults_to_no_retries_and_is_not_serialized() {
+    let cap = make_capability("my_tool", "A tool", vec![]);
+    assert!(cap.resilience.is_empty());
+    assert_eq!(cap.resilience.backoff_ms, DEFAULT_RETRY_BACKOFF_MS);
+    assert!(!serde_yaml::to_string(&cap).unwrap().contains("resilience"));
+}
+
+#[test]
+fn client_credentials_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/client_credentials_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    assert_eq!(cap.auth.auth_type, OAUTH2_CLIENT_CREDENTIALS);
+    assert_eq!(cap.auth.client_id.as_deref(), Some("env:CRM_CLIENT_ID"));
+    assert_eq!(cap.auth.client_auth, ClientAuthMethod::Basic);
+    assert_eq!(cap.auth.scopes, vec!["accounts.read"]);
+    assert_eq!(
+        cap.auth.audience.as_deref(),
+        Some("https://api.crm.example.com")
+    );
+}
+
+#[test]
+fn client_auth_post_parses() {
+    let auth: AuthConfig = serde_yaml::from_str("client_auth: post").unwrap();
+    assert_eq!(auth.client_auth, ClientAuthMethod::Post);
+}
+
+#[test]
+fn aws_sigv4_sample_capability_loads() {
+    let yaml = include_str!("../../../capabilities/examples/aws_sigv4_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+
+    assert_eq!(cap.auth.auth_type, AWS_SIGV4);
+    let aws = cap.auth.aws.unwrap();
+    assert_eq!(aws.region, "eu-west-1");
+    assert_eq!(aws.service, "execute-api");
+    assert!(aws.access_key_id.is_none());
+}
+
+#[test]
+fn template_sample_capability_loads_and_renders() {
+    let yaml = include_str!("../../../capabilities/examples/template_example.yaml");
+    let cap: CapabilityDefinition = serde_yaml::from_str(yaml).unwrap();
+    let config = &cap.providers.named["primary"].config;
+    let filter = config.body.as_ref().unwrap()["filter"].as_str().unwrap();
+    assert!(template::is_template(filter));
+
+    let render = |params| template::render(filter, &params, Escape::None);
+    let rendered = render(serde_json::json!({"status": "Open", "tags": ["vip"]})).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(rendered.trim()).unwrap(),
+        serde_json::json!({"status": "open", "tags": ["vip"]})
+    );
+    assert_eq!(
+        render(serde_json::json!({"query": "x"})).unwrap().trim(),
+        ""
+    );
+}
+
 // ── response_transform field ──────────────────────────────────────────────────
 
 #[test]
@@ -1115,7 +1536,7 @@ fn assert_linear_capability_shape(
         "{relative_path} should keep the GraphQL extraction path wired",
     );
 
-    let pipeline = crate::transform::TransformPipeline::compile(&cap.transform);
+    let pipeline = crate::transform::TransformPipeline::compile(&cap.transform).unwrap();
     let shaped = pipeline.apply(raw_after_response_path);
     assert_eq!(
         shaped, expected_shaped,
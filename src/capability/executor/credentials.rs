@@ -3,7 +3,13 @@
 //! Credential resolution for capability execution
 //!
 //! All credential sources: `env:VAR`, `keychain:name`, `oauth:provider`,
-//! `file:/path:field`, `{env.VAR}`, `BARE_UPPER_NAME`.
+//! `file:/path:field`, `vault:MOUNT/PATH#FIELD`, `aws-secrets:NAME#FIELD`,
+//! `aws-ssm:NAME`, `op:VAULT/ITEM/FIELD`, `bw:ITEM#FIELD`, `secret:KEY.PATH`,
+//! `{env.VAR}`, `BARE_UPPER_NAME`, plus access tokens obtained with the OAuth
+//! 2.0 client credentials grant (`type: oauth2_client_credentials`).
+//!
+//! Every lookup is recorded in the secret access audit trail
+//! ([`crate::secrets::audit`]).
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -12,16 +18,35 @@ use serde::Deserialize;
 use serde_json::Value;
 use tracing::{info, warn};
 
+use crate::capability::{AuthConfig, ClientAuthMethod, OAUTH2_CLIENT_CREDENTIALS};
 use crate::oauth::TokenInfo;
 use crate::{Error, Result};
 
 use super::CapabilityExecutor;
 
+/// Lifetime assumed for a client credentials token without `expires_in`.
+const DEFAULT_CLIENT_TOKEN_LIFETIME_SECS: u64 = 300;
+
 impl CapabilityExecutor {
-    /// Fetch credential from secure storage.
-    pub(super) async fn fetch_credential(&self, auth: &super::super::AuthConfig) -> Result<String> {
-        let key = &auth.key;
+    /// Fetch the credential of `auth`: an access token for
+    /// `oauth2_client_credentials`, otherwise the secret `auth.key` refers to.
+    pub(super) async fn fetch_credential(&self, auth: &AuthConfig) -> Result<String> {
+        if auth.auth_type == OAUTH2_CLIENT_CREDENTIALS {
+            self.fetch_client_credentials_token(auth).await
+        } else {
+            self.resolve_credential(&auth.key, auth).await
+        }
+    }
+
+    /// Fetch the credential `key` refers to from secure storage, recording
+    /// the access in the audit trail.
+    pub(super) async fn resolve_credential(&self, key: &str, auth: &AuthConfig) -> Result<String> {
+        let credential = self.lookup_credential(key, auth).await;
+        crate::secrets::audit::resolved(key, credential.is_ok());
+        credential
+    }
 
+    async fn lookup_credential(&self, key: &str, auth: &AuthConfig) -> Result<String> {
         if let Some(var_name) = key.strip_prefix("env:") {
             std::env::var(var_name).map_err(|_| {
                 Error::Config(format!(
@@ -36,6 +61,41 @@ impl CapabilityExecutor {
                 .await
         } else if let Some(file_spec) = key.strip_prefix("file:") {
             self.fetch_from_file(file_spec)
+        } else if let Some(reference) = key.strip_prefix("vault:") {
+            let client = crate::secrets::vault::client().ok_or_else(|| {
+                Error::Config(format!(
+                    "Credential 'vault:{reference}' needs a `vault:` section in the gateway config"
+                ))
+            })?;
+            client.read(reference).await
+        } else if let Some(reference) = key.strip_prefix("aws-secrets:") {
+            crate::secrets::aws::client()
+                .read(
+                    crate::secrets::aws::AwsSecretSource::SecretsManager,
+                    reference,
+                )
+                .await
+        } else if let Some(reference) = key.strip_prefix("aws-ssm:") {
+            crate::secrets::aws::client()
+                .read(
+                    crate::secrets::aws::AwsSecretSource::ParameterStore,
+                    reference,
+                )
+                .await
+        } else if let Some(reference) = key.strip_prefix("op:") {
+            crate::secrets::password_manager::read(
+                crate::secrets::password_manager::PasswordManager::OnePassword,
+                reference,
+            )
+            .await
+        } else if let Some(reference) = key.strip_prefix("bw:") {
+            crate::secrets::password_manager::read(
+                crate::secrets::password_manager::PasswordManager::Bitwarden,
+                reference,
+            )
+            .await
+        } else if let Some(path) = key.strip_prefix("secret:") {
+            crate::secrets::encrypted_file::lookup(path)
         } else if key.starts_with("{env.") && key.ends_with('}') {
             let var_name = &key[5..key.len() - 1];
             std::env::var(var_name)
@@ -50,7 +110,7 @@ impl CapabilityExecutor {
             })
         } else {
             Err(Error::Config(format!(
-                "Unknown credential format: {}. Use env:, keychain:, oauth:, file:, or set environment variable",
+                "Unknown credential format: {}. Use env:, keychain:, oauth:, file:, vault:, aws-secrets:, aws-ssm:, op:, bw:, secret:, or set environment variable",
                 key.chars().take(20).collect::<String>()
             )))
         }
@@ -176,6 +236,132 @@ impl CapabilityExecutor {
         )))
     }
 
+    /// Fetch an access token with the OAuth 2.0 client credentials grant.
+    ///
+    /// Tokens are cached in memory per token endpoint, client, scopes and
+    /// audience, and reused until 60 seconds before they expire. Client
+    /// credentials tokens carry no refresh token; an expired one is replaced
+    /// by a new grant.
+    pub(super) async fn fetch_client_credentials_token(&self, auth: &AuthConfig) -> Result<String> {
+        let endpoint = auth
+            .token_endpoint
+            .as_deref()
+            .filter(|e| !e.is_empty())
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "auth.token_endpoint is required for {OAUTH2_CLIENT_CREDENTIALS}"
+                ))
+            })?;
+        let client_id = match auth.client_id.as_deref() {
+            Some(id) if is_credential_reference(id) => self.resolve_credential(id, auth).await?,
+            Some(id) if !id.is_empty() => id.to_string(),
+            _ => {
+                return Err(Error::Config(format!(
+                    "auth.client_id is required for {OAUTH2_CLIENT_CREDENTIALS}"
+                )));
+            }
+        };
+
+        let cache_key = client_token_cache_key(endpoint, &client_id, auth);
+        {
+            let tokens = self.oauth_tokens.read();
+            if let Some(token) = tokens.get(&cache_key)
+                && !token.is_expired()
+            {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let client_secret = self.resolve_credential(&auth.key, auth).await?;
+        let token = self
+            .request_client_credentials_token(endpoint, &client_id, &client_secret, auth)
+            .await?;
+        let access_token = token.access_token.clone();
+        {
+            let tokens = self.oauth_tokens.read();
+            tokens.insert(cache_key, token);
+        }
+        info!(token_endpoint = %endpoint, "Obtained OAuth client credentials token");
+        Ok(access_token)
+    }
+
+    /// POST the client credentials grant to `endpoint`.
+    async fn request_client_credentials_token(
+        &self,
+        endpoint: &str,
+        client_id: &str,
+        client_secret: &str,
+        auth: &AuthConfig,
+    ) -> Result<TokenInfo> {
+        let scope = auth.scopes.join(" ");
+        let mut form = vec![("grant_type", "client_credentials")];
+        if !scope.is_empty() {
+            form.push(("scope", scope.as_str()));
+        }
+        if let Some(audience) = auth.audience.as_deref() {
+            form.push(("audience", audience));
+        }
+
+        let mut request = self
+            .client
+            .post(endpoint)
+            .header(reqwest::header::ACCEPT, "application/json");
+        match auth.client_auth {
+            // RFC 6749 section 2.3.1: both parts are form-encoded first.
+            ClientAuthMethod::Basic => {
+                request =
+                    request.basic_auth(form_encode(client_id), Some(form_encode(client_secret)));
+            }
+            ClientAuthMethod::Post => {
+                form.push(("client_id", client_id));
+                form.push(("client_secret", client_secret));
+            }
+        }
+
+        let response = request.form(&form).send().await.map_err(|e| {
+            Error::Config(format!(
+                "OAuth client credentials request to '{endpoint}' failed: {e}"
+            ))
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            // The OAuth `error` code only; the description may echo input.
+            let code = response
+                .json::<Value>()
+                .await
+                .ok()
+                .and_then(|body| body.get("error")?.as_str().map(str::to_string));
+            return Err(Error::Config(match code {
+                Some(code) => format!(
+                    "OAuth client credentials grant at '{endpoint}' failed: HTTP {status} ({code})"
+                ),
+                None => {
+                    format!("OAuth client credentials grant at '{endpoint}' failed: HTTP {status}")
+                }
+            }));
+        }
+
+        let resp: RefreshTokenResponse = response.json().await.map_err(|e| {
+            Error::Config(format!(
+                "Failed to parse OAuth client credentials response from '{endpoint}': {e}"
+            ))
+        })?;
+        Ok(TokenInfo {
+            token_endpoint: Some(endpoint.to_string()),
+            client_id: Some(client_id.to_string()),
+            ..TokenInfo::from_response(
+                resp.access_token,
+                resp.token_type,
+                None,
+                Some(
+                    resp.expires_in
+                        .unwrap_or(DEFAULT_CLIENT_TOKEN_LIFETIME_SECS),
+                ),
+                resp.scope,
+            )
+        })
+    }
+
     /// Perform the OAuth refresh-token grant and persist the refreshed token.
     ///
     /// `client_id` is forwarded when present (required by Google and other providers).
@@ -319,6 +505,38 @@ impl std::fmt::Debug for RefreshTokenResponse {
     }
 }
 
+/// Whether `value` names a secret source rather than being a literal.
+fn is_credential_reference(value: &str) -> bool {
+    [
+        "env:",
+        "keychain:",
+        "file:",
+        "vault:",
+        "aws-secrets:",
+        "aws-ssm:",
+        "op:",
+        "bw:",
+        "secret:",
+        "{env.",
+    ]
+    .iter()
+    .any(|prefix| value.starts_with(prefix))
+}
+
+/// Cache key of a client credentials token. Distinct scopes or audiences
+/// get distinct tokens.
+fn client_token_cache_key(endpoint: &str, client_id: &str, auth: &AuthConfig) -> String {
+    format!(
+        "client_credentials|{endpoint}|{client_id}|{}|{}",
+        auth.scopes.join(" "),
+        auth.audience.as_deref().unwrap_or_default()
+    )
+}
+
+fn form_encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
 fn expand_home_dir(path: &str) -> Result<std::path::PathBuf> {
     if let Some(rest) = path.strip_prefix("~/") {
         match dirs::home_dir() {
@@ -368,35 +586,22 @@ mod tests {
     use std::sync::Arc;
     use std::time::{SystemTime, UNIX_EPOCH};
 
-    use dashmap::DashMap;
-    use parking_lot::RwLock;
     use tempfile::tempdir;
 
-    use crate::capability::response_cache::ResponseCache;
+    use crate::capability::{AuthConfig, OAUTH2_CLIENT_CREDENTIALS};
     use crate::oauth::{TokenInfo, TokenStorage};
-    use crate::secrets::SecretResolver;
 
     use super::super::CapabilityExecutor;
+    use super::{client_token_cache_key, is_credential_reference};
 
     fn executor_with_storage(storage: Arc<TokenStorage>) -> CapabilityExecutor {
-        CapabilityExecutor {
-            client: reqwest::Client::new(),
-            cache: ResponseCache::new(),
-            token_storage: Some(storage),
-            oauth_tokens: RwLock::new(DashMap::new()),
-            secret_resolver: Arc::new(SecretResolver::new()),
-            health: crate::failsafe::HealthTracker::new("test"),
-        }
+        CapabilityExecutor::with_token_storage(storage)
     }
 
     fn executor_no_storage() -> CapabilityExecutor {
         CapabilityExecutor {
-            client: reqwest::Client::new(),
-            cache: ResponseCache::new(),
             token_storage: None,
-            oauth_tokens: RwLock::new(DashMap::new()),
-            secret_resolver: Arc::new(SecretResolver::new()),
-            health: crate::failsafe::HealthTracker::new("test"),
+            ..CapabilityExecutor::new()
         }
     }
 
@@ -493,6 +698,143 @@ mod tests {
         );
     }
 
+    /// Token endpoint issuing `token-<n>` to client `app:s3cret` (HTTP
+    /// Basic), counting grants in `hits`.
+    async fn spawn_token_endpoint(
+        hits: Arc<std::sync::atomic::AtomicU32>,
+        expires_in: u64,
+    ) -> std::net::SocketAddr {
+        use axum::extract::{Form, State};
+        use axum::http::{HeaderMap, StatusCode};
+        use std::collections::HashMap;
+        use std::sync::atomic::Ordering;
+
+        let handler = move |State(hits): State<Arc<std::sync::atomic::AtomicU32>>,
+                            headers: HeaderMap,
+                            Form(form): Form<HashMap<String, String>>| async move {
+            // base64("app:s3cret")
+            let authorized = headers.get("authorization").and_then(|v| v.to_str().ok())
+                == Some("Basic YXBwOnMzY3JldA==");
+            if !authorized
+                || form.get("grant_type").map(String::as_str) != Some("client_credentials")
+            {
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    axum::Json(serde_json::json!({"error": "invalid_client"})),
+                );
+            }
+            let n = hits.fetch_add(1, Ordering::SeqCst) + 1;
+            (
+                StatusCode::OK,
+                axum::Json(serde_json::json!({
+                    "access_token": format!("token-{n}"),
+                    "token_type": "Bearer",
+                    "expires_in": expires_in,
+                    "scope": form.get("scope"),
+                })),
+            )
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                axum::Router::new()
+                    .route("/token", axum::routing::post(handler))
+                    .with_state(hits),
+            )
+            .await
+            .unwrap();
+        });
+        addr
+    }
+
+    /// Client credentials auth for `addr`, with `secret` in a credential
+    /// file inside the returned directory.
+    fn client_credentials_auth(
+        addr: std::net::SocketAddr,
+        secret: &str,
+    ) -> (tempfile::TempDir, AuthConfig) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("client.json");
+        std::fs::write(&path, serde_json::json!({"secret": secret}).to_string()).unwrap();
+        let auth = AuthConfig {
+            required: true,
+            auth_type: OAUTH2_CLIENT_CREDENTIALS.to_string(),
+            key: format!("file:{}:secret", path.display()),
+            token_endpoint: Some(format!("http://{addr}/token")),
+            client_id: Some("app".to_string()),
+            scopes: vec!["read".to_string(), "write".to_string()],
+            ..AuthConfig::default()
+        };
+        (dir, auth)
+    }
+
+    #[tokio::test]
+    async fn client_credentials_token_is_cached_until_expiry() {
+        // GIVEN: a token endpoint issuing hour-long tokens
+        let hits = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let addr = spawn_token_endpoint(Arc::clone(&hits), 3600).await;
+        let (_dir, auth) = client_credentials_auth(addr, "s3cret");
+        let ex = executor_no_storage();
+
+        // WHEN: fetching the credential twice
+        let first = ex.fetch_credential(&auth).await.unwrap();
+        let second = ex.fetch_credential(&auth).await.unwrap();
+
+        // THEN: one grant serves both
+        assert_eq!((first.as_str(), second.as_str()), ("token-1", "token-1"));
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn client_credentials_token_is_renewed_when_expired() {
+        // GIVEN: tokens that expire within the 60 second margin
+        let hits = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let addr = spawn_token_endpoint(Arc::clone(&hits), 30).await;
+        let (_dir, auth) = client_credentials_auth(addr, "s3cret");
+        let ex = executor_no_storage();
+
+        // WHEN / THEN: each fetch performs a new grant
+        assert_eq!(ex.fetch_credential(&auth).await.unwrap(), "token-1");
+        assert_eq!(ex.fetch_credential(&auth).await.unwrap(), "token-2");
+    }
+
+    #[tokio::test]
+    async fn client_credentials_rejection_reports_oauth_error_code() {
+        let hits = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let addr = spawn_token_endpoint(hits, 3600).await;
+        let (_dir, auth) = client_credentials_auth(addr, "wrong");
+
+        let err = executor_no_storage()
+            .fetch_credential(&auth)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("401"), "{err}");
+        assert!(err.contains("invalid_client"), "{err}");
+        assert!(!err.contains("wrong"), "{err}");
+    }
+
+    #[test]
+    fn client_credentials_cache_key_separates_scopes() {
+        let auth = AuthConfig {
+            scopes: vec!["read".to_string()],
+            ..AuthConfig::default()
+        };
+        let wider = AuthConfig {
+            scopes: vec!["read".to_string(), "write".to_string()],
+            ..AuthConfig::default()
+        };
+        assert_ne!(
+            client_token_cache_key("https://idp/token", "app", &auth),
+            client_token_cache_key("https://idp/token", "app", &wider)
+        );
+        assert!(is_credential_reference("env:CLIENT_ID"));
+        assert!(!is_credential_reference("my-client-id"));
+    }
+
     #[test]
     fn env_var_name_detection() {
         assert!(CapabilityExecutor::looks_like_env_var_name("API_KEY"));
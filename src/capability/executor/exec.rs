@@ -0,0 +1,338 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Local command executor
+//!
+//! Implements [`ProtocolExecutor`] for `service: exec` capabilities: a fixed
+//! program run directly (never through a shell) with argument templates
+//! filled from the caller's arguments. Each placeholder expands inside one
+//! argv element, so argument values cannot add arguments or shell syntax; a
+//! value that begins an element may not start with `-`, so it cannot be
+//! read as an option either.
+//!
+//! The child starts with a cleared environment (`PATH` plus the configured
+//! `env`), no stdin, and is killed when the provider `timeout` elapses.
+//! Stdout and stderr are each capped at `max_output_bytes`; a command that
+//! writes more is killed and its result marked truncated.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt as _};
+use tracing::debug;
+
+use super::CapabilityExecutor;
+use super::rest::{ExecutionContext, ProtocolExecutor};
+use crate::capability::definition::{ExecConfig, ProtocolConfig};
+use crate::capability::validate_personal_capability_identity;
+use crate::{Error, Result};
+
+/// Characters of stderr quoted in the error for a failed command.
+const STDERR_IN_ERROR: usize = 500;
+
+/// Output of a finished (or cut short) command.
+#[derive(Debug)]
+struct CommandOutput {
+    exit_code: Option<i32>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    truncated: bool,
+}
+
+/// Local command executor.
+pub struct ExecExecutor<'a> {
+    /// Shared reference to the parent executor (owns the secret resolver).
+    pub(super) executor: &'a CapabilityExecutor,
+}
+
+impl ExecExecutor<'_> {
+    /// Fill the argument templates (`command[1..]`) from caller arguments,
+    /// falling back to `config.defaults`.
+    pub(crate) fn build_args(config: &ExecConfig, params: &Value) -> Result<Vec<String>> {
+        config
+            .command
+            .iter()
+            .skip(1)
+            .map(|template| fill_template(template, config, params))
+            .collect()
+    }
+
+    /// Build the result from the command output.
+    fn result(config: &ExecConfig, output: &CommandOutput) -> Result<Value> {
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if !output.truncated && output.exit_code != Some(0) {
+            let tail: String = stderr.trim().chars().take(STDERR_IN_ERROR).collect();
+            return Err(Error::Protocol(format!(
+                "Command exited with status {}: {tail}",
+                output
+                    .exit_code
+                    .map_or_else(|| "signal".to_string(), |c| c.to_string())
+            )));
+        }
+        if config.response_format == "json" && !output.truncated {
+            return serde_json::from_str(&stdout)
+                .map_err(|e| Error::Protocol(format!("Command output is not JSON: {e}")));
+        }
+        Ok(serde_json::json!({
+            "exit_code": output.exit_code,
+            "stdout": stdout,
+            "stderr": stderr,
+            "truncated": output.truncated,
+        }))
+    }
+
+    /// Spawn the command and collect its capped output.
+    async fn run(
+        &self,
+        config: &ExecConfig,
+        args: Vec<String>,
+        timeout: Duration,
+    ) -> Result<CommandOutput> {
+        let program = &config.command[0];
+        let mut command = tokio::process::Command::new(program);
+        command
+            .args(args)
+            .env_clear()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(path) = std::env::var_os("PATH") {
+            command.env("PATH", path);
+        }
+        for (key, template) in &config.env {
+            // Secrets resolve here; caller arguments never reach the env.
+            let value = self.executor.substitute_string(template, &Value::Null)?;
+            command.env(key, value);
+        }
+        if let Some(dir) = &config.working_dir {
+            command.current_dir(crate::config_reload::expand_tilde(dir));
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| Error::Transport(format!("Failed to start '{program}': {e}")))?;
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let cap = config.max_output_bytes;
+
+        let collect = async {
+            let ((stdout, out_truncated), (stderr, err_truncated)) =
+                tokio::try_join!(read_capped(stdout, cap), read_capped(stderr, cap))?;
+            let truncated = out_truncated || err_truncated;
+            let exit_code = if truncated {
+                child.start_kill()?;
+                None
+            } else {
+                child.wait().await?.code()
+            };
+            Ok::<_, std::io::Error>(CommandOutput {
+                exit_code,
+                stdout,
+                stderr,
+                truncated,
+            })
+        };
+        match tokio::time::timeout(timeout, collect).await {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(e)) => Err(Error::Transport(format!(
+                "Failed to read output of '{program}': {e}"
+            ))),
+            Err(_) => Err(Error::Transport(format!(
+                "Command '{program}' timed out after {}s",
+                timeout.as_secs()
+            ))),
+        }
+    }
+}
+
+/// Replace `{name}` placeholders in one argument template.
+fn fill_template(template: &str, config: &ExecConfig, params: &Value) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        let value = params
+            .get(name)
+            .or_else(|| config.defaults.get(name))
+            .ok_or_else(|| Error::Config(format!("Command argument '{name}' is required")))?;
+        let text = match value {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        if text.contains('\0') {
+            return Err(Error::Config(format!(
+                "Command argument '{name}' contains a NUL byte"
+            )));
+        }
+        if out.is_empty() && start == 0 && text.starts_with('-') {
+            return Err(Error::Config(format!(
+                "Command argument '{name}' may not start with '-'"
+            )));
+        }
+        out.push_str(&rest[..start]);
+        out.push_str(&text);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Read `reader` to the end, keeping at most `cap` bytes; `true` when more
+/// were available.
+async fn read_capped<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    cap: usize,
+) -> std::io::Result<(Vec<u8>, bool)> {
+    let Some(reader) = reader else {
+        return Ok((Vec::new(), false));
+    };
+    let mut buf = Vec::new();
+    reader
+        .take(u64::try_from(cap).unwrap_or(u64::MAX).saturating_add(1))
+        .read_to_end(&mut buf)
+        .await?;
+    let truncated = buf.len() > cap;
+    buf.truncate(cap);
+    Ok((buf, truncated))
+}
+
+#[async_trait]
+impl ProtocolExecutor for ExecExecutor<'_> {
+    fn protocol_name(&self) -> &'static str {
+        "exec"
+    }
+
+    async fn execute(
+        &self,
+        config: &ProtocolConfig,
+        params: Value,
+        ctx: &ExecutionContext<'_>,
+    ) -> Result<Value> {
+        let exec_config = config
+            .as_exec()
+            .ok_or_else(|| Error::Internal("ExecExecutor received non-exec config".to_string()))?;
+        validate_personal_capability_identity(ctx.capability, &ctx.context)?;
+        exec_config.check_runnable().map_err(Error::Config)?;
+        let args = Self::build_args(exec_config, &params)?;
+
+        debug!(
+            capability = %ctx.capability.name,
+            program = %exec_config.command[0],
+            "Executing command"
+        );
+        let output = self
+            .run(exec_config, args, Duration::from_secs(ctx.timeout_secs))
+            .await?;
+        let body = Self::result(exec_config, &output)?;
+        match &exec_config.response_path {
+            Some(path) => self.executor.extract_path(&body, path),
+            None => Ok(body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(command: &[&str]) -> ExecConfig {
+        ExecConfig {
+            command: command.iter().map(ToString::to_string).collect(),
+            max_output_bytes: 16,
+            dangerous: true,
+            ..ExecConfig::default()
+        }
+    }
+
+    #[test]
+    fn build_args_substitutes_within_each_element() {
+        // GIVEN: templates with whole and embedded placeholders, one defaulted
+        let mut config = config(&["git", "log", "-n", "{count}", "--author={who}"]);
+        config.defaults.insert("count".to_string(), json!(5));
+
+        // WHEN: the caller supplies a value with spaces and shell syntax
+        let args = ExecExecutor::build_args(&config, &json!({"who": "a b; rm -rf ~"})).unwrap();
+
+        // THEN: every value stays inside its own argument
+        assert_eq!(args, vec!["log", "-n", "5", "--author=a b; rm -rf ~"]);
+    }
+
+    #[test]
+    fn build_args_rejects_option_like_and_missing_values() {
+        let config = config(&["ls", "{path}"]);
+        let err = ExecExecutor::build_args(&config, &json!({"path": "--help"})).unwrap_err();
+        assert!(err.to_string().contains("may not start with '-'"));
+
+        let err = ExecExecutor::build_args(&config, &json!({})).unwrap_err();
+        assert!(err.to_string().contains("'path' is required"));
+    }
+
+    #[test]
+    fn build_args_rejects_option_like_values_at_the_start_of_an_element() {
+        // A value that begins a longer element is just as option-like.
+        let prefixed = config(&["ls", "{path}.txt"]);
+        let err = ExecExecutor::build_args(&prefixed, &json!({"path": "-rf"})).unwrap_err();
+        assert!(err.to_string().contains("may not start with '-'"));
+        let joined = config(&["ls", "{dir}{path}"]);
+        let err =
+            ExecExecutor::build_args(&joined, &json!({"dir": "", "path": "-rf"})).unwrap_err();
+        assert!(err.to_string().contains("may not start with '-'"));
+    }
+
+    #[test]
+    fn check_runnable_requires_dangerous_and_fixed_program() {
+        let mut config = config(&["{tool}", "x"]);
+        assert!(config.check_runnable().unwrap_err().contains("fixed"));
+        config.command[0] = "echo".to_string();
+        assert!(config.check_runnable().is_ok());
+        config.dangerous = false;
+        assert!(config.check_runnable().unwrap_err().contains("dangerous"));
+    }
+
+    #[test]
+    fn result_reports_failures_and_parses_json() {
+        let config = config(&["true"]);
+        let failed = CommandOutput {
+            exit_code: Some(2),
+            stdout: Vec::new(),
+            stderr: b"no such file\n".to_vec(),
+            truncated: false,
+        };
+        let err = ExecExecutor::result(&config, &failed).unwrap_err();
+        assert!(err.to_string().contains("status 2: no such file"));
+
+        let json_config = ExecConfig {
+            response_format: "json".to_string(),
+            ..config
+        };
+        let ok = CommandOutput {
+            exit_code: Some(0),
+            stdout: br#"{"ok": true}"#.to_vec(),
+            stderr: Vec::new(),
+            truncated: false,
+        };
+        assert_eq!(
+            ExecExecutor::result(&json_config, &ok).unwrap(),
+            json!({"ok": true})
+        );
+    }
+
+    #[tokio::test]
+    async fn read_capped_marks_truncation() {
+        let (buf, truncated) = read_capped(Some(&b"0123456789"[..]), 4).await.unwrap();
+        assert_eq!(buf, b"0123");
+        assert!(truncated);
+
+        let (buf, truncated) = read_capped(Some(&b"0123"[..]), 4).await.unwrap();
+        assert_eq!(buf, b"0123");
+        assert!(!truncated);
+    }
+}
@@ -0,0 +1,241 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Provider selection, failover and per-provider health
+//!
+//! A call tries a capability's providers one after another until one
+//! succeeds or fails with an error that a repeated attempt could not fix
+//! (see [`is_retryable`]); a mutating call that may already have reached
+//! its provider is therefore never repeated against the next. The order
+//! is [`ProvidersConfig::ordered`], except that:
+//!
+//! - under `strategy: weighted` the first provider is drawn at random in
+//!   proportion to `weight`;
+//! - a provider marked unhealthy (three consecutive failures) moves to the
+//!   end until [`PROVIDER_RETRY_AFTER`] has passed since its last failure,
+//!   when it is tried in its normal place again.
+//!
+//! [`ProvidersConfig::ordered`]: crate::capability::ProvidersConfig::ordered
+//! [`is_retryable`]: super::resilience::is_retryable
+
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use rand::RngExt;
+use serde_json::Value;
+
+use super::CapabilityExecutor;
+use super::resilience::{ProviderFailure, is_idempotent, is_retryable};
+use crate::capability::{
+    CapabilityDefinition, CapabilityExecutionContext, ProtocolConfig, ProviderConfig,
+    ProviderStrategy,
+};
+use crate::failsafe::HealthTracker;
+use crate::{Error, Result};
+
+/// How long an unhealthy provider is passed over before it is tried again.
+pub(super) const PROVIDER_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Health of each `capability/provider` pair.
+#[derive(Default)]
+pub(super) struct ProviderHealth {
+    trackers: DashMap<String, Arc<HealthTracker>>,
+}
+
+impl ProviderHealth {
+    /// Tracker of provider `provider` of `capability`.
+    pub(super) fn tracker(&self, capability: &str, provider: &str) -> Arc<HealthTracker> {
+        let key = format!("{capability}/{provider}");
+        Arc::clone(
+            self.trackers
+                .entry(key.clone())
+                .or_insert_with(|| Arc::new(HealthTracker::new(&key)))
+                .value(),
+        )
+    }
+
+    /// Whether the provider should be tried in its normal place.
+    fn available(&self, capability: &str, provider: &str) -> bool {
+        let key = format!("{capability}/{provider}");
+        let Some(tracker) = self.trackers.get(&key) else {
+            return true;
+        };
+        if tracker.is_healthy() {
+            return true;
+        }
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let since_failure = now_ms.saturating_sub(u128::from(tracker.metrics().last_failure_ms));
+        since_failure >= PROVIDER_RETRY_AFTER.as_millis()
+    }
+
+    /// `capability/provider` keys currently marked unhealthy, sorted.
+    pub(super) fn unhealthy(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .trackers
+            .iter()
+            .filter(|entry| !entry.value().is_healthy())
+            .map(|entry| entry.key().clone())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Providers of `capability` in the order a call tries them.
+    pub(super) fn candidates<'a>(
+        &self,
+        capability: &'a CapabilityDefinition,
+    ) -> Vec<(String, &'a ProviderConfig)> {
+        let ordered = capability.providers.ordered();
+        let (mut candidates, passed_over): (Vec<_>, Vec<_>) = ordered
+            .into_iter()
+            .partition(|(name, _)| self.available(&capability.name, name));
+
+        if capability.providers.strategy == ProviderStrategy::Weighted {
+            let weights: Vec<u32> = candidates.iter().map(|(_, p)| p.weight).collect();
+            let total: u64 = weights.iter().copied().map(u64::from).sum();
+            if total > 0 {
+                let roll = rand::rng().random_range(0..total);
+                if let Some(idx) = weighted_pick(&weights, roll) {
+                    let picked = candidates.remove(idx);
+                    candidates.insert(0, picked);
+                }
+            }
+        }
+        candidates.extend(passed_over);
+        candidates
+    }
+}
+
+impl CapabilityExecutor {
+    /// Call `candidates` in turn until one answers; a retryable failure
+    /// moves on to the next, any other ends the call. Returns the answering
+    /// provider's name, protocol and response, or the last failure.
+    pub(super) async fn call_providers(
+        &self,
+        capability: &CapabilityDefinition,
+        candidates: Vec<(String, &ProviderConfig)>,
+        params: &Value,
+        context: &CapabilityExecutionContext,
+    ) -> Result<(String, ProtocolConfig, Value)> {
+        let mut last_error = None;
+        for (name, provider) in candidates {
+            let protocol_config = provider.protocol_config();
+            let tracker = self.providers.tracker(&capability.name, &name);
+            let started = Instant::now();
+            match self
+                .call_provider(capability, provider, &protocol_config, params, context)
+                .await
+            {
+                Ok(response) => {
+                    tracker.record_success(started.elapsed());
+                    return Ok((name, protocol_config, response));
+                }
+                Err(ProviderFailure::RateLimited(e)) => return Err(e),
+                Err(ProviderFailure::Failed(e)) => {
+                    tracker.record_failure();
+                    tracing::warn!(provider = %name, error = %e, "Capability provider failed");
+                    if !is_retryable(&e, is_idempotent(capability, &protocol_config)) {
+                        return Err(e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| Error::Config("No provider configured".to_string())))
+    }
+}
+
+/// Index whose cumulative weight range contains `roll` (in
+/// `0..sum(weights)`).
+fn weighted_pick(weights: &[u32], mut roll: u64) -> Option<usize> {
+    for (idx, weight) in weights.iter().copied().map(u64::from).enumerate() {
+        if roll < weight {
+            return Some(idx);
+        }
+        roll -= weight;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capability(yaml: &str) -> CapabilityDefinition {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    const TWO_PROVIDERS: &str = r"
+name: search
+providers:
+  backup:
+    config: {base_url: 'https://b.example.com'}
+  primary:
+    config: {base_url: 'https://a.example.com'}
+  fallback:
+    - config: {base_url: 'https://c.example.com'}
+";
+
+    fn names(candidates: &[(String, &ProviderConfig)]) -> Vec<String> {
+        candidates.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    #[test]
+    fn candidates_follow_failover_order() {
+        let cap = capability(TWO_PROVIDERS);
+        let health = ProviderHealth::default();
+        assert_eq!(
+            names(&health.candidates(&cap)),
+            vec!["primary", "backup", "fallback[0]"]
+        );
+    }
+
+    #[test]
+    fn unhealthy_provider_moves_to_the_end() {
+        // GIVEN: three consecutive failures of the primary provider
+        let cap = capability(TWO_PROVIDERS);
+        let health = ProviderHealth::default();
+        for _ in 0..3 {
+            health.tracker("search", "primary").record_failure();
+        }
+
+        // WHEN: ordering the providers for the next call
+        let candidates = health.candidates(&cap);
+
+        // THEN: the primary is only tried last, and is reported unhealthy
+        assert_eq!(names(&candidates), vec!["backup", "fallback[0]", "primary"]);
+        assert_eq!(health.unhealthy(), vec!["search/primary"]);
+    }
+
+    #[test]
+    fn weighted_pick_maps_rolls_to_weight_ranges() {
+        let weights = [3, 0, 1];
+        assert_eq!(weighted_pick(&weights, 0), Some(0));
+        assert_eq!(weighted_pick(&weights, 2), Some(0));
+        assert_eq!(weighted_pick(&weights, 3), Some(2));
+        assert_eq!(weighted_pick(&weights, 4), None);
+    }
+
+    #[test]
+    fn weighted_strategy_never_starts_with_a_zero_weight_provider() {
+        let cap = capability(
+            r"
+name: search
+providers:
+  strategy: weighted
+  primary:
+    weight: 0
+    config: {base_url: 'https://a.example.com'}
+  backup:
+    config: {base_url: 'https://b.example.com'}
+",
+        );
+        let health = ProviderHealth::default();
+        for _ in 0..20 {
+            assert_eq!(names(&health.candidates(&cap)), vec!["backup", "primary"]);
+        }
+    }
+}
@@ -4,10 +4,12 @@
 //!
 //! Implements [`ProtocolExecutor`] for GraphQL APIs — sends a
 //! `{ query, variables }` JSON body as a POST request to the configured
-//! endpoint.
+//! endpoint. Query and mutation templates take their variables from the MCP
+//! arguments and may expose a caller-selectable `{selection}` of fields.
 
 use async_trait::async_trait;
 use serde_json::Value;
+use std::collections::HashSet;
 use std::time::Duration;
 
 use super::CapabilityExecutor;
@@ -29,21 +31,32 @@ pub struct GraphqlExecutor<'a> {
     pub(super) executor: &'a CapabilityExecutor,
 }
 
+/// Placeholder in a query template replaced by the selected fields.
+const SELECTION_PLACEHOLDER: &str = "{selection}";
+
+/// Caller arguments that steer the request instead of becoming variables.
+const RESERVED_PARAMS: &[&str] = &["query", "variables", "fields"];
+
 impl GraphqlExecutor<'_> {
     /// Build the JSON body for a GraphQL request.
     ///
     /// # Body structure
     ///
     /// ```json
-    /// { "query": "...", "variables": { ... } }
+    /// { "query": "...", "variables": { ... }, "operationName": "..." }
     /// ```
     ///
     /// The `query` comes from:
     /// 1. `params.query` (caller override), or
     /// 2. `config.query` (default from capability definition)
     ///
-    /// Variables are merged: `config.variables` as base, then
-    /// `params.variables` (or all remaining params) overlaid on top.
+    /// `{selection}` is replaced by the configured fields (or the subset the
+    /// caller lists in `fields`), then `{param}` placeholders by caller
+    /// parameters.
+    ///
+    /// Variables are merged: `config.variables` as base, then caller
+    /// parameters named like a variable the query declares (after
+    /// `config.variable_map` renames), then `params.variables` on top.
     pub(crate) fn build_body(
         config: &crate::capability::GraphqlConfig,
         params: &Value,
@@ -51,7 +64,7 @@ impl GraphqlExecutor<'_> {
         let params_obj = params.as_object();
 
         // 1. Resolve query string
-        let query = params_obj
+        let mut query = params_obj
             .and_then(|m| m.get("query"))
             .and_then(Value::as_str)
             .map(ToString::to_string)
@@ -63,30 +76,33 @@ impl GraphqlExecutor<'_> {
                 )
             })?;
 
+        // Substitute the field selection before caller placeholders, so a
+        // caller parameter named `selection` cannot widen it.
+        if query.contains(SELECTION_PLACEHOLDER) {
+            let fields = Self::selection(config, params_obj.and_then(|m| m.get("fields")))?;
+            query = query.replace(SELECTION_PLACEHOLDER, &fields);
+        }
+
         // Substitute {param} placeholders in the query template
-        let query = if let Some(obj) = params_obj {
-            let mut q = query;
+        if let Some(obj) = params_obj {
             for (key, value) in obj {
-                if key == "query" || key == "variables" {
+                if RESERVED_PARAMS.contains(&key.as_str()) {
                     continue;
                 }
                 let placeholder = format!("{{{key}}}");
-                if q.contains(&placeholder) {
+                if query.contains(&placeholder) {
                     let value_str = match value {
                         Value::String(s) => s.clone(),
                         Value::Number(n) => n.to_string(),
                         Value::Bool(b) => b.to_string(),
                         _ => serde_json::to_string(value).unwrap_or_default(),
                     };
-                    q = q.replace(&placeholder, &value_str);
+                    query = query.replace(&placeholder, &value_str);
                 }
             }
-            q
-        } else {
-            query
-        };
+        }
 
-        // 2. Merge variables: config defaults + caller overrides
+        // 2. Merge variables: config defaults + mapped arguments + caller overrides
         let mut variables = serde_json::Map::new();
 
         // Base: config.variables
@@ -94,6 +110,20 @@ impl GraphqlExecutor<'_> {
             variables.insert(k.clone(), v.clone());
         }
 
+        // Arguments feeding a declared `$variable`
+        if let Some(obj) = params_obj {
+            let declared = declared_variables(&query);
+            for (key, value) in obj {
+                if RESERVED_PARAMS.contains(&key.as_str()) || value.is_null() {
+                    continue;
+                }
+                let name = config.variable_map.get(key).unwrap_or(key);
+                if declared.contains(name.as_str()) {
+                    variables.insert(name.clone(), value.clone());
+                }
+            }
+        }
+
         // Overlay: caller-supplied variables
         if let Some(caller_vars) = params_obj
             .and_then(|m| m.get("variables"))
@@ -109,10 +139,67 @@ impl GraphqlExecutor<'_> {
         if !variables.is_empty() {
             body.insert("variables".to_string(), Value::Object(variables));
         }
+        if let Some(operation) = &config.operation_name {
+            body.insert(
+                "operationName".to_string(),
+                Value::String(operation.clone()),
+            );
+        }
 
         Ok(Value::Object(body))
     }
 
+    /// Fields to substitute for `{selection}`: the configured selection, or
+    /// the subset of it named by the caller's `fields` (an array, or a
+    /// comma-separated string).
+    fn selection(
+        config: &crate::capability::GraphqlConfig,
+        requested: Option<&Value>,
+    ) -> Result<String> {
+        if config.selection.is_empty() {
+            return Err(Error::Config(
+                "GraphQL query uses {selection} but the capability configures no 'selection'"
+                    .to_string(),
+            ));
+        }
+        let requested: Vec<&str> = match requested {
+            None | Some(Value::Null) => return Ok(config.selection.join(" ")),
+            Some(Value::String(list)) => list.split(',').map(str::trim).collect(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| {
+                    item.as_str().ok_or_else(|| {
+                        Error::Config("GraphQL 'fields' must list field names".to_string())
+                    })
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => {
+                return Err(Error::Config(
+                    "GraphQL 'fields' must be an array of field names".to_string(),
+                ));
+            }
+        };
+
+        let mut fields = Vec::with_capacity(requested.len());
+        for field in requested.into_iter().filter(|f| !f.is_empty()) {
+            if !config.selection.iter().any(|allowed| allowed == field) {
+                return Err(Error::Config(format!(
+                    "GraphQL field '{field}' is not selectable; available: {}",
+                    config.selection.join(", ")
+                )));
+            }
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+        if fields.is_empty() {
+            return Err(Error::Config(
+                "GraphQL 'fields' must name at least one field".to_string(),
+            ));
+        }
+        Ok(fields.join(" "))
+    }
+
     /// Parse a GraphQL response, checking for errors.
     ///
     /// A GraphQL response always has the shape:
@@ -151,6 +238,23 @@ impl GraphqlExecutor<'_> {
     }
 }
 
+/// Names of the `$variables` used in a GraphQL document.
+fn declared_variables(query: &str) -> HashSet<&str> {
+    let mut names = HashSet::new();
+    let mut rest = query;
+    while let Some(pos) = rest.find('$') {
+        rest = &rest[pos + 1..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if end > 0 {
+            names.insert(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+    names
+}
+
 /// Extract a value at a dot-separated path from a JSON value.
 fn extract_path(value: &Value, path: &str) -> Value {
     let mut current = value;
@@ -238,18 +342,19 @@ impl ProtocolExecutor for GraphqlExecutor<'_> {
         );
 
         let timeout = Duration::from_secs(ctx.timeout_secs);
-        let response = super::send_with_retry(
-            self.executor
-                .client
-                .post(&graphql_config.endpoint)
-                .headers(headers)
-                .json(&body)
-                .timeout(timeout),
-            "GraphQL request",
-            false,
-            &self.executor.health,
-        )
-        .await?;
+        let response = self
+            .executor
+            .send(
+                self.executor
+                    .client
+                    .post(&graphql_config.endpoint)
+                    .headers(headers)
+                    .json(&body)
+                    .timeout(timeout),
+                "GraphQL request",
+                false,
+            )
+            .await?;
 
         let status = response.status();
         if !status.is_success() {
@@ -300,6 +405,7 @@ mod tests {
                 v
             },
             response_path: Some("data.viewer".to_string()),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -326,6 +432,7 @@ mod tests {
             query: Some("{ users { id name } }".to_string()),
             variables: HashMap::new(),
             response_path: None,
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&config).unwrap();
@@ -438,6 +545,93 @@ mod tests {
         assert!(err.to_string().contains("query not provided"), "{err}");
     }
 
+    #[test]
+    fn build_body_maps_arguments_to_declared_variables() {
+        // GIVEN: a query declaring $first and $owner, with `limit` renamed to `first`
+        let config = GraphqlConfig {
+            query: Some(
+                "query($first: Int!, $owner: String) { repos(first: $first, owner: $owner) { name } }"
+                    .to_string(),
+            ),
+            variable_map: HashMap::from([("limit".to_string(), "first".to_string())]),
+            ..Default::default()
+        };
+        let params = serde_json::json!({"limit": 3, "owner": "octocat", "unrelated": true});
+
+        // WHEN: building the body
+        let body = GraphqlExecutor::build_body(&config, &params).unwrap();
+
+        // THEN: only declared variables are sent
+        assert_eq!(
+            body["variables"],
+            serde_json::json!({"first": 3, "owner": "octocat"})
+        );
+    }
+
+    #[test]
+    fn build_body_sends_operation_name() {
+        let config = GraphqlConfig {
+            query: Some("mutation Star($id: ID!) { addStar(input: {starrableId: $id}) { clientMutationId } }".to_string()),
+            operation_name: Some("Star".to_string()),
+            ..Default::default()
+        };
+
+        let body = GraphqlExecutor::build_body(&config, &serde_json::json!({"id": "R_1"})).unwrap();
+
+        assert_eq!(body["operationName"], "Star");
+        assert_eq!(body["variables"]["id"], "R_1");
+    }
+
+    #[test]
+    fn build_body_substitutes_selection_and_caller_subset() {
+        let config = GraphqlConfig {
+            query: Some("{ viewer { {selection} } }".to_string()),
+            selection: vec!["login".to_string(), "name".to_string(), "bio".to_string()],
+            ..Default::default()
+        };
+
+        let body = GraphqlExecutor::build_body(&config, &serde_json::json!({})).unwrap();
+        assert_eq!(body["query"], "{ viewer { login name bio } }");
+
+        let params = serde_json::json!({"fields": ["bio", "login"], "selection": "secret"});
+        let body = GraphqlExecutor::build_body(&config, &params).unwrap();
+        assert_eq!(body["query"], "{ viewer { bio login } }");
+
+        let body =
+            GraphqlExecutor::build_body(&config, &serde_json::json!({"fields": "name, bio"}))
+                .unwrap();
+        assert_eq!(body["query"], "{ viewer { name bio } }");
+    }
+
+    #[test]
+    fn build_body_rejects_fields_outside_the_selection() {
+        let config = GraphqlConfig {
+            query: Some("{ viewer { {selection} } }".to_string()),
+            selection: vec!["login".to_string()],
+            ..Default::default()
+        };
+
+        let params = serde_json::json!({"fields": ["login", "email"]});
+        let err = GraphqlExecutor::build_body(&config, &params).unwrap_err();
+        assert!(
+            err.to_string().contains("'email' is not selectable"),
+            "{err}"
+        );
+
+        let unconfigured = GraphqlConfig {
+            selection: Vec::new(),
+            ..config
+        };
+        let err = GraphqlExecutor::build_body(&unconfigured, &serde_json::json!({})).unwrap_err();
+        assert!(err.to_string().contains("no 'selection'"), "{err}");
+    }
+
+    #[test]
+    fn declared_variables_finds_each_name() {
+        let names = declared_variables("query($a: Int, $b_2: [ID!]) { x(a: $a, b: $b_2) }");
+        assert_eq!(names, HashSet::from(["a", "b_2"]));
+    }
+
     // ── parse_response ──────────────────────────────────────────────────
 
     #[test]
@@ -0,0 +1,586 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! gRPC protocol executor (`grpc` feature)
+//!
+//! Implements [`ProtocolExecutor`] for unary gRPC methods without generated
+//! code: the method's message types are read from a compiled
+//! `FileDescriptorSet` or fetched with server reflection, the caller's JSON
+//! arguments are converted into a dynamic request message, and the response
+//! message is converted back to JSON.
+//!
+//! Connections are pinned to addresses that passed the SSRF check, like the
+//! HTTP client's `PinningResolver`, so a DNS answer cannot change between
+//! the check and the connect.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use prost::Message as _;
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor, SerializeOptions,
+};
+use serde_json::Value;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::{Code, Status};
+use tonic_reflection::pb::v1::{
+    ServerReflectionRequest, server_reflection_client::ServerReflectionClient,
+    server_reflection_request::MessageRequest, server_reflection_response::MessageResponse,
+};
+use tracing::debug;
+
+use super::CapabilityExecutor;
+use super::rest::{ExecutionContext, ProtocolExecutor};
+use crate::capability::definition::{GrpcConfig, ProtocolConfig};
+use crate::capability::execution_context::validate_capability_url_for_context;
+use crate::capability::validate_personal_capability_identity;
+use crate::security::ssrf::{SystemResolver, resolve_and_validate_host};
+use crate::{Error, Result};
+
+/// Connections and message schemas reused across calls.
+#[derive(Default)]
+pub(crate) struct GrpcClients {
+    /// Channels keyed by endpoint URL.
+    channels: DashMap<String, Channel>,
+    /// Descriptor pools keyed by descriptor-set path and modification time,
+    /// or by `reflection:<endpoint>#<service>`.
+    pools: DashMap<String, DescriptorPool>,
+}
+
+/// gRPC protocol executor.
+pub struct GrpcExecutor<'a> {
+    /// Shared reference to the parent executor (owns credential stores and
+    /// the gRPC connection cache).
+    pub(super) executor: &'a CapabilityExecutor,
+}
+
+impl GrpcExecutor<'_> {
+    /// Split `package.Service/Method` into service and method names.
+    pub(crate) fn parse_method(method: &str) -> Result<(&str, &str)> {
+        method
+            .trim_start_matches('/')
+            .rsplit_once('/')
+            .filter(|(service, name)| !service.is_empty() && !name.is_empty())
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "gRPC method '{method}' must be 'package.Service/Method'"
+                ))
+            })
+    }
+
+    /// Request fields: `default_fields` as base, caller arguments on top.
+    pub(crate) fn build_request_json(config: &GrpcConfig, params: &Value) -> Value {
+        let mut fields: serde_json::Map<String, Value> = config
+            .default_fields
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        if let Some(obj) = params.as_object() {
+            for (k, v) in obj {
+                fields.insert(k.clone(), v.clone());
+            }
+        }
+        Value::Object(fields)
+    }
+
+    /// Convert JSON arguments into a request message of type `desc`.
+    pub(crate) fn encode_request(desc: MessageDescriptor, json: Value) -> Result<DynamicMessage> {
+        let name = desc.full_name().to_string();
+        DynamicMessage::deserialize(desc, json)
+            .map_err(|e| Error::Config(format!("Arguments do not match gRPC message {name}: {e}")))
+    }
+
+    /// Convert a response message to JSON, keeping proto field names.
+    pub(crate) fn decode_response(message: &DynamicMessage) -> Result<Value> {
+        message
+            .serialize_with_options(
+                serde_json::value::Serializer,
+                &SerializeOptions::new().use_proto_field_name(true),
+            )
+            .map_err(|e| Error::Protocol(format!("Failed to convert gRPC response: {e}")))
+    }
+
+    /// Channel to `endpoint`, connected on first use.
+    async fn channel(&self, endpoint: &str, ctx: &ExecutionContext<'_>) -> Result<Channel> {
+        let url = url::Url::parse(endpoint)
+            .map_err(|e| Error::Config(format!("Invalid gRPC endpoint '{endpoint}': {e}")))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::Config(format!("gRPC endpoint '{endpoint}' has no host")))?;
+        let literal_ip = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .ok();
+        // IP literals are checked on every call: whether loopback is allowed
+        // depends on the caller's context.
+        if literal_ip.is_some() {
+            validate_capability_url_for_context(endpoint, &ctx.context)?;
+        }
+        if let Some(channel) = self.executor.grpc.channels.get(endpoint) {
+            return Ok(channel.clone());
+        }
+
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| Error::Config(format!("gRPC endpoint '{endpoint}' needs a port")))?;
+        // Resolve once and connect to the checked address.
+        let ip = match literal_ip {
+            Some(ip) => ip,
+            None => resolve_and_validate_host(host, &SystemResolver).await?[0],
+        };
+        let addr = SocketAddr::new(ip, port);
+
+        let mut builder = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| Error::Config(format!("Invalid gRPC endpoint '{endpoint}': {e}")))?
+            .connect_timeout(Duration::from_secs(10))
+            .tcp_keepalive(Some(Duration::from_secs(30)));
+        if url.scheme() == "https" {
+            builder = builder
+                .tls_config(ClientTlsConfig::new().domain_name(host).with_webpki_roots())
+                .map_err(|e| Error::Config(format!("gRPC TLS setup failed: {e}")))?;
+        }
+
+        let channel = builder
+            .connect_with_connector(tower::service_fn(move |_| async move {
+                let stream = tokio::net::TcpStream::connect(addr).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }))
+            .await
+            .map_err(|e| Error::Transport(format!("gRPC connect to {endpoint} failed: {e}")))?;
+        self.executor
+            .grpc
+            .channels
+            .insert(endpoint.to_string(), channel.clone());
+        Ok(channel)
+    }
+
+    /// Descriptor pool holding `service`: from the descriptor set file when
+    /// configured (reloaded when the file changes), else via reflection.
+    async fn pool(
+        &self,
+        config: &GrpcConfig,
+        service: &str,
+        channel: &Channel,
+    ) -> Result<DescriptorPool> {
+        let Some(path) = &config.descriptor_set else {
+            let key = format!("reflection:{}#{service}", config.endpoint);
+            if let Some(pool) = self.executor.grpc.pools.get(&key) {
+                return Ok(pool.clone());
+            }
+            let pool = reflect_pool(channel.clone(), service).await?;
+            self.executor.grpc.pools.insert(key, pool.clone());
+            return Ok(pool);
+        };
+
+        let path = crate::config_reload::expand_tilde(path);
+        let modified = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map_err(|e| {
+                Error::Config(format!(
+                    "gRPC descriptor set {} is unreadable: {e}",
+                    path.display()
+                ))
+            })?;
+        let since_epoch = modified
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let key = format!("{}@{since_epoch}", path.display());
+        if let Some(pool) = self.executor.grpc.pools.get(&key) {
+            return Ok(pool.clone());
+        }
+        let bytes = std::fs::read(&path).map_err(|e| {
+            Error::Config(format!(
+                "gRPC descriptor set {} is unreadable: {e}",
+                path.display()
+            ))
+        })?;
+        let pool = DescriptorPool::decode(bytes.as_slice()).map_err(|e| {
+            Error::Config(format!(
+                "gRPC descriptor set {} is invalid: {e}",
+                path.display()
+            ))
+        })?;
+        self.executor.grpc.pools.insert(key, pool.clone());
+        Ok(pool)
+    }
+}
+
+/// Look up `service/method` in `pool`; only unary methods are supported.
+pub(crate) fn find_method(
+    pool: &DescriptorPool,
+    service: &str,
+    method: &str,
+) -> Result<MethodDescriptor> {
+    let service_desc = pool
+        .get_service_by_name(service)
+        .ok_or_else(|| Error::Config(format!("gRPC service '{service}' is not described")))?;
+    let method_desc = service_desc
+        .methods()
+        .find(|m| m.name() == method)
+        .ok_or_else(|| {
+            Error::Config(format!("gRPC service '{service}' has no method '{method}'"))
+        })?;
+    if method_desc.is_client_streaming() || method_desc.is_server_streaming() {
+        return Err(Error::Config(format!(
+            "gRPC method '{service}/{method}' is streaming; only unary methods are supported"
+        )));
+    }
+    Ok(method_desc)
+}
+
+/// Fetch the files describing `service` (and their imports) with server
+/// reflection.
+async fn reflect_pool(channel: Channel, service: &str) -> Result<DescriptorPool> {
+    let mut client = ServerReflectionClient::new(channel);
+    let mut files: HashMap<String, prost_types::FileDescriptorProto> = HashMap::new();
+    let mut pending = vec![MessageRequest::FileContainingSymbol(service.to_string())];
+
+    while let Some(request) = pending.pop() {
+        let request = ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(request),
+        };
+        let mut responses = client
+            .server_reflection_info(tokio_stream::once(request))
+            .await
+            .map_err(|s| status_error("reflection", &s))?
+            .into_inner();
+        while let Some(response) = responses
+            .message()
+            .await
+            .map_err(|s| status_error("reflection", &s))?
+        {
+            match response.message_response {
+                Some(MessageResponse::FileDescriptorResponse(found)) => {
+                    for bytes in found.file_descriptor_proto {
+                        let file = prost_types::FileDescriptorProto::decode(bytes.as_slice())
+                            .map_err(|e| {
+                                Error::Protocol(format!("Invalid reflected descriptor: {e}"))
+                            })?;
+                        files.insert(file.name().to_string(), file);
+                    }
+                }
+                Some(MessageResponse::ErrorResponse(e)) => {
+                    return Err(Error::Protocol(format!(
+                        "gRPC reflection failed: {}",
+                        e.error_message
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        // Servers may answer with the file alone; ask for missing imports.
+        for file in files.values() {
+            for dependency in &file.dependency {
+                let requested = pending.iter().any(
+                    |r| matches!(r, MessageRequest::FileByFilename(name) if name == dependency),
+                );
+                if !files.contains_key(dependency) && !requested {
+                    pending.push(MessageRequest::FileByFilename(dependency.clone()));
+                }
+            }
+        }
+    }
+
+    let mut pool = DescriptorPool::new();
+    pool.add_file_descriptor_protos(files.into_values())
+        .map_err(|e| Error::Protocol(format!("Reflected descriptors are invalid: {e}")))?;
+    debug!(service, "Loaded gRPC descriptors via reflection");
+    Ok(pool)
+}
+
+/// Map a gRPC status to a gateway error.
+fn status_error(what: &str, status: &Status) -> Error {
+    let message = format!(
+        "gRPC {what} failed: {:?}: {}",
+        status.code(),
+        status.message()
+    );
+    match status.code() {
+        Code::Unavailable | Code::DeadlineExceeded => Error::Transport(message),
+        _ => Error::Protocol(message),
+    }
+}
+
+/// Codec for dynamic messages whose type is only known at runtime.
+struct DynamicCodec {
+    response: MessageDescriptor,
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder(self.response.clone())
+    }
+}
+
+struct DynamicEncoder;
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(
+        &mut self,
+        item: Self::Item,
+        dst: &mut EncodeBuf<'_>,
+    ) -> std::result::Result<(), Status> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(format!("encode failed: {e}")))
+    }
+}
+
+struct DynamicDecoder(MessageDescriptor);
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(
+        &mut self,
+        src: &mut DecodeBuf<'_>,
+    ) -> std::result::Result<Option<Self::Item>, Status> {
+        DynamicMessage::decode(self.0.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(format!("decode failed: {e}")))
+    }
+}
+
+#[async_trait]
+impl ProtocolExecutor for GrpcExecutor<'_> {
+    fn protocol_name(&self) -> &'static str {
+        "grpc"
+    }
+
+    async fn execute(
+        &self,
+        config: &ProtocolConfig,
+        params: Value,
+        ctx: &ExecutionContext<'_>,
+    ) -> Result<Value> {
+        let grpc_config = config.as_grpc().ok_or_else(|| {
+            Error::Config(format!(
+                "GrpcExecutor received non-gRPC config: {}",
+                config.protocol_name()
+            ))
+        })?;
+        validate_personal_capability_identity(ctx.capability, &ctx.context)?;
+
+        if grpc_config.endpoint.is_empty() {
+            return Err(Error::Config(
+                "gRPC endpoint URL not configured".to_string(),
+            ));
+        }
+        let (service, method) = Self::parse_method(&grpc_config.method)?;
+
+        let channel = self.channel(&grpc_config.endpoint, ctx).await?;
+        let pool = self.pool(grpc_config, service, &channel).await?;
+        let method_desc = find_method(&pool, service, method)?;
+        let message = Self::encode_request(
+            method_desc.input(),
+            Self::build_request_json(grpc_config, &params),
+        )?;
+
+        // Metadata: configured headers, then capability auth.
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value_template) in &grpc_config.headers {
+            let value = self.executor.substitute_string(value_template, &params)?;
+            if let Ok(header_name) = name
+                .to_ascii_lowercase()
+                .parse::<reqwest::header::HeaderName>()
+                && let Ok(header_value) = value.parse::<reqwest::header::HeaderValue>()
+            {
+                headers.insert(header_name, header_value);
+            }
+        }
+        if ctx.capability.auth.required && ctx.capability.auth.param.is_none() {
+            self.executor
+                .inject_auth(&mut headers, &ctx.capability.auth)
+                .await?;
+        }
+
+        let deadline = Duration::from_secs(ctx.timeout_secs);
+        let mut request = tonic::Request::new(message);
+        *request.metadata_mut() = tonic::metadata::MetadataMap::from_headers(headers);
+        request.set_timeout(deadline);
+
+        let path = format!("/{service}/{method}")
+            .parse()
+            .map_err(|e| Error::Config(format!("Invalid gRPC method path: {e}")))?;
+        let mut client = tonic::client::Grpc::new(channel);
+        client
+            .ready()
+            .await
+            .map_err(|e| Error::Transport(format!("gRPC channel not ready: {e}")))?;
+
+        debug!(service, method, "Executing gRPC request");
+        let started = std::time::Instant::now();
+        let outcome = client
+            .unary(
+                request,
+                path,
+                DynamicCodec {
+                    response: method_desc.output(),
+                },
+            )
+            .await;
+        let response = match outcome {
+            Ok(response) => {
+                self.executor.health.record_success(started.elapsed());
+                response.into_inner()
+            }
+            Err(status) => {
+                if matches!(status.code(), Code::Unavailable) {
+                    self.executor.health.record_failure();
+                    self.executor.grpc.channels.remove(&grpc_config.endpoint);
+                } else {
+                    self.executor.health.record_success(started.elapsed());
+                }
+                return Err(status_error("call", &status));
+            }
+        };
+
+        let body = Self::decode_response(&response)?;
+        match &grpc_config.response_path {
+            Some(path) => self.executor.extract_path(&body, path),
+            None => Ok(body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+        MethodDescriptorProto, ServiceDescriptorProto, field_descriptor_proto,
+    };
+
+    fn field(name: &str, number: i32, kind: field_descriptor_proto::Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(kind as i32),
+            label: Some(field_descriptor_proto::Label::Optional as i32),
+            ..Default::default()
+        }
+    }
+
+    /// `shop.v1.OrderService` with unary `GetOrder` and streaming `Watch`.
+    fn order_pool() -> DescriptorPool {
+        let message = |name: &str, fields| DescriptorProto {
+            name: Some(name.to_string()),
+            field: fields,
+            ..Default::default()
+        };
+        let method = |name: &str, streaming: bool| MethodDescriptorProto {
+            name: Some(name.to_string()),
+            input_type: Some(".shop.v1.GetOrderRequest".to_string()),
+            output_type: Some(".shop.v1.Order".to_string()),
+            server_streaming: Some(streaming),
+            ..Default::default()
+        };
+        let file = FileDescriptorProto {
+            name: Some("shop/v1/orders.proto".to_string()),
+            package: Some("shop.v1".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![
+                message(
+                    "GetOrderRequest",
+                    vec![
+                        field("order_id", 1, field_descriptor_proto::Type::String),
+                        field("include_items", 2, field_descriptor_proto::Type::Bool),
+                    ],
+                ),
+                message(
+                    "Order",
+                    vec![
+                        field("order_id", 1, field_descriptor_proto::Type::String),
+                        field("total_cents", 2, field_descriptor_proto::Type::Int64),
+                    ],
+                ),
+            ],
+            service: vec![ServiceDescriptorProto {
+                name: Some("OrderService".to_string()),
+                method: vec![method("GetOrder", false), method("Watch", true)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let set = FileDescriptorSet { file: vec![file] };
+        DescriptorPool::decode(set.encode_to_vec().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn parse_method_splits_service_and_method() {
+        assert_eq!(
+            GrpcExecutor::parse_method("shop.v1.OrderService/GetOrder").unwrap(),
+            ("shop.v1.OrderService", "GetOrder")
+        );
+        assert_eq!(
+            GrpcExecutor::parse_method("/shop.v1.OrderService/GetOrder").unwrap(),
+            ("shop.v1.OrderService", "GetOrder")
+        );
+        assert!(GrpcExecutor::parse_method("GET").is_err());
+        assert!(GrpcExecutor::parse_method("shop.v1.OrderService/").is_err());
+    }
+
+    #[test]
+    fn find_method_rejects_unknown_and_streaming_methods() {
+        let pool = order_pool();
+        assert!(find_method(&pool, "shop.v1.OrderService", "GetOrder").is_ok());
+
+        let err = find_method(&pool, "shop.v1.OrderService", "Watch").unwrap_err();
+        assert!(err.to_string().contains("streaming"), "{err}");
+        let err = find_method(&pool, "shop.v1.OrderService", "Cancel").unwrap_err();
+        assert!(err.to_string().contains("no method 'Cancel'"), "{err}");
+        assert!(find_method(&pool, "shop.v1.Missing", "GetOrder").is_err());
+    }
+
+    #[test]
+    fn request_json_round_trips_through_the_message_type() {
+        // GIVEN: defaults from the capability and caller arguments
+        let pool = order_pool();
+        let method = find_method(&pool, "shop.v1.OrderService", "GetOrder").unwrap();
+        let config = GrpcConfig {
+            default_fields: HashMap::from([("include_items".to_string(), serde_json::json!(true))]),
+            ..Default::default()
+        };
+        let json =
+            GrpcExecutor::build_request_json(&config, &serde_json::json!({"order_id": "o-1"}));
+
+        // WHEN: encoding to protobuf and decoding back
+        let message = GrpcExecutor::encode_request(method.input(), json).unwrap();
+        let bytes = message.encode_to_vec();
+        let decoded = DynamicMessage::decode(method.input(), bytes.as_slice()).unwrap();
+
+        // THEN: both fields survive with their proto names
+        assert_eq!(
+            GrpcExecutor::decode_response(&decoded).unwrap(),
+            serde_json::json!({"order_id": "o-1", "include_items": true})
+        );
+    }
+
+    #[test]
+    fn encode_request_rejects_mismatched_arguments() {
+        let pool = order_pool();
+        let method = find_method(&pool, "shop.v1.OrderService", "GetOrder").unwrap();
+        let err = GrpcExecutor::encode_request(method.input(), serde_json::json!({"order_id": 7}))
+            .unwrap_err();
+        assert!(err.to_string().contains("shop.v1.GetOrderRequest"), "{err}");
+    }
+}
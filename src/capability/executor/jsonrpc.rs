@@ -182,18 +182,19 @@ impl ProtocolExecutor for JsonRpcExecutor<'_> {
         );
 
         let timeout = Duration::from_secs(ctx.timeout_secs);
-        let response = super::send_with_retry(
-            self.executor
-                .client
-                .post(&jsonrpc_config.endpoint)
-                .headers(headers)
-                .json(&body)
-                .timeout(timeout),
-            "JSON-RPC request",
-            false,
-            &self.executor.health,
-        )
-        .await?;
+        let response = self
+            .executor
+            .send(
+                self.executor
+                    .client
+                    .post(&jsonrpc_config.endpoint)
+                    .headers(headers)
+                    .json(&body)
+                    .timeout(timeout),
+                "JSON-RPC request",
+                false,
+            )
+            .await?;
 
         let status = response.status();
         if !status.is_success() {
@@ -481,6 +482,8 @@ mod tests {
             service: "jsonrpc".to_string(),
             cost_per_call: 0.0,
             timeout: 30,
+            priority: None,
+            weight: 1,
             config: RestConfig {
                 endpoint: "http://localhost:8545".to_string(),
                 method: "eth_blockNumber".to_string(),
@@ -509,6 +512,8 @@ mod tests {
             service: "jsonrpc".to_string(),
             cost_per_call: 0.0,
             timeout: 30,
+            priority: None,
+            weight: 1,
             config: RestConfig {
                 base_url: "http://localhost:8080".to_string(),
                 path: "/rpc".to_string(),
@@ -0,0 +1,180 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Mocked upstream HTTP for capability tests
+//!
+//! An executor built with [`CapabilityExecutor::with_mock`] answers every
+//! outbound capability request (REST, GraphQL, JSON-RPC, pagination and
+//! streaming) from a list of [`MockResponse`]s instead of the network, and
+//! records each rendered request so tests can assert on its URL, headers and
+//! body. Used by `mcp-gateway cap test --mock`.
+//!
+//! [`CapabilityExecutor::with_mock`]: super::CapabilityExecutor::with_mock
+
+use std::collections::{BTreeMap, HashMap};
+
+use parking_lot::Mutex;
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Error, Result};
+
+/// A canned upstream response and the requests it answers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MockResponse {
+    /// HTTP method to match (any method when unset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// URL to match; see [`url_matches`].
+    pub url: String,
+    /// Response status code.
+    #[serde(default = "default_status")]
+    pub status: u16,
+    /// Response headers.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Response body: a string is sent as-is, anything else as JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+const fn default_status() -> u16 {
+    200
+}
+
+/// An outbound request as the executor rendered it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecordedRequest {
+    /// HTTP method.
+    pub method: String,
+    /// Full URL, including the query string.
+    pub url: String,
+    /// Request headers; names are lowercase.
+    pub headers: BTreeMap<String, String>,
+    /// Request body: parsed JSON when it is JSON, otherwise text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// Whether `url` matches `pattern`.
+///
+/// A pattern ending in `*` matches every URL starting with the rest of it.
+/// Otherwise the URLs must be equal; a pattern without a query string
+/// ignores the query string of `url`.
+#[must_use]
+pub fn url_matches(pattern: &str, url: &str) -> bool {
+    if let Some(prefix) = pattern.strip_suffix('*') {
+        return url.starts_with(prefix);
+    }
+    if pattern.contains('?') {
+        return pattern == url;
+    }
+    url.split('?').next() == Some(pattern)
+}
+
+impl MockResponse {
+    fn matches(&self, request: &RecordedRequest) -> bool {
+        self.method
+            .as_ref()
+            .is_none_or(|m| m.eq_ignore_ascii_case(&request.method))
+            && url_matches(&self.url, &request.url)
+    }
+
+    fn to_response(&self) -> Result<reqwest::Response> {
+        let mut builder = axum::http::Response::builder().status(self.status);
+        let mut has_content_type = false;
+        for (name, value) in &self.headers {
+            has_content_type |= name.eq_ignore_ascii_case("content-type");
+            builder = builder.header(name, value);
+        }
+        let body = match &self.body {
+            None => Vec::new(),
+            Some(Value::String(text)) => text.clone().into_bytes(),
+            Some(json) => {
+                if !has_content_type {
+                    builder = builder.header("content-type", "application/json");
+                }
+                serde_json::to_vec(json)?
+            }
+        };
+        let response = builder
+            .body(body)
+            .map_err(|e| Error::Config(format!("Invalid mock response for {}: {e}", self.url)))?;
+        Ok(reqwest::Response::from(response))
+    }
+}
+
+/// Mocked upstream: answers requests with the first matching
+/// [`MockResponse`] and records every request.
+#[derive(Debug, Default)]
+pub struct HttpMock {
+    responses: Vec<MockResponse>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl HttpMock {
+    /// Create a mock serving `responses`, tried in order.
+    #[must_use]
+    pub fn new(responses: Vec<MockResponse>) -> Self {
+        Self {
+            responses,
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Requests received so far, in order.
+    #[must_use]
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().clone()
+    }
+
+    /// Record `request` and answer it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error when the request cannot be built or no
+    /// response matches it.
+    pub(super) fn respond(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        let request = request
+            .build()
+            .map_err(|e| Error::Transport(format!("Request failed: {e}")))?;
+        let recorded = record(&request);
+        let matched = self.responses.iter().find(|r| r.matches(&recorded));
+        let response = match matched {
+            Some(mock) => mock.to_response(),
+            None => Err(Error::Transport(format!(
+                "No mock response for {} {}",
+                recorded.method, recorded.url
+            ))),
+        };
+        self.requests.lock().push(recorded);
+        response
+    }
+}
+
+fn record(request: &reqwest::Request) -> RecordedRequest {
+    let headers = request
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect();
+    let body = request
+        .body()
+        .and_then(reqwest::Body::as_bytes)
+        .filter(|bytes| !bytes.is_empty())
+        .map(|bytes| {
+            serde_json::from_slice(bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+        });
+    RecordedRequest {
+        method: request.method().to_string(),
+        url: request.url().to_string(),
+        headers,
+        body,
+    }
+}
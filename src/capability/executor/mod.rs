@@ -18,12 +18,25 @@
 //! - `{env.VAR}` - Template format for environment variables
 
 mod credentials;
+pub mod exec;
+mod failover;
 pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod jsonrpc;
+pub mod mock;
+mod pagination;
 mod params;
+mod resilience;
 pub mod rest;
+mod sigv4;
+#[cfg(feature = "sql")]
+pub mod sql;
+mod stream;
+mod upload;
 mod xml;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -35,10 +48,12 @@ use reqwest::{
 };
 use serde_json::Value;
 
+pub(crate) use sigv4::{AwsCredentialCache, sign_request as sign_aws_request};
+
 use super::response_cache::ResponseCache;
 use super::{
-    CapabilityDefinition, CapabilityExecutionContext, ProviderConfig, RestConfig,
-    validate_capability_url_for_context, validate_personal_capability_identity,
+    AWS_SIGV4, CapabilityDefinition, CapabilityExecutionContext, ProviderConfig, RestConfig,
+    template, validate_capability_url_for_context, validate_personal_capability_identity,
 };
 use crate::oauth::{TokenInfo, TokenStorage};
 use crate::secrets::SecretResolver;
@@ -63,6 +78,25 @@ pub struct CapabilityExecutor {
     /// counts as a live backend. Surfaced via the capability backend in
     /// `/health` (MIK-5080).
     pub(super) health: crate::failsafe::HealthTracker,
+    /// Health of each capability's providers, steering failover order.
+    providers: failover::ProviderHealth,
+    /// Upstream request buckets of capabilities with
+    /// `resilience.rate_limit`.
+    pub(super) upstream_limits: crate::failsafe::KeyedRateLimiter,
+    /// AWS credentials from the container or instance profile endpoint.
+    pub(super) aws_credentials: sigv4::AwsCredentialCache,
+    /// gRPC channels and message schemas.
+    #[cfg(feature = "grpc")]
+    pub(super) grpc: grpc::GrpcClients,
+    /// SQL connection pools.
+    #[cfg(feature = "sql")]
+    pub(super) sql: sql::SqlPools,
+    /// Mocked upstream answering requests instead of the network
+    /// (`cap test --mock`).
+    pub(super) mock: Option<Arc<mock::HttpMock>>,
+    /// Per-capability timeouts overriding the provider timeout and the
+    /// `resilience.timeout` deadline (`capabilities.timeouts`).
+    pub(super) timeouts: HashMap<String, Duration>,
 }
 
 /// Maximum number of send attempts (1 initial + 2 retries) for transient
@@ -79,7 +113,8 @@ pub(super) const MAX_SEND_ATTEMPTS: u32 = 3;
 ///
 /// Retry policy:
 /// - **Connection** failures are always retried — no request bytes were sent,
-///   so a retry is side-effect-free.
+///   so a retry is side-effect-free. Once retries are exhausted they surface
+///   as [`Error::BackendUnavailable`], telling callers the request never left.
 /// - **Timeout** failures are retried only when `retry_timeouts` is true (i.e.
 ///   the request is idempotent). A timeout on a non-idempotent POST may mean
 ///   the upstream already processed it, so blindly replaying it could duplicate
@@ -108,7 +143,7 @@ pub(super) async fn send_with_retry(
                 }
                 Err(e) => {
                     health.record_failure();
-                    Err(Error::Transport(format!("{label} failed: {e}")))
+                    Err(send_error(label, &e))
                 }
             };
         };
@@ -132,7 +167,7 @@ pub(super) async fn send_with_retry(
                     continue;
                 }
                 health.record_failure();
-                return Err(Error::Transport(format!("{label} failed: {e}")));
+                return Err(send_error(label, &e));
             }
         }
     }
@@ -140,6 +175,17 @@ pub(super) async fn send_with_retry(
     unreachable!("send_with_retry exhausted attempts without returning")
 }
 
+/// Error for a failed send: [`Error::BackendUnavailable`] when the request
+/// never reached the upstream (refused connection, DNS failure), otherwise
+/// [`Error::Transport`].
+fn send_error(label: &str, e: &reqwest::Error) -> Error {
+    if e.is_connect() {
+        Error::BackendUnavailable(format!("{label} failed: {e}"))
+    } else {
+        Error::Transport(format!("{label} failed: {e}"))
+    }
+}
+
 impl CapabilityExecutor {
     /// Build a pooled HTTP client suitable for capability execution.
     ///
@@ -188,6 +234,15 @@ impl CapabilityExecutor {
             oauth_tokens: RwLock::new(DashMap::new()),
             secret_resolver: Arc::new(SecretResolver::new()),
             health: crate::failsafe::HealthTracker::new("capabilities"),
+            providers: failover::ProviderHealth::default(),
+            upstream_limits: crate::failsafe::KeyedRateLimiter::default(),
+            aws_credentials: sigv4::AwsCredentialCache::default(),
+            #[cfg(feature = "grpc")]
+            grpc: grpc::GrpcClients::default(),
+            #[cfg(feature = "sql")]
+            sql: sql::SqlPools::default(),
+            mock: None,
+            timeouts: HashMap::new(),
         }
     }
 
@@ -203,6 +258,13 @@ impl CapabilityExecutor {
         self.health.metrics()
     }
 
+    /// `capability/provider` pairs currently marked unhealthy, which calls
+    /// try only after their other providers.
+    #[must_use]
+    pub fn unhealthy_providers(&self) -> Vec<String> {
+        self.providers.unhealthy()
+    }
+
     /// Create an executor with a custom OAuth token storage.
     ///
     /// # Panics
@@ -217,9 +279,60 @@ impl CapabilityExecutor {
             oauth_tokens: RwLock::new(DashMap::new()),
             secret_resolver: Arc::new(SecretResolver::new()),
             health: crate::failsafe::HealthTracker::new("capabilities"),
+            providers: failover::ProviderHealth::default(),
+            upstream_limits: crate::failsafe::KeyedRateLimiter::default(),
+            aws_credentials: sigv4::AwsCredentialCache::default(),
+            #[cfg(feature = "grpc")]
+            grpc: grpc::GrpcClients::default(),
+            #[cfg(feature = "sql")]
+            sql: sql::SqlPools::default(),
+            mock: None,
+            timeouts: HashMap::new(),
         }
     }
 
+    /// Answer outbound capability requests from `mock` instead of the
+    /// network.
+    #[must_use]
+    pub fn with_mock(mut self, mock: Arc<mock::HttpMock>) -> Self {
+        self.mock = Some(mock);
+        self
+    }
+
+    /// Override the timeout of the named capabilities, both per provider
+    /// attempt and overall.
+    #[must_use]
+    pub fn with_timeouts(mut self, timeouts: HashMap<String, Duration>) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Timeout of one provider attempt of `capability`.
+    pub(super) fn provider_timeout(
+        &self,
+        capability: &CapabilityDefinition,
+        provider: &ProviderConfig,
+    ) -> Duration {
+        self.timeouts
+            .get(&capability.name)
+            .copied()
+            .unwrap_or(Duration::from_secs(provider.timeout))
+    }
+
+    /// Send a capability request through [`send_with_retry`], or to the mock
+    /// when one is set.
+    pub(super) async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        label: &str,
+        idempotent: bool,
+    ) -> Result<reqwest::Response> {
+        if let Some(mock) = &self.mock {
+            return mock.respond(request);
+        }
+        send_with_retry(request, label, idempotent, &self.health).await
+    }
+
     /// Store an OAuth token for a provider.
     pub fn set_oauth_token(&self, provider: &str, token: TokenInfo) {
         let tokens = self.oauth_tokens.read();
@@ -273,9 +386,10 @@ impl CapabilityExecutor {
 
         let start_time = std::time::Instant::now();
 
-        let provider = capability
-            .primary_provider()
-            .ok_or_else(|| Error::Config("No primary provider configured".to_string()))?;
+        let candidates = self.providers.candidates(capability);
+        if candidates.is_empty() {
+            return Err(Error::Config("No provider configured".to_string()));
+        }
 
         // Check cache first
         if capability.is_cacheable() {
@@ -286,15 +400,29 @@ impl CapabilityExecutor {
             }
         }
 
-        // Route through the protocol executor trait.
-        let protocol_config = provider.protocol_config();
-        let response = self
-            .dispatch_protocol(capability, provider, &protocol_config, &params, &context)
-            .await?;
+        // Try each provider in turn, within the capability's deadline.
+        let call = self.call_providers(capability, candidates, &params, &context);
+        let deadline = self
+            .timeouts
+            .get(&capability.name)
+            .copied()
+            .or_else(|| capability.resilience.timeout_duration());
+        let (provider_name, protocol_config, response) = match deadline {
+            Some(deadline) => tokio::time::timeout(deadline, call).await.map_err(|_| {
+                Error::BackendTimeout(format!(
+                    "Capability '{}' did not complete within {}s",
+                    capability.name,
+                    deadline.as_secs()
+                ))
+            })??,
+            None => call.await?,
+        };
 
         // Apply response transform pipeline if configured
         let response = {
-            let pipeline = TransformPipeline::compile(&capability.transform);
+            let pipeline = TransformPipeline::compile(&capability.transform).map_err(|e| {
+                Error::Config(format!("Capability '{}' transform: {e}", capability.name))
+            })?;
             if pipeline.is_noop() {
                 response
             } else {
@@ -306,7 +434,7 @@ impl CapabilityExecutor {
         let latency = start_time.elapsed();
         tracing::info!(
             latency_ms = latency.as_millis(),
-            provider = %provider.service,
+            provider = %provider_name,
             protocol = %protocol_config.protocol_name(),
             "Capability executed successfully"
         );
@@ -337,7 +465,7 @@ impl CapabilityExecutor {
 
         let ctx = ExecutionContext {
             capability,
-            timeout_secs: provider.timeout,
+            timeout_secs: self.provider_timeout(capability, provider).as_secs().max(1),
             context: context.clone(),
         };
 
@@ -360,8 +488,36 @@ impl CapabilityExecutor {
                     .execute(protocol_config, params.clone(), &ctx)
                     .await
             }
+            #[cfg(feature = "grpc")]
+            "grpc" => {
+                let executor = grpc::GrpcExecutor { executor: self };
+                executor
+                    .execute(protocol_config, params.clone(), &ctx)
+                    .await
+            }
+            #[cfg(not(feature = "grpc"))]
+            "grpc" => Err(Error::Config(
+                "gRPC capabilities need a gateway built with the `grpc` feature".to_string(),
+            )),
+            #[cfg(feature = "sql")]
+            "sql" => {
+                let executor = sql::SqlExecutor { executor: self };
+                executor
+                    .execute(protocol_config, params.clone(), &ctx)
+                    .await
+            }
+            #[cfg(not(feature = "sql"))]
+            "sql" => Err(Error::Config(
+                "SQL capabilities need a gateway built with the `sql` feature".to_string(),
+            )),
+            "exec" => {
+                let executor = exec::ExecExecutor { executor: self };
+                executor
+                    .execute(protocol_config, params.clone(), &ctx)
+                    .await
+            }
             other => Err(Error::Config(format!(
-                "Unsupported protocol '{other}'. Available: rest, graphql, jsonrpc"
+                "Unsupported protocol '{other}'. Available: rest, graphql, jsonrpc, grpc, sql, exec"
             ))),
         }
     }
@@ -415,6 +571,7 @@ impl CapabilityExecutor {
         {
             let credential = self.fetch_credential(&capability.auth).await?;
             request = request.query(&[(param_name.as_str(), credential.as_str())]);
+            crate::secrets::audit::injected(&capability.auth.key, &format!("query:{param_name}"));
         }
 
         // Add query parameters (from config.params with substitution)
@@ -443,20 +600,33 @@ impl CapabilityExecutor {
         // Add body for POST/PUT/PATCH
         let method_upper = config.method.to_uppercase();
         if matches!(method_upper.as_str(), "POST" | "PUT" | "PATCH") {
-            request = self.attach_request_body(request, config, params)?;
+            request = if config.files.is_empty() {
+                self.attach_request_body(request, config, params)?
+            } else {
+                self.attach_files(request, config, params, context).await?
+            };
+        }
+        // Signing covers the finished request, so it comes last.
+        if capability.auth.required && capability.auth.auth_type == AWS_SIGV4 {
+            request = self.sign_aws_request(request, &capability.auth).await?;
         }
 
-        let timeout = Duration::from_secs(provider.timeout);
+        let timeout = self.provider_timeout(capability, provider);
         // Retry timeouts only for idempotent HTTP methods; a timeout on a
         // mutating method may have already been processed upstream.
         let idempotent = matches!(method_upper.as_str(), "GET" | "HEAD" | "OPTIONS" | "TRACE");
-        let response = send_with_retry(
-            request.timeout(timeout),
-            "Request",
-            idempotent,
-            &self.health,
-        )
-        .await?;
+        if let Some(stream) = &config.stream {
+            return self
+                .execute_streaming(request, config, stream, timeout, idempotent)
+                .await;
+        }
+        let request = request.timeout(timeout);
+        if let Some(pagination) = &config.pagination {
+            return self
+                .execute_paginated(request, config, pagination, idempotent, context)
+                .await;
+        }
+        let response = self.send(request, "Request", idempotent).await?;
 
         self.handle_response(response, config).await
     }
@@ -523,6 +693,11 @@ impl CapabilityExecutor {
         for (name, value_template) in &config.headers {
             let value = self.substitute_string(value_template, params)?;
 
+            // A template rendering nothing leaves its header out.
+            if value.is_empty() && template::is_template(value_template) {
+                continue;
+            }
+
             // Skip Authorization headers with unresolved {access_token} —
             // inject_auth will handle auth from the credential key.
             if name.eq_ignore_ascii_case("authorization") && value.contains("{access_token}") {
@@ -536,8 +711,17 @@ impl CapabilityExecutor {
             }
         }
 
-        // Skip header injection when auth.param is set (credential goes as query param).
-        if auth.required && auth.param.is_none() {
+        // Content negotiation: ask for the format the response is parsed as,
+        // unless the capability sets `Accept` itself.
+        if !headers.contains_key(reqwest::header::ACCEPT)
+            && let Some(accept) = params::accept_for_format(&config.response_format)
+        {
+            headers.insert(reqwest::header::ACCEPT, HeaderValue::from_static(accept));
+        }
+
+        // Skip header injection when auth.param is set (credential goes as
+        // query param) and for aws_sigv4 (the finished request is signed).
+        if auth.required && auth.param.is_none() && auth.auth_type != AWS_SIGV4 {
             self.inject_auth(&mut headers, auth).await?;
         }
 
@@ -555,6 +739,11 @@ impl CapabilityExecutor {
         headers: &mut HeaderMap,
         auth: &super::AuthConfig,
     ) -> Result<()> {
+        if auth.auth_type == AWS_SIGV4 {
+            return Err(Error::Config(
+                "aws_sigv4 signing is supported for REST providers only".to_string(),
+            ));
+        }
         let credential = self.fetch_credential(auth).await?;
 
         let header_name: HeaderName = auth
@@ -582,6 +771,7 @@ impl CapabilityExecutor {
         let header_val: HeaderValue = header_value
             .parse()
             .map_err(|_| Error::Config("Invalid credential format".to_string()))?;
+        crate::secrets::audit::injected(&auth.key, &format!("header:{header_name}"));
         headers.insert(header_name, header_val);
 
         Ok(())
@@ -626,6 +816,9 @@ impl CapabilityExecutor {
     /// is present the template is substituted and the resulting string is sent
     /// verbatim (no JSON encoding).  This is required for databases such as
     /// `SurrealDB` whose `/sql` endpoint only accepts raw SQL as `text/plain`.
+    ///
+    /// With an XML content type the (string) template is filled with
+    /// XML-escaped values and sent as-is, along with `soap_action` if set.
     fn attach_request_body(
         &self,
         mut request: reqwest::RequestBuilder,
@@ -633,9 +826,30 @@ impl CapabilityExecutor {
         params: &Value,
     ) -> Result<reqwest::RequestBuilder> {
         let use_plain_text = config.body_content_type.eq_ignore_ascii_case("text/plain");
+        let use_xml = config
+            .body_content_type
+            .to_ascii_lowercase()
+            .contains("xml");
 
         if let Some(ref body_template) = config.body {
-            if use_plain_text {
+            if use_xml {
+                let Value::String(template) = body_template else {
+                    return Err(Error::Config(
+                        "XML request body must be a string template".to_string(),
+                    ));
+                };
+                let mut content_type = config.body_content_type.clone();
+                if let Some(action) = &config.soap_action {
+                    if content_type.to_ascii_lowercase().contains("soap+xml") {
+                        content_type = format!("{content_type}; action=\"{action}\"");
+                    } else {
+                        request = request.header("SOAPAction", format!("\"{action}\""));
+                    }
+                }
+                request = request
+                    .header(reqwest::header::CONTENT_TYPE, content_type)
+                    .body(self.substitute_xml(template, params)?);
+            } else if use_plain_text {
                 // Substitute into the template and send as a raw string body.
                 // The template must be a JSON string value; after substitution
                 // we send the string contents (not JSON-encoded).
@@ -0,0 +1,291 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Automatic pagination (`config.pagination`)
+//!
+//! The first request goes out as built. Each follow-up is a copy of it with
+//! the cursor or page number set as a query parameter or, for
+//! `link_header`, with the URL of the `rel="next"` link. Next links must stay
+//! on the first request's origin, so credentials are never sent elsewhere.
+//!
+//! The items at `merge_path` of every page are appended to the first page's
+//! items; the merged first page is the result.
+
+use reqwest::{Request, RequestBuilder, Response};
+use serde_json::Value;
+use tracing::debug;
+use url::Url;
+
+use super::CapabilityExecutor;
+use crate::capability::{
+    CapabilityExecutionContext, PaginationConfig, PaginationStrategy, RestConfig,
+    validate_capability_url_for_context,
+};
+use crate::{Error, Result};
+
+impl CapabilityExecutor {
+    /// Send `request` and follow its next pages, merging their items.
+    pub(super) async fn execute_paginated(
+        &self,
+        request: RequestBuilder,
+        config: &RestConfig,
+        pagination: &PaginationConfig,
+        idempotent: bool,
+        context: &CapabilityExecutionContext,
+    ) -> Result<Value> {
+        let first = request
+            .build()
+            .map_err(|e| Error::Config(format!("Invalid request: {e}")))?;
+        let mut request = resendable(&first)?;
+        if pagination.strategy == PaginationStrategy::Page {
+            set_query_param(
+                request.url_mut(),
+                pagination.param_name(),
+                &pagination.start_page.to_string(),
+            );
+        }
+
+        let mut merged: Option<Value> = None;
+        let mut page = pagination.start_page;
+        for fetched in 1..=pagination.max_pages.max(1) {
+            let url = request.url().clone();
+            let response = self
+                .send(
+                    RequestBuilder::from_parts(self.client.clone(), request),
+                    "Request",
+                    idempotent,
+                )
+                .await?;
+            let link = next_link(&response, &url);
+            let body = self.read_response(response, config).await?;
+            let cursor = match &pagination.cursor_path {
+                Some(path) if pagination.strategy == PaginationStrategy::Cursor => {
+                    cursor_value(&self.extract_path(&body, path)?)
+                }
+                _ => None,
+            };
+            if merge_page(&mut merged, body, pagination.merge_path.as_deref())? == 0 {
+                break;
+            }
+
+            let mut next = resendable(&first)?;
+            match pagination.strategy {
+                PaginationStrategy::Cursor => {
+                    let Some(cursor) = cursor else { break };
+                    set_query_param(next.url_mut(), pagination.param_name(), &cursor);
+                }
+                PaginationStrategy::Page => {
+                    page += 1;
+                    set_query_param(next.url_mut(), pagination.param_name(), &page.to_string());
+                }
+                PaginationStrategy::LinkHeader => {
+                    let Some(link) = link else { break };
+                    *next.url_mut() = follow_link(first.url(), link, context)?;
+                }
+            }
+            if fetched == pagination.max_pages {
+                debug!(pages = fetched, "Stopping pagination at max_pages");
+                break;
+            }
+            request = next;
+        }
+        self.project_response(merged.unwrap_or(Value::Null), config)
+    }
+}
+
+/// A copy of `request` for the next page.
+fn resendable(request: &Request) -> Result<Request> {
+    request.try_clone().ok_or_else(|| {
+        Error::Config("Paginated requests need a body that can be resent".to_string())
+    })
+}
+
+/// Append the items of `body` to `merged` (or make `body` the merged result
+/// when it is the first page). Returns the number of items on the page.
+fn merge_page(merged: &mut Option<Value>, mut body: Value, path: Option<&str>) -> Result<usize> {
+    let Some(all) = merged.as_mut() else {
+        let count = items_mut(&mut body, path)
+            .ok_or_else(|| {
+                Error::Protocol(format!(
+                    "Pagination merge_path '{}' is not an array in the response",
+                    path.unwrap_or_default()
+                ))
+            })?
+            .len();
+        *merged = Some(body);
+        return Ok(count);
+    };
+    // A later page without an items array has nothing more to add.
+    let Some(items) = items_mut(&mut body, path) else {
+        return Ok(0);
+    };
+    let count = items.len();
+    if let Some(all) = items_mut(all, path) {
+        all.append(items);
+    }
+    Ok(count)
+}
+
+/// The array at dot `path` of `value` (`value` itself when `path` is unset).
+fn items_mut<'a>(value: &'a mut Value, path: Option<&str>) -> Option<&'a mut Vec<Value>> {
+    let mut current = value;
+    for segment in path.unwrap_or_default().split('.') {
+        if segment.is_empty() {
+            continue;
+        }
+        current = match current {
+            Value::Object(map) => map.get_mut(segment)?,
+            Value::Array(items) => items.get_mut(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    current.as_array_mut()
+}
+
+/// A next-page cursor; `null`, `false` and `""` end pagination.
+fn cursor_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Set query parameter `key` of `url` to `value`, replacing earlier values.
+fn set_query_param(url: &mut Url, key: &str, value: &str) {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| k != key)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(key, value);
+}
+
+/// The `rel="next"` target of the response's `Link` headers, resolved
+/// against the request URL.
+fn next_link(response: &Response, url: &Url) -> Option<Url> {
+    response
+        .headers()
+        .get_all(reqwest::header::LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(parse_next_link)
+        .and_then(|target| url.join(target).ok())
+}
+
+/// The target of the `rel="next"` entry of a `Link` header value.
+fn parse_next_link(header: &str) -> Option<&str> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.trim().strip_prefix('<')?.split_once('>')?;
+        params
+            .split(';')
+            .filter_map(|param| param.split_once('='))
+            .any(|(key, value)| {
+                key.trim().eq_ignore_ascii_case("rel")
+                    && value
+                        .trim()
+                        .trim_matches('"')
+                        .split_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("next"))
+            })
+            .then_some(target)
+    })
+}
+
+/// The URL of the next page: `link`, on the first request's origin, with
+/// any query parameters of the first request it does not set itself (such
+/// as an API key).
+fn follow_link(first: &Url, mut link: Url, context: &CapabilityExecutionContext) -> Result<Url> {
+    if link.origin() != first.origin() {
+        return Err(Error::Protocol(format!(
+            "Next page link '{link}' is not on the origin of the first request"
+        )));
+    }
+    validate_capability_url_for_context(link.as_str(), context)?;
+    let present: Vec<String> = link.query_pairs().map(|(k, _)| k.into_owned()).collect();
+    let carried: Vec<(String, String)> = first
+        .query_pairs()
+        .filter(|(k, _)| !present.iter().any(|p| p == k))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if !carried.is_empty() {
+        link.query_pairs_mut().extend_pairs(carried);
+    }
+    Ok(link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_next_link_finds_the_next_relation() {
+        let header = r#"<https://api.example.com/items?page=1>; rel="prev", <https://api.example.com/items?page=3>; rel="next last""#;
+        assert_eq!(
+            parse_next_link(header),
+            Some("https://api.example.com/items?page=3")
+        );
+        assert_eq!(
+            parse_next_link(r#"<https://a.example/x>; rel="prev""#),
+            None
+        );
+    }
+
+    #[test]
+    fn set_query_param_replaces_existing_values() {
+        let mut url = Url::parse("https://api.example.com/items?q=rust&cursor=a").unwrap();
+        set_query_param(&mut url, "cursor", "b c");
+        assert_eq!(url.query(), Some("q=rust&cursor=b+c"));
+    }
+
+    #[test]
+    fn merge_page_appends_items_at_merge_path() {
+        // GIVEN: two pages with items under data.items
+        let mut merged = None;
+
+        // WHEN: merging both, then an empty page
+        let first = merge_page(
+            &mut merged,
+            json!({"data": {"items": [1, 2]}, "next": "x"}),
+            Some("data.items"),
+        )
+        .unwrap();
+        let second = merge_page(
+            &mut merged,
+            json!({"data": {"items": [3]}}),
+            Some("data.items"),
+        )
+        .unwrap();
+        let third = merge_page(&mut merged, json!({"data": {}}), Some("data.items")).unwrap();
+
+        // THEN: the first page's envelope holds every item
+        assert_eq!((first, second, third), (2, 1, 0));
+        assert_eq!(
+            merged.unwrap(),
+            json!({"data": {"items": [1, 2, 3]}, "next": "x"})
+        );
+    }
+
+    #[test]
+    fn merge_page_requires_an_array_on_the_first_page() {
+        let mut merged = None;
+        let err = merge_page(&mut merged, json!({"data": {}}), Some("data")).unwrap_err();
+        assert!(err.to_string().contains("merge_path 'data'"), "{err}");
+    }
+
+    #[test]
+    fn follow_link_stays_on_origin_and_carries_missing_params() {
+        let first = Url::parse("https://api.example.com/items?api_key=k&page=1").unwrap();
+        let context = CapabilityExecutionContext::default();
+
+        let link = Url::parse("https://api.example.com/items?page=2").unwrap();
+        let next = follow_link(&first, link, &context).unwrap();
+        assert_eq!(next.query(), Some("page=2&api_key=k"));
+
+        let elsewhere = Url::parse("https://evil.example.com/items?page=2").unwrap();
+        assert!(follow_link(&first, elsewhere, &context).is_err());
+    }
+}
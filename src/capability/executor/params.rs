@@ -6,9 +6,10 @@ use base64::{Engine as _, engine::general_purpose::STANDARD};
 use reqwest::Response;
 use serde_json::Value;
 
+use crate::capability::template::{self, Escape};
 use crate::{Error, Result};
 
-use super::xml::xml_to_json;
+use super::xml::{document_to_json, parse_xml, soap_fault_message, xml_to_json, xpath_to_json};
 use super::{super::RestConfig, CapabilityExecutor};
 
 fn graphql_error_message(body: &Value) -> Option<String> {
@@ -39,14 +40,35 @@ impl CapabilityExecutor {
         &self,
         response: Response,
         config: &RestConfig,
+    ) -> Result<Value> {
+        let body = self.read_response(response, config).await?;
+        self.project_response(body, config)
+    }
+
+    /// Read a response body as [`handle_response`](Self::handle_response)
+    /// does, without applying `response_path`.
+    pub(super) async fn read_response(
+        &self,
+        response: Response,
+        config: &RestConfig,
     ) -> Result<Value> {
         let status = response.status();
 
+        let response_format = config.response_format.to_ascii_lowercase();
+        let is_xml = detect_xml_format(response.headers(), &response_format);
+
         if !status.is_success() {
             let error_text = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
+            // SOAP services report errors as a fault envelope (usually 500).
+            if is_xml
+                && let Ok(document) = xml_to_json(&error_text)
+                && let Some(fault) = soap_fault_message(&document)
+            {
+                return Err(Error::Protocol(format!("SOAP fault ({status}): {fault}")));
+            }
             return Err(Error::Protocol(format!(
                 "API returned {}: {}",
                 status,
@@ -55,7 +77,6 @@ impl CapabilityExecutor {
             )));
         }
 
-        let response_format = config.response_format.to_ascii_lowercase();
         if response_format == "text" {
             // Plain-text responses (e.g. Wolfram|Alpha LLM API) aren't JSON.
             // Wrap the raw body so downstream output mapping has a JSON value.
@@ -83,22 +104,43 @@ impl CapabilityExecutor {
             }));
         }
 
-        let is_xml = detect_xml_format(response.headers(), &response_format);
-
-        let body: Value = if is_xml {
+        if is_xml {
             let text = response
                 .text()
                 .await
                 .map_err(|e| Error::Protocol(format!("Failed to read XML response: {e}")))?;
-            xml_to_json(&text)
-                .map_err(|e| Error::Protocol(format!("Failed to parse XML response: {e}")))?
+            let root = parse_xml(&text)
+                .map_err(|e| Error::Protocol(format!("Failed to parse XML response: {e}")))?;
+            let document = document_to_json(&root);
+            if let Some(fault) = soap_fault_message(&document) {
+                return Err(Error::Protocol(format!("SOAP fault: {fault}")));
+            }
+            match &config.response_xpath {
+                Some(expr) => xpath_to_json(&root, expr).map_err(Error::Config),
+                None => Ok(document),
+            }
+        } else if config.response_xpath.is_some() {
+            Err(Error::Config(
+                "response_xpath needs an XML response".to_string(),
+            ))
         } else {
             response
                 .json()
                 .await
-                .map_err(|e| Error::Protocol(format!("Failed to parse response: {e}")))?
-        };
+                .map_err(|e| Error::Protocol(format!("Failed to parse response: {e}")))
+        }
+    }
 
+    /// Apply `response_path` to a body read by
+    /// [`read_response`](Self::read_response). Text and binary bodies are
+    /// returned as they are.
+    pub(super) fn project_response(&self, body: Value, config: &RestConfig) -> Result<Value> {
+        if matches!(
+            config.response_format.to_ascii_lowercase().as_str(),
+            "text" | "binary"
+        ) {
+            return Ok(body);
+        }
         if let Some(ref path) = config.response_path {
             let projected = self.extract_path(&body, path)?;
             if projected.is_null()
@@ -140,29 +182,80 @@ impl CapabilityExecutor {
 
     /// Substitute `{param}` references in a string template.
     ///
-    /// After placeholder substitution, `{keychain.X}` and `{env.VAR}` secrets
-    /// are resolved via [`SecretResolver`](crate::secrets::SecretResolver).
+    /// `{keychain.X}`, `{vault.X}`, `{aws-secrets.X}`, `{aws-ssm.X}`, `{op.X}`,
+    /// `{bw.X}`, `{secret.X}` and `{env.VAR}` references written in the
+    /// template are resolved via [`SecretResolver`](crate::secrets::SecretResolver);
+    /// references inside argument values are inserted literally. Other
+    /// unknown placeholders are left as-is.
+    /// A [template](crate::capability::template) has its secrets resolved
+    /// first and is then rendered, so argument values are never resolved.
     pub(super) fn substitute_string(&self, template: &str, params: &Value) -> Result<String> {
-        let mut result = template.to_string();
+        if template::is_template(template) {
+            let source = self.secret_resolver.resolve(template)?;
+            return template::render(&source, params, Escape::None);
+        }
+        self.substitute_placeholders(template, params, str::to_string)
+    }
 
-        if let Value::Object(map) = params {
-            for (key, value) in map {
-                let placeholder = format!("{{{key}}}");
-                if result.contains(&placeholder) {
-                    let value_str = match value {
-                        Value::String(s) => s.clone(),
-                        Value::Number(n) => n.to_string(),
-                        Value::Bool(b) => b.to_string(),
-                        Value::Null => String::new(),
-                        _ => serde_json::to_string(value).unwrap_or_default(),
-                    };
-                    result = result.replace(&placeholder, &value_str);
+    /// Substitute `{param}` references in an XML body template, escaping
+    /// every substituted value so arguments cannot inject markup.
+    ///
+    /// Secret references resolve as in
+    /// [`substitute_string`](Self::substitute_string), to their escaped value.
+    /// A [template](crate::capability::template) is rendered with XML
+    /// escaping instead.
+    pub(super) fn substitute_xml(&self, template: &str, params: &Value) -> Result<String> {
+        if template::is_template(template) {
+            let source = self.secret_resolver.resolve(template)?;
+            return template::render(&source, params, Escape::Xml);
+        }
+        self.substitute_placeholders(template, params, |value| {
+            quick_xml::escape::escape(value).into_owned()
+        })
+    }
+
+    /// Replace each `{name}` in `template` in a single pass: by the argument
+    /// `name`, else by the secret it references, passed through `encode`.
+    /// Inserted text is never scanned again, so argument values cannot
+    /// reference secrets or other arguments.
+    fn substitute_placeholders(
+        &self,
+        template: &str,
+        params: &Value,
+        encode: impl Fn(&str) -> String,
+    ) -> Result<String> {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 1..start + len];
+            // In `{"q": "{query}"}` the placeholder opens at the last `{`
+            if let Some(inner) = name.rfind('{') {
+                out.push_str(&rest[..=start + inner]);
+                rest = &rest[start + inner + 1..];
+                continue;
+            }
+            let placeholder = &rest[start..=start + len];
+            let value = match params.get(name) {
+                Some(Value::String(s)) => Some(s.clone()),
+                Some(Value::Null) => Some(String::new()),
+                Some(other) => Some(other.to_string()),
+                None if is_secret_reference(name) => {
+                    Some(self.secret_resolver.resolve(placeholder)?)
                 }
+                None => None,
+            };
+            out.push_str(&rest[..start]);
+            match value {
+                Some(value) => out.push_str(&encode(&value)),
+                None => out.push_str(placeholder),
             }
+            rest = &rest[start + len + 1..];
         }
-
-        result = self.secret_resolver.resolve(&result)?;
-        Ok(result)
+        out.push_str(rest);
+        Ok(out)
     }
 
     /// Resolve a map of string templates to `(key, value)` query-param pairs.
@@ -179,7 +272,8 @@ impl CapabilityExecutor {
         for (key, value_template) in template {
             let value = self.substitute_string(value_template, params)?;
             // Skip empty values and unresolved {placeholder} templates
-            if !value.is_empty() && value != "null" && !value.starts_with('{') {
+            let unresolved = value.starts_with('{') && !template::is_template(value_template);
+            if !value.is_empty() && value != "null" && !unresolved {
                 result.push((key.clone(), value));
             }
         }
@@ -260,6 +354,21 @@ impl CapabilityExecutor {
     // ── Private decomposition helpers ─────────────────────────────────────────
 
     fn substitute_string_value(&self, s: &str, params: &Value) -> Result<Value> {
+        if template::is_template(s) {
+            // A template rendering nothing leaves its field out; one
+            // rendering a JSON object or array yields that value.
+            let rendered = self.substitute_string(s, params)?;
+            let trimmed = rendered.trim();
+            if trimmed.is_empty() {
+                return Ok(Value::Null);
+            }
+            if trimmed.starts_with(['{', '[']) {
+                return serde_json::from_str(trimmed).map_err(|e| {
+                    Error::Config(format!("Request template did not render valid JSON: {e}"))
+                });
+            }
+            return Ok(Value::String(rendered));
+        }
         let trimmed = s.trim();
         // Pure placeholder like "{priority}" → preserve original typed value
         if is_pure_placeholder(trimmed) {
@@ -317,6 +426,28 @@ fn is_pure_placeholder(s: &str) -> bool {
         && s.matches('{').count() == 1
         && !s.starts_with("{env.")
         && !s.starts_with("{keychain.")
+        && !s.starts_with("{vault.")
+        && !s.starts_with("{aws-secrets.")
+        && !s.starts_with("{aws-ssm.")
+        && !s.starts_with("{op.")
+        && !s.starts_with("{bw.")
+        && !s.starts_with("{secret.")
+}
+
+/// Whether `name` (a placeholder without braces) references a secret store.
+fn is_secret_reference(name: &str) -> bool {
+    [
+        "env.",
+        "keychain.",
+        "vault.",
+        "aws-secrets.",
+        "aws-ssm.",
+        "op.",
+        "bw.",
+        "secret.",
+    ]
+    .iter()
+    .any(|prefix| name.starts_with(prefix))
 }
 
 /// Returns `true` when a substituted string is still an unresolved placeholder.
@@ -324,6 +455,16 @@ fn is_unresolved_placeholder(s: &str) -> bool {
     s.starts_with('{') && s.ends_with('}') && !s.contains(' ')
 }
 
+/// `Accept` header for an explicit `response_format`, so services that
+/// negotiate content return the format the capability parses.
+pub(super) fn accept_for_format(response_format: &str) -> Option<&'static str> {
+    match response_format.to_ascii_lowercase().as_str() {
+        "json" => Some("application/json"),
+        "xml" => Some("application/xml, text/xml, application/soap+xml"),
+        _ => None,
+    }
+}
+
 /// Determine whether the response should be parsed as XML.
 ///
 /// Priority: explicit `response_format` field > `Content-Type` header.
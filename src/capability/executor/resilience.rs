@@ -0,0 +1,232 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Per-capability retries and upstream rate limit (`resilience:`)
+//!
+//! Each provider attempt first takes a token from the capability's
+//! `rate_limit` bucket; an empty bucket rejects the call outright, since
+//! every provider would draw from the same bucket. HTTP 429 is retried up
+//! to `retries` times with exponential backoff before the provider counts
+//! as failed; transport errors, timeouts and 5xx answers only when the call
+//! is idempotent, since a mutating request may already have been processed
+//! upstream. A request that never left (refused connection, failed DNS
+//! lookup) is retried for every call. The `timeout` deadline of the whole
+//! call is applied by the caller.
+
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tracing::debug;
+
+use super::CapabilityExecutor;
+use crate::Error;
+use crate::capability::{
+    CapabilityDefinition, CapabilityExecutionContext, ProtocolConfig, ProviderConfig,
+};
+use crate::config::RateSpec;
+
+/// Why a provider gave no response.
+pub(super) enum ProviderFailure {
+    /// The provider failed, after any retries; the next one may be tried.
+    Failed(Error),
+    /// The capability's upstream rate limit is used up; no provider may be
+    /// tried.
+    RateLimited(Error),
+}
+
+impl CapabilityExecutor {
+    /// Call one provider, retrying retryable failures as configured in
+    /// `capability.resilience`.
+    pub(super) async fn call_provider(
+        &self,
+        capability: &CapabilityDefinition,
+        provider: &ProviderConfig,
+        protocol_config: &ProtocolConfig,
+        params: &Value,
+        context: &CapabilityExecutionContext,
+    ) -> std::result::Result<Value, ProviderFailure> {
+        let resilience = &capability.resilience;
+        let idempotent = is_idempotent(capability, protocol_config);
+        let mut retry = 0;
+        loop {
+            if let Some(limit) = resilience.rate_limit {
+                self.upstream_limits
+                    .check(&capability.name, limit)
+                    .map_err(|retry_after| {
+                        ProviderFailure::RateLimited(rate_limited(
+                            &capability.name,
+                            limit,
+                            retry_after,
+                        ))
+                    })?;
+            }
+            match self
+                .dispatch_protocol(capability, provider, protocol_config, params, context)
+                .await
+            {
+                Ok(response) => return Ok(response),
+                Err(e) if retry < resilience.retries && is_retryable(&e, idempotent) => {
+                    retry += 1;
+                    let delay = resilience.backoff(retry);
+                    debug!(
+                        retry,
+                        delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                        error = %e,
+                        "Retrying capability provider"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(ProviderFailure::Failed(e)),
+            }
+        }
+    }
+}
+
+/// Whether a call through `protocol_config` may be repeated without
+/// repeating its side effects. `metadata.idempotent` decides when set;
+/// otherwise REST `GET`, `HEAD`, `OPTIONS` and `TRACE` are, and SQL, whose
+/// queries are read-only.
+pub(super) fn is_idempotent(
+    capability: &CapabilityDefinition,
+    protocol_config: &ProtocolConfig,
+) -> bool {
+    capability
+        .metadata
+        .idempotent
+        .unwrap_or_else(|| match protocol_config {
+            ProtocolConfig::Rest(config) => matches!(
+                config.method.to_uppercase().as_str(),
+                "GET" | "HEAD" | "OPTIONS" | "TRACE"
+            ),
+            ProtocolConfig::Sql(_) => true,
+            _ => false,
+        })
+}
+
+/// Whether `error` may succeed on a repeated attempt: a request that was
+/// never sent and HTTP 429 always, and transport failures, timeouts and 5xx
+/// answers when the call is `idempotent`.
+pub(super) fn is_retryable(error: &Error, idempotent: bool) -> bool {
+    match error {
+        Error::BackendUnavailable(_) => true,
+        Error::Transport(_) | Error::Http(_) | Error::BackendTimeout(_) => idempotent,
+        Error::Protocol(message) => message
+            .strip_prefix("API returned ")
+            .and_then(|rest| rest.get(..3))
+            .and_then(|code| code.parse::<u16>().ok())
+            .is_some_and(|code| code == 429 || (idempotent && (500..600).contains(&code))),
+        _ => false,
+    }
+}
+
+/// Rejection of a call beyond the capability's `rate_limit`.
+fn rate_limited(capability: &str, limit: RateSpec, retry_after: Duration) -> Error {
+    let retry_after_ms = u64::try_from(retry_after.as_millis()).unwrap_or(u64::MAX);
+    Error::JsonRpc {
+        code: -32000,
+        message: format!(
+            "Upstream rate limit of capability '{capability}' exceeded ({limit}); retry after {retry_after_ms}ms"
+        ),
+        data: Some(json!({
+            "error": "rate_limited",
+            "capability": capability,
+            "limit": limit.to_string(),
+            "retry_after_ms": retry_after_ms,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retryable_errors_are_transient_failures_429_and_5xx() {
+        assert!(is_retryable(&Error::Transport("reset".to_string()), true));
+        assert!(is_retryable(
+            &Error::Protocol("API returned 503 Service Unavailable: busy".to_string()),
+            true
+        ));
+        assert!(is_retryable(
+            &Error::Protocol("API returned 429 Too Many Requests: slow down".to_string()),
+            true
+        ));
+        assert!(!is_retryable(
+            &Error::Protocol("API returned 404 Not Found: nope".to_string()),
+            true
+        ));
+        assert!(!is_retryable(&Error::Config("bad".to_string()), true));
+    }
+
+    #[test]
+    fn non_idempotent_calls_retry_only_429() {
+        // GIVEN: a call that may already have been processed upstream
+        // WHEN/THEN: timeouts, transport errors and 5xx are not retried
+        assert!(!is_retryable(&Error::Transport("reset".to_string()), false));
+        assert!(!is_retryable(
+            &Error::BackendTimeout("slow".to_string()),
+            false
+        ));
+        assert!(!is_retryable(
+            &Error::Protocol("API returned 502 Bad Gateway: down".to_string()),
+            false
+        ));
+        // THEN: a 429 was rejected before processing, so it is retried
+        assert!(is_retryable(
+            &Error::Protocol("API returned 429 Too Many Requests: slow down".to_string()),
+            false
+        ));
+        // AND: so is a request that never reached the upstream
+        assert!(is_retryable(
+            &Error::BackendUnavailable("Request failed: connection refused".to_string()),
+            false
+        ));
+    }
+
+    fn rest(method: &str) -> ProtocolConfig {
+        ProtocolConfig::Rest(Box::new(crate::capability::RestConfig {
+            method: method.to_string(),
+            ..Default::default()
+        }))
+    }
+
+    fn capability(metadata: &str) -> CapabilityDefinition {
+        serde_yaml::from_str(&format!(
+            "name: search\nmetadata: {metadata}\nproviders:\n  primary:\n    config: {{base_url: 'https://a.example.com'}}\n"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn idempotency_follows_rest_method_and_protocol() {
+        let cap = capability("{}");
+        assert!(is_idempotent(&cap, &rest("get")));
+        assert!(is_idempotent(&cap, &rest("HEAD")));
+        assert!(!is_idempotent(&cap, &rest("POST")));
+        assert!(!is_idempotent(&cap, &rest("DELETE")));
+    }
+
+    #[test]
+    fn metadata_idempotent_overrides_the_method() {
+        // GIVEN: an upsert declared idempotent, and a GET declared not to be
+        let upsert = capability("{idempotent: true}");
+        let next = capability("{idempotent: false}");
+
+        // WHEN/THEN: the declaration wins over the method
+        assert!(is_idempotent(&upsert, &rest("PUT")));
+        assert!(!is_idempotent(&next, &rest("GET")));
+    }
+
+    #[test]
+    fn rate_limited_error_carries_retry_after() {
+        let limit: RateSpec = "60/min".parse().unwrap();
+        let Error::JsonRpc { message, data, .. } =
+            rate_limited("search", limit, Duration::from_millis(1500))
+        else {
+            panic!("expected JsonRpc error");
+        };
+        assert!(message.contains("capability 'search'"), "{message}");
+        let data = data.unwrap();
+        assert_eq!(data["limit"], "60/min");
+        assert_eq!(data["retry_after_ms"], 1500);
+    }
+}
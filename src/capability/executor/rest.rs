@@ -110,6 +110,8 @@ impl ProtocolExecutor for RestExecutor<'_> {
             service: "rest".to_string(),
             cost_per_call: 0.0,
             timeout: ctx.timeout_secs,
+            priority: None,
+            weight: 1,
             config: rest_config.clone(),
         };
 
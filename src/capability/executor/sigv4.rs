@@ -0,0 +1,553 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! AWS Signature Version 4 (`auth.type: aws_sigv4`)
+//!
+//! The finished REST request is signed just before it is sent: its query is
+//! rewritten in canonical form, `x-amz-date` (plus `x-amz-security-token`
+//! and, for S3, `x-amz-content-sha256`) is added, and the `Authorization`
+//! header carries the signature over the method, path, query, those headers,
+//! `host`, `content-type`, and the SHA-256 of the body.
+//!
+//! Credentials come from `auth.aws` references, the standard environment
+//! variables, or the container / instance profile endpoint. Credentials
+//! from an endpoint are cached until five minutes before they expire.
+
+use std::fmt::{self, Write as _};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, KeyInit, Mac};
+use parking_lot::Mutex;
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderValue};
+use reqwest::{Request, RequestBuilder};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use super::CapabilityExecutor;
+use crate::capability::{AuthConfig, AwsSigV4Config};
+use crate::{Error, Result};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// EC2 instance metadata service (`IMDSv2`).
+const IMDS_BASE: &str = "http://169.254.169.254";
+
+/// ECS task credentials endpoint, joined with
+/// `AWS_CONTAINER_CREDENTIALS_RELATIVE_URI`.
+const ECS_CREDENTIALS_BASE: &str = "http://169.254.170.2";
+
+/// Timeout of each call to a credentials endpoint.
+const CREDENTIALS_ENDPOINT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Endpoint credentials are refreshed this many minutes before they expire.
+const CREDENTIALS_REFRESH_MARGIN_MINUTES: i64 = 5;
+
+/// AWS access key, secret and optional session token.
+#[derive(Clone)]
+pub(crate) struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+// Redacts the secret and session token (CWE-532).
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+/// Credentials from the container or instance profile endpoint, shared by
+/// every `aws_sigv4` capability that does not name its own (and by the AWS
+/// secret sources, [`crate::secrets::aws`]).
+#[derive(Default)]
+pub(crate) struct AwsCredentialCache {
+    cached: Mutex<Option<AwsCredentials>>,
+}
+
+/// Credentials document of the ECS and EC2 endpoints.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EndpointCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    token: Option<String>,
+    expiration: Option<String>,
+}
+
+impl CapabilityExecutor {
+    /// Sign `request` for the region and service in `auth.aws`.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `auth.aws` is missing, no credentials are found, or the
+    /// body is streamed and so cannot be hashed.
+    pub(super) async fn sign_aws_request(
+        &self,
+        request: RequestBuilder,
+        auth: &AuthConfig,
+    ) -> Result<RequestBuilder> {
+        let aws = auth.aws.as_ref().ok_or_else(|| {
+            Error::Config("auth.aws (region and service) is required for aws_sigv4".to_string())
+        })?;
+        let credentials = self.aws_credentials(aws, auth).await?;
+        let mut request = request
+            .build()
+            .map_err(|e| Error::Config(format!("Invalid request: {e}")))?;
+        sign_request(&mut request, aws, &credentials, Utc::now())?;
+        Ok(RequestBuilder::from_parts(self.client.clone(), request))
+    }
+
+    /// Credentials for `aws`: its references, the environment, then the
+    /// container or instance profile endpoint.
+    async fn aws_credentials(
+        &self,
+        aws: &AwsSigV4Config,
+        auth: &AuthConfig,
+    ) -> Result<AwsCredentials> {
+        if let (Some(key_id), Some(secret)) = (&aws.access_key_id, &aws.secret_access_key) {
+            let session_token = match &aws.session_token {
+                Some(token) => Some(self.resolve_credential(token, auth).await?),
+                None => None,
+            };
+            return Ok(AwsCredentials {
+                access_key_id: self.resolve_credential(key_id, auth).await?,
+                secret_access_key: self.resolve_credential(secret, auth).await?,
+                session_token,
+                expires_at: None,
+            });
+        }
+        self.aws_credentials.ambient(&self.client).await
+    }
+}
+
+impl AwsCredentialCache {
+    /// A cache pre-filled with non-expiring credentials.
+    #[cfg(test)]
+    pub(crate) fn seeded(access_key_id: &str, secret_access_key: &str) -> Self {
+        Self {
+            cached: Mutex::new(Some(AwsCredentials {
+                access_key_id: access_key_id.to_string(),
+                secret_access_key: secret_access_key.to_string(),
+                session_token: None,
+                expires_at: None,
+            })),
+        }
+    }
+
+    /// Credentials from the environment, then the ECS task role or the EC2
+    /// instance profile.
+    ///
+    /// # Errors
+    ///
+    /// Fails when none of these sources has credentials.
+    pub(crate) async fn ambient(&self, client: &reqwest::Client) -> Result<AwsCredentials> {
+        if let (Ok(access_key_id), Ok(secret_access_key)) = (
+            std::env::var("AWS_ACCESS_KEY_ID"),
+            std::env::var("AWS_SECRET_ACCESS_KEY"),
+        ) {
+            return Ok(AwsCredentials {
+                access_key_id,
+                secret_access_key,
+                session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+                expires_at: None,
+            });
+        }
+
+        if let Some(cached) = self.cached.lock().as_ref()
+            && cached.expires_at.is_none_or(|at| {
+                Utc::now() + chrono::TimeDelta::minutes(CREDENTIALS_REFRESH_MARGIN_MINUTES) < at
+            })
+        {
+            return Ok(cached.clone());
+        }
+        let fetched = match std::env::var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI") {
+            Ok(uri) => container_credentials(client, &uri).await,
+            Err(_) => instance_profile_credentials(client).await,
+        }
+        .map_err(|e| {
+            Error::Config(format!(
+                "No AWS credentials: set AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY \
+                 (or auth.aws.access_key_id and secret_access_key for a capability), or \
+                 run with a task role or instance profile ({e})"
+            ))
+        })?;
+        *self.cached.lock() = Some(fetched.clone());
+        Ok(fetched)
+    }
+}
+
+/// Credentials of the ECS task role.
+async fn container_credentials(
+    client: &reqwest::Client,
+    relative_uri: &str,
+) -> Result<AwsCredentials> {
+    let response = client
+        .get(format!("{ECS_CREDENTIALS_BASE}{relative_uri}"))
+        .timeout(CREDENTIALS_ENDPOINT_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?;
+    endpoint_credentials(response.json().await?)
+}
+
+/// Credentials of the EC2 instance profile, through `IMDSv2`.
+async fn instance_profile_credentials(client: &reqwest::Client) -> Result<AwsCredentials> {
+    let token = client
+        .put(format!("{IMDS_BASE}/latest/api/token"))
+        .header("x-aws-ec2-metadata-token-ttl-seconds", "21600")
+        .timeout(CREDENTIALS_ENDPOINT_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let roles_url = format!("{IMDS_BASE}/latest/meta-data/iam/security-credentials/");
+    let roles = client
+        .get(&roles_url)
+        .header("x-aws-ec2-metadata-token", &token)
+        .timeout(CREDENTIALS_ENDPOINT_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let role = roles
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .ok_or_else(|| Error::Config("the instance has no IAM role".to_string()))?;
+    let response = client
+        .get(format!("{roles_url}{role}"))
+        .header("x-aws-ec2-metadata-token", &token)
+        .timeout(CREDENTIALS_ENDPOINT_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?;
+    endpoint_credentials(response.json().await?)
+}
+
+fn endpoint_credentials(document: EndpointCredentials) -> Result<AwsCredentials> {
+    let expires_at = match document.expiration {
+        Some(expiration) => Some(
+            DateTime::parse_from_rfc3339(&expiration)
+                .map_err(|e| Error::Protocol(format!("Invalid credentials expiration: {e}")))?
+                .with_timezone(&Utc),
+        ),
+        None => None,
+    };
+    Ok(AwsCredentials {
+        access_key_id: document.access_key_id,
+        secret_access_key: document.secret_access_key,
+        session_token: document.token,
+        expires_at,
+    })
+}
+
+/// Sign `request` in place at time `now`.
+pub(crate) fn sign_request(
+    request: &mut Request,
+    aws: &AwsSigV4Config,
+    credentials: &AwsCredentials,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let body: &[u8] = match request.body() {
+        None => &[],
+        Some(body) => body.as_bytes().ok_or_else(|| {
+            Error::Config("aws_sigv4 signing needs a buffered request body".to_string())
+        })?,
+    };
+    let payload_hash = hex::encode(Sha256::digest(body));
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let is_s3 = aws.service == "s3";
+
+    // Send exactly the query that is signed.
+    let query = canonical_query(request.url());
+    request
+        .url_mut()
+        .set_query((!query.is_empty()).then_some(query.as_str()));
+
+    let url = request.url();
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let mut signed: Vec<(&'static str, String)> =
+        vec![("host", host), ("x-amz-date", amz_date.clone())];
+    if let Some(content_type) = request.headers().get(CONTENT_TYPE) {
+        signed.push((
+            "content-type",
+            content_type.to_str().unwrap_or_default().to_string(),
+        ));
+    }
+    if is_s3 {
+        signed.push(("x-amz-content-sha256", payload_hash.clone()));
+    }
+    if let Some(token) = &credentials.session_token {
+        signed.push(("x-amz-security-token", token.clone()));
+    }
+    signed.sort_by(|a, b| a.0.cmp(b.0));
+
+    let canonical_headers = signed.iter().fold(String::new(), |mut out, (name, value)| {
+        let _ = writeln!(out, "{name}:{}", normalize_header_value(value));
+        out
+    });
+    let signed_headers = signed
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{}\n{}\n{query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}",
+        request.method(),
+        canonical_uri(url.path(), is_s3),
+    );
+
+    let scope = format!("{date}/{}/{}/aws4_request", aws.region, aws.service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = signing_key(
+        &credentials.secret_access_key,
+        &date,
+        &aws.region,
+        &aws.service,
+    );
+    let signature = hex::encode(hmac(&key, string_to_sign.as_bytes()));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id
+    );
+
+    let headers = request.headers_mut();
+    for (name, value) in signed {
+        if name == "host" || name == "content-type" {
+            continue;
+        }
+        headers.insert(name, header_value(&value)?);
+    }
+    headers.insert(AUTHORIZATION, header_value(&authorization)?);
+    Ok(())
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value)
+        .map_err(|_| Error::Config("Invalid AWS credential format".to_string()))
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The key of the `date/region/service` scope.
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac(format!("AWS4{secret}").as_bytes(), date.as_bytes());
+    let k_region = hmac(&k_date, region.as_bytes());
+    let k_service = hmac(&k_region, service.as_bytes());
+    hmac(&k_service, b"aws4_request")
+}
+
+/// The URI-encoded path. Services other than S3 expect each segment encoded
+/// twice.
+fn canonical_uri(path: &str, is_s3: bool) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(|segment| {
+            let once = uri_encode(&percent_decode(segment));
+            if is_s3 { once } else { uri_encode(&once) }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Query parameters sorted by key and value, each URI-encoded.
+fn canonical_query(url: &url::Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (uri_encode(&k), uri_encode(&v)))
+        .collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encode everything but the unreserved characters of RFC 3986.
+fn uri_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// Decode `%XX` escapes of a path segment; invalid escapes stay as they are.
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = segment
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Trim a header value and collapse runs of spaces.
+fn normalize_header_value(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn example_credentials() -> AwsCredentials {
+        AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn signing_key_matches_aws_example() {
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn sign_request_matches_aws_iam_example() {
+        // GIVEN: the IAM ListUsers request of the AWS SigV4 documentation
+        let client = reqwest::Client::new();
+        let mut request = client
+            .get("https://iam.amazonaws.com/?Version=2010-05-08&Action=ListUsers")
+            .header(
+                CONTENT_TYPE,
+                "application/x-www-form-urlencoded; charset=utf-8",
+            )
+            .build()
+            .unwrap();
+        let aws = AwsSigV4Config {
+            region: "us-east-1".to_string(),
+            service: "iam".to_string(),
+            ..AwsSigV4Config::default()
+        };
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+
+        // WHEN: signing it
+        sign_request(&mut request, &aws, &example_credentials(), now).unwrap();
+
+        // THEN: the documented signature, with the query in canonical order
+        assert_eq!(
+            request.headers()[AUTHORIZATION],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+        assert_eq!(request.headers()["x-amz-date"], "20150830T123600Z");
+        assert_eq!(
+            request.url().query(),
+            Some("Action=ListUsers&Version=2010-05-08")
+        );
+    }
+
+    #[test]
+    fn s3_requests_sign_the_payload_hash_and_session_token() {
+        let client = reqwest::Client::new();
+        let mut request = client
+            .put("https://bucket.s3.eu-west-1.amazonaws.com/a%20b/c.txt")
+            .body("hello")
+            .build()
+            .unwrap();
+        let aws = AwsSigV4Config {
+            region: "eu-west-1".to_string(),
+            service: "s3".to_string(),
+            ..AwsSigV4Config::default()
+        };
+        let credentials = AwsCredentials {
+            session_token: Some("session".to_string()),
+            ..example_credentials()
+        };
+
+        sign_request(&mut request, &aws, &credentials, Utc::now()).unwrap();
+
+        let headers = request.headers();
+        assert_eq!(
+            headers["x-amz-content-sha256"],
+            hex::encode(Sha256::digest(b"hello"))
+        );
+        assert_eq!(headers["x-amz-security-token"], "session");
+        let authorization = headers[AUTHORIZATION].to_str().unwrap();
+        assert!(
+            authorization.contains(
+                "SignedHeaders=host;x-amz-content-sha256;x-amz-date;x-amz-security-token"
+            ),
+            "{authorization}"
+        );
+    }
+
+    #[test]
+    fn canonical_uri_double_encodes_outside_s3() {
+        assert_eq!(canonical_uri("/a%20b/c", true), "/a%20b/c");
+        assert_eq!(canonical_uri("/a%20b/c", false), "/a%2520b/c");
+        assert_eq!(canonical_uri("", false), "/");
+    }
+
+    #[test]
+    fn canonical_query_sorts_and_encodes_spaces_as_percent_20() {
+        let url = url::Url::parse("https://example.com/?b=2&a=x+y&a=1").unwrap();
+        assert_eq!(canonical_query(&url), "a=1&a=x%20y&b=2");
+    }
+
+    #[test]
+    fn credentials_debug_redacts_secrets() {
+        let credentials = AwsCredentials {
+            session_token: Some("SENTINEL_TOKEN".to_string()),
+            ..example_credentials()
+        };
+        let debug = format!("{credentials:?}");
+        assert!(!debug.contains("wJalrXUtnFEMI"), "{debug}");
+        assert!(!debug.contains("SENTINEL_TOKEN"), "{debug}");
+    }
+}
@@ -0,0 +1,371 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! SQL protocol executor (`sql` feature)
+//!
+//! Implements [`ProtocolExecutor`] for read-only queries against Postgres,
+//! `MySQL` and `SQLite` through sqlx's `Any` driver. The statement comes from
+//! the capability YAML; caller arguments are only ever bound to its
+//! placeholders. Writes are blocked twice: statements must be a single
+//! `SELECT`/`WITH`/`VALUES` ([`SqlConfig::check_read_only`]), and every
+//! pooled connection is switched to read-only mode when it is opened.
+//!
+//! The `Any` driver decodes booleans, integers, floats, text and blobs.
+//! Other column types (timestamps, numerics, UUIDs…) must be cast in the
+//! query, e.g. `created_at::text`.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use dashmap::DashMap;
+use futures::TryStreamExt as _;
+use serde_json::{Map, Value};
+use sqlx::any::{AnyArguments, AnyPoolOptions, AnyRow};
+use sqlx::query::Query;
+use sqlx::{Any, AnyPool, Column as _, Executor as _, Row as _, TypeInfo as _, ValueRef as _};
+use tracing::debug;
+
+use super::CapabilityExecutor;
+use super::rest::{ExecutionContext, ProtocolExecutor};
+use crate::capability::definition::{ProtocolConfig, SqlConfig, SqlShape};
+use crate::capability::validate_personal_capability_identity;
+use crate::{Error, Result};
+
+/// Connections per database URL.
+const MAX_CONNECTIONS: u32 = 4;
+
+/// Connection pools reused across calls.
+#[derive(Default)]
+pub(crate) struct SqlPools {
+    /// Pools keyed by resolved connection URL.
+    pools: DashMap<String, AnyPool>,
+}
+
+/// SQL protocol executor.
+pub struct SqlExecutor<'a> {
+    /// Shared reference to the parent executor (owns the secret resolver
+    /// and the connection pools).
+    pub(super) executor: &'a CapabilityExecutor,
+}
+
+impl SqlExecutor<'_> {
+    /// Values bound to the statement, in `config.bind` order: the caller's
+    /// argument, else the configured default.
+    pub(crate) fn bind_values(config: &SqlConfig, params: &Value) -> Result<Vec<Value>> {
+        config
+            .bind
+            .iter()
+            .map(|name| {
+                params
+                    .get(name)
+                    .or_else(|| config.defaults.get(name))
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::Config(format!("SQL query argument '{name}' is required"))
+                    })
+            })
+            .collect()
+    }
+
+    /// Shape the fetched rows as configured.
+    pub(crate) fn shape(
+        shape: SqlShape,
+        columns: &[String],
+        rows: Vec<Map<String, Value>>,
+        truncated: bool,
+    ) -> Value {
+        match shape {
+            SqlShape::Rows => serde_json::json!({
+                "columns": columns,
+                "row_count": rows.len(),
+                "truncated": truncated,
+                "rows": rows,
+            }),
+            SqlShape::Row => rows.into_iter().next().map_or(Value::Null, Value::Object),
+            SqlShape::Value => rows
+                .into_iter()
+                .next()
+                .and_then(|mut row| columns.first().and_then(|c| row.remove(c)))
+                .unwrap_or(Value::Null),
+        }
+    }
+
+    /// Pool for `url`, opened on first use.
+    async fn pool(&self, url: &str) -> Result<AnyPool> {
+        if let Some(pool) = self.executor.sql.pools.get(url) {
+            return Ok(pool.clone());
+        }
+        sqlx::any::install_default_drivers();
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(MAX_CONNECTIONS)
+            .acquire_timeout(Duration::from_secs(10))
+            .after_connect(|conn, _meta| {
+                Box::pin(async move {
+                    let statement = match conn.backend_name() {
+                        "PostgreSQL" => "SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY",
+                        "MySQL" => "SET SESSION TRANSACTION READ ONLY",
+                        _ => "PRAGMA query_only = ON",
+                    };
+                    conn.execute(statement).await.map(|_| ())
+                })
+            })
+            .connect(url)
+            .await
+            .map_err(|e| Error::Transport(format!("Failed to connect to database: {e}")))?;
+        self.executor
+            .sql
+            .pools
+            .insert(url.to_string(), pool.clone());
+        Ok(pool)
+    }
+
+    /// Run the statement, returning column names, at most `max_rows` rows
+    /// and whether more were available.
+    async fn fetch(
+        pool: &AnyPool,
+        config: &SqlConfig,
+        values: Vec<Value>,
+    ) -> std::result::Result<(Vec<String>, Vec<Map<String, Value>>, bool), sqlx::Error> {
+        let query = values
+            .into_iter()
+            .fold(sqlx::query(&config.query), bind_json);
+        let mut stream = query.fetch(pool);
+        let mut columns = Vec::new();
+        let mut rows = Vec::new();
+        let mut truncated = false;
+        while let Some(row) = stream.try_next().await? {
+            if rows.len() == config.max_rows {
+                truncated = true;
+                break;
+            }
+            if columns.is_empty() {
+                columns = row.columns().iter().map(|c| c.name().to_string()).collect();
+            }
+            rows.push(row_to_json(&row)?);
+        }
+        Ok((columns, rows, truncated))
+    }
+}
+
+/// Bind one JSON value; arrays and objects are bound as JSON text.
+fn bind_json<'q>(
+    query: Query<'q, Any, AnyArguments<'q>>,
+    value: Value,
+) -> Query<'q, Any, AnyArguments<'q>> {
+    match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Bool(b) => query.bind(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => query.bind(i),
+            None => query.bind(n.as_f64()),
+        },
+        Value::String(s) => query.bind(s),
+        other => query.bind(other.to_string()),
+    }
+}
+
+/// Convert a row to a JSON object keyed by column name.
+fn row_to_json(row: &AnyRow) -> std::result::Result<Map<String, Value>, sqlx::Error> {
+    let mut object = Map::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let value = if row.try_get_raw(i)?.is_null() {
+            Value::Null
+        } else {
+            match column.type_info().name() {
+                "BOOLEAN" => Value::from(row.try_get::<bool, _>(i)?),
+                "SMALLINT" => Value::from(row.try_get::<i16, _>(i)?),
+                "INTEGER" => Value::from(row.try_get::<i32, _>(i)?),
+                "BIGINT" => Value::from(row.try_get::<i64, _>(i)?),
+                "REAL" => Value::from(row.try_get::<f32, _>(i)?),
+                "DOUBLE" => Value::from(row.try_get::<f64, _>(i)?),
+                "BLOB" => Value::from(
+                    base64::engine::general_purpose::STANDARD.encode(row.try_get::<Vec<u8>, _>(i)?),
+                ),
+                _ => Value::from(row.try_get::<String, _>(i)?),
+            }
+        };
+        object.insert(column.name().to_string(), value);
+    }
+    Ok(object)
+}
+
+#[async_trait]
+impl ProtocolExecutor for SqlExecutor<'_> {
+    fn protocol_name(&self) -> &'static str {
+        "sql"
+    }
+
+    async fn execute(
+        &self,
+        config: &ProtocolConfig,
+        params: Value,
+        ctx: &ExecutionContext<'_>,
+    ) -> Result<Value> {
+        let sql_config = config
+            .as_sql()
+            .ok_or_else(|| Error::Internal("SqlExecutor received non-SQL config".to_string()))?;
+        validate_personal_capability_identity(ctx.capability, &ctx.context)?;
+
+        if sql_config.url.is_empty() {
+            return Err(Error::Config(
+                "SQL connection URL not configured".to_string(),
+            ));
+        }
+        sql_config.check_read_only().map_err(Error::Config)?;
+        let values = Self::bind_values(sql_config, &params)?;
+        // Caller arguments never reach the connection URL.
+        let url = self
+            .executor
+            .substitute_string(&sql_config.url, &Value::Null)?;
+
+        debug!(capability = %ctx.capability.name, "Executing SQL query");
+        let started = std::time::Instant::now();
+        let deadline = Duration::from_secs(ctx.timeout_secs);
+        let outcome = tokio::time::timeout(deadline, async {
+            let pool = self.pool(&url).await?;
+            Self::fetch(&pool, sql_config, values)
+                .await
+                .map_err(|e| Error::Protocol(format!("SQL query failed: {e}")))
+        })
+        .await;
+        let (columns, rows, truncated) = match outcome {
+            Ok(Ok(result)) => {
+                self.executor.health.record_success(started.elapsed());
+                result
+            }
+            Ok(Err(e)) => {
+                if matches!(e, Error::Transport(_)) {
+                    self.executor.health.record_failure();
+                } else {
+                    self.executor.health.record_success(started.elapsed());
+                }
+                return Err(e);
+            }
+            Err(_) => {
+                self.executor.health.record_failure();
+                return Err(Error::Transport(format!(
+                    "SQL query timed out after {}s",
+                    ctx.timeout_secs
+                )));
+            }
+        };
+
+        let body = Self::shape(sql_config.shape, &columns, rows, truncated);
+        match &sql_config.response_path {
+            Some(path) => self.executor.extract_path(&body, path),
+            None => Ok(body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(bind: &[&str]) -> SqlConfig {
+        SqlConfig {
+            url: "sqlite::memory:".to_string(),
+            query: "SELECT 1".to_string(),
+            bind: bind.iter().map(ToString::to_string).collect(),
+            max_rows: 10,
+            ..SqlConfig::default()
+        }
+    }
+
+    fn rows() -> (Vec<String>, Vec<Map<String, Value>>) {
+        let columns = vec!["region".to_string(), "revenue".to_string()];
+        let rows = [("eu", 120), ("us", 80)]
+            .into_iter()
+            .map(|(region, revenue)| {
+                json!({"region": region, "revenue": revenue})
+                    .as_object()
+                    .cloned()
+                    .unwrap()
+            })
+            .collect();
+        (columns, rows)
+    }
+
+    #[test]
+    fn bind_values_follow_bind_order_with_defaults() {
+        // GIVEN: two bound arguments, one with a configured default
+        let mut config = config(&["since", "region"]);
+        config.defaults.insert("region".to_string(), json!("eu"));
+
+        // WHEN: the caller supplies only the first
+        let values = SqlExecutor::bind_values(&config, &json!({"since": "2026-01-01"})).unwrap();
+
+        // THEN: values come in bind order, the default filling the gap
+        assert_eq!(values, vec![json!("2026-01-01"), json!("eu")]);
+    }
+
+    #[test]
+    fn bind_values_require_arguments_without_default() {
+        let err = SqlExecutor::bind_values(&config(&["since"]), &json!({})).unwrap_err();
+        assert!(err.to_string().contains("'since'"));
+    }
+
+    #[test]
+    fn shape_rows_reports_columns_and_truncation() {
+        let (columns, rows) = rows();
+        let shaped = SqlExecutor::shape(SqlShape::Rows, &columns, rows, true);
+        assert_eq!(shaped["columns"], json!(["region", "revenue"]));
+        assert_eq!(shaped["row_count"], 2);
+        assert_eq!(shaped["truncated"], true);
+        assert_eq!(shaped["rows"][1]["region"], "us");
+    }
+
+    #[test]
+    fn shape_row_and_value_take_the_first_row() {
+        let (columns, rows) = rows();
+        assert_eq!(
+            SqlExecutor::shape(SqlShape::Row, &columns, rows.clone(), false),
+            json!({"region": "eu", "revenue": 120})
+        );
+        assert_eq!(
+            SqlExecutor::shape(SqlShape::Value, &columns, rows, false),
+            json!("eu")
+        );
+        assert_eq!(
+            SqlExecutor::shape(SqlShape::Value, &columns, Vec::new(), false),
+            Value::Null
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_binds_arguments_and_limits_rows() {
+        // GIVEN: an in-memory SQLite database with three rows
+        sqlx::any::install_default_drivers();
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        pool.execute(
+            "CREATE TABLE orders (region TEXT, amount INTEGER);
+             INSERT INTO orders VALUES ('eu', 5), ('us', 7), ('eu', 9);",
+        )
+        .await
+        .unwrap();
+        let mut config = config(&["min"]);
+        config.query =
+            "SELECT region, amount FROM orders WHERE amount >= $1 ORDER BY amount".to_string();
+        config.max_rows = 1;
+
+        // WHEN: the query runs with a bound minimum
+        let (columns, rows, truncated) = SqlExecutor::fetch(&pool, &config, vec![json!(6)])
+            .await
+            .unwrap();
+
+        // THEN: only rows matching the bound value are returned, up to max_rows
+        assert_eq!(columns, vec!["region", "amount"]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            Value::Object(rows[0].clone()),
+            json!({"region": "us", "amount": 7})
+        );
+        assert!(truncated);
+    }
+}
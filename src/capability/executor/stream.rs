@@ -0,0 +1,296 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Streaming responses (`config.stream`)
+//!
+//! The body is read chunk by chunk and split into items (SSE event data,
+//! lines, or raw chunks). Each item's text is appended to the result and
+//! reported as an MCP progress notification for the call in flight, carrying
+//! the text itself when `partial` is set. The provider timeout bounds the wait
+//! for each chunk, so a long generation that keeps producing output is not
+//! cut off.
+
+use std::time::Duration;
+
+use futures::StreamExt as _;
+use reqwest::RequestBuilder;
+use serde_json::Value;
+
+use super::CapabilityExecutor;
+use crate::capability::{ResponseStreamConfig, RestConfig, StreamFormat};
+use crate::gateway::progress;
+use crate::{Error, Result};
+
+/// Splits a byte stream into items.
+#[derive(Debug)]
+struct ItemDecoder {
+    format: StreamFormat,
+    /// Bytes not yet forming a complete line (or UTF-8 character).
+    pending: Vec<u8>,
+    /// `data` lines of the SSE event being read.
+    data: Vec<String>,
+}
+
+impl ItemDecoder {
+    fn new(format: StreamFormat) -> Self {
+        Self {
+            format,
+            pending: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    /// Feed a chunk; returns the items it completes.
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        if self.format == StreamFormat::Chunks {
+            // Hold back an incomplete trailing UTF-8 sequence.
+            let valid = match std::str::from_utf8(&self.pending) {
+                Ok(text) => text.len(),
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                Err(_) => self.pending.len(),
+            };
+            let bytes: Vec<u8> = self.pending.drain(..valid).collect();
+            let text = String::from_utf8_lossy(&bytes).into_owned();
+            return if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![text]
+            };
+        }
+        let mut items = Vec::new();
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            self.line(line.trim_end_matches(['\n', '\r']), &mut items);
+        }
+        items
+    }
+
+    /// End of body: returns the items still buffered.
+    fn finish(&mut self) -> Vec<String> {
+        let mut items = self.push(b"\n");
+        if self.format == StreamFormat::Sse {
+            self.line("", &mut items);
+        }
+        items
+    }
+
+    fn line(&mut self, line: &str, items: &mut Vec<String>) {
+        if self.format == StreamFormat::Lines {
+            if !line.trim().is_empty() {
+                items.push(line.to_string());
+            }
+            return;
+        }
+        if line.is_empty() {
+            if !self.data.is_empty() {
+                items.push(self.data.join("\n"));
+                self.data.clear();
+            }
+        } else if let Some(data) = line.strip_prefix("data:") {
+            self.data
+                .push(data.strip_prefix(' ').unwrap_or(data).to_string());
+        }
+        // Comments (`:`) and the `event`, `id` and `retry` fields are ignored.
+    }
+}
+
+/// Text collected from the stream.
+#[derive(Debug, Default)]
+struct StreamedText {
+    text: String,
+    events: usize,
+    truncated: bool,
+    done: bool,
+}
+
+impl StreamedText {
+    /// Add items; sets `done` at the end marker or the size limit.
+    fn add(&mut self, items: Vec<String>, config: &ResponseStreamConfig) {
+        for item in items {
+            if self.done {
+                return;
+            }
+            if item.trim() == config.done {
+                self.done = true;
+                return;
+            }
+            let Some(piece) = item_text(item, config.text_path.as_deref()) else {
+                continue;
+            };
+            self.events += 1;
+            progress::report(config.partial.then_some(piece.as_str()));
+
+            let separator = if self.text.is_empty()
+                || config.text_path.is_some()
+                || config.format == StreamFormat::Chunks
+            {
+                ""
+            } else {
+                "\n"
+            };
+            let room = config.max_bytes.saturating_sub(self.text.len());
+            if separator.len() + piece.len() > room {
+                let mut cut = room.saturating_sub(separator.len()).min(piece.len());
+                while !piece.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                self.text.push_str(separator);
+                self.text.push_str(&piece[..cut]);
+                self.truncated = true;
+                self.done = true;
+                return;
+            }
+            self.text.push_str(separator);
+            self.text.push_str(&piece);
+        }
+    }
+
+    fn into_json(self) -> Value {
+        serde_json::json!({
+            "text": self.text,
+            "events": self.events,
+            "truncated": self.truncated,
+        })
+    }
+}
+
+/// The text of one item: the string at `text_path` of a JSON item, or the
+/// item itself. Items without text at `text_path` are skipped.
+fn item_text(item: String, text_path: Option<&str>) -> Option<String> {
+    let Some(path) = text_path else {
+        return Some(item);
+    };
+    let Ok(value) = serde_json::from_str::<Value>(&item) else {
+        return Some(item);
+    };
+    let mut current = &value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        current = match current {
+            Value::Object(map) => map.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    match current {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+impl CapabilityExecutor {
+    /// Send `request` and consume its streaming response.
+    ///
+    /// `idle_timeout` bounds the wait for the response and for each chunk.
+    pub(super) async fn execute_streaming(
+        &self,
+        request: RequestBuilder,
+        config: &RestConfig,
+        stream: &ResponseStreamConfig,
+        idle_timeout: Duration,
+        idempotent: bool,
+    ) -> Result<Value> {
+        let send = self.send(request, "Request", idempotent);
+        let response = tokio::time::timeout(idle_timeout, send)
+            .await
+            .map_err(|_| idle_error(idle_timeout))??;
+        if !response.status().is_success() {
+            // Reads the error body and fails.
+            return self.read_response(response, config).await;
+        }
+
+        let mut body = response.bytes_stream();
+        let mut decoder = ItemDecoder::new(stream.format);
+        let mut collected = StreamedText::default();
+        while !collected.done {
+            let chunk = match tokio::time::timeout(idle_timeout, body.next()).await {
+                Err(_) => return Err(idle_error(idle_timeout)),
+                Ok(None) => break,
+                Ok(Some(chunk)) => {
+                    chunk.map_err(|e| Error::Transport(format!("Failed to read stream: {e}")))?
+                }
+            };
+            collected.add(decoder.push(&chunk), stream);
+        }
+        if !collected.done {
+            collected.add(decoder.finish(), stream);
+        }
+        self.project_response(collected.into_json(), config)
+    }
+}
+
+fn idle_error(idle_timeout: Duration) -> Error {
+    Error::Transport(format!(
+        "Stream produced no data for {}s",
+        idle_timeout.as_secs()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(format: StreamFormat, text_path: Option<&str>) -> ResponseStreamConfig {
+        ResponseStreamConfig {
+            format,
+            text_path: text_path.map(str::to_string),
+            ..ResponseStreamConfig::default()
+        }
+    }
+
+    #[test]
+    fn sse_decoder_joins_data_lines_across_chunks() {
+        // GIVEN: SSE events split at arbitrary byte boundaries
+        let mut decoder = ItemDecoder::new(StreamFormat::Sse);
+
+        // WHEN: feeding the chunks
+        let mut items = decoder.push(b": keep-alive\n\nevent: delta\ndata: {\"a\"");
+        items.extend(decoder.push(b":1}\n\ndata: line one\r\ndata: line two\n"));
+        items.extend(decoder.finish());
+
+        // THEN: each event's data is one item
+        assert_eq!(items, vec!["{\"a\":1}", "line one\nline two"]);
+    }
+
+    #[test]
+    fn chunk_decoder_holds_back_split_utf8() {
+        let mut decoder = ItemDecoder::new(StreamFormat::Chunks);
+        let bytes = "hé".as_bytes();
+        assert_eq!(decoder.push(&bytes[..2]), vec!["h"]);
+        assert_eq!(decoder.push(&bytes[2..]), vec!["é"]);
+    }
+
+    #[test]
+    fn streamed_text_concatenates_deltas_until_done() {
+        let config = config(StreamFormat::Sse, Some("choices.0.delta.content"));
+        let mut collected = StreamedText::default();
+        collected.add(
+            vec![
+                r#"{"choices":[{"delta":{"role":"assistant"}}]}"#.to_string(),
+                r#"{"choices":[{"delta":{"content":"Hel"}}]}"#.to_string(),
+                r#"{"choices":[{"delta":{"content":"lo"}}]}"#.to_string(),
+                "[DONE]".to_string(),
+                r#"{"choices":[{"delta":{"content":"ignored"}}]}"#.to_string(),
+            ],
+            &config,
+        );
+        assert!(collected.done);
+        assert_eq!(
+            collected.into_json(),
+            serde_json::json!({"text": "Hello", "events": 2, "truncated": false})
+        );
+    }
+
+    #[test]
+    fn streamed_text_truncates_at_max_bytes() {
+        let config = ResponseStreamConfig {
+            max_bytes: 8,
+            ..config(StreamFormat::Lines, None)
+        };
+        let mut collected = StreamedText::default();
+        collected.add(vec!["abcd".to_string(), "éfgh".to_string()], &config);
+        assert!(collected.truncated && collected.done);
+        assert_eq!(collected.text, "abcd\néf");
+    }
+}
@@ -0,0 +1,423 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! File parameters (`config.files`)
+//!
+//! A file argument is one of:
+//!
+//! - a base64 string, or a `data:<mime>;base64,<data>` URL;
+//! - an object `{data, filename?, content_type?}` with base64 `data`;
+//! - a resource URI, as a string or `{uri, filename?, content_type?}`:
+//!   `http(s)://` URLs are downloaded (SSRF-checked like every capability
+//!   URL), `file://` URIs are read only below the parameter's `file_roots`.
+//!
+//! Files are sent as `multipart/form-data` parts alongside the other
+//! arguments, or — with any other `body_content_type` — as the raw request
+//! body. Every file is limited to the parameter's `max_bytes`.
+
+use std::path::PathBuf;
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use reqwest::RequestBuilder;
+use reqwest::multipart::{Form, Part};
+use serde_json::Value;
+
+use super::CapabilityExecutor;
+use crate::capability::{
+    CapabilityExecutionContext, FileParamConfig, RestConfig, validate_capability_url_for_context,
+};
+use crate::{Error, Result};
+
+/// A loaded file argument.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct FileInput {
+    pub(super) bytes: Vec<u8>,
+    pub(super) filename: Option<String>,
+    pub(super) content_type: Option<String>,
+}
+
+/// Where a file argument's content comes from.
+#[derive(Debug, PartialEq, Eq)]
+enum FileSource {
+    Inline(String),
+    Uri(String),
+}
+
+/// Split a file argument into its source and caller-supplied metadata.
+fn file_source(name: &str, value: &Value) -> Result<(FileSource, Option<String>, Option<String>)> {
+    let field = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+    let source = match value {
+        Value::String(s) if is_uri(s) => FileSource::Uri(s.clone()),
+        Value::String(s) => FileSource::Inline(s.clone()),
+        Value::Object(obj) => match (obj.get("data"), obj.get("uri")) {
+            (Some(Value::String(data)), None) => FileSource::Inline(data.clone()),
+            (None, Some(Value::String(uri))) => FileSource::Uri(uri.clone()),
+            _ => {
+                return Err(Error::Config(format!(
+                    "File argument '{name}' needs exactly one of 'data' or 'uri'"
+                )));
+            }
+        },
+        _ => {
+            return Err(Error::Config(format!(
+                "File argument '{name}' must be a base64 string, a URI or an object"
+            )));
+        }
+    };
+    Ok((source, field("filename"), field("content_type")))
+}
+
+fn is_uri(s: &str) -> bool {
+    ["http://", "https://", "file://"]
+        .iter()
+        .any(|scheme| s.starts_with(scheme))
+}
+
+/// Decode base64 (or a base64 `data:` URL), enforcing `max_bytes`.
+///
+/// Returns the bytes and the data URL's media type, if any.
+fn decode_inline(name: &str, data: &str, max_bytes: usize) -> Result<(Vec<u8>, Option<String>)> {
+    let (media_type, encoded) = match data.strip_prefix("data:") {
+        Some(rest) => {
+            let (meta, payload) = rest.split_once(',').ok_or_else(|| {
+                Error::Config(format!("File argument '{name}' is a malformed data URL"))
+            })?;
+            let media_type = meta.strip_suffix(";base64").ok_or_else(|| {
+                Error::Config(format!("File argument '{name}' data URL must be base64"))
+            })?;
+            (
+                Some(media_type)
+                    .filter(|m| !m.is_empty())
+                    .map(str::to_string),
+                payload,
+            )
+        }
+        None => (None, data),
+    };
+    let encoded: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+    // Reject before decoding: base64 grows data by a third.
+    if encoded.len() / 4 * 3 > max_bytes.saturating_add(2) {
+        return Err(too_large(name, max_bytes));
+    }
+    let bytes = STANDARD
+        .decode(encoded.as_bytes())
+        .map_err(|e| Error::Config(format!("File argument '{name}' is not valid base64: {e}")))?;
+    if bytes.len() > max_bytes {
+        return Err(too_large(name, max_bytes));
+    }
+    Ok((bytes, media_type))
+}
+
+fn too_large(name: &str, max_bytes: usize) -> Error {
+    Error::Config(format!(
+        "File argument '{name}' exceeds the {max_bytes}-byte limit"
+    ))
+}
+
+/// Last path segment of a URI, used as the default file name.
+fn uri_file_name(uri: &str) -> Option<String> {
+    url::Url::parse(uri)
+        .ok()?
+        .path_segments()?
+        .next_back()
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+impl CapabilityExecutor {
+    /// Load the file argument `value` of parameter `name`.
+    pub(super) async fn load_file(
+        &self,
+        name: &str,
+        spec: &FileParamConfig,
+        value: &Value,
+        context: &CapabilityExecutionContext,
+    ) -> Result<FileInput> {
+        let (source, filename, content_type) = file_source(name, value)?;
+        let (bytes, detected_type, uri_name) = match source {
+            FileSource::Inline(data) => {
+                let (bytes, media_type) = decode_inline(name, &data, spec.max_bytes)?;
+                (bytes, media_type, None)
+            }
+            FileSource::Uri(uri) if uri.starts_with("file://") => {
+                let bytes = read_local_file(name, &uri, spec).await?;
+                (bytes, None, uri_file_name(&uri))
+            }
+            FileSource::Uri(uri) => {
+                let (bytes, media_type) = self.download(name, &uri, spec, context).await?;
+                (bytes, media_type, uri_file_name(&uri))
+            }
+        };
+        Ok(FileInput {
+            bytes,
+            filename: filename.or(uri_name),
+            content_type: content_type
+                .or(detected_type)
+                .or_else(|| spec.content_type.clone()),
+        })
+    }
+
+    /// Download an `http(s)` resource, enforcing `max_bytes` while reading.
+    async fn download(
+        &self,
+        name: &str,
+        uri: &str,
+        spec: &FileParamConfig,
+        context: &CapabilityExecutionContext,
+    ) -> Result<(Vec<u8>, Option<String>)> {
+        validate_capability_url_for_context(uri, context)?;
+        let mut response = self
+            .client
+            .get(uri)
+            .send()
+            .await
+            .map_err(|e| Error::Transport(format!("Failed to fetch file '{name}': {e}")))?;
+        if !response.status().is_success() {
+            return Err(Error::Protocol(format!(
+                "Fetching file '{name}' returned {}",
+                response.status()
+            )));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > u64::try_from(spec.max_bytes).unwrap_or(u64::MAX))
+        {
+            return Err(too_large(name, spec.max_bytes));
+        }
+        let media_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| Error::Transport(format!("Failed to fetch file '{name}': {e}")))?
+        {
+            if bytes.len() + chunk.len() > spec.max_bytes {
+                return Err(too_large(name, spec.max_bytes));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok((bytes, media_type))
+    }
+
+    /// Attach the declared file arguments (and the remaining arguments) as
+    /// the request body.
+    pub(super) async fn attach_files(
+        &self,
+        request: RequestBuilder,
+        config: &RestConfig,
+        params: &Value,
+        context: &CapabilityExecutionContext,
+    ) -> Result<RequestBuilder> {
+        let mut files = Vec::new();
+        for (name, spec) in &config.files {
+            if let Some(value) = params.get(name).filter(|v| !v.is_null()) {
+                files.push((
+                    name,
+                    spec,
+                    self.load_file(name, spec, value, context).await?,
+                ));
+            }
+        }
+
+        let content_type = config.body_content_type.to_ascii_lowercase();
+        if !content_type.is_empty() && !content_type.starts_with("multipart/") {
+            // Raw body: exactly one file is the whole request.
+            let [(_, _, file)] = <[_; 1]>::try_from(files).map_err(|files| {
+                Error::Config(format!(
+                    "A raw {} body needs exactly one file argument, got {}",
+                    config.body_content_type,
+                    files.len()
+                ))
+            })?;
+            let file_type = file
+                .content_type
+                .unwrap_or_else(|| config.body_content_type.clone());
+            return Ok(request
+                .header(reqwest::header::CONTENT_TYPE, file_type)
+                .body(file.bytes));
+        }
+
+        let mut form = Form::new();
+        let fields = match &config.body {
+            Some(template) => self.substitute_value(template, params)?,
+            None => params.clone(),
+        };
+        if let Value::Object(map) = fields {
+            for (key, value) in map {
+                if config.files.contains_key(&key) || value.is_null() {
+                    continue;
+                }
+                let text = match value {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                form = form.text(key, text);
+            }
+        }
+        for (name, spec, file) in files {
+            let filename = match &spec.filename {
+                Some(template) => Some(self.substitute_string(template, params)?),
+                None => file.filename,
+            };
+            let mut part =
+                Part::bytes(file.bytes).file_name(filename.unwrap_or_else(|| name.clone()));
+            if let Some(content_type) = &file.content_type {
+                part = part.mime_str(content_type).map_err(|e| {
+                    Error::Config(format!(
+                        "File argument '{name}' has an invalid content type: {e}"
+                    ))
+                })?;
+            }
+            form = form.part(spec.field.clone().unwrap_or_else(|| name.clone()), part);
+        }
+        Ok(request.multipart(form))
+    }
+}
+
+/// Read a `file://` URI below one of `spec.file_roots`.
+async fn read_local_file(name: &str, uri: &str, spec: &FileParamConfig) -> Result<Vec<u8>> {
+    let path = url::Url::parse(uri)
+        .ok()
+        .and_then(|u| u.to_file_path().ok())
+        .ok_or_else(|| Error::Config(format!("File argument '{name}' has an invalid file URI")))?;
+    let denied = || {
+        Error::Config(format!(
+            "File argument '{name}' is outside the allowed file_roots"
+        ))
+    };
+    // Canonicalise both sides so `..` and symlinks cannot escape a root.
+    let path = tokio::fs::canonicalize(&path).await.map_err(|_| denied())?;
+    let mut allowed = false;
+    for root in &spec.file_roots {
+        let root: PathBuf = crate::config_reload::expand_tilde(root);
+        if let Ok(root) = tokio::fs::canonicalize(&root).await
+            && path.starts_with(&root)
+        {
+            allowed = true;
+            break;
+        }
+    }
+    if !allowed {
+        return Err(denied());
+    }
+    let metadata = tokio::fs::metadata(&path)
+        .await
+        .map_err(|e| Error::Config(format!("File argument '{name}': {e}")))?;
+    if metadata.len() > u64::try_from(spec.max_bytes).unwrap_or(u64::MAX) {
+        return Err(too_large(name, spec.max_bytes));
+    }
+    tokio::fs::read(&path)
+        .await
+        .map_err(|e| Error::Config(format!("File argument '{name}': {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(max_bytes: usize) -> FileParamConfig {
+        FileParamConfig {
+            max_bytes,
+            ..FileParamConfig::default()
+        }
+    }
+
+    #[test]
+    fn file_source_accepts_strings_uris_and_objects() {
+        assert_eq!(
+            file_source("doc", &json!("aGVsbG8=")).unwrap(),
+            (FileSource::Inline("aGVsbG8=".to_string()), None, None)
+        );
+        assert_eq!(
+            file_source("doc", &json!("https://example.com/a.pdf"))
+                .unwrap()
+                .0,
+            FileSource::Uri("https://example.com/a.pdf".to_string())
+        );
+        assert_eq!(
+            file_source(
+                "doc",
+                &json!({"data": "aGk=", "filename": "hi.txt", "content_type": "text/plain"})
+            )
+            .unwrap(),
+            (
+                FileSource::Inline("aGk=".to_string()),
+                Some("hi.txt".to_string()),
+                Some("text/plain".to_string())
+            )
+        );
+        assert!(file_source("doc", &json!({"data": "aGk=", "uri": "https://x"})).is_err());
+        assert!(file_source("doc", &json!(42)).is_err());
+    }
+
+    #[test]
+    fn decode_inline_reads_data_urls_and_enforces_limit() {
+        let (bytes, media_type) =
+            decode_inline("doc", "data:text/plain;base64,aGVs\nbG8=", 16).unwrap();
+        assert_eq!(bytes, b"hello");
+        assert_eq!(media_type.as_deref(), Some("text/plain"));
+
+        let err = decode_inline("doc", "aGVsbG8=", 4).unwrap_err();
+        assert!(err.to_string().contains("4-byte limit"), "{err}");
+        assert!(decode_inline("doc", "data:text/plain,hello", 16).is_err());
+    }
+
+    #[tokio::test]
+    async fn local_files_must_be_below_a_root() {
+        // GIVEN: a root directory with a file, and a file outside it
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("uploads");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("scan.pdf"), b"%PDF").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), b"no").unwrap();
+        let spec = FileParamConfig {
+            file_roots: vec![root.display().to_string()],
+            ..spec(16)
+        };
+        let uri = |p: PathBuf| url::Url::from_file_path(p).unwrap().to_string();
+
+        // WHEN/THEN: the file below the root is read
+        let bytes = read_local_file("doc", &uri(root.join("scan.pdf")), &spec)
+            .await
+            .unwrap();
+        assert_eq!(bytes, b"%PDF");
+
+        // WHEN/THEN: escaping the root, directly or via `..`, is denied
+        for outside in [
+            dir.path().join("secret.txt"),
+            root.join("..").join("secret.txt"),
+        ] {
+            let err = read_local_file("doc", &uri(outside), &spec)
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("file_roots"), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn raw_body_needs_exactly_one_file() {
+        let executor = CapabilityExecutor::new();
+        let mut config = RestConfig {
+            body_content_type: "application/pdf".to_string(),
+            ..RestConfig::default()
+        };
+        config.files.insert("a".to_string(), spec(16));
+        config.files.insert("b".to_string(), spec(16));
+        let request = executor.client.post("https://example.com/upload");
+
+        let err = executor
+            .attach_files(
+                request,
+                &config,
+                &json!({"a": "aGk=", "b": "aGk="}),
+                &CapabilityExecutionContext::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exactly one file"), "{err}");
+    }
+}
@@ -14,50 +14,89 @@
 //!
 //! This is sufficient for common structured XML APIs like the ECB exchange
 //! rate feed while avoiding a heavyweight XML-to-JSON library.
+//!
+//! [`xpath_to_json`] selects part of a document first, with the `XPath` subset
+//! SOAP responses need: child (`/`) and descendant (`//`) steps, name tests
+//! matched on local names (`soap:Body` and `Body` are the same), `*`, `.`,
+//! `@attr`, `text()`, and `[n]`, `[last()]`, `[@attr]`, `[@attr='v']` and
+//! `[child='v']` predicates.
+
+use std::collections::HashSet;
 
 use serde_json::Value;
 
-/// Convert an XML string into a `serde_json::Value`.
-pub(super) fn xml_to_json(xml: &str) -> std::result::Result<Value, String> {
+/// A parsed XML element (names are local names).
+#[derive(Debug, Default)]
+pub(super) struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    /// Last non-empty text or CDATA segment, trimmed.
+    text: Option<String>,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    /// JSON form of the element's attributes, text and children.
+    fn to_json(&self) -> Value {
+        let mut obj = serde_json::Map::new();
+        for (key, value) in &self.attributes {
+            obj.insert(format!("@{key}"), Value::String(value.clone()));
+        }
+        if let Some(text) = &self.text {
+            obj.insert("#text".to_string(), Value::String(text.clone()));
+        }
+        for child in &self.children {
+            insert_child(&mut obj, &child.name, child.to_json());
+        }
+        Value::Object(obj)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parse `xml` into a synthetic root element holding the document.
+pub(super) fn parse_xml(xml: &str) -> std::result::Result<XmlElement, String> {
     use quick_xml::Reader;
-    use quick_xml::events::Event;
+    use quick_xml::events::{BytesStart, Event};
+
+    fn element(e: &BytesStart<'_>) -> XmlElement {
+        XmlElement {
+            name: local_name(e.name().as_ref()),
+            attributes: e
+                .attributes()
+                .flatten()
+                .map(|attr| {
+                    (
+                        local_name(attr.key.as_ref()),
+                        String::from_utf8_lossy(&attr.value).to_string(),
+                    )
+                })
+                .collect(),
+            ..XmlElement::default()
+        }
+    }
 
     let mut reader = Reader::from_str(xml);
 
-    // Stack of (element_name, json_object) pairs.
-    let mut stack: Vec<(String, serde_json::Map<String, Value>)> = Vec::new();
-    // Push a synthetic root so we always have a target.
-    stack.push(("_root".to_string(), serde_json::Map::new()));
+    // Stack of open elements, below a synthetic root so we always have a
+    // target.
+    let mut stack = vec![XmlElement {
+        name: "_root".to_string(),
+        ..XmlElement::default()
+    }];
 
     loop {
         match reader.read_event() {
-            Ok(Event::Start(ref e)) => {
-                let name = local_name(e.name().as_ref());
-                let mut obj = serde_json::Map::new();
-
-                // Collect attributes
-                for attr in e.attributes().flatten() {
-                    let key = format!("@{}", local_name(attr.key.as_ref()));
-                    let val = String::from_utf8_lossy(&attr.value).to_string();
-                    obj.insert(key, Value::String(val));
-                }
-
-                stack.push((name, obj));
-            }
+            Ok(Event::Start(ref e)) => stack.push(element(e)),
             Ok(Event::Empty(ref e)) => {
                 // Self-closing element, e.g. <Cube currency='USD' rate='1.05'/>
-                let name = local_name(e.name().as_ref());
-                let mut obj = serde_json::Map::new();
-
-                for attr in e.attributes().flatten() {
-                    let key = format!("@{}", local_name(attr.key.as_ref()));
-                    let val = String::from_utf8_lossy(&attr.value).to_string();
-                    obj.insert(key, Value::String(val));
-                }
-
-                // Attach to parent
                 if let Some(parent) = stack.last_mut() {
-                    insert_child(&mut parent.1, &name, Value::Object(obj));
+                    parent.children.push(element(e));
                 }
             }
             Ok(Event::Text(ref e)) => {
@@ -65,7 +104,7 @@ pub(super) fn xml_to_json(xml: &str) -> std::result::Result<Value, String> {
                 if !text.is_empty()
                     && let Some(current) = stack.last_mut()
                 {
-                    current.1.insert("#text".to_string(), Value::String(text));
+                    current.text = Some(text);
                 }
             }
             Ok(Event::CData(ref e)) => {
@@ -73,19 +112,17 @@ pub(super) fn xml_to_json(xml: &str) -> std::result::Result<Value, String> {
                 if !text.is_empty()
                     && let Some(current) = stack.last_mut()
                 {
-                    current.1.insert("#text".to_string(), Value::String(text));
+                    current.text = Some(text);
                 }
             }
             Ok(Event::End(_)) => {
-                if let Some((name, obj)) = stack.pop() {
-                    let value = Value::Object(obj);
-                    if let Some(parent) = stack.last_mut() {
-                        insert_child(&mut parent.1, &name, value);
-                    } else {
-                        // Should not happen (we have a synthetic root), but
-                        // return what we have.
-                        return Ok(value);
-                    }
+                // The synthetic root is never popped: quick-xml rejects
+                // unmatched end tags.
+                if stack.len() > 1
+                    && let Some(done) = stack.pop()
+                    && let Some(parent) = stack.last_mut()
+                {
+                    parent.children.push(done);
                 }
             }
             Ok(Event::Eof) => break,
@@ -99,13 +136,326 @@ pub(super) fn xml_to_json(xml: &str) -> std::result::Result<Value, String> {
         }
     }
 
-    // Unwrap the synthetic root.  If it has a single child, return that
-    // child directly (common case: the XML has one root element).
-    let (_, root_obj) = stack.pop().unwrap_or_default();
-    if root_obj.len() == 1 {
-        Ok(root_obj.into_values().next().unwrap_or(Value::Null))
+    Ok(stack.swap_remove(0))
+}
+
+/// Convert an XML string into a `serde_json::Value`.
+pub(super) fn xml_to_json(xml: &str) -> std::result::Result<Value, String> {
+    Ok(document_to_json(&parse_xml(xml)?))
+}
+
+/// JSON form of a parsed document. If the synthetic root has a single
+/// child (common case: the XML has one root element), that child is
+/// returned directly.
+pub(super) fn document_to_json(root: &XmlElement) -> Value {
+    match root.to_json() {
+        Value::Object(obj) if obj.len() == 1 => obj.into_values().next().unwrap_or(Value::Null),
+        other => other,
+    }
+}
+
+/// Select nodes of a parsed document with an `XPath` expression and convert
+/// them to JSON: `null` when nothing matches, the node itself for one match,
+/// an array for several. Elements convert like [`xml_to_json`]; attributes
+/// and `text()` become strings.
+pub(super) fn xpath_to_json(root: &XmlElement, expr: &str) -> std::result::Result<Value, String> {
+    let steps = parse_xpath(expr)?;
+    let mut nodes: Vec<Value> = select(root, &steps)
+        .into_iter()
+        .map(|node| match node {
+            Node::Element(e) => e.to_json(),
+            Node::Value(v) => Value::String(v.to_string()),
+        })
+        .collect();
+    Ok(match nodes.len() {
+        0 => Value::Null,
+        1 => nodes.swap_remove(0),
+        _ => Value::Array(nodes),
+    })
+}
+
+/// Message of a SOAP 1.1 or 1.2 fault in a converted envelope, if any.
+pub(super) fn soap_fault_message(body: &Value) -> Option<String> {
+    let fault = body.get("Body")?.get("Fault")?;
+    let text = |v: Option<&Value>| {
+        v.map(|v| match v {
+            Value::Array(items) => items.first().cloned().unwrap_or(Value::Null),
+            other => other.clone(),
+        })
+        .and_then(|v| v.get("#text").and_then(Value::as_str).map(str::to_string))
+    };
+    // SOAP 1.1: faultcode/faultstring; SOAP 1.2: Code/Value, Reason/Text.
+    let code = text(fault.get("faultcode"))
+        .or_else(|| text(fault.get("Code").and_then(|c| c.get("Value"))));
+    let reason = text(fault.get("faultstring"))
+        .or_else(|| text(fault.get("Reason").and_then(|r| r.get("Text"))))
+        .unwrap_or_else(|| "unknown fault".to_string());
+    Some(match code {
+        Some(code) => format!("{code}: {reason}"),
+        None => reason,
+    })
+}
+
+// ── XPath subset ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Child,
+    Descendant,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum NodeTest {
+    /// Element by local name (`*` matches any).
+    Element(String),
+    /// Attribute by local name (`*` matches any).
+    Attribute(String),
+    Text,
+    SelfNode,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Predicate {
+    Index(usize),
+    Last,
+    HasAttribute(String),
+    AttributeEquals(String, String),
+    ChildEquals(String, String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Predicate>,
+}
+
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Element(&'a XmlElement),
+    Value(&'a str),
+}
+
+/// Parse an expression into location steps.
+fn parse_xpath(expr: &str) -> std::result::Result<Vec<Step>, String> {
+    let mut rest = expr.trim();
+    let mut axis = Axis::Child;
+    if let Some(after) = rest.strip_prefix("//") {
+        axis = Axis::Descendant;
+        rest = after;
+    } else if let Some(after) = rest.strip_prefix('/') {
+        rest = after;
+    }
+
+    let mut steps = Vec::new();
+    loop {
+        let end = step_end(rest)?;
+        steps.push(parse_step(&rest[..end], axis).map_err(|e| format!("XPath '{expr}': {e}"))?);
+        rest = &rest[end..];
+        if rest.is_empty() {
+            return Ok(steps);
+        }
+        if let Some(after) = rest.strip_prefix("//") {
+            axis = Axis::Descendant;
+            rest = after;
+        } else {
+            axis = Axis::Child;
+            rest = &rest[1..];
+        }
+    }
+}
+
+/// Byte offset of the `/` ending the first step (outside predicates and
+/// string literals), or the length of `s`.
+fn step_end(s: &str) -> std::result::Result<usize, String> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth = depth.saturating_sub(1),
+            (None, '/') if depth == 0 => return Ok(i),
+            _ => {}
+        }
+    }
+    if quote.is_some() || depth > 0 {
+        return Err(format!("unterminated predicate or literal in '{s}'"));
+    }
+    Ok(s.len())
+}
+
+fn parse_step(text: &str, axis: Axis) -> std::result::Result<Step, String> {
+    let (head, mut rest) = text.split_at(text.find('[').unwrap_or(text.len()));
+    let head = head.trim();
+    let node_test = match head {
+        "" => return Err("empty step".to_string()),
+        "." => NodeTest::SelfNode,
+        "text()" => NodeTest::Text,
+        _ => match head.strip_prefix('@') {
+            Some(name) => NodeTest::Attribute(xpath_name(name)?),
+            None => NodeTest::Element(xpath_name(head)?),
+        },
+    };
+
+    let mut predicates = Vec::new();
+    while !rest.is_empty() {
+        let inner = rest
+            .strip_prefix('[')
+            .and_then(|r| r.find(']').map(|end| (&r[..end], &r[end + 1..])));
+        let Some((inner, after)) = inner else {
+            return Err(format!("unexpected '{rest}'"));
+        };
+        predicates.push(parse_predicate(inner.trim())?);
+        rest = after.trim_start();
+    }
+    Ok(Step {
+        axis,
+        test: node_test,
+        predicates,
+    })
+}
+
+fn parse_predicate(text: &str) -> std::result::Result<Predicate, String> {
+    if text == "last()" {
+        return Ok(Predicate::Last);
+    }
+    if let Ok(index) = text.parse::<usize>() {
+        return if index == 0 {
+            Err("positions start at 1".to_string())
+        } else {
+            Ok(Predicate::Index(index))
+        };
+    }
+    let Some((lhs, rhs)) = text.split_once('=') else {
+        return match text.strip_prefix('@') {
+            Some(name) => Ok(Predicate::HasAttribute(xpath_name(name)?)),
+            None => Err(format!("unsupported predicate '[{text}]'")),
+        };
+    };
+    let rhs = rhs.trim();
+    let literal = rhs
+        .strip_prefix('\'')
+        .and_then(|r| r.strip_suffix('\''))
+        .or_else(|| rhs.strip_prefix('"').and_then(|r| r.strip_suffix('"')))
+        .ok_or_else(|| format!("predicate value {rhs} must be a quoted string"))?
+        .to_string();
+    let lhs = lhs.trim();
+    Ok(match lhs.strip_prefix('@') {
+        Some(name) => Predicate::AttributeEquals(xpath_name(name)?, literal),
+        None => Predicate::ChildEquals(xpath_name(lhs)?, literal),
+    })
+}
+
+/// Local part of a name test, validated.
+fn xpath_name(name: &str) -> std::result::Result<String, String> {
+    let local = name.rsplit(':').next().unwrap_or(name);
+    if local == "*"
+        || (!local.is_empty()
+            && local
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+    {
+        Ok(local.to_string())
     } else {
-        Ok(Value::Object(root_obj))
+        Err(format!("unsupported name '{name}'"))
+    }
+}
+
+/// Evaluate `steps` from the document root.
+fn select<'a>(root: &'a XmlElement, steps: &[Step]) -> Vec<Node<'a>> {
+    let mut current = vec![Node::Element(root)];
+    for step in steps {
+        let mut next = Vec::new();
+        let mut seen = HashSet::new();
+        for node in &current {
+            let Node::Element(element) = *node else {
+                continue;
+            };
+            let contexts = match step.axis {
+                Axis::Child => vec![element],
+                Axis::Descendant => descendants_or_self(element),
+            };
+            for context in contexts {
+                let matched = apply_predicates(apply_test(context, &step.test), &step.predicates);
+                for node in matched {
+                    // An element reached through two `//` contexts counts once.
+                    if let Node::Element(e) = node
+                        && !seen.insert(std::ptr::from_ref(e))
+                    {
+                        continue;
+                    }
+                    next.push(node);
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+fn descendants_or_self(element: &XmlElement) -> Vec<&XmlElement> {
+    let mut out = vec![element];
+    let mut i = 0;
+    while i < out.len() {
+        let children = &out[i].children;
+        out.extend(children.iter());
+        i += 1;
+    }
+    out
+}
+
+fn apply_test<'a>(context: &'a XmlElement, test: &NodeTest) -> Vec<Node<'a>> {
+    match test {
+        NodeTest::SelfNode => vec![Node::Element(context)],
+        NodeTest::Text => context
+            .text
+            .as_deref()
+            .map(Node::Value)
+            .into_iter()
+            .collect(),
+        NodeTest::Element(name) => context
+            .children
+            .iter()
+            .filter(|c| name == "*" || c.name == *name)
+            .map(Node::Element)
+            .collect(),
+        NodeTest::Attribute(name) => context
+            .attributes
+            .iter()
+            .filter(|(key, _)| name == "*" || key == name)
+            .map(|(_, value)| Node::Value(value))
+            .collect(),
+    }
+}
+
+fn apply_predicates<'a>(mut nodes: Vec<Node<'a>>, predicates: &[Predicate]) -> Vec<Node<'a>> {
+    for predicate in predicates {
+        nodes = match predicate {
+            Predicate::Index(n) => nodes.into_iter().nth(n - 1).into_iter().collect(),
+            Predicate::Last => nodes.pop().into_iter().collect(),
+            _ => nodes
+                .into_iter()
+                .filter(|node| match node {
+                    Node::Element(e) => element_matches(e, predicate),
+                    Node::Value(_) => false,
+                })
+                .collect(),
+        };
+    }
+    nodes
+}
+
+fn element_matches(element: &XmlElement, predicate: &Predicate) -> bool {
+    match predicate {
+        Predicate::HasAttribute(name) => element.attribute(name).is_some(),
+        Predicate::AttributeEquals(name, value) => element.attribute(name) == Some(value),
+        Predicate::ChildEquals(name, value) => element
+            .children
+            .iter()
+            .any(|c| c.name == *name && c.text.as_deref() == Some(value)),
+        Predicate::Index(_) | Predicate::Last => true,
     }
 }
 
@@ -143,7 +493,7 @@ fn local_name(raw: &[u8]) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::xml_to_json;
+    use super::{parse_xml, soap_fault_message, xml_to_json, xpath_to_json};
     use serde_json::json;
 
     // Correctness contract (guards quick-xml bumps — MIK-6731): namespace prefix
@@ -182,4 +532,84 @@ mod tests {
         // Malformed (unclosed tag) must surface an Err, not panic.
         assert!(xml_to_json("<a><b></a>").is_err());
     }
+
+    const SOAP_RESPONSE: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+        <soap:Body>
+            <m:GetRatesResponse xmlns:m="urn:rates">
+                <m:Rate currency="USD"><m:Value>1.05</m:Value></m:Rate>
+                <m:Rate currency="GBP"><m:Value>0.85</m:Value></m:Rate>
+            </m:GetRatesResponse>
+        </soap:Body>
+    </soap:Envelope>"#;
+
+    #[test]
+    fn xpath_selects_by_local_name_predicate_and_position() {
+        // GIVEN: a namespaced SOAP response
+        // WHEN: selecting with prefixed, descendant and predicate steps
+        // THEN: matches ignore prefixes and follow XPath positions
+        let doc = parse_xml(SOAP_RESPONSE).unwrap();
+        assert_eq!(
+            xpath_to_json(
+                &doc,
+                "/soap:Envelope/soap:Body//Rate[@currency='GBP']/Value/text()"
+            )
+            .unwrap(),
+            json!("0.85")
+        );
+        assert_eq!(
+            xpath_to_json(&doc, "//Rate/@currency").unwrap(),
+            json!(["USD", "GBP"])
+        );
+        assert_eq!(
+            xpath_to_json(&doc, "//Rate[last()]").unwrap(),
+            json!({"@currency": "GBP", "Value": {"#text": "0.85"}})
+        );
+        assert_eq!(
+            xpath_to_json(&doc, "//Rate[Value='1.05']/@currency").unwrap(),
+            json!("USD")
+        );
+        assert_eq!(xpath_to_json(&doc, "//Missing").unwrap(), json!(null));
+    }
+
+    #[test]
+    fn xpath_rejects_unsupported_syntax() {
+        let doc = parse_xml(SOAP_RESPONSE).unwrap();
+        for expr in [
+            "",
+            "//Rate[",
+            "//Rate[position() > 1]",
+            "//Rate[0]",
+            "count(//Rate)",
+        ] {
+            assert!(xpath_to_json(&doc, expr).is_err(), "{expr}");
+        }
+    }
+
+    #[test]
+    fn soap_fault_message_reads_both_versions() {
+        let soap11 = xml_to_json(
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><s:Fault>
+                <faultcode>s:Client</faultcode><faultstring>Unknown currency</faultstring>
+            </s:Fault></s:Body></s:Envelope>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            soap_fault_message(&soap11).as_deref(),
+            Some("s:Client: Unknown currency")
+        );
+
+        let soap12 = xml_to_json(
+            r#"<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope"><env:Body><env:Fault>
+                <env:Code><env:Value>env:Sender</env:Value></env:Code>
+                <env:Reason><env:Text xml:lang="en">Bad rate</env:Text></env:Reason>
+            </env:Fault></env:Body></env:Envelope>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            soap_fault_message(&soap12).as_deref(),
+            Some("env:Sender: Bad rate")
+        );
+
+        assert!(soap_fault_message(&xml_to_json(SOAP_RESPONSE).unwrap()).is_none());
+    }
 }
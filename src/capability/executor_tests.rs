@@ -143,6 +143,33 @@ fn test_substitute_string() {
     assert_eq!(result, "Hello World, your score is 100");
 }
 
+#[test]
+fn substitute_string_inserts_secret_references_in_arguments_literally() {
+    // GIVEN: a caller argument naming a secret store reference
+    let executor = CapabilityExecutor::new();
+    let params = serde_json::json!({"q": "{vault.secret/prod/db#password}", "n": "{q}"});
+
+    // WHEN: substituting it into a query template
+    let result = executor
+        .substitute_string("search?q={q}&n={n}", &params)
+        .unwrap();
+
+    // THEN: the reference is sent as typed, never resolved or re-substituted
+    assert_eq!(result, "search?q={vault.secret/prod/db#password}&n={q}");
+}
+
+#[test]
+fn substitute_string_finds_placeholders_inside_braces() {
+    let executor = CapabilityExecutor::new();
+    let params = serde_json::json!({"query": "rust"});
+
+    let result = executor
+        .substitute_string(r#"{"q": "{query}", "x": "{missing}"}"#, &params)
+        .unwrap();
+
+    assert_eq!(result, r#"{"q": "rust", "x": "{missing}"}"#);
+}
+
 #[test]
 fn test_extract_path() {
     let executor = CapabilityExecutor::new();
@@ -625,6 +652,46 @@ fn build_url_with_static_params_substitution_in_endpoint() {
     );
     assert!(url.contains("version=2"), "URL should contain version=2");
 }
+#[test]
+fn templated_body_fields_are_left_out_when_not_provided() {
+    // GIVEN: a JSON body with a conditional field and a loop
+    let executor = CapabilityExecutor::new();
+    let body = serde_json::json!({
+        "query": "{q}",
+        "filter": "{% if lang %}{\"lang\": \"{{ lang }}\"}{% endif %}",
+        "tags": "[{% for t in tags %}\"{{ t | lower }}\"{% if not loop.last %},{% endif %}{% endfor %}]",
+    });
+
+    // WHEN: substituting without `lang`
+    let rendered = executor
+        .substitute_value(&body, &serde_json::json!({"q": "rust", "tags": ["A", "B"]}))
+        .unwrap();
+
+    // THEN: `filter` is omitted and `tags` is a JSON array
+    assert_eq!(
+        rendered,
+        serde_json::json!({"query": "rust", "tags": ["a", "b"]})
+    );
+}
+
+#[test]
+fn templated_query_params_render_and_skip_empty_results() {
+    let executor = CapabilityExecutor::new();
+    let mut template = std::collections::HashMap::new();
+    template.insert(
+        "since".to_string(),
+        "{% if since %}{{ since }}{% endif %}".to_string(),
+    );
+    template.insert("where".to_string(), "{{ where | tojson }}".to_string());
+
+    let params = serde_json::json!({"where": {"state": "open"}});
+    let result = executor.substitute_params(&template, &params).unwrap();
+
+    assert_eq!(
+        result,
+        vec![("where".to_string(), r#"{"state":"open"}"#.to_string())]
+    );
+}
 
 // ── body_content_type tests ──────────────────────────────────────────────────
 
@@ -817,6 +884,119 @@ body: "SELECT * FROM bus_msg LIMIT 10"
     );
 }
 
+// ── XML / SOAP ────────────────────────────────────────────────────────────────
+
+#[test]
+fn substitute_xml_escapes_argument_values() {
+    // GIVEN: a SOAP body template and an argument carrying markup
+    let executor = CapabilityExecutor::new();
+    let template =
+        "<m:GetRate><m:Currency>{currency}</m:Currency><m:Note>{missing}</m:Note></m:GetRate>";
+
+    // WHEN: substituting
+    let body = executor
+        .substitute_xml(
+            template,
+            &serde_json::json!({"currency": "USD</m:Currency><x>&"}),
+        )
+        .unwrap();
+
+    // THEN: the value is escaped in place and unknown placeholders stay
+    assert_eq!(
+        body,
+        "<m:GetRate><m:Currency>USD&lt;/m:Currency&gt;&lt;x&gt;&amp;</m:Currency><m:Note>{missing}</m:Note></m:GetRate>"
+    );
+}
+
+#[test]
+fn soap_service_defaults_to_xml_post() {
+    let provider: crate::capability::ProviderConfig = serde_yaml::from_str(
+        r"
+service: soap
+config:
+  endpoint: https://rates.example.com/soap
+  soap_action: urn:rates#GetRate
+  body: '<Envelope/>'
+  response_xpath: //Rate
+",
+    )
+    .unwrap();
+    let proto = provider.protocol_config();
+    let rest = proto.as_rest().unwrap();
+    assert_eq!(rest.method, "POST");
+    assert_eq!(rest.body_content_type, "text/xml; charset=utf-8");
+    assert_eq!(rest.response_format, "xml");
+    assert_eq!(rest.soap_action.as_deref(), Some("urn:rates#GetRate"));
+}
+
+#[tokio::test]
+async fn handle_response_applies_xpath_and_surfaces_soap_faults() {
+    async fn rates() -> AxumResponse {
+        AxumResponse::builder()
+            .status(200)
+            .header(header::CONTENT_TYPE, "text/xml; charset=utf-8")
+            .body(Body::from(
+                r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>
+                    <GetRatesResponse><Rate currency="USD">1.05</Rate><Rate currency="GBP">0.85</Rate></GetRatesResponse>
+                </s:Body></s:Envelope>"#,
+            ))
+            .unwrap()
+    }
+    async fn fault() -> AxumResponse {
+        AxumResponse::builder()
+            .status(500)
+            .header(header::CONTENT_TYPE, "text/xml")
+            .body(Body::from(
+                r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><s:Fault>
+                    <faultcode>s:Client</faultcode><faultstring>Unknown currency</faultstring>
+                </s:Fault></s:Body></s:Envelope>"#,
+            ))
+            .unwrap()
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new()
+                .route("/rates", get(rates))
+                .route("/fault", get(fault)),
+        )
+        .await
+        .unwrap();
+    });
+
+    let executor = CapabilityExecutor::new();
+    let config = RestConfig {
+        response_xpath: Some("//Rate[@currency='GBP']/text()".to_string()),
+        ..Default::default()
+    };
+
+    let response = executor
+        .client
+        .get(format!("http://{addr}/rates"))
+        .send()
+        .await
+        .unwrap();
+    let body = executor.handle_response(response, &config).await.unwrap();
+    assert_eq!(body, serde_json::json!("0.85"));
+
+    let response = executor
+        .client
+        .get(format!("http://{addr}/fault"))
+        .send()
+        .await
+        .unwrap();
+    let err = executor
+        .handle_response(response, &config)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("SOAP fault"), "{err}");
+    assert!(err.contains("s:Client: Unknown currency"), "{err}");
+}
+
 #[tokio::test]
 async fn send_with_retry_recovers_from_transient_timeouts() {
     use std::io::{Read, Write};
@@ -942,3 +1122,488 @@ async fn send_with_retry_does_not_retry_timeouts_when_not_idempotent() {
         "a non-idempotent timeout must NOT be retried (single attempt)"
     );
 }
+
+// ── Pagination ────────────────────────────────────────────────────────────────
+
+async fn spawn_paged_server() -> std::net::SocketAddr {
+    use axum::extract::Query;
+    use std::collections::HashMap;
+
+    // Three pages: cursors in the body, and the same pages as Link headers.
+    async fn items(Query(query): Query<HashMap<String, String>>) -> AxumResponse {
+        let page: u32 = query
+            .get("cursor")
+            .or_else(|| query.get("page"))
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(1);
+        let next = (page < 3).then(|| (page + 1).to_string());
+        let body = serde_json::json!({
+            "data": {"items": [page * 10, page * 10 + 1]},
+            "meta": {"next_cursor": next},
+        });
+        let mut response = AxumResponse::builder().header(header::CONTENT_TYPE, "application/json");
+        if let Some(next) = &next {
+            response = response.header(header::LINK, format!("</items?page={next}>; rel=\"next\""));
+        }
+        response.body(Body::from(body.to_string())).unwrap()
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, Router::new().route("/items", get(items)))
+            .await
+            .unwrap();
+    });
+    addr
+}
+
+#[tokio::test]
+async fn pagination_follows_cursors_and_merges_items() {
+    // GIVEN: a three-page API and a cursor pagination config
+    let addr = spawn_paged_server().await;
+    let executor = CapabilityExecutor::new();
+    let pagination = crate::capability::PaginationConfig {
+        cursor_path: Some("meta.next_cursor".to_string()),
+        merge_path: Some("data.items".to_string()),
+        ..Default::default()
+    };
+    let config = RestConfig {
+        response_path: Some("data.items".to_string()),
+        ..Default::default()
+    };
+
+    // WHEN: executing the first request
+    let request = executor.client.get(format!("http://{addr}/items"));
+    let items = executor
+        .execute_paginated(
+            request,
+            &config,
+            &pagination,
+            true,
+            &CapabilityExecutionContext::default(),
+        )
+        .await
+        .unwrap();
+
+    // THEN: every page's items are merged in order
+    assert_eq!(items, serde_json::json!([10, 11, 20, 21, 30, 31]));
+}
+
+#[tokio::test]
+async fn pagination_follows_link_headers_up_to_max_pages() {
+    let addr = spawn_paged_server().await;
+    let executor = CapabilityExecutor::new();
+    let pagination = crate::capability::PaginationConfig {
+        strategy: crate::capability::PaginationStrategy::LinkHeader,
+        merge_path: Some("data.items".to_string()),
+        max_pages: 2,
+        ..Default::default()
+    };
+    let config = RestConfig::default();
+    let context = CapabilityExecutionContext::default().with_isolated_loopback_egress();
+
+    let request = executor.client.get(format!("http://{addr}/items"));
+    let body = executor
+        .execute_paginated(request, &config, &pagination, true, &context)
+        .await
+        .unwrap();
+
+    assert_eq!(body["data"]["items"], serde_json::json!([10, 11, 20, 21]));
+}
+
+// ── Provider failover ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn failing_primary_fails_over_and_is_marked_unhealthy() {
+    use axum::http::StatusCode;
+
+    async fn down() -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+    async fn up() -> Json<serde_json::Value> {
+        Json(serde_json::json!({"served_by": "backup"}))
+    }
+
+    // GIVEN: a primary that always returns 503 and a healthy fallback
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new()
+                .route("/down", get(down))
+                .route("/up", get(up)),
+        )
+        .await
+        .unwrap();
+    });
+    let cap = crate::capability::parse_capability(&format!(
+        r"
+name: flaky
+description: Flaky lookup
+providers:
+  primary:
+    service: rest
+    config:
+      base_url: http://{addr}
+      path: /down
+  fallback:
+    - service: rest
+      config:
+        base_url: http://{addr}
+        path: /up
+"
+    ))
+    .unwrap();
+    let executor = CapabilityExecutor::new();
+
+    // WHEN: calling three times
+    for _ in 0..3 {
+        let context = CapabilityExecutionContext::default().with_isolated_loopback_egress();
+        let result = executor
+            .execute_with_context(&cap, serde_json::json!({}), context)
+            .await
+            .unwrap();
+
+        // THEN: every call is answered by the fallback
+        assert_eq!(result["served_by"], "backup");
+    }
+    // AND: the primary is reported unhealthy after three failures
+    assert_eq!(executor.unhealthy_providers(), vec!["flaky/primary"]);
+}
+
+#[tokio::test]
+async fn failing_mutating_primary_does_not_fail_over() {
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    async fn down() -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+
+    // GIVEN: a POST primary that returns 503 and a fallback counting calls
+    let fallback_hits = Arc::new(AtomicU32::new(0));
+    let hits = Arc::clone(&fallback_hits);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new().route("/down", post(down)).route(
+                "/up",
+                post(move || async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    Json(serde_json::json!({"served_by": "backup"}))
+                }),
+            ),
+        )
+        .await
+        .unwrap();
+    });
+    let cap = crate::capability::parse_capability(&format!(
+        r"
+name: create_order
+description: Create an order
+providers:
+  primary:
+    service: rest
+    config:
+      base_url: http://{addr}
+      path: /down
+      method: POST
+  fallback:
+    - service: rest
+      config:
+        base_url: http://{addr}
+        path: /up
+        method: POST
+"
+    ))
+    .unwrap();
+    let executor = CapabilityExecutor::new();
+
+    // WHEN: calling once
+    let context = CapabilityExecutionContext::default().with_isolated_loopback_egress();
+    let result = executor
+        .execute_with_context(&cap, serde_json::json!({}), context)
+        .await;
+
+    // THEN: the primary's 503 is returned and the fallback is never called,
+    // since the primary may already have created the order
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("503"), "{err}");
+    assert_eq!(fallback_hits.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn unreachable_mutating_primary_fails_over() {
+    use axum::routing::post;
+
+    // GIVEN: a POST primary whose port refuses connections, and a fallback
+    let refused = {
+        let l = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        l.local_addr().unwrap()
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new().route(
+                "/up",
+                post(|| async { Json(serde_json::json!({"served_by": "backup"})) }),
+            ),
+        )
+        .await
+        .unwrap();
+    });
+    let cap = crate::capability::parse_capability(&format!(
+        r"
+name: create_order
+description: Create an order
+providers:
+  primary:
+    service: rest
+    config:
+      base_url: http://{refused}
+      path: /down
+      method: POST
+  fallback:
+    - service: rest
+      config:
+        base_url: http://{addr}
+        path: /up
+        method: POST
+"
+    ))
+    .unwrap();
+    let executor = CapabilityExecutor::new();
+
+    // WHEN: calling once
+    let context = CapabilityExecutionContext::default().with_isolated_loopback_egress();
+    let result = executor
+        .execute_with_context(&cap, serde_json::json!({}), context)
+        .await
+        .unwrap();
+
+    // THEN: the request never reached the primary, so the fallback answers
+    assert_eq!(result["served_by"], "backup");
+}
+
+#[tokio::test]
+async fn mutating_primary_declared_idempotent_fails_over() {
+    use axum::http::StatusCode;
+    use axum::routing::put;
+
+    async fn down() -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+
+    // GIVEN: a PUT capability declared idempotent, whose primary returns 503
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new().route("/down", put(down)).route(
+                "/up",
+                put(|| async { Json(serde_json::json!({"served_by": "backup"})) }),
+            ),
+        )
+        .await
+        .unwrap();
+    });
+    let cap = crate::capability::parse_capability(&format!(
+        r"
+name: upsert_order
+description: Create or replace an order
+metadata:
+  idempotent: true
+providers:
+  primary:
+    service: rest
+    config:
+      base_url: http://{addr}
+      path: /down
+      method: PUT
+  fallback:
+    - service: rest
+      config:
+        base_url: http://{addr}
+        path: /up
+        method: PUT
+"
+    ))
+    .unwrap();
+    let executor = CapabilityExecutor::new();
+
+    // WHEN: calling once
+    let context = CapabilityExecutionContext::default().with_isolated_loopback_egress();
+    let result = executor
+        .execute_with_context(&cap, serde_json::json!({}), context)
+        .await
+        .unwrap();
+
+    // THEN: repeating the call is safe, so the fallback answers
+    assert_eq!(result["served_by"], "backup");
+}
+
+// ── Resilience ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn resilience_retries_5xx_then_enforces_the_upstream_rate_limit() {
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    // Fails the first two requests, then answers with the request count.
+    async fn flaky(State(hits): State<Arc<AtomicU32>>) -> AxumResponse {
+        let hit = hits.fetch_add(1, Ordering::SeqCst) + 1;
+        let (status, body) = if hit <= 2 {
+            (StatusCode::BAD_GATEWAY, serde_json::json!({}))
+        } else {
+            (StatusCode::OK, serde_json::json!({"hit": hit}))
+        };
+        AxumResponse::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    // GIVEN: an upstream failing twice, two retries and a 4/hour budget
+    let hits = Arc::new(AtomicU32::new(0));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let state = Arc::clone(&hits);
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new().route("/flaky", get(flaky)).with_state(state),
+        )
+        .await
+        .unwrap();
+    });
+    let cap = crate::capability::parse_capability(&format!(
+        r"
+name: flaky_limited
+description: Flaky, rate-limited lookup
+resilience:
+  retries: 2
+  backoff_ms: 10
+  timeout: 10
+  rate_limit: 4/hour
+providers:
+  primary:
+    service: rest
+    config:
+      base_url: http://{addr}
+      path: /flaky
+"
+    ))
+    .unwrap();
+    let executor = CapabilityExecutor::new();
+    let call = || {
+        executor.execute_with_context(
+            &cap,
+            serde_json::json!({}),
+            CapabilityExecutionContext::default().with_isolated_loopback_egress(),
+        )
+    };
+
+    // WHEN: calling once
+    let result = call().await.unwrap();
+
+    // THEN: the third attempt answers
+    assert_eq!(result["hit"], 3);
+
+    // WHEN: calling twice more
+    call().await.unwrap();
+    let err = call().await.unwrap_err().to_string();
+
+    // THEN: the fifth upstream request is refused before it is sent
+    assert!(err.contains("Upstream rate limit"), "{err}");
+    assert_eq!(hits.load(Ordering::SeqCst), 4);
+}
+
+// ── Streaming responses ───────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn streaming_response_reports_partial_text_as_progress() {
+    use crate::capability::{ResponseStreamConfig, StreamFormat};
+    use crate::gateway::progress::{self, ProgressReporter};
+    use crate::gateway::streaming::NotificationMultiplexer;
+    use std::sync::Arc;
+
+    async fn completions() -> AxumResponse {
+        let body = concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        AxumResponse::builder()
+            .header(header::CONTENT_TYPE, "text/event-stream")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    // GIVEN: an SSE completion endpoint and a caller that asked for progress
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new().route("/complete", post(completions)),
+        )
+        .await
+        .unwrap();
+    });
+    let multiplexer = Arc::new(NotificationMultiplexer::new(
+        Arc::new(crate::backend::BackendRegistry::new()),
+        crate::config::StreamingConfig::default(),
+    ));
+    let (session_id, mut rx) = multiplexer.get_or_create_session(Some("stream-session"));
+    let reporter = ProgressReporter::new(multiplexer, session_id, serde_json::json!(1));
+
+    let executor = CapabilityExecutor::new();
+    let stream = ResponseStreamConfig {
+        format: StreamFormat::Sse,
+        text_path: Some("choices.0.delta.content".to_string()),
+        partial: true,
+        ..Default::default()
+    };
+    let config = RestConfig {
+        stream: Some(stream.clone()),
+        ..Default::default()
+    };
+
+    // WHEN: executing the streaming request inside the progress scope
+    let request = executor.client.post(format!("http://{addr}/complete"));
+    let result = progress::with_progress(
+        Some(reporter),
+        executor.execute_streaming(
+            request,
+            &config,
+            &stream,
+            std::time::Duration::from_secs(5),
+            false,
+        ),
+    )
+    .await
+    .unwrap();
+
+    // THEN: the text is assembled and each delta was sent as progress
+    assert_eq!(
+        result,
+        serde_json::json!({"text": "Hello", "events": 2, "truncated": false})
+    );
+    let first = rx.recv().await.unwrap();
+    assert_eq!(first.data["params"]["message"], "Hel");
+    let second = rx.recv().await.unwrap();
+    assert_eq!(second.data["params"]["progress"], 2);
+    assert_eq!(second.data["params"]["message"], "lo");
+}
@@ -0,0 +1,288 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Offline capability test harness
+//!
+//! Runs a capability against mocked upstream responses (see
+//! [`HttpMock`]) instead of the live API, so capability tests cost nothing
+//! and do not flake in CI. Backs `mcp-gateway cap test --mock` and
+//! `--suite`.
+//!
+//! # Files
+//!
+//! A responses file lists canned responses; the first one matching a
+//! request's method and URL answers it:
+//!
+//! ```yaml
+//! responses:
+//!   - method: GET
+//!     url: https://api.example.com/v1/weather   # `*` suffix = prefix match
+//!     status: 200
+//!     body: { temp: 21 }
+//! ```
+//!
+//! A suite file holds several cases for one capability. Each case runs on a
+//! fresh executor; its own `responses` are tried before the suite's. Every
+//! `expect.requests` entry must match at least one rendered request, and
+//! `result` must be contained in the result (objects may have extra keys):
+//!
+//! ```yaml
+//! responses: [...]
+//! cases:
+//!   - name: current weather
+//!     args: { city: Helsinki }
+//!     expect:
+//!       requests:
+//!         - url: https://api.example.com/v1/weather?q=Helsinki
+//!           headers: { accept: application/json }
+//!       result: { temp: 21 }
+//!   - name: upstream error surfaces
+//!     args: { city: Nowhere }
+//!     responses:
+//!       - url: https://api.example.com/*
+//!         status: 404
+//!     expect:
+//!       error: "404"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::executor::mock::{HttpMock, MockResponse, RecordedRequest, url_matches};
+use super::{CapabilityDefinition, CapabilityExecutor};
+use crate::{Error, Result};
+
+/// Canned responses file (`cap test --mock`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MockResponses {
+    /// Responses, tried in order.
+    #[serde(default)]
+    pub responses: Vec<MockResponse>,
+}
+
+/// Test-suite file (`cap test --suite`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TestSuite {
+    /// Responses shared by every case, tried after the case's own.
+    #[serde(default)]
+    pub responses: Vec<MockResponse>,
+    /// Test cases, run in order.
+    pub cases: Vec<TestCase>,
+}
+
+/// One invocation of the capability and what it should do.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestCase {
+    /// Name shown in the report.
+    pub name: String,
+    /// Arguments passed to the capability.
+    #[serde(default = "empty_object")]
+    pub args: Value,
+    /// Responses for this case, tried before the suite's.
+    #[serde(default)]
+    pub responses: Vec<MockResponse>,
+    /// Assertions.
+    #[serde(default)]
+    pub expect: Expectation,
+}
+
+fn empty_object() -> Value {
+    Value::Object(serde_json::Map::new())
+}
+
+/// Assertions on one case.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Expectation {
+    /// Requests the capability must send.
+    #[serde(default)]
+    pub requests: Vec<RequestExpectation>,
+    /// Value the result must contain.
+    #[serde(default)]
+    pub result: Option<Value>,
+    /// Text the error must contain; the case must fail when set.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A request the capability must send. Unset fields match anything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequestExpectation {
+    /// HTTP method.
+    #[serde(default)]
+    pub method: Option<String>,
+    /// URL pattern, as for [`MockResponse::url`].
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Headers that must be present with these values (names are
+    /// case-insensitive).
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Value the request body must contain.
+    #[serde(default)]
+    pub body: Option<Value>,
+}
+
+impl RequestExpectation {
+    fn matches(&self, request: &RecordedRequest) -> bool {
+        self.method
+            .as_ref()
+            .is_none_or(|m| m.eq_ignore_ascii_case(&request.method))
+            && self
+                .url
+                .as_ref()
+                .is_none_or(|url| url_matches(url, &request.url))
+            && self
+                .headers
+                .iter()
+                .all(|(name, value)| request.headers.get(&name.to_ascii_lowercase()) == Some(value))
+            && self.body.as_ref().is_none_or(|body| {
+                request
+                    .body
+                    .as_ref()
+                    .is_some_and(|actual| json_contains(actual, body))
+            })
+    }
+}
+
+/// Outcome of one [`TestCase`].
+#[derive(Debug, Clone)]
+pub struct CaseOutcome {
+    /// Case name.
+    pub name: String,
+    /// Capability result, or the error message.
+    pub result: std::result::Result<Value, String>,
+    /// Requests the capability sent.
+    pub requests: Vec<RecordedRequest>,
+    /// Failed assertions; empty when the case passed.
+    pub failures: Vec<String>,
+}
+
+impl CaseOutcome {
+    /// Whether every assertion held.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Read a YAML file of type `T`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed.
+pub fn load_yaml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("Failed to read {}: {e}", path.display())))?;
+    serde_yaml::from_str(&content)
+        .map_err(|e| Error::Config(format!("Failed to parse {}: {e}", path.display())))
+}
+
+/// Execute `capability` with `args`, answering its requests from
+/// `responses`. Returns the result and the requests sent.
+pub async fn run_mocked(
+    capability: &CapabilityDefinition,
+    args: Value,
+    responses: Vec<MockResponse>,
+) -> (Result<Value>, Vec<RecordedRequest>) {
+    let mock = Arc::new(HttpMock::new(responses));
+    let executor = CapabilityExecutor::new().with_mock(Arc::clone(&mock));
+    let result = executor.execute(capability, args).await;
+    (result, mock.requests())
+}
+
+/// Run one case; `shared` responses are tried after the case's own.
+pub async fn run_case(
+    capability: &CapabilityDefinition,
+    case: &TestCase,
+    shared: &[MockResponse],
+) -> CaseOutcome {
+    let responses = case.responses.iter().chain(shared).cloned().collect();
+    let (result, requests) = run_mocked(capability, case.args.clone(), responses).await;
+    let result = result.map_err(|e| e.to_string());
+    let failures = check_expectation(&case.expect, &result, &requests);
+    CaseOutcome {
+        name: case.name.clone(),
+        result,
+        requests,
+        failures,
+    }
+}
+
+/// Run every case of `suite`; `extra` responses are tried after the
+/// suite's own.
+pub async fn run_suite(
+    capability: &CapabilityDefinition,
+    suite: &TestSuite,
+    extra: &[MockResponse],
+) -> Vec<CaseOutcome> {
+    let shared: Vec<MockResponse> = suite.responses.iter().chain(extra).cloned().collect();
+    let mut outcomes = Vec::with_capacity(suite.cases.len());
+    for case in &suite.cases {
+        outcomes.push(run_case(capability, case, &shared).await);
+    }
+    outcomes
+}
+
+fn check_expectation(
+    expect: &Expectation,
+    result: &std::result::Result<Value, String>,
+    requests: &[RecordedRequest],
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    match (result, &expect.error) {
+        (Ok(_), Some(expected)) => {
+            failures.push(format!(
+                "expected an error containing '{expected}', got success"
+            ));
+        }
+        (Err(error), Some(expected)) if !error.contains(expected.as_str()) => {
+            failures.push(format!(
+                "expected an error containing '{expected}', got: {error}"
+            ));
+        }
+        (Err(error), None) => failures.push(format!("execution failed: {error}")),
+        _ => {}
+    }
+    if let (Ok(actual), Some(expected)) = (result, &expect.result)
+        && !json_contains(actual, expected)
+    {
+        failures.push(format!("result {actual} does not contain {expected}"));
+    }
+    for (i, expected) in expect.requests.iter().enumerate() {
+        if !requests.iter().any(|request| expected.matches(request)) {
+            failures.push(format!(
+                "no request matched expectation #{} ({} {})",
+                i + 1,
+                expected.method.as_deref().unwrap_or("*"),
+                expected.url.as_deref().unwrap_or("*"),
+            ));
+        }
+    }
+    failures
+}
+
+/// Whether `actual` contains `expected`: objects may have extra keys,
+/// arrays must have the same length with each element contained, and other
+/// values must be equal.
+#[must_use]
+pub fn json_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| json_contains(a, value))),
+        (Value::Array(actual), Value::Array(expected)) => {
+            actual.len() == expected.len()
+                && actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(a, e)| json_contains(a, e))
+        }
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests;
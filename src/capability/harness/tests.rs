@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Unit tests for the capability test harness.
+
+use serde_json::json;
+
+use super::*;
+use crate::capability::parse_capability;
+
+const CREATE_ISSUE: &str = r#"
+name: create_issue
+description: Create an issue.
+schema:
+  input:
+    type: object
+    properties:
+      title: { type: string }
+providers:
+  primary:
+    service: rest
+    config:
+      base_url: https://api.example.com
+      path: /v1/issues
+      method: POST
+      headers:
+        X-Api-Version: "2"
+      body:
+        title: "{title}"
+        labels: [triage]
+"#;
+
+const SUITE: &str = r#"
+responses:
+  - method: POST
+    url: https://api.example.com/v1/issues
+    status: 201
+    body: { id: 7, title: Broken build, url: "https://example.com/7" }
+cases:
+  - name: creates the issue
+    args: { title: Broken build }
+    expect:
+      requests:
+        - method: POST
+          url: https://api.example.com/v1/issues
+          headers: { X-API-Version: "2" }
+          body: { title: Broken build, labels: [triage] }
+      result: { id: 7 }
+  - name: upstream rejection is an error
+    args: { title: Duplicate }
+    responses:
+      - url: https://api.example.com/*
+        status: 422
+        body: { message: duplicate }
+    expect:
+      error: "422"
+  - name: wrong expectation fails
+    args: { title: Other }
+    expect:
+      requests:
+        - body: { title: Something else }
+      result: { id: 8 }
+"#;
+
+#[tokio::test]
+async fn suite_cases_run_against_mocked_responses() {
+    // GIVEN: a POST capability and a suite of three cases
+    let cap = parse_capability(CREATE_ISSUE).unwrap();
+    let suite: TestSuite = serde_yaml::from_str(SUITE).unwrap();
+
+    // WHEN: running the suite
+    let outcomes = run_suite(&cap, &suite, &[]).await;
+
+    // THEN: the rendered request and result match in the first case
+    assert_eq!(outcomes.len(), 3);
+    assert!(outcomes[0].passed(), "{:?}", outcomes[0]);
+    assert_eq!(outcomes[0].requests.len(), 1);
+    assert_eq!(outcomes[0].requests[0].headers["x-api-version"], "2");
+
+    // AND: the case's own response wins over the suite's
+    assert!(outcomes[1].passed(), "{:?}", outcomes[1]);
+    assert!(outcomes[1].result.is_err());
+
+    // AND: unmet request and result expectations are both reported
+    assert_eq!(outcomes[2].failures.len(), 2, "{:?}", outcomes[2]);
+}
+
+#[tokio::test]
+async fn unmatched_request_fails_without_network() {
+    // GIVEN: no mocked response for the capability's URL
+    let cap = parse_capability(CREATE_ISSUE).unwrap();
+    let responses = vec![MockResponse {
+        url: "https://other.example.com/*".to_string(),
+        ..MockResponse::default()
+    }];
+
+    // WHEN: executing it
+    let (result, requests) = run_mocked(&cap, json!({"title": "x"}), responses).await;
+
+    // THEN: the call fails naming the request, which is still recorded
+    let err = result.unwrap_err().to_string();
+    assert!(
+        err.contains("No mock response for POST https://api.example.com/v1/issues"),
+        "{err}"
+    );
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].body,
+        Some(json!({"title": "x", "labels": ["triage"]}))
+    );
+}
+
+#[test]
+fn url_patterns_and_json_containment() {
+    assert!(url_matches("https://a.test/v1", "https://a.test/v1?q=1"));
+    assert!(!url_matches(
+        "https://a.test/v1?q=2",
+        "https://a.test/v1?q=1"
+    ));
+    assert!(url_matches("https://a.test/*", "https://a.test/v1/x"));
+
+    let actual = json!({"a": 1, "b": {"c": [1, {"d": 2, "e": 3}]}});
+    assert!(json_contains(&actual, &json!({"b": {"c": [1, {"d": 2}]}})));
+    assert!(!json_contains(&actual, &json!({"b": {"c": [1]}})));
+    assert!(!json_contains(&actual, &json!({"a": 2})));
+}
@@ -7,12 +7,32 @@ use super::{
     validate_capability_definition,
 };
 use crate::{Error, Result};
-use std::path::Path;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
 /// Loader for capability definitions from directories
 pub struct CapabilityLoader;
 
+/// Capability files found in one directory tree, with the files that were
+/// skipped.
+#[derive(Debug, Default)]
+pub struct DirectoryLoad {
+    /// Each capability that loaded, with the file it came from.
+    pub loaded: Vec<(PathBuf, CapabilityDefinition)>,
+    /// Files that failed to parse or validate.
+    pub failures: Vec<LoadFailure>,
+}
+
+/// A capability file that could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoadFailure {
+    /// Path of the file.
+    pub path: PathBuf,
+    /// Why the file was skipped.
+    pub error: String,
+}
+
 impl CapabilityLoader {
     /// Load all capabilities from a directory (recursive)
     ///
@@ -27,6 +47,20 @@ impl CapabilityLoader {
     ///
     /// Returns an error if the directory does not exist or is not a valid directory.
     pub async fn load_directory(path: &str) -> Result<Vec<CapabilityDefinition>> {
+        let load = Self::load_directory_with_failures(path).await?;
+        Ok(load.loaded.into_iter().map(|(_, cap)| cap).collect())
+    }
+
+    /// Load all capabilities from a directory (recursive), keeping the path
+    /// of each loaded file and reporting the files that failed.
+    ///
+    /// Used by hot-reload, which keeps the last good definition of a file
+    /// that no longer loads.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory does not exist or is not a valid directory.
+    pub async fn load_directory_with_failures(path: &str) -> Result<DirectoryLoad> {
         let path = Path::new(path);
 
         if !path.exists() {
@@ -43,26 +77,24 @@ impl CapabilityLoader {
             )));
         }
 
-        let mut capabilities = Vec::new();
-        Self::load_directory_recursive(path, &mut capabilities).await?;
+        let mut load = DirectoryLoad::default();
+        Self::load_directory_recursive(path, &mut load).await?;
 
-        let unpinned = count_unpinned(&capabilities);
+        let unpinned = count_unpinned(load.loaded.iter().map(|(_, cap)| cap));
         info!(
-            count = capabilities.len(),
+            count = load.loaded.len(),
             unpinned,
+            failed = load.failures.len(),
             path = %path.display(),
             "Loaded capabilities: {} loaded, {unpinned} unpinned",
-            capabilities.len(),
+            load.loaded.len(),
         );
 
-        Ok(capabilities)
+        Ok(load)
     }
 
     /// Recursively load capabilities from a directory
-    async fn load_directory_recursive(
-        dir: &Path,
-        capabilities: &mut Vec<CapabilityDefinition>,
-    ) -> Result<()> {
+    async fn load_directory_recursive(dir: &Path, load: &mut DirectoryLoad) -> Result<()> {
         let mut entries = tokio::fs::read_dir(dir).await.map_err(|e| {
             Error::Config(format!("Failed to read directory {}: {e}", dir.display()))
         })?;
@@ -88,7 +120,7 @@ impl CapabilityLoader {
 
             if path.is_dir() {
                 // Recurse into subdirectories
-                Box::pin(Self::load_directory_recursive(&path, capabilities)).await?;
+                Box::pin(Self::load_directory_recursive(&path, load)).await?;
             } else if path
                 .extension()
                 .is_some_and(|ext| ext == "yaml" || ext == "yml")
@@ -97,10 +129,14 @@ impl CapabilityLoader {
                 match Self::load_capability_file(&path).await {
                     Ok(cap) => {
                         debug!(name = %cap.name, path = %path.display(), "Loaded capability");
-                        capabilities.push(cap);
+                        load.loaded.push((path, cap));
                     }
                     Err(e) => {
                         warn!(error = %e, path = %path.display(), "Failed to load capability");
+                        load.failures.push(LoadFailure {
+                            path,
+                            error: e.to_string(),
+                        });
                     }
                 }
             }
@@ -186,8 +222,11 @@ impl CapabilityLoader {
 /// directory-level `N loaded, M unpinned` summary log so an operator can see
 /// catalog pin coverage at a glance instead of scanning one INFO line per
 /// unpinned file (MIK-6742).
-fn count_unpinned(capabilities: &[CapabilityDefinition]) -> usize {
-    capabilities.iter().filter(|c| c.sha256.is_none()).count()
+fn count_unpinned<'a>(capabilities: impl IntoIterator<Item = &'a CapabilityDefinition>) -> usize {
+    capabilities
+        .into_iter()
+        .filter(|c| c.sha256.is_none())
+        .count()
 }
 
 #[cfg(test)]
@@ -32,22 +32,29 @@
 //! The executor injects credentials at runtime, so they never appear in
 //! logs, error messages, or MCP responses.
 
+mod asyncapi;
 mod backend;
 mod definition;
 #[cfg(feature = "discovery")]
 pub mod discovery;
 mod execution_context;
 mod executor;
+pub mod harness;
 pub mod hash;
 mod loader;
 mod openapi;
 mod parser;
 mod response_cache;
 mod schema_validator;
+pub mod template;
 pub mod validator;
 mod watcher;
 
-pub use backend::{CapabilityBackend, CapabilityBackendStatus, RugPullRecord};
+pub use asyncapi::AsyncApiConverter;
+pub use backend::{
+    CapabilityBackend, CapabilityBackendStatus, CapabilityReloadReport, RugPullRecord,
+    ToolsChangedHook,
+};
 pub use definition::ProtocolConfig;
 pub use definition::*;
 #[cfg(feature = "discovery")]
@@ -60,9 +67,11 @@ pub(crate) use execution_context::{
 pub use executor::CapabilityExecutor;
 pub use executor::graphql::GraphqlExecutor;
 pub use executor::jsonrpc::JsonRpcExecutor;
+pub use executor::mock::{HttpMock, MockResponse, RecordedRequest};
 pub use executor::rest::{ExecutionContext, ProtocolExecutor};
+pub(crate) use executor::{AwsCredentialCache, sign_aws_request};
 pub use hash::{compute_capability_hash, rewrite_with_pin, strip_sha256_line};
-pub use loader::CapabilityLoader;
+pub use loader::{CapabilityLoader, DirectoryLoad, LoadFailure};
 pub use openapi::{AuthTemplate, CacheTemplate, GeneratedCapability, OpenApiConverter};
 pub use parser::{parse_capability, parse_capability_file, validate_capability};
 pub use schema_validator::{
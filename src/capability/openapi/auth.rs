@@ -6,6 +6,7 @@
 //! internal `ResolvedAuth` used while emitting capability YAML.
 
 use super::model::OpenApiSecurityScheme;
+use crate::capability::OAUTH2_CLIENT_CREDENTIALS;
 
 /// Auth scheme resolved from an `OpenAPI` `securitySchemes` entry.
 ///
@@ -15,7 +16,8 @@ use super::model::OpenApiSecurityScheme;
 /// human-readable description pointing at the credential env var.
 #[derive(Debug, Clone)]
 pub(crate) struct ResolvedAuth {
-    /// mcp-gateway auth type (`bearer`, `api_key`, `oauth`, `basic`).
+    /// mcp-gateway auth type (`bearer`, `api_key`, `oauth`, `basic`,
+    /// `oauth2_client_credentials`).
     pub(crate) auth_type: String,
     /// Credential reference (e.g. `env:MYAPI_TOKEN`). Overridden by the
     /// `--auth-key` CLI flag via `with_default_auth`.
@@ -29,6 +31,17 @@ pub(crate) struct ResolvedAuth {
     pub(crate) query_param: Option<String>,
     /// Header prefix (e.g. `Bearer`).
     pub(crate) prefix: Option<String>,
+    /// Token endpoint, client ID reference and scopes of an
+    /// `oauth2_client_credentials` scheme.
+    pub(crate) client_credentials: Option<ClientCredentials>,
+}
+
+/// Settings of the `OAuth2` client credentials flow.
+#[derive(Debug, Clone)]
+pub(crate) struct ClientCredentials {
+    pub(crate) token_endpoint: String,
+    pub(crate) client_id: String,
+    pub(crate) scopes: Vec<String>,
 }
 
 impl ResolvedAuth {
@@ -40,6 +53,7 @@ impl ResolvedAuth {
             header: Some("Authorization".to_string()),
             query_param: None,
             prefix: Some("Bearer".to_string()),
+            client_credentials: None,
         }
     }
 
@@ -61,6 +75,7 @@ impl ResolvedAuth {
             header: header_name,
             query_param: query_name,
             prefix: None,
+            client_credentials: None,
         }
     }
 
@@ -72,6 +87,74 @@ impl ResolvedAuth {
             header: Some("Authorization".to_string()),
             query_param: None,
             prefix: Some("Bearer".to_string()),
+            client_credentials: None,
+        }
+    }
+
+    /// Client credentials grant against `token_url`; the client ID and
+    /// secret are read from `<SCHEME>_CLIENT_ID` and `<SCHEME>_CLIENT_SECRET`.
+    pub(crate) fn client_credentials(
+        scheme_name: &str,
+        token_url: &str,
+        scopes: &[String],
+    ) -> Self {
+        let env_prefix = env_prefix_from_scheme(scheme_name);
+        Self {
+            auth_type: OAUTH2_CLIENT_CREDENTIALS.to_string(),
+            key: format!("env:{env_prefix}_CLIENT_SECRET"),
+            description: format!("OAuth2 client credentials for security scheme '{scheme_name}'"),
+            header: Some("Authorization".to_string()),
+            query_param: None,
+            prefix: Some("Bearer".to_string()),
+            client_credentials: Some(ClientCredentials {
+                token_endpoint: token_url.to_string(),
+                client_id: format!("env:{env_prefix}_CLIENT_ID"),
+                scopes: scopes.to_vec(),
+            }),
+        }
+    }
+
+    /// Auth for `scheme` as named by a security requirement granting
+    /// `scopes`, or `None` when the gateway cannot supply it (cookie API
+    /// keys, unknown types).
+    pub(crate) fn from_scheme(
+        scheme_name: &str,
+        scheme: &OpenApiSecurityScheme,
+        scopes: &[String],
+    ) -> Option<Self> {
+        match scheme.scheme_type.to_ascii_lowercase().as_str() {
+            "http" => match scheme
+                .scheme
+                .as_deref()
+                .map(str::to_ascii_lowercase)
+                .as_deref()
+            {
+                Some("bearer") => Some(Self::bearer(scheme_name)),
+                Some("basic") => Some(Self::basic(scheme_name)),
+                _ => None,
+            },
+            "apikey"
+                if scheme
+                    .location
+                    .as_deref()
+                    .is_some_and(|l| l.eq_ignore_ascii_case("cookie")) =>
+            {
+                None
+            }
+            "apikey" => Some(Self::api_key(scheme_name, scheme)),
+            "oauth2" => Some(
+                match scheme
+                    .flows
+                    .as_ref()
+                    .and_then(|flows| flows.client_credentials.as_ref())
+                    .filter(|flow| !flow.token_url.is_empty())
+                {
+                    Some(flow) => Self::client_credentials(scheme_name, &flow.token_url, scopes),
+                    None => Self::oauth2(scheme_name),
+                },
+            ),
+            "openidconnect" => Some(Self::oauth2(scheme_name)),
+            _ => None,
         }
     }
 
@@ -83,6 +166,7 @@ impl ResolvedAuth {
             header: Some("Authorization".to_string()),
             query_param: None,
             prefix: Some("Basic".to_string()),
+            client_credentials: None,
         }
     }
 }
@@ -90,6 +174,11 @@ impl ResolvedAuth {
 /// Convert an `OpenAPI` security scheme name into a conventional environment
 /// variable name. For example, `petstore_auth` → `PETSTORE_AUTH_TOKEN`.
 pub(crate) fn env_var_from_scheme(scheme_name: &str) -> String {
+    format!("{}_TOKEN", env_prefix_from_scheme(scheme_name))
+}
+
+/// Uppercase environment variable prefix of a security scheme name.
+fn env_prefix_from_scheme(scheme_name: &str) -> String {
     let upper: String = scheme_name
         .chars()
         .map(|c| {
@@ -100,7 +189,7 @@ pub(crate) fn env_var_from_scheme(scheme_name: &str) -> String {
             }
         })
         .collect();
-    format!("{}_TOKEN", upper.trim_matches('_'))
+    upper.trim_matches('_').to_string()
 }
 
 /// Lowercase slug form of an `OpenAPI` security scheme name, used as the
@@ -19,11 +19,12 @@ use crate::{Error, Result};
 use super::auth::ResolvedAuth;
 use super::generated::{AuthTemplate, CacheTemplate, GeneratedCapability};
 use super::model::{
-    OpenApiComponents, OpenApiOperation, OpenApiParameter, OpenApiRequestBody, OpenApiResponse,
-    OpenApiSpec,
+    OpenApiComponents, OpenApiMediaType, OpenApiOperation, OpenApiParameter, OpenApiPathItem,
+    OpenApiRequestBody, OpenApiResponse, OpenApiServer, OpenApiSpec, SecurityRequirements,
 };
-use super::refs::{resolve_parameter, resolve_request_body, resolve_schema_refs};
+use super::refs::{Documents, INLINE_SPEC_URL, parse_document};
 use super::sanitize::{sanitize_description, yaml_scalar};
+use super::schema::flatten_schema;
 
 /// `OpenAPI` to Capability converter
 pub struct OpenApiConverter {
@@ -89,33 +90,74 @@ impl OpenApiConverter {
 
     /// Convert an `OpenAPI` spec file to capabilities
     ///
+    /// `$ref`s into other local files (e.g. `schemas.yaml#/Pet`) are
+    /// resolved relative to the spec file; refs to remote documents are
+    /// left unresolved.
+    ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be read or the spec cannot be parsed.
     pub fn convert_file(&self, path: &str) -> Result<Vec<GeneratedCapability>> {
-        let content = fs::read_to_string(path)
+        let read = |path: &std::path::Path| {
+            fs::read_to_string(path)
+                .map_err(|e| Error::Config(format!("Failed to read OpenAPI spec: {e}")))
+        };
+        let path = fs::canonicalize(path)
             .map_err(|e| Error::Config(format!("Failed to read OpenAPI spec: {e}")))?;
+        let root = url::Url::from_file_path(&path)
+            .map_err(|()| Error::Config(format!("Invalid spec path {}", path.display())))?;
+        let mut documents = Documents::new(root, parse_document(&read(&path)?)?);
+
+        loop {
+            let missing = documents.missing();
+            if missing.is_empty() {
+                break;
+            }
+            for url in missing {
+                let document = match url.to_file_path() {
+                    Ok(path) if url.scheme() == "file" => {
+                        read(&path).and_then(|content| parse_document(&content))
+                    }
+                    _ => Err(Error::Config(
+                        "only local files are loaded for a spec file".to_string(),
+                    )),
+                };
+                documents.add(&url, document);
+            }
+        }
 
-        self.convert_string(&content)
+        self.convert_documents(&documents)
     }
 
     /// Convert an `OpenAPI` spec string to capabilities
     ///
+    /// Only `$ref`s within the string itself can be resolved.
+    ///
     /// # Errors
     ///
     /// Returns an error if the content cannot be parsed as YAML or JSON.
     pub fn convert_string(&self, content: &str) -> Result<Vec<GeneratedCapability>> {
-        // Try JSON first (most specs are served as JSON); fall back to YAML.
-        let spec: OpenApiSpec = serde_json::from_str(content)
-            .or_else(|_| serde_yaml::from_str(content))
-            .map_err(|e| Error::Config(format!("Failed to parse OpenAPI spec: {e}")))?;
+        let root = url::Url::parse(INLINE_SPEC_URL)
+            .map_err(|e| Error::Internal(format!("Invalid inline spec URL: {e}")))?;
+        let mut documents = Documents::new(root, parse_document(content)?);
+        for url in documents.missing() {
+            documents.add(
+                &url,
+                Err(Error::Config(
+                    "a spec given as a string cannot refer to other documents".to_string(),
+                )),
+            );
+        }
 
-        self.convert_spec(&spec)
+        self.convert_documents(&documents)
     }
 
     /// Fetch an `OpenAPI` spec from `url`, parse it, and convert it to
     /// capabilities.
     ///
+    /// `$ref`s to other documents are fetched the same way, relative to the
+    /// document they appear in; only `http`/`https` documents are loaded.
+    ///
     /// The host portion of `url` is automatically set as the converter's
     /// [`with_host_override`] so that relative `servers` entries (such as
     /// Petstore's `/api/v3`) resolve to absolute URLs in the generated
@@ -152,7 +194,7 @@ impl OpenApiConverter {
             }
         }
 
-        let response = reqwest::Client::builder()
+        let client = reqwest::Client::builder()
             .user_agent(format!("mcp-gateway/{}", env!("CARGO_PKG_VERSION")))
             .dns_resolver(PinningResolver::new(SystemResolver))
             .redirect(reqwest::redirect::Policy::custom(
@@ -164,25 +206,51 @@ impl OpenApiConverter {
                 },
             ))
             .build()
-            .map_err(|e| Error::Config(format!("Failed to build HTTP client: {e}")))?
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| Error::Config(format!("Failed to fetch OpenAPI spec: {e}")))?;
-
-        if !response.status().is_success() {
-            return Err(Error::Config(format!(
-                "OpenAPI spec fetch failed: HTTP {}",
-                response.status()
-            )));
+            .map_err(|e| Error::Config(format!("Failed to build HTTP client: {e}")))?;
+
+        let body = fetch_spec(&client, url).await?;
+        let mut documents = Documents::new(parsed, parse_document(&body)?);
+        loop {
+            let missing = documents.missing();
+            if missing.is_empty() {
+                break;
+            }
+            for url in missing {
+                let document = if matches!(url.scheme(), "http" | "https") {
+                    match validate_url_not_ssrf(url.as_str()) {
+                        Ok(()) => fetch_spec(&client, url.as_str())
+                            .await
+                            .and_then(|body| parse_document(&body)),
+                        Err(e) => Err(e),
+                    }
+                } else {
+                    Err(Error::Config(
+                        "only http(s) documents are loaded for a spec URL".to_string(),
+                    ))
+                };
+                documents.add(&url, document);
+            }
         }
 
-        let body = response
-            .text()
-            .await
-            .map_err(|e| Error::Config(format!("Failed to read OpenAPI spec body: {e}")))?;
+        self.convert_documents(&documents)
+    }
 
-        self.convert_string(&body)
+    /// Dereference the spec held by `documents` and convert it.
+    fn convert_documents(&self, documents: &Documents) -> Result<Vec<GeneratedCapability>> {
+        // Only the parts the converter reads are dereferenced; inlining all
+        // of `components` would only cost time.
+        let root = documents.root();
+        let mut spec = root.clone();
+        if let Some(paths) = root.get("paths") {
+            spec["paths"] = documents.dereference(paths);
+        }
+        if let Some(schemes) = root.pointer("/components/securitySchemes") {
+            spec["components"]["securitySchemes"] = documents.dereference(schemes);
+        }
+        let spec: OpenApiSpec = serde_json::from_value(spec)
+            .map_err(|e| Error::Config(format!("Failed to parse OpenAPI spec: {e}")))?;
+
+        self.convert_spec(&spec)
     }
 
     /// Convert a parsed `OpenAPI` spec to capabilities
@@ -198,34 +266,35 @@ impl OpenApiConverter {
         let empty_components = OpenApiComponents::default();
         let components = spec.components.as_ref().unwrap_or(&empty_components);
 
-        // Get base URL. If the servers block is missing or its URL is relative
-        // (e.g. "/api/v3"), fall back to the override host supplied on the
-        // converter (set automatically when importing from a URL) or a
-        // placeholder the operator must edit before deploying.
-        let raw_base = spec
-            .servers
-            .as_ref()
-            .and_then(|s| s.first())
-            .map(|s| s.url.clone());
-        let base_url = Self::resolve_base_url(raw_base.as_deref(), self.host_override.as_deref());
-
-        // Detect the best-match security scheme.
-        let auth_scheme = self.pick_security_scheme(components);
-        let auth_required = auth_scheme.is_some();
+        // Fallback when no security requirement names a usable scheme.
+        let default_scheme = self.pick_security_scheme(components);
 
         let mut capabilities = Vec::new();
 
-        for (path, methods) in &spec.paths {
-            for (method, operation) in methods {
-                if !Self::is_http_method(method) {
-                    continue;
-                }
+        for (path, item) in &spec.paths {
+            for (method, operation) in item.operations() {
+                // Servers: the operation's, the path's, then the spec's. If
+                // the servers block is missing or its URL is relative (e.g.
+                // "/api/v3"), fall back to the override host supplied on the
+                // converter (set automatically when importing from a URL) or
+                // a placeholder the operator must edit before deploying.
+                let raw_base = [&operation.servers, &item.servers, &spec.servers]
+                    .into_iter()
+                    .find_map(|servers| servers.as_ref().and_then(|s| s.first()))
+                    .map(OpenApiServer::expanded_url);
+                let base_url =
+                    Self::resolve_base_url(raw_base.as_deref(), self.host_override.as_deref());
+                let auth_scheme = Self::operation_auth(
+                    operation.security.as_ref().or(spec.security.as_ref()),
+                    components,
+                    default_scheme.as_ref(),
+                );
                 match self.convert_operation(
                     &base_url,
                     path,
                     method,
+                    item,
                     operation,
-                    components,
                     auth_scheme.as_ref(),
                 ) {
                     Ok(cap) => capabilities.push(cap),
@@ -241,20 +310,39 @@ impl OpenApiConverter {
 
         info!(
             count = capabilities.len(),
-            auth = auth_required,
+            auth = !components.security_schemes.is_empty(),
             "Generated capabilities"
         );
         Ok(capabilities)
     }
 
-    /// True if `method` is one of the standard HTTP operation verbs used by
-    /// `OpenAPI`. Any other key under a path (e.g. `parameters`, `summary`,
-    /// `servers`) is ignored at conversion time.
-    fn is_http_method(method: &str) -> bool {
-        matches!(
-            method.to_ascii_lowercase().as_str(),
-            "get" | "post" | "put" | "patch" | "delete" | "head" | "options" | "trace"
-        )
+    /// Auth of an operation with security `requirements` (its own or the
+    /// spec's).
+    ///
+    /// The first alternative naming a single scheme the gateway can supply
+    /// wins. An empty list, or only the anonymous `{}` alternative, means no
+    /// auth. Without requirements, or when none can be supplied,
+    /// `default_scheme` applies.
+    fn operation_auth(
+        requirements: Option<&SecurityRequirements>,
+        components: &OpenApiComponents,
+        default_scheme: Option<&ResolvedAuth>,
+    ) -> Option<ResolvedAuth> {
+        let Some(requirements) = requirements else {
+            return default_scheme.cloned();
+        };
+        if requirements.iter().all(HashMap::is_empty) {
+            return None;
+        }
+        requirements
+            .iter()
+            .filter(|requirement| requirement.len() == 1)
+            .flat_map(HashMap::iter)
+            .find_map(|(name, scopes)| {
+                let scheme = components.security_schemes.get(name)?;
+                ResolvedAuth::from_scheme(name, scheme, scopes)
+            })
+            .or_else(|| default_scheme.cloned())
     }
 
     /// Pick a representative security scheme from `components.securitySchemes`.
@@ -281,10 +369,10 @@ impl OpenApiConverter {
                 return Some(ResolvedAuth::api_key(name, scheme));
             }
         }
-        // Then oauth2.
+        // Then oauth2 (client credentials when the scheme offers that flow).
         for (name, scheme) in schemes {
             if scheme.scheme_type.eq_ignore_ascii_case("oauth2") {
-                return Some(ResolvedAuth::oauth2(name));
+                return ResolvedAuth::from_scheme(name, scheme, &[]);
             }
         }
         // Finally basic.
@@ -327,8 +415,8 @@ impl OpenApiConverter {
         base_url: &str,
         path: &str,
         method: &str,
+        item: &OpenApiPathItem,
         op: &OpenApiOperation,
-        components: &OpenApiComponents,
         auth_scheme: Option<&ResolvedAuth>,
     ) -> Result<GeneratedCapability> {
         // Generate capability name: prefer operationId, otherwise synthesise
@@ -353,22 +441,24 @@ impl OpenApiConverter {
             .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
         let description = sanitize_description(&raw_description);
 
-        // Resolve $refs on every parameter before schema generation.
-        let resolved_params: Vec<OpenApiParameter> = op
+        // Path-level parameters apply unless the operation redefines them
+        // (same name and location). Unresolved refs leave a nameless entry,
+        // which is dropped.
+        let resolved_params: Vec<OpenApiParameter> = item
             .parameters
             .iter()
-            .filter_map(|p| resolve_parameter(p, components))
+            .filter(|shared| {
+                !op.parameters
+                    .iter()
+                    .any(|p| p.name == shared.name && p.location == shared.location)
+            })
+            .chain(&op.parameters)
+            .filter(|p| !p.name.is_empty())
+            .cloned()
             .collect();
 
-        // Resolve $ref on the request body (if any).
-        let resolved_body = op
-            .request_body
-            .as_ref()
-            .and_then(|b| resolve_request_body(b, components));
-
-        // Build input schema from resolved parameters and request body.
-        let input_schema =
-            self.build_input_schema(&resolved_params, resolved_body.as_ref(), components);
+        // Build input schema from parameters and request body.
+        let input_schema = self.build_input_schema(&resolved_params, op.request_body.as_ref());
 
         // Build output schema from responses
         let output_schema = self.build_output_schema(&op.responses);
@@ -432,7 +522,6 @@ impl OpenApiConverter {
         &self,
         params: &[OpenApiParameter],
         body: Option<&OpenApiRequestBody>,
-        components: &OpenApiComponents,
     ) -> Value {
         let mut properties = serde_json::Map::new();
         let mut required = Vec::new();
@@ -444,9 +533,8 @@ impl OpenApiConverter {
             }
             let schema = param
                 .schema
-                .clone()
-                .map(|s| resolve_schema_refs(&s, components))
-                .unwrap_or(serde_json::json!({"type": "string"}));
+                .as_ref()
+                .map_or_else(|| serde_json::json!({"type": "string"}), flatten_schema);
             let mut prop = if schema.is_object() {
                 schema.as_object().cloned().unwrap_or_default()
             } else {
@@ -467,18 +555,16 @@ impl OpenApiConverter {
             }
         }
 
-        // Add request body properties (simplified — assumes object type).
-        // Prefer application/json, then the first entry, then form types.
+        // Add request body properties (compositions flattened into one
+        // object). Prefer JSON, then form types, then the first entry.
         if let Some(body) = body {
-            let media = body
-                .content
-                .get("application/json")
+            let media = json_media(&body.content)
                 .or_else(|| body.content.get("application/x-www-form-urlencoded"))
                 .or_else(|| body.content.values().next());
             if let Some(media) = media
                 && let Some(ref raw_schema) = media.schema
             {
-                let schema = resolve_schema_refs(raw_schema, components);
+                let schema = flatten_schema(raw_schema);
                 if let Some(body_props) = schema.get("properties").and_then(|p| p.as_object()) {
                     for (k, v) in body_props {
                         properties.entry(k.clone()).or_insert_with(|| v.clone());
@@ -511,18 +597,23 @@ impl OpenApiConverter {
     /// Build output schema from responses
     #[allow(clippy::unused_self)]
     fn build_output_schema(&self, responses: &HashMap<String, OpenApiResponse>) -> Value {
-        // Look for 200 or 2xx response
-        let response = responses
-            .get("200")
-            .or_else(|| responses.get("201"))
+        // Look for 200, then any 2xx response, then the default one.
+        let mut success: Vec<_> = responses
+            .iter()
+            .filter(|(code, _)| code.starts_with('2'))
+            .collect();
+        success.sort_by(|a, b| a.0.cmp(b.0));
+        let response = success
+            .first()
+            .map(|(_, resp)| *resp)
             .or_else(|| responses.get("default"));
 
         if let Some(resp) = response
             && let Some(ref content) = resp.content
-            && let Some(media) = content.get("application/json")
+            && let Some(media) = json_media(content)
             && let Some(ref schema) = media.schema
         {
-            return schema.clone();
+            return flatten_schema(schema);
         }
 
         // Default: any object
@@ -660,6 +751,20 @@ impl OpenApiConverter {
                 };
             let _ = writeln!(yaml, "  type: {auth_type}");
             let _ = writeln!(yaml, "  key: {key}");
+            if auth_type == auth.auth_type
+                && let Some(flow) = &auth.client_credentials
+            {
+                let _ = writeln!(
+                    yaml,
+                    "  token_endpoint: {}",
+                    yaml_scalar(&flow.token_endpoint)
+                );
+                let _ = writeln!(yaml, "  client_id: {}", flow.client_id);
+                if !flow.scopes.is_empty() {
+                    let scopes: Vec<String> = flow.scopes.iter().map(|s| yaml_scalar(s)).collect();
+                    let _ = writeln!(yaml, "  scopes: [{}]", scopes.join(", "));
+                }
+            }
             let _ = writeln!(yaml, "  description: {}", yaml_scalar(&description));
             if let Some(h) = header {
                 let _ = writeln!(yaml, "  header: {h}");
@@ -690,6 +795,44 @@ impl OpenApiConverter {
     }
 }
 
+/// The JSON entry of a `content` map: `application/json`, else any
+/// `application/*+json` type.
+fn json_media(content: &HashMap<String, OpenApiMediaType>) -> Option<&OpenApiMediaType> {
+    content.get("application/json").or_else(|| {
+        let mut types: Vec<_> = content
+            .iter()
+            .filter(|(media_type, _)| {
+                let media_type = media_type.split(';').next().unwrap_or_default();
+                media_type == "application/json"
+                    || (media_type.starts_with("application/") && media_type.ends_with("+json"))
+            })
+            .collect();
+        types.sort_by(|a, b| a.0.cmp(b.0));
+        types.first().map(|(_, media)| *media)
+    })
+}
+
+/// GET `url` and return the body of a successful response.
+async fn fetch_spec(client: &reqwest::Client, url: &str) -> Result<String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| Error::Config(format!("Failed to fetch OpenAPI spec: {e}")))?;
+
+    if !response.status().is_success() {
+        return Err(Error::Config(format!(
+            "OpenAPI spec fetch failed: HTTP {}",
+            response.status()
+        )));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| Error::Config(format!("Failed to read OpenAPI spec body: {e}")))
+}
+
 impl Default for OpenApiConverter {
     fn default() -> Self {
         Self::new()
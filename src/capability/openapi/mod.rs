@@ -3,7 +3,8 @@
 //! `OpenAPI` to Capability converter
 //!
 //! Generates capability YAML definitions from `OpenAPI` specifications.
-//! Supports `OpenAPI` 3.0 and 3.1.
+//! Supports `OpenAPI` 3.0 and 3.1, including `$ref`s into other documents,
+//! `allOf`/`oneOf`/`anyOf` schemas, per-operation servers and security.
 //!
 //! # Usage
 //!
@@ -23,9 +24,11 @@ mod generated;
 
 mod model;
 
-mod refs;
+pub(super) mod refs;
 
-mod sanitize;
+pub(super) mod sanitize;
+
+pub(super) mod schema;
 
 pub use convert::OpenApiConverter;
 
@@ -3,13 +3,17 @@
 //! Parsed `OpenAPI` document model.
 //!
 //! Single responsibility: serde deserialization types mirroring the subset
-//! of the `OpenAPI` 3.0/3.1 schema the converter consumes.
+//! of the `OpenAPI` 3.0/3.1 schema the converter consumes. The converter
+//! dereferences `$ref`s before deserializing, so no type here holds one.
 
 use std::collections::HashMap;
 
 use serde::Deserialize;
 use serde_json::Value;
 
+/// Security requirements: alternatives, each naming schemes and scopes.
+pub(crate) type SecurityRequirements = Vec<HashMap<String, Vec<String>>>;
+
 /// Simplified `OpenAPI` spec structure (just what we need)
 #[derive(Debug, Deserialize)]
 pub(crate) struct OpenApiSpec {
@@ -17,8 +21,13 @@ pub(crate) struct OpenApiSpec {
     pub(crate) swagger: Option<String>,
     pub(crate) info: OpenApiInfo,
     pub(crate) servers: Option<Vec<OpenApiServer>>,
-    pub(crate) paths: HashMap<String, HashMap<String, OpenApiOperation>>,
+    /// Optional in 3.1, where a document may only hold webhooks or
+    /// components.
+    #[serde(default)]
+    pub(crate) paths: HashMap<String, OpenApiPathItem>,
     pub(crate) components: Option<OpenApiComponents>,
+    #[serde(default)]
+    pub(crate) security: Option<SecurityRequirements>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,12 +40,67 @@ pub(crate) struct OpenApiInfo {
     pub(crate) version: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 #[allow(dead_code)]
 pub(crate) struct OpenApiServer {
     pub(crate) url: String,
     #[serde(default)]
     pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) variables: HashMap<String, OpenApiServerVariable>,
+}
+
+impl OpenApiServer {
+    /// `url` with each `{variable}` replaced by its default.
+    pub(crate) fn expanded_url(&self) -> String {
+        self.variables
+            .iter()
+            .fold(self.url.clone(), |url, (name, variable)| {
+                url.replace(&format!("{{{name}}}"), &variable.default)
+            })
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct OpenApiServerVariable {
+    #[serde(default)]
+    pub(crate) default: String,
+}
+
+/// Operations of one path, with what they share.
+#[derive(Debug, Deserialize, Default)]
+pub(crate) struct OpenApiPathItem {
+    #[serde(default)]
+    pub(crate) parameters: Vec<OpenApiParameter>,
+    #[serde(default)]
+    pub(crate) servers: Option<Vec<OpenApiServer>>,
+    pub(crate) get: Option<OpenApiOperation>,
+    pub(crate) put: Option<OpenApiOperation>,
+    pub(crate) post: Option<OpenApiOperation>,
+    pub(crate) delete: Option<OpenApiOperation>,
+    pub(crate) options: Option<OpenApiOperation>,
+    pub(crate) head: Option<OpenApiOperation>,
+    pub(crate) patch: Option<OpenApiOperation>,
+    pub(crate) trace: Option<OpenApiOperation>,
+}
+
+impl OpenApiPathItem {
+    /// The operations present, with their lowercase HTTP method.
+    pub(crate) fn operations(&self) -> Vec<(&'static str, &OpenApiOperation)> {
+        [
+            ("get", &self.get),
+            ("put", &self.put),
+            ("post", &self.post),
+            ("delete", &self.delete),
+            ("options", &self.options),
+            ("head", &self.head),
+            ("patch", &self.patch),
+            ("trace", &self.trace),
+        ]
+        .into_iter()
+        .filter_map(|(method, op)| op.as_ref().map(|op| (method, op)))
+        .collect()
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -58,7 +122,9 @@ pub(crate) struct OpenApiOperation {
     #[serde(default)]
     pub(crate) tags: Vec<String>,
     #[serde(default)]
-    pub(crate) security: Option<Vec<HashMap<String, Vec<String>>>>,
+    pub(crate) security: Option<SecurityRequirements>,
+    #[serde(default)]
+    pub(crate) servers: Option<Vec<OpenApiServer>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -73,8 +139,6 @@ pub(crate) struct OpenApiParameter {
     pub(crate) description: Option<String>,
     #[serde(default)]
     pub(crate) schema: Option<Value>,
-    #[serde(default, rename = "$ref")]
-    pub(crate) reference: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -84,8 +148,6 @@ pub(crate) struct OpenApiRequestBody {
     pub(crate) required: bool,
     #[serde(default)]
     pub(crate) content: HashMap<String, OpenApiMediaType>,
-    #[serde(default, rename = "$ref")]
-    pub(crate) reference: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -105,17 +167,12 @@ pub(crate) struct OpenApiResponse {
 
 #[derive(Debug, Deserialize, Default)]
 pub(crate) struct OpenApiComponents {
-    #[serde(default)]
-    pub(crate) schemas: HashMap<String, Value>,
     #[serde(default, rename = "securitySchemes")]
     pub(crate) security_schemes: HashMap<String, OpenApiSecurityScheme>,
-    #[serde(default)]
-    pub(crate) parameters: HashMap<String, OpenApiParameter>,
-    #[serde(default, rename = "requestBodies")]
-    pub(crate) request_bodies: HashMap<String, OpenApiRequestBody>,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub(crate) struct OpenApiSecurityScheme {
     #[serde(rename = "type")]
@@ -126,4 +183,23 @@ pub(crate) struct OpenApiSecurityScheme {
     pub(crate) name: Option<String>,
     #[serde(rename = "in", default)]
     pub(crate) location: Option<String>,
+    #[serde(default)]
+    pub(crate) flows: Option<OpenApiOAuthFlows>,
+    #[serde(default)]
+    pub(crate) open_id_connect_url: Option<String>,
+}
+
+/// The `flows` of an `oauth2` scheme; only client credentials are used.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OpenApiOAuthFlows {
+    #[serde(default)]
+    pub(crate) client_credentials: Option<OpenApiOAuthFlow>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OpenApiOAuthFlow {
+    #[serde(default)]
+    pub(crate) token_url: String,
 }
@@ -1,98 +1,227 @@
 // SPDX-FileCopyrightText: 2026 Mikko Parkkola
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
-//! `OpenAPI` `$ref` resolution against the components section.
+//! `OpenAPI` `$ref` resolution.
 //!
-//! Single responsibility: dereference parameter, request-body, and schema
-//! `$ref` pointers into inline definitions.
+//! Single responsibility: hold the documents a spec consists of and replace
+//! `$ref` pointers with their targets. A `$ref` is a URL relative to the
+//! document it appears in, with a JSON Pointer fragment: `#/components/...`
+//! within the same document, `common.yaml#/...` or an absolute URL in
+//! another one. Refs are followed through any number of documents and
+//! nested refs; a cycle, or a chain deeper than [`MAX_REF_DEPTH`], is left
+//! as the `$ref` itself. The `AsyncAPI` importer uses the same resolution.
+
+use std::collections::{HashMap, HashSet};
 
 use serde_json::Value;
-use tracing::warn;
-
-use super::model::{OpenApiComponents, OpenApiParameter, OpenApiRequestBody};
-
-/// Resolve a parameter `$ref` against `components.parameters`. Non-ref
-/// parameters are returned unchanged. Refs that cannot be resolved are
-/// dropped (with a warning) so they do not end up in the generated schema
-/// with an empty name.
-pub(crate) fn resolve_parameter(
-    param: &OpenApiParameter,
-    components: &OpenApiComponents,
-) -> Option<OpenApiParameter> {
-    if let Some(ref reference) = param.reference {
-        let key = reference.trim_start_matches("#/components/parameters/");
-        if let Some(resolved) = components.parameters.get(key) {
-            Some(resolved.clone())
-        } else {
-            warn!(reference = %reference, "Unresolved parameter $ref");
-            None
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::{Error, Result};
+
+/// URL given to a spec converted from a string; its relative refs resolve
+/// to documents that can never be loaded.
+pub(crate) const INLINE_SPEC_URL: &str = "inline:///openapi";
+
+/// Most refs followed within one another.
+const MAX_REF_DEPTH: usize = 8;
+
+/// Most documents loaded for one spec (including the root).
+const MAX_DOCUMENTS: usize = 64;
+
+/// Parse a spec document as JSON or, failing that, YAML.
+///
+/// # Errors
+///
+/// Returns [`Error::Config`] when it is neither.
+pub(crate) fn parse_document(content: &str) -> Result<Value> {
+    serde_json::from_str(content)
+        .or_else(|_| serde_yaml::from_str(content))
+        .map_err(|e| Error::Config(format!("Failed to parse OpenAPI spec: {e}")))
+}
+
+/// The root document of a spec and the documents its refs point into.
+pub(crate) struct Documents {
+    root: Url,
+    loaded: HashMap<String, Value>,
+    unavailable: HashSet<String>,
+}
+
+impl Documents {
+    pub(crate) fn new(root: Url, document: Value) -> Self {
+        let mut loaded = HashMap::new();
+        loaded.insert(document_key(&root), document);
+        Self {
+            root,
+            loaded,
+            unavailable: HashSet::new(),
         }
-    } else if param.name.is_empty() {
-        None
-    } else {
-        Some(param.clone())
     }
-}
 
-/// Resolve a request body `$ref` against `components.requestBodies`.
-pub(crate) fn resolve_request_body(
-    body: &OpenApiRequestBody,
-    components: &OpenApiComponents,
-) -> Option<OpenApiRequestBody> {
-    if let Some(ref reference) = body.reference {
-        let key = reference.trim_start_matches("#/components/requestBodies/");
-        if let Some(resolved) = components.request_bodies.get(key) {
-            Some(resolved.clone())
-        } else {
-            warn!(reference = %reference, "Unresolved requestBody $ref");
-            None
+    pub(crate) fn root(&self) -> &Value {
+        &self.loaded[&document_key(&self.root)]
+    }
+
+    /// Documents referred to but neither loaded nor known to be unavailable.
+    pub(crate) fn missing(&self) -> Vec<Url> {
+        let mut missing = Vec::new();
+        let mut seen = HashSet::new();
+        for (key, document) in &self.loaded {
+            let Ok(base) = Url::parse(key) else {
+                continue;
+            };
+            collect_refs(document, &mut |reference| {
+                let Ok(target) = base.join(reference) else {
+                    return;
+                };
+                let key = document_key(&target);
+                if !self.loaded.contains_key(&key)
+                    && !self.unavailable.contains(&key)
+                    && seen.insert(key)
+                {
+                    let mut url = target;
+                    url.set_fragment(None);
+                    missing.push(url);
+                }
+            });
         }
-    } else {
-        Some(body.clone())
+        missing
+    }
+
+    /// Record the outcome of loading the document at `url`.
+    pub(crate) fn add(&mut self, url: &Url, document: Result<Value>) {
+        let key = document_key(url);
+        match document {
+            Ok(document) if self.loaded.len() < MAX_DOCUMENTS => {
+                debug!(url = %url, "Loaded referenced spec document");
+                self.loaded.insert(key, document);
+            }
+            Ok(_) => {
+                warn!(url = %url, limit = MAX_DOCUMENTS, "Too many spec documents; ref left unresolved");
+                self.unavailable.insert(key);
+            }
+            Err(e) => {
+                warn!(url = %url, error = %e, "Referenced spec document unavailable");
+                self.unavailable.insert(key);
+            }
+        }
+    }
+
+    /// `value`, taken from the root document, with every `$ref` replaced by
+    /// its target.
+    pub(crate) fn dereference(&self, value: &Value) -> Value {
+        self.dereference_in(value, &self.root, &mut Vec::new())
+    }
+
+    fn dereference_in(&self, value: &Value, base: &Url, chain: &mut Vec<String>) -> Value {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    return self.follow(reference, map, base, chain);
+                }
+                Value::Object(
+                    map.iter()
+                        .map(|(k, v)| (k.clone(), self.dereference_in(v, base, chain)))
+                        .collect(),
+                )
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.dereference_in(item, base, chain))
+                    .collect(),
+            ),
+            _ => value.clone(),
+        }
+    }
+
+    /// Target of `reference`, dereferenced in turn, with the ref object's
+    /// other keys (allowed next to `$ref` in 3.1) laid over it.
+    fn follow(
+        &self,
+        reference: &str,
+        ref_object: &serde_json::Map<String, Value>,
+        base: &Url,
+        chain: &mut Vec<String>,
+    ) -> Value {
+        let unresolved = || Value::Object(ref_object.clone());
+        let Ok(target) = base.join(reference) else {
+            warn!(reference = %reference, "Invalid $ref");
+            return unresolved();
+        };
+        let id = target.to_string();
+        if chain.contains(&id) || chain.len() >= MAX_REF_DEPTH {
+            debug!(reference = %reference, "Recursive or deeply nested $ref left in place");
+            return unresolved();
+        }
+        let pointer = target.fragment().map(decode_pointer).unwrap_or_default();
+        let Some(resolved) = self
+            .loaded
+            .get(&document_key(&target))
+            .and_then(|document| document.pointer(&pointer))
+        else {
+            warn!(reference = %reference, "Unresolved $ref");
+            return unresolved();
+        };
+
+        chain.push(id);
+        let mut resolved = self.dereference_in(resolved, &target, chain);
+        chain.pop();
+
+        if let Value::Object(resolved) = &mut resolved {
+            for (key, value) in ref_object {
+                if key != "$ref" {
+                    resolved.insert(key.clone(), self.dereference_in(value, base, chain));
+                }
+            }
+        }
+        resolved
     }
 }
 
-/// Recursively resolve `$ref` pointers inside a JSON Schema against
-/// `components.schemas`.
-///
-/// Only `#/components/schemas/<Name>` references are followed; unknown or
-/// external refs are left in place (they become a no-op at YAML emission
-/// time).  Recursion is bounded to 8 levels to prevent cycles.
-pub(crate) fn resolve_schema_refs(value: &Value, components: &OpenApiComponents) -> Value {
-    resolve_schema_refs_inner(value, components, 0)
+/// Identity of the document `url` points into.
+fn document_key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.to_string()
 }
 
-pub(crate) fn resolve_schema_refs_inner(
-    value: &Value,
-    components: &OpenApiComponents,
-    depth: u8,
-) -> Value {
-    const MAX_DEPTH: u8 = 8;
-    if depth >= MAX_DEPTH {
-        return value.clone();
+/// A URL fragment as a JSON Pointer: percent-decoded, `""` for the whole
+/// document.
+fn decode_pointer(fragment: &str) -> String {
+    let bytes = fragment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = fragment
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
     }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn collect_refs(value: &Value, found: &mut impl FnMut(&str)) {
     match value {
         Value::Object(map) => {
-            if let Some(Value::String(reference)) = map.get("$ref")
-                && let Some(key) = reference.strip_prefix("#/components/schemas/")
-                && let Some(target) = components.schemas.get(key)
-            {
-                return resolve_schema_refs_inner(target, components, depth + 1);
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                found(reference);
             }
-            let mut out = serde_json::Map::with_capacity(map.len());
-            for (k, v) in map {
-                out.insert(
-                    k.clone(),
-                    resolve_schema_refs_inner(v, components, depth + 1),
-                );
+            for value in map.values() {
+                collect_refs(value, found);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_refs(item, found);
             }
-            Value::Object(out)
         }
-        Value::Array(items) => Value::Array(
-            items
-                .iter()
-                .map(|i| resolve_schema_refs_inner(i, components, depth + 1))
-                .collect(),
-        ),
-        _ => value.clone(),
+        _ => {}
     }
 }
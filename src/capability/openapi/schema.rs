@@ -0,0 +1,179 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! JSON Schema simplification for generated tool schemas.
+//!
+//! Single responsibility: rewrite dereferenced `OpenAPI` schemas into the
+//! plain object/property shape tool inputs need:
+//!
+//! - `allOf` parts are merged into one schema;
+//! - `oneOf` / `anyOf` variants that are all objects are merged into one
+//!   object whose properties are the union and whose `required` is the
+//!   intersection (what every variant requires); a single non-null variant
+//!   replaces the composition;
+//! - 3.1 type lists drop `"null"` (`type: [string, "null"]` becomes
+//!   `type: string`), and `discriminator` is removed.
+
+use serde_json::{Map, Value};
+
+/// Simplified copy of `schema`.
+pub(crate) fn flatten_schema(schema: &Value) -> Value {
+    let Value::Object(map) = schema else {
+        return schema.clone();
+    };
+    let mut out = Map::with_capacity(map.len());
+    for (key, value) in map {
+        match key.as_str() {
+            "allOf" | "oneOf" | "anyOf" | "discriminator" => {}
+            "type" => {
+                if let Some(kind) = without_null(value) {
+                    out.insert(key.clone(), kind);
+                }
+            }
+            "properties" | "patternProperties" => {
+                let props: Option<Map<String, Value>> = value.as_object().map(|props| {
+                    props
+                        .iter()
+                        .map(|(name, prop)| (name.clone(), flatten_schema(prop)))
+                        .collect()
+                });
+                out.insert(
+                    key.clone(),
+                    props.map_or_else(|| value.clone(), Value::Object),
+                );
+            }
+            "items" | "additionalProperties" | "not" => {
+                out.insert(key.clone(), flatten_schema(value));
+            }
+            _ => {
+                out.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    if let Some(Value::Array(parts)) = map.get("allOf") {
+        for part in parts {
+            merge_into(&mut out, &flatten_schema(part), true);
+        }
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(Value::Array(variants)) = map.get(key) {
+            merge_variants(&mut out, key, variants);
+        }
+    }
+    Value::Object(out)
+}
+
+/// Merge `oneOf` / `anyOf` `variants` into `out`, or keep them as a
+/// (simplified) `anyOf` when they are not all objects.
+fn merge_variants(out: &mut Map<String, Value>, key: &str, variants: &[Value]) {
+    let variants: Vec<Value> = variants
+        .iter()
+        .map(flatten_schema)
+        .filter(|variant| variant.get("type").and_then(Value::as_str) != Some("null"))
+        .collect();
+    match variants.as_slice() {
+        [] => {}
+        [only] => merge_into(out, only, true),
+        _ if variants.iter().all(is_object_schema) => {
+            out.entry("type".to_string())
+                .or_insert_with(|| Value::String("object".to_string()));
+            for variant in &variants {
+                merge_into(out, variant, false);
+            }
+            let common = variants
+                .iter()
+                .map(required_of)
+                .reduce(|common, required| {
+                    common
+                        .into_iter()
+                        .filter(|r| required.contains(r))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let mut required = required_of(&Value::Object(out.clone()));
+            for name in common {
+                if !required.contains(&name) {
+                    required.push(name);
+                }
+            }
+            if !required.is_empty() {
+                out.insert("required".to_string(), Value::Array(required));
+            }
+        }
+        _ => {
+            let key = if key == "oneOf" { "anyOf" } else { key };
+            out.insert(key.to_string(), Value::Array(variants));
+        }
+    }
+}
+
+/// Lay `part` under `out`: keys `out` already has win, except that
+/// `properties` are united and, if `with_required`, so is `required`.
+fn merge_into(out: &mut Map<String, Value>, part: &Value, with_required: bool) {
+    let Value::Object(part) = part else {
+        return;
+    };
+    for (key, value) in part {
+        match key.as_str() {
+            "properties" => {
+                let Value::Object(props) = value else {
+                    continue;
+                };
+                let target = out
+                    .entry(key.clone())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if let Value::Object(target) = target {
+                    for (name, prop) in props {
+                        target.entry(name.clone()).or_insert_with(|| prop.clone());
+                    }
+                }
+            }
+            "required" => {
+                if !with_required {
+                    continue;
+                }
+                let mut required = required_of(&Value::Object(out.clone()));
+                for name in value.as_array().into_iter().flatten() {
+                    if !required.contains(name) {
+                        required.push(name.clone());
+                    }
+                }
+                out.insert(key.clone(), Value::Array(required));
+            }
+            _ => {
+                out.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+}
+
+fn is_object_schema(schema: &Value) -> bool {
+    schema.get("properties").is_some()
+        || schema.get("type").and_then(Value::as_str) == Some("object")
+}
+
+fn required_of(schema: &Value) -> Vec<Value> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// A `type` without `"null"`: the single remaining type, the remaining
+/// list, or nothing when only `"null"` was listed.
+fn without_null(kind: &Value) -> Option<Value> {
+    let Value::Array(kinds) = kind else {
+        return Some(kind.clone());
+    };
+    let kinds: Vec<Value> = kinds
+        .iter()
+        .filter(|k| k.as_str() != Some("null"))
+        .cloned()
+        .collect();
+    match kinds.len() {
+        0 => None,
+        1 => kinds.into_iter().next(),
+        _ => Some(Value::Array(kinds)),
+    }
+}
@@ -471,6 +471,250 @@ fn security_scheme_selection_prefers_bearer() {
     );
 }
 
+// ── OpenAPI 3.1, $ref resolution, compositions and security ─────────────
+
+const OPENAPI_31_FIXTURE: &str = r##"
+openapi: 3.1.0
+info: {title: Orders, version: "2"}
+servers:
+  - url: https://{region}.orders.example.com/v2
+    variables:
+      region: {default: eu}
+security:
+  - apiKey: []
+paths:
+  /orders/{orderId}:
+    summary: One order
+    parameters:
+      - $ref: "#/components/parameters/OrderId"
+    get:
+      operationId: getOrder
+      responses:
+        "200":
+          $ref: "#/components/responses/Order"
+    patch:
+      operationId: updateOrder
+      security:
+        - {}
+        - machine: [orders.write]
+      requestBody:
+        $ref: "#/components/requestBodies/OrderUpdate"
+      responses:
+        "204": {description: updated}
+  /health:
+    get:
+      operationId: health
+      security: []
+      servers:
+        - url: https://status.example.com
+      responses:
+        "200": {description: ok}
+components:
+  parameters:
+    OrderId:
+      name: orderId
+      in: path
+      required: true
+      schema: {$ref: "#/components/schemas/Id"}
+  schemas:
+    Id: {type: string, pattern: "^o_"}
+    Base:
+      type: object
+      required: [note]
+      properties:
+        note: {type: [string, "null"]}
+    Order:
+      allOf:
+        - $ref: "#/components/schemas/Base"
+        - type: object
+          properties:
+            id: {$ref: "#/components/schemas/Id"}
+            parent: {$ref: "#/components/schemas/Order"}
+  requestBodies:
+    OrderUpdate:
+      content:
+        application/merge-patch+json:
+          schema:
+            allOf:
+              - $ref: "#/components/schemas/Base"
+            oneOf:
+              - type: object
+                required: [status]
+                properties:
+                  status: {type: string}
+              - type: object
+                required: [status, reason]
+                properties:
+                  status: {type: string}
+                  reason: {type: string}
+  responses:
+    Order:
+      description: The order
+      content:
+        application/json:
+          schema: {$ref: "#/components/schemas/Order"}
+  securitySchemes:
+    apiKey: {type: apiKey, in: header, name: X-Api-Key}
+    machine:
+      type: oauth2
+      flows:
+        clientCredentials:
+          tokenUrl: https://auth.example.com/token
+          scopes: {orders.write: write orders}
+"##;
+
+fn parsed(caps: &[GeneratedCapability], name: &str) -> CapabilityDefinition {
+    parse_capability(&find_cap(caps, name).yaml).unwrap()
+}
+
+#[test]
+fn openapi_31_path_items_servers_and_nested_refs_convert() {
+    // GIVEN: a 3.1 spec with path-level parameters, server variables and
+    // refs that point at further refs
+    let caps = OpenApiConverter::new()
+        .convert_string(OPENAPI_31_FIXTURE)
+        .unwrap();
+
+    // THEN: every operation is converted, with the shared parameter
+    assert_eq!(names(&caps), vec!["getorder", "health", "updateorder"]);
+    let get = parsed(&caps, "getorder");
+    let provider = &get.providers.named["primary"];
+    assert_eq!(provider.config.base_url, "https://eu.orders.example.com/v2");
+    assert_eq!(get.schema.input["properties"]["orderId"]["pattern"], "^o_");
+    assert_eq!(get.schema.input["required"], serde_json::json!(["orderId"]));
+
+    // THEN: the response allOf is merged, the null type dropped, and the
+    // recursive ref left in place
+    let output = &get.schema.output;
+    assert_eq!(output["properties"]["note"]["type"], "string");
+    assert_eq!(output["properties"]["id"]["type"], "string");
+    assert_eq!(
+        output["properties"]["parent"]["$ref"],
+        "#/components/schemas/Order"
+    );
+
+    // THEN: per-operation servers override the spec's
+    let health = parsed(&caps, "health");
+    assert_eq!(
+        health.providers.named["primary"].config.base_url,
+        "https://status.example.com"
+    );
+}
+
+#[test]
+fn openapi_compositions_flatten_into_one_input_object() {
+    let caps = OpenApiConverter::new()
+        .convert_string(OPENAPI_31_FIXTURE)
+        .unwrap();
+    let input = parsed(&caps, "updateorder").schema.input;
+
+    let mut props: Vec<_> = input["properties"].as_object().unwrap().keys().collect();
+    props.sort();
+    assert_eq!(props, vec!["note", "orderId", "reason", "status"]);
+    // `status` is required by every oneOf variant, `reason` by only one.
+    let required = input["required"].as_array().unwrap();
+    assert!(required.contains(&serde_json::json!("status")));
+    assert!(!required.contains(&serde_json::json!("reason")));
+    assert!(required.contains(&serde_json::json!("note")));
+}
+
+#[test]
+fn operation_security_requirements_pick_the_auth_template() {
+    let caps = OpenApiConverter::new()
+        .convert_string(OPENAPI_31_FIXTURE)
+        .unwrap();
+
+    // Spec-level requirement: the API key header.
+    let get = parsed(&caps, "getorder");
+    assert_eq!(get.auth.auth_type, "api_key");
+    assert_eq!(get.auth.header.as_deref(), Some("X-Api-Key"));
+
+    // `security: []` disables auth.
+    assert!(!parsed(&caps, "health").auth.required);
+
+    // Client credentials flow with the requirement's scopes.
+    let update = parsed(&caps, "updateorder");
+    assert_eq!(
+        update.auth.auth_type,
+        crate::capability::OAUTH2_CLIENT_CREDENTIALS
+    );
+    assert_eq!(
+        update.auth.token_endpoint.as_deref(),
+        Some("https://auth.example.com/token")
+    );
+    assert_eq!(
+        update.auth.client_id.as_deref(),
+        Some("env:MACHINE_CLIENT_ID")
+    );
+    assert_eq!(update.auth.key, "env:MACHINE_CLIENT_SECRET");
+    assert_eq!(update.auth.scopes, vec!["orders.write"]);
+
+    for cap in &caps {
+        let issues = validate_capability_definition(&parsed(&caps, &cap.name), None);
+        let errors: Vec<_> = issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+            .collect();
+        assert!(errors.is_empty(), "{}: {errors:?}\n{}", cap.name, cap.yaml);
+    }
+}
+
+#[test]
+fn convert_file_resolves_refs_into_other_files() {
+    // GIVEN: a spec whose schema lives in a sibling file, which refers to a
+    // third one
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("api.yaml"),
+        r#"
+openapi: 3.1.0
+info: {title: Split, version: "1"}
+servers: [{url: "https://api.example.com"}]
+paths:
+  /pets:
+    post:
+      operationId: addPet
+      requestBody:
+        content:
+          application/json:
+            schema: {$ref: "schemas/pet.yaml#/Pet"}
+      responses: {"201": {description: created}}
+"#,
+    )
+    .unwrap();
+    std::fs::create_dir(dir.path().join("schemas")).unwrap();
+    std::fs::write(
+        dir.path().join("schemas/pet.yaml"),
+        r#"
+Pet:
+  type: object
+  required: [name]
+  properties:
+    name: {type: string}
+    owner: {$ref: "common.json#/Owner"}
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("schemas/common.json"),
+        r#"{"Owner": {"type": "object", "properties": {"email": {"type": "string"}}}}"#,
+    )
+    .unwrap();
+
+    // WHEN: converting the file
+    let caps = OpenApiConverter::new()
+        .convert_file(dir.path().join("api.yaml").to_str().unwrap())
+        .unwrap();
+
+    // THEN: both external refs are inlined
+    let input = parse_capability(&caps[0].yaml).unwrap().schema.input;
+    assert_eq!(input["required"], serde_json::json!(["name"]));
+    assert_eq!(
+        input["properties"]["owner"]["properties"]["email"]["type"],
+        "string"
+    );
+}
+
 fn names(caps: &[GeneratedCapability]) -> Vec<&str> {
     caps.iter().map(|c| c.name.as_str()).collect()
 }
@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Request templates with conditionals, loops and filters
+//!
+//! A request string (header, query parameter or body) that contains
+//! `{{ ... }}`, `{% ... %}` or `{# ... #}` is rendered with
+//! [minijinja](https://docs.rs/minijinja) instead of plain `{param}`
+//! substitution. The call arguments are the template variables; a missing
+//! argument is undefined (and falsy), so
+//!
+//! ```text
+//! {% if cursor %}{{ cursor }}{% endif %}
+//! ```
+//!
+//! renders nothing when `cursor` was not passed. Templates without these
+//! markers keep the `{param}` syntax.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use minijinja::{AutoEscape, Environment, UndefinedBehavior};
+use serde_json::Value;
+
+use crate::{Error, Result};
+
+/// Global functions and variables that are not call arguments.
+const BUILTIN_NAMES: &[&str] = &["range", "dict", "namespace", "debug", "loop"];
+
+static PLAIN: LazyLock<Environment<'static>> = LazyLock::new(|| environment(AutoEscape::None));
+static XML: LazyLock<Environment<'static>> = LazyLock::new(|| environment(AutoEscape::Html));
+
+/// Escaping applied to values a template writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    /// Values are written as they are.
+    None,
+    /// Values are escaped for XML text and attributes.
+    Xml,
+}
+
+fn environment(escape: AutoEscape) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Chainable);
+    env.set_auto_escape_callback(move |_| escape);
+    env
+}
+
+/// Whether `text` uses template syntax rather than `{param}` placeholders.
+#[must_use]
+pub fn is_template(text: &str) -> bool {
+    text.contains("{{") || text.contains("{%") || text.contains("{#")
+}
+
+/// Render `template` with the call arguments `params` as variables.
+///
+/// # Errors
+///
+/// Returns [`Error::Config`] when the template does not parse or fails to
+/// render.
+pub fn render(template: &str, params: &Value, escape: Escape) -> Result<String> {
+    let env = match escape {
+        Escape::None => &*PLAIN,
+        Escape::Xml => &*XML,
+    };
+    env.render_str(template, params)
+        .map_err(|e| Error::Config(format!("Invalid request template: {e}")))
+}
+
+/// Arguments `template` refers to, or the parse error.
+///
+/// # Errors
+///
+/// Returns the parser's message when the template is malformed.
+pub fn variables(template: &str) -> std::result::Result<HashSet<String>, String> {
+    let parsed = PLAIN
+        .template_from_str(template)
+        .map_err(|e| e.to_string())?;
+    let mut names = parsed.undeclared_variables(false);
+    names.retain(|name| !BUILTIN_NAMES.contains(&name.as_str()));
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn plain_placeholders_are_not_templates() {
+        assert!(!is_template("/users/{id}"));
+        assert!(!is_template("{env.API_KEY}"));
+        assert!(is_template("{{ id }}"));
+        assert!(is_template("{% if id %}x{% endif %}"));
+    }
+
+    #[test]
+    fn missing_arguments_are_falsy() {
+        // GIVEN: a field that is only written when its argument is given
+        let template = r#"{"q": "{{ q }}"{% if limit %}, "limit": {{ limit }}{% endif %}}"#;
+
+        // WHEN: rendering with and without the argument
+        let with = render(template, &json!({"q": "rust", "limit": 5}), Escape::None).unwrap();
+        let without = render(template, &json!({"q": "rust"}), Escape::None).unwrap();
+
+        // THEN: the field is present only when provided
+        assert_eq!(with, r#"{"q": "rust", "limit": 5}"#);
+        assert_eq!(without, r#"{"q": "rust"}"#);
+    }
+
+    #[test]
+    fn loops_and_filters_render() {
+        let rendered = render(
+            "{% for tag in tags %}{{ tag | upper }}{% if not loop.last %},{% endif %}{% endfor %}",
+            &json!({"tags": ["a", "b"]}),
+            Escape::None,
+        )
+        .unwrap();
+        assert_eq!(rendered, "A,B");
+        let rendered = render(
+            "{{ filter | tojson }}",
+            &json!({"filter": {"x": 1}}),
+            Escape::None,
+        )
+        .unwrap();
+        assert_eq!(rendered, r#"{"x":1}"#);
+    }
+
+    #[test]
+    fn xml_escape_covers_argument_values() {
+        let rendered =
+            render("<q>{{ q }}</q>", &json!({"q": "</q><admin/>"}), Escape::Xml).unwrap();
+        assert!(!rendered.contains("<admin/>"), "{rendered}");
+    }
+
+    #[test]
+    fn variables_lists_arguments_and_reports_syntax_errors() {
+        let names =
+            variables("{% for t in tags %}{{ t }}{% endfor %}{{ q | default('x') }}").unwrap();
+        assert_eq!(names, HashSet::from(["tags".to_string(), "q".to_string()]));
+        assert!(variables("{% if q %}unclosed").is_err());
+    }
+}
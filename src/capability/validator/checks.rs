@@ -7,7 +7,10 @@
 
 use std::collections::{HashMap, HashSet};
 
-use crate::capability::{CapabilityDefinition, PathSelectorConfig, RestConfig};
+use crate::capability::{
+    AWS_SIGV4, CapabilityDefinition, ExecConfig, OAUTH2_CLIENT_CREDENTIALS, PaginationConfig,
+    PaginationStrategy, PathSelectorConfig, ProviderStrategy, RestConfig, SqlConfig, template,
+};
 use crate::validator::schema_helpers;
 
 use super::Issue;
@@ -141,6 +144,7 @@ pub(super) fn check_schema_output(output: &serde_json::Value, issues: &mut Vec<I
 /// CAP-006: All `{param}` placeholders in URL/path must exist in `schema.input.properties`.
 /// CAP-007: `static_params` keys must not overlap with `params` keys.
 /// CAP-008: `base_url` must be a valid URL; `path` must start with `'/'`.
+/// CAP-015: `strategy: weighted` needs a provider with a non-zero `weight`.
 pub(super) fn check_providers(cap: &CapabilityDefinition, issues: &mut Vec<Issue>) {
     if cap.providers.is_empty() && cap.webhooks.is_empty() {
         issues.push(
@@ -150,6 +154,18 @@ pub(super) fn check_providers(cap: &CapabilityDefinition, issues: &mut Vec<Issue
         return;
     }
 
+    if cap.providers.strategy == ProviderStrategy::Weighted
+        && cap.providers.ordered().iter().all(|(_, p)| p.weight == 0)
+    {
+        issues.push(
+            Issue::error(
+                "CAP-015",
+                "strategy 'weighted' needs at least one provider with a non-zero weight",
+            )
+            .with_field("providers.strategy"),
+        );
+    }
+
     let schema_props = schema_helpers::input_property_names(&cap.schema.input);
 
     for (provider_name, provider) in &cap.providers.named {
@@ -182,7 +198,7 @@ pub(super) fn check_providers(cap: &CapabilityDefinition, issues: &mut Vec<Issue
 /// Non-REST services (cli, `local_binary`, `local_ml`, microfetch, etc.) use
 /// other config fields (command, binary, handler) and should not be rejected
 /// for missing URL fields.
-const REST_LIKE_SERVICES: &[&str] = &["rest", "graphql"];
+const REST_LIKE_SERVICES: &[&str] = &["rest", "soap", "graphql", "grpc", "sql"];
 
 /// Returns true if this service type requires `base_url` or endpoint.
 pub(super) fn service_requires_url(service: &str) -> bool {
@@ -287,6 +303,23 @@ fn check_rest_config(
         );
     }
 
+    if let Some(body) = &config.body {
+        check_body_templates(body, context, schema_props, issues);
+    }
+
+    if service == "sql" {
+        check_sql_config(config, context, schema_props, issues);
+    }
+    if service == "exec" {
+        check_exec_config(config, context, schema_props, issues);
+    }
+    if !config.files.is_empty() {
+        check_file_params(config, context, schema_props, issues);
+    }
+    if let Some(pagination) = &config.pagination {
+        check_pagination(config, pagination, context, issues);
+    }
+
     // CAP-007: static_params must not overlap with params.
     let static_keys: HashSet<&str> = config.static_params.keys().map(String::as_str).collect();
     let param_keys: HashSet<&str> = config.params.keys().map(String::as_str).collect();
@@ -464,10 +497,18 @@ fn check_placeholders_in_text(
     schema_props: &HashSet<String>,
     issues: &mut Vec<Issue>,
 ) {
+    if template::is_template(text) {
+        check_template(text, context, field, schema_props, issues);
+        return;
+    }
     for placeholder in extract_placeholders(text) {
         // System-resolved references are not schema parameters.
         // env.VAR — environment variable substitution
         // keychain.KEY — macOS Keychain lookup
+        // vault.MOUNT/PATH#FIELD — Vault KV v2 lookup
+        // aws-secrets.NAME#FIELD / aws-ssm.NAME — AWS secret lookups
+        // op.VAULT/ITEM/FIELD / bw.ITEM#FIELD — password manager CLI lookups
+        // secret.KEY.PATH — encrypted secrets file entry
         // oauth.PROVIDER — OAuth token injection
         // access_token / refresh_token — OAuth runtime injection
         // api_key — runtime API key injection
@@ -483,6 +524,12 @@ fn check_placeholders_in_text(
         ];
         if placeholder.starts_with("env.")
             || placeholder.starts_with("keychain.")
+            || placeholder.starts_with("vault.")
+            || placeholder.starts_with("aws-secrets.")
+            || placeholder.starts_with("aws-ssm.")
+            || placeholder.starts_with("op.")
+            || placeholder.starts_with("bw.")
+            || placeholder.starts_with("secret.")
             || placeholder.starts_with("oauth.")
             || RUNTIME_PLACEHOLDERS.contains(&placeholder.as_str())
             // Computed auth headers (e.g. {podcast_index_auth_header})
@@ -505,7 +552,7 @@ fn check_placeholders_in_text(
             &placeholder[..bracket_pos]
         } else if let Some(dot_pos) = placeholder.find('.') {
             // Nested property access like `foo.bar` — check the root property.
-            // (env/keychain/oauth prefixes are already handled above.)
+            // (env/keychain/vault/aws/op/bw/secret/oauth prefixes are already handled above.)
             &placeholder[..dot_pos]
         } else {
             placeholder.as_str()
@@ -522,22 +569,80 @@ fn check_placeholders_in_text(
     }
 }
 
+// ── CAP-019 ───────────────────────────────────────────────────────────────────
+
+/// CAP-019: a request template (`{{ }}` / `{% %}`) must parse.
+/// CAP-006: the variables it reads must exist in `schema.input.properties`.
+fn check_template(
+    text: &str,
+    context: &str,
+    field: &str,
+    schema_props: &HashSet<String>,
+    issues: &mut Vec<Issue>,
+) {
+    match template::variables(text) {
+        Err(e) => issues.push(Issue::error(
+            "CAP-019",
+            format!("{context}.{field}: invalid template: {e}"),
+        )),
+        Ok(names) => {
+            let mut unknown: Vec<_> = names.difference(schema_props).collect();
+            unknown.sort();
+            for name in unknown {
+                issues.push(Issue::error(
+                    "CAP-006",
+                    format!(
+                        "{context}.{field}: template variable '{name}' has no matching entry in schema.input.properties"
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Check the templates among the strings of a request `body`.
+fn check_body_templates(
+    body: &serde_json::Value,
+    context: &str,
+    schema_props: &HashSet<String>,
+    issues: &mut Vec<Issue>,
+) {
+    match body {
+        serde_json::Value::String(s) if template::is_template(s) => {
+            check_template(s, context, "body", schema_props, issues);
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                check_body_templates(item, context, schema_props, issues);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values() {
+                check_body_templates(value, context, schema_props, issues);
+            }
+        }
+        _ => {}
+    }
+}
+
 // ── CAP-009 ───────────────────────────────────────────────────────────────────
 
-/// CAP-009: Duplicate capability names across files.
+/// CAP-009: Duplicate capability names across files. Different `version`s
+/// of one name are not duplicates: they are loaded side by side.
 ///
 /// Returns `(file_path, Issue)` pairs so callers can attach them to the right file.
 pub(super) fn check_duplicate_names(
     caps: &[(String, CapabilityDefinition)],
 ) -> Vec<(String, Issue)> {
-    let mut seen: HashMap<&str, &str> = HashMap::new(); // name -> first_path
+    // (name, version) -> first_path
+    let mut seen: HashMap<(&str, Option<&str>), &str> = HashMap::new();
     let mut results = Vec::new();
 
     for (path, cap) in caps {
         if cap.name.is_empty() {
             continue;
         }
-        match seen.get(cap.name.as_str()) {
+        match seen.get(&(cap.name.as_str(), cap.version.as_deref())) {
             Some(&first_path) => {
                 results.push((
                     path.clone(),
@@ -551,7 +656,7 @@ pub(super) fn check_duplicate_names(
                 ));
             }
             None => {
-                seen.insert(&cap.name, path);
+                seen.insert((&cap.name, cap.version.as_deref()), path);
             }
         }
     }
@@ -559,18 +664,324 @@ pub(super) fn check_duplicate_names(
     results
 }
 
+/// CAP-011: a SQL provider needs a single read-only `query`, and every
+/// `bind` name must be an input property or a `static_params` default.
+fn check_sql_config(
+    config: &RestConfig,
+    context: &str,
+    schema_props: &HashSet<String>,
+    issues: &mut Vec<Issue>,
+) {
+    let sql = SqlConfig {
+        query: config.query.clone().unwrap_or_default(),
+        ..SqlConfig::default()
+    };
+    if let Err(reason) = sql.check_read_only() {
+        issues.push(Issue::error("CAP-011", format!("{context}: {reason}")));
+    }
+    for name in &config.bind {
+        if !schema_props.contains(name) && !config.static_params.contains_key(name) {
+            issues.push(Issue::error(
+                "CAP-011",
+                format!(
+                    "{context}: bind argument '{name}' is neither an input property nor a static_params default"
+                ),
+            ));
+        }
+    }
+}
+
+/// CAP-012: an exec provider needs `dangerous: true` and a fixed program,
+/// and every argument placeholder must be an input property or a
+/// `static_params` default.
+fn check_exec_config(
+    config: &RestConfig,
+    context: &str,
+    schema_props: &HashSet<String>,
+    issues: &mut Vec<Issue>,
+) {
+    let exec = ExecConfig {
+        command: config.command.clone(),
+        dangerous: config.dangerous,
+        ..ExecConfig::default()
+    };
+    if let Err(reason) = exec.check_runnable() {
+        issues.push(Issue::error("CAP-012", format!("{context}: {reason}")));
+    }
+    for name in config
+        .command
+        .iter()
+        .skip(1)
+        .flat_map(|a| extract_placeholders(a))
+    {
+        if !schema_props.contains(&name) && !config.static_params.contains_key(&name) {
+            issues.push(Issue::error(
+                "CAP-012",
+                format!(
+                    "{context}: command placeholder '{{{name}}}' is neither an input property nor a static_params default"
+                ),
+            ));
+        }
+    }
+}
+
+/// CAP-013: every `files` key must be an input property with a non-zero
+/// `max_bytes`, and a raw (non-multipart) body takes exactly one file.
+fn check_file_params(
+    config: &RestConfig,
+    context: &str,
+    schema_props: &HashSet<String>,
+    issues: &mut Vec<Issue>,
+) {
+    for (name, spec) in &config.files {
+        if !schema_props.contains(name) {
+            issues.push(Issue::error(
+                "CAP-013",
+                format!("{context}: file parameter '{name}' is not an input property"),
+            ));
+        }
+        if spec.max_bytes == 0 {
+            issues.push(Issue::error(
+                "CAP-013",
+                format!("{context}: file parameter '{name}' has max_bytes 0"),
+            ));
+        }
+    }
+    let content_type = config.body_content_type.to_ascii_lowercase();
+    if !content_type.is_empty() && !content_type.starts_with("multipart/") && config.files.len() > 1
+    {
+        issues.push(Issue::error(
+            "CAP-013",
+            format!(
+                "{context}: a raw '{}' body takes exactly one file parameter",
+                config.body_content_type
+            ),
+        ));
+    }
+}
+
+/// CAP-014: pagination needs a `cursor_path` for the `cursor` strategy, at
+/// least one page, a buffered JSON or XML response, and a body that can be
+/// resent.
+fn check_pagination(
+    config: &RestConfig,
+    pagination: &PaginationConfig,
+    context: &str,
+    issues: &mut Vec<Issue>,
+) {
+    let mut error = |message: &str| {
+        issues.push(Issue::error(
+            "CAP-014",
+            format!("{context}: pagination {message}"),
+        ));
+    };
+    if pagination.strategy == PaginationStrategy::Cursor && pagination.cursor_path.is_none() {
+        error("strategy 'cursor' needs 'cursor_path'");
+    }
+    if pagination.max_pages == 0 {
+        error("max_pages must be at least 1");
+    }
+    if matches!(
+        config.response_format.to_ascii_lowercase().as_str(),
+        "text" | "binary"
+    ) {
+        error("needs a JSON or XML response_format");
+    }
+    if !config.files.is_empty() {
+        error("cannot resend file uploads");
+    }
+    if config.stream.is_some() {
+        error("cannot be combined with 'stream'");
+    }
+}
+
+// ── CAP-016 ───────────────────────────────────────────────────────────────────
+
+/// CAP-016: a `resilience.timeout` must be at least one second; a
+/// `backoff_ms` above `max_backoff_ms` is capped and so only a warning.
+pub(super) fn check_resilience(cap: &CapabilityDefinition, issues: &mut Vec<Issue>) {
+    let resilience = &cap.resilience;
+    if resilience.timeout == Some(0) {
+        issues.push(
+            Issue::error("CAP-016", "resilience.timeout must be at least 1 second")
+                .with_field("resilience.timeout"),
+        );
+    }
+    if resilience.backoff_ms > resilience.max_backoff_ms {
+        issues.push(
+            Issue::warning(
+                "CAP-016",
+                format!(
+                    "resilience.backoff_ms ({}) exceeds max_backoff_ms ({}); every retry waits max_backoff_ms",
+                    resilience.backoff_ms, resilience.max_backoff_ms
+                ),
+            )
+            .with_field("resilience.backoff_ms"),
+        );
+    }
+}
+
+// ── CAP-017 ───────────────────────────────────────────────────────────────────
+
+/// CAP-017: `auth.type: oauth2_client_credentials` needs a valid
+/// `token_endpoint`, a `client_id`, and a client secret reference in `key`.
+pub(super) fn check_auth(cap: &CapabilityDefinition, issues: &mut Vec<Issue>) {
+    let auth = &cap.auth;
+    if auth.auth_type != OAUTH2_CLIENT_CREDENTIALS {
+        return;
+    }
+    let mut error = |field: &'static str, message: &str| {
+        issues.push(
+            Issue::error("CAP-017", format!("{OAUTH2_CLIENT_CREDENTIALS}: {message}"))
+                .with_field(field),
+        );
+    };
+    match auth.token_endpoint.as_deref() {
+        None | Some("") => error("auth.token_endpoint", "auth.token_endpoint is required"),
+        Some(endpoint) if url::Url::parse(endpoint).is_err() => {
+            error(
+                "auth.token_endpoint",
+                "auth.token_endpoint is not a valid URL",
+            );
+        }
+        Some(_) => {}
+    }
+    if auth.client_id.as_deref().is_none_or(str::is_empty) {
+        error("auth.client_id", "auth.client_id is required");
+    }
+    if auth.key.is_empty() {
+        error("auth.key", "auth.key must reference the client secret");
+    }
+}
+
+// ── CAP-018 ───────────────────────────────────────────────────────────────────
+
+/// CAP-018: `auth.type: aws_sigv4` needs `auth.aws.region` and `service`,
+/// both or neither of the key references, and REST providers without
+/// pagination or file uploads (signatures cover a single, buffered request).
+pub(super) fn check_aws_sigv4(cap: &CapabilityDefinition, issues: &mut Vec<Issue>) {
+    if cap.auth.auth_type != AWS_SIGV4 {
+        return;
+    }
+    let mut error = |field: &'static str, message: String| {
+        issues.push(Issue::error("CAP-018", format!("{AWS_SIGV4}: {message}")).with_field(field));
+    };
+    match &cap.auth.aws {
+        None => error(
+            "auth.aws",
+            "auth.aws with region and service is required".to_string(),
+        ),
+        Some(aws) => {
+            if aws.region.is_empty() || aws.service.is_empty() {
+                error(
+                    "auth.aws",
+                    "auth.aws.region and auth.aws.service are required".to_string(),
+                );
+            }
+            if aws.access_key_id.is_some() != aws.secret_access_key.is_some() {
+                error(
+                    "auth.aws",
+                    "set both access_key_id and secret_access_key, or neither".to_string(),
+                );
+            }
+        }
+    }
+    for (name, provider) in cap.providers.ordered() {
+        if !matches!(provider.service.as_str(), "rest" | "") {
+            error(
+                "providers",
+                format!(
+                    "providers.{name} uses service '{}'; only REST requests are signed",
+                    provider.service
+                ),
+            );
+        }
+        if provider.config.pagination.is_some() || !provider.config.files.is_empty() {
+            error(
+                "providers",
+                format!("providers.{name} cannot combine signing with pagination or file uploads"),
+            );
+        }
+    }
+}
+
+// ── CAP-020 ───────────────────────────────────────────────────────────────────
+
+/// CAP-020: a `version` must be non-empty and made of letters, digits, `.`,
+/// `-` and `+`; `replaced_by` must name another tool and is only meaningful
+/// on a `deprecated` capability.
+pub(super) fn check_versioning(cap: &CapabilityDefinition, issues: &mut Vec<Issue>) {
+    if let Some(version) = &cap.version
+        && (version.is_empty()
+            || !version
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')))
+    {
+        issues.push(
+            Issue::error(
+                "CAP-020",
+                format!(
+                    "version '{version}' must be non-empty and use only letters, digits, '.', '-' and '+'"
+                ),
+            )
+            .with_field("version"),
+        );
+    }
+    if let Some(replacement) = &cap.replaced_by {
+        if replacement.is_empty() || *replacement == cap.name {
+            issues.push(
+                Issue::error(
+                    "CAP-020",
+                    "replaced_by must name a different tool".to_string(),
+                )
+                .with_field("replaced_by"),
+            );
+        }
+        if !cap.deprecated {
+            issues.push(
+                Issue::warning(
+                    "CAP-020",
+                    "replaced_by is set but the capability is not marked deprecated: true"
+                        .to_string(),
+                )
+                .with_field("replaced_by"),
+            );
+        }
+    }
+}
+
+// ── CAP-021 ───────────────────────────────────────────────────────────────────
+
+/// CAP-021: a `query` in `transform` or `response_transform` must parse.
+pub(super) fn check_transforms(cap: &CapabilityDefinition, issues: &mut Vec<Issue>) {
+    for (field, transform) in [
+        ("transform.query", &cap.transform),
+        ("response_transform.query", &cap.response_transform),
+    ] {
+        if let Err(e) = transform.validate() {
+            issues.push(Issue::error("CAP-021", format!("{field}: {e}")).with_field(field));
+        }
+    }
+}
+
 // ── CAP-010 ───────────────────────────────────────────────────────────────────
 
-/// Warn when the file stem (sans extension) does not match the `name` field.
+/// Warn when the file stem (sans extension) does not match the `name` field
+/// or, for a versioned capability, its versioned name (`search_v2.yaml`).
 ///
 /// This is informational — mismatches lead to confusion but are not blocking.
-pub(super) fn check_path_label(file_path: &str, name: &str, issues: &mut Vec<Issue>) {
+pub(super) fn check_path_label(
+    file_path: &str,
+    cap: &CapabilityDefinition,
+    issues: &mut Vec<Issue>,
+) {
+    let name = cap.name.as_str();
     let stem = std::path::Path::new(file_path)
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("");
 
-    if !stem.is_empty() && !name.is_empty() && stem != name {
+    if !stem.is_empty() && !name.is_empty() && stem != name && stem != cap.versioned_name() {
         issues.push(Issue::warning(
             "CAP-010",
             format!("file name '{stem}.yaml' does not match capability name '{name}'; rename the file to match"),
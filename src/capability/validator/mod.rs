@@ -145,9 +145,14 @@ pub fn validate_capability_definition(
     checks::check_schema_input(&cap.schema.input, &mut issues);
     checks::check_schema_output(&cap.schema.output, &mut issues);
     checks::check_providers(cap, &mut issues);
+    checks::check_resilience(cap, &mut issues);
+    checks::check_auth(cap, &mut issues);
+    checks::check_aws_sigv4(cap, &mut issues);
+    checks::check_versioning(cap, &mut issues);
+    checks::check_transforms(cap, &mut issues);
 
     if let Some(path) = file_path {
-        checks::check_path_label(path, &cap.name, &mut issues);
+        checks::check_path_label(path, cap, &mut issues);
     }
 
     issues
@@ -5,8 +5,9 @@ mod yaml_roundtrip;
 use super::checks::extract_placeholders;
 use super::*;
 use crate::capability::{
-    AuthConfig, CacheConfig, CapabilityDefinition, CapabilityMetadata, ProviderConfig,
-    ProvidersConfig, RestConfig, SchemaDefinition,
+    AuthConfig, AwsSigV4Config, CacheConfig, CapabilityDefinition, CapabilityMetadata,
+    FileParamConfig, PaginationConfig, PaginationStrategy, ProviderConfig, ProviderStrategy,
+    ProvidersConfig, ResilienceConfig, RestConfig, SchemaDefinition,
 };
 use crate::transform::TransformConfig;
 use serde_json::json;
@@ -28,6 +29,10 @@ pub(super) fn minimal_cap(name: &str) -> CapabilityDefinition {
         response_transform: TransformConfig::default(),
         projection: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
         webhooks: HashMap::new(),
         sha256: None,
     }
@@ -41,6 +46,8 @@ pub(super) fn providers_with_base_url(base_url: &str) -> ProvidersConfig {
             service: "rest".to_string(),
             cost_per_call: 0.0,
             timeout: 30,
+            priority: None,
+            weight: 1,
             config: RestConfig {
                 base_url: base_url.to_string(),
                 path: String::new(),
@@ -51,6 +58,7 @@ pub(super) fn providers_with_base_url(base_url: &str) -> ProvidersConfig {
     ProvidersConfig {
         named,
         fallback: vec![],
+        ..Default::default()
     }
 }
 
@@ -62,6 +70,8 @@ pub(super) fn providers_with_path(base_url: &str, path: &str) -> ProvidersConfig
             service: "rest".to_string(),
             cost_per_call: 0.0,
             timeout: 30,
+            priority: None,
+            weight: 1,
             config: RestConfig {
                 base_url: base_url.to_string(),
                 path: path.to_string(),
@@ -72,6 +82,7 @@ pub(super) fn providers_with_path(base_url: &str, path: &str) -> ProvidersConfig
     ProvidersConfig {
         named,
         fallback: vec![],
+        ..Default::default()
     }
 }
 
@@ -303,6 +314,8 @@ fn provider_missing_url_is_error() {
             service: "rest".to_string(),
             cost_per_call: 0.0,
             timeout: 30,
+            priority: None,
+            weight: 1,
             config: RestConfig::default(), // base_url and endpoint both empty
         },
     );
@@ -310,6 +323,7 @@ fn provider_missing_url_is_error() {
     cap.providers = ProvidersConfig {
         named,
         fallback: vec![],
+        ..Default::default()
     };
     let issues = validate_capability_definition(&cap, None);
     assert!(
@@ -339,6 +353,10 @@ fn placeholder_with_schema_property_passes() {
             response_transform: TransformConfig::default(),
             projection: None,
             visible_in_states: vec![],
+            resilience: ResilienceConfig::default(),
+            version: None,
+            deprecated: false,
+            replaced_by: None,
             webhooks: HashMap::new(),
             sha256: None,
         },
@@ -370,6 +388,10 @@ fn placeholder_without_schema_property_is_error() {
             response_transform: TransformConfig::default(),
             projection: None,
             visible_in_states: vec![],
+            resilience: ResilienceConfig::default(),
+            version: None,
+            deprecated: false,
+            replaced_by: None,
             webhooks: HashMap::new(),
             sha256: None,
         },
@@ -401,6 +423,10 @@ fn env_placeholder_in_url_is_ignored() {
             response_transform: TransformConfig::default(),
             projection: None,
             visible_in_states: vec![],
+            resilience: ResilienceConfig::default(),
+            version: None,
+            deprecated: false,
+            replaced_by: None,
             webhooks: HashMap::new(),
             sha256: None,
         },
@@ -427,6 +453,8 @@ fn static_params_overlap_with_params_is_warning() {
             service: "rest".to_string(),
             cost_per_call: 0.0,
             timeout: 30,
+            priority: None,
+            weight: 1,
             config: RestConfig {
                 base_url: "https://api.example.com".to_string(),
                 params: {
@@ -450,6 +478,7 @@ fn static_params_overlap_with_params_is_warning() {
     cap.providers = ProvidersConfig {
         named,
         fallback: vec![],
+        ..Default::default()
     };
     let issues = validate_capability_definition(&cap, None);
     assert!(
@@ -470,6 +499,8 @@ fn static_params_no_overlap_passes() {
             service: "rest".to_string(),
             cost_per_call: 0.0,
             timeout: 30,
+            priority: None,
+            weight: 1,
             config: RestConfig {
                 base_url: "https://api.example.com".to_string(),
                 params: {
@@ -493,6 +524,7 @@ fn static_params_no_overlap_passes() {
     cap.providers = ProvidersConfig {
         named,
         fallback: vec![],
+        ..Default::default()
     };
     let issues = validate_capability_definition(&cap, None);
     assert!(!has_code(&warnings_of(&issues), "CAP-007"));
@@ -547,6 +579,10 @@ fn path_without_leading_slash_is_warning() {
             response_transform: TransformConfig::default(),
             projection: None,
             visible_in_states: vec![],
+            resilience: ResilienceConfig::default(),
+            version: None,
+            deprecated: false,
+            replaced_by: None,
             webhooks: HashMap::new(),
             sha256: None,
         },
@@ -608,6 +644,397 @@ fn unique_capability_names_pass_duplicate_check() {
     assert!(!has_code(&all_issues, "CAP-009"));
 }
 
+// ── CAP-011: SQL providers ────────────────────────────────────────────────────
+
+fn sql_cap(query: &str, bind: &[&str]) -> CapabilityDefinition {
+    let mut cap = with_input_schema(
+        minimal_cap("my_tool"),
+        json!({"type": "object", "properties": {"since": {"type": "string"}}}),
+    );
+    let provider = cap.providers.named.get_mut("primary").unwrap();
+    provider.service = "sql".to_string();
+    provider.config = RestConfig {
+        endpoint: "postgres://reporting.internal/shop".to_string(),
+        query: Some(query.to_string()),
+        bind: bind.iter().map(ToString::to_string).collect(),
+        ..RestConfig::default()
+    };
+    cap
+}
+
+#[test]
+fn sql_select_with_known_bind_passes() {
+    let cap = sql_cap(
+        "SELECT region FROM orders WHERE created_at >= $1",
+        &["since"],
+    );
+    let issues = validate_capability_definition(&cap, None);
+    assert!(!has_code(&errors_of(&issues), "CAP-011"));
+}
+
+#[test]
+fn sql_write_or_unknown_bind_is_error() {
+    // GIVEN: a DELETE statement, and a SELECT binding an undeclared argument
+    // WHEN: validating
+    // THEN: both are CAP-011 errors
+    for cap in [
+        sql_cap("DELETE FROM orders", &[]),
+        sql_cap("SELECT 1; DROP TABLE orders", &[]),
+        sql_cap("SELECT region FROM orders WHERE id = $1", &["order_id"]),
+    ] {
+        let issues = validate_capability_definition(&cap, None);
+        assert!(has_code(&errors_of(&issues), "CAP-011"), "{issues:?}");
+    }
+}
+
+// ── CAP-012: exec providers ───────────────────────────────────────────────────
+
+fn exec_cap(command: &[&str], dangerous: bool) -> CapabilityDefinition {
+    let mut cap = with_input_schema(
+        minimal_cap("my_tool"),
+        json!({"type": "object", "properties": {"count": {"type": "integer"}}}),
+    );
+    let provider = cap.providers.named.get_mut("primary").unwrap();
+    provider.service = "exec".to_string();
+    provider.config = RestConfig {
+        command: command.iter().map(ToString::to_string).collect(),
+        dangerous,
+        ..RestConfig::default()
+    };
+    cap
+}
+
+#[test]
+fn exec_with_dangerous_flag_and_known_placeholders_passes() {
+    let cap = exec_cap(&["git", "log", "-n", "{count}"], true);
+    let issues = validate_capability_definition(&cap, None);
+    assert!(!has_code(&errors_of(&issues), "CAP-012"), "{issues:?}");
+    assert!(!has_code(&errors_of(&issues), "CAP-005"), "{issues:?}");
+}
+
+#[test]
+fn exec_without_flag_or_with_unknown_placeholder_is_error() {
+    // GIVEN: no dangerous flag, a templated program, an undeclared placeholder
+    // WHEN: validating
+    // THEN: each is a CAP-012 error
+    for cap in [
+        exec_cap(&["git", "log"], false),
+        exec_cap(&["{tool}", "log"], true),
+        exec_cap(&["git", "log", "{branch}"], true),
+    ] {
+        let issues = validate_capability_definition(&cap, None);
+        assert!(has_code(&errors_of(&issues), "CAP-012"), "{issues:?}");
+    }
+}
+
+// ── CAP-013: file parameters ──────────────────────────────────────────────────
+
+fn upload_cap(files: &[&str], body_content_type: &str) -> CapabilityDefinition {
+    let mut cap = with_input_schema(
+        minimal_cap("my_tool"),
+        json!({"type": "object", "properties": {"document": {"type": "string"}}}),
+    );
+    let provider = cap.providers.named.get_mut("primary").unwrap();
+    provider.config.method = "POST".to_string();
+    provider.config.body_content_type = body_content_type.to_string();
+    provider.config.files = files
+        .iter()
+        .map(|name| ((*name).to_string(), FileParamConfig::default()))
+        .collect();
+    cap
+}
+
+#[test]
+fn file_param_declared_in_schema_passes() {
+    let cap = upload_cap(&["document"], "");
+    let issues = validate_capability_definition(&cap, None);
+    assert!(!has_code(&errors_of(&issues), "CAP-013"), "{issues:?}");
+}
+
+#[test]
+fn unknown_file_param_or_raw_body_with_two_files_is_error() {
+    // GIVEN: an undeclared file parameter, and two files for a raw body
+    // WHEN: validating
+    // THEN: each is a CAP-013 error
+    for cap in [
+        upload_cap(&["attachment"], ""),
+        upload_cap(&["document", "attachment"], "application/pdf"),
+    ] {
+        let issues = validate_capability_definition(&cap, None);
+        assert!(has_code(&errors_of(&issues), "CAP-013"), "{issues:?}");
+    }
+}
+
+// ── CAP-014: pagination ───────────────────────────────────────────────────────
+
+fn paginated_cap(pagination: PaginationConfig, response_format: &str) -> CapabilityDefinition {
+    let mut cap = minimal_cap("my_tool");
+    let provider = cap.providers.named.get_mut("primary").unwrap();
+    provider.config.pagination = Some(pagination);
+    provider.config.response_format = response_format.to_string();
+    cap
+}
+
+#[test]
+fn cursor_pagination_with_cursor_path_passes() {
+    let cap = paginated_cap(
+        PaginationConfig {
+            cursor_path: Some("meta.next".to_string()),
+            ..PaginationConfig::default()
+        },
+        "json",
+    );
+    let issues = validate_capability_definition(&cap, None);
+    assert!(!has_code(&errors_of(&issues), "CAP-014"), "{issues:?}");
+}
+
+#[test]
+fn incomplete_pagination_is_error() {
+    // GIVEN: a cursor strategy without cursor_path, zero pages, a text response
+    // WHEN: validating
+    // THEN: each is a CAP-014 error
+    let page = PaginationConfig {
+        strategy: PaginationStrategy::Page,
+        ..PaginationConfig::default()
+    };
+    for cap in [
+        paginated_cap(PaginationConfig::default(), "json"),
+        paginated_cap(
+            PaginationConfig {
+                max_pages: 0,
+                ..page.clone()
+            },
+            "json",
+        ),
+        paginated_cap(page, "text"),
+    ] {
+        let issues = validate_capability_definition(&cap, None);
+        assert!(has_code(&errors_of(&issues), "CAP-014"), "{issues:?}");
+    }
+}
+
+// ── CAP-015: provider strategy ────────────────────────────────────────────────
+
+#[test]
+fn weighted_strategy_without_positive_weight_is_error() {
+    // GIVEN: a weighted capability whose only provider has weight 0
+    let mut cap = minimal_cap("my_tool");
+    cap.providers.strategy = ProviderStrategy::Weighted;
+    cap.providers.named.get_mut("primary").unwrap().weight = 0;
+
+    // WHEN: validating
+    let issues = validate_capability_definition(&cap, None);
+
+    // THEN: CAP-015 error; restoring a weight clears it
+    assert!(has_code(&errors_of(&issues), "CAP-015"), "{issues:?}");
+    cap.providers.named.get_mut("primary").unwrap().weight = 1;
+    let issues = validate_capability_definition(&cap, None);
+    assert!(!has_code(&errors_of(&issues), "CAP-015"), "{issues:?}");
+}
+
+// ── CAP-016: resilience ───────────────────────────────────────────────────────
+
+#[test]
+fn zero_resilience_timeout_is_error_and_inverted_backoff_warns() {
+    // GIVEN: a zero deadline and a first backoff above the cap
+    let mut cap = minimal_cap("my_tool");
+    cap.resilience.timeout = Some(0);
+    cap.resilience.backoff_ms = 10_000;
+
+    // WHEN: validating
+    let issues = validate_capability_definition(&cap, None);
+
+    // THEN: the timeout is an error, the backoff a warning
+    let cap_016: Vec<_> = issues.iter().filter(|i| i.code == "CAP-016").collect();
+    assert_eq!(cap_016.len(), 2, "{issues:?}");
+    assert!(has_code(&errors_of(&issues), "CAP-016"));
+    assert!(
+        cap_016
+            .iter()
+            .any(|i| i.severity == IssueSeverity::Warning && i.message.contains("backoff_ms"))
+    );
+}
+
+// ── CAP-017: client credentials auth ──────────────────────────────────────────
+
+#[test]
+fn client_credentials_auth_needs_endpoint_client_and_secret() {
+    // GIVEN: a client credentials capability without its settings
+    let mut cap = minimal_cap("my_tool");
+    cap.auth.auth_type = "oauth2_client_credentials".to_string();
+
+    // WHEN: validating
+    let issues = validate_capability_definition(&cap, None);
+
+    // THEN: endpoint, client ID and secret are each reported
+    let fields: Vec<_> = issues
+        .iter()
+        .filter(|i| i.code == "CAP-017")
+        .filter_map(|i| i.field)
+        .collect();
+    assert_eq!(
+        fields,
+        vec!["auth.token_endpoint", "auth.client_id", "auth.key"]
+    );
+
+    // WHEN: completing them
+    cap.auth.token_endpoint = Some("https://idp.example.com/oauth/token".to_string());
+    cap.auth.client_id = Some("env:CLIENT_ID".to_string());
+    cap.auth.key = "env:CLIENT_SECRET".to_string();
+
+    // THEN: no CAP-017 issue remains
+    let issues = validate_capability_definition(&cap, None);
+    assert!(!has_code(&issues, "CAP-017"), "{issues:?}");
+}
+
+// ── CAP-018: AWS SigV4 ────────────────────────────────────────────────────────
+
+#[test]
+fn aws_sigv4_needs_region_service_and_a_plain_rest_provider() {
+    // GIVEN: aws_sigv4 without auth.aws, on a paginated provider
+    let mut cap = minimal_cap("my_tool");
+    cap.auth.auth_type = "aws_sigv4".to_string();
+    cap.providers
+        .named
+        .get_mut("primary")
+        .unwrap()
+        .config
+        .pagination = Some(PaginationConfig::default());
+
+    // WHEN: validating
+    let issues = validate_capability_definition(&cap, None);
+
+    // THEN: both problems are CAP-018 errors
+    let messages: Vec<_> = errors_of(&issues)
+        .into_iter()
+        .filter(|i| i.code == "CAP-018")
+        .map(|i| i.message)
+        .collect();
+    assert_eq!(messages.len(), 2, "{messages:?}");
+    assert!(messages.iter().any(|m| m.contains("pagination")));
+
+    // WHEN: fixing both
+    cap.auth.aws = Some(AwsSigV4Config {
+        region: "eu-west-1".to_string(),
+        service: "execute-api".to_string(),
+        ..AwsSigV4Config::default()
+    });
+    cap.providers
+        .named
+        .get_mut("primary")
+        .unwrap()
+        .config
+        .pagination = None;
+
+    // THEN: no CAP-018 issue remains
+    let issues = validate_capability_definition(&cap, None);
+    assert!(!has_code(&issues, "CAP-018"), "{issues:?}");
+}
+
+// ── CAP-019: request templates ────────────────────────────────────────────────
+
+#[test]
+fn request_templates_must_parse_and_read_declared_inputs() {
+    // GIVEN: a valid template header and a body template reading `since`
+    let mut cap = with_input_schema(
+        minimal_cap("my_tool"),
+        json!({"type": "object", "properties": {"q": {"type": "string"}}}),
+    );
+    let config = &mut cap.providers.named.get_mut("primary").unwrap().config;
+    config.headers.insert(
+        "X-Query".to_string(),
+        "{% if q %}{{ q | upper }}{% endif %}".to_string(),
+    );
+    config.body = Some(json!({"filter": "{{ since }}"}));
+
+    // WHEN: validating
+    let issues = validate_capability_definition(&cap, None);
+
+    // THEN: only the undeclared `since` is reported
+    let errors = errors_of(&issues);
+    assert!(has_code(&errors, "CAP-006"), "{issues:?}");
+    assert!(!has_code(&errors, "CAP-019"), "{issues:?}");
+
+    // WHEN: a template does not parse
+    let config = &mut cap.providers.named.get_mut("primary").unwrap().config;
+    config.body = None;
+    config
+        .params
+        .insert("q".to_string(), "{% if q %}{{ q }}".to_string());
+
+    // THEN: it is a CAP-019 error
+    let issues = validate_capability_definition(&cap, None);
+    assert!(has_code(&errors_of(&issues), "CAP-019"), "{issues:?}");
+    assert!(!has_code(&errors_of(&issues), "CAP-006"), "{issues:?}");
+}
+
+// ── CAP-020: versioning ───────────────────────────────────────────────────────
+
+#[test]
+fn versions_and_replacements_are_checked() {
+    // GIVEN: a deprecated, versioned capability pointing at its successor
+    let mut cap = minimal_cap("search");
+    cap.version = Some("1.2.0-beta+build".to_string());
+    cap.deprecated = true;
+    cap.replaced_by = Some("search_v2".to_string());
+
+    // WHEN: validating with the versioned file name
+    let issues =
+        validate_capability_definition(&cap, Some("capabilities/search_v1_2_0_beta_build.yaml"));
+
+    // THEN: neither CAP-020 nor a path-label warning is reported
+    assert!(!has_code(&issues, "CAP-020"), "{issues:?}");
+    assert!(!has_code(&issues, "CAP-010"), "{issues:?}");
+
+    // WHEN: the version has a space and the replacement names itself
+    cap.version = Some("1 2".to_string());
+    cap.replaced_by = Some("search".to_string());
+
+    // THEN: both are CAP-020 errors
+    let errors = errors_of(&validate_capability_definition(&cap, None));
+    assert_eq!(
+        errors.iter().filter(|i| i.code == "CAP-020").count(),
+        2,
+        "{errors:?}"
+    );
+
+    // WHEN: replaced_by is set without deprecated
+    cap.version = None;
+    cap.deprecated = false;
+    cap.replaced_by = Some("search_v2".to_string());
+
+    // THEN: CAP-020 is a warning only
+    let issues = validate_capability_definition(&cap, None);
+    assert!(has_code(&warnings_of(&issues), "CAP-020"), "{issues:?}");
+    assert!(!has_code(&errors_of(&issues), "CAP-020"), "{issues:?}");
+}
+
+#[test]
+fn different_versions_of_one_name_are_not_duplicates() {
+    // GIVEN: two versions of `search` and a repeat of one of them
+    let versioned = |version: &str| {
+        let mut cap = minimal_cap("search");
+        cap.version = Some(version.to_string());
+        cap
+    };
+    let caps = vec![
+        ("capabilities/search_v1.yaml".to_string(), versioned("1")),
+        ("capabilities/search.yaml".to_string(), versioned("2")),
+        ("capabilities/copy/search.yaml".to_string(), versioned("2")),
+    ];
+
+    // WHEN: validate_capabilities is called
+    let results = validate_capabilities(&caps);
+
+    // THEN: only the repeated version is flagged
+    let flagged: Vec<_> = results
+        .iter()
+        .filter(|(_, issues)| has_code(issues, "CAP-009"))
+        .map(|(path, _)| path.as_str())
+        .collect();
+    assert_eq!(flagged, vec!["capabilities/copy/search.yaml"]);
+}
+
 // ── extract_placeholders ──────────────────────────────────────────────────────
 
 #[test]
@@ -635,3 +1062,21 @@ fn extract_placeholders_env_ref() {
     let found: Vec<_> = extract_placeholders("https://{env.API_HOST}/v1").collect();
     assert_eq!(found, vec!["env.API_HOST"]);
 }
+
+// ── CAP-021: transform queries ────────────────────────────────────────────────
+
+#[test]
+fn invalid_transform_queries_are_errors() {
+    // GIVEN: a capability with a valid response query and an invalid one
+    let mut cap = minimal_cap("search");
+    cap.transform.query = Some("items[*].id".to_string());
+    cap.response_transform.query = Some("items[?".to_string());
+
+    // WHEN: validating
+    let errors = errors_of(&validate_capability_definition(&cap, None));
+
+    // THEN: only the invalid query is a CAP-021 error
+    let cap_021: Vec<_> = errors.iter().filter(|i| i.code == "CAP-021").collect();
+    assert_eq!(cap_021.len(), 1, "{errors:?}");
+    assert_eq!(cap_021[0].field, Some("response_transform.query"));
+}
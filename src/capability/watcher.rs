@@ -27,7 +27,9 @@ impl CapabilityWatcher {
     /// Start watching capability directories for changes
     ///
     /// This spawns a background task that watches for file changes and
-    /// triggers `backend.reload()` when changes are detected.
+    /// triggers `backend.reload()` when changes are detected. The backend's
+    /// tools-changed hook (see `CapabilityBackend::set_tools_changed_hook`)
+    /// announces the new tool list.
     ///
     /// # Errors
     ///
@@ -157,10 +159,20 @@ impl CapabilityWatcher {
 
                                     info!(backend = %backend.name, "Hot-reloading capabilities...");
                                     match backend.reload().await {
-                                        Ok(count) => {
+                                        Ok(report) => {
+                                            for failure in &report.failures {
+                                                warn!(
+                                                    backend = %backend.name,
+                                                    path = %failure.path.display(),
+                                                    error = %failure.error,
+                                                    "Capability file failed to load"
+                                                );
+                                            }
                                             info!(
                                                 backend = %backend.name,
-                                                capabilities = count,
+                                                capabilities = report.total,
+                                                failed = report.failures.len(),
+                                                tools_changed = report.tools_changed,
                                                 "Hot-reload complete"
                                             );
                                         }
@@ -0,0 +1,88 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Public MCP directory search CLI definitions.
+
+use clap::Subcommand;
+
+use crate::cli::output::OutputFormat;
+use crate::discovery::directory::DirectoryKind;
+
+/// MCP server discovery subcommands.
+#[derive(Subcommand, Debug)]
+pub enum DiscoverCommand {
+    /// Search public MCP server directories (the official MCP registry,
+    /// Smithery and `PulseMCP`) and show how to install each hit.
+    ///
+    /// Nothing is written unless servers are picked with `--add`; each one
+    /// becomes a backend in the gateway config (`--config`, default
+    /// `gateway.yaml`) running its stdio command or pointing at its remote
+    /// URL, with its expected environment variables set to `${VAR}`
+    /// placeholders.
+    #[command(about = "Search public MCP directories and import servers")]
+    Remote {
+        /// Text to search for
+        #[arg(short, long)]
+        query: String,
+
+        /// Directory to search; may be repeated (default: all). Smithery is
+        /// skipped unless `SMITHERY_API_KEY` is set.
+        #[arg(short, long = "source", value_enum)]
+        sources: Vec<DirectoryKind>,
+
+        /// Maximum results per directory
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+
+        /// Output format
+        #[arg(short, long, default_value = "table", value_enum)]
+        format: OutputFormat,
+
+        /// Add the server with this id or backend name to the config; may be
+        /// repeated
+        #[arg(long = "add", value_name = "NAME")]
+        add: Vec<String>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::{Cli, Command};
+
+    #[test]
+    fn discover_remote_parses_sources_and_selection() {
+        // GIVEN / WHEN: a remote search limited to two directories
+        let cli = Cli::parse_from([
+            "mcp-gateway",
+            "discover",
+            "remote",
+            "--query",
+            "github",
+            "--source",
+            "official",
+            "--source",
+            "pulsemcp",
+            "--add",
+            "github-mcp",
+        ]);
+
+        // THEN: the directories and selection are kept in order
+        match cli.command {
+            Some(Command::Discover(DiscoverCommand::Remote {
+                query,
+                sources,
+                add,
+                limit,
+                ..
+            })) => {
+                assert_eq!(query, "github");
+                assert_eq!(sources, [DirectoryKind::Official, DirectoryKind::Pulsemcp]);
+                assert_eq!(add, ["github-mcp"]);
+                assert_eq!(limit, 10);
+            }
+            other => panic!("unexpected: {other:?}"),
+        }
+    }
+}
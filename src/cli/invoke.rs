@@ -183,6 +183,7 @@ pub fn catalogue_to_registry_entries(catalogue: &ToolCatalogue) -> Vec<RegistryE
             path: String::new(),
             tags: c.metadata.tags.clone(),
             requires_key: c.auth.required,
+            ..RegistryEntry::default()
         })
         .collect()
 }
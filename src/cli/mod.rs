@@ -28,6 +28,7 @@
 //! ```
 
 pub mod completion;
+pub mod discover;
 pub mod identity;
 pub mod invoke;
 pub mod output;
@@ -41,12 +42,13 @@ use clap_complete::Shell;
 
 use crate::cli::output::OutputFormat;
 
+pub use discover::DiscoverCommand;
 pub use identity::{IdentityCommand, IdentityGrantScopeArg, IdentityGrantsCommand};
 pub use skills::SkillsCommand;
 pub use subcommands::{
     AuditCommand, CapCommand, KubernetesCommand, PluginCommand, ProtocolImportCommand,
-    ProtocolImportKind, RankingCommand, RuntimeProviderArg, TlsCommand, TrustCommand,
-    TrustLabCommand,
+    ProtocolImportKind, RankingCommand, RuntimeProviderArg, TlsCommand, TransitionsCommand,
+    TrustCommand, TrustLabCommand,
 };
 
 // ── Config-export CLI types ───────────────────────────────────────────────────
@@ -177,6 +179,10 @@ pub enum Command {
     #[command(subcommand, about = "Capability management commands")]
     Cap(CapCommand),
 
+    /// Search public MCP server directories and import servers into the config
+    #[command(subcommand, about = "Discover MCP servers in public directories")]
+    Discover(DiscoverCommand),
+
     /// Preview safe protocol imports before writing or enabling generated tools
     #[command(
         subcommand,
@@ -192,6 +198,10 @@ pub enum Command {
     #[command(subcommand, about = "Adaptive ranking evaluation commands")]
     Ranking(RankingCommand),
 
+    /// Inspect learned tool-to-tool transitions.
+    #[command(subcommand, about = "Learned tool sequence reports")]
+    Transitions(TransitionsCommand),
+
     /// Manage TLS certificates for mTLS authenticated tool access (RFC-0051)
     #[command(
         subcommand,
@@ -239,17 +249,69 @@ pub enum Command {
         price: f64,
     },
 
+    /// Generate LLM keywords and summaries for all backend tools
+    ///
+    /// Starts every enabled backend from `--config`, collects its tool
+    /// definitions, and sends them to the `enrichment` LLM in batches. The
+    /// result is written to an overlay file that search reads at startup.
+    /// Tools already enriched from an unchanged description are skipped
+    /// unless `--force` is given.
+    #[command(about = "Enrich tool descriptions with LLM-generated keywords")]
+    Enrich {
+        /// Overlay file to write (defaults to `enrichment.overlay_file`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Model to use (defaults to `enrichment.model`)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Re-enrich every tool, including unchanged ones
+        #[arg(long)]
+        force: bool,
+
+        /// List the tools that would be sent without calling the LLM
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Lint capability YAMLs against agent-UX best practices
     ///
     /// Validates one or more capability files (or directories) against the
     /// full agent-UX rules engine (AX-001..AX-009) and reports issues with
-    /// colored output. Supports JSON, SARIF, and auto-fix modes.
+    /// colored output. Supports JSON, SARIF, `JUnit`, and auto-fix modes.
+    ///
+    /// `--server` validates the tools a live MCP server lists instead: a
+    /// configured backend name, an HTTP URL, or a stdio command to spawn.
+    /// Cross-capability checks run over everything validated together, so
+    /// `--all-backends` catches conflicts across the whole gateway.
     #[command(about = "Validate capability definitions against agent-UX rules")]
     Validate {
         /// Files or directories to validate (YAML capabilities)
-        #[arg(required = true)]
+        #[arg(required_unless_present_any = ["servers", "all_backends"])]
         paths: Vec<PathBuf>,
 
+        /// Live MCP server to validate (backend name, URL or command; repeatable)
+        #[arg(long = "server", value_name = "NAME|URL|COMMAND")]
+        servers: Vec<String>,
+
+        /// Validate every enabled backend in the gateway config
+        #[arg(long)]
+        all_backends: bool,
+
+        /// Call tools on the live servers and check responses against their
+        /// declared `outputSchema` (read-only tools unless `--invoke` is given)
+        #[arg(long)]
+        conformance: bool,
+
+        /// Tool to call in conformance mode (repeatable; implies `--conformance`)
+        #[arg(long = "invoke", value_name = "TOOL")]
+        invoke: Vec<String>,
+
+        /// YAML/JSON file of sample arguments per tool for conformance mode
+        #[arg(long, value_name = "FILE")]
+        samples: Option<PathBuf>,
+
         /// Output format
         #[arg(short, long, default_value = "text", value_enum)]
         format: crate::validator::OutputFormat,
@@ -258,10 +320,28 @@ pub enum Command {
         #[arg(short, long, default_value = "info", value_enum)]
         severity: crate::validator::SeverityFilter,
 
-        /// Auto-fix issues where possible (rewrites YAML in place)
+        /// Auto-fix issues where possible (rewrites YAML in place; live
+        /// servers get gateway-side remediations in the overrides file)
         #[arg(long)]
         fix: bool,
 
+        /// Overrides file for `--fix` on live servers [default: tool-overrides.json]
+        #[arg(long, value_name = "FILE")]
+        overrides: Option<PathBuf>,
+
+        /// YAML file with custom rules (name/description patterns, banned
+        /// words, required schema fields, description length)
+        #[arg(long, value_name = "FILE")]
+        rules: Option<PathBuf>,
+
+        /// Baseline of known violations; only new issues are reported and fail
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+
+        /// Record the current violations into the `--baseline` file
+        #[arg(long, requires = "baseline")]
+        update_baseline: bool,
+
         /// Disable colored output
         #[arg(long)]
         no_color: bool,
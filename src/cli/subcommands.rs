@@ -47,10 +47,12 @@ pub enum CapCommand {
     /// Generate capability YAML files from an `OpenAPI` 3.x or Swagger 2.0 spec
     ///
     /// Reads the spec, creates one capability file per operation, and writes
-    /// them to the output directory. Supports both YAML and JSON input.
-    #[command(about = "Convert an OpenAPI spec into capability definitions")]
+    /// them to the output directory. Supports both YAML and JSON input. An
+    /// `AsyncAPI` 2.x/3.0 file instead becomes one webhook capability with an
+    /// inbound endpoint per channel.
+    #[command(about = "Convert an OpenAPI or AsyncAPI spec into capability definitions")]
     Import {
-        /// Path to the `OpenAPI` specification file (YAML or JSON)
+        /// Path to the `OpenAPI` or `AsyncAPI` specification file (YAML or JSON)
         #[arg(required = true)]
         spec: PathBuf,
 
@@ -68,6 +70,12 @@ pub enum CapCommand {
     },
 
     /// Execute a capability once and print the result (useful for debugging)
+    ///
+    /// With `--mock`, upstream HTTP calls are answered from a responses file
+    /// instead of the live API and the rendered requests are printed. With
+    /// `--suite`, every case in a test-suite file runs against mocked
+    /// responses and its request/result assertions are checked; the command
+    /// fails when any case fails.
     #[command(about = "Test a capability by invoking it with sample arguments")]
     Test {
         /// Path to the capability YAML file to execute
@@ -77,18 +85,32 @@ pub enum CapCommand {
         /// JSON object of arguments to pass to the capability
         #[arg(short, long, default_value = "{}")]
         args: String,
+
+        /// YAML file of mocked upstream responses (`responses:` list)
+        #[arg(long)]
+        mock: Option<PathBuf>,
+
+        /// YAML test-suite file with several cases (`cases:` list)
+        #[arg(long)]
+        suite: Option<PathBuf>,
     },
 
     /// Scan local configs and running processes for MCP servers
     ///
     /// Checks Claude Desktop, VS Code, Cursor, Windsurf, ~/.config/mcp/,
-    /// running MCP processes, and `MCP_SERVER_*` environment variables.
+    /// running MCP processes, `MCP_SERVER_*` environment variables, and
+    /// `_mcp._tcp` services advertised via mDNS on the local network.
     ///
     /// Use `--shadow` for a passive `ShadowRadar` report of servers that are
     /// *not* already registered as backends in the gateway configuration. The
     /// report classifies ownership, transport exposure, trust status, data
     /// risk, recommended action, confidence, verification, and rollback. It
     /// never invokes discovered tools.
+    ///
+    /// With `--write-config` (outside `--shadow`), each server is first
+    /// started or connected to and asked for `initialize` and `tools/list`;
+    /// the protocol version and tool count are shown, and servers that do
+    /// not answer are written with `enabled: false`.
     #[command(about = "Auto-discover existing MCP servers on this machine")]
     Discover {
         /// Output format: "table" (human-readable), "json", or "yaml"
@@ -112,14 +134,38 @@ pub enum CapCommand {
         /// Defaults to `gateway.yaml` in the current directory.
         #[arg(long)]
         gateway_config: Option<PathBuf>,
+
+        /// How long to listen for `_mcp._tcp` mDNS advertisements on the
+        /// local network, in milliseconds (0 disables mDNS)
+        #[arg(long, default_value_t = 1500)]
+        mdns_timeout_ms: u64,
+
+        /// How long each server gets to answer the probe run before
+        /// `--write-config`, in milliseconds (0 skips the probe)
+        #[arg(long, default_value_t = 10_000)]
+        probe_timeout_ms: u64,
     },
 
-    /// Download a capability from a GitHub repository into the local directory
+    /// Download a capability into the local directory
+    ///
+    /// Without `--from-github`, the registries configured under
+    /// `registry.sources` are queried in priority order; cached copies are
+    /// used when a registry is unreachable. Registry installs are recorded in
+    /// `<output>/capabilities.lock`, and capabilities already locked there
+    /// are reinstalled at exactly the locked version and checksum.
+    ///
+    /// With `--frozen`, only locked capabilities are installed (all of them
+    /// when no name is given) and the lockfile is never modified.
     #[command(about = "Install a capability from the community registry")]
     Install {
         /// Name of the capability to install (e.g. `stock_quote`)
-        #[arg(required = true)]
-        name: String,
+        #[arg(required_unless_present = "frozen")]
+        name: Option<String>,
+
+        /// Install exactly what `capabilities.lock` records; fail instead of
+        /// changing it
+        #[arg(long, conflicts_with = "from_github")]
+        frozen: bool,
 
         /// Fetch from a remote GitHub repository instead of the local directory
         #[arg(long)]
@@ -139,6 +185,9 @@ pub enum CapCommand {
     },
 
     /// Find capabilities by name, description, or tag
+    ///
+    /// Searches the local capabilities directory and every registry
+    /// configured under `registry.sources`.
     #[command(about = "Search the capability registry")]
     Search {
         /// Text to match against capability names, descriptions, and tags
@@ -158,6 +207,98 @@ pub enum CapCommand {
         capabilities: PathBuf,
     },
 
+    /// List installed capabilities with newer versions in a registry
+    ///
+    /// Compares each capability's `version:` with the version listed by its
+    /// locked registry (or the highest-priority registry listing it) and
+    /// prints the release notes published since.
+    #[command(about = "Show capabilities with newer registry versions")]
+    Outdated {
+        /// Capabilities directory to check
+        #[arg(default_value = "capabilities")]
+        directory: PathBuf,
+    },
+
+    /// Upgrade installed capabilities to their latest registry versions
+    ///
+    /// Replaces each outdated capability file in place and updates
+    /// `capabilities.lock`. Pass names to upgrade only those.
+    #[command(about = "Upgrade capabilities to newer registry versions")]
+    Update {
+        /// Capabilities to upgrade (default: every outdated one)
+        names: Vec<String>,
+
+        /// Capabilities directory to update
+        #[arg(short, long, default_value = "capabilities")]
+        directory: PathBuf,
+
+        /// Show what would be upgraded without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Record the capabilities installed in a directory in its lockfile
+    ///
+    /// Writes `<directory>/capabilities.lock` with each capability's file,
+    /// version and SHA-256, keeping the registry origin of unchanged
+    /// entries. With `--frozen`, the lockfile is left alone and the command
+    /// fails if any capability is missing, modified or unlocked.
+    #[command(about = "Generate or check a capabilities lockfile")]
+    Lock {
+        /// Capabilities directory to lock
+        #[arg(default_value = "capabilities")]
+        directory: PathBuf,
+
+        /// Check the directory against the lockfile instead of rewriting it
+        #[arg(long)]
+        frozen: bool,
+    },
+
+    /// Package a capability into a registry directory and update its index
+    ///
+    /// Validates the capability, copies it to
+    /// `<registry>/packages/<name>/<version>.yaml` and records its version,
+    /// SHA-256 and schema in `<registry>/index.json`. The `version:` must be
+    /// semver and newer than the published one; removing or retyping schema
+    /// properties requires a major bump, adding optional ones a minor bump.
+    /// With `--publisher`, the package is signed with that publisher's key.
+    #[command(about = "Publish a capability to a registry directory")]
+    Publish {
+        /// Path to the capability YAML file to publish
+        #[arg(required = true)]
+        file: PathBuf,
+
+        /// Registry directory holding `index.json` and `packages/`
+        #[arg(short, long, default_value = "registry")]
+        registry: PathBuf,
+
+        /// Release notes for this version, shown by `cap outdated`
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Publisher name to sign the package as
+        #[arg(long, requires = "signing_key_env")]
+        publisher: Option<String>,
+
+        /// Environment variable holding the publisher's signing key (see
+        /// `cap keygen`)
+        #[arg(long, requires = "publisher")]
+        signing_key_env: Option<String>,
+    },
+
+    /// Generate an Ed25519 key for signing published capabilities
+    ///
+    /// Prints a private signing key, to keep in the environment variable
+    /// passed to `cap publish --signing-key-env`, and the public key to add
+    /// under `registry.sources[].public_keys` of every gateway that trusts
+    /// the publisher.
+    #[command(about = "Generate a capability signing key")]
+    Keygen {
+        /// Publisher name the key is for
+        #[arg(required = true)]
+        publisher: String,
+    },
+
     /// Probe a URL for an `OpenAPI` or GraphQL spec and generate capability files
     ///
     /// Runs SSRF validation, discovers the spec via parallel probing, converts
@@ -272,6 +413,37 @@ pub enum RankingCommand {
     },
 }
 
+/// Learned tool-transition subcommands.
+#[derive(Subcommand, Debug)]
+pub enum TransitionsCommand {
+    /// Report the most common tool transitions and sequences.
+    ///
+    /// Reads the transition data the gateway persists while running, so the
+    /// report reflects every session since the file was created.
+    #[command(about = "Show the most common tool sequences")]
+    Report {
+        /// Transition data file (defaults to `~/.mcp-gateway/transitions.json`).
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Maximum transitions and sequences to list.
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Minimum observations for a step to appear in a sequence.
+        #[arg(long, default_value = "3")]
+        min_count: u64,
+
+        /// Maximum tools per sequence.
+        #[arg(long, default_value = "4")]
+        max_length: usize,
+
+        /// Output format (`json` exports the full report).
+        #[arg(short, long, default_value = "table", value_enum)]
+        format: OutputFormat,
+    },
+}
+
 /// Protocol source formats accepted by the safe import preview command.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
 pub enum ProtocolImportKind {
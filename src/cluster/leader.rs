@@ -0,0 +1,223 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+//! Leader election for periodic background work.
+//!
+//! Some jobs must run once per cluster, not once per replica: discovery
+//! scans, proactive OAuth token refresh (a rotating refresh token spent by
+//! two replicas revokes the session), scheduled playbook runs and the
+//! periodic save of usage statistics and cost data. Replicas elect a leader
+//! through a lease in the cluster state (key `{prefix}leader`, holding the
+//! replica ID) and only the leader runs them.
+//!
+//! The leader renews its lease three times per `cluster.leader_lease`; a
+//! replica that stops renewing (crash, partition) loses it when the lease
+//! expires and another one takes over. While the cluster state is
+//! unreachable the leader keeps its role until its lease would have
+//! expired, then steps down, so two replicas never both believe they lead.
+//! On shutdown the leader hands the lease back after its final flush.
+//!
+//! Standalone gateways have no election and always run the jobs.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tracing::{info, warn};
+
+use super::SharedState;
+
+/// Cluster state key of the leader lease.
+pub const LEADER_KEY: &str = "leader";
+
+/// The election of this process, once clustered.
+static ELECTION: OnceLock<Arc<LeaderElection>> = OnceLock::new();
+
+/// Whether this replica should run cluster-wide background jobs.
+///
+/// Always `true` unless an election was [`install`]ed.
+#[must_use]
+pub fn is_leader() -> bool {
+    ELECTION.get().is_none_or(|election| election.is_leader())
+}
+
+/// Make `election` the one [`is_leader`] consults. Only the first call has
+/// an effect.
+pub fn install(election: Arc<LeaderElection>) {
+    let _ = ELECTION.set(election);
+}
+
+/// This replica's candidacy for the leader lease.
+#[derive(Debug)]
+pub struct LeaderElection {
+    state: Arc<dyn SharedState>,
+    replica_id: String,
+    lease: Duration,
+    leader: AtomicBool,
+    /// When the lease last acquired or renewed runs out.
+    held_until: Mutex<Option<Instant>>,
+}
+
+impl LeaderElection {
+    /// Stand for election in `state` with a lease of `lease`.
+    #[must_use]
+    pub fn new(state: Arc<dyn SharedState>, lease: Duration) -> Self {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "gateway".to_string());
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        Self {
+            state,
+            replica_id: format!("{host}-{}", &suffix[..8]),
+            lease,
+            leader: AtomicBool::new(false),
+            held_until: Mutex::new(None),
+        }
+    }
+
+    /// Identifier stored in the lease while this replica leads.
+    #[must_use]
+    pub fn replica_id(&self) -> &str {
+        &self.replica_id
+    }
+
+    /// Whether this replica currently leads.
+    #[must_use]
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::Acquire)
+    }
+
+    /// How often [`campaign`](Self::campaign) should run.
+    #[must_use]
+    pub fn renew_interval(&self) -> Duration {
+        (self.lease / 3).max(Duration::from_millis(100))
+    }
+
+    /// One election round: take the lease if it is free, renew it if this
+    /// replica holds it. Returns whether this replica leads afterwards.
+    pub fn campaign(&self) -> bool {
+        let started = Instant::now();
+        let outcome = match self
+            .state
+            .put_if_absent(LEADER_KEY, &self.replica_id, self.lease)
+        {
+            Ok(None) => Ok(true),
+            Ok(Some(holder)) if holder == self.replica_id => {
+                self.state
+                    .extend_if(LEADER_KEY, &self.replica_id, self.lease)
+            }
+            Ok(Some(_)) => Ok(false),
+            Err(e) => Err(e),
+        };
+        let leads = {
+            let mut held_until = self.held_until.lock();
+            match outcome {
+                Ok(true) => {
+                    *held_until = Some(started + self.lease);
+                    true
+                }
+                Ok(false) => {
+                    *held_until = None;
+                    false
+                }
+                Err(e) => {
+                    let leads = held_until.is_some_and(|until| Instant::now() < until);
+                    warn!(error = %e, leads, "Leader lease not renewed; cluster state unreachable");
+                    leads
+                }
+            }
+        };
+        let was_leader = self.leader.swap(leads, Ordering::AcqRel);
+        if leads && !was_leader {
+            info!(replica = %self.replica_id, "Elected leader for background jobs");
+        } else if !leads && was_leader {
+            info!(replica = %self.replica_id, "Lost leadership; background jobs paused");
+        }
+        leads
+    }
+
+    /// Give the lease up (on shutdown) so another replica takes over
+    /// without waiting for it to expire.
+    pub fn resign(&self) {
+        if !self.leader.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        *self.held_until.lock() = None;
+        match self.state.delete_if(LEADER_KEY, &self.replica_id) {
+            Ok(_) => info!(replica = %self.replica_id, "Resigned leadership"),
+            Err(e) => warn!(error = %e, "Leader lease not released; it expires on its own"),
+        }
+    }
+}
+
+/// Spawn the background task that keeps `election` campaigning every
+/// [`LeaderElection::renew_interval`] until shutdown.
+pub fn spawn_leader_election(
+    election: Arc<LeaderElection>,
+    mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(election.renew_interval());
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let candidate = Arc::clone(&election);
+                    if let Err(e) = tokio::task::spawn_blocking(move || candidate.campaign()).await {
+                        warn!(error = %e, "Leader election task failed");
+                    }
+                }
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cluster::MemoryState;
+
+    fn replicas(lease: Duration) -> (LeaderElection, LeaderElection) {
+        let state: Arc<dyn SharedState> = Arc::new(MemoryState::new());
+        (
+            LeaderElection::new(Arc::clone(&state), lease),
+            LeaderElection::new(state, lease),
+        )
+    }
+
+    #[test]
+    fn only_one_replica_leads() {
+        // GIVEN: two replicas campaigning for one lease
+        let (a, b) = replicas(Duration::from_secs(30));
+
+        // WHEN / THEN: the first takes it and keeps it on renewal
+        assert!(a.campaign());
+        assert!(!b.campaign());
+        assert!(a.campaign());
+        assert!(a.is_leader() && !b.is_leader());
+    }
+
+    #[test]
+    fn resigning_hands_the_lease_over() {
+        let (a, b) = replicas(Duration::from_secs(30));
+        assert!(a.campaign());
+
+        a.resign();
+
+        assert!(!a.is_leader());
+        assert!(b.campaign());
+    }
+
+    #[test]
+    fn expired_lease_is_taken_over() {
+        // GIVEN: a leader that stops renewing
+        let (a, b) = replicas(Duration::from_millis(20));
+        assert!(a.campaign());
+
+        // WHEN: its lease runs out
+        std::thread::sleep(Duration::from_millis(40));
+
+        // THEN: the other replica takes over and the old leader steps down
+        assert!(b.campaign());
+        assert!(!a.campaign());
+    }
+}
@@ -0,0 +1,367 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+//! Shared state for running several gateway replicas as one.
+//!
+//! Behind a load balancer each replica would otherwise make its own safety
+//! decisions: a client gets N times its rate limit, an operator kill only
+//! stops one replica, and a session loses its routing profile (and its
+//! duplicate-call protection) when a request lands elsewhere. With
+//! `cluster.backend: redis` these live in Redis instead:
+//!
+//! | State | Key | Consumer |
+//! |-------|-----|----------|
+//! | Rate-limit buckets | `{prefix}rate:{client}:{backend}` | [`ClientRateLimiter`](crate::failsafe::ClientRateLimiter) |
+//! | Operator kills | `{prefix}killed` (set of `server` / `server:tool`) | [`KillSwitch`](crate::kill_switch::KillSwitch) |
+//! | Session → profile | `{prefix}profile:{session_id}` | [`SessionProfileStore`](crate::routing_profile::SessionProfileStore) |
+//! | Idempotency keys | `{prefix}idempotency:{sha256(key)}` | [`IdempotencyStore`](crate::idempotency::IdempotencyStore) |
+//! | Leader lease | `{prefix}leader` (replica ID) | [`leader`] |
+//!
+//! Rate limits, profile bindings and idempotency keys are read and written
+//! on the request path. Kills are written through by `gateway_kill_server` /
+//! `gateway_revive_server` and pulled by every replica each
+//! `cluster.sync_interval`. Error budgets and circuit breakers stay per
+//! replica: each one observes the backend itself.
+//!
+//! Every call is bounded by `cluster.timeout`. When Redis is unreachable the
+//! consumers fall back to the replica's own state and log a warning, so an
+//! outage degrades the cluster to independent replicas rather than failing
+//! requests; Redis is then skipped for a backoff, so the fallback is
+//! immediate.
+//!
+//! The [`SharedState`] calls are synchronous because their consumers are:
+//! like the directory-backed idempotency store they do blocking I/O, kept
+//! short by the timeout and moved off the async worker by the Redis state.
+
+use std::collections::HashSet;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use tracing::{debug, warn};
+
+use crate::Result;
+use crate::config::{ClusterConfig, RateSpec};
+use crate::failsafe::KeyedRateLimiter;
+use crate::kill_switch::KillSwitch;
+
+pub mod leader;
+#[cfg(feature = "cluster-redis")]
+pub mod redis;
+
+/// Set of operator-killed targets (`server` or `server:tool`).
+pub const KILLED_SET: &str = "killed";
+
+/// Key/value, set and rate-limit primitives shared by all replicas.
+///
+/// Keys are given without the configured prefix; implementations add it.
+pub trait SharedState: std::fmt::Debug + Send + Sync + 'static {
+    /// Take one token from bucket `key`, holding `limit.count` tokens
+    /// refilled evenly across the period.
+    ///
+    /// Returns `Ok(Err(wait))` with the wait until the next token when the
+    /// bucket is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn take_token(
+        &self,
+        key: &str,
+        limit: RateSpec,
+    ) -> io::Result<std::result::Result<(), Duration>>;
+
+    /// Value of `key`, if set and not expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn get(&self, key: &str) -> io::Result<Option<String>>;
+
+    /// Set `key` to `value`, expiring after `ttl`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn put(&self, key: &str, value: &str, ttl: Duration) -> io::Result<()>;
+
+    /// Set `key` unless it holds a live value, which is returned instead.
+    /// Atomic across replicas.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn put_if_absent(&self, key: &str, value: &str, ttl: Duration) -> io::Result<Option<String>>;
+
+    /// Delete `key` (missing keys are not an error).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn delete(&self, key: &str) -> io::Result<()>;
+
+    /// Reset the expiry of `key` to `ttl` if it still holds `value`.
+    /// Returns whether it did. Atomic across replicas.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn extend_if(&self, key: &str, value: &str, ttl: Duration) -> io::Result<bool>;
+
+    /// Delete `key` if it holds `value`. Returns whether it did. Atomic
+    /// across replicas.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn delete_if(&self, key: &str, value: &str) -> io::Result<bool>;
+
+    /// Add `member` to set `set`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn add_member(&self, set: &str, member: &str) -> io::Result<()>;
+
+    /// Remove `member` from set `set`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn remove_member(&self, set: &str, member: &str) -> io::Result<()>;
+
+    /// Members of set `set` (empty when it does not exist).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared store cannot be reached.
+    fn members(&self, set: &str) -> io::Result<HashSet<String>>;
+}
+
+/// Open the shared state configured in `cluster`.
+///
+/// Returns `None` for a standalone gateway.
+///
+/// # Errors
+///
+/// Returns an error if the Redis URL is invalid or the server unreachable,
+/// or the gateway was built without the `cluster-redis` feature.
+pub fn open(config: &ClusterConfig) -> Result<Option<Arc<dyn SharedState>>> {
+    match config {
+        ClusterConfig::Standalone => Ok(None),
+        #[cfg(feature = "cluster-redis")]
+        ClusterConfig::Redis {
+            url,
+            key_prefix,
+            timeout,
+            ..
+        } => {
+            let url = crate::secrets::SecretResolver::new().resolve(url)?;
+            let state = redis::RedisState::open(&url, key_prefix, *timeout)?;
+            tracing::info!(key_prefix = %key_prefix, "Cluster state shared through Redis");
+            Ok(Some(Arc::new(state)))
+        }
+        #[cfg(not(feature = "cluster-redis"))]
+        ClusterConfig::Redis { .. } => Err(crate::Error::Config(
+            "cluster backend 'redis' needs a gateway built with the `cluster-redis` feature"
+                .to_string(),
+        )),
+    }
+}
+
+/// Share an operator kill (`killed = true`) or revive of `target` with the
+/// other replicas.
+pub fn publish_kill(state: &dyn SharedState, target: &str, killed: bool) {
+    let result = if killed {
+        state.add_member(KILLED_SET, target)
+    } else {
+        state.remove_member(KILLED_SET, target)
+    };
+    if let Err(e) = result {
+        warn!(target, killed, error = %e, "Kill switch change not shared with other replicas");
+    }
+}
+
+/// Spawn a background task that applies the shared kill list to
+/// `kill_switch` every `interval`.
+///
+/// The task stops when the `Arc` reference count of `kill_switch` drops to 1
+/// (i.e., all other owners have dropped their handles).
+pub fn spawn_kill_switch_sync(
+    state: Arc<dyn SharedState>,
+    kill_switch: Arc<KillSwitch>,
+    interval: Duration,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            // Stop if we are the sole Arc holder (server is shutting down).
+            if Arc::strong_count(&kill_switch) <= 1 {
+                break;
+            }
+            let state = Arc::clone(&state);
+            match tokio::task::spawn_blocking(move || state.members(KILLED_SET)).await {
+                Ok(Ok(targets)) => kill_switch.apply_shared(&targets),
+                Ok(Err(e)) => debug!(error = %e, "Shared kill list unavailable"),
+                Err(e) => warn!(error = %e, "Shared kill list sync task failed"),
+            }
+        }
+    });
+}
+
+/// In-process [`SharedState`], for tests and single-process embedding.
+#[derive(Default)]
+pub struct MemoryState {
+    values: DashMap<String, (String, Instant)>,
+    sets: DashMap<String, HashSet<String>>,
+    buckets: KeyedRateLimiter,
+}
+
+impl MemoryState {
+    /// Create empty state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl std::fmt::Debug for MemoryState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryState")
+            .field("values", &self.values.len())
+            .field("sets", &self.sets.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SharedState for MemoryState {
+    fn take_token(
+        &self,
+        key: &str,
+        limit: RateSpec,
+    ) -> io::Result<std::result::Result<(), Duration>> {
+        Ok(self.buckets.check(key, limit))
+    }
+
+    fn get(&self, key: &str) -> io::Result<Option<String>> {
+        Ok(self
+            .values
+            .get(key)
+            .filter(|entry| entry.1 > Instant::now())
+            .map(|entry| entry.0.clone()))
+    }
+
+    fn put(&self, key: &str, value: &str, ttl: Duration) -> io::Result<()> {
+        self.values
+            .insert(key.to_string(), (value.to_string(), Instant::now() + ttl));
+        Ok(())
+    }
+
+    fn put_if_absent(&self, key: &str, value: &str, ttl: Duration) -> io::Result<Option<String>> {
+        let now = Instant::now();
+        let mut entry = self
+            .values
+            .entry(key.to_string())
+            .or_insert_with(|| (String::new(), now));
+        if entry.1 > now {
+            return Ok(Some(entry.0.clone()));
+        }
+        *entry = (value.to_string(), now + ttl);
+        Ok(None)
+    }
+
+    fn delete(&self, key: &str) -> io::Result<()> {
+        self.values.remove(key);
+        Ok(())
+    }
+
+    fn extend_if(&self, key: &str, value: &str, ttl: Duration) -> io::Result<bool> {
+        let now = Instant::now();
+        Ok(self.values.get_mut(key).is_some_and(|mut entry| {
+            let held = entry.1 > now && entry.0 == value;
+            if held {
+                entry.1 = now + ttl;
+            }
+            held
+        }))
+    }
+
+    fn delete_if(&self, key: &str, value: &str) -> io::Result<bool> {
+        let now = Instant::now();
+        Ok(self
+            .values
+            .remove_if(key, |_, entry| entry.1 > now && entry.0 == value)
+            .is_some())
+    }
+
+    fn add_member(&self, set: &str, member: &str) -> io::Result<()> {
+        self.sets
+            .entry(set.to_string())
+            .or_default()
+            .insert(member.to_string());
+        Ok(())
+    }
+
+    fn remove_member(&self, set: &str, member: &str) -> io::Result<()> {
+        if let Some(mut members) = self.sets.get_mut(set) {
+            members.remove(member);
+        }
+        Ok(())
+    }
+
+    fn members(&self, set: &str) -> io::Result<HashSet<String>> {
+        Ok(self
+            .sets
+            .get(set)
+            .map(|members| members.clone())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn put_if_absent_returns_the_live_value() {
+        let state = MemoryState::new();
+        let ttl = Duration::from_secs(60);
+
+        assert_eq!(state.put_if_absent("k", "first", ttl).unwrap(), None);
+        assert_eq!(
+            state.put_if_absent("k", "second", ttl).unwrap().as_deref(),
+            Some("first")
+        );
+
+        // An expired value is replaced
+        state.put("k", "stale", Duration::ZERO).unwrap();
+        assert_eq!(state.put_if_absent("k", "third", ttl).unwrap(), None);
+        assert_eq!(state.get("k").unwrap().as_deref(), Some("third"));
+    }
+
+    #[test]
+    fn published_kills_reach_another_kill_switch() {
+        // GIVEN: two replicas sharing one state
+        let state = MemoryState::new();
+        let replica = KillSwitch::new();
+
+        // WHEN: an operator kills a server and a tool on the first replica
+        publish_kill(&state, "github", true);
+        publish_kill(&state, "slack:post_message", true);
+        replica.apply_shared(&state.members(KILLED_SET).unwrap());
+
+        // THEN: the second replica enforces both
+        assert!(replica.is_killed("github"));
+        assert!(replica.is_tool_killed("slack", "post_message"));
+
+        // WHEN: the server is revived anywhere
+        publish_kill(&state, "github", false);
+        replica.apply_shared(&state.members(KILLED_SET).unwrap());
+
+        // THEN: the second replica revives it, keeping the tool kill
+        assert!(!replica.is_killed("github"));
+        assert!(replica.is_tool_killed("slack", "post_message"));
+    }
+}
@@ -0,0 +1,329 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+//! Redis cluster state — one logical gateway across replicas.
+//!
+//! Rate limits use GCRA (the algorithm behind the in-process `governor`
+//! buckets): one key per bucket holds its theoretical arrival time, updated
+//! by a script so concurrent replicas cannot both take the last token. The
+//! script reads the Redis server clock, so replica clock skew does not
+//! matter. Every key expires on its own; nothing needs reaping.
+//!
+//! Calls block, so on a multi-threaded runtime they run in
+//! [`tokio::task::block_in_place`], handing the worker's other tasks to
+//! another thread. After a call fails to reach Redis, further calls fail
+//! at once until a backoff passes (1s, doubling up to 30s), so during an
+//! outage requests fall back to local state instead of each waiting out
+//! `cluster.timeout`.
+
+use std::collections::HashSet;
+use std::io;
+use std::time::{Duration, Instant};
+
+use ::redis::{Client, Commands, Connection, RedisError, RedisResult, Script};
+use parking_lot::Mutex;
+
+use super::SharedState;
+use crate::config::RateSpec;
+use crate::{Error, Result};
+
+/// Take one token from a GCRA bucket.
+///
+/// `ARGV[1]` is the emission interval and `ARGV[2]` the burst, in
+/// milliseconds and tokens. Returns 0 when a token was taken, otherwise the
+/// wait in milliseconds.
+const TAKE_TOKEN: &str = r"
+local t = redis.call('TIME')
+local now = t[1] * 1000 + math.floor(t[2] / 1000)
+local interval = tonumber(ARGV[1])
+local tat = tonumber(redis.call('GET', KEYS[1]) or now)
+if tat < now then tat = now end
+local next_tat = tat + interval
+local wait = next_tat - interval * tonumber(ARGV[2]) - now
+if wait > 0 then return wait end
+redis.call('SET', KEYS[1], next_tat, 'PX', next_tat - now)
+return 0
+";
+
+/// Set `KEYS[1]` to `ARGV[1]` for `ARGV[2]` ms unless it exists; returns the
+/// existing value.
+const PUT_IF_ABSENT: &str = r"
+local existing = redis.call('GET', KEYS[1])
+if existing then return existing end
+redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+return false
+";
+
+/// Reset the expiry of `KEYS[1]` to `ARGV[2]` ms if it holds `ARGV[1]`.
+const EXTEND_IF: &str = r"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+";
+
+/// Delete `KEYS[1]` if it holds `ARGV[1]`.
+const DELETE_IF: &str = r"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+";
+
+/// Idle connections kept for reuse.
+const MAX_IDLE_CONNECTIONS: usize = 16;
+
+/// How long Redis is skipped after a failed call; doubled per consecutive
+/// failure.
+const FAILURE_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound of [`FAILURE_BACKOFF`] after repeated failures.
+const MAX_FAILURE_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Consecutive failures to reach Redis, and when to try again.
+#[derive(Debug, Default)]
+struct FailureBackoff {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl FailureBackoff {
+    /// Time left before Redis may be called again, if any.
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.retry_at
+            .filter(|at| *at > now)
+            .map(|at| at.duration_since(now))
+    }
+
+    fn failed(&mut self, now: Instant) {
+        self.failures = self.failures.saturating_add(1);
+        let delay = FAILURE_BACKOFF
+            .saturating_mul(1 << (self.failures - 1).min(5))
+            .min(MAX_FAILURE_BACKOFF);
+        self.retry_at = Some(now + delay);
+    }
+
+    fn succeeded(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Cluster state in Redis.
+pub struct RedisState {
+    client: Client,
+    prefix: String,
+    timeout: Duration,
+    idle: Mutex<Vec<Connection>>,
+    backoff: Mutex<FailureBackoff>,
+    take_token: Script,
+    put_if_absent: Script,
+    extend_if: Script,
+    delete_if: Script,
+}
+
+impl std::fmt::Debug for RedisState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisState")
+            .field("prefix", &self.prefix)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisState {
+    /// Connect to the Redis server at `url`; every key starts with `prefix`
+    /// and every call gives up after `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid or the server unreachable.
+    pub fn open(url: &str, prefix: &str, timeout: Duration) -> Result<Self> {
+        let client =
+            Client::open(url).map_err(|e| Error::Config(format!("Invalid cluster.url: {e}")))?;
+        let state = Self {
+            client,
+            prefix: prefix.to_string(),
+            timeout,
+            idle: Mutex::new(Vec::new()),
+            backoff: Mutex::new(FailureBackoff::default()),
+            take_token: Script::new(TAKE_TOKEN),
+            put_if_absent: Script::new(PUT_IF_ABSENT),
+            extend_if: Script::new(EXTEND_IF),
+            delete_if: Script::new(DELETE_IF),
+        };
+        let conn = state.connect().map_err(|e| {
+            Error::Transport(format!("Failed to connect to the cluster Redis store: {e}"))
+        })?;
+        state.idle.lock().push(conn);
+        Ok(state)
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
+    }
+
+    fn connect(&self) -> RedisResult<Connection> {
+        let conn = self.client.get_connection_with_timeout(self.timeout)?;
+        conn.set_read_timeout(Some(self.timeout))?;
+        conn.set_write_timeout(Some(self.timeout))?;
+        Ok(conn)
+    }
+
+    /// Run `f` on a pooled connection, unless Redis is being skipped after
+    /// a failure. A connection whose call failed is dropped rather than
+    /// reused, since it may hold a half-read reply.
+    fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> RedisResult<T>) -> io::Result<T> {
+        if let Some(remaining) = self.backoff.lock().remaining(Instant::now()) {
+            return Err(io::Error::other(format!(
+                "cluster Redis store unreachable; retrying in {}ms",
+                remaining.as_millis()
+            )));
+        }
+        let result: RedisResult<T> = off_worker(|| {
+            let pooled = self.idle.lock().pop();
+            let mut conn = match pooled {
+                Some(conn) => conn,
+                None => self.connect()?,
+            };
+            let result = f(&mut conn)?;
+            let mut idle = self.idle.lock();
+            if idle.len() < MAX_IDLE_CONNECTIONS {
+                idle.push(conn);
+            }
+            Ok(result)
+        });
+        let mut backoff = self.backoff.lock();
+        match &result {
+            Ok(_) => backoff.succeeded(),
+            Err(e) if e.is_io_error() => backoff.failed(Instant::now()),
+            Err(_) => {}
+        }
+        result.map_err(to_io)
+    }
+}
+
+/// Run blocking `f` without stalling the tokio worker it is called on.
+fn off_worker<T>(f: impl FnOnce() -> T) -> T {
+    let multi_thread = tokio::runtime::Handle::try_current()
+        .is_ok_and(|h| h.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread);
+    if multi_thread {
+        tokio::task::block_in_place(f)
+    } else {
+        f()
+    }
+}
+
+fn to_io(e: RedisError) -> io::Error {
+    io::Error::other(e)
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis())
+        .unwrap_or(u64::MAX)
+        .max(1)
+}
+
+impl SharedState for RedisState {
+    fn take_token(
+        &self,
+        key: &str,
+        limit: RateSpec,
+    ) -> io::Result<std::result::Result<(), Duration>> {
+        let count = limit.count.max(1);
+        let interval = millis(limit.period() / count);
+        let key = self.key(key);
+        let wait_ms: u64 = self.with_conn(|conn| {
+            self.take_token
+                .key(&key)
+                .arg(interval)
+                .arg(count)
+                .invoke(conn)
+        })?;
+        Ok(if wait_ms == 0 {
+            Ok(())
+        } else {
+            Err(Duration::from_millis(wait_ms))
+        })
+    }
+
+    fn get(&self, key: &str) -> io::Result<Option<String>> {
+        self.with_conn(|conn| conn.get(self.key(key)))
+    }
+
+    fn put(&self, key: &str, value: &str, ttl: Duration) -> io::Result<()> {
+        self.with_conn(|conn| conn.pset_ex(self.key(key), value, millis(ttl)))
+    }
+
+    fn put_if_absent(&self, key: &str, value: &str, ttl: Duration) -> io::Result<Option<String>> {
+        let key = self.key(key);
+        self.with_conn(|conn| {
+            self.put_if_absent
+                .key(&key)
+                .arg(value)
+                .arg(millis(ttl))
+                .invoke(conn)
+        })
+    }
+
+    fn delete(&self, key: &str) -> io::Result<()> {
+        self.with_conn(|conn| conn.del(self.key(key)))
+    }
+
+    fn extend_if(&self, key: &str, value: &str, ttl: Duration) -> io::Result<bool> {
+        let key = self.key(key);
+        self.with_conn(|conn| {
+            self.extend_if
+                .key(&key)
+                .arg(value)
+                .arg(millis(ttl))
+                .invoke(conn)
+        })
+    }
+
+    fn delete_if(&self, key: &str, value: &str) -> io::Result<bool> {
+        let key = self.key(key);
+        self.with_conn(|conn| self.delete_if.key(&key).arg(value).invoke(conn))
+    }
+
+    fn add_member(&self, set: &str, member: &str) -> io::Result<()> {
+        self.with_conn(|conn| conn.sadd(self.key(set), member))
+    }
+
+    fn remove_member(&self, set: &str, member: &str) -> io::Result<()> {
+        self.with_conn(|conn| conn.srem(self.key(set), member))
+    }
+
+    fn members(&self, set: &str) -> io::Result<HashSet<String>> {
+        self.with_conn(|conn| conn.smembers(self.key(set)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_backoff_doubles_up_to_the_cap_and_resets_on_success() {
+        // GIVEN: no failures yet
+        let now = Instant::now();
+        let mut backoff = FailureBackoff::default();
+        assert_eq!(backoff.remaining(now), None);
+
+        // WHEN: calls keep failing
+        backoff.failed(now);
+        assert_eq!(backoff.remaining(now), Some(FAILURE_BACKOFF));
+        backoff.failed(now);
+        assert_eq!(backoff.remaining(now), Some(FAILURE_BACKOFF * 2));
+        for _ in 0..10 {
+            backoff.failed(now);
+        }
+
+        // THEN: the wait is capped, and lifted by a success
+        assert_eq!(backoff.remaining(now), Some(MAX_FAILURE_BACKOFF));
+        assert_eq!(backoff.remaining(now + MAX_FAILURE_BACKOFF), None);
+        backoff.succeeded();
+        assert_eq!(backoff.remaining(now), None);
+        backoff.failed(now);
+        assert_eq!(backoff.remaining(now), Some(FAILURE_BACKOFF));
+    }
+}
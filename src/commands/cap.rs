@@ -2,25 +2,39 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Capability (`cap`) subcommand handlers for `mcp-gateway`.
 
+use std::path::Path;
 use std::process::ExitCode;
 use std::sync::Arc;
 
 use mcp_gateway::{
     capability::{
-        AuthTemplate, CapabilityExecutor, CapabilityLoader, OpenApiConverter,
-        compute_capability_hash, parse_capability_file, rewrite_with_pin, validate_capability,
+        AsyncApiConverter, AuthTemplate, CapabilityExecutor, CapabilityLoader, MockResponse,
+        OpenApiConverter, compute_capability_hash,
+        harness::{self, MockResponses, TestSuite},
+        parse_capability_file, rewrite_with_pin, validate_capability,
     },
     cli::CapCommand,
+    config::Config,
     discovery::{
         AutoDiscovery,
+        probe::probe_all,
         shadow::{ShadowRemediationAction, ShadowScanReport, ShadowTrustStatus},
     },
-    registry::Registry,
+    registry::{
+        Registry,
+        lockfile::{LOCKFILE_NAME, LockedCapability, Lockfile},
+        publish::{SchemaChange, publish_capability},
+        remote::{RegistrySearch, RemoteInstall, RemoteRegistries},
+        trust::{SigningKey, generate_signing_key},
+    },
 };
 
 /// Run a `cap` subcommand (validate, list, import, test, discover, install, search, ...).
+///
+/// `config_path` is the global `--config`; `install` and `search` read the
+/// remote registries (`registry.sources`) from it.
 #[allow(clippy::too_many_lines)]
-pub async fn run_cap_command(cmd: CapCommand) -> ExitCode {
+pub async fn run_cap_command(cmd: CapCommand, config_path: Option<&Path>) -> ExitCode {
     match cmd {
         CapCommand::Validate { file } => cap_validate(file).await,
         CapCommand::Pin { file } => cap_pin(file).await,
@@ -31,26 +45,71 @@ pub async fn run_cap_command(cmd: CapCommand) -> ExitCode {
             prefix,
             auth_key,
         } => cap_import(spec, output, prefix, auth_key).await,
-        CapCommand::Test { file, args } => cap_test(file, args).await,
+        CapCommand::Test {
+            file,
+            args,
+            mock,
+            suite,
+        } => cap_test(file, args, mock, suite).await,
         CapCommand::Discover {
             format,
             write_config,
             config_path,
             shadow,
             gateway_config,
-        } => cap_discover(format, write_config, config_path, shadow, gateway_config).await,
+            mdns_timeout_ms,
+            probe_timeout_ms,
+        } => {
+            let mut discovery = AutoDiscovery::new();
+            if mdns_timeout_ms > 0 {
+                discovery = discovery.with_mdns(std::time::Duration::from_millis(mdns_timeout_ms));
+            }
+            let probe_timeout =
+                (probe_timeout_ms > 0).then(|| std::time::Duration::from_millis(probe_timeout_ms));
+            cap_discover(
+                &discovery,
+                format,
+                write_config,
+                config_path,
+                shadow,
+                gateway_config,
+                probe_timeout,
+            )
+            .await
+        }
         CapCommand::Install {
             name,
+            frozen,
             from_github,
             repo,
             branch,
             output,
-        } => cap_install(name, from_github, repo, branch, output).await,
+        } => cap_install(name, frozen, from_github, repo, branch, output, config_path).await,
         CapCommand::Search {
             query,
             capabilities,
-        } => cap_search(query, capabilities).await,
+        } => cap_search(query, capabilities, config_path).await,
         CapCommand::RegistryList { capabilities } => cap_registry_list(capabilities).await,
+        CapCommand::Outdated { directory } => {
+            super::cap_update::cap_outdated(&directory, config_path).await
+        }
+        CapCommand::Update {
+            names,
+            directory,
+            dry_run,
+        } => super::cap_update::cap_update(&names, &directory, dry_run, config_path).await,
+        CapCommand::Lock { directory, frozen } => cap_lock(&directory, frozen),
+        CapCommand::Publish {
+            file,
+            registry,
+            notes,
+            publisher,
+            signing_key_env,
+        } => {
+            let signer = publisher.as_deref().zip(signing_key_env.as_deref());
+            cap_publish(&file, &registry, notes.as_deref(), signer)
+        }
+        CapCommand::Keygen { publisher } => cap_keygen(&publisher),
         #[cfg(feature = "discovery")]
         CapCommand::ImportUrl {
             url,
@@ -175,8 +234,8 @@ async fn cap_import(
     auth_key: Option<String>,
 ) -> ExitCode {
     let mut converter = OpenApiConverter::new();
-    if let Some(p) = prefix {
-        converter = converter.with_prefix(&p);
+    if let Some(p) = &prefix {
+        converter = converter.with_prefix(p);
     }
     if let Some(key) = auth_key {
         converter = converter.with_default_auth(AuthTemplate {
@@ -187,8 +246,18 @@ async fn cap_import(
     }
     let spec_ref = spec.to_string_lossy().to_string();
     let is_url = spec_ref.starts_with("http://") || spec_ref.starts_with("https://");
+    let is_asyncapi = !is_url
+        && std::fs::read_to_string(&spec)
+            .is_ok_and(|content| AsyncApiConverter::is_asyncapi(&content));
     let result = if is_url {
         converter.convert_url(&spec_ref).await
+    } else if is_asyncapi {
+        // Event catalogues become one webhook-only capability.
+        let mut converter = AsyncApiConverter::new();
+        if let Some(p) = &prefix {
+            converter = converter.with_prefix(p);
+        }
+        converter.convert_file(&spec_ref).map(|cap| vec![cap])
     } else {
         converter.convert_file(&spec_ref)
     };
@@ -217,7 +286,12 @@ async fn cap_import(
     }
 }
 
-async fn cap_test(file: std::path::PathBuf, args: String) -> ExitCode {
+async fn cap_test(
+    file: std::path::PathBuf,
+    args: String,
+    mock: Option<std::path::PathBuf>,
+    suite: Option<std::path::PathBuf>,
+) -> ExitCode {
     let cap = match parse_capability_file(&file).await {
         Ok(c) => c,
         Err(e) => {
@@ -225,6 +299,17 @@ async fn cap_test(file: std::path::PathBuf, args: String) -> ExitCode {
             return ExitCode::FAILURE;
         }
     };
+    let responses = match mock.as_deref().map(harness::load_yaml::<MockResponses>) {
+        None => None,
+        Some(Ok(file)) => Some(file.responses),
+        Some(Err(e)) => {
+            eprintln!("❌ {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(suite) = suite {
+        return cap_test_suite(&cap, &suite, responses.unwrap_or_default()).await;
+    }
     let params: serde_json::Value = match serde_json::from_str(&args) {
         Ok(v) => v,
         Err(e) => {
@@ -238,8 +323,18 @@ async fn cap_test(file: std::path::PathBuf, args: String) -> ExitCode {
         serde_json::to_string_pretty(&params).unwrap_or_default()
     );
     println!();
-    let executor = Arc::new(CapabilityExecutor::new());
-    match executor.execute(&cap, params).await {
+    let result = if let Some(responses) = responses {
+        let (result, requests) = harness::run_mocked(&cap, params, responses).await;
+        println!(
+            "Requests (mocked):\n{}\n",
+            serde_json::to_string_pretty(&requests).unwrap_or_default()
+        );
+        result
+    } else {
+        let executor = Arc::new(CapabilityExecutor::new());
+        executor.execute(&cap, params).await
+    };
+    match result {
         Ok(result) => {
             println!("✅ Success:\n");
             println!(
@@ -255,20 +350,59 @@ async fn cap_test(file: std::path::PathBuf, args: String) -> ExitCode {
     }
 }
 
+/// Run a `cap test --suite` file and print one line per case.
+async fn cap_test_suite(
+    cap: &mcp_gateway::capability::CapabilityDefinition,
+    suite: &std::path::Path,
+    responses: Vec<MockResponse>,
+) -> ExitCode {
+    let suite: TestSuite = match harness::load_yaml(suite) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "Testing capability: {} ({} cases)\n",
+        cap.name,
+        suite.cases.len()
+    );
+    let outcomes = harness::run_suite(cap, &suite, &responses).await;
+    let failed = outcomes.iter().filter(|o| !o.passed()).count();
+    for outcome in &outcomes {
+        if outcome.passed() {
+            println!("  ✅ {}", outcome.name);
+        } else {
+            println!("  ❌ {}", outcome.name);
+            for failure in &outcome.failures {
+                println!("     - {failure}");
+            }
+        }
+    }
+    println!("\n{} passed, {failed} failed", outcomes.len() - failed);
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
 async fn cap_discover(
+    discovery: &AutoDiscovery,
     format: String,
     write_config: bool,
     config_path: Option<std::path::PathBuf>,
     shadow: bool,
     gateway_config: Option<std::path::PathBuf>,
+    probe_timeout: Option<std::time::Duration>,
 ) -> ExitCode {
-    let discovery = AutoDiscovery::new();
     let structured_output = matches!(format.as_str(), "json" | "yaml");
     if !structured_output {
         println!("🔍 Discovering MCP servers...\n");
     }
     match discovery.discover_all().await {
-        Ok(servers) => {
+        Ok(mut servers) => {
             if shadow {
                 return cap_discover_shadow(
                     servers,
@@ -284,8 +418,23 @@ async fn cap_discover(
                 print_discover_empty();
                 return ExitCode::SUCCESS;
             }
+            if write_config && let Some(timeout) = probe_timeout {
+                if !structured_output {
+                    println!("🩺 Probing {} server(s)...\n", servers.len());
+                }
+                probe_all(&mut servers, timeout).await;
+            }
             print_discovered_servers(&servers, &format);
             if write_config {
+                let unreachable = servers
+                    .iter()
+                    .filter(|s| s.metadata.probe.as_ref().is_some_and(|p| !p.reachable))
+                    .count();
+                if unreachable > 0 {
+                    println!(
+                        "\n⚠️  {unreachable} server(s) did not answer and will be written disabled."
+                    );
+                }
                 println!("\n📝 Writing discovered servers to config...");
                 match crate::write_discovered_to_config(&servers, config_path.as_deref()) {
                     Ok(path) => {
@@ -519,20 +668,38 @@ fn print_server_entry(server: &mcp_gateway::discovery::DiscoveredServer) {
     if let Some(pid) = server.metadata.pid {
         println!("   PID: {pid}");
     }
+    if let Some(expires_at) = server.metadata.expires_at {
+        println!("   Advertised until: {}", expires_at.format("%H:%M:%S UTC"));
+    }
+    match &server.metadata.probe {
+        Some(probe) if probe.reachable => println!(
+            "   Probe: ✅ protocol {}, {} tool(s), {}ms",
+            probe.protocol_version.as_deref().unwrap_or("?"),
+            probe.tool_count.unwrap_or(0),
+            probe.elapsed_ms
+        ),
+        Some(probe) => println!(
+            "   Probe: ❌ unreachable: {}",
+            probe.error.as_deref().unwrap_or("unknown error")
+        ),
+        None => {}
+    }
     println!();
 }
 
 async fn cap_install(
-    name: String,
+    name: Option<String>,
+    frozen: bool,
     from_github: bool,
     repo: String,
     branch: String,
     output: std::path::PathBuf,
+    config_path: Option<&Path>,
 ) -> ExitCode {
-    if from_github {
+    if let (true, Some(name)) = (from_github, &name) {
         println!("📦 Installing {name} from GitHub ({repo})...");
         let registry = Registry::new(&output);
-        match registry.install_from_github(&name, &repo, &branch).await {
+        match registry.install_from_github(name, &repo, &branch).await {
             Ok(path) => {
                 println!("✅ Installed to {}", path.display());
                 ExitCode::SUCCESS
@@ -542,14 +709,217 @@ async fn cap_install(
                 ExitCode::FAILURE
             }
         }
+    } else if let Some(registries) = remote_registries(config_path) {
+        install_from_registries(&registries, name, frozen, &output).await
+    } else if frozen {
+        eprintln!(
+            "❌ --frozen needs `registry.sources` configured to reinstall locked capabilities"
+        );
+        ExitCode::FAILURE
     } else {
         println!("ℹ️  All capabilities are already available in the capabilities directory.");
         println!("   Use 'cap list' to see available capabilities.");
+        println!("   Configure `registry.sources` to install from remote registries.");
+        ExitCode::SUCCESS
+    }
+}
+
+/// Install `name` (or, when frozen without a name, every registry entry of
+/// the lockfile), honouring `<output>/capabilities.lock`.
+async fn install_from_registries(
+    registries: &RemoteRegistries,
+    name: Option<String>,
+    frozen: bool,
+    output: &Path,
+) -> ExitCode {
+    let mut lock = match Lockfile::load(output) {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let names: Vec<String> = name.map_or_else(
+        || {
+            lock.capabilities
+                .iter()
+                .filter(|(_, locked)| locked.registry.is_some())
+                .map(|(name, _)| name.clone())
+                .collect()
+        },
+        |name| vec![name],
+    );
+    if names.is_empty() {
+        println!(
+            "ℹ️  {LOCKFILE_NAME} in {} locks no registry installs",
+            output.display()
+        );
+        return ExitCode::SUCCESS;
+    }
+    println!(
+        "📦 Installing {} from registries ({})...",
+        names.join(", "),
+        registries.names().collect::<Vec<_>>().join(", ")
+    );
+
+    let mut failed = false;
+    let mut locked_new = false;
+    for name in &names {
+        let result = match lock.capabilities.get(name) {
+            Some(locked) => registries.install_locked(name, locked, output).await,
+            None if frozen => {
+                eprintln!(
+                    "❌ {name} is not in {LOCKFILE_NAME}; install it without --frozen to lock it"
+                );
+                failed = true;
+                continue;
+            }
+            None => registries.install(name, output).await,
+        };
+        let installed = match result {
+            Ok(installed) => installed,
+            Err(e) => {
+                eprintln!("❌ Installation of {name} failed: {e}");
+                failed = true;
+                continue;
+            }
+        };
+        print_install(name, &installed);
+        if !lock.capabilities.contains_key(name) {
+            let path = installed
+                .path
+                .strip_prefix(output)
+                .unwrap_or(&installed.path);
+            lock.capabilities.insert(
+                name.clone(),
+                LockedCapability {
+                    path: path.to_string_lossy().replace('\\', "/"),
+                    sha256: installed.sha256,
+                    version: installed.version,
+                    registry: Some(installed.registry),
+                    package: Some(installed.package),
+                    publisher: installed.publisher,
+                    signature: installed.signature,
+                },
+            );
+            locked_new = true;
+        }
+    }
+
+    if locked_new {
+        match lock.save(output) {
+            Ok(()) => println!("🔒 Updated {}", output.join(LOCKFILE_NAME).display()),
+            Err(e) => {
+                eprintln!("❌ Failed to write {LOCKFILE_NAME}: {e}");
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
         ExitCode::SUCCESS
     }
 }
 
-async fn cap_search(query: String, capabilities: std::path::PathBuf) -> ExitCode {
+fn print_install(name: &str, installed: &RemoteInstall) {
+    let version = installed
+        .version
+        .as_deref()
+        .map_or_else(String::new, |v| format!(" {v}"));
+    let cached = if installed.cached {
+        " (offline: from cache)"
+    } else {
+        ""
+    };
+    let signed = installed
+        .publisher
+        .as_deref()
+        .map_or_else(String::new, |p| format!(", signed by {p}"));
+    println!(
+        "✅ Installed {name}{version} from '{}' to {}{signed}{cached}",
+        installed.registry,
+        installed.path.display()
+    );
+}
+
+/// Write `<directory>/capabilities.lock`, or with `frozen` check the
+/// directory against it.
+fn cap_lock(directory: &Path, frozen: bool) -> ExitCode {
+    let locked = match Lockfile::load(directory) {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let installed = match Lockfile::scan(directory, &locked) {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("❌ Failed to scan {}: {e}", directory.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    if frozen {
+        let drift = locked.drift(&installed);
+        if drift.is_empty() {
+            println!(
+                "✅ {} matches {LOCKFILE_NAME} ({} capabilities)",
+                directory.display(),
+                locked.capabilities.len()
+            );
+            return ExitCode::SUCCESS;
+        }
+        eprintln!(
+            "❌ {} has drifted from {LOCKFILE_NAME}:",
+            directory.display()
+        );
+        for item in &drift {
+            eprintln!("   - {item}");
+        }
+        return ExitCode::FAILURE;
+    }
+    if let Err(e) = installed.save(directory) {
+        eprintln!("❌ Failed to write {LOCKFILE_NAME}: {e}");
+        return ExitCode::FAILURE;
+    }
+    println!(
+        "🔒 Locked {} capabilities in {}",
+        installed.capabilities.len(),
+        directory.join(LOCKFILE_NAME).display()
+    );
+    ExitCode::SUCCESS
+}
+
+/// Client for the configured remote registries, or `None` when there are none.
+pub(super) fn remote_registries(config_path: Option<&Path>) -> Option<RemoteRegistries> {
+    let config = match Config::load(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("⚠️  Could not load config ({e}); remote registries unavailable");
+            return None;
+        }
+    };
+    if config.registry.sources.is_empty() {
+        return None;
+    }
+    match RemoteRegistries::new(&config.registry) {
+        Ok(registries) => Some(registries),
+        Err(e) => {
+            eprintln!("⚠️  {e}");
+            None
+        }
+    }
+}
+
+async fn cap_search(
+    query: String,
+    capabilities: std::path::PathBuf,
+    config_path: Option<&Path>,
+) -> ExitCode {
+    if let Some(registries) = remote_registries(config_path) {
+        print_remote_search(&registries.search(&query).await, &query);
+    }
     let reg = Registry::new(&capabilities);
     match reg.build_index().await {
         Ok(index) => {
@@ -580,6 +950,38 @@ async fn cap_search(query: String, capabilities: std::path::PathBuf) -> ExitCode
     }
 }
 
+fn print_remote_search(search: &RegistrySearch, query: &str) {
+    for (registry, reason) in &search.unavailable {
+        eprintln!("⚠️  Registry '{registry}' unavailable: {reason}");
+    }
+    if search.matches.is_empty() {
+        println!("No remote capabilities found matching '{query}'\n");
+        return;
+    }
+    println!(
+        "Found {} remote capability(ies) matching '{query}':\n",
+        search.matches.len()
+    );
+    for found in &search.matches {
+        let entry = &found.entry;
+        let auth = if entry.requires_key { " 🔑" } else { "" };
+        let version = entry
+            .version
+            .as_deref()
+            .map_or_else(String::new, |v| format!(" {v}"));
+        let cached = if found.cached { ", cached" } else { "" };
+        println!(
+            "  {}{version} - {}{auth} [{}{cached}]",
+            entry.name, entry.description, found.registry
+        );
+        if !entry.tags.is_empty() {
+            println!("    Tags: {}", entry.tags.join(", "));
+        }
+        println!();
+    }
+    println!("Install with: mcp-gateway cap install <name>\n");
+}
+
 async fn cap_registry_list(capabilities: std::path::PathBuf) -> ExitCode {
     let reg = Registry::new(&capabilities);
     match reg.build_index().await {
@@ -603,6 +1005,83 @@ async fn cap_registry_list(capabilities: std::path::PathBuf) -> ExitCode {
     }
 }
 
+/// `signer` is the publisher and the environment variable holding its key.
+fn cap_publish(
+    file: &Path,
+    registry: &Path,
+    notes: Option<&str>,
+    signer: Option<(&str, &str)>,
+) -> ExitCode {
+    let key = match signer
+        .map(|(publisher, var)| signing_key(publisher, var))
+        .transpose()
+    {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let outcome = match publish_capability(registry, file, notes, key.as_ref()) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            eprintln!("❌ Publish failed: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if outcome.unchanged {
+        println!(
+            "✅ {} {} is already published (unchanged)",
+            outcome.name, outcome.version
+        );
+        return ExitCode::SUCCESS;
+    }
+    match &outcome.previous {
+        Some(previous) => println!(
+            "✅ Published {} {previous} -> {}",
+            outcome.name, outcome.version
+        ),
+        None => println!("✅ Published {} {}", outcome.name, outcome.version),
+    }
+    println!("   package: {}", outcome.package.display());
+    println!("   sha256:  {}", outcome.sha256);
+    if let Some(publisher) = &outcome.publisher {
+        println!("   signed:  {publisher}");
+    }
+    if outcome.schema_change != SchemaChange::None {
+        println!("   schema:  {:?} change", outcome.schema_change);
+        for reason in &outcome.reasons {
+            println!("     - {reason}");
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn signing_key(publisher: &str, var: &str) -> mcp_gateway::Result<SigningKey> {
+    let seed = std::env::var(var).map_err(|_| {
+        mcp_gateway::Error::Config(format!(
+            "Signing key: environment variable {var} is not set"
+        ))
+    })?;
+    SigningKey::from_seed(publisher, &seed)
+}
+
+fn cap_keygen(publisher: &str) -> ExitCode {
+    let (seed, public_key) = match generate_signing_key() {
+        Ok(pair) => pair,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("🔑 Signing key for publisher '{publisher}'\n");
+    println!("Private key (keep secret; export it for `cap publish --signing-key-env`):");
+    println!("  {seed}\n");
+    println!("Public key (add to registry.sources[].public_keys of trusting gateways):");
+    println!("  {publisher}: \"{public_key}\"");
+    ExitCode::SUCCESS
+}
+
 #[cfg(test)]
 mod tests {
     use super::cap_pin;
@@ -0,0 +1,163 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! `cap outdated` and `cap update` handlers for `mcp-gateway`.
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use mcp_gateway::registry::{
+    lockfile::{LOCKFILE_NAME, LockedCapability, Lockfile},
+    remote::RemoteRegistries,
+    update::{AvailableUpdate, changelog_snippet, find_updates},
+};
+
+/// Longest changelog line printed per version.
+const SNIPPET_CHARS: usize = 100;
+
+/// Print the capabilities in `directory` that have newer registry versions.
+pub(super) async fn cap_outdated(directory: &Path, config_path: Option<&Path>) -> ExitCode {
+    let Some((_, _, updates)) = available_updates(directory, config_path).await else {
+        return ExitCode::FAILURE;
+    };
+    if updates.is_empty() {
+        println!(
+            "✅ All capabilities in {} are up to date",
+            directory.display()
+        );
+        return ExitCode::SUCCESS;
+    }
+    println!("{} capability(ies) can be upgraded:\n", updates.len());
+    for update in &updates {
+        print_update(update);
+    }
+    println!("Upgrade with: mcp-gateway cap update [name...]");
+    ExitCode::SUCCESS
+}
+
+/// Upgrade `names` (every outdated capability when empty) in place.
+pub(super) async fn cap_update(
+    names: &[String],
+    directory: &Path,
+    dry_run: bool,
+    config_path: Option<&Path>,
+) -> ExitCode {
+    let Some((registries, mut lock, updates)) = available_updates(directory, config_path).await
+    else {
+        return ExitCode::FAILURE;
+    };
+    for name in names {
+        if !updates.iter().any(|update| &update.name == name) {
+            println!(
+                "ℹ️  {name} is up to date or not installed in {}",
+                directory.display()
+            );
+        }
+    }
+    let selected: Vec<&AvailableUpdate> = updates
+        .iter()
+        .filter(|update| names.is_empty() || names.contains(&update.name))
+        .collect();
+    if selected.is_empty() {
+        println!("✅ Nothing to upgrade");
+        return ExitCode::SUCCESS;
+    }
+
+    let mut failed = false;
+    for update in selected {
+        if dry_run {
+            print_update(update);
+            continue;
+        }
+        let path = directory.join(&update.path);
+        match registries
+            .install_entry(&update.registry, &update.entry, &path)
+            .await
+        {
+            Ok(installed) => {
+                println!(
+                    "✅ Upgraded {} {} → {} ({})",
+                    update.name,
+                    update.installed,
+                    update.latest,
+                    path.display()
+                );
+                lock.capabilities.insert(
+                    update.name.clone(),
+                    LockedCapability {
+                        path: update.path.clone(),
+                        sha256: installed.sha256,
+                        version: installed.version,
+                        registry: Some(installed.registry),
+                        package: Some(installed.package),
+                        publisher: installed.publisher,
+                        signature: installed.signature,
+                    },
+                );
+            }
+            Err(e) => {
+                eprintln!("❌ Upgrade of {} failed: {e}", update.name);
+                failed = true;
+            }
+        }
+    }
+    if dry_run {
+        println!("(dry run: nothing changed)");
+        return ExitCode::SUCCESS;
+    }
+    if let Err(e) = lock.save(directory) {
+        eprintln!("❌ Failed to write {LOCKFILE_NAME}: {e}");
+        failed = true;
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Registries, the directory's lockfile and the updates available for it;
+/// `None` after printing why they could not be determined.
+async fn available_updates(
+    directory: &Path,
+    config_path: Option<&Path>,
+) -> Option<(RemoteRegistries, Lockfile, Vec<AvailableUpdate>)> {
+    let Some(registries) = super::cap::remote_registries(config_path) else {
+        eprintln!("❌ No remote registries configured (`registry.sources`)");
+        return None;
+    };
+    let installed = Lockfile::load(directory)
+        .and_then(|lock| Lockfile::scan(directory, &lock).map(|installed| (lock, installed)));
+    let (lock, installed) = match installed {
+        Ok(pair) => pair,
+        Err(e) => {
+            eprintln!("❌ Failed to read {}: {e}", directory.display());
+            return None;
+        }
+    };
+    let (indexes, unavailable) = registries.indexes().await;
+    for (registry, reason) in &unavailable {
+        eprintln!("⚠️  Registry '{registry}' unavailable: {reason}");
+    }
+    let updates = find_updates(&installed, &indexes);
+    Some((registries, lock, updates))
+}
+
+fn print_update(update: &AvailableUpdate) {
+    let breaking = if update.is_breaking() {
+        "  ⚠️  breaking"
+    } else {
+        ""
+    };
+    println!(
+        "  {} {} → {} [{}]{breaking}",
+        update.name, update.installed, update.latest, update.registry
+    );
+    for change in &update.changes {
+        println!(
+            "    {}: {}",
+            change.version,
+            changelog_snippet(&change.notes, SNIPPET_CHARS)
+        );
+    }
+    println!();
+}
@@ -0,0 +1,286 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Implementation of `mcp-gateway discover remote`.
+//!
+//! Searches the public MCP directories, prints each hit with its install
+//! metadata and, for the servers picked with `--add`, writes a backend
+//! entry into the gateway config.
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use mcp_gateway::{
+    cli::{DiscoverCommand, output::OutputFormat},
+    config::TransportConfig,
+    config_persistence::{load_existing_or_default, write_config},
+    discovery::directory::{
+        DirectoryClient, DirectoryKind, DirectorySearch, DirectoryServer, SMITHERY_API_KEY_ENV,
+    },
+};
+use serde_json::json;
+
+/// Run a `discover` subcommand.
+pub async fn run_discover_command(cmd: DiscoverCommand, config_path: Option<&Path>) -> ExitCode {
+    let DiscoverCommand::Remote {
+        query,
+        sources,
+        limit,
+        format,
+        add,
+    } = cmd;
+    let sources = if sources.is_empty() {
+        // Smithery needs an API key; only ask it when one is configured
+        // or it was requested explicitly.
+        let has_key = std::env::var(SMITHERY_API_KEY_ENV).is_ok_and(|key| !key.is_empty());
+        DirectoryKind::ALL
+            .into_iter()
+            .filter(|kind| has_key || *kind != DirectoryKind::Smithery)
+            .collect()
+    } else {
+        sources
+    };
+
+    let client = match DirectoryClient::new() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let search = client.search_all(&sources, &query, limit).await;
+    print_search(&search, &query, format);
+    if search.servers.is_empty() && !search.unavailable.is_empty() {
+        return ExitCode::FAILURE;
+    }
+    if add.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    let path = config_path.unwrap_or_else(|| Path::new("gateway.yaml"));
+    match add_servers(&search.servers, &add, path) {
+        Ok(added) => {
+            for (name, server) in added {
+                println!(
+                    "Added '{name}' from {} ({}).",
+                    server.directory.label(),
+                    server.id
+                );
+                for var in server.env.iter().filter(|var| var.required) {
+                    let status = if std::env::var(&var.name).is_ok() {
+                        "set"
+                    } else {
+                        "NOT SET"
+                    };
+                    println!("  Required: {} {status}", var.name);
+                }
+            }
+            println!("Config written to {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Find the server `name` selects: an exact id wins, otherwise the backend
+/// name must identify a single server.
+fn select<'a>(servers: &'a [DirectoryServer], name: &str) -> Result<&'a DirectoryServer, String> {
+    if let Some(server) = servers.iter().find(|s| s.id == name) {
+        return Ok(server);
+    }
+    let matches: Vec<&DirectoryServer> = servers.iter().filter(|s| s.matches(name)).collect();
+    match matches.as_slice() {
+        [] => Err(format!("'{name}' is not in the search results")),
+        [server] => Ok(server),
+        several => Err(format!(
+            "'{name}' matches several servers; pick one by id: {}",
+            several
+                .iter()
+                .map(|s| s.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Add every server selected by `names` to the config at `path`. Nothing
+/// is written unless all of them can be added.
+fn add_servers<'a>(
+    servers: &'a [DirectoryServer],
+    names: &[String],
+    path: &Path,
+) -> Result<Vec<(String, &'a DirectoryServer)>, String> {
+    let mut config = load_existing_or_default(path).map_err(|e| e.to_string())?;
+    let mut added = Vec::with_capacity(names.len());
+    for name in names {
+        let server = select(servers, name)?;
+        let backend = server.to_backend_config().ok_or_else(|| {
+            format!(
+                "'{}' lists no remote URL or package the gateway can run",
+                server.id
+            )
+        })?;
+        let backend_name = server.backend_name();
+        if config.backends.contains_key(&backend_name) {
+            return Err(format!(
+                "Backend '{backend_name}' already exists in {}. Remove it first.",
+                path.display()
+            ));
+        }
+        config.backends.insert(backend_name.clone(), backend);
+        added.push((backend_name, server));
+    }
+    write_config(path, &config)?;
+    Ok(added)
+}
+
+fn install_line(transport: Option<&TransportConfig>) -> String {
+    match transport {
+        Some(TransportConfig::Stdio { command, .. }) => format!("stdio: {command}"),
+        Some(TransportConfig::Http {
+            http_url,
+            streamable_http,
+            ..
+        }) => {
+            let kind = if *streamable_http {
+                "streamable-http"
+            } else {
+                "sse"
+            };
+            format!("{kind}: {http_url}")
+        }
+        #[cfg(feature = "a2a")]
+        Some(TransportConfig::A2a { a2a_url, .. }) => format!("a2a: {a2a_url}"),
+        None => "no supported install method".to_string(),
+    }
+}
+
+fn print_search(search: &DirectorySearch, query: &str, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            let unavailable: Vec<_> = search
+                .unavailable
+                .iter()
+                .map(|(kind, reason)| json!({"directory": kind, "reason": reason}))
+                .collect();
+            println!(
+                "{}",
+                json!({"servers": search.servers, "unavailable": unavailable})
+            );
+        }
+        OutputFormat::Plain => {
+            for server in &search.servers {
+                println!("{}", server.id);
+            }
+        }
+        OutputFormat::Table => print_table(search, query),
+    }
+    if format != OutputFormat::Json {
+        for (kind, reason) in &search.unavailable {
+            eprintln!("Warning: {} skipped: {reason}", kind.label());
+        }
+    }
+}
+
+fn print_table(search: &DirectorySearch, query: &str) {
+    if search.servers.is_empty() {
+        println!("No servers found for '{query}'.");
+        return;
+    }
+    println!("{} server(s) found for '{query}':\n", search.servers.len());
+    for server in &search.servers {
+        let version = server
+            .version
+            .as_deref()
+            .map_or_else(String::new, |v| format!(" {v}"));
+        println!(
+            "  [{}] {}{version} -> backend '{}'",
+            server.directory.label(),
+            server.id,
+            server.backend_name()
+        );
+        if !server.description.is_empty() {
+            println!("    {}", server.description);
+        }
+        println!("    {}", install_line(server.transport.as_ref()));
+        for var in &server.env {
+            let mut flags = Vec::new();
+            if var.required {
+                flags.push("required");
+            }
+            if var.secret {
+                flags.push("secret");
+            }
+            let flags = if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", flags.join(", "))
+            };
+            println!("    env: {}{flags}", var.name);
+        }
+        if let Some(homepage) = &server.homepage {
+            println!("    {homepage}");
+        }
+    }
+    println!("\nAdd a server with: mcp-gateway discover remote --query '{query}' --add <NAME>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_gateway::discovery::directory::DirectoryEnvVar;
+
+    fn server(directory: DirectoryKind, id: &str) -> DirectoryServer {
+        DirectoryServer {
+            directory,
+            id: id.to_string(),
+            description: format!("{id} tools"),
+            version: None,
+            homepage: None,
+            transport: Some(TransportConfig::Stdio {
+                command: format!("npx -y {id}"),
+                cwd: None,
+                protocol_version: None,
+            }),
+            env: vec![DirectoryEnvVar {
+                name: "GITHUB_TOKEN".to_string(),
+                required: true,
+                secret: true,
+                description: String::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn add_servers_writes_selected_backends_with_env_placeholders() {
+        // GIVEN: search results with the same backend name in two directories
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gateway.yaml");
+        let servers = [
+            server(DirectoryKind::Official, "io.github.acme/github"),
+            server(DirectoryKind::Pulsemcp, "@other/github"),
+        ];
+
+        // WHEN: picking by the ambiguous backend name
+        let err = add_servers(&servers, &["github".to_string()], &path).unwrap_err();
+
+        // THEN: nothing is written and the ids are offered instead
+        assert!(err.contains("io.github.acme/github"), "{err}");
+        assert!(!path.exists());
+
+        // WHEN: picking by id
+        let added = add_servers(&servers, &["@other/github".to_string()], &path).unwrap();
+
+        // THEN: the backend is written with a placeholder, not a secret
+        assert_eq!(added[0].0, "github");
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("npx -y @other/github"), "{written}");
+        assert!(written.contains("${GITHUB_TOKEN}"), "{written}");
+
+        // AND: adding it again is refused
+        let err = add_servers(&servers, &["@other/github".to_string()], &path).unwrap_err();
+        assert!(err.contains("already exists"), "{err}");
+    }
+}
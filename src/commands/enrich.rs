@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Enrich command handler for `mcp-gateway enrich`.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use futures::future::join_all;
+use mcp_gateway::{
+    backend::{Backend, runtime_plan_for_backend},
+    config::Config,
+    enrichment::{EnrichmentClient, EnrichmentOverlay, OVERLAY_VERSION, ToolSource},
+};
+
+/// Run the `enrich` command: collect backend tools, enrich the new or
+/// changed ones with the configured LLM, and write the overlay file.
+pub async fn run_enrich_command(
+    config_path: Option<&Path>,
+    output: Option<PathBuf>,
+    model: Option<String>,
+    force: bool,
+    dry_run: bool,
+) -> ExitCode {
+    let config = match Config::load(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("❌ Failed to load config: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut settings = config.enrichment.clone();
+    if let Some(model) = model {
+        settings.model = model;
+    }
+    let Some(path) = output.or_else(|| settings.overlay_path()) else {
+        eprintln!("❌ No overlay file: pass --output or set enrichment.overlay_file");
+        return ExitCode::FAILURE;
+    };
+
+    let tools = collect_tools(&config).await;
+    let mut overlay = if force || !path.exists() {
+        EnrichmentOverlay::default()
+    } else {
+        match EnrichmentOverlay::load(&path) {
+            Ok(overlay) => overlay,
+            Err(e) => {
+                eprintln!("⚠️  {e}; starting a new overlay");
+                EnrichmentOverlay::default()
+            }
+        }
+    };
+    // Drop entries for tools that no longer exist.
+    overlay
+        .tools
+        .retain(|key, _| tools.iter().any(|t| t.key() == *key));
+    let pending: Vec<ToolSource> = tools
+        .iter()
+        .filter(|t| overlay.get(&t.server, &t.name, &t.description).is_none())
+        .cloned()
+        .collect();
+
+    println!(
+        "🔎 {} tools found, {} to enrich with {}",
+        tools.len(),
+        pending.len(),
+        settings.model
+    );
+    if dry_run {
+        for tool in &pending {
+            println!("  • {}", tool.key());
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let client = EnrichmentClient::from_config(&settings);
+    let mut failed = 0;
+    for batch in pending.chunks(settings.batch_size) {
+        match client.enrich(batch).await {
+            Ok(entries) => {
+                failed += batch.len().saturating_sub(entries.len());
+                overlay.tools.extend(entries);
+            }
+            Err(e) => {
+                eprintln!("⚠️  Batch of {} tools failed: {e}", batch.len());
+                failed += batch.len();
+            }
+        }
+    }
+
+    overlay.version = OVERLAY_VERSION;
+    overlay.model = client.model().to_string();
+    overlay.generated_at = chrono::Utc::now().to_rfc3339();
+    if let Err(e) = overlay.save(&path) {
+        eprintln!("❌ Failed to write {}: {e}", path.display());
+        return ExitCode::FAILURE;
+    }
+
+    println!(
+        "✅ Wrote {} enriched tools to {}",
+        overlay.len(),
+        path.display()
+    );
+    if failed > 0 {
+        eprintln!("⚠️  {failed} tools were not enriched; re-run to retry them");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+/// Start every enabled backend and collect its tool definitions.
+///
+/// Backends that fail to start are reported and skipped.
+async fn collect_tools(config: &Config) -> Vec<ToolSource> {
+    let fetches = config.enabled_backends().map(|(name, backend_config)| {
+        let backend = Backend::new_with_runtime_plan(
+            name,
+            backend_config.clone(),
+            &config.failsafe,
+            config.meta_mcp.cache_ttl,
+            runtime_plan_for_backend(name, backend_config, &config.runtime),
+        );
+        async move {
+            let tools = backend.get_tools().await;
+            let _ = backend.stop().await;
+            match tools {
+                Ok(tools) => tools
+                    .into_iter()
+                    .map(|tool| ToolSource {
+                        server: backend.name.clone(),
+                        name: tool.name,
+                        description: tool.description.unwrap_or_default(),
+                    })
+                    .collect(),
+                Err(e) => {
+                    eprintln!("⚠️  {}: {e}", backend.name);
+                    Vec::new()
+                }
+            }
+        }
+    });
+    let mut tools: Vec<ToolSource> = join_all(fetches).await.into_iter().flatten().collect();
+    tools.sort_by_key(ToolSource::key);
+    tools
+}
@@ -8,11 +8,14 @@
 #[cfg(feature = "webui")]
 mod add_remove;
 mod cap;
+mod cap_update;
 #[cfg(feature = "config-export")]
 mod config_export;
 #[cfg(feature = "discovery")]
 pub(crate) mod discover;
+mod discover_remote;
 mod doctor;
+mod enrich;
 mod identity;
 mod kubernetes;
 pub mod paths;
@@ -22,6 +25,7 @@ mod ranking;
 mod setup;
 mod skills;
 mod stats;
+mod transitions;
 mod trust;
 mod upgrade;
 
@@ -30,7 +34,9 @@ pub use add_remove::{run_add_command, run_get_command, run_list_command, run_rem
 pub use cap::run_cap_command;
 #[cfg(feature = "config-export")]
 pub use config_export::run_config_export;
+pub use discover_remote::run_discover_command;
 pub use doctor::{run_doctor_command, run_doctor_shadow_command};
+pub use enrich::run_enrich_command;
 pub use identity::run_identity_command;
 pub use kubernetes::run_kubernetes_command;
 pub use plugin::{run_plugin_install, run_plugin_list, run_plugin_search, run_plugin_uninstall};
@@ -42,6 +48,7 @@ pub use skills::{
     run_skills_show,
 };
 pub use stats::{default_stats_url, run_stats_command};
+pub use transitions::run_transitions_command;
 pub use trust::run_trust_command;
 pub use upgrade::{check_upgrade, data_dir as upgrade_data_dir, run_upgrade_command};
 
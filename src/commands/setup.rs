@@ -198,6 +198,7 @@ fn source_label(source: &DiscoverySource) -> String {
         DiscoverySource::McpConfig => "~/.config/mcp".to_string(),
         DiscoverySource::RunningProcess => "running process".to_string(),
         DiscoverySource::Environment => "environment".to_string(),
+        DiscoverySource::Mdns => "local network (mDNS)".to_string(),
     }
 }
 
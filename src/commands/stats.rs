@@ -95,6 +95,8 @@ fn print_stats_body(body: &serde_json::Value, _url: &str) -> ExitCode {
         );
         println!("Estimated Savings: {}", stats["estimated_savings_usd"]);
         print_top_tools(&stats);
+        print_latency(&stats);
+        print_breakdowns(&stats);
         return ExitCode::SUCCESS;
     }
     if let Some(error) = body.get("error") {
@@ -136,6 +138,41 @@ fn print_top_tools(stats: &serde_json::Value) {
     }
 }
 
+fn print_latency(stats: &serde_json::Value) {
+    if let Some(servers) = stats["latency_by_server"].as_array()
+        && !servers.is_empty()
+    {
+        println!("\n⏱  Latency by Backend (p50 / p95 / p99 ms):");
+        for entry in servers {
+            println!(
+                "  • {} - {:.1} / {:.1} / {:.1} ({} samples)",
+                entry["key"].as_str().unwrap_or(""),
+                entry["p50_ms"].as_f64().unwrap_or(0.0),
+                entry["p95_ms"].as_f64().unwrap_or(0.0),
+                entry["p99_ms"].as_f64().unwrap_or(0.0),
+                entry["samples"]
+            );
+        }
+    }
+}
+
+fn print_breakdowns(stats: &serde_json::Value) {
+    for (field, title) in [
+        ("errors_by_class", "❗ Errors by Class"),
+        ("cache_hits_by_kind", "💾 Cache Hits by Kind"),
+        ("cache_hits_by_server", "💾 Cache Hits by Backend"),
+    ] {
+        if let Some(counts) = stats[field].as_object()
+            && !counts.is_empty()
+        {
+            println!("\n{title}:");
+            for (name, count) in counts {
+                println!("  • {name}: {count}");
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
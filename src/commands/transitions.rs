@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Transition report command handlers for `mcp-gateway transitions`.
+
+use std::{path::Path, process::ExitCode};
+
+use mcp_gateway::{
+    cli::{TransitionsCommand, output::OutputFormat},
+    transition::{ToolSequence, TransitionCount, TransitionTracker},
+};
+use serde::Serialize;
+
+use super::paths::home_path;
+
+/// Run a `transitions` subcommand.
+pub fn run_transitions_command(cmd: TransitionsCommand) -> ExitCode {
+    match cmd {
+        TransitionsCommand::Report {
+            file,
+            limit,
+            min_count,
+            max_length,
+            format,
+        } => {
+            let path = file.unwrap_or_else(|| home_path(".mcp-gateway/transitions.json"));
+            match build_report(&path, limit, min_count, max_length) {
+                Ok(report) => {
+                    print_report(&report, format);
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TransitionReport {
+    schema_version: &'static str,
+    total_transitions: u64,
+    transitions: Vec<TransitionCount>,
+    sequences: Vec<ToolSequence>,
+}
+
+fn build_report(
+    path: &Path,
+    limit: usize,
+    min_count: u64,
+    max_length: usize,
+) -> Result<TransitionReport, String> {
+    if !path.exists() {
+        return Err(format!(
+            "No transition data at {}; it is written while the gateway serves tool calls",
+            path.display()
+        ));
+    }
+    let tracker = TransitionTracker::new();
+    tracker
+        .load(path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    Ok(report_for(&tracker, limit, min_count, max_length))
+}
+
+fn report_for(
+    tracker: &TransitionTracker,
+    limit: usize,
+    min_count: u64,
+    max_length: usize,
+) -> TransitionReport {
+    TransitionReport {
+        schema_version: "transitions-report.v1",
+        total_transitions: tracker.total_transitions(),
+        transitions: tracker.top_transitions(limit),
+        sequences: tracker.top_sequences(max_length.max(2), min_count, limit),
+    }
+}
+
+fn print_report(report: &TransitionReport, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(report).unwrap_or_default()
+            );
+        }
+        OutputFormat::Plain => {
+            for seq in &report.sequences {
+                println!("{}\t{}", seq.count, seq.tools.join(" -> "));
+            }
+        }
+        OutputFormat::Table => {
+            println!(
+                "Learned tool transitions ({} observed)",
+                report.total_transitions
+            );
+            println!();
+            println!("{:>7} {:>6}  SEQUENCE", "COUNT", "CONF");
+            println!("{}", "-".repeat(72));
+            if report.sequences.is_empty() {
+                println!("(no sequence clears the minimum count yet)");
+            }
+            for seq in &report.sequences {
+                println!(
+                    "{:>7} {:>5.0}%  {}",
+                    seq.count,
+                    seq.confidence * 100.0,
+                    seq.tools.join(" → ")
+                );
+            }
+            println!();
+            println!("{:>7} {:>6}  FROM → TO", "COUNT", "CONF");
+            println!("{}", "-".repeat(72));
+            for t in &report.transitions {
+                println!(
+                    "{:>7} {:>5.0}%  {} → {}",
+                    t.count,
+                    t.confidence * 100.0,
+                    t.from,
+                    t.to
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_reads_persisted_transitions() {
+        // GIVEN: a tracker saved the way the gateway persists it
+        let tracker = TransitionTracker::new();
+        for session in ["s1", "s2", "s3"] {
+            tracker.record_transition(session, "gh:search");
+            tracker.record_transition(session, "gh:get_issue");
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transitions.json");
+        tracker.save(&path).unwrap();
+
+        // WHEN: building the report from the file
+        let report = build_report(&path, 10, 3, 4).unwrap();
+
+        // THEN: the repeated pair is listed as a sequence
+        assert_eq!(report.total_transitions, 3);
+        assert_eq!(report.sequences.len(), 1);
+        assert_eq!(report.sequences[0].tools, ["gh:search", "gh:get_issue"]);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["schema_version"], "transitions-report.v1");
+    }
+
+    #[test]
+    fn report_explains_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = build_report(&dir.path().join("missing.json"), 10, 3, 4).unwrap_err();
+        assert!(err.contains("No transition data"), "{err}");
+    }
+}
@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Per-request access log configuration.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Access log ─────────────────────────────────────────────────────────────────
+
+/// One JSON line per completed HTTP request, written to its own file and
+/// kept apart from application tracing.
+///
+/// When the file reaches `max_size_bytes` it is renamed to `<path>.1`
+/// (shifting older files up to `<path>.<max_files>`, the oldest dropped).
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// access_log:
+///   enabled: true
+///   path: ~/.mcp-gateway/access.jsonl
+///   max_size_bytes: 104857600
+///   max_files: 5
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessLogConfig {
+    /// Write the access log (off by default).
+    pub enabled: bool,
+    /// Log file; `~` is expanded.
+    pub path: String,
+    /// Size at which the file is rotated.
+    pub max_size_bytes: u64,
+    /// Rotated files kept besides the live one (0 = truncate on rotation).
+    pub max_files: u32,
+}
+
+impl AccessLogConfig {
+    /// `path` with `~` expanded.
+    #[must_use]
+    pub fn file_path(&self) -> PathBuf {
+        crate::config_reload::expand_tilde(&self.path)
+    }
+
+    /// Validate the sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the log is enabled with an
+    /// empty path or a zero rotation size.
+    pub fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.path.trim().is_empty() {
+            return Err(Error::ConfigValidation(
+                "access_log.path must not be empty".to_string(),
+            ));
+        }
+        if self.max_size_bytes == 0 {
+            return Err(Error::ConfigValidation(
+                "access_log.max_size_bytes must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for AccessLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "~/.mcp-gateway/access.jsonl".to_string(),
+            max_size_bytes: 100 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
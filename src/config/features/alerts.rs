@@ -0,0 +1,244 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Operator alert configuration (auto-kills, breaker trips, disabled
+//! capabilities, threshold rules).
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::BackendConfig;
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_CIRCUIT_POLL_INTERVAL_SECS: u64 = 5;
+const DEFAULT_EVALUATION_INTERVAL_SECS: u64 = 30;
+const DEFAULT_MIN_EVENTS: u64 = 10;
+
+// ── Alerts ─────────────────────────────────────────────────────────────────────
+
+/// Where operator alerts are delivered.
+///
+/// Alerts are always logged. Each sink below is optional; with no sink and
+/// no rule set no alert task runs.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// alerts:
+///   webhook_url: "https://hooks.example.com/mcp-gateway"
+///   slack_webhook_url: "https://hooks.slack.com/services/T000/B000/XXXX"
+///   mcp_notifications: true
+///   rules:
+///     - name: github-errors
+///       backend: github
+///       metric: error_rate
+///       above: 0.2
+///       for: 5m
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertsConfig {
+    /// Endpoint receiving each alert as a JSON POST.
+    pub webhook_url: Option<String>,
+    /// Slack (or Slack-compatible, e.g. Mattermost) incoming-webhook URL.
+    pub slack_webhook_url: Option<String>,
+    /// Send alerts to connected MCP clients as `notifications/message`.
+    pub mcp_notifications: bool,
+    /// How often backend circuit breakers are checked for new trips (and
+    /// OAuth token refreshes for new failures).
+    #[serde(with = "crate::config::humantime_serde")]
+    pub circuit_poll_interval: Duration,
+    /// Threshold rules over backend and cache metrics.
+    pub rules: Vec<AlertRuleConfig>,
+    /// How often `rules` are evaluated.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub evaluation_interval: Duration,
+    /// Minimum calls in a rate rule's window before it may fire, so a single
+    /// failure on an idle backend does not page.
+    pub min_events: u64,
+}
+
+impl AlertsConfig {
+    /// Returns `true` when at least one delivery sink is configured.
+    #[must_use]
+    pub fn has_sinks(&self) -> bool {
+        self.webhook_url.is_some() || self.slack_webhook_url.is_some() || self.mcp_notifications
+    }
+
+    /// Returns `true` when the alert task has anything to do: deliver to a
+    /// sink or evaluate rules (which are logged even without a sink).
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.has_sinks() || !self.rules.is_empty()
+    }
+
+    /// Validate sink URLs and rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if a webhook URL is not `http(s)`,
+    /// or a rule is unnamed, duplicated, names an unknown backend, or has a
+    /// threshold that does not fit its metric.
+    pub fn validate(&self, backends: &HashMap<String, BackendConfig>) -> Result<()> {
+        for (field, url) in [
+            ("webhook_url", &self.webhook_url),
+            ("slack_webhook_url", &self.slack_webhook_url),
+        ] {
+            if let Some(url) = url
+                && !(url.starts_with("https://") || url.starts_with("http://"))
+            {
+                return Err(Error::ConfigValidation(format!(
+                    "alerts.{field} must be an http(s) URL, got '{url}'"
+                )));
+            }
+        }
+        if self.circuit_poll_interval.is_zero() {
+            return Err(Error::ConfigValidation(
+                "alerts.circuit_poll_interval must be greater than zero".to_string(),
+            ));
+        }
+        if !self.rules.is_empty() && self.evaluation_interval.is_zero() {
+            return Err(Error::ConfigValidation(
+                "alerts.evaluation_interval must be greater than zero".to_string(),
+            ));
+        }
+        let mut names = HashSet::new();
+        for rule in &self.rules {
+            if rule.name.is_empty() {
+                return Err(Error::ConfigValidation(
+                    "alerts.rules entries need a name".to_string(),
+                ));
+            }
+            if !names.insert(rule.name.as_str()) {
+                return Err(Error::ConfigValidation(format!(
+                    "alerts.rules has more than one rule named '{}'",
+                    rule.name
+                )));
+            }
+            rule.validate(backends)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            slack_webhook_url: None,
+            mcp_notifications: false,
+            circuit_poll_interval: Duration::from_secs(DEFAULT_CIRCUIT_POLL_INTERVAL_SECS),
+            rules: Vec::new(),
+            evaluation_interval: Duration::from_secs(DEFAULT_EVALUATION_INTERVAL_SECS),
+            min_events: DEFAULT_MIN_EVENTS,
+        }
+    }
+}
+
+// ── Rules ──────────────────────────────────────────────────────────────────────
+
+/// Metric an [`AlertRuleConfig`] watches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// Fraction of a backend's calls that failed (`0.0`–`1.0`), measured
+    /// over the rule's `for` window.
+    ErrorRate,
+    /// A backend's p99 latency in milliseconds over its recent calls.
+    LatencyP99,
+    /// Fraction of response-cache lookups that hit (`0.0`–`1.0`), measured
+    /// over the rule's `for` window. Gateway-wide; takes no `backend`.
+    CacheHitRate,
+    /// A backend's circuit breaker is open or its health tracker marks it
+    /// unhealthy. Takes no threshold.
+    BackendDown,
+}
+
+impl AlertMetric {
+    /// Whether the metric is a rate over a window of calls.
+    #[must_use]
+    pub fn is_rate(self) -> bool {
+        matches!(self, Self::ErrorRate | Self::CacheHitRate)
+    }
+}
+
+/// "Alert when `metric` is `above`/`below` a threshold `for` a duration".
+///
+/// Rate metrics are measured over the trailing `for` window; the others must
+/// breach at every evaluation across it. A firing rule alerts once and sends
+/// a resolve notification when the condition clears.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRuleConfig {
+    /// Rule name used in alerts.
+    pub name: String,
+    /// Backend the rule applies to; every backend when omitted.
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Watched metric.
+    pub metric: AlertMetric,
+    /// Fire when the metric exceeds this value.
+    #[serde(default)]
+    pub above: Option<f64>,
+    /// Fire when the metric drops below this value.
+    #[serde(default)]
+    pub below: Option<f64>,
+    /// How long the condition must hold before the rule fires.
+    #[serde(rename = "for", default, with = "crate::config::humantime_serde")]
+    pub for_duration: Duration,
+}
+
+impl AlertRuleConfig {
+    /// Whether `value` breaches the rule's threshold.
+    #[must_use]
+    pub fn breaches(&self, value: f64) -> bool {
+        if self.metric == AlertMetric::BackendDown {
+            return value > 0.0;
+        }
+        self.above.is_some_and(|above| value > above)
+            || self.below.is_some_and(|below| value < below)
+    }
+
+    fn validate(&self, backends: &HashMap<String, BackendConfig>) -> Result<()> {
+        let name = &self.name;
+        let thresholds = [self.above, self.below];
+        let set = thresholds.iter().flatten().count();
+        if self.metric == AlertMetric::BackendDown {
+            if set != 0 {
+                return Err(Error::ConfigValidation(format!(
+                    "alert rule '{name}': backend_down takes no above/below threshold"
+                )));
+            }
+        } else if set != 1 {
+            return Err(Error::ConfigValidation(format!(
+                "alert rule '{name}' needs exactly one of above or below"
+            )));
+        }
+        if self.metric.is_rate()
+            && let Some(value) = thresholds
+                .into_iter()
+                .flatten()
+                .find(|v| !(0.0..=1.0).contains(v))
+        {
+            return Err(Error::ConfigValidation(format!(
+                "alert rule '{name}': rate thresholds must be between 0 and 1, got {value}"
+            )));
+        }
+        if self.metric.is_rate() && self.for_duration.is_zero() {
+            return Err(Error::ConfigValidation(format!(
+                "alert rule '{name}': rate metrics need a non-zero 'for' window"
+            )));
+        }
+        match &self.backend {
+            Some(_) if self.metric == AlertMetric::CacheHitRate => Err(Error::ConfigValidation(
+                format!("alert rule '{name}': cache_hit_rate is gateway-wide and takes no backend"),
+            )),
+            Some(backend) if !backends.contains_key(backend) => Err(Error::ConfigValidation(
+                format!("alert rule '{name}' names unknown backend '{backend}'"),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
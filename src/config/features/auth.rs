@@ -24,7 +24,7 @@ pub struct AuthConfig {
     /// API keys for multi-client access with optional restrictions.
     #[serde(default)]
     pub api_keys: Vec<ApiKeyConfig>,
-    /// Paths that bypass authentication (default: `["/health"]`).
+    /// Paths that bypass authentication (default: `["/health", "/livez", "/readyz"]`).
     #[serde(default = "default_public_paths")]
     pub public_paths: Vec<String>,
     /// Optional per-client circuit breaker applied after authenticated identity is established.
@@ -63,7 +63,11 @@ impl std::fmt::Debug for AuthConfig {
 }
 
 fn default_public_paths() -> Vec<String> {
-    vec!["/health".to_string()]
+    vec![
+        "/health".to_string(),
+        "/livez".to_string(),
+        "/readyz".to_string(),
+    ]
 }
 
 impl Default for AuthConfig {
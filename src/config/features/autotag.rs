@@ -0,0 +1,97 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Keyword auto-tagging configuration.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_MAX_TAGS: usize = 7;
+const DEFAULT_MIN_WORD_LENGTH: usize = 3;
+
+// ── Autotag ────────────────────────────────────────────────────────────────────
+
+/// Rules for the `[keywords: …]` tags appended to backend tool descriptions.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// autotag:
+///   max_tags: 5
+///   stopwords: [hakee, palauttaa, työkalu]
+///   keywords: [lasku, asiakas]
+///   rules:
+///     - pattern: "(?i)\\b(lasku|invoice)"
+///       tags: [billing]
+///     - pattern: "\\b([A-Z]{2,}-\\d+)\\b"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutotagConfig {
+    /// Maximum tags appended per tool.
+    pub max_tags: usize,
+    /// Words shorter than this (in characters) are ignored.
+    pub min_word_length: usize,
+    /// Extra stopwords, added to the built-in English list.
+    pub stopwords: Vec<String>,
+    /// Use only `stopwords`, dropping the built-in English list.
+    pub replace_default_stopwords: bool,
+    /// Domain terms always tagged when present, ahead of the length-ranked
+    /// fill (added to the built-in list such as `email` and `calendar`).
+    pub keywords: Vec<String>,
+    /// Regex extraction rules, applied before word extraction.
+    pub rules: Vec<AutotagRuleConfig>,
+}
+
+/// A regex extraction rule.
+///
+/// When `pattern` matches a description, `tags` are added. Without `tags`,
+/// the match itself becomes the tag (capture group 1 if the pattern has one).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutotagRuleConfig {
+    /// Regular expression matched against the description.
+    pub pattern: String,
+    /// Tags added when the pattern matches.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl AutotagConfig {
+    /// Validate tag limits and rule patterns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if `max_tags` is zero or a rule
+    /// pattern is not a valid regular expression.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_tags == 0 {
+            return Err(Error::ConfigValidation(
+                "autotag.max_tags must be at least 1".to_string(),
+            ));
+        }
+        for rule in &self.rules {
+            regex::Regex::new(&rule.pattern).map_err(|e| {
+                Error::ConfigValidation(format!(
+                    "autotag rule pattern '{}' is invalid: {e}",
+                    rule.pattern
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for AutotagConfig {
+    fn default() -> Self {
+        Self {
+            max_tags: DEFAULT_MAX_TAGS,
+            min_word_length: DEFAULT_MIN_WORD_LENGTH,
+            stopwords: Vec::new(),
+            replace_default_stopwords: false,
+            keywords: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+}
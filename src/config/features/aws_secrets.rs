@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! AWS Secrets Manager / SSM Parameter Store secret source configuration.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+// ── AWS secrets ────────────────────────────────────────────────────────────────
+
+/// Settings of the `{aws-secrets.NAME#FIELD}` and `{aws-ssm.NAME}` secret
+/// references (and their `aws-secrets:` / `aws-ssm:` capability credential
+/// forms).
+///
+/// The references work without this section when the region is set in the
+/// environment. Requests are signed with credentials from
+/// `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY`, the ECS task role, or the
+/// EC2 instance profile.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// aws_secrets:
+///   region: eu-west-1
+///   cache_ttl: 10m
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AwsSecretsConfig {
+    /// AWS region; defaults to `AWS_REGION`, then `AWS_DEFAULT_REGION`. A
+    /// reference given as a full ARN uses the ARN's region.
+    pub region: Option<String>,
+    /// Endpoint replacing `https://{service}.{region}.amazonaws.com`, e.g. a
+    /// VPC endpoint or `LocalStack`.
+    pub endpoint: Option<String>,
+    /// How long a read secret is reused before AWS is asked again.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub cache_ttl: Duration,
+    /// Timeout of each request to AWS.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub timeout: Duration,
+}
+
+impl Default for AwsSecretsConfig {
+    fn default() -> Self {
+        Self {
+            region: None,
+            endpoint: None,
+            cache_ttl: Duration::from_secs(DEFAULT_CACHE_TTL_SECS),
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl AwsSecretsConfig {
+    /// Validate the region, endpoint and timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the region is blank, the
+    /// endpoint is not `http(s)`, or the timeout is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.region.as_ref().is_some_and(|r| r.trim().is_empty()) {
+            return Err(Error::ConfigValidation(
+                "aws_secrets.region must not be empty".to_string(),
+            ));
+        }
+        if let Some(endpoint) = &self.endpoint
+            && !(endpoint.starts_with("https://") || endpoint.starts_with("http://"))
+        {
+            return Err(Error::ConfigValidation(format!(
+                "aws_secrets.endpoint must be an http(s) URL, got '{endpoint}'"
+            )));
+        }
+        if self.timeout.is_zero() {
+            return Err(Error::ConfigValidation(
+                "aws_secrets.timeout must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Capability configuration for direct REST API integration.
 
+use std::collections::HashMap;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 // ── Capability ─────────────────────────────────────────────────────────────────
@@ -16,6 +19,13 @@ pub struct CapabilityConfig {
     pub name: String,
     /// Directories to load capability definitions from.
     pub directories: Vec<String>,
+    /// Per-capability timeouts overriding the definition's provider
+    /// `timeout` and `resilience.timeout` (e.g. `render_pdf: 300s`).
+    #[serde(
+        with = "crate::config::humantime_serde::map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub timeouts: HashMap<String, Duration>,
 }
 
 impl Default for CapabilityConfig {
@@ -34,6 +44,7 @@ impl Default for CapabilityConfig {
                 }
                 dirs
             },
+            timeouts: HashMap::new(),
         }
     }
 }
@@ -0,0 +1,202 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Shared-state clustering configuration.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Cluster ────────────────────────────────────────────────────────────────────
+
+/// Default prefix of every key the cluster state writes.
+pub const DEFAULT_CLUSTER_KEY_PREFIX: &str = "mcpgw:cluster:";
+
+/// Where replicas share their safety state.
+///
+/// `standalone` (the default) keeps rate-limit buckets, kill switches,
+/// session profile bindings and idempotency keys in process, so every
+/// replica decides alone and a load balancer needs sticky sessions. With
+/// `redis`, replicas pointing at the same server behave as one gateway:
+/// rate limits drain one shared bucket, operator kills and revives reach
+/// every replica within `sync_interval`, a session keeps its profile on
+/// any replica, and a duplicate call is suppressed wherever it lands.
+/// Replicas also elect a leader that alone runs periodic background jobs.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// cluster:
+///   backend: redis
+///   url: "{env.CLUSTER_REDIS_URL}"
+///   sync_interval: 1s
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum ClusterConfig {
+    /// Every replica keeps its own state.
+    #[default]
+    Standalone,
+    /// Redis server shared by all replicas (needs the `cluster-redis`
+    /// feature).
+    Redis {
+        /// `redis://` or `rediss://` URL; may reference `{env.VAR}` or
+        /// another secret.
+        url: String,
+        /// Prefix of every key the cluster state writes.
+        #[serde(default = "default_key_prefix")]
+        key_prefix: String,
+        /// How often kill-switch changes made on other replicas are pulled.
+        #[serde(
+            default = "default_sync_interval",
+            with = "crate::config::humantime_serde"
+        )]
+        sync_interval: Duration,
+        /// Connect / read / write timeout of one Redis call. A call that
+        /// fails or times out falls back to the replica's own state.
+        #[serde(default = "default_timeout", with = "crate::config::humantime_serde")]
+        timeout: Duration,
+        /// How long a session keeps its profile binding after it was last
+        /// set.
+        #[serde(
+            default = "default_session_ttl",
+            with = "crate::config::humantime_serde"
+        )]
+        session_ttl: Duration,
+        /// Lease of the replica elected to run cluster-wide background jobs
+        /// (discovery, token refresh, scheduled playbooks, stats saves).
+        /// A crashed leader is replaced within this time.
+        #[serde(
+            default = "default_leader_lease",
+            with = "crate::config::humantime_serde"
+        )]
+        leader_lease: Duration,
+    },
+}
+
+fn default_key_prefix() -> String {
+    DEFAULT_CLUSTER_KEY_PREFIX.to_string()
+}
+
+fn default_sync_interval() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_millis(250)
+}
+
+fn default_session_ttl() -> Duration {
+    Duration::from_secs(24 * 60 * 60)
+}
+
+fn default_leader_lease() -> Duration {
+    Duration::from_secs(15)
+}
+
+// Manual `Debug` that redacts the Redis URL, which may carry a password
+// (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for ClusterConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Standalone => f.write_str("Standalone"),
+            Self::Redis {
+                key_prefix,
+                sync_interval,
+                timeout,
+                session_ttl,
+                leader_lease,
+                ..
+            } => f
+                .debug_struct("Redis")
+                .field("url", &"<redacted>")
+                .field("key_prefix", key_prefix)
+                .field("sync_interval", sync_interval)
+                .field("timeout", timeout)
+                .field("session_ttl", session_ttl)
+                .field("leader_lease", leader_lease)
+                .finish(),
+        }
+    }
+}
+
+impl ClusterConfig {
+    /// Whether replicas share state.
+    #[must_use]
+    pub fn is_clustered(&self) -> bool {
+        !matches!(self, Self::Standalone)
+    }
+
+    /// Validate the Redis settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the URL or key prefix is empty,
+    /// an interval is zero, or the leader lease is shorter than a second.
+    pub fn validate(&self) -> Result<()> {
+        let Self::Redis {
+            url,
+            key_prefix,
+            sync_interval,
+            timeout,
+            session_ttl,
+            leader_lease,
+        } = self
+        else {
+            return Ok(());
+        };
+        let invalid = if url.trim().is_empty() {
+            Some("url must not be empty")
+        } else if key_prefix.is_empty() {
+            Some("key_prefix must not be empty")
+        } else if sync_interval.is_zero() {
+            Some("sync_interval must be positive")
+        } else if timeout.is_zero() {
+            Some("timeout must be positive")
+        } else if session_ttl.is_zero() {
+            Some("session_ttl must be positive")
+        } else if *leader_lease < Duration::from_secs(1) {
+            Some("leader_lease must be at least 1s")
+        } else {
+            None
+        };
+        invalid.map_or(Ok(()), |reason| {
+            Err(Error::ConfigValidation(format!("cluster.{reason}")))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redis_defaults_and_validation() {
+        // GIVEN: a Redis cluster config with only the URL set
+        let config: ClusterConfig =
+            serde_yaml::from_str("backend: redis\nurl: redis://localhost:6379\n").unwrap();
+
+        // THEN: defaults apply and it validates
+        let ClusterConfig::Redis {
+            key_prefix,
+            sync_interval,
+            ..
+        } = &config
+        else {
+            panic!("expected redis backend");
+        };
+        assert_eq!(key_prefix, DEFAULT_CLUSTER_KEY_PREFIX);
+        assert_eq!(*sync_interval, Duration::from_secs(1));
+        assert!(config.is_clustered());
+        assert!(config.validate().is_ok());
+        assert!(!format!("{config:?}").contains("localhost"));
+    }
+
+    #[test]
+    fn empty_url_is_rejected() {
+        let config: ClusterConfig = serde_yaml::from_str("backend: redis\nurl: ''\n").unwrap();
+        assert!(config.validate().is_err());
+        assert!(ClusterConfig::default().validate().is_ok());
+    }
+}
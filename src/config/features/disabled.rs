@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Declarative kill list applied to the kill switch at startup and on reload.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Disabled ───────────────────────────────────────────────────────────────────
+
+/// Servers, tools and capabilities kept disabled across restarts.
+///
+/// Entries are applied to the operator kill switch when the gateway starts and
+/// whenever the config is reloaded; removing an entry re-enables the target on
+/// the next reload. `gateway_revive_server` can still lift an entry until the
+/// next restart or reload.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// disabled:
+///   servers: [legacy-crm]
+///   tools: ["github:delete_repository"]
+///   capabilities: [weather_forecast]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisabledConfig {
+    /// Backend names to disable entirely.
+    pub servers: Vec<String>,
+    /// Single tools to disable, as `server:tool`.
+    pub tools: Vec<String>,
+    /// Capability names (tools of the `capabilities.name` backend) to disable.
+    pub capabilities: Vec<String>,
+}
+
+impl DisabledConfig {
+    /// Returns `true` when nothing is listed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty() && self.tools.is_empty() && self.capabilities.is_empty()
+    }
+
+    /// Disabled `(server, tool)` pairs, with capabilities resolved against the
+    /// capability backend named `capability_backend`.
+    #[must_use]
+    pub fn tool_targets(&self, capability_backend: &str) -> Vec<(String, String)> {
+        self.tools
+            .iter()
+            .filter_map(|t| t.split_once(':'))
+            .map(|(server, tool)| (server.to_string(), tool.to_string()))
+            .chain(
+                self.capabilities
+                    .iter()
+                    .map(|c| (capability_backend.to_string(), c.clone())),
+            )
+            .collect()
+    }
+
+    /// Validate entry syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] for empty names, a server name
+    /// containing `:`, or a tool entry not written as `server:tool`.
+    pub fn validate(&self) -> Result<()> {
+        for server in &self.servers {
+            if server.is_empty() || server.contains(':') {
+                return Err(Error::ConfigValidation(format!(
+                    "disabled.servers entry '{server}' must be a backend name"
+                )));
+            }
+        }
+        for tool in &self.tools {
+            if !tool
+                .split_once(':')
+                .is_some_and(|(server, name)| !server.is_empty() && !name.is_empty())
+            {
+                return Err(Error::ConfigValidation(format!(
+                    "disabled.tools entry '{tool}' must be written as 'server:tool'"
+                )));
+            }
+        }
+        if self.capabilities.iter().any(String::is_empty) {
+            return Err(Error::ConfigValidation(
+                "disabled.capabilities entries must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
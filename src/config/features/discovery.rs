@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Background MCP server discovery configuration.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_INTERVAL_SECS: u64 = 5 * 60;
+/// Scans spawn `ps` and read every AI-client config; keep them infrequent.
+const MIN_INTERVAL_SECS: u64 = 10;
+
+// ── Discovery ──────────────────────────────────────────────────────────────────
+
+/// Which newly discovered servers the gateway adds to its config by itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoveryAdoptPolicy {
+    /// Only report new servers (default).
+    #[default]
+    Off,
+    /// Adopt servers found in AI-client config files on this machine.
+    Local,
+    /// Also adopt running processes and servers advertised over mDNS.
+    All,
+}
+
+/// Periodic discovery inside the serving gateway.
+///
+/// Every `interval` the gateway runs the same scan as `cap discover`,
+/// compares the result with its configured backends and tells connected
+/// clients about servers that appeared or went away. `gateway_discovery_status`
+/// reports the current picture.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// discovery:
+///   enabled: true
+///   interval: 10m
+///   mdns: true
+///   auto_adopt: local
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscoveryConfig {
+    /// Run background discovery.
+    pub enabled: bool,
+    /// Time between scans.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub interval: Duration,
+    /// Also browse the local network for `_mcp._tcp` services.
+    pub mdns: bool,
+    /// Servers to add to the config file automatically. Needs the gateway
+    /// to run with a config file so the change can be written and reloaded.
+    pub auto_adopt: DiscoveryAdoptPolicy,
+}
+
+impl DiscoveryConfig {
+    /// Validate the scan interval.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] when discovery is enabled with an
+    /// interval under ten seconds.
+    pub fn validate(&self) -> Result<()> {
+        if self.enabled && self.interval < Duration::from_secs(MIN_INTERVAL_SECS) {
+            return Err(Error::ConfigValidation(format!(
+                "discovery.interval must be at least {MIN_INTERVAL_SECS}s"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(DEFAULT_INTERVAL_SECS),
+            mdns: false,
+            auto_adopt: DiscoveryAdoptPolicy::Off,
+        }
+    }
+}
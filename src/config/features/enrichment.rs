@@ -0,0 +1,82 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Offline LLM enrichment configuration.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+const DEFAULT_API_KEY_ENV: &str = "OPENAI_API_KEY";
+const DEFAULT_BATCH_SIZE: usize = 50;
+
+// ── Enrichment ─────────────────────────────────────────────────────────────────
+
+/// LLM-generated keywords and summaries for backend tools.
+///
+/// `mcp-gateway enrich` writes `overlay_file`; search reads it at startup and
+/// prefers its keywords over the heuristic `autotag` ones.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// enrichment:
+///   overlay_file: ~/.mcp-gateway/enrichment.json
+///   model: gpt-4o-mini
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnrichmentConfig {
+    /// Overlay file written by `mcp-gateway enrich` and read by search.
+    pub overlay_file: Option<String>,
+    /// Base URL of an OpenAI-compatible chat completions API.
+    pub base_url: String,
+    /// Model used for enrichment.
+    pub model: String,
+    /// Environment variable holding the API key (unset = no auth header,
+    /// e.g. for a local model server).
+    pub api_key_env: String,
+    /// Tools sent per LLM request.
+    pub batch_size: usize,
+}
+
+impl EnrichmentConfig {
+    /// `overlay_file` with `~` expanded.
+    #[must_use]
+    pub fn overlay_path(&self) -> Option<PathBuf> {
+        self.overlay_file
+            .as_deref()
+            .map(crate::config_reload::expand_tilde)
+    }
+
+    /// Validate the batch size.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if `batch_size` is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.batch_size == 0 {
+            return Err(Error::ConfigValidation(
+                "enrichment.batch_size must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            overlay_file: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            api_key_env: DEFAULT_API_KEY_ENV.to_string(),
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+}
@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Failsafe configuration — circuit breaker, retry, rate limit, health check.
 
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
@@ -28,6 +31,10 @@ const DEFAULT_RATE_LIMIT_BURST: u32 = 50;
 const DEFAULT_HEALTH_CHECK_INTERVAL_SECS: u64 = 10;
 const DEFAULT_HEALTH_CHECK_TIMEOUT_SECS: u64 = 5;
 
+const DEFAULT_AUTO_REVIVE_COOLDOWN_SECS: u64 = 60;
+const DEFAULT_AUTO_REVIVE_MAX_COOLDOWN_SECS: u64 = 15 * 60;
+const DEFAULT_AUTO_REVIVE_TIMEOUT_SECS: u64 = 5;
+
 // ── Failsafe ───────────────────────────────────────────────────────────────────
 
 /// Failsafe configuration.
@@ -42,6 +49,8 @@ pub struct FailsafeConfig {
     pub rate_limit: RateLimitConfig,
     /// Health check configuration.
     pub health_check: HealthCheckConfig,
+    /// Probing of backends auto-killed by their error budget.
+    pub auto_revive: AutoReviveConfig,
 }
 
 /// Circuit breaker configuration.
@@ -110,6 +119,11 @@ pub struct RateLimitConfig {
     pub requests_per_second: u32,
     /// Burst size.
     pub burst_size: u32,
+    /// Per-client, per-backend token buckets: client name → backend name →
+    /// rate (`30/min`). `*` matches any client or backend; every client still
+    /// gets its own bucket. Applied to tool calls independently of `enabled`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub limits: HashMap<String, HashMap<String, RateSpec>>,
 }
 
 impl Default for RateLimitConfig {
@@ -118,10 +132,112 @@ impl Default for RateLimitConfig {
             enabled: true,
             requests_per_second: DEFAULT_RATE_LIMIT_RPS,
             burst_size: DEFAULT_RATE_LIMIT_BURST,
+            limits: HashMap::new(),
         }
     }
 }
 
+/// A token-bucket rate written as `<count>/<period>`, e.g. `30/min`.
+///
+/// The bucket holds `count` tokens and refills all of them once per period,
+/// so a full bucket allows a burst of `count` calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RateSpec {
+    /// Calls allowed per period (at least 1).
+    pub count: u32,
+    /// Refill period.
+    pub per: RatePeriod,
+}
+
+/// Refill period of a [`RateSpec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RatePeriod {
+    /// `sec`
+    Second,
+    /// `min`
+    Minute,
+    /// `hour`
+    Hour,
+    /// `day`
+    Day,
+}
+
+impl RatePeriod {
+    /// Length of the period.
+    #[must_use]
+    pub fn duration(self) -> Duration {
+        Duration::from_secs(match self {
+            Self::Second => 1,
+            Self::Minute => 60,
+            Self::Hour => 3_600,
+            Self::Day => 86_400,
+        })
+    }
+}
+
+impl RateSpec {
+    /// Length of the refill period.
+    #[must_use]
+    pub fn period(self) -> Duration {
+        self.per.duration()
+    }
+}
+
+impl FromStr for RateSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (count, per) = s
+            .split_once('/')
+            .ok_or_else(|| format!("rate '{s}' must look like '<count>/<sec|min|hour|day>'"))?;
+        let count = count
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|c| *c > 0)
+            .ok_or_else(|| format!("rate '{s}' must have a positive integer count"))?;
+        let per = match per.trim().to_ascii_lowercase().as_str() {
+            "s" | "sec" | "second" => RatePeriod::Second,
+            "m" | "min" | "minute" => RatePeriod::Minute,
+            "h" | "hour" => RatePeriod::Hour,
+            "d" | "day" => RatePeriod::Day,
+            other => {
+                return Err(format!(
+                    "rate '{s}' has unknown period '{other}' (expected sec, min, hour or day)"
+                ));
+            }
+        };
+        Ok(Self { count, per })
+    }
+}
+
+impl TryFrom<String> for RateSpec {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<RateSpec> for String {
+    fn from(spec: RateSpec) -> Self {
+        spec.to_string()
+    }
+}
+
+impl fmt::Display for RateSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let per = match self.per {
+            RatePeriod::Second => "sec",
+            RatePeriod::Minute => "min",
+            RatePeriod::Hour => "hour",
+            RatePeriod::Day => "day",
+        };
+        write!(f, "{}/{per}", self.count)
+    }
+}
+
 /// Health check configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -146,6 +262,39 @@ impl Default for HealthCheckConfig {
     }
 }
 
+/// Auto-revive of backends killed by the error budget.
+///
+/// After `cooldown` the gateway pings the killed backend; on success the
+/// backend is revived, on failure it stays killed and the wait doubles (up to
+/// `max_cooldown`) — the half-open state of a circuit breaker. Backends killed
+/// by an operator via `gateway_kill_server` are never auto-revived.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoReviveConfig {
+    /// Enable auto-revive probing.
+    pub enabled: bool,
+    /// Wait after the auto-kill before the first probe.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub cooldown: Duration,
+    /// Upper bound for the wait after repeated failed probes.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub max_cooldown: Duration,
+    /// Probe timeout.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub timeout: Duration,
+}
+
+impl Default for AutoReviveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cooldown: Duration::from_secs(DEFAULT_AUTO_REVIVE_COOLDOWN_SECS),
+            max_cooldown: Duration::from_secs(DEFAULT_AUTO_REVIVE_MAX_COOLDOWN_SECS),
+            timeout: Duration::from_secs(DEFAULT_AUTO_REVIVE_TIMEOUT_SECS),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,4 +324,23 @@ mod tests {
             health.interval
         );
     }
+
+    #[test]
+    fn rate_spec_parses_and_round_trips() {
+        let spec: RateSpec = "30/min".parse().unwrap();
+        assert_eq!(spec.count, 30);
+        assert_eq!(spec.period(), Duration::from_secs(60));
+        assert_eq!(spec.to_string(), "30/min");
+        assert_eq!(
+            " 5 / Sec ".parse::<RateSpec>().unwrap().to_string(),
+            "5/sec"
+        );
+    }
+
+    #[test]
+    fn rate_spec_rejects_malformed_values() {
+        for bad in ["30", "0/min", "-1/min", "ten/min", "30/fortnight"] {
+            assert!(bad.parse::<RateSpec>().is_err(), "{bad} should not parse");
+        }
+    }
 }
@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Idempotency (duplicate tool-call suppression) configuration.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::idempotency::{COMPLETED_TTL, IN_FLIGHT_TIMEOUT};
+use crate::{Error, Result};
+
+// ── Idempotency ────────────────────────────────────────────────────────────────
+
+/// Duplicate suppression for `gateway_invoke`.
+///
+/// With `store_dir` set, in-flight markers and completed results are also
+/// written to that directory, so suppression survives restarts within the
+/// 24h window. Replicas pointing at the same directory (a shared volume)
+/// suppress each other's duplicates.
+///
+/// `always` / `never` take `server:tool` globs and override the tool's
+/// read-only / idempotent annotations, which otherwise exempt a tool from
+/// key derivation.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// idempotency:
+///   enabled: true
+///   store_dir: ~/.mcp-gateway/idempotency
+///   completed_ttl: 1h
+///   never: ["dice:roll"]
+///   always: ["payments:*"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdempotencyConfig {
+    /// Suppress duplicate calls (explicit `idempotency_key`, or a key
+    /// derived from the tool and its arguments).
+    pub enabled: bool,
+    /// Directory persisting entries across restarts and replicas
+    /// (unset = in memory only). Ignored when `cluster` shares state
+    /// through Redis, which then holds the entries.
+    pub store_dir: Option<String>,
+    /// How often expired entries are evicted.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub cleanup_interval: Duration,
+    /// How long a completed result is replayed for a duplicate call.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub completed_ttl: Duration,
+    /// How long an unfinished call blocks duplicates.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub in_flight_timeout: Duration,
+    /// Derive a key from the tool and arguments when the client supplies
+    /// none (default `true`). Unlisted tools annotated read-only or
+    /// idempotent are never derived.
+    pub auto_derive: bool,
+    /// Tools (`server:tool` globs) always checked, with a derived key if
+    /// needed.
+    pub always: Vec<String>,
+    /// Tools never checked, even with an explicit key. Wins over `always`.
+    pub never: Vec<String>,
+}
+
+impl IdempotencyConfig {
+    /// `store_dir` with `~` expanded.
+    #[must_use]
+    pub fn store_path(&self) -> Option<PathBuf> {
+        self.store_dir
+            .as_deref()
+            .map(crate::config_reload::expand_tilde)
+    }
+
+    /// Validate the windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if a TTL or the cleanup interval
+    /// is zero.
+    pub fn validate(&self) -> Result<()> {
+        for (field, value) in [
+            ("completed_ttl", self.completed_ttl),
+            ("in_flight_timeout", self.in_flight_timeout),
+            ("cleanup_interval", self.cleanup_interval),
+        ] {
+            if value.is_zero() {
+                return Err(Error::ConfigValidation(format!(
+                    "idempotency.{field} must be greater than zero"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            store_dir: None,
+            cleanup_interval: Duration::from_secs(60),
+            completed_ttl: COMPLETED_TTL,
+            in_flight_timeout: IN_FLIGHT_TIMEOUT,
+            auto_derive: true,
+            always: Vec::new(),
+            never: Vec::new(),
+        }
+    }
+}
@@ -2,10 +2,14 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Key Server configuration — OIDC identity to temporary scoped API keys.
 
+use std::collections::BTreeMap;
 use std::env;
+use std::net::IpAddr;
+use std::path::PathBuf;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
+use super::failsafe::{RatePeriod, RateSpec};
 use crate::{Error, Result};
 
 // ── Constants ──────────────────────────────────────────────────────────────────
@@ -14,6 +18,12 @@ const DEFAULT_TOKEN_TTL_SECS: u64 = 3600;
 const DEFAULT_MAX_TOKENS_PER_IDENTITY: u32 = 5;
 const DEFAULT_MAX_OIDC_TOKEN_AGE_SECS: u64 = 300;
 const DEFAULT_CLEANUP_INTERVAL_SECS: u64 = 60;
+const DEFAULT_JWT_ISSUER: &str = "mcp-gateway";
+const DEFAULT_REDIS_KEY_PREFIX: &str = "mcpgw:key_server:";
+const DEFAULT_ISSUANCE_PER_IDENTITY: u32 = 10;
+const DEFAULT_ISSUANCE_PER_IP: u32 = 60;
+const DEFAULT_FLOOD_THRESHOLD: u32 = 20;
+const DEFAULT_FLOOD_WINDOW_SECS: u64 = 600;
 
 // ── Key Server ─────────────────────────────────────────────────────────────────
 
@@ -46,6 +56,10 @@ pub struct KeyServerConfig {
     /// Issued token lifetime in seconds (default: 3600 = 1 hour).
     #[serde(default = "default_token_ttl_secs")]
     pub token_ttl_secs: u64,
+    /// Refresh token lifetime in seconds — how long a session can be renewed
+    /// without a new OIDC exchange. `0` (default) issues no refresh tokens.
+    #[serde(default)]
+    pub refresh_token_ttl_secs: u64,
     /// Maximum active tokens per identity before new issuance is rejected (default: 5).
     #[serde(default = "default_max_tokens_per_identity")]
     pub max_tokens_per_identity: u32,
@@ -61,6 +75,9 @@ pub struct KeyServerConfig {
     /// Access policy rules (first-match-wins).
     #[serde(default)]
     pub policies: Vec<KeyServerPolicyConfig>,
+    /// Named scope sets that policy rules grant via `bundles`.
+    #[serde(default)]
+    pub scope_bundles: BTreeMap<String, PolicyScopesConfig>,
     /// Admin bearer token for revocation endpoints (`env:VAR_NAME` supported).
     /// If `None`, revocation endpoints return 503.
     #[serde(default)]
@@ -73,6 +90,17 @@ pub struct KeyServerConfig {
     /// provider and a policy rule, so enabling it does not bypass policy.
     #[serde(default)]
     pub delegated_bearer: bool,
+    /// Issue signed JWTs instead of opaque tokens, so other services (and
+    /// other gateway instances sharing the key) can validate them against
+    /// `/.well-known/jwks.json`. `None` (default) issues opaque tokens.
+    #[serde(default)]
+    pub jwt: Option<KeyServerJwtConfig>,
+    /// Where issued tokens are kept (default: in memory, lost on restart).
+    #[serde(default)]
+    pub store: KeyServerStoreConfig,
+    /// Rate limits and anomaly alerts on `POST /auth/token`.
+    #[serde(default)]
+    pub issuance: KeyServerIssuanceConfig,
 }
 
 // Manual `Debug` that redacts the admin bearer token (CWE-532, mirrors PR
@@ -84,13 +112,18 @@ impl std::fmt::Debug for KeyServerConfig {
         f.debug_struct("KeyServerConfig")
             .field("enabled", &self.enabled)
             .field("token_ttl_secs", &self.token_ttl_secs)
+            .field("refresh_token_ttl_secs", &self.refresh_token_ttl_secs)
             .field("max_tokens_per_identity", &self.max_tokens_per_identity)
             .field("max_oidc_token_age_secs", &self.max_oidc_token_age_secs)
             .field("cleanup_interval_secs", &self.cleanup_interval_secs)
             .field("oidc", &self.oidc)
             .field("policies", &self.policies)
+            .field("scope_bundles", &self.scope_bundles)
             .field("admin_token", &redact_opt(&self.admin_token))
             .field("delegated_bearer", &self.delegated_bearer)
+            .field("jwt", &self.jwt)
+            .field("store", &self.store)
+            .field("issuance", &self.issuance)
             .finish()
     }
 }
@@ -116,13 +149,18 @@ impl Default for KeyServerConfig {
         Self {
             enabled: false,
             token_ttl_secs: DEFAULT_TOKEN_TTL_SECS,
+            refresh_token_ttl_secs: 0,
             max_tokens_per_identity: DEFAULT_MAX_TOKENS_PER_IDENTITY,
             max_oidc_token_age_secs: DEFAULT_MAX_OIDC_TOKEN_AGE_SECS,
             cleanup_interval_secs: DEFAULT_CLEANUP_INTERVAL_SECS,
             oidc: Vec::new(),
             policies: Vec::new(),
+            scope_bundles: BTreeMap::new(),
             admin_token: None,
             delegated_bearer: false,
+            jwt: None,
+            store: KeyServerStoreConfig::default(),
+            issuance: KeyServerIssuanceConfig::default(),
         }
     }
 }
@@ -177,6 +215,265 @@ impl KeyServerConfig {
                 )));
             }
         }
+        for (idx, policy) in self.policies.iter().enumerate() {
+            policy.validate(idx, &self.scope_bundles)?;
+        }
+        if self.refresh_token_ttl_secs != 0 && self.refresh_token_ttl_secs <= self.token_ttl_secs {
+            return Err(Error::ConfigValidation(format!(
+                "key_server.refresh_token_ttl_secs ({}) must exceed token_ttl_secs ({}); \
+                 a refresh token that expires first can never be used",
+                self.refresh_token_ttl_secs, self.token_ttl_secs
+            )));
+        }
+        if let Some(jwt) = &self.jwt {
+            jwt.validate()?;
+        }
+        self.issuance.validate()?;
+        self.store.validate()
+    }
+}
+
+/// Throttling and anomaly detection on the token endpoint.
+///
+/// Limits are token buckets written as `<count>/<period>`; `null` disables
+/// one. Anomalies never block issuance, they only raise `token.anomaly`
+/// audit alerts.
+///
+/// # Example
+///
+/// ```yaml
+/// key_server:
+///   issuance:
+///     per_identity: 10/min
+///     per_ip: 60/min
+///     trusted_proxies: [10.0.0.2]
+///     flood_threshold: 20
+///     flood_window_secs: 600
+///     detect_scope_escalation: true
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyServerIssuanceConfig {
+    /// Token requests per verified identity (default: `10/min`).
+    pub per_identity: Option<RateSpec>,
+    /// Token requests per client IP, checked before any verification
+    /// (default: `60/min`). The IP is the connection's peer address, or the
+    /// one a `trusted_proxies` peer reports in `X-Forwarded-For` /
+    /// `X-Real-IP`.
+    pub per_ip: Option<RateSpec>,
+    /// Reverse proxies whose `X-Forwarded-For` / `X-Real-IP` headers name
+    /// the client (default: none). Other peers cannot set their IP by
+    /// header.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Alert when one identity is issued more than this many tokens within
+    /// `flood_window_secs` (default: 20; 0 disables).
+    pub flood_threshold: u32,
+    /// Window of the flood alert in seconds (default: 600).
+    pub flood_window_secs: u64,
+    /// Alert when a token grants backends or tools the identity's previous
+    /// token did not (default: `true`).
+    pub detect_scope_escalation: bool,
+}
+
+impl Default for KeyServerIssuanceConfig {
+    fn default() -> Self {
+        Self {
+            per_identity: Some(RateSpec {
+                count: DEFAULT_ISSUANCE_PER_IDENTITY,
+                per: RatePeriod::Minute,
+            }),
+            per_ip: Some(RateSpec {
+                count: DEFAULT_ISSUANCE_PER_IP,
+                per: RatePeriod::Minute,
+            }),
+            trusted_proxies: Vec::new(),
+            flood_threshold: DEFAULT_FLOOD_THRESHOLD,
+            flood_window_secs: DEFAULT_FLOOD_WINDOW_SECS,
+            detect_scope_escalation: true,
+        }
+    }
+}
+
+impl KeyServerIssuanceConfig {
+    /// Validate the issuance settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if a flood threshold is set
+    /// without a window.
+    pub fn validate(&self) -> Result<()> {
+        if self.flood_threshold > 0 && self.flood_window_secs == 0 {
+            return Err(Error::ConfigValidation(
+                "key_server.issuance.flood_window_secs must be positive when \
+                 flood_threshold is set"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Token store backend of the key server.
+///
+/// The persistent backends keep issued tokens across restarts; a Redis store
+/// (or a `SQLite` file on shared storage) is shared by all replicas pointing
+/// at it.
+///
+/// # Example
+///
+/// ```yaml
+/// key_server:
+///   store:
+///     backend: redis
+///     url: "{env.KEY_SERVER_REDIS_URL}"
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum KeyServerStoreConfig {
+    /// In-process map; tokens are lost on restart.
+    #[default]
+    Memory,
+    /// `SQLite` database file (needs the `token-store-sqlite` feature).
+    Sqlite {
+        /// Database file, created if missing.
+        path: PathBuf,
+    },
+    /// Redis server (needs the `token-store-redis` feature).
+    Redis {
+        /// `redis://` or `rediss://` URL; may reference `{env.VAR}` or
+        /// another secret.
+        url: String,
+        /// Prefix of every key the store writes.
+        #[serde(default = "default_redis_key_prefix")]
+        key_prefix: String,
+    },
+}
+
+fn default_redis_key_prefix() -> String {
+    DEFAULT_REDIS_KEY_PREFIX.to_string()
+}
+
+// Manual `Debug` that redacts the Redis URL, which may carry a password
+// (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for KeyServerStoreConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Memory => f.write_str("Memory"),
+            Self::Sqlite { path } => f.debug_struct("Sqlite").field("path", path).finish(),
+            Self::Redis { key_prefix, .. } => f
+                .debug_struct("Redis")
+                .field("url", &"<redacted>")
+                .field("key_prefix", key_prefix)
+                .finish(),
+        }
+    }
+}
+
+impl KeyServerStoreConfig {
+    /// Validate the backend settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the `SQLite` path, Redis URL or
+    /// key prefix is empty.
+    pub fn validate(&self) -> Result<()> {
+        let empty = match self {
+            Self::Memory => None,
+            Self::Sqlite { path } => path.as_os_str().is_empty().then_some("path"),
+            Self::Redis { url, key_prefix } => {
+                if url.trim().is_empty() {
+                    Some("url")
+                } else if key_prefix.is_empty() {
+                    Some("key_prefix")
+                } else {
+                    None
+                }
+            }
+        };
+        match empty {
+            Some(field) => Err(Error::ConfigValidation(format!(
+                "key_server.store.{field} must not be empty"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Signed JWT issuance for the key server.
+///
+/// Tokens are signed with ES256. Without a `signing_key` a key is generated
+/// at startup, so tokens do not survive a restart and no other instance can
+/// share it; set a key for multi-instance deployments.
+///
+/// # Example
+///
+/// ```yaml
+/// key_server:
+///   jwt:
+///     signing_key: "{env.KEY_SERVER_SIGNING_KEY}"
+///     issuer: "https://gateway.example.com"
+///     audience: "mcp-gateway"
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyServerJwtConfig {
+    /// P-256 private key as a PKCS#8 PEM, usually `{env.VAR}`,
+    /// `{keychain.SERVICE}` or another secret reference.
+    pub signing_key: Option<String>,
+    /// `kid` published in the JWKS. Defaults to the key's RFC 7638
+    /// thumbprint, which is the same on every instance sharing the key.
+    pub key_id: Option<String>,
+    /// `iss` claim of issued tokens.
+    pub issuer: String,
+    /// `aud` claim of issued tokens; omitted when `None`.
+    pub audience: Option<String>,
+}
+
+impl Default for KeyServerJwtConfig {
+    fn default() -> Self {
+        Self {
+            signing_key: None,
+            key_id: None,
+            issuer: DEFAULT_JWT_ISSUER.to_string(),
+            audience: None,
+        }
+    }
+}
+
+// Manual `Debug` that redacts the signing key (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for KeyServerJwtConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyServerJwtConfig")
+            .field(
+                "signing_key",
+                &self.signing_key.as_ref().map(|_| "<redacted>"),
+            )
+            .field("key_id", &self.key_id)
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .finish()
+    }
+}
+
+impl KeyServerJwtConfig {
+    /// Validate the JWT settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the signing key, key id or
+    /// issuer is empty.
+    pub fn validate(&self) -> Result<()> {
+        for (field, value) in [
+            ("signing_key", self.signing_key.as_deref()),
+            ("key_id", self.key_id.as_deref()),
+            ("issuer", Some(self.issuer.as_str())),
+        ] {
+            if value.is_some_and(|v| v.trim().is_empty()) {
+                return Err(Error::ConfigValidation(format!(
+                    "key_server.jwt.{field} must not be empty"
+                )));
+            }
+        }
         Ok(())
     }
 }
@@ -212,16 +509,85 @@ pub struct KeyServerProviderConfig {
 }
 
 /// An access policy rule: match criteria + granted scopes.
+///
+/// # Example
+///
+/// ```yaml
+/// key_server:
+///   scope_bundles:
+///     search: { backends: ["tavily", "brave"], rate_limit: 60 }
+///   policies:
+///     - match: { claims: { employment_type: "contractor" } }
+///       effect: deny
+///     - match: { groups: ["eng-*"] }
+///       bundles: [search]
+///     - match: { claims: { "realm_access.roles": ["mcp-admin"] } }
+///       scopes: { backends: ["*"], tools: ["*"] }
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyServerPolicyConfig {
     /// Criteria that must be satisfied for this rule to match.
     #[serde(rename = "match")]
     pub match_criteria: PolicyMatchConfig,
-    /// Scopes granted when this rule matches.
-    pub scopes: PolicyScopesConfig,
+    /// `allow` (default) grants the rule's scopes; `deny` rejects the
+    /// identity.
+    #[serde(default)]
+    pub effect: PolicyEffect,
+    /// Names of `scope_bundles` granted when this rule matches.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bundles: Vec<String>,
+    /// Scopes granted when this rule matches, merged with its bundles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<PolicyScopesConfig>,
+}
+
+impl KeyServerPolicyConfig {
+    /// Validate rule `idx` against the configured bundles.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the rule names an unknown
+    /// bundle, an allow rule grants nothing, or a deny rule grants scopes.
+    pub fn validate(
+        &self,
+        idx: usize,
+        bundles: &BTreeMap<String, PolicyScopesConfig>,
+    ) -> Result<()> {
+        if let Some(name) = self
+            .bundles
+            .iter()
+            .find(|name| !bundles.contains_key(*name))
+        {
+            return Err(Error::ConfigValidation(format!(
+                "key_server.policies[{idx}] references unknown scope bundle '{name}'"
+            )));
+        }
+        let grants = self.scopes.is_some() || !self.bundles.is_empty();
+        match self.effect {
+            PolicyEffect::Allow if !grants => Err(Error::ConfigValidation(format!(
+                "key_server.policies[{idx}] must declare `scopes` or `bundles`"
+            ))),
+            PolicyEffect::Deny if grants => Err(Error::ConfigValidation(format!(
+                "key_server.policies[{idx}] is a deny rule and cannot grant `scopes` or `bundles`"
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What a matching policy rule does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyEffect {
+    /// Grant the rule's scopes.
+    #[default]
+    Allow,
+    /// Reject the identity.
+    Deny,
 }
 
-/// Match criteria for a policy rule. All non-`None` fields must match.
+/// Match criteria for a policy rule. All non-`None` (non-empty) fields must
+/// match.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PolicyMatchConfig {
     /// Email domain suffix (e.g., `"company.com"`).
@@ -236,6 +602,41 @@ pub struct PolicyMatchConfig {
     /// Required group membership.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub group: Option<String>,
+    /// Membership in any of these groups; a trailing `*` matches a prefix.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    /// ID token claims, by name or dotted path (`realm_access.roles`), each
+    /// with one or more accepted values; a trailing `*` matches a prefix.
+    /// A claim holding a list matches when any element does.
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        deserialize_with = "deserialize_claim_values"
+    )]
+    pub claims: BTreeMap<String, Vec<String>>,
+}
+
+/// Deserialize claim patterns given as a single value or a list.
+fn deserialize_claim_values<'de, D>(
+    deserializer: D,
+) -> std::result::Result<BTreeMap<String, Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(BTreeMap::<String, OneOrMany>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(claim, values)| match values {
+            OneOrMany::One(value) => (claim, vec![value]),
+            OneOrMany::Many(values) => (claim, values),
+        })
+        .collect())
 }
 
 /// Scopes granted by a policy rule.
@@ -340,6 +741,210 @@ mod tests {
         };
         assert!(cfg.validate().is_ok());
     }
+
+    /// JWT issuance rejects an empty issuer or signing key.
+    #[test]
+    fn validate_rejects_empty_jwt_settings() {
+        let with_jwt = |jwt: KeyServerJwtConfig| KeyServerConfig {
+            jwt: Some(jwt),
+            ..enabled_with(vec![provider("https://issuer.example", vec!["aud"])])
+        };
+        assert!(with_jwt(KeyServerJwtConfig::default()).validate().is_ok());
+
+        let err = with_jwt(KeyServerJwtConfig {
+            issuer: " ".to_string(),
+            ..KeyServerJwtConfig::default()
+        })
+        .validate()
+        .expect_err("empty issuer");
+        assert!(err.to_string().contains("key_server.jwt.issuer"));
+
+        assert!(
+            with_jwt(KeyServerJwtConfig {
+                signing_key: Some(String::new()),
+                ..KeyServerJwtConfig::default()
+            })
+            .validate()
+            .is_err()
+        );
+    }
+
+    /// Refresh tokens must outlive the access tokens they renew.
+    #[test]
+    fn validate_rejects_refresh_ttl_not_exceeding_token_ttl() {
+        let with_refresh = |refresh_token_ttl_secs| KeyServerConfig {
+            refresh_token_ttl_secs,
+            ..enabled_with(vec![provider("https://issuer.example", vec!["aud"])])
+        };
+        assert!(with_refresh(0).validate().is_ok());
+        assert!(with_refresh(86_400).validate().is_ok());
+        let err = with_refresh(3600)
+            .validate()
+            .expect_err("refresh ttl equal to token ttl");
+        assert!(err.to_string().contains("refresh_token_ttl_secs"));
+    }
+}
+
+#[cfg(test)]
+mod policy_config {
+    use super::*;
+
+    fn parse(yaml: &str) -> KeyServerConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn policy_rules_parse_claims_effects_and_bundles() {
+        let config = parse(
+            r#"
+scope_bundles:
+  search: { backends: ["tavily"], rate_limit: 60 }
+policies:
+  - match: { claims: { employment_type: contractor } }
+    effect: deny
+  - match: { groups: ["eng-*"], claims: { "realm_access.roles": ["dev", "ops"] } }
+    bundles: [search]
+  - match: { domain: company.com }
+    scopes: { backends: ["*"] }
+"#,
+        );
+
+        let [contractors, engineers, company] = config.policies.as_slice() else {
+            panic!("expected three rules");
+        };
+        assert_eq!(contractors.effect, PolicyEffect::Deny);
+        assert_eq!(
+            contractors.match_criteria.claims["employment_type"],
+            vec!["contractor"]
+        );
+        assert_eq!(engineers.effect, PolicyEffect::Allow);
+        assert_eq!(engineers.match_criteria.groups, vec!["eng-*"]);
+        assert_eq!(
+            engineers.match_criteria.claims["realm_access.roles"],
+            vec!["dev", "ops"]
+        );
+        assert_eq!(engineers.bundles, vec!["search"]);
+        assert!(company.scopes.is_some());
+        assert_eq!(config.scope_bundles["search"].rate_limit, 60);
+    }
+
+    #[test]
+    fn validate_rejects_inconsistent_rules() {
+        let enabled = |yaml: &str| KeyServerConfig {
+            enabled: true,
+            ..parse(yaml)
+        };
+
+        let err = enabled("policies: [{ match: { domain: a.com }, bundles: [missing] }]")
+            .validate()
+            .expect_err("unknown bundle");
+        assert!(err.to_string().contains("'missing'"), "{err}");
+
+        let err = enabled("policies: [{ match: { domain: a.com } }]")
+            .validate()
+            .expect_err("allow rule granting nothing");
+        assert!(err.to_string().contains("`scopes` or `bundles`"), "{err}");
+
+        assert!(
+            enabled("policies: [{ match: { domain: a.com }, effect: deny, scopes: {} }]")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            enabled("policies: [{ match: { domain: a.com }, effect: deny }]")
+                .validate()
+                .is_ok()
+        );
+    }
+}
+
+#[cfg(test)]
+mod issuance_config {
+    use super::*;
+
+    #[test]
+    fn issuance_limits_parse_and_can_be_disabled() {
+        // GIVEN: an issuance section overriding one limit and disabling another
+        let yaml = "issuance: { per_identity: 3/hour, per_ip: null, flood_threshold: 0 }";
+
+        // WHEN: parsing it
+        let issuance = serde_yaml::from_str::<KeyServerConfig>(yaml)
+            .unwrap()
+            .issuance;
+
+        // THEN: the override applies, the rest keep their defaults
+        assert_eq!(issuance.per_identity, Some("3/hour".parse().unwrap()));
+        assert_eq!(issuance.per_ip, None);
+        assert_eq!(issuance.flood_threshold, 0);
+        assert!(issuance.detect_scope_escalation);
+        assert_eq!(
+            KeyServerConfig::default().issuance.per_ip,
+            Some("60/min".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn validate_rejects_a_flood_threshold_without_a_window() {
+        let issuance = KeyServerIssuanceConfig {
+            flood_window_secs: 0,
+            ..KeyServerIssuanceConfig::default()
+        };
+        let err = issuance.validate().unwrap_err();
+        assert!(err.to_string().contains("flood_window_secs"));
+        assert!(
+            KeyServerIssuanceConfig {
+                flood_threshold: 0,
+                ..issuance
+            }
+            .validate()
+            .is_ok()
+        );
+    }
+}
+
+#[cfg(test)]
+mod store_config {
+    use super::*;
+
+    #[test]
+    fn store_backends_parse_from_yaml() {
+        let parse = |yaml: &str| serde_yaml::from_str::<KeyServerConfig>(yaml).unwrap().store;
+
+        assert!(matches!(
+            parse("enabled: true"),
+            KeyServerStoreConfig::Memory
+        ));
+        assert!(matches!(
+            parse("store: { backend: sqlite, path: tokens.db }"),
+            KeyServerStoreConfig::Sqlite { path } if path == std::path::Path::new("tokens.db")
+        ));
+        let KeyServerStoreConfig::Redis { url, key_prefix } =
+            parse("store: { backend: redis, url: 'redis://cache:6379' }")
+        else {
+            panic!("expected redis store");
+        };
+        assert_eq!(url, "redis://cache:6379");
+        assert_eq!(key_prefix, DEFAULT_REDIS_KEY_PREFIX);
+    }
+
+    #[test]
+    fn store_validation_rejects_empty_settings() {
+        assert!(KeyServerStoreConfig::Memory.validate().is_ok());
+        let err = KeyServerStoreConfig::Redis {
+            url: " ".to_string(),
+            key_prefix: DEFAULT_REDIS_KEY_PREFIX.to_string(),
+        }
+        .validate()
+        .unwrap_err();
+        assert!(err.to_string().contains("key_server.store.url"));
+        assert!(
+            KeyServerStoreConfig::Sqlite {
+                path: PathBuf::new()
+            }
+            .validate()
+            .is_err()
+        );
+    }
 }
 
 #[cfg(test)]
@@ -353,6 +958,14 @@ mod cwe532_debug_redaction {
     fn key_server_config_debug_redacts_admin_token() {
         let cfg = KeyServerConfig {
             admin_token: Some(SENTINEL.to_string()),
+            jwt: Some(KeyServerJwtConfig {
+                signing_key: Some(SENTINEL.to_string()),
+                ..KeyServerJwtConfig::default()
+            }),
+            store: KeyServerStoreConfig::Redis {
+                url: format!("redis://:{SENTINEL}@cache:6379"),
+                key_prefix: DEFAULT_REDIS_KEY_PREFIX.to_string(),
+            },
             ..KeyServerConfig::default()
         };
         let dbg = format!("{cfg:?}");
@@ -0,0 +1,120 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Push-based metrics export configuration (OTLP / `StatsD`).
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+const DEFAULT_PREFIX: &str = "mcp_gateway";
+const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4318/v1/metrics";
+const DEFAULT_STATSD_ENDPOINT: &str = "127.0.0.1:8125";
+
+// ── Metrics export ─────────────────────────────────────────────────────────────
+
+/// Wire protocol used to push metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsExportProtocol {
+    /// OTLP over HTTP with JSON encoding (`POST …/v1/metrics`).
+    #[default]
+    Otlp,
+    /// `StatsD` over UDP, with `DogStatsD`-style `|#key:value` tags.
+    Statsd,
+}
+
+/// Periodic push of usage stats and backend health metrics.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// metrics_export:
+///   enabled: true
+///   protocol: statsd
+///   endpoint: "statsd.internal:8125"
+///   interval: 30s
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsExportConfig {
+    /// Enable the exporter.
+    pub enabled: bool,
+    /// Wire protocol.
+    pub protocol: MetricsExportProtocol,
+    /// OTLP: full metrics URL. `StatsD`: `host:port`. Empty selects the
+    /// protocol default (`http://localhost:4318/v1/metrics` or
+    /// `127.0.0.1:8125`).
+    pub endpoint: String,
+    /// Push interval.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub interval: Duration,
+    /// Metric name prefix (e.g. `mcp_gateway.invocations_total`).
+    pub prefix: String,
+    /// Extra HTTP headers sent with OTLP requests (e.g. collector API keys).
+    pub headers: HashMap<String, String>,
+}
+
+impl MetricsExportConfig {
+    /// Validate the exporter settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the exporter is enabled with a zero `interval`.
+    pub fn validate(&self) -> Result<()> {
+        if self.enabled && self.interval.is_zero() {
+            return Err(Error::ConfigValidation(
+                "metrics_export.interval must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Configured endpoint, or the protocol default when unset.
+    #[must_use]
+    pub fn effective_endpoint(&self) -> &str {
+        if !self.endpoint.is_empty() {
+            return &self.endpoint;
+        }
+        match self.protocol {
+            MetricsExportProtocol::Otlp => DEFAULT_OTLP_ENDPOINT,
+            MetricsExportProtocol::Statsd => DEFAULT_STATSD_ENDPOINT,
+        }
+    }
+}
+
+impl std::fmt::Debug for MetricsExportConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: std::collections::BTreeMap<&str, &str> = self
+            .headers
+            .keys()
+            .map(|name| (name.as_str(), "<redacted>"))
+            .collect();
+        f.debug_struct("MetricsExportConfig")
+            .field("enabled", &self.enabled)
+            .field("protocol", &self.protocol)
+            .field("endpoint", &self.endpoint)
+            .field("interval", &self.interval)
+            .field("prefix", &self.prefix)
+            .field("headers", &headers)
+            .finish()
+    }
+}
+
+impl Default for MetricsExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            protocol: MetricsExportProtocol::default(),
+            endpoint: String::new(),
+            interval: Duration::from_secs(DEFAULT_INTERVAL_SECS),
+            prefix: DEFAULT_PREFIX.to_string(),
+            headers: HashMap::new(),
+        }
+    }
+}
@@ -5,35 +5,75 @@
 //! Each domain has its own sub-module; this `mod.rs` re-exports everything so
 //! callers continue to use `crate::config::KeyServerConfig`, etc.
 
+mod access_log;
+mod alerts;
 mod auth;
+mod autotag;
+mod aws_secrets;
 mod cache;
 mod capability;
+mod cluster;
 mod code_mode;
+mod disabled;
+mod discovery;
+mod enrichment;
 mod failsafe;
+mod idempotency;
 mod key_server;
+mod metrics_export;
+mod password_managers;
 mod playbooks;
+mod prediction;
+mod ranking;
+mod readiness;
+mod registry;
 mod runtime;
+mod secrets_file;
 mod security;
+mod slo;
+mod stats_history;
 mod streaming;
+mod vault;
 mod webhooks;
 
+pub use access_log::AccessLogConfig;
+pub use alerts::{AlertMetric, AlertRuleConfig, AlertsConfig};
 pub use auth::{AgentAuthConfig, AgentDefinitionConfig, ApiKeyConfig, AuthConfig};
+pub use autotag::{AutotagConfig, AutotagRuleConfig};
+pub use aws_secrets::AwsSecretsConfig;
 pub use cache::CacheConfig;
 pub use capability::CapabilityConfig;
+pub use cluster::ClusterConfig;
 pub use code_mode::CodeModeConfig;
+pub use disabled::DisabledConfig;
+pub use discovery::{DiscoveryAdoptPolicy, DiscoveryConfig};
+pub use enrichment::EnrichmentConfig;
 pub use failsafe::{
-    CircuitBreakerConfig, FailsafeConfig, HealthCheckConfig, RateLimitConfig, RetryConfig,
+    AutoReviveConfig, CircuitBreakerConfig, FailsafeConfig, HealthCheckConfig, RateLimitConfig,
+    RatePeriod, RateSpec, RetryConfig,
 };
+pub use idempotency::IdempotencyConfig;
 pub use key_server::{
-    KeyServerConfig, KeyServerOidcConfig, KeyServerPolicyConfig, KeyServerProviderConfig,
+    KeyServerConfig, KeyServerIssuanceConfig, KeyServerJwtConfig, KeyServerOidcConfig,
+    KeyServerPolicyConfig, KeyServerProviderConfig, KeyServerStoreConfig, PolicyEffect,
     PolicyMatchConfig, PolicyScopesConfig,
 };
+pub use metrics_export::{MetricsExportConfig, MetricsExportProtocol};
+pub use password_managers::{BitwardenConfig, OnePasswordConfig};
 pub use playbooks::PlaybooksConfig;
+pub use prediction::PredictionConfig;
+pub use ranking::RankingConfig;
+pub use readiness::ReadinessConfig;
+pub use registry::{RegistryAuthConfig, RegistryConfig, RegistrySourceConfig, RegistryTrustConfig};
 pub use runtime::{RuntimeAvailabilityConfig, RuntimeConfig, RuntimeProfileConfig};
+pub use secrets_file::SecretsFileConfig;
 pub use security::{
     AgentIdentityConfig, ContextIntegrityConfig, ContextIntegrityPresetConfig,
     IdentityGrantsConfig, RemoteServerSigningConfig, ResponseContractConfig, SecurityConfig,
     ToolContractConfig,
 };
+pub use slo::{BurnRateAlertConfig, LatencyObjectiveConfig, SloConfig, SloObjectiveConfig};
+pub use stats_history::StatsHistoryConfig;
 pub use streaming::StreamingConfig;
+pub use vault::{VaultAuthConfig, VaultConfig};
 pub use webhooks::WebhookConfig;
@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! 1Password and Bitwarden CLI secret source configuration.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+const DEFAULT_OP_BINARY: &str = "op";
+const DEFAULT_BW_BINARY: &str = "bw";
+
+// ── 1Password ──────────────────────────────────────────────────────────────────
+
+/// Settings of `{op.VAULT/ITEM/FIELD}` references (and `op:VAULT/ITEM/FIELD`
+/// capability credentials), read with `op read`.
+///
+/// Without a `service_account_token` the `op` CLI uses its own sign-in, e.g.
+/// the desktop app integration.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// onepassword:
+///   service_account_token: "{keychain.op-service-account}"
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OnePasswordConfig {
+    /// Path or name of the `op` executable.
+    pub binary: PathBuf,
+    /// Account to use when several are signed in (`op --account`).
+    pub account: Option<String>,
+    /// Service account token, passed as `OP_SERVICE_ACCOUNT_TOKEN`. May
+    /// reference `{env.VAR}` or `{keychain.SERVICE}`.
+    pub service_account_token: Option<String>,
+    /// How long a read secret is reused before `op` is run again.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub cache_ttl: Duration,
+}
+
+impl Default for OnePasswordConfig {
+    fn default() -> Self {
+        Self {
+            binary: PathBuf::from(DEFAULT_OP_BINARY),
+            account: None,
+            service_account_token: None,
+            cache_ttl: Duration::from_secs(DEFAULT_CACHE_TTL_SECS),
+        }
+    }
+}
+
+// Manual `Debug` that redacts the service account token (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for OnePasswordConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnePasswordConfig")
+            .field("binary", &self.binary)
+            .field("account", &self.account)
+            .field(
+                "service_account_token",
+                &self.service_account_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("cache_ttl", &self.cache_ttl)
+            .finish()
+    }
+}
+
+impl OnePasswordConfig {
+    /// Validate the service account token reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the token is empty or itself
+    /// refers to a password manager.
+    pub fn validate(&self) -> Result<()> {
+        validate_login(
+            "onepassword.service_account_token",
+            self.service_account_token.as_deref(),
+        )
+    }
+}
+
+// ── Bitwarden ──────────────────────────────────────────────────────────────────
+
+/// Settings of `{bw.ITEM}` / `{bw.ITEM#FIELD}` references (and `bw:ITEM#FIELD`
+/// capability credentials), read with `bw get item`.
+///
+/// Without a `session` the `bw` CLI uses `BW_SESSION` from the gateway's
+/// environment, i.e. a vault unlocked with `bw unlock`.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// bitwarden:
+///   session: "{env.BW_SESSION}"
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BitwardenConfig {
+    /// Path or name of the `bw` executable.
+    pub binary: PathBuf,
+    /// Session key of an unlocked vault, passed as `BW_SESSION`. May
+    /// reference `{env.VAR}` or `{keychain.SERVICE}`.
+    pub session: Option<String>,
+    /// How long a read secret is reused before `bw` is run again.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub cache_ttl: Duration,
+}
+
+impl Default for BitwardenConfig {
+    fn default() -> Self {
+        Self {
+            binary: PathBuf::from(DEFAULT_BW_BINARY),
+            session: None,
+            cache_ttl: Duration::from_secs(DEFAULT_CACHE_TTL_SECS),
+        }
+    }
+}
+
+// Manual `Debug` that redacts the session key (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for BitwardenConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BitwardenConfig")
+            .field("binary", &self.binary)
+            .field("session", &self.session.as_ref().map(|_| "<redacted>"))
+            .field("cache_ttl", &self.cache_ttl)
+            .finish()
+    }
+}
+
+impl BitwardenConfig {
+    /// Validate the session reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the session is empty or itself
+    /// refers to a password manager.
+    pub fn validate(&self) -> Result<()> {
+        validate_login("bitwarden.session", self.session.as_deref())
+    }
+}
+
+/// A login credential must be non-empty and cannot come from the CLIs it
+/// unlocks.
+fn validate_login(field: &str, value: Option<&str>) -> Result<()> {
+    let Some(value) = value else {
+        return Ok(());
+    };
+    if value.trim().is_empty() {
+        return Err(Error::ConfigValidation(format!(
+            "{field} must not be empty"
+        )));
+    }
+    if value.contains("{op.") || value.contains("{bw.") {
+        return Err(Error::ConfigValidation(format!(
+            "{field} cannot reference a password manager; use {{env.VAR}} or {{keychain.SERVICE}}"
+        )));
+    }
+    Ok(())
+}
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Playbook configuration for multi-step tool chains.
 
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
 // ── Playbooks ──────────────────────────────────────────────────────────────────
@@ -14,6 +16,22 @@ pub struct PlaybooksConfig {
     pub enabled: bool,
     /// Directories to load playbook definitions from.
     pub directories: Vec<String>,
+    /// Publish each playbook in `tools/list` as `gateway_run_{name}`, with
+    /// its `inputs` as the input schema.
+    pub expose_as_tools: bool,
+    /// Directory checkpointing `gateway_run_playbook` runs so they can be
+    /// resumed with `resume: run_id` (unset = runs are not persisted).
+    pub state_dir: Option<String>,
+}
+
+impl PlaybooksConfig {
+    /// `state_dir` with `~` expanded.
+    #[must_use]
+    pub fn state_path(&self) -> Option<PathBuf> {
+        self.state_dir
+            .as_deref()
+            .map(crate::config_reload::expand_tilde)
+    }
 }
 
 impl Default for PlaybooksConfig {
@@ -21,6 +39,8 @@ impl Default for PlaybooksConfig {
         Self {
             enabled: false,
             directories: vec!["playbooks".to_string()],
+            expose_as_tools: false,
+            state_dir: None,
         }
     }
 }
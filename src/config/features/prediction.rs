@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Next-tool prediction and predictive backend warm-up configuration.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Prediction ─────────────────────────────────────────────────────────────────
+
+/// Thresholds for `predicted_next` hints and predictive warm-up.
+///
+/// When a prediction clears both thresholds the gateway starts the backend
+/// owning the predicted tool and prefetches its tool list in the background,
+/// so the predicted call does not pay the cold-start cost.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// prediction:
+///   min_confidence: 0.3
+///   min_count: 3
+///   warm_up: true
+///   warm_up_cooldown: 30s
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PredictionConfig {
+    /// Minimum share of observed transitions (0.0–1.0) for a prediction.
+    pub min_confidence: f64,
+    /// Minimum number of observed transitions for a prediction.
+    pub min_count: u64,
+    /// Start predicted backends and prefetch their tools (default `true`).
+    pub warm_up: bool,
+    /// Minimum time between warm-up attempts for the same backend.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub warm_up_cooldown: Duration,
+}
+
+impl PredictionConfig {
+    /// Validate thresholds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if `min_confidence` is outside
+    /// `[0, 1]`.
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err(Error::ConfigValidation(format!(
+                "prediction.min_confidence must be between 0 and 1, got {}",
+                self.min_confidence
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for PredictionConfig {
+    fn default() -> Self {
+        Self {
+            min_confidence: 0.30,
+            min_count: 3,
+            warm_up: true,
+            warm_up_cooldown: Duration::from_secs(30),
+        }
+    }
+}
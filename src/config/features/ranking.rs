@@ -0,0 +1,44 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Search ranking configuration.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+// ── Ranking ────────────────────────────────────────────────────────────────────
+
+/// Tuning for `gateway_search_tools` result ranking.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// ranking:
+///   usage_half_life: 14d
+///   synonyms_file: ~/.mcp-gateway/synonyms.yaml
+///   personalize: true
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    /// Half-life of the usage boost: a use this long ago weighs half as
+    /// much as one now. `0s` (the default) ranks by lifetime usage counts.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub usage_half_life: Duration,
+    /// YAML file of extra synonym groups and aliases merged with the
+    /// built-in table. Reloaded automatically when it changes.
+    pub synonyms_file: Option<String>,
+    /// Boost tools already used in the calling session, and tools popular
+    /// with sessions of the same routing profile (default `true`).
+    pub personalize: bool,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            usage_half_life: Duration::ZERO,
+            synonyms_file: None,
+            personalize: true,
+        }
+    }
+}
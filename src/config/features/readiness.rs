@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Readiness criteria for the `GET /readyz` probe.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::BackendConfig;
+use crate::{Error, Result};
+
+// ── Readiness ──────────────────────────────────────────────────────────────────
+
+/// When the gateway reports itself ready to receive traffic.
+///
+/// A backend counts as healthy when its circuit breaker is not open, the
+/// health tracker considers it live and, for OAuth backends, the latest
+/// token refresh succeeded. `/livez` ignores these criteria: it only says
+/// the process is serving requests.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// readiness:
+///   required_backends: [github, linear, slack, jira, notion]
+///   min_healthy_percent: 80
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadinessConfig {
+    /// Backends counted for readiness (empty = every registered backend).
+    pub required_backends: Vec<String>,
+    /// Percentage of the counted backends that must be healthy (default 100).
+    pub min_healthy_percent: u8,
+    /// Also require the in-process capability backend to be healthy
+    /// (default `true`).
+    pub require_capabilities: bool,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            required_backends: Vec::new(),
+            min_healthy_percent: 100,
+            require_capabilities: true,
+        }
+    }
+}
+
+impl ReadinessConfig {
+    /// Validate the threshold and the required backend names.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] for a percentage above 100 or a
+    /// required backend that is not configured.
+    pub fn validate(&self, backends: &HashMap<String, BackendConfig>) -> Result<()> {
+        if self.min_healthy_percent > 100 {
+            return Err(Error::ConfigValidation(format!(
+                "readiness.min_healthy_percent must be at most 100, got {}",
+                self.min_healthy_percent
+            )));
+        }
+        if let Some(unknown) = self
+            .required_backends
+            .iter()
+            .find(|name| !backends.contains_key(*name))
+        {
+            return Err(Error::ConfigValidation(format!(
+                "readiness.required_backends names unknown backend '{unknown}'"
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Remote capability registry configuration.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_CACHE_DIR: &str = "~/.mcp-gateway/registry-cache";
+
+// ── Registries ─────────────────────────────────────────────────────────────────
+
+/// Remote capability registries used by `cap install` and `cap search`.
+///
+/// Each source is a registry directory written by `mcp-gateway cap publish`
+/// and served over HTTP(S). Indexes and packages are cached under
+/// `cache_dir`; when a registry is unreachable the cached copy is used.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// registry:
+///   sources:
+///     - name: internal
+///       url: https://capabilities.example.com/registry
+///       priority: 10
+///       auth:
+///         type: bearer
+///         token_env: CAPABILITY_REGISTRY_TOKEN
+///     - name: community
+///       url: https://registry.example.org
+///       public_keys:
+///         acme: "q7Xk...base64 Ed25519 public key...="
+///       trust:
+///         require_signed: true
+///         allowed_publishers: [acme]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegistryConfig {
+    /// Registries to query; on a name clash the highest `priority` wins.
+    pub sources: Vec<RegistrySourceConfig>,
+    /// Local cache of fetched indexes and packages. Supports `~`.
+    pub cache_dir: String,
+}
+
+impl RegistryConfig {
+    /// `cache_dir` with `~` expanded.
+    #[must_use]
+    pub fn cache_path(&self) -> PathBuf {
+        crate::config_reload::expand_tilde(&self.cache_dir)
+    }
+
+    /// Validate source names, URLs, auth and trust policies.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if a source name is empty, unsafe
+    /// as a directory name or duplicated, a URL is not `http(s)`, basic
+    /// auth has no username, or the trust policy is unusable (see
+    /// [`RegistrySourceConfig::validate_trust`]).
+    pub fn validate(&self) -> Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for source in &self.sources {
+            let name = &source.name;
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(Error::ConfigValidation(format!(
+                    "registry.sources name '{name}' must be non-empty and use only letters, digits, '-' or '_'"
+                )));
+            }
+            if !seen.insert(name) {
+                return Err(Error::ConfigValidation(format!(
+                    "registry.sources name '{name}' is defined twice"
+                )));
+            }
+            if !(source.url.starts_with("https://") || source.url.starts_with("http://")) {
+                return Err(Error::ConfigValidation(format!(
+                    "registry.sources '{name}' url must be an http(s) URL, got '{}'",
+                    source.url
+                )));
+            }
+            if let RegistryAuthConfig::Basic { username, .. } = &source.auth
+                && username.is_empty()
+            {
+                return Err(Error::ConfigValidation(format!(
+                    "registry.sources '{name}' basic auth needs a username"
+                )));
+            }
+            source.validate_trust()?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for RegistryConfig {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            cache_dir: DEFAULT_CACHE_DIR.to_string(),
+        }
+    }
+}
+
+/// One remote registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistrySourceConfig {
+    /// Short name, used in output and as the cache sub-directory.
+    pub name: String,
+    /// Base URL; `index.json` and package paths are resolved against it.
+    pub url: String,
+    /// Higher priorities are queried first and win name clashes.
+    #[serde(default)]
+    pub priority: i32,
+    /// Credentials sent with every request.
+    #[serde(default)]
+    pub auth: RegistryAuthConfig,
+    /// Publisher name → base64 Ed25519 public key. Packages signed by these
+    /// publishers are verified on install and load.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub public_keys: BTreeMap<String, String>,
+    /// Which packages this registry may install.
+    #[serde(default)]
+    pub trust: RegistryTrustConfig,
+}
+
+impl RegistrySourceConfig {
+    /// Decoded public key of `publisher`, if configured and valid.
+    #[must_use]
+    pub fn public_key(&self, publisher: &str) -> Option<Vec<u8>> {
+        self.public_keys
+            .get(publisher)
+            .and_then(|key| STANDARD.decode(key).ok())
+            .filter(|key| key.len() == 32)
+    }
+
+    /// Whether a package signed by `publisher` is acceptable.
+    #[must_use]
+    pub fn allows_publisher(&self, publisher: &str) -> bool {
+        self.trust.allowed_publishers.is_empty()
+            || self.trust.allowed_publishers.iter().any(|p| p == publisher)
+    }
+
+    /// Whether installs from this registry are signature-checked.
+    #[must_use]
+    pub fn verifies_signatures(&self) -> bool {
+        self.trust.require_signed || !self.public_keys.is_empty()
+    }
+
+    /// Validate `public_keys` and `trust`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if a public key is not 32 bytes
+    /// of base64, an allowed publisher has no public key, or signatures are
+    /// required without any public key to check them against.
+    pub fn validate_trust(&self) -> Result<()> {
+        let name = &self.name;
+        for publisher in self.public_keys.keys() {
+            if self.public_key(publisher).is_none() {
+                return Err(Error::ConfigValidation(format!(
+                    "registry.sources '{name}' public key of '{publisher}' must be a base64 Ed25519 key (32 bytes)"
+                )));
+            }
+        }
+        if let Some(publisher) = self
+            .trust
+            .allowed_publishers
+            .iter()
+            .find(|p| !self.public_keys.contains_key(*p))
+        {
+            return Err(Error::ConfigValidation(format!(
+                "registry.sources '{name}' allows publisher '{publisher}' but has no public key for it"
+            )));
+        }
+        if self.trust.require_signed && self.public_keys.is_empty() {
+            return Err(Error::ConfigValidation(format!(
+                "registry.sources '{name}' requires signed packages but lists no public_keys"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Trust policy of one registry.
+///
+/// Signatures from a configured publisher are always verified and a bad
+/// one is always refused. `require_signed` additionally refuses unsigned
+/// packages and packages from publishers without a configured key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RegistryTrustConfig {
+    /// Refuse packages without a valid signature from a known publisher.
+    pub require_signed: bool,
+    /// Publishers whose packages are accepted; empty accepts every
+    /// publisher in `public_keys`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_publishers: Vec<String>,
+}
+
+/// Registry credentials. Secrets are read from environment variables so
+/// they never live in the config file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RegistryAuthConfig {
+    /// No credentials.
+    #[default]
+    None,
+    /// `Authorization: Bearer <token>`.
+    Bearer {
+        /// Environment variable holding the token.
+        token_env: String,
+    },
+    /// HTTP basic auth.
+    Basic {
+        /// User name.
+        username: String,
+        /// Environment variable holding the password.
+        password_env: String,
+    },
+}
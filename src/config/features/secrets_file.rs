@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Encrypted secrets file configuration.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_SOPS_BINARY: &str = "sops";
+const DEFAULT_AGE_BINARY: &str = "age";
+
+// ── Secrets file ───────────────────────────────────────────────────────────────
+
+/// A SOPS- or age-encrypted YAML file of secrets, decrypted at startup and
+/// referenced as `{secret.KEY.PATH}` (and `secret:KEY.PATH` capability
+/// credentials).
+///
+/// Files with SOPS metadata are decrypted with `sops`, which also handles
+/// KMS and PGP keys; whole-file age encryption is decrypted with `age`.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// secrets_file:
+///   path: secrets.enc.yaml
+///   key: "{keychain.mcp-gateway-age-key}"
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecretsFileConfig {
+    /// Encrypted file; a relative path is resolved against the config
+    /// file's directory.
+    pub path: PathBuf,
+    /// age identity (`AGE-SECRET-KEY-1…`), usually `{env.VAR}` or
+    /// `{keychain.SERVICE}`. Required for age files; for SOPS files it is
+    /// passed as `SOPS_AGE_KEY`, and SOPS's own key lookup applies without it.
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Path or name of the `sops` executable.
+    #[serde(default = "default_sops_binary")]
+    pub sops_binary: PathBuf,
+    /// Path or name of the `age` executable.
+    #[serde(default = "default_age_binary")]
+    pub age_binary: PathBuf,
+}
+
+fn default_sops_binary() -> PathBuf {
+    PathBuf::from(DEFAULT_SOPS_BINARY)
+}
+
+fn default_age_binary() -> PathBuf {
+    PathBuf::from(DEFAULT_AGE_BINARY)
+}
+
+// Manual `Debug` that redacts the age identity (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for SecretsFileConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretsFileConfig")
+            .field("path", &self.path)
+            .field("key", &self.key.as_ref().map(|_| "<redacted>"))
+            .field("sops_binary", &self.sops_binary)
+            .field("age_binary", &self.age_binary)
+            .finish()
+    }
+}
+
+impl SecretsFileConfig {
+    /// Validate the file path and key reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the path or key is empty, or
+    /// the key refers to the secrets file itself.
+    pub fn validate(&self) -> Result<()> {
+        if self.path.as_os_str().is_empty() {
+            return Err(Error::ConfigValidation(
+                "secrets_file.path must not be empty".to_string(),
+            ));
+        }
+        if let Some(key) = &self.key {
+            if key.trim().is_empty() {
+                return Err(Error::ConfigValidation(
+                    "secrets_file.key must not be empty".to_string(),
+                ));
+            }
+            if key.contains("{secret.") {
+                return Err(Error::ConfigValidation(
+                    "secrets_file.key cannot come from the secrets file; use {env.VAR} or {keychain.SERVICE}"
+                        .to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
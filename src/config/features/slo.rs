@@ -0,0 +1,176 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Service-level objective (SLO) and burn-rate alert configuration.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_EVALUATION_INTERVAL_SECS: u64 = 60;
+const DEFAULT_LATENCY_TARGET: f64 = 0.99;
+const DEFAULT_MIN_EVENTS: u64 = 20;
+
+// ── SLO ────────────────────────────────────────────────────────────────────────
+
+/// SLO tracking with multi-window error-budget burn alerts.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// slo:
+///   enabled: true
+///   webhook_url: "https://hooks.example.com/slo"
+///   objectives:
+///     github:
+///       success_rate: 0.995
+///       latency: { threshold: 800ms, target: 0.99 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SloConfig {
+    /// Enable SLO tracking.
+    pub enabled: bool,
+    /// Per-backend objectives (key = backend name).
+    pub objectives: HashMap<String, SloObjectiveConfig>,
+    /// Burn-rate alert rules. An alert fires when the burn rate exceeds
+    /// `burn_rate` over both `long_window` and `short_window`.
+    pub alerts: Vec<BurnRateAlertConfig>,
+    /// Webhook receiving JSON alert events (`firing` / `resolved`).
+    /// Alerts are always logged; the webhook is optional.
+    pub webhook_url: Option<String>,
+    /// How often burn rates are evaluated.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub evaluation_interval: Duration,
+    /// Minimum calls in an alert's long window before it may fire, so a
+    /// single failure on an idle backend does not page.
+    pub min_events: u64,
+}
+
+impl SloConfig {
+    /// Validate targets and alert windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] for a target outside `(0, 1)`, a
+    /// non-positive burn rate, or a short window not shorter than its long
+    /// window.
+    pub fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let mut names: Vec<&String> = self.objectives.keys().collect();
+        names.sort();
+        for name in names {
+            let objective = &self.objectives[name];
+            let targets = [
+                ("success_rate", objective.success_rate),
+                (
+                    "latency.target",
+                    objective.latency.as_ref().map(|l| l.target),
+                ),
+            ];
+            for (field, target) in targets {
+                if let Some(target) = target
+                    && !(target > 0.0 && target < 1.0)
+                {
+                    return Err(Error::ConfigValidation(format!(
+                        "slo.objectives.{name}.{field} must be between 0 and 1 (exclusive), \
+                         got {target}"
+                    )));
+                }
+            }
+        }
+        for alert in &self.alerts {
+            if alert.burn_rate <= 0.0 || alert.short_window >= alert.long_window {
+                return Err(Error::ConfigValidation(format!(
+                    "slo alert '{}' needs burn_rate > 0 and short_window < long_window",
+                    alert.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for SloConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            objectives: HashMap::new(),
+            alerts: BurnRateAlertConfig::defaults(),
+            webhook_url: None,
+            evaluation_interval: Duration::from_secs(DEFAULT_EVALUATION_INTERVAL_SECS),
+            min_events: DEFAULT_MIN_EVENTS,
+        }
+    }
+}
+
+/// Objectives for one backend. Each configured indicator has its own budget.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SloObjectiveConfig {
+    /// Target fraction of successful calls (e.g. `0.995`).
+    pub success_rate: Option<f64>,
+    /// Latency objective.
+    pub latency: Option<LatencyObjectiveConfig>,
+}
+
+/// "`target` of calls complete within `threshold`".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyObjectiveConfig {
+    /// Latency bound a call must meet to count as good.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub threshold: Duration,
+    /// Target fraction of calls within `threshold` (default `0.99`).
+    #[serde(default = "default_latency_target")]
+    pub target: f64,
+}
+
+fn default_latency_target() -> f64 {
+    DEFAULT_LATENCY_TARGET
+}
+
+/// Multi-window burn-rate alert rule.
+///
+/// A burn rate of 1 spends the error budget exactly over the SLO period;
+/// 14.4 spends a 30-day budget's 2 % in one hour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnRateAlertConfig {
+    /// Alert name used in events (e.g. `fast-burn`).
+    pub name: String,
+    /// Long window; confirms the burn is significant.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub long_window: Duration,
+    /// Short window; confirms the burn is still happening.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub short_window: Duration,
+    /// Burn-rate threshold.
+    pub burn_rate: f64,
+}
+
+impl BurnRateAlertConfig {
+    /// The standard fast-burn (1h/5m at 14.4×) and slow-burn (6h/30m at
+    /// 6×) page rules.
+    #[must_use]
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self {
+                name: "fast-burn".to_string(),
+                long_window: Duration::from_secs(3600),
+                short_window: Duration::from_secs(300),
+                burn_rate: 14.4,
+            },
+            Self {
+                name: "slow-burn".to_string(),
+                long_window: Duration::from_secs(6 * 3600),
+                short_window: Duration::from_secs(1800),
+                burn_rate: 6.0,
+            },
+        ]
+    }
+}
@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! In-memory stats history configuration (served by `GET /stats`).
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_RESOLUTION_SECS: u64 = 60;
+const DEFAULT_RETENTION_SECS: u64 = 24 * 3600;
+/// Upper bound on retained samples, so a fine resolution over a long
+/// retention cannot grow memory without limit.
+const MAX_SAMPLES: u64 = 100_000;
+
+// ── Stats history ──────────────────────────────────────────────────────────────
+
+/// Periodic stats snapshots kept in memory for trend charts.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// stats_history:
+///   resolution: 30s
+///   retention: 6h
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsHistoryConfig {
+    /// Record history samples (on by default; a day at one-minute
+    /// resolution is 1440 small samples).
+    pub enabled: bool,
+    /// Interval between samples.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub resolution: Duration,
+    /// How long samples are kept.
+    #[serde(with = "crate::config::humantime_serde")]
+    pub retention: Duration,
+}
+
+impl StatsHistoryConfig {
+    /// Number of samples retained (`retention / resolution`).
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        let resolution = self.resolution.as_secs().max(1);
+        usize::try_from((self.retention.as_secs() / resolution).max(1)).unwrap_or(usize::MAX)
+    }
+
+    /// Validate the resolution and retention.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] when the resolution is under one
+    /// second, the retention is shorter than the resolution, or the pair
+    /// would retain more than 100 000 samples.
+    pub fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let resolution = self.resolution.as_secs();
+        let retention = self.retention.as_secs();
+        if resolution == 0 || retention < resolution {
+            return Err(Error::ConfigValidation(
+                "stats_history needs resolution >= 1s and retention >= resolution".to_string(),
+            ));
+        }
+        if retention / resolution > MAX_SAMPLES {
+            return Err(Error::ConfigValidation(format!(
+                "stats_history would retain {} samples (max {MAX_SAMPLES}); \
+                 raise resolution or lower retention",
+                retention / resolution
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for StatsHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resolution: Duration::from_secs(DEFAULT_RESOLUTION_SECS),
+            retention: Duration::from_secs(DEFAULT_RETENTION_SECS),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Vault secret source configuration.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+// ── Constants ──────────────────────────────────────────────────────────────────
+
+const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_K8S_JWT_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+// ── Vault ──────────────────────────────────────────────────────────────────────
+
+/// Vault server backing `{vault.MOUNT/PATH#FIELD}` references
+/// (and `vault:MOUNT/PATH#FIELD` capability credentials).
+///
+/// Secrets are read from KV version 2 engines. The login token is renewed in
+/// the background; `AppRole` and Kubernetes logins are repeated when it can no
+/// longer be renewed.
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// vault:
+///   address: "https://vault.internal:8200"
+///   auth:
+///     method: approle
+///     role_id: "{env.VAULT_ROLE_ID}"
+///     secret_id: "{env.VAULT_SECRET_ID}"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VaultConfig {
+    /// Vault server URL.
+    pub address: String,
+    /// Vault Enterprise namespace, sent as `X-Vault-Namespace`.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// How the gateway logs in.
+    pub auth: VaultAuthConfig,
+    /// How long a read secret is reused before Vault is asked again.
+    #[serde(default = "default_cache_ttl", with = "crate::config::humantime_serde")]
+    pub cache_ttl: Duration,
+    /// Timeout of each request to Vault.
+    #[serde(default = "default_timeout", with = "crate::config::humantime_serde")]
+    pub timeout: Duration,
+}
+
+fn default_cache_ttl() -> Duration {
+    Duration::from_secs(DEFAULT_CACHE_TTL_SECS)
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_secs(DEFAULT_TIMEOUT_SECS)
+}
+
+/// Vault login method. String values may reference `{env.VAR}` or
+/// `{keychain.SERVICE}`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case", deny_unknown_fields)]
+pub enum VaultAuthConfig {
+    /// A pre-issued token.
+    Token {
+        /// Vault token.
+        token: String,
+    },
+    /// `AppRole` login.
+    Approle {
+        /// Auth mount path.
+        #[serde(default = "default_approle_mount")]
+        mount: String,
+        /// Role ID.
+        role_id: String,
+        /// Secret ID.
+        secret_id: String,
+    },
+    /// Kubernetes service-account login.
+    Kubernetes {
+        /// Auth mount path.
+        #[serde(default = "default_kubernetes_mount")]
+        mount: String,
+        /// Vault role bound to the service account.
+        role: String,
+        /// Service-account token file.
+        #[serde(default = "default_k8s_jwt_path")]
+        jwt_path: PathBuf,
+    },
+}
+
+fn default_approle_mount() -> String {
+    "approle".to_string()
+}
+
+fn default_kubernetes_mount() -> String {
+    "kubernetes".to_string()
+}
+
+fn default_k8s_jwt_path() -> PathBuf {
+    PathBuf::from(DEFAULT_K8S_JWT_PATH)
+}
+
+// Manual `Debug` that redacts the login credentials (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for VaultAuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Token { .. } => f
+                .debug_struct("Token")
+                .field("token", &"<redacted>")
+                .finish(),
+            Self::Approle { mount, role_id, .. } => f
+                .debug_struct("Approle")
+                .field("mount", mount)
+                .field("role_id", role_id)
+                .field("secret_id", &"<redacted>")
+                .finish(),
+            Self::Kubernetes {
+                mount,
+                role,
+                jwt_path,
+            } => f
+                .debug_struct("Kubernetes")
+                .field("mount", mount)
+                .field("role", role)
+                .field("jwt_path", jwt_path)
+                .finish(),
+        }
+    }
+}
+
+impl VaultConfig {
+    /// Validate the server URL and login settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if the address is not `http(s)`, a
+    /// login field is empty or itself refers to Vault, or the timeout is zero.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.address.starts_with("https://") || self.address.starts_with("http://")) {
+            return Err(Error::ConfigValidation(format!(
+                "vault.address must be an http(s) URL, got '{}'",
+                self.address
+            )));
+        }
+        if self.timeout.is_zero() {
+            return Err(Error::ConfigValidation(
+                "vault.timeout must be greater than zero".to_string(),
+            ));
+        }
+        let fields: Vec<(&str, &str)> = match &self.auth {
+            VaultAuthConfig::Token { token } => vec![("token", token)],
+            VaultAuthConfig::Approle {
+                mount,
+                role_id,
+                secret_id,
+            } => vec![
+                ("mount", mount),
+                ("role_id", role_id),
+                ("secret_id", secret_id),
+            ],
+            VaultAuthConfig::Kubernetes { mount, role, .. } => {
+                vec![("mount", mount), ("role", role)]
+            }
+        };
+        for (field, value) in fields {
+            if value.trim().is_empty() {
+                return Err(Error::ConfigValidation(format!(
+                    "vault.auth.{field} must not be empty"
+                )));
+            }
+            // The login itself cannot come from Vault.
+            if value.contains("{vault.") {
+                return Err(Error::ConfigValidation(format!(
+                    "vault.auth.{field} cannot reference Vault; use {{env.VAR}} or {{keychain.SERVICE}}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
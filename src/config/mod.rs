@@ -29,14 +29,21 @@ use crate::{Error, Result};
 
 // Re-export all feature config types so external code needs only `crate::config::Foo`.
 pub use features::{
-    AgentAuthConfig, AgentDefinitionConfig, AgentIdentityConfig, ApiKeyConfig, AuthConfig,
-    CacheConfig, CapabilityConfig, CircuitBreakerConfig, CodeModeConfig, ContextIntegrityConfig,
-    ContextIntegrityPresetConfig, FailsafeConfig, HealthCheckConfig, IdentityGrantsConfig,
-    KeyServerConfig, KeyServerOidcConfig, KeyServerPolicyConfig, KeyServerProviderConfig,
-    PlaybooksConfig, PolicyMatchConfig, PolicyScopesConfig, RateLimitConfig,
-    RemoteServerSigningConfig, ResponseContractConfig, RetryConfig, RuntimeAvailabilityConfig,
-    RuntimeConfig, RuntimeProfileConfig, SecurityConfig, StreamingConfig, ToolContractConfig,
-    WebhookConfig,
+    AccessLogConfig, AgentAuthConfig, AgentDefinitionConfig, AgentIdentityConfig, AlertMetric,
+    AlertRuleConfig, AlertsConfig, ApiKeyConfig, AuthConfig, AutoReviveConfig, AutotagConfig,
+    AutotagRuleConfig, AwsSecretsConfig, BitwardenConfig, BurnRateAlertConfig, CacheConfig,
+    CapabilityConfig, CircuitBreakerConfig, ClusterConfig, CodeModeConfig, ContextIntegrityConfig,
+    ContextIntegrityPresetConfig, DisabledConfig, DiscoveryAdoptPolicy, DiscoveryConfig,
+    EnrichmentConfig, FailsafeConfig, HealthCheckConfig, IdempotencyConfig, IdentityGrantsConfig,
+    KeyServerConfig, KeyServerIssuanceConfig, KeyServerJwtConfig, KeyServerOidcConfig,
+    KeyServerPolicyConfig, KeyServerProviderConfig, KeyServerStoreConfig, LatencyObjectiveConfig,
+    MetricsExportConfig, MetricsExportProtocol, OnePasswordConfig, PlaybooksConfig, PolicyEffect,
+    PolicyMatchConfig, PolicyScopesConfig, PredictionConfig, RankingConfig, RateLimitConfig,
+    RatePeriod, RateSpec, ReadinessConfig, RegistryAuthConfig, RegistryConfig,
+    RegistrySourceConfig, RegistryTrustConfig, RemoteServerSigningConfig, ResponseContractConfig,
+    RetryConfig, RuntimeAvailabilityConfig, RuntimeConfig, RuntimeProfileConfig, SecretsFileConfig,
+    SecurityConfig, SloConfig, SloObjectiveConfig, StatsHistoryConfig, StreamingConfig,
+    ToolContractConfig, VaultAuthConfig, VaultConfig, WebhookConfig,
 };
 
 // ── Root config ───────────────────────────────────────────────────────────────
@@ -72,6 +79,47 @@ pub struct Config {
     pub security: SecurityConfig,
     /// Webhook receiver configuration.
     pub webhooks: WebhookConfig,
+    /// Push-based metrics export (OTLP / `StatsD`).
+    pub metrics_export: MetricsExportConfig,
+    /// Per-backend SLOs with error-budget burn alerts.
+    pub slo: SloConfig,
+    /// In-memory stats time series served by `GET /stats`.
+    pub stats_history: StatsHistoryConfig,
+    /// Search result ranking tuning.
+    pub ranking: RankingConfig,
+    /// Keyword tagging of backend tool descriptions.
+    pub autotag: AutotagConfig,
+    /// LLM-generated tool keywords and summaries (`mcp-gateway enrich`).
+    pub enrichment: EnrichmentConfig,
+    /// Duplicate tool-call suppression for `gateway_invoke`.
+    pub idempotency: IdempotencyConfig,
+    /// Next-tool prediction thresholds and predictive backend warm-up.
+    pub prediction: PredictionConfig,
+    /// Servers, tools and capabilities kept disabled by the kill switch.
+    pub disabled: DisabledConfig,
+    /// Delivery of operator alerts (auto-kills, breaker trips).
+    pub alerts: AlertsConfig,
+    /// Readiness criteria for `GET /readyz`.
+    pub readiness: ReadinessConfig,
+    /// State shared between gateway replicas (rate limits, kill switch,
+    /// session profiles, idempotency keys).
+    pub cluster: ClusterConfig,
+    /// Per-request structured access log.
+    pub access_log: AccessLogConfig,
+    /// Background discovery of MCP servers that are not configured yet.
+    pub discovery: DiscoveryConfig,
+    /// Cron-scheduled playbook runs and tool calls.
+    pub scheduler: crate::scheduler::SchedulerConfig,
+    /// Vault server for `{vault.MOUNT/PATH#FIELD}` secret references.
+    pub vault: Option<VaultConfig>,
+    /// AWS Secrets Manager / SSM Parameter Store secret references.
+    pub aws_secrets: AwsSecretsConfig,
+    /// 1Password CLI for `{op.VAULT/ITEM/FIELD}` secret references.
+    pub onepassword: OnePasswordConfig,
+    /// Bitwarden CLI for `{bw.ITEM#FIELD}` secret references.
+    pub bitwarden: BitwardenConfig,
+    /// Encrypted file for `{secret.KEY}` secret references.
+    pub secrets_file: Option<SecretsFileConfig>,
     /// Routing profiles for session-scoped tool access control.
     #[serde(default)]
     pub routing_profiles: HashMap<String, RoutingProfileConfig>,
@@ -96,6 +144,9 @@ pub struct Config {
     /// Plugin marketplace and local plugin directory.
     #[serde(default)]
     pub marketplace: MarketplaceConfig,
+    /// Remote capability registries for `cap install` / `cap search`.
+    #[serde(default)]
+    pub registry: RegistryConfig,
     /// Enterprise control-plane governance (identity-to-role mapping, MIK-6688).
     #[serde(default)]
     pub control_plane: crate::control_plane::ControlPlaneConfig,
@@ -298,6 +349,7 @@ impl Config {
             tracing::warn!("Server port is 0; OS will assign an ephemeral port");
         }
         self.validate_backend_names()?;
+        self.validate_backend_namespaces()?;
         self.validate_backend_urls()?;
         self.validate_remote_backend_provenance()?;
         self.validate_required_env_references()?;
@@ -305,7 +357,56 @@ impl Config {
         self.validate_backend_runtime_profiles()?;
         self.control_plane.role_mapping.validate()?;
         self.validate_identity_propagation()?;
+        self.validate_forward_identity()?;
+        self.validate_response_transforms()?;
+        self.validate_backend_oauth()?;
         self.key_server.validate()?;
+        self.mtls.acme.validate()?;
+        self.slo.validate()?;
+        self.stats_history.validate()?;
+        self.metrics_export.validate()?;
+        self.autotag.validate()?;
+        self.enrichment.validate()?;
+        self.idempotency.validate()?;
+        self.prediction.validate()?;
+        self.disabled.validate()?;
+        self.alerts.validate(&self.backends)?;
+        self.readiness.validate(&self.backends)?;
+        self.cluster.validate()?;
+        self.access_log.validate()?;
+        self.discovery.validate()?;
+        self.scheduler.validate()?;
+        self.registry.validate()?;
+        if let Some(vault) = &self.vault {
+            vault.validate()?;
+        }
+        self.aws_secrets.validate()?;
+        self.onepassword.validate()?;
+        self.bitwarden.validate()?;
+        if let Some(secrets_file) = &self.secrets_file {
+            secrets_file.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Validate per-backend OAuth settings: the `client_credentials` grant
+    /// has no browser step to register a client, so both halves of the
+    /// credential must be configured.
+    fn validate_backend_oauth(&self) -> Result<()> {
+        for (name, backend) in &self.backends {
+            let Some(oauth) = backend.oauth.as_ref().filter(|o| o.enabled) else {
+                continue;
+            };
+            if oauth.grant_type == OAuthGrantType::ClientCredentials
+                && (oauth.client_id.is_none() || oauth.client_secret.is_none())
+            {
+                return Err(Error::ConfigValidation(format!(
+                    "backend '{name}' oauth.grant_type client_credentials requires \
+                     oauth.client_id and oauth.client_secret"
+                )));
+            }
+            validate_oauth_accounts(name, &oauth.accounts)?;
+        }
         Ok(())
     }
 
@@ -418,6 +519,60 @@ impl Config {
         Ok(())
     }
 
+    /// Namespaces become part of every exposed tool name, so they must be
+    /// identifier-safe and unique — two backends sharing a namespace would
+    /// reintroduce exactly the collisions the prefix exists to prevent.
+    fn validate_backend_namespaces(&self) -> Result<()> {
+        let mut seen: HashMap<&str, &str> = HashMap::new();
+        let mut names: Vec<&String> = self.backends.keys().collect();
+        names.sort();
+        for name in names {
+            let Some(namespace) = self.backends[name].namespace.as_deref() else {
+                continue;
+            };
+            if namespace.is_empty()
+                || !namespace
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(Error::ConfigValidation(format!(
+                    "backend '{name}' namespace '{namespace}' must be non-empty and contain \
+                     only ASCII letters, digits, '_' or '-'"
+                )));
+            }
+            if let Some(other) = seen.insert(namespace, name) {
+                return Err(Error::ConfigValidation(format!(
+                    "backends '{other}' and '{name}' share namespace '{namespace}'"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_forward_identity(&self) -> Result<()> {
+        for (name, backend) in &self.backends {
+            if let Some(forward) = &backend.forward_identity {
+                forward.validate(name)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject a per-tool response transform whose `query` does not parse,
+    /// rather than failing every call to that tool.
+    fn validate_response_transforms(&self) -> Result<()> {
+        for (name, backend) in &self.backends {
+            for (tool, transform) in &backend.response_transforms {
+                transform.validate().map_err(|e| {
+                    Error::ConfigValidation(format!(
+                        "backend '{name}' response_transforms.{tool}: {e}"
+                    ))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
     fn validate_remote_backend_provenance(&self) -> Result<()> {
         let policy = &self.security.remote_server_signing;
 
@@ -556,6 +711,40 @@ impl Config {
     }
 }
 
+/// Account names must be unique, identifier-safe, and each caller identity
+/// may map to at most one account so automatic selection is unambiguous.
+fn validate_oauth_accounts(backend: &str, accounts: &[OAuthAccount]) -> Result<()> {
+    let mut names = std::collections::HashSet::new();
+    let mut identities = HashMap::new();
+    for account in accounts {
+        let name = account.name.as_str();
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if !valid {
+            return Err(Error::ConfigValidation(format!(
+                "backend '{backend}' oauth account name '{name}' must be non-empty and use \
+                 only letters, digits, '_', '-' or '.'"
+            )));
+        }
+        if !names.insert(name) {
+            return Err(Error::ConfigValidation(format!(
+                "backend '{backend}' oauth account '{name}' is defined twice"
+            )));
+        }
+        for identity in &account.identities {
+            if let Some(other) = identities.insert(identity.as_str(), name) {
+                return Err(Error::ConfigValidation(format!(
+                    "backend '{backend}' oauth identity '{identity}' is listed by both \
+                     accounts '{other}' and '{name}'"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 fn remote_transport_identity(transport: &TransportConfig) -> Option<(&'static str, &str)> {
     match transport {
         TransportConfig::Http { http_url, .. } => Some((transport.transport_type(), http_url)),
@@ -583,7 +772,8 @@ pub struct ServerConfig {
     /// Request timeout.
     #[serde(with = "humantime_serde")]
     pub request_timeout: Duration,
-    /// Graceful shutdown timeout.
+    /// How long a graceful drain waits for in-flight requests and scheduled
+    /// runs before shutting down anyway.
     #[serde(with = "humantime_serde")]
     pub shutdown_timeout: Duration,
     /// Maximum request body size (bytes).
@@ -724,6 +914,14 @@ pub struct BackendConfig {
     /// Request timeout for this backend.
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
+    /// Per-tool request timeouts overriding `timeout`, keyed by exposed tool
+    /// name (e.g. `render_pdf: 300s`).
+    #[serde(
+        default,
+        with = "humantime_serde::map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub timeouts: HashMap<String, Duration>,
     /// Environment variables (for stdio).
     pub env: HashMap<String, String>,
     /// HTTP headers (for http/sse).
@@ -750,6 +948,156 @@ pub struct BackendConfig {
     /// static-credential behavior (IDP.5).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub identity_propagation: Option<crate::identity_propagation::IdentityPropagationConfig>,
+    /// Tool namespace prefix. When set, every tool this backend exposes is
+    /// renamed `{namespace}_{tool}` in search results, `tools/list` responses,
+    /// and invoke routing; the prefix is stripped again before the call is
+    /// forwarded to the backend. Disambiguates backends that expose tools with
+    /// the same name (e.g. two `search` tools).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Allow/deny glob filters over this backend's tool names. Filtered-out
+    /// tools are hidden from search and `tools/list` and refused on invoke.
+    #[serde(default, skip_serializing_if = "BackendToolFilter::is_empty")]
+    pub tools: BackendToolFilter,
+    /// Per-tool response transforms, keyed by exposed tool name. Same
+    /// `query` / `project` / `rename` / `redact` / `format` operations as a
+    /// capability's `response_transform`; `_full: true` bypasses them.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub response_transforms: HashMap<String, crate::transform::TransformConfig>,
+    /// Per-tool argument rewriting (rename / defaults / coerce), keyed by
+    /// exposed tool name, applied before `tools/call` reaches the backend.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub argument_transforms: HashMap<String, crate::provider::transforms::ArgumentRewriteConfig>,
+    /// Cost model used to attribute invocation spend to clients and sessions.
+    #[serde(default, skip_serializing_if = "BackendCostConfig::is_default")]
+    pub cost: BackendCostConfig,
+    /// Tell the backend who is calling (off unless configured).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forward_identity: Option<ForwardIdentityConfig>,
+}
+
+/// Per-backend tool filter, compiled into a
+/// [`crate::provider::transforms::FilterTransform`].
+///
+/// Patterns are matched against the exposed tool name (after any `namespace`
+/// prefix) and support `*` wildcards, e.g. `"*_read"` or `"*delete*"`. A
+/// non-empty `allow` list hides every tool that matches none of its patterns;
+/// `deny` then removes matches from whatever remains.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendToolFilter {
+    /// Tool name patterns to expose. Empty = expose all.
+    pub allow: Vec<String>,
+    /// Tool name patterns to hide.
+    pub deny: Vec<String>,
+}
+
+impl BackendToolFilter {
+    /// Returns `true` when neither list has any patterns.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+}
+
+/// Per-backend cost model for cost attribution.
+///
+/// Each invocation is charged `per_call_usd` plus its estimated tokens
+/// (arguments + result, ~4 bytes per token) at `price_per_million`.
+///
+/// ```yaml
+/// cost:
+///   per_call_usd: 0.002
+///   price_per_million: 3.0
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendCostConfig {
+    /// Flat charge per invocation in USD (e.g. a paid upstream API).
+    pub per_call_usd: f64,
+    /// Token price in USD per million tokens; `None` uses the gateway
+    /// default (`15.0`).
+    pub price_per_million: Option<f64>,
+}
+
+impl BackendCostConfig {
+    /// Returns `true` when no override is configured.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Estimated USD cost of one call with `token_count` estimated tokens.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn charge(&self, token_count: u64) -> f64 {
+        let price = self
+            .price_per_million
+            .unwrap_or(crate::cost_accounting::DEFAULT_PRICE_PER_MILLION);
+        self.per_call_usd + token_count as f64 * price / 1_000_000.0
+    }
+}
+
+/// Forward the authenticated caller's identity to a backend that does its
+/// own authorization.
+///
+/// Each configured header carries one identity attribute; `meta` adds all of
+/// them to the request's `_meta` under `io.mcp-gateway/caller`, which also
+/// reaches stdio and WebSocket backends (headers only reach HTTP ones).
+/// Attributes the caller lacks are omitted. Values come from the gateway's
+/// own authentication, never from the client's request headers.
+///
+/// ```yaml
+/// forward_identity:
+///   headers:
+///     client: X-Gateway-Client
+///     email: X-Gateway-User-Email
+///   meta: true
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForwardIdentityConfig {
+    /// Header names per identity attribute.
+    pub headers: ForwardIdentityHeaders,
+    /// Add the identity to the request `_meta`.
+    pub meta: bool,
+}
+
+/// Header names for [`ForwardIdentityConfig`]; unset attributes are not sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForwardIdentityHeaders {
+    /// API key / client name.
+    pub client: Option<String>,
+    /// OIDC subject from the key server.
+    pub subject: Option<String>,
+    /// OIDC email from the key server.
+    pub email: Option<String>,
+    /// mTLS client certificate identity (SPIFFE ID, else CN).
+    pub cert: Option<String>,
+}
+
+impl ForwardIdentityConfig {
+    /// Validate the header names.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] if a header name is not a valid
+    /// HTTP header name.
+    pub fn validate(&self, backend: &str) -> Result<()> {
+        let h = &self.headers;
+        for name in [&h.client, &h.subject, &h.email, &h.cert]
+            .into_iter()
+            .flatten()
+        {
+            if axum::http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                return Err(Error::ConfigValidation(format!(
+                    "backend '{backend}' forward_identity: invalid header name '{name}'"
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 // Manual `Debug` that redacts the credential-injection rules (CWE-532, mirrors
@@ -764,6 +1112,7 @@ impl std::fmt::Debug for BackendConfig {
             .field("transport", &self.transport)
             .field("idle_timeout", &self.idle_timeout)
             .field("timeout", &self.timeout)
+            .field("timeouts", &self.timeouts)
             // `env` and `headers` values routinely carry credentials
             // (Authorization bearers, API keys, env-injected secrets). The
             // field names are neutral, so the name-based leak lint cannot see
@@ -775,6 +1124,18 @@ impl std::fmt::Debug for BackendConfig {
             .field("passthrough", &self.passthrough)
             .field("runtime_profile", &self.runtime_profile)
             .field("identity_propagation", &self.identity_propagation)
+            .field("namespace", &self.namespace)
+            .field("tools", &self.tools)
+            .field(
+                "response_transforms",
+                &self.response_transforms.keys().collect::<Vec<_>>(),
+            )
+            .field(
+                "argument_transforms",
+                &self.argument_transforms.keys().collect::<Vec<_>>(),
+            )
+            .field("cost", &self.cost)
+            .field("forward_identity", &self.forward_identity)
             .finish()
     }
 }
@@ -787,6 +1148,7 @@ impl Default for BackendConfig {
             transport: TransportConfig::default(),
             idle_timeout: Duration::from_secs(300),
             timeout: Duration::from_secs(30),
+            timeouts: HashMap::new(),
             env: HashMap::new(),
             headers: HashMap::new(),
             oauth: None,
@@ -794,10 +1156,34 @@ impl Default for BackendConfig {
             passthrough: false,
             runtime_profile: None,
             identity_propagation: None,
+            namespace: None,
+            tools: BackendToolFilter::default(),
+            response_transforms: HashMap::new(),
+            argument_transforms: HashMap::new(),
+            cost: BackendCostConfig::default(),
+            forward_identity: None,
         }
     }
 }
 
+impl BackendConfig {
+    /// Timeout of a `tools/call` to `tool`: its `timeouts` entry, else
+    /// `timeout`.
+    #[must_use]
+    pub fn tool_timeout(&self, tool: &str) -> Duration {
+        self.timeouts.get(tool).copied().unwrap_or(self.timeout)
+    }
+
+    /// Longest timeout any request may need, which bounds the transport.
+    #[must_use]
+    pub fn max_timeout(&self) -> Duration {
+        self.timeouts
+            .values()
+            .copied()
+            .fold(self.timeout, Duration::max)
+    }
+}
+
 /// OAuth configuration for a backend.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct OAuthConfig {
@@ -807,11 +1193,20 @@ pub struct OAuthConfig {
     /// OAuth scopes to request (if empty, uses server's supported scopes).
     #[serde(default)]
     pub scopes: Vec<String>,
+    /// How the gateway obtains tokens (default: browser-based authorization code).
+    #[serde(default)]
+    pub grant_type: OAuthGrantType,
     /// Client ID (optional — uses dynamic registration or generates one if not set).
+    ///
+    /// May reference a secret as `{env.VAR}`, `{keychain.SERVICE}` or
+    /// `{vault.MOUNT/PATH#FIELD}`.
     #[serde(default)]
     pub client_id: Option<String>,
     /// Client secret for providers that issue fixed credentials (e.g. Slack, Figma).
     /// When set, sent as `client_secret` in the token-exchange request.
+    ///
+    /// May reference a secret as `{env.VAR}`, `{keychain.SERVICE}` or
+    /// `{vault.MOUNT/PATH#FIELD}`.
     #[serde(default)]
     pub client_secret: Option<String>,
     /// Hostname for the local OAuth callback server (default: `"localhost"`).
@@ -846,6 +1241,45 @@ pub struct OAuthConfig {
     /// ignores this flag — the sole caller always owns the token.
     #[serde(default)]
     pub shared_account: bool,
+    /// Named logins kept alongside the default one, so callers can reach the
+    /// backend as different users. Each account authorizes and stores its
+    /// token separately.
+    #[serde(default)]
+    pub accounts: Vec<OAuthAccount>,
+}
+
+/// A named OAuth login for a backend (see [`OAuthConfig::accounts`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OAuthAccount {
+    /// Account name, used as the `account` argument of `gateway_invoke`.
+    pub name: String,
+    /// Caller identities (OIDC subject or email) allowed to use this account;
+    /// a listed caller uses it automatically. Empty = any caller may select it.
+    #[serde(default)]
+    pub identities: Vec<String>,
+}
+
+impl OAuthAccount {
+    /// Whether the account is reserved for specific callers.
+    #[must_use]
+    pub fn is_restricted(&self) -> bool {
+        !self.identities.is_empty()
+    }
+
+    /// Whether `subject` or `email` is one of this account's identities.
+    #[must_use]
+    pub fn lists(&self, subject: &str, email: &str) -> bool {
+        self.identities.iter().any(|i| i == subject || i == email)
+    }
+}
+
+impl OAuthConfig {
+    /// The configured account called `name`.
+    #[must_use]
+    pub fn account(&self, name: &str) -> Option<&OAuthAccount> {
+        self.accounts.iter().find(|a| a.name == name)
+    }
 }
 
 // Manual `Debug` that redacts the fixed OAuth client secret (CWE-532, mirrors
@@ -857,6 +1291,7 @@ impl std::fmt::Debug for OAuthConfig {
         f.debug_struct("OAuthConfig")
             .field("enabled", &self.enabled)
             .field("scopes", &self.scopes)
+            .field("grant_type", &self.grant_type)
             .field("client_id", &self.client_id)
             .field("client_secret", &redact_opt(&self.client_secret))
             .field("callback_host", &self.callback_host)
@@ -864,10 +1299,23 @@ impl std::fmt::Debug for OAuthConfig {
             .field("callback_path", &self.callback_path)
             .field("token_refresh_buffer_secs", &self.token_refresh_buffer_secs)
             .field("shared_account", &self.shared_account)
+            .field("accounts", &self.accounts)
             .finish()
     }
 }
 
+/// OAuth grant used to obtain backend tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthGrantType {
+    /// Authorization code with PKCE; opens a browser on first use.
+    #[default]
+    AuthorizationCode,
+    /// Client credentials (RFC 6749 §4.4); headless, needs `client_id` and
+    /// `client_secret`.
+    ClientCredentials,
+}
+
 fn default_token_refresh_buffer() -> u64 {
     300
 }
@@ -1001,10 +1449,15 @@ pub mod humantime_serde {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&format!("{}s", duration.as_secs()))
+        if duration.subsec_millis() == 0 {
+            serializer.serialize_str(&format!("{}s", duration.as_secs()))
+        } else {
+            serializer.serialize_str(&format!("{}ms", duration.as_millis()))
+        }
     }
 
-    /// Deserialize a human-readable duration string (e.g., `"30s"`, `"5m"`, `"100ms"`).
+    /// Deserialize a human-readable duration string (e.g., `"30s"`, `"5m"`, `"1h"`,
+    /// `"7d"`, `"100ms"`).
     ///
     /// # Errors
     ///
@@ -1015,7 +1468,12 @@ pub mod humantime_serde {
     {
         let s = String::deserialize(deserializer)?;
 
-        if let Some(secs) = s.strip_suffix('s') {
+        // `ms` must be checked before `s` and `m`, which are its suffixes.
+        if let Some(ms) = s.strip_suffix("ms") {
+            ms.parse::<u64>()
+                .map(Duration::from_millis)
+                .map_err(serde::de::Error::custom)
+        } else if let Some(secs) = s.strip_suffix('s') {
             secs.parse::<u64>()
                 .map(Duration::from_secs)
                 .map_err(serde::de::Error::custom)
@@ -1023,9 +1481,14 @@ pub mod humantime_serde {
             mins.parse::<u64>()
                 .map(|m| Duration::from_secs(m * 60))
                 .map_err(serde::de::Error::custom)
-        } else if let Some(ms) = s.strip_suffix("ms") {
-            ms.parse::<u64>()
-                .map(Duration::from_millis)
+        } else if let Some(hours) = s.strip_suffix('h') {
+            hours
+                .parse::<u64>()
+                .map(|h| Duration::from_secs(h * 3600))
+                .map_err(serde::de::Error::custom)
+        } else if let Some(days) = s.strip_suffix('d') {
+            days.parse::<u64>()
+                .map(|d| Duration::from_secs(d * 86_400))
                 .map_err(serde::de::Error::custom)
         } else {
             s.parse::<u64>()
@@ -1033,6 +1496,51 @@ pub mod humantime_serde {
                 .map_err(serde::de::Error::custom)
         }
     }
+
+    /// `HashMap<String, Duration>` with human-readable values
+    /// (`{ render_pdf: 300s }`).
+    pub mod map {
+        use std::collections::HashMap;
+        use std::hash::BuildHasher;
+        use std::time::Duration;
+
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        #[derive(Serialize, Deserialize)]
+        struct Human(#[serde(with = "crate::config::humantime_serde")] Duration);
+
+        /// Serialize each value as [`serialize`](super::serialize) does.
+        ///
+        /// # Errors
+        ///
+        /// Returns a serialization error if the serializer fails.
+        pub fn serialize<S, H>(
+            map: &HashMap<String, Duration, H>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            H: BuildHasher,
+        {
+            serializer.collect_map(map.iter().map(|(key, value)| (key, Human(*value))))
+        }
+
+        /// Deserialize each value as [`deserialize`](super::deserialize)
+        /// does.
+        ///
+        /// # Errors
+        ///
+        /// Returns a deserialization error if a value is not a duration.
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<String, Duration>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Ok(HashMap::<String, Human>::deserialize(deserializer)?
+                .into_iter()
+                .map(|(key, Human(value))| (key, value))
+                .collect())
+        }
+    }
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -1083,6 +1591,8 @@ mod cwe532_debug_redaction {
         let cfg = OAuthConfig {
             enabled: true,
             scopes: vec!["read".to_string()],
+            grant_type: OAuthGrantType::AuthorizationCode,
+            accounts: Vec::new(),
             client_id: Some("client-123".to_string()),
             client_secret: Some(SENTINEL.to_string()),
             callback_host: None,
@@ -463,6 +463,404 @@ backends:
     assert!(matches!(result, Err(crate::Error::ConfigValidation(_))));
 }
 
+#[test]
+fn backend_namespace_parses_from_yaml() {
+    let yaml = r"
+backends:
+  docs:
+    command: docs-server
+    namespace: docs
+  web:
+    command: web-server
+";
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    assert_eq!(config.backends["docs"].namespace.as_deref(), Some("docs"));
+    assert!(config.backends["web"].namespace.is_none());
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn backend_tool_filter_parses_from_yaml() {
+    let yaml = r#"
+backends:
+  files:
+    command: files-server
+    tools:
+      allow: ["*_read", "list_*"]
+      deny: ["*delete*"]
+"#;
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    let filter = &config.backends["files"].tools;
+    assert_eq!(filter.allow, vec!["*_read", "list_*"]);
+    assert_eq!(filter.deny, vec!["*delete*"]);
+    assert!(BackendConfig::default().tools.is_empty());
+}
+
+#[test]
+fn backend_response_transforms_parse_from_yaml() {
+    let yaml = r#"
+backends:
+  docs:
+    command: docs-server
+    response_transforms:
+      search:
+        query: "results[*].{id: id, title: title}"
+"#;
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    let transform = &config.backends["docs"].response_transforms["search"];
+    assert_eq!(
+        transform.query.as_deref(),
+        Some("results[*].{id: id, title: title}")
+    );
+    assert!(!transform.is_empty());
+}
+
+#[test]
+fn validate_rejects_invalid_response_transform_query() {
+    let yaml = r#"
+backends:
+  docs:
+    command: docs-server
+    response_transforms:
+      search:
+        query: "results[?"
+"#;
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("response_transforms.search"), "{err}");
+}
+
+#[test]
+fn backend_argument_transforms_parse_from_yaml() {
+    let yaml = r"
+backends:
+  docs:
+    command: docs-server
+    argument_transforms:
+      search:
+        rename: { query: q }
+        defaults: { limit: 20 }
+        coerce: { limit: integer }
+";
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    let rules = &config.backends["docs"].argument_transforms["search"];
+    assert_eq!(rules.rename["query"], "q");
+    assert_eq!(rules.defaults["limit"], serde_json::json!(20));
+    assert_eq!(
+        rules.coerce["limit"],
+        crate::provider::transforms::CoerceType::Integer
+    );
+}
+
+#[test]
+fn per_client_rate_limits_parse_from_yaml() {
+    let yaml = r#"
+failsafe:
+  rate_limit:
+    limits:
+      clientA:
+        github: 30/min
+      "*":
+        "*": 5/sec
+"#;
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    let limits = &config.failsafe.rate_limit.limits;
+    assert_eq!(limits["clientA"]["github"].to_string(), "30/min");
+    assert_eq!(limits["*"]["*"].count, 5);
+    assert!(config.failsafe.rate_limit.enabled);
+}
+
+#[test]
+fn per_client_rate_limit_rejects_malformed_rate() {
+    let yaml = r"
+failsafe:
+  rate_limit:
+    limits:
+      clientA:
+        github: 30/fortnight
+";
+    let err = serde_yaml::from_str::<Config>(yaml)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("unknown period"), "{err}");
+}
+
+#[test]
+fn backend_cost_model_parses_and_charges() {
+    let yaml = r#"
+backends:
+  search:
+    http_url: "https://search.example/mcp"
+    cost:
+      per_call_usd: 0.002
+      price_per_million: 3.0
+"#;
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    let cost = &config.backends["search"].cost;
+    assert!((cost.per_call_usd - 0.002).abs() < 1e-12);
+    // 0.002 + 1000 tokens × $3/M
+    assert!((cost.charge(1_000) - 0.005).abs() < 1e-12);
+    // Default model: $15/M, no per-call fee
+    assert!((BackendCostConfig::default().charge(1_000) - 0.015).abs() < 1e-12);
+}
+
+#[test]
+fn metrics_export_parses_from_yaml() {
+    let yaml = r#"
+metrics_export:
+  enabled: true
+  protocol: statsd
+  endpoint: "statsd.internal:8125"
+  interval: 15s
+"#;
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    let export = &config.metrics_export;
+    assert!(export.enabled);
+    assert_eq!(export.protocol, MetricsExportProtocol::Statsd);
+    assert_eq!(export.effective_endpoint(), "statsd.internal:8125");
+    assert_eq!(export.interval, Duration::from_secs(15));
+    assert_eq!(export.prefix, "mcp_gateway");
+}
+
+#[test]
+fn metrics_export_defaults_to_disabled_otlp() {
+    let export = Config::default().metrics_export;
+    assert!(!export.enabled);
+    assert_eq!(export.protocol, MetricsExportProtocol::Otlp);
+    assert_eq!(
+        export.effective_endpoint(),
+        "http://localhost:4318/v1/metrics"
+    );
+}
+
+#[test]
+fn validate_rejects_zero_metrics_export_interval() {
+    let mut config = Config::default();
+    config.metrics_export.enabled = true;
+    config.metrics_export.interval = Duration::ZERO;
+
+    let err = config.validate().unwrap_err();
+
+    assert!(err.to_string().contains("metrics_export.interval"), "{err}");
+}
+
+#[test]
+fn metrics_export_debug_redacts_header_values() {
+    let mut export = Config::default().metrics_export;
+    export
+        .headers
+        .insert("x-api-key".to_string(), "collector-secret".to_string());
+
+    let debug = format!("{export:?}");
+
+    assert!(debug.contains("x-api-key"), "{debug}");
+    assert!(!debug.contains("collector-secret"), "{debug}");
+}
+
+#[test]
+fn slo_parses_objectives_and_alert_windows() {
+    let yaml = r#"
+slo:
+  enabled: true
+  webhook_url: "https://hooks.example.com/slo"
+  objectives:
+    github:
+      success_rate: 0.995
+      latency: { threshold: 800ms }
+  alerts:
+    - name: page
+      long_window: 1h
+      short_window: 5m
+      burn_rate: 14.4
+"#;
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+    config.validate().unwrap();
+
+    let slo = &config.slo;
+    assert!(slo.enabled);
+    let github = &slo.objectives["github"];
+    assert_eq!(github.success_rate, Some(0.995));
+    let latency = github.latency.as_ref().unwrap();
+    assert_eq!(latency.threshold, Duration::from_millis(800));
+    assert!((latency.target - 0.99).abs() < f64::EPSILON);
+    assert_eq!(slo.alerts.len(), 1);
+    assert_eq!(slo.alerts[0].long_window, Duration::from_secs(3600));
+    assert_eq!(slo.alerts[0].short_window, Duration::from_secs(300));
+}
+
+#[test]
+fn slo_defaults_to_fast_and_slow_burn_rules() {
+    let slo = Config::default().slo;
+    assert!(!slo.enabled);
+    let names: Vec<&str> = slo.alerts.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, vec!["fast-burn", "slow-burn"]);
+}
+
+#[test]
+fn validate_rejects_slo_target_outside_unit_interval() {
+    let mut config = Config::default();
+    config.slo.enabled = true;
+    config.slo.objectives.insert(
+        "github".to_string(),
+        SloObjectiveConfig {
+            success_rate: Some(1.5),
+            latency: None,
+        },
+    );
+
+    let msg = config.validate().unwrap_err().to_string();
+    assert!(msg.contains("slo.objectives.github.success_rate"), "{msg}");
+}
+
+#[test]
+fn validate_rejects_slo_short_window_not_shorter() {
+    let mut config = Config::default();
+    config.slo.enabled = true;
+    config.slo.alerts[0].short_window = config.slo.alerts[0].long_window;
+
+    let msg = config.validate().unwrap_err().to_string();
+    assert!(msg.contains("fast-burn"), "{msg}");
+}
+
+#[test]
+fn humantime_round_trips_millis_and_hours() {
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "humantime_serde")] Duration);
+
+    let hours: Wrapper = serde_yaml::from_str("2h").unwrap();
+    assert_eq!(hours.0, Duration::from_secs(7200));
+    let millis: Wrapper = serde_yaml::from_str("250ms").unwrap();
+    assert_eq!(millis.0, Duration::from_millis(250));
+    assert_eq!(serde_yaml::to_string(&millis).unwrap().trim(), "250ms");
+    assert_eq!(
+        serde_yaml::to_string(&Wrapper(Duration::from_secs(30)))
+            .unwrap()
+            .trim(),
+        "30s"
+    );
+}
+
+#[test]
+fn stats_history_defaults_to_one_day_at_one_minute() {
+    let history = Config::default().stats_history;
+    assert!(history.enabled);
+    assert_eq!(history.capacity(), 1440);
+}
+
+#[test]
+fn validate_rejects_unbounded_stats_history() {
+    let mut config = Config::default();
+    config.stats_history.resolution = Duration::from_secs(1);
+    config.stats_history.retention = Duration::from_secs(30 * 24 * 3600);
+
+    let msg = config.validate().unwrap_err().to_string();
+    assert!(msg.contains("stats_history"), "{msg}");
+}
+
+#[test]
+fn ranking_usage_half_life_accepts_days() {
+    let config: Config = serde_yaml::from_str("ranking:\n  usage_half_life: 14d\n").unwrap();
+    assert_eq!(
+        config.ranking.usage_half_life,
+        Duration::from_secs(14 * 86_400)
+    );
+    assert!(Config::default().ranking.usage_half_life.is_zero());
+}
+
+#[test]
+fn autotag_defaults_match_builtin_heuristics() {
+    let autotag = Config::default().autotag;
+    assert_eq!(autotag.max_tags, 7);
+    assert_eq!(autotag.min_word_length, 3);
+    assert!(autotag.rules.is_empty());
+}
+
+#[test]
+fn validate_rejects_invalid_autotag_rules() {
+    let mut config = Config::default();
+    config.autotag.max_tags = 0;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("autotag.max_tags"), "{err}");
+
+    let config: Config =
+        serde_yaml::from_str("autotag:\n  rules:\n    - pattern: \"[unclosed\"\n").unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("autotag rule pattern"), "{err}");
+}
+
+#[test]
+fn enrichment_overlay_path_expands_tilde() {
+    let config: Config =
+        serde_yaml::from_str("enrichment:\n  overlay_file: ~/enrichment.json\n").unwrap();
+    let path = config.enrichment.overlay_path().unwrap();
+    assert!(
+        !path.to_string_lossy().starts_with('~'),
+        "{}",
+        path.display()
+    );
+    assert!(Config::default().enrichment.overlay_path().is_none());
+}
+
+#[test]
+fn validate_rejects_zero_enrichment_batch_size() {
+    let mut config = Config::default();
+    config.enrichment.batch_size = 0;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("enrichment.batch_size"), "{err}");
+}
+
+#[test]
+fn ranking_personalization_defaults_on() {
+    assert!(Config::default().ranking.personalize);
+    let config: Config = serde_yaml::from_str("ranking:\n  usage_half_life: 1h\n").unwrap();
+    assert!(config.ranking.personalize);
+    let config: Config = serde_yaml::from_str("ranking:\n  personalize: false\n").unwrap();
+    assert!(!config.ranking.personalize);
+}
+
+#[test]
+fn validate_rejects_invalid_backend_namespace() {
+    let mut config = Config::default();
+    config.backends.insert(
+        "docs".to_string(),
+        BackendConfig {
+            namespace: Some("bad ns".to_string()),
+            ..BackendConfig::default()
+        },
+    );
+
+    let msg = config.validate().unwrap_err().to_string();
+    assert!(
+        msg.contains("bad ns"),
+        "error should name the namespace: {msg}"
+    );
+}
+
+#[test]
+fn validate_rejects_duplicate_backend_namespace() {
+    let mut config = Config::default();
+    for name in ["docs_a", "docs_b"] {
+        config.backends.insert(
+            name.to_string(),
+            BackendConfig {
+                namespace: Some("docs".to_string()),
+                ..BackendConfig::default()
+            },
+        );
+    }
+
+    let msg = config.validate().unwrap_err().to_string();
+    assert!(msg.contains("share namespace 'docs'"), "{msg}");
+}
+
 fn signed_remote_provenance_yaml() -> String {
     r#"
 security:
@@ -584,6 +982,8 @@ fn oauth_cfg(enabled: bool) -> OAuthConfig {
     OAuthConfig {
         enabled,
         scopes: vec![],
+        grant_type: OAuthGrantType::AuthorizationCode,
+        accounts: Vec::new(),
         client_id: None,
         client_secret: None,
         callback_host: None,
@@ -606,6 +1006,7 @@ fn validate_accepts_stateless_signed_assertion_backend() {
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }),
     );
     assert!(
@@ -628,6 +1029,7 @@ fn validate_accepts_per_user_session_mode_now_that_pool_ships() {
             session_mode: SessionMode::PerUser,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }),
     );
     assert!(
@@ -649,6 +1051,7 @@ fn validate_rejects_identity_propagation_on_non_http_transport() {
         session_mode: SessionMode::Stateless,
         token_exchange_endpoint: None,
         token_exchange_scope: None,
+        token_exchange_client: None,
     });
     backend.transport = TransportConfig::Stdio {
         command: "echo".to_string(),
@@ -676,6 +1079,7 @@ fn validate_rejects_empty_audience_backend() {
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }),
     );
     assert!(matches!(
@@ -698,6 +1102,7 @@ fn validate_rejects_required_unimplemented_strategy() {
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }),
     );
     assert!(config.validate().is_err());
@@ -718,6 +1123,7 @@ fn validate_rejects_token_exchange_without_endpoint() {
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }),
     );
     let err = config.validate().unwrap_err().to_string();
@@ -741,6 +1147,7 @@ fn validate_accepts_properly_configured_token_exchange_backend() {
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: Some("https://idp.internal/token".to_string()),
             token_exchange_scope: Some("mail.read".to_string()),
+            token_exchange_client: None,
         }),
     );
     assert!(
@@ -770,6 +1177,7 @@ fn validate_rejects_identity_propagation_with_enabled_backend_oauth() {
         session_mode: SessionMode::Stateless,
         token_exchange_endpoint: None,
         token_exchange_scope: None,
+        token_exchange_client: None,
     });
     backend.oauth = Some(oauth_cfg(true));
     config.backends.insert("mem".to_string(), backend);
@@ -794,6 +1202,7 @@ fn validate_accepts_identity_propagation_with_disabled_backend_oauth() {
         session_mode: SessionMode::Stateless,
         token_exchange_endpoint: None,
         token_exchange_scope: None,
+        token_exchange_client: None,
     });
     backend.oauth = Some(oauth_cfg(false));
     config.backends.insert("mem".to_string(), backend);
@@ -802,3 +1211,386 @@ fn validate_accepts_identity_propagation_with_disabled_backend_oauth() {
         "disabled backend oauth must not trip the F3 gate"
     );
 }
+
+#[test]
+fn validate_requires_credentials_for_client_credentials_grant() {
+    // GIVEN: a backend using the headless grant with only a client id
+    let yaml = r#"
+backends:
+  billing:
+    http_url: "https://billing.internal/mcp"
+    oauth:
+      grant_type: client_credentials
+      client_id: "{env.BILLING_CLIENT_ID}"
+"#;
+    let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        config.backends["billing"]
+            .oauth
+            .as_ref()
+            .unwrap()
+            .grant_type,
+        OAuthGrantType::ClientCredentials
+    );
+
+    // WHEN / THEN: validation asks for the secret
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("client_secret"), "{err}");
+    assert!(err.contains("billing"), "{err}");
+
+    // AND: with the secret reference it validates
+    config
+        .backends
+        .get_mut("billing")
+        .unwrap()
+        .oauth
+        .as_mut()
+        .unwrap()
+        .client_secret = Some("{keychain.billing-oauth}".to_string());
+    config.validate().unwrap();
+}
+
+#[test]
+fn validate_rejects_ambiguous_oauth_accounts() {
+    // GIVEN: two accounts claiming the same caller
+    let yaml = r#"
+backends:
+  crm:
+    http_url: "https://crm.internal/mcp"
+    oauth:
+      accounts:
+        - name: alice
+          identities: [alice@corp]
+        - name: sales
+          identities: [alice@corp, bob@corp]
+"#;
+    let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    // WHEN / THEN: validation names the shared identity
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("alice@corp"), "{err}");
+
+    // AND: distinct identities validate, bad names do not
+    let oauth = config
+        .backends
+        .get_mut("crm")
+        .unwrap()
+        .oauth
+        .as_mut()
+        .unwrap();
+    oauth.accounts[1].identities = vec!["bob@corp".to_string()];
+    config.validate().unwrap();
+    config
+        .backends
+        .get_mut("crm")
+        .unwrap()
+        .oauth
+        .as_mut()
+        .unwrap()
+        .accounts[1]
+        .name = "sales team".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn prediction_defaults_keep_previous_thresholds() {
+    let prediction = Config::default().prediction;
+    assert!((prediction.min_confidence - 0.30).abs() < f64::EPSILON);
+    assert_eq!(prediction.min_count, 3);
+    assert!(prediction.warm_up);
+}
+
+#[test]
+fn validate_rejects_out_of_range_prediction_confidence() {
+    let mut config = Config::default();
+    config.prediction.min_confidence = 1.5;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("prediction.min_confidence"), "{err}");
+}
+
+#[test]
+fn idempotency_store_dir_expands_tilde() {
+    let config: Config =
+        serde_yaml::from_str("idempotency:\n  enabled: true\n  store_dir: ~/idem\n").unwrap();
+    assert!(config.idempotency.enabled);
+    let path = config.idempotency.store_path().unwrap();
+    assert!(path.ends_with("idem") && !path.starts_with("~"), "{path:?}");
+}
+
+#[test]
+fn validate_rejects_zero_idempotency_ttl() {
+    let config: Config = serde_yaml::from_str("idempotency:\n  completed_ttl: 0s\n").unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("idempotency.completed_ttl"), "{err}");
+}
+
+#[test]
+fn disabled_list_resolves_tools_and_capabilities() {
+    let config: Config = serde_yaml::from_str(
+        "disabled:\n  servers: [legacy]\n  tools: [\"github:delete_repo\"]\n  capabilities: [weather]\n",
+    )
+    .unwrap();
+    config.validate().unwrap();
+    assert_eq!(config.disabled.servers, ["legacy"]);
+    assert_eq!(
+        config.disabled.tool_targets("gateway"),
+        [
+            ("github".to_string(), "delete_repo".to_string()),
+            ("gateway".to_string(), "weather".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn validate_rejects_disabled_tool_without_server() {
+    let config: Config = serde_yaml::from_str("disabled:\n  tools: [delete_repo]\n").unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("disabled.tools"), "{err}");
+}
+
+#[test]
+fn alerts_parse_sinks_and_reject_non_http_urls() {
+    let config: Config = serde_yaml::from_str(
+        "alerts:\n  slack_webhook_url: https://hooks.slack.com/services/T/B/X\n  mcp_notifications: true\n",
+    )
+    .unwrap();
+    assert!(config.alerts.has_sinks());
+    assert!(config.validate().is_ok());
+    assert!(!Config::default().alerts.has_sinks());
+
+    let config: Config =
+        serde_yaml::from_str("alerts:\n  webhook_url: hooks.example.com/x\n").unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("alerts.webhook_url"), "{err}");
+}
+
+#[test]
+fn alert_rules_parse_and_reject_mismatched_thresholds() {
+    let mut config: Config = serde_yaml::from_str(
+        "alerts:\n  rules:\n    - name: gh-errors\n      backend: github\n      metric: error_rate\n      above: 0.2\n      for: 5m\n",
+    )
+    .unwrap();
+    let rule = &config.alerts.rules[0];
+    assert_eq!(rule.metric, AlertMetric::ErrorRate);
+    assert_eq!(rule.for_duration, Duration::from_secs(300));
+    assert!(config.alerts.is_enabled());
+    assert!(!config.alerts.has_sinks());
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("unknown backend 'github'"), "{err}");
+
+    let github: BackendConfig =
+        serde_yaml::from_str("http_url: https://api.githubcopilot.com/mcp\n").unwrap();
+    config.backends.insert("github".to_string(), github);
+    assert!(config.validate().is_ok());
+
+    config.alerts.rules[0].below = Some(0.1);
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("exactly one of above or below"), "{err}");
+
+    config.alerts.rules[0].below = None;
+    config.alerts.rules[0].above = Some(20.0);
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("between 0 and 1"), "{err}");
+}
+
+#[test]
+fn registry_sources_parse_and_reject_duplicate_names() {
+    let config: Config = serde_yaml::from_str(
+        "registry:\n  sources:\n    - name: internal\n      url: https://caps.example.com\n      priority: 10\n      auth:\n        type: bearer\n        token_env: CAPS_TOKEN\n",
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    assert_eq!(config.registry.sources[0].priority, 10);
+    assert_eq!(
+        config.registry.sources[0].auth,
+        RegistryAuthConfig::Bearer {
+            token_env: "CAPS_TOKEN".to_string()
+        }
+    );
+
+    let config: Config = serde_yaml::from_str(
+        "registry:\n  sources:\n    - { name: a, url: https://a.example.com }\n    - { name: a, url: https://b.example.com }\n",
+    )
+    .unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("defined twice"), "{err}");
+}
+
+#[test]
+fn registry_trust_policy_needs_keys_for_allowed_publishers() {
+    let key = "A".repeat(43) + "=";
+    let config: Config = serde_yaml::from_str(&format!(
+        "registry:\n  sources:\n    - name: community\n      url: https://r.example.org\n      public_keys:\n        acme: \"{key}\"\n      trust:\n        require_signed: true\n        allowed_publishers: [acme]\n"
+    ))
+    .unwrap();
+    assert!(config.validate().is_ok());
+    assert!(config.registry.sources[0].trust.require_signed);
+
+    let config: Config = serde_yaml::from_str(
+        "registry:\n  sources:\n    - name: community\n      url: https://r.example.org\n      trust:\n        require_signed: true\n        allowed_publishers: [acme]\n",
+    )
+    .unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("no public key for it"), "{err}");
+
+    let config: Config = serde_yaml::from_str(
+        "registry:\n  sources:\n    - name: community\n      url: https://r.example.org\n      public_keys:\n        acme: not-a-key\n",
+    )
+    .unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("base64 Ed25519"), "{err}");
+}
+
+#[test]
+fn validate_vault_login_settings() {
+    // GIVEN: a Vault section whose AppRole secret comes from Vault itself
+    let yaml = r#"
+vault:
+  address: "https://vault.internal:8200"
+  auth:
+    method: approle
+    role_id: "{env.VAULT_ROLE_ID}"
+    secret_id: "{vault.secret/gateway#secret_id}"
+"#;
+    let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    // WHEN / THEN: validation refuses the circular reference
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("vault.auth.secret_id"), "{err}");
+
+    // AND: an env-sourced secret validates, and defaults apply
+    let vault = config.vault.as_mut().unwrap();
+    if let VaultAuthConfig::Approle {
+        secret_id, mount, ..
+    } = &mut vault.auth
+    {
+        assert_eq!(mount, "approle");
+        *secret_id = "{env.VAULT_SECRET_ID}".to_string();
+    }
+    assert_eq!(vault.cache_ttl, Duration::from_secs(300));
+    config.validate().unwrap();
+
+    // AND: a non-http address is rejected
+    config.vault.as_mut().unwrap().address = "vault.internal:8200".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn validate_aws_secrets_settings() {
+    // GIVEN: a config without an `aws_secrets` section
+    let mut config: Config = serde_yaml::from_str("backends: {}").unwrap();
+
+    // THEN: defaults apply and validate
+    assert_eq!(config.aws_secrets.region, None);
+    assert_eq!(config.aws_secrets.cache_ttl, Duration::from_secs(300));
+    config.validate().unwrap();
+
+    // WHEN: the section sets a region and cache TTL
+    let yaml = r"
+aws_secrets:
+  region: eu-west-1
+  cache_ttl: 10m
+";
+    config = serde_yaml::from_str(yaml).unwrap();
+
+    // THEN: they are parsed
+    assert_eq!(config.aws_secrets.region.as_deref(), Some("eu-west-1"));
+    assert_eq!(config.aws_secrets.cache_ttl, Duration::from_secs(600));
+    config.validate().unwrap();
+
+    // AND: a non-http endpoint is rejected
+    config.aws_secrets.endpoint = Some("localhost:4566".to_string());
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("aws_secrets.endpoint"), "{err}");
+}
+
+#[test]
+fn validate_password_manager_logins() {
+    // GIVEN: password manager sections with CLI settings
+    let yaml = r#"
+onepassword:
+  account: my-team.1password.com
+  service_account_token: "{keychain.op-service-account}"
+bitwarden:
+  binary: /usr/local/bin/bw
+  session: "{op.Private/Bitwarden/session}"
+"#;
+    let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(config.onepassword.binary, std::path::PathBuf::from("op"));
+    assert_eq!(
+        config.bitwarden.binary,
+        std::path::PathBuf::from("/usr/local/bin/bw")
+    );
+
+    // WHEN / THEN: a session read from another password manager is refused
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("bitwarden.session"), "{err}");
+
+    // AND: an environment-sourced session validates
+    config.bitwarden.session = Some("{env.BW_SESSION}".to_string());
+    config.validate().unwrap();
+
+    // AND: the secrets are redacted from debug output
+    let debug = format!("{:?}", config.onepassword);
+    assert!(!debug.contains("op-service-account"), "{debug}");
+}
+
+#[test]
+fn validate_secrets_file_key() {
+    // GIVEN: a secrets file whose key is stored in the file itself
+    let yaml = r#"
+secrets_file:
+  path: secrets.enc.yaml
+  key: "{secret.age_key}"
+"#;
+    let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    // WHEN / THEN: validation refuses the circular reference
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("secrets_file.key"), "{err}");
+
+    // AND: a keychain-held key validates, with default CLI names
+    let secrets_file = config.secrets_file.as_mut().unwrap();
+    secrets_file.key = Some("{keychain.mcp-gateway-age-key}".to_string());
+    assert_eq!(secrets_file.sops_binary, std::path::PathBuf::from("sops"));
+    config.validate().unwrap();
+}
+
+#[test]
+fn backend_tool_timeouts_parse_and_override() {
+    // GIVEN: a backend with a per-tool timeout override
+    let backend: BackendConfig =
+        serde_yaml::from_str("command: docs-server\ntimeout: 30s\ntimeouts:\n  render_pdf: 5m\n")
+            .unwrap();
+
+    // THEN: the listed tool gets its own timeout, others the backend's
+    assert_eq!(backend.tool_timeout("render_pdf"), Duration::from_secs(300));
+    assert_eq!(backend.tool_timeout("search"), Duration::from_secs(30));
+    assert_eq!(backend.max_timeout(), Duration::from_secs(300));
+
+    // AND: it round-trips in human-readable form
+    let yaml = serde_yaml::to_string(&backend).unwrap();
+    assert!(yaml.contains("render_pdf: 300s"), "{yaml}");
+}
+
+#[test]
+fn backend_forward_identity_parses_and_rejects_bad_header_names() {
+    // GIVEN: a backend forwarding the client name as a header and in _meta
+    let backend: BackendConfig = serde_yaml::from_str(
+        "http_url: https://crm.internal/mcp\nforward_identity:\n  headers:\n    client: X-Gateway-Client\n  meta: true\n",
+    )
+    .unwrap();
+    let forward = backend.forward_identity.clone().unwrap();
+    assert_eq!(forward.headers.client.as_deref(), Some("X-Gateway-Client"));
+    assert!(forward.meta);
+
+    // WHEN: a header name is not a valid HTTP token
+    let mut config = Config::default();
+    let mut bad = backend;
+    bad.forward_identity.as_mut().unwrap().headers.email = Some("X Bad".to_string());
+    config.backends.insert("crm".to_string(), bad);
+
+    // THEN: validation names the backend and the header
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("crm") && err.contains("X Bad"), "{err}");
+}
@@ -186,17 +186,27 @@ impl ReloadOutcome {
 /// pointer-width CAS.
 pub struct LiveConfig {
     inner: RwLock<Arc<Config>>,
+    /// Notifies subscribers of every swap.
+    updates: tokio::sync::watch::Sender<Arc<Config>>,
 }
 
 impl LiveConfig {
     /// Create a new `LiveConfig` seeded with the startup configuration.
     #[must_use]
     pub fn new(config: Config) -> Self {
+        let config = Arc::new(config);
         Self {
-            inner: RwLock::new(Arc::new(config)),
+            inner: RwLock::new(Arc::clone(&config)),
+            updates: tokio::sync::watch::Sender::new(config),
         }
     }
 
+    /// Subscribe to config swaps; the receiver yields each new snapshot.
+    #[must_use]
+    pub fn subscribe(&self) -> tokio::sync::watch::Receiver<Arc<Config>> {
+        self.updates.subscribe()
+    }
+
     /// Clone the current active configuration snapshot.
     #[must_use]
     pub fn get(&self) -> Arc<Config> {
@@ -205,7 +215,9 @@ impl LiveConfig {
 
     /// Atomically replace the current config.
     pub fn set(&self, config: Config) {
-        *self.inner.write() = Arc::new(config);
+        let config = Arc::new(config);
+        *self.inner.write() = Arc::clone(&config);
+        self.updates.send_replace(config);
     }
 }
 
@@ -333,6 +345,8 @@ struct MetaFields {
     /// `server_address_changed`. Tracked here so a public-url-only edit is not
     /// silently ignored until the next restart.
     server_public_url: String,
+    /// Declarative kill list, re-applied to the kill switch on every reload.
+    disabled: String,
     #[cfg(feature = "cost-governance")]
     cost_governance: String,
 }
@@ -359,6 +373,7 @@ impl MetaFields {
             marketplace: canonical_json(&c.marketplace),
             control_plane: canonical_json(&c.control_plane),
             server_public_url: c.server.public_url.clone().unwrap_or_default(),
+            disabled: canonical_json(&c.disabled),
             #[cfg(feature = "cost-governance")]
             cost_governance: canonical_json(&c.cost_governance),
         }
@@ -515,7 +530,7 @@ enum ReloadTrigger {
 ///
 /// Returns the path unchanged if it does not start with `~` or if the home
 /// directory cannot be determined.
-fn expand_tilde(path_str: &str) -> PathBuf {
+pub(crate) fn expand_tilde(path_str: &str) -> PathBuf {
     if path_str.starts_with('~')
         && let Some(home) = dirs::home_dir()
     {
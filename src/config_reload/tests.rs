@@ -768,3 +768,25 @@ fn control_plane_role_mapping_change_is_detected() {
         "control_plane change should set profiles_changed"
     );
 }
+
+#[test]
+fn diff_detects_disabled_list_change() {
+    let old = Config::default();
+    let mut new = Config::default();
+    new.disabled.servers.push("legacy".to_string());
+    assert!(compute_diff(&old, &new).profiles_changed);
+}
+
+#[test]
+fn live_config_set_notifies_subscribers() {
+    let live = LiveConfig::new(Config::default());
+    let mut updates = live.subscribe();
+    assert!(!updates.has_changed().unwrap());
+
+    let mut next = Config::default();
+    next.disabled.servers.push("legacy".to_string());
+    live.set(next);
+
+    assert!(updates.has_changed().unwrap());
+    assert_eq!(updates.borrow_and_update().disabled.servers, ["legacy"]);
+}
@@ -169,6 +169,7 @@ mod tests {
             name: None,
             groups: groups.iter().map(|g| (*g).to_string()).collect(),
             issuer: issuer.to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
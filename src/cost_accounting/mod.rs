@@ -17,6 +17,7 @@
 //!
 //! `record()` is the single write path; everything else is read-only.
 
+use std::fmt::Write as _;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -32,6 +33,9 @@ const DEFAULT_WARNING_FRACTION: f64 = 0.80;
 /// Default price per million tokens (Claude Opus 4.6 input).
 pub const DEFAULT_PRICE_PER_MILLION: f64 = 15.0;
 
+/// Client label used in attribution for calls without an API-key identity.
+pub const ANONYMOUS_CLIENT: &str = "anonymous";
+
 // ── CostRecord ────────────────────────────────────────────────────────────────
 
 /// A single recorded tool-call cost event.
@@ -63,6 +67,27 @@ impl CostRecord {
             estimated_cost_usd,
         }
     }
+
+    /// Create a `CostRecord` with an explicitly computed cost (e.g. from a
+    /// per-backend cost model).
+    #[must_use]
+    pub fn with_cost(backend: &str, tool: &str, token_count: u64, estimated_cost_usd: f64) -> Self {
+        Self {
+            timestamp: now_secs(),
+            backend: backend.to_string(),
+            tool: tool.to_string(),
+            token_count,
+            estimated_cost_usd,
+        }
+    }
+}
+
+/// Rough token estimate for a JSON payload (~4 bytes of serialized JSON per
+/// token).
+#[must_use]
+pub fn estimate_tokens(value: &serde_json::Value) -> u64 {
+    let len = serde_json::to_string(value).map_or(0, |s| s.len());
+    (len as u64).div_ceil(4)
 }
 
 // ── Budget limits ─────────────────────────────────────────────────────────────
@@ -490,7 +515,25 @@ impl CostTracker {
         price_per_million: f64,
     ) {
         let rec = CostRecord::new(backend, tool, token_count, price_per_million);
+        self.record_event(session_id, api_key_name, rec);
+    }
+
+    /// Record a tool-call event whose cost was already computed (e.g. from a
+    /// per-backend cost model).
+    pub fn record_with_cost(
+        &self,
+        session_id: &str,
+        api_key_name: Option<&str>,
+        backend: &str,
+        tool: &str,
+        token_count: u64,
+        cost_usd: f64,
+    ) {
+        let rec = CostRecord::with_cost(backend, tool, token_count, cost_usd);
+        self.record_event(session_id, api_key_name, rec);
+    }
 
+    fn record_event(&self, session_id: &str, api_key_name: Option<&str>, rec: CostRecord) {
         // Per-session
         self.per_session
             .entry(session_id.to_string())
@@ -545,6 +588,136 @@ impl CostTracker {
         self.per_key.iter().map(|e| e.value().snapshot()).collect()
     }
 
+    /// Per-(client, session, backend, tool) spend, sorted by client, then
+    /// session, backend and tool.
+    #[must_use]
+    pub fn attribution_rows(&self) -> Vec<AttributionRow> {
+        let mut rows: Vec<AttributionRow> = Vec::new();
+        for entry in &self.per_session {
+            let session = entry.value();
+            let client = session
+                .api_key_name
+                .clone()
+                .unwrap_or_else(|| ANONYMOUS_CLIENT.to_string());
+            let mut by_tool: std::collections::BTreeMap<(String, String), AttributionRow> =
+                std::collections::BTreeMap::new();
+            for r in session.records.lock().iter() {
+                let row = by_tool
+                    .entry((r.backend.clone(), r.tool.clone()))
+                    .or_insert_with(|| AttributionRow {
+                        client: client.clone(),
+                        session_id: session.session_id.clone(),
+                        backend: r.backend.clone(),
+                        tool: r.tool.clone(),
+                        call_count: 0,
+                        token_count: 0,
+                        cost_usd: 0.0,
+                    });
+                row.call_count += 1;
+                row.token_count += r.token_count;
+                row.cost_usd += r.estimated_cost_usd;
+            }
+            rows.extend(by_tool.into_values());
+        }
+        rows.sort_by(|a, b| {
+            (&a.client, &a.session_id, &a.backend, &a.tool).cmp(&(
+                &b.client,
+                &b.session_id,
+                &b.backend,
+                &b.tool,
+            ))
+        });
+        rows
+    }
+
+    /// Spend rolled up per client and per session, highest cost first.
+    #[must_use]
+    pub fn attribution(&self) -> CostAttribution {
+        Self::roll_up(self.attribution_rows())
+    }
+
+    /// [`attribution`](Self::attribution) limited to the sessions of one
+    /// client (an API key name, or [`ANONYMOUS_CLIENT`]).
+    #[must_use]
+    pub fn client_attribution(&self, client: &str) -> CostAttribution {
+        let mut rows = self.attribution_rows();
+        rows.retain(|row| row.client == client);
+        Self::roll_up(rows)
+    }
+
+    fn roll_up(rows: Vec<AttributionRow>) -> CostAttribution {
+        let mut by_client: std::collections::HashMap<String, ClientCost> =
+            std::collections::HashMap::new();
+        let mut by_session: std::collections::HashMap<String, SessionCostSummary> =
+            std::collections::HashMap::new();
+        for row in rows {
+            let session =
+                by_session
+                    .entry(row.session_id.clone())
+                    .or_insert_with(|| SessionCostSummary {
+                        session_id: row.session_id.clone(),
+                        client: row.client.clone(),
+                        call_count: 0,
+                        token_count: 0,
+                        cost_usd: 0.0,
+                    });
+            if session.call_count == 0 {
+                by_client
+                    .entry(row.client.clone())
+                    .or_insert_with(|| ClientCost::new(&row.client))
+                    .session_count += 1;
+            }
+            session.call_count += row.call_count;
+            session.token_count += row.token_count;
+            session.cost_usd += row.cost_usd;
+
+            let client = by_client
+                .entry(row.client.clone())
+                .or_insert_with(|| ClientCost::new(&row.client));
+            client.call_count += row.call_count;
+            client.token_count += row.token_count;
+            client.cost_usd += row.cost_usd;
+        }
+
+        let mut by_client: Vec<ClientCost> = by_client.into_values().collect();
+        by_client.sort_by(|a, b| {
+            b.cost_usd
+                .total_cmp(&a.cost_usd)
+                .then_with(|| a.client.cmp(&b.client))
+        });
+        let mut by_session: Vec<SessionCostSummary> = by_session.into_values().collect();
+        by_session.sort_by(|a, b| {
+            b.cost_usd
+                .total_cmp(&a.cost_usd)
+                .then_with(|| a.session_id.cmp(&b.session_id))
+        });
+        CostAttribution {
+            by_client,
+            by_session,
+        }
+    }
+
+    /// Render [`attribution_rows`](Self::attribution_rows) as CSV with a
+    /// header row, for chargeback spreadsheets.
+    #[must_use]
+    pub fn attribution_csv(&self) -> String {
+        let mut csv = String::from("client,session_id,backend,tool,calls,tokens,cost_usd\n");
+        for row in self.attribution_rows() {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{:.6}",
+                csv_field(&row.client),
+                csv_field(&row.session_id),
+                csv_field(&row.backend),
+                csv_field(&row.tool),
+                row.call_count,
+                row.token_count,
+                row.cost_usd
+            );
+        }
+        csv
+    }
+
     /// Aggregate total across all sessions.
     #[must_use]
     pub fn aggregate(&self) -> AggregateCost {
@@ -602,8 +775,95 @@ pub struct AggregateCost {
     pub total_cost_usd: f64,
 }
 
+// ── Attribution types ─────────────────────────────────────────────────────────
+
+/// Spend for one (client, session, backend, tool) combination.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributionRow {
+    /// API key name, or [`ANONYMOUS_CLIENT`].
+    pub client: String,
+    /// Session ID.
+    pub session_id: String,
+    /// Backend name.
+    pub backend: String,
+    /// Tool name.
+    pub tool: String,
+    /// Number of calls.
+    pub call_count: u64,
+    /// Estimated tokens.
+    pub token_count: u64,
+    /// Estimated cost in USD.
+    pub cost_usd: f64,
+}
+
+/// Spend rolled up per client and per session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostAttribution {
+    /// Per-client totals, highest cost first.
+    pub by_client: Vec<ClientCost>,
+    /// Per-session totals, highest cost first.
+    pub by_session: Vec<SessionCostSummary>,
+}
+
+/// Spend attributed to one client identity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientCost {
+    /// API key name, or [`ANONYMOUS_CLIENT`].
+    pub client: String,
+    /// Sessions that recorded spend.
+    pub session_count: u64,
+    /// Number of calls.
+    pub call_count: u64,
+    /// Estimated tokens.
+    pub token_count: u64,
+    /// Estimated cost in USD.
+    pub cost_usd: f64,
+}
+
+impl ClientCost {
+    fn new(client: &str) -> Self {
+        Self {
+            client: client.to_string(),
+            session_count: 0,
+            call_count: 0,
+            token_count: 0,
+            cost_usd: 0.0,
+        }
+    }
+}
+
+/// Spend attributed to one session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCostSummary {
+    /// Session ID.
+    pub session_id: String,
+    /// API key name, or [`ANONYMOUS_CLIENT`].
+    pub client: String,
+    /// Number of calls.
+    pub call_count: u64,
+    /// Estimated tokens.
+    pub token_count: u64,
+    /// Estimated cost in USD.
+    pub cost_usd: f64,
+}
+
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
+/// Quote a CSV field when needed, and defuse spreadsheet formula injection
+/// (client and tool names are caller-controlled).
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
 fn now_secs() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
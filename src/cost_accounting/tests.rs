@@ -231,3 +231,95 @@ fn aggregate_cost_is_zero_on_empty_tracker() {
     assert_eq!(agg.total_calls, 0);
     assert!(agg.total_cost_usd.abs() < 1e-12);
 }
+
+// ── Attribution ───────────────────────────────────────────────────
+
+#[test]
+fn estimate_tokens_uses_four_bytes_per_token() {
+    // `{"q":"abcd"}` is 12 bytes → 3 tokens
+    assert_eq!(estimate_tokens(&serde_json::json!({"q": "abcd"})), 3);
+    assert_eq!(estimate_tokens(&serde_json::json!("ab")), 1);
+}
+
+#[test]
+fn record_with_cost_keeps_explicit_cost() {
+    let tracker = CostTracker::new();
+    tracker.record_with_cost("s1", Some("team-a"), "srv", "t", 100, 0.25);
+    let snap = tracker.session_snapshot("s1").unwrap();
+    assert_eq!(snap.total_tokens, 100);
+    assert!((snap.total_cost_usd - 0.25).abs() < 1e-9);
+    assert!((tracker.key_snapshot("team-a").unwrap().by_tool[0].cost_usd - 0.25).abs() < 1e-9);
+}
+
+#[test]
+fn attribution_rolls_up_by_client_and_session() {
+    // GIVEN: team-a spends in two sessions, team-b in one, plus an anonymous session
+    let tracker = CostTracker::new();
+    tracker.record_with_cost("s1", Some("team-a"), "github", "search", 10, 0.10);
+    tracker.record_with_cost("s1", Some("team-a"), "github", "search", 10, 0.10);
+    tracker.record_with_cost("s2", Some("team-a"), "jira", "create", 5, 0.05);
+    tracker.record_with_cost("s3", Some("team-b"), "github", "search", 50, 1.00);
+    tracker.record_with_cost("s4", None, "github", "search", 1, 0.01);
+
+    // WHEN
+    let attribution = tracker.attribution();
+
+    // THEN: clients are ordered by spend, with session counts
+    let clients: Vec<&str> = attribution
+        .by_client
+        .iter()
+        .map(|c| c.client.as_str())
+        .collect();
+    assert_eq!(clients, vec!["team-b", "team-a", ANONYMOUS_CLIENT]);
+    let team_a = &attribution.by_client[1];
+    assert_eq!(team_a.session_count, 2);
+    assert_eq!(team_a.call_count, 3);
+    assert_eq!(team_a.token_count, 25);
+    assert!((team_a.cost_usd - 0.25).abs() < 1e-9);
+
+    assert_eq!(attribution.by_session[0].session_id, "s3");
+    assert_eq!(attribution.by_session.len(), 4);
+}
+
+#[test]
+fn attribution_rows_group_by_backend_and_tool() {
+    let tracker = CostTracker::new();
+    tracker.record_with_cost("s1", Some("team-a"), "github", "search", 10, 0.10);
+    tracker.record_with_cost("s1", Some("team-a"), "github", "search", 10, 0.10);
+    tracker.record_with_cost("s1", Some("team-a"), "github", "issue", 3, 0.03);
+
+    let rows = tracker.attribution_rows();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].tool, "issue");
+    assert_eq!(rows[1].tool, "search");
+    assert_eq!(rows[1].call_count, 2);
+}
+
+#[test]
+fn attribution_csv_has_header_and_rows() {
+    let tracker = CostTracker::new();
+    tracker.record_with_cost("s1", Some("team-a"), "github", "search", 10, 0.1);
+
+    let csv = tracker.attribution_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "client,session_id,backend,tool,calls,tokens,cost_usd",
+            "team-a,s1,github,search,1,10,0.100000",
+        ]
+    );
+}
+
+#[test]
+fn attribution_csv_quotes_and_defuses_formulas() {
+    let tracker = CostTracker::new();
+    tracker.record_with_cost("s,1", Some("=HYPERLINK(\"x\")"), "srv", "t", 1, 0.0);
+
+    let csv = tracker.attribution_csv();
+    let row = csv.lines().nth(1).unwrap();
+    assert!(
+        row.starts_with("\"'=HYPERLINK(\"\"x\"\")\",\"s,1\",srv,t,"),
+        "{row}"
+    );
+}
@@ -236,6 +236,8 @@ impl ConfigScanner {
                         port: None,
                         command: None,
                         working_dir: None,
+                        expires_at: None,
+                        probe: None,
                     },
                 });
             }
@@ -347,6 +349,8 @@ impl ConfigScanner {
                     port: None,
                     command: Some(full_command),
                     working_dir,
+                    expires_at: None,
+                    probe: None,
                 },
             });
         }
@@ -368,6 +372,8 @@ impl ConfigScanner {
                     port: Self::extract_port_from_url(url),
                     command: None,
                     working_dir: None,
+                    expires_at: None,
+                    probe: None,
                 },
             });
         }
@@ -531,6 +537,8 @@ impl ConfigScanner {
                 port: None,
                 command: Some(full_command),
                 working_dir: None,
+                expires_at: None,
+                probe: None,
             },
         })
     }
@@ -0,0 +1,625 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Public MCP server directories
+//!
+//! Searches the directories that list MCP servers people can install:
+//!
+//! - the official MCP registry (`registry.modelcontextprotocol.io`),
+//! - Smithery (`registry.smithery.ai`, needs `SMITHERY_API_KEY`),
+//! - `PulseMCP` (`api.pulsemcp.com`).
+//!
+//! Each hit is normalised into a [`DirectoryServer`] carrying the install
+//! metadata the gateway needs: a remote URL when the server is hosted, or
+//! the stdio command that runs its package (`npx`, `uvx` or `docker`), plus
+//! the environment variables it expects. [`DirectoryServer::to_backend_config`]
+//! turns a hit into a backend entry whose env values are `${VAR}`
+//! placeholders, so no secret is ever written to the config.
+//!
+//! Directories that cannot be queried are reported alongside the results
+//! instead of failing the whole search.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::backend_name;
+use crate::config::{BackendConfig, TransportConfig};
+use crate::{Error, Result};
+
+/// Environment variable holding the Smithery registry API key.
+pub const SMITHERY_API_KEY_ENV: &str = "SMITHERY_API_KEY";
+
+/// A public MCP server directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DirectoryKind {
+    /// The official MCP registry
+    Official,
+    /// Smithery (requires `SMITHERY_API_KEY`)
+    Smithery,
+    /// `PulseMCP`
+    Pulsemcp,
+}
+
+impl DirectoryKind {
+    /// Every directory, in the order results are listed.
+    pub const ALL: [Self; 3] = [Self::Official, Self::Smithery, Self::Pulsemcp];
+
+    /// Display name.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Official => "official",
+            Self::Smithery => "smithery",
+            Self::Pulsemcp => "pulsemcp",
+        }
+    }
+
+    /// Public API base URL.
+    #[must_use]
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            Self::Official => "https://registry.modelcontextprotocol.io",
+            Self::Smithery => "https://registry.smithery.ai",
+            Self::Pulsemcp => "https://api.pulsemcp.com",
+        }
+    }
+}
+
+/// An environment variable a directory server expects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DirectoryEnvVar {
+    /// Variable name.
+    pub name: String,
+    /// Whether the server refuses to start without it.
+    pub required: bool,
+    /// Whether the value is a secret (API key, token).
+    pub secret: bool,
+    /// What the variable is for.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+/// A server listed in a public directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryServer {
+    /// Directory that lists it.
+    pub directory: DirectoryKind,
+    /// Identifier within the directory (e.g. `io.github.owner/server`).
+    pub id: String,
+    /// Short description.
+    pub description: String,
+    /// Latest published version, when the directory records one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Homepage or source repository.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    /// How to reach the server: a remote URL or a stdio command. `None`
+    /// when the directory lists no package type the gateway can run.
+    pub transport: Option<TransportConfig>,
+    /// Environment variables the server expects.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<DirectoryEnvVar>,
+}
+
+impl DirectoryServer {
+    /// Suggested backend name: the last segment of [`Self::id`].
+    #[must_use]
+    pub fn backend_name(&self) -> String {
+        backend_name(self.id.rsplit('/').next().unwrap_or(&self.id))
+    }
+
+    /// Whether `name` selects this server, by id or backend name.
+    #[must_use]
+    pub fn matches(&self, name: &str) -> bool {
+        self.id == name || self.backend_name() == name
+    }
+
+    /// Backend entry for this server, with every expected env var set to a
+    /// `${VAR}` placeholder. `None` when there is no usable transport.
+    #[must_use]
+    pub fn to_backend_config(&self) -> Option<BackendConfig> {
+        let transport = self.transport.clone()?;
+        let env: HashMap<String, String> = self
+            .env
+            .iter()
+            .map(|var| (var.name.clone(), format!("${{{}}}", var.name)))
+            .collect();
+        Some(BackendConfig {
+            description: self.description.clone(),
+            enabled: true,
+            transport,
+            env,
+            ..Default::default()
+        })
+    }
+}
+
+/// Result of searching several directories.
+#[derive(Debug, Clone, Default)]
+pub struct DirectorySearch {
+    /// Matches, grouped by directory in query order.
+    pub servers: Vec<DirectoryServer>,
+    /// Directories that could not be queried, with the reason.
+    pub unavailable: Vec<(DirectoryKind, String)>,
+}
+
+/// Client for the public MCP server directories.
+pub struct DirectoryClient {
+    client: reqwest::Client,
+    base_urls: HashMap<DirectoryKind, String>,
+    smithery_api_key: Option<String>,
+}
+
+impl DirectoryClient {
+    /// Create a client for the public directories, reading the Smithery
+    /// API key from [`SMITHERY_API_KEY_ENV`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be constructed.
+    pub fn new() -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("mcp-gateway/", env!("CARGO_PKG_VERSION")))
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .map_err(|e| Error::Config(format!("failed to build HTTP client: {e}")))?;
+        Ok(Self {
+            client,
+            base_urls: HashMap::new(),
+            smithery_api_key: std::env::var(SMITHERY_API_KEY_ENV)
+                .ok()
+                .filter(|key| !key.is_empty()),
+        })
+    }
+
+    /// Query `kind` at `base_url` instead of its public API.
+    #[must_use]
+    pub fn with_base_url(mut self, kind: DirectoryKind, base_url: impl Into<String>) -> Self {
+        self.base_urls.insert(kind, base_url.into());
+        self
+    }
+
+    /// Use `key` for Smithery instead of [`SMITHERY_API_KEY_ENV`].
+    #[must_use]
+    pub fn with_smithery_api_key(mut self, key: Option<String>) -> Self {
+        self.smithery_api_key = key;
+        self
+    }
+
+    fn base_url(&self, kind: DirectoryKind) -> &str {
+        self.base_urls
+            .get(&kind)
+            .map_or(kind.default_base_url(), String::as_str)
+            .trim_end_matches('/')
+    }
+
+    /// Search `kind` for `query`, returning at most `limit` servers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory is unreachable, rejects the
+    /// request or answers with something other than a server list.
+    pub async fn search(
+        &self,
+        kind: DirectoryKind,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<DirectoryServer>> {
+        let base = self.base_url(kind);
+        let page = limit.to_string();
+        let request = match kind {
+            DirectoryKind::Official => self.client.get(format!("{base}/v0/servers")).query(&[
+                ("search", query),
+                ("version", "latest"),
+                ("limit", &page),
+            ]),
+            DirectoryKind::Smithery => {
+                let Some(key) = &self.smithery_api_key else {
+                    return Err(Error::Config(format!(
+                        "set {SMITHERY_API_KEY_ENV} to search Smithery"
+                    )));
+                };
+                self.client
+                    .get(format!("{base}/servers"))
+                    .bearer_auth(key)
+                    .query(&[("q", query), ("pageSize", &page)])
+            }
+            DirectoryKind::Pulsemcp => self
+                .client
+                .get(format!("{base}/v0beta/servers"))
+                .query(&[("query", query), ("count_per_page", &page)]),
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::Transport(format!("{} unreachable: {e}", kind.label())))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::Transport(format!(
+                "{} answered HTTP {status}",
+                kind.label()
+            )));
+        }
+        let body: Value = response.json().await.map_err(|e| {
+            Error::Protocol(format!("{} sent an invalid response: {e}", kind.label()))
+        })?;
+        let mut servers = match kind {
+            DirectoryKind::Official => parse_official(&body),
+            DirectoryKind::Smithery => parse_smithery(&body),
+            DirectoryKind::Pulsemcp => parse_pulsemcp(&body),
+        }
+        .ok_or_else(|| Error::Protocol(format!("{} response has no server list", kind.label())))?;
+        servers.truncate(limit);
+        Ok(servers)
+    }
+
+    /// Search every directory in `kinds` for `query`, collecting the ones
+    /// that could not be queried instead of failing.
+    pub async fn search_all(
+        &self,
+        kinds: &[DirectoryKind],
+        query: &str,
+        limit: usize,
+    ) -> DirectorySearch {
+        let mut search = DirectorySearch::default();
+        for &kind in kinds {
+            match self.search(kind, query, limit).await {
+                Ok(mut servers) => search.servers.append(&mut servers),
+                Err(e) => search.unavailable.push((kind, e.to_string())),
+            }
+        }
+        search
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Stdio command that runs `identifier` from a package registry.
+fn package_command(registry: &str, identifier: &str, version: Option<&str>) -> Option<String> {
+    let command = match (registry, version) {
+        ("npm", Some(v)) => format!("npx -y {identifier}@{v}"),
+        ("npm", None) => format!("npx -y {identifier}"),
+        ("pypi", Some(v)) => format!("uvx {identifier}@{v}"),
+        ("pypi", None) => format!("uvx {identifier}"),
+        ("oci" | "docker", Some(v)) if !identifier.rsplit('/').next()?.contains(':') => {
+            format!("docker run -i --rm {identifier}:{v}")
+        }
+        ("oci" | "docker", _) => format!("docker run -i --rm {identifier}"),
+        _ => return None,
+    };
+    Some(command)
+}
+
+fn stdio(command: String) -> TransportConfig {
+    TransportConfig::Stdio {
+        command,
+        cwd: None,
+        protocol_version: None,
+    }
+}
+
+fn http(url: String, streamable_http: bool) -> TransportConfig {
+    TransportConfig::Http {
+        http_url: url,
+        streamable_http,
+        protocol_version: None,
+    }
+}
+
+/// Parse an official registry `/v0/servers` response. Hosted remotes are
+/// preferred over packages; among packages, the first stdio one the
+/// gateway knows how to run wins.
+fn parse_official(body: &Value) -> Option<Vec<DirectoryServer>> {
+    let mut servers: Vec<DirectoryServer> = Vec::new();
+    for item in body.get("servers")?.as_array()? {
+        let server = item.get("server").unwrap_or(item);
+        let Some(id) = str_field(server, "name") else {
+            continue;
+        };
+        if servers.iter().any(|s| s.id == id) {
+            continue;
+        }
+        let remote = server
+            .get("remotes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .find_map(|remote| {
+                let url = str_field(remote, "url")?;
+                match remote.get("type").and_then(Value::as_str) {
+                    Some("streamable-http") => Some(http(url, true)),
+                    Some("sse") => Some(http(url, false)),
+                    _ => None,
+                }
+            });
+        let package = server
+            .get("packages")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|package| {
+                package
+                    .pointer("/transport/type")
+                    .and_then(Value::as_str)
+                    .is_none_or(|t| t == "stdio")
+            })
+            .find_map(|package| {
+                let command = package_command(
+                    package
+                        .get("registryType")
+                        .or_else(|| package.get("registry_type"))
+                        .and_then(Value::as_str)?,
+                    package.get("identifier").and_then(Value::as_str)?,
+                    package.get("version").and_then(Value::as_str),
+                )?;
+                Some((command, package))
+            });
+        let (transport, env) = match (remote, package) {
+            (Some(remote), _) => (Some(remote), Vec::new()),
+            (None, Some((command, package))) => (Some(stdio(command)), package_env(package)),
+            (None, None) => (None, Vec::new()),
+        };
+        servers.push(DirectoryServer {
+            directory: DirectoryKind::Official,
+            description: str_field(server, "description").unwrap_or_default(),
+            version: str_field(server, "version"),
+            homepage: str_field(server, "websiteUrl")
+                .or_else(|| server.get("repository").and_then(|r| str_field(r, "url"))),
+            transport,
+            env,
+            id,
+        });
+    }
+    Some(servers)
+}
+
+fn package_env(package: &Value) -> Vec<DirectoryEnvVar> {
+    package
+        .get("environmentVariables")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|var| {
+            Some(DirectoryEnvVar {
+                name: str_field(var, "name")?,
+                required: var.get("isRequired").and_then(Value::as_bool) == Some(true),
+                secret: var.get("isSecret").and_then(Value::as_bool) == Some(true),
+                description: str_field(var, "description").unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Parse a Smithery `/servers` response. Hosted servers are reached through
+/// Smithery's streamable HTTP endpoint; the rest run via the Smithery CLI.
+fn parse_smithery(body: &Value) -> Option<Vec<DirectoryServer>> {
+    let servers = body
+        .get("servers")?
+        .as_array()?
+        .iter()
+        .filter_map(|server| {
+            let id = str_field(server, "qualifiedName")?;
+            let transport = if server.get("remote").and_then(Value::as_bool) == Some(true) {
+                http(format!("https://server.smithery.ai/{id}/mcp"), true)
+            } else {
+                stdio(format!("npx -y @smithery/cli@latest run {id}"))
+            };
+            Some(DirectoryServer {
+                directory: DirectoryKind::Smithery,
+                description: str_field(server, "description").unwrap_or_default(),
+                version: None,
+                homepage: str_field(server, "homepage"),
+                transport: Some(transport),
+                env: Vec::new(),
+                id,
+            })
+        })
+        .collect();
+    Some(servers)
+}
+
+/// Parse a `PulseMCP` `/v0beta/servers` response.
+fn parse_pulsemcp(body: &Value) -> Option<Vec<DirectoryServer>> {
+    let servers = body
+        .get("servers")?
+        .as_array()?
+        .iter()
+        .filter_map(|server| {
+            let package = str_field(server, "package_name");
+            let id = package.clone().or_else(|| str_field(server, "name"))?;
+            let remote = server
+                .get("remotes")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .find_map(|remote| {
+                    let url = str_field(remote, "url_direct")?;
+                    let sse = remote.get("transport").and_then(Value::as_str) == Some("sse");
+                    Some(http(url, !sse))
+                });
+            let transport = remote.or_else(|| {
+                let registry = str_field(server, "package_registry")?;
+                package_command(&registry, package.as_deref()?, None).map(stdio)
+            });
+            Some(DirectoryServer {
+                directory: DirectoryKind::Pulsemcp,
+                description: str_field(server, "short_description").unwrap_or_default(),
+                version: None,
+                homepage: str_field(server, "source_code_url")
+                    .or_else(|| str_field(server, "external_url"))
+                    .or_else(|| str_field(server, "url")),
+                transport,
+                env: Vec::new(),
+                id,
+            })
+        })
+        .collect();
+    Some(servers)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{Json, Router, extract::State, http::Uri, routing::get};
+    use parking_lot::Mutex;
+    use serde_json::json;
+
+    use super::*;
+
+    fn official_body() -> Value {
+        json!({
+            "servers": [
+                {
+                    "server": {
+                        "name": "io.github.acme/github-mcp",
+                        "description": "GitHub tools",
+                        "version": "1.2.0",
+                        "repository": {"url": "https://github.com/acme/github-mcp"},
+                        "packages": [
+                            {"registryType": "pypi", "identifier": "github-mcp",
+                             "version": "1.2.0", "transport": {"type": "streamable-http"}},
+                            {"registryType": "npm", "identifier": "@acme/github-mcp",
+                             "version": "1.2.0", "transport": {"type": "stdio"},
+                             "environmentVariables": [
+                                 {"name": "GITHUB_TOKEN", "isRequired": true, "isSecret": true,
+                                  "description": "Personal access token"}
+                             ]}
+                        ]
+                    },
+                    "_meta": {}
+                },
+                {
+                    "server": {
+                        "name": "com.example/hosted",
+                        "description": "Hosted search",
+                        "remotes": [{"type": "streamable-http", "url": "https://mcp.example.com/mcp"}]
+                    }
+                },
+                {"server": {"name": "com.example/unknown", "packages": [
+                    {"registryType": "nuget", "identifier": "Example.Server"}
+                ]}}
+            ]
+        })
+    }
+
+    #[test]
+    fn official_entries_become_stdio_or_remote_backends() {
+        // GIVEN: an official registry response with a package, a remote and
+        // an unsupported package type
+        // WHEN: parsing it
+        let servers = parse_official(&official_body()).unwrap();
+
+        // THEN: the npm package runs via npx with placeholder env vars
+        assert_eq!(servers.len(), 3);
+        assert_eq!(servers[0].backend_name(), "github-mcp");
+        let backend = servers[0].to_backend_config().unwrap();
+        assert!(matches!(
+            &backend.transport,
+            TransportConfig::Stdio { command, .. } if command == "npx -y @acme/github-mcp@1.2.0"
+        ));
+        assert_eq!(backend.env["GITHUB_TOKEN"], "${GITHUB_TOKEN}");
+        assert!(servers[0].env[0].secret);
+        // AND: the hosted server is reached over streamable HTTP
+        assert!(matches!(
+            &servers[1].transport,
+            Some(TransportConfig::Http { http_url, streamable_http: true, .. })
+                if http_url == "https://mcp.example.com/mcp"
+        ));
+        // AND: the unsupported package is listed but cannot be added
+        assert!(servers[2].to_backend_config().is_none());
+        assert!(servers[0].matches("io.github.acme/github-mcp"));
+    }
+
+    #[test]
+    fn smithery_and_pulsemcp_entries_map_to_install_metadata() {
+        // GIVEN: Smithery and PulseMCP responses
+        let smithery = json!({"servers": [
+            {"qualifiedName": "@acme/search", "description": "Search", "remote": true},
+            {"qualifiedName": "@acme/local", "remote": false}
+        ]});
+        let pulse = json!({"servers": [
+            {"name": "Files", "short_description": "File tools",
+             "package_registry": "npm", "package_name": "@acme/files", "remotes": []},
+            {"name": "Weather", "remotes": [{"url_direct": "https://w.example.com/sse", "transport": "sse"}]}
+        ]});
+
+        // WHEN: parsing them
+        let smithery = parse_smithery(&smithery).unwrap();
+        let pulse = parse_pulsemcp(&pulse).unwrap();
+
+        // THEN: hosted servers use their URL, packages their runner
+        assert!(matches!(
+            &smithery[0].transport,
+            Some(TransportConfig::Http { http_url, streamable_http: true, .. })
+                if http_url == "https://server.smithery.ai/@acme/search/mcp"
+        ));
+        assert!(matches!(
+            &smithery[1].transport,
+            Some(TransportConfig::Stdio { command, .. })
+                if command == "npx -y @smithery/cli@latest run @acme/local"
+        ));
+        assert_eq!(smithery[1].backend_name(), "local");
+        assert!(matches!(
+            &pulse[0].transport,
+            Some(TransportConfig::Stdio { command, .. }) if command == "npx -y @acme/files"
+        ));
+        assert!(matches!(
+            &pulse[1].transport,
+            Some(TransportConfig::Http {
+                streamable_http: false,
+                ..
+            })
+        ));
+    }
+
+    /// Stub official registry that records the query strings it receives.
+    async fn servers(State(queries): State<Arc<Mutex<Vec<String>>>>, uri: Uri) -> Json<Value> {
+        queries
+            .lock()
+            .push(uri.query().unwrap_or_default().to_string());
+        Json(official_body())
+    }
+
+    #[tokio::test]
+    async fn search_all_queries_directories_and_reports_unavailable_ones() {
+        // GIVEN: a stub official registry that records its queries
+        let queries: Arc<Mutex<Vec<String>>> = Arc::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/v0/servers", get(servers))
+            .with_state(queries.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let client = DirectoryClient::new()
+            .unwrap()
+            .with_base_url(DirectoryKind::Official, format!("http://{addr}/"))
+            .with_smithery_api_key(None);
+
+        // WHEN: searching the official registry and Smithery without a key
+        let search = client
+            .search_all(
+                &[DirectoryKind::Official, DirectoryKind::Smithery],
+                "github",
+                2,
+            )
+            .await;
+
+        // THEN: the official results are capped at the limit
+        assert_eq!(search.servers.len(), 2);
+        assert!(queries.lock()[0].contains("search=github"));
+        // AND: Smithery is reported as unavailable instead of failing
+        assert_eq!(search.unavailable.len(), 1);
+        assert_eq!(search.unavailable[0].0, DirectoryKind::Smithery);
+        assert!(search.unavailable[0].1.contains(SMITHERY_API_KEY_ENV));
+    }
+}
@@ -0,0 +1,607 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! mDNS / DNS-SD discovery of MCP servers on the local network
+//!
+//! Devices advertise an MCP endpoint as a `_mcp._tcp.local` DNS-SD service.
+//! [`browse`] sends one multicast PTR query from an ephemeral port, so
+//! responders answer directly without the gateway joining the multicast
+//! group, and collects answers until the timeout.
+//!
+//! Records are kept in an [`MdnsCache`] for their TTL; a record announced
+//! with TTL 0 (a "goodbye") is dropped at once. The TXT record describes the
+//! endpoint:
+//!
+//! | Key | Meaning | Default |
+//! |-----|---------|---------|
+//! | `path` | HTTP path of the MCP endpoint | `/mcp` |
+//! | `scheme` | `http` or `https` | `http` |
+//! | `transport` | `streamable-http` for Streamable HTTP | legacy HTTP+SSE |
+//! | `name` | backend name | instance name |
+//! | `description` | human-readable description | |
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use tokio::net::UdpSocket;
+use tracing::debug;
+
+use super::{DiscoveredServer, DiscoverySource, ServerMetadata, backend_name};
+use crate::config::TransportConfig;
+use crate::{Error, Result};
+
+/// DNS-SD service type advertised by MCP servers.
+pub const MCP_SERVICE: &str = "_mcp._tcp.local";
+
+const MDNS_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_AAAA: u16 = 28;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+/// "Unicast response requested" bit of a question's class.
+const CLASS_QU: u16 = 0x8000;
+/// Compression pointers followed per name before giving up.
+const MAX_POINTER_HOPS: usize = 16;
+
+/// One resource record of an mDNS response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MdnsRecord {
+    /// Service type → instance.
+    Ptr {
+        /// Service type, e.g. `_mcp._tcp.local`.
+        service: String,
+        /// Instance name, e.g. `scope._mcp._tcp.local`.
+        instance: String,
+        /// TTL in seconds.
+        ttl: u32,
+    },
+    /// Instance → host and port.
+    Srv {
+        /// Instance name.
+        instance: String,
+        /// Target host name.
+        host: String,
+        /// TCP port.
+        port: u16,
+        /// TTL in seconds.
+        ttl: u32,
+    },
+    /// Instance → `key=value` attributes.
+    Txt {
+        /// Instance name.
+        instance: String,
+        /// Attributes; a bare key maps to an empty value.
+        entries: BTreeMap<String, String>,
+        /// TTL in seconds.
+        ttl: u32,
+    },
+    /// Host → address (A or AAAA).
+    Address {
+        /// Host name.
+        host: String,
+        /// Address.
+        addr: IpAddr,
+        /// TTL in seconds.
+        ttl: u32,
+    },
+}
+
+/// A resolved MCP service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MdnsService {
+    /// Instance name, e.g. `scope._mcp._tcp.local`.
+    pub instance: String,
+    /// Host name from the SRV record.
+    pub host: String,
+    /// TCP port.
+    pub port: u16,
+    /// Known addresses of `host`, IPv4 first.
+    pub addresses: Vec<IpAddr>,
+    /// TXT attributes.
+    pub txt: BTreeMap<String, String>,
+    /// Remaining lifetime of the advertisement.
+    pub ttl: Duration,
+}
+
+impl MdnsService {
+    /// Instance label without the service type, e.g. `scope`.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        self.instance
+            .strip_suffix(MCP_SERVICE)
+            .map_or(self.instance.as_str(), |label| label.trim_end_matches('.'))
+    }
+
+    /// Endpoint URL built from the first address (the host name when none
+    /// is known), the port and the TXT `scheme` and `path`.
+    #[must_use]
+    pub fn url(&self) -> String {
+        let host = match self.addresses.first() {
+            Some(IpAddr::V4(addr)) => addr.to_string(),
+            Some(IpAddr::V6(addr)) => format!("[{addr}]"),
+            None => self.host.trim_end_matches('.').to_string(),
+        };
+        let scheme = self.txt.get("scheme").map_or("http", String::as_str);
+        let path = self.txt.get("path").map_or("/mcp", String::as_str);
+        let slash = if path.starts_with('/') { "" } else { "/" };
+        format!("{scheme}://{host}:{}{slash}{path}", self.port)
+    }
+
+    /// Convert to a discovered server with an HTTP transport.
+    #[must_use]
+    pub fn to_discovered(&self) -> DiscoveredServer {
+        let name = self
+            .txt
+            .get("name")
+            .map_or_else(|| backend_name(self.label()), |name| backend_name(name));
+        let description = self.txt.get("description").map_or_else(
+            || {
+                format!(
+                    "{} (mDNS on {})",
+                    self.label(),
+                    self.host.trim_end_matches('.')
+                )
+            },
+            Clone::clone,
+        );
+        let expires_at = chrono::Duration::from_std(self.ttl)
+            .ok()
+            .map(|ttl| chrono::Utc::now() + ttl);
+        DiscoveredServer {
+            name,
+            description,
+            source: DiscoverySource::Mdns,
+            transport: TransportConfig::Http {
+                http_url: self.url(),
+                streamable_http: self
+                    .txt
+                    .get("transport")
+                    .is_some_and(|t| t == "streamable-http"),
+                protocol_version: None,
+            },
+            metadata: ServerMetadata {
+                config_path: None,
+                pid: None,
+                port: Some(self.port),
+                command: None,
+                working_dir: None,
+                expires_at,
+                probe: None,
+            },
+        }
+    }
+}
+
+/// mDNS records seen so far, each kept until its TTL runs out.
+#[derive(Debug, Default)]
+pub struct MdnsCache {
+    instances: HashMap<String, Instant>,
+    srv: HashMap<String, (String, u16, Instant)>,
+    txt: HashMap<String, (BTreeMap<String, String>, Instant)>,
+    addresses: HashMap<String, Vec<(IpAddr, Instant)>>,
+}
+
+impl MdnsCache {
+    /// Record `records` received at `now`. TTL 0 removes a record.
+    pub fn observe(&mut self, records: Vec<MdnsRecord>, now: Instant) {
+        let expiry = |ttl: u32| now + Duration::from_secs(u64::from(ttl));
+        for record in records {
+            match record {
+                MdnsRecord::Ptr {
+                    service,
+                    instance,
+                    ttl,
+                } if service.eq_ignore_ascii_case(MCP_SERVICE) => {
+                    if ttl == 0 {
+                        self.instances.remove(&instance);
+                    } else {
+                        self.instances.insert(instance, expiry(ttl));
+                    }
+                }
+                MdnsRecord::Ptr { .. } => {}
+                MdnsRecord::Srv {
+                    instance,
+                    host,
+                    port,
+                    ttl,
+                } => {
+                    if ttl == 0 {
+                        self.srv.remove(&instance);
+                    } else {
+                        self.srv.insert(instance, (host, port, expiry(ttl)));
+                    }
+                }
+                MdnsRecord::Txt {
+                    instance,
+                    entries,
+                    ttl,
+                } => {
+                    if ttl == 0 {
+                        self.txt.remove(&instance);
+                    } else {
+                        self.txt.insert(instance, (entries, expiry(ttl)));
+                    }
+                }
+                MdnsRecord::Address { host, addr, ttl } => {
+                    let known = self.addresses.entry(host).or_default();
+                    known.retain(|(a, _)| *a != addr);
+                    if ttl > 0 {
+                        known.push((addr, expiry(ttl)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drop every record expired at `now`.
+    pub fn prune(&mut self, now: Instant) {
+        self.instances.retain(|_, expires| *expires > now);
+        self.srv.retain(|_, (_, _, expires)| *expires > now);
+        self.txt.retain(|_, (_, expires)| *expires > now);
+        for known in self.addresses.values_mut() {
+            known.retain(|(_, expires)| *expires > now);
+        }
+        self.addresses.retain(|_, known| !known.is_empty());
+    }
+
+    /// Services advertised and resolvable (PTR and SRV live) at `now`,
+    /// sorted by instance name. A service lives as long as its shorter-lived
+    /// PTR or SRV record.
+    #[must_use]
+    pub fn services(&self, now: Instant) -> Vec<MdnsService> {
+        let mut services: Vec<MdnsService> = self
+            .instances
+            .iter()
+            .filter(|(_, expires)| **expires > now)
+            .filter_map(|(instance, ptr_expires)| {
+                let (host, port, srv_expires) = self.srv.get(instance)?;
+                if *srv_expires <= now {
+                    return None;
+                }
+                let mut addresses: Vec<IpAddr> = self
+                    .addresses
+                    .get(host)
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, expires)| *expires > now)
+                    .map(|(addr, _)| *addr)
+                    .collect();
+                addresses.sort_by_key(IpAddr::is_ipv6);
+                let txt = self
+                    .txt
+                    .get(instance)
+                    .filter(|(_, expires)| *expires > now)
+                    .map(|(entries, _)| entries.clone())
+                    .unwrap_or_default();
+                Some(MdnsService {
+                    instance: instance.clone(),
+                    host: host.clone(),
+                    port: *port,
+                    addresses,
+                    txt,
+                    ttl: (*ptr_expires).min(*srv_expires) - now,
+                })
+            })
+            .collect();
+        services.sort_by(|a, b| a.instance.cmp(&b.instance));
+        services
+    }
+}
+
+/// Query the local network for `_mcp._tcp` services for `timeout` and add
+/// the answers to `cache`.
+///
+/// # Errors
+///
+/// Returns an error if no UDP socket can be opened or the query cannot be
+/// sent (e.g. no multicast route).
+pub async fn browse(cache: &mut MdnsCache, timeout: Duration) -> Result<()> {
+    let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+        .await
+        .map_err(|e| Error::Transport(format!("mDNS: cannot open UDP socket: {e}")))?;
+    socket
+        .send_to(&query_packet(), (MDNS_GROUP, MDNS_PORT))
+        .await
+        .map_err(|e| Error::Transport(format!("mDNS: cannot send query: {e}")))?;
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut buf = vec![0u8; 9000];
+    loop {
+        let received = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await;
+        let Ok(Ok((len, from))) = received else {
+            break;
+        };
+        match parse_response(&buf[..len]) {
+            Ok(records) => cache.observe(records, Instant::now()),
+            Err(e) => debug!(%from, error = %e, "Ignoring malformed mDNS packet"),
+        }
+    }
+    Ok(())
+}
+
+/// Browse for `timeout` and return the MCP services found.
+///
+/// # Errors
+///
+/// See [`browse`].
+pub async fn discover(timeout: Duration) -> Result<Vec<DiscoveredServer>> {
+    let mut cache = MdnsCache::default();
+    browse(&mut cache, timeout).await?;
+    Ok(cache
+        .services(Instant::now())
+        .iter()
+        .map(MdnsService::to_discovered)
+        .collect())
+}
+
+/// PTR query for [`MCP_SERVICE`], asking for unicast replies.
+fn query_packet() -> Vec<u8> {
+    // ID 0, standard query, one question.
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in MCP_SERVICE.split('.') {
+        packet.push(u8::try_from(label.len()).unwrap_or(u8::MAX));
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    packet.extend_from_slice(&(CLASS_IN | CLASS_QU).to_be_bytes());
+    packet
+}
+
+/// Parse the answer, authority and additional records of an mDNS response.
+/// Record types other than PTR, SRV, TXT, A and AAAA are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the packet is truncated or not a response.
+pub fn parse_response(packet: &[u8]) -> Result<Vec<MdnsRecord>> {
+    let malformed = || Error::Protocol("malformed mDNS packet".to_string());
+    if packet.len() < 12 || packet[2] & 0x80 == 0 {
+        return Err(malformed());
+    }
+    let count = |at: usize| usize::from(u16::from_be_bytes([packet[at], packet[at + 1]]));
+    let questions = count(4);
+    let records = count(6) + count(8) + count(10);
+
+    let mut offset = 12;
+    for _ in 0..questions {
+        offset = read_name(packet, offset)?.1 + 4;
+    }
+    let mut parsed = Vec::new();
+    for _ in 0..records {
+        let (name, next) = read_name(packet, offset)?;
+        let header = packet.get(next..next + 10).ok_or_else(malformed)?;
+        let rtype = u16::from_be_bytes([header[0], header[1]]);
+        let class = u16::from_be_bytes([header[2], header[3]]) & !CLASS_QU;
+        let ttl = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+        let len = usize::from(u16::from_be_bytes([header[8], header[9]]));
+        let start = next + 10;
+        let data = packet.get(start..start + len).ok_or_else(malformed)?;
+        offset = start + len;
+        if class != CLASS_IN {
+            continue;
+        }
+        let record = match rtype {
+            TYPE_PTR => MdnsRecord::Ptr {
+                service: name,
+                instance: read_name(packet, start)?.0,
+                ttl,
+            },
+            TYPE_SRV if len >= 6 => MdnsRecord::Srv {
+                instance: name,
+                port: u16::from_be_bytes([data[4], data[5]]),
+                host: read_name(packet, start + 6)?.0,
+                ttl,
+            },
+            TYPE_TXT => MdnsRecord::Txt {
+                instance: name,
+                entries: parse_txt(data),
+                ttl,
+            },
+            TYPE_A if len == 4 => MdnsRecord::Address {
+                host: name,
+                addr: IpAddr::V4(Ipv4Addr::new(data[0], data[1], data[2], data[3])),
+                ttl,
+            },
+            TYPE_AAAA if len == 16 => {
+                let octets: [u8; 16] = data.try_into().map_err(|_| malformed())?;
+                MdnsRecord::Address {
+                    host: name,
+                    addr: IpAddr::V6(Ipv6Addr::from(octets)),
+                    ttl,
+                }
+            }
+            _ => continue,
+        };
+        parsed.push(record);
+    }
+    Ok(parsed)
+}
+
+/// Read the (possibly compressed) name at `offset`; returns it without the
+/// trailing dot and the offset after it.
+fn read_name(packet: &[u8], offset: usize) -> Result<(String, usize)> {
+    let malformed = || Error::Protocol("malformed mDNS name".to_string());
+    let mut labels: Vec<String> = Vec::new();
+    let mut at = offset;
+    let mut end = None;
+    let mut hops = 0;
+    loop {
+        let len = *packet.get(at).ok_or_else(malformed)?;
+        match len {
+            0 => {
+                return Ok((labels.join("."), end.unwrap_or(at + 1)));
+            }
+            0xC0..=0xFF => {
+                let low = *packet.get(at + 1).ok_or_else(malformed)?;
+                end.get_or_insert(at + 2);
+                hops += 1;
+                if hops > MAX_POINTER_HOPS {
+                    return Err(malformed());
+                }
+                at = usize::from(u16::from_be_bytes([len & 0x3F, low]));
+            }
+            1..=63 => {
+                let label = packet
+                    .get(at + 1..at + 1 + usize::from(len))
+                    .ok_or_else(malformed)?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                at += 1 + usize::from(len);
+            }
+            _ => return Err(malformed()),
+        }
+    }
+}
+
+fn parse_txt(data: &[u8]) -> BTreeMap<String, String> {
+    let mut entries = BTreeMap::new();
+    let mut at = 0;
+    while let Some(&len) = data.get(at) {
+        let Some(entry) = data.get(at + 1..at + 1 + usize::from(len)) else {
+            break;
+        };
+        let entry = String::from_utf8_lossy(entry);
+        if !entry.is_empty() {
+            let (key, value) = entry.split_once('=').unwrap_or((entry.as_ref(), ""));
+            entries
+                .entry(key.to_ascii_lowercase())
+                .or_insert_with(|| value.to_string());
+        }
+        at += 1 + usize::from(len);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds mDNS responses without name compression.
+    #[derive(Default)]
+    struct Response {
+        records: Vec<u8>,
+        count: u16,
+    }
+
+    impl Response {
+        fn name(out: &mut Vec<u8>, name: &str) {
+            for label in name.split('.') {
+                out.push(u8::try_from(label.len()).unwrap());
+                out.extend_from_slice(label.as_bytes());
+            }
+            out.push(0);
+        }
+
+        fn record(mut self, name: &str, rtype: u16, ttl: u32, data: &[u8]) -> Self {
+            Self::name(&mut self.records, name);
+            self.records.extend_from_slice(&rtype.to_be_bytes());
+            self.records
+                .extend_from_slice(&(CLASS_IN | CLASS_QU).to_be_bytes());
+            self.records.extend_from_slice(&ttl.to_be_bytes());
+            self.records
+                .extend_from_slice(&u16::try_from(data.len()).unwrap().to_be_bytes());
+            self.records.extend_from_slice(data);
+            self.count += 1;
+            self
+        }
+
+        fn service(self, label: &str, ttl: u32) -> Self {
+            let instance = format!("{label}.{MCP_SERVICE}");
+            let mut target = Vec::new();
+            Self::name(&mut target, &instance);
+            let mut srv = vec![0, 0, 0, 0, 0x1F, 0x90];
+            Self::name(&mut srv, "scope.local");
+            self.record(MCP_SERVICE, TYPE_PTR, ttl, &target)
+                .record(&instance, TYPE_SRV, ttl, &srv)
+                .record(
+                    &instance,
+                    TYPE_TXT,
+                    ttl,
+                    b"\x09path=/rpc\x19transport=streamable-http",
+                )
+                .record("scope.local", TYPE_A, ttl, &[192, 168, 1, 40])
+        }
+
+        fn build(self) -> Vec<u8> {
+            let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+            packet[6..8].copy_from_slice(&self.count.to_be_bytes());
+            packet.extend(self.records);
+            packet
+        }
+    }
+
+    #[test]
+    fn parses_a_service_announcement_into_a_discovered_server() {
+        // GIVEN: a response announcing "Lab Scope" on 192.168.1.40:8080
+        let packet = Response::default().service("Lab Scope", 120).build();
+
+        // WHEN: parsing and caching it
+        let mut cache = MdnsCache::default();
+        let now = Instant::now();
+        cache.observe(parse_response(&packet).unwrap(), now);
+        let services = cache.services(now);
+
+        // THEN: one service resolves to its streamable HTTP endpoint
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].label(), "Lab Scope");
+        assert_eq!(services[0].url(), "http://192.168.1.40:8080/rpc");
+        let server = services[0].to_discovered();
+        assert_eq!(server.name, "lab-scope");
+        assert_eq!(server.source, DiscoverySource::Mdns);
+        assert!(matches!(
+            server.transport,
+            TransportConfig::Http {
+                streamable_http: true,
+                ..
+            }
+        ));
+        assert!(server.metadata.expires_at.is_some());
+    }
+
+    #[test]
+    fn services_expire_with_their_ttl_and_on_goodbye() {
+        let now = Instant::now();
+        let mut cache = MdnsCache::default();
+        let announce = Response::default()
+            .service("a", 60)
+            .service("b", 600)
+            .build();
+        cache.observe(parse_response(&announce).unwrap(), now);
+        assert_eq!(cache.services(now).len(), 2);
+
+        // "a" is gone after its 60 s TTL
+        let later = now + Duration::from_secs(61);
+        let services = cache.services(later);
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].label(), "b");
+
+        // "b" sends a goodbye (TTL 0)
+        let goodbye = Response::default().service("b", 0).build();
+        cache.observe(parse_response(&goodbye).unwrap(), later);
+        cache.prune(later);
+        assert!(cache.services(later).is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_packets_and_pointer_loops() {
+        let packet = Response::default().service("a", 60).build();
+        assert!(parse_response(&packet[..packet.len() - 3]).is_err());
+
+        // A name that points at itself.
+        let mut looped = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        looped.extend_from_slice(&[0xC0, 12]);
+        assert!(parse_response(&looped).is_err());
+    }
+
+    #[test]
+    fn query_asks_for_mcp_ptr_records() {
+        let query = query_packet();
+        assert_eq!(&query[4..6], &[0, 1]);
+        assert!(query.windows(4).any(|w| w == b"_mcp"));
+        assert_eq!(&query[query.len() - 4..], &[0, 12, 0x80, 1]);
+    }
+}
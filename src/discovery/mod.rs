@@ -2,10 +2,15 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! MCP Server Auto-Discovery
 //!
-//! Scans for existing MCP server configurations in common locations
-//! and running MCP server processes to enable zero-config integration.
+//! Scans for existing MCP server configurations in common locations,
+//! running MCP server processes and, when enabled, MCP servers advertised
+//! on the local network via mDNS to enable zero-config integration.
+//! [`directory`] searches public MCP server directories for servers that
+//! are not installed yet. [`probe`] checks that a discovered server
+//! actually answers before it is written to a config.
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use tracing::debug;
@@ -14,6 +19,10 @@ use crate::Result;
 use crate::config::{BackendConfig, TransportConfig};
 
 pub mod config_scanner;
+pub mod directory;
+pub mod mdns;
+pub mod monitor;
+pub mod probe;
 pub mod process_scanner;
 pub mod shadow;
 
@@ -60,6 +69,8 @@ pub enum DiscoverySource {
     RunningProcess,
     /// Environment variable
     Environment,
+    /// `_mcp._tcp` service advertised via mDNS on the local network
+    Mdns,
 }
 
 /// Server metadata from discovery
@@ -75,25 +86,51 @@ pub struct ServerMetadata {
     pub command: Option<String>,
     /// Working directory
     pub working_dir: Option<PathBuf>,
+    /// When a network advertisement (mDNS TTL) runs out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Outcome of connecting to the server, when it was probed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probe: Option<probe::ProbeResult>,
 }
 
 impl DiscoveredServer {
     /// Convert to backend config
+    ///
+    /// A server that failed its [`probe`] is written disabled.
     #[must_use]
     pub fn to_backend_config(&self) -> BackendConfig {
         BackendConfig {
             description: self.description.clone(),
-            enabled: true,
+            enabled: self.metadata.probe.as_ref().is_none_or(|p| p.reachable),
             transport: self.transport.clone(),
             ..Default::default()
         }
     }
 }
 
+/// Lowercase `label` with runs of other characters turned into `-`.
+pub(crate) fn backend_name(label: &str) -> String {
+    let mut name = String::with_capacity(label.len());
+    for c in label.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            name.push(c.to_ascii_lowercase());
+        } else if !name.is_empty() && !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    name.trim_end_matches('-').to_string()
+}
+
+/// Default time `cap discover` listens for mDNS answers.
+pub const DEFAULT_MDNS_TIMEOUT: Duration = Duration::from_millis(1500);
+
 /// MCP Auto-Discovery orchestrator
 pub struct AutoDiscovery {
     config_scanner: ConfigScanner,
     process_scanner: ProcessScanner,
+    /// How long to browse mDNS in `discover_all`; `None` skips it.
+    mdns_timeout: Option<Duration>,
 }
 
 impl AutoDiscovery {
@@ -103,9 +140,18 @@ impl AutoDiscovery {
         Self {
             config_scanner: ConfigScanner::new(),
             process_scanner: ProcessScanner::new(),
+            mdns_timeout: None,
         }
     }
 
+    /// Also browse the local network for `_mcp._tcp` services for
+    /// `timeout` in [`Self::discover_all`].
+    #[must_use]
+    pub fn with_mdns(mut self, timeout: Duration) -> Self {
+        self.mdns_timeout = Some(timeout);
+        self
+    }
+
     /// Discover all MCP servers from all sources
     ///
     /// # Errors
@@ -132,7 +178,17 @@ impl AutoDiscovery {
             }
         }
 
-        // Deduplicate by name (prefer config over process)
+        if let Some(timeout) = self.mdns_timeout {
+            debug!("Browsing mDNS for MCP servers");
+            match mdns::discover(timeout).await {
+                Ok(mut lan_servers) => servers.append(&mut lan_servers),
+                Err(e) => {
+                    tracing::warn!("mDNS discovery failed: {e}");
+                }
+            }
+        }
+
+        // Deduplicate by name (prefer config over process, process over mDNS)
         let mut unique_servers: Vec<DiscoveredServer> = Vec::new();
         for server in servers {
             if !unique_servers.iter().any(|s| s.name == server.name) {
@@ -164,6 +220,9 @@ impl AutoDiscovery {
             DiscoverySource::McpConfig => self.config_scanner.scan_mcp_config_dir().await,
             DiscoverySource::RunningProcess => self.process_scanner.scan().await,
             DiscoverySource::Environment => self.config_scanner.scan_environment(),
+            DiscoverySource::Mdns => {
+                mdns::discover(self.mdns_timeout.unwrap_or(DEFAULT_MDNS_TIMEOUT)).await
+            }
         }
     }
 }
@@ -0,0 +1,312 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Continuous background discovery
+//!
+//! [`DiscoveryMonitor`] re-runs [`AutoDiscovery`] on the `discovery.interval`
+//! of a serving gateway and keeps the servers that are not configured as
+//! backends (compared by name, as in the `ShadowRadar` report). Each scan is
+//! diffed against the previous one; a non-empty [`DiscoveryChange`] is sent
+//! to connected clients as a `notifications/message` logging notification
+//! and the latest picture is served by the `gateway_discovery_status`
+//! meta-tool.
+//!
+//! With `discovery.auto_adopt` set, servers the policy allows are written to
+//! the config file and picked up by the regular config reload; they then
+//! count as configured and leave the unmanaged list without being reported
+//! as removed.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use super::{AutoDiscovery, DEFAULT_MDNS_TIMEOUT, DiscoveredServer, DiscoverySource};
+use crate::Result;
+use crate::config::{BackendConfig, DiscoveryAdoptPolicy, DiscoveryConfig};
+
+/// MCP logger name on `notifications/message`.
+const MCP_LOGGER: &str = "mcp-gateway.discovery";
+
+/// Servers that appeared or went away between two scans.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiscoveryChange {
+    /// Newly seen unmanaged servers.
+    pub added: Vec<DiscoveredServer>,
+    /// Names of unmanaged servers no longer seen.
+    pub removed: Vec<String>,
+}
+
+impl DiscoveryChange {
+    /// Whether nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// MCP `notifications/message` (logging) notification.
+    #[must_use]
+    pub fn mcp_notification(&self) -> Value {
+        let added: Vec<&str> = self.added.iter().map(|s| s.name.as_str()).collect();
+        json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": {
+                "level": "info",
+                "logger": MCP_LOGGER,
+                "data": {
+                    "message": format!(
+                        "MCP discovery: {} new, {} gone",
+                        self.added.len(),
+                        self.removed.len()
+                    ),
+                    "added": added,
+                    "removed": self.removed,
+                },
+            }
+        })
+    }
+}
+
+/// Latest state of background discovery.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiscoveryStatus {
+    /// Completed scans.
+    pub scans: u64,
+    /// When the last scan finished.
+    pub last_scan: Option<DateTime<Utc>>,
+    /// Why the last scan failed, if it did.
+    pub last_error: Option<String>,
+    /// Discovered servers that are not configured backends.
+    pub unmanaged: Vec<DiscoveredServer>,
+    /// When the unmanaged list last changed.
+    pub last_change_at: Option<DateTime<Utc>>,
+    /// The most recent non-empty change.
+    pub last_change: Option<DiscoveryChange>,
+    /// Backends added to the config by the auto-adopt policy.
+    pub adopted: Vec<String>,
+}
+
+/// Periodic discovery state shared by the scan task and the meta-tool.
+pub struct DiscoveryMonitor {
+    discovery: AutoDiscovery,
+    policy: DiscoveryAdoptPolicy,
+    status: RwLock<DiscoveryStatus>,
+}
+
+impl DiscoveryMonitor {
+    /// Create a monitor for `config`.
+    #[must_use]
+    pub fn new(config: &DiscoveryConfig) -> Self {
+        let discovery = if config.mdns {
+            AutoDiscovery::new().with_mdns(DEFAULT_MDNS_TIMEOUT)
+        } else {
+            AutoDiscovery::new()
+        };
+        Self {
+            discovery,
+            policy: config.auto_adopt,
+            status: RwLock::default(),
+        }
+    }
+
+    /// The configured auto-adopt policy.
+    #[must_use]
+    pub fn policy(&self) -> DiscoveryAdoptPolicy {
+        self.policy
+    }
+
+    /// Snapshot of the current state.
+    #[must_use]
+    pub fn status(&self) -> DiscoveryStatus {
+        self.status.read().clone()
+    }
+
+    /// Run one scan and diff it against the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if discovery fails; the error is also recorded in
+    /// [`DiscoveryStatus::last_error`] and the previous picture is kept.
+    pub async fn scan(
+        &self,
+        configured: &HashMap<String, BackendConfig>,
+    ) -> Result<DiscoveryChange> {
+        match self.discovery.discover_all().await {
+            Ok(found) => Ok(self.observe(found, configured, Utc::now())),
+            Err(e) => {
+                let mut status = self.status.write();
+                status.scans += 1;
+                status.last_scan = Some(Utc::now());
+                status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// Record the servers found at `now`, keeping those not in `configured`,
+    /// and return what changed since the previous scan.
+    pub fn observe(
+        &self,
+        found: Vec<DiscoveredServer>,
+        configured: &HashMap<String, BackendConfig>,
+        now: DateTime<Utc>,
+    ) -> DiscoveryChange {
+        let unmanaged: Vec<DiscoveredServer> = found
+            .into_iter()
+            .filter(|server| !configured.contains_key(&server.name))
+            .collect();
+        let mut status = self.status.write();
+        let previous: HashSet<&str> = status.unmanaged.iter().map(|s| s.name.as_str()).collect();
+        let current: HashSet<&str> = unmanaged.iter().map(|s| s.name.as_str()).collect();
+        let change = DiscoveryChange {
+            added: unmanaged
+                .iter()
+                .filter(|s| !previous.contains(s.name.as_str()))
+                .cloned()
+                .collect(),
+            removed: status
+                .unmanaged
+                .iter()
+                .filter(|s| !current.contains(s.name.as_str()) && !configured.contains_key(&s.name))
+                .map(|s| s.name.clone())
+                .collect(),
+        };
+        status.scans += 1;
+        status.last_scan = Some(now);
+        status.last_error = None;
+        status.unmanaged = unmanaged;
+        if !change.is_empty() {
+            status.last_change_at = Some(now);
+            status.last_change = Some(change.clone());
+        }
+        change
+    }
+
+    /// Unmanaged servers the auto-adopt policy allows adding.
+    #[must_use]
+    pub fn adoptable(&self) -> Vec<DiscoveredServer> {
+        self.status
+            .read()
+            .unmanaged
+            .iter()
+            .filter(|server| match self.policy {
+                DiscoveryAdoptPolicy::Off => false,
+                DiscoveryAdoptPolicy::Local => !matches!(
+                    server.source,
+                    DiscoverySource::RunningProcess | DiscoverySource::Mdns
+                ),
+                DiscoveryAdoptPolicy::All => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Record that `names` were added to the config, so they leave the
+    /// unmanaged list without being reported as removed.
+    pub fn record_adopted(&self, names: &[String]) {
+        let mut status = self.status.write();
+        status
+            .unmanaged
+            .retain(|server| !names.contains(&server.name));
+        status.adopted.extend(names.iter().cloned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TransportConfig;
+    use crate::discovery::ServerMetadata;
+
+    fn server(name: &str, source: DiscoverySource) -> DiscoveredServer {
+        DiscoveredServer {
+            name: name.to_string(),
+            description: String::new(),
+            source,
+            transport: TransportConfig::Stdio {
+                command: format!("npx -y {name}"),
+                cwd: None,
+                protocol_version: None,
+            },
+            metadata: ServerMetadata::default(),
+        }
+    }
+
+    #[test]
+    fn observe_reports_new_and_removed_unmanaged_servers() {
+        // GIVEN: a monitor that saw `github` and `slack`, with `tavily` configured
+        let monitor = DiscoveryMonitor::new(&DiscoveryConfig::default());
+        let configured: HashMap<String, BackendConfig> =
+            HashMap::from([("tavily".to_string(), BackendConfig::default())]);
+        let first = monitor.observe(
+            vec![
+                server("github", DiscoverySource::ClaudeDesktop),
+                server("slack", DiscoverySource::Cursor),
+                server("tavily", DiscoverySource::ClaudeCode),
+            ],
+            &configured,
+            Utc::now(),
+        );
+        assert_eq!(first.added.len(), 2);
+
+        // WHEN: the next scan finds `github` and a new `notion`
+        let change = monitor.observe(
+            vec![
+                server("github", DiscoverySource::ClaudeDesktop),
+                server("notion", DiscoverySource::Mdns),
+            ],
+            &configured,
+            Utc::now(),
+        );
+
+        // THEN: only the difference is reported
+        let added: Vec<&str> = change.added.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(added, ["notion"]);
+        assert_eq!(change.removed, ["slack"]);
+        let notification = change.mcp_notification();
+        assert_eq!(notification["method"], "notifications/message");
+        assert_eq!(notification["params"]["data"]["added"][0], "notion");
+        // AND: an unchanged scan reports nothing
+        let again = monitor.observe(
+            vec![
+                server("github", DiscoverySource::ClaudeDesktop),
+                server("notion", DiscoverySource::Mdns),
+            ],
+            &configured,
+            Utc::now(),
+        );
+        assert!(again.is_empty());
+        assert_eq!(monitor.status().scans, 3);
+    }
+
+    #[test]
+    fn adoption_follows_policy_and_is_not_reported_as_removal() {
+        // GIVEN: a `local` policy and servers from a client config and the LAN
+        let monitor = DiscoveryMonitor::new(&DiscoveryConfig {
+            auto_adopt: DiscoveryAdoptPolicy::Local,
+            ..DiscoveryConfig::default()
+        });
+        let mut configured = HashMap::new();
+        let found = vec![
+            server("github", DiscoverySource::ClaudeDesktop),
+            server("printer", DiscoverySource::Mdns),
+        ];
+        monitor.observe(found.clone(), &configured, Utc::now());
+
+        // WHEN: asking what may be adopted and adopting it
+        let adoptable = monitor.adoptable();
+        let names: Vec<String> = adoptable.iter().map(|s| s.name.clone()).collect();
+        assert_eq!(names, ["github"]);
+        monitor.record_adopted(&names);
+        configured.insert("github".to_string(), adoptable[0].to_backend_config());
+
+        // THEN: the next scan reports no change
+        let change = monitor.observe(found, &configured, Utc::now());
+        assert!(change.is_empty(), "{change:?}");
+        let status = monitor.status();
+        assert_eq!(status.adopted, ["github"]);
+        assert_eq!(status.unmanaged.len(), 1);
+    }
+}
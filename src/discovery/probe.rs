@@ -0,0 +1,244 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Liveness probe for discovered servers
+//!
+//! Client config files keep entries for servers that were uninstalled long
+//! ago. [`probe`] connects to a [`DiscoveredServer`] the way a backend would,
+//! runs the `initialize` handshake and `tools/list`, and records the outcome
+//! in [`ServerMetadata::probe`](super::ServerMetadata::probe) so that
+//! unreachable entries are written disabled instead of as live backends.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::DiscoveredServer;
+use crate::config::TransportConfig;
+use crate::transport::{HttpTransport, StdioTransport, Transport};
+use crate::{Error, Result};
+
+/// Default time allowed for connecting to and listing one server.
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper bound on `tools/list` pages followed while counting tools.
+const MAX_TOOL_PAGES: usize = 50;
+
+/// Outcome of probing one discovered server.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProbeResult {
+    /// Whether `initialize` and `tools/list` both succeeded.
+    pub reachable: bool,
+    /// Negotiated MCP protocol version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    /// Number of tools the server lists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_count: Option<usize>,
+    /// Why the server could not be reached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Time taken by the probe, in milliseconds.
+    pub elapsed_ms: u64,
+}
+
+/// Connect to `server`, run `initialize` and `tools/list`, then disconnect.
+///
+/// Never fails: connection, handshake and timeout errors are reported as an
+/// unreachable [`ProbeResult`].
+pub async fn probe(server: &DiscoveredServer, timeout: Duration) -> ProbeResult {
+    let started = Instant::now();
+    let outcome = match tokio::time::timeout(timeout, handshake(&server.transport, timeout)).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(Error::BackendTimeout(format!(
+            "no answer within {}ms",
+            timeout.as_millis()
+        ))),
+    };
+    let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    match outcome {
+        Ok((protocol_version, tool_count)) => ProbeResult {
+            reachable: true,
+            protocol_version: Some(protocol_version),
+            tool_count: Some(tool_count),
+            error: None,
+            elapsed_ms,
+        },
+        Err(e) => ProbeResult {
+            reachable: false,
+            error: Some(e.to_string()),
+            elapsed_ms,
+            ..ProbeResult::default()
+        },
+    }
+}
+
+/// Probe every server concurrently and store each result in its metadata.
+pub async fn probe_all(servers: &mut [DiscoveredServer], timeout: Duration) {
+    let results = join_all(servers.iter().map(|server| probe(server, timeout))).await;
+    for (server, result) in servers.iter_mut().zip(results) {
+        server.metadata.probe = Some(result);
+    }
+}
+
+/// Run the handshake and return the protocol version and tool count.
+async fn handshake(transport: &TransportConfig, timeout: Duration) -> Result<(String, usize)> {
+    match transport {
+        TransportConfig::Stdio {
+            command,
+            cwd,
+            protocol_version,
+        } => {
+            let stdio = StdioTransport::new(
+                command,
+                HashMap::new(),
+                cwd.clone(),
+                timeout,
+                protocol_version.clone(),
+            );
+            stdio.start().await?;
+            let tools = count_tools(stdio.as_ref()).await;
+            let version = stdio.protocol_version();
+            let _ = stdio.close().await;
+            tools.map(|count| (version, count))
+        }
+        TransportConfig::Http {
+            http_url,
+            streamable_http,
+            protocol_version,
+        } => {
+            let http = HttpTransport::new_with_oauth(
+                http_url,
+                HashMap::new(),
+                timeout,
+                *streamable_http,
+                None,
+                protocol_version.clone(),
+            )?;
+            http.initialize().await?;
+            let tools = count_tools(http.as_ref()).await;
+            let version = http.protocol_version();
+            let _ = http.close().await;
+            tools.map(|count| (version, count))
+        }
+        #[cfg(feature = "a2a")]
+        TransportConfig::A2a { .. } => Err(Error::Config(
+            "A2A agents are not MCP servers and cannot be probed".to_string(),
+        )),
+    }
+}
+
+/// Count the tools a connected server lists, following pagination.
+async fn count_tools(transport: &dyn Transport) -> Result<usize> {
+    let mut count = 0;
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_TOOL_PAGES {
+        let params = cursor.as_ref().map(|c| json!({ "cursor": c }));
+        let response = transport.request("tools/list", params).await?;
+        if let Some(error) = response.error {
+            return Err(Error::Protocol(format!(
+                "tools/list failed: {}",
+                error.message
+            )));
+        }
+        let result = response.result.unwrap_or_default();
+        count += result
+            .get("tools")
+            .and_then(serde_json::Value::as_array)
+            .map_or(0, Vec::len);
+        cursor = result
+            .get("nextCursor")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Json, Router, http::StatusCode, response::IntoResponse, routing::post};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::discovery::{DiscoverySource, ServerMetadata};
+
+    async fn mcp_stub(Json(request): Json<Value>) -> axum::response::Response {
+        let id = request["id"].clone();
+        match request["method"].as_str() {
+            Some("initialize") => Json(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {"tools": {}},
+                    "serverInfo": {"name": "stub", "version": "1.0.0"}
+                }
+            }))
+            .into_response(),
+            Some("tools/list") => Json(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {"tools": [{"name": "a"}, {"name": "b"}, {"name": "c"}]}
+            }))
+            .into_response(),
+            _ => StatusCode::ACCEPTED.into_response(),
+        }
+    }
+
+    fn http_server(name: &str, url: String) -> DiscoveredServer {
+        DiscoveredServer {
+            name: name.to_string(),
+            description: String::new(),
+            source: DiscoverySource::Mdns,
+            transport: TransportConfig::Http {
+                http_url: url,
+                streamable_http: true,
+                protocol_version: None,
+            },
+            metadata: ServerMetadata::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn probe_all_records_live_and_dead_servers() {
+        // GIVEN: a streamable-HTTP MCP stub and a port nobody listens on
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/mcp", post(mcp_stub))
+            .route("/mcp/", post(mcp_stub));
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead_addr = closed.local_addr().unwrap();
+        drop(closed);
+        let mut servers = vec![
+            http_server("live", format!("http://{addr}/mcp")),
+            http_server("dead", format!("http://{dead_addr}/mcp")),
+        ];
+
+        // WHEN: probing both
+        probe_all(&mut servers, Duration::from_secs(5)).await;
+
+        // THEN: the live one reports its version and tools
+        let live = servers[0].metadata.probe.clone().unwrap();
+        assert!(live.reachable, "{live:?}");
+        assert_eq!(live.protocol_version.as_deref(), Some("2025-06-18"));
+        assert_eq!(live.tool_count, Some(3));
+        assert!(servers[0].to_backend_config().enabled);
+
+        // AND: the dead one is marked and written disabled
+        let dead = servers[1].metadata.probe.clone().unwrap();
+        assert!(!dead.reachable);
+        assert!(dead.error.is_some());
+        assert!(!servers[1].to_backend_config().enabled);
+
+        server.abort();
+    }
+}
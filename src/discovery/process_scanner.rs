@@ -198,6 +198,8 @@ impl ProcessScanner {
                             port,
                             command: Some(command.clone()),
                             working_dir: None,
+                            expires_at: None,
+                            probe: None,
                         },
                     });
 
@@ -265,6 +267,8 @@ impl ProcessScanner {
                             port,
                             command: Some(command.to_string()),
                             working_dir: None,
+                            expires_at: None,
+                            probe: None,
                         },
                     });
 
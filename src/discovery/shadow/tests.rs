@@ -31,6 +31,8 @@ fn stdio_server(
             port,
             command: Some(command.to_string()),
             working_dir: None,
+            expires_at: None,
+            probe: None,
         },
     }
 }
@@ -58,6 +60,8 @@ fn http_server(
             port,
             command: None,
             working_dir: None,
+            expires_at: None,
+            probe: None,
         },
     }
 }
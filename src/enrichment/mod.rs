@@ -0,0 +1,338 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Offline LLM enrichment of backend tool descriptions.
+//!
+//! `mcp-gateway enrich` sends every backend tool definition to an LLM once,
+//! in batches, and writes the returned keywords and summaries to an
+//! [`EnrichmentOverlay`] file. At runtime search reads the overlay and uses
+//! those keywords in place of the per-request [`crate::autotag`] heuristics.
+//!
+//! Each entry records a hash of the description it was generated from, so a
+//! tool whose description changed falls back to the heuristics until the next
+//! run, and re-runs only pay for new or changed tools.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::config::EnrichmentConfig;
+use crate::hashing::sha256_hex;
+use crate::{Error, Result};
+
+/// Current overlay file format version.
+pub const OVERLAY_VERSION: u32 = 1;
+
+/// Descriptions shorter than this (in characters) are extended with the
+/// generated summary; longer ones are kept as written.
+const SHORT_DESCRIPTION_CHARS: usize = 40;
+
+/// Maximum keywords kept per tool.
+const MAX_KEYWORDS: usize = 10;
+
+// ============================================================================
+// Overlay
+// ============================================================================
+
+/// Enrichment overlay file (JSON).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnrichmentOverlay {
+    /// File format version.
+    pub version: u32,
+    /// Model that generated the entries.
+    #[serde(default)]
+    pub model: String,
+    /// RFC 3339 timestamp of the last run.
+    #[serde(default)]
+    pub generated_at: String,
+    /// Entries keyed by `server:tool`.
+    #[serde(default)]
+    pub tools: BTreeMap<String, ToolEnrichment>,
+}
+
+/// Generated keywords and summary for one tool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolEnrichment {
+    /// One-sentence summary of what the tool does.
+    #[serde(default)]
+    pub summary: String,
+    /// Search keywords, lowercase.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Hash of the description the entry was generated from.
+    pub source_hash: String,
+}
+
+impl Default for EnrichmentOverlay {
+    fn default() -> Self {
+        Self {
+            version: OVERLAY_VERSION,
+            model: String::new(),
+            generated_at: String::new(),
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+impl EnrichmentOverlay {
+    /// Read an overlay file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the file cannot be read or parsed, or was
+    /// written by a newer format version.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read enrichment overlay {}: {e}",
+                path.display()
+            ))
+        })?;
+        let overlay: Self = serde_json::from_str(&content)
+            .map_err(|e| Error::Config(format!("Invalid enrichment overlay: {e}")))?;
+        if overlay.version > OVERLAY_VERSION {
+            return Err(Error::Config(format!(
+                "Enrichment overlay version {} is newer than supported ({OVERLAY_VERSION})",
+                overlay.version
+            )));
+        }
+        Ok(overlay)
+    }
+
+    /// Write the overlay as pretty-printed JSON, creating parent directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The entry for `server:tool` if it was generated from `description`.
+    #[must_use]
+    pub fn get(&self, server: &str, tool: &str, description: &str) -> Option<&ToolEnrichment> {
+        self.tools
+            .get(&format!("{server}:{tool}"))
+            .filter(|entry| entry.source_hash == source_hash(description))
+    }
+
+    /// Number of tools with entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    /// Returns `true` when the overlay has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+}
+
+impl ToolEnrichment {
+    /// Enrich `description` with the generated summary and keywords.
+    ///
+    /// The keywords are appended as a `[keywords: …]` section (so the
+    /// heuristic tagger leaves the result alone). Empty or very short
+    /// descriptions are extended with the summary.
+    #[must_use]
+    pub fn apply(&self, description: &str) -> String {
+        let description = description.trim_end();
+        let summary = self.summary.trim();
+        let base = if summary.is_empty() || description.chars().count() >= SHORT_DESCRIPTION_CHARS {
+            description.to_string()
+        } else if description.is_empty() {
+            summary.to_string()
+        } else {
+            format!("{description} — {summary}")
+        };
+        if self.keywords.is_empty() {
+            base
+        } else {
+            format!("{base} [keywords: {}]", self.keywords.join(", "))
+        }
+    }
+}
+
+/// Hash identifying the description an entry was generated from.
+#[must_use]
+pub fn source_hash(description: &str) -> String {
+    sha256_hex(description.trim().as_bytes())[..16].to_string()
+}
+
+// ============================================================================
+// LLM client
+// ============================================================================
+
+/// A tool definition sent for enrichment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolSource {
+    /// Backend name.
+    pub server: String,
+    /// Tool name.
+    pub name: String,
+    /// Description as published by the backend.
+    pub description: String,
+}
+
+impl ToolSource {
+    /// Overlay key (`server:tool`).
+    #[must_use]
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.server, self.name)
+    }
+}
+
+/// Calls an OpenAI-compatible chat completions API.
+pub struct EnrichmentClient {
+    http: reqwest::Client,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl EnrichmentClient {
+    /// Build a client from the `enrichment` config, reading the API key
+    /// from `api_key_env`.
+    #[must_use]
+    pub fn from_config(config: &EnrichmentConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            endpoint: format!("{}/chat/completions", config.base_url.trim_end_matches('/')),
+            model: config.model.clone(),
+            api_key: std::env::var(&config.api_key_env)
+                .ok()
+                .filter(|k| !k.is_empty()),
+        }
+    }
+
+    /// Model used for requests.
+    #[must_use]
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Enrich one batch of tools with a single LLM request.
+    ///
+    /// Tools the model skipped are missing from the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the reply is not the
+    /// expected JSON.
+    pub async fn enrich(&self, tools: &[ToolSource]) -> Result<Vec<(String, ToolEnrichment)>> {
+        let body = json!({
+            "model": self.model,
+            "temperature": 0,
+            "response_format": {"type": "json_object"},
+            "messages": [
+                {"role": "system", "content": SYSTEM_PROMPT},
+                {"role": "user", "content": build_prompt(tools)},
+            ],
+        });
+        let mut request = self.http.post(&self.endpoint).json(&body);
+        if let Some(ref key) = self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(Error::Transport(format!(
+                "Enrichment LLM returned {status}: {}",
+                detail.chars().take(200).collect::<String>()
+            )));
+        }
+        let reply: Value = response.json().await?;
+        let content = reply["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| Error::Protocol("Enrichment LLM reply has no content".to_string()))?;
+        parse_reply(content, tools)
+    }
+}
+
+const SYSTEM_PROMPT: &str = "You write search metadata for tools used by AI agents. \
+For each tool, give a one-sentence summary of what it does and up to 10 lowercase \
+search keywords: synonyms, domain terms and likely user intents that are not \
+obvious from the name. Write summaries and keywords in English even when the \
+description is in another language. Reply with JSON only: \
+{\"tools\": [{\"tool\": \"<server:name>\", \"summary\": \"...\", \"keywords\": [\"...\"]}]}";
+
+/// User prompt listing the tools of one batch.
+#[must_use]
+pub fn build_prompt(tools: &[ToolSource]) -> String {
+    let listed: Vec<Value> = tools
+        .iter()
+        .map(|t| json!({"tool": t.key(), "description": t.description}))
+        .collect();
+    format!(
+        "Tools:\n{}",
+        serde_json::to_string_pretty(&listed).unwrap_or_default()
+    )
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    tools: Vec<ReplyEntry>,
+}
+
+#[derive(Deserialize)]
+struct ReplyEntry {
+    tool: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    keywords: Vec<String>,
+}
+
+/// Parse the model's JSON reply into overlay entries for `tools`.
+///
+/// Tolerates Markdown code fences; entries for tools not in the batch are
+/// ignored and keywords are lowercased, deduplicated and capped.
+///
+/// # Errors
+///
+/// Returns [`Error::Protocol`] if the reply is not the expected JSON.
+pub fn parse_reply(content: &str, tools: &[ToolSource]) -> Result<Vec<(String, ToolEnrichment)>> {
+    let json = content
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    let reply: Reply = serde_json::from_str(json)
+        .map_err(|e| Error::Protocol(format!("Enrichment LLM reply is not valid JSON: {e}")))?;
+
+    let mut entries = Vec::new();
+    for entry in reply.tools {
+        let Some(source) = tools.iter().find(|t| t.key() == entry.tool) else {
+            continue;
+        };
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in entry.keywords {
+            let keyword = keyword.trim().to_lowercase().replace([',', '[', ']'], " ");
+            let keyword = keyword.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !keyword.is_empty() && !keywords.contains(&keyword) {
+                keywords.push(keyword);
+            }
+        }
+        keywords.truncate(MAX_KEYWORDS);
+        entries.push((
+            entry.tool,
+            ToolEnrichment {
+                summary: entry.summary.trim().to_string(),
+                keywords,
+                source_hash: source_hash(&source.description),
+            },
+        ));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests;
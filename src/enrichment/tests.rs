@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use axum::routing::post;
+use axum::{Json, Router};
+
+use super::*;
+
+fn tool(server: &str, name: &str, description: &str) -> ToolSource {
+    ToolSource {
+        server: server.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+    }
+}
+
+fn entry(summary: &str, keywords: &[&str], description: &str) -> ToolEnrichment {
+    ToolEnrichment {
+        summary: summary.to_string(),
+        keywords: keywords.iter().map(|k| (*k).to_string()).collect(),
+        source_hash: source_hash(description),
+    }
+}
+
+// ── ToolEnrichment::apply ────────────────────────────────────────────
+
+#[test]
+fn apply_appends_keywords_to_long_descriptions() {
+    let desc = "Creates an invoice for a customer in the accounting system.";
+    let enriched = entry("Bill a customer.", &["billing", "lasku"], desc).apply(desc);
+    assert_eq!(enriched, format!("{desc} [keywords: billing, lasku]"));
+}
+
+#[test]
+fn apply_extends_short_descriptions_with_summary() {
+    let e = entry(
+        "Search GitHub issues and pull requests.",
+        &["github"],
+        "Search",
+    );
+    assert_eq!(
+        e.apply("Search"),
+        "Search — Search GitHub issues and pull requests. [keywords: github]"
+    );
+    assert_eq!(
+        e.apply(""),
+        "Search GitHub issues and pull requests. [keywords: github]"
+    );
+}
+
+#[test]
+fn applied_description_is_left_alone_by_autotag() {
+    let enriched = entry("", &["billing"], "x").apply("Creates invoices");
+    assert_eq!(crate::autotag::enrich_description(&enriched), enriched);
+}
+
+// ── EnrichmentOverlay ────────────────────────────────────────────────
+
+#[test]
+fn overlay_entry_is_ignored_once_description_changes() {
+    let mut overlay = EnrichmentOverlay::default();
+    overlay.tools.insert(
+        "erp:create_invoice".to_string(),
+        entry("Bill a customer.", &["billing"], "Luo lasku"),
+    );
+
+    assert!(overlay.get("erp", "create_invoice", "Luo lasku ").is_some());
+    assert!(
+        overlay
+            .get("erp", "create_invoice", "Luo uusi lasku")
+            .is_none()
+    );
+    assert!(overlay.get("erp", "other", "Luo lasku").is_none());
+}
+
+#[test]
+fn overlay_round_trips_through_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested/enrichment.json");
+    let mut overlay = EnrichmentOverlay {
+        model: "test-model".to_string(),
+        ..EnrichmentOverlay::default()
+    };
+    overlay
+        .tools
+        .insert("a:b".to_string(), entry("Does b.", &["bee"], "b"));
+
+    overlay.save(&path).unwrap();
+
+    assert_eq!(EnrichmentOverlay::load(&path).unwrap(), overlay);
+}
+
+#[test]
+fn load_rejects_newer_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("enrichment.json");
+    std::fs::write(&path, r#"{"version": 99, "tools": {}}"#).unwrap();
+    let err = EnrichmentOverlay::load(&path).unwrap_err().to_string();
+    assert!(err.contains("newer"), "{err}");
+}
+
+// ── parse_reply ──────────────────────────────────────────────────────
+
+#[test]
+fn parse_reply_normalizes_keywords_and_skips_unknown_tools() {
+    let tools = [tool("erp", "create_invoice", "Luo lasku")];
+    let content = r#"```json
+{"tools": [
+  {"tool": "erp:create_invoice", "summary": " Create an invoice. ",
+   "keywords": ["Billing", "billing", "Accounts,  Receivable", ""]},
+  {"tool": "erp:invented", "summary": "x", "keywords": ["y"]}
+]}
+```"#;
+
+    let entries = parse_reply(content, &tools).unwrap();
+
+    assert_eq!(entries.len(), 1);
+    let (key, e) = &entries[0];
+    assert_eq!(key, "erp:create_invoice");
+    assert_eq!(e.summary, "Create an invoice.");
+    assert_eq!(e.keywords, ["billing", "accounts receivable"]);
+    assert_eq!(e.source_hash, source_hash("Luo lasku"));
+}
+
+#[test]
+fn parse_reply_rejects_non_json() {
+    assert!(parse_reply("Sure! Here are your keywords.", &[]).is_err());
+}
+
+#[test]
+fn build_prompt_lists_every_tool() {
+    let prompt = build_prompt(&[tool("a", "one", "First"), tool("b", "two", "Second")]);
+    assert!(prompt.contains("a:one") && prompt.contains("First"));
+    assert!(prompt.contains("b:two") && prompt.contains("Second"));
+}
+
+// ── EnrichmentClient ─────────────────────────────────────────────────
+
+#[tokio::test]
+async fn client_sends_batch_and_parses_reply() {
+    async fn completions(Json(body): Json<Value>) -> Json<Value> {
+        assert_eq!(body["model"], "test-model");
+        let prompt = body["messages"][1]["content"].as_str().unwrap();
+        assert!(prompt.contains("github:search"));
+        Json(json!({
+            "choices": [{"message": {"content": json!({"tools": [
+                {"tool": "github:search", "summary": "Search code.", "keywords": ["code"]}
+            ]}).to_string()}}]
+        }))
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new().route("/v1/chat/completions", post(completions)),
+        )
+        .await
+        .unwrap();
+    });
+
+    let client = EnrichmentClient::from_config(&EnrichmentConfig {
+        base_url: format!("http://{addr}/v1/"),
+        model: "test-model".to_string(),
+        api_key_env: "MCP_GATEWAY_TEST_UNSET_ENRICHMENT_KEY".to_string(),
+        ..EnrichmentConfig::default()
+    });
+    let entries = client
+        .enrich(&[tool("github", "search", "Search")])
+        .await
+        .unwrap();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].1.keywords, ["code"]);
+}
@@ -138,6 +138,32 @@ impl Error {
         }
     }
 
+    /// Short `snake_case` name of the variant, for logs and analytics.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config",
+            Self::ConfigValidation(_) => "config_validation",
+            Self::ConfigWatcher(_) => "config_watcher",
+            Self::CapabilityHashMismatch { .. } => "capability_hash_mismatch",
+            Self::BackendNotFound(_) => "backend_not_found",
+            Self::BackendUnavailable(_) => "backend_unavailable",
+            Self::CircuitOpen(_) => "circuit_open",
+            Self::ToolNotFound(_) => "tool_not_found",
+            Self::BackendTimeout(_) => "backend_timeout",
+            Self::Transport(_) => "transport",
+            Self::Protocol(_) => "protocol",
+            Self::OAuth(_) => "oauth",
+            Self::Tls(_) => "tls",
+            Self::JsonRpc { .. } => "json_rpc",
+            Self::Io(_) => "io",
+            Self::Json(_) => "json",
+            Self::Http(_) => "http",
+            Self::Shutdown => "shutdown",
+            Self::Internal(_) => "internal",
+        }
+    }
+
     /// Convert to JSON-RPC error code
     #[must_use]
     pub fn to_rpc_code(&self) -> i32 {
@@ -8,10 +8,11 @@ mod rate_limiter;
 mod retry;
 
 pub use circuit_breaker::{
-    CircuitBreaker, CircuitBreakerStats, CircuitState, build_circuit_breaker_error,
+    BreakerOpenEvent, CircuitBreaker, CircuitBreakerStats, CircuitState,
+    build_circuit_breaker_error,
 };
 pub use health::{HealthMetrics, HealthTracker};
-pub use rate_limiter::RateLimiter;
+pub use rate_limiter::{ClientRateLimiter, KeyedRateLimiter, RateLimitExceeded, RateLimiter};
 pub use retry::{RetryPolicy, with_retry};
 
 use std::sync::Arc;
@@ -2,28 +2,35 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Rate limiting implementation
 
+use std::collections::HashMap;
 use std::num::NonZeroU32;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
+use dashmap::DashMap;
+use governor::clock::{Clock, DefaultClock};
 use governor::{Quota, RateLimiter as GovernorLimiter};
 use parking_lot::Mutex;
+use serde_json::json;
+use tracing::warn;
 
-use crate::config::RateLimitConfig;
+use crate::Error;
+use crate::cluster::SharedState;
+use crate::config::{RateLimitConfig, RateSpec};
+
+type DirectLimiter =
+    GovernorLimiter<governor::state::NotKeyed, governor::state::InMemoryState, DefaultClock>;
+
+/// Wildcard matching any client or backend in `rate_limit.limits`.
+const ANY: &str = "*";
 
 /// Rate limiter for request throttling
 pub struct RateLimiter {
     /// Whether rate limiting is enabled
     enabled: AtomicBool,
     /// Internal rate limiter (lazy initialized)
-    inner: Mutex<
-        Option<
-            GovernorLimiter<
-                governor::state::NotKeyed,
-                governor::state::InMemoryState,
-                governor::clock::DefaultClock,
-            >,
-        >,
-    >,
+    inner: Mutex<Option<DirectLimiter>>,
     /// Quota configuration
     rps: u32,
     burst: u32,
@@ -61,3 +68,323 @@ impl RateLimiter {
         self.enabled.store(enabled, Ordering::Relaxed);
     }
 }
+
+/// Token buckets dimensioned by client identity and backend
+/// (`failsafe.rate_limit.limits`).
+///
+/// Each `(client, backend)` pair owns its own bucket, so one tenant draining
+/// its GitHub budget cannot starve another. Limits resolve most-specific first:
+/// exact client and backend, then `client/*`, `*/backend`, and `*/*`. Pairs
+/// with no matching rule are unlimited.
+///
+/// With [`with_shared`](Self::with_shared) the buckets live in the cluster
+/// state, so all replicas drain one budget; the local buckets take over
+/// while it is unreachable.
+pub struct ClientRateLimiter {
+    limits: HashMap<String, HashMap<String, RateSpec>>,
+    buckets: DashMap<(String, String), Arc<DirectLimiter>>,
+    shared: Option<Arc<dyn SharedState>>,
+}
+
+/// A call refused by [`ClientRateLimiter::check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitExceeded {
+    /// Client identity the bucket belongs to.
+    pub client: String,
+    /// Backend the bucket belongs to.
+    pub backend: String,
+    /// The configured rate.
+    pub limit: RateSpec,
+    /// Time until the next call would be admitted.
+    pub retry_after: Duration,
+}
+
+impl RateLimitExceeded {
+    /// Human-readable rejection message.
+    #[must_use]
+    pub fn message(&self) -> String {
+        format!(
+            "Rate limit exceeded for client '{}' on backend '{}' ({}); retry after {}ms",
+            self.client,
+            self.backend,
+            self.limit,
+            self.retry_after.as_millis()
+        )
+    }
+
+    /// Structured JSON-RPC `error.data` payload carrying the reset time.
+    #[must_use]
+    pub fn data(&self) -> serde_json::Value {
+        let reset_at =
+            chrono::Utc::now() + chrono::Duration::from_std(self.retry_after).unwrap_or_default();
+        json!({
+            "error": "rate_limited",
+            "client": self.client,
+            "backend": self.backend,
+            "limit": self.limit.to_string(),
+            "retry_after_ms": u64::try_from(self.retry_after.as_millis()).unwrap_or(u64::MAX),
+            "reset_at": reset_at.to_rfc3339(),
+        })
+    }
+}
+
+impl From<RateLimitExceeded> for Error {
+    fn from(exceeded: RateLimitExceeded) -> Self {
+        Self::JsonRpc {
+            code: -32000,
+            message: exceeded.message(),
+            data: Some(exceeded.data()),
+        }
+    }
+}
+
+impl ClientRateLimiter {
+    /// Create a limiter from `rate_limit.limits` (client → backend → rate).
+    #[must_use]
+    pub fn new(limits: HashMap<String, HashMap<String, RateSpec>>) -> Self {
+        Self {
+            limits,
+            buckets: DashMap::new(),
+            shared: None,
+        }
+    }
+
+    /// Keep the buckets in the cluster state shared by all replicas.
+    #[must_use]
+    pub fn with_shared(mut self, shared: Arc<dyn SharedState>) -> Self {
+        self.shared = Some(shared);
+        self
+    }
+
+    /// Whether any per-client limit is configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.limits.values().all(HashMap::is_empty)
+    }
+
+    /// The rate that applies to `client` calling `backend`, if any.
+    #[must_use]
+    pub fn limit_for(&self, client: &str, backend: &str) -> Option<RateSpec> {
+        [(client, backend), (client, ANY), (ANY, backend), (ANY, ANY)]
+            .into_iter()
+            .find_map(|(c, b)| self.limits.get(c)?.get(b).copied())
+    }
+
+    /// Take one token from the `(client, backend)` bucket.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RateLimitExceeded`] with the wait until the next token when
+    /// the bucket is empty.
+    pub fn check(&self, client: &str, backend: &str) -> Result<(), RateLimitExceeded> {
+        let Some(limit) = self.limit_for(client, backend) else {
+            return Ok(());
+        };
+        let exceeded = |retry_after| RateLimitExceeded {
+            client: client.to_string(),
+            backend: backend.to_string(),
+            limit,
+            retry_after,
+        };
+        if let Some(shared) = &self.shared {
+            match shared.take_token(&format!("rate:{client}:{backend}"), limit) {
+                Ok(taken) => return taken.map_err(exceeded),
+                Err(e) => warn!(error = %e, "Shared rate limit unavailable; using local bucket"),
+            }
+        }
+        let bucket = Arc::clone(
+            &self
+                .buckets
+                .entry((client.to_string(), backend.to_string()))
+                .or_insert_with(|| Arc::new(GovernorLimiter::direct(quota(limit)))),
+        );
+        bucket
+            .check()
+            .map_err(|not_until| exceeded(not_until.wait_time_from(DefaultClock::default().now())))
+    }
+}
+
+/// Buckets a [`KeyedRateLimiter`] may hold before idle ones are dropped.
+const MAX_KEYED_BUCKETS: usize = 10_000;
+
+/// Token buckets keyed by name, with the rate supplied on each check.
+///
+/// Used for limits declared next to what they protect, such as a
+/// capability's `resilience.rate_limit`. A bucket is rebuilt when its rate
+/// changes (for example after a hot reload). Once [`MAX_KEYED_BUCKETS`] keys
+/// are held, buckets unused for a whole period are dropped: they have refilled
+/// completely, so a fresh bucket behaves the same.
+#[derive(Default)]
+pub struct KeyedRateLimiter {
+    buckets: DashMap<String, KeyedBucket>,
+}
+
+struct KeyedBucket {
+    limit: RateSpec,
+    limiter: Arc<DirectLimiter>,
+    last_used: Instant,
+}
+
+impl KeyedBucket {
+    fn new(limit: RateSpec, now: Instant) -> Self {
+        Self {
+            limit,
+            limiter: Arc::new(GovernorLimiter::direct(quota(limit))),
+            last_used: now,
+        }
+    }
+}
+
+impl KeyedRateLimiter {
+    /// Take one token from the bucket of `key`, limited to `limit`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the wait until the next token when the bucket is empty.
+    pub fn check(&self, key: &str, limit: RateSpec) -> Result<(), Duration> {
+        let now = Instant::now();
+        if self.buckets.len() >= MAX_KEYED_BUCKETS && !self.buckets.contains_key(key) {
+            self.evict_idle(now);
+        }
+        let bucket = {
+            let mut entry = self
+                .buckets
+                .entry(key.to_string())
+                .or_insert_with(|| KeyedBucket::new(limit, now));
+            if entry.limit != limit {
+                *entry = KeyedBucket::new(limit, now);
+            }
+            entry.last_used = now;
+            Arc::clone(&entry.limiter)
+        };
+        bucket
+            .check()
+            .map_err(|not_until| not_until.wait_time_from(DefaultClock::default().now()))
+    }
+
+    /// Drop the buckets not used for a whole period before `now`.
+    fn evict_idle(&self, now: Instant) {
+        self.buckets
+            .retain(|_, bucket| now.duration_since(bucket.last_used) < bucket.limit.period());
+    }
+}
+
+/// Quota holding `count` tokens, refilled evenly across the period.
+fn quota(limit: RateSpec) -> Quota {
+    let count = NonZeroU32::new(limit.count).unwrap_or(NonZeroU32::MIN);
+    Quota::with_period(limit.period() / count.get())
+        .unwrap_or_else(|| Quota::per_second(count))
+        .allow_burst(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(rules: &[(&str, &str, &str)]) -> ClientRateLimiter {
+        let mut limits: HashMap<String, HashMap<String, RateSpec>> = HashMap::new();
+        for (client, backend, rate) in rules {
+            limits
+                .entry((*client).to_string())
+                .or_default()
+                .insert((*backend).to_string(), rate.parse().unwrap());
+        }
+        ClientRateLimiter::new(limits)
+    }
+
+    #[test]
+    fn buckets_are_isolated_per_client() {
+        // GIVEN a wildcard limit of 2/min on github
+        let l = limiter(&[("*", "github", "2/min")]);
+        // WHEN client a drains its bucket
+        assert!(l.check("a", "github").is_ok());
+        assert!(l.check("a", "github").is_ok());
+        let err = l.check("a", "github").unwrap_err();
+        // THEN a is refused with a reset time, b is unaffected
+        assert_eq!(err.client, "a");
+        assert!(err.retry_after > Duration::ZERO);
+        assert!(l.check("b", "github").is_ok());
+    }
+
+    #[test]
+    fn specific_rule_wins_over_wildcards() {
+        let l = limiter(&[
+            ("*", "*", "1/min"),
+            ("a", "*", "5/min"),
+            ("a", "github", "30/min"),
+        ]);
+        assert_eq!(l.limit_for("a", "github").unwrap().to_string(), "30/min");
+        assert_eq!(l.limit_for("a", "jira").unwrap().to_string(), "5/min");
+        assert_eq!(l.limit_for("b", "github").unwrap().to_string(), "1/min");
+    }
+
+    #[test]
+    fn unmatched_pairs_are_unlimited() {
+        let l = limiter(&[("a", "github", "1/min")]);
+        for _ in 0..10 {
+            assert!(l.check("b", "github").is_ok());
+            assert!(l.check("a", "jira").is_ok());
+        }
+    }
+
+    #[test]
+    fn exceeded_converts_to_structured_json_rpc_error() {
+        let l = limiter(&[("a", "github", "1/hour")]);
+        l.check("a", "github").unwrap();
+        let err: Error = l.check("a", "github").unwrap_err().into();
+        let Error::JsonRpc {
+            code,
+            message,
+            data: Some(data),
+        } = err
+        else {
+            panic!("expected JsonRpc error with data");
+        };
+        assert_eq!(code, -32000);
+        assert!(message.contains("Rate limit exceeded"));
+        assert_eq!(data["limit"], "1/hour");
+        assert!(data["retry_after_ms"].as_u64().unwrap() > 0);
+        assert!(data["reset_at"].is_string());
+    }
+
+    #[test]
+    fn shared_buckets_span_limiters() {
+        // GIVEN: two replicas sharing one cluster state, 2/min for a on github
+        let shared: Arc<dyn SharedState> = Arc::new(crate::cluster::MemoryState::new());
+        let replica_a = limiter(&[("a", "github", "2/min")]).with_shared(Arc::clone(&shared));
+        let replica_b = limiter(&[("a", "github", "2/min")]).with_shared(shared);
+
+        // WHEN: each replica admits one call
+        replica_a.check("a", "github").unwrap();
+        replica_b.check("a", "github").unwrap();
+
+        // THEN: the third call is refused on either replica
+        assert!(replica_a.check("a", "github").is_err());
+        assert!(replica_b.check("a", "github").is_err());
+    }
+
+    #[test]
+    fn keyed_limiter_keeps_a_bucket_per_key_and_rebuilds_on_new_rate() {
+        let l = KeyedRateLimiter::default();
+        let one: RateSpec = "1/hour".parse().unwrap();
+        l.check("search", one).unwrap();
+        assert!(l.check("search", one).unwrap_err() > Duration::ZERO);
+        assert!(l.check("weather", one).is_ok());
+        assert!(l.check("search", "5/hour".parse().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn keyed_limiter_evicts_only_buckets_idle_for_a_whole_period() {
+        // GIVEN: one per-second and one per-hour bucket, both just used
+        let l = KeyedRateLimiter::default();
+        l.check("fast", "1/sec".parse().unwrap()).unwrap();
+        l.check("slow", "1/hour".parse().unwrap()).unwrap();
+
+        // WHEN: eviction runs two seconds later
+        l.evict_idle(Instant::now() + Duration::from_secs(2));
+
+        // THEN: only the refilled per-second bucket is gone
+        assert!(!l.buckets.contains_key("fast"));
+        assert!(l.buckets.contains_key("slow"));
+    }
+}
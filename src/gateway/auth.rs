@@ -467,6 +467,7 @@ pub async fn auth_middleware(
             return deny;
         }
         debug!(client = %client.name, path = %path, "Authenticated via static key");
+        crate::access_log::annotate(|r| r.client = Some(client.name.clone()));
         request.extensions_mut().insert(client);
         return next.run(request).await;
     }
@@ -482,6 +483,7 @@ pub async fn auth_middleware(
         request
             .extensions_mut()
             .insert(identity_token.identity.clone());
+        crate::access_log::annotate(|r| r.client = Some(client.name.clone()));
         request.extensions_mut().insert(client);
         return next.run(request).await;
     }
@@ -501,6 +503,7 @@ pub async fn auth_middleware(
         }
         debug!(client = %client.name, path = %path, "Authenticated via delegated OIDC bearer");
         request.extensions_mut().insert(identity);
+        crate::access_log::annotate(|r| r.client = Some(client.name.clone()));
         request.extensions_mut().insert(client);
         return next.run(request).await;
     }
@@ -0,0 +1,235 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Caller identity forwarded to backends that opt in.
+//!
+//! [`caller_identity_middleware`] runs inside the authentication layers and
+//! collects what they established — the API key / client name, the key
+//! server's OIDC identity and the mTLS certificate identity — into a
+//! [`CallerIdentity`] held in the [`CALLER`] task-local. When a backend has
+//! `forward_identity` configured, [`apply`] adds it to each outbound request
+//! as headers and/or `_meta` (see [`ForwardIdentityConfig`]).
+//!
+//! Only values the gateway itself verified are forwarded; identity headers a
+//! client sends are never passed through.
+
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::config::ForwardIdentityConfig;
+use crate::gateway::auth::AuthenticatedClient;
+use crate::key_server::oidc::VerifiedIdentity;
+use crate::mtls::CertIdentity;
+
+/// `_meta` key the identity is added under.
+pub const META_KEY: &str = "io.mcp-gateway/caller";
+
+tokio::task_local! {
+    /// Identity of the caller of the request being served.
+    pub static CALLER: CallerIdentity;
+}
+
+/// Who is calling, as established by the gateway's authentication.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CallerIdentity {
+    /// API key / client name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+    /// OIDC subject.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// OIDC email.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// mTLS certificate identity (SPIFFE ID, else CN).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert: Option<String>,
+}
+
+impl CallerIdentity {
+    /// Collect the identity from the request extensions set by the auth layers.
+    #[must_use]
+    pub fn from_request(request: &Request) -> Self {
+        let extensions = request.extensions();
+        let verified = extensions.get::<VerifiedIdentity>();
+        let cert = extensions.get::<CertIdentity>();
+        Self {
+            client: extensions
+                .get::<AuthenticatedClient>()
+                .map(|c| c.name.clone()),
+            subject: verified.map(|v| v.subject.clone()),
+            email: verified.map(|v| v.email.clone()).filter(|e| !e.is_empty()),
+            cert: cert.and_then(|c| c.spiffe_id.clone().or_else(|| c.common_name.clone())),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The identity of the current request's caller, if any.
+#[must_use]
+pub fn current() -> Option<CallerIdentity> {
+    CALLER.try_with(Clone::clone).ok()
+}
+
+/// Axum middleware installing the [`CallerIdentity`] for the request.
+pub async fn caller_identity_middleware(request: Request, next: Next) -> Response {
+    let caller = CallerIdentity::from_request(&request);
+    if caller.is_empty() {
+        return next.run(request).await;
+    }
+    CALLER.scope(caller, next.run(request)).await
+}
+
+/// Add the current caller's identity to a backend request per `config`.
+///
+/// The configured header names and the [`META_KEY`] `_meta` entry are
+/// cleared first, with or without a caller, so a value the client supplied
+/// never reaches the backend looking verified. Header values that are not
+/// valid HTTP header values are skipped.
+pub fn apply(
+    config: &ForwardIdentityConfig,
+    params: &mut Option<Value>,
+    headers: &mut Vec<(String, String)>,
+) {
+    let names = &config.headers;
+    let configured = [&names.client, &names.subject, &names.email, &names.cert];
+    headers.retain(|(existing, _)| {
+        !configured
+            .iter()
+            .filter_map(|name| name.as_deref())
+            .any(|name| existing.eq_ignore_ascii_case(name))
+    });
+    if let Some(meta) = params
+        .as_mut()
+        .and_then(|params| params.get_mut("_meta"))
+        .and_then(Value::as_object_mut)
+    {
+        meta.remove(META_KEY);
+    }
+
+    let Some(caller) = current() else {
+        return;
+    };
+    for (name, value) in
+        configured
+            .into_iter()
+            .zip([&caller.client, &caller.subject, &caller.email, &caller.cert])
+    {
+        if let (Some(name), Some(value)) = (name, value)
+            && HeaderValue::from_str(value).is_ok()
+        {
+            headers.push((name.clone(), value.clone()));
+        }
+    }
+    if config.meta {
+        let params = params.get_or_insert_with(|| json!({}));
+        if let Some(obj) = params.as_object_mut() {
+            let meta = obj.entry("_meta").or_insert_with(|| json!({}));
+            if let Some(meta) = meta.as_object_mut() {
+                meta.insert(META_KEY.to_string(), json!(caller));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ForwardIdentityHeaders;
+
+    fn caller() -> CallerIdentity {
+        CallerIdentity {
+            client: Some("ci-bot".to_string()),
+            email: Some("dev@example.com".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_adds_configured_headers_and_meta() {
+        // GIVEN: a backend forwarding the client name and email as headers and in _meta
+        let config = ForwardIdentityConfig {
+            headers: ForwardIdentityHeaders {
+                client: Some("X-Gateway-Client".to_string()),
+                email: Some("X-Gateway-User-Email".to_string()),
+                subject: Some("X-Gateway-User".to_string()),
+                cert: None,
+            },
+            meta: true,
+        };
+        let mut params = Some(json!({ "name": "search", "arguments": {} }));
+        let mut headers = vec![("x-gateway-client".to_string(), "spoofed".to_string())];
+
+        // WHEN: applied for a caller without an OIDC subject
+        CALLER
+            .scope(caller(), async {
+                apply(&config, &mut params, &mut headers);
+            })
+            .await;
+
+        // THEN: present attributes are sent, replacing same-named headers
+        assert_eq!(
+            headers,
+            [
+                ("X-Gateway-Client".to_string(), "ci-bot".to_string()),
+                (
+                    "X-Gateway-User-Email".to_string(),
+                    "dev@example.com".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            params.unwrap()["_meta"][META_KEY],
+            json!({ "client": "ci-bot", "email": "dev@example.com" })
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_is_a_no_op_without_a_caller() {
+        let config = ForwardIdentityConfig {
+            meta: true,
+            ..Default::default()
+        };
+        let mut params = None;
+        let mut headers = Vec::new();
+
+        apply(&config, &mut params, &mut headers);
+
+        assert!(params.is_none());
+        assert!(headers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn apply_strips_client_supplied_identity_without_a_caller() {
+        // GIVEN: a request carrying a forged identity header and _meta entry
+        let config = ForwardIdentityConfig {
+            headers: ForwardIdentityHeaders {
+                client: Some("X-Gateway-Client".to_string()),
+                subject: Some("X-Gateway-User".to_string()),
+                ..Default::default()
+            },
+            meta: true,
+        };
+        let mut params = Some(json!({
+            "name": "search",
+            "_meta": { (META_KEY): { "subject": "admin" }, "progressToken": 1 }
+        }));
+        let mut headers = vec![
+            ("x-gateway-user".to_string(), "admin".to_string()),
+            ("Accept".to_string(), "application/json".to_string()),
+        ];
+
+        // WHEN: applied outside any authenticated caller
+        apply(&config, &mut params, &mut headers);
+
+        // THEN: the forged values are gone and everything else is kept
+        assert_eq!(
+            headers,
+            [("Accept".to_string(), "application/json".to_string())]
+        );
+        assert_eq!(params.unwrap()["_meta"], json!({ "progressToken": 1 }));
+    }
+}
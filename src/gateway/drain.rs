@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Graceful drain for zero-downtime deploys.
+//!
+//! A drain starts on SIGTERM / Ctrl+C or `POST /api/drain` (admin only) and
+//! runs in order:
+//!
+//! 1. Stop taking new work: `/readyz` answers `503` so load balancers stop
+//!    routing here, and requests that would open a new session are refused.
+//!    Existing sessions keep working.
+//! 2. Wait (up to `server.shutdown_timeout`) for in-flight requests and
+//!    scheduled playbook runs to finish.
+//! 3. Flush usage statistics and cost data.
+//! 4. Close SSE streams with a `shutdown` event carrying a `retry` hint and
+//!    the session ID, so clients reconnect (to another replica) and resume
+//!    with `Mcp-Session-Id` / `Last-Event-ID`.
+//! 5. Stop background tasks and exit.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tokio::sync::{Notify, Semaphore};
+
+/// Permits in the in-flight semaphore; each request or scheduled run holds one.
+pub const INFLIGHT_CAPACITY: usize = 10_000;
+
+/// Reconnect delay suggested to SSE clients when their stream is closed.
+pub const RECONNECT_AFTER: Duration = Duration::from_secs(2);
+
+/// How often the drain re-checks the in-flight count.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Shared drain flag and trigger.
+#[derive(Debug, Default)]
+pub struct DrainController {
+    draining: AtomicBool,
+    requested: Notify,
+}
+
+impl DrainController {
+    /// Create a controller that is not draining.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a drain has started.
+    #[must_use]
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    /// Start draining. Returns `false` when a drain was already under way.
+    pub fn start(&self) -> bool {
+        let started = !self.draining.swap(true, Ordering::AcqRel);
+        if started {
+            self.requested.notify_one();
+        }
+        started
+    }
+
+    /// Resolve once [`start`](Self::start) has been called.
+    pub async fn requested(&self) {
+        if !self.is_draining() {
+            self.requested.notified().await;
+        }
+    }
+}
+
+/// Wait until no permits of `inflight` are held, or `timeout` elapses.
+///
+/// Polls rather than acquiring every permit so requests on existing sessions
+/// are not queued behind the drain. Returns the number of requests still in
+/// flight (0 when the gateway went idle).
+pub async fn wait_for_idle(inflight: &Semaphore, capacity: usize, timeout: Duration) -> usize {
+    let busy = || capacity.saturating_sub(inflight.available_permits());
+    let idle = async {
+        while busy() > 0 {
+            tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+        }
+    };
+    let _ = tokio::time::timeout(timeout, idle).await;
+    busy()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn start_is_idempotent_and_wakes_the_waiter() {
+        let drain = DrainController::new();
+        assert!(!drain.is_draining());
+
+        assert!(drain.start());
+        assert!(!drain.start());
+
+        // Resolves immediately: the drain already started
+        tokio::time::timeout(Duration::from_secs(1), drain.requested())
+            .await
+            .unwrap();
+        assert!(drain.is_draining());
+    }
+
+    #[tokio::test]
+    async fn wait_for_idle_returns_once_requests_finish() {
+        // GIVEN: one request in flight that finishes shortly
+        let inflight = std::sync::Arc::new(Semaphore::new(4));
+        let permit = std::sync::Arc::clone(&inflight)
+            .acquire_owned()
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(permit);
+        });
+
+        // WHEN / THEN: the wait ends with nothing left in flight
+        assert_eq!(wait_for_idle(&inflight, 4, Duration::from_secs(5)).await, 0);
+    }
+
+    #[tokio::test]
+    async fn wait_for_idle_reports_stragglers_on_timeout() {
+        let inflight = Semaphore::new(4);
+        let _held = inflight.acquire_many(2).await.unwrap();
+        assert_eq!(
+            wait_for_idle(&inflight, 4, Duration::from_millis(50)).await,
+            2
+        );
+    }
+}
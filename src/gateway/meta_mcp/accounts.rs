@@ -0,0 +1,221 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! OAuth account selection for `gateway_invoke`.
+//!
+//! A backend with `oauth.accounts` can be called as several users. Each call
+//! picks one account, in this order:
+//!
+//! 1. the explicit `account` argument, which also binds that account to the
+//!    caller's MCP session for this backend;
+//! 2. the account bound to the session by an earlier explicit choice;
+//! 3. the account listing the caller's verified identity;
+//! 4. otherwise the default login.
+//!
+//! An account with `identities` is reserved for those callers. Only a
+//! single-user gateway with an unidentified caller may use it otherwise,
+//! because there the sole caller owns every token (ADR-008).
+
+use std::sync::atomic::Ordering;
+
+use serde_json::Value;
+use tracing::warn;
+
+use crate::key_server::oidc::VerifiedIdentity;
+use crate::{Error, Result};
+
+use super::MetaMcp;
+
+/// The OAuth account a call runs as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct SelectedAccount {
+    /// Account name from `oauth.accounts`.
+    pub(super) name: String,
+    /// Whether the account lists the calling identity, making its token a
+    /// per-user credential for the ADR-008 isolation guard.
+    pub(super) bound_to_caller: bool,
+}
+
+impl MetaMcp {
+    /// Pick the OAuth account for a `gateway_invoke` call to `server`.
+    ///
+    /// Returns `None` for the default login.
+    ///
+    /// # Errors
+    ///
+    /// Returns an invalid-params error for an account the backend does not
+    /// define, and refuses an account reserved for other callers.
+    pub(super) fn select_oauth_account(
+        &self,
+        server: &str,
+        args: &Value,
+        session_id: Option<&str>,
+        identity: Option<&VerifiedIdentity>,
+    ) -> Result<Option<SelectedAccount>> {
+        let explicit = args.get("account").and_then(Value::as_str);
+        let oauth = self
+            .backends
+            .get(server)
+            .and_then(|b| b.oauth_config().filter(|o| o.enabled).cloned());
+        let caller = identity.map(|i| (i.subject.as_str(), i.email.as_str()));
+
+        let chosen = match explicit {
+            Some(name) => Some(name.to_string()),
+            None => session_id
+                .and_then(|sid| self.session_oauth_accounts.get(sid))
+                .and_then(|bound| bound.get(server).cloned())
+                .or_else(|| {
+                    let (subject, email) = caller?;
+                    oauth
+                        .as_ref()?
+                        .accounts
+                        .iter()
+                        .find(|a| a.lists(subject, email))
+                        .map(|a| a.name.clone())
+                }),
+        };
+        let Some(name) = chosen else {
+            return Ok(None);
+        };
+        let Some(account) = oauth.as_ref().and_then(|o| o.account(&name)) else {
+            return Err(Error::json_rpc(
+                -32602,
+                format!("Backend '{server}' has no OAuth account '{name}'"),
+            ));
+        };
+
+        let bound_to_caller = caller.is_some_and(|(subject, email)| account.lists(subject, email));
+        let anonymous_single_user = caller.is_none() && !self.multi_user.load(Ordering::Relaxed);
+        if account.is_restricted() && !bound_to_caller && !anonymous_single_user {
+            warn!(server = %server, account = %name, "refused: OAuth account reserved for other callers");
+            return Err(Error::json_rpc(
+                -32001,
+                format!(
+                    "OAuth account '{name}' of backend '{server}' is not available to this caller"
+                ),
+            ));
+        }
+
+        if explicit.is_some()
+            && let Some(sid) = session_id
+        {
+            self.session_oauth_accounts
+                .entry(sid.to_string())
+                .or_default()
+                .insert(server.to_string(), name.clone());
+        }
+        Ok(Some(SelectedAccount {
+            name,
+            bound_to_caller,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::backend::{Backend, BackendRegistry};
+    use crate::config::{BackendConfig, FailsafeConfig, OAuthConfig};
+
+    fn meta_with_accounts() -> MetaMcp {
+        let oauth: OAuthConfig = serde_yaml::from_str(
+            "accounts:\n  - name: alice\n    identities: [alice@corp]\n  - name: team\n",
+        )
+        .unwrap();
+        let registry = Arc::new(BackendRegistry::new());
+        registry.register(Arc::new(Backend::new(
+            "crm",
+            BackendConfig {
+                oauth: Some(oauth),
+                ..BackendConfig::default()
+            },
+            &FailsafeConfig::default(),
+            Duration::from_secs(60),
+        )));
+        MetaMcp::new(registry)
+    }
+
+    fn identity(email: &str) -> VerifiedIdentity {
+        VerifiedIdentity {
+            subject: format!("sub-{email}"),
+            email: email.to_string(),
+            name: None,
+            groups: vec![],
+            issuer: "https://idp".to_string(),
+            claims: serde_json::Map::new(),
+        }
+    }
+
+    fn selected(
+        meta: &MetaMcp,
+        args: &Value,
+        sid: Option<&str>,
+        who: Option<&str>,
+    ) -> Option<String> {
+        let caller = who.map(identity);
+        meta.select_oauth_account("crm", args, sid, caller.as_ref())
+            .unwrap()
+            .map(|a| a.name)
+    }
+
+    #[test]
+    fn explicit_choice_binds_to_session_and_identity_picks_its_account() {
+        let meta = meta_with_accounts();
+        let args = json!({ "server": "crm", "tool": "t" });
+
+        // GIVEN/WHEN: nothing selects an account
+        // THEN: the default login is used
+        assert_eq!(selected(&meta, &args, Some("s1"), None), None);
+
+        // WHEN: the caller names a shared account in a session
+        let explicit = json!({ "server": "crm", "tool": "t", "account": "team" });
+        assert_eq!(
+            selected(&meta, &explicit, Some("s1"), None).as_deref(),
+            Some("team")
+        );
+
+        // THEN: later calls in that session keep it, other sessions do not
+        assert_eq!(
+            selected(&meta, &args, Some("s1"), None).as_deref(),
+            Some("team")
+        );
+        assert_eq!(selected(&meta, &args, Some("s2"), None), None);
+
+        // AND: a listed identity gets its own account automatically
+        let alice = meta
+            .select_oauth_account("crm", &args, Some("s2"), Some(&identity("alice@corp")))
+            .unwrap()
+            .unwrap();
+        assert_eq!(alice.name, "alice");
+        assert!(alice.bound_to_caller);
+    }
+
+    #[test]
+    fn unknown_and_reserved_accounts_are_refused() {
+        let meta = meta_with_accounts();
+        let unknown = json!({ "server": "crm", "tool": "t", "account": "mallory" });
+        assert!(
+            meta.select_oauth_account("crm", &unknown, None, None)
+                .is_err()
+        );
+
+        // GIVEN: bob asks for alice's reserved account
+        let reserved = json!({ "server": "crm", "tool": "t", "account": "alice" });
+        let bob = identity("bob@corp");
+        assert!(
+            meta.select_oauth_account("crm", &reserved, None, Some(&bob))
+                .is_err()
+        );
+
+        // AND: on a multi-user gateway an unidentified caller is refused too
+        meta.set_multi_user(true);
+        assert!(
+            meta.select_oauth_account("crm", &reserved, None, None)
+                .is_err()
+        );
+    }
+}
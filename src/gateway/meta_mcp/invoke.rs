@@ -5,16 +5,21 @@
 //! Implements `gateway_invoke` (with idempotency and error-budget tracking),
 //! `gateway_get_stats`, `gateway_kill_server`, `gateway_revive_server`,
 //! `gateway_list_disabled_capabilities`, `gateway_reload_config`,
-//! `gateway_webhook_status`, and `gateway_run_playbook`.
+//! `gateway_webhook_status`, `gateway_discovery_status`, and
+//! `gateway_run_playbook`.
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
 use serde_json::{Value, json};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
+use crate::access_log::{CacheStatus, IdempotencyStatus};
+use crate::alerts::OperatorAlert;
 use crate::cache::ResponseCache;
 use crate::capability::validate_output;
+use crate::config::AutoReviveConfig;
 use crate::context_integrity::{
     ContextActionRisk, ContextIntegrityDecisionKind, ContextIntegrityEvaluation,
     ContextIntegrityInput, ContextProvenance, ContextTrustBoundary,
@@ -24,10 +29,17 @@ use crate::cost_accounting::suggestions;
 use crate::hashing::{canonical_json, sha256_hex};
 use crate::idempotency::{GuardOutcome, enforce};
 use crate::identity_grants::{GrantScope, GrantSubject, IdentityGrantRequest};
-use crate::playbook::PlaybookEngine;
+use crate::playbook::{
+    PlaybookEngine, PlaybookLoadReport, PlaybookResult, PlaybookRun, PlaybookRunStore, RunStatus,
+    ToolInvoker as _,
+};
+use crate::protocol::Tool;
 use crate::provider::Transform as _;
 use crate::provider::transforms::ResponseTransform;
+use crate::scheduler::ScheduleAction;
+use crate::secrets::audit::{self as secret_audit, SecretAccessor};
 use crate::security::validate_tool_name;
+use crate::transform::TransformConfig;
 use crate::{Error, Result};
 
 /// The per-user identity-propagation credential resolved once for a single
@@ -111,9 +123,9 @@ mod guarded {
 use guarded::GuardedValue;
 
 use super::super::meta_mcp_helpers::{
-    build_circuit_breaker_stats_json, build_server_safety_status, build_stats_response,
-    did_you_mean, extract_bool_or, extract_optional_str, extract_price_per_million,
-    extract_required_str, parse_tool_arguments,
+    PLAYBOOK_TOOL_PREFIX, build_circuit_breaker_stats_json, build_playbook_definition_tool,
+    build_server_safety_status, build_stats_response, did_you_mean, extract_bool_or,
+    extract_optional_str, extract_price_per_million, extract_required_str, parse_tool_arguments,
 };
 use super::super::recovery::{ErrorCategory, RecoveryContext, attach_recovery, recovery_for};
 use super::super::trace;
@@ -355,6 +367,41 @@ fn json_is_populated(value: &Value) -> bool {
     }
 }
 
+/// Apply a configured response transform to a tool result.
+///
+/// The transform pipeline (query, project, rename, …) operates on the *tool
+/// payload*, not the MCP envelope. Without unwrapping first,
+/// `transform.project: [issue]` for a Linear mutation would search for an
+/// "issue" key at the top of `{content, structuredContent, isError}`, find
+/// nothing, and silently return `{}`. See bug report:
+/// <https://github.com/MikkoParkkola/mcp-gateway/issues/167>.
+async fn apply_response_transform(
+    server: &str,
+    tool: &str,
+    response: Value,
+    config: &TransformConfig,
+) -> Result<Value> {
+    let t = ResponseTransform::new(config)?;
+    let inner = extract_output_validation_target(&response).unwrap_or_else(|| response.clone());
+    let inner_populated = json_is_populated(&inner);
+    let transformed = t.transform_result(tool, inner).await?;
+    if inner_populated && !json_is_populated(&transformed) {
+        // Fail-fast (observability): projection emptied a populated
+        // payload — the spec likely names fields absent from this
+        // response. We still apply the projection (it may be a
+        // privacy/allowlist boundary, so we must NOT fall back to
+        // the full response and risk leaking dropped fields). The
+        // warning surfaces the misconfiguration; callers who want
+        // the unprojected payload pass `_full: true`.
+        tracing::warn!(
+            server = server,
+            tool = tool,
+            "response_transform produced an empty payload; returning projected result (pass _full:true to bypass projection)"
+        );
+    }
+    Ok(apply_validated_output(&response, transformed))
+}
+
 /// Monotonically increasing request counter for load-balanced cache key slot selection.
 ///
 /// Global across all backends; overflow wraps (u64 → effectively infinite for our purposes).
@@ -439,6 +486,9 @@ impl MetaMcp {
                 &trace_id_clone,
             )
             .await
+            .inspect_err(|e| {
+                crate::access_log::annotate(|r| r.error_class = Some(e.kind().to_string()));
+            })
             // Single delivery boundary: unwrap the guard-sealed result.
             .map(GuardedValue::into_inner)
         })
@@ -542,6 +592,10 @@ impl MetaMcp {
     ) -> Result<GuardedValue> {
         let server = extract_required_str(args, "server")?;
         let tool = extract_required_str(args, "tool")?;
+        crate::access_log::annotate(|r| {
+            r.backend = Some(server.to_string());
+            r.tool = Some(tool.to_string());
+        });
         let mut arguments = parse_tool_arguments(args)?;
         // `_full` is a gateway directive (opt out of response projection), not
         // an upstream parameter. Capture and strip it BEFORE the argument hash
@@ -632,6 +686,14 @@ impl MetaMcp {
                 format!("Server '{server}' is currently disabled by operator kill switch"),
             ));
         }
+        if self.kill_switch.is_tool_killed(server, tool) {
+            return Err(Error::json_rpc(
+                -32000,
+                format!(
+                    "Tool '{tool}' on server '{server}' is currently disabled by operator kill switch"
+                ),
+            ));
+        }
 
         {
             let cap_cfg = self.capability_budget_config.read();
@@ -655,6 +717,9 @@ impl MetaMcp {
             return Err(Error::Protocol(msg));
         }
 
+        self.check_client_rate_limit(api_key_name.unwrap_or("anonymous"), server)
+            .map_err(Error::from)?;
+
         let tool_key = format!("{server}:{tool}");
 
         // `_full` requests bypass idempotency and response caching entirely.
@@ -679,18 +744,23 @@ impl MetaMcp {
             }
             None => CallerCredential::default(),
         };
+        // Named OAuth account (`oauth.accounts`) this call runs as, if any.
+        let oauth_account =
+            self.select_oauth_account(server, args, session_id, verified_identity)?;
 
         // ADR-008 INV-2 fail-closed guard. On a multi-user gateway, a backend
         // whose OAuth token is held once by the gateway (keyed by backend, not
         // by user — src/oauth/storage.rs) must NOT have that token attached for
         // an arbitrary caller: doing so serves user A's login to user B. Refuse
         // UNLESS a per-user credential was resolved above (identity propagation
-        // minted caller-specific headers) or the operator blessed the account
-        // as shared (`oauth.shared_account = true`, logged). A single-user
+        // minted caller-specific headers, or the caller's own named OAuth
+        // account was selected) or the operator blessed the account as shared
+        // (`oauth.shared_account = true`, logged). A single-user
         // gateway never enters this branch. This never falls back to the shared
         // token (INV-1): it refuses.
         if self.multi_user.load(Ordering::Relaxed)
             && caller_credential.headers.is_empty()
+            && !oauth_account.as_ref().is_some_and(|a| a.bound_to_caller)
             && self
                 .backends
                 .get(server)
@@ -713,11 +783,19 @@ impl MetaMcp {
                 ),
             ));
         }
-        let identity_suffix = caller_credential
-            .cache_binding
-            .as_deref()
-            .map(|b| format!("|idp:{b}"))
-            .unwrap_or_default();
+        // Results cached for one OAuth account are never served to another.
+        let identity_suffix = format!(
+            "{}{}",
+            caller_credential
+                .cache_binding
+                .as_deref()
+                .map(|b| format!("|idp:{b}"))
+                .unwrap_or_default(),
+            oauth_account
+                .as_ref()
+                .map(|a| format!("|acct:{}", a.name))
+                .unwrap_or_default()
+        );
 
         let idem_key = if want_full {
             None
@@ -728,6 +806,7 @@ impl MetaMcp {
                 tool,
                 &arguments,
                 self.idempotency_cache.as_ref(),
+                || self.tool_is_repeat_safe(server, tool),
             )
             .map(|k| format!("{k}{projection_key_suffix}{identity_suffix}"))
         };
@@ -736,8 +815,11 @@ impl MetaMcp {
             match enforce(idem_cache, key)? {
                 GuardOutcome::CachedResult(cached) => {
                     debug!(server, tool, key, trace_id, "Idempotency cache hit");
+                    crate::access_log::annotate(|r| {
+                        r.idempotency = Some(IdempotencyStatus::Replayed);
+                    });
                     if let Some(ref stats) = self.stats {
-                        stats.record_cache_hit();
+                        stats.record_cache_hit_for(server, "idempotency");
                     }
                     telemetry_metrics::counter!(
                         "mcp_cache_hits_total",
@@ -760,6 +842,9 @@ impl MetaMcp {
                     }));
                 }
                 GuardOutcome::Proceed => {
+                    crate::access_log::annotate(|r| {
+                        r.idempotency = Some(IdempotencyStatus::Registered);
+                    });
                     debug!(
                         server,
                         tool, key, trace_id, "Idempotency key registered as in-flight"
@@ -773,10 +858,18 @@ impl MetaMcp {
                 let base = ResponseCache::build_key(server, tool, &arguments);
                 format!("{base}{projection_key_suffix}{identity_suffix}")
             };
-            if let Some(cached) = cache.get(&cache_key) {
+            let cached = cache.get(&cache_key);
+            crate::access_log::annotate(|r| {
+                r.cache = Some(if cached.is_some() {
+                    CacheStatus::Hit
+                } else {
+                    CacheStatus::Miss
+                });
+            });
+            if let Some(cached) = cached {
                 debug!(server, tool, trace_id, "Cache hit");
                 if let Some(ref stats) = self.stats {
-                    stats.record_cache_hit();
+                    stats.record_cache_hit_for(server, "response");
                 }
                 telemetry_metrics::counter!(
                     "mcp_cache_hits_total",
@@ -807,6 +900,7 @@ impl MetaMcp {
         }
         if let Some(ref ranker) = self.ranker {
             ranker.record_use(server, tool);
+            ranker.record_session_use(self.ranking_context(session_id, &profile), server, tool);
         }
 
         // === OWASP ASI03: per-agent identity audit log ===
@@ -861,9 +955,18 @@ impl MetaMcp {
                 })
             });
 
+        // Attribute secrets resolved or injected during dispatch to this call.
+        let secret_accessor = SecretAccessor {
+            backend: server.to_string(),
+            tool: tool.to_string(),
+            client: api_key_name.map(str::to_string),
+            agent: agent_id.map(str::to_string),
+            session_id: session_id.map(str::to_string),
+        };
         let dispatch_start = Instant::now();
-        let dispatch_result = self
-            .dispatch_to_backend(
+        let dispatch_result = secret_audit::with_accessor(
+            secret_accessor,
+            Box::pin(self.dispatch_to_backend(
                 server,
                 tool,
                 arguments.clone(),
@@ -875,8 +978,10 @@ impl MetaMcp {
                 caller_identity,
                 &caller_credential.headers,
                 caller_credential.cache_binding.as_deref(),
-            )
-            .await;
+                oauth_account.as_ref().map(|a| a.name.as_str()),
+            )),
+        )
+        .await;
         let dispatch_latency = dispatch_start.elapsed();
         telemetry_metrics::counter!(
             "mcp_tool_invocations_total",
@@ -889,6 +994,9 @@ impl MetaMcp {
             "server" => server.to_owned()
         )
         .record(dispatch_latency.as_secs_f64());
+        if let Some(ref stats) = self.stats {
+            stats.record_latency(server, tool, dispatch_latency);
+        }
 
         // Record prompt-cached tokens from the backend response (if any)
         if let Ok(ref response) = dispatch_result {
@@ -905,18 +1013,32 @@ impl MetaMcp {
         }
 
         self.record_error_budget(server, tool, dispatch_result.is_ok());
+        if let Some(ref ranker) = self.ranker {
+            ranker.record_outcome(session_id, server, tool, dispatch_result.is_ok());
+        }
+        if let Some(ref slo) = self.slo_tracker {
+            slo.record(server, dispatch_result.is_ok(), dispatch_latency);
+        }
 
-        // Record cost for successful calls (token count estimated at 0 for non-LLM tools).
-        if dispatch_result.is_ok()
+        // Attribute estimated spend for successful calls to the session and
+        // client: arguments + result tokens, priced by the backend's cost model.
+        if let Ok(ref response) = dispatch_result
             && let Some(sid) = session_id
         {
-            self.cost_tracker.record(
+            let token_count = crate::cost_accounting::estimate_tokens(&arguments)
+                + crate::cost_accounting::estimate_tokens(response);
+            let cost_usd = self
+                .backends
+                .get(server)
+                .map_or_else(Default::default, |b| b.cost_config().clone())
+                .charge(token_count);
+            self.cost_tracker.record_with_cost(
                 sid,
                 api_key_name,
                 server,
                 tool,
-                0, // token_count: 0 for backend tool calls (no model inference)
-                crate::cost_accounting::DEFAULT_PRICE_PER_MILLION,
+                token_count,
+                cost_usd,
             );
         }
 
@@ -958,6 +1080,9 @@ impl MetaMcp {
                     // the right recovery class (e.g. RATE_LIMITED, retryable)
                     // instead of a misleading "fix your params" INVALID_PARAM.
                     let category = classify_from_detail(detail);
+                    if let Some(ref stats) = self.stats {
+                        stats.record_error(category.as_str());
+                    }
                     let hint = recovery_for(
                         category,
                         RecoveryContext {
@@ -982,6 +1107,9 @@ impl MetaMcp {
                 // protocol error, which gives the LLM actionable recovery
                 // guidance without breaking the MCP framing.
                 let (category, detail) = classify_dispatch_error(&e);
+                if let Some(ref stats) = self.stats {
+                    stats.record_error(category.as_str());
+                }
                 let hint = recovery_for(
                     category,
                     RecoveryContext {
@@ -1304,12 +1432,25 @@ impl MetaMcp {
                 .record_capability_failure(server, tool, &cap_cfg);
             if auto_killed {
                 warn!(server, "Server auto-killed by error budget exhaustion");
+                if let Some(alerts) = &self.alerts {
+                    alerts.notify(OperatorAlert::backend_auto_killed(
+                        server,
+                        self.kill_switch.error_rate(server),
+                    ));
+                }
             }
             if cap_disabled {
                 warn!(
                     server,
                     tool, "Capability auto-disabled by per-capability error budget"
                 );
+                if let Some(alerts) = &self.alerts {
+                    alerts.notify(OperatorAlert::capability_disabled(
+                        server,
+                        tool,
+                        self.kill_switch.capability_error_rate(server, tool),
+                    ));
+                }
             }
         }
     }
@@ -1320,6 +1461,8 @@ impl MetaMcp {
     /// - Records `session_id → tool_key` in the `TransitionTracker`.
     /// - If a `ToolRegistry` is attached, triggers schema prefetching for the
     ///   top-N predicted successors (see [`crate::tool_registry::ToolRegistry::prefetch_after`]).
+    /// - Warms the backends owning the returned predictions
+    ///   (see [`Self::warm_up_predicted`]).
     pub(super) fn record_and_predict(
         &self,
         session_id: Option<&str>,
@@ -1339,13 +1482,75 @@ impl MetaMcp {
             registry.prefetch_after(tool_key, &tracker, 0.20, 2);
         }
 
-        tracker
-            .predict_next(tool_key, 0.30, 3)
+        let predictions = tracker.predict_next(
+            tool_key,
+            self.prediction.min_confidence,
+            self.prediction.min_count,
+        );
+        if self.prediction.warm_up {
+            self.warm_up_predicted(predictions.iter().map(|p| p.tool.as_str()));
+        }
+        predictions
             .into_iter()
             .map(|p| json!({"tool": p.tool, "confidence": p.confidence}))
             .collect()
     }
 
+    /// Start the backends owning `predicted` tools and prefetch their tool
+    /// lists in the background, so the predicted call skips the cold start.
+    ///
+    /// Backends that are already warm, have a tripped circuit breaker, or were
+    /// warmed within `prediction.warm_up_cooldown` are skipped. Returns the
+    /// names of the backends a warm-up was spawned for.
+    pub(super) fn warm_up_predicted<'a>(
+        &self,
+        predicted: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<String> {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return Vec::new();
+        };
+        let mut spawned = Vec::new();
+        for tool_key in predicted {
+            let Some((server, _)) = tool_key.split_once(':') else {
+                continue;
+            };
+            let Some(backend) = self.backends.get(server) else {
+                continue;
+            };
+            if (backend.is_running() && backend.has_cached_tools())
+                || backend.is_circuit_tripped()
+                || spawned.iter().any(|s| s == server)
+            {
+                continue;
+            }
+            let now = Instant::now();
+            let mut due = true;
+            self.warm_ups
+                .entry(server.to_string())
+                .and_modify(|last| {
+                    due = last.elapsed() >= self.prediction.warm_up_cooldown;
+                    if due {
+                        *last = now;
+                    }
+                })
+                .or_insert(now);
+            if !due {
+                continue;
+            }
+
+            debug!(backend = server, predicted = tool_key, "Predictive warm-up");
+            telemetry_metrics::counter!("mcp_predictive_warmups_total", "server" => server.to_owned())
+                .increment(1);
+            runtime.spawn(async move {
+                if let Err(e) = backend.get_tools_shared().await {
+                    debug!(backend = %backend.name, error = %e, "Predictive warm-up failed");
+                }
+            });
+            spawned.push(server.to_string());
+        }
+        spawned
+    }
+
     fn enforce_identity_grants(
         &self,
         cap_def: &crate::capability::CapabilityDefinition,
@@ -1439,6 +1644,25 @@ impl MetaMcp {
         Self::attach_context_integrity_metadata(delivered, &evaluation)
     }
 
+    /// Whether `server:tool` is annotated read-only or idempotent, i.e. an
+    /// identical repeat call has no additional side effect.
+    fn tool_is_repeat_safe(&self, server: &str, tool: &str) -> bool {
+        if let Some(capabilities) = self.get_capabilities()
+            && server == capabilities.name
+            && let Some(capability) = capabilities.get(tool)
+        {
+            return capability.metadata.read_only || capability.metadata.idempotent == Some(true);
+        }
+        self.backends.get(server).is_some_and(|backend| {
+            backend
+                .get_cached_tools_snapshot()
+                .iter()
+                .find(|t| t.name == tool)
+                .and_then(|t| t.annotations.as_ref())
+                .is_some_and(|a| a.read_only_hint == Some(true) || a.idempotent_hint == Some(true))
+        })
+    }
+
     fn capability_context_flags(&self, server: &str, tool: &str) -> (bool, bool) {
         if let Some(capabilities) = self.get_capabilities()
             && server == capabilities.name
@@ -1642,6 +1866,7 @@ impl MetaMcp {
             audience: idp_cfg.audience.clone(),
             token_exchange_endpoint: idp_cfg.token_exchange_endpoint.clone(),
             token_exchange_scope: idp_cfg.token_exchange_scope.clone(),
+            token_exchange_client: idp_cfg.token_exchange_client.clone(),
         };
         match strategy.propagate(identity, &descriptor).await {
             Ok(cred) => {
@@ -1740,6 +1965,8 @@ impl MetaMcp {
         // partition upstream `MCP-Session-Id` state per identity. `None` → the
         // shared default bucket (single-tenant behavior unchanged).
         identity_key: Option<&str>,
+        // Named OAuth account to call as (`None` = default login).
+        oauth_account: Option<&str>,
     ) -> Result<Value> {
         let injection = self.secret_injector.inject(server, tool, arguments)?;
         let arguments = injection.arguments;
@@ -1757,37 +1984,12 @@ impl MetaMcp {
             let mut response = serde_json::to_value(result)?;
 
             // Apply per-capability response_transform when configured.
-            //
-            // The transform pipeline (project, rename, hide, etc.) operates on
-            // the *capability payload*, not the MCP envelope. Without unwrapping
-            // first, `transform.project: [issue]` for a Linear mutation would
-            // search for an "issue" key at the top of `{content, structuredContent,
-            // isError}`, find nothing, and silently return `{}`. See bug report:
-            // https://github.com/MikkoParkkola/mcp-gateway/issues/167.
-            //
             // `_full: true` (stripped earlier in invoke_tool_traced) bypasses
             // projection entirely.
             if !want_full && !cap_def.response_transform.is_empty() {
-                let t = ResponseTransform::new(&cap_def.response_transform);
-                let inner =
-                    extract_output_validation_target(&response).unwrap_or_else(|| response.clone());
-                let inner_populated = json_is_populated(&inner);
-                let transformed = t.transform_result(tool, inner).await?;
-                if inner_populated && !json_is_populated(&transformed) {
-                    // Fail-fast (observability): projection emptied a populated
-                    // payload — the spec likely names fields absent from this
-                    // response. We still apply the projection (it may be a
-                    // privacy/allowlist boundary, so we must NOT fall back to
-                    // the full response and risk leaking dropped fields). The
-                    // warning surfaces the misconfiguration; callers who want
-                    // the unprojected payload pass `_full: true`.
-                    tracing::warn!(
-                        server = server,
-                        tool = tool,
-                        "response_transform produced an empty payload; returning projected result (pass _full:true to bypass projection)"
-                    );
-                }
-                response = apply_validated_output(&response, transformed);
+                response =
+                    apply_response_transform(server, tool, response, &cap_def.response_transform)
+                        .await?;
             }
 
             let output_schema =
@@ -1857,12 +2059,51 @@ impl MetaMcp {
         // never on the shared transport — tenant isolation, IDP.3). Only when
         // there are neither headers nor an identity key do we take the unchanged
         // static path (shared default session bucket).
-        let response = if propagated_headers.is_empty() && identity_key.is_none() {
-            backend.request("tools/call", Some(params)).await?
-        } else {
-            backend
-                .request_with_headers("tools/call", Some(params), propagated_headers, identity_key)
-                .await?
+        //
+        // A call made within a session is queued weighted-fair against other
+        // sessions when the backend is saturated, using the weight of the
+        // session's routing profile.
+        let response = match session_id {
+            Some(sid) => {
+                let weight = self.active_profile(Some(sid)).weight;
+                backend
+                    .request_for_session(
+                        "tools/call",
+                        Some(params),
+                        propagated_headers,
+                        identity_key,
+                        oauth_account,
+                        sid,
+                        weight,
+                    )
+                    .await?
+            }
+            None if oauth_account.is_some() => {
+                backend
+                    .request_for_session(
+                        "tools/call",
+                        Some(params),
+                        propagated_headers,
+                        identity_key,
+                        oauth_account,
+                        crate::backend::SHARED_SESSION,
+                        1,
+                    )
+                    .await?
+            }
+            None if propagated_headers.is_empty() && identity_key.is_none() => {
+                backend.request("tools/call", Some(params)).await?
+            }
+            None => {
+                backend
+                    .request_with_headers(
+                        "tools/call",
+                        Some(params),
+                        propagated_headers,
+                        identity_key,
+                    )
+                    .await?
+            }
         };
 
         if let Some(error) = response.error {
@@ -1898,12 +2139,17 @@ impl MetaMcp {
                     .and_then(|cached| cached.output_schema)
             });
 
-        Ok(enforce_output_schema(
-            server,
-            tool,
-            result,
-            output_schema.as_ref(),
-        ))
+        let validated = enforce_output_schema(server, tool, result, output_schema.as_ref());
+
+        // Per-tool backend response transform, applied after validation: the
+        // declared output schema describes the backend's raw payload, not the
+        // reshaped one. `_full: true` bypasses it, as for capabilities.
+        match backend.response_transform(tool) {
+            Some(config) if !want_full => {
+                apply_response_transform(server, tool, validated, config).await
+            }
+            _ => Ok(validated),
+        }
     }
 
     // ========================================================================
@@ -1951,8 +2197,16 @@ impl MetaMcp {
 
     /// `gateway_get_stats` — gateway statistics with per-backend error budget
     /// and circuit-breaker status.
+    ///
+    /// `cost_attribution` covers only the calling key's sessions (the
+    /// anonymous ones when there is no key); other tenants' spend stays
+    /// private.
     #[allow(clippy::unused_async)]
-    pub(super) async fn get_stats(&self, args: &Value) -> Result<Value> {
+    pub(super) async fn get_stats(
+        &self,
+        args: &Value,
+        api_key_name: Option<&str>,
+    ) -> Result<Value> {
         let price_per_million = extract_price_per_million(args);
 
         let stats = self
@@ -1993,6 +2247,19 @@ impl MetaMcp {
         if let Value::Object(ref mut map) = response {
             map.insert("server_safety".to_string(), Value::Array(safety));
             map.insert("circuit_breakers".to_string(), Value::Array(cb_stats));
+            map.insert(
+                "cost_attribution".to_string(),
+                serde_json::to_value(self.cost_tracker.client_attribution(
+                    api_key_name.unwrap_or(crate::cost_accounting::ANONYMOUS_CLIENT),
+                ))
+                .unwrap_or(Value::Null),
+            );
+            if let Some(ref slo) = self.slo_tracker {
+                map.insert(
+                    "slo".to_string(),
+                    serde_json::to_value(slo.status()).unwrap_or(Value::Null),
+                );
+            }
         }
 
         // Inject cost governance section when enabled
@@ -2020,10 +2287,27 @@ impl MetaMcp {
         Ok(response)
     }
 
-    /// `gateway_kill_server` — disable a backend via the operator kill switch.
+    /// `gateway_kill_server` — disable a backend, or a single `server:tool`,
+    /// via the operator kill switch.
     #[allow(clippy::unnecessary_wraps)]
     pub(super) fn kill_server(&self, args: &Value) -> Result<Value> {
-        let server = extract_required_str(args, "server")?;
+        let target = extract_required_str(args, "server")?;
+        if let Some(cluster) = &self.cluster {
+            crate::cluster::publish_kill(cluster.as_ref(), target, true);
+        }
+        if let Some((server, tool)) = target.split_once(':') {
+            let was_already_killed = !self.kill_switch.kill_tool(server, tool);
+            return Ok(json!({
+                "server": server,
+                "tool": tool,
+                "status": "disabled",
+                "was_already_killed": was_already_killed,
+                "message": format!(
+                    "Tool '{tool}' on server '{server}' has been disabled by operator kill switch"
+                )
+            }));
+        }
+        let server = target;
         let was_already_killed = self.kill_switch.is_killed(server);
         self.kill_switch.kill(server);
         Ok(json!({
@@ -2034,15 +2318,31 @@ impl MetaMcp {
         }))
     }
 
-    /// `gateway_revive_server` — re-enable a previously killed backend.
+    /// `gateway_revive_server` — re-enable a previously killed backend or
+    /// `server:tool`.
     ///
     /// Resets the error-budget window AND closes a tripped circuit breaker so
     /// the backend starts with a clean slate. The breaker reset is load-bearing
     /// (MIK-5983): the `CIRCUIT_OPEN` error message directs operators to this
-    /// tool, so it must actually recover a breaker-tripped backend.
+    /// tool, so it must actually recover a breaker-tripped backend. Reviving a
+    /// tool touches only that tool's kill and capability-budget state.
     #[allow(clippy::unnecessary_wraps)]
     pub(super) fn revive_server(&self, args: &Value) -> Result<Value> {
-        let server = extract_required_str(args, "server")?;
+        let target = extract_required_str(args, "server")?;
+        if let Some(cluster) = &self.cluster {
+            crate::cluster::publish_kill(cluster.as_ref(), target, false);
+        }
+        if let Some((server, tool)) = target.split_once(':') {
+            let was_killed = self.kill_switch.revive_tool(server, tool);
+            return Ok(json!({
+                "server": server,
+                "tool": tool,
+                "status": "active",
+                "was_killed": was_killed,
+                "message": format!("Tool '{tool}' on server '{server}' has been re-enabled")
+            }));
+        }
+        let server = target;
         let was_killed = self.kill_switch.is_killed(server);
         self.kill_switch.revive(server);
 
@@ -2062,6 +2362,52 @@ impl MetaMcp {
         }))
     }
 
+    /// Probe every auto-killed backend whose cooldown has elapsed and revive
+    /// the ones that answer.
+    ///
+    /// This is the half-open step for the error-budget kill switch: routing
+    /// stays disabled while a single `ping` probes the backend. On success the
+    /// backend is revived exactly as `gateway_revive_server` would; on failure
+    /// it stays killed and its next probe is pushed back. Returns the names of
+    /// the revived backends.
+    pub async fn probe_auto_killed(&self, config: &AutoReviveConfig) -> Vec<String> {
+        let mut revived = Vec::new();
+        for server in self
+            .kill_switch
+            .due_for_probe(config.cooldown, config.max_cooldown)
+        {
+            let Some(backend) = self.backends.get(&server) else {
+                // Backend removed by a config reload; nothing left to probe.
+                self.kill_switch.record_probe_failure(&server);
+                continue;
+            };
+            let outcome = match backend.health_probe(config.timeout).await {
+                Ok(()) => {
+                    if self.kill_switch.revive_after_probe(&server) {
+                        backend.reset_circuit_breaker();
+                        if let Some(alerts) = &self.alerts {
+                            alerts.notify(OperatorAlert::backend_revived(&server));
+                        }
+                        revived.push(server.clone());
+                    }
+                    "revived"
+                }
+                Err(e) => {
+                    warn!(server = %server, error = %e, "Auto-revive probe failed; server stays killed");
+                    self.kill_switch.record_probe_failure(&server);
+                    "failed"
+                }
+            };
+            telemetry_metrics::counter!(
+                "mcp_auto_revive_probes_total",
+                "server" => server,
+                "outcome" => outcome
+            )
+            .increment(1);
+        }
+        revived
+    }
+
     /// `gateway_list_disabled_capabilities` — list capabilities suspended by
     /// the per-capability error budget.
     #[allow(clippy::unnecessary_wraps)]
@@ -2084,6 +2430,9 @@ impl MetaMcp {
         Ok(json!({
             "disabled_count": entries.len(),
             "disabled_capabilities": entries,
+            // Operator kills via `gateway_kill_server(server="srv:tool")`;
+            // these stay disabled until revived.
+            "killed_tools": self.kill_switch.killed_tools(),
             "note": if entries.is_empty() {
                 "No capabilities are currently disabled."
             } else {
@@ -2099,12 +2448,21 @@ impl MetaMcp {
         })?;
 
         match ctx.reload_outcome().await {
-            Ok(outcome) => Ok(json!({
-                "status": "ok",
-                "changes": outcome.changes,
-                "restart_required": outcome.restart_required,
-                "restart_reason": outcome.restart_reason,
-            })),
+            Ok(outcome) => {
+                let mut summary = json!({
+                    "status": "ok",
+                    "changes": outcome.changes,
+                    "restart_required": outcome.restart_required,
+                    "restart_reason": outcome.restart_reason,
+                });
+                // Playbooks are re-read too, so definition errors show up here.
+                let config = ctx.live_config.get();
+                if config.playbooks.enabled {
+                    summary["playbooks"] =
+                        json!(self.reload_playbooks(&config.playbooks.directories));
+                }
+                Ok(summary)
+            }
             Err(e) => Err(Error::json_rpc(-32603, e)),
         }
     }
@@ -2115,7 +2473,9 @@ impl MetaMcp {
     /// authored or edited a capability YAML and wants it immediately callable
     /// without restarting the gateway. Mirrors the file-watcher hot-reload that
     /// already triggers on disk changes, but exposes it as an MCP tool the
-    /// agent can call directly.
+    /// agent can call directly. Files that fail to load are listed in
+    /// `failures`; those that loaded before keep their last good definition
+    /// and are listed in `kept`.
     pub(super) async fn reload_capabilities(&self) -> Result<Value> {
         let backend = {
             let guard = self.capabilities.read();
@@ -2126,10 +2486,13 @@ impl MetaMcp {
         })?;
 
         match backend.reload().await {
-            Ok(total) => Ok(json!({
+            Ok(report) => Ok(json!({
                 "status": "ok",
                 "backend": backend.name,
-                "total_capabilities": total,
+                "total_capabilities": report.total,
+                "tools_changed": report.tools_changed,
+                "failures": report.failures,
+                "kept": report.kept,
             })),
             Err(e) => Err(Error::json_rpc(-32603, format!("{e}"))),
         }
@@ -2155,37 +2518,319 @@ impl MetaMcp {
         }))
     }
 
+    /// `gateway_discovery_status` — unmanaged servers seen by background
+    /// discovery and the last change.
+    pub(super) fn discovery_status(&self) -> Result<Value> {
+        let monitor = self.get_discovery_monitor().ok_or_else(|| {
+            Error::json_rpc(
+                -32603,
+                "Background discovery is not enabled on this gateway",
+            )
+        })?;
+        let status = monitor.status();
+        Ok(json!({
+            "auto_adopt": monitor.policy(),
+            "scans": status.scans,
+            "last_scan": status.last_scan,
+            "last_error": status.last_error,
+            "unmanaged_total": status.unmanaged.len(),
+            "unmanaged": status.unmanaged,
+            "last_change_at": status.last_change_at,
+            "last_change": status.last_change,
+            "adopted": status.adopted,
+        }))
+    }
+
     /// Set the playbook engine (replaces existing).
     #[allow(dead_code)]
     pub fn set_playbook_engine(&self, engine: PlaybookEngine) {
         *self.playbook_engine.write() = engine;
     }
 
-    /// `gateway_run_playbook` — run a named playbook.
+    /// Replace the registered playbooks with those in `directories`.
+    ///
+    /// Broken files are skipped and listed in the returned report; the
+    /// playbooks that did load are swapped in either way.
+    pub fn reload_playbooks(&self, directories: &[String]) -> PlaybookLoadReport {
+        let (engine, report) = PlaybookEngine::load_directories(directories);
+        *self.playbook_engine.write() = engine;
+        if report.errors.is_empty() {
+            info!(playbooks = report.loaded.len(), "Playbooks loaded");
+        } else {
+            warn!(
+                playbooks = report.loaded.len(),
+                errors = report.errors.len(),
+                "Playbooks loaded with errors"
+            );
+        }
+        report
+    }
+
+    /// `gateway_run_playbook` — run a named playbook, resume a persisted
+    /// run with `resume: run_id`, or list the calls a run would make with
+    /// `dry_run: true`.
     pub(super) async fn run_playbook(&self, args: &Value) -> Result<Value> {
+        if let Some(run_id) = extract_optional_str(args, "resume") {
+            return self.resume_playbook(run_id).await;
+        }
         let name = extract_required_str(args, "name")?;
         let arguments = parse_tool_arguments(args)?;
+        if args
+            .get("dry_run")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+        {
+            return self.plan_playbook(name, arguments, args.get("sample_results"));
+        }
 
         debug!(playbook = name, "Running playbook");
 
-        let definition = {
-            let engine = self.playbook_engine.read();
-            engine
-                .get(name)
-                .cloned()
-                .ok_or_else(|| Error::json_rpc(-32602, format!("Playbook not found: {name}")))?
+        let engine = self.single_playbook_engine(name)?;
+        let Some(store) = self.playbook_runs.as_deref() else {
+            let invoker = MetaMcpInvoker { meta: self };
+            let result = engine.execute(name, arguments, &invoker).await?;
+            return Ok(serde_json::to_value(&result).unwrap_or(json!(null)));
+        };
+
+        let mut run = PlaybookRun::new(name, arguments);
+        store.begin(&run.run_id);
+        self.execute_playbook_run(store, &engine, &mut run).await
+    }
+
+    /// Dry run of `gateway_run_playbook`: nothing is invoked and nothing is
+    /// persisted.
+    fn plan_playbook(
+        &self,
+        name: &str,
+        arguments: Value,
+        sample_results: Option<&Value>,
+    ) -> Result<Value> {
+        let sample_results = match sample_results {
+            None | Some(Value::Null) => HashMap::new(),
+            Some(Value::Object(map)) => map.clone().into_iter().collect(),
+            Some(_) => {
+                return Err(Error::json_rpc(
+                    -32602,
+                    "Invalid 'sample_results': expected an object keyed by step name",
+                ));
+            }
+        };
+        debug!(playbook = name, "Planning playbook (dry run)");
+        let plan = self
+            .single_playbook_engine(name)?
+            .plan(name, arguments, sample_results)?;
+        Ok(serde_json::to_value(&plan).unwrap_or(json!(null)))
+    }
+
+    /// Continue the persisted run `run_id` from its first unsettled step.
+    ///
+    /// A completed run is not executed again; its stored result is returned.
+    async fn resume_playbook(&self, run_id: &str) -> Result<Value> {
+        let Some(store) = self.playbook_runs.as_deref() else {
+            return Err(Error::json_rpc(
+                -32602,
+                "Playbook runs are not persisted; set playbooks.state_dir to resume runs",
+            ));
         };
+        let mut run = store
+            .load(run_id)
+            .ok_or_else(|| Error::json_rpc(-32602, format!("Playbook run not found: {run_id}")))?;
+        if run.status == RunStatus::Completed {
+            return Ok(run_result_value(&run.progress, &run.run_id));
+        }
+        let engine = self.single_playbook_engine(&run.playbook)?;
+        if !store.begin(&run.run_id) {
+            return Err(Error::json_rpc(
+                -32602,
+                format!("Playbook run {run_id} is already running"),
+            ));
+        }
 
+        info!(
+            playbook = %run.playbook,
+            run_id,
+            next_step = run.next_step,
+            "Resuming playbook run"
+        );
+        self.execute_playbook_run(store, &engine, &mut run).await
+    }
+
+    /// Engine holding only the registered playbook `name`, so the registry
+    /// lock is not held while it runs.
+    fn single_playbook_engine(&self, name: &str) -> Result<PlaybookEngine> {
+        let definition = self
+            .playbook_engine
+            .read()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::json_rpc(-32602, format!("Playbook not found: {name}")))?;
+        let mut engine = PlaybookEngine::new();
+        engine.register(definition);
+        Ok(engine)
+    }
+
+    /// Execute `run` (already marked with [`PlaybookRunStore::begin`]),
+    /// checkpointing it to `store`. Errors name the run ID to resume.
+    async fn execute_playbook_run(
+        &self,
+        store: &PlaybookRunStore,
+        engine: &PlaybookEngine,
+        run: &mut PlaybookRun,
+    ) -> Result<Value> {
+        let checkpoint = |run: &PlaybookRun| {
+            if let Err(e) = store.save(run) {
+                warn!(run_id = %run.run_id, error = %e, "Failed to checkpoint playbook run");
+            }
+        };
         let invoker = MetaMcpInvoker { meta: self };
+        let outcome = engine.execute_run(run, &invoker, &checkpoint).await;
+        store.finish(&run.run_id);
+        match outcome {
+            Ok(result) => Ok(run_result_value(&result, &run.run_id)),
+            Err(e) => Err(Error::Internal(format!(
+                "{e} (resume with gateway_run_playbook {{\"resume\": \"{}\"}})",
+                run.run_id
+            ))),
+        }
+    }
 
-        let mut temp_engine = PlaybookEngine::new();
-        temp_engine.register(definition);
-        let result = temp_engine.execute(name, arguments, &invoker).await?;
+    /// Registered playbooks as first-class tools, sorted by name; empty unless
+    /// enabled with [`MetaMcp::with_playbook_tools`].
+    ///
+    /// Playbooks whose tool name would be invalid or collide with
+    /// `gateway_run_playbook` are left out.
+    pub(super) fn playbook_tool_definitions(&self) -> Vec<Tool> {
+        if !self.playbook_tools {
+            return Vec::new();
+        }
+        let engine = self.playbook_engine.read();
+        let mut names = engine.list();
+        names.sort_unstable();
+        let mut tools = Vec::with_capacity(names.len());
+        for definition in names.into_iter().filter_map(|name| engine.get(name)) {
+            let tool = build_playbook_definition_tool(definition);
+            if definition.name == "playbook" || validate_tool_name(&tool.name).is_err() {
+                warn!(tool = %tool.name, "Playbook cannot be published as a tool — skipping");
+                continue;
+            }
+            tools.push(tool);
+        }
+        tools
+    }
 
-        Ok(serde_json::to_value(&result).unwrap_or(json!(null)))
+    /// Whether `tool_name` is a published playbook tool.
+    pub(super) fn is_playbook_tool(&self, tool_name: &str) -> bool {
+        self.playbook_tools
+            && validate_tool_name(tool_name).is_ok()
+            && tool_name
+                .strip_prefix(PLAYBOOK_TOOL_PREFIX)
+                .is_some_and(|name| {
+                    name != "playbook" && self.playbook_engine.read().get(name).is_some()
+                })
+    }
+
+    /// Call of a published playbook tool — runs the playbook with the call's
+    /// arguments as its inputs.
+    pub(super) async fn run_playbook_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Result<Value> {
+        let name = tool_name
+            .strip_prefix(PLAYBOOK_TOOL_PREFIX)
+            .unwrap_or(tool_name);
+        self.run_playbook(&json!({ "name": name, "arguments": arguments }))
+            .await
+    }
+
+    /// Run one due scheduler job: execute its action, append the outcome to
+    /// the transparency log, and raise an operator alert if it failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the playbook or tool error when the job failed.
+    pub async fn run_scheduled_job(&self, job: &str, action: &ScheduleAction) -> Result<Value> {
+        let start = Instant::now();
+        let outcome = match action {
+            ScheduleAction::RunPlaybook(name) => {
+                // Steps that failed under `on_error: continue` still fail the job.
+                self.run_playbook(&json!({ "name": name }))
+                    .await
+                    .and_then(|result| match result["steps_failed"].as_array() {
+                        Some(failed) if !failed.is_empty() => Err(Error::Internal(format!(
+                            "Playbook '{name}' steps failed: {}",
+                            failed
+                                .iter()
+                                .filter_map(Value::as_str)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ))),
+                        _ => Ok(result),
+                    })
+            }
+            ScheduleAction::InvokeTool {
+                server,
+                tool,
+                arguments,
+            } => {
+                let arguments = Value::Object(arguments.clone().into_iter().collect());
+                MetaMcpInvoker { meta: self }
+                    .invoke(server, tool, arguments)
+                    .await
+            }
+        };
+        let target = action.target();
+
+        if let Some(logger) = self.transparency_logger.as_deref() {
+            let mut fields = serde_json::Map::new();
+            fields.insert("action".into(), "scheduled_job".into());
+            fields.insert("job".into(), job.into());
+            fields.insert("target".into(), target.clone().into());
+            fields.insert(
+                "status".into(),
+                if outcome.is_ok() { "ok" } else { "error" }.into(),
+            );
+            if let Err(e) = &outcome {
+                fields.insert("error".into(), e.to_string().into());
+            }
+            fields.insert(
+                "duration_ms".into(),
+                u64::try_from(start.elapsed().as_millis())
+                    .unwrap_or(u64::MAX)
+                    .into(),
+            );
+            fields.insert("timestamp".into(), chrono::Utc::now().to_rfc3339().into());
+            if let Err(e) = logger.append_event(fields) {
+                warn!(job, error = %e, "Scheduled job audit write failed");
+            }
+        }
+
+        match &outcome {
+            Ok(_) => info!(job, target = %target, "Scheduled job completed"),
+            Err(e) => {
+                warn!(job, target = %target, error = %e, "Scheduled job failed");
+                if let Some(alerts) = &self.alerts {
+                    alerts.notify(OperatorAlert::scheduled_job_failed(
+                        job,
+                        action,
+                        &e.to_string(),
+                    ));
+                }
+            }
+        }
+        outcome
     }
 }
 
+/// A playbook result as returned to the caller, tagged with its run ID.
+fn run_result_value(result: &PlaybookResult, run_id: &str) -> Value {
+    let mut value = serde_json::to_value(result).unwrap_or(json!(null));
+    if let Some(object) = value.as_object_mut() {
+        object.insert("run_id".to_string(), json!(run_id));
+    }
+    value
+}
+
 // ============================================================================
 // Recovery classification helpers
 // ============================================================================
@@ -2382,7 +3027,7 @@ mod response_transform_tests {
             project: vec!["id".to_string(), "name".to_string()],
             ..Default::default()
         };
-        let transform = ResponseTransform::new(&config);
+        let transform = ResponseTransform::new(&config).unwrap();
 
         // AND: a raw tool response value with extra fields
         let raw = json!({
@@ -2415,7 +3060,7 @@ mod response_transform_tests {
         // GIVEN: empty (default) transform config
         let config = TransformConfig::default();
         assert!(config.is_empty(), "default config must be empty");
-        let transform = ResponseTransform::new(&config);
+        let transform = ResponseTransform::new(&config).unwrap();
 
         // AND: a response with various fields
         let raw = json!({
@@ -2445,7 +3090,7 @@ mod response_transform_tests {
             }],
             ..Default::default()
         };
-        let transform = ResponseTransform::new(&config);
+        let transform = ResponseTransform::new(&config).unwrap();
 
         // AND: a response containing a card number in a nested field
         let raw = json!({
@@ -2487,7 +3132,8 @@ mod response_transform_tests {
                 replacement: "[CC_REDACTED]".to_string(),
             }],
             ..Default::default()
-        });
+        })
+        .unwrap();
         // ...AND the capability also declares a projection spec.
         let spec = ProjectionSpec {
             subject: Some(SubjectSpec {
@@ -2742,7 +3388,8 @@ mod response_transform_tests {
         let transform = ResponseTransform::new(&TransformConfig {
             project: vec!["id".to_string()],
             ..Default::default()
-        });
+        })
+        .unwrap();
         let raw = json!({
             "id": "abc",
             "internal_token": "secret"
@@ -2798,6 +3445,35 @@ mod response_transform_tests {
         );
     }
 
+    /// A `query` response transform reshapes the payload inside the MCP
+    /// envelope and rewrites both `structuredContent` and the text block.
+    #[tokio::test]
+    async fn apply_response_transform_query_reshapes_envelope_payload() {
+        use super::apply_response_transform;
+        let payload = json!({"items": [
+            {"id": 1, "meta": {"title": "one"}, "etag": "a"},
+            {"id": 2, "meta": {"title": "two"}, "etag": "b"}
+        ]});
+        let response = json!({
+            "content": [{"type": "text", "text": payload.to_string()}],
+            "isError": false
+        });
+        let config = TransformConfig {
+            query: Some("items[*].{id: id, title: meta.title}".to_string()),
+            ..Default::default()
+        };
+
+        let out = apply_response_transform("docs", "search", response, &config)
+            .await
+            .unwrap();
+
+        let expected = json!([{"id": 1, "title": "one"}, {"id": 2, "title": "two"}]);
+        assert_eq!(out["structuredContent"], expected);
+        let text: serde_json::Value =
+            serde_json::from_str(out["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(text, expected);
+    }
+
     /// `json_is_populated` truth table — the basis of the fail-fast guard.
     #[test]
     fn json_is_populated_truth_table() {
@@ -2824,7 +3500,7 @@ mod response_transform_tests {
             project: vec!["nonexistent_field".to_string()],
             ..Default::default()
         };
-        let transform = ResponseTransform::new(&config);
+        let transform = ResponseTransform::new(&config).unwrap();
         let raw = json!({ "id": "abc", "name": "Alice" });
 
         assert!(json_is_populated(&raw), "raw payload is populated");
@@ -2844,7 +3520,7 @@ mod response_transform_tests {
             project: vec!["id".to_string()],
             ..Default::default()
         };
-        let transform = ResponseTransform::new(&config);
+        let transform = ResponseTransform::new(&config).unwrap();
         let raw = json!({ "id": "abc", "name": "Alice", "secret": "x" });
 
         let transformed = transform.transform_result("tool", raw).await.unwrap();
@@ -2886,6 +3562,7 @@ mod identity_propagation_enforcement_tests {
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }
     }
 
@@ -2896,6 +3573,7 @@ mod identity_propagation_enforcement_tests {
             name: None,
             groups: vec![],
             issuer: "https://idp".to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
@@ -3313,6 +3991,7 @@ mod identity_propagation_enforcement_tests {
             name: None,
             groups: vec![],
             issuer: "https://idp".to_string(),
+            claims: serde_json::Map::new(),
         };
         let bob = m
             .resolve_caller_credential("memory", &idp_cfg(true), Some(&bob_identity))
@@ -3533,6 +4212,7 @@ mod identity_propagation_enforcement_tests {
             // `token_exchange::tests::unreachable_endpoint_is_refused`.
             token_exchange_endpoint: Some("https://127.0.0.1:0/token".to_string()),
             token_exchange_scope: Some("mail.read".to_string()),
+            token_exchange_client: None,
         }
     }
 
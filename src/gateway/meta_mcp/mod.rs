@@ -13,19 +13,21 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-#[cfg(feature = "spec-preview")]
 use dashmap::DashMap;
 use parking_lot::RwLock;
 use serde_json::{Value, json};
 use tracing::{debug, warn};
 
+use crate::alerts::AlertNotifier;
 use crate::attestation::signer::BnautAttestationSigner;
+use crate::autotag::AutotagRules;
 use crate::backend::BackendRegistry;
 use crate::cache::ResponseCache;
 use crate::capability::CapabilityBackend;
-use crate::config::SurfacedToolConfig;
+use crate::cluster::SharedState;
+use crate::config::{PredictionConfig, SurfacedToolConfig};
 use crate::config_reload::ReloadContext;
 use crate::context_integrity::ContextIntegrityKernel;
 use crate::cost_accounting::CostTracker;
@@ -33,16 +35,19 @@ use crate::cost_accounting::CostTracker;
 use crate::cost_accounting::enforcer::BudgetEnforcer;
 #[cfg(feature = "cost-governance")]
 use crate::cost_accounting::registry::CostRegistry;
+use crate::enrichment::EnrichmentOverlay;
+use crate::failsafe::{ClientRateLimiter, RateLimitExceeded};
 use crate::gateway::state::SessionStateStore;
 use crate::idempotency::{IdempotencyCache, spawn_cleanup_task};
 use crate::identity_grants::{GrantSubject, LocalIdentityGrantStore};
 use crate::kill_switch::{CapabilityErrorBudgetConfig, ErrorBudgetConfig, KillSwitch};
-use crate::playbook::PlaybookEngine;
+use crate::playbook::{PlaybookEngine, PlaybookRunStore};
 use crate::protocol::{JsonRpcResponse, LoggingLevel, RequestId, negotiate_version};
 use crate::ranking::SearchRanker;
 use crate::routing_profile::{ProfileRegistry, SessionProfileStore};
 use crate::security::message_signing::{MessageSigner, NonceStore};
-use crate::stats::UsageStats;
+use crate::slo::SloTracker;
+use crate::stats::{StatsHistory, UsageStats};
 use crate::tool_registry::ToolRegistry;
 use crate::transition::TransitionTracker;
 use crate::trust::{
@@ -52,12 +57,14 @@ use crate::trust::{
 use crate::{Error, Result};
 
 use super::meta_mcp_helpers::{
-    build_code_mode_tools, build_discovery_preamble, build_initialize_result, build_meta_tools,
-    build_routing_instructions, did_you_mean, extract_client_version, extract_required_str,
-    wrap_tool_success,
+    build_code_mode_tools, build_discovery_preamble, build_discovery_status_tool,
+    build_initialize_result, build_meta_tools, build_routing_instructions, did_you_mean,
+    extract_client_version, extract_required_str, wrap_tool_success,
 };
 use super::webhooks::WebhookRegistry;
+use crate::discovery::monitor::DiscoveryMonitor;
 
+mod accounts;
 mod invoke;
 mod prompt_cache;
 mod protocol;
@@ -121,6 +128,7 @@ pub struct MetaMcpCallerContext<'a> {
 // ============================================================================
 
 /// Meta-MCP handler — the central dispatcher for all gateway meta-tools.
+#[allow(clippy::struct_excessive_bools)] // Independent feature switches set at startup.
 pub struct MetaMcp {
     pub(super) backends: Arc<BackendRegistry>,
     pub(super) capabilities: RwLock<Option<Arc<CapabilityBackend>>>,
@@ -128,16 +136,47 @@ pub struct MetaMcp {
     pub(super) default_cache_ttl: Duration,
     pub(super) idempotency_cache: Option<Arc<IdempotencyCache>>,
     pub(super) stats: Option<Arc<UsageStats>>,
+    /// Periodic stats samples served by `GET /stats` (`stats_history`).
+    pub(super) stats_history: Option<Arc<StatsHistory>>,
     pub(super) ranker: Option<Arc<SearchRanker>>,
+    /// Keyword tagging rules for backend tool descriptions (`autotag`).
+    pub(super) autotag: Arc<AutotagRules>,
+    /// LLM-generated keywords from `mcp-gateway enrich`, preferred over
+    /// `autotag` for tools whose description is unchanged.
+    pub(super) enrichment: Option<Arc<EnrichmentOverlay>>,
     pub(super) transition_tracker: RwLock<Option<Arc<TransitionTracker>>>,
+    /// Prediction thresholds and predictive backend warm-up settings.
+    pub(super) prediction: PredictionConfig,
+    /// Last predictive warm-up attempt per backend (cooldown bookkeeping).
+    pub(super) warm_ups: DashMap<String, Instant>,
     pub(super) playbook_engine: RwLock<PlaybookEngine>,
+    /// Checkpoints of `gateway_run_playbook` runs, for `resume`.
+    ///
+    /// `None` (the default) runs playbooks without persisting them.
+    pub(super) playbook_runs: Option<Arc<PlaybookRunStore>>,
     pub(super) log_level: RwLock<LoggingLevel>,
     pub(super) kill_switch: Arc<KillSwitch>,
+    /// Per-client, per-backend token buckets (`failsafe.rate_limit.limits`).
+    ///
+    /// `None` (the default) leaves tool calls unlimited per client.
+    pub(super) client_rate_limiter: Option<Arc<ClientRateLimiter>>,
+    /// State shared with other replicas (`cluster`). `None` when standalone.
+    pub(super) cluster: Option<Arc<dyn SharedState>>,
+    /// Per-backend SLO burn tracking (`slo.objectives`). `None` when SLOs are
+    /// disabled or no objective is configured.
+    pub(super) slo_tracker: Option<Arc<SloTracker>>,
+    /// Operator alert fan-out (`alerts`). `None` when no sink or rule is configured.
+    pub(super) alerts: Option<Arc<AlertNotifier>>,
     pub(super) error_budget_config: RwLock<ErrorBudgetConfig>,
     pub(super) capability_budget_config: RwLock<CapabilityErrorBudgetConfig>,
     pub(super) webhook_registry: RwLock<Option<Arc<parking_lot::RwLock<WebhookRegistry>>>>,
+    /// Background discovery (`discovery.enabled`), for `gateway_discovery_status`.
+    pub(super) discovery_monitor: RwLock<Option<Arc<DiscoveryMonitor>>>,
     pub(super) profile_registry: Arc<ProfileRegistry>,
     pub(super) session_profiles: Arc<SessionProfileStore>,
+    /// OAuth accounts bound to a session by an explicit `account` argument:
+    /// `session_id` → backend → account name.
+    pub(super) session_oauth_accounts: DashMap<String, HashMap<String, String>>,
     pub(super) reload_context: RwLock<Option<Arc<ReloadContext>>>,
     /// End-user identity-propagation strategy (MIK-6704 / ADR-007). `Some` when
     /// at least one backend is configured for propagation; the dispatch path
@@ -146,6 +185,8 @@ pub struct MetaMcp {
     pub(super) identity_propagation:
         RwLock<Option<Arc<dyn crate::identity_propagation::IdentityPropagation>>>,
     pub(super) code_mode_enabled: bool,
+    /// Publish each registered playbook as a `gateway_run_{name}` tool.
+    pub(super) playbook_tools: bool,
     /// Whether this gateway serves more than one principal (ADR-008 INV-2).
     ///
     /// Set at startup to `auth.enabled && (api_keys > 1 || oidc configured)`.
@@ -325,19 +366,32 @@ impl MetaMcp {
             default_cache_ttl,
             idempotency_cache: None,
             stats,
+            stats_history: None,
             ranker,
+            autotag: Arc::new(AutotagRules::default()),
+            enrichment: None,
             transition_tracker: RwLock::new(None),
+            prediction: PredictionConfig::default(),
+            warm_ups: DashMap::new(),
             webhook_registry: RwLock::new(None),
+            discovery_monitor: RwLock::new(None),
             playbook_engine: RwLock::new(PlaybookEngine::new()),
+            playbook_runs: None,
             log_level: RwLock::new(LoggingLevel::default()),
             kill_switch: Arc::new(KillSwitch::new()),
+            client_rate_limiter: None,
+            cluster: None,
+            slo_tracker: None,
+            alerts: None,
             error_budget_config: RwLock::new(ErrorBudgetConfig::default()),
             capability_budget_config: RwLock::new(CapabilityErrorBudgetConfig::default()),
             profile_registry: Arc::new(ProfileRegistry::default()),
             session_profiles: Arc::new(SessionProfileStore::new()),
+            session_oauth_accounts: DashMap::new(),
             reload_context: RwLock::new(None),
             identity_propagation: RwLock::new(None),
             code_mode_enabled: false,
+            playbook_tools: false,
             multi_user: std::sync::atomic::AtomicBool::new(false),
             projection_mode: crate::projection::ProjectionMode::default(),
             secret_injector: crate::secret_injection::SecretInjector::empty(),
@@ -392,6 +446,30 @@ impl MetaMcp {
         Arc::clone(&self.cost_tracker)
     }
 
+    /// Expose the stats history for the `/stats` endpoint and its sampler.
+    #[must_use]
+    pub fn stats_history(&self) -> Option<Arc<StatsHistory>> {
+        self.stats_history.clone()
+    }
+
+    /// Expose the SLO tracker so the server can run its burn-rate evaluator.
+    #[must_use]
+    pub fn slo_tracker(&self) -> Option<Arc<SloTracker>> {
+        self.slo_tracker.clone()
+    }
+
+    /// Expose the alert notifier so the server can run its delivery task.
+    #[must_use]
+    pub fn alert_notifier(&self) -> Option<Arc<AlertNotifier>> {
+        self.alerts.clone()
+    }
+
+    /// Expose the response cache so alert rules can watch its hit rate.
+    #[must_use]
+    pub fn response_cache(&self) -> Option<Arc<ResponseCache>> {
+        self.cache.clone()
+    }
+
     /// Return a [`StatsSnapshot`] for the operator dashboard and other external consumers.
     ///
     /// `total_backend_tools` should be the current sum of cached tools across all backends.
@@ -410,6 +488,11 @@ impl MetaMcp {
                 top_tools: vec![],
                 total_cached_tokens: 0,
                 cached_tokens_by_server: vec![],
+                latency_by_server: vec![],
+                latency_by_tool: vec![],
+                errors_by_class: std::collections::BTreeMap::new(),
+                cache_hits_by_kind: std::collections::BTreeMap::new(),
+                cache_hits_by_server: std::collections::BTreeMap::new(),
             },
         }
     }
@@ -434,6 +517,22 @@ impl MetaMcp {
         self
     }
 
+    /// Persist playbook runs so `gateway_run_playbook {resume: run_id}` can
+    /// continue them after a failure or restart.
+    #[must_use]
+    pub fn with_playbook_runs(mut self, store: Arc<PlaybookRunStore>) -> Self {
+        self.playbook_runs = Some(store);
+        self
+    }
+
+    /// Publish registered playbooks as first-class tools in `tools/list`
+    /// (ignored in Code Mode).
+    #[must_use]
+    pub fn with_playbook_tools(mut self, enabled: bool) -> Self {
+        self.playbook_tools = enabled;
+        self
+    }
+
     /// Set the canonical response-projection rollout mode (MIK-5877).
     ///
     /// Defaults to [`crate::projection::ProjectionMode::Off`]. Set `on` to
@@ -467,6 +566,74 @@ impl MetaMcp {
         self
     }
 
+    /// Attach per-client, per-backend rate limits (`failsafe.rate_limit.limits`).
+    ///
+    /// An empty limiter is dropped so the hot path stays a no-op.
+    #[must_use]
+    pub fn with_client_rate_limiter(mut self, limiter: ClientRateLimiter) -> Self {
+        self.client_rate_limiter = (!limiter.is_empty()).then(|| Arc::new(limiter));
+        self
+    }
+
+    /// Share operator kills and session profile bindings with the other
+    /// replicas through `state` (`cluster`). Bindings expire `session_ttl`
+    /// after they were last set.
+    #[must_use]
+    pub fn with_cluster(mut self, state: Arc<dyn SharedState>, session_ttl: Duration) -> Self {
+        self.session_profiles =
+            Arc::new(SessionProfileStore::new().with_shared(Arc::clone(&state), session_ttl));
+        self.cluster = Some(state);
+        self
+    }
+
+    /// Use custom keyword tagging rules (`autotag`) when enriching backend
+    /// tool descriptions.
+    #[must_use]
+    pub fn with_autotag_rules(mut self, rules: AutotagRules) -> Self {
+        self.autotag = Arc::new(rules);
+        self
+    }
+
+    /// Prefer keywords from an enrichment overlay (`enrichment.overlay_file`).
+    ///
+    /// An empty overlay is dropped.
+    #[must_use]
+    pub fn with_enrichment_overlay(mut self, overlay: EnrichmentOverlay) -> Self {
+        self.enrichment = (!overlay.is_empty()).then(|| Arc::new(overlay));
+        self
+    }
+
+    /// Set next-tool prediction thresholds and predictive warm-up behaviour.
+    #[must_use]
+    pub fn with_prediction(mut self, config: PredictionConfig) -> Self {
+        self.prediction = config;
+        self
+    }
+
+    /// Attach the in-memory stats time series served by `GET /stats`.
+    #[must_use]
+    pub fn with_stats_history(mut self, history: Arc<StatsHistory>) -> Self {
+        self.stats_history = Some(history);
+        self
+    }
+
+    /// Attach per-backend SLO tracking (`slo.objectives`).
+    ///
+    /// A tracker without objectives is dropped so the hot path stays a no-op.
+    #[must_use]
+    pub fn with_slo_tracker(mut self, tracker: Arc<SloTracker>) -> Self {
+        self.slo_tracker = (!tracker.is_empty()).then_some(tracker);
+        self
+    }
+
+    /// Raise operator alerts (auto-kills, revives, disabled capabilities)
+    /// through `notifier`.
+    #[must_use]
+    pub fn with_alerts(mut self, notifier: Arc<AlertNotifier>) -> Self {
+        self.alerts = Some(notifier);
+        self
+    }
+
     /// Attach a local identity grant store for personal capability dispatch.
     #[must_use]
     pub fn with_identity_grants(mut self, grants: LocalIdentityGrantStore) -> Self {
@@ -499,7 +666,6 @@ impl MetaMcp {
     }
 
     /// Enable idempotency support with a background cleanup task.
-    #[allow(dead_code)]
     pub fn enable_idempotency(&mut self, cache: Arc<IdempotencyCache>, cleanup_interval: Duration) {
         spawn_cleanup_task(Arc::clone(&cache), cleanup_interval);
         self.idempotency_cache = Some(cache);
@@ -562,6 +728,11 @@ impl MetaMcp {
         *self.webhook_registry.write() = Some(registry);
     }
 
+    /// Attach the background discovery monitor for `gateway_discovery_status`.
+    pub fn set_discovery_monitor(&self, monitor: Arc<DiscoveryMonitor>) {
+        *self.discovery_monitor.write() = Some(monitor);
+    }
+
     /// Enable action mode for response-side anomaly screening (issue #133, D2).
     ///
     /// When called, responses with HIGH/CRITICAL inspection findings are
@@ -783,8 +954,26 @@ impl MetaMcp {
         self
     }
 
+    /// Take a token from `client`'s bucket for `backend`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RateLimitExceeded`] with the configured limit and wait time
+    /// when the bucket is empty.
+    pub fn check_client_rate_limit(
+        &self,
+        client: &str,
+        backend: &str,
+    ) -> std::result::Result<(), RateLimitExceeded> {
+        let Some(limiter) = &self.client_rate_limiter else {
+            return Ok(());
+        };
+        limiter.check(client, backend).inspect_err(|exceeded| {
+            warn!(client, backend, limit = %exceeded.limit, "Client rate limit exceeded");
+        })
+    }
+
     /// Expose the kill switch for external introspection or testing.
-    #[allow(dead_code)]
     pub fn kill_switch(&self) -> Arc<KillSwitch> {
         Arc::clone(&self.kill_switch)
     }
@@ -825,6 +1014,10 @@ impl MetaMcp {
         self.webhook_registry.read().clone()
     }
 
+    pub(super) fn get_discovery_monitor(&self) -> Option<Arc<DiscoveryMonitor>> {
+        self.discovery_monitor.read().clone()
+    }
+
     pub(super) fn get_reload_context(&self) -> Option<Arc<ReloadContext>> {
         self.reload_context.read().clone()
     }
@@ -902,6 +1095,37 @@ impl MetaMcp {
         );
         self.profile_registry.get(&name)
     }
+
+    /// Searchable description of a backend tool: the enrichment overlay
+    /// entry when one matches the current description, else `autotag`.
+    pub(super) fn enrich_tool_description(
+        &self,
+        server: &str,
+        tool: &str,
+        description: &str,
+    ) -> String {
+        self.enrichment
+            .as_ref()
+            .and_then(|overlay| overlay.get(server, tool, description))
+            .map_or_else(
+                || self.autotag.enrich(description),
+                |entry| entry.apply(description),
+            )
+    }
+
+    /// Ranking context for a session: its id plus its routing profile when
+    /// that is not the gateway default (which every session shares).
+    pub(super) fn ranking_context<'a>(
+        &self,
+        session_id: Option<&'a str>,
+        profile: &'a crate::routing_profile::RoutingProfile,
+    ) -> crate::ranking::SessionContext<'a> {
+        crate::ranking::SessionContext {
+            session_id,
+            profile: (profile.name != self.profile_registry.default_name())
+                .then_some(profile.name.as_str()),
+        }
+    }
 }
 
 // ============================================================================
@@ -989,7 +1213,8 @@ impl MetaMcp {
     /// Handle `tools/list` — Code Mode returns 2 tools; Traditional returns full set.
     ///
     /// When surfaced tools are configured, their schemas are appended after the
-    /// meta-tools (subject to routing profile filtering).  Tools whose backend
+    /// meta-tools (subject to routing profile filtering), followed by published
+    /// playbooks.  Tools whose backend
     /// cache is empty are silently omitted rather than blocking the response.
     pub fn handle_tools_list(&self, id: RequestId) -> JsonRpcResponse {
         self.handle_tools_list_for_session(id, None)
@@ -1005,14 +1230,18 @@ impl MetaMcp {
             build_code_mode_tools()
         } else {
             let (tool_count, server_count) = self.backend_counts();
-            build_meta_tools(
+            let mut tools = build_meta_tools(
                 self.stats.is_some(),
                 self.get_webhook_registry().is_some(),
                 self.get_reload_context().is_some(),
                 true, // cost_report always enabled (tracker is always present)
                 tool_count,
                 server_count,
-            )
+            );
+            if self.get_discovery_monitor().is_some() {
+                tools.push(build_discovery_status_tool());
+            }
+            tools
         };
         let mut tool_descriptors =
             project_tool_descriptors_trust_cards("gateway:meta", "mcp-gateway", &tools);
@@ -1033,6 +1262,13 @@ impl MetaMcp {
                     ));
                 }
             }
+            for tool in self.playbook_tool_definitions() {
+                tool_descriptors.push(project_tool_descriptor_trust_card(
+                    "gateway:playbook",
+                    "mcp-gateway",
+                    &tool,
+                ));
+            }
         }
 
         // Append session-promoted tools (spec-preview only).
@@ -1176,9 +1412,10 @@ impl MetaMcp {
                 )
                 .await
             }
-            "gateway_get_stats" => self.get_stats(&arguments).await,
+            "gateway_get_stats" => self.get_stats(&arguments, caller.api_key_name).await,
             "gateway_cost_report" => self.get_cost_report(&arguments, session_id).await,
             "gateway_webhook_status" => self.webhook_status(),
+            "gateway_discovery_status" => self.discovery_status(),
             "gateway_run_playbook" => self.run_playbook(&arguments).await,
             "gateway_kill_server" => self.kill_server(&arguments),
             "gateway_revive_server" => self.revive_server(&arguments),
@@ -1189,6 +1426,7 @@ impl MetaMcp {
             "gateway_set_state" => self.set_state(&arguments, session_id),
             "gateway_reload_config" => self.reload_config().await,
             "gateway_reload_capabilities" => self.reload_capabilities().await,
+            name if self.is_playbook_tool(name) => self.run_playbook_tool(name, arguments).await,
             _ => {
                 const META_TOOLS: &[&str] = &[
                     "gateway_search",
@@ -1200,6 +1438,7 @@ impl MetaMcp {
                     "gateway_get_stats",
                     "gateway_cost_report",
                     "gateway_webhook_status",
+                    "gateway_discovery_status",
                     "gateway_run_playbook",
                     "gateway_kill_server",
                     "gateway_revive_server",
@@ -154,6 +154,8 @@ reducing noise and accidental invocations of unrelated backends.
 If a backend misbehaves you can stop routing to it immediately:
   gateway_kill_server(server=X)
   gateway_revive_server(server=X)  — re-enables and resets error budget
+
+Pass server=X:tool to disable or re-enable a single tool instead.
 "
     .to_string()
 }
@@ -10,8 +10,8 @@ use std::sync::Arc;
 use serde_json::{Value, json};
 use tracing::debug;
 
-use crate::autotag;
 use crate::backend::Backend;
+use crate::capability::CapabilityDefinition;
 use crate::projection::Role;
 use crate::protocol::Tool;
 use crate::ranking::json_to_search_result;
@@ -32,6 +32,21 @@ use super::support::{
     ranked_results_to_code_mode_json,
 };
 
+/// Add a capability's `version` and any deprecation warning to its search
+/// match.
+fn annotate_capability_match(entry: &mut Value, capability: &CapabilityDefinition) {
+    if let Some(version) = &capability.version {
+        entry["version"] = json!(version);
+    }
+    if let Some(warning) = capability.deprecation_warning() {
+        entry["deprecated"] = json!(true);
+        entry["warning"] = json!(warning);
+        if let Some(replacement) = &capability.replaced_by {
+            entry["replaced_by"] = json!(replacement);
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 struct CodeModeSearchOptions {
     include_schema: bool,
@@ -210,7 +225,8 @@ impl MetaMcp {
                 if Self::code_mode_tool_matches(&cap.name, &tool, query, options.use_glob) {
                     let mut entry =
                         build_code_mode_match_json(&cap.name, &tool, options.include_schema);
-                    if cap_killed {
+                    annotate_capability_match(&mut entry, &capability);
+                    if cap_killed || self.kill_switch.is_tool_killed(&cap.name, &tool.name) {
                         entry["status"] = json!("disabled");
                     }
                     matches.push(entry);
@@ -249,7 +265,8 @@ impl MetaMcp {
                     .map(|tool| {
                         let mut t = tool.clone();
                         if let Some(ref desc) = t.description {
-                            t.description = Some(autotag::enrich_description(desc));
+                            t.description =
+                                Some(self.enrich_tool_description(&backend.name, &t.name, desc));
                         }
                         t
                     })
@@ -265,7 +282,9 @@ impl MetaMcp {
                             &tool,
                             options.include_schema,
                         );
-                        if backend_killed {
+                        if backend_killed
+                            || self.kill_switch.is_tool_killed(&backend.name, &tool.name)
+                        {
                             entry["status"] = json!("disabled");
                         }
                         matches.push(entry);
@@ -307,7 +326,8 @@ impl MetaMcp {
                         &tool,
                         &capability.metadata.chains_with,
                     );
-                    if cap_killed {
+                    annotate_capability_match(&mut entry, &capability);
+                    if cap_killed || self.kill_switch.is_tool_killed(&cap.name, &tool.name) {
                         entry["status"] = json!("disabled");
                     }
                     matches.push(entry);
@@ -340,7 +360,8 @@ impl MetaMcp {
                     .map(|tool| {
                         let mut t = tool.clone();
                         if let Some(ref desc) = t.description {
-                            t.description = Some(autotag::enrich_description(desc));
+                            t.description =
+                                Some(self.enrich_tool_description(&backend.name, &t.name, desc));
                         }
                         t
                     })
@@ -352,7 +373,9 @@ impl MetaMcp {
                 for tool in enriched {
                     if tool_matches_query(&tool, query) {
                         let mut entry = build_match_json(&backend.name, &tool);
-                        if backend_killed {
+                        if backend_killed
+                            || self.kill_switch.is_tool_killed(&backend.name, &tool.name)
+                        {
                             entry["status"] = json!("disabled");
                         }
                         matches.push(entry);
@@ -413,7 +436,11 @@ impl MetaMcp {
                 .iter()
                 .filter_map(json_to_code_mode_search_result)
                 .collect();
-            let ranked = ranker.rank(search_results, &query);
+            let ranked = ranker.rank_for(
+                search_results,
+                &query,
+                self.ranking_context(session_id, &profile),
+            );
             matches = ranked_results_to_code_mode_json(ranked, include_schema, &matches);
         }
 
@@ -667,7 +694,7 @@ impl MetaMcp {
                     "name": tool.name,
                     "description": tool.description.as_deref().unwrap_or("")
                 });
-                if cap_killed {
+                if cap_killed || self.kill_switch.is_tool_killed(&cap.name, &tool.name) {
                     entry["status"] = json!("disabled");
                 }
                 all_tools.push(entry);
@@ -691,14 +718,18 @@ impl MetaMcp {
                     if !profile.tool_allowed(&tool.name) || !tool_matches_role(tool, role_filter) {
                         continue;
                     }
-                    let desc =
-                        autotag::enrich_description(tool.description.as_deref().unwrap_or(""));
+                    let desc = self.enrich_tool_description(
+                        &backend.name,
+                        &tool.name,
+                        tool.description.as_deref().unwrap_or(""),
+                    );
                     let mut entry = json!({
                         "server": &backend.name,
                         "name": &tool.name,
                         "description": desc
                     });
-                    if backend_killed {
+                    if backend_killed || self.kill_switch.is_tool_killed(&backend.name, &tool.name)
+                    {
                         entry["status"] = json!("disabled");
                     }
                     all_tools.push(entry);
@@ -764,7 +795,14 @@ impl MetaMcp {
         // Apply ranking if enabled, then truncate to limit
         if let Some(ref ranker) = self.ranker {
             let search_results: Vec<_> = matches.iter().filter_map(json_to_search_result).collect();
-            let ranked = ranker.rank(search_results, &query);
+            let ranked = ranker.rank_for(
+                search_results,
+                &query,
+                self.ranking_context(session_id, &profile),
+            );
+            if let Some(sid) = session_id {
+                ranker.record_search(sid, &query, &ranked[..ranked.len().min(limit)]);
+            }
             matches = ranked_results_to_json(ranked);
         }
 
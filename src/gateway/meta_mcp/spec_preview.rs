@@ -9,7 +9,6 @@
 use serde_json::{Value, json};
 use tracing::debug;
 
-use crate::autotag;
 use crate::protocol::{JsonRpcResponse, RequestId, Tool, ToolsListResult};
 
 use super::super::meta_mcp_helpers::did_you_mean;
@@ -99,7 +98,8 @@ impl MetaMcp {
                 }
                 // Enrich description with auto-tags before matching
                 if let Some(ref desc) = t.description {
-                    t.description = Some(autotag::enrich_description(desc));
+                    t.description =
+                        Some(self.enrich_tool_description(&backend.name, &t.name, desc));
                 }
                 if tool_text_matches(&t, query) {
                     tools.push(t);
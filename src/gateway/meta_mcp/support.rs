@@ -8,7 +8,7 @@
 use serde_json::{Value, json};
 
 use crate::Result;
-use crate::idempotency::{IdempotencyCache, derive_key};
+use crate::idempotency::{IdempotencyCache, ToolIdempotency, derive_key};
 use crate::playbook::ToolInvoker;
 
 use super::super::meta_mcp_helpers::extract_optional_str;
@@ -21,10 +21,13 @@ use super::MetaMcp;
 /// Resolve the idempotency key for a `gateway_invoke` call.
 ///
 /// Priority:
-/// 1. Explicit `"idempotency_key"` string in `args` — used verbatim.
-/// 2. Auto-derived from `(server, tool, arguments)` when an `IdempotencyCache`
-///    is active.  This protects against exact-duplicate LLM retries even when
-///    the client supplies no key.
+/// 1. Tools the cache's [`IdempotencyPolicy`](crate::idempotency::IdempotencyPolicy)
+///    marks `never` are not checked at all.
+/// 2. Explicit `"idempotency_key"` string in `args` — used verbatim.
+/// 3. Auto-derived from `(server, tool, arguments)` for tools marked `always`,
+///    or — when auto-derivation is on — for tools not annotated read-only or
+///    idempotent (`repeat_safe`). This protects against exact-duplicate LLM
+///    retries even when the client supplies no key.
 ///
 /// Returns `None` when no idempotency cache is configured.
 pub(super) fn resolve_idempotency_key(
@@ -33,12 +36,20 @@ pub(super) fn resolve_idempotency_key(
     tool: &str,
     arguments: &Value,
     idem_cache: Option<&std::sync::Arc<IdempotencyCache>>,
+    repeat_safe: impl FnOnce() -> bool,
 ) -> Option<String> {
-    idem_cache?;
+    let policy = idem_cache?.policy();
+    let mode = policy.mode(server, tool);
+    if mode == ToolIdempotency::Never {
+        return None;
+    }
     // Explicit key takes precedence.
     if let Some(key) = extract_optional_str(args, "idempotency_key") {
         return Some(key.to_string());
     }
+    if !policy.derives_key(mode, repeat_safe) {
+        return None;
+    }
     // Auto-derive from (server, tool, arguments) — stable, deterministic.
     let combined = format!("{server}:{tool}");
     Some(derive_key(&combined, arguments))
@@ -42,6 +42,7 @@ impl MetaMcp {
             "gateway_get_stats",
             "gateway_cost_report",
             "gateway_webhook_status",
+            "gateway_discovery_status",
             "gateway_run_playbook",
             "gateway_kill_server",
             "gateway_revive_server",
@@ -224,6 +224,39 @@ fn handle_tools_list_code_mode_disabled_returns_meta_tools() {
     );
 }
 
+#[test]
+fn discovery_status_tool_is_listed_and_served_once_discovery_runs() {
+    use crate::config::{DiscoveryAdoptPolicy, DiscoveryConfig};
+    use crate::discovery::monitor::DiscoveryMonitor;
+
+    // GIVEN: a gateway without background discovery
+    let meta = make_meta_mcp();
+    let listed = |meta: &MetaMcp| {
+        let response = meta.handle_tools_list(RequestId::Number(1));
+        response.result.unwrap()["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|tool| tool["name"] == "gateway_discovery_status")
+    };
+    assert!(!listed(&meta));
+    assert!(meta.discovery_status().is_err());
+
+    // WHEN: a discovery monitor is attached
+    meta.set_discovery_monitor(Arc::new(DiscoveryMonitor::new(&DiscoveryConfig {
+        enabled: true,
+        auto_adopt: DiscoveryAdoptPolicy::Local,
+        ..DiscoveryConfig::default()
+    })));
+
+    // THEN: the tool is listed and reports the policy and scan state
+    assert!(listed(&meta));
+    let status = meta.discovery_status().unwrap();
+    assert_eq!(status["auto_adopt"], "local");
+    assert_eq!(status["scans"], 0);
+    assert_eq!(status["unmanaged_total"], 0);
+}
+
 #[test]
 fn handle_tools_list_code_mode_enabled_returns_exactly_two_tools() {
     // GIVEN: code mode is enabled
@@ -1325,6 +1358,7 @@ fn with_surfaced_tools_drops_all_known_meta_tool_names() {
         "gateway_get_stats",
         "gateway_cost_report",
         "gateway_webhook_status",
+        "gateway_discovery_status",
         "gateway_run_playbook",
         "gateway_kill_server",
         "gateway_revive_server",
@@ -1677,6 +1711,80 @@ fn revive_server_unregistered_backend_reports_breaker_not_open() {
     assert_eq!(result["status"], "active");
 }
 
+// =========================================================================
+// gateway_kill_server — per-tool targets
+// =========================================================================
+
+fn meta_with_tool_call_backend(name: &str) -> MetaMcp {
+    use crate::backend::Backend;
+    use crate::config::{BackendConfig, FailsafeConfig};
+
+    let registry = Arc::new(BackendRegistry::new());
+    let backend = Arc::new(Backend::new(
+        name,
+        BackendConfig::default(),
+        &FailsafeConfig::default(),
+        Duration::from_secs(300),
+    ));
+    backend.set_transport_for_test(Arc::new(ToolCallTestTransport {
+        result: json!({"content": [{"type": "text", "text": "ok"}], "isError": false}),
+    }));
+    registry.register(backend);
+    MetaMcp::new(registry)
+}
+
+async fn invoke(meta: &MetaMcp, server: &str, tool: &str) -> crate::Result<serde_json::Value> {
+    meta.invoke_tool(
+        &json!({"server": server, "tool": tool, "arguments": {}}),
+        Some("session-1"),
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn kill_server_with_tool_target_disables_only_that_tool() {
+    // GIVEN: a live backend
+    let meta = meta_with_tool_call_backend("docs");
+
+    // WHEN: the operator kills one of its tools
+    let result = meta
+        .kill_server(&json!({"server": "docs:delete_page"}))
+        .unwrap();
+
+    // THEN: only that tool is rejected; the backend stays live
+    assert_eq!(result["tool"], "delete_page");
+    assert_eq!(result["was_already_killed"], false);
+    assert!(!meta.kill_switch.is_killed("docs"));
+    let err = invoke(&meta, "docs", "delete_page").await.unwrap_err();
+    assert!(
+        err.to_string().contains("disabled by operator kill switch"),
+        "{err}"
+    );
+    assert!(invoke(&meta, "docs", "search").await.is_ok());
+    assert_eq!(
+        meta.list_disabled_capabilities().unwrap()["killed_tools"],
+        json!(["docs:delete_page"])
+    );
+}
+
+#[tokio::test]
+async fn revive_server_with_tool_target_re_enables_tool() {
+    let meta = meta_with_tool_call_backend("docs");
+    meta.kill_server(&json!({"server": "docs:delete_page"}))
+        .unwrap();
+
+    let result = meta
+        .revive_server(&json!({"server": "docs:delete_page"}))
+        .unwrap();
+
+    assert_eq!(result["was_killed"], true);
+    assert!(invoke(&meta, "docs", "delete_page").await.is_ok());
+}
+
 // ── Discovery-surface firewall scan (OWASP ASI01 tool-poisoning) ──────────
 //
 // `scan_tool_list_value` is the seam that routes the aggregated discovery
@@ -2193,3 +2301,426 @@ mod attestation_wiring {
         assert!(validator.verify_result_provenance(&signed));
     }
 }
+
+// ── predictive warm-up ────────────────────────────────────────────────
+
+fn meta_with_cold_backend(name: &str) -> MetaMcp {
+    use crate::backend::Backend;
+    use crate::config::{BackendConfig, FailsafeConfig};
+
+    let registry = Arc::new(BackendRegistry::new());
+    registry.register(Arc::new(Backend::new(
+        name,
+        BackendConfig::default(),
+        &FailsafeConfig::default(),
+        Duration::from_secs(300),
+    )));
+    MetaMcp::new(registry)
+}
+
+#[tokio::test]
+async fn warm_up_predicted_spawns_once_per_backend_within_cooldown() {
+    // GIVEN: a cold backend and predictions naming two of its tools plus
+    // tools of unknown or malformed servers
+    let meta = meta_with_cold_backend("docs");
+    let predicted = ["docs:search", "docs:fetch", "ghost:tool", "no_separator"];
+
+    // WHEN: warming up twice in a row
+    let first = meta.warm_up_predicted(predicted);
+    let second = meta.warm_up_predicted(predicted);
+
+    // THEN: the backend is warmed once and the repeat is held by the cooldown
+    assert_eq!(first, ["docs"]);
+    assert!(second.is_empty());
+}
+
+#[tokio::test]
+async fn record_and_predict_warms_predicted_backend() {
+    // GIVEN: a learned "a:first → docs:search" transition above the thresholds
+    let meta = meta_with_cold_backend("docs").with_prediction(crate::config::PredictionConfig {
+        min_count: 2,
+        ..crate::config::PredictionConfig::default()
+    });
+    meta.set_transition_tracker(Arc::new(TransitionTracker::new()));
+    for session in ["s1", "s2"] {
+        meta.record_and_predict(Some(session), "a:first");
+        meta.record_and_predict(Some(session), "docs:search");
+    }
+
+    // WHEN: a new session invokes the first tool
+    let predictions = meta.record_and_predict(Some("s3"), "a:first");
+
+    // THEN: the prediction is returned and the backend was already warmed,
+    // so a second warm-up is within the cooldown
+    assert_eq!(predictions[0]["tool"], "docs:search");
+    assert!(meta.warm_up_predicted(["docs:search"]).is_empty());
+}
+
+#[test]
+fn warm_up_predicted_is_noop_outside_runtime() {
+    let meta = meta_with_cold_backend("docs");
+    assert!(meta.warm_up_predicted(["docs:search"]).is_empty());
+}
+
+// ── auto-revive probing ───────────────────────────────────────────────
+
+struct PingTestTransport {
+    healthy: bool,
+}
+
+#[async_trait::async_trait]
+impl crate::transport::Transport for PingTestTransport {
+    async fn request(
+        &self,
+        method: &str,
+        _params: Option<serde_json::Value>,
+    ) -> crate::Result<crate::protocol::JsonRpcResponse> {
+        assert_eq!(method, "ping");
+        if self.healthy {
+            Ok(crate::protocol::JsonRpcResponse::success_serialized(
+                RequestId::Number(1),
+                json!({}),
+            ))
+        } else {
+            Err(crate::Error::Transport("connection refused".to_string()))
+        }
+    }
+
+    async fn notify(&self, _method: &str, _params: Option<serde_json::Value>) -> crate::Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    async fn close(&self) -> crate::Result<()> {
+        Ok(())
+    }
+}
+
+/// A `MetaMcp` whose backend `name` was just auto-killed by its error budget.
+fn meta_with_auto_killed_backend(name: &str, healthy: bool) -> MetaMcp {
+    let meta = meta_with_cold_backend(name);
+    if let Some(backend) = meta.backends.get(name) {
+        backend.set_transport_for_test(Arc::new(PingTestTransport { healthy }));
+    }
+    assert!(
+        meta.kill_switch
+            .record_failure(name, 10, Duration::from_secs(60), 0.5, 1)
+    );
+    meta
+}
+
+fn immediate_auto_revive() -> crate::config::AutoReviveConfig {
+    crate::config::AutoReviveConfig {
+        cooldown: Duration::ZERO,
+        ..crate::config::AutoReviveConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn probe_auto_killed_revives_backend_that_answers() {
+    // GIVEN: an auto-killed backend that answers pings
+    let meta = meta_with_auto_killed_backend("docs", true);
+
+    // WHEN: the cooldown has elapsed and the probe runs
+    let revived = meta.probe_auto_killed(&immediate_auto_revive()).await;
+
+    // THEN: the backend is live again with a clean budget window
+    assert_eq!(revived, ["docs"]);
+    assert!(!meta.kill_switch.is_killed("docs"));
+    assert_eq!(meta.kill_switch.window_counts("docs"), (0, 0));
+}
+
+#[tokio::test]
+async fn probe_auto_killed_keeps_failing_backend_killed() {
+    // GIVEN: an auto-killed backend that still fails
+    let meta = meta_with_auto_killed_backend("docs", false);
+
+    // WHEN: the probe runs
+    let revived = meta.probe_auto_killed(&immediate_auto_revive()).await;
+
+    // THEN: it stays killed and remains scheduled for another probe
+    assert!(revived.is_empty());
+    assert!(meta.kill_switch.is_killed("docs"));
+    assert!(meta.kill_switch.is_auto_killed("docs"));
+}
+
+#[tokio::test]
+async fn probe_auto_killed_waits_for_cooldown() {
+    let meta = meta_with_auto_killed_backend("docs", true);
+    let revived = meta
+        .probe_auto_killed(&crate::config::AutoReviveConfig::default())
+        .await;
+    assert!(revived.is_empty());
+    assert!(meta.kill_switch.is_killed("docs"));
+}
+
+#[tokio::test]
+async fn probe_auto_killed_raises_revived_alert() {
+    // GIVEN: alerts attached and an auto-killed backend that answers pings
+    let notifier = Arc::new(crate::alerts::AlertNotifier::new());
+    let mut alerts = notifier.subscribe();
+    let meta = meta_with_auto_killed_backend("docs", true).with_alerts(notifier);
+
+    // WHEN: the probe revives it
+    meta.probe_auto_killed(&immediate_auto_revive()).await;
+
+    // THEN: operators are told the backend is back
+    let alert = alerts.try_recv().unwrap();
+    assert_eq!(alert.kind, crate::alerts::AlertKind::BackendRevived);
+    assert_eq!(alert.backend, "docs");
+}
+
+// ── Scheduled jobs ───────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn run_scheduled_job_failure_raises_alert() {
+    // GIVEN: alerts attached and a job naming a playbook that does not exist
+    let notifier = Arc::new(crate::alerts::AlertNotifier::new());
+    let mut alerts = notifier.subscribe();
+    let meta = make_meta_mcp().with_alerts(notifier);
+    let action = crate::scheduler::ScheduleAction::RunPlaybook("missing".to_string());
+
+    // WHEN: the scheduler runs it
+    let result = meta.run_scheduled_job("nightly", &action).await;
+
+    // THEN: the job fails and operators are told which job it was
+    assert!(result.is_err());
+    let alert = alerts.try_recv().unwrap();
+    assert_eq!(alert.kind, crate::alerts::AlertKind::ScheduledJobFailed);
+    assert_eq!(alert.details["job"], "nightly");
+    assert_eq!(alert.details["target"], "playbook:missing");
+}
+
+// ── Playbooks as tools ───────────────────────────────────────────────────
+
+fn meta_with_playbook(expose: bool) -> MetaMcp {
+    let meta = meta_with_tool_call_backend("docs").with_playbook_tools(expose);
+    let definition: crate::playbook::PlaybookDefinition = serde_yaml::from_str(
+        r#"
+name: lookup
+description: Look up a page
+inputs:
+  type: object
+  properties:
+    page: { type: string }
+  required: [page]
+steps:
+  - name: fetch
+    server: docs
+    tool: get_page
+    arguments: { page: "$inputs.page" }
+"#,
+    )
+    .unwrap();
+    let mut engine = crate::playbook::PlaybookEngine::new();
+    engine.register(definition);
+    meta.set_playbook_engine(engine);
+    meta
+}
+
+fn listed_names(meta: &MetaMcp) -> Vec<String> {
+    let result = meta.handle_tools_list(RequestId::Number(1)).result.unwrap();
+    result["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|t| t["name"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn tools_list_publishes_playbooks_when_enabled() {
+    // GIVEN: a registered playbook published as a tool
+    let meta = meta_with_playbook(true);
+
+    // WHEN: listing tools
+    let result = meta.handle_tools_list(RequestId::Number(1)).result.unwrap();
+
+    // THEN: the playbook appears with its declared input schema
+    let tool = result["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["name"] == "gateway_run_lookup")
+        .expect("playbook tool listed");
+    assert_eq!(tool["description"], "Look up a page");
+    assert_eq!(tool["inputSchema"]["required"], json!(["page"]));
+}
+
+#[test]
+fn tools_list_omits_playbooks_by_default() {
+    let meta = meta_with_playbook(false);
+    assert!(!listed_names(&meta).contains(&"gateway_run_lookup".to_string()));
+}
+
+#[tokio::test]
+async fn tools_call_runs_published_playbook() {
+    // GIVEN: a published playbook whose step calls a live backend
+    let meta = meta_with_playbook(true);
+
+    // WHEN: an ordinary client calls it by its tool name
+    let response = meta
+        .handle_tools_call(
+            RequestId::Number(1),
+            "gateway_run_lookup",
+            json!({"page": "intro"}),
+            None,
+            MetaMcpCallerContext::default(),
+        )
+        .await;
+
+    // THEN: the playbook ran its step
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let text = response.result.unwrap()["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let run: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(run["steps_completed"], json!(["fetch"]), "{text}");
+}
+
+#[tokio::test]
+async fn tools_call_rejects_unpublished_playbook_tool() {
+    let meta = meta_with_playbook(false);
+    let response = meta
+        .handle_tools_call(
+            RequestId::Number(1),
+            "gateway_run_lookup",
+            json!({}),
+            None,
+            MetaMcpCallerContext::default(),
+        )
+        .await;
+    assert_eq!(response.error.unwrap().code, -32601);
+}
+
+// ── Durable playbook runs ────────────────────────────────────────────────
+
+#[tokio::test]
+async fn run_playbook_persists_run_and_resume_returns_completed_result() {
+    // GIVEN: a run store and a playbook calling a live backend
+    let dir = tempfile::tempdir().unwrap();
+    let store = Arc::new(crate::playbook::PlaybookRunStore::open(dir.path()).unwrap());
+    let meta = meta_with_playbook(false).with_playbook_runs(Arc::clone(&store));
+
+    // WHEN: the playbook runs
+    let result = meta
+        .run_playbook(&json!({"name": "lookup", "arguments": {"page": "intro"}}))
+        .await
+        .unwrap();
+
+    // THEN: the result names a persisted, completed run
+    let run_id = result["run_id"].as_str().unwrap();
+    let run = store.load(run_id).unwrap();
+    assert_eq!(run.status, crate::playbook::RunStatus::Completed);
+    assert_eq!(run.next_step, 1);
+
+    // WHEN: resuming the completed run
+    let resumed = meta.run_playbook(&json!({"resume": run_id})).await.unwrap();
+
+    // THEN: the stored result is returned without running it again
+    assert_eq!(resumed["steps_completed"], json!(["fetch"]));
+    assert_eq!(resumed["run_id"], run_id);
+}
+
+#[tokio::test]
+async fn resume_requires_a_known_persisted_run() {
+    let meta = meta_with_playbook(false);
+    let err = meta
+        .run_playbook(&json!({"resume": "0b4c1a52-7b7e-4d8e-9d3c-2f7b1a0e9c11"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("state_dir"), "{err}");
+
+    let dir = tempfile::tempdir().unwrap();
+    let store = Arc::new(crate::playbook::PlaybookRunStore::open(dir.path()).unwrap());
+    let meta = meta_with_playbook(false).with_playbook_runs(store);
+    let err = meta
+        .run_playbook(&json!({"resume": "0b4c1a52-7b7e-4d8e-9d3c-2f7b1a0e9c11"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not found"), "{err}");
+}
+
+#[tokio::test]
+async fn run_playbook_dry_run_plans_without_invoking_or_persisting() {
+    // GIVEN: a run store and a playbook whose step calls a backend
+    let dir = tempfile::tempdir().unwrap();
+    let store = Arc::new(crate::playbook::PlaybookRunStore::open(dir.path()).unwrap());
+    let meta = meta_with_playbook(false).with_playbook_runs(store);
+
+    // WHEN: dry-running it
+    let plan = meta
+        .run_playbook(&json!({"name": "lookup", "arguments": {"page": "intro"}, "dry_run": true}))
+        .await
+        .unwrap();
+
+    // THEN: the resolved call is listed, and no run was recorded
+    assert_eq!(
+        plan["calls"],
+        json!([{"step": "fetch", "server": "docs", "tool": "get_page", "arguments": {"page": "intro"}}])
+    );
+    assert!(plan.get("run_id").is_none());
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+    let err = meta
+        .run_playbook(&json!({"name": "lookup", "dry_run": true, "sample_results": []}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("sample_results"), "{err}");
+}
+
+#[test]
+fn reload_playbooks_swaps_in_directory_contents() {
+    // GIVEN: a registered playbook and a directory holding a different one
+    let meta = meta_with_playbook(true);
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("triage.yaml"),
+        "name: triage\ndescription: Triage\nsteps:\n  - name: a\n    server: docs\n    tool: t\n",
+    )
+    .unwrap();
+
+    // WHEN: reloading from the directory
+    let report = meta.reload_playbooks(&[dir.path().display().to_string()]);
+
+    // THEN: tools/list reflects the directory, not the old registration
+    assert_eq!(report.loaded, ["triage"]);
+    let names = listed_names(&meta);
+    assert!(names.contains(&"gateway_run_triage".to_string()));
+    assert!(!names.contains(&"gateway_run_lookup".to_string()));
+}
+
+#[tokio::test]
+async fn get_stats_attributes_cost_only_to_the_calling_key() {
+    // GIVEN: spend recorded by two API keys
+    let mut meta = make_meta_mcp();
+    meta.stats = Some(Arc::new(crate::stats::UsageStats::new()));
+    meta.cost_tracker
+        .record("s-alice", Some("alice"), "docs", "search", 1_000, 1.0);
+    meta.cost_tracker
+        .record("s-bob", Some("bob"), "docs", "search", 9_000, 1.0);
+
+    // WHEN: alice asks for gateway statistics
+    let stats = meta.get_stats(&json!({}), Some("alice")).await.unwrap();
+
+    // THEN: only alice's client and session are listed
+    let attribution = &stats["cost_attribution"];
+    let clients: Vec<&str> = attribution["by_client"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["client"].as_str().unwrap())
+        .collect();
+    assert_eq!(clients, ["alice"]);
+    let sessions: Vec<&str> = attribution["by_session"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["session_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(sessions, ["s-alice"]);
+}
@@ -12,7 +12,7 @@ use crate::protocol::{
     Content, Info, InitializeResult, JsonRpcResponse, PromptsCapability, RequestId,
     ResourcesCapability, ServerCapabilities, Tool, ToolsCallResult, ToolsCapability,
 };
-use crate::ranking::{SearchResult, expand_synonyms};
+use crate::ranking::{SearchResult, synonyms};
 use crate::stats::StatsSnapshot;
 use crate::{Error, Result};
 
@@ -20,9 +20,10 @@ use crate::{Error, Result};
 // The individual builders are used via `super::*` in the tests sub-module.
 #[allow(unused_imports)]
 pub(crate) use super::meta_mcp_tool_defs::{
-    build_base_tools, build_code_mode_execute_tool, build_code_mode_search_tool,
-    build_code_mode_tools, build_kill_server_tool, build_list_disabled_capabilities_tool,
-    build_meta_tools, build_reload_capabilities_tool, build_reload_config_tool,
+    PLAYBOOK_TOOL_PREFIX, build_base_tools, build_code_mode_execute_tool,
+    build_code_mode_search_tool, build_code_mode_tools, build_discovery_status_tool,
+    build_kill_server_tool, build_list_disabled_capabilities_tool, build_meta_tools,
+    build_playbook_definition_tool, build_reload_capabilities_tool, build_reload_config_tool,
     build_revive_server_tool, build_set_state_tool, build_stats_tool, build_webhook_status_tool,
 };
 
@@ -363,9 +364,9 @@ fn word_matches_text(word: &str, text: &str) -> bool {
     if text.contains(word) {
         return true;
     }
-    expand_synonyms(word)
+    synonyms::expand(word)
         .iter()
-        .any(|syn| *syn != word && text.contains(*syn))
+        .any(|syn| text.contains(syn.as_str()))
 }
 
 /// Build suggestions from the tag index when a search returns zero results.
@@ -588,7 +589,12 @@ pub(crate) fn build_stats_response(snapshot: &StatsSnapshot, price_per_million:
         "estimated_savings_usd": format!("${:.2}", estimated_savings),
         "top_tools": snapshot.top_tools,
         "total_cached_tokens": snapshot.total_cached_tokens,
-        "cached_tokens_by_server": snapshot.cached_tokens_by_server
+        "cached_tokens_by_server": snapshot.cached_tokens_by_server,
+        "latency_by_server": snapshot.latency_by_server,
+        "latency_by_tool": snapshot.latency_by_tool,
+        "errors_by_class": snapshot.errors_by_class,
+        "cache_hits_by_kind": snapshot.cache_hits_by_kind,
+        "cache_hits_by_server": snapshot.cache_hits_by_server
     })
 }
 
@@ -226,7 +226,7 @@ fn build_routing_instructions_includes_chain_section_when_chains_present() {
     // GIVEN: capabilities where one declares chains_with
     use crate::capability::{
         AuthConfig, CacheConfig, CapabilityDefinition, CapabilityMetadata, ProvidersConfig,
-        SchemaDefinition,
+        ResilienceConfig, SchemaDefinition,
     };
     use crate::transform::TransformConfig;
     use std::collections::HashMap;
@@ -248,6 +248,10 @@ fn build_routing_instructions_includes_chain_section_when_chains_present() {
         response_transform: TransformConfig::default(),
         projection: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
         webhooks: HashMap::new(),
         sha256: None,
     };
@@ -271,7 +275,7 @@ fn build_routing_instructions_omits_chain_section_when_no_chains() {
     // GIVEN: capabilities with no chains_with set
     use crate::capability::{
         AuthConfig, CacheConfig, CapabilityDefinition, CapabilityMetadata, ProvidersConfig,
-        SchemaDefinition,
+        ResilienceConfig, SchemaDefinition,
     };
     use crate::transform::TransformConfig;
     use std::collections::HashMap;
@@ -293,6 +297,10 @@ fn build_routing_instructions_omits_chain_section_when_no_chains() {
         response_transform: TransformConfig::default(),
         projection: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
         webhooks: HashMap::new(),
         sha256: None,
     };
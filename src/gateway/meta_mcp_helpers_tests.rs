@@ -365,7 +365,8 @@ fn make_capability_def(
     tags: &[&str],
 ) -> crate::capability::CapabilityDefinition {
     use crate::capability::{
-        AuthConfig, CacheConfig, CapabilityMetadata, ProvidersConfig, SchemaDefinition,
+        AuthConfig, CacheConfig, CapabilityMetadata, ProvidersConfig, ResilienceConfig,
+        SchemaDefinition,
     };
     use crate::transform::TransformConfig;
 
@@ -386,6 +387,10 @@ fn make_capability_def(
         response_transform: TransformConfig::default(),
         projection: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
         webhooks: std::collections::HashMap::new(),
         sha256: None,
     }
@@ -1,7 +1,9 @@
 // SPDX-FileCopyrightText: 2026 Mikko Parkkola
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use std::collections::BTreeMap;
+
 use super::*;
-use crate::stats::StatsSnapshot;
+use crate::stats::{LatencySummary, StatsSnapshot};
 
 // ── build_search_response ───────────────────────────────────────────
 
@@ -194,6 +196,11 @@ fn build_stats_response_fields() {
         top_tools: vec![],
         total_cached_tokens: 0,
         cached_tokens_by_server: vec![],
+        latency_by_server: vec![],
+        latency_by_tool: vec![],
+        errors_by_class: BTreeMap::new(),
+        cache_hits_by_kind: BTreeMap::new(),
+        cache_hits_by_server: BTreeMap::new(),
     };
     let resp = build_stats_response(&snapshot, 15.0);
     assert_eq!(resp["invocations"], 100);
@@ -217,6 +224,11 @@ fn build_stats_response_zero_values() {
         top_tools: vec![],
         total_cached_tokens: 0,
         cached_tokens_by_server: vec![],
+        latency_by_server: vec![],
+        latency_by_tool: vec![],
+        errors_by_class: BTreeMap::new(),
+        cache_hits_by_kind: BTreeMap::new(),
+        cache_hits_by_server: BTreeMap::new(),
     };
     let resp = build_stats_response(&snapshot, 15.0);
     assert_eq!(resp["invocations"], 0);
@@ -235,12 +247,49 @@ fn build_stats_response_custom_price() {
         top_tools: vec![],
         total_cached_tokens: 0,
         cached_tokens_by_server: vec![],
+        latency_by_server: vec![],
+        latency_by_tool: vec![],
+        errors_by_class: BTreeMap::new(),
+        cache_hits_by_kind: BTreeMap::new(),
+        cache_hits_by_server: BTreeMap::new(),
     };
     let resp = build_stats_response(&snapshot, 3.0);
     assert_eq!(resp["estimated_savings_usd"], "$3.00");
     assert_eq!(resp["cache_hit_rate"], "50.0%");
 }
 
+#[test]
+fn build_stats_response_includes_latency_and_breakdowns() {
+    let snapshot = StatsSnapshot {
+        invocations: 4,
+        cache_hits: 1,
+        cache_hit_rate: 0.25,
+        tools_discovered: 0,
+        tools_available: 10,
+        tokens_saved: 0,
+        top_tools: vec![],
+        total_cached_tokens: 0,
+        cached_tokens_by_server: vec![],
+        latency_by_server: vec![LatencySummary {
+            key: "github".to_string(),
+            samples: 4,
+            p50_ms: 12.0,
+            p95_ms: 80.0,
+            p99_ms: 95.5,
+        }],
+        latency_by_tool: vec![],
+        errors_by_class: BTreeMap::from([("timeout".to_string(), 2)]),
+        cache_hits_by_kind: BTreeMap::from([("response".to_string(), 1)]),
+        cache_hits_by_server: BTreeMap::from([("github".to_string(), 1)]),
+    };
+    let resp = build_stats_response(&snapshot, 15.0);
+    assert_eq!(resp["latency_by_server"][0]["key"], "github");
+    assert_eq!(resp["latency_by_server"][0]["p99_ms"], 95.5);
+    assert_eq!(resp["errors_by_class"]["timeout"], 2);
+    assert_eq!(resp["cache_hits_by_kind"]["response"], 1);
+    assert_eq!(resp["cache_hits_by_server"]["github"], 1);
+}
+
 // ── wrap_tool_success ───────────────────────────────────────────────
 
 #[test]
@@ -8,6 +8,7 @@
 
 use serde_json::json;
 
+use crate::playbook::PlaybookDefinition;
 use crate::protocol::{Tool, ToolAnnotations};
 
 // ============================================================================
@@ -139,7 +140,11 @@ fn build_invoke_tool() -> Tool {
             "properties": {
                 "server":    { "type": "string", "description": "Backend server name" },
                 "tool":      { "type": "string", "description": "Tool name to invoke" },
-                "arguments": { "type": "object", "description": "Tool arguments", "default": {} }
+                "arguments": { "type": "object", "description": "Tool arguments", "default": {} },
+                "account":   {
+                    "type": "string",
+                    "description": "Named OAuth account of the server to call as; sticks to the session"
+                }
             },
             "required": ["server", "tool"]
         }),
@@ -178,7 +183,7 @@ pub(crate) fn build_stats_tool() -> Tool {
         title: Some("Get Gateway Statistics".to_string()),
         description: Some(
             "Get usage statistics including invocations, cache hits, \
-         token savings, and top tools"
+         token savings, top tools, and estimated cost per client and session"
                 .to_string(),
         ),
         input_schema: json!({
@@ -219,9 +224,21 @@ pub(crate) fn build_playbook_tool() -> Tool {
                     "type": "object",
                     "description": "Playbook input arguments",
                     "default": {}
+                },
+                "resume": {
+                    "type": "string",
+                    "description": "run_id of an earlier run to resume from its last completed step (instead of name)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Return the ordered tool calls the playbook would make for these arguments without invoking anything",
+                    "default": false
+                },
+                "sample_results": {
+                    "type": "object",
+                    "description": "With dry_run: stand-in results of earlier steps, keyed by step name, for conditions and arguments that reference them"
                 }
-            },
-            "required": ["name"]
+            }
         }),
         output_schema: None,
         annotations: Some(write_non_idempotent_open_world_annotations("Run Playbook")),
@@ -230,6 +247,64 @@ pub(crate) fn build_playbook_tool() -> Tool {
     }
 }
 
+/// Prefix of the tool name a playbook is published under (`playbooks.expose_as_tools`).
+pub(crate) const PLAYBOOK_TOOL_PREFIX: &str = "gateway_run_";
+
+/// Build the first-class tool definition for one playbook.
+///
+/// The input schema is the playbook's `inputs` when it is a JSON Schema
+/// object; a bare property map is wrapped as the `properties` of one, and a
+/// missing `inputs` yields an empty object schema.
+pub(crate) fn build_playbook_definition_tool(definition: &PlaybookDefinition) -> Tool {
+    let input_schema = match &definition.inputs {
+        serde_json::Value::Object(map)
+            if map.contains_key("type") || map.contains_key("properties") =>
+        {
+            definition.inputs.clone()
+        }
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            json!({ "type": "object", "properties": map })
+        }
+        _ => json!({ "type": "object", "properties": {} }),
+    };
+    let title = format!("Playbook: {}", definition.name);
+    Tool {
+        name: format!("{PLAYBOOK_TOOL_PREFIX}{}", definition.name),
+        title: Some(title.clone()),
+        description: Some(definition.description.clone()),
+        input_schema,
+        output_schema: None,
+        annotations: Some(write_non_idempotent_open_world_annotations(&title)),
+        role: None,
+        projection: None,
+    }
+}
+
+/// Build the `gateway_discovery_status` meta-tool definition.
+pub(crate) fn build_discovery_status_tool() -> Tool {
+    Tool {
+        name: "gateway_discovery_status".to_string(),
+        title: Some("Discovery Status".to_string()),
+        description: Some(
+            "Report background MCP server discovery: servers found on this machine \
+         or network that are not configured backends, the last change (new and \
+         removed servers), backends added by the auto-adopt policy, and when the \
+         last scan ran. Connected clients also receive a `notifications/message` \
+         whenever the set of unmanaged servers changes."
+                .to_string(),
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {},
+            "required": []
+        }),
+        output_schema: None,
+        annotations: Some(read_only_annotations("Discovery Status")),
+        role: None,
+        projection: None,
+    }
+}
+
 /// Build the webhook status meta-tool definition.
 pub(crate) fn build_webhook_status_tool() -> Tool {
     Tool {
@@ -292,8 +367,9 @@ pub(crate) fn build_kill_server_tool() -> Tool {
         name: "gateway_kill_server".to_string(),
         title: Some("Kill Server".to_string()),
         description: Some(
-            "Immediately disable routing to a backend server (operator kill switch). \
-         The server's tools remain visible in search/list but are marked as disabled."
+            "Immediately disable routing to a backend server, or to a single tool \
+         with server=\"server:tool\" (operator kill switch). \
+         Disabled tools remain visible in search/list but are marked as disabled."
                 .to_string(),
         ),
         input_schema: json!({
@@ -301,7 +377,7 @@ pub(crate) fn build_kill_server_tool() -> Tool {
             "properties": {
                 "server": {
                     "type": "string",
-                    "description": "Name of the backend server to disable"
+                    "description": "Backend server to disable, or \"server:tool\" to disable one tool"
                 }
             },
             "required": ["server"]
@@ -319,8 +395,8 @@ pub(crate) fn build_revive_server_tool() -> Tool {
         name: "gateway_revive_server".to_string(),
         title: Some("Revive Server".to_string()),
         description: Some(
-            "Re-enable routing to a previously disabled backend server. \
-         Also resets the error budget so the server gets a clean slate."
+            "Re-enable routing to a previously disabled backend server or \"server:tool\". \
+         Also resets the error budget so the server or tool gets a clean slate."
                 .to_string(),
         ),
         input_schema: json!({
@@ -328,7 +404,7 @@ pub(crate) fn build_revive_server_tool() -> Tool {
             "properties": {
                 "server": {
                     "type": "string",
-                    "description": "Name of the backend server to re-enable"
+                    "description": "Backend server to re-enable, or \"server:tool\" for one tool"
                 }
             },
             "required": ["server"]
@@ -576,8 +652,8 @@ pub(crate) fn build_meta_tools(
 /// Build the `gateway_reload_capabilities` meta-tool definition.
 ///
 /// Re-reads every YAML capability file in every configured capability directory
-/// without restarting the gateway. Returns the new total count plus per-directory
-/// added / removed / changed lists. Pairs with `gateway_reload_config` (which
+/// without restarting the gateway. Returns the new total count, whether the
+/// tool list changed, and the files that failed to load. Pairs with `gateway_reload_config` (which
 /// reloads `config.yaml` and backend definitions) but addresses the more common
 /// hot path: an agent has just authored or edited a capability YAML and wants
 /// it visible without disconnecting.
@@ -587,10 +663,12 @@ pub(crate) fn build_reload_capabilities_tool() -> Tool {
         title: Some("Reload Capabilities".to_string()),
         description: Some(
             "Re-read all YAML capability files from disk and rebuild the capability \
-         backend's tool surface. Returns the new total. Useful when an agent has \
-         just written a new capability YAML and wants it usable without restarting \
-         the gateway. Clients should re-list capability tools to see additions; \
-         `tools/list_changed` notification is a follow-up."
+         backend's tool surface. Returns the new total, whether the tool list \
+         changed, and the files that failed to load (a broken file keeps serving \
+         its last good definition). Useful when an agent has just written a new \
+         capability YAML and wants it usable without restarting the gateway. \
+         Connected clients receive `notifications/tools/list_changed` when the \
+         tool list changed."
                 .to_string(),
         ),
         input_schema: json!({
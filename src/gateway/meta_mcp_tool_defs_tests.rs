@@ -367,3 +367,48 @@ fn all_code_mode_tools_have_descriptions() {
         );
     }
 }
+
+// ============================================================================
+// Playbook tools
+// ============================================================================
+
+fn playbook(inputs: &serde_json::Value) -> PlaybookDefinition {
+    serde_json::from_value(serde_json::json!({
+        "name": "triage",
+        "description": "Triage an issue",
+        "inputs": inputs,
+        "steps": [],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn playbook_tool_uses_declared_json_schema() {
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": {"issue": {"type": "integer"}},
+        "required": ["issue"]
+    });
+    let tool = build_playbook_definition_tool(&playbook(&schema));
+    assert_eq!(tool.name, "gateway_run_triage");
+    assert_eq!(tool.description.as_deref(), Some("Triage an issue"));
+    assert_eq!(tool.input_schema, schema);
+}
+
+#[test]
+fn playbook_tool_wraps_bare_property_map() {
+    let tool = build_playbook_definition_tool(&playbook(
+        &serde_json::json!({"issue": {"type": "integer"}}),
+    ));
+    assert_eq!(tool.input_schema["type"], "object");
+    assert_eq!(tool.input_schema["properties"]["issue"]["type"], "integer");
+}
+
+#[test]
+fn playbook_tool_without_inputs_takes_empty_object() {
+    let tool = build_playbook_definition_tool(&playbook(&serde_json::Value::Null));
+    assert_eq!(
+        tool.input_schema,
+        serde_json::json!({"type": "object", "properties": {}})
+    );
+}
@@ -3,14 +3,17 @@
 //! Gateway server implementation
 
 pub mod auth;
+pub mod caller;
 pub(crate) mod destructive_confirmation;
 mod differential;
+pub mod drain;
 mod http_error;
 mod meta_mcp;
 mod meta_mcp_helpers;
 mod meta_mcp_tool_defs;
 mod middleware;
 pub mod oauth;
+pub mod progress;
 pub mod proxy;
 pub mod recovery;
 mod router;
@@ -24,6 +27,7 @@ pub mod webhooks;
 mod ws_listener;
 
 pub use auth::{AuthState, ResolvedAuthConfig, auth_middleware};
+pub(crate) use meta_mcp_helpers::tool_name_matches_glob;
 pub use oauth::{
     AgentAuthState, AgentIdentity, AgentRegistry, GatewayKeyPair, agent_auth_middleware,
 };
@@ -9,7 +9,8 @@
 //!
 //! An in-process ECDSA P-256 key pair is generated at startup and cached for
 //! the lifetime of the process.  Key rotation is supported via
-//! [`GatewayKeyPair::rotate`].
+//! [`GatewayKeyPair::rotate`].  Other gateway signing keys, such as the key
+//! server's JWT key, are added to the set with [`GatewayKeyPair::publish`].
 //!
 //! P-256 (ES256) is preferred over RSA because:
 //! - Faster key generation
@@ -102,6 +103,8 @@ impl std::fmt::Debug for GatewayKeyInfo {
 #[derive(Clone)]
 pub struct GatewayKeyPair {
     inner: Arc<RwLock<GatewayKeyInfo>>,
+    /// Further public keys listed in the JWKS (see [`Self::publish`]).
+    published: Arc<RwLock<Vec<Jwk>>>,
 }
 
 impl GatewayKeyPair {
@@ -112,9 +115,37 @@ impl GatewayKeyPair {
     /// Returns an error string if key generation fails.
     pub fn generate() -> Result<Self, String> {
         let key_info = generate_key_info()?;
-        Ok(Self {
+        Ok(Self::with_key_info(key_info))
+    }
+
+    /// Load an ECDSA P-256 key pair from a PKCS#8 PEM private key.
+    ///
+    /// `kid` defaults to the RFC 7638 thumbprint of the public key, so every
+    /// process loading the same key publishes the same `kid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if the PEM is not a P-256 private key.
+    pub fn from_pem(pem: &str, kid: Option<String>) -> Result<Self, String> {
+        let key_pair =
+            RcgenKeyPair::from_pem(pem).map_err(|e| format!("Invalid signing key: {e}"))?;
+        if key_pair.algorithm() != &rcgen::PKCS_ECDSA_P256_SHA256 {
+            return Err("Signing key must be an ECDSA P-256 key".to_string());
+        }
+        let (x, y) = extract_ec_public_components_raw(key_pair.public_key_raw())?;
+        Ok(Self::with_key_info(GatewayKeyInfo {
+            kid: kid.unwrap_or_else(|| jwk_thumbprint(&x, &y)),
+            private_key_pem: key_pair.serialize_pem(),
+            x,
+            y,
+        }))
+    }
+
+    fn with_key_info(key_info: GatewayKeyInfo) -> Self {
+        Self {
             inner: Arc::new(RwLock::new(key_info)),
-        })
+            published: Arc::new(RwLock::new(Vec::new())),
+        }
     }
 
     /// Rotate the key pair — generates a new one and atomically replaces the old.
@@ -142,17 +173,36 @@ impl GatewayKeyPair {
     /// on another thread while holding a write lock, which is a fatal condition).
     pub fn jwks(&self) -> JwkSet {
         let guard = self.inner.read().expect("RwLock poisoned");
-        JwkSet {
-            keys: vec![Jwk {
-                kty: "EC".to_string(),
-                use_: "sig".to_string(),
-                alg: "ES256".to_string(),
-                crv: "P-256".to_string(),
-                kid: guard.kid.clone(),
-                x: guard.x.clone(),
-                y: guard.y.clone(),
-            }],
-        }
+        let mut keys = vec![Jwk {
+            kty: "EC".to_string(),
+            use_: "sig".to_string(),
+            alg: "ES256".to_string(),
+            crv: "P-256".to_string(),
+            kid: guard.kid.clone(),
+            x: guard.x.clone(),
+            y: guard.y.clone(),
+        }];
+        keys.extend(
+            self.published
+                .read()
+                .expect("RwLock poisoned")
+                .iter()
+                .cloned(),
+        );
+        JwkSet { keys }
+    }
+
+    /// List `keys` in the JWKS next to this key pair's own key. Keys are
+    /// kept across [`Self::rotate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal `RwLock` is poisoned.
+    pub fn publish(&self, keys: impl IntoIterator<Item = Jwk>) {
+        self.published
+            .write()
+            .expect("RwLock poisoned")
+            .extend(keys);
     }
 
     /// Return the current key info (for token signing).
@@ -204,6 +254,15 @@ fn extract_ec_public_components_raw(raw: &[u8]) -> Result<(String, String), Stri
     Ok((x, y))
 }
 
+/// RFC 7638 JWK thumbprint of a P-256 public key, base64url-encoded.
+fn jwk_thumbprint(x: &str, y: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    // Required members in lexicographic order, no whitespace (RFC 7638 §3.2).
+    let canonical = format!(r#"{{"crv":"P-256","kty":"EC","x":"{x}","y":"{y}"}}"#);
+    URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()))
+}
+
 // ── Minimal DER helpers ───────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -220,6 +279,40 @@ mod tests {
         assert!(info.private_key_pem.contains("PRIVATE KEY"));
     }
 
+    #[test]
+    fn from_pem_loads_a_key_with_a_stable_thumbprint_kid() {
+        // GIVEN: the PEM of a generated key
+        let pem = GatewayKeyPair::generate()
+            .unwrap()
+            .key_info()
+            .private_key_pem;
+
+        // WHEN: loading it twice, and once with an explicit kid
+        let first = GatewayKeyPair::from_pem(&pem, None).unwrap().key_info();
+        let second = GatewayKeyPair::from_pem(&pem, None).unwrap().key_info();
+        let named = GatewayKeyPair::from_pem(&pem, Some("ks-1".to_string())).unwrap();
+
+        // THEN: the derived kid is the same, and an explicit kid wins
+        assert_eq!(first.kid, second.kid);
+        assert_eq!(first.kid.len(), 43, "base64url SHA-256");
+        assert_eq!(named.key_info().kid, "ks-1");
+        assert!(GatewayKeyPair::from_pem("not a key", None).is_err());
+    }
+
+    #[test]
+    fn published_keys_are_listed_after_the_own_key() {
+        let kp = GatewayKeyPair::generate().unwrap();
+        let other = GatewayKeyPair::generate().unwrap();
+
+        kp.publish(other.jwks().keys);
+        kp.rotate().unwrap();
+
+        let jwks = kp.jwks();
+        assert_eq!(jwks.keys.len(), 2);
+        assert_eq!(jwks.keys[0].kid, kp.key_info().kid);
+        assert_eq!(jwks.keys[1].kid, other.key_info().kid);
+    }
+
     #[test]
     fn debug_output_redacts_private_key() {
         // MIK-6733 KEY.1/KEY.2: `{:?}` must never leak the signing private key.
@@ -0,0 +1,157 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! MCP progress notifications for the call in flight.
+//!
+//! A client opts in by sending `_meta.progressToken` with `tools/call`. The
+//! router then installs a [`ProgressReporter`] in the [`PROGRESS`]
+//! task-local for the duration of the call, and code anywhere below it (for
+//! example a capability consuming a streaming response) calls [`report`] to
+//! send `notifications/progress` to the caller's session. Outside such a
+//! scope [`report`] is a no-op.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde_json::{Value, json};
+
+use super::streaming::{NotificationMultiplexer, TaggedNotification};
+
+tokio::task_local! {
+    /// Task-local progress reporter of the current `tools/call`.
+    ///
+    /// Set by [`with_progress`]; used by [`report`].
+    pub static PROGRESS: ProgressReporter;
+}
+
+/// Sends `notifications/progress` for one request's progress token.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    multiplexer: Arc<NotificationMultiplexer>,
+    session_id: String,
+    token: Value,
+    progress: Arc<AtomicU64>,
+}
+
+impl ProgressReporter {
+    /// Reporter for `token`, delivering to `session_id`.
+    #[must_use]
+    pub fn new(
+        multiplexer: Arc<NotificationMultiplexer>,
+        session_id: String,
+        token: Value,
+    ) -> Self {
+        Self {
+            multiplexer,
+            session_id,
+            token,
+            progress: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Send the next progress step, optionally with a message (such as a
+    /// chunk of partial output). Returns whether the session received it.
+    pub fn report(&self, message: Option<&str>) -> bool {
+        let progress = self.progress.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut params = json!({
+            "progressToken": self.token,
+            "progress": progress,
+        });
+        if let Some(message) = message {
+            params["message"] = Value::String(message.to_string());
+        }
+        self.multiplexer.send_to_session(
+            &self.session_id,
+            TaggedNotification {
+                source: "gateway".to_string(),
+                event_type: "message".to_string(),
+                data: json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/progress",
+                    "params": params,
+                }),
+                event_id: Some(self.multiplexer.next_event_id()),
+            },
+        )
+    }
+}
+
+/// The `_meta.progressToken` of request `params`, when it is a string or an
+/// integer as MCP requires.
+#[must_use]
+pub fn progress_token(params: Option<&Value>) -> Option<Value> {
+    let token = params?.get("_meta")?.get("progressToken")?;
+    (token.is_string() || token.is_i64() || token.is_u64()).then(|| token.clone())
+}
+
+/// Run `future` with `reporter` installed as the task-local reporter, or
+/// unchanged when there is none.
+pub async fn with_progress<F, T>(reporter: Option<ProgressReporter>, future: F) -> T
+where
+    F: std::future::Future<Output = T>,
+{
+    match reporter {
+        Some(reporter) => PROGRESS.scope(reporter, future).await,
+        None => future.await,
+    }
+}
+
+/// Report progress of the current call, if its caller asked for progress.
+///
+/// Returns `false` when no reporter is installed or the session is gone.
+pub fn report(message: Option<&str>) -> bool {
+    PROGRESS
+        .try_with(|reporter| reporter.report(message))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::BackendRegistry;
+    use crate::config::StreamingConfig;
+
+    #[test]
+    fn progress_token_accepts_strings_and_integers() {
+        let params = json!({"name": "t", "_meta": {"progressToken": "tok-1"}});
+        assert_eq!(progress_token(Some(&params)), Some(json!("tok-1")));
+        let params = json!({"_meta": {"progressToken": 7}});
+        assert_eq!(progress_token(Some(&params)), Some(json!(7)));
+        let params = json!({"_meta": {"progressToken": {"x": 1}}});
+        assert_eq!(progress_token(Some(&params)), None);
+        assert_eq!(progress_token(None), None);
+    }
+
+    #[tokio::test]
+    async fn report_is_a_no_op_outside_a_scope() {
+        assert!(!report(Some("ignored")));
+    }
+
+    #[tokio::test]
+    async fn report_sends_increasing_progress_to_the_session() {
+        // GIVEN: a session and a reporter installed for its call
+        let multiplexer = Arc::new(NotificationMultiplexer::new(
+            Arc::new(BackendRegistry::new()),
+            StreamingConfig::default(),
+        ));
+        let (session_id, mut rx) = multiplexer.get_or_create_session(Some("s-1"));
+        let reporter = ProgressReporter::new(multiplexer, session_id, json!("tok"));
+
+        // WHEN: reporting twice from inside the scope
+        with_progress(Some(reporter), async {
+            assert!(report(None));
+            assert!(report(Some("partial")));
+        })
+        .await;
+
+        // THEN: the session receives numbered progress notifications
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first.event_type, "message");
+        assert_eq!(first.data["method"], "notifications/progress");
+        assert_eq!(first.data["params"]["progress"], 1);
+        let second = rx.recv().await.unwrap();
+        assert_eq!(
+            second.data["params"],
+            json!({"progressToken": "tok", "progress": 2, "message": "partial"})
+        );
+    }
+}
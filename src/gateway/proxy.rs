@@ -17,6 +17,11 @@
 //! `elicitation/create`, the gateway also tracks in-flight request IDs so the
 //! client's POST-back response can be matched to the originating backend call.
 //! Fire-and-forget helpers still exist for one-way notification-style flows.
+//!
+//! Each request only goes to sessions whose client declared the matching
+//! capability at `initialize` (see [`ClientFeature`]); when none did, the
+//! bidirectional flows fail fast with [`SamplingError::NoSession`] instead of
+//! waiting out the timeout, so the caller can take its fallback path.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -31,7 +36,7 @@ use uuid::Uuid;
 
 use crate::protocol::{ElicitationCreateParams, Root, SamplingCreateMessageParams};
 
-use super::streaming::{NotificationMultiplexer, TaggedNotification};
+use super::streaming::{ClientFeature, NotificationMultiplexer, TaggedNotification};
 
 // ============================================================================
 // Sampling error types
@@ -142,12 +147,12 @@ impl ProxyManager {
     /// 4. Awaits the first client's POST-back response, subject to `timeout`.
     /// 5. Returns the response on success, or a [`SamplingError`] on failure.
     ///
-    /// Broadcasting ensures the request reaches any client capable of handling
+    /// Broadcasting ensures the request reaches any client that declared
     /// sampling, regardless of which session happens to be "first."
     ///
     /// # Errors
     ///
-    /// - [`SamplingError::NoSession`] if no sessions are connected.
+    /// - [`SamplingError::NoSession`] if no connected client declared `sampling`.
     /// - [`SamplingError::Timeout`] if no client responds within `timeout`.
     /// - [`SamplingError::Cancelled`] if the oneshot channel is dropped unexpectedly.
     pub async fn forward_sampling_with_response(
@@ -174,9 +179,16 @@ impl ProxyManager {
             event_id: Some(self.multiplexer.next_event_id()),
         };
 
-        // Broadcast to ALL sessions — first Claude Code instance to respond wins.
-        self.multiplexer.broadcast(notification);
-        debug!(%id, "Broadcast sampling/createMessage as MCP message to all sessions");
+        // Broadcast to every sampling-capable session — first to respond wins.
+        let sent = self
+            .multiplexer
+            .broadcast_to_capable(ClientFeature::Sampling, notification);
+        if sent == 0 {
+            self.cancel_pending(&id);
+            debug!(%id, "No connected client declared sampling; not forwarding sampling/createMessage");
+            return Err(SamplingError::NoSession);
+        }
+        debug!(%id, sessions = sent, "Broadcast sampling/createMessage as MCP message");
 
         match tokio::time::timeout(timeout, rx).await {
             Ok(Ok(response)) => {
@@ -226,9 +238,16 @@ impl ProxyManager {
             event_id: Some(self.multiplexer.next_event_id()),
         };
 
-        // Broadcast to ALL sessions — first Claude Code instance to respond wins.
-        self.multiplexer.broadcast(notification);
-        debug!(%id, "Broadcast elicitation/create as MCP message to all sessions");
+        // Broadcast to every elicitation-capable session — first to respond wins.
+        let sent = self
+            .multiplexer
+            .broadcast_to_capable(ClientFeature::Elicitation, notification);
+        if sent == 0 {
+            self.cancel_pending(&id);
+            debug!(%id, "No connected client declared elicitation; not forwarding elicitation/create");
+            return Err(SamplingError::NoSession);
+        }
+        debug!(%id, sessions = sent, "Broadcast elicitation/create as MCP message");
 
         match tokio::time::timeout(timeout, rx).await {
             Ok(Ok(response)) => {
@@ -253,6 +272,14 @@ impl ProxyManager {
 
     /// Forward an `elicitation/create` request to connected clients (fire-and-forget).
     pub fn forward_elicitation(&self, session_id: &str, params: &ElicitationCreateParams) -> bool {
+        if !self
+            .multiplexer
+            .session_supports(session_id, ClientFeature::Elicitation)
+        {
+            debug!(session_id = %session_id, "Client did not declare support for elicitation/create; not forwarding");
+            return false;
+        }
+
         let data = json!({
             "jsonrpc": "2.0",
             "method": "elicitation/create",
@@ -283,6 +310,14 @@ impl ProxyManager {
     ///
     /// In v1, this sends the sampling request as a notification over SSE.
     pub fn forward_sampling(&self, session_id: &str, params: &SamplingCreateMessageParams) -> bool {
+        if !self
+            .multiplexer
+            .session_supports(session_id, ClientFeature::Sampling)
+        {
+            debug!(session_id = %session_id, "Client did not declare support for sampling/createMessage; not forwarding");
+            return false;
+        }
+
         let data = json!({
             "jsonrpc": "2.0",
             "method": "sampling/createMessage",
@@ -313,6 +348,14 @@ impl ProxyManager {
     ///
     /// In v1, this sends the roots request as a notification over SSE.
     pub fn forward_roots_list(&self, session_id: &str) -> bool {
+        if !self
+            .multiplexer
+            .session_supports(session_id, ClientFeature::Roots)
+        {
+            debug!(session_id = %session_id, "Client did not declare support for roots/list; not forwarding");
+            return false;
+        }
+
         let data = json!({
             "jsonrpc": "2.0",
             "method": "roots/list"
@@ -393,7 +436,20 @@ mod tests {
     use super::*;
     use crate::backend::BackendRegistry;
     use crate::config::StreamingConfig;
-    use crate::protocol::{Content, ModelHint, ModelPreferences, SamplingMessage, ToolChoice};
+    use crate::protocol::{
+        ClientCapabilities, Content, ElicitationCapability, ModelHint, ModelPreferences,
+        RootsCapability, SamplingCapability, SamplingMessage, ToolChoice,
+    };
+
+    fn capabilities(feature: ClientFeature) -> ClientCapabilities {
+        let mut caps = ClientCapabilities::default();
+        match feature {
+            ClientFeature::Sampling => caps.sampling = Some(SamplingCapability::default()),
+            ClientFeature::Elicitation => caps.elicitation = Some(ElicitationCapability::default()),
+            ClientFeature::Roots => caps.roots = Some(RootsCapability::default()),
+        }
+        caps
+    }
 
     fn make_multiplexer() -> Arc<NotificationMultiplexer> {
         let backends = Arc::new(BackendRegistry::new());
@@ -584,6 +640,48 @@ mod tests {
         assert_eq!(received.data["params"]["message"], "Enter name");
     }
 
+    #[tokio::test]
+    async fn forward_elicitation_skips_client_without_capability() {
+        // GIVEN: a client that declared sampling but not elicitation
+        let mux = make_multiplexer();
+        let (session_id, mut rx) = mux.get_or_create_session(Some("no-elicit"));
+        mux.set_client_capabilities(&session_id, capabilities(ClientFeature::Sampling));
+        let proxy = ProxyManager::new(Arc::clone(&mux));
+
+        let params = ElicitationCreateParams {
+            message: "Enter name".to_string(),
+            requested_schema: None,
+        };
+
+        // WHEN / THEN: nothing is sent
+        assert!(!proxy.forward_elicitation(&session_id, &params));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn elicitation_with_response_fails_fast_without_capable_client() {
+        // GIVEN: the only client did not declare elicitation
+        let mux = make_multiplexer();
+        let (session_id, mut rx) = mux.get_or_create_session(Some("roots-only"));
+        mux.set_client_capabilities(&session_id, capabilities(ClientFeature::Roots));
+        let proxy = ProxyManager::new(Arc::clone(&mux));
+
+        let params = ElicitationCreateParams {
+            message: "Confirm?".to_string(),
+            requested_schema: None,
+        };
+
+        // WHEN: an elicitation is requested with a long timeout
+        let result = proxy
+            .forward_elicitation_with_response(&session_id, &params, Duration::from_secs(3600))
+            .await;
+
+        // THEN: it fails at once, leaving nothing pending or queued
+        assert!(matches!(result, Err(SamplingError::NoSession)));
+        assert!(proxy.pending_sampling.read().is_empty());
+        assert!(rx.try_recv().is_err());
+    }
+
     // ── Sampling forwarding ────────────────────────────────────────────
 
     #[test]
@@ -645,6 +743,49 @@ mod tests {
         assert_eq!(received.data["params"]["maxTokens"], 1024);
     }
 
+    #[tokio::test]
+    async fn sampling_with_response_reaches_only_capable_clients() {
+        // GIVEN: one client that declared sampling and one that did not
+        let mux = make_multiplexer();
+        let (capable, mut capable_rx) = mux.get_or_create_session(Some("sampler"));
+        let (other, mut other_rx) = mux.get_or_create_session(Some("plain"));
+        mux.set_client_capabilities(&capable, capabilities(ClientFeature::Sampling));
+        mux.set_client_capabilities(&other, ClientCapabilities::default());
+        let proxy = Arc::new(ProxyManager::new(Arc::clone(&mux)));
+
+        let params = SamplingCreateMessageParams {
+            messages: vec![SamplingMessage {
+                role: "user".to_string(),
+                content: Content::Text {
+                    text: "Hi".to_string(),
+                    annotations: None,
+                },
+            }],
+            tools: None,
+            tool_choice: None,
+            model_preferences: None,
+            system_prompt: None,
+            max_tokens: 16,
+        };
+
+        // WHEN: a sampling request is forwarded and the capable client answers
+        let call = {
+            let proxy = Arc::clone(&proxy);
+            tokio::spawn(async move {
+                proxy
+                    .forward_sampling_with_response("broadcast", &params, Duration::from_secs(5))
+                    .await
+            })
+        };
+        let request = capable_rx.recv().await.unwrap();
+        let id = request.data["id"].as_str().unwrap().to_string();
+        assert!(proxy.resolve_pending(&id, json!({"result": "ok"})));
+
+        // THEN: the caller gets the answer and the other client saw nothing
+        assert_eq!(call.await.unwrap().unwrap()["result"], "ok");
+        assert!(other_rx.try_recv().is_err());
+    }
+
     // ── Roots forwarding ───────────────────────────────────────────────
 
     #[test]
@@ -57,6 +57,21 @@ pub enum ErrorCategory {
     Timeout,
 }
 
+impl ErrorCategory {
+    /// Stable `snake_case` label used in statistics breakdowns.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Validation => "validation",
+            Self::BackendError => "backend_error",
+            Self::CircuitBreakerTrip => "circuit_open",
+            Self::NotFound => "not_found",
+            Self::RateLimited => "rate_limited",
+            Self::Timeout => "timeout",
+        }
+    }
+}
+
 /// Machine-readable error codes embedded in recovery hints.
 pub mod error_codes {
     /// A required parameter was missing, had the wrong type, or violated a schema constraint.
@@ -7,7 +7,7 @@ use std::sync::Arc;
 use axum::{
     Json,
     extract::{Path, State},
-    http::StatusCode,
+    http::{StatusCode, header},
     response::IntoResponse,
 };
 use serde_json::{Value, json};
@@ -16,12 +16,14 @@ use tracing::{debug, error, warn};
 
 use super::AppState;
 use super::authorization::{ToolTarget, authorize_tool_target};
-use super::helpers::{build_http_error_response, build_http_response, parse_request};
-use crate::backend::normalize_tool_annotations;
+use super::helpers::{
+    build_http_error_response, build_http_response, header_protocol_version, parse_request,
+};
+use crate::backend::{Backend, normalize_tool_annotations};
 use crate::gateway::auth::AuthenticatedClient;
 use crate::gateway::oauth::AgentIdentity as OAuthAgentIdentity;
 use crate::mtls::CertIdentity;
-use crate::protocol::{JsonRpcResponse, RequestId, Tool};
+use crate::protocol::{JsonRpcResponse, RequestId, Tool, compat};
 #[cfg(feature = "firewall")]
 use crate::security::firewall::FirewallAction;
 use crate::security::{sanitize_json_value, validate_tool_name};
@@ -148,8 +150,10 @@ fn backend_security_error_with_status(
 }
 
 /// Fill missing MCP tool annotation hints on direct backend `tools/list`
-/// responses before returning them to clients.
-fn normalize_tools_list_response(backend_name: &str, response: &mut JsonRpcResponse) {
+/// responses, and apply the backend's configured namespace and tool filter,
+/// before returning them to clients.
+fn normalize_tools_list_response(backend: &Backend, response: &mut JsonRpcResponse) {
+    let backend_name = backend.name.as_str();
     if response.error.is_some() {
         return;
     }
@@ -167,6 +171,8 @@ fn normalize_tools_list_response(backend_name: &str, response: &mut JsonRpcRespo
     };
 
     normalize_tool_annotations(backend_name, &mut tools);
+    backend.apply_tool_namespace(&mut tools);
+    backend.apply_tool_filter(&mut tools);
 
     let server_id = format!("backend:{backend_name}");
     let tools = project_tool_descriptors_trust_cards(&server_id, backend_name, &tools);
@@ -475,14 +481,23 @@ pub(super) async fn backend_handler(
     };
 
     // Parse request
-    let (id, method, params) = match parse_request(&json_request) {
+    let (id, method, mut params) = match parse_request(&json_request) {
         Ok(parsed) => parsed,
         Err(response) => {
             return build_http_response(&response, StatusCode::BAD_REQUEST);
         }
     };
+    // The client's protocol version; the backend side is adapted by `Backend`.
+    let client_version = header_protocol_version(&inbound_headers);
+    if let (Some(version), Some(params)) = (client_version.as_deref(), params.as_mut()) {
+        compat::upgrade_params(&method, params, version);
+    }
 
     debug!(backend = %name, method = %method, client = ?client.as_ref().map(|c| &c.name), "Backend request");
+    crate::access_log::annotate(|r| {
+        r.backend = Some(name.clone());
+        r.method = Some(method.clone());
+    });
 
     // Handle notifications - forward to backend but return 202 Accepted.
     // Resolve (best-effort) the same session-bucket identity_key a matching
@@ -521,6 +536,21 @@ pub(super) async fn backend_handler(
     // For requests, id is guaranteed to exist
     let id = id.expect("id should exist for non-notification requests");
 
+    // Per-client, per-backend token bucket (`failsafe.rate_limit.limits`),
+    // shared with the meta dispatch path so both routes drain one budget.
+    if method == "tools/call" {
+        let caller = client.as_ref().map_or("anonymous", |c| c.name.as_str());
+        if let Err(exceeded) = state.meta_mcp.check_client_rate_limit(caller, &name) {
+            let response = JsonRpcResponse::error_with_data(
+                Some(id),
+                -32000,
+                exceeded.message(),
+                exceeded.data(),
+            );
+            return build_http_response(&response, StatusCode::TOO_MANY_REQUESTS);
+        }
+    }
+
     // End-user identity propagation for the direct backend route (MIK-6704 /
     // ADR-007). Parity with the meta dispatch path: for a propagation-configured
     // backend, resolve the per-user credential and forward it via
@@ -798,7 +828,7 @@ pub(super) async fn backend_handler(
             // direct-route clients must receive the ID they supplied.
             response.id = Some(id.clone());
             if method == "tools/list" {
-                normalize_tools_list_response(&name, &mut response);
+                normalize_tools_list_response(&backend, &mut response);
                 scan_direct_tools_list_response(&state, &name, client.as_ref(), &mut response);
             } else if method == "tools/call" {
                 scan_direct_backend_response(
@@ -816,6 +846,11 @@ pub(super) async fn backend_handler(
                     &mut response,
                 );
             }
+            if let (Some(version), Some(result)) =
+                (client_version.as_deref(), response.result.as_mut())
+            {
+                compat::downgrade_result(&method, result, version);
+            }
             build_http_response(&response, StatusCode::OK)
         }
         Err(e) => {
@@ -964,30 +999,30 @@ fn scan_direct_tools_list_response(
 /// Query parameters:
 /// - `key=<name>`: view cost for a single API key
 /// - `session=<id>`: view cost for a specific session
+/// - `format=csv`: per-client/session/backend/tool attribution as CSV
 /// - (no params): aggregate view across all sessions and keys
 pub(super) async fn costs_handler(
     State(state): State<Arc<AppState>>,
     request: axum::http::Request<axum::body::Body>,
 ) -> impl IntoResponse {
-    use std::collections::HashMap;
-
-    let query: HashMap<String, String> = request
-        .uri()
-        .query()
-        .map(|q| {
-            q.split('&')
-                .filter_map(|part| {
-                    let mut kv = part.splitn(2, '=');
-                    let k = kv.next()?;
-                    let v = kv.next().unwrap_or("");
-                    Some((k.to_string(), v.to_string()))
-                })
-                .collect()
-        })
-        .unwrap_or_default();
-
+    let query = query_params(&request);
     let tracker = state.meta_mcp.cost_tracker();
 
+    if query.get("format").is_some_and(|f| f == "csv") {
+        return (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"gateway-costs.csv\"",
+                ),
+            ],
+            tracker.attribution_csv(),
+        )
+            .into_response();
+    }
+
     let body = if let Some(key_name) = query.get("key") {
         match tracker.key_snapshot(key_name) {
             Some(snap) => serde_json::to_value(snap).unwrap_or(serde_json::json!(null)),
@@ -1008,10 +1043,80 @@ pub(super) async fn costs_handler(
             "aggregate": serde_json::to_value(tracker.aggregate()).unwrap_or(serde_json::json!(null)),
             "sessions": serde_json::to_value(tracker.all_sessions()).unwrap_or(serde_json::json!([])),
             "keys": serde_json::to_value(tracker.all_keys()).unwrap_or(serde_json::json!([])),
+            "attribution": serde_json::to_value(tracker.attribution()).unwrap_or(serde_json::json!(null)),
         })
     };
 
-    (StatusCode::OK, Json(body))
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+/// GET /stats — current usage snapshot plus the retained time series.
+///
+/// Query parameters:
+/// - `since=<unix seconds>`: only history samples taken at or after this time
+/// - `history=false`: omit the time series
+///
+/// `history` is `null` when `stats_history.enabled` is `false`.
+pub(super) async fn stats_handler(
+    State(state): State<Arc<AppState>>,
+    request: axum::http::Request<axum::body::Body>,
+) -> impl IntoResponse {
+    let query = query_params(&request);
+    let since = match query.get("since").map(|s| s.parse::<u64>()) {
+        None => None,
+        Some(Ok(since)) => Some(since),
+        Some(Err(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "'since' must be a Unix timestamp in seconds"})),
+            )
+                .into_response();
+        }
+    };
+
+    let tools = state
+        .backends
+        .statuses()
+        .values()
+        .map(|s| s.tools_cached)
+        .sum();
+    let history = state
+        .meta_mcp
+        .stats_history()
+        .filter(|_| query.get("history").is_none_or(|h| h != "false"))
+        .map(|history| {
+            serde_json::json!({
+                "resolution_secs": history.resolution().as_secs(),
+                "retention_secs": history.retention().as_secs(),
+                "samples": history.samples(since),
+            })
+        });
+
+    let body = serde_json::json!({
+        "snapshot": state.meta_mcp.stats_snapshot(tools),
+        "history": history,
+    });
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+/// Parse `a=1&b=2` query parameters (values are not percent-decoded).
+fn query_params(
+    request: &axum::http::Request<axum::body::Body>,
+) -> std::collections::HashMap<String, String> {
+    request
+        .uri()
+        .query()
+        .map(|q| {
+            q.split('&')
+                .filter_map(|part| {
+                    let mut kv = part.splitn(2, '=');
+                    let k = kv.next()?;
+                    let v = kv.next().unwrap_or("");
+                    Some((k.to_string(), v.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
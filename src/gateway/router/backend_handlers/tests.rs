@@ -23,6 +23,7 @@ mod passthrough {
             session_mode: SessionMode::PerUser,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }
     }
 
@@ -197,6 +198,7 @@ mod identity_propagation_audit {
             name: None,
             groups: vec![],
             issuer: "https://idp.test.invalid".to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
@@ -337,6 +339,7 @@ mod identity_propagation_audit {
             session_mode: SessionMode::PerUser,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         };
         let mut inbound = HeaderMap::new();
         inbound.insert(
@@ -501,6 +504,18 @@ mod identity_propagation_audit {
     }
 }
 
+fn test_backend(name: &str, namespace: Option<&str>) -> Backend {
+    Backend::new(
+        name,
+        crate::config::BackendConfig {
+            namespace: namespace.map(str::to_string),
+            ..crate::config::BackendConfig::default()
+        },
+        &crate::config::FailsafeConfig::default(),
+        std::time::Duration::from_secs(60),
+    )
+}
+
 #[test]
 fn normalize_tools_list_response_fills_direct_backend_proxy_annotations() {
     let mut response = JsonRpcResponse::success(
@@ -525,7 +540,7 @@ fn normalize_tools_list_response_fills_direct_backend_proxy_annotations() {
         }),
     );
 
-    normalize_tools_list_response("beeper", &mut response);
+    normalize_tools_list_response(&test_backend("beeper", None), &mut response);
 
     let result = response.result.expect("success result");
     assert_eq!(result["nextCursor"], "abc");
@@ -559,3 +574,27 @@ fn normalize_tools_list_response_fills_direct_backend_proxy_annotations() {
     assert_eq!(archive["idempotentHint"], false);
     assert_eq!(archive["openWorldHint"], true);
 }
+
+#[test]
+fn normalize_tools_list_response_applies_backend_namespace() {
+    let mut response = JsonRpcResponse::success(
+        RequestId::Number(1),
+        json!({
+            "tools": [
+                {
+                    "name": "search",
+                    "description": "Search things",
+                    "inputSchema": {"type": "object"}
+                }
+            ]
+        }),
+    );
+
+    normalize_tools_list_response(&test_backend("docs", Some("docs")), &mut response);
+
+    let result = response.result.expect("success result");
+    assert_eq!(result["tools"][0]["name"], "docs_search");
+    // Annotation inference still sees the backend's own name.
+    assert_eq!(result["tools"][0]["annotations"]["readOnlyHint"], true);
+    assert_eq!(result["tools"][0]["trustCard"]["toolName"], "docs_search");
+}
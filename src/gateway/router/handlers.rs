@@ -21,19 +21,22 @@ use super::authorization::{
 use super::helpers::{
     attach_session_header, build_accepted_response, build_error_response,
     build_http_error_response, build_http_response, build_response, extract_tools_call_params,
-    parse_elicitation_params, parse_request, parse_sampling_params,
+    header_protocol_version, parse_client_capabilities, parse_elicitation_params, parse_request,
+    parse_sampling_params,
 };
 use crate::gateway::auth::AuthenticatedClient;
 use crate::gateway::destructive_confirmation::{
     ConfirmationOutcome, is_destructive_meta_tool, require_destructive_confirmation,
 };
+use crate::gateway::drain;
 use crate::gateway::meta_mcp::MetaMcpCallerContext;
 use crate::gateway::oauth::AgentIdentity as OAuthAgentIdentity;
+use crate::gateway::progress::{self, ProgressReporter};
 use crate::gateway::streaming::create_sse_response;
 use crate::identity_grants::GrantSubject;
 use crate::key_server::oidc::VerifiedIdentity;
 use crate::mtls::CertIdentity;
-use crate::protocol::JsonRpcResponse;
+use crate::protocol::{JsonRpcResponse, compat};
 #[cfg(feature = "firewall")]
 use crate::security::firewall::FirewallAction;
 use crate::security::{extract_agent_identity, sanitize_json_value, validate_agent_identity};
@@ -163,6 +166,11 @@ pub(super) async fn mcp_sse_handler(
         .and_then(|v| v.to_str().ok())
         .map(String::from);
 
+    if let Some(refused) = refuse_new_session_while_draining(&state, existing_session_id.as_deref())
+    {
+        return refused;
+    }
+
     let last_event_id = headers
         .get("last-event-id")
         .and_then(|v| v.to_str().ok())
@@ -219,6 +227,9 @@ pub(super) async fn mcp_delete_handler(
     match session_id {
         Some(id) if state.multiplexer.has_session(id) => {
             state.multiplexer.remove_session(id);
+            if let Some(ref ranker) = state.meta_mcp.ranker {
+                ranker.forget_session(id);
+            }
             info!(session_id = %id, "Session terminated by client");
             StatusCode::NO_CONTENT
         }
@@ -230,6 +241,66 @@ pub(super) async fn mcp_delete_handler(
     }
 }
 
+/// While the gateway drains, refuse requests that would open a new session.
+///
+/// Requests on sessions this instance already knows keep working so their
+/// in-flight work can finish; everything else gets `503` with `Retry-After`
+/// and should be retried against another replica.
+fn refuse_new_session_while_draining(
+    state: &AppState,
+    session_id: Option<&str>,
+) -> Option<axum::response::Response> {
+    if !state.drain.is_draining() || session_id.is_some_and(|id| state.multiplexer.has_session(id))
+    {
+        return None;
+    }
+    let mut response = build_http_error_response(
+        None,
+        -32000,
+        "Gateway is draining; retry against another instance",
+        StatusCode::SERVICE_UNAVAILABLE,
+    )
+    .into_response();
+    response.headers_mut().insert(
+        axum::http::header::RETRY_AFTER,
+        axum::http::HeaderValue::from(drain::RECONNECT_AFTER.as_secs()),
+    );
+    Some(response)
+}
+
+/// `POST /api/drain` — start a graceful drain (admin only).
+///
+/// The gateway stops taking new sessions, lets in-flight work finish, closes
+/// SSE streams with a reconnect hint and exits (see [`drain`]).
+pub(super) async fn drain_handler(
+    State(state): State<Arc<AppState>>,
+    request: axum::http::Request<axum::body::Body>,
+) -> impl IntoResponse {
+    let is_admin = request
+        .extensions()
+        .get::<AuthenticatedClient>()
+        .is_some_and(|c| c.admin);
+    if !is_admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "Admin authentication required" })),
+        );
+    }
+
+    let started = state.drain.start();
+    if started {
+        info!("Drain requested via /api/drain");
+    }
+    (
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "status": "draining",
+            "already_draining": !started,
+            "in_flight": drain::INFLIGHT_CAPACITY.saturating_sub(state.inflight.available_permits()),
+        })),
+    )
+}
+
 /// Deprecated SSE endpoint handler - surfaces a clear error instead of silent 404
 pub(super) async fn sse_deprecated_handler() -> impl IntoResponse {
     build_http_response(
@@ -396,6 +467,13 @@ pub(super) async fn meta_mcp_handler(
             .into_response();
         }
     };
+    if let Some(refused) = refuse_new_session_while_draining(
+        &state,
+        headers.get("mcp-session-id").and_then(|v| v.to_str().ok()),
+    ) {
+        return refused;
+    }
+
     // Track in-flight request for graceful drain
     let _inflight_permit = state.inflight.acquire().await;
 
@@ -459,7 +537,7 @@ pub(super) async fn meta_mcp_handler(
     }
 
     // Parse request
-    let (id, method, params) = match parse_request(&request) {
+    let (id, method, mut params) = match parse_request(&request) {
         Ok(parsed) => parsed,
         Err(response) => {
             return build_response(response, &session_id, StatusCode::BAD_REQUEST);
@@ -467,6 +545,10 @@ pub(super) async fn meta_mcp_handler(
     };
 
     debug!(method = %method, session_id = %session_id, "Meta-MCP request");
+    crate::access_log::annotate(|r| {
+        r.session = Some(session_id.clone());
+        r.method = Some(method.clone());
+    });
 
     // Handle notifications (no id) - return 202 Accepted with empty body
     if method.starts_with("notifications/") {
@@ -483,14 +565,28 @@ pub(super) async fn meta_mcp_handler(
         .and_then(|v| v.to_str().ok())
         .map(String::from);
 
+    // Older clients talk to the gateway in their negotiated protocol version.
+    let client_version = state
+        .multiplexer
+        .protocol_version(&session_id)
+        .or_else(|| header_protocol_version(&headers));
+    if let (Some(version), Some(params)) = (client_version.as_deref(), params.as_mut()) {
+        compat::upgrade_params(&method, params, version);
+    }
+
     // Route to appropriate handler
-    let response = match method.as_str() {
-        "initialize" => state.meta_mcp.handle_initialize(
-            id,
-            params.as_ref(),
-            Some(session_id.as_str()),
-            header_profile.as_deref(),
-        ),
+    let mut response = match method.as_str() {
+        "initialize" => {
+            state
+                .multiplexer
+                .set_client_capabilities(&session_id, parse_client_capabilities(params.as_ref()));
+            state.meta_mcp.handle_initialize(
+                id,
+                params.as_ref(),
+                Some(session_id.as_str()),
+                header_profile.as_deref(),
+            )
+        }
         "tools/list" => state.meta_mcp.handle_tools_list_with_url_override(
             id,
             params.as_ref(),
@@ -611,9 +707,14 @@ pub(super) async fn meta_mcp_handler(
                 // Confirmed or Unsupported → fall through to execute
             }
 
-            let mut call_response = state
-                .meta_mcp
-                .handle_tools_call(
+            // `_meta.progressToken`: streaming providers report progress to
+            // this session while the call runs.
+            let reporter = progress::progress_token(params.as_ref()).map(|token| {
+                ProgressReporter::new(Arc::clone(&state.multiplexer), session_id.clone(), token)
+            });
+            let mut call_response = progress::with_progress(
+                reporter,
+                state.meta_mcp.handle_tools_call(
                     id,
                     tool_name,
                     arguments,
@@ -624,8 +725,9 @@ pub(super) async fn meta_mcp_handler(
                         grant_subject,
                         verified_identity: verified_identity.as_ref(),
                     },
-                )
-                .await;
+                ),
+            )
+            .await;
 
             // Firewall: post-invocation response scan + credential redaction.
             #[cfg(feature = "firewall")]
@@ -755,6 +857,23 @@ pub(super) async fn meta_mcp_handler(
         _ => JsonRpcResponse::error(Some(id), -32601, format!("Method not found: {method}")),
     };
 
+    let client_version = if method == "initialize" {
+        let negotiated = response
+            .result
+            .as_ref()
+            .and_then(|r| r.get("protocolVersion"))
+            .and_then(Value::as_str);
+        if let Some(version) = negotiated {
+            state.multiplexer.set_protocol_version(&session_id, version);
+        }
+        negotiated.map(String::from)
+    } else {
+        client_version
+    };
+    if let (Some(version), Some(result)) = (client_version.as_deref(), response.result.as_mut()) {
+        compat::downgrade_result(&method, result, version);
+    }
+
     telemetry_metrics::counter!(
         "mcp_jsonrpc_requests_total",
         "method" => method.clone(),
@@ -786,7 +905,10 @@ fn describe_destructive_action(tool_name: &str, params: Option<&Value>) -> Strin
                 .and_then(|a| a.get("server"))
                 .and_then(Value::as_str)
                 .unwrap_or("<unknown>");
-            format!("kill server '{server}'")
+            match server.split_once(':') {
+                Some((server, tool)) => format!("kill tool '{tool}' on server '{server}'"),
+                None => format!("kill server '{server}'"),
+            }
         }
         other => format!("execute destructive meta-tool '{other}'"),
     }
@@ -910,6 +1032,7 @@ mod caller_identity_tests {
             name: Some("Owner".to_string()),
             groups: Vec::new(),
             issuer: "https://issuer.example".to_string(),
+            claims: serde_json::Map::new(),
         };
 
         let subject = caller_grant_subject(Some(&verified), &headers, true, None, None).unwrap();
@@ -12,7 +12,8 @@ use serde_json::{Value, json};
 use tracing::warn;
 
 use crate::protocol::{
-    ElicitationCreateParams, JsonRpcResponse, RequestId, SamplingCreateMessageParams,
+    ClientCapabilities, ElicitationCreateParams, JsonRpcResponse, RequestId,
+    SamplingCreateMessageParams,
 };
 
 fn build_session_response<T>(
@@ -100,6 +101,24 @@ pub(super) fn build_accepted_response(session_id: &str) -> axum::response::Respo
     build_json_response(json!({}), session_id, StatusCode::ACCEPTED)
 }
 
+/// Protocol version from the `MCP-Protocol-Version` request header.
+pub(super) fn header_protocol_version(headers: &axum::http::HeaderMap) -> Option<String> {
+    headers
+        .get("mcp-protocol-version")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+}
+
+/// Extract the client capabilities from `initialize` params.
+///
+/// A missing or malformed `capabilities` object counts as declaring nothing.
+pub(super) fn parse_client_capabilities(params: Option<&Value>) -> ClientCapabilities {
+    params
+        .and_then(|p| p.get("capabilities"))
+        .and_then(|caps| serde_json::from_value(caps.clone()).ok())
+        .unwrap_or_default()
+}
+
 /// Parse `sampling/createMessage` params from raw JSON, returning an early
 /// HTTP error response on failure.
 #[allow(clippy::result_large_err)] // early-return pattern mirrors existing handlers
@@ -28,6 +28,7 @@ mod authorization;
 mod backend_handlers;
 mod handlers;
 pub(crate) mod helpers;
+mod probes;
 mod well_known;
 
 #[cfg(test)]
@@ -68,6 +69,9 @@ pub struct AppState {
     /// Each in-flight request holds a permit; shutdown waits for all permits
     /// to be returned.
     pub inflight: Arc<tokio::sync::Semaphore>,
+    /// Graceful drain state: set while the gateway refuses new sessions and
+    /// waits for in-flight work before exiting.
+    pub drain: Arc<crate::gateway::drain::DrainController>,
     /// Agent auth state (issue #80 — agent-scoped JWT permissions).
     pub agent_auth: AgentAuthState,
     /// Gateway RSA key pair for JWKS endpoint.
@@ -102,6 +106,9 @@ pub struct AppState {
     /// (`idp_mint` / `idp_refuse`) into the same hash chain (MIK-6740). `None`
     /// when the transparency log is disabled — audit writes are then a no-op.
     pub transparency_log: Option<Arc<crate::security::TransparencyLogger>>,
+    /// Per-request structured access log (`access_log`), `None` when
+    /// disabled.
+    pub access_log: Option<Arc<crate::access_log::AccessLog>>,
 }
 
 /// Create the router.
@@ -154,7 +161,11 @@ pub fn create_router(state: Arc<AppState>) -> Router {
     #[allow(unused_mut)]
     let mut routes = Router::new()
         .route("/health", get(handlers::health_handler))
+        .route("/livez", get(probes::livez_handler))
+        .route("/readyz", get(probes::readyz_handler))
         .route("/api/costs", get(backend_handlers::costs_handler))
+        .route("/api/drain", post(handlers::drain_handler))
+        .route("/stats", get(backend_handlers::stats_handler))
         .route(
             "/mcp",
             post(handlers::meta_mcp_handler)
@@ -179,6 +190,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
     }
 
     let mut app = routes
+        // Capture the authenticated caller for backends with `forward_identity`
+        .layer(middleware::from_fn(
+            super::caller::caller_identity_middleware,
+        ))
         // Agent JWT scope middleware runs inside the standard auth layer.
         .layer(middleware::from_fn_with_state(
             agent_auth_state,
@@ -187,7 +202,14 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         // Authentication middleware (applied before other layers)
         .layer(middleware::from_fn_with_state(auth_state, auth_middleware))
         .layer(CatchPanicLayer::new())
+        // Access log sits inside compression so it sees uncompressed sizes
+        .layer(middleware::from_fn_with_state(
+            state.access_log.clone(),
+            crate::access_log::access_log_middleware,
+        ))
         .layer(CompressionLayer::new())
+        // Join the caller's W3C trace, inside the per-request span
+        .layer(middleware::from_fn(super::trace::trace_context_middleware))
         .layer(TraceLayer::new_for_http())
         .with_state(Arc::clone(&state));
 
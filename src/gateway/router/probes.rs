@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Liveness and readiness probes (`GET /livez`, `GET /readyz`).
+//!
+//! `/health` answers 503 as soon as any backend degrades, which is the wrong
+//! signal for an orchestrator: restarting the gateway does not fix a flaky
+//! upstream, and pulling it out of rotation because one of twenty backends is
+//! down takes the other nineteen with it. The probes split the two questions:
+//!
+//! - `/livez` — the process is up and serving HTTP. Always `200`.
+//! - `/readyz` — the gateway can usefully take traffic, judged by the
+//!   `readiness` config: which backends count and what share of them must be
+//!   healthy. `200` when ready, `503` otherwise — including while the
+//!   gateway drains for shutdown.
+//!
+//! A backend is healthy for readiness when its circuit breaker is not open,
+//! the health tracker considers it live and, for OAuth backends, the latest
+//! token refresh succeeded. As with `/health`, per-backend detail (connection,
+//! circuit state, last successful call, token validity) is only shown to
+//! authenticated clients; public callers get the counts.
+
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use serde_json::json;
+
+use super::AppState;
+use crate::backend::Backend;
+use crate::config::ReadinessConfig;
+use crate::gateway::auth::AuthenticatedClient;
+
+/// Readiness-relevant state of one backend.
+#[derive(Debug, Clone, Serialize)]
+#[allow(clippy::struct_excessive_bools)] // Serialized probe facts reported side by side.
+struct BackendProbe {
+    name: String,
+    /// Counted towards the readiness threshold.
+    required: bool,
+    /// Transport is running.
+    connected: bool,
+    /// Circuit breaker state (`Unknown` for a required backend that is not
+    /// registered).
+    circuit_state: String,
+    /// Health-tracker liveness.
+    live: bool,
+    /// Last successful call (millis since epoch), if any.
+    last_success_ms: Option<u64>,
+    /// Whether the latest OAuth token refresh succeeded (OAuth backends only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oauth_token_valid: Option<bool>,
+    /// Healthy for readiness purposes.
+    healthy: bool,
+}
+
+impl BackendProbe {
+    fn of(backend: &Backend, required: bool) -> Self {
+        let status = backend.status();
+        let metrics = backend.health_metrics();
+        let oauth_token_valid = backend
+            .oauth_config()
+            .filter(|oauth| oauth.enabled)
+            .map(|_| !backend.oauth_refresh_health().is_failing());
+        let healthy =
+            status.circuit_state != "Open" && status.healthy && oauth_token_valid != Some(false);
+        Self {
+            name: status.name,
+            required,
+            connected: status.running,
+            circuit_state: status.circuit_state,
+            live: status.healthy,
+            last_success_ms: (metrics.last_success_ms > 0).then_some(metrics.last_success_ms),
+            oauth_token_valid,
+            healthy,
+        }
+    }
+
+    /// A required backend that is not registered (disabled or failed to load).
+    fn missing(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            required: true,
+            connected: false,
+            circuit_state: "Unknown".to_string(),
+            live: false,
+            last_success_ms: None,
+            oauth_token_valid: None,
+            healthy: false,
+        }
+    }
+}
+
+/// Outcome of the readiness criteria.
+#[derive(Debug, Serialize)]
+struct Readiness {
+    ready: bool,
+    /// Healthy backends among the counted ones.
+    healthy: usize,
+    /// Backends counted towards the threshold.
+    counted: usize,
+    min_healthy_percent: u8,
+    /// `None` when no capability backend is loaded.
+    capability_backend_healthy: Option<bool>,
+}
+
+/// Collect a probe for every registered backend, plus placeholders for
+/// required backends that are not registered.
+fn collect_probes(state: &AppState, config: &ReadinessConfig) -> Vec<BackendProbe> {
+    let is_required = |name: &str| {
+        config.required_backends.is_empty() || config.required_backends.iter().any(|r| r == name)
+    };
+    let mut probes: Vec<BackendProbe> = state
+        .backends
+        .all()
+        .iter()
+        .map(|backend| BackendProbe::of(backend, is_required(&backend.name)))
+        .collect();
+    for name in &config.required_backends {
+        if !probes.iter().any(|p| &p.name == name) {
+            probes.push(BackendProbe::missing(name));
+        }
+    }
+    probes.sort_by(|a, b| a.name.cmp(&b.name));
+    probes
+}
+
+/// Apply the readiness criteria to the collected probes.
+fn evaluate(
+    probes: &[BackendProbe],
+    capability_backend_healthy: Option<bool>,
+    config: &ReadinessConfig,
+) -> Readiness {
+    let counted = probes.iter().filter(|p| p.required).count();
+    let healthy = probes.iter().filter(|p| p.required && p.healthy).count();
+    let threshold_met = healthy * 100 >= counted * usize::from(config.min_healthy_percent);
+    let capabilities_ok =
+        !config.require_capabilities || capability_backend_healthy.is_none_or(|h| h);
+    Readiness {
+        ready: threshold_met && capabilities_ok,
+        healthy,
+        counted,
+        min_healthy_percent: config.min_healthy_percent,
+        capability_backend_healthy,
+    }
+}
+
+/// Liveness probe: the process is serving requests.
+pub(super) async fn livez_handler() -> impl IntoResponse {
+    Json(json!({
+        "status": "alive",
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
+/// Readiness probe: the configured share of backends is healthy.
+pub(super) async fn readyz_handler(
+    State(state): State<Arc<AppState>>,
+    request: axum::http::Request<axum::body::Body>,
+) -> impl IntoResponse {
+    let config = state.live_config.get();
+    let probes = collect_probes(&state, &config.readiness);
+    let capability_healthy = state
+        .meta_mcp
+        .get_capabilities()
+        .map(|c| c.status().healthy);
+    let readiness = evaluate(&probes, capability_healthy, &config.readiness);
+
+    let is_admin = request
+        .extensions()
+        .get::<AuthenticatedClient>()
+        .is_some_and(|c| c.name != "public" && c.name != "anonymous");
+
+    let draining = state.drain.is_draining();
+    let ready = readiness.ready && !draining;
+    let status = if draining {
+        "draining"
+    } else if ready {
+        "ready"
+    } else {
+        "not_ready"
+    };
+
+    let mut response = json!({
+        "status": status,
+        "version": env!("CARGO_PKG_VERSION"),
+        "readiness": readiness,
+    });
+    if is_admin {
+        response["backends"] = serde_json::to_value(&probes).unwrap_or_default();
+    }
+
+    let code = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(name: &str, required: bool, healthy: bool) -> BackendProbe {
+        BackendProbe {
+            required,
+            healthy,
+            ..BackendProbe::missing(name)
+        }
+    }
+
+    fn config(min_healthy_percent: u8) -> ReadinessConfig {
+        ReadinessConfig {
+            min_healthy_percent,
+            ..ReadinessConfig::default()
+        }
+    }
+
+    #[test]
+    fn threshold_counts_only_required_backends() {
+        // GIVEN: four of five required backends healthy, one optional one down
+        let mut probes: Vec<_> = (0..4)
+            .map(|i| probe(&format!("b{i}"), true, true))
+            .collect();
+        probes.push(probe("b4", true, false));
+        probes.push(probe("optional", false, false));
+
+        // WHEN / THEN: 80% is enough, 100% is not
+        let ready = evaluate(&probes, None, &config(80));
+        assert!(ready.ready);
+        assert_eq!((ready.healthy, ready.counted), (4, 5));
+        assert!(!evaluate(&probes, None, &config(100)).ready);
+    }
+
+    #[test]
+    fn unhealthy_capability_backend_blocks_readiness_unless_opted_out() {
+        let probes = vec![probe("a", true, true)];
+        assert!(!evaluate(&probes, Some(false), &config(100)).ready);
+        assert!(evaluate(&probes, None, &config(100)).ready);
+
+        let lenient = ReadinessConfig {
+            require_capabilities: false,
+            ..ReadinessConfig::default()
+        };
+        assert!(evaluate(&probes, Some(false), &lenient).ready);
+    }
+
+    #[test]
+    fn no_counted_backends_is_ready() {
+        assert!(evaluate(&[], None, &config(100)).ready);
+    }
+
+    #[test]
+    fn missing_required_backend_is_unhealthy() {
+        let missing = BackendProbe::missing("github");
+        assert!(missing.required && !missing.healthy);
+        assert_eq!(missing.circuit_state, "Unknown");
+    }
+}
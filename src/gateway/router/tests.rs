@@ -3,7 +3,7 @@
 use super::helpers::{
     attach_session_header, build_accepted_response, build_error_response,
     build_http_error_response, build_json_response, extract_request_id, extract_tools_call_params,
-    is_notification_method, parse_elicitation_params, parse_request,
+    is_notification_method, parse_client_capabilities, parse_elicitation_params, parse_request,
 };
 use super::{AppState, create_router};
 use crate::backend::{Backend, BackendRegistry};
@@ -59,6 +59,7 @@ fn test_router_app_state_with_streaming(streaming_config: StreamingConfig) -> Ar
         ssrf_protection: false,
         trust_configured_backends: false,
         inflight: Arc::new(tokio::sync::Semaphore::new(8)),
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs: Vec::new(),
@@ -72,6 +73,7 @@ fn test_router_app_state_with_streaming(streaming_config: StreamingConfig) -> Ar
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     })
 }
 
@@ -107,6 +109,7 @@ fn test_router_app_state_with_agent_auth_enabled() -> Arc<AppState> {
         ssrf_protection: false,
         trust_configured_backends: false,
         inflight: Arc::new(tokio::sync::Semaphore::new(8)),
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs: Vec::new(),
@@ -120,6 +123,7 @@ fn test_router_app_state_with_agent_auth_enabled() -> Arc<AppState> {
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     })
 }
 
@@ -151,6 +155,7 @@ fn test_router_app_state_with_code_mode(enabled: bool) -> Arc<AppState> {
         ssrf_protection: false,
         trust_configured_backends: false,
         inflight: Arc::new(tokio::sync::Semaphore::new(8)),
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs: Vec::new(),
@@ -164,6 +169,7 @@ fn test_router_app_state_with_code_mode(enabled: bool) -> Arc<AppState> {
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     })
 }
 
@@ -214,6 +220,7 @@ fn test_router_app_state_with_provenance_backend(backend: Arc<Backend>) -> Arc<A
         ssrf_protection: false,
         trust_configured_backends: false,
         inflight: Arc::new(tokio::sync::Semaphore::new(8)),
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs: Vec::new(),
@@ -227,6 +234,7 @@ fn test_router_app_state_with_provenance_backend(backend: Arc<Backend>) -> Arc<A
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     })
 }
 
@@ -289,6 +297,7 @@ fn test_router_app_state_minting_without_route_audit(backend: Arc<Backend>) -> A
         ssrf_protection: false,
         trust_configured_backends: false,
         inflight: Arc::new(tokio::sync::Semaphore::new(8)),
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs: Vec::new(),
@@ -302,6 +311,7 @@ fn test_router_app_state_minting_without_route_audit(backend: Arc<Backend>) -> A
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     })
 }
 
@@ -336,6 +346,7 @@ fn test_router_app_state_with_ssrf(
         ssrf_protection,
         trust_configured_backends,
         inflight: Arc::new(tokio::sync::Semaphore::new(8)),
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs: Vec::new(),
@@ -349,6 +360,7 @@ fn test_router_app_state_with_ssrf(
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     })
 }
 
@@ -397,6 +409,7 @@ fn test_router_app_state_with_auth(auth: &AuthConfig) -> Arc<AppState> {
         ssrf_protection: false,
         trust_configured_backends: false,
         inflight: Arc::new(tokio::sync::Semaphore::new(8)),
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs: Vec::new(),
@@ -410,6 +423,7 @@ fn test_router_app_state_with_auth(auth: &AuthConfig) -> Arc<AppState> {
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     })
 }
 
@@ -616,6 +630,31 @@ fn extract_tools_call_params_empty_object() {
     assert_eq!(args, json!({}));
 }
 
+// =====================================================================
+// parse_client_capabilities
+// =====================================================================
+
+#[test]
+fn parse_client_capabilities_reads_declared_features() {
+    let params = json!({
+        "protocolVersion": "2025-06-18",
+        "capabilities": { "sampling": {}, "roots": { "listChanged": true } }
+    });
+    let caps = parse_client_capabilities(Some(&params));
+    assert!(caps.sampling.is_some());
+    assert!(caps.roots.is_some());
+    assert!(caps.elicitation.is_none());
+}
+
+#[test]
+fn parse_client_capabilities_defaults_to_nothing_declared() {
+    let malformed = json!({ "capabilities": "all" });
+    for params in [None, Some(&malformed)] {
+        let caps = parse_client_capabilities(params);
+        assert!(caps.sampling.is_none() && caps.elicitation.is_none() && caps.roots.is_none());
+    }
+}
+
 // =====================================================================
 // parse_request - valid requests
 // =====================================================================
@@ -1027,6 +1066,7 @@ async fn backend_handler_discovery_method_fails_closed_for_required_propagation(
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }),
         ..BackendConfig::default()
     };
@@ -1094,6 +1134,7 @@ async fn backend_handler_required_mint_without_route_audit_fails_closed_generica
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         }),
         enabled: true,
         ..BackendConfig::default()
@@ -1129,6 +1170,7 @@ async fn backend_handler_required_mint_without_route_audit_fails_closed_generica
         name: None,
         groups: vec![],
         issuer: "https://idp".to_string(),
+        claims: serde_json::Map::new(),
     });
 
     let response = router.oneshot(request).await.unwrap();
@@ -1379,6 +1421,58 @@ async fn backend_handler_direct_route_no_provenance_when_disabled() {
     );
 }
 
+#[tokio::test]
+async fn meta_mcp_downgrades_results_for_session_protocol_version() {
+    // GIVEN: a client that negotiated 2024-11-05 at initialize
+    let router = create_router(test_router_app_state());
+    let post = |body: Value| {
+        axum::http::Request::builder()
+            .method("POST")
+            .uri("/mcp")
+            .header("content-type", "application/json")
+            .header("mcp-session-id", "compat-session")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap()
+    };
+    let init = router
+        .clone()
+        .oneshot(post(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": { "name": "old-client", "version": "0.1" }
+            }
+        })))
+        .await
+        .unwrap();
+    let body = to_bytes(init.into_body(), usize::MAX).await.unwrap();
+    let init: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+    assert!(init.pointer("/result/serverInfo/title").is_none());
+
+    // WHEN: the same session lists tools
+    let response = router
+        .oneshot(post(
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        ))
+        .await
+        .unwrap();
+
+    // THEN: fields introduced after 2024-11-05 are gone
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    let tools = json["result"]["tools"].as_array().unwrap();
+    assert!(!tools.is_empty());
+    for tool in tools {
+        for field in ["annotations", "title", "outputSchema", "icons", "execution"] {
+            assert!(tool.get(field).is_none(), "{field} leaked: {tool}");
+        }
+    }
+}
+
 #[tokio::test]
 async fn meta_mcp_gateway_execute_enforces_api_key_tool_scope() {
     let router = create_router(test_router_app_state_with_auth(&scoped_auth_config(false)));
@@ -1686,6 +1780,40 @@ async fn sse_deprecated_endpoint_returns_jsonrpc_error_with_migration_data() {
     );
 }
 
+// =====================================================================
+// /stats endpoint
+// =====================================================================
+
+#[tokio::test]
+async fn stats_endpoint_returns_snapshot_without_history() {
+    let router = create_router(test_router_app_state());
+    let request = axum::http::Request::builder()
+        .uri("/stats")
+        .body(axum::body::Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value =
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+    assert_eq!(body["snapshot"]["invocations"], 0);
+    assert_eq!(body["history"], Value::Null);
+}
+
+#[tokio::test]
+async fn stats_endpoint_rejects_non_numeric_since() {
+    let router = create_router(test_router_app_state());
+    let request = axum::http::Request::builder()
+        .uri("/stats?since=yesterday")
+        .body(axum::body::Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 // =====================================================================
 // /metrics endpoint
 // =====================================================================
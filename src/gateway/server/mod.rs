@@ -19,26 +19,39 @@ use super::meta_mcp::{MetaMcp, MetaMcpCallerContext};
 use super::oauth::{AgentAuthState, AgentDefinition, AgentRegistry, GatewayKeyPair};
 use super::proxy::ProxyManager;
 use super::router::{AppState, create_router};
-use super::streaming::NotificationMultiplexer;
+use super::streaming::{NotificationMultiplexer, TaggedNotification};
 use super::webhooks::WebhookRegistry;
+use crate::alerts::AlertNotifier;
+use crate::autotag::AutotagRules;
 use crate::backend::{Backend, BackendRegistry, runtime_plan_for_backend};
 use crate::cache::ResponseCache;
 use crate::capability::{CapabilityBackend, CapabilityExecutor, CapabilityWatcher};
-use crate::config::Config;
+use crate::cluster::leader::{self, LeaderElection};
+use crate::config::{ClusterConfig, Config, DiscoveryAdoptPolicy, DiscoveryConfig};
+use crate::config_persistence::{load_existing_or_default, write_config_and_reload};
 use crate::config_reload::{ConfigWatcher, LiveConfig, ReloadContext};
 #[cfg(feature = "cost-governance")]
 use crate::cost_accounting::{
     enforcer::BudgetEnforcer, persistence as cost_persistence, registry::CostRegistry,
 };
-use crate::key_server::{KeyServer, store::spawn_reaper};
+use crate::discovery::monitor::DiscoveryMonitor;
+use crate::enrichment::EnrichmentOverlay;
+use crate::failsafe::ClientRateLimiter;
+use crate::gateway::drain::{self, DrainController};
+use crate::idempotency::{IdempotencyCache, IdempotencyPolicy, IdempotencyStore, IdempotencyTtls};
+use crate::key_server::{JwtIssuer, KeyServer, store::spawn_reaper};
+use crate::kill_switch::KillSwitch;
 use crate::mtls::MtlsPolicy;
-use crate::playbook::PlaybookEngine;
+use crate::playbook::{PlaybookRunStore, PlaybookWatcher};
 use crate::ranking::SearchRanker;
+use crate::ranking::synonyms::SynonymWatcher;
 use crate::routing_profile::ProfileRegistry;
+use crate::scheduler::CronScheduler;
 use crate::security::ToolPolicy;
 #[cfg(feature = "firewall")]
 use crate::security::firewall::Firewall;
-use crate::stats::UsageStats;
+use crate::slo::SloTracker;
+use crate::stats::StatsHistory;
 use crate::transition::TransitionTracker;
 use crate::{Error, Result};
 use warmstart::{WarmStartMode, build_warm_start_list, spawn_warm_start_task};
@@ -232,6 +245,210 @@ fn spawn_export_task(
     Some(status)
 }
 
+/// Probe backends auto-killed by the error budget and revive the ones that
+/// recover (see [`MetaMcp::probe_auto_killed`]).
+fn spawn_auto_revive_task(
+    meta_mcp: Arc<MetaMcp>,
+    config: crate::config::AutoReviveConfig,
+    mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+) {
+    if !config.enabled {
+        return;
+    }
+    // Due times are tracked per server; the tick only bounds how late a probe
+    // can start.
+    let tick = config.cooldown.min(std::time::Duration::from_secs(10));
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(tick.max(std::time::Duration::from_secs(1)));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    for server in meta_mcp.probe_auto_killed(&config).await {
+                        info!(server = %server, "Auto-killed server revived after successful probe");
+                    }
+                }
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    });
+}
+
+/// Run scheduler jobs on their cron expressions (see
+/// [`MetaMcp::run_scheduled_job`]).
+///
+/// Wakes at the top of every minute; each due job runs on its own task so a
+/// slow playbook never delays the next tick. Runs hold an `inflight` permit so
+/// a graceful drain waits for them, and no new runs start once it has begun.
+fn spawn_scheduler_task(
+    meta_mcp: Arc<MetaMcp>,
+    config: &crate::scheduler::SchedulerConfig,
+    inflight: Arc<tokio::sync::Semaphore>,
+    drain: Arc<DrainController>,
+    mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+) -> Result<()> {
+    if !config.enabled || config.jobs.is_empty() {
+        return Ok(());
+    }
+    let scheduler = Arc::new(CronScheduler::from_config(config.clone())?);
+    scheduler.precompute_next_runs(&chrono::Utc::now());
+    info!(jobs = scheduler.len(), "Cron scheduler started");
+    tokio::spawn(async move {
+        loop {
+            // Wake just after the next minute boundary.
+            let into_minute = chrono::Utc::now().timestamp_millis().rem_euclid(60_000);
+            let until_next_minute =
+                std::time::Duration::from_millis(60_000_u64 - into_minute.unsigned_abs() + 1);
+            tokio::select! {
+                () = tokio::time::sleep(until_next_minute) => {
+                    if drain.is_draining() || !leader::is_leader() {
+                        continue;
+                    }
+                    let now = chrono::Utc::now();
+                    for entry in scheduler.due_entries(&now) {
+                        let meta_mcp = Arc::clone(&meta_mcp);
+                        let scheduler = Arc::clone(&scheduler);
+                        let inflight = Arc::clone(&inflight);
+                        tokio::spawn(async move {
+                            let _inflight_permit = inflight.acquire_owned().await;
+                            match meta_mcp.run_scheduled_job(&entry.name, &entry.action).await {
+                                Ok(_) => scheduler.record_success(&entry.name, now),
+                                Err(e) => scheduler.record_failure(&entry.name, now, &e.to_string()),
+                            }
+                        });
+                    }
+                }
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Re-run MCP server discovery every `discovery.interval`, notify connected
+/// clients when unmanaged servers appear or go away, and adopt the ones the
+/// `auto_adopt` policy allows (see [`DiscoveryMonitor`]).
+fn spawn_discovery_task(
+    meta_mcp: &MetaMcp,
+    config: &DiscoveryConfig,
+    live_config: Arc<LiveConfig>,
+    reload_ctx: Option<Arc<ReloadContext>>,
+    multiplexer: Arc<NotificationMultiplexer>,
+    mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+) {
+    if !config.enabled {
+        return;
+    }
+    if config.auto_adopt != DiscoveryAdoptPolicy::Off && reload_ctx.is_none() {
+        warn!("discovery.auto_adopt needs a config file; new servers are only reported");
+    }
+    let monitor = Arc::new(DiscoveryMonitor::new(config));
+    meta_mcp.set_discovery_monitor(Arc::clone(&monitor));
+    let interval = config.interval;
+    info!(interval = ?interval, policy = ?config.auto_adopt, "Background discovery started");
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if !leader::is_leader() {
+                        continue;
+                    }
+                    let configured = live_config.get().backends.clone();
+                    match monitor.scan(&configured).await {
+                        Ok(change) if !change.is_empty() => {
+                            info!(
+                                added = change.added.len(),
+                                removed = change.removed.len(),
+                                "Discovered MCP servers changed"
+                            );
+                            multiplexer.broadcast(TaggedNotification {
+                                source: "gateway".to_string(),
+                                event_type: "notification".to_string(),
+                                data: change.mcp_notification(),
+                                event_id: Some(multiplexer.next_event_id()),
+                            });
+                        }
+                        Ok(_) => {}
+                        Err(e) => warn!(error = %e, "Background discovery failed"),
+                    }
+                    if let Some(ctx) = &reload_ctx {
+                        adopt_discovered(&monitor, ctx).await;
+                    }
+                }
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    });
+}
+
+/// Write the servers the auto-adopt policy allows into the config file and
+/// reload it.
+async fn adopt_discovered(monitor: &DiscoveryMonitor, ctx: &ReloadContext) {
+    let adoptable = monitor.adoptable();
+    if adoptable.is_empty() {
+        return;
+    }
+    let mut config = match load_existing_or_default(&ctx.config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!(error = %e, "Cannot read config to adopt discovered MCP servers");
+            return;
+        }
+    };
+    let mut adopted = Vec::with_capacity(adoptable.len());
+    for server in adoptable {
+        if !config.backends.contains_key(&server.name) {
+            config
+                .backends
+                .insert(server.name.clone(), server.to_backend_config());
+            adopted.push(server.name);
+        }
+    }
+    match write_config_and_reload(&ctx.config_path, &config, Some(ctx)).await {
+        Ok(()) => {
+            info!(backends = ?adopted, "Adopted discovered MCP servers");
+            monitor.record_adopted(&adopted);
+        }
+        Err(e) => warn!(error = %e, "Failed to adopt discovered MCP servers"),
+    }
+}
+
+/// Apply the `disabled:` config section to the kill switch.
+fn apply_disabled_list(kill_switch: &KillSwitch, config: &Config) {
+    let tools = config.disabled.tool_targets(&config.capabilities.name);
+    kill_switch.apply_declared(&config.disabled.servers, &tools);
+    if !config.disabled.is_empty() {
+        info!(
+            servers = config.disabled.servers.len(),
+            tools = tools.len(),
+            "Applied disabled list from config"
+        );
+    }
+}
+
+/// Re-apply the `disabled:` config section after every config reload.
+fn spawn_disabled_list_sync(
+    kill_switch: Arc<KillSwitch>,
+    live_config: &LiveConfig,
+    mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+) {
+    let mut updates = live_config.subscribe();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                changed = updates.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    let config = Arc::clone(&updates.borrow_and_update());
+                    apply_disabled_list(&kill_switch, &config);
+                }
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    });
+}
+
 /// Poll one exporter off the async runtime and fold the outcome into `status`.
 async fn poll_export_source(
     exporter: &Arc<std::sync::Mutex<crate::control_plane::LogExporter>>,
@@ -304,14 +521,9 @@ struct BuiltMetaMcp {
     meta_mcp: Arc<MetaMcp>,
     tool_policy: Arc<ToolPolicy>,
     mtls_policy: Arc<MtlsPolicy>,
-    /// Ranker handle retained for graceful-shutdown persistence (HTTP mode).
-    ranker: Arc<SearchRanker>,
-    /// On-disk path for ranker persistence.
-    ranker_path: std::path::PathBuf,
-    /// Transition tracker retained for shutdown persistence (HTTP mode).
-    transition_tracker: Arc<TransitionTracker>,
-    /// On-disk path for transition persistence.
-    transition_path: std::path::PathBuf,
+    /// Ranker, transition and stats handles retained for periodic and
+    /// shutdown persistence.
+    usage_state: persistence::UsageState,
     /// Data directory used by cost-governance persistence.
     data_dir: std::path::PathBuf,
     /// Transparency log handle, `None` when disabled (issue #133, D3).
@@ -320,6 +532,8 @@ struct BuiltMetaMcp {
     /// `MetaMcp`, can also write identity-propagation audit events into the
     /// same tamper-evident chain (MIK-6740).
     transparency_log: Option<Arc<crate::security::TransparencyLogger>>,
+    /// State shared with other replicas (`cluster`), `None` when standalone.
+    cluster: Option<Arc<dyn crate::cluster::SharedState>>,
 }
 
 /// Decide whether to install a provenance-receipt signer for runtime
@@ -381,6 +595,20 @@ impl Gateway {
     ) -> Result<Self> {
         config.validate()?;
 
+        // Before any backend or capability resolves a `{vault.…}`,
+        // `{aws-secrets.…}`, password manager or `{secret.…}` reference.
+        if let Some(vault) = &config.vault {
+            crate::secrets::vault::install(vault);
+        }
+        crate::secrets::aws::install(&config.aws_secrets);
+        crate::secrets::password_manager::install(&config.onepassword, &config.bitwarden);
+        if let Some(secrets_file) = &config.secrets_file {
+            crate::secrets::encrypted_file::install(
+                secrets_file,
+                config_path.as_deref().and_then(std::path::Path::parent),
+            )?;
+        }
+
         let backends = Arc::new(BackendRegistry::new());
 
         // Register backends
@@ -435,29 +663,14 @@ impl Gateway {
         let tool_policy = Arc::new(ToolPolicy::from_config(&self.config.security.tool_policy));
         let mtls_policy = Arc::new(MtlsPolicy::from_config(&self.config.mtls));
 
-        // ── Usage stats + search ranker with on-disk persistence ─────────────
-        let usage_stats = Some(Arc::new(UsageStats::new()));
-
+        // ── Usage stats, search ranker, transitions with on-disk persistence ─
         let data_dir = persistence::standard_data_dir();
         persistence::ensure_data_dir(&data_dir);
-
-        let ranker_path = data_dir.join("usage.json");
-        let ranker = Arc::new(SearchRanker::new());
-        persistence::load_if_exists(
-            &ranker_path,
-            |path| ranker.load(path),
-            "Failed to load search ranker usage data",
-            "Loaded search ranking usage data",
-        );
-
-        // ── Transition tracker ───────────────────────────────────────────────
-        let transition_path = data_dir.join("transitions.json");
-        let transition_tracker = Arc::new(TransitionTracker::new());
-        persistence::load_if_exists(
-            &transition_path,
-            |path| transition_tracker.load(path),
-            "Failed to load transition tracking data",
-            "Loaded transition tracking data",
+        let usage_state = persistence::UsageState::load(
+            &data_dir,
+            SearchRanker::new()
+                .with_half_life(self.config.ranking.usage_half_life)
+                .with_personalization(self.config.ranking.personalize),
         );
 
         // ── Routing profiles + secret injector ──────────────────────────────
@@ -489,13 +702,21 @@ impl Gateway {
             }
         };
 
+        // ── Cluster state (rate limits, kills, profiles, idempotency) ────────
+        let cluster = crate::cluster::open(&self.config.cluster)?;
+        let mut client_rate_limiter =
+            ClientRateLimiter::new(self.config.failsafe.rate_limit.limits.clone());
+        if let Some(state) = &cluster {
+            client_rate_limiter = client_rate_limiter.with_shared(Arc::clone(state));
+        }
+
         // ── MetaMcp builder ──────────────────────────────────────────────────
         #[allow(unused_mut)]
         let mut meta_mcp_builder = MetaMcp::with_features(
             Arc::clone(&self.backends),
             cache,
-            usage_stats,
-            Some(Arc::clone(&ranker)),
+            Some(Arc::clone(&usage_state.stats)),
+            Some(Arc::clone(&usage_state.ranker)),
             self.config.cache.default_ttl,
         )
         .with_profile_registry(profile_registry)
@@ -503,6 +724,8 @@ impl Gateway {
         .with_projection_mode(self.config.meta_mcp.projection_mode)
         .with_secret_injector(secret_injector)
         .with_surfaced_tools(self.config.meta_mcp.surfaced_tools.clone())
+        .with_playbook_tools(self.config.playbooks.enabled && self.config.playbooks.expose_as_tools)
+        .with_client_rate_limiter(client_rate_limiter)
         .with_trusted_identity_headers(
             self.config
                 .security
@@ -510,6 +733,83 @@ impl Gateway {
                 .trust_caller_identity_headers,
         );
 
+        if let Some(state) = &cluster
+            && let ClusterConfig::Redis { session_ttl, .. } = &self.config.cluster
+        {
+            meta_mcp_builder = meta_mcp_builder.with_cluster(Arc::clone(state), *session_ttl);
+        }
+
+        if self.config.stats_history.enabled {
+            let history = &self.config.stats_history;
+            meta_mcp_builder = meta_mcp_builder.with_stats_history(Arc::new(StatsHistory::new(
+                history.resolution,
+                history.capacity(),
+            )));
+        }
+        if self.config.slo.enabled {
+            meta_mcp_builder =
+                meta_mcp_builder.with_slo_tracker(Arc::new(SloTracker::new(&self.config.slo)));
+        }
+        if self.config.alerts.is_enabled() {
+            meta_mcp_builder = meta_mcp_builder.with_alerts(Arc::new(AlertNotifier::new()));
+        }
+        meta_mcp_builder =
+            meta_mcp_builder.with_autotag_rules(AutotagRules::from_config(&self.config.autotag)?);
+        if let Some(path) = self.config.enrichment.overlay_path() {
+            match EnrichmentOverlay::load(&path) {
+                Ok(overlay) => {
+                    info!(tools = overlay.len(), "Loaded enrichment overlay");
+                    meta_mcp_builder = meta_mcp_builder.with_enrichment_overlay(overlay);
+                }
+                Err(e) => warn!(error = %e, "Enrichment overlay not loaded; using autotag"),
+            }
+        }
+        meta_mcp_builder = meta_mcp_builder.with_prediction(self.config.prediction.clone());
+        if self.config.idempotency.enabled {
+            let settings = &self.config.idempotency;
+            let mut idempotency = IdempotencyCache::new()
+                .with_ttls(IdempotencyTtls {
+                    completed: settings.completed_ttl,
+                    in_flight: settings.in_flight_timeout,
+                })
+                .with_policy(IdempotencyPolicy {
+                    auto_derive: settings.auto_derive,
+                    always: settings.always.clone(),
+                    never: settings.never.clone(),
+                });
+            if let Some(state) = &cluster {
+                info!("Idempotency entries shared through the cluster state");
+                idempotency = idempotency.with_store(IdempotencyStore::shared(Arc::clone(state)));
+            } else if let Some(dir) = settings.store_path() {
+                match IdempotencyStore::open(&dir) {
+                    Ok(store) => {
+                        info!(dir = %dir.display(), "Idempotency entries persisted");
+                        idempotency = idempotency.with_store(store);
+                    }
+                    Err(e) => warn!(error = %e, "Idempotency store unavailable; using memory only"),
+                }
+            }
+            meta_mcp_builder.enable_idempotency(Arc::new(idempotency), settings.cleanup_interval);
+        }
+
+        if let Some(dir) = self
+            .config
+            .playbooks
+            .enabled
+            .then(|| self.config.playbooks.state_path())
+            .flatten()
+        {
+            match PlaybookRunStore::open(&dir) {
+                Ok(store) => {
+                    info!(dir = %dir.display(), "Playbook runs persisted");
+                    meta_mcp_builder = meta_mcp_builder.with_playbook_runs(Arc::new(store));
+                }
+                Err(e) => {
+                    warn!(error = %e, "Playbook run store unavailable; runs are not resumable");
+                }
+            }
+        }
+
         #[cfg(feature = "cost-governance")]
         if let (Some(registry), Some(enforcer)) = (cost_registry_opt, budget_enforcer_opt) {
             meta_mcp_builder = meta_mcp_builder.with_cost_governance(enforcer, registry);
@@ -539,7 +839,17 @@ impl Gateway {
             license_tier = self.config.security.context_integrity.license_tier(),
             "Context integrity policy configured"
         );
-        meta_mcp.set_transition_tracker(Arc::clone(&transition_tracker));
+        meta_mcp.set_transition_tracker(Arc::clone(&usage_state.transition_tracker));
+        apply_disabled_list(&meta_mcp.kill_switch(), &self.config);
+        if let Some(state) = &cluster
+            && let ClusterConfig::Redis { sync_interval, .. } = &self.config.cluster
+        {
+            crate::cluster::spawn_kill_switch_sync(
+                Arc::clone(state),
+                meta_mcp.kill_switch(),
+                *sync_interval,
+            );
+        }
 
         // ── Transparency log (issue #133, D3) ─────────────────────────────────
         // The opened `Arc` is kept as `transparency_log` (not just handed to
@@ -561,6 +871,7 @@ impl Gateway {
                     Arc::get_mut(&mut meta_mcp)
                         .expect("no other Arc references at this point")
                         .enable_transparency_log(Arc::clone(&logger));
+                    crate::secrets::audit::install(Arc::clone(&logger));
                     transparency_log = Some(logger);
                     info!("Transparency log enabled");
                 }
@@ -695,12 +1006,10 @@ impl Gateway {
             meta_mcp,
             tool_policy,
             mtls_policy,
-            ranker,
-            ranker_path,
-            transition_tracker,
-            transition_path,
+            usage_state,
             data_dir,
             transparency_log,
+            cluster,
         })
     }
 
@@ -738,14 +1047,24 @@ impl Gateway {
             meta_mcp,
             tool_policy,
             mtls_policy,
-            ranker,
-            ranker_path,
-            transition_tracker,
-            transition_path,
+            usage_state,
             data_dir,
             transparency_log,
+            cluster,
         } = self.build_meta_mcp().await?;
 
+        // ── Leader election for cluster-wide background jobs ─────────────────
+        let election = match (&cluster, &self.config.cluster) {
+            (Some(state), ClusterConfig::Redis { leader_lease, .. }) => {
+                let election = Arc::new(LeaderElection::new(Arc::clone(state), *leader_lease));
+                info!(replica = %election.replica_id(), "Standing for leader election");
+                leader::install(Arc::clone(&election));
+                leader::spawn_leader_election(Arc::clone(&election), shutdown_tx.subscribe());
+                Some(election)
+            }
+            _ => None,
+        };
+
         // Log policy and feature states now that the shared builder has run.
         if self.config.security.tool_policy.enabled {
             info!("Tool security policy enabled");
@@ -774,9 +1093,6 @@ impl Gateway {
             );
         }
 
-        let ranker_for_shutdown = Arc::clone(&ranker);
-        let tracker_for_shutdown = Arc::clone(&transition_tracker);
-
         // T2.6: warn when a surfaced tool's backend is not in warm_start.
         for surfaced in &self.config.meta_mcp.surfaced_tools {
             if !self.config.meta_mcp.warm_start.contains(&surfaced.server) {
@@ -789,6 +1105,9 @@ impl Gateway {
             }
         }
 
+        // Custom search synonyms, reloaded whenever the file changes
+        let _synonym_watcher = self.start_synonym_watcher(shutdown_tx.subscribe());
+
         // Create webhook registry
         let webhook_registry = Arc::new(parking_lot::RwLock::new(WebhookRegistry::new(
             self.config.webhooks.clone(),
@@ -797,12 +1116,18 @@ impl Gateway {
         // Load capabilities if enabled. Capability directories can be large;
         // when webhook route construction does not depend on them, populate the
         // backend in the background so health/MCP endpoints bind promptly.
-        let _capability_watcher: Option<CapabilityWatcher> = if self.config.capabilities.enabled {
-            let executor = Arc::new(CapabilityExecutor::new());
+        let (_capability_watcher, capability_backend): (
+            Option<CapabilityWatcher>,
+            Option<Arc<CapabilityBackend>>,
+        ) = if self.config.capabilities.enabled {
+            let executor = Arc::new(
+                CapabilityExecutor::new().with_timeouts(self.config.capabilities.timeouts.clone()),
+            );
             let cap_backend = Arc::new(CapabilityBackend::new(
                 &self.config.capabilities.name,
                 executor,
             ));
+            cap_backend.set_registry_trust(&self.config.registry);
             meta_mcp.set_capabilities(Arc::clone(&cap_backend));
 
             let capability_dirs = self.config.capabilities.directories.clone();
@@ -856,7 +1181,10 @@ impl Gateway {
             });
 
             // Start file watcher for hot-reload
-            match CapabilityWatcher::start(Arc::clone(&cap_backend), shutdown_tx.subscribe()) {
+            let watcher = match CapabilityWatcher::start(
+                Arc::clone(&cap_backend),
+                shutdown_tx.subscribe(),
+            ) {
                 Ok(w) => {
                     info!("Capability hot-reload enabled");
                     Some(w)
@@ -865,30 +1193,15 @@ impl Gateway {
                     warn!(error = %e, "Failed to start capability watcher, hot-reload disabled");
                     None
                 }
-            }
+            };
+            (watcher, Some(cap_backend))
         } else {
-            None
+            (None, None)
         };
 
         // Load playbooks if enabled
         if self.config.playbooks.enabled {
-            let mut engine = PlaybookEngine::new();
-            let mut total_playbooks = 0;
-            for dir in &self.config.playbooks.directories {
-                match engine.load_from_directory(dir) {
-                    Ok(count) => {
-                        total_playbooks += count;
-                        debug!(directory = %dir, count, "Loaded playbooks");
-                    }
-                    Err(e) => {
-                        debug!(directory = %dir, error = %e, "Failed to load playbooks");
-                    }
-                }
-            }
-            if total_playbooks > 0 {
-                info!(playbooks = total_playbooks, "Playbook engine ready");
-            }
-            meta_mcp.set_playbook_engine(engine);
+            meta_mcp.reload_playbooks(&self.config.playbooks.directories);
         }
 
         let multiplexer = Arc::new(NotificationMultiplexer::new(
@@ -897,6 +1210,16 @@ impl Gateway {
         ));
         multiplexer.spawn_reaper_on();
         let proxy_manager = Arc::new(ProxyManager::new(Arc::clone(&multiplexer)));
+        // Capability files added, edited or removed at runtime (watcher or
+        // `gateway_reload_capabilities`) change the tool list; tell clients.
+        if let Some(cap_backend) = &capability_backend {
+            let proxy_manager = Arc::clone(&proxy_manager);
+            cap_backend.set_tools_changed_hook(Arc::new(move || {
+                proxy_manager.broadcast_tools_list_changed();
+            }));
+        }
+        let _playbook_watcher =
+            self.start_playbook_watcher(&meta_mcp, &proxy_manager, shutdown_tx.subscribe());
         let auth_config = Arc::new(ResolvedAuthConfig::try_from_config(&self.config.auth)?);
 
         // Wire webhook registry into MetaMcp for gateway_webhook_status.
@@ -910,6 +1233,11 @@ impl Gateway {
         // MIK-6702). Created unconditionally; without a config path it simply
         // never changes.
         let live_config = Arc::new(LiveConfig::new(self.config.clone()));
+        spawn_disabled_list_sync(
+            meta_mcp.kill_switch(),
+            &live_config,
+            shutdown_tx.subscribe(),
+        );
 
         // SIEM evidence-export background task (MIK-6703). None when disabled.
         let export_status = spawn_export_task(
@@ -918,12 +1246,30 @@ impl Gateway {
             shutdown_tx.subscribe(),
         );
 
+        spawn_auto_revive_task(
+            Arc::clone(&meta_mcp),
+            self.config.failsafe.auto_revive.clone(),
+            shutdown_tx.subscribe(),
+        );
+        // In-flight request tracker: each request or scheduled run holds a
+        // permit; the drain waits until all permits are returned.
+        let inflight = Arc::new(tokio::sync::Semaphore::new(drain::INFLIGHT_CAPACITY));
+        let drain = Arc::new(DrainController::new());
+
+        spawn_scheduler_task(
+            Arc::clone(&meta_mcp),
+            &self.config.scheduler,
+            Arc::clone(&inflight),
+            Arc::clone(&drain),
+            shutdown_tx.subscribe(),
+        )?;
+
         // Wire the config hot-reload *context* into meta_mcp before it moves
         // into AppState. The file watcher that can mutate `live_config` is
         // started later (after `create_router`) so the router's startup
         // bind-origin snapshot reads `live_config` while it still equals the
         // config the listener binds — no startup reload race (MIK-6750 r4).
-        if let Some(ref path) = self.config_path {
+        let reload_ctx = self.config_path.as_ref().map(|path| {
             let reload_ctx = Arc::new(ReloadContext::new(
                 path.clone(),
                 Arc::clone(&live_config),
@@ -932,11 +1278,16 @@ impl Gateway {
                 self.config.meta_mcp.cache_ttl,
             ));
             meta_mcp.set_reload_context(Arc::clone(&reload_ctx));
-        }
-
-        // In-flight request tracker: large initial permits, drain waits for
-        // all permits to be returned (i.e., all in-flight requests complete).
-        let inflight = Arc::new(tokio::sync::Semaphore::new(10_000));
+            reload_ctx
+        });
+        spawn_discovery_task(
+            &meta_mcp,
+            &self.config.discovery,
+            Arc::clone(&live_config),
+            reload_ctx,
+            Arc::clone(&multiplexer),
+            shutdown_tx.subscribe(),
+        );
 
         // Create key server if enabled
         let key_server = if self.config.key_server.enabled {
@@ -945,7 +1296,31 @@ impl Gateway {
             ks_config.admin_token = ks_config.resolve_admin_token()?;
 
             let cleanup_interval = std::time::Duration::from_secs(ks_config.cleanup_interval_secs);
-            let ks = Arc::new(KeyServer::new(ks_config));
+            if ks_config
+                .jwt
+                .as_ref()
+                .is_some_and(|c| c.signing_key.is_none())
+            {
+                warn!(
+                    "key_server.jwt has no signing_key; tokens are signed with a per-process key"
+                );
+            }
+            let jwt = ks_config
+                .jwt
+                .as_ref()
+                .map(JwtIssuer::from_config)
+                .transpose()?;
+            let store = crate::key_server::store::open(&ks_config.store).await?;
+            let mut ks = KeyServer::new(ks_config).with_store(Arc::clone(&store));
+            if let Some(jwt) = jwt {
+                // Revocations recorded before this start still apply.
+                for (jti, exp) in store.revoked().await {
+                    jwt.revoke_jti(&jti, exp);
+                }
+                info!(kid = %jwt.key_id(), "Key server issues signed JWTs");
+                ks = ks.with_jwt(jwt);
+            }
+            let ks = Arc::new(ks);
 
             spawn_reaper(
                 Arc::clone(&ks.store),
@@ -1004,6 +1379,11 @@ impl Gateway {
             }
         });
 
+        // Publish the key server's JWT signing key next to the gateway key.
+        if let Some(jwt) = key_server.as_ref().and_then(|ks| ks.jwt.as_ref()) {
+            gateway_key_pair.publish(jwt.jwks().keys);
+        }
+
         // Wire end-user identity propagation (MIK-6704 / ADR-007, MIK-6729):
         // when a backend opts into a *minting* strategy, give MetaMcp the single
         // process-wide strategy that matches the configured kind. Config
@@ -1120,6 +1500,21 @@ impl Gateway {
         let control_plane_store =
             build_control_plane_store(&self.config, self.config_path.as_deref());
 
+        let access_log = if self.config.access_log.enabled {
+            match crate::access_log::AccessLog::open(&self.config.access_log) {
+                Ok(log) => {
+                    info!(path = %self.config.access_log.file_path().display(), "Access log enabled");
+                    Some(Arc::new(log))
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to open access log, access logging disabled");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         let state = Arc::new(AppState {
             backends: Arc::clone(&self.backends),
             meta_mcp,
@@ -1135,6 +1530,7 @@ impl Gateway {
             ssrf_protection: self.config.security.ssrf_protection,
             trust_configured_backends: self.config.security.trust_configured_backends,
             inflight: Arc::clone(&inflight),
+            drain: Arc::clone(&drain),
             agent_auth,
             gateway_key_pair,
             capability_dirs: if self.config.capabilities.enabled {
@@ -1150,6 +1546,7 @@ impl Gateway {
             live_config: Arc::clone(&live_config),
             export_status,
             transparency_log,
+            access_log,
         });
 
         // Create router
@@ -1303,6 +1700,79 @@ impl Gateway {
             }
         });
 
+        // Spawn periodic usage persistence (ranker, transitions, stats)
+        {
+            let usage_state = usage_state.clone();
+            let mut shutdown_rx_usage = shutdown_tx.subscribe();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(persistence::USAGE_SAVE_INTERVAL);
+                // Skip first immediate tick (state was just loaded)
+                interval.tick().await;
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {
+                            if leader::is_leader() {
+                                usage_state.save_periodic();
+                            }
+                        }
+                        _ = shutdown_rx_usage.recv() => {
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+
+        // Spawn the stats history sampler (feeds `GET /stats`)
+        if let Some(history) = meta_mcp_for_shutdown.stats_history() {
+            let usage_stats = Arc::clone(&usage_state.stats);
+            let backends = Arc::clone(&self.backends);
+            let mut shutdown_rx_history = shutdown_tx.subscribe();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(history.resolution());
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {
+                            let tools = backends.statuses().values().map(|s| s.tools_cached).sum();
+                            let now = std::time::SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .map_or(0, |d| d.as_secs());
+                            history.record(&usage_stats.snapshot(tools), now);
+                        }
+                        _ = shutdown_rx_history.recv() => {
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+
+        // Spawn push-based metrics export (no-op unless enabled)
+        crate::metrics_export::spawn(
+            &self.config.metrics_export,
+            Arc::clone(&self.backends),
+            Arc::clone(&usage_state.stats),
+            shutdown_tx.subscribe(),
+        )
+        .await;
+
+        // Spawn the SLO burn-rate evaluator (only when objectives are set)
+        if let Some(tracker) = meta_mcp_for_shutdown.slo_tracker() {
+            crate::slo::spawn(tracker, &self.config.slo, shutdown_tx.subscribe());
+        }
+
+        // Spawn operator alert delivery (only when a sink or rule is configured)
+        if let Some(notifier) = meta_mcp_for_shutdown.alert_notifier() {
+            crate::alerts::spawn(
+                &notifier,
+                Arc::clone(&self.backends),
+                meta_mcp_for_shutdown.response_cache(),
+                Some(Arc::clone(&multiplexer)),
+                &self.config.alerts,
+                shutdown_tx.subscribe(),
+            );
+        }
+
         // Spawn periodic cost-governance persistence (every 5 minutes)
         #[cfg(feature = "cost-governance")]
         if let Some(ref enforcer) = meta_mcp_for_shutdown.budget_enforcer {
@@ -1316,6 +1786,9 @@ impl Gateway {
                 loop {
                     tokio::select! {
                         _ = interval.tick() => {
+                            if !leader::is_leader() {
+                                continue;
+                            }
                             let snap = enforcer_persist.snapshot();
                             let persisted = build_persisted_costs(&snap);
                             if let Err(e) = cost_persistence::save(&costs_path_periodic, &persisted) {
@@ -1332,69 +1805,84 @@ impl Gateway {
             });
         }
 
-        // Run server — plain HTTP or mTLS depending on config
-        if self.config.mtls.enabled {
-            serve_tls(app, addr, &self.config.mtls, shutdown_signal(shutdown_tx)).await?;
-        } else {
-            axum::serve(listener, app)
-                .with_graceful_shutdown(shutdown_signal(shutdown_tx))
-                .await
-                .map_err(|e| Error::Tls(e.to_string()))?;
-        }
-
-        // Save search ranker usage data
-        persistence::save_with_logging(
-            &ranker_path,
-            |path| ranker_for_shutdown.save(path),
-            "Failed to save search ranker usage data",
-            "Saved search ranking usage data",
-        );
+        // Graceful drain: runs once a signal or `POST /api/drain` arrives and
+        // resolves when the listener may close (see `crate::gateway::drain`).
+        let drain_sequence = {
+            let drain = Arc::clone(&drain);
+            let inflight = Arc::clone(&inflight);
+            let multiplexer = Arc::clone(&multiplexer);
+            let usage_state = usage_state.clone();
+            let shutdown_tx = shutdown_tx.clone();
+            let drain_timeout = self.config.server.shutdown_timeout;
+            #[cfg(feature = "cost-governance")]
+            let budget_enforcer = meta_mcp_for_shutdown.budget_enforcer.clone();
+            #[cfg(feature = "cost-governance")]
+            let costs_path = data_dir.join("costs.json");
+            async move {
+                shutdown_signal(&drain).await;
+
+                info!(timeout = ?drain_timeout, "Draining in-flight requests...");
+                let remaining =
+                    drain::wait_for_idle(&inflight, drain::INFLIGHT_CAPACITY, drain_timeout).await;
+                if remaining == 0 {
+                    info!("All in-flight requests completed");
+                } else {
+                    warn!(
+                        remaining_requests = remaining,
+                        "Drain timeout reached, proceeding with shutdown"
+                    );
+                }
 
-        // Save transition tracking data
-        persistence::save_with_logging(
-            &transition_path,
-            |path| tracker_for_shutdown.save(path),
-            "Failed to save transition tracking data",
-            "Saved transition tracking data",
-        );
+                // Save search ranker, transition and usage statistics data
+                // (the leader's alone when clustered)
+                let leads = leader::is_leader();
+                if leads {
+                    usage_state.save();
+                }
 
-        // Save cost governance data on graceful shutdown
-        #[cfg(feature = "cost-governance")]
-        if let Some(ref enforcer) = meta_mcp_for_shutdown.budget_enforcer {
-            let costs_path = data_dir.join("costs.json");
-            let snap = enforcer.snapshot();
-            let persisted = build_persisted_costs(&snap);
-            persistence::save_with_logging(
-                &costs_path,
-                |path| cost_persistence::save(path, &persisted),
-                "Failed to save cost data on shutdown",
-                "Saved cost governance data",
-            );
-        }
+                // Save cost governance data
+                #[cfg(feature = "cost-governance")]
+                if leads && let Some(enforcer) = budget_enforcer {
+                    let persisted = build_persisted_costs(&enforcer.snapshot());
+                    persistence::save_with_logging(
+                        &costs_path,
+                        |path| cost_persistence::save(path, &persisted),
+                        "Failed to save cost data on shutdown",
+                        "Saved cost governance data",
+                    );
+                }
 
-        // Graceful drain: wait for in-flight requests to complete.
-        // The semaphore has 10,000 permits; each in-flight request holds one.
-        // We try to acquire all 10,000 (meaning all requests finished) with a timeout.
-        let drain_timeout = self.config.server.shutdown_timeout;
-        info!(timeout = ?drain_timeout, "Draining in-flight requests...");
+                if let Some(election) = election {
+                    tokio::task::spawn_blocking(move || election.resign())
+                        .await
+                        .ok();
+                }
 
-        let drain_result = tokio::time::timeout(drain_timeout, inflight.acquire_many(10_000)).await;
+                let closed = multiplexer.close_streams(drain::RECONNECT_AFTER);
+                info!(streams = closed, "Closed SSE streams with reconnect hint");
 
-        match drain_result {
-            Ok(Ok(_permits)) => {
-                info!("All in-flight requests completed");
-            }
-            Ok(Err(_)) => {
-                warn!("Inflight semaphore closed unexpectedly during drain");
-            }
-            Err(_) => {
-                let available = inflight.available_permits();
-                let remaining = 10_000_usize.saturating_sub(available);
-                warn!(
-                    remaining_requests = remaining,
-                    "Drain timeout reached, proceeding with shutdown"
-                );
+                let _ = shutdown_tx.send(());
             }
+        };
+
+        // Run server — plain HTTP or mTLS depending on config
+        if self.config.mtls.enabled {
+            serve_tls(
+                app,
+                addr,
+                &self.config.mtls,
+                shutdown_tx.subscribe(),
+                drain_sequence,
+            )
+            .await?;
+        } else {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .with_graceful_shutdown(drain_sequence)
+            .await
+            .map_err(|e| Error::Tls(e.to_string()))?;
         }
 
         // Stop all backends
@@ -1429,15 +1917,19 @@ impl Gateway {
             meta_mcp,
             tool_policy,
             mtls_policy,
+            usage_state,
             ..
         } = self.build_meta_mcp().await?;
 
         if self.config.capabilities.enabled {
-            let executor = Arc::new(CapabilityExecutor::new());
+            let executor = Arc::new(
+                CapabilityExecutor::new().with_timeouts(self.config.capabilities.timeouts.clone()),
+            );
             let cap_backend = Arc::new(CapabilityBackend::new(
                 &self.config.capabilities.name,
                 executor,
             ));
+            cap_backend.set_registry_trust(&self.config.registry);
             for dir in &self.config.capabilities.directories {
                 if let Ok(count) = cap_backend.load_from_directory(dir).await {
                     debug!(directory = %dir, count, "Loaded capabilities (stdio)");
@@ -1447,13 +1939,7 @@ impl Gateway {
         }
 
         if self.config.playbooks.enabled {
-            let mut engine = crate::playbook::PlaybookEngine::new();
-            for dir in &self.config.playbooks.directories {
-                if let Ok(count) = engine.load_from_directory(dir) {
-                    debug!(directory = %dir, count, "Loaded playbooks (stdio)");
-                }
-            }
-            meta_mcp.set_playbook_engine(engine);
+            meta_mcp.reload_playbooks(&self.config.playbooks.directories);
         }
 
         // Warm-start backends (same as HTTP mode)
@@ -1463,6 +1949,32 @@ impl Gateway {
             spawn_warm_start_task(&self.backends, warm_start_list, WarmStartMode::Stdio);
         }
 
+        // Push-based metrics export; stops when `export_shutdown` is dropped.
+        let (export_shutdown, export_shutdown_rx) = tokio::sync::broadcast::channel::<()>(1);
+        crate::metrics_export::spawn(
+            &self.config.metrics_export,
+            Arc::clone(&self.backends),
+            Arc::clone(&usage_state.stats),
+            export_shutdown_rx,
+        )
+        .await;
+        if let Some(tracker) = meta_mcp.slo_tracker() {
+            crate::slo::spawn(tracker, &self.config.slo, export_shutdown.subscribe());
+        }
+        if let Some(notifier) = meta_mcp.alert_notifier() {
+            // No streamable-HTTP sessions in stdio mode; webhooks only.
+            crate::alerts::spawn(
+                &notifier,
+                Arc::clone(&self.backends),
+                meta_mcp.response_cache(),
+                None,
+                &self.config.alerts,
+                export_shutdown.subscribe(),
+            );
+        }
+
+        let _synonym_watcher = self.start_synonym_watcher(export_shutdown.subscribe());
+
         info!("MCP Gateway stdio mode ready — reading JSON-RPC from stdin");
 
         // ── Read → dispatch → write loop ────────────────────────────────────
@@ -1523,10 +2035,61 @@ impl Gateway {
         }
 
         info!("stdio: EOF reached, shutting down");
+        drop(export_shutdown);
+        usage_state.save();
         self.backends.stop_all().await;
         Ok(())
     }
 
+    /// Load `ranking.synonyms_file` and watch it for changes.
+    ///
+    /// A missing or invalid file is logged and leaves the built-in synonyms
+    /// in effect.
+    fn start_synonym_watcher(
+        &self,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Option<SynonymWatcher> {
+        let file = self.config.ranking.synonyms_file.as_deref()?;
+        match SynonymWatcher::start(crate::config_reload::expand_tilde(file), shutdown_rx) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!(error = %e, "Failed to load custom synonyms; using built-in table");
+                None
+            }
+        }
+    }
+
+    /// Watch the playbook directories and reload playbooks on change,
+    /// announcing `tools/list_changed` when they are published as tools.
+    fn start_playbook_watcher(
+        &self,
+        meta_mcp: &Arc<MetaMcp>,
+        proxy_manager: &Arc<ProxyManager>,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Option<PlaybookWatcher> {
+        let playbooks = &self.config.playbooks;
+        if !playbooks.enabled {
+            return None;
+        }
+        let directories = playbooks.directories.clone();
+        let announce = playbooks.expose_as_tools;
+        let meta_mcp = Arc::clone(meta_mcp);
+        let proxy_manager = Arc::clone(proxy_manager);
+        let reload = Arc::new(move || {
+            meta_mcp.reload_playbooks(&directories);
+            if announce {
+                proxy_manager.broadcast_tools_list_changed();
+            }
+        });
+        match PlaybookWatcher::start(&playbooks.directories, reload, shutdown_rx) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!(error = %e, "Failed to start playbook watcher, hot-reload disabled");
+                None
+            }
+        }
+    }
+
     /// Write a JSON-RPC response to stdout followed by a newline.
     async fn write_response(stdout: &mut tokio::io::Stdout, value: &serde_json::Value) {
         let serialized = match serde_json::to_string(value) {
@@ -1793,6 +2356,7 @@ mod tests {
                 session_mode: SessionMode::Stateless,
                 token_exchange_endpoint: None,
                 token_exchange_scope: None,
+                token_exchange_client: None,
             }),
             ..BackendConfig::default()
         }
@@ -1907,6 +2471,8 @@ mod tests {
             oauth: Some(crate::config::OAuthConfig {
                 enabled,
                 scopes: vec![],
+                grant_type: crate::config::OAuthGrantType::AuthorizationCode,
+                accounts: Vec::new(),
                 client_id: None,
                 client_secret: None,
                 callback_host: None,
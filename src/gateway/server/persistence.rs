@@ -2,8 +2,18 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+
+use crate::ranking::SearchRanker;
+use crate::stats::UsageStats;
+use crate::transition::TransitionTracker;
+
+/// How often learned usage state is flushed to disk while the gateway runs,
+/// so a crash or `kill -9` loses at most this much history.
+pub(super) const USAGE_SAVE_INTERVAL: Duration = Duration::from_secs(300);
 
 pub(super) fn standard_data_dir() -> PathBuf {
     dirs::home_dir()
@@ -51,6 +61,124 @@ pub(super) fn save_with_logging<F, E>(
     }
 }
 
+/// Write `path` through `save` into a temporary file next to it, then
+/// rename that over `path`, so a crash mid-write never leaves a truncated
+/// file behind.
+fn save_atomically(
+    path: &Path,
+    save: impl FnOnce(&Path) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    let result = save(&tmp).and_then(|()| std::fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Usage state learned at runtime and persisted under the data directory:
+/// search-ranker usage counts, tool transitions, and usage statistics.
+#[derive(Clone)]
+pub(super) struct UsageState {
+    pub(super) ranker: Arc<SearchRanker>,
+    pub(super) transition_tracker: Arc<TransitionTracker>,
+    pub(super) stats: Arc<UsageStats>,
+    data_dir: PathBuf,
+}
+
+impl UsageState {
+    /// Create fresh state around `ranker` and load whatever was persisted
+    /// in `data_dir`.
+    pub(super) fn load(data_dir: &Path, ranker: SearchRanker) -> Self {
+        let state = Self {
+            ranker: Arc::new(ranker),
+            transition_tracker: Arc::new(TransitionTracker::new()),
+            stats: Arc::new(UsageStats::new()),
+            data_dir: data_dir.to_path_buf(),
+        };
+        load_if_exists(
+            &state.ranker_path(),
+            |path| state.ranker.load(path),
+            "Failed to load search ranker usage data",
+            "Loaded search ranking usage data",
+        );
+        load_if_exists(
+            &state.transition_path(),
+            |path| state.transition_tracker.load(path),
+            "Failed to load transition tracking data",
+            "Loaded transition tracking data",
+        );
+        load_if_exists(
+            &state.stats_path(),
+            |path| state.stats.load(path),
+            "Failed to load usage statistics",
+            "Loaded usage statistics",
+        );
+        state
+    }
+
+    fn ranker_path(&self) -> PathBuf {
+        self.data_dir.join("usage.json")
+    }
+
+    fn transition_path(&self) -> PathBuf {
+        self.data_dir.join("transitions.json")
+    }
+
+    fn stats_path(&self) -> PathBuf {
+        self.data_dir.join("stats.json")
+    }
+
+    /// Write all components to disk, logging each outcome.
+    pub(super) fn save(&self) {
+        save_with_logging(
+            &self.ranker_path(),
+            |path| save_atomically(path, |tmp| self.ranker.save(tmp)),
+            "Failed to save search ranker usage data",
+            "Saved search ranking usage data",
+        );
+        save_with_logging(
+            &self.transition_path(),
+            |path| save_atomically(path, |tmp| self.transition_tracker.save(tmp)),
+            "Failed to save transition tracking data",
+            "Saved transition tracking data",
+        );
+        save_with_logging(
+            &self.stats_path(),
+            |path| save_atomically(path, |tmp| self.stats.save(tmp)),
+            "Failed to save usage statistics",
+            "Saved usage statistics",
+        );
+    }
+
+    /// Periodic variant of [`save`](Self::save): failures warn, successes
+    /// log at debug level to keep the steady-state log quiet.
+    pub(super) fn save_periodic(&self) {
+        let results = [
+            (
+                "search ranker",
+                save_atomically(&self.ranker_path(), |tmp| self.ranker.save(tmp)),
+            ),
+            (
+                "transitions",
+                save_atomically(&self.transition_path(), |tmp| {
+                    self.transition_tracker.save(tmp)
+                }),
+            ),
+            (
+                "usage statistics",
+                save_atomically(&self.stats_path(), |tmp| self.stats.save(tmp)),
+            ),
+        ];
+        for (component, result) in results {
+            match result {
+                Ok(()) => debug!(component, "Periodic usage persistence saved"),
+                Err(e) => warn!(error = %e, component, "Periodic usage persistence failed"),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +231,49 @@ mod tests {
 
         assert!(called.get());
     }
+
+    #[test]
+    fn save_atomically_keeps_the_previous_file_when_the_write_fails() {
+        // GIVEN: a previously saved file
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("usage.json");
+        std::fs::write(&path, "{\"old\":true}").unwrap();
+
+        // WHEN: a save writes part of the file and then fails
+        let result = save_atomically(&path, |tmp| {
+            std::fs::write(tmp, "{\"trunc")?;
+            Err(std::io::Error::other("disk full"))
+        });
+
+        // THEN: the previous file is intact and no temporary file is left
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"old\":true}");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        // AND: a successful save replaces it
+        save_atomically(&path, |tmp| std::fs::write(tmp, "{\"new\":true}")).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"new\":true}");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn usage_state_round_trips_through_data_dir() {
+        // GIVEN: usage state with ranker, transition and stats activity
+        let dir = tempfile::tempdir().unwrap();
+        let state = UsageState::load(dir.path(), SearchRanker::new());
+        state.ranker.record_use("srv", "search");
+        state.transition_tracker.record_transition("s", "srv:a");
+        state.transition_tracker.record_transition("s", "srv:b");
+        state.stats.record_invocation("srv", "search");
+
+        // WHEN: saved and reloaded from the same directory
+        state.save_periodic();
+        let restored = UsageState::load(dir.path(), SearchRanker::new());
+
+        // THEN: every component is restored
+        assert_eq!(restored.ranker.usage_count("srv", "search"), 1);
+        assert_eq!(restored.transition_tracker.total_transitions(), 1);
+        assert_eq!(restored.stats.tool_usage("srv", "search"), 1);
+        assert_eq!(restored.stats.snapshot(0).invocations, 1);
+    }
 }
@@ -5,7 +5,8 @@
 //! Contains free functions used during server startup and shutdown:
 //! - [`log_startup_banner`]: emits the startup info block to the tracing log.
 //! - [`serve_tls`]: starts the mTLS HTTPS listener via `axum-server`.
-//! - [`shutdown_signal`]: awaits Ctrl+C / SIGTERM and broadcasts shutdown.
+//! - [`shutdown_signal`]: awaits Ctrl+C / SIGTERM or a drain request and
+//!   starts the drain.
 //! - [`build_persisted_costs`]: converts an enforcer snapshot to the
 //!   persistence format (cost-governance feature only).
 
@@ -28,6 +29,7 @@ use tracing::{info, warn};
 
 use crate::backend::BackendRegistry;
 use crate::config::Config;
+use crate::gateway::drain::DrainController;
 use crate::mtls::CertIdentity;
 
 /// Emit the startup banner to the tracing log.
@@ -85,20 +87,61 @@ pub(super) fn log_startup_banner(config: &Config, backends: &BackendRegistry) {
 ///
 /// Builds a `rustls::ServerConfig` from `mtls_config`, wraps it in
 /// `axum-server`'s `RustlsConfig`, and runs until the `shutdown_fut` resolves.
+/// Certificates are hot-reloaded until `reload_shutdown_rx` fires (see
+/// [`CertReloader`](crate::mtls::CertReloader)), and obtained from an ACME CA
+/// when `mtls.acme` is enabled.
 pub(super) async fn serve_tls(
     app: axum::Router,
     addr: SocketAddr,
     mtls_config: &crate::mtls::MtlsConfig,
+    reload_shutdown_rx: tokio::sync::broadcast::Receiver<()>,
     shutdown_fut: impl std::future::Future<Output = ()> + Send + 'static,
 ) -> crate::Result<()> {
-    use crate::mtls::cert_manager::build_tls_config;
+    use crate::mtls::{CertReloader, build_tls_config_with_crls, revocation};
 
-    let rustls_cfg = build_tls_config(mtls_config)?;
+    #[cfg(feature = "acme")]
+    if mtls_config.acme.enabled {
+        crate::mtls::acme::ensure_placeholder(mtls_config)?;
+    }
+    #[cfg(not(feature = "acme"))]
+    if mtls_config.acme.enabled {
+        return Err(crate::Error::Config(
+            "mtls.acme needs a gateway built with the `acme` feature".to_string(),
+        ));
+    }
+    #[cfg(feature = "acme")]
+    let acme_shutdown_rx = reload_shutdown_rx.resubscribe();
+
+    let crls =
+        revocation::initial_crls(&mtls_config.revocation, &revocation::http_client()).await?;
+    let rustls_cfg = build_tls_config_with_crls(mtls_config, &crls)?;
     let rustls_config = RustlsConfig::from_config(Arc::new(rustls_cfg));
+    let reloader = CertReloader::start(
+        mtls_config.clone(),
+        rustls_config.clone(),
+        crls,
+        reload_shutdown_rx,
+    )?;
+
+    #[cfg(feature = "acme")]
+    let acme_alpn = if mtls_config.acme.enabled {
+        let acme = crate::mtls::acme::AcmeManager::start(
+            mtls_config,
+            reloader.reload_trigger(),
+            acme_shutdown_rx,
+        )
+        .await?;
+        (mtls_config.acme.challenge == crate::mtls::config::AcmeChallenge::TlsAlpn01)
+            .then(|| (acme.alpn_challenges(), rustls_config.clone()))
+    } else {
+        None
+    };
 
     info!(
         addr = %addr,
         require_client_cert = mtls_config.require_client_cert,
+        watch_certs = mtls_config.watch_certs,
+        revocation = ?mtls_config.revocation.fail_mode,
         "mTLS listener starting"
     );
 
@@ -112,23 +155,46 @@ pub(super) async fn serve_tls(
     });
 
     let acceptor = PeerCertIdentityAcceptor::new(RustlsAcceptor::new(rustls_config));
+    #[cfg(feature = "acme")]
+    let acceptor = acceptor.with_acme_alpn(acme_alpn);
 
-    axum_server::bind(addr)
+    let served = axum_server::bind(addr)
         .acceptor(acceptor)
         .handle(handle)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .await
-        .map_err(|e| crate::Error::Tls(format!("TLS server error: {e}")))
+        .map_err(|e| crate::Error::Tls(format!("TLS server error: {e}")));
+    // The reloader's file watcher lives as long as the listener.
+    drop(reloader);
+    served
 }
 
 #[derive(Debug, Clone)]
 struct PeerCertIdentityAcceptor {
     inner: RustlsAcceptor,
+    /// TLS-ALPN-01 challenges and the listener config, when ACME answers
+    /// them on this listener.
+    #[cfg(feature = "acme")]
+    acme_alpn: Option<(crate::mtls::acme::AlpnChallenges, RustlsConfig)>,
 }
 
 impl PeerCertIdentityAcceptor {
     fn new(inner: RustlsAcceptor) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            #[cfg(feature = "acme")]
+            acme_alpn: None,
+        }
+    }
+
+    /// Answer TLS-ALPN-01 challenges on this listener.
+    #[cfg(feature = "acme")]
+    fn with_acme_alpn(
+        mut self,
+        acme_alpn: Option<(crate::mtls::acme::AlpnChallenges, RustlsConfig)>,
+    ) -> Self {
+        self.acme_alpn = acme_alpn;
+        self
     }
 }
 
@@ -143,8 +209,19 @@ where
 
     fn accept(&self, stream: I, service: S) -> Self::Future {
         let acceptor = self.inner.clone();
+        #[cfg(feature = "acme")]
+        let acme_alpn = self.acme_alpn.clone();
 
         Box::pin(async move {
+            #[cfg(feature = "acme")]
+            let (stream, service) = match acme_alpn {
+                Some((challenges, tls)) => (
+                    crate::mtls::acme::accept(stream, &challenges, &tls).await?,
+                    service,
+                ),
+                None => acceptor.accept(stream, service).await?,
+            };
+            #[cfg(not(feature = "acme"))]
             let (stream, service) = acceptor.accept(stream, service).await?;
             let identity = client_identity_from_peer_chain(stream.get_ref().1.peer_certificates())?;
             let service = PeerCertIdentityLayer::new(identity).layer(service);
@@ -217,9 +294,9 @@ where
 
 /// Shutdown signal handler.
 ///
-/// Resolves on Ctrl+C (all platforms) or SIGTERM (Unix only), then broadcasts
-/// the shutdown signal to all subscriber tasks.
-pub(super) async fn shutdown_signal(shutdown_tx: tokio::sync::broadcast::Sender<()>) {
+/// Resolves on Ctrl+C (all platforms), SIGTERM (Unix only) or a drain
+/// requested through `POST /api/drain`, and marks the gateway as draining.
+pub(super) async fn shutdown_signal(drain: &DrainController) {
     let ctrl_c = async {
         signal::ctrl_c()
             .await
@@ -240,10 +317,11 @@ pub(super) async fn shutdown_signal(shutdown_tx: tokio::sync::broadcast::Sender<
     tokio::select! {
         () = ctrl_c => {},
         () = terminate => {},
+        () = drain.requested() => {},
     }
 
-    info!("Shutdown signal received");
-    let _ = shutdown_tx.send(());
+    info!("Shutdown signal received, draining");
+    drain.start();
 }
 
 /// Build a `PersistedCosts` snapshot from the current enforcer state.
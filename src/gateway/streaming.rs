@@ -26,6 +26,34 @@ use uuid::Uuid;
 use crate::Result;
 use crate::backend::BackendRegistry;
 use crate::config::StreamingConfig;
+use crate::protocol::ClientCapabilities;
+
+/// Event type that ends an SSE stream (sent by [`NotificationMultiplexer::close_streams`]).
+pub const SHUTDOWN_EVENT: &str = "shutdown";
+
+/// A server-to-client feature a client must declare at `initialize` before
+/// the gateway sends it the corresponding requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientFeature {
+    /// `sampling/createMessage`
+    Sampling,
+    /// `elicitation/create`
+    Elicitation,
+    /// `roots/list`
+    Roots,
+}
+
+impl ClientFeature {
+    /// Whether `capabilities` declares this feature.
+    #[must_use]
+    pub fn declared_by(self, capabilities: &ClientCapabilities) -> bool {
+        match self {
+            Self::Sampling => capabilities.sampling.is_some(),
+            Self::Elicitation => capabilities.elicitation.is_some(),
+            Self::Roots => capabilities.roots.is_some(),
+        }
+    }
+}
 
 /// A tagged notification event from a backend
 #[derive(Debug, Clone, Serialize)]
@@ -55,6 +83,21 @@ struct ClientSession {
     subscribed_backends: RwLock<Vec<String>>,
     /// Timestamp of session creation (for TTL-based reaping)
     created_at: Instant,
+    /// Capabilities declared at `initialize` (`None` until then)
+    capabilities: RwLock<Option<ClientCapabilities>>,
+    /// Protocol version negotiated at `initialize` (`None` until then)
+    protocol_version: RwLock<Option<String>>,
+}
+
+impl ClientSession {
+    /// Sessions that never initialized through this gateway (e.g. resumed
+    /// after a restart) are assumed to support everything, as before.
+    fn supports(&self, feature: ClientFeature) -> bool {
+        self.capabilities
+            .read()
+            .as_ref()
+            .is_none_or(|caps| feature.declared_by(caps))
+    }
 }
 
 /// Notification Multiplexer
@@ -165,6 +208,8 @@ impl NotificationMultiplexer {
             last_event_id: RwLock::new(None),
             subscribed_backends: RwLock::new(Vec::new()),
             created_at: Instant::now(),
+            capabilities: RwLock::new(None),
+            protocol_version: RwLock::new(None),
         });
 
         sessions.insert(id.clone(), session);
@@ -191,6 +236,36 @@ impl NotificationMultiplexer {
         self.sessions.read().len()
     }
 
+    /// Record the capabilities `session_id` declared at `initialize`.
+    pub fn set_client_capabilities(&self, session_id: &str, capabilities: ClientCapabilities) {
+        if let Some(session) = self.sessions.read().get(session_id) {
+            *session.capabilities.write() = Some(capabilities);
+        }
+    }
+
+    /// Record the protocol version negotiated with `session_id`.
+    pub fn set_protocol_version(&self, session_id: &str, version: &str) {
+        if let Some(session) = self.sessions.read().get(session_id) {
+            *session.protocol_version.write() = Some(version.to_string());
+        }
+    }
+
+    /// Protocol version negotiated with `session_id`, if it initialized here.
+    pub fn protocol_version(&self, session_id: &str) -> Option<String> {
+        self.sessions
+            .read()
+            .get(session_id)
+            .and_then(|session| session.protocol_version.read().clone())
+    }
+
+    /// Whether `session_id` exists and can handle `feature`.
+    pub fn session_supports(&self, session_id: &str, feature: ClientFeature) -> bool {
+        self.sessions
+            .read()
+            .get(session_id)
+            .is_some_and(|session| session.supports(feature))
+    }
+
     /// Send a notification to a specific session
     pub fn send_to_session(&self, session_id: &str, notification: TaggedNotification) -> bool {
         let sessions = self.sessions.read();
@@ -216,6 +291,49 @@ impl NotificationMultiplexer {
         }
     }
 
+    /// Broadcast a notification to the sessions that can handle `feature`.
+    ///
+    /// Returns the number of sessions it was sent to.
+    #[allow(clippy::needless_pass_by_value)] // mirrors `broadcast`
+    pub fn broadcast_to_capable(
+        &self,
+        feature: ClientFeature,
+        notification: TaggedNotification,
+    ) -> usize {
+        let sessions = self.sessions.read();
+        sessions
+            .values()
+            .filter(|session| session.supports(feature))
+            .filter(|session| session.tx.send(notification.clone()).is_ok())
+            .count()
+    }
+
+    /// End every open SSE stream with a [`SHUTDOWN_EVENT`] telling the client
+    /// to reconnect after `retry` and resume its session.
+    ///
+    /// Returns the number of streams notified.
+    pub fn close_streams(&self, retry: Duration) -> usize {
+        let sessions = self.sessions.read();
+        let retry_ms = u64::try_from(retry.as_millis()).unwrap_or(u64::MAX);
+        let mut notified = 0;
+        for (session_id, session) in sessions.iter() {
+            let notification = TaggedNotification {
+                source: "gateway".to_string(),
+                event_type: SHUTDOWN_EVENT.to_string(),
+                data: json!({
+                    "reason": "draining",
+                    "session_id": session_id,
+                    "retry_ms": retry_ms,
+                }),
+                event_id: Some(self.next_event_id()),
+            };
+            if session.tx.send(notification).is_ok() {
+                notified += 1;
+            }
+        }
+        notified
+    }
+
     /// Generate a unique event ID
     pub fn next_event_id(&self) -> String {
         let id = self
@@ -330,6 +448,13 @@ pub fn create_sse_response(
                         event
                     };
 
+                    // Gateway draining: pass on the reconnect hint and end the stream
+                    if notification.event_type == SHUTDOWN_EVENT {
+                        let retry_ms = notification.data["retry_ms"].as_u64().unwrap_or(0);
+                        yield Ok(event.retry(Duration::from_millis(retry_ms)));
+                        break;
+                    }
+
                     yield Ok(event);
                 }
                 Err(broadcast::error::RecvError::Closed) => {
@@ -352,6 +477,31 @@ pub fn create_sse_response(
 mod tests {
     use super::*;
 
+    #[test]
+    fn session_supports_only_declared_features_once_initialized() {
+        let multiplexer = NotificationMultiplexer::new(
+            Arc::new(BackendRegistry::new()),
+            StreamingConfig::default(),
+        );
+        let (session_id, _rx) = multiplexer.get_or_create_session(Some("caps"));
+
+        // GIVEN: no initialize seen yet — everything is assumed supported
+        assert!(multiplexer.session_supports(&session_id, ClientFeature::Elicitation));
+
+        // WHEN: the client declares only roots
+        let caps = ClientCapabilities {
+            roots: Some(crate::protocol::RootsCapability::default()),
+            ..Default::default()
+        };
+        multiplexer.set_client_capabilities(&session_id, caps);
+
+        // THEN: only roots is supported, and unknown sessions support nothing
+        assert!(multiplexer.session_supports(&session_id, ClientFeature::Roots));
+        assert!(!multiplexer.session_supports(&session_id, ClientFeature::Sampling));
+        assert!(!multiplexer.session_supports(&session_id, ClientFeature::Elicitation));
+        assert!(!multiplexer.session_supports("missing", ClientFeature::Roots));
+    }
+
     #[tokio::test]
     async fn test_session_creation() {
         let backends = Arc::new(BackendRegistry::new());
@@ -414,6 +564,26 @@ mod tests {
         assert_eq!(r2.source, "global");
     }
 
+    #[tokio::test]
+    async fn close_streams_sends_resumable_shutdown_event() {
+        // GIVEN: two open sessions
+        let backends = Arc::new(BackendRegistry::new());
+        let multiplexer = NotificationMultiplexer::new(backends, StreamingConfig::default());
+        let (_id, mut rx) = multiplexer.get_or_create_session(Some("session-1"));
+        let (_id2, _rx2) = multiplexer.get_or_create_session(Some("session-2"));
+
+        // WHEN: the gateway drains
+        let closed = multiplexer.close_streams(Duration::from_secs(2));
+
+        // THEN: every stream gets a shutdown event with the resume details
+        assert_eq!(closed, 2);
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.event_type, SHUTDOWN_EVENT);
+        assert_eq!(event.data["session_id"], "session-1");
+        assert_eq!(event.data["retry_ms"], 2000);
+        assert!(event.event_id.is_some());
+    }
+
     // ── Session reaper tests ─────────────────────────────────────────────
 
     /// GIVEN a session with no active receivers and an elapsed TTL
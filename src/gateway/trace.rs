@@ -15,6 +15,15 @@
 //! slot.  Call [`with_trace_id`] to scope a future to a particular ID, and
 //! [`current`] to read it from anywhere in the call stack.
 //!
+//! # W3C Trace Context
+//!
+//! A request carrying a `traceparent` header (and optionally `tracestate`)
+//! joins the caller's trace: [`trace_context_middleware`] parses the headers
+//! into a [`TraceContext`], records it on a request span and installs it in
+//! [`TRACE_CONTEXT`]. HTTP backends then receive a `traceparent` with the same
+//! trace ID and a fresh span ID for the gateway hop, plus the untouched
+//! `tracestate`, next to `X-Trace-Id`. Malformed headers are ignored.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -27,13 +36,71 @@
 //! }).await;
 //! ```
 
+use axum::{extract::Request, http::HeaderMap, middleware::Next, response::Response};
+use tracing::Instrument;
 use uuid::Uuid;
 
+use crate::tracing_context::{SpanId, TraceParent};
+
 tokio::task_local! {
     /// Task-local storage for the current request trace ID.
     ///
     /// Set by [`with_trace_id`]; read by [`current`].
     pub static TRACE_ID: String;
+
+    /// Task-local W3C trace context of the inbound request.
+    ///
+    /// Set by [`with_context`]; read by [`current_context`].
+    pub static TRACE_CONTEXT: TraceContext;
+}
+
+/// Longest `tracestate` value forwarded (the W3C limit is 32 members of at
+/// most 256 characters each).
+const MAX_TRACESTATE_LEN: usize = 8192;
+
+/// W3C trace context received from the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// Parsed `traceparent` header.
+    pub parent: TraceParent,
+    /// Raw `tracestate` header, forwarded unchanged.
+    pub state: Option<String>,
+}
+
+impl TraceContext {
+    /// Parse the `traceparent` / `tracestate` headers of a request.
+    ///
+    /// Returns `None` when `traceparent` is missing or malformed; a
+    /// `tracestate` without a valid `traceparent` is discarded, as the spec
+    /// requires.
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let parent = headers
+            .get("traceparent")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| TraceParent::parse(v.trim()))?;
+        let state = headers
+            .get_all("tracestate")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>()
+            .join(",");
+        let state = (!state.is_empty() && state.len() <= MAX_TRACESTATE_LEN).then_some(state);
+        Some(Self { parent, state })
+    }
+
+    /// `traceparent` for an outbound call: the caller's trace ID and flags
+    /// with a new span ID for the gateway hop.
+    #[must_use]
+    pub fn outbound_traceparent(&self) -> String {
+        TraceParent {
+            parent_span_id: SpanId::generate(),
+            ..self.parent.clone()
+        }
+        .to_header_value()
+    }
 }
 
 /// Generate a new gateway trace ID: `"gw-<uuid-v4>"`.
@@ -68,8 +135,61 @@ where
     TRACE_ID.scope(trace_id, future).await
 }
 
+/// Return the W3C trace context of the current request, if the caller sent
+/// one.
+#[must_use]
+pub fn current_context() -> Option<TraceContext> {
+    TRACE_CONTEXT.try_with(Clone::clone).ok()
+}
+
+/// Run `future` with `context` installed as the task-local trace context.
+pub async fn with_context<F, T>(context: TraceContext, future: F) -> T
+where
+    F: std::future::Future<Output = T>,
+{
+    TRACE_CONTEXT.scope(context, future).await
+}
+
+/// Add `traceparent` / `tracestate` for the current request's trace context
+/// to outbound `headers`. No-op outside a traced request.
+pub fn inject_context(headers: &mut reqwest::header::HeaderMap) {
+    let Some(context) = current_context() else {
+        return;
+    };
+    if let Ok(v) = context.outbound_traceparent().parse() {
+        headers.insert("traceparent", v);
+    }
+    if let Some(state) = context.state
+        && let Ok(v) = state.parse()
+    {
+        headers.insert("tracestate", v);
+    }
+}
+
+/// Axum middleware that joins the caller's W3C trace.
+///
+/// When the request carries a valid `traceparent`, the rest of the stack
+/// runs inside a `trace_context` span recording the caller's trace and span
+/// IDs, with the context installed for [`inject_context`].
+pub async fn trace_context_middleware(request: Request, next: Next) -> Response {
+    let Some(context) = TraceContext::from_headers(request.headers()) else {
+        return next.run(request).await;
+    };
+    let span = tracing::info_span!(
+        "trace_context",
+        trace_id = %context.parent.trace_id,
+        parent_span_id = %context.parent.parent_span_id,
+        sampled = context.parent.is_sampled(),
+    );
+    with_context(context, next.run(request))
+        .instrument(span)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
+    use axum::http::HeaderValue;
+
     use super::*;
 
     // ── generate ──────────────────────────────────────────────────────────
@@ -135,6 +255,74 @@ mod tests {
         assert_eq!(result.1, Some(inner));
     }
 
+    // ── W3C trace context ─────────────────────────────────────────────────
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn context_parses_traceparent_and_tracestate() {
+        // GIVEN: both W3C headers, tracestate split across two lines
+        let map = headers(&[
+            ("traceparent", TRACEPARENT),
+            ("tracestate", "vendor=a"),
+            ("tracestate", "other=b"),
+        ]);
+        // WHEN: parsed
+        let ctx = TraceContext::from_headers(&map).expect("valid traceparent");
+        // THEN: the lines are joined in order
+        assert_eq!(ctx.parent.to_header_value(), TRACEPARENT);
+        assert_eq!(ctx.state.as_deref(), Some("vendor=a,other=b"));
+    }
+
+    #[test]
+    fn context_ignores_malformed_traceparent() {
+        let map = headers(&[("traceparent", "garbage"), ("tracestate", "vendor=a")]);
+        assert_eq!(TraceContext::from_headers(&map), None);
+        assert_eq!(TraceContext::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn outbound_traceparent_keeps_trace_and_flags_with_new_span() {
+        let ctx = TraceContext::from_headers(&headers(&[("traceparent", TRACEPARENT)])).unwrap();
+
+        let outbound = TraceParent::parse(&ctx.outbound_traceparent()).unwrap();
+
+        assert_eq!(outbound.trace_id, ctx.parent.trace_id);
+        assert_eq!(outbound.flags, ctx.parent.flags);
+        assert_ne!(outbound.parent_span_id, ctx.parent.parent_span_id);
+    }
+
+    #[tokio::test]
+    async fn inject_context_only_inside_scope() {
+        // GIVEN: a context with tracestate
+        let map = headers(&[("traceparent", TRACEPARENT), ("tracestate", "vendor=a")]);
+        let ctx = TraceContext::from_headers(&map).unwrap();
+
+        // WHEN: injecting inside and outside the scope
+        let mut inside = reqwest::header::HeaderMap::new();
+        with_context(ctx, async { inject_context(&mut inside) }).await;
+        let mut outside = reqwest::header::HeaderMap::new();
+        inject_context(&mut outside);
+
+        // THEN: only the scoped call adds the headers
+        assert!(
+            inside["traceparent"]
+                .to_str()
+                .unwrap()
+                .starts_with("00-4bf92f35")
+        );
+        assert_eq!(inside["tracestate"], "vendor=a");
+        assert!(outside.is_empty());
+    }
+
     #[tokio::test]
     async fn current_returns_none_after_scope_exits() {
         // GIVEN: a scope that completes
@@ -1343,6 +1343,7 @@ mod role_wiring_tests {
             name: None,
             groups: groups.iter().map(|g| (*g).to_string()).collect(),
             issuer: issuer.to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
@@ -111,6 +111,9 @@ pub struct WebhookEndpointInfo {
     pub signature_required: bool,
     /// Whether notifications are broadcast to SSE clients
     pub notify: bool,
+    /// JSON Schema of the notification data, when the webhook declares one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
     /// Current delivery statistics
     pub stats: EndpointStatsSnapshot,
 }
@@ -190,6 +193,7 @@ impl WebhookRegistry {
                 webhook_name: name.clone(),
                 signature_required: def.secret.is_some(),
                 notify: def.notify,
+                schema: def.schema.clone(),
                 stats: stats.snapshot(),
             })
             .collect();
@@ -31,6 +31,7 @@ fn make_definition(notify: bool) -> WebhookDefinition {
         signature_header: None,
         notify,
         transform: WebhookTransform::default(),
+        schema: None,
     }
 }
 
@@ -213,7 +214,7 @@ fn make_capability_with_webhooks(
 ) -> crate::capability::CapabilityDefinition {
     use crate::capability::{
         AuthConfig, CacheConfig, CapabilityDefinition, CapabilityMetadata, ProvidersConfig,
-        SchemaDefinition, WebhookDefinition, WebhookTransform,
+        ResilienceConfig, SchemaDefinition, WebhookDefinition, WebhookTransform,
     };
     use crate::transform::TransformConfig;
     use std::collections::HashMap;
@@ -229,6 +230,7 @@ fn make_capability_with_webhooks(
                 signature_header: None,
                 notify: *notify,
                 transform: WebhookTransform::default(),
+                schema: None,
             },
         );
     }
@@ -246,6 +248,10 @@ fn make_capability_with_webhooks(
         response_transform: TransformConfig::default(),
         projection: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
         webhooks,
         sha256: None,
     }
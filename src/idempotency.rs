@@ -14,13 +14,21 @@
 //!    - `InFlight` and not timed-out → return `Err(Error::DuplicateRequest)`.
 //!    - `Completed` → return cached result immediately (no re-execution).
 //! 4. A background task periodically evicts stale entries to bound memory usage.
+//!
+//! With an [`IdempotencyStore`] attached, entries are also written to disk or
+//! the cluster state, so suppression survives restarts and is shared by
+//! replicas using the same store.
+
+mod store;
+
+pub use store::IdempotencyStore;
 
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
 use serde_json::Value;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::hashing::{canonical_json, sha256_hex_chunks};
 use crate::{Error, Result};
@@ -36,6 +44,25 @@ pub const COMPLETED_TTL: Duration = Duration::from_secs(24 * 60 * 60);
 /// is treated as stale and a new execution is allowed.
 pub const IN_FLIGHT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
 
+/// Expiry windows for idempotency entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdempotencyTtls {
+    /// How long a completed result is replayed (default [`COMPLETED_TTL`]).
+    pub completed: Duration,
+    /// How long an in-flight marker blocks duplicates (default
+    /// [`IN_FLIGHT_TIMEOUT`]).
+    pub in_flight: Duration,
+}
+
+impl Default for IdempotencyTtls {
+    fn default() -> Self {
+        Self {
+            completed: COMPLETED_TTL,
+            in_flight: IN_FLIGHT_TIMEOUT,
+        }
+    }
+}
+
 // ── State machine ─────────────────────────────────────────────────────────────
 
 /// State of an idempotency entry.
@@ -51,9 +78,15 @@ impl IdempotencyState {
     /// Return `true` when this entry is stale and should be evicted.
     #[must_use]
     pub fn is_expired(&self) -> bool {
+        self.is_expired_with(IdempotencyTtls::default())
+    }
+
+    /// Return `true` when this entry is stale under `ttls`.
+    #[must_use]
+    pub fn is_expired_with(&self, ttls: IdempotencyTtls) -> bool {
         match self {
-            Self::InFlight(started) => started.elapsed() > IN_FLIGHT_TIMEOUT,
-            Self::Completed(_, stored) => stored.elapsed() > COMPLETED_TTL,
+            Self::InFlight(started) => started.elapsed() > ttls.in_flight,
+            Self::Completed(_, stored) => stored.elapsed() > ttls.completed,
         }
     }
 
@@ -92,6 +125,9 @@ impl IdempotencyState {
 #[derive(Debug, Default)]
 pub struct IdempotencyCache {
     entries: DashMap<String, IdempotencyState>,
+    store: Option<IdempotencyStore>,
+    ttls: IdempotencyTtls,
+    policy: IdempotencyPolicy,
 }
 
 /// Outcome of checking the idempotency cache before executing a tool call.
@@ -139,15 +175,51 @@ impl IdempotencyCache {
     pub fn new() -> Self {
         Self {
             entries: DashMap::new(),
+            store: None,
+            ttls: IdempotencyTtls::default(),
+            policy: IdempotencyPolicy::default(),
         }
     }
 
+    /// Use `ttls` instead of the 24h / 5min defaults.
+    #[must_use]
+    pub fn with_ttls(mut self, ttls: IdempotencyTtls) -> Self {
+        self.ttls = ttls;
+        self
+    }
+
+    /// Decide per tool whether calls are checked (see [`IdempotencyPolicy`]).
+    #[must_use]
+    pub fn with_policy(mut self, policy: IdempotencyPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Which calls are checked.
+    #[must_use]
+    pub fn policy(&self) -> &IdempotencyPolicy {
+        &self.policy
+    }
+
+    /// Persist entries in `store` in addition to memory.
+    ///
+    /// Store I/O failures are logged and the cache keeps working from memory.
+    #[must_use]
+    pub fn with_store(mut self, store: IdempotencyStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
     /// Check the cache state for `key` and return what the caller should do.
     ///
-    /// Stale in-flight entries (exceeded [`IN_FLIGHT_TIMEOUT`]) are evicted and
-    /// treated as `Proceed` so a fresh execution can start.
+    /// Stale in-flight entries (exceeded the in-flight timeout) are evicted and
+    /// treated as `Proceed` so a fresh execution can start. Keys unknown in
+    /// memory are looked up in the store, if any.
     pub fn check(&self, key: &str) -> CheckOutcome {
         let Some(entry) = self.entries.get(key) else {
+            if let Some(outcome) = self.check_store(key) {
+                return outcome;
+            }
             let (plan, evict) = decide_check_plan(CacheEntryStatus::Missing);
             debug_assert!(!evict);
             return match plan {
@@ -158,11 +230,11 @@ impl IdempotencyCache {
         };
 
         let status = match entry.value() {
-            IdempotencyState::InFlight(started) if started.elapsed() <= IN_FLIGHT_TIMEOUT => {
+            IdempotencyState::InFlight(started) if started.elapsed() <= self.ttls.in_flight => {
                 CacheEntryStatus::LiveInFlight
             }
             IdempotencyState::InFlight(_) => CacheEntryStatus::StaleInFlight,
-            IdempotencyState::Completed(_, stored) if stored.elapsed() <= COMPLETED_TTL => {
+            IdempotencyState::Completed(_, stored) if stored.elapsed() <= self.ttls.completed => {
                 CacheEntryStatus::LiveCompleted
             }
             IdempotencyState::Completed(_, _) => CacheEntryStatus::ExpiredCompleted,
@@ -188,14 +260,61 @@ impl IdempotencyCache {
         }
     }
 
+    /// Live store entry for `key`. Completed entries are copied into memory;
+    /// in-flight ones are not, because their owner may clear them.
+    fn check_store(&self, key: &str) -> Option<CheckOutcome> {
+        match self.store.as_ref()?.load(key, self.ttls)? {
+            IdempotencyState::InFlight(_) => Some(CheckOutcome::InFlight),
+            IdempotencyState::Completed(value, stored) => {
+                self.entries.insert(
+                    key.to_string(),
+                    IdempotencyState::Completed(value.clone(), stored),
+                );
+                Some(CheckOutcome::Completed(value))
+            }
+        }
+    }
+
+    /// Register `key` as in-flight unless another process claimed it first.
+    ///
+    /// Without a store this is [`mark_in_flight`](Self::mark_in_flight) and
+    /// always returns [`CheckOutcome::Proceed`]. With a store the claim is
+    /// atomic across processes sharing it; a live entry found there is
+    /// returned instead.
+    pub fn claim(&self, key: &str) -> CheckOutcome {
+        if let Some(ref store) = self.store {
+            match store.claim(key, self.ttls) {
+                Ok(None) => {}
+                Ok(Some(IdempotencyState::InFlight(_))) => return CheckOutcome::InFlight,
+                Ok(Some(IdempotencyState::Completed(value, _))) => {
+                    return CheckOutcome::Completed(value);
+                }
+                Err(e) => warn!(error = %e, "Idempotency store claim failed; using memory only"),
+            }
+        }
+        self.entries
+            .insert(key.to_string(), IdempotencyState::InFlight(Instant::now()));
+        CheckOutcome::Proceed
+    }
+
     /// Register `key` as in-flight.  Overwrites any stale entry.
     pub fn mark_in_flight(&self, key: &str) {
         self.entries
             .insert(key.to_string(), IdempotencyState::InFlight(Instant::now()));
+        if let Some(ref store) = self.store
+            && let Err(e) = store.mark_in_flight(key, self.ttls)
+        {
+            warn!(error = %e, "Failed to persist idempotency entry");
+        }
     }
 
     /// Transition `key` from in-flight to completed with `result`.
     pub fn mark_completed(&self, key: &str, result: Value) {
+        if let Some(ref store) = self.store
+            && let Err(e) = store.mark_completed(key, &result, self.ttls)
+        {
+            warn!(error = %e, "Failed to persist idempotency result");
+        }
         self.entries.insert(
             key.to_string(),
             IdempotencyState::Completed(result, Instant::now()),
@@ -205,6 +324,11 @@ impl IdempotencyCache {
     /// Remove `key` entirely (used when a call fails and should be retryable).
     pub fn remove(&self, key: &str) {
         self.entries.remove(key);
+        if let Some(ref store) = self.store
+            && let Err(e) = store.remove(key)
+        {
+            warn!(error = %e, "Failed to remove persisted idempotency entry");
+        }
     }
 
     /// Evict all stale entries.  Called by the background maintenance task.
@@ -212,7 +336,11 @@ impl IdempotencyCache {
         let stale: Vec<String> = self
             .entries
             .iter()
-            .filter_map(|e| e.value().is_expired().then(|| e.key().clone()))
+            .filter_map(|e| {
+                e.value()
+                    .is_expired_with(self.ttls)
+                    .then(|| e.key().clone())
+            })
             .collect();
 
         let count = stale.len();
@@ -222,6 +350,13 @@ impl IdempotencyCache {
         if count > 0 {
             debug!(count, "Evicted stale idempotency entries");
         }
+        if let Some(ref store) = self.store {
+            match store.evict_expired(self.ttls) {
+                Ok(0) => {}
+                Ok(count) => debug!(count, "Evicted stale persisted idempotency entries"),
+                Err(e) => warn!(error = %e, "Failed to evict persisted idempotency entries"),
+            }
+        }
     }
 
     /// Current number of tracked entries.
@@ -281,6 +416,78 @@ mod verification {
     }
 }
 
+// ── Per-tool policy ───────────────────────────────────────────────────────────
+
+/// How calls to one tool are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolIdempotency {
+    /// Check every call; derive a key when the client supplies none.
+    Always,
+    /// Never check, even with an explicit `idempotency_key`.
+    Never,
+    /// Check calls with an explicit key; derive one only when auto-derivation
+    /// is on and the tool is not annotated read-only or idempotent.
+    Auto,
+}
+
+/// Per-tool idempotency decisions.
+///
+/// Patterns are `server:tool` globs (`*`, `?`, case-insensitive); `never`
+/// wins when a tool matches both lists. Listed tools override the tool's
+/// read-only / idempotent annotations.
+#[derive(Debug, Clone)]
+pub struct IdempotencyPolicy {
+    /// Derive keys for unlisted tools without an explicit key.
+    pub auto_derive: bool,
+    /// Tools that are always checked.
+    pub always: Vec<String>,
+    /// Tools that are never checked.
+    pub never: Vec<String>,
+}
+
+impl Default for IdempotencyPolicy {
+    fn default() -> Self {
+        Self {
+            auto_derive: true,
+            always: Vec::new(),
+            never: Vec::new(),
+        }
+    }
+}
+
+impl IdempotencyPolicy {
+    /// Mode for `server:tool`.
+    #[must_use]
+    pub fn mode(&self, server: &str, tool: &str) -> ToolIdempotency {
+        let name = format!("{server}:{tool}");
+        let listed = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|p| crate::gateway::tool_name_matches_glob(&name, p))
+        };
+        if listed(&self.never) {
+            ToolIdempotency::Never
+        } else if listed(&self.always) {
+            ToolIdempotency::Always
+        } else {
+            ToolIdempotency::Auto
+        }
+    }
+
+    /// Whether a call without an explicit key gets a derived one.
+    ///
+    /// `repeat_safe` reports whether the tool is annotated read-only or
+    /// idempotent; it is only evaluated for unlisted tools.
+    #[must_use]
+    pub fn derives_key(&self, mode: ToolIdempotency, repeat_safe: impl FnOnce() -> bool) -> bool {
+        match mode {
+            ToolIdempotency::Always => true,
+            ToolIdempotency::Never => false,
+            ToolIdempotency::Auto => self.auto_derive && !repeat_safe(),
+        }
+    }
+}
+
 // ── Key generation ────────────────────────────────────────────────────────────
 
 /// Derive an idempotency key from `tool_name` and `arguments`.
@@ -317,11 +524,12 @@ pub enum GuardOutcome {
 /// Returns [`Error::DuplicateRequest`] (HTTP 409 equivalent) when an identical
 /// request is already in flight.
 pub fn enforce(cache: &IdempotencyCache, key: &str) -> Result<GuardOutcome> {
-    match cache.check(key) {
-        CheckOutcome::Proceed => {
-            cache.mark_in_flight(key);
-            Ok(GuardOutcome::Proceed)
-        }
+    let outcome = match cache.check(key) {
+        CheckOutcome::Proceed => cache.claim(key),
+        other => other,
+    };
+    match outcome {
+        CheckOutcome::Proceed => Ok(GuardOutcome::Proceed),
         CheckOutcome::InFlight => Err(Error::json_rpc(
             409,
             format!("Duplicate request in progress for key: {key}"),
@@ -680,4 +888,136 @@ mod tests {
 
         assert_eq!(cache.len(), 0, "stale entry should have been evicted");
     }
+
+    // ── persistent store ──────────────────────────────────────────────────────
+
+    fn stored_cache(dir: &std::path::Path) -> IdempotencyCache {
+        IdempotencyCache::new().with_store(IdempotencyStore::open(dir).unwrap())
+    }
+
+    #[test]
+    fn completed_result_survives_restart() {
+        // GIVEN: a completed call recorded by one cache instance
+        let dir = tempfile::tempdir().unwrap();
+        let before = stored_cache(dir.path());
+        assert!(matches!(enforce(&before, "k"), Ok(GuardOutcome::Proceed)));
+        before.mark_completed("k", json!({"sent": true}));
+        drop(before);
+
+        // WHEN: a fresh instance (a restarted gateway) sees the same key
+        let after = stored_cache(dir.path());
+
+        // THEN: the stored result is returned instead of re-executing
+        match enforce(&after, "k") {
+            Ok(GuardOutcome::CachedResult(v)) => assert_eq!(v, json!({"sent": true})),
+            other => panic!("expected cached result, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn in_flight_claim_is_shared_between_replicas() {
+        // GIVEN: two replicas sharing a store directory
+        let dir = tempfile::tempdir().unwrap();
+        let a = stored_cache(dir.path());
+        let b = stored_cache(dir.path());
+
+        // WHEN: replica A starts a call and replica B receives the retry
+        assert!(matches!(enforce(&a, "k"), Ok(GuardOutcome::Proceed)));
+
+        // THEN: B rejects it as a duplicate until A gives the key up
+        assert!(enforce(&b, "k").is_err());
+        a.remove("k");
+        assert!(matches!(enforce(&b, "k"), Ok(GuardOutcome::Proceed)));
+    }
+
+    #[test]
+    fn cluster_state_store_is_shared_between_replicas() {
+        // GIVEN: two replicas keeping entries in one cluster state
+        let state: Arc<dyn crate::cluster::SharedState> =
+            Arc::new(crate::cluster::MemoryState::new());
+        let a = IdempotencyCache::new().with_store(IdempotencyStore::shared(Arc::clone(&state)));
+        let b = IdempotencyCache::new().with_store(IdempotencyStore::shared(state));
+
+        // WHEN: replica A claims the key, B rejects the retry
+        assert!(matches!(enforce(&a, "k"), Ok(GuardOutcome::Proceed)));
+        assert!(enforce(&b, "k").is_err());
+
+        // THEN: once A completes, B replays the result
+        a.mark_completed("k", json!({"sent": true}));
+        match enforce(&b, "k") {
+            Ok(GuardOutcome::CachedResult(v)) => assert_eq!(v, json!({"sent": true})),
+            other => panic!("expected cached result, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn store_evicts_expired_and_unreadable_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = stored_cache(dir.path());
+        cache.mark_completed("live", json!(1));
+        std::fs::write(dir.path().join("garbage.json"), "not json").unwrap();
+        let expired = serde_json::json!({"state": "completed", "at_ms": 0, "result": null});
+        std::fs::write(dir.path().join("old.json"), expired.to_string()).unwrap();
+
+        cache.evict_expired();
+
+        assert!(!dir.path().join("garbage.json").exists());
+        assert!(!dir.path().join("old.json").exists());
+        assert!(matches!(
+            stored_cache(dir.path()).check("live"),
+            CheckOutcome::Completed(_)
+        ));
+    }
+
+    // ── TTLs and per-tool policy ──────────────────────────────────────────────
+
+    #[test]
+    fn configured_completed_ttl_expires_results_early() {
+        // GIVEN: a cache replaying results for one minute only
+        let cache = IdempotencyCache::new().with_ttls(IdempotencyTtls {
+            completed: Duration::from_secs(60),
+            ..IdempotencyTtls::default()
+        });
+        cache.entries.insert(
+            "k".to_string(),
+            IdempotencyState::Completed(
+                json!("old"),
+                Instant::now()
+                    .checked_sub(Duration::from_secs(120))
+                    .unwrap(),
+            ),
+        );
+
+        // WHEN / THEN: a two-minute-old result no longer suppresses the call
+        assert!(matches!(cache.check("k"), CheckOutcome::Proceed));
+    }
+
+    #[test]
+    fn policy_never_wins_over_always() {
+        let policy = IdempotencyPolicy {
+            always: vec!["payments:*".to_string()],
+            never: vec!["payments:quote".to_string()],
+            ..IdempotencyPolicy::default()
+        };
+        assert_eq!(policy.mode("payments", "charge"), ToolIdempotency::Always);
+        assert_eq!(policy.mode("Payments", "QUOTE"), ToolIdempotency::Never);
+        assert_eq!(policy.mode("dice", "roll"), ToolIdempotency::Auto);
+    }
+
+    #[test]
+    fn policy_derives_keys_for_unlisted_tools_unless_repeat_safe() {
+        // GIVEN: the default policy and one with auto-derivation off
+        let auto = IdempotencyPolicy::default();
+        let manual = IdempotencyPolicy {
+            auto_derive: false,
+            ..IdempotencyPolicy::default()
+        };
+
+        // THEN: annotations exempt unlisted tools; listing overrides them
+        assert!(auto.derives_key(ToolIdempotency::Auto, || false));
+        assert!(!auto.derives_key(ToolIdempotency::Auto, || true));
+        assert!(!manual.derives_key(ToolIdempotency::Auto, || false));
+        assert!(manual.derives_key(ToolIdempotency::Always, || true));
+        assert!(!auto.derives_key(ToolIdempotency::Never, || false));
+    }
 }
@@ -0,0 +1,267 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Idempotency entries shared across restarts and replicas.
+//!
+//! Entries live either in a directory or in the cluster state (see
+//! [`crate::cluster`]). In a directory each key is one JSON file named by
+//! the SHA-256 of the key (keys may carry arbitrary client-supplied text).
+//! Completed entries are immutable, so any process can read them without
+//! coordination; claiming a key as in-flight takes a directory-wide
+//! [`ExclusiveFileLock`] so two replicas pointing at the same directory
+//! cannot both start the same call. In the cluster state the same JSON is
+//! stored under `idempotency:{sha256(key)}`, claimed with an atomic
+//! set-if-absent and expired by the store itself.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use super::{IdempotencyState, IdempotencyTtls};
+use crate::cluster::SharedState;
+use crate::fs_lock::ExclusiveFileLock;
+use crate::hashing::sha256_hex;
+
+/// Persisted entry; timestamps are wall-clock milliseconds since the epoch
+/// because `Instant`s do not survive a restart.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum StoredEntry {
+    InFlight { at_ms: u64 },
+    Completed { at_ms: u64, result: Value },
+}
+
+impl StoredEntry {
+    /// Convert to an in-memory state, or `None` once expired.
+    fn into_state(self, now_ms: u64, ttls: IdempotencyTtls) -> Option<IdempotencyState> {
+        let (at_ms, ttl) = match &self {
+            Self::InFlight { at_ms } => (*at_ms, ttls.in_flight),
+            Self::Completed { at_ms, .. } => (*at_ms, ttls.completed),
+        };
+        let age = Duration::from_millis(now_ms.saturating_sub(at_ms));
+        if age > ttl {
+            return None;
+        }
+        // An entry older than this process' monotonic clock origin is
+        // treated as fresh; it still expires within one TTL from now.
+        let at = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+        Some(match self {
+            Self::InFlight { .. } => IdempotencyState::InFlight(at),
+            Self::Completed { result, .. } => IdempotencyState::Completed(result, at),
+        })
+    }
+}
+
+/// Persistent idempotency entries, in a directory or the cluster state.
+#[derive(Debug)]
+pub struct IdempotencyStore {
+    backing: Backing,
+}
+
+#[derive(Debug)]
+enum Backing {
+    Dir(PathBuf),
+    Shared(Arc<dyn SharedState>),
+}
+
+impl IdempotencyStore {
+    /// Open (creating if needed) the store directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created.
+    pub fn open(dir: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            backing: Backing::Dir(dir.to_path_buf()),
+        })
+    }
+
+    /// Keep entries in the cluster state shared by all replicas.
+    #[must_use]
+    pub fn shared(state: Arc<dyn SharedState>) -> Self {
+        Self {
+            backing: Backing::Shared(state),
+        }
+    }
+
+    /// Directory holding the entries (`None` in the cluster state).
+    #[must_use]
+    pub fn dir(&self) -> Option<&Path> {
+        match &self.backing {
+            Backing::Dir(dir) => Some(dir),
+            Backing::Shared(_) => None,
+        }
+    }
+
+    fn path(dir: &Path, key: &str) -> PathBuf {
+        dir.join(format!("{}.json", sha256_hex(key.as_bytes())))
+    }
+
+    fn shared_key(key: &str) -> String {
+        format!("idempotency:{}", sha256_hex(key.as_bytes()))
+    }
+
+    /// Live entry for `key`, if any.
+    pub(super) fn load(&self, key: &str, ttls: IdempotencyTtls) -> Option<IdempotencyState> {
+        let entry = match &self.backing {
+            Backing::Dir(dir) => read_entry(&Self::path(dir, key))?,
+            Backing::Shared(state) => match state.get(&Self::shared_key(key)) {
+                Ok(json) => parse_entry(&json?)?,
+                Err(e) => {
+                    debug!(error = %e, "Shared idempotency entry unavailable");
+                    return None;
+                }
+            },
+        };
+        entry.into_state(now_ms(), ttls)
+    }
+
+    /// Register `key` as in-flight unless a live entry already exists, in
+    /// which case that entry is returned and nothing is written.
+    pub(super) fn claim(
+        &self,
+        key: &str,
+        ttls: IdempotencyTtls,
+    ) -> std::io::Result<Option<IdempotencyState>> {
+        let in_flight = StoredEntry::InFlight { at_ms: now_ms() };
+        match &self.backing {
+            Backing::Dir(dir) => {
+                let _lock = ExclusiveFileLock::acquire(&dir.join(".claim.lock"))?;
+                let path = Self::path(dir, key);
+                if let Some(existing) = read_entry(&path).and_then(|e| e.into_state(now_ms(), ttls))
+                {
+                    return Ok(Some(existing));
+                }
+                write_entry(&path, &in_flight)?;
+            }
+            Backing::Shared(state) => {
+                let shared_key = Self::shared_key(key);
+                let existing =
+                    state.put_if_absent(&shared_key, &to_json(&in_flight)?, ttls.in_flight)?;
+                if let Some(existing) = existing {
+                    if let Some(live) =
+                        parse_entry(&existing).and_then(|e| e.into_state(now_ms(), ttls))
+                    {
+                        return Ok(Some(live));
+                    }
+                    // Unreadable, or written with a longer TTL than ours
+                    state.put(&shared_key, &to_json(&in_flight)?, ttls.in_flight)?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Register `key` as in-flight, replacing any existing entry.
+    pub(super) fn mark_in_flight(&self, key: &str, ttls: IdempotencyTtls) -> std::io::Result<()> {
+        self.write(
+            key,
+            &StoredEntry::InFlight { at_ms: now_ms() },
+            ttls.in_flight,
+        )
+    }
+
+    /// Store the completed `result` for `key`.
+    pub(super) fn mark_completed(
+        &self,
+        key: &str,
+        result: &Value,
+        ttls: IdempotencyTtls,
+    ) -> std::io::Result<()> {
+        let entry = StoredEntry::Completed {
+            at_ms: now_ms(),
+            result: result.clone(),
+        };
+        self.write(key, &entry, ttls.completed)
+    }
+
+    fn write(&self, key: &str, entry: &StoredEntry, ttl: Duration) -> std::io::Result<()> {
+        match &self.backing {
+            Backing::Dir(dir) => write_entry(&Self::path(dir, key), entry),
+            Backing::Shared(state) => state.put(&Self::shared_key(key), &to_json(entry)?, ttl),
+        }
+    }
+
+    /// Delete the entry for `key` (missing entries are not an error).
+    pub(super) fn remove(&self, key: &str) -> std::io::Result<()> {
+        match &self.backing {
+            Backing::Dir(dir) => match std::fs::remove_file(Self::path(dir, key)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+            Backing::Shared(state) => state.delete(&Self::shared_key(key)),
+        }
+    }
+
+    /// Delete expired and unreadable entries; returns how many were removed.
+    /// Entries in the cluster state expire by themselves.
+    pub(super) fn evict_expired(&self, ttls: IdempotencyTtls) -> std::io::Result<usize> {
+        let Backing::Dir(dir) = &self.backing else {
+            return Ok(0);
+        };
+        let now = now_ms();
+        let mut removed = 0;
+        for dir_entry in std::fs::read_dir(dir)? {
+            let path = dir_entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let live = read_entry(&path)
+                .and_then(|e| e.into_state(now, ttls))
+                .is_some();
+            if !live && std::fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+fn read_entry(path: &Path) -> Option<StoredEntry> {
+    let bytes = std::fs::read(path).ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(entry) => Some(entry),
+        Err(e) => {
+            debug!(path = %path.display(), error = %e, "Ignoring unreadable idempotency entry");
+            None
+        }
+    }
+}
+
+fn parse_entry(json: &str) -> Option<StoredEntry> {
+    serde_json::from_str(json)
+        .inspect_err(|e| debug!(error = %e, "Ignoring unreadable idempotency entry"))
+        .ok()
+}
+
+fn to_json(entry: &StoredEntry) -> std::io::Result<String> {
+    serde_json::to_string(entry)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Write-then-rename so readers never see a partial entry. Entries hold tool
+/// results, so files are owner-only on unix.
+fn write_entry(path: &Path, entry: &StoredEntry) -> std::io::Result<()> {
+    let bytes = serde_json::to_vec(entry)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    let mut opts = std::fs::OpenOptions::new();
+    opts.create(true).write(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        opts.mode(0o600);
+    }
+    std::io::Write::write_all(&mut opts.open(&tmp)?, &bytes)?;
+    std::fs::rename(&tmp, path)
+}
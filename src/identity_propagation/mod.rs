@@ -67,6 +67,32 @@ pub struct BackendDescriptor {
     pub token_exchange_endpoint: Option<String>,
     /// Optional RFC 8693 `scope` to request from the token-exchange endpoint.
     pub token_exchange_scope: Option<String>,
+    /// Registered client to authenticate as at the token-exchange endpoint.
+    /// `None` authenticates the gateway with `private_key_jwt`.
+    pub token_exchange_client: Option<TokenExchangeClient>,
+}
+
+/// OAuth client the gateway authenticates as at a backend's token-exchange
+/// endpoint (`client_secret_post`), for identity providers that only issue
+/// token-exchange rights to registered clients (Keycloak, Entra ID, Okta).
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenExchangeClient {
+    /// Client id registered at the identity provider.
+    pub client_id: String,
+    /// Client secret; may be a `{env.VAR}` or `{keychain.SERVICE}` reference,
+    /// resolved when the exchange runs.
+    pub client_secret: String,
+}
+
+// Manual `Debug` that redacts the client secret (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for TokenExchangeClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenExchangeClient")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"<redacted>")
+            .finish()
+    }
 }
 
 /// A per-user credential to present to a backend, plus the metadata caches and
@@ -227,6 +253,10 @@ pub struct IdentityPropagationConfig {
     /// Optional RFC 8693 `scope` requested from the token-exchange endpoint.
     #[serde(default)]
     pub token_exchange_scope: Option<String>,
+    /// Registered client to authenticate as at the token-exchange endpoint
+    /// instead of the gateway's own `private_key_jwt`.
+    #[serde(default)]
+    pub token_exchange_client: Option<TokenExchangeClient>,
 }
 
 impl IdentityPropagationConfig {
@@ -241,7 +271,8 @@ impl IdentityPropagationConfig {
     /// - the strategy is `token_exchange` and `token_exchange_endpoint` is
     ///   absent or empty (MIK-6729) — this is checked unconditionally, not
     ///   only when `required`, since a `token_exchange` entry with no
-    ///   endpoint can never mint anything and is never a valid config.
+    ///   endpoint can never mint anything and is never a valid config;
+    /// - `token_exchange_client` has an empty `client_id` or `client_secret`.
     ///
     /// Note IDP.7: a `required` backend is only accepted with an explicit
     /// [`SessionMode`]; there is no implicit shared-session default, so a
@@ -264,6 +295,16 @@ impl IdentityPropagationConfig {
                     .to_string(),
             ));
         }
+        if self
+            .token_exchange_client
+            .as_ref()
+            .is_some_and(|c| c.client_id.trim().is_empty() || c.client_secret.trim().is_empty())
+        {
+            return Err(PropagationError::Misconfigured(
+                "token_exchange_client requires a non-empty client_id and client_secret"
+                    .to_string(),
+            ));
+        }
         // Only signed-assertion, passthrough, and token-exchange are
         // implemented; a required backend configured for an unimplemented
         // strategy (vault) must fail closed, not silently run without
@@ -626,6 +667,7 @@ mod tests {
             name: None,
             groups: vec!["eng".to_string()],
             issuer: issuer.to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
@@ -737,6 +779,7 @@ mod tests {
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         };
         assert!(cfg.validate().is_err());
 
@@ -749,6 +792,7 @@ mod tests {
             session_mode: SessionMode::PerUser,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         };
         assert!(cfg.validate().is_err());
 
@@ -762,6 +806,7 @@ mod tests {
             session_mode: SessionMode::PerUser,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         };
         assert!(cfg.validate().is_err());
 
@@ -773,6 +818,7 @@ mod tests {
             session_mode: SessionMode::PerUser,
             token_exchange_endpoint: Some("https://idp.internal/token".to_string()),
             token_exchange_scope: Some("mail.read".to_string()),
+            token_exchange_client: None,
         };
         assert!(cfg.validate().is_ok());
 
@@ -784,6 +830,7 @@ mod tests {
             session_mode: SessionMode::Stateless,
             token_exchange_endpoint: None,
             token_exchange_scope: None,
+            token_exchange_client: None,
         };
         assert!(cfg.validate().is_ok());
     }
@@ -12,7 +12,9 @@
 //!    configured token-exchange endpoint, authenticating the gateway itself
 //!    to the endpoint via RFC 7523 `private_key_jwt` (a `client_assertion`
 //!    signed with the SAME gateway key — no shared `client_secret` ever
-//!    leaves the gateway process).
+//!    leaves the gateway process). A backend whose identity provider only
+//!    grants token exchange to registered clients configures a
+//!    [`TokenExchangeClient`] instead, sent as `client_secret_post`.
 //! 3. Parse the endpoint's `access_token` + `expires_in` and inject the
 //!    downstream token as the outbound `Authorization` header.
 //! 4. Cache the exchanged token in-memory, keyed by `(subject, audience,
@@ -50,10 +52,11 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     BackendDescriptor, IdentityPropagation, PropagatedCredential, PropagationError,
-    SignedAssertionStrategy, cache_binding, sign_es256_jwt,
+    SignedAssertionStrategy, TokenExchangeClient, cache_binding, sign_es256_jwt,
 };
 use crate::gateway::oauth::GatewayKeyPair;
 use crate::key_server::oidc::VerifiedIdentity;
+use crate::secrets::SecretResolver;
 
 /// RFC 8693 §2.1 grant type identifier.
 const GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
@@ -188,6 +191,9 @@ pub struct TokenExchangeStrategy {
     /// MIK-6729 review M1). IDP.6: entries past `expires_at` are treated as
     /// absent and re-exchanged, never served stale.
     cache: DashMap<String, CachedExchange>,
+    /// Resolves `{env.VAR}` / `{keychain.SERVICE}` client secrets of
+    /// [`TokenExchangeClient`]s, caching keychain lookups.
+    secrets: SecretResolver,
 }
 
 impl TokenExchangeStrategy {
@@ -219,6 +225,7 @@ impl TokenExchangeStrategy {
             key,
             http,
             cache: DashMap::new(),
+            secrets: SecretResolver::new(),
         }
     }
 
@@ -237,6 +244,35 @@ impl TokenExchangeStrategy {
         sign_es256_jwt(&self.key, &claims)
     }
 
+    /// Form fields authenticating the gateway at `endpoint`: the configured
+    /// client's id and secret, or a `private_key_jwt` client assertion.
+    fn client_authentication(
+        &self,
+        endpoint: &str,
+        client: Option<&TokenExchangeClient>,
+    ) -> Result<Vec<(&'static str, String)>, PropagationError> {
+        let Some(client) = client else {
+            return Ok(vec![
+                ("client_assertion_type", CLIENT_ASSERTION_TYPE.to_string()),
+                ("client_assertion", self.mint_client_assertion(endpoint)?),
+            ]);
+        };
+        let secret = self.secrets.resolve(&client.client_secret).map_err(|e| {
+            PropagationError::Misconfigured(format!(
+                "token_exchange_client secret unavailable: {e}"
+            ))
+        })?;
+        if secret.is_empty() {
+            return Err(PropagationError::Misconfigured(
+                "token_exchange_client secret resolved to an empty value".to_string(),
+            ));
+        }
+        Ok(vec![
+            ("client_id", client.client_id.clone()),
+            ("client_secret", secret),
+        ])
+    }
+
     /// Return a still-valid cached exchange for `binding`, if any.
     fn cached(&self, binding: &str) -> Option<PropagatedCredential> {
         let entry = self.cache.get(binding)?;
@@ -284,16 +320,14 @@ impl TokenExchangeStrategy {
         subject_token: &str,
         backend: &BackendDescriptor,
     ) -> Result<TokenExchangeResponseBody, PropagationError> {
-        let client_assertion = self.mint_client_assertion(endpoint)?;
         let mut form: Vec<(&str, String)> = vec![
             ("grant_type", GRANT_TYPE.to_string()),
             ("subject_token", subject_token.to_string()),
             ("subject_token_type", SUBJECT_TOKEN_TYPE.to_string()),
             ("resource", backend.audience.clone()),
             ("audience", backend.audience.clone()),
-            ("client_assertion_type", CLIENT_ASSERTION_TYPE.to_string()),
-            ("client_assertion", client_assertion),
         ];
+        form.extend(self.client_authentication(endpoint, backend.token_exchange_client.as_ref())?);
         if let Some(scope) = backend
             .token_exchange_scope
             .as_deref()
@@ -373,7 +407,17 @@ impl IdentityPropagation for TokenExchangeStrategy {
 
         let subject_key = identity.stable_actor_id();
         let scope = backend.token_exchange_scope.as_deref().unwrap_or("");
-        let binding = exchange_cache_key(&subject_key, &backend.audience, endpoint, scope);
+        // Tokens are issued to the authenticating client, so two backends
+        // that differ only in their client must not share cache entries.
+        let client_id = backend
+            .token_exchange_client
+            .as_ref()
+            .map_or(CLIENT_ID, |c| c.client_id.as_str());
+        let binding = format!(
+            "{}:{}:{client_id}",
+            exchange_cache_key(&subject_key, &backend.audience, endpoint, scope),
+            client_id.len()
+        );
 
         if let Some(mut cred) = self.cached(&binding) {
             cred.subject_key = subject_key;
@@ -439,6 +483,7 @@ mod tests {
             name: None,
             groups: vec!["eng".to_string()],
             issuer: "https://idp".to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
@@ -448,6 +493,7 @@ mod tests {
             audience: "https://mail.internal".to_string(),
             token_exchange_endpoint: endpoint.map(str::to_string),
             token_exchange_scope: Some("mail.read".to_string()),
+            token_exchange_client: None,
         }
     }
 
@@ -505,6 +551,72 @@ mod tests {
         server.abort();
     }
 
+    // A backend with a registered exchange client authenticates with
+    // `client_secret_post` instead of the gateway's `private_key_jwt`.
+    #[tokio::test]
+    async fn registered_client_authenticates_with_client_secret() {
+        use std::collections::HashMap;
+        use std::sync::Mutex;
+
+        use axum::{Form, Json, Router, extract::State, routing::post};
+        use tokio::net::TcpListener;
+
+        type Seen = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+        async fn token_handler(
+            State(seen): State<Seen>,
+            Form(form): Form<HashMap<String, String>>,
+        ) -> Json<serde_json::Value> {
+            seen.lock().unwrap().push(form);
+            Json(serde_json::json!({"access_token": "obo-token", "expires_in": 300}))
+        }
+
+        // GIVEN: an STS that records the forms it receives
+        let seen: Seen = Arc::default();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/token", post(token_handler))
+            .with_state(Arc::clone(&seen));
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        let s = TokenExchangeStrategy::with_http_client(
+            Arc::new(GatewayKeyPair::generate().expect("keygen")),
+            300,
+            reqwest::Client::new(),
+        );
+        let endpoint = format!("http://{addr}/token");
+        let mut mail = backend(Some(&endpoint));
+        mail.token_exchange_client = Some(TokenExchangeClient {
+            client_id: "mail-obo".to_string(),
+            client_secret: "s3cret".to_string(),
+        });
+
+        // WHEN: exchanging for the backend
+        let cred = s.propagate(&identity("alice"), &mail).await.unwrap();
+
+        // THEN: the client id and secret are sent, no client assertion
+        assert_eq!(cred.headers[0].1, "Bearer obo-token");
+        let form = seen.lock().unwrap().pop().unwrap();
+        assert_eq!(form["client_id"], "mail-obo");
+        assert_eq!(form["client_secret"], "s3cret");
+        assert!(!form.contains_key("client_assertion"));
+
+        // AND: the same backend without the client does not reuse its token
+        let default_client = backend(Some(&endpoint));
+        let other = s
+            .propagate(&identity("alice"), &default_client)
+            .await
+            .unwrap();
+        assert_ne!(cred.cache_binding, other.cache_binding);
+        let form = seen.lock().unwrap().pop().unwrap();
+        assert!(form.contains_key("client_assertion"));
+        assert!(!form.contains_key("client_secret"));
+
+        server.abort();
+    }
+
     // IDP.2 — no endpoint configured must refuse, never fall through to a
     // static credential.
     #[tokio::test]
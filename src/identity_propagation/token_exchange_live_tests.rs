@@ -26,14 +26,16 @@ use axum::{Router, routing::get};
 use tokio::net::TcpListener;
 
 use crate::config::{
-    KeyServerConfig, KeyServerPolicyConfig, KeyServerProviderConfig, PolicyMatchConfig,
-    PolicyScopesConfig,
+    KeyServerConfig, KeyServerPolicyConfig, KeyServerProviderConfig, PolicyEffect,
+    PolicyMatchConfig, PolicyScopesConfig,
 };
 use crate::gateway::oauth::{GatewayKeyPair, jwks_handler};
 use crate::identity_propagation::{BackendDescriptor, IdentityPropagation, TokenExchangeStrategy};
 use crate::key_server::oidc::VerifiedIdentity;
 use crate::key_server::policy::PolicyEngine;
-use crate::key_server::{InMemoryTokenStore, KeyServer, OidcVerifier, handler::key_server_routes};
+use crate::key_server::{
+    InMemoryTokenStore, IssuanceGuard, KeyServer, OidcVerifier, handler::key_server_routes,
+};
 
 /// Issuer value [`super::SignedAssertionStrategy`] always stamps into the
 /// minted subject-token assertion (`SignedAssertionStrategy::ISSUER`). The
@@ -92,7 +94,9 @@ impl LiveServer {
                 reqwest::Client::new(),
             )),
             policy: Arc::new(PolicyEngine::new(config.policies.clone())),
+            issuance: IssuanceGuard::new(config.issuance.clone()),
             config,
+            jwt: None,
         });
 
         let jwks_router = Router::new()
@@ -131,6 +135,7 @@ fn identity(email: &str) -> VerifiedIdentity {
         name: None,
         groups: vec!["eng".to_string()],
         issuer: "https://corp-idp.example".to_string(),
+        claims: serde_json::Map::new(),
     }
 }
 
@@ -141,12 +146,15 @@ fn allow_all_policy() -> KeyServerPolicyConfig {
             issuer: None,
             email: None,
             group: None,
+            ..PolicyMatchConfig::default()
         },
-        scopes: PolicyScopesConfig {
+        effect: PolicyEffect::Allow,
+        bundles: Vec::new(),
+        scopes: Some(PolicyScopesConfig {
             backends: vec!["*".to_string()],
             tools: vec!["*".to_string()],
             rate_limit: 42,
-        },
+        }),
     }
 }
 
@@ -156,6 +164,7 @@ fn backend(endpoint: &str) -> BackendDescriptor {
         audience: "https://mail.internal".to_string(),
         token_exchange_endpoint: Some(endpoint.to_string()),
         token_exchange_scope: Some("backends:mail-svc tools:mail_read".to_string()),
+        token_exchange_client: None,
     }
 }
 
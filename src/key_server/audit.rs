@@ -11,17 +11,25 @@
 //! | Event | When |
 //! |-------|------|
 //! | `token.issued` | A new temporary token is successfully issued |
+//! | `token.refreshed` | A refresh token is exchanged for a new access token |
+//! | `token.refresh_reused` | A spent refresh token is presented; its family is revoked |
 //! | `token.used` | A temporary token is validated for a request |
 //! | `token.expired` | A token is rejected because its `exp` has passed |
 //! | `token.revoked` | A token is explicitly revoked via `DELETE /auth/token/{jti}` |
 //! | `token.denied` | OIDC verification or policy matching failed |
+//! | `token.rate_limited` | A token request exceeded a `key_server.issuance` limit |
+//! | `token.anomaly` | An issued token completes an unusual pattern (flood, scope escalation) |
 //! | `token.invalid` | The token string is structurally invalid |
 
 use std::net::IpAddr;
 
 use serde::Serialize;
 
-use super::{oidc::VerifiedIdentity, store::TemporaryToken};
+use super::{
+    issuance::Anomaly,
+    oidc::VerifiedIdentity,
+    store::{RefreshFamily, TemporaryToken},
+};
 
 /// Structured audit event emitted for every token lifecycle transition.
 #[derive(Debug, Serialize)]
@@ -67,6 +75,32 @@ impl AuditEvent {
         }
     }
 
+    /// Construct a `token.refreshed` event for the newly issued `token`.
+    #[must_use]
+    pub fn refreshed(token: &TemporaryToken) -> Self {
+        Self {
+            event: "token.refreshed",
+            ..Self::issued(token)
+        }
+    }
+
+    /// Construct a `token.refresh_reused` event: a spent refresh token of
+    /// `family` was presented, so the family and its latest access token are
+    /// revoked.
+    #[must_use]
+    pub fn refresh_reused(family: &RefreshFamily, client_ip: Option<IpAddr>) -> Self {
+        Self {
+            event: "token.refresh_reused",
+            identity: Some(family.identity.clone()),
+            token_jti: Some(family.access_jti.clone()),
+            backends: None,
+            tools: None,
+            rate_limit: None,
+            client_ip,
+            reason: Some(format!("refresh token reuse in family {}", family.id)),
+        }
+    }
+
     /// Construct a `token.used` event.
     #[must_use]
     pub fn used(token: &TemporaryToken, client_ip: Option<IpAddr>) -> Self {
@@ -127,6 +161,37 @@ impl AuditEvent {
         }
     }
 
+    /// Construct a `token.rate_limited` event: the `limit` issuance rate
+    /// (`"ip"` or `"identity"`) is exhausted.
+    #[must_use]
+    pub fn rate_limited(
+        limit: &str,
+        identity: Option<VerifiedIdentity>,
+        client_ip: Option<IpAddr>,
+    ) -> Self {
+        Self {
+            event: "token.rate_limited",
+            identity,
+            token_jti: None,
+            backends: None,
+            tools: None,
+            rate_limit: None,
+            client_ip,
+            reason: Some(format!("per-{limit} issuance rate exceeded")),
+        }
+    }
+
+    /// Construct a `token.anomaly` alert for the just-issued `token`.
+    #[must_use]
+    pub fn anomaly(token: &TemporaryToken, anomaly: &Anomaly) -> Self {
+        Self {
+            event: "token.anomaly",
+            reason: Some(anomaly.reason()),
+            rate_limit: None,
+            ..Self::issued(token)
+        }
+    }
+
     /// Construct a `token.invalid` event.
     #[must_use]
     pub fn invalid(reason: impl Into<String>, client_ip: Option<IpAddr>) -> Self {
@@ -174,6 +239,7 @@ mod tests {
             name: Some("Alice".to_string()),
             groups: Vec::new(),
             issuer: "https://accounts.google.com".to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
@@ -225,6 +291,56 @@ mod tests {
         assert!(event.backends.is_none());
     }
 
+    #[test]
+    fn refresh_events_name_the_identity() {
+        // GIVEN: a token and the refresh family it came from
+        let token = make_token();
+        let family = RefreshFamily {
+            id: "family-1".to_string(),
+            current: String::new(),
+            identity: make_identity(),
+            scopes: token.scopes.clone(),
+            access_jti: token.jti.clone(),
+            exp: token.exp,
+        };
+
+        // WHEN: build the refresh events
+        let refreshed = AuditEvent::refreshed(&token);
+        let reused = AuditEvent::refresh_reused(&family, None);
+
+        // THEN: both carry the identity and the access token JTI
+        assert_eq!(refreshed.event, "token.refreshed");
+        assert!(refreshed.backends.is_some());
+        assert_eq!(reused.event, "token.refresh_reused");
+        assert_eq!(reused.token_jti, Some(token.jti));
+        assert!(reused.reason.unwrap().contains("family-1"));
+    }
+
+    #[test]
+    fn issuance_alerts_carry_their_reason() {
+        // GIVEN: a token that completed a flood
+        let token = make_token();
+        let flood = Anomaly::Flood {
+            count: 21,
+            window_secs: 600,
+        };
+
+        // WHEN: build the alert and a rate-limit event
+        let alert = AuditEvent::anomaly(&token, &flood);
+        let limited = AuditEvent::rate_limited("identity", Some(make_identity()), None);
+
+        // THEN: both name the identity and explain themselves
+        assert_eq!(alert.event, "token.anomaly");
+        assert_eq!(alert.token_jti, Some(token.jti));
+        assert!(alert.reason.unwrap().contains("21 tokens"));
+        assert_eq!(limited.event, "token.rate_limited");
+        assert!(limited.identity.is_some());
+        assert_eq!(
+            limited.reason.as_deref(),
+            Some("per-identity issuance rate exceeded")
+        );
+    }
+
     #[test]
     fn denied_event_contains_reason() {
         // GIVEN/WHEN: build denied event with a reason
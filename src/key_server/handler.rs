@@ -24,22 +24,50 @@
 //! &subject_token_type=urn%3Aietf%3Aparams%3Aoauth%3Atoken-type%3Aid_token
 //! ```
 //!
+//! ## Refresh Tokens
+//!
+//! With `key_server.refresh_token_ttl_secs` set, the exchange also returns a
+//! `refresh_token`. Clients renew the session on the same endpoint
+//! ([RFC 6749 §6](https://www.rfc-editor.org/rfc/rfc6749#section-6)) without
+//! a new OIDC token:
+//!
+//! ```text
+//! grant_type=refresh_token&refresh_token=<refresh token>
+//! ```
+//!
+//! Every refresh re-evaluates the access policy and rotates the refresh
+//! token. Presenting a spent refresh token means it leaked: the session's
+//! refresh family and its latest access token are revoked.
+//!
+//! ## Rate Limits
+//!
+//! Requests are limited per client IP before any verification and per
+//! identity once one is known (`key_server.issuance`); a refused request
+//! gets `429` with `Retry-After`. Issued tokens are screened for floods and
+//! scope escalation, which raise `token.anomaly` audit alerts (see
+//! [`super::issuance`]).
+//!
 //! ## Admin Authentication
 //!
 //! Revocation endpoints require `Authorization: Bearer <admin_token>` where
 //! `admin_token` is the value from `key_server.admin.bearer_token` in config.
 //! If no admin token is configured, the endpoints return `503 Service Unavailable`.
+//!
+//! ## JWT tokens
+//!
+//! With `key_server.jwt` configured, `access_token` is a signed JWT (see
+//! [`super::jwt`]). Revoking a JTI then always succeeds, since this instance
+//! may not have issued the token; the revocation applies to this instance.
 
 use std::{
-    net::IpAddr,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
-    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use axum::{
-    Form, Json, Router,
-    extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
+    Extension, Form, Json, Router,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
     response::IntoResponse,
     routing::{delete, post},
 };
@@ -50,20 +78,30 @@ use tracing::warn;
 use super::{
     KeyServer,
     audit::{self, AuditEvent},
+    issuance::Throttled,
+    oidc::VerifiedIdentity,
     policy::RequestedScopes,
-    store::{InMemoryTokenStore, TemporaryToken},
+    store::{InMemoryTokenStore, RefreshFamily, TemporaryToken, TokenScopes, now_secs},
 };
 use crate::config::KeyServerOidcConfig;
+use crate::hashing::sha256_hex;
+
+const TOKEN_EXCHANGE_GRANT: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+const REFRESH_TOKEN_GRANT: &str = "refresh_token";
 
 // ── Request / Response types ───────────────────────────────────────────────
 
-/// RFC 8693 token exchange request body.
+/// RFC 8693 token exchange (or RFC 6749 refresh) request body.
 #[derive(Deserialize)]
 pub struct TokenExchangeRequest {
-    /// Must be `urn:ietf:params:oauth:grant-type:token-exchange`.
+    /// `urn:ietf:params:oauth:grant-type:token-exchange` or `refresh_token`.
     pub grant_type: String,
-    /// The OIDC ID token (JWT).
+    /// The OIDC ID token (JWT), for a token exchange.
+    #[serde(default)]
     pub subject_token: String,
+    /// The refresh token, for `grant_type=refresh_token`.
+    #[serde(default)]
+    pub refresh_token: String,
     /// Must be `urn:ietf:params:oauth:token-type:id_token`.
     #[serde(default)]
     pub subject_token_type: String,
@@ -79,6 +117,7 @@ impl std::fmt::Debug for TokenExchangeRequest {
         f.debug_struct("TokenExchangeRequest")
             .field("grant_type", &self.grant_type)
             .field("subject_token", &"<redacted>")
+            .field("refresh_token", &"<redacted>")
             .field("subject_token_type", &self.subject_token_type)
             .field("scope", &self.scope)
             .finish()
@@ -88,7 +127,7 @@ impl std::fmt::Debug for TokenExchangeRequest {
 /// RFC 8693 token exchange response.
 #[derive(Serialize)]
 pub struct TokenExchangeResponse {
-    /// The issued opaque bearer token.
+    /// The issued bearer token: opaque, or a signed JWT.
     pub access_token: String,
     /// Always `"Bearer"`.
     pub token_type: String,
@@ -98,6 +137,9 @@ pub struct TokenExchangeResponse {
     pub scope: String,
     /// JTI for revocation.
     pub jti: String,
+    /// Renews the session; present when refresh tokens are enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
 }
 
 impl std::fmt::Debug for TokenExchangeResponse {
@@ -108,6 +150,10 @@ impl std::fmt::Debug for TokenExchangeResponse {
             .field("expires_in", &self.expires_in)
             .field("scope", &self.scope)
             .field("jti", &self.jti)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| "<redacted>"),
+            )
             .finish()
     }
 }
@@ -137,35 +183,65 @@ pub fn key_server_routes(key_server: Arc<KeyServer>) -> Router {
 
 // ── Handlers ──────────────────────────────────────────────────────────────
 
-/// Extract client IP from `X-Forwarded-For` or `X-Real-IP` headers.
-fn extract_client_ip(headers: &HeaderMap) -> Option<IpAddr> {
-    headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.split(',').next())
-        .and_then(|s| s.trim().parse().ok())
+/// The client IP of a request arriving from `peer`.
+///
+/// Forwarding headers are client-controlled, so they are only believed when
+/// `peer` is one of `trusted_proxies`: then the client is the nearest
+/// `X-Forwarded-For` hop that is not itself a trusted proxy, else
+/// `X-Real-IP`. Any other peer is the client.
+fn client_ip(
+    peer: Option<IpAddr>,
+    headers: &HeaderMap,
+    trusted_proxies: &[IpAddr],
+) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer);
+    }
+    let forwarded: Vec<IpAddr> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|s| s.trim().parse().ok())
+        .collect();
+    forwarded
+        .into_iter()
+        .rev()
+        .find(|ip| !trusted_proxies.contains(ip))
         .or_else(|| {
             headers
                 .get("x-real-ip")
                 .and_then(|v| v.to_str().ok())
                 .and_then(|s| s.trim().parse().ok())
         })
+        .or(Some(peer))
 }
 
-/// `POST /auth/token` — Exchange an OIDC identity token for a temporary gateway token.
+/// `POST /auth/token` — Exchange an OIDC identity token for a temporary gateway
+/// token, or renew a session with its refresh token.
 ///
 /// Accepts `application/x-www-form-urlencoded` per RFC 8693 / RFC 6749 §4.1.3 —
 /// see the module docs. `axum::extract::Form` rejects any other content type
 /// with `415 Unsupported Media Type` before this handler body runs.
 async fn exchange_token(
     State(ks): State<Arc<KeyServer>>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
     headers: HeaderMap,
     Form(body): Form<TokenExchangeRequest>,
 ) -> impl IntoResponse {
     // Items must be outside statement blocks to satisfy clippy::items_after_statements
-    let client_ip: Option<IpAddr> = extract_client_ip(&headers);
+    let peer = connect_info.map(|Extension(ConnectInfo(addr))| addr.ip());
+    let client_ip: Option<IpAddr> = client_ip(peer, &headers, &ks.config.issuance.trusted_proxies);
 
-    if body.grant_type != "urn:ietf:params:oauth:grant-type:token-exchange" {
+    if let Err(throttled) = ks.issuance.check_ip(client_ip) {
+        return throttled_response(&throttled, None, client_ip);
+    }
+
+    if body.grant_type == REFRESH_TOKEN_GRANT {
+        return refresh_session(&ks, &body.refresh_token, client_ip).await;
+    }
+    if body.grant_type != TOKEN_EXCHANGE_GRANT {
         warn!(grant_type = %body.grant_type, "Invalid grant_type");
         let ev = AuditEvent::invalid(
             format!("invalid grant_type: {}", body.grant_type),
@@ -175,10 +251,20 @@ async fn exchange_token(
         return error_response(
             StatusCode::BAD_REQUEST,
             "unsupported_grant_type",
-            "grant_type must be 'urn:ietf:params:oauth:grant-type:token-exchange'",
+            "grant_type must be 'urn:ietf:params:oauth:grant-type:token-exchange' or 'refresh_token'",
         );
     }
 
+    exchange_identity_token(&ks, &body, client_ip).await
+}
+
+/// `grant_type=urn:ietf:params:oauth:grant-type:token-exchange` — verify the
+/// OIDC subject token, resolve its policy and start a session.
+async fn exchange_identity_token(
+    ks: &KeyServer,
+    body: &TokenExchangeRequest,
+    client_ip: Option<IpAddr>,
+) -> axum::response::Response {
     if body.subject_token.is_empty() {
         return error_response(
             StatusCode::BAD_REQUEST,
@@ -207,6 +293,10 @@ async fn exchange_token(
         }
     };
 
+    if let Err(throttled) = ks.issuance.check_identity(&identity) {
+        return throttled_response(&throttled, Some(&identity), client_ip);
+    }
+
     // Parse requested scopes from scope string
     let requested = parse_scope_string(&body.scope);
 
@@ -225,7 +315,39 @@ async fn exchange_token(
         );
     };
 
-    // Enforce max tokens per identity
+    if let Err(response) = check_token_limit(ks, &identity, client_ip).await {
+        return response;
+    }
+
+    // Issue token
+    let token = match issue_token(
+        ks,
+        InMemoryTokenStore::generate_jti(),
+        identity,
+        scopes,
+        client_ip,
+    ) {
+        Ok(token) => token,
+        Err(response) => return response,
+    };
+
+    let refresh_token = start_refresh_family(ks, &token).await;
+    let response = token_response(ks, &token, refresh_token);
+
+    let ev = AuditEvent::issued(&token);
+    audit::emit(&ev);
+    screen_issuance(ks, &token);
+    ks.store.insert(token).await;
+
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// Enforce `max_tokens_per_identity`.
+async fn check_token_limit(
+    ks: &KeyServer,
+    identity: &VerifiedIdentity,
+    client_ip: Option<IpAddr>,
+) -> Result<(), axum::response::Response> {
     let active_count = ks.store.count_for_subject(&identity.subject).await;
     if active_count >= ks.config.max_tokens_per_identity as usize {
         warn!(
@@ -242,40 +364,140 @@ async fn exchange_token(
             client_ip,
         );
         audit::emit(&ev);
-        return error_response(
+        return Err(error_response(
             StatusCode::TOO_MANY_REQUESTS,
             "too_many_tokens",
             "Maximum number of active tokens for this identity exceeded",
+        ));
+    }
+    Ok(())
+}
+
+/// Start a refresh family for a new session; `None` when refresh tokens are
+/// disabled.
+async fn start_refresh_family(ks: &KeyServer, token: &TemporaryToken) -> Option<String> {
+    if ks.config.refresh_token_ttl_secs > 0 {
+        let id = InMemoryTokenStore::generate_jti();
+        let refresh_token = RefreshFamily::generate_token(&id);
+        ks.store
+            .insert_refresh(RefreshFamily {
+                id,
+                current: sha256_hex(refresh_token.as_bytes()),
+                identity: token.identity.clone(),
+                scopes: token.scopes.clone(),
+                access_jti: token.jti.clone(),
+                exp: token.iat + ks.config.refresh_token_ttl_secs,
+            })
+            .await;
+        Some(refresh_token)
+    } else {
+        None
+    }
+}
+
+/// `grant_type=refresh_token` — renew a session, rotating its refresh token.
+///
+/// The policy is re-evaluated against the session's identity, so a user who
+/// lost access cannot keep refreshing. The `max_tokens_per_identity` limit
+/// does not apply: a refresh continues an existing session.
+async fn refresh_session(
+    ks: &KeyServer,
+    refresh_token: &str,
+    client_ip: Option<IpAddr>,
+) -> axum::response::Response {
+    if ks.config.refresh_token_ttl_secs == 0 {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "unsupported_grant_type",
+            "Refresh tokens are not enabled",
         );
     }
 
-    // Issue token
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or(Duration::ZERO)
-        .as_secs();
-
-    let ttl = ks.config.token_ttl_secs;
-    let token = TemporaryToken {
-        jti: InMemoryTokenStore::generate_jti(),
-        token: InMemoryTokenStore::generate_bearer(),
-        identity: identity.clone(),
-        scopes: scopes.clone(),
-        iat: now,
-        exp: now + ttl,
-        client_ip,
+    let family = match RefreshFamily::id_of(refresh_token) {
+        Some(id) => ks.store.get_refresh(id).await,
+        None => None,
+    };
+    let Some(family) = family else {
+        let ev = AuditEvent::invalid("unknown or expired refresh token", client_ip);
+        audit::emit(&ev);
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "invalid_grant",
+            "Refresh token is invalid or expired",
+        );
     };
 
-    let response = TokenExchangeResponse {
-        access_token: token.token.clone(),
-        token_type: "Bearer".to_string(),
-        expires_in: ttl,
-        scope: format_scope_string(&token),
-        jti: token.jti.clone(),
+    if let Err(throttled) = ks.issuance.check_identity(&family.identity) {
+        return throttled_response(&throttled, Some(&family.identity), client_ip);
+    }
+
+    // An empty scope list means "all", so a grant narrowed to nothing by a
+    // policy change must be refused rather than widened.
+    let narrowed_to_nothing =
+        |granted: &[String], now: &[String]| !granted.is_empty() && now.is_empty();
+    let requested = RequestedScopes {
+        backends: family.scopes.backends.clone(),
+        tools: family.scopes.tools.clone(),
+    };
+    let scopes = ks
+        .policy
+        .resolve_scopes(&family.identity, &requested)
+        .filter(|scopes| {
+            !narrowed_to_nothing(&family.scopes.backends, &scopes.backends)
+                && !narrowed_to_nothing(&family.scopes.tools, &scopes.tools)
+        });
+    let Some(scopes) = scopes else {
+        warn!(email = %family.identity.email, "No policy matched on refresh");
+        ks.store.revoke_refresh(&family.id).await;
+        let ev = AuditEvent::denied(
+            format!("no policy matched for {} on refresh", family.identity.email),
+            client_ip,
+        );
+        audit::emit(&ev);
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "access_denied",
+            "No access policy matched for this identity",
+        );
     };
 
-    let ev = AuditEvent::issued(&token);
+    let jti = InMemoryTokenStore::generate_jti();
+    let next = RefreshFamily::generate_token(&family.id);
+    let rotated = ks
+        .store
+        .rotate_refresh(
+            &family.id,
+            &sha256_hex(refresh_token.as_bytes()),
+            &sha256_hex(next.as_bytes()),
+            &jti,
+        )
+        .await;
+    if !rotated {
+        // A spent token: it was copied, or two clients share one session.
+        warn!(family = %family.id, subject = %family.identity.subject, "Refresh token reuse detected");
+        ks.store.revoke_refresh(&family.id).await;
+        ks.store.revoke_by_jti(&family.access_jti).await;
+        if let Some(jwt) = &ks.jwt {
+            jwt.revoke_jti(&family.access_jti, now_secs() + ks.config.token_ttl_secs);
+        }
+        let ev = AuditEvent::refresh_reused(&family, client_ip);
+        audit::emit(&ev);
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "invalid_grant",
+            "Refresh token was already used; the session has been revoked",
+        );
+    }
+
+    let token = match issue_token(ks, jti, family.identity, scopes, client_ip) {
+        Ok(token) => token,
+        Err(response) => return response,
+    };
+    let response = token_response(ks, &token, Some(next));
+
+    let ev = AuditEvent::refreshed(&token);
     audit::emit(&ev);
+    screen_issuance(ks, &token);
     ks.store.insert(token).await;
 
     (StatusCode::OK, Json(response)).into_response()
@@ -293,7 +515,11 @@ async fn revoke_token(
         return response;
     }
 
-    let removed = ks.store.revoke_by_jti(&jti).await;
+    let mut removed = ks.store.revoke_by_jti(&jti).await;
+    if let Some(jwt) = &ks.jwt {
+        jwt.revoke_jti(&jti, now_secs() + ks.config.token_ttl_secs);
+        removed = true;
+    }
     if removed {
         let ev = AuditEvent::revoked(&jti, None);
         audit::emit(&ev);
@@ -320,6 +546,9 @@ async fn revoke_tokens_by_subject(
     }
 
     let count = ks.store.revoke_by_subject(&params.subject).await;
+    if let Some(jwt) = &ks.jwt {
+        jwt.revoke_subject(&params.subject);
+    }
     let ev = AuditEvent::revoked(&format!("bulk:{}", params.subject), None);
     audit::emit(&ev);
 
@@ -332,6 +561,94 @@ async fn revoke_tokens_by_subject(
 
 // ── Helpers ───────────────────────────────────────────────────────────────
 
+/// Build an access token with id `jti`, signed when JWT issuance is on.
+///
+/// The `Err` variant carries the `500` response for a signing failure.
+#[allow(clippy::result_large_err)]
+fn issue_token(
+    ks: &KeyServer,
+    jti: String,
+    identity: VerifiedIdentity,
+    scopes: TokenScopes,
+    client_ip: Option<IpAddr>,
+) -> Result<TemporaryToken, axum::response::Response> {
+    let now = now_secs();
+    let mut token = TemporaryToken {
+        jti,
+        token: InMemoryTokenStore::generate_bearer(),
+        identity,
+        scopes,
+        iat: now,
+        exp: now + ks.config.token_ttl_secs,
+        client_ip,
+    };
+    if let Some(jwt) = &ks.jwt {
+        match jwt.sign(&token) {
+            Ok(signed) => token.token = signed,
+            Err(e) => {
+                warn!(error = %e, "Token signing failed");
+                return Err(error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "server_error",
+                    "Token signing failed",
+                ));
+            }
+        }
+    }
+    Ok(token)
+}
+
+/// Screen the just-issued `token`, raising a `token.anomaly` alert per
+/// finding. Anomalies never block issuance.
+fn screen_issuance(ks: &KeyServer, token: &TemporaryToken) {
+    for anomaly in ks.issuance.record(token) {
+        warn!(
+            subject = %token.identity.subject,
+            anomaly = %anomaly.reason(),
+            "Unusual token issuance"
+        );
+        let ev = AuditEvent::anomaly(token, &anomaly);
+        audit::emit(&ev);
+    }
+}
+
+/// The `429` response (and audit event) for a throttled token request.
+fn throttled_response(
+    throttled: &Throttled,
+    identity: Option<&VerifiedIdentity>,
+    client_ip: Option<IpAddr>,
+) -> axum::response::Response {
+    warn!(limit = throttled.limit, client_ip = ?client_ip, "Token endpoint rate limit exceeded");
+    let ev = AuditEvent::rate_limited(throttled.limit, identity.cloned(), client_ip);
+    audit::emit(&ev);
+    let retry_after = throttled.retry_after.as_millis().div_ceil(1000).max(1);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after.to_string())],
+        Json(json!({
+            "error": "rate_limited",
+            "message": "Too many token requests; retry later"
+        })),
+    )
+        .into_response()
+}
+
+/// The token endpoint response for `token`.
+fn token_response(
+    ks: &KeyServer,
+    token: &TemporaryToken,
+    refresh_token: Option<String>,
+) -> TokenExchangeResponse {
+    TokenExchangeResponse {
+        access_token: token.token.clone(),
+        token_type: "Bearer".to_string(),
+        expires_in: ks.config.token_ttl_secs,
+        scope: format_scope_string(token),
+        jti: token.jti.clone(),
+        refresh_token,
+    }
+}
+
 /// Check the `Authorization: Bearer <token>` header against the configured
 /// admin token. Returns `Err(response)` if auth fails.
 ///
@@ -426,6 +743,7 @@ fn error_response(status: StatusCode, error: &str, message: &str) -> axum::respo
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
 
     #[test]
     fn debug_output_redacts_exchange_tokens() {
@@ -434,6 +752,7 @@ mod tests {
         let req = TokenExchangeRequest {
             grant_type: "urn:ietf:params:oauth:grant-type:token-exchange".to_string(),
             subject_token: "eyJ-SUBJECT-JWT-SECRET".to_string(),
+            refresh_token: "mcpgwr_family.REFRESH-SECRET".to_string(),
             subject_token_type: "urn:ietf:params:oauth:token-type:id_token".to_string(),
             scope: "gmail".to_string(),
         };
@@ -443,6 +762,7 @@ mod tests {
             expires_in: 3600,
             scope: "gmail".to_string(),
             jti: "jti-123".to_string(),
+            refresh_token: Some("mcpgwr_family.ISSUED-REFRESH-SECRET".to_string()),
         };
         let rdbg = format!("{req:?}");
         let sdbg = format!("{resp:?}");
@@ -454,10 +774,262 @@ mod tests {
             !sdbg.contains("mcpgw_ISSUED-BEARER-SECRET"),
             "leaked access token: {sdbg}"
         );
+        assert!(
+            !rdbg.contains("REFRESH-SECRET") && !sdbg.contains("REFRESH-SECRET"),
+            "leaked refresh token: {rdbg} {sdbg}"
+        );
         assert!(rdbg.contains("<redacted>") && sdbg.contains("<redacted>"));
         assert!(sdbg.contains("jti-123"), "jti stays visible for revocation");
     }
 
+    mod refresh {
+        use super::*;
+        use crate::config::{
+            KeyServerConfig, KeyServerPolicyConfig, PolicyEffect, PolicyMatchConfig,
+            PolicyScopesConfig,
+        };
+        use axum::body::{Body, to_bytes};
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        fn policy(domain: &str) -> KeyServerPolicyConfig {
+            KeyServerPolicyConfig {
+                match_criteria: PolicyMatchConfig {
+                    domain: Some(domain.to_string()),
+                    issuer: None,
+                    email: None,
+                    group: None,
+                    ..PolicyMatchConfig::default()
+                },
+                effect: PolicyEffect::Allow,
+                bundles: Vec::new(),
+                scopes: Some(PolicyScopesConfig {
+                    backends: vec!["tavily".to_string()],
+                    tools: Vec::new(),
+                    rate_limit: 100,
+                }),
+            }
+        }
+
+        /// A key server with one session for alice, and her refresh token.
+        async fn session(policy_domain: &str) -> (Arc<KeyServer>, String) {
+            let ks = Arc::new(KeyServer::new(KeyServerConfig {
+                enabled: true,
+                refresh_token_ttl_secs: 86_400,
+                policies: vec![policy(policy_domain)],
+                ..KeyServerConfig::default()
+            }));
+            let identity = VerifiedIdentity {
+                subject: "alice-sub".to_string(),
+                email: "alice@company.com".to_string(),
+                name: None,
+                groups: Vec::new(),
+                issuer: "https://accounts.google.com".to_string(),
+                claims: serde_json::Map::new(),
+            };
+            let scopes = TokenScopes {
+                backends: vec!["tavily".to_string()],
+                tools: Vec::new(),
+                rate_limit: 100,
+            };
+            let token = issue_token(&ks, "jti-1".to_string(), identity, scopes, None).unwrap();
+            let refresh_token = RefreshFamily::generate_token("family-1");
+            ks.store
+                .insert_refresh(RefreshFamily {
+                    id: "family-1".to_string(),
+                    current: sha256_hex(refresh_token.as_bytes()),
+                    identity: token.identity.clone(),
+                    scopes: token.scopes.clone(),
+                    access_jti: token.jti.clone(),
+                    exp: token.iat + 86_400,
+                })
+                .await;
+            ks.store.insert(token).await;
+            (ks, refresh_token)
+        }
+
+        async fn refresh(
+            ks: &Arc<KeyServer>,
+            refresh_token: &str,
+        ) -> (StatusCode, serde_json::Value) {
+            let body = serde_urlencoded::to_string([
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+            ])
+            .unwrap();
+            let request = Request::post("/auth/token")
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from(body))
+                .unwrap();
+            let response = key_server_routes(Arc::clone(ks))
+                .oneshot(request)
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice(&body).unwrap())
+        }
+
+        #[tokio::test]
+        async fn refresh_rotates_the_refresh_token() {
+            // GIVEN: alice's session
+            let (ks, first) = session("company.com").await;
+
+            // WHEN: refreshing it
+            let (status, body) = refresh(&ks, &first).await;
+
+            // THEN: a new access token and a new refresh token are issued
+            assert_eq!(status, StatusCode::OK, "{body}");
+            let access = body["access_token"].as_str().unwrap();
+            let second = body["refresh_token"].as_str().unwrap();
+            assert_ne!(second, first);
+            assert_eq!(body["scope"], "backends:tavily");
+            assert!(ks.validate_token(access).await.is_some());
+
+            // AND: the new refresh token works in turn
+            assert_eq!(refresh(&ks, second).await.0, StatusCode::OK);
+        }
+
+        #[tokio::test]
+        async fn reusing_a_spent_refresh_token_revokes_the_session() {
+            // GIVEN: alice's session, refreshed once
+            let (ks, first) = session("company.com").await;
+            let (_, body) = refresh(&ks, &first).await;
+            let access = body["access_token"].as_str().unwrap().to_string();
+            let second = body["refresh_token"].as_str().unwrap().to_string();
+
+            // WHEN: the spent first token is replayed
+            let (status, body) = refresh(&ks, &first).await;
+
+            // THEN: it is refused, and the whole session ends
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error"], "invalid_grant");
+            assert!(ks.validate_token(&access).await.is_none());
+            assert_eq!(refresh(&ks, &second).await.0, StatusCode::BAD_REQUEST);
+        }
+
+        #[tokio::test]
+        async fn refresh_reevaluates_the_policy() {
+            // GIVEN: a session whose identity no longer matches any policy
+            let (ks, refresh_token) = session("other.example").await;
+
+            // WHEN: refreshing it
+            let (status, _) = refresh(&ks, &refresh_token).await;
+
+            // THEN: access is denied and the family is gone
+            assert_eq!(status, StatusCode::FORBIDDEN);
+            assert!(ks.store.get_refresh("family-1").await.is_none());
+        }
+
+        #[tokio::test]
+        async fn refresh_grant_is_rejected_when_disabled() {
+            // GIVEN: a key server without refresh tokens
+            let ks = Arc::new(KeyServer::new(KeyServerConfig::default()));
+
+            // WHEN/THEN: the refresh grant is unsupported
+            let (status, body) = refresh(&ks, "mcpgwr_family.secret").await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error"], "unsupported_grant_type");
+        }
+    }
+
+    mod rate_limits {
+        use super::*;
+        use crate::config::{KeyServerConfig, KeyServerIssuanceConfig};
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        /// POST from the peer `ip`, claiming to forward for `forwarded_for`.
+        async fn post_via(
+            ks: &Arc<KeyServer>,
+            ip: &str,
+            forwarded_for: &str,
+        ) -> axum::response::Response {
+            let peer: SocketAddr = format!("{ip}:40000").parse().unwrap();
+            let mut request = Request::post("/auth/token")
+                .header("content-type", "application/x-www-form-urlencoded")
+                .header("x-forwarded-for", forwarded_for)
+                .body(Body::from("grant_type=password"))
+                .unwrap();
+            request.extensions_mut().insert(ConnectInfo(peer));
+            key_server_routes(Arc::clone(ks))
+                .oneshot(request)
+                .await
+                .unwrap()
+        }
+
+        async fn post_from(ks: &Arc<KeyServer>, ip: &str) -> axum::response::Response {
+            post_via(ks, ip, "198.51.100.1").await
+        }
+
+        fn limited_server(trusted_proxies: Vec<IpAddr>) -> Arc<KeyServer> {
+            Arc::new(KeyServer::new(KeyServerConfig {
+                enabled: true,
+                issuance: KeyServerIssuanceConfig {
+                    per_ip: Some("1/min".parse().unwrap()),
+                    trusted_proxies,
+                    ..KeyServerIssuanceConfig::default()
+                },
+                ..KeyServerConfig::default()
+            }))
+        }
+
+        #[tokio::test]
+        async fn token_requests_are_limited_per_client_ip() {
+            // GIVEN: one token request per minute per IP
+            let ks = limited_server(Vec::new());
+
+            // WHEN: one address sends two requests
+            let first = post_from(&ks, "203.0.113.7").await;
+            let second = post_from(&ks, "203.0.113.7").await;
+
+            // THEN: the second is refused before the grant is even looked at
+            assert_eq!(first.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+            let retry_after: u64 = second.headers()[header::RETRY_AFTER]
+                .to_str()
+                .unwrap()
+                .parse()
+                .unwrap();
+            assert!((1..=60).contains(&retry_after));
+
+            // AND: another address is unaffected
+            let other = post_from(&ks, "203.0.113.8").await;
+            assert_eq!(other.status(), StatusCode::BAD_REQUEST);
+        }
+
+        #[tokio::test]
+        async fn forwarded_headers_from_untrusted_peers_are_ignored() {
+            // GIVEN: one token request per minute per IP and no trusted proxy
+            let ks = limited_server(Vec::new());
+
+            // WHEN: one peer rotates X-Forwarded-For between requests
+            let first = post_via(&ks, "203.0.113.7", "198.51.100.1").await;
+            let second = post_via(&ks, "203.0.113.7", "198.51.100.2").await;
+
+            // THEN: both count against the peer address
+            assert_eq!(first.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+        }
+
+        #[tokio::test]
+        async fn trusted_proxies_report_the_client_ip() {
+            // GIVEN: a trusted proxy in front of the key server
+            let ks = limited_server(vec!["10.0.0.2".parse().unwrap()]);
+
+            // WHEN: it forwards two clients, one with a spoofed leading hop
+            let first = post_via(&ks, "10.0.0.2", "198.51.100.1").await;
+            let spoofed = post_via(&ks, "10.0.0.2", "192.0.2.9, 198.51.100.1").await;
+            let other = post_via(&ks, "10.0.0.2", "198.51.100.2").await;
+
+            // THEN: each client is limited by the hop the proxy appended
+            assert_eq!(first.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(spoofed.status(), StatusCode::TOO_MANY_REQUESTS);
+            assert_eq!(other.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
     #[test]
     fn parse_scope_string_empty() {
         // GIVEN: empty scope string
@@ -527,6 +1099,7 @@ mod tests {
                 name: None,
                 groups: Vec::new(),
                 issuer: "https://accounts.google.com".to_string(),
+                claims: serde_json::Map::new(),
             },
             scopes: TokenScopes::default(),
             iat: now,
@@ -561,6 +1134,7 @@ mod tests {
                 name: None,
                 groups: Vec::new(),
                 issuer: "https://accounts.google.com".to_string(),
+                claims: serde_json::Map::new(),
             },
             scopes: TokenScopes {
                 backends: vec!["tavily".to_string()],
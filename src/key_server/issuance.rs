@@ -0,0 +1,359 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+//! Issuance throttling and anomaly detection for `POST /auth/token`.
+//!
+//! Every call to the token endpoint can cost a JWKS fetch, a signature check
+//! and a store write, so it is throttled twice:
+//!
+//! - **per client IP**, before any verification work, and
+//! - **per identity**, once the OIDC token (or refresh family) names one.
+//!
+//! Both are token buckets (`key_server.issuance.per_ip` / `per_identity`).
+//! A refused request gets `429 Too Many Requests` with `Retry-After` and a
+//! `token.rate_limited` audit event.
+//!
+//! Issued tokens are then screened for patterns worth a human look. Findings
+//! never block issuance; each raises a `token.anomaly` audit alert:
+//!
+//! | Anomaly | Trigger |
+//! |---------|---------|
+//! | Token flood | More than `flood_threshold` tokens for one identity within `flood_window_secs` |
+//! | Scope escalation | A token grants backends or tools the identity's previous token did not |
+//!
+//! The client IP is the connection's peer address. `X-Forwarded-For` /
+//! `X-Real-IP` are only believed from a peer listed in `trusted_proxies`, so
+//! a client cannot pick a fresh bucket by rotating a header.
+
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use dashmap::DashMap;
+
+use super::oidc::VerifiedIdentity;
+use super::store::{TemporaryToken, TokenScopes};
+use crate::config::KeyServerIssuanceConfig;
+use crate::failsafe::KeyedRateLimiter;
+
+/// Identities tracked for anomaly detection before idle ones are dropped.
+const MAX_TRACKED_IDENTITIES: usize = 10_000;
+
+/// Rate limits and anomaly detection for token issuance.
+pub struct IssuanceGuard {
+    config: KeyServerIssuanceConfig,
+    limits: KeyedRateLimiter,
+    /// Stable actor id → recent issuances.
+    history: DashMap<String, IssuanceHistory>,
+}
+
+/// Recent issuances to one identity.
+struct IssuanceHistory {
+    /// Issue times within the flood window, oldest first.
+    recent: VecDeque<u64>,
+    /// Scopes of the latest token.
+    scopes: TokenScopes,
+}
+
+/// A token request refused by [`IssuanceGuard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throttled {
+    /// The exhausted limit: `"ip"` or `"identity"`.
+    pub limit: &'static str,
+    /// Time until the next request would be admitted.
+    pub retry_after: Duration,
+}
+
+/// An unusual issuance pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Anomaly {
+    /// `count` tokens were issued to one identity within `window_secs`.
+    Flood {
+        /// Tokens in the window, including the latest.
+        count: usize,
+        /// Length of the window.
+        window_secs: u64,
+    },
+    /// The token grants scopes the identity's previous token did not.
+    /// `*` means the dimension widened to everything.
+    ScopeEscalation {
+        /// Newly granted backends.
+        backends: Vec<String>,
+        /// Newly granted tools.
+        tools: Vec<String>,
+    },
+}
+
+impl Anomaly {
+    /// Human-readable description for the audit trail.
+    #[must_use]
+    pub fn reason(&self) -> String {
+        match self {
+            Self::Flood { count, window_secs } => {
+                format!("token flood: {count} tokens within {window_secs}s")
+            }
+            Self::ScopeEscalation { backends, tools } => format!(
+                "scope escalation: backends [{}], tools [{}] not granted before",
+                backends.join(","),
+                tools.join(",")
+            ),
+        }
+    }
+}
+
+impl IssuanceGuard {
+    /// Create a guard enforcing `config`.
+    #[must_use]
+    pub fn new(config: KeyServerIssuanceConfig) -> Self {
+        Self {
+            config,
+            limits: KeyedRateLimiter::default(),
+            history: DashMap::new(),
+        }
+    }
+
+    /// Count one token request from `client_ip`. Requests without a known
+    /// IP (no peer address, as in in-process calls) are not limited.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Throttled`] when the IP has exhausted `per_ip`.
+    pub fn check_ip(&self, client_ip: Option<IpAddr>) -> Result<(), Throttled> {
+        let (Some(limit), Some(ip)) = (self.config.per_ip, client_ip) else {
+            return Ok(());
+        };
+        self.limits
+            .check(&format!("ip:{ip}"), limit)
+            .map_err(|retry_after| Throttled {
+                limit: "ip",
+                retry_after,
+            })
+    }
+
+    /// Count one token request for `identity`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Throttled`] when the identity has exhausted `per_identity`.
+    pub fn check_identity(&self, identity: &VerifiedIdentity) -> Result<(), Throttled> {
+        let Some(limit) = self.config.per_identity else {
+            return Ok(());
+        };
+        self.limits
+            .check(&format!("identity:{}", identity.stable_actor_id()), limit)
+            .map_err(|retry_after| Throttled {
+                limit: "identity",
+                retry_after,
+            })
+    }
+
+    /// Record the issued `token` and return the anomalies it completes.
+    ///
+    /// A flood is reported once, as the window crosses the threshold, not
+    /// for every token after it.
+    pub fn record(&self, token: &TemporaryToken) -> Vec<Anomaly> {
+        let now = token.iat;
+        let window = self.config.flood_window_secs;
+        if self.history.len() >= MAX_TRACKED_IDENTITIES {
+            self.history
+                .retain(|_, h| h.recent.back().is_some_and(|last| last + window > now));
+        }
+
+        let mut anomalies = Vec::new();
+        let mut history = self
+            .history
+            .entry(token.identity.stable_actor_id())
+            .or_insert_with(|| IssuanceHistory {
+                recent: VecDeque::new(),
+                scopes: token.scopes.clone(),
+            });
+
+        if self.config.detect_scope_escalation {
+            let backends = widened(&history.scopes.backends, &token.scopes.backends);
+            let tools = widened(&history.scopes.tools, &token.scopes.tools);
+            if !backends.is_empty() || !tools.is_empty() {
+                anomalies.push(Anomaly::ScopeEscalation { backends, tools });
+            }
+        }
+        history.scopes = token.scopes.clone();
+
+        history.recent.retain(|issued| issued + window > now);
+        history.recent.push_back(now);
+        let threshold = self.config.flood_threshold as usize;
+        if threshold > 0 && history.recent.len() == threshold + 1 {
+            anomalies.push(Anomaly::Flood {
+                count: history.recent.len(),
+                window_secs: window,
+            });
+        }
+        anomalies
+    }
+}
+
+/// Entries `next` grants beyond `previous`. An empty list or `*` grants
+/// everything.
+fn widened(previous: &[String], next: &[String]) -> Vec<String> {
+    let is_wildcard = |list: &[String]| list.is_empty() || list.iter().any(|s| s == "*");
+    if is_wildcard(previous) {
+        Vec::new()
+    } else if is_wildcard(next) {
+        vec!["*".to_string()]
+    } else {
+        next.iter()
+            .filter(|entry| !previous.contains(entry))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RateSpec;
+    use crate::key_server::store::now_secs;
+
+    fn identity(subject: &str) -> VerifiedIdentity {
+        VerifiedIdentity {
+            subject: subject.to_string(),
+            email: format!("{subject}@company.com"),
+            name: None,
+            groups: Vec::new(),
+            issuer: "https://accounts.google.com".to_string(),
+            claims: serde_json::Map::new(),
+        }
+    }
+
+    fn token(subject: &str, iat: u64, backends: &[&str]) -> TemporaryToken {
+        TemporaryToken {
+            jti: format!("jti-{subject}-{iat}"),
+            token: String::new(),
+            identity: identity(subject),
+            scopes: TokenScopes {
+                backends: backends.iter().map(ToString::to_string).collect(),
+                tools: Vec::new(),
+                rate_limit: 100,
+            },
+            iat,
+            exp: iat + 3600,
+            client_ip: None,
+        }
+    }
+
+    #[test]
+    fn ip_limit_throttles_each_address_separately() {
+        // GIVEN: two requests per minute per IP
+        let guard = IssuanceGuard::new(KeyServerIssuanceConfig {
+            per_ip: Some("2/min".parse::<RateSpec>().unwrap()),
+            ..KeyServerIssuanceConfig::default()
+        });
+        let first: IpAddr = "10.0.0.1".parse().unwrap();
+        let second: IpAddr = "10.0.0.2".parse().unwrap();
+
+        // WHEN: the first address makes three requests
+        assert!(guard.check_ip(Some(first)).is_ok());
+        assert!(guard.check_ip(Some(first)).is_ok());
+        let throttled = guard.check_ip(Some(first)).unwrap_err();
+
+        // THEN: only that address is throttled, and unknown IPs never are
+        assert_eq!(throttled.limit, "ip");
+        assert!(throttled.retry_after > Duration::ZERO);
+        assert!(guard.check_ip(Some(second)).is_ok());
+        for _ in 0..5 {
+            assert!(guard.check_ip(None).is_ok());
+        }
+    }
+
+    #[test]
+    fn identity_limit_can_be_disabled() {
+        let limited = IssuanceGuard::new(KeyServerIssuanceConfig {
+            per_identity: Some("1/hour".parse::<RateSpec>().unwrap()),
+            ..KeyServerIssuanceConfig::default()
+        });
+        assert!(limited.check_identity(&identity("alice")).is_ok());
+        assert_eq!(
+            limited
+                .check_identity(&identity("alice"))
+                .unwrap_err()
+                .limit,
+            "identity"
+        );
+        assert!(limited.check_identity(&identity("bob")).is_ok());
+
+        let unlimited = IssuanceGuard::new(KeyServerIssuanceConfig {
+            per_identity: None,
+            ..KeyServerIssuanceConfig::default()
+        });
+        for _ in 0..20 {
+            assert!(unlimited.check_identity(&identity("alice")).is_ok());
+        }
+    }
+
+    #[test]
+    fn flood_is_reported_once_when_the_threshold_is_crossed() {
+        // GIVEN: an alert above three tokens in ten minutes
+        let guard = IssuanceGuard::new(KeyServerIssuanceConfig {
+            flood_threshold: 3,
+            flood_window_secs: 600,
+            ..KeyServerIssuanceConfig::default()
+        });
+        let now = now_secs();
+
+        // WHEN: alice gets five tokens a minute apart
+        let findings: Vec<Vec<Anomaly>> = (0..5)
+            .map(|i| guard.record(&token("alice", now + i * 60, &["tavily"])))
+            .collect();
+
+        // THEN: only the fourth token raises the alert
+        assert!(findings[..3].iter().all(Vec::is_empty));
+        assert_eq!(
+            findings[3],
+            vec![Anomaly::Flood {
+                count: 4,
+                window_secs: 600
+            }]
+        );
+        assert!(findings[4].is_empty());
+
+        // AND: tokens spread beyond the window never do
+        let spread: Vec<Vec<Anomaly>> = (0..5)
+            .map(|i| guard.record(&token("bob", now + i * 600, &["tavily"])))
+            .collect();
+        assert!(spread.iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn scope_escalation_compares_with_the_previous_token() {
+        let guard = IssuanceGuard::new(KeyServerIssuanceConfig::default());
+        let now = now_secs();
+
+        // First token: nothing to compare with
+        assert!(guard.record(&token("alice", now, &["tavily"])).is_empty());
+        // Narrower: fine
+        assert!(guard.record(&token("alice", now, &["tavily"])).is_empty());
+        // A new backend
+        assert_eq!(
+            guard.record(&token("alice", now, &["tavily", "github"])),
+            vec![Anomaly::ScopeEscalation {
+                backends: vec!["github".to_string()],
+                tools: Vec::new(),
+            }]
+        );
+        // Everything
+        assert_eq!(
+            guard.record(&token("alice", now, &[])),
+            vec![Anomaly::ScopeEscalation {
+                backends: vec!["*".to_string()],
+                tools: Vec::new(),
+            }]
+        );
+        // Already everything
+        assert!(guard.record(&token("alice", now, &["*"])).is_empty());
+
+        let quiet = IssuanceGuard::new(KeyServerIssuanceConfig {
+            detect_scope_escalation: false,
+            ..KeyServerIssuanceConfig::default()
+        });
+        quiet.record(&token("alice", now, &["tavily"]));
+        assert!(quiet.record(&token("alice", now, &[])).is_empty());
+    }
+}
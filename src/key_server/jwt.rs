@@ -0,0 +1,335 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+//! Signed JWT issuance — self-contained gateway tokens.
+//!
+//! With `key_server.jwt` configured, the bearer returned by `POST /auth/token`
+//! is an ES256-signed JWT carrying the identity and scopes instead of an
+//! opaque `mcpgw_` value. Any service can verify it against the key published
+//! at `/.well-known/jwks.json`, and gateway instances sharing the signing key
+//! accept each other's tokens without calling back.
+//!
+//! # Claims
+//!
+//! ```json
+//! {
+//!   "iss": "mcp-gateway", "aud": "mcp-gateway", "sub": "<OIDC subject>",
+//!   "jti": "<uuid>", "iat": 1760000000, "exp": 1760003600,
+//!   "email": "alice@company.com", "groups": ["eng"],
+//!   "idp": "https://accounts.google.com",
+//!   "backends": ["tavily"], "tools": [], "rate_limit": 100
+//! }
+//! ```
+//!
+//! # Revocation
+//!
+//! Revocation is local: a revoked `jti` or subject is rejected by the
+//! instance that revoked it until the token would have expired. Other
+//! verifiers rely on the token lifetime, so keep `token_ttl_secs` short.
+
+use dashmap::DashMap;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::oidc::VerifiedIdentity;
+use super::store::{TemporaryToken, TokenScopes, now_secs};
+use crate::config::KeyServerJwtConfig;
+use crate::gateway::oauth::{GatewayKeyPair, JwkSet};
+use crate::secrets::SecretResolver;
+use crate::{Error, Result};
+
+/// Claims of a gateway-issued JWT.
+#[derive(Debug, Serialize, Deserialize)]
+struct GatewayTokenClaims {
+    iss: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aud: Option<String>,
+    sub: String,
+    jti: String,
+    iat: u64,
+    exp: u64,
+    email: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<String>,
+    /// OIDC issuer that authenticated the subject.
+    idp: String,
+    #[serde(default)]
+    backends: Vec<String>,
+    #[serde(default)]
+    tools: Vec<String>,
+    #[serde(default)]
+    rate_limit: u32,
+}
+
+/// Signs temporary tokens as JWTs and verifies them.
+pub struct JwtIssuer {
+    key: GatewayKeyPair,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    issuer: String,
+    audience: Option<String>,
+    /// Revoked JTI → expiry of the token.
+    revoked_jtis: DashMap<String, u64>,
+    /// Subject → tokens issued at or before this time are revoked.
+    revoked_subjects: DashMap<String, u64>,
+}
+
+impl JwtIssuer {
+    /// Build an issuer from `config`, resolving the signing key reference
+    /// or generating a key when none is configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signing key cannot be resolved or is not a
+    /// P-256 private key.
+    pub fn from_config(config: &KeyServerJwtConfig) -> Result<Self> {
+        let key = match &config.signing_key {
+            Some(reference) => {
+                let pem = SecretResolver::new().resolve(reference)?;
+                GatewayKeyPair::from_pem(&pem, config.key_id.clone())
+                    .map_err(|e| Error::Config(format!("key_server.jwt.signing_key: {e}")))?
+            }
+            None => GatewayKeyPair::generate().map_err(Error::Config)?,
+        };
+        let info = key.key_info();
+        let encoding = EncodingKey::from_ec_pem(info.private_key_pem.as_bytes())
+            .map_err(|e| Error::Config(format!("key_server.jwt.signing_key: {e}")))?;
+        let decoding = DecodingKey::from_ec_components(&info.x, &info.y)
+            .map_err(|e| Error::Config(format!("key_server.jwt.signing_key: {e}")))?;
+        Ok(Self {
+            key,
+            encoding,
+            decoding,
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+            revoked_jtis: DashMap::new(),
+            revoked_subjects: DashMap::new(),
+        })
+    }
+
+    /// `kid` of the signing key.
+    #[must_use]
+    pub fn key_id(&self) -> String {
+        self.key.key_info().kid
+    }
+
+    /// The public signing key, for `/.well-known/jwks.json`.
+    #[must_use]
+    pub fn jwks(&self) -> JwkSet {
+        self.key.jwks()
+    }
+
+    /// Sign `token` (its bearer value is ignored) as a JWT.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encoding fails.
+    pub fn sign(&self, token: &TemporaryToken) -> Result<String> {
+        let claims = GatewayTokenClaims {
+            iss: self.issuer.clone(),
+            aud: self.audience.clone(),
+            sub: token.identity.subject.clone(),
+            jti: token.jti.clone(),
+            iat: token.iat,
+            exp: token.exp,
+            email: token.identity.email.clone(),
+            name: token.identity.name.clone(),
+            groups: token.identity.groups.clone(),
+            idp: token.identity.issuer.clone(),
+            backends: token.scopes.backends.clone(),
+            tools: token.scopes.tools.clone(),
+            rate_limit: token.scopes.rate_limit,
+        };
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(self.key_id());
+        jsonwebtoken::encode(&header, &claims, &self.encoding)
+            .map_err(|e| Error::Internal(format!("JWT signing failed: {e}")))
+    }
+
+    /// Verify a JWT issued with this key and rebuild its token. Returns
+    /// `None` when the signature, issuer, audience or expiry does not check
+    /// out, or the token was revoked here.
+    #[must_use]
+    pub fn verify(&self, bearer: &str) -> Option<TemporaryToken> {
+        let mut validation = Validation::new(Algorithm::ES256);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        let claims =
+            match jsonwebtoken::decode::<GatewayTokenClaims>(bearer, &self.decoding, &validation) {
+                Ok(data) => data.claims,
+                Err(e) => {
+                    debug!(error = %e, "Gateway JWT verification failed");
+                    return None;
+                }
+            };
+        if self.revoked_jtis.contains_key(&claims.jti)
+            || self
+                .revoked_subjects
+                .get(&claims.sub)
+                .is_some_and(|cutoff| claims.iat <= *cutoff)
+        {
+            debug!(jti = %claims.jti, "Gateway JWT is revoked");
+            return None;
+        }
+        Some(TemporaryToken {
+            jti: claims.jti,
+            token: bearer.to_string(),
+            identity: VerifiedIdentity {
+                subject: claims.sub,
+                email: claims.email,
+                name: claims.name,
+                groups: claims.groups,
+                issuer: claims.idp,
+                claims: serde_json::Map::new(),
+            },
+            scopes: TokenScopes {
+                backends: claims.backends,
+                tools: claims.tools,
+                rate_limit: claims.rate_limit,
+            },
+            iat: claims.iat,
+            exp: claims.exp,
+            client_ip: None,
+        })
+    }
+
+    /// Reject the token `jti` until `until` (its latest possible expiry).
+    pub fn revoke_jti(&self, jti: &str, until: u64) {
+        let now = now_secs();
+        self.revoked_jtis.retain(|_, exp| *exp > now);
+        self.revoked_jtis.insert(jti.to_string(), until);
+    }
+
+    /// Reject every token of `subject` issued up to now.
+    pub fn revoke_subject(&self, subject: &str) {
+        self.revoked_subjects
+            .insert(subject.to_string(), now_secs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issuer(audience: Option<&str>) -> JwtIssuer {
+        JwtIssuer::from_config(&KeyServerJwtConfig {
+            audience: audience.map(str::to_string),
+            ..KeyServerJwtConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn token(subject: &str, iat: u64) -> TemporaryToken {
+        TemporaryToken {
+            jti: format!("jti-{subject}-{iat}"),
+            token: String::new(),
+            identity: VerifiedIdentity {
+                subject: subject.to_string(),
+                email: format!("{subject}@company.com"),
+                name: None,
+                groups: vec!["eng".to_string()],
+                issuer: "https://accounts.google.com".to_string(),
+                claims: serde_json::Map::new(),
+            },
+            scopes: TokenScopes {
+                backends: vec!["tavily".to_string()],
+                tools: Vec::new(),
+                rate_limit: 100,
+            },
+            iat,
+            exp: iat + 3600,
+            client_ip: None,
+        }
+    }
+
+    #[test]
+    fn signed_tokens_round_trip_identity_and_scopes() {
+        // GIVEN: an issuer and a token
+        let jwt = issuer(Some("mcp-gateway"));
+        let issued = token("alice", now_secs());
+
+        // WHEN: signing and verifying it
+        let bearer = jwt.sign(&issued).unwrap();
+        let verified = jwt.verify(&bearer).expect("valid JWT");
+
+        // THEN: identity and scopes survive, and the header names the JWKS key
+        assert_eq!(verified.jti, issued.jti);
+        assert_eq!(verified.token, bearer);
+        assert_eq!(verified.identity.email, "alice@company.com");
+        assert_eq!(verified.identity.groups, vec!["eng"]);
+        assert_eq!(verified.scopes.backends, vec!["tavily"]);
+        assert_eq!(verified.scopes.rate_limit, 100);
+        let header = jsonwebtoken::decode_header(&bearer).unwrap();
+        assert_eq!(header.kid.as_deref(), Some(jwt.jwks().keys[0].kid.as_str()));
+    }
+
+    #[test]
+    fn foreign_expired_and_mismatched_tokens_are_rejected() {
+        let jwt = issuer(Some("mcp-gateway"));
+
+        // Another key
+        let foreign = issuer(Some("mcp-gateway")).sign(&token("alice", now_secs()));
+        assert!(jwt.verify(&foreign.unwrap()).is_none());
+
+        // Expired
+        let expired = jwt.sign(&token("alice", now_secs() - 7200)).unwrap();
+        assert!(jwt.verify(&expired).is_none());
+
+        // Other audience
+        let other_aud = issuer(None);
+        let bearer = other_aud.sign(&token("alice", now_secs())).unwrap();
+        assert!(other_aud.verify(&bearer).is_some());
+        assert!(jwt.verify(&bearer).is_none());
+
+        // Opaque bearer
+        assert!(jwt.verify("mcpgw_abc").is_none());
+    }
+
+    #[test]
+    fn revoked_jtis_and_subjects_are_rejected() {
+        // GIVEN: tokens for two subjects
+        let jwt = issuer(None);
+        let now = now_secs();
+        let alice = jwt.sign(&token("alice", now - 10)).unwrap();
+        let alice_old = jwt.sign(&token("alice", now - 20)).unwrap();
+        let bob = jwt.sign(&token("bob", now - 10)).unwrap();
+
+        // WHEN: revoking one of alice's tokens, then all of bob's
+        jwt.revoke_jti(&format!("jti-alice-{}", now - 10), now + 3600);
+        jwt.revoke_subject("bob");
+
+        // THEN: only the remaining token verifies
+        assert!(jwt.verify(&alice).is_none());
+        assert!(jwt.verify(&alice_old).is_some());
+        assert!(jwt.verify(&bob).is_none());
+    }
+
+    #[test]
+    fn instances_sharing_a_signing_key_accept_each_others_tokens() {
+        // GIVEN: two issuers loaded from the same PEM key
+        let pem = GatewayKeyPair::generate()
+            .unwrap()
+            .key_info()
+            .private_key_pem;
+        let config = KeyServerJwtConfig {
+            signing_key: Some(pem),
+            ..KeyServerJwtConfig::default()
+        };
+        let first = JwtIssuer::from_config(&config).unwrap();
+        let second = JwtIssuer::from_config(&config).unwrap();
+
+        // WHEN: one instance issues a token
+        let bearer = first.sign(&token("alice", now_secs())).unwrap();
+
+        // THEN: the other verifies it and publishes the same kid
+        assert!(second.verify(&bearer).is_some());
+        assert_eq!(first.key_id(), second.key_id());
+    }
+}
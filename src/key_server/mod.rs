@@ -7,7 +7,15 @@
 //!
 //! 1. **Token Exchange**: Accept an OIDC identity token (`POST /auth/token`),
 //!    verify it against a configured OIDC issuer, map the identity to scopes
-//!    via the policy engine, and return a short-lived opaque bearer token.
+//!    via the policy engine, and return a short-lived opaque bearer token —
+//!    or, with `key_server.jwt`, a signed JWT that other services verify
+//!    against `/.well-known/jwks.json` ([`jwt`]).
+//!
+//!    With `key_server.refresh_token_ttl_secs`, a rotating refresh token
+//!    renews the session on the same endpoint (see [`handler`]).
+//!
+//!    Issuance is rate limited per client IP and per identity, and unusual
+//!    patterns raise audit alerts ([`issuance`]).
 //!
 //! 2. **Validation**: The auth middleware calls [`KeyServer::validate_token`] as
 //!    a secondary validation path after the static key check.
@@ -33,6 +41,8 @@
 
 pub mod audit;
 pub mod handler;
+pub mod issuance;
+pub mod jwt;
 pub mod oidc;
 pub mod policy;
 pub mod store;
@@ -47,6 +57,8 @@ use oidc::VerifiedIdentity;
 use policy::RequestedScopes;
 
 pub use audit::AuditEvent;
+pub use issuance::IssuanceGuard;
+pub use jwt::JwtIssuer;
 pub use oidc::{JwksCache, OidcVerifier};
 pub use policy::PolicyEngine;
 pub use store::{InMemoryTokenStore, TemporaryToken, TokenStore};
@@ -65,6 +77,10 @@ pub struct KeyServer {
     pub policy: Arc<PolicyEngine>,
     /// Key server configuration
     pub config: KeyServerConfig,
+    /// JWT signer; `None` issues opaque tokens.
+    pub jwt: Option<Arc<JwtIssuer>>,
+    /// Issuance rate limits and anomaly detection
+    pub issuance: IssuanceGuard,
 }
 
 impl KeyServer {
@@ -72,26 +88,58 @@ impl KeyServer {
     #[must_use]
     pub fn new(config: KeyServerConfig) -> Self {
         let store = Arc::new(InMemoryTokenStore::new());
-        let oidc = Arc::new(OidcVerifier::new(config.oidc.clone()));
-        let policy = Arc::new(PolicyEngine::new(config.policies.clone()));
+        let policy =
+            PolicyEngine::new(config.policies.clone()).with_bundles(config.scope_bundles.clone());
+        let oidc =
+            Arc::new(OidcVerifier::new(config.oidc.clone()).with_claims(policy.claim_names()));
+        let policy = Arc::new(policy);
+        let issuance = IssuanceGuard::new(config.issuance.clone());
 
         Self {
             store,
             oidc,
             policy,
             config,
+            jwt: None,
+            issuance,
         }
     }
 
+    /// Keep issued tokens in `store` instead of in memory.
+    #[must_use]
+    pub fn with_store(mut self, store: Arc<dyn TokenStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Issue signed JWTs with `issuer` instead of opaque tokens.
+    #[must_use]
+    pub fn with_jwt(mut self, issuer: JwtIssuer) -> Self {
+        self.jwt = Some(Arc::new(issuer));
+        self
+    }
+
     /// Validate a bearer token from an incoming request.
     ///
     /// Returns the [`AuthenticatedClient`] and the associated [`TemporaryToken`]
     /// if the token is valid and not expired/revoked. Returns `None` otherwise.
+    ///
+    /// With JWT issuance, a token missing from the store — issued by another
+    /// instance sharing the signing key, or before a restart — is accepted
+    /// when its signature verifies and the store has not revoked it.
     pub async fn validate_token(
         &self,
         token: &str,
     ) -> Option<(AuthenticatedClient, TemporaryToken)> {
-        let temp = self.store.get(token).await?;
+        let temp = if let Some(temp) = self.store.get(token).await {
+            temp
+        } else {
+            let temp = self.jwt.as_ref()?.verify(token)?;
+            if self.store.is_revoked(&temp.jti).await {
+                return None;
+            }
+            temp
+        };
 
         let client = AuthenticatedClient {
             name: oidc_client_identity_key(&temp.identity),
@@ -179,6 +227,7 @@ mod tests {
             name: None,
             groups: vec![],
             issuer: issuer.to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
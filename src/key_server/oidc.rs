@@ -10,7 +10,8 @@
 //! 3. Fetch the provider's JWKS (cached for 1 hour; refreshed on unknown `kid`).
 //! 4. Verify the JWT signature and standard claims (`exp`, `iat`, `aud`, `iss`).
 //! 5. Apply domain/audience restrictions from the provider config.
-//! 6. Return a [`VerifiedIdentity`] with the extracted claims.
+//! 6. Return a [`VerifiedIdentity`] with the extracted claims, keeping only
+//!    the custom claims that access policies match on.
 //!
 //! # Security properties
 //!
@@ -116,6 +117,10 @@ pub struct VerifiedIdentity {
     pub groups: Vec<String>,
     /// Issuer URL.
     pub issuer: String,
+    /// Custom claims access policies match on (see
+    /// [`OidcVerifier::with_claims`]); other claims are dropped.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub claims: serde_json::Map<String, serde_json::Value>,
 }
 
 impl VerifiedIdentity {
@@ -164,6 +169,9 @@ struct IdTokenClaims {
     /// Groups (custom claim)
     #[serde(default)]
     groups: Option<Vec<String>>,
+    /// Every other claim.
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Cached JWKS entry.
@@ -323,6 +331,8 @@ impl Default for JwksCache {
 pub struct OidcVerifier {
     providers: Vec<KeyServerProviderConfig>,
     jwks_cache: Arc<JwksCache>,
+    /// Custom claims copied into [`VerifiedIdentity::claims`].
+    retained_claims: Vec<String>,
 }
 
 impl OidcVerifier {
@@ -332,9 +342,18 @@ impl OidcVerifier {
         Self {
             providers,
             jwks_cache: Arc::new(JwksCache::new()),
+            retained_claims: Vec::new(),
         }
     }
 
+    /// Keep the custom claims `names` in verified identities, for policy
+    /// matching.
+    #[must_use]
+    pub fn with_claims(mut self, names: Vec<String>) -> Self {
+        self.retained_claims = names;
+        self
+    }
+
     /// Test-only constructor injecting a custom HTTP client into the JWKS
     /// cache. See [`JwksCache::with_http_client`] — same MIK-6729 rationale.
     #[cfg(test)]
@@ -345,6 +364,7 @@ impl OidcVerifier {
         Self {
             providers,
             jwks_cache: Arc::new(JwksCache::with_http_client(http)),
+            retained_claims: Vec::new(),
         }
     }
 
@@ -443,12 +463,20 @@ impl OidcVerifier {
             }
         }
 
+        let mut extra = claims.extra;
+        let retained = self
+            .retained_claims
+            .iter()
+            .filter_map(|name| Some((name.clone(), extra.remove(name)?)))
+            .collect();
+
         Ok(VerifiedIdentity {
             subject: claims.sub,
             email: claims.email.unwrap_or_default(),
             name: claims.name,
             groups: claims.groups.unwrap_or_default(),
             issuer: claims.iss,
+            claims: retained,
         })
     }
 
@@ -718,6 +746,7 @@ mod tests {
             name: Some("Alice".to_string()),
             groups: vec!["ml-engineers".to_string()],
             issuer: "https://accounts.google.com".to_string(),
+            claims: serde_json::Map::new(),
         };
 
         // WHEN: serialized to JSON
@@ -738,6 +767,7 @@ mod tests {
             name: None,
             groups: vec![],
             issuer: "https://idp/a".to_string(),
+            claims: serde_json::Map::new(),
         };
         let b = VerifiedIdentity {
             subject: "c".to_string(),
@@ -745,6 +775,7 @@ mod tests {
             name: None,
             groups: vec![],
             issuer: "https://idp/a:b".to_string(),
+            claims: serde_json::Map::new(),
         };
         // Naive format collides: "oidc:https://idp/a:b:c" for both.
         assert_eq!(
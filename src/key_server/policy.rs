@@ -19,8 +19,19 @@
 //! | `issuer` | Exact OIDC issuer URL |
 //! | `email` | Exact email address |
 //! | `group` | Any group in the identity's `groups` list |
+//! | `groups` | Any of these patterns matches any of the identity's groups |
+//! | `claims` | Each named claim (dotted path for nested claims) matches one of its patterns |
 //!
-//! All non-`None` fields must match for the rule to fire.
+//! All non-`None` fields must match for the rule to fire. In `groups` and
+//! `claims`, a trailing `*` matches a prefix, as in scopes.
+//!
+//! ## Effects and bundles
+//!
+//! A rule with `effect: deny` rejects the identities it matches, so a narrow
+//! deny rule placed first carves an exception out of broader allow rules. An
+//! allow rule grants its `scopes` merged with the named `scope_bundles` it
+//! lists: backend and tool lists are unioned, and the most generous rate
+//! limit wins.
 //!
 //! ## Scope intersection
 //!
@@ -29,10 +40,12 @@
 //! what it asked for. Requesting no specific scopes grants everything the policy
 //! allows (the common case).
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use tracing::{debug, warn};
 
-use crate::config::KeyServerPolicyConfig;
+use crate::config::{KeyServerPolicyConfig, PolicyEffect, PolicyScopesConfig};
 
 use super::oidc::VerifiedIdentity;
 use super::store::TokenScopes;
@@ -40,19 +53,46 @@ use super::store::TokenScopes;
 /// The access policy engine.
 pub struct PolicyEngine {
     rules: Vec<KeyServerPolicyConfig>,
+    bundles: BTreeMap<String, PolicyScopesConfig>,
 }
 
 impl PolicyEngine {
     /// Build the engine from the ordered rule list from configuration.
     #[must_use]
     pub fn new(rules: Vec<KeyServerPolicyConfig>) -> Self {
-        Self { rules }
+        Self {
+            rules,
+            bundles: BTreeMap::new(),
+        }
+    }
+
+    /// Resolve rule `bundles` against these named scope sets.
+    #[must_use]
+    pub fn with_bundles(mut self, bundles: BTreeMap<String, PolicyScopesConfig>) -> Self {
+        self.bundles = bundles;
+        self
+    }
+
+    /// Top-level names of the ID token claims the rules match on, for
+    /// [`OidcVerifier::with_claims`](super::oidc::OidcVerifier::with_claims).
+    #[must_use]
+    pub fn claim_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .rules
+            .iter()
+            .flat_map(|rule| rule.match_criteria.claims.keys())
+            .map(|path| path.split('.').next().unwrap_or(path).to_string())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
     }
 
     /// Resolve the effective scopes for a verified identity.
     ///
     /// Evaluates rules in order; returns the scopes of the first matching rule.
-    /// If no rule matches, returns `None` (the caller should reject the request).
+    /// If no rule matches, or the first match is a deny rule, returns `None`
+    /// (the caller should reject the request).
     #[must_use]
     pub fn resolve_scopes(
         &self,
@@ -65,24 +105,76 @@ impl PolicyEngine {
                 issuer: rule.match_criteria.issuer.clone(),
                 email: rule.match_criteria.email.clone(),
                 group: rule.match_criteria.group.clone(),
+                groups: rule.match_criteria.groups.clone(),
+                claims: rule.match_criteria.claims.clone(),
             };
-            let policy_scopes = PolicyScopes {
-                backends: rule.scopes.backends.clone(),
-                tools: rule.scopes.tools.clone(),
-                rate_limit: rule.scopes.rate_limit,
-            };
-            if matches_rule(&criteria, identity) {
-                debug!(
-                    email = %identity.email,
-                    issuer = %identity.issuer,
-                    "Policy rule matched"
-                );
-                return Some(apply_intersection(&policy_scopes, requested));
+            if !matches_rule(&criteria, identity) {
+                continue;
             }
+            if rule.effect == PolicyEffect::Deny {
+                debug!(email = %identity.email, "Deny policy rule matched");
+                return None;
+            }
+            debug!(
+                email = %identity.email,
+                issuer = %identity.issuer,
+                "Policy rule matched"
+            );
+            return self
+                .rule_scopes(rule)
+                .map(|policy_scopes| apply_intersection(&policy_scopes, requested));
         }
         debug!(email = %identity.email, "No policy rule matched");
         None
     }
+
+    /// The rule's inline scopes merged with its bundles. `None` if it grants
+    /// nothing, which would otherwise read as "everything".
+    fn rule_scopes(&self, rule: &KeyServerPolicyConfig) -> Option<PolicyScopes> {
+        let mut parts: Vec<&PolicyScopesConfig> = rule.scopes.iter().collect();
+        for name in &rule.bundles {
+            if let Some(bundle) = self.bundles.get(name) {
+                parts.push(bundle);
+            } else {
+                warn!(bundle = %name, "Policy rule references unknown scope bundle");
+            }
+        }
+        match parts.as_slice() {
+            [] => None,
+            [only] => Some(PolicyScopes {
+                backends: only.backends.clone(),
+                tools: only.tools.clone(),
+                rate_limit: only.rate_limit,
+            }),
+            _ => Some(PolicyScopes {
+                backends: union_scope_lists(parts.iter().map(|p| &p.backends)),
+                tools: union_scope_lists(parts.iter().map(|p| &p.tools)),
+                // 0 = unlimited, the most generous of all.
+                rate_limit: if parts.iter().any(|p| p.rate_limit == 0) {
+                    0
+                } else {
+                    parts.iter().map(|p| p.rate_limit).max().unwrap_or(0)
+                },
+            }),
+        }
+    }
+}
+
+/// Union of scope lists; a wildcard list (empty or containing `"*"`) makes
+/// the union a wildcard.
+fn union_scope_lists<'a>(lists: impl Iterator<Item = &'a Vec<String>>) -> Vec<String> {
+    let mut union: Vec<String> = Vec::new();
+    for list in lists {
+        if list.is_empty() || list.iter().any(|item| item == "*") {
+            return vec!["*".to_string()];
+        }
+        for item in list {
+            if !union.contains(item) {
+                union.push(item.clone());
+            }
+        }
+    }
+    union
 }
 
 /// Scopes requested by the client in the token exchange request.
@@ -124,7 +216,44 @@ fn matches_rule(criteria: &MatchCriteria, identity: &VerifiedIdentity) -> bool {
         return false;
     }
 
-    true
+    if !criteria.groups.is_empty()
+        && !identity
+            .groups
+            .iter()
+            .any(|g| criteria.groups.iter().any(|p| scope_matches(p, g)))
+    {
+        return false;
+    }
+
+    criteria.claims.iter().all(|(path, patterns)| {
+        claim_values(&identity.claims, path)
+            .iter()
+            .any(|value| patterns.iter().any(|p| scope_matches(p, value)))
+    })
+}
+
+/// The values of the claim at dotted `path`, as strings. A list claim yields
+/// its scalar elements; a missing claim or an object yields nothing.
+fn claim_values(claims: &serde_json::Map<String, serde_json::Value>, path: &str) -> Vec<String> {
+    let mut segments = path.split('.');
+    let Some(mut value) = segments.next().and_then(|first| claims.get(first)) else {
+        return Vec::new();
+    };
+    for segment in segments {
+        match value.get(segment) {
+            Some(next) => value = next,
+            None => return Vec::new(),
+        }
+    }
+    let scalar = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Bool(_) | serde_json::Value::Number(_) => Some(v.to_string()),
+        _ => None,
+    };
+    match value {
+        serde_json::Value::Array(items) => items.iter().filter_map(scalar).collect(),
+        other => scalar(other).into_iter().collect(),
+    }
 }
 
 /// Compute the intersection of policy-granted and client-requested scopes.
@@ -199,6 +328,12 @@ pub struct MatchCriteria {
     /// Group membership
     #[serde(skip_serializing_if = "Option::is_none")]
     pub group: Option<String>,
+    /// Membership in any of these groups (trailing `*` = prefix)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    /// Claim path → accepted values (trailing `*` = prefix)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub claims: BTreeMap<String, Vec<String>>,
 }
 
 /// Scopes granted by a policy rule.
@@ -218,7 +353,9 @@ pub struct PolicyScopes {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{KeyServerPolicyConfig, PolicyMatchConfig, PolicyScopesConfig};
+    use crate::config::{
+        KeyServerPolicyConfig, PolicyEffect, PolicyMatchConfig, PolicyScopesConfig,
+    };
 
     fn make_identity(email: &str, issuer: &str, groups: &[&str]) -> VerifiedIdentity {
         VerifiedIdentity {
@@ -230,6 +367,7 @@ mod tests {
                 .map(std::string::ToString::to_string)
                 .collect(),
             issuer: issuer.to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
@@ -244,12 +382,15 @@ mod tests {
                 issuer: None,
                 email: None,
                 group: None,
+                ..PolicyMatchConfig::default()
             },
-            scopes: PolicyScopesConfig {
+            effect: PolicyEffect::Allow,
+            bundles: Vec::new(),
+            scopes: Some(PolicyScopesConfig {
                 backends: vec!["*".to_string()],
                 tools: vec!["*".to_string()],
                 rate_limit: 100,
-            },
+            }),
         }
     }
 
@@ -260,12 +401,15 @@ mod tests {
                 issuer: Some("https://token.actions.githubusercontent.com".to_string()),
                 email: None,
                 group: None,
+                ..PolicyMatchConfig::default()
             },
-            scopes: PolicyScopesConfig {
+            effect: PolicyEffect::Allow,
+            bundles: Vec::new(),
+            scopes: Some(PolicyScopesConfig {
                 backends: vec!["tavily".to_string(), "brave".to_string()],
                 tools: vec!["tavily-search".to_string(), "brave_*".to_string()],
                 rate_limit: 50,
-            },
+            }),
         }
     }
 
@@ -345,12 +489,15 @@ mod tests {
                 domain: None,
                 issuer: None,
                 group: None,
+                ..PolicyMatchConfig::default()
             },
-            scopes: PolicyScopesConfig {
+            effect: PolicyEffect::Allow,
+            bundles: Vec::new(),
+            scopes: Some(PolicyScopesConfig {
                 backends: vec!["*".to_string()],
                 tools: vec!["*".to_string()],
                 rate_limit: 0,
-            },
+            }),
         };
         let engine = make_engine(vec![rule]);
         let identity = make_identity("admin@company.com", "https://accounts.google.com", &[]);
@@ -372,12 +519,15 @@ mod tests {
                 domain: None,
                 issuer: None,
                 email: None,
+                ..PolicyMatchConfig::default()
             },
-            scopes: PolicyScopesConfig {
+            effect: PolicyEffect::Allow,
+            bundles: Vec::new(),
+            scopes: Some(PolicyScopesConfig {
                 backends: vec!["*".to_string()],
                 tools: vec!["*".to_string()],
                 rate_limit: 0,
-            },
+            }),
         };
         let engine = make_engine(vec![rule]);
         let identity = make_identity(
@@ -393,6 +543,207 @@ mod tests {
         assert!(scopes.is_some());
     }
 
+    fn rule(
+        match_criteria: PolicyMatchConfig,
+        effect: PolicyEffect,
+        bundles: &[&str],
+        scopes: Option<PolicyScopesConfig>,
+    ) -> KeyServerPolicyConfig {
+        KeyServerPolicyConfig {
+            match_criteria,
+            effect,
+            bundles: bundles.iter().map(ToString::to_string).collect(),
+            scopes,
+        }
+    }
+
+    fn scopes(backends: &[&str], tools: &[&str], rate_limit: u32) -> PolicyScopesConfig {
+        PolicyScopesConfig {
+            backends: backends.iter().map(ToString::to_string).collect(),
+            tools: tools.iter().map(ToString::to_string).collect(),
+            rate_limit,
+        }
+    }
+
+    fn with_claims(mut identity: VerifiedIdentity, claims: serde_json::Value) -> VerifiedIdentity {
+        if let serde_json::Value::Object(claims) = claims {
+            identity.claims = claims;
+        }
+        identity
+    }
+
+    #[test]
+    fn resolve_scopes_matches_group_patterns() {
+        // GIVEN: a rule for any `eng-*` group
+        let engine = make_engine(vec![rule(
+            PolicyMatchConfig {
+                groups: vec!["eng-*".to_string(), "sre".to_string()],
+                ..PolicyMatchConfig::default()
+            },
+            PolicyEffect::Allow,
+            &[],
+            Some(scopes(&["tavily"], &[], 10)),
+        )]);
+        let issuer = "https://accounts.google.com";
+
+        // THEN: any matching group fires it
+        let platform = make_identity("a@company.com", issuer, &["eng-platform"]);
+        let sre = make_identity("b@company.com", issuer, &["sales", "sre"]);
+        let sales = make_identity("c@company.com", issuer, &["sales"]);
+        let none = RequestedScopes::default();
+        assert!(engine.resolve_scopes(&platform, &none).is_some());
+        assert!(engine.resolve_scopes(&sre, &none).is_some());
+        assert!(engine.resolve_scopes(&sales, &none).is_none());
+    }
+
+    #[test]
+    fn resolve_scopes_matches_nested_and_list_claims() {
+        // GIVEN: a rule on a nested Keycloak roles claim and a scalar claim
+        let engine = make_engine(vec![rule(
+            PolicyMatchConfig {
+                claims: BTreeMap::from([
+                    (
+                        "realm_access.roles".to_string(),
+                        vec!["mcp-admin".to_string()],
+                    ),
+                    ("department".to_string(), vec!["ml*".to_string()]),
+                ]),
+                ..PolicyMatchConfig::default()
+            },
+            PolicyEffect::Allow,
+            &[],
+            Some(scopes(&["*"], &["*"], 0)),
+        )]);
+        let base = make_identity("alice@company.com", "https://idp.example", &[]);
+        let none = RequestedScopes::default();
+
+        // THEN: both claims must match
+        let admin = with_claims(
+            base.clone(),
+            serde_json::json!({
+                "realm_access": { "roles": ["viewer", "mcp-admin"] },
+                "department": "ml-research",
+            }),
+        );
+        assert!(engine.resolve_scopes(&admin, &none).is_some());
+        let viewer = with_claims(
+            base.clone(),
+            serde_json::json!({
+                "realm_access": { "roles": ["viewer"] },
+                "department": "ml-research",
+            }),
+        );
+        assert!(engine.resolve_scopes(&viewer, &none).is_none());
+        assert!(engine.resolve_scopes(&base, &none).is_none());
+    }
+
+    #[test]
+    fn deny_rule_carves_out_an_exception() {
+        // GIVEN: a deny rule for contractors ahead of a company-wide rule
+        let engine = make_engine(vec![
+            rule(
+                PolicyMatchConfig {
+                    claims: BTreeMap::from([(
+                        "employment_type".to_string(),
+                        vec!["contractor".to_string()],
+                    )]),
+                    ..PolicyMatchConfig::default()
+                },
+                PolicyEffect::Deny,
+                &[],
+                None,
+            ),
+            company_rule(),
+        ]);
+        let employee = make_identity("alice@company.com", "https://idp.example", &[]);
+        let contractor = with_claims(
+            employee.clone(),
+            serde_json::json!({ "employment_type": "contractor" }),
+        );
+
+        // THEN: the contractor is denied, the employee is not
+        let none = RequestedScopes::default();
+        assert!(engine.resolve_scopes(&contractor, &none).is_none());
+        assert!(engine.resolve_scopes(&employee, &none).is_some());
+    }
+
+    #[test]
+    fn bundles_merge_with_inline_scopes() {
+        // GIVEN: two bundles and a rule granting both plus an extra tool
+        let bundles = BTreeMap::from([
+            (
+                "search".to_string(),
+                scopes(&["tavily", "brave"], &["*search*"], 60),
+            ),
+            ("mail".to_string(), scopes(&["gmail"], &["gmail_read"], 30)),
+        ]);
+        let engine = PolicyEngine::new(vec![rule(
+            PolicyMatchConfig {
+                domain: Some("company.com".to_string()),
+                ..PolicyMatchConfig::default()
+            },
+            PolicyEffect::Allow,
+            &["search", "mail"],
+            Some(scopes(&["brave"], &["calendar_list"], 20)),
+        )])
+        .with_bundles(bundles);
+        let identity = make_identity("alice@company.com", "https://idp.example", &[]);
+
+        // WHEN: resolve
+        let scopes = engine
+            .resolve_scopes(&identity, &RequestedScopes::default())
+            .unwrap();
+
+        // THEN: lists are unioned and the highest rate limit wins
+        assert_eq!(scopes.backends, vec!["brave", "tavily", "gmail"]);
+        assert_eq!(
+            scopes.tools,
+            vec!["calendar_list", "*search*", "gmail_read"]
+        );
+        assert_eq!(scopes.rate_limit, 60);
+    }
+
+    #[test]
+    fn union_of_a_wildcard_list_is_a_wildcard() {
+        let all = vec!["*".to_string()];
+        let some = vec!["tavily".to_string()];
+        assert_eq!(union_scope_lists([&some, &all].into_iter()), vec!["*"]);
+        assert_eq!(
+            union_scope_lists([&some, &Vec::new()].into_iter()),
+            vec!["*"]
+        );
+        assert_eq!(
+            union_scope_lists([&some, &some].into_iter()),
+            vec!["tavily"]
+        );
+    }
+
+    #[test]
+    fn claim_names_lists_top_level_claims_once() {
+        let claims = |paths: &[&str]| PolicyMatchConfig {
+            claims: paths
+                .iter()
+                .map(|p| ((*p).to_string(), vec!["x".to_string()]))
+                .collect(),
+            ..PolicyMatchConfig::default()
+        };
+        let engine = make_engine(vec![
+            rule(
+                claims(&["realm_access.roles", "department"]),
+                PolicyEffect::Deny,
+                &[],
+                None,
+            ),
+            rule(
+                claims(&["realm_access.groups"]),
+                PolicyEffect::Deny,
+                &[],
+                None,
+            ),
+        ]);
+        assert_eq!(engine.claim_names(), vec!["department", "realm_access"]);
+    }
+
     // ── intersect_scope_list ──────────────────────────────────────────────
 
     #[test]
@@ -3,9 +3,20 @@
 
 //! Token store — persistence layer for issued temporary tokens.
 //!
-//! The [`TokenStore`] trait abstracts over storage backends. The only current
-//! implementation is [`InMemoryTokenStore`], backed by a `DashMap` with a
-//! background reaper that evicts expired tokens every 60 seconds.
+//! The [`TokenStore`] trait abstracts over storage backends:
+//!
+//! - [`InMemoryTokenStore`], backed by a `DashMap` with a background reaper
+//!   that evicts expired tokens every 60 seconds (the default);
+//! - `sqlite::SqliteTokenStore` and `redis::RedisTokenStore`, which keep
+//!   tokens across restarts and share them between replicas (behind the
+//!   `token-store-sqlite` / `token-store-redis` features).
+//!
+//! Stores also hold refresh token families (see [`RefreshFamily`]), and
+//! revoking a subject ends its families too.
+//!
+//! The persistent stores keep a revocation list of JTIs revoked before they
+//! expired. [`open`] reconciles it at startup: tokens on the list, or already
+//! expired, are dropped before the gateway serves requests.
 //!
 //! # Design
 //!
@@ -20,9 +31,16 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use dashmap::DashMap;
 use rand::RngExt;
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use tracing::{debug, info};
 
 use super::oidc::VerifiedIdentity;
+use crate::Result;
+use crate::config::KeyServerStoreConfig;
+
+#[cfg(feature = "token-store-redis")]
+pub mod redis;
+#[cfg(feature = "token-store-sqlite")]
+pub mod sqlite;
 
 /// A temporary gateway token issued after OIDC verification.
 #[derive(Clone, Serialize, Deserialize)]
@@ -84,6 +102,57 @@ pub struct TokenScopes {
     pub rate_limit: u32,
 }
 
+/// A chain of rotating refresh tokens started by one token exchange.
+///
+/// Each refresh spends the family's current refresh token and issues the
+/// next one. Presenting a spent token means it was copied, so the handler
+/// revokes the whole family.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshFamily {
+    /// Family identifier, embedded in each of its refresh tokens.
+    pub id: String,
+    /// SHA-256 of the current refresh token; earlier tokens are spent.
+    pub current: String,
+    /// Verified identity of the session.
+    pub identity: VerifiedIdentity,
+    /// Scopes granted at the original exchange.
+    pub scopes: TokenScopes,
+    /// JTI of the latest access token issued from this family.
+    pub access_jti: String,
+    /// The session cannot be renewed after this time (Unix epoch seconds).
+    pub exp: u64,
+}
+
+impl RefreshFamily {
+    /// Generate a refresh token for family `id`.
+    ///
+    /// Format: `mcpgwr_<family id>.<43-char URL-safe base64>`.
+    #[must_use]
+    pub fn generate_token(id: &str) -> String {
+        let random_bytes: [u8; 32] = rand::rng().random();
+        format!(
+            "mcpgwr_{id}.{}",
+            base64::Engine::encode(
+                &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+                random_bytes,
+            )
+        )
+    }
+
+    /// The family ID embedded in a refresh token, if it is one.
+    #[must_use]
+    pub fn id_of(token: &str) -> Option<&str> {
+        let (id, secret) = token.strip_prefix("mcpgwr_")?.split_once('.')?;
+        (!id.is_empty() && !secret.is_empty()).then_some(id)
+    }
+
+    /// Returns `true` if the session can no longer be renewed.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        now_secs() >= self.exp
+    }
+}
+
 /// Trait abstracting the token storage backend.
 ///
 /// Implementations must be `Send + Sync` because the token store is shared
@@ -103,23 +172,109 @@ pub trait TokenStore: Send + Sync + 'static {
     /// Returns `true` if the token existed and was removed.
     async fn revoke_by_jti(&self, jti: &str) -> bool;
 
-    /// Revoke all tokens for a given OIDC subject (e.g., on offboarding).
+    /// Revoke all tokens for a given OIDC subject (e.g., on offboarding),
+    /// including its refresh token families. Returns the number of access
+    /// tokens revoked.
     async fn revoke_by_subject(&self, subject: &str) -> usize;
 
     /// Count active (non-expired) tokens for a given OIDC subject.
     async fn count_for_subject(&self, subject: &str) -> usize;
 
-    /// Remove all expired tokens. Called periodically by the background reaper.
+    /// Remove all expired tokens and refresh families. Called periodically by
+    /// the background reaper; returns the number of access tokens removed.
     async fn reap_expired(&self) -> usize;
+
+    /// Store a new refresh token family.
+    async fn insert_refresh(&self, family: RefreshFamily);
+
+    /// Look up a refresh token family by ID. Returns `None` if it does not
+    /// exist or has expired.
+    async fn get_refresh(&self, id: &str) -> Option<RefreshFamily>;
+
+    /// Rotate family `id` from the refresh token hashed as `current` to the
+    /// one hashed as `next`, recording `access_jti` as its latest access
+    /// token.
+    ///
+    /// Returns `false` if the family is gone or `current` is not its refresh
+    /// token, so two clients racing with one token cannot both rotate it.
+    async fn rotate_refresh(&self, id: &str, current: &str, next: &str, access_jti: &str) -> bool;
+
+    /// Delete family `id`, ending the session's renewals.
+    async fn revoke_refresh(&self, id: &str);
+
+    /// JTIs revoked before their expiry, with that expiry. Empty for stores
+    /// without a revocation list.
+    async fn revoked(&self) -> Vec<(String, u64)> {
+        Vec::new()
+    }
+
+    /// Whether `jti` is on the revocation list.
+    async fn is_revoked(&self, _jti: &str) -> bool {
+        false
+    }
+
+    /// Drop expired tokens and tokens on the revocation list. Called once
+    /// at startup; returns the number of tokens dropped.
+    async fn reconcile(&self) -> usize {
+        self.reap_expired().await
+    }
 }
 
-/// In-memory token store backed by two `DashMap` indices.
+/// Open the token store `config` selects and reconcile it.
+///
+/// # Errors
+///
+/// Returns an error if the backend cannot be reached or the gateway was
+/// built without its feature.
+pub async fn open(config: &KeyServerStoreConfig) -> Result<Arc<dyn TokenStore>> {
+    let store: Arc<dyn TokenStore> = match config {
+        KeyServerStoreConfig::Memory => Arc::new(InMemoryTokenStore::new()),
+        #[cfg(feature = "token-store-sqlite")]
+        KeyServerStoreConfig::Sqlite { path } => {
+            Arc::new(sqlite::SqliteTokenStore::open(path).await?)
+        }
+        #[cfg(not(feature = "token-store-sqlite"))]
+        KeyServerStoreConfig::Sqlite { .. } => {
+            return Err(crate::Error::Config(
+                "key_server.store backend 'sqlite' needs a gateway built with the `token-store-sqlite` feature"
+                    .to_string(),
+            ));
+        }
+        #[cfg(feature = "token-store-redis")]
+        KeyServerStoreConfig::Redis { url, key_prefix } => {
+            let url = crate::secrets::SecretResolver::new().resolve(url)?;
+            Arc::new(redis::RedisTokenStore::open(&url, key_prefix).await?)
+        }
+        #[cfg(not(feature = "token-store-redis"))]
+        KeyServerStoreConfig::Redis { .. } => {
+            return Err(crate::Error::Config(
+                "key_server.store backend 'redis' needs a gateway built with the `token-store-redis` feature"
+                    .to_string(),
+            ));
+        }
+    };
+    let dropped = store.reconcile().await;
+    info!(dropped, "Reconciled key server token store");
+    Ok(store)
+}
+
+/// Current Unix time in seconds.
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+/// In-memory token store backed by `DashMap` indices.
 ///
 /// - `by_bearer`: bearer value → `TemporaryToken`  (O(1) validation)
 /// - `by_jti`:    JTI string  → bearer value       (O(1) revocation)
+/// - `refresh`:   family ID   → `RefreshFamily`
 pub struct InMemoryTokenStore {
     by_bearer: DashMap<String, TemporaryToken>,
     by_jti: DashMap<String, String>,
+    refresh: DashMap<String, RefreshFamily>,
 }
 
 impl InMemoryTokenStore {
@@ -129,6 +284,7 @@ impl InMemoryTokenStore {
         Self {
             by_bearer: DashMap::new(),
             by_jti: DashMap::new(),
+            refresh: DashMap::new(),
         }
     }
 
@@ -209,6 +365,8 @@ impl TokenStore for InMemoryTokenStore {
         for jti in jtis_to_revoke {
             self.revoke_by_jti(&jti).await;
         }
+        self.refresh
+            .retain(|_, family| family.identity.subject != subject);
         count
     }
 
@@ -234,8 +392,38 @@ impl TokenStore for InMemoryTokenStore {
                 debug!(jti = %token.jti, "Reaped expired token");
             }
         }
+        self.refresh.retain(|_, family| !family.is_expired());
         count
     }
+
+    async fn insert_refresh(&self, family: RefreshFamily) {
+        self.refresh.insert(family.id.clone(), family);
+    }
+
+    async fn get_refresh(&self, id: &str) -> Option<RefreshFamily> {
+        let family = self.refresh.get(id)?.clone();
+        if family.is_expired() {
+            self.refresh.remove(id);
+            return None;
+        }
+        Some(family)
+    }
+
+    async fn rotate_refresh(&self, id: &str, current: &str, next: &str, access_jti: &str) -> bool {
+        let Some(mut family) = self.refresh.get_mut(id) else {
+            return false;
+        };
+        if family.current != current || family.is_expired() {
+            return false;
+        }
+        family.current = next.to_string();
+        family.access_jti = access_jti.to_string();
+        true
+    }
+
+    async fn revoke_refresh(&self, id: &str) {
+        self.refresh.remove(id);
+    }
 }
 
 /// Spawn a background task that reaps expired tokens every `interval`.
@@ -277,6 +465,7 @@ mod tests {
             name: None,
             groups: Vec::new(),
             issuer: "https://accounts.google.com".to_string(),
+            claims: serde_json::Map::new(),
         }
     }
 
@@ -510,4 +699,89 @@ mod tests {
         // THEN: is_expired returns false
         assert!(!token.is_expired());
     }
+
+    #[tokio::test]
+    async fn open_selects_the_configured_backend() {
+        // GIVEN: the default memory backend
+        let store = open(&KeyServerStoreConfig::Memory).await.unwrap();
+        let token = make_token("sub123", "alice@company.com", 3600);
+        let bearer = token.token.clone();
+        store.insert(token).await;
+
+        // THEN: it works and has no revocation list
+        assert!(store.get(&bearer).await.is_some());
+        assert!(store.revoked().await.is_empty());
+
+        // AND: a backend the gateway was built without is a startup error
+        #[cfg(not(feature = "token-store-redis"))]
+        {
+            let err = open(&KeyServerStoreConfig::Redis {
+                url: "redis://localhost".to_string(),
+                key_prefix: "mcpgw:".to_string(),
+            })
+            .await
+            .err()
+            .expect("redis store needs its feature");
+            assert!(err.to_string().contains("token-store-redis"), "{err}");
+        }
+    }
+
+    fn make_family(subject: &str, token: &str) -> RefreshFamily {
+        RefreshFamily {
+            id: InMemoryTokenStore::generate_jti(),
+            current: crate::hashing::sha256_hex(token.as_bytes()),
+            identity: make_identity(subject, "alice@company.com"),
+            scopes: TokenScopes::default(),
+            access_jti: "jti-1".to_string(),
+            exp: now_secs() + 86_400,
+        }
+    }
+
+    #[test]
+    fn refresh_tokens_embed_their_family_id() {
+        let token = RefreshFamily::generate_token("family-1");
+        assert!(token.starts_with("mcpgwr_family-1."));
+        assert_eq!(RefreshFamily::id_of(&token), Some("family-1"));
+        assert_eq!(RefreshFamily::id_of("mcpgw_abc"), None);
+        assert_eq!(RefreshFamily::id_of("mcpgwr_family-1."), None);
+    }
+
+    #[tokio::test]
+    async fn rotate_refresh_spends_the_current_token_once() {
+        // GIVEN: a family whose current token is `first`
+        let store = InMemoryTokenStore::new();
+        let family = make_family("alice-sub", "first");
+        let id = family.id.clone();
+        let (first, second) = (family.current.clone(), "second-hash".to_string());
+        store.insert_refresh(family).await;
+
+        // WHEN: rotating it twice with the same token
+        let rotated = store.rotate_refresh(&id, &first, &second, "jti-2").await;
+        let replayed = store.rotate_refresh(&id, &first, "third", "jti-3").await;
+
+        // THEN: only the first rotation wins
+        assert!(rotated);
+        assert!(!replayed);
+        let family = store.get_refresh(&id).await.unwrap();
+        assert_eq!(family.current, second);
+        assert_eq!(family.access_jti, "jti-2");
+    }
+
+    #[tokio::test]
+    async fn revoke_by_subject_ends_refresh_families() {
+        // GIVEN: refresh families for alice and bob
+        let store = InMemoryTokenStore::new();
+        let alice = make_family("alice-sub", "a");
+        let bob = make_family("bob-sub", "b");
+        let (alice_id, bob_id) = (alice.id.clone(), bob.id.clone());
+        store.insert_refresh(alice).await;
+        store.insert_refresh(bob).await;
+
+        // WHEN: offboarding alice
+        store.revoke_by_subject("alice-sub").await;
+
+        // THEN: only bob can still refresh
+        assert!(store.get_refresh(&alice_id).await.is_none());
+        assert!(store.get_refresh(&bob_id).await.is_some());
+    }
 }
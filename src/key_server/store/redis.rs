@@ -0,0 +1,367 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+//! Redis token store — tokens shared by every replica.
+//!
+//! # Key layout
+//!
+//! | Key | Value |
+//! |-----|-------|
+//! | `{prefix}token:{sha256(bearer)}` | token JSON, bearer blanked |
+//! | `{prefix}jti:{jti}` | `sha256(bearer)` |
+//! | `{prefix}subject:{subject}` | set of JTIs |
+//! | `{prefix}revoked:{jti}` | expiry of the revoked token |
+//! | `{prefix}refresh:{id}` | hash: `current`, `access_jti`, `family` JSON |
+//! | `{prefix}refresh_subject:{subject}` | set of refresh family IDs |
+//!
+//! Every key expires with its token, so Redis does the reaping. As with the
+//! `SQLite` store, no usable bearer value is ever written.
+
+use std::collections::HashMap;
+
+use ::redis::aio::ConnectionManager;
+use ::redis::{AsyncCommands, Client, RedisResult, Script};
+use tracing::{debug, warn};
+
+use super::{RefreshFamily, TemporaryToken, TokenStore, now_secs};
+use crate::hashing::sha256_hex;
+use crate::{Error, Result};
+
+/// Compare-and-set of a refresh family's current token hash.
+const ROTATE_REFRESH: &str = r"
+if redis.call('HGET', KEYS[1], 'current') == ARGV[1] then
+    redis.call('HSET', KEYS[1], 'current', ARGV[2], 'access_jti', ARGV[3])
+    return 1
+end
+return 0
+";
+
+/// Token store in Redis.
+pub struct RedisTokenStore {
+    conn: ConnectionManager,
+    prefix: String,
+}
+
+impl RedisTokenStore {
+    /// Connect to the Redis server at `url`; every key starts with `prefix`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid or the server unreachable.
+    pub async fn open(url: &str, prefix: &str) -> Result<Self> {
+        let client = Client::open(url)
+            .map_err(|e| Error::Config(format!("Invalid key_server.store.url: {e}")))?;
+        let conn = ConnectionManager::new(client).await.map_err(|e| {
+            Error::Transport(format!(
+                "Failed to connect to the key server Redis store: {e}"
+            ))
+        })?;
+        Ok(Self {
+            conn,
+            prefix: prefix.to_string(),
+        })
+    }
+
+    fn key(&self, kind: &str, id: &str) -> String {
+        format!("{}{kind}:{id}", self.prefix)
+    }
+
+    /// Delete the token `jti` and put it on the revocation list.
+    async fn revoke(&self, jti: &str) -> RedisResult<bool> {
+        let mut conn = self.conn.clone();
+        let Some(hash) = conn.get::<_, Option<String>>(self.key("jti", jti)).await? else {
+            return Ok(false);
+        };
+        let token_key = self.key("token", &hash);
+        let token = conn
+            .get::<_, Option<String>>(&token_key)
+            .await?
+            .and_then(|json| serde_json::from_str::<TemporaryToken>(&json).ok());
+        let mut pipe = ::redis::pipe();
+        pipe.atomic()
+            .del(&token_key)
+            .ignore()
+            .del(self.key("jti", jti))
+            .ignore();
+        if let Some(token) = token {
+            pipe.srem(self.key("subject", &token.identity.subject), jti)
+                .ignore()
+                .set_ex(self.key("revoked", jti), token.exp, ttl(token.exp))
+                .ignore();
+        }
+        pipe.query_async::<()>(&mut conn).await?;
+        Ok(true)
+    }
+
+    /// All keys matching `pattern`.
+    async fn scan(&self, pattern: &str) -> RedisResult<Vec<String>> {
+        let mut conn = self.conn.clone();
+        let mut keys = Vec::new();
+        let mut cursor = 0_u64;
+        loop {
+            let (next, batch): (u64, Vec<String>) = ::redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(100)
+                .query_async(&mut conn)
+                .await?;
+            keys.extend(batch);
+            if next == 0 {
+                return Ok(keys);
+            }
+            cursor = next;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenStore for RedisTokenStore {
+    async fn insert(&self, token: TemporaryToken) {
+        let hash = sha256_hex(token.token.as_bytes());
+        let stored = TemporaryToken {
+            token: String::new(),
+            ..token
+        };
+        let Ok(json) = serde_json::to_string(&stored) else {
+            warn!(jti = %stored.jti, "Failed to serialize token");
+            return;
+        };
+        let seconds = ttl(stored.exp);
+        let subject_key = self.key("subject", &stored.identity.subject);
+        let result = ::redis::pipe()
+            .atomic()
+            .set_ex(self.key("token", &hash), json, seconds)
+            .ignore()
+            .set_ex(self.key("jti", &stored.jti), &hash, seconds)
+            .ignore()
+            .sadd(&subject_key, &stored.jti)
+            .ignore()
+            // All tokens share one lifetime, so the newest expires last.
+            .expire(&subject_key, i64::try_from(seconds).unwrap_or(i64::MAX))
+            .ignore()
+            .query_async::<()>(&mut self.conn.clone())
+            .await;
+        if let Err(e) = result {
+            warn!(jti = %stored.jti, error = %e, "Failed to store token");
+        }
+    }
+
+    async fn get(&self, bearer: &str) -> Option<TemporaryToken> {
+        let json = self
+            .conn
+            .clone()
+            .get::<_, Option<String>>(self.key("token", &sha256_hex(bearer.as_bytes())))
+            .await
+            .inspect_err(|e| warn!(error = %e, "Token store lookup failed"))
+            .ok()??;
+        let token: TemporaryToken = serde_json::from_str(&json)
+            .inspect_err(|e| warn!(error = %e, "Stored token is unreadable"))
+            .ok()?;
+        if token.is_expired() {
+            return None;
+        }
+        Some(TemporaryToken {
+            token: bearer.to_string(),
+            ..token
+        })
+    }
+
+    async fn revoke_by_jti(&self, jti: &str) -> bool {
+        self.revoke(jti)
+            .await
+            .inspect_err(|e| warn!(jti, error = %e, "Failed to revoke token"))
+            .unwrap_or(false)
+    }
+
+    async fn revoke_by_subject(&self, subject: &str) -> usize {
+        let jtis: Vec<String> = self
+            .conn
+            .clone()
+            .smembers(self.key("subject", subject))
+            .await
+            .inspect_err(|e| warn!(error = %e, "Failed to list tokens for revocation"))
+            .unwrap_or_default();
+        let mut count = 0;
+        for jti in jtis {
+            if self.revoke_by_jti(&jti).await {
+                count += 1;
+            }
+        }
+        let families_key = self.key("refresh_subject", subject);
+        let mut conn = self.conn.clone();
+        let families: Vec<String> = conn.smembers(&families_key).await.unwrap_or_default();
+        let mut pipe = ::redis::pipe();
+        pipe.atomic().del(&families_key).ignore();
+        for id in &families {
+            pipe.del(self.key("refresh", id)).ignore();
+        }
+        if let Err(e) = pipe.query_async::<()>(&mut conn).await {
+            warn!(error = %e, "Failed to revoke refresh tokens");
+        }
+        count
+    }
+
+    async fn count_for_subject(&self, subject: &str) -> usize {
+        let mut conn = self.conn.clone();
+        let subject_key = self.key("subject", subject);
+        let jtis: Vec<String> = conn.smembers(&subject_key).await.unwrap_or_default();
+        let mut count = 0;
+        for jti in jtis {
+            match conn.exists::<_, bool>(self.key("jti", &jti)).await {
+                Ok(true) => count += 1,
+                // Expired: drop it from the set.
+                Ok(false) => {
+                    let _: RedisResult<()> = conn.srem(&subject_key, &jti).await;
+                }
+                Err(e) => warn!(error = %e, "Failed to count tokens"),
+            }
+        }
+        count
+    }
+
+    async fn reap_expired(&self) -> usize {
+        // Keys expire in Redis itself.
+        0
+    }
+
+    async fn insert_refresh(&self, family: RefreshFamily) {
+        let Ok(json) = serde_json::to_string(&family) else {
+            warn!(family = %family.id, "Failed to serialize refresh family");
+            return;
+        };
+        let seconds = i64::try_from(ttl(family.exp)).unwrap_or(i64::MAX);
+        let key = self.key("refresh", &family.id);
+        let families_key = self.key("refresh_subject", &family.identity.subject);
+        let result = ::redis::pipe()
+            .atomic()
+            .hset_multiple(
+                &key,
+                &[
+                    ("current", family.current.as_str()),
+                    ("access_jti", family.access_jti.as_str()),
+                    ("family", json.as_str()),
+                ],
+            )
+            .ignore()
+            .expire(&key, seconds)
+            .ignore()
+            .sadd(&families_key, &family.id)
+            .ignore()
+            .expire(&families_key, seconds)
+            .ignore()
+            .query_async::<()>(&mut self.conn.clone())
+            .await;
+        if let Err(e) = result {
+            warn!(family = %family.id, error = %e, "Failed to store refresh family");
+        }
+    }
+
+    async fn get_refresh(&self, id: &str) -> Option<RefreshFamily> {
+        let mut fields: HashMap<String, String> = self
+            .conn
+            .clone()
+            .hgetall(self.key("refresh", id))
+            .await
+            .inspect_err(|e| warn!(error = %e, "Refresh family lookup failed"))
+            .ok()?;
+        let family: RefreshFamily = serde_json::from_str(&fields.remove("family")?)
+            .inspect_err(|e| warn!(error = %e, "Stored refresh family is unreadable"))
+            .ok()?;
+        if family.is_expired() {
+            return None;
+        }
+        Some(RefreshFamily {
+            current: fields.remove("current")?,
+            access_jti: fields.remove("access_jti")?,
+            ..family
+        })
+    }
+
+    async fn rotate_refresh(&self, id: &str, current: &str, next: &str, access_jti: &str) -> bool {
+        Script::new(ROTATE_REFRESH)
+            .key(self.key("refresh", id))
+            .arg(current)
+            .arg(next)
+            .arg(access_jti)
+            .invoke_async::<bool>(&mut self.conn.clone())
+            .await
+            .inspect_err(|e| warn!(family = id, error = %e, "Failed to rotate refresh token"))
+            .unwrap_or(false)
+    }
+
+    async fn revoke_refresh(&self, id: &str) {
+        let result: RedisResult<()> = self.conn.clone().del(self.key("refresh", id)).await;
+        if let Err(e) = result {
+            warn!(family = id, error = %e, "Failed to revoke refresh family");
+        }
+    }
+
+    async fn revoked(&self) -> Vec<(String, u64)> {
+        let keys = match self.scan(&self.key("revoked", "*")).await {
+            Ok(keys) if !keys.is_empty() => keys,
+            Ok(_) => return Vec::new(),
+            Err(e) => {
+                warn!(error = %e, "Failed to read the revocation list");
+                return Vec::new();
+            }
+        };
+        let expiries: Vec<Option<u64>> = self
+            .conn
+            .clone()
+            .mget(&keys)
+            .await
+            .inspect_err(|e| warn!(error = %e, "Failed to read the revocation list"))
+            .unwrap_or_default();
+        let revoked_prefix = self.key("revoked", "");
+        keys.iter()
+            .zip(expiries)
+            .filter_map(|(key, exp)| Some((key.strip_prefix(&revoked_prefix)?.to_string(), exp?)))
+            .collect()
+    }
+
+    async fn is_revoked(&self, jti: &str) -> bool {
+        match self
+            .conn
+            .clone()
+            .exists::<_, bool>(self.key("revoked", jti))
+            .await
+        {
+            Ok(revoked) => revoked,
+            Err(e) => {
+                // Fail closed: an unreadable revocation list revokes.
+                warn!(jti, error = %e, "Failed to read the revocation list");
+                true
+            }
+        }
+    }
+
+    async fn reconcile(&self) -> usize {
+        let mut dropped = 0;
+        for (jti, _) in self.revoked().await {
+            // A token still present was revoked mid-write; finish the job.
+            if self.revoke_by_jti(&jti).await {
+                dropped += 1;
+            }
+        }
+        debug!(dropped, "Reconciled Redis token store");
+        dropped
+    }
+}
+
+/// Seconds until `exp`, at least one (`SET EX 0` is an error).
+fn ttl(exp: u64) -> u64 {
+    exp.saturating_sub(now_secs()).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ttl_is_never_zero() {
+        assert_eq!(ttl(0), 1);
+        assert!(ttl(now_secs() + 60) >= 59);
+    }
+}
@@ -0,0 +1,478 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+//! `SQLite` token store — tokens survive restarts.
+//!
+//! Tokens are keyed by the SHA-256 of their bearer value, so the database
+//! never holds a usable credential; the stored token JSON has its bearer
+//! blanked and it is restored from the presented value on lookup.
+//!
+//! Refresh families keep their rotating fields (`current_hash`,
+//! `access_jti`) in columns, so rotation is a single conditional `UPDATE`.
+//!
+//! Storage errors are logged and treated as "not found", so validation
+//! fails closed.
+
+use std::path::Path;
+use std::time::Duration;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use tracing::{debug, warn};
+
+use super::{RefreshFamily, TemporaryToken, TokenStore, now_secs};
+use crate::hashing::sha256_hex;
+use crate::{Error, Result};
+
+const MAX_CONNECTIONS: u32 = 4;
+
+const SCHEMA: [&str; 6] = [
+    "CREATE TABLE IF NOT EXISTS key_server_tokens (
+        bearer_hash TEXT PRIMARY KEY,
+        jti TEXT NOT NULL UNIQUE,
+        subject TEXT NOT NULL,
+        exp INTEGER NOT NULL,
+        token TEXT NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS key_server_tokens_subject ON key_server_tokens (subject)",
+    "CREATE INDEX IF NOT EXISTS key_server_tokens_exp ON key_server_tokens (exp)",
+    "CREATE TABLE IF NOT EXISTS key_server_revocations (
+        jti TEXT PRIMARY KEY,
+        exp INTEGER NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS key_server_refresh (
+        id TEXT PRIMARY KEY,
+        subject TEXT NOT NULL,
+        current_hash TEXT NOT NULL,
+        access_jti TEXT NOT NULL,
+        exp INTEGER NOT NULL,
+        family TEXT NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS key_server_refresh_subject ON key_server_refresh (subject)",
+];
+
+/// Token store in a `SQLite` database file.
+pub struct SqliteTokenStore {
+    pool: SqlitePool,
+}
+
+impl SqliteTokenStore {
+    /// Open (creating if missing) the database at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or migrated.
+    pub async fn open(path: &Path) -> Result<Self> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .busy_timeout(Duration::from_secs(5));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(MAX_CONNECTIONS)
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                Error::Config(format!(
+                    "Failed to open key server token store {}: {e}",
+                    path.display()
+                ))
+            })?;
+        for statement in SCHEMA {
+            sqlx::query(statement).execute(&pool).await.map_err(|e| {
+                Error::Config(format!("Failed to create key server token tables: {e}"))
+            })?;
+        }
+        Ok(Self { pool })
+    }
+
+    /// Delete the token `jti` and put it on the revocation list.
+    async fn revoke(&self, jti: &str) -> std::result::Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let exp: Option<i64> =
+            sqlx::query_scalar("DELETE FROM key_server_tokens WHERE jti = ? RETURNING exp")
+                .bind(jti)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if let Some(exp) = exp {
+            sqlx::query("INSERT OR REPLACE INTO key_server_revocations (jti, exp) VALUES (?, ?)")
+                .bind(jti)
+                .bind(exp)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(exp.is_some())
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenStore for SqliteTokenStore {
+    async fn insert(&self, token: TemporaryToken) {
+        let bearer_hash = sha256_hex(token.token.as_bytes());
+        let stored = TemporaryToken {
+            token: String::new(),
+            ..token
+        };
+        let json = match serde_json::to_string(&stored) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!(jti = %stored.jti, error = %e, "Failed to serialize token");
+                return;
+            }
+        };
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO key_server_tokens (bearer_hash, jti, subject, exp, token)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(bearer_hash)
+        .bind(&stored.jti)
+        .bind(&stored.identity.subject)
+        .bind(to_sql(stored.exp))
+        .bind(json)
+        .execute(&self.pool)
+        .await;
+        if let Err(e) = result {
+            warn!(jti = %stored.jti, error = %e, "Failed to store token");
+        }
+    }
+
+    async fn get(&self, bearer: &str) -> Option<TemporaryToken> {
+        let json: String = sqlx::query_scalar(
+            "SELECT token FROM key_server_tokens WHERE bearer_hash = ? AND exp > ?",
+        )
+        .bind(sha256_hex(bearer.as_bytes()))
+        .bind(to_sql(now_secs()))
+        .fetch_optional(&self.pool)
+        .await
+        .inspect_err(|e| warn!(error = %e, "Token store lookup failed"))
+        .ok()??;
+        let token: TemporaryToken = serde_json::from_str(&json)
+            .inspect_err(|e| warn!(error = %e, "Stored token is unreadable"))
+            .ok()?;
+        Some(TemporaryToken {
+            token: bearer.to_string(),
+            ..token
+        })
+    }
+
+    async fn revoke_by_jti(&self, jti: &str) -> bool {
+        self.revoke(jti)
+            .await
+            .inspect_err(|e| warn!(jti, error = %e, "Failed to revoke token"))
+            .unwrap_or(false)
+    }
+
+    async fn revoke_by_subject(&self, subject: &str) -> usize {
+        let jtis: Vec<String> =
+            sqlx::query_scalar("SELECT jti FROM key_server_tokens WHERE subject = ?")
+                .bind(subject)
+                .fetch_all(&self.pool)
+                .await
+                .inspect_err(|e| warn!(error = %e, "Failed to list tokens for revocation"))
+                .unwrap_or_default();
+        let mut count = 0;
+        for jti in jtis {
+            if self.revoke_by_jti(&jti).await {
+                count += 1;
+            }
+        }
+        if let Err(e) = sqlx::query("DELETE FROM key_server_refresh WHERE subject = ?")
+            .bind(subject)
+            .execute(&self.pool)
+            .await
+        {
+            warn!(error = %e, "Failed to revoke refresh tokens");
+        }
+        count
+    }
+
+    async fn count_for_subject(&self, subject: &str) -> usize {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM key_server_tokens WHERE subject = ? AND exp > ?",
+        )
+        .bind(subject)
+        .bind(to_sql(now_secs()))
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(|e| warn!(error = %e, "Failed to count tokens"))
+        .unwrap_or(0);
+        usize::try_from(count).unwrap_or(0)
+    }
+
+    async fn reap_expired(&self) -> usize {
+        let now = to_sql(now_secs());
+        // Revocations of expired tokens are no longer needed either.
+        if let Err(e) = sqlx::query("DELETE FROM key_server_revocations WHERE exp <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+        {
+            warn!(error = %e, "Failed to prune the revocation list");
+        }
+        if let Err(e) = sqlx::query("DELETE FROM key_server_refresh WHERE exp <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+        {
+            warn!(error = %e, "Failed to reap expired refresh tokens");
+        }
+        sqlx::query("DELETE FROM key_server_tokens WHERE exp <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map(|result| usize::try_from(result.rows_affected()).unwrap_or(usize::MAX))
+            .inspect_err(|e| warn!(error = %e, "Failed to reap expired tokens"))
+            .unwrap_or(0)
+    }
+
+    async fn insert_refresh(&self, family: RefreshFamily) {
+        let json = match serde_json::to_string(&family) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!(family = %family.id, error = %e, "Failed to serialize refresh family");
+                return;
+            }
+        };
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO key_server_refresh
+             (id, subject, current_hash, access_jti, exp, family) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&family.id)
+        .bind(&family.identity.subject)
+        .bind(&family.current)
+        .bind(&family.access_jti)
+        .bind(to_sql(family.exp))
+        .bind(json)
+        .execute(&self.pool)
+        .await;
+        if let Err(e) = result {
+            warn!(family = %family.id, error = %e, "Failed to store refresh family");
+        }
+    }
+
+    async fn get_refresh(&self, id: &str) -> Option<RefreshFamily> {
+        let (current, access_jti, json): (String, String, String) = sqlx::query_as(
+            "SELECT current_hash, access_jti, family FROM key_server_refresh
+             WHERE id = ? AND exp > ?",
+        )
+        .bind(id)
+        .bind(to_sql(now_secs()))
+        .fetch_optional(&self.pool)
+        .await
+        .inspect_err(|e| warn!(error = %e, "Refresh family lookup failed"))
+        .ok()??;
+        let family: RefreshFamily = serde_json::from_str(&json)
+            .inspect_err(|e| warn!(error = %e, "Stored refresh family is unreadable"))
+            .ok()?;
+        Some(RefreshFamily {
+            current,
+            access_jti,
+            ..family
+        })
+    }
+
+    async fn rotate_refresh(&self, id: &str, current: &str, next: &str, access_jti: &str) -> bool {
+        sqlx::query(
+            "UPDATE key_server_refresh SET current_hash = ?, access_jti = ?
+             WHERE id = ? AND current_hash = ? AND exp > ?",
+        )
+        .bind(next)
+        .bind(access_jti)
+        .bind(id)
+        .bind(current)
+        .bind(to_sql(now_secs()))
+        .execute(&self.pool)
+        .await
+        .inspect_err(|e| warn!(family = id, error = %e, "Failed to rotate refresh token"))
+        .is_ok_and(|result| result.rows_affected() == 1)
+    }
+
+    async fn revoke_refresh(&self, id: &str) {
+        if let Err(e) = sqlx::query("DELETE FROM key_server_refresh WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+        {
+            warn!(family = id, error = %e, "Failed to revoke refresh family");
+        }
+    }
+
+    async fn revoked(&self) -> Vec<(String, u64)> {
+        sqlx::query_as::<_, (String, i64)>(
+            "SELECT jti, exp FROM key_server_revocations WHERE exp > ?",
+        )
+        .bind(to_sql(now_secs()))
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| warn!(error = %e, "Failed to read the revocation list"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(jti, exp)| (jti, u64::try_from(exp).unwrap_or(0)))
+        .collect()
+    }
+
+    async fn is_revoked(&self, jti: &str) -> bool {
+        match sqlx::query_scalar::<_, i64>("SELECT 1 FROM key_server_revocations WHERE jti = ?")
+            .bind(jti)
+            .fetch_optional(&self.pool)
+            .await
+        {
+            Ok(row) => row.is_some(),
+            Err(e) => {
+                // Fail closed: an unreadable revocation list revokes.
+                warn!(jti, error = %e, "Failed to read the revocation list");
+                true
+            }
+        }
+    }
+
+    async fn reconcile(&self) -> usize {
+        let expired = self.reap_expired().await;
+        let revoked = sqlx::query(
+            "DELETE FROM key_server_tokens
+             WHERE jti IN (SELECT jti FROM key_server_revocations)",
+        )
+        .execute(&self.pool)
+        .await
+        .map(|result| usize::try_from(result.rows_affected()).unwrap_or(usize::MAX))
+        .inspect_err(|e| warn!(error = %e, "Failed to reconcile the revocation list"))
+        .unwrap_or(0);
+        debug!(expired, revoked, "Reconciled SQLite token store");
+        expired + revoked
+    }
+}
+
+/// `SQLite` integers are signed.
+fn to_sql(secs: u64) -> i64 {
+    i64::try_from(secs).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_server::oidc::VerifiedIdentity;
+    use crate::key_server::store::{InMemoryTokenStore, TokenScopes};
+
+    fn token(subject: &str, exp: u64) -> TemporaryToken {
+        TemporaryToken {
+            jti: InMemoryTokenStore::generate_jti(),
+            token: InMemoryTokenStore::generate_bearer(),
+            identity: VerifiedIdentity {
+                subject: subject.to_string(),
+                email: format!("{subject}@company.com"),
+                name: None,
+                groups: Vec::new(),
+                issuer: "https://accounts.google.com".to_string(),
+                claims: serde_json::Map::new(),
+            },
+            scopes: TokenScopes::default(),
+            iat: now_secs(),
+            exp,
+            client_ip: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn tokens_survive_reopening_and_revocations_persist() {
+        // GIVEN: a store file with two tokens for alice
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens.db");
+        let store = SqliteTokenStore::open(&path).await.unwrap();
+        let kept = token("alice", now_secs() + 3600);
+        let revoked = token("alice", now_secs() + 3600);
+        let (kept_bearer, revoked_bearer) = (kept.token.clone(), revoked.token.clone());
+        let revoked_jti = revoked.jti.clone();
+        store.insert(kept).await;
+        store.insert(revoked).await;
+
+        // WHEN: revoking one and reopening the file
+        assert!(store.revoke_by_jti(&revoked_jti).await);
+        drop(store);
+        let store = SqliteTokenStore::open(&path).await.unwrap();
+
+        // THEN: the other token is still valid, with its bearer restored
+        let found = store.get(&kept_bearer).await.expect("token survives");
+        assert_eq!(found.token, kept_bearer);
+        assert!(store.get(&revoked_bearer).await.is_none());
+        assert_eq!(store.count_for_subject("alice").await, 1);
+        assert!(store.is_revoked(&revoked_jti).await);
+        assert_eq!(store.revoked().await.len(), 1);
+
+        // AND: the database never holds a bearer value
+        let raw = std::fs::read(&path).unwrap();
+        let raw = String::from_utf8_lossy(&raw);
+        assert!(!raw.contains(&kept_bearer));
+    }
+
+    #[tokio::test]
+    async fn reconcile_drops_expired_and_revoked_tokens() {
+        // GIVEN: an expired token and a live token whose JTI is on the
+        // revocation list (e.g. revoked by another replica mid-write)
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteTokenStore::open(&dir.path().join("tokens.db"))
+            .await
+            .unwrap();
+        let expired = token("bob", now_secs() - 1);
+        let listed = token("bob", now_secs() + 3600);
+        let listed_bearer = listed.token.clone();
+        sqlx::query("INSERT INTO key_server_revocations (jti, exp) VALUES (?, ?)")
+            .bind(&listed.jti)
+            .bind(to_sql(listed.exp))
+            .execute(&store.pool)
+            .await
+            .unwrap();
+        store.insert(expired).await;
+        store.insert(listed).await;
+
+        // WHEN: reconciling at startup
+        let dropped = store.reconcile().await;
+
+        // THEN: both are gone
+        assert_eq!(dropped, 2);
+        assert!(store.get(&listed_bearer).await.is_none());
+        assert_eq!(store.count_for_subject("bob").await, 0);
+    }
+
+    #[tokio::test]
+    async fn refresh_families_rotate_once_and_survive_reopening() {
+        // GIVEN: a stored refresh family for alice
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens.db");
+        let store = SqliteTokenStore::open(&path).await.unwrap();
+        let access = token("alice", now_secs() + 3600);
+        let family = RefreshFamily {
+            id: "family-1".to_string(),
+            current: "first".to_string(),
+            identity: access.identity,
+            scopes: access.scopes,
+            access_jti: access.jti,
+            exp: now_secs() + 86_400,
+        };
+        store.insert_refresh(family).await;
+
+        // WHEN: rotating it, replaying the spent token, and reopening
+        assert!(
+            store
+                .rotate_refresh("family-1", "first", "second", "jti-2")
+                .await
+        );
+        assert!(
+            !store
+                .rotate_refresh("family-1", "first", "third", "jti-3")
+                .await
+        );
+        drop(store);
+        let store = SqliteTokenStore::open(&path).await.unwrap();
+
+        // THEN: the rotated state persisted
+        let family = store
+            .get_refresh("family-1")
+            .await
+            .expect("family survives");
+        assert_eq!(family.current, "second");
+        assert_eq!(family.access_jti, "jti-2");
+
+        // AND: offboarding alice ends it
+        store.revoke_by_subject("alice").await;
+        assert!(store.get_refresh("family-1").await.is_none());
+    }
+}
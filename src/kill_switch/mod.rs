@@ -5,11 +5,14 @@
 //! Provides three complementary mechanisms:
 //!
 //! - **Kill switch** (`KillSwitch`): operator-controlled, instant disable/re-enable of
-//!   any backend by name. Changes take effect on the next `gateway_invoke` call.
+//!   any backend by name, or of a single tool by `server:tool`. Changes take effect on
+//!   the next `gateway_invoke` call.
 //!
 //! - **Backend error budget** (`ErrorBudget`): per-backend sliding-window error-rate
 //!   tracker. When a backend exceeds its configured failure threshold it is automatically
-//!   killed. The operator can revive it manually via `gateway_revive_server`.
+//!   killed. The operator can revive it manually via `gateway_revive_server`; when
+//!   auto-revive is enabled the gateway also probes it after a cooldown and revives
+//!   it on success, like a circuit breaker's half-open state.
 //!
 //! - **Per-capability error budget**: per-capability sliding-window error-rate tracker.
 //!   When a single capability exceeds its threshold, only that capability is disabled —
@@ -25,6 +28,7 @@ pub mod budget;
 
 pub use budget::{CapabilityErrorBudgetConfig, ErrorBudgetConfig};
 
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -84,6 +88,10 @@ pub(crate) fn decide_budget_action(
 pub struct KillSwitch {
     /// Set of backend server names that are currently disabled.
     killed: DashSet<String>,
+    /// Tools disabled by an operator, keyed `"{backend}:{tool}"`.
+    ///
+    /// Unlike disabled capabilities these never auto-recover.
+    killed_tools: DashSet<String>,
     /// Per-backend error budgets (sliding window).
     budgets: DashMap<String, Arc<parking_lot::Mutex<BudgetWindow>>>,
     /// Per-capability error budgets.
@@ -96,6 +104,48 @@ pub struct KillSwitch {
     /// Key: `"{backend}:{capability}"`. Capabilities are re-enabled
     /// automatically once the cooldown period elapses.
     disabled_capabilities: DashMap<String, Instant>,
+    /// Servers killed by the error budget (not by an operator), awaiting an
+    /// auto-revive probe.
+    auto_killed: DashMap<String, AutoKill>,
+    /// Servers and `"{backend}:{tool}"` keys last applied from the `disabled:`
+    /// config section, so a reload can revive entries that were removed.
+    declared: parking_lot::Mutex<Declared>,
+    /// Targets last applied from the cluster's shared kill list, so one
+    /// revived on another replica can be revived here too.
+    shared: parking_lot::Mutex<HashSet<String>>,
+}
+
+/// Targets applied by [`KillSwitch::apply_declared`].
+#[derive(Debug, Default)]
+struct Declared {
+    servers: HashSet<String>,
+    tools: HashSet<String>,
+}
+
+/// Probe schedule for a server killed by its error budget.
+#[derive(Debug, Clone, Copy)]
+struct AutoKill {
+    /// When the server was killed or last failed a probe.
+    since: Instant,
+    /// Consecutive failed probes; each one doubles the wait.
+    failed_probes: u32,
+}
+
+impl AutoKill {
+    fn new() -> Self {
+        Self {
+            since: Instant::now(),
+            failed_probes: 0,
+        }
+    }
+
+    /// Wait before the next probe: `cooldown` doubled per failed probe,
+    /// capped at `max_cooldown`.
+    fn wait(&self, cooldown: Duration, max_cooldown: Duration) -> Duration {
+        cooldown
+            .saturating_mul(2u32.saturating_pow(self.failed_probes))
+            .min(max_cooldown.max(cooldown))
+    }
 }
 
 impl KillSwitch {
@@ -104,9 +154,13 @@ impl KillSwitch {
     pub fn new() -> Self {
         Self {
             killed: DashSet::new(),
+            killed_tools: DashSet::new(),
             budgets: DashMap::new(),
             capability_budgets: DashMap::new(),
             disabled_capabilities: DashMap::new(),
+            auto_killed: DashMap::new(),
+            declared: parking_lot::Mutex::new(Declared::default()),
+            shared: parking_lot::Mutex::new(HashSet::new()),
         }
     }
 
@@ -115,7 +169,10 @@ impl KillSwitch {
     /// Immediately disable routing to `server`.
     ///
     /// Idempotent — calling this on an already-killed server is a no-op.
+    /// An operator kill is never auto-revived, so this also cancels any
+    /// pending auto-revive probe.
     pub fn kill(&self, server: &str) {
+        self.auto_killed.remove(server);
         if self.killed.insert(server.to_string()) {
             warn!(server = server, "Kill switch engaged: server disabled");
         }
@@ -126,6 +183,7 @@ impl KillSwitch {
     /// Idempotent — calling this on an already-live server is a no-op.
     /// Also resets the error-budget window so the backend gets a clean slate.
     pub fn revive(&self, server: &str) {
+        self.auto_killed.remove(server);
         if self.killed.remove(server).is_some() {
             info!(server = server, "Kill switch released: server re-enabled");
         }
@@ -148,6 +206,156 @@ impl KillSwitch {
         self.killed.iter().map(|s| s.clone()).collect()
     }
 
+    /// Immediately disable routing to a single `tool` on `server`, leaving the
+    /// rest of the backend live.
+    ///
+    /// Idempotent. Returns `true` when the tool was not already killed.
+    pub fn kill_tool(&self, server: &str, tool: &str) -> bool {
+        let key = Self::capability_key(server, tool);
+        let newly_killed = self.killed_tools.insert(key.clone());
+        if newly_killed {
+            warn!(tool = key, "Kill switch engaged: tool disabled");
+        }
+        newly_killed
+    }
+
+    /// Re-enable routing to `tool` on `server`.
+    ///
+    /// Also clears an auto-disable by the per-capability error budget and
+    /// resets its window. Returns `true` when the tool had been killed.
+    pub fn revive_tool(&self, server: &str, tool: &str) -> bool {
+        let key = Self::capability_key(server, tool);
+        let was_killed = self.killed_tools.remove(&key).is_some();
+        if was_killed {
+            info!(tool = key, "Kill switch released: tool re-enabled");
+        }
+        self.revive_capability(server, tool);
+        was_killed
+    }
+
+    /// Returns `true` when an operator has disabled `tool` on `server`.
+    #[must_use]
+    #[inline]
+    pub fn is_tool_killed(&self, server: &str, tool: &str) -> bool {
+        !self.killed_tools.is_empty()
+            && self
+                .killed_tools
+                .contains(&Self::capability_key(server, tool))
+    }
+
+    /// Returns the currently-killed tools as sorted `"server:tool"` keys.
+    #[must_use]
+    pub fn killed_tools(&self) -> Vec<String> {
+        let mut tools: Vec<String> = self.killed_tools.iter().map(|s| s.clone()).collect();
+        tools.sort();
+        tools
+    }
+
+    /// Apply the declarative kill list from config.
+    ///
+    /// Every listed server and `(server, tool)` pair is killed (again, if an
+    /// operator revived it since the previous call); entries dropped from the
+    /// list since the previous call are revived. Targets killed at runtime by
+    /// an operator or the error budget are left alone unless listed.
+    pub fn apply_declared(&self, servers: &[String], tools: &[(String, String)]) {
+        let next = Declared {
+            servers: servers.iter().cloned().collect(),
+            tools: tools
+                .iter()
+                .map(|(server, tool)| Self::capability_key(server, tool))
+                .collect(),
+        };
+        let mut declared = self.declared.lock();
+        for server in declared.servers.difference(&next.servers) {
+            self.revive(server);
+        }
+        for key in declared.tools.difference(&next.tools) {
+            if let Some((server, tool)) = key.split_once(':') {
+                self.revive_tool(server, tool);
+            }
+        }
+        for server in &next.servers {
+            self.kill(server);
+        }
+        for (server, tool) in tools {
+            self.kill_tool(server, tool);
+        }
+        *declared = next;
+    }
+
+    /// Apply the operator kill list shared by every replica (see
+    /// [`crate::cluster`]).
+    ///
+    /// `targets` holds server names and `server:tool` keys. Targets added
+    /// since the previous call are killed and targets removed since then are
+    /// revived, so a kill or revive made on any replica takes effect here.
+    /// Local kills not in the shared list are left alone.
+    pub fn apply_shared(&self, targets: &HashSet<String>) {
+        let mut shared = self.shared.lock();
+        for target in shared.difference(targets) {
+            match target.split_once(':') {
+                Some((server, tool)) => {
+                    self.revive_tool(server, tool);
+                }
+                None => self.revive(target),
+            }
+        }
+        for target in targets.difference(&shared) {
+            match target.split_once(':') {
+                Some((server, tool)) => {
+                    self.kill_tool(server, tool);
+                }
+                None => self.kill(target),
+            }
+        }
+        shared.clone_from(targets);
+    }
+
+    // ── Auto-revive ───────────────────────────────────────────────────────────
+
+    /// Returns `true` when `server` was killed by its error budget and is
+    /// waiting for an auto-revive probe.
+    #[must_use]
+    pub fn is_auto_killed(&self, server: &str) -> bool {
+        self.auto_killed.contains_key(server)
+    }
+
+    /// Auto-killed servers whose probe is due.
+    ///
+    /// A server becomes due `cooldown` after it was killed; every failed probe
+    /// doubles the wait, up to `max_cooldown`.
+    #[must_use]
+    pub fn due_for_probe(&self, cooldown: Duration, max_cooldown: Duration) -> Vec<String> {
+        self.auto_killed
+            .iter()
+            .filter(|e| e.since.elapsed() >= e.wait(cooldown, max_cooldown))
+            .map(|e| e.key().clone())
+            .collect()
+    }
+
+    /// Record a failed probe: `server` stays killed and its next probe is
+    /// rescheduled with a longer wait.
+    pub fn record_probe_failure(&self, server: &str) {
+        if let Some(mut entry) = self.auto_killed.get_mut(server) {
+            entry.since = Instant::now();
+            entry.failed_probes = entry.failed_probes.saturating_add(1);
+        }
+    }
+
+    /// Revive `server` after a successful probe.
+    ///
+    /// Returns `false` (and leaves the server alone) when it is no longer
+    /// awaiting a probe, e.g. because an operator killed or revived it while
+    /// the probe was running.
+    pub fn revive_after_probe(&self, server: &str) -> bool {
+        if self.auto_killed.remove(server).is_none() {
+            return false;
+        }
+        info!(server = server, "Auto-revive probe succeeded");
+        self.revive(server);
+        true
+    }
+
     // ── Backend error budget ──────────────────────────────────────────────────
 
     /// Record a successful call for `server`.
@@ -208,6 +416,7 @@ impl KillSwitch {
                     "Error budget exhausted — auto-killing server"
                 );
                 self.killed.insert(server.to_string());
+                self.auto_killed.insert(server.to_string(), AutoKill::new());
                 return true;
             }
             BudgetAction::Ignore => {}
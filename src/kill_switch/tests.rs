@@ -730,3 +730,162 @@ fn backend_level_budget_still_kills_when_all_capabilities_fail() {
         "backend must be killed when cumulative error rate exceeds backend threshold"
     );
 }
+
+// ── Auto-revive scheduling ───────────────────────────────────────────────
+
+/// Kill `server` through its error budget (one failure is enough).
+fn auto_kill(ks: &KillSwitch, server: &str) {
+    assert!(ks.record_failure(server, 10, Duration::from_secs(60), 0.5, 1));
+}
+
+#[test]
+fn budget_kill_schedules_auto_revive_probe() {
+    // GIVEN: a server killed by its error budget
+    let ks = KillSwitch::new();
+    auto_kill(&ks, "srv");
+    // THEN: it awaits a probe, due once the cooldown has elapsed
+    assert!(ks.is_auto_killed("srv"));
+    assert_eq!(ks.due_for_probe(Duration::ZERO, Duration::ZERO), ["srv"]);
+    assert!(
+        ks.due_for_probe(Duration::from_secs(60), Duration::from_secs(600))
+            .is_empty()
+    );
+}
+
+#[test]
+fn operator_kill_is_never_auto_revived() {
+    // GIVEN: an operator-killed server and an auto-killed server the operator
+    // then killed explicitly
+    let ks = KillSwitch::new();
+    ks.kill("manual");
+    auto_kill(&ks, "escalated");
+    ks.kill("escalated");
+    // THEN: neither is scheduled for a probe, and a late probe result is ignored
+    assert!(ks.due_for_probe(Duration::ZERO, Duration::ZERO).is_empty());
+    assert!(!ks.revive_after_probe("escalated"));
+    assert!(ks.is_killed("escalated"));
+}
+
+#[test]
+fn failed_probe_doubles_wait_up_to_max_cooldown() {
+    // GIVEN: an auto-killed server whose probe failed ten times
+    let ks = KillSwitch::new();
+    auto_kill(&ks, "srv");
+    for _ in 0..10 {
+        ks.record_probe_failure("srv");
+    }
+    std::thread::sleep(Duration::from_millis(30));
+    // THEN: a 10ms cooldown now means a ~10s wait...
+    assert!(
+        ks.due_for_probe(Duration::from_millis(10), Duration::from_secs(1))
+            .is_empty()
+    );
+    // ...unless the cap is lower
+    assert_eq!(
+        ks.due_for_probe(Duration::from_millis(10), Duration::from_millis(20)),
+        ["srv"]
+    );
+}
+
+#[test]
+fn successful_probe_revives_and_clears_schedule() {
+    let ks = KillSwitch::new();
+    auto_kill(&ks, "srv");
+    assert!(ks.revive_after_probe("srv"));
+    assert!(!ks.is_killed("srv"));
+    assert!(!ks.is_auto_killed("srv"));
+    assert_eq!(ks.window_counts("srv"), (0, 0));
+}
+
+#[test]
+fn manual_revive_cancels_auto_revive_probe() {
+    let ks = KillSwitch::new();
+    auto_kill(&ks, "srv");
+    ks.revive("srv");
+    assert!(!ks.is_auto_killed("srv"));
+}
+
+// ── Per-tool kill switch ─────────────────────────────────────────────────
+
+#[test]
+fn kill_tool_disables_only_that_tool() {
+    // GIVEN: a fresh kill switch
+    let ks = KillSwitch::new();
+    // WHEN: one tool is killed
+    assert!(ks.kill_tool("srv", "delete"));
+    // THEN: only that tool is disabled; the server and its other tools stay live
+    assert!(ks.is_tool_killed("srv", "delete"));
+    assert!(!ks.is_tool_killed("srv", "search"));
+    assert!(!ks.is_tool_killed("other", "delete"));
+    assert!(!ks.is_killed("srv"));
+    assert_eq!(ks.killed_tools(), ["srv:delete"]);
+}
+
+#[test]
+fn kill_tool_is_idempotent() {
+    let ks = KillSwitch::new();
+    assert!(ks.kill_tool("srv", "delete"));
+    assert!(!ks.kill_tool("srv", "delete"));
+    assert!(ks.is_tool_killed("srv", "delete"));
+}
+
+#[test]
+fn revive_tool_also_clears_capability_auto_disable() {
+    // GIVEN: a tool killed by an operator and auto-disabled by its budget
+    let ks = KillSwitch::new();
+    let cfg = CapabilityErrorBudgetConfig {
+        min_samples: 1,
+        ..CapabilityErrorBudgetConfig::default()
+    };
+    ks.record_capability_failure("srv", "delete", &cfg);
+    ks.kill_tool("srv", "delete");
+    assert!(ks.is_capability_disabled("srv", "delete"));
+    // WHEN: it is revived
+    assert!(ks.revive_tool("srv", "delete"));
+    // THEN: both kinds of disable are cleared
+    assert!(!ks.is_tool_killed("srv", "delete"));
+    assert!(!ks.is_capability_disabled("srv", "delete"));
+    assert!(!ks.revive_tool("srv", "delete"));
+}
+
+// ── Declarative kill list ────────────────────────────────────────────────
+
+#[test]
+fn apply_declared_kills_listed_targets() {
+    let ks = KillSwitch::new();
+    ks.apply_declared(
+        &["legacy".to_string()],
+        &[("github".to_string(), "delete_repo".to_string())],
+    );
+    assert!(ks.is_killed("legacy"));
+    assert!(ks.is_tool_killed("github", "delete_repo"));
+    // Declared kills are operator kills, never auto-revived.
+    assert!(!ks.is_auto_killed("legacy"));
+}
+
+#[test]
+fn apply_declared_revives_only_entries_dropped_from_the_list() {
+    // GIVEN: a declared list and a separate runtime operator kill
+    let ks = KillSwitch::new();
+    ks.apply_declared(
+        &["legacy".to_string(), "flaky".to_string()],
+        &[("github".to_string(), "delete_repo".to_string())],
+    );
+    ks.kill("manual");
+    // WHEN: the list is reloaded without "flaky" and the tool
+    ks.apply_declared(&["legacy".to_string()], &[]);
+    // THEN: dropped entries are revived; the rest are untouched
+    assert!(ks.is_killed("legacy"));
+    assert!(!ks.is_killed("flaky"));
+    assert!(!ks.is_tool_killed("github", "delete_repo"));
+    assert!(ks.is_killed("manual"));
+}
+
+#[test]
+fn apply_declared_re_kills_listed_target_revived_at_runtime() {
+    let ks = KillSwitch::new();
+    ks.apply_declared(&["legacy".to_string()], &[]);
+    ks.revive("legacy");
+    ks.apply_declared(&["legacy".to_string()], &[]);
+    assert!(ks.is_killed("legacy"));
+}
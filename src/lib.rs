@@ -30,6 +30,8 @@
 
 #[cfg(feature = "a2a")]
 pub mod a2a;
+pub mod access_log;
+pub mod alerts;
 pub mod attestation;
 pub mod autotag;
 pub mod backend;
@@ -37,6 +39,7 @@ pub mod cache;
 pub mod capability;
 pub mod chains;
 pub mod cli;
+pub mod cluster;
 pub mod config;
 pub mod config_persistence;
 pub mod config_reload;
@@ -45,6 +48,7 @@ pub mod context_integrity;
 pub mod control_plane;
 pub mod cost_accounting;
 pub mod discovery;
+pub mod enrichment;
 pub mod error;
 pub mod failsafe;
 mod fs_lock;
@@ -58,6 +62,7 @@ pub mod kill_switch;
 pub mod kubernetes;
 #[cfg(feature = "metrics")]
 pub mod metrics;
+pub mod metrics_export;
 pub mod mtls;
 pub mod oauth;
 pub mod playbook;
@@ -78,6 +83,7 @@ pub mod semantic_search;
 pub mod session_sandbox;
 pub mod simhash;
 pub mod skills;
+pub mod slo;
 pub mod stats;
 #[cfg(feature = "tool-profiles")]
 pub mod tool_profiles;
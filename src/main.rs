@@ -46,7 +46,12 @@ async fn main() -> ExitCode {
             profile,
             with_examples,
         }) => commands::run_init_command(&output, with_examples, profile),
-        Some(Command::Cap(cap_cmd)) => commands::run_cap_command(cap_cmd).await,
+        Some(Command::Cap(cap_cmd)) => {
+            commands::run_cap_command(cap_cmd, config_path.as_deref()).await
+        }
+        Some(Command::Discover(discover_cmd)) => {
+            commands::run_discover_command(discover_cmd, config_path.as_deref()).await
+        }
         Some(Command::Import(import_cmd)) => {
             commands::run_protocol_import_command(import_cmd).await
         }
@@ -54,6 +59,9 @@ async fn main() -> ExitCode {
             commands::run_kubernetes_command(kubernetes_cmd)
         }
         Some(Command::Ranking(ranking_cmd)) => commands::run_ranking_command(ranking_cmd),
+        Some(Command::Transitions(transitions_cmd)) => {
+            commands::run_transitions_command(transitions_cmd)
+        }
         Some(Command::Tls(tls_cmd)) => commands::run_tls_command(tls_cmd),
         Some(Command::Trust(trust_cmd)) => commands::run_trust_command(trust_cmd).await,
         Some(Command::Identity(identity_cmd)) => commands::run_identity_command(identity_cmd).await,
@@ -66,20 +74,55 @@ async fn main() -> ExitCode {
             );
             commands::run_stats_command(&effective_url, price).await
         }
+        Some(Command::Enrich {
+            output,
+            model,
+            force,
+            dry_run,
+        }) => {
+            commands::run_enrich_command(config_path.as_deref(), output, model, force, dry_run)
+                .await
+        }
         Some(Command::Validate {
             paths,
+            servers,
+            all_backends,
+            conformance,
+            invoke,
+            samples,
             format,
             severity,
             fix,
+            overrides,
+            rules,
+            baseline,
+            update_baseline,
             no_color,
         }) => {
             let config = ValidateConfig {
                 format,
                 min_severity: severity,
                 auto_fix: fix,
+                overrides_file: overrides,
                 color: !no_color,
+                rules_file: rules,
+                conformance: (conformance || !invoke.is_empty()).then_some(
+                    mcp_gateway::validator::conformance::ConformanceConfig {
+                        tools: invoke,
+                        samples_file: samples,
+                    },
+                ),
+                baseline,
+                update_baseline,
             };
-            mcp_gateway::validator::cli_handler::run_validate_command(&paths, &config).await
+            mcp_gateway::validator::cli_handler::run_validate_command(
+                &paths,
+                &servers,
+                all_backends,
+                config_path.as_deref(),
+                &config,
+            )
+            .await
         }
         Some(Command::Tool(tool_cmd)) => commands::run_tool_command(tool_cmd).await,
         Some(Command::Skills(SkillsCommand::Generate {
@@ -221,7 +264,7 @@ async fn main() -> ExitCode {
         #[cfg(feature = "runtime-substrate")]
         Some(Command::Runtime(rt_cmd)) => run_runtime_command(rt_cmd),
         Some(Command::Serve { stdio: true }) => Box::pin(run_stdio_server(cli)).await,
-        Some(Command::Serve { stdio: false }) | None => run_server(cli).await,
+        Some(Command::Serve { stdio: false }) | None => Box::pin(run_server(cli)).await,
     }
 }
 
@@ -0,0 +1,242 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Push-based metrics export.
+//!
+//! Some deployments cannot be scraped at `/metrics`. This module collects the
+//! gateway's usage statistics ([`UsageStats`]) and per-backend failsafe health
+//! ([`BackendStatus`]) into a flat list of [`MetricPoint`]s on a fixed
+//! interval and pushes them through a [`MetricsSink`]:
+//!
+//! - [`OtlpSink`] — OTLP/HTTP with JSON encoding, to any OpenTelemetry
+//!   collector.
+//! - [`StatsdSink`] — `StatsD` over UDP with `DogStatsD` tags.
+//!
+//! Counters are reported as cumulative totals since startup (or since the
+//! persisted stats were loaded); each sink maps them to its protocol's
+//! counter semantics.
+
+mod otlp;
+mod statsd;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::Result;
+use crate::backend::{BackendRegistry, BackendStatus};
+use crate::config::{MetricsExportConfig, MetricsExportProtocol};
+use crate::stats::{StatsSnapshot, UsageStats};
+
+pub use otlp::OtlpSink;
+pub use statsd::StatsdSink;
+
+/// How a metric value aggregates over time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Monotonic cumulative total.
+    Counter,
+    /// Point-in-time value.
+    Gauge,
+}
+
+/// One labelled metric sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricPoint {
+    /// Metric name without prefix (e.g. `invocations_total`).
+    pub name: &'static str,
+    /// Counter or gauge.
+    pub kind: MetricKind,
+    /// Sample value.
+    pub value: f64,
+    /// Label pairs, in a stable order.
+    pub labels: Vec<(&'static str, String)>,
+}
+
+impl MetricPoint {
+    #[allow(clippy::cast_precision_loss)]
+    fn counter(name: &'static str, value: u64) -> Self {
+        Self {
+            name,
+            kind: MetricKind::Counter,
+            value: value as f64,
+            labels: Vec::new(),
+        }
+    }
+
+    fn gauge(name: &'static str, value: f64) -> Self {
+        Self {
+            name,
+            kind: MetricKind::Gauge,
+            value,
+            labels: Vec::new(),
+        }
+    }
+
+    fn label(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.labels.push((key, value.into()));
+        self
+    }
+}
+
+/// Destination for periodic metric pushes.
+#[async_trait]
+pub trait MetricsSink: Send {
+    /// Push one batch of samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch could not be delivered; the exporter
+    /// logs it and retries with fresh samples on the next interval.
+    async fn export(&mut self, points: &[MetricPoint]) -> Result<()>;
+}
+
+/// Flatten a stats snapshot and backend statuses into metric samples.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn collect<S: std::hash::BuildHasher>(
+    snapshot: &StatsSnapshot,
+    statuses: &HashMap<String, BackendStatus, S>,
+) -> Vec<MetricPoint> {
+    let mut points = vec![
+        MetricPoint::counter("invocations_total", snapshot.invocations),
+        MetricPoint::counter("cache_hits_total", snapshot.cache_hits),
+        MetricPoint::counter("tools_discovered_total", snapshot.tools_discovered),
+        MetricPoint::counter("tokens_saved_total", snapshot.tokens_saved),
+        MetricPoint::gauge("cache_hit_rate", snapshot.cache_hit_rate),
+        MetricPoint::gauge("tools_available", snapshot.tools_available as f64),
+    ];
+
+    for entry in &snapshot.cached_tokens_by_server {
+        points.push(
+            MetricPoint::counter("cached_tokens_total", entry.cached_tokens)
+                .label("backend", &entry.server),
+        );
+    }
+    for (class, count) in &snapshot.errors_by_class {
+        points.push(MetricPoint::counter("errors_total", *count).label("class", class));
+    }
+    for (kind, count) in &snapshot.cache_hits_by_kind {
+        points.push(MetricPoint::counter("cache_hits_by_kind_total", *count).label("kind", kind));
+    }
+    for latency in &snapshot.latency_by_server {
+        for (quantile, value) in [
+            ("0.5", latency.p50_ms),
+            ("0.95", latency.p95_ms),
+            ("0.99", latency.p99_ms),
+        ] {
+            points.push(
+                MetricPoint::gauge("latency_ms", value)
+                    .label("backend", &latency.key)
+                    .label("quantile", quantile),
+            );
+        }
+    }
+
+    let mut names: Vec<&String> = statuses.keys().collect();
+    names.sort();
+    for name in names {
+        let status = &statuses[name];
+        let circuit = match status.circuit_state.as_str() {
+            "open" => 2.0,
+            "half_open" => 1.0,
+            _ => 0.0,
+        };
+        points.extend([
+            MetricPoint::counter("backend_requests_total", status.request_count)
+                .label("backend", name),
+            MetricPoint::gauge("backend_up", f64::from(u8::from(status.running)))
+                .label("backend", name),
+            MetricPoint::gauge("backend_healthy", f64::from(u8::from(status.healthy)))
+                .label("backend", name),
+            MetricPoint::gauge("backend_circuit_state", circuit).label("backend", name),
+            MetricPoint::gauge(
+                "backend_consecutive_failures",
+                status.consecutive_failures as f64,
+            )
+            .label("backend", name),
+        ]);
+        if let Some(p95) = status.latency_p95_ms {
+            points.push(
+                MetricPoint::gauge("backend_health_latency_p95_ms", p95 as f64)
+                    .label("backend", name),
+            );
+        }
+    }
+
+    points
+}
+
+/// Build the sink selected by `config`.
+///
+/// # Errors
+///
+/// Returns an error if the HTTP client or UDP socket cannot be created.
+pub async fn build_sink(config: &MetricsExportConfig) -> Result<Box<dyn MetricsSink>> {
+    let endpoint = config.effective_endpoint();
+    Ok(match config.protocol {
+        MetricsExportProtocol::Otlp => Box::new(OtlpSink::new(
+            endpoint,
+            &config.prefix,
+            config.headers.clone(),
+        )?),
+        MetricsExportProtocol::Statsd => {
+            Box::new(StatsdSink::connect(endpoint, &config.prefix).await?)
+        }
+    })
+}
+
+/// Spawn the periodic exporter; returns `None` when export is disabled or
+/// the sink cannot be created (logged).
+pub async fn spawn(
+    config: &MetricsExportConfig,
+    backends: Arc<BackendRegistry>,
+    stats: Arc<UsageStats>,
+    mut shutdown: broadcast::Receiver<()>,
+) -> Option<JoinHandle<()>> {
+    if !config.enabled {
+        return None;
+    }
+    let mut sink = match build_sink(config).await {
+        Ok(sink) => sink,
+        Err(e) => {
+            warn!(error = %e, "Failed to start metrics exporter");
+            return None;
+        }
+    };
+    info!(
+        protocol = ?config.protocol,
+        endpoint = config.effective_endpoint(),
+        interval = ?config.interval,
+        "Metrics export enabled"
+    );
+
+    let period = config.interval;
+    Some(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        // Skip first immediate tick (nothing has happened yet)
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let statuses = backends.statuses();
+                    let tools = statuses.values().map(|s| s.tools_cached).sum();
+                    let points = collect(&stats.snapshot(tools), &statuses);
+                    match sink.export(&points).await {
+                        Ok(()) => debug!(points = points.len(), "Metrics exported"),
+                        Err(e) => warn!(error = %e, "Metrics export failed"),
+                    }
+                }
+                _ = shutdown.recv() => {
+                    break;
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! OTLP/HTTP JSON sink.
+//!
+//! Encodes samples with the OTLP protobuf-JSON mapping so no protobuf
+//! dependency is needed; every OpenTelemetry collector accepts
+//! `Content-Type: application/json` on `/v1/metrics`. Counters become
+//! cumulative monotonic sums, gauges become gauges.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+
+use super::{MetricKind, MetricPoint, MetricsSink};
+use crate::{Error, Result};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// OTLP `AGGREGATION_TEMPORALITY_CUMULATIVE`.
+const CUMULATIVE: u8 = 2;
+
+/// Pushes metrics to an OpenTelemetry collector over OTLP/HTTP.
+pub struct OtlpSink {
+    client: reqwest::Client,
+    endpoint: String,
+    prefix: String,
+    headers: HashMap<String, String>,
+    start_time_nanos: u128,
+}
+
+impl OtlpSink {
+    /// Create a sink posting to `endpoint` (a full `…/v1/metrics` URL).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(endpoint: &str, prefix: &str, headers: HashMap<String, String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            client,
+            endpoint: endpoint.to_string(),
+            prefix: prefix.to_string(),
+            headers,
+            start_time_nanos: unix_nanos(),
+        })
+    }
+
+    /// Build the `ExportMetricsServiceRequest` JSON body.
+    pub(super) fn encode(&self, points: &[MetricPoint], now_nanos: u128) -> Value {
+        // Group samples by name so each metric carries all its data points.
+        let mut grouped: BTreeMap<&str, (MetricKind, Vec<Value>)> = BTreeMap::new();
+        for point in points {
+            let attributes: Vec<Value> = point
+                .labels
+                .iter()
+                .map(|(key, value)| json!({"key": key, "value": {"stringValue": value}}))
+                .collect();
+            grouped
+                .entry(point.name)
+                .or_insert_with(|| (point.kind, Vec::new()))
+                .1
+                .push(json!({
+                    "attributes": attributes,
+                    "startTimeUnixNano": self.start_time_nanos.to_string(),
+                    "timeUnixNano": now_nanos.to_string(),
+                    "asDouble": point.value,
+                }));
+        }
+
+        let metrics: Vec<Value> = grouped
+            .into_iter()
+            .map(|(name, (kind, data_points))| {
+                let name = metric_name(&self.prefix, name);
+                match kind {
+                    MetricKind::Counter => json!({
+                        "name": name,
+                        "sum": {
+                            "dataPoints": data_points,
+                            "aggregationTemporality": CUMULATIVE,
+                            "isMonotonic": true,
+                        },
+                    }),
+                    MetricKind::Gauge => json!({
+                        "name": name,
+                        "gauge": {"dataPoints": data_points},
+                    }),
+                }
+            })
+            .collect();
+
+        json!({
+            "resourceMetrics": [{
+                "resource": {
+                    "attributes": [
+                        {"key": "service.name", "value": {"stringValue": "mcp-gateway"}},
+                        {"key": "service.version", "value": {"stringValue": env!("CARGO_PKG_VERSION")}},
+                    ],
+                },
+                "scopeMetrics": [{
+                    "scope": {"name": "mcp-gateway", "version": env!("CARGO_PKG_VERSION")},
+                    "metrics": metrics,
+                }],
+            }],
+        })
+    }
+}
+
+#[async_trait]
+impl MetricsSink for OtlpSink {
+    async fn export(&mut self, points: &[MetricPoint]) -> Result<()> {
+        let body = self.encode(points, unix_nanos());
+        let mut request = self.client.post(&self.endpoint).json(&body);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Transport(format!(
+                "OTLP collector returned HTTP {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn metric_name(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}.{name}")
+    }
+}
+
+fn unix_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos())
+}
@@ -0,0 +1,128 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! `StatsD` UDP sink.
+//!
+//! `StatsD` counters are per-flush increments, so the sink remembers the last
+//! cumulative value of each counter series and sends the difference. Labels
+//! are sent as `DogStatsD` tags (`|#backend:github`), which Datadog, Telegraf
+//! and the Prometheus `statsd_exporter` all understand.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use tokio::net::UdpSocket;
+
+use super::{MetricKind, MetricPoint, MetricsSink};
+use crate::{Error, Result};
+
+/// Keep datagrams under a typical path MTU to avoid IP fragmentation.
+const MAX_DATAGRAM: usize = 1432;
+
+/// Pushes metrics to a `StatsD` daemon over UDP.
+pub struct StatsdSink {
+    socket: UdpSocket,
+    prefix: String,
+    last_counters: HashMap<String, f64>,
+}
+
+impl StatsdSink {
+    /// Bind a local UDP socket and connect it to `endpoint` (`host:port`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket cannot be bound or the endpoint does
+    /// not resolve.
+    pub async fn connect(endpoint: &str, prefix: &str) -> Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket
+            .connect(endpoint)
+            .await
+            .map_err(|e| Error::Transport(format!("StatsD endpoint '{endpoint}': {e}")))?;
+        Ok(Self {
+            socket,
+            prefix: prefix.to_string(),
+            last_counters: HashMap::new(),
+        })
+    }
+
+    /// Encode samples as `StatsD` lines, turning counters into deltas.
+    ///
+    /// Counter series whose value has not changed are omitted; a value lower
+    /// than last time (a reset) is sent in full.
+    pub(super) fn encode(&mut self, points: &[MetricPoint]) -> Vec<String> {
+        let mut lines = Vec::with_capacity(points.len());
+        for point in points {
+            let name = if self.prefix.is_empty() {
+                point.name.to_string()
+            } else {
+                format!("{}.{}", self.prefix, point.name)
+            };
+            let tags = if point.labels.is_empty() {
+                String::new()
+            } else {
+                let joined: Vec<String> = point
+                    .labels
+                    .iter()
+                    .map(|(key, value)| format!("{key}:{}", sanitize(value)))
+                    .collect();
+                format!("|#{}", joined.join(","))
+            };
+            match point.kind {
+                MetricKind::Gauge => lines.push(format!("{name}:{}|g{tags}", point.value)),
+                MetricKind::Counter => {
+                    let series = format!("{name}{tags}");
+                    let last = self.last_counters.insert(series, point.value);
+                    let delta = match last {
+                        Some(last) if point.value >= last => point.value - last,
+                        _ => point.value,
+                    };
+                    if delta > 0.0 {
+                        lines.push(format!("{name}:{delta}|c{tags}"));
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+#[async_trait]
+impl MetricsSink for StatsdSink {
+    async fn export(&mut self, points: &[MetricPoint]) -> Result<()> {
+        for datagram in pack(&self.encode(points)) {
+            self.socket.send(datagram.as_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Join lines into newline-separated datagrams of at most [`MAX_DATAGRAM`]
+/// bytes (a single oversized line is sent on its own).
+pub(super) fn pack(lines: &[String]) -> Vec<String> {
+    let mut datagrams = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + 1 + line.len() > MAX_DATAGRAM {
+            datagrams.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        datagrams.push(current);
+    }
+    datagrams
+}
+
+/// Replace characters that are structural in the `StatsD` line format.
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            ':' | '|' | ',' | '#' | '\n' => '_',
+            other => other,
+        })
+        .collect()
+}
@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+use super::statsd::pack;
+use super::*;
+
+fn status(name: &str, circuit: &str, healthy: bool) -> BackendStatus {
+    BackendStatus {
+        name: name.to_string(),
+        running: true,
+        transport: "stdio".to_string(),
+        tools_cached: 3,
+        circuit_state: circuit.to_string(),
+        request_count: 12,
+        healthy,
+        consecutive_failures: u64::from(!healthy) * 4,
+        latency_p95_ms: Some(40),
+        runtime: None,
+    }
+}
+
+fn sample_points() -> Vec<MetricPoint> {
+    let stats = UsageStats::new();
+    stats.record_invocation("github", "search");
+    stats.record_invocation("github", "search");
+    stats.record_error("timeout");
+    stats.record_cache_hit_for("github", "response");
+    stats.record_latency("github", "search", Duration::from_millis(8));
+    let statuses = HashMap::from([
+        ("github".to_string(), status("github", "closed", true)),
+        ("jira".to_string(), status("jira", "open", false)),
+    ]);
+    collect(&stats.snapshot(10), &statuses)
+}
+
+fn find<'a>(points: &'a [MetricPoint], name: &str, label: Option<&str>) -> &'a MetricPoint {
+    points
+        .iter()
+        .find(|p| p.name == name && label.is_none_or(|l| p.labels.iter().any(|(_, v)| v == l)))
+        .unwrap_or_else(|| panic!("missing metric {name} {label:?}"))
+}
+
+// ── collect ─────────────────────────────────────────────────────────
+
+#[test]
+fn collect_includes_usage_counters() {
+    let points = sample_points();
+    let invocations = find(&points, "invocations_total", None);
+    assert_eq!(invocations.kind, MetricKind::Counter);
+    assert!((invocations.value - 2.0).abs() < f64::EPSILON);
+    assert!((find(&points, "errors_total", Some("timeout")).value - 1.0).abs() < f64::EPSILON);
+    assert!(
+        (find(&points, "cache_hits_by_kind_total", Some("response")).value - 1.0).abs()
+            < f64::EPSILON
+    );
+}
+
+#[test]
+fn collect_includes_latency_quantiles() {
+    let points = sample_points();
+    let quantiles: Vec<&str> = points
+        .iter()
+        .filter(|p| p.name == "latency_ms")
+        .flat_map(|p| p.labels.iter().filter(|(k, _)| *k == "quantile"))
+        .map(|(_, v)| v.as_str())
+        .collect();
+    assert_eq!(quantiles, vec!["0.5", "0.95", "0.99"]);
+}
+
+#[test]
+fn collect_includes_backend_health() {
+    let points = sample_points();
+    let jira_circuit = find(&points, "backend_circuit_state", Some("jira"));
+    assert!((jira_circuit.value - 2.0).abs() < f64::EPSILON);
+    let jira_healthy = find(&points, "backend_healthy", Some("jira"));
+    assert!(jira_healthy.value.abs() < f64::EPSILON);
+    let github_healthy = find(&points, "backend_healthy", Some("github"));
+    assert!((github_healthy.value - 1.0).abs() < f64::EPSILON);
+    assert_eq!(
+        find(&points, "backend_requests_total", Some("github")).kind,
+        MetricKind::Counter
+    );
+}
+
+// ── OTLP ────────────────────────────────────────────────────────────
+
+#[test]
+fn otlp_encode_groups_points_by_metric() {
+    let sink = OtlpSink::new("http://localhost:4318/v1/metrics", "gw", HashMap::new()).unwrap();
+    let body = sink.encode(&sample_points(), 42);
+
+    let metrics = body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+        .as_array()
+        .unwrap();
+    let healthy = metrics
+        .iter()
+        .find(|m| m["name"] == "gw.backend_healthy")
+        .unwrap();
+    assert_eq!(healthy["gauge"]["dataPoints"].as_array().unwrap().len(), 2);
+
+    let invocations = metrics
+        .iter()
+        .find(|m| m["name"] == "gw.invocations_total")
+        .unwrap();
+    assert_eq!(invocations["sum"]["isMonotonic"], true);
+    assert_eq!(invocations["sum"]["aggregationTemporality"], 2);
+    assert_eq!(invocations["sum"]["dataPoints"][0]["asDouble"], 2.0);
+    assert_eq!(invocations["sum"]["dataPoints"][0]["timeUnixNano"], "42");
+}
+
+#[test]
+fn otlp_encode_maps_labels_to_attributes() {
+    let sink = OtlpSink::new("http://localhost:4318/v1/metrics", "", HashMap::new()).unwrap();
+    let points = [MetricPoint::counter("errors_total", 3).label("class", "timeout")];
+    let body = sink.encode(&points, 1);
+    let point = &body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"][0];
+    assert_eq!(point["name"], "errors_total");
+    assert_eq!(
+        point["sum"]["dataPoints"][0]["attributes"][0],
+        serde_json::json!({"key": "class", "value": {"stringValue": "timeout"}})
+    );
+}
+
+// ── StatsD ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn statsd_encode_sends_counter_deltas_and_gauges() {
+    let mut sink = StatsdSink::connect("127.0.0.1:9", "gw").await.unwrap();
+    let first = [
+        MetricPoint::counter("invocations_total", 5),
+        MetricPoint::gauge("backend_healthy", 1.0).label("backend", "github"),
+    ];
+    assert_eq!(
+        sink.encode(&first),
+        vec![
+            "gw.invocations_total:5|c".to_string(),
+            "gw.backend_healthy:1|g|#backend:github".to_string(),
+        ]
+    );
+
+    // Unchanged counters are omitted; increases send only the delta.
+    assert_eq!(
+        sink.encode(&[MetricPoint::counter("invocations_total", 5)]),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        sink.encode(&[MetricPoint::counter("invocations_total", 8)]),
+        vec!["gw.invocations_total:3|c".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn statsd_encode_sanitizes_tag_values() {
+    let mut sink = StatsdSink::connect("127.0.0.1:9", "").await.unwrap();
+    let lines = sink.encode(&[MetricPoint::gauge("latency_ms", 1.5).label("backend", "a:b|c")]);
+    assert_eq!(lines, vec!["latency_ms:1.5|g|#backend:a_b_c".to_string()]);
+}
+
+#[test]
+fn pack_splits_lines_across_datagrams() {
+    let lines: Vec<String> = (0..100).map(|i| format!("gw.metric_{i:03}:1|c")).collect();
+    let datagrams = pack(&lines);
+    assert!(datagrams.len() > 1);
+    assert!(datagrams.iter().all(|d| d.len() <= 1432));
+    assert_eq!(
+        datagrams.iter().map(|d| d.lines().count()).sum::<usize>(),
+        100
+    );
+}
+
+#[tokio::test]
+async fn statsd_export_delivers_udp_datagram() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = receiver.local_addr().unwrap().to_string();
+    let mut sink = StatsdSink::connect(&addr, "gw").await.unwrap();
+
+    sink.export(&[MetricPoint::counter("invocations_total", 2)])
+        .await
+        .unwrap();
+
+    let mut buf = [0u8; 512];
+    let len = tokio::time::timeout(Duration::from_secs(2), receiver.recv(&mut buf))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&buf[..len], b"gw.invocations_total:2|c");
+}
+
+// ── spawn ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn spawn_is_noop_when_disabled() {
+    let (_tx, rx) = broadcast::channel(1);
+    let handle = spawn(
+        &MetricsExportConfig::default(),
+        Arc::new(BackendRegistry::new()),
+        Arc::new(UsageStats::new()),
+        rx,
+    )
+    .await;
+    assert!(handle.is_none());
+}
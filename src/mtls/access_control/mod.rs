@@ -169,6 +169,7 @@ struct CompiledCriteria {
     ou: Option<GlobPattern>,
     san_uri: Option<GlobPattern>,
     san_dns: Option<GlobPattern>,
+    spiffe_id: Option<GlobPattern>,
     /// `true` means match-all (catch-all rule)
     any: bool,
 }
@@ -180,6 +181,7 @@ impl CompiledCriteria {
             ou: cfg.ou.as_deref().map(GlobPattern::new),
             san_uri: cfg.san_uri.as_deref().map(GlobPattern::new),
             san_dns: cfg.san_dns.as_deref().map(GlobPattern::new),
+            spiffe_id: cfg.spiffe_id.as_deref().map(GlobPattern::new),
             any: cfg.any.unwrap_or(false),
         }
     }
@@ -216,10 +218,22 @@ impl CompiledCriteria {
         {
             return false;
         }
+        if let Some(ref pat) = self.spiffe_id
+            && !identity
+                .spiffe_id
+                .as_deref()
+                .is_some_and(|id| pat.matches(id))
+        {
+            return false;
+        }
         // At least one criterion must have been specified (otherwise it's a
         // vacuously-true rule with no match fields — we treat as no-match to
         // avoid accidental allow-all from an empty `match:` block).
-        self.cn.is_some() || self.ou.is_some() || self.san_uri.is_some() || self.san_dns.is_some()
+        self.cn.is_some()
+            || self.ou.is_some()
+            || self.san_uri.is_some()
+            || self.san_dns.is_some()
+            || self.spiffe_id.is_some()
     }
 }
 
@@ -16,6 +16,7 @@ fn identity(
         organizational_unit: ou.map(str::to_owned),
         san_uris: san_uris.iter().map(|s| (*s).to_owned()).collect(),
         san_dns_names: san_dns.iter().map(|s| (*s).to_owned()).collect(),
+        spiffe_id: None,
         display_name: cn.unwrap_or("<unknown>").to_owned(),
     }
 }
@@ -37,6 +38,7 @@ fn rule(
             ou: ou.map(str::to_owned),
             san_uri: san_uri.map(str::to_owned),
             san_dns: None,
+            spiffe_id: None,
             any,
         },
         allow: ToolScopeConfig {
@@ -259,6 +261,82 @@ fn san_uri_glob_does_not_match_different_spiffe_path() {
     );
 }
 
+// ── SPIFFE ID matching ────────────────────────────────────────────────────
+
+fn spiffe_rule(pattern: &str) -> PolicyRuleConfig {
+    PolicyRuleConfig {
+        match_criteria: CertMatchConfig {
+            spiffe_id: Some(pattern.to_owned()),
+            ..CertMatchConfig::default()
+        },
+        allow: ToolScopeConfig {
+            backends: vec!["*".to_owned()],
+            tools: vec!["*".to_owned()],
+        },
+        deny: ToolScopeConfig::default(),
+    }
+}
+
+fn svid(spiffe_id: Option<&str>, san_uris: &[&str]) -> CertIdentity {
+    CertIdentity {
+        san_uris: san_uris.iter().map(|s| (*s).to_owned()).collect(),
+        spiffe_id: spiffe_id.map(str::to_owned),
+        ..CertIdentity::default()
+    }
+}
+
+#[test]
+fn spiffe_id_glob_matches_workload() {
+    // GIVEN: a rule for every agent workload in the trust domain
+    let policy = policy_with_rules(vec![spiffe_rule("spiffe://org/agent/*")]);
+    let agent = svid(
+        Some("spiffe://org/agent/cursor"),
+        &["spiffe://org/agent/cursor"],
+    );
+    let ci = svid(Some("spiffe://org/ci/runner"), &["spiffe://org/ci/runner"]);
+
+    // THEN: only the agent is allowed
+    assert_eq!(
+        policy.evaluate(Some(&agent), "github", "search"),
+        PolicyDecision::Allow
+    );
+    assert_eq!(
+        policy.evaluate(Some(&ci), "github", "search"),
+        PolicyDecision::Deny
+    );
+}
+
+#[test]
+fn spiffe_id_rule_ignores_uri_sans_without_a_spiffe_id() {
+    // GIVEN: a cert whose SPIFFE URIs did not yield an SVID identity
+    // (e.g. two of them)
+    let policy = policy_with_rules(vec![spiffe_rule("spiffe://org/agent/*")]);
+    let ambiguous = svid(None, &["spiffe://org/agent/a", "spiffe://org/agent/b"]);
+
+    // THEN: the rule does not fire
+    assert_eq!(
+        policy.evaluate(Some(&ambiguous), "github", "search"),
+        PolicyDecision::Deny
+    );
+}
+
+#[test]
+fn spiffe_id_is_parsed_from_policy_yaml() {
+    let yaml = r#"
+enabled: true
+policies:
+  - match: { spiffe_id: "spiffe://org/agent/*" }
+    allow: { backends: ["*"], tools: ["*"] }
+"#;
+    let cfg: MtlsConfig = serde_yaml::from_str(yaml).unwrap();
+    let policy = MtlsPolicy::from_config(&cfg);
+    let agent = svid(Some("spiffe://org/agent/claude"), &[]);
+    assert_eq!(
+        policy.evaluate(Some(&agent), "tavily", "search"),
+        PolicyDecision::Allow
+    );
+}
+
 // ── catch-all rule ────────────────────────────────────────────────────────
 
 #[test]
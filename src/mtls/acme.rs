@@ -0,0 +1,742 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! ACME (Let's Encrypt) server certificates for the mTLS listener.
+//!
+//! With `mtls.acme.enabled`, the gateway obtains `server_cert` /
+//! `server_key` from the ACME CA itself and renews them
+//! `renew_before_days` before they expire.  The files are replaced
+//! atomically and the [`CertReloader`](super::CertReloader) swaps them into
+//! the running listener.
+//!
+//! # Flow
+//!
+//! 1. Startup: if there is no certificate yet, a self-signed placeholder for
+//!    the domains is written so the listener can start.
+//! 2. A background task orders a certificate whenever the current one is a
+//!    placeholder, does not cover every domain, or is due for renewal; then
+//!    checks again every 12 hours.  A failed order is retried after an hour.
+//! 3. Domain control is proven with
+//!    - **HTTP-01**: a responder on `http_listen` serves
+//!      `/.well-known/acme-challenge/<token>`, or
+//!    - **TLS-ALPN-01**: the listener answers handshakes offering ALPN
+//!      `acme-tls/1` with the challenge certificate ([`accept`]).  These
+//!      bypass client certificate checks and carry no HTTP traffic.
+//!
+//! The ACME account is created on first use and its credentials kept in
+//! `state_dir/account.json`.  Creating it agrees to the CA's terms of
+//! service.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::extract::{Path as UrlPath, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum_server::tls_rustls::RustlsConfig;
+use dashmap::DashMap;
+use instant_acme::{
+    Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, NewAccount,
+    NewOrder, Order, OrderStatus,
+};
+use rcgen::{CertificateParams, CustomExtension, DnType, KeyPair};
+use rustls::crypto::aws_lc_rs::sign::any_supported_type;
+use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::sign::{CertifiedKey, SingleCertAndKey};
+use rustls::{ServerConfig, version};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{broadcast, mpsc};
+use tokio_rustls::LazyConfigAcceptor;
+use tokio_rustls::server::TlsStream;
+use tracing::{debug, info, warn};
+use x509_parser::extensions::GeneralName;
+
+use super::config::{AcmeChallenge, AcmeConfig, MtlsConfig};
+use crate::config_reload::expand_tilde;
+use crate::{Error, Result};
+
+/// ALPN protocol of TLS-ALPN-01 validation handshakes (RFC 8737).
+const ACME_TLS_ALPN: &[u8] = b"acme-tls/1";
+
+/// Time between certificate checks.
+const CHECK_INTERVAL: Duration = Duration::from_secs(12 * 3600);
+
+/// Time before retrying a failed order.
+const RETRY_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Order status polls before giving up.
+const MAX_POLLS: u32 = 10;
+
+/// Handshake deadline on the TLS-ALPN-01 accept path.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Common name of the self-signed startup certificate.
+const PLACEHOLDER_CN: &str = "mcp-gateway ACME placeholder";
+
+/// HTTP-01 token → key authorization.
+type Http01Tokens = Arc<DashMap<String, String>>;
+
+/// TLS-ALPN-01 challenge certificates, by domain.
+#[derive(Debug, Clone, Default)]
+pub struct AlpnChallenges {
+    configs: Arc<DashMap<String, Arc<ServerConfig>>>,
+}
+
+impl AlpnChallenges {
+    /// The challenge config for a handshake to `server_name` offering
+    /// `alpn`, if it is a TLS-ALPN-01 validation for a pending challenge.
+    pub fn resolve<'a>(
+        &self,
+        server_name: Option<&str>,
+        mut alpn: impl Iterator<Item = &'a [u8]>,
+    ) -> Option<Arc<ServerConfig>> {
+        if !alpn.any(|protocol| protocol == ACME_TLS_ALPN) {
+            return None;
+        }
+        self.configs
+            .get(server_name?)
+            .map(|config| Arc::clone(&config))
+    }
+}
+
+/// Obtains and renews the listener's server certificate.
+pub struct AcmeManager {
+    alpn: AlpnChallenges,
+}
+
+impl AcmeManager {
+    /// Start the challenge responder and the renewal task.
+    ///
+    /// `reload_tx` is signalled after a new certificate is written; the
+    /// task stops on `shutdown_rx`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP-01 responder cannot bind `http_listen`.
+    pub async fn start(
+        mtls: &MtlsConfig,
+        reload_tx: mpsc::Sender<()>,
+        shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<Self> {
+        let http01 = Http01Tokens::default();
+        let alpn = AlpnChallenges::default();
+        if mtls.acme.challenge == AcmeChallenge::Http01 {
+            serve_http01(
+                &mtls.acme.http_listen,
+                Arc::clone(&http01),
+                shutdown_rx.resubscribe(),
+            )
+            .await?;
+        }
+
+        let renewer = Renewer {
+            config: mtls.acme.clone(),
+            cert_path: PathBuf::from(&mtls.server_cert),
+            key_path: PathBuf::from(&mtls.server_key),
+            http01,
+            alpn: alpn.clone(),
+        };
+        tokio::spawn(renewer.run(reload_tx, shutdown_rx));
+
+        info!(
+            domains = ?mtls.acme.domains,
+            challenge = ?mtls.acme.challenge,
+            directory = %mtls.acme.directory_url,
+            "ACME certificate management started"
+        );
+        Ok(Self { alpn })
+    }
+
+    /// The TLS-ALPN-01 challenges for [`accept`].
+    #[must_use]
+    pub fn alpn_challenges(&self) -> AlpnChallenges {
+        self.alpn.clone()
+    }
+}
+
+/// Write a self-signed certificate for the ACME domains if `server_cert` or
+/// `server_key` does not exist yet, so the listener can start before the
+/// first order completes.
+///
+/// # Errors
+///
+/// Returns an error if the certificate cannot be generated or written.
+pub fn ensure_placeholder(mtls: &MtlsConfig) -> Result<()> {
+    let cert_path = Path::new(&mtls.server_cert);
+    let key_path = Path::new(&mtls.server_key);
+    if cert_path.exists() && key_path.exists() {
+        return Ok(());
+    }
+    let mut params = CertificateParams::new(mtls.acme.domains.clone())
+        .map_err(|e| Error::Tls(format!("ACME placeholder: {e}")))?;
+    params
+        .distinguished_name
+        .push(DnType::CommonName, PLACEHOLDER_CN);
+    let key = KeyPair::generate().map_err(|e| Error::Tls(format!("ACME placeholder: {e}")))?;
+    let cert = params
+        .self_signed(&key)
+        .map_err(|e| Error::Tls(format!("ACME placeholder: {e}")))?;
+    write_atomic(key_path, key.serialize_pem().as_bytes(), true)?;
+    write_atomic(cert_path, cert.pem().as_bytes(), false)?;
+    warn!(
+        server_cert = %mtls.server_cert,
+        "No server certificate yet; serving a self-signed placeholder until ACME issues one"
+    );
+    Ok(())
+}
+
+/// Accept a TLS connection, answering TLS-ALPN-01 validation handshakes
+/// with the challenge certificate and all others with `tls`.
+///
+/// # Errors
+///
+/// Returns an error if the handshake fails or times out, and for answered
+/// validation handshakes, which carry no HTTP traffic.
+pub async fn accept<I>(
+    stream: I,
+    challenges: &AlpnChallenges,
+    tls: &RustlsConfig,
+) -> io::Result<TlsStream<I>>
+where
+    I: AsyncRead + AsyncWrite + Unpin,
+{
+    let handshake = async {
+        let start = LazyConfigAcceptor::new(rustls::server::Acceptor::default(), stream).await?;
+        let challenge = {
+            let hello = start.client_hello();
+            challenges.resolve(hello.server_name(), hello.alpn().into_iter().flatten())
+        };
+        if let Some(config) = challenge {
+            // The validator only reads the certificate.
+            let _ = start.into_stream(config).await;
+            debug!("ACME TLS-ALPN-01 validation answered");
+            return Err(io::Error::other("ACME TLS-ALPN-01 validation handshake"));
+        }
+        start.into_stream(tls.get_inner()).await
+    };
+    tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "TLS handshake timed out"))?
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Renewal
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The renewal task's state.
+struct Renewer {
+    config: AcmeConfig,
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    http01: Http01Tokens,
+    alpn: AlpnChallenges,
+}
+
+impl Renewer {
+    /// Check, renew and wait until shutdown.
+    async fn run(self, reload_tx: mpsc::Sender<()>, mut shutdown_rx: broadcast::Receiver<()>) {
+        loop {
+            let wait = match self.renew_if_due().await {
+                Ok(renewed) => {
+                    if renewed {
+                        let _ = reload_tx.send(()).await;
+                    }
+                    CHECK_INTERVAL
+                }
+                Err(e) => {
+                    warn!(error = %e, "ACME certificate order failed; retrying in an hour");
+                    RETRY_INTERVAL
+                }
+            };
+            tokio::select! {
+                () = tokio::time::sleep(wait) => {}
+                _ = shutdown_rx.recv() => {
+                    debug!("ACME renewal task shutting down");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Order a new certificate if the current one is due.  Returns whether
+    /// new files were written.
+    async fn renew_if_due(&self) -> Result<bool> {
+        let current = std::fs::read(&self.cert_path).unwrap_or_default();
+        let renew_before = Duration::from_secs(u64::from(self.config.renew_before_days) * 86_400);
+        if !needs_renewal(&current, &self.config.domains, renew_before, unix_now()) {
+            debug!("ACME certificate is current");
+            return Ok(false);
+        }
+
+        info!(domains = ?self.config.domains, "Ordering ACME certificate");
+        let result = self.order().await;
+        self.http01.clear();
+        self.alpn.configs.clear();
+        let (chain_pem, key_pem) = result?;
+
+        write_atomic(&self.key_path, key_pem.as_bytes(), true)?;
+        write_atomic(&self.cert_path, chain_pem.as_bytes(), false)?;
+        info!(domains = ?self.config.domains, "ACME certificate issued");
+        Ok(true)
+    }
+
+    /// Run one order to completion; returns the chain and key PEM.
+    async fn order(&self) -> Result<(String, String)> {
+        let account = self.account().await?;
+        let identifiers: Vec<Identifier> = self
+            .config
+            .domains
+            .iter()
+            .map(|domain| Identifier::Dns(domain.clone()))
+            .collect();
+        let mut order = account
+            .new_order(&NewOrder {
+                identifiers: &identifiers,
+            })
+            .await
+            .map_err(acme_error)?;
+
+        self.answer_challenges(&mut order).await?;
+        wait_for(&mut order, OrderStatus::Ready).await?;
+
+        let key = KeyPair::generate().map_err(|e| Error::Tls(format!("ACME key: {e}")))?;
+        let csr = CertificateParams::new(self.config.domains.clone())
+            .and_then(|params| params.serialize_request(&key))
+            .map_err(|e| Error::Tls(format!("ACME CSR: {e}")))?;
+        order
+            .finalize(csr.der().as_ref())
+            .await
+            .map_err(acme_error)?;
+
+        let mut delay = Duration::from_secs(1);
+        for _ in 0..MAX_POLLS {
+            if let Some(chain) = order.certificate().await.map_err(acme_error)? {
+                return Ok((chain, key.serialize_pem()));
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(Duration::from_secs(10));
+        }
+        Err(Error::Tls(
+            "ACME certificate was not issued in time".to_string(),
+        ))
+    }
+
+    /// Publish the configured challenge for every pending authorization.
+    async fn answer_challenges(&self, order: &mut Order) -> Result<()> {
+        let wanted = match self.config.challenge {
+            AcmeChallenge::Http01 => ChallengeType::Http01,
+            AcmeChallenge::TlsAlpn01 => ChallengeType::TlsAlpn01,
+        };
+        let authorizations = order.authorizations().await.map_err(acme_error)?;
+        for authz in &authorizations {
+            match authz.status {
+                AuthorizationStatus::Pending => {}
+                AuthorizationStatus::Valid => continue,
+                status => {
+                    return Err(Error::Tls(format!(
+                        "ACME authorization for {:?} is {status:?}",
+                        authz.identifier
+                    )));
+                }
+            }
+            let Identifier::Dns(domain) = &authz.identifier;
+            let challenge = authz
+                .challenges
+                .iter()
+                .find(|c| c.r#type == wanted)
+                .ok_or_else(|| {
+                    Error::Tls(format!(
+                        "ACME CA offers no {wanted:?} challenge for {domain}"
+                    ))
+                })?;
+
+            let key_auth = order.key_authorization(challenge);
+            match self.config.challenge {
+                AcmeChallenge::Http01 => {
+                    self.http01
+                        .insert(challenge.token.clone(), key_auth.as_str().to_string());
+                }
+                AcmeChallenge::TlsAlpn01 => {
+                    let config = alpn_challenge_config(domain, key_auth.digest().as_ref())?;
+                    self.alpn.configs.insert(domain.clone(), config);
+                }
+            }
+            order
+                .set_challenge_ready(&challenge.url)
+                .await
+                .map_err(acme_error)?;
+        }
+        Ok(())
+    }
+
+    /// Load the ACME account, creating it on first use.
+    async fn account(&self) -> Result<Account> {
+        let path = expand_tilde(&self.config.state_dir).join("account.json");
+        if let Ok(json) = std::fs::read_to_string(&path) {
+            let credentials: AccountCredentials = serde_json::from_str(&json).map_err(|e| {
+                Error::Config(format!("Invalid ACME account '{}': {e}", path.display()))
+            })?;
+            return Account::from_credentials(credentials)
+                .await
+                .map_err(acme_error);
+        }
+
+        let contact = contact_uris(&self.config.contact);
+        let contact: Vec<&str> = contact.iter().map(String::as_str).collect();
+        let (account, credentials) = Account::create(
+            &NewAccount {
+                contact: &contact,
+                terms_of_service_agreed: true,
+                only_return_existing: false,
+            },
+            &self.config.directory_url,
+            None,
+        )
+        .await
+        .map_err(acme_error)?;
+
+        let json = serde_json::to_vec_pretty(&credentials)
+            .map_err(|e| Error::Config(format!("Cannot serialize ACME account: {e}")))?;
+        write_atomic(&path, &json, true)?;
+        info!(path = %path.display(), "ACME account created");
+        Ok(account)
+    }
+}
+
+/// Poll `order` until it reaches `target`.
+async fn wait_for(order: &mut Order, target: OrderStatus) -> Result<()> {
+    let mut delay = Duration::from_millis(500);
+    for _ in 0..MAX_POLLS {
+        tokio::time::sleep(delay).await;
+        let state = order.refresh().await.map_err(acme_error)?;
+        if state.status == target {
+            return Ok(());
+        }
+        if state.status == OrderStatus::Invalid {
+            return Err(Error::Tls(format!(
+                "ACME order is invalid: {:?}",
+                state.error
+            )));
+        }
+        delay = (delay * 2).min(Duration::from_secs(10));
+    }
+    Err(Error::Tls(format!(
+        "ACME order did not become {target:?} in time"
+    )))
+}
+
+/// Whether the certificate in `pem` must be replaced: it is missing,
+/// self-signed, misses one of `domains`, or expires within `renew_before`.
+fn needs_renewal(pem: &[u8], domains: &[String], renew_before: Duration, now: i64) -> bool {
+    let Ok((_, pem)) = x509_parser::pem::parse_x509_pem(pem) else {
+        return true;
+    };
+    let Ok(cert) = pem.parse_x509() else {
+        return true;
+    };
+    if cert.subject().as_raw() == cert.issuer().as_raw() {
+        return true;
+    }
+    let remaining = cert.validity().not_after.timestamp() - now;
+    if remaining < i64::try_from(renew_before.as_secs()).unwrap_or(i64::MAX) {
+        return true;
+    }
+    let names: Vec<&str> = match cert.subject_alternative_name() {
+        Ok(Some(san)) => san
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DNSName(dns) => Some(*dns),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    !domains
+        .iter()
+        .all(|domain| names.contains(&domain.as_str()))
+}
+
+/// Contact addresses as `mailto:` URIs.
+fn contact_uris(contact: &[String]) -> Vec<String> {
+    contact
+        .iter()
+        .map(|c| {
+            if c.starts_with("mailto:") {
+                c.clone()
+            } else {
+                format!("mailto:{c}")
+            }
+        })
+        .collect()
+}
+
+/// A TLS-ALPN-01 challenge config for `domain`: a self-signed certificate
+/// carrying the `acmeIdentifier` extension with `digest`.
+fn alpn_challenge_config(domain: &str, digest: &[u8]) -> Result<Arc<ServerConfig>> {
+    let mut params = CertificateParams::new(vec![domain.to_string()])
+        .map_err(|e| Error::Tls(format!("ACME challenge cert: {e}")))?;
+    params.custom_extensions = vec![CustomExtension::new_acme_identifier(digest)];
+    let key = KeyPair::generate().map_err(|e| Error::Tls(format!("ACME challenge cert: {e}")))?;
+    let cert = params
+        .self_signed(&key)
+        .map_err(|e| Error::Tls(format!("ACME challenge cert: {e}")))?;
+
+    // Not `with_single_cert`: its key check parses the certificate with
+    // webpki, which rejects the critical `acmeIdentifier` extension.
+    let signing_key = any_supported_type(&PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(
+        key.serialize_der(),
+    )))
+    .map_err(|e| Error::Tls(format!("ACME challenge cert: {e}")))?;
+    let certified = CertifiedKey::new(vec![cert.der().clone()], signing_key);
+    let mut config = ServerConfig::builder_with_protocol_versions(&[&version::TLS13])
+        .with_no_client_auth()
+        .with_cert_resolver(Arc::new(SingleCertAndKey::from(certified)));
+    config.alpn_protocols = vec![ACME_TLS_ALPN.to_vec()];
+    Ok(Arc::new(config))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// HTTP-01 responder
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Serve HTTP-01 key authorizations on `listen` until shutdown.
+async fn serve_http01(
+    listen: &str,
+    tokens: Http01Tokens,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .map_err(|e| Error::Tls(format!("ACME HTTP-01 responder cannot bind {listen}: {e}")))?;
+    let app = http01_routes(tokens);
+    tokio::spawn(async move {
+        let shutdown = async move {
+            let _ = shutdown_rx.recv().await;
+        };
+        if let Err(e) = axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown)
+            .await
+        {
+            warn!(error = %e, "ACME HTTP-01 responder failed");
+        }
+    });
+    info!(listen, "ACME HTTP-01 responder listening");
+    Ok(())
+}
+
+fn http01_routes(tokens: Http01Tokens) -> Router {
+    Router::new()
+        .route("/.well-known/acme-challenge/{token}", get(http01_response))
+        .with_state(tokens)
+}
+
+async fn http01_response(
+    State(tokens): State<Http01Tokens>,
+    UrlPath(token): UrlPath<String>,
+) -> Response {
+    match tokens.get(&token) {
+        Some(key_auth) => key_auth.clone().into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[allow(clippy::needless_pass_by_value)] // used as a `map_err` callback
+fn acme_error(e: instant_acme::Error) -> Error {
+    Error::Tls(format!("ACME: {e}"))
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX))
+}
+
+/// Replace `path` with `contents` via a rename, so readers never see a
+/// partial file.  `private` files are created mode 0600.
+fn write_atomic(path: &Path, contents: &[u8], private: bool) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| Error::Config(format!("Cannot create dir '{}': {e}", dir.display())))?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    let mut file = options
+        .open(&tmp)
+        .map_err(|e| Error::Config(format!("Cannot write '{}': {e}", tmp.display())))?;
+    file.write_all(contents)
+        .and_then(|()| file.sync_all())
+        .map_err(|e| Error::Config(format!("Cannot write '{}': {e}", tmp.display())))?;
+    std::fs::rename(&tmp, path)
+        .map_err(|e| Error::Config(format!("Cannot replace '{}': {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtls::cert_manager::{CaParams, CertGenerator, LeafCertParams, load_certs};
+    use axum::body::{Body, to_bytes};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    fn domains() -> Vec<String> {
+        vec!["gateway.example.com".to_string()]
+    }
+
+    fn issued_cert(san_dns: Vec<String>, validity_days: u32) -> String {
+        let ca = CertGenerator::init_ca(&CaParams {
+            cn: "Test ACME CA",
+            validity_days: 365,
+        })
+        .unwrap();
+        CertGenerator::issue_leaf(
+            &LeafCertParams {
+                cn: "gateway.example.com",
+                ou: None,
+                san_dns,
+                san_uris: vec![],
+                validity_days,
+            },
+            &ca.cert_pem,
+            &ca.key_pem,
+        )
+        .unwrap()
+        .cert_pem
+    }
+
+    #[test]
+    fn certificate_is_renewed_when_due_or_not_covering_the_domains() {
+        let now = unix_now();
+        let fresh = issued_cert(domains(), 90);
+        assert!(!needs_renewal(fresh.as_bytes(), &domains(), 30 * DAY, now));
+
+        // Expiring within the renewal window
+        let expiring = issued_cert(domains(), 10);
+        assert!(needs_renewal(
+            expiring.as_bytes(),
+            &domains(),
+            30 * DAY,
+            now
+        ));
+
+        // A domain was added to the config
+        let more = vec![
+            "gateway.example.com".to_string(),
+            "mcp.example.com".to_string(),
+        ];
+        assert!(needs_renewal(fresh.as_bytes(), &more, 30 * DAY, now));
+
+        // Missing or unreadable
+        assert!(needs_renewal(b"", &domains(), 30 * DAY, now));
+    }
+
+    #[test]
+    fn placeholder_is_written_once_and_always_renewed() {
+        // GIVEN: no certificate on disk
+        let dir = tempfile::tempdir().unwrap();
+        let mtls = MtlsConfig {
+            server_cert: dir.path().join("server.crt").to_str().unwrap().to_string(),
+            server_key: dir.path().join("server.key").to_str().unwrap().to_string(),
+            acme: AcmeConfig {
+                enabled: true,
+                domains: domains(),
+                ..AcmeConfig::default()
+            },
+            ..MtlsConfig::default()
+        };
+
+        // WHEN: preparing the listener
+        ensure_placeholder(&mtls).unwrap();
+
+        // THEN: a loadable self-signed certificate exists, which is ordered over
+        let pem = std::fs::read(&mtls.server_cert).unwrap();
+        assert_eq!(load_certs(&mtls.server_cert).unwrap().len(), 1);
+        assert!(needs_renewal(&pem, &domains(), 30 * DAY, unix_now()));
+
+        // AND: an existing certificate is left alone
+        std::fs::write(&mtls.server_cert, "issued").unwrap();
+        ensure_placeholder(&mtls).unwrap();
+        assert_eq!(std::fs::read(&mtls.server_cert).unwrap(), b"issued");
+    }
+
+    #[test]
+    fn alpn_challenge_is_served_only_for_validation_handshakes() {
+        let challenges = AlpnChallenges::default();
+        let config = alpn_challenge_config("gateway.example.com", &[0u8; 32]).unwrap();
+        assert_eq!(config.alpn_protocols, vec![ACME_TLS_ALPN.to_vec()]);
+        challenges
+            .configs
+            .insert("gateway.example.com".to_string(), config);
+
+        let acme: [&[u8]; 1] = [ACME_TLS_ALPN];
+        let h2: [&[u8]; 1] = [b"h2"];
+        assert!(
+            challenges
+                .resolve(Some("gateway.example.com"), acme.into_iter())
+                .is_some()
+        );
+        // Ordinary clients of the same name get the real certificate
+        assert!(
+            challenges
+                .resolve(Some("gateway.example.com"), h2.into_iter())
+                .is_none()
+        );
+        // Unknown names and missing SNI never match
+        assert!(
+            challenges
+                .resolve(Some("other.example.com"), acme.into_iter())
+                .is_none()
+        );
+        assert!(challenges.resolve(None, acme.into_iter()).is_none());
+    }
+
+    #[tokio::test]
+    async fn http01_responder_serves_pending_tokens() {
+        let tokens = Http01Tokens::default();
+        tokens.insert("abc".to_string(), "abc.thumbprint".to_string());
+
+        let request = |path: &str| Request::builder().uri(path).body(Body::empty()).unwrap();
+        let found = http01_routes(Arc::clone(&tokens))
+            .oneshot(request("/.well-known/acme-challenge/abc"))
+            .await
+            .unwrap();
+        assert_eq!(found.status(), StatusCode::OK);
+        let body = to_bytes(found.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"abc.thumbprint");
+
+        let missing = http01_routes(tokens)
+            .oneshot(request("/.well-known/acme-challenge/other"))
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn contact_addresses_become_mailto_uris() {
+        assert_eq!(
+            contact_uris(&["ops@example.com".to_string(), "mailto:a@b.c".to_string()]),
+            vec!["mailto:ops@example.com", "mailto:a@b.c"]
+        );
+    }
+}
@@ -4,6 +4,7 @@
 //!
 //! Provides:
 //! - [`build_tls_config`] — build a `rustls::ServerConfig` from `MtlsConfig`
+//! - [`build_tls_config_with_crls`] — the same, adding downloaded CRLs
 //! - [`load_certs`] / [`load_private_key`] — PEM file loading
 //! - [`CertGenerator`] — `rcgen`-backed cert generation for `mcp-gateway tls` CLI commands
 //!
@@ -27,12 +28,13 @@ use rcgen::{
     date_time_ymd,
 };
 use rustls::pki_types::pem::PemObject;
-use rustls::pki_types::{CertificateDer, CertificateRevocationListDer, PrivateKeyDer};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use rustls::server::WebPkiClientVerifier;
 use rustls::{ServerConfig, version};
 use tracing::debug;
 
-use crate::mtls::config::MtlsConfig;
+use crate::mtls::config::{MtlsConfig, RevocationFailMode};
+use crate::mtls::revocation::{Crl, parse_crls};
 use crate::{Error, Result};
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -58,6 +60,17 @@ use crate::{Error, Result};
 /// Returns an error if any certificate or key file cannot be read or parsed,
 /// or if the rustls config cannot be built (e.g. mismatched cert/key pair).
 pub fn build_tls_config(config: &MtlsConfig) -> Result<ServerConfig> {
+    build_tls_config_with_crls(config, &[])
+}
+
+/// Build the mTLS `rustls::ServerConfig`, checking client certificates
+/// against `crls` (downloaded from `revocation.crl_urls`) as well as
+/// `crl_path`.
+///
+/// # Errors
+///
+/// As [`build_tls_config`].
+pub fn build_tls_config_with_crls(config: &MtlsConfig, crls: &[Crl]) -> Result<ServerConfig> {
     let server_certs = load_certs(&config.server_cert)?;
     let server_key = load_private_key(&config.server_key)?;
     let ca_certs = load_certs(&config.ca_cert)?;
@@ -69,13 +82,18 @@ pub fn build_tls_config(config: &MtlsConfig) -> Result<ServerConfig> {
             .map_err(|e| Error::Config(format!("Failed to add CA cert to trust store: {e}")))?;
     }
 
-    let client_verifier = build_client_verifier(config, root_store)?;
+    let client_verifier = build_client_verifier(config, root_store, crls)?;
 
     // Enforce TLS 1.3 minimum — defense-in-depth on top of the removed tls12 feature.
-    let mut tls_cfg = rustls::ServerConfig::builder_with_protocol_versions(&[&version::TLS13])
-        .with_client_cert_verifier(client_verifier)
-        .with_single_cert(server_certs, server_key)
-        .map_err(|e| Error::Config(format!("TLS config error (cert/key mismatch?): {e}")))?;
+    let builder = rustls::ServerConfig::builder_with_protocol_versions(&[&version::TLS13])
+        .with_client_cert_verifier(client_verifier);
+    let mut tls_cfg = match config.ocsp_response {
+        Some(ref ocsp_path) => {
+            builder.with_single_cert_with_ocsp(server_certs, server_key, read_file(ocsp_path)?)
+        }
+        None => builder.with_single_cert(server_certs, server_key),
+    }
+    .map_err(|e| Error::Config(format!("TLS config error (cert/key mismatch?): {e}")))?;
 
     // Prefer HTTP/2, fall back to HTTP/1.1
     tls_cfg.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
@@ -321,19 +339,26 @@ fn read_file(path: &str) -> Result<Vec<u8>> {
 }
 
 /// Build a `WebPkiClientVerifier` with optional CRL support.
+///
+/// `revocation.fail_mode` applies once any CRL is loaded: hard-fail rejects
+/// certificates no CRL covers and CRLs past their `nextUpdate`.
 fn build_client_verifier(
     config: &MtlsConfig,
     root_store: rustls::RootCertStore,
+    fetched_crls: &[Crl],
 ) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>> {
     let store = Arc::new(root_store);
     let builder = WebPkiClientVerifier::builder(store);
 
     // Load CRL if configured
-    let builder = if let Some(ref crl_path) = config.crl_path {
-        let crls = load_crls(crl_path)?;
-        builder.with_crls(crls)
-    } else {
-        builder
+    let mut crls = fetched_crls.to_vec();
+    if let Some(ref crl_path) = config.crl_path {
+        crls.extend(load_crls(crl_path)?);
+    }
+    let builder = builder.with_crls(crls);
+    let builder = match config.revocation.fail_mode {
+        RevocationFailMode::Hard => builder.enforce_revocation_expiration(),
+        RevocationFailMode::Soft => builder.allow_unknown_revocation_status(),
     };
 
     // Require or allow unauthenticated clients
@@ -351,12 +376,9 @@ fn build_client_verifier(
     Ok(verifier)
 }
 
-/// Load CRL entries from a PEM file.
-fn load_crls(path: &str) -> Result<Vec<CertificateRevocationListDer<'static>>> {
-    let pem_data = read_file(path)?;
-    CertificateRevocationListDer::pem_slice_iter(pem_data.as_slice())
-        .collect::<std::result::Result<Vec<_>, _>>()
-        .map_err(|e| Error::Config(format!("Failed to parse CRL from '{path}': {e}")))
+/// Load CRL entries from a PEM or DER file.
+fn load_crls(path: &str) -> Result<Vec<Crl>> {
+    parse_crls(&read_file(path)?, path)
 }
 
 /// Convert a validity period (days) into a future `OffsetDateTime` for `rcgen`.
@@ -715,6 +737,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn build_tls_config_staples_an_ocsp_response() {
+        // GIVEN: a valid PKI and an OCSP response for the server cert
+        let dir = tempfile::tempdir().unwrap();
+        let (ca_cert, server_cert, server_key) = write_pki_to_dir(dir.path());
+        let ocsp_path = dir.path().join("server.ocsp");
+        fs::write(&ocsp_path, b"\x30\x03\x0a\x01\x00").unwrap();
+
+        let config = MtlsConfig {
+            enabled: true,
+            server_cert,
+            server_key,
+            ca_cert,
+            ocsp_response: Some(ocsp_path.to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+        // WHEN/THEN: the config builds, and fails once the response is gone
+        assert!(build_tls_config(&config).is_ok());
+        fs::remove_file(&ocsp_path).unwrap();
+        let msg = build_tls_config(&config).unwrap_err().to_string();
+        assert!(msg.contains("server.ocsp"), "unexpected error: {msg}");
+    }
+
+    #[test]
+    fn build_tls_config_rejects_an_unparsable_crl() {
+        // GIVEN: a CRL file holding no CRL
+        let dir = tempfile::tempdir().unwrap();
+        let (ca_cert, server_cert, server_key) = write_pki_to_dir(dir.path());
+        let crl_path = dir.path().join("crl.pem");
+        fs::write(&crl_path, b"not a crl").unwrap();
+
+        let config = MtlsConfig {
+            enabled: true,
+            server_cert,
+            server_key,
+            ca_cert,
+            crl_path: Some(crl_path.to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+        // WHEN/THEN: startup fails instead of silently skipping revocation
+        let msg = build_tls_config(&config).unwrap_err().to_string();
+        assert!(msg.contains("No CRL found"), "unexpected error: {msg}");
+    }
+
     #[test]
     fn build_tls_config_fails_with_mismatched_cert_and_key() {
         // GIVEN: two separate CA-issued leaf certs (different keys)
@@ -3,8 +3,8 @@
 //! mTLS configuration types.
 //!
 //! Defines the YAML-deserialisable configuration for mutual TLS:
-//! server certificate paths, CA trust store, CRL, and certificate-based
-//! tool access policies.
+//! server certificate paths, CA trust store, CRL, ACME, and
+//! certificate-based tool access policies.
 //!
 //! # Example YAML
 //!
@@ -30,6 +30,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::{Error, Result};
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Top-level mTLS config
 // ─────────────────────────────────────────────────────────────────────────────
@@ -67,6 +69,31 @@ pub struct MtlsConfig {
     #[serde(default)]
     pub crl_path: Option<String>,
 
+    /// CRL distribution URLs and the revocation fail policy.
+    #[serde(default)]
+    pub revocation: RevocationConfig,
+
+    /// Optional path to a DER-encoded OCSP response for `server_cert`,
+    /// stapled into every handshake so clients need not query the CA.
+    ///
+    /// Refresh it on disk before it expires; it is reloaded like the
+    /// certificates.
+    #[serde(default)]
+    pub ocsp_response: Option<String>,
+
+    /// Reload the certificates when any of the files above change
+    /// (default `true`).
+    ///
+    /// New handshakes use the new certificates; established connections are
+    /// kept.  `SIGHUP` triggers a reload either way.
+    #[serde(default = "default_watch_certs")]
+    pub watch_certs: bool,
+
+    /// Obtain and renew `server_cert` / `server_key` from an ACME CA such
+    /// as Let's Encrypt (needs the `acme` feature).
+    #[serde(default)]
+    pub acme: AcmeConfig,
+
     /// Ordered list of certificate-based tool access policies.
     ///
     /// Evaluated in order; the **first matching rule wins**.  If no rule
@@ -79,6 +106,170 @@ fn default_require_client_cert() -> bool {
     true
 }
 
+fn default_watch_certs() -> bool {
+    true
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Revocation config
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Client certificate revocation checking.
+///
+/// CRLs come from `crl_path` and from `crl_urls`, which are re-fetched every
+/// `refresh_secs`.  A failed refresh keeps the last CRLs fetched.  Without
+/// any CRL, revocation is not checked.
+///
+/// ```yaml
+/// mtls:
+///   crl_path: "/etc/mcp-gateway/tls/crl.pem"
+///   revocation:
+///     crl_urls: ["http://ca.internal/intermediate.crl"]
+///     refresh_secs: 900
+///     fail_mode: hard
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RevocationConfig {
+    /// CRL distribution URLs (PEM or DER).
+    pub crl_urls: Vec<String>,
+
+    /// Seconds between CRL downloads (default 3600; 0 fetches only at
+    /// startup).
+    pub refresh_secs: u64,
+
+    /// What to do when revocation status cannot be established.
+    pub fail_mode: RevocationFailMode,
+}
+
+impl Default for RevocationConfig {
+    fn default() -> Self {
+        Self {
+            crl_urls: Vec::new(),
+            refresh_secs: 3600,
+            fail_mode: RevocationFailMode::Hard,
+        }
+    }
+}
+
+/// Policy for certificates whose revocation status is unknown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevocationFailMode {
+    /// Reject certificates no CRL covers and CRLs past their `nextUpdate`;
+    /// refuse to start when a CRL URL cannot be fetched (default).
+    #[default]
+    Hard,
+    /// Accept certificates no CRL covers and stale CRLs; start without a CRL
+    /// URL that cannot be fetched.  Certificates listed as revoked are still
+    /// rejected.
+    Soft,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// ACME config
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Let's Encrypt directory, used unless `directory_url` is set.
+pub const LETS_ENCRYPT_DIRECTORY: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+/// Automatic server certificates from an ACME CA.
+///
+/// The certificate and key are written to `server_cert` / `server_key` and
+/// renewed `renew_before_days` before they expire.  `ca_cert` still names
+/// the CA for *client* certificates.
+///
+/// ```yaml
+/// mtls:
+///   acme:
+///     enabled: true
+///     domains: ["gateway.example.com"]
+///     contact: ["ops@example.com"]
+///     challenge: http-01
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AcmeConfig {
+    /// Manage the server certificate with ACME.
+    pub enabled: bool,
+
+    /// DNS names the certificate is issued for.  Each must resolve to this
+    /// gateway.
+    pub domains: Vec<String>,
+
+    /// Contact email addresses for the ACME account (expiry notices).
+    pub contact: Vec<String>,
+
+    /// ACME directory URL (default: Let's Encrypt production).
+    pub directory_url: String,
+
+    /// How domain control is proven.
+    pub challenge: AcmeChallenge,
+
+    /// Listen address of the HTTP-01 challenge responder.  The CA connects
+    /// on port 80.
+    pub http_listen: String,
+
+    /// Directory for the ACME account credentials.
+    pub state_dir: String,
+
+    /// Renew this many days before the certificate expires.
+    pub renew_before_days: u32,
+}
+
+impl Default for AcmeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            domains: Vec::new(),
+            contact: Vec::new(),
+            directory_url: LETS_ENCRYPT_DIRECTORY.to_string(),
+            challenge: AcmeChallenge::Http01,
+            http_listen: "0.0.0.0:80".to_string(),
+            state_dir: "~/.mcp-gateway/acme".to_string(),
+            renew_before_days: 30,
+        }
+    }
+}
+
+impl AcmeConfig {
+    /// Validate the ACME settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] when ACME is enabled without
+    /// domains or with a renewal window of zero.
+    pub fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.domains.iter().all(|d| d.trim().is_empty()) {
+            return Err(Error::ConfigValidation(
+                "mtls.acme.domains must name at least one domain".to_string(),
+            ));
+        }
+        if self.renew_before_days == 0 {
+            return Err(Error::ConfigValidation(
+                "mtls.acme.renew_before_days must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// ACME challenge type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AcmeChallenge {
+    /// Serve the challenge over plain HTTP on `http_listen` (default).
+    #[default]
+    #[serde(rename = "http-01")]
+    Http01,
+    /// Answer the challenge in the TLS handshake on the gateway's own
+    /// listener (ALPN `acme-tls/1`); needs the listener on port 443.
+    #[serde(rename = "tls-alpn-01")]
+    TlsAlpn01,
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Policy rule config
 // ─────────────────────────────────────────────────────────────────────────────
@@ -121,6 +312,11 @@ pub struct CertMatchConfig {
     /// Glob match on a SAN URI value (e.g. `"spiffe://company.com/agent/*"`).
     pub san_uri: Option<String>,
 
+    /// Exact or glob match on the SPIFFE ID of an X.509-SVID
+    /// (e.g. `"spiffe://company.com/agent/*"`).  Unlike `san_uri`, only a
+    /// certificate's single well-formed SPIFFE ID can match.
+    pub spiffe_id: Option<String>,
+
     /// Glob match on a SAN DNS name.
     pub san_dns: Option<String>,
 
@@ -174,6 +370,44 @@ mod tests {
         assert!(!cfg.require_client_cert);
     }
 
+    #[test]
+    fn watch_certs_defaults_to_true_when_parsed() {
+        let yaml = "enabled: true\nserver_cert: a\nserver_key: b\nca_cert: c";
+        let cfg: MtlsConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(cfg.watch_certs);
+        let cfg: MtlsConfig = serde_yaml::from_str(&format!("{yaml}\nwatch_certs: false")).unwrap();
+        assert!(!cfg.watch_certs);
+    }
+
+    #[test]
+    fn acme_is_off_by_default_and_validated_when_on() {
+        // GIVEN: ACME enabled with a challenge type but no domains
+        let yaml = "acme:\n  enabled: true\n  challenge: tls-alpn-01";
+        let mut cfg: MtlsConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(cfg.acme.challenge, AcmeChallenge::TlsAlpn01);
+        assert_eq!(cfg.acme.directory_url, LETS_ENCRYPT_DIRECTORY);
+        // THEN: validation asks for a domain
+        assert!(cfg.acme.validate().is_err());
+
+        cfg.acme.domains = vec!["gateway.example.com".to_string()];
+        assert!(cfg.acme.validate().is_ok());
+        assert!(!MtlsConfig::default().acme.enabled);
+    }
+
+    #[test]
+    fn revocation_defaults_to_hard_fail_hourly_refresh() {
+        let cfg: MtlsConfig = serde_yaml::from_str("enabled: true").unwrap();
+        assert!(cfg.revocation.crl_urls.is_empty());
+        assert_eq!(cfg.revocation.refresh_secs, 3600);
+        assert_eq!(cfg.revocation.fail_mode, RevocationFailMode::Hard);
+        assert!(cfg.ocsp_response.is_none());
+
+        let yaml = "revocation:\n  crl_urls: [\"http://ca/crl\"]\n  fail_mode: soft";
+        let cfg: MtlsConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(cfg.revocation.crl_urls, ["http://ca/crl"]);
+        assert_eq!(cfg.revocation.fail_mode, RevocationFailMode::Soft);
+    }
+
     #[test]
     fn crl_path_defaults_to_none() {
         let cfg = MtlsConfig::default();
@@ -3,7 +3,8 @@
 //! Certificate identity extraction.
 //!
 //! Parses an X.509 DER-encoded certificate and extracts the fields used for
-//! policy matching: Common Name, Organisational Unit, SAN URIs, SAN DNS names.
+//! policy matching: Common Name, Organisational Unit, SAN URIs, SAN DNS names,
+//! and the SPIFFE ID of an X.509-SVID.
 //!
 //! # No unsafe
 //!
@@ -39,6 +40,10 @@ pub struct CertIdentity {
     /// Subject Alternative Name — DNS entries.
     pub san_dns_names: Vec<String>,
 
+    /// SPIFFE ID of an X.509-SVID: the certificate's single, well-formed
+    /// `spiffe://` URI SAN.  `None` when there is none, or more than one.
+    pub spiffe_id: Option<String>,
+
     /// Pre-computed human-readable label for logs/audit events.
     pub display_name: String,
 }
@@ -57,14 +62,16 @@ impl CertIdentity {
         let common_name = extract_cn(&cert);
         let organizational_unit = extract_ou(&cert);
         let (san_uris, san_dns_names) = extract_sans(&cert);
+        let spiffe_id = extract_spiffe_id(&san_uris);
 
-        let display_name = build_display_name(common_name.as_ref(), &san_uris);
+        let display_name = build_display_name(common_name.as_ref(), spiffe_id.as_ref());
 
         Ok(Self {
             common_name,
             organizational_unit,
             san_uris,
             san_dns_names,
+            spiffe_id,
             display_name,
         })
     }
@@ -110,16 +117,49 @@ fn extract_sans(cert: &X509Certificate<'_>) -> (Vec<String>, Vec<String>) {
     (uris, dns_names)
 }
 
+/// Pick the SPIFFE ID out of the SAN URIs.
+///
+/// An X.509-SVID carries exactly one SPIFFE ID; a certificate with several
+/// is ambiguous and yields `None`, as does a malformed one.
+fn extract_spiffe_id(san_uris: &[String]) -> Option<String> {
+    let mut ids = san_uris.iter().filter(|u| u.starts_with("spiffe://"));
+    match (ids.next(), ids.next()) {
+        (Some(id), None) if is_spiffe_id(id) => Some(id.clone()),
+        _ => None,
+    }
+}
+
+/// Whether `uri` is a well-formed SPIFFE ID: `spiffe://<trust-domain>/<path>`
+/// with a lowercase trust domain and no empty, `.` or `..` path segments,
+/// query, fragment, port or user info.
+fn is_spiffe_id(uri: &str) -> bool {
+    let Some(rest) = uri.strip_prefix("spiffe://") else {
+        return false;
+    };
+    let (trust_domain, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let domain_ok = !trust_domain.is_empty()
+        && trust_domain
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"-._".contains(&b));
+    let path_ok = path.is_empty()
+        || path[1..].split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"-._".contains(&b))
+        });
+    domain_ok && path_ok
+}
+
 /// Build a human-readable display name for logs.
 ///
-/// Prefers the SPIFFE URI if present, then CN, then `"<unknown>"`.
-fn build_display_name(cn: Option<&String>, san_uris: &[String]) -> String {
-    san_uris
-        .iter()
-        .find(|u| u.starts_with("spiffe://"))
-        .map(String::as_str)
-        .or_else(|| cn.map(String::as_str))
-        .unwrap_or("<unknown>")
+/// Prefers the SPIFFE ID if present, then CN, then `"<unknown>"`.
+fn build_display_name(cn: Option<&String>, spiffe_id: Option<&String>) -> String {
+    spiffe_id
+        .or(cn)
+        .map_or("<unknown>", String::as_str)
         .to_owned()
 }
 
@@ -258,7 +298,7 @@ mod tests {
     #[test]
     fn display_name_is_unknown_when_no_cn_or_spiffe() {
         // GIVEN: the build_display_name helper with no CN and no SPIFFE URI
-        let name = build_display_name(None, &[]);
+        let name = build_display_name(None, None);
         assert_eq!(name, "<unknown>");
     }
 
@@ -275,6 +315,71 @@ mod tests {
         assert_eq!(id.display_name, "fallback-cn");
     }
 
+    // ── SPIFFE ID ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn from_der_extracts_spiffe_id_from_svid() {
+        // GIVEN: an X.509-SVID with a SPIFFE URI SAN and a DNS SAN
+        let der = make_cert_der(
+            "svid",
+            None,
+            &[
+                uri_san("spiffe://company.com/ns/prod/sa/agent"),
+                dns_san("agent.internal"),
+            ],
+        );
+        let id = CertIdentity::from_der(&der).unwrap();
+        // THEN: the SPIFFE ID is picked out
+        assert_eq!(
+            id.spiffe_id.as_deref(),
+            Some("spiffe://company.com/ns/prod/sa/agent")
+        );
+    }
+
+    #[test]
+    fn spiffe_id_is_none_for_ambiguous_or_non_spiffe_certs() {
+        // GIVEN: two SPIFFE URIs — not a valid SVID
+        let two = make_cert_der(
+            "two",
+            None,
+            &[
+                uri_san("spiffe://company.com/agent/a"),
+                uri_san("spiffe://company.com/agent/b"),
+            ],
+        );
+        assert!(CertIdentity::from_der(&two).unwrap().spiffe_id.is_none());
+
+        // GIVEN: only a non-SPIFFE URI
+        let https = make_cert_der("https", None, &[uri_san("https://company.com/agent")]);
+        assert!(CertIdentity::from_der(&https).unwrap().spiffe_id.is_none());
+    }
+
+    #[test]
+    fn is_spiffe_id_follows_the_spiffe_id_grammar() {
+        for valid in [
+            "spiffe://company.com",
+            "spiffe://company.com/agent/cursor",
+            "spiffe://prod-1.company_x.com/ns/a.b/sa/c-d",
+        ] {
+            assert!(is_spiffe_id(valid), "{valid}");
+        }
+        for invalid in [
+            "spiffe://",
+            "spiffe:///agent",
+            "spiffe://Company.com/agent",
+            "spiffe://company.com:443/agent",
+            "spiffe://user@company.com/agent",
+            "spiffe://company.com/",
+            "spiffe://company.com/agent//x",
+            "spiffe://company.com/agent/../admin",
+            "spiffe://company.com/agent?x=1",
+            "spiffe://company.com/agent#x",
+            "https://company.com/agent",
+        ] {
+            assert!(!is_spiffe_id(invalid), "{invalid}");
+        }
+    }
+
     // ── ou absent → None ──────────────────────────────────────────────────────
 
     #[test]
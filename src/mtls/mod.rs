@@ -22,6 +22,9 @@
 //! - [`identity`] — X.509 certificate field extraction (`CertIdentity`)
 //! - [`access_control`] — Policy evaluation (`MtlsPolicy`, `PolicyDecision`)
 //! - [`cert_manager`] — rustls config building and certificate generation CLI helpers
+//! - [`reloader`] — certificate hot reload on file change or `SIGHUP`
+//! - [`revocation`] — CRL download and the revocation fail policy
+//! - `acme` — ACME server certificates (`acme` feature)
 //!
 //! # Quick start
 //!
@@ -45,14 +48,19 @@
 //! ```
 
 pub mod access_control;
+#[cfg(feature = "acme")]
+pub mod acme;
 pub mod cert_manager;
 pub mod config;
 pub mod identity;
+pub mod reloader;
+pub mod revocation;
 
 pub use access_control::{MtlsPolicy, PolicyDecision};
 pub use cert_manager::{
-    CaParams, CertGenerator, GeneratedCert, LeafCertParams, build_tls_config, load_certs,
-    load_private_key,
+    CaParams, CertGenerator, GeneratedCert, LeafCertParams, build_tls_config,
+    build_tls_config_with_crls, load_certs, load_private_key,
 };
 pub use config::MtlsConfig;
 pub use identity::CertIdentity;
+pub use reloader::CertReloader;
@@ -0,0 +1,431 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Certificate hot reload for the mTLS listener.
+//!
+//! Short-lived certificates are rotated on disk by cert-manager, Vault Agent
+//! or a cron job.  [`CertReloader`] rebuilds the rustls config when the server
+//! certificate, key, CA or CRL file changes, or when the process receives
+//! `SIGHUP`, and swaps it into the running listener.  Established connections
+//! keep the config they were handshaken with; only new handshakes see the new
+//! one, so nothing is dropped.
+//!
+//! # Design
+//!
+//! - The **parent directories** are watched, not the files, so atomic renames
+//!   and Kubernetes secret volumes (which swap a `..data` symlink) are seen.
+//! - Events are debounced by 500 ms, and a reload only happens when the file
+//!   contents actually changed.
+//! - A reload that fails — say the certificate was written but the key not
+//!   yet — keeps the current config and is retried on the next change or
+//!   `SIGHUP`.
+//! - CRLs from `revocation.crl_urls` are downloaded again every
+//!   `refresh_secs`.  A failed download keeps the previous CRLs.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum_server::tls_rustls::RustlsConfig;
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, info, warn};
+
+use super::cert_manager::build_tls_config_with_crls;
+use super::config::MtlsConfig;
+use super::revocation::{self, Crl};
+use crate::hashing::sha256_hex_chunks;
+use crate::{Error, Result};
+
+/// Quiet period after the last file event before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Reloads the mTLS listener's certificates while it runs.
+pub struct CertReloader {
+    /// Keep the underlying notify watcher alive.
+    _watcher: Mutex<Option<RecommendedWatcher>>,
+    /// Requests a reload, like a file change.
+    trigger_tx: mpsc::Sender<()>,
+}
+
+impl CertReloader {
+    /// Start reloading `tls` from the files named in `config` and the
+    /// downloaded `crls` it was built with.
+    ///
+    /// Files are watched when `config.watch_certs` is set; `SIGHUP` always
+    /// triggers a reload on Unix.  The reload task stops on `shutdown_rx`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file watcher cannot be created or the current
+    /// files cannot be read.
+    pub fn start(
+        config: MtlsConfig,
+        tls: RustlsConfig,
+        crls: Vec<Crl>,
+        shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<Self> {
+        let (trigger_tx, trigger_rx) = mpsc::channel::<()>(16);
+        let watcher = if config.watch_certs {
+            Some(Self::create_watcher(
+                trigger_tx.clone(),
+                &watched_dirs(&config),
+            )?)
+        } else {
+            None
+        };
+        spawn_hangup_listener(trigger_tx.clone());
+
+        let fingerprint = fingerprint(&config, &crls)?;
+        Self::spawn_reload_task(trigger_rx, shutdown_rx, config, tls, crls, fingerprint);
+
+        Ok(Self {
+            _watcher: Mutex::new(watcher),
+            trigger_tx,
+        })
+    }
+
+    /// A sender that requests a reload, for components that rewrite the
+    /// certificate files themselves.
+    #[must_use]
+    pub fn reload_trigger(&self) -> mpsc::Sender<()> {
+        self.trigger_tx.clone()
+    }
+
+    /// Create the notify watcher, forwarding any change in `dirs`.
+    fn create_watcher(
+        trigger_tx: mpsc::Sender<()>,
+        dirs: &[PathBuf],
+    ) -> Result<RecommendedWatcher> {
+        let mut watcher = RecommendedWatcher::new(
+            move |result: std::result::Result<Event, notify::Error>| {
+                if let Ok(event) = result
+                    && matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    )
+                {
+                    debug!(paths = ?event.paths, "Certificate watcher: change detected");
+                    let _ = trigger_tx.try_send(());
+                }
+            },
+            Config::default().with_poll_interval(Duration::from_secs(2)),
+        )
+        .map_err(|e| Error::ConfigWatcher(format!("Certificate watcher: {e}")))?;
+
+        for dir in dirs {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                warn!(directory = %dir.display(), error = %e, "Certificate watcher: failed to watch dir");
+            }
+        }
+
+        Ok(watcher)
+    }
+
+    /// Spawn the debounced reload task, which also refreshes the CRLs.
+    fn spawn_reload_task(
+        mut trigger_rx: mpsc::Receiver<()>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+        config: MtlsConfig,
+        tls: RustlsConfig,
+        mut crls: Vec<Crl>,
+        mut fingerprint: String,
+    ) {
+        tokio::spawn(async move {
+            let mut last_trigger: Option<Instant> = None;
+            let mut ticker = tokio::time::interval(Duration::from_millis(100));
+            let mut crl_refresh = crl_refresh_interval(&config);
+            let client = revocation::http_client();
+
+            loop {
+                tokio::select! {
+                    Some(()) = trigger_rx.recv() => {
+                        last_trigger = Some(Instant::now());
+                    }
+                    _ = ticker.tick() => {
+                        if last_trigger.is_some_and(|t| t.elapsed() >= DEBOUNCE) {
+                            last_trigger = None;
+                            if let Err(e) = reload(&config, &crls, &tls, &mut fingerprint) {
+                                warn!(error = %e, "mTLS certificate reload failed; keeping the current certificates");
+                            }
+                        }
+                    }
+                    () = next_tick(crl_refresh.as_mut()) => {
+                        match revocation::fetch_crls(&client, &config.revocation.crl_urls).await {
+                            Ok(fresh) => {
+                                crls = fresh;
+                                if let Err(e) = reload(&config, &crls, &tls, &mut fingerprint) {
+                                    warn!(error = %e, "mTLS reload after CRL refresh failed; keeping the current config");
+                                }
+                            }
+                            Err(e) => warn!(error = %e, "CRL refresh failed; keeping the previous CRLs"),
+                        }
+                    }
+                    _ = shutdown_rx.recv() => {
+                        debug!("Certificate reloader shutting down");
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Rebuild and swap in the TLS config if the files or `crls` differ from
+/// `fingerprint`.  Returns whether a new config was installed.
+///
+/// `fingerprint` only advances on success, so a failed reload is retried
+/// on the next trigger even if the files are not touched again.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read or the new config cannot be
+/// built; `tls` is left unchanged.
+pub fn reload(
+    config: &MtlsConfig,
+    crls: &[Crl],
+    tls: &RustlsConfig,
+    fingerprint: &mut String,
+) -> Result<bool> {
+    let current = self::fingerprint(config, crls)?;
+    if current == *fingerprint {
+        debug!("mTLS certificates unchanged; skipping reload");
+        return Ok(false);
+    }
+    let rustls_cfg = build_tls_config_with_crls(config, crls)?;
+    tls.reload_from_config(Arc::new(rustls_cfg));
+    *fingerprint = current;
+    info!(server_cert = %config.server_cert, "mTLS certificates reloaded");
+    Ok(true)
+}
+
+/// Digest of the certificate, key, CA, CRL and OCSP file contents and the
+/// downloaded CRLs.
+fn fingerprint(config: &MtlsConfig, crls: &[Crl]) -> Result<String> {
+    let contents = cert_files(config)
+        .map(|path| {
+            std::fs::read(path).map_err(|e| Error::Config(format!("Cannot read '{path}': {e}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(sha256_hex_chunks(
+        contents
+            .iter()
+            .map(Vec::as_slice)
+            .chain(crls.iter().map(AsRef::as_ref)),
+    ))
+}
+
+/// Every file the TLS config is built from.
+fn cert_files(config: &MtlsConfig) -> impl Iterator<Item = &str> {
+    [
+        config.server_cert.as_str(),
+        config.server_key.as_str(),
+        config.ca_cert.as_str(),
+    ]
+    .into_iter()
+    .chain(config.crl_path.as_deref())
+    .chain(config.ocsp_response.as_deref())
+}
+
+/// Interval for re-downloading `revocation.crl_urls`, if any.  The first
+/// tick is one period out: startup already fetched them.
+fn crl_refresh_interval(config: &MtlsConfig) -> Option<tokio::time::Interval> {
+    let revocation = &config.revocation;
+    if revocation.crl_urls.is_empty() || revocation.refresh_secs == 0 {
+        return None;
+    }
+    let period = Duration::from_secs(revocation.refresh_secs);
+    Some(tokio::time::interval_at(
+        tokio::time::Instant::now() + period,
+        period,
+    ))
+}
+
+/// Wait for the next tick of `interval`, or forever without one.
+async fn next_tick(interval: Option<&mut tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Distinct parent directories of the certificate files.
+fn watched_dirs(config: &MtlsConfig) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for file in cert_files(config) {
+        let dir = match Path::new(file).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Forward `SIGHUP` to `trigger_tx` until the reload task is gone.
+#[cfg(unix)]
+fn spawn_hangup_listener(trigger_tx: mpsc::Sender<()>) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!(error = %e, "Failed to install SIGHUP handler; certificate reload on signal disabled");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received; reloading mTLS certificates");
+            if trigger_tx.send(()).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_hangup_listener(_trigger_tx: mpsc::Sender<()>) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mtls::cert_manager::{
+        CaParams, CertGenerator, GeneratedCert, LeafCertParams, build_tls_config,
+    };
+
+    fn ca() -> GeneratedCert {
+        CertGenerator::init_ca(&CaParams {
+            cn: "Test CA",
+            validity_days: 365,
+        })
+        .unwrap()
+    }
+
+    fn server_cert(ca: &GeneratedCert) -> GeneratedCert {
+        CertGenerator::issue_leaf(
+            &LeafCertParams {
+                cn: "gateway.test",
+                ou: None,
+                san_dns: vec!["gateway.test".to_string()],
+                san_uris: vec![],
+                validity_days: 1,
+            },
+            &ca.cert_pem,
+            &ca.key_pem,
+        )
+        .unwrap()
+    }
+
+    /// Write a CA and server cert under `dir` and return the config naming them.
+    fn write_pki(dir: &Path, ca: &GeneratedCert) -> MtlsConfig {
+        let leaf = server_cert(ca);
+        std::fs::write(dir.join("ca.crt"), &ca.cert_pem).unwrap();
+        std::fs::write(dir.join("server.crt"), &leaf.cert_pem).unwrap();
+        std::fs::write(dir.join("server.key"), &leaf.key_pem).unwrap();
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        MtlsConfig {
+            enabled: true,
+            server_cert: path("server.crt"),
+            server_key: path("server.key"),
+            ca_cert: path("ca.crt"),
+            ..MtlsConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn reload_swaps_in_rotated_certificates_only_when_they_change() {
+        // GIVEN: a listener config built from the files on disk
+        let dir = tempfile::tempdir().unwrap();
+        let ca = ca();
+        let config = write_pki(dir.path(), &ca);
+        let tls = RustlsConfig::from_config(Arc::new(build_tls_config(&config).unwrap()));
+        let mut fingerprint = fingerprint(&config, &[]).unwrap();
+
+        // WHEN: nothing changed
+        // THEN: no reload happens
+        assert!(!reload(&config, &[], &tls, &mut fingerprint).unwrap());
+
+        // WHEN: the server certificate is rotated
+        write_pki(dir.path(), &ca);
+        let before = tls.get_inner();
+
+        // THEN: the new config is installed
+        assert!(reload(&config, &[], &tls, &mut fingerprint).unwrap());
+        assert!(!Arc::ptr_eq(&before, &tls.get_inner()));
+    }
+
+    #[tokio::test]
+    async fn failed_reload_keeps_the_current_config_and_retries() {
+        // GIVEN: a running config
+        let dir = tempfile::tempdir().unwrap();
+        let ca = ca();
+        let config = write_pki(dir.path(), &ca);
+        let tls = RustlsConfig::from_config(Arc::new(build_tls_config(&config).unwrap()));
+        let mut fingerprint = fingerprint(&config, &[]).unwrap();
+        let before = tls.get_inner();
+
+        // WHEN: a new certificate lands before its key
+        let rotated = server_cert(&ca);
+        std::fs::write(&config.server_cert, &rotated.cert_pem).unwrap();
+
+        // THEN: the reload fails and the old config stays
+        assert!(reload(&config, &[], &tls, &mut fingerprint).is_err());
+        assert!(Arc::ptr_eq(&before, &tls.get_inner()));
+
+        // WHEN: the key follows
+        std::fs::write(&config.server_key, &rotated.key_pem).unwrap();
+
+        // THEN: the next trigger succeeds
+        assert!(reload(&config, &[], &tls, &mut fingerprint).unwrap());
+    }
+
+    #[tokio::test]
+    async fn changed_crls_trigger_a_reload() {
+        // GIVEN: a config built without downloaded CRLs
+        let dir = tempfile::tempdir().unwrap();
+        let config = write_pki(dir.path(), &ca());
+        let tls = RustlsConfig::from_config(Arc::new(build_tls_config(&config).unwrap()));
+        let mut fingerprint = fingerprint(&config, &[]).unwrap();
+
+        // WHEN: a CRL refresh returns a list that cannot be used
+        let crls = vec![Crl::from(vec![0x30, 0x00])];
+
+        // THEN: the change is noticed, the reload fails, and the old config stays
+        let before = tls.get_inner();
+        assert!(reload(&config, &crls, &tls, &mut fingerprint).is_err());
+        assert!(Arc::ptr_eq(&before, &tls.get_inner()));
+    }
+
+    #[test]
+    fn crl_refresh_needs_urls_and_a_period() {
+        let mut config = MtlsConfig::default();
+        assert!(crl_refresh_interval(&config).is_none());
+        config.revocation.crl_urls = vec!["http://ca/crl".to_string()];
+        config.revocation.refresh_secs = 0;
+        assert!(crl_refresh_interval(&config).is_none());
+    }
+
+    #[test]
+    fn watched_dirs_are_distinct_parents() {
+        let config = MtlsConfig {
+            server_cert: "/etc/tls/server.crt".to_string(),
+            server_key: "/etc/tls/server.key".to_string(),
+            ca_cert: "/etc/ca/ca.crt".to_string(),
+            crl_path: Some("crl.pem".to_string()),
+            ..MtlsConfig::default()
+        };
+        assert_eq!(
+            watched_dirs(&config),
+            vec![
+                PathBuf::from("/etc/tls"),
+                PathBuf::from("/etc/ca"),
+                PathBuf::from(".")
+            ]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Client certificate revocation — CRL download and parsing.
+//!
+//! CRLs come from two places:
+//!
+//! - `mtls.crl_path`, read from disk together with the certificates, and
+//! - `mtls.revocation.crl_urls`, downloaded at startup and every
+//!   `refresh_secs` by the [`CertReloader`](super::CertReloader), which
+//!   rebuilds the TLS config whenever they change.
+//!
+//! `mtls.revocation.fail_mode` decides what happens when a certificate's
+//! status cannot be established — see [`RevocationFailMode`].  A revoked
+//! certificate is rejected in either mode.
+//!
+//! OCSP is not checked for client certificates: rustls has no OCSP verifier,
+//! so publish CRLs or keep client certificates short-lived.  The gateway can
+//! staple an OCSP response for its own certificate (`mtls.ocsp_response`).
+
+use std::time::Duration;
+
+use rustls::pki_types::CertificateRevocationListDer;
+use rustls::pki_types::pem::PemObject;
+use tracing::{debug, warn};
+
+use super::config::{RevocationConfig, RevocationFailMode};
+use crate::{Error, Result};
+
+/// A parsed certificate revocation list.
+pub type Crl = CertificateRevocationListDer<'static>;
+
+/// Parse the CRLs in `data`: PEM `X509 CRL` blocks, or a single DER CRL.
+///
+/// # Errors
+///
+/// Returns an error if `data` holds neither.
+pub fn parse_crls(data: &[u8], source: &str) -> Result<Vec<Crl>> {
+    let crls = Crl::pem_slice_iter(data)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::Config(format!("Failed to parse CRL from '{source}': {e}")))?;
+    if !crls.is_empty() {
+        return Ok(crls);
+    }
+    // DER: an ASN.1 SEQUENCE.
+    if data.first() == Some(&0x30) {
+        return Ok(vec![Crl::from(data.to_vec())]);
+    }
+    Err(Error::Config(format!("No CRL found in '{source}'")))
+}
+
+/// HTTP client for CRL downloads.  Distribution points are usually plain
+/// HTTP; CRLs are signed, so that is safe.
+#[must_use]
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Download and parse every CRL in `urls`.
+///
+/// # Errors
+///
+/// Returns an error if any URL cannot be fetched or parsed; no partial set
+/// is returned.
+pub async fn fetch_crls(client: &reqwest::Client, urls: &[String]) -> Result<Vec<Crl>> {
+    let mut crls = Vec::new();
+    for url in urls {
+        let response = client
+            .get(url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| Error::Transport(format!("Failed to fetch CRL '{url}': {e}")))?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| Error::Transport(format!("Failed to read CRL '{url}': {e}")))?;
+        crls.extend(parse_crls(&body, url)?);
+        debug!(url, "CRL fetched");
+    }
+    Ok(crls)
+}
+
+/// The CRLs to start with.  In soft-fail mode an unreachable URL is logged
+/// and the listener starts without its CRLs.
+///
+/// # Errors
+///
+/// In hard-fail mode, returns an error if any CRL URL cannot be fetched.
+pub async fn initial_crls(config: &RevocationConfig, client: &reqwest::Client) -> Result<Vec<Crl>> {
+    if config.crl_urls.is_empty() {
+        return Ok(Vec::new());
+    }
+    match fetch_crls(client, &config.crl_urls).await {
+        Ok(crls) => Ok(crls),
+        Err(e) if config.fail_mode == RevocationFailMode::Soft => {
+            warn!(error = %e, "CRL download failed; starting without the CRL URLs (soft-fail)");
+            Ok(Vec::new())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, routing::get};
+
+    /// A minimal CRL: only the outer SEQUENCE matters for parsing here.
+    const DER_CRL: &[u8] = &[0x30, 0x03, 0x02, 0x01, 0x01];
+
+    fn pem(der: &[u8]) -> String {
+        use base64::Engine as _;
+        format!(
+            "-----BEGIN X509 CRL-----\n{}\n-----END X509 CRL-----\n",
+            base64::engine::general_purpose::STANDARD.encode(der)
+        )
+    }
+
+    /// Serve `body` at `/crl` on an ephemeral port; returns the URL.
+    async fn serve(body: &'static [u8]) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/crl", get(move || async move { body }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}/crl")
+    }
+
+    #[test]
+    fn parse_crls_accepts_pem_and_der() {
+        let from_pem = parse_crls(pem(DER_CRL).repeat(2).as_bytes(), "crl.pem").unwrap();
+        assert_eq!(from_pem.len(), 2);
+        assert_eq!(from_pem[0].as_ref(), DER_CRL);
+
+        let from_der = parse_crls(DER_CRL, "crl.der").unwrap();
+        assert_eq!(from_der[0].as_ref(), DER_CRL);
+
+        let err = parse_crls(b"not a crl", "junk").unwrap_err();
+        assert!(err.to_string().contains("No CRL found in 'junk'"));
+    }
+
+    #[tokio::test]
+    async fn fetch_crls_downloads_every_url() {
+        // GIVEN: a distribution point serving a DER CRL
+        let url = serve(DER_CRL).await;
+
+        // WHEN: fetching it twice over
+        let crls = fetch_crls(&http_client(), &[url.clone(), url])
+            .await
+            .unwrap();
+
+        // THEN: both copies are parsed
+        assert_eq!(crls.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn unreachable_crl_url_fails_only_in_hard_mode() {
+        // GIVEN: a CRL URL nothing listens on
+        let config = RevocationConfig {
+            crl_urls: vec!["http://127.0.0.1:1/crl".to_string()],
+            ..RevocationConfig::default()
+        };
+        let client = http_client();
+
+        // THEN: hard-fail refuses to start, soft-fail starts without it
+        assert!(initial_crls(&config, &client).await.is_err());
+        let soft = RevocationConfig {
+            fail_mode: RevocationFailMode::Soft,
+            ..config
+        };
+        assert!(initial_crls(&soft, &client).await.unwrap().is_empty());
+    }
+}
@@ -5,7 +5,6 @@
 //! Main OAuth client implementation with PKCE support.
 
 use std::sync::Arc;
-use std::time::Duration;
 
 use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
 use parking_lot::RwLock;
@@ -19,7 +18,8 @@ use url::Url;
 
 use super::callback;
 use super::metadata::{self, AuthorizationServerMetadata, ProtectedResourceMetadata};
-use super::storage::{TokenInfo, TokenStorage};
+use super::refresh::{self, RefreshHealth};
+use super::storage::{RegisteredClient, TokenInfo, TokenStorage};
 use crate::{Error, Result};
 
 /// Provenance of a `client_id` (MIK-6750 r7, Defect 2).
@@ -80,6 +80,10 @@ pub struct OAuthClient {
     /// Pre-configured client secret (for providers like Slack / Figma).
     client_secret: Option<String>,
 
+    /// Secret issued with a dynamically-registered `client_id`. Cleared
+    /// together with that id; a configured `client_secret` takes precedence.
+    registered_secret: RwLock<Option<String>>,
+
     /// Callback host override (default: "localhost", dual-binds IPv4+IPv6).
     callback_host: Option<String>,
 
@@ -91,8 +95,22 @@ pub struct OAuthClient {
 
     /// Seconds before expiry at which the background task proactively refreshes.
     ///
-    /// The task triggers when `time_until_expiry < max(lifetime * 10%, buffer)`.
+    /// The task triggers when `time_until_expiry < buffer + refresh_jitter_secs`.
     token_refresh_buffer_secs: u64,
+
+    /// Random extra lead time (up to 20% of the buffer) chosen once per
+    /// client, so backends with the same buffer do not refresh together.
+    refresh_jitter_secs: u64,
+
+    /// Outcome of background refreshes, polled by operator alerts.
+    refresh_health: Arc<RefreshHealth>,
+
+    /// Named account whose token this client holds (`None` = default login).
+    account: Option<String>,
+
+    /// Obtain tokens with the `client_credentials` grant instead of the
+    /// browser-based authorization code flow.
+    client_credentials: bool,
 }
 
 /// OAuth token response
@@ -124,9 +142,22 @@ impl std::fmt::Debug for TokenResponse {
 /// Client registration response
 #[derive(Deserialize)]
 struct ClientRegistrationResponse {
-    client_id: String,
-    #[allow(dead_code)]
-    client_secret: Option<String>,
+    #[serde(rename = "client_id")]
+    id: String,
+    #[serde(rename = "client_secret")]
+    secret: Option<String>,
+    #[serde(rename = "client_secret_expires_at")]
+    secret_expires_at: Option<u64>,
+}
+
+impl From<ClientRegistrationResponse> for RegisteredClient {
+    fn from(response: ClientRegistrationResponse) -> Self {
+        Self {
+            client_id: response.id,
+            client_secret: response.secret,
+            client_secret_expires_at: response.secret_expires_at,
+        }
+    }
 }
 
 // Manual `Debug` that redacts the issued client secret (CWE-532, mirrors PR
@@ -136,8 +167,9 @@ impl std::fmt::Debug for ClientRegistrationResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let redact_opt = |v: &Option<String>| if v.is_some() { "<redacted>" } else { "None" };
         f.debug_struct("ClientRegistrationResponse")
-            .field("client_id", &self.client_id)
-            .field("client_secret", &redact_opt(&self.client_secret))
+            .field("client_id", &self.id)
+            .field("client_secret", &redact_opt(&self.secret))
+            .field("client_secret_expires_at", &self.secret_expires_at)
             .finish()
     }
 }
@@ -160,6 +192,12 @@ pub struct OAuthClientConfig {
     pub callback_path: Option<String>,
     /// Seconds before expiry to proactively refresh (default: 300).
     pub token_refresh_buffer_secs: u64,
+    /// Use the `client_credentials` grant (no browser); needs `client_id`.
+    pub client_credentials: bool,
+    /// Where background refresh outcomes are recorded (`None` = private).
+    pub refresh_health: Option<Arc<RefreshHealth>>,
+    /// Named account to authorize and store tokens for (`None` = default).
+    pub account: Option<String>,
 }
 
 // Manual `Debug` that redacts the fixed OAuth client secret (CWE-532, mirrors
@@ -175,6 +213,9 @@ impl std::fmt::Debug for OAuthClientConfig {
             .field("callback_port", &self.callback_port)
             .field("callback_path", &self.callback_path)
             .field("token_refresh_buffer_secs", &self.token_refresh_buffer_secs)
+            .field("client_credentials", &self.client_credentials)
+            .field("refresh_health", &self.refresh_health)
+            .field("account", &self.account)
             .finish()
     }
 }
@@ -210,10 +251,15 @@ impl OAuthClient {
             client_id: RwLock::new(cfg.client_id),
             client_id_source: RwLock::new(client_id_source),
             client_secret: cfg.client_secret,
+            registered_secret: RwLock::new(None),
             callback_host: cfg.callback_host,
             callback_port: cfg.callback_port,
             callback_path: cfg.callback_path,
             token_refresh_buffer_secs: cfg.token_refresh_buffer_secs,
+            refresh_jitter_secs: refresh::buffer_jitter_secs(cfg.token_refresh_buffer_secs),
+            refresh_health: cfg.refresh_health.unwrap_or_default(),
+            account: cfg.account,
+            client_credentials: cfg.client_credentials,
         }
     }
 
@@ -262,7 +308,11 @@ impl OAuthClient {
             Some(AuthorizationServerMetadata::discover(&self.http_client, auth_base).await?);
 
         // Load any cached token
-        if let Some(token) = self.storage.load(&self.backend_name, &self.resource_url) {
+        if let Some(token) = self.storage.load_account(
+            &self.backend_name,
+            &self.resource_url,
+            self.account.as_deref(),
+        ) {
             *self.current_token.write() = Some(token);
         }
 
@@ -284,20 +334,41 @@ impl OAuthClient {
     /// Because of that guard, any id loaded here is necessarily a prior
     /// Dynamic Client Registration, never operator config (Defect 2,
     /// MIK-6750 r7) — safe to mark `Registered` so a later `invalid_client`
-    /// rejection may purge it.
+    /// rejection may purge it. A registration whose issued secret has
+    /// expired is not restored, so the next authorization registers afresh;
+    /// saving that registration replaces the expired record rather than
+    /// adopting it (see [`TokenStorage::save_registered_client`]).
     fn restore_persisted_client_id(&self) {
         if self.client_id.read().is_some() {
             return;
         }
-        if let Some(cid) = self
+        if let Some(client) = self
             .storage
-            .load_client_id(&self.backend_name, &self.resource_url)
+            .load_registered_client(&self.backend_name, &self.resource_url)
         {
-            *self.client_id.write() = Some(cid);
-            *self.client_id_source.write() = Some(ClientIdSource::Registered);
+            if client.is_expired() {
+                info!(backend = %self.backend_name, "Registered client secret expired; will re-register");
+                return;
+            }
+            self.adopt_registered_client(client);
         }
     }
 
+    /// Use a dynamically-registered client's credentials from now on.
+    fn adopt_registered_client(&self, client: RegisteredClient) {
+        *self.client_id.write() = Some(client.client_id);
+        *self.client_id_source.write() = Some(ClientIdSource::Registered);
+        *self.registered_secret.write() = client.client_secret;
+    }
+
+    /// Client secret sent on token requests: the configured one, otherwise
+    /// the one issued by dynamic registration.
+    fn effective_client_secret(&self) -> Option<String> {
+        self.client_secret
+            .clone()
+            .or_else(|| self.registered_secret.read().clone())
+    }
+
     /// Get a valid access token, refreshing or re-authorizing as needed
     ///
     /// # Errors
@@ -345,7 +416,8 @@ impl OAuthClient {
 
     /// Return true if the token should be proactively refreshed.
     ///
-    /// Triggers when remaining lifetime is below `max(lifetime * 10%, buffer)`.
+    /// Triggers when remaining lifetime is below the refresh buffer plus this
+    /// client's jitter, so an already-expired token is always due.
     #[must_use]
     pub fn needs_proactive_refresh(&self) -> bool {
         let token = self.current_token.read();
@@ -362,18 +434,13 @@ impl OAuthClient {
             .as_secs();
 
         let remaining = expires_at.saturating_sub(now);
-
-        // Compute 10% of total lifetime using the stored expires_at as a proxy.
-        // We don't store issued_at, so approximate lifetime as (expires_at - now + remaining)
-        // which simplifies to: use a fixed fraction of the buffer itself.
-        // Practical rule: trigger refresh at max(buffer, 10% of remaining_at_last_check).
-        // Since we check every 60s, use the simpler form: remaining < buffer.
-        remaining < self.token_refresh_buffer_secs
+        remaining < self.token_refresh_buffer_secs + self.refresh_jitter_secs
     }
 
     /// Attempt client-credentials grant (headless re-auth, no browser required).
     ///
-    /// Returns `Ok(token)` only when the authorization server explicitly lists
+    /// Unless the grant is configured for this backend, returns `Ok(token)`
+    /// only when the authorization server explicitly lists
     /// `"client_credentials"` in `grant_types_supported` — so we never try it
     /// against a server that won't accept it.
     async fn try_client_credentials(&self) -> Result<String> {
@@ -382,10 +449,11 @@ impl OAuthClient {
             .as_ref()
             .ok_or_else(|| Error::OAuth("OAuth not initialized".to_string()))?;
 
-        if !auth_meta
-            .grant_types_supported
-            .iter()
-            .any(|g| g == "client_credentials")
+        if !self.client_credentials
+            && !auth_meta
+                .grant_types_supported
+                .iter()
+                .any(|g| g == "client_credentials")
         {
             return Err(Error::OAuth(
                 "Server does not support client_credentials grant".to_string(),
@@ -434,8 +502,12 @@ impl OAuthClient {
             token_response.scope,
         );
 
-        self.storage
-            .save(&self.backend_name, &self.resource_url, &token)?;
+        self.storage.save_account(
+            &self.backend_name,
+            &self.resource_url,
+            self.account.as_deref(),
+            &token,
+        )?;
         *self.current_token.write() = Some(token.clone());
 
         info!(backend = %self.backend_name, "Token renewed via client_credentials");
@@ -444,9 +516,11 @@ impl OAuthClient {
 
     /// Try all headless renewal strategies (`refresh_token` → `client_credentials`).
     ///
-    /// Returns `Ok(true)` on success, `Ok(false)` when all automatic methods
-    /// are unavailable and manual re-authorization is required.
-    async fn attempt_background_renewal(&self) -> bool {
+    /// # Errors
+    ///
+    /// Returns the last strategy's error when every automatic method failed
+    /// and manual re-authorization is required.
+    pub async fn attempt_background_renewal(&self) -> Result<()> {
         // Strategy 1: refresh_token grant
         let refresh_token_opt = {
             let token = self.current_token.read();
@@ -455,7 +529,7 @@ impl OAuthClient {
 
         if let Some(refresh_token) = refresh_token_opt {
             match self.refresh_token(&refresh_token).await {
-                Ok(_) => return true,
+                Ok(_) => return Ok(()),
                 Err(e) => {
                     debug!(
                         backend = %self.backend_name,
@@ -467,25 +541,47 @@ impl OAuthClient {
         }
 
         // Strategy 2: client_credentials grant (headless, for Beeper-style tokens)
-        match self.try_client_credentials().await {
-            Ok(_) => return true,
-            Err(e) => {
-                debug!(
-                    backend = %self.backend_name,
-                    error = %e,
-                    "client_credentials renewal failed"
-                );
-            }
+        self.try_client_credentials().await.map(|_| ())
+    }
+
+    /// Replace the in-memory token with the stored one if the store holds a
+    /// later-expiring token, as written by the cluster leader's refresh.
+    ///
+    /// Returns whether the token was replaced.
+    pub fn adopt_stored_token(&self) -> bool {
+        let Some(stored) = self.storage.load_account(
+            &self.backend_name,
+            &self.resource_url,
+            self.account.as_deref(),
+        ) else {
+            return false;
+        };
+        let mut current = self.current_token.write();
+        let newer = current.as_ref().is_none_or(|t| {
+            stored.expires_at.unwrap_or(u64::MAX) > t.expires_at.unwrap_or(u64::MAX)
+        });
+        if newer {
+            *current = Some(stored);
         }
+        newer
+    }
 
-        false
+    /// Shared record of this client's background refresh outcomes.
+    #[must_use]
+    pub fn refresh_health(&self) -> Arc<RefreshHealth> {
+        Arc::clone(&self.refresh_health)
     }
 
     /// Spawn a background task that proactively refreshes the token before it
-    /// expires.  The task runs for the lifetime of the provided `Arc`; it
-    /// stops automatically when the last strong reference is dropped.
+    /// expires.  The task runs until its `JoinHandle` is aborted.
+    ///
+    /// Checks run every [`refresh::REFRESH_CHECK_INTERVAL`] ± 20%. A failed
+    /// renewal is retried on the next check; only the first failure of a
+    /// streak is logged as a warning and bumps the client's
+    /// [`RefreshHealth`], which operator alerts report.
     ///
-    /// The returned `JoinHandle` can be aborted to cancel the task.
+    /// In a cluster only the leader refreshes (a rotating refresh token must
+    /// be spent once); other replicas adopt the token it stores instead.
     ///
     /// # Panics
     ///
@@ -496,27 +592,35 @@ impl OAuthClient {
     ) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
             loop {
-                tokio::time::sleep(Duration::from_secs(60)).await;
-
-                // Use a weak reference pattern: if the Arc has been dropped
-                // (HttpTransport gone), stop the loop.
-                let needs_refresh = {
-                    let guard = client.lock().await;
-                    guard.needs_proactive_refresh()
-                };
-
-                if needs_refresh {
-                    let success = {
-                        let guard = client.lock().await;
-                        guard.attempt_background_renewal().await
-                    };
-
-                    if !success {
-                        warn!(
-                            backend = %backend_name,
-                            "All automatic token renewal strategies failed — \
-                             manual re-authorization required"
-                        );
+                tokio::time::sleep(refresh::next_check_delay()).await;
+
+                let guard = client.lock().await;
+                if !crate::cluster::leader::is_leader() {
+                    if guard.adopt_stored_token() {
+                        debug!(backend = %backend_name, "Adopted token refreshed by cluster leader");
+                    }
+                    continue;
+                }
+                if !guard.needs_proactive_refresh() {
+                    continue;
+                }
+                match guard.attempt_background_renewal().await {
+                    Ok(()) => {
+                        if guard.refresh_health.record_success() {
+                            info!(backend = %backend_name, "Background token refresh recovered");
+                        }
+                    }
+                    Err(e) => {
+                        if guard.refresh_health.record_failure(e.to_string()) {
+                            warn!(
+                                backend = %backend_name,
+                                error = %e,
+                                "All automatic token renewal strategies failed — \
+                                 retrying; manual re-authorization may be required"
+                            );
+                        } else {
+                            debug!(backend = %backend_name, error = %e, "Token renewal still failing");
+                        }
                     }
                 }
             }
@@ -600,9 +704,10 @@ impl OAuthClient {
             ("client_id", client_id.to_string()),
             ("code_verifier", code_verifier.to_string()),
         ];
-        // Include client_secret when the provider requires it (Slack, Figma, …).
-        if let Some(ref secret) = self.client_secret {
-            params.push(("client_secret", secret.clone()));
+        // Include client_secret when the provider requires it (Slack, Figma, …)
+        // or issued one at dynamic registration.
+        if let Some(secret) = self.effective_client_secret() {
+            params.push(("client_secret", secret));
         }
         // RFC 8707 Resource Indicator — must match the authorization request so
         // the AS issues an audience-bound token (issue #369).
@@ -617,8 +722,8 @@ impl OAuthClient {
             ("refresh_token", refresh_token.to_string()),
             ("client_id", client_id.to_string()),
         ];
-        if let Some(ref secret) = self.client_secret {
-            params.push(("client_secret", secret.clone()));
+        if let Some(secret) = self.effective_client_secret() {
+            params.push(("client_secret", secret));
         }
         // RFC 8707 Resource Indicator — keep the refreshed token audience-bound
         // to this MCP server, matching the original grant (issue #369).
@@ -627,13 +732,19 @@ impl OAuthClient {
     }
 
     /// Form parameters for the `client_credentials` grant (RFC 6749 §4.4 +
-    /// RFC 8707). No `client_secret` is added here: this path historically
+    /// RFC 8707). The `client_secret` is only sent when the grant is
+    /// configured for this backend: the renewal fallback historically
     /// authenticates public/dynamically-registered clients without one.
     fn client_credentials_params(&self, client_id: &str) -> Vec<(&'static str, String)> {
         let mut params = vec![
             ("grant_type", "client_credentials".to_string()),
             ("client_id", client_id.to_string()),
         ];
+        if self.client_credentials
+            && let Some(ref secret) = self.client_secret
+        {
+            params.push(("client_secret", secret.clone()));
+        }
         let scope_str = self.scopes.join(" ");
         if !scope_str.is_empty() {
             params.push(("scope", scope_str));
@@ -646,11 +757,18 @@ impl OAuthClient {
 
     /// Perform the authorization flow
     ///
+    /// With the `client_credentials` grant configured, the token is requested
+    /// directly from the token endpoint; no browser or callback is involved.
+    ///
     /// # Errors
     ///
     /// Returns an error if any step of the OAuth authorization flow fails
     /// (callback server, client registration, browser auth, or code exchange).
     pub async fn authorize(&self) -> Result<String> {
+        if self.client_credentials {
+            return self.try_client_credentials().await;
+        }
+
         let auth_meta = self
             .auth_metadata
             .as_ref()
@@ -687,7 +805,12 @@ impl OAuthClient {
 
         // Open browser
         let auth_url_str = auth_url.to_string();
-        info!(url = %auth_url_str, "Opening browser for authorization");
+        info!(
+            url = %auth_url_str,
+            backend = %self.backend_name,
+            account = self.account.as_deref(),
+            "Opening browser for authorization"
+        );
 
         if !open_browser(&auth_url_str) {
             warn!("Failed to open browser automatically");
@@ -705,8 +828,12 @@ impl OAuthClient {
             .await?;
 
         // Store and cache the token
-        self.storage
-            .save(&self.backend_name, &self.resource_url, &token)?;
+        self.storage.save_account(
+            &self.backend_name,
+            &self.resource_url,
+            self.account.as_deref(),
+            &token,
+        )?;
         *self.current_token.write() = Some(token.clone());
 
         Ok(token.access_token)
@@ -825,8 +952,12 @@ impl OAuthClient {
         );
 
         // Store and cache
-        self.storage
-            .save(&self.backend_name, &self.resource_url, &token)?;
+        self.storage.save_account(
+            &self.backend_name,
+            &self.resource_url,
+            self.account.as_deref(),
+            &token,
+        )?;
         *self.current_token.write() = Some(token.clone());
 
         info!(backend = %self.backend_name, "Token refreshed successfully");
@@ -848,22 +979,22 @@ impl OAuthClient {
         // Try dynamic registration if supported
         if let Some(ref reg_endpoint) = auth_meta.registration_endpoint {
             match self.register_client(reg_endpoint, redirect_uri).await {
-                Ok(client_id) => {
+                Ok(client) => {
                     // Persist immediately: registration succeeded even if the
                     // browser authorize step below never completes. Without this
                     // every connection re-registers and opens a new OAuth tab.
-                    match self.storage.save_client_id(
+                    match self.storage.save_registered_client(
                         &self.backend_name,
                         &self.resource_url,
-                        &client_id,
+                        &client,
                     ) {
                         Ok(persisted) => {
                             // First-writer-wins: a co-located instance may have
                             // registered concurrently; adopt the authoritative
-                            // on-disk id so both instances converge on one.
-                            *self.client_id.write() = Some(persisted.clone());
-                            *self.client_id_source.write() = Some(ClientIdSource::Registered);
-                            return Ok(persisted);
+                            // on-disk client so both instances converge on one.
+                            let client_id = persisted.client_id.clone();
+                            self.adopt_registered_client(persisted);
+                            return Ok(client_id);
                         }
                         Err(e) => {
                             // Do NOT silently swallow: a lost write re-opens the
@@ -881,8 +1012,8 @@ impl OAuthClient {
                                 "Failed to persist registered client_id; it will be re-registered \
                                  on next restart (auth churn until the write path is fixed)"
                             );
-                            *self.client_id.write() = Some(client_id.clone());
-                            *self.client_id_source.write() = Some(ClientIdSource::Registered);
+                            let client_id = client.client_id.clone();
+                            self.adopt_registered_client(client);
                             return Ok(client_id);
                         }
                     }
@@ -932,6 +1063,7 @@ impl OAuthClient {
         );
         *self.client_id.write() = None;
         *self.client_id_source.write() = None;
+        *self.registered_secret.write() = None;
         if let Err(e) = self
             .storage
             .delete_client_id(&self.backend_name, &self.resource_url)
@@ -940,15 +1072,14 @@ impl OAuthClient {
         }
     }
 
-    /// Register a new client dynamically with the specified redirect URI
-    async fn register_client(&self, endpoint: &str, redirect_uri: &str) -> Result<String> {
-        let body = serde_json::json!({
-            "client_name": format!("MCP Gateway - {}", self.backend_name),
-            "redirect_uris": [redirect_uri],
-            "grant_types": ["authorization_code", "refresh_token"],
-            "response_types": ["code"],
-            "token_endpoint_auth_method": "none"
-        });
+    /// Register a new client dynamically (RFC 7591) with the specified
+    /// redirect URI
+    async fn register_client(
+        &self,
+        endpoint: &str,
+        redirect_uri: &str,
+    ) -> Result<RegisteredClient> {
+        let body = self.registration_request(redirect_uri);
 
         let response = self
             .http_client
@@ -971,8 +1102,42 @@ impl OAuthClient {
             .await
             .map_err(|e| Error::OAuth(format!("Failed to parse registration response: {e}")))?;
 
-        info!(client_id = %reg_response.client_id, "Registered OAuth client");
-        Ok(reg_response.client_id)
+        info!(
+            client_id = %reg_response.id,
+            confidential = reg_response.secret.is_some(),
+            "Registered OAuth client"
+        );
+        Ok(reg_response.into())
+    }
+
+    /// RFC 7591 registration metadata for this gateway.
+    ///
+    /// Registers as a public client (`none`) unless the authorization server
+    /// advertises token endpoint auth methods without it, in which case a
+    /// confidential `client_secret_post` client is requested and the issued
+    /// secret is persisted with the id.
+    fn registration_request(&self, redirect_uri: &str) -> serde_json::Value {
+        let methods = self
+            .auth_metadata
+            .as_ref()
+            .map(|meta| meta.token_endpoint_auth_methods_supported.as_slice())
+            .unwrap_or_default();
+        let auth_method = if methods.is_empty() || methods.iter().any(|m| m == "none") {
+            "none"
+        } else {
+            "client_secret_post"
+        };
+        let mut body = serde_json::json!({
+            "client_name": format!("MCP Gateway - {}", self.backend_name),
+            "redirect_uris": [redirect_uri],
+            "grant_types": ["authorization_code", "refresh_token"],
+            "response_types": ["code"],
+            "token_endpoint_auth_method": auth_method
+        });
+        if !self.scopes.is_empty() {
+            body["scope"] = self.scopes.join(" ").into();
+        }
+        body
     }
 }
 
@@ -1060,8 +1225,9 @@ mod cwe532_debug_redaction {
     #[test]
     fn client_registration_response_debug_redacts_secret() {
         let r = ClientRegistrationResponse {
-            client_id: "client-123".to_string(),
-            client_secret: Some(SENTINEL.to_string()),
+            id: "client-123".to_string(),
+            secret: Some(SENTINEL.to_string()),
+            secret_expires_at: None,
         };
         let dbg = format!("{r:?}");
         assert!(!dbg.contains(SENTINEL), "leaked client_secret: {dbg}");
@@ -736,3 +736,234 @@ fn client_credentials_params_include_resource() {
         "client_credentials must send the RFC 8707 resource indicator"
     );
 }
+
+// =========================================================================
+// client_credentials grant
+// =========================================================================
+
+type CapturedForm = Arc<parking_lot::Mutex<Vec<(String, String)>>>;
+
+async fn token_endpoint(
+    axum::extract::State(captured): axum::extract::State<CapturedForm>,
+    axum::Form(form): axum::Form<Vec<(String, String)>>,
+) -> axum::Json<serde_json::Value> {
+    *captured.lock() = form;
+    axum::Json(serde_json::json!({
+        "access_token": "cc-token",
+        "token_type": "Bearer",
+        "expires_in": 120
+    }))
+}
+
+fn token_only_metadata(token_endpoint: &str) -> AuthorizationServerMetadata {
+    serde_json::from_value(serde_json::json!({
+        "issuer": "https://auth.example.test",
+        "token_endpoint": token_endpoint,
+    }))
+    .unwrap()
+}
+
+/// A configured `client_credentials` grant authorizes without a browser,
+/// sends the client secret and needs no `grant_types_supported` entry.
+#[tokio::test]
+async fn client_credentials_grant_authorizes_headlessly() {
+    // GIVEN: a token endpoint on a server whose metadata lists no grants
+    let captured = CapturedForm::default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = axum::Router::new()
+        .route("/token", axum::routing::post(token_endpoint))
+        .with_state(Arc::clone(&captured));
+    let server = tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let storage = Arc::new(TokenStorage::new(dir.path().to_path_buf()).unwrap());
+    let mut client = OAuthClient::new(
+        Client::new(),
+        "billing".to_string(),
+        "https://billing.example.test/mcp".to_string(),
+        vec!["billing.read".to_string()],
+        storage,
+        OAuthClientConfig {
+            client_id: Some("svc-id".to_string()),
+            client_secret: Some("svc-secret".to_string()),
+            token_refresh_buffer_secs: 300,
+            client_credentials: true,
+            ..Default::default()
+        },
+    );
+    client.auth_metadata = Some(token_only_metadata(&format!("http://{addr}/token")));
+
+    // WHEN: authorizing
+    let token = client.authorize().await.unwrap();
+
+    // THEN: the token comes straight from the token endpoint
+    assert_eq!(token, "cc-token");
+    let form = captured.lock().clone();
+    let field = |key: &str| form.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+    assert_eq!(field("grant_type").as_deref(), Some("client_credentials"));
+    assert_eq!(field("client_secret").as_deref(), Some("svc-secret"));
+    assert_eq!(field("scope").as_deref(), Some("billing.read"));
+    // AND: the 120s token is due for proactive renewal within the 300s buffer
+    assert!(client.has_valid_token());
+    assert!(client.needs_proactive_refresh());
+    client.attempt_background_renewal().await.unwrap();
+
+    server.abort();
+}
+
+// =========================================================================
+// Dynamic client registration (RFC 7591)
+// =========================================================================
+
+type CapturedJson = Arc<parking_lot::Mutex<serde_json::Value>>;
+
+async fn registration_endpoint(
+    axum::extract::State(captured): axum::extract::State<CapturedJson>,
+    axum::Json(body): axum::Json<serde_json::Value>,
+) -> (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+    *captured.lock() = body;
+    (
+        axum::http::StatusCode::CREATED,
+        axum::Json(serde_json::json!({
+            "client_id": "dcr-client",
+            "client_secret": "dcr-secret",
+            "client_secret_expires_at": 0
+        })),
+    )
+}
+
+fn dcr_client(storage: Arc<TokenStorage>, registration_endpoint: Option<&str>) -> OAuthClient {
+    let mut client = OAuthClient::new(
+        Client::new(),
+        "crm".to_string(),
+        "https://crm.example.test/mcp".to_string(),
+        vec!["crm.read".to_string()],
+        storage,
+        OAuthClientConfig {
+            token_refresh_buffer_secs: 300,
+            ..Default::default()
+        },
+    );
+    client.auth_metadata = Some(
+        serde_json::from_value(serde_json::json!({
+            "issuer": "https://auth.example.test",
+            "authorization_endpoint": "https://auth.example.test/authorize",
+            "token_endpoint": "https://auth.example.test/token",
+            "registration_endpoint": registration_endpoint,
+            "token_endpoint_auth_methods_supported": ["client_secret_post"]
+        }))
+        .unwrap(),
+    );
+    client
+}
+
+/// A server that only accepts confidential clients gets a `client_secret_post`
+/// registration, and the issued secret is persisted and reused after restart.
+#[tokio::test]
+async fn dynamic_registration_persists_issued_client_secret() {
+    // GIVEN: a registration endpoint that issues a secret
+    let captured = CapturedJson::default();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = axum::Router::new()
+        .route("/register", axum::routing::post(registration_endpoint))
+        .with_state(Arc::clone(&captured));
+    let server = tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let storage = Arc::new(TokenStorage::new(dir.path().to_path_buf()).unwrap());
+    let client = dcr_client(
+        Arc::clone(&storage),
+        Some(&format!("http://{addr}/register")),
+    );
+
+    // WHEN: the authorization flow needs a client id
+    let client_id = client
+        .ensure_client_id_with_redirect("http://localhost:8085/oauth/callback")
+        .await
+        .unwrap();
+
+    // THEN: a confidential client was requested and its secret is used
+    assert_eq!(client_id, "dcr-client");
+    let request = captured.lock().clone();
+    assert_eq!(request["token_endpoint_auth_method"], "client_secret_post");
+    assert_eq!(request["scope"], "crm.read");
+    let params = client.token_exchange_params("code", "http://cb", &client_id, "verifier");
+    assert_eq!(param(&params, "client_secret"), Some("dcr-secret"));
+
+    // AND: a restarted client restores id and secret without registering
+    let restarted = dcr_client(Arc::clone(&storage), None);
+    restarted.restore_persisted_client_id();
+    let params = restarted.refresh_params("refresh", "dcr-client");
+    assert_eq!(param(&params, "client_secret"), Some("dcr-secret"));
+    assert_eq!(
+        storage
+            .load_registered_client("crm", "https://crm.example.test/mcp")
+            .unwrap()
+            .client_secret
+            .as_deref(),
+        Some("dcr-secret")
+    );
+
+    server.abort();
+}
+
+/// An expired registration is not restored, and the re-registration that
+/// follows replaces it on disk rather than adopting it again.
+#[tokio::test]
+async fn expired_registration_is_replaced_by_re_registration() {
+    // GIVEN: a persisted registration whose secret has expired
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = axum::Router::new()
+        .route("/register", axum::routing::post(registration_endpoint))
+        .with_state(CapturedJson::default());
+    let server = tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let storage = Arc::new(TokenStorage::new(dir.path().to_path_buf()).unwrap());
+    storage
+        .save_registered_client(
+            "crm",
+            "https://crm.example.test/mcp",
+            &RegisteredClient {
+                client_id: "stale-client".to_string(),
+                client_secret: Some("stale-secret".to_string()),
+                client_secret_expires_at: Some(1),
+            },
+        )
+        .unwrap();
+    let client = dcr_client(
+        Arc::clone(&storage),
+        Some(&format!("http://{addr}/register")),
+    );
+
+    // WHEN: the client starts and then needs a client id
+    client.restore_persisted_client_id();
+    assert!(
+        client.client_id.read().is_none(),
+        "expired record not restored"
+    );
+    let client_id = client
+        .ensure_client_id_with_redirect("http://localhost:8085/oauth/callback")
+        .await
+        .unwrap();
+
+    // THEN: the fresh registration is used and persisted
+    assert_eq!(client_id, "dcr-client");
+    let params = client.token_exchange_params("code", "http://cb", &client_id, "verifier");
+    assert_eq!(param(&params, "client_secret"), Some("dcr-secret"));
+    assert_eq!(
+        storage
+            .load_registered_client("crm", "https://crm.example.test/mcp")
+            .unwrap()
+            .client_id,
+        "dcr-client"
+    );
+
+    server.abort();
+}
@@ -19,7 +19,9 @@ pub struct AuthorizationServerMetadata {
     /// Authorization server issuer URL
     pub issuer: String,
 
-    /// Authorization endpoint URL
+    /// Authorization endpoint URL (absent on servers that only issue
+    /// `client_credentials` tokens)
+    #[serde(default)]
     pub authorization_endpoint: String,
 
     /// Token endpoint URL
@@ -8,15 +8,19 @@
 //! Features:
 //! - OAuth metadata discovery (RFC 8414)
 //! - Authorization code flow with PKCE
-//! - Token storage and automatic refresh
+//! - Dynamic client registration (RFC 7591) with persisted credentials
+//! - Token storage and jittered background refresh ahead of expiry
 //! - Browser-based authorization
 //! - Callback server for auth code reception
 
 mod callback;
 mod client;
 mod metadata;
+mod refresh;
 mod storage;
+mod vault;
 
 pub use client::{OAuthClient, OAuthClientConfig};
 pub use metadata::{AuthorizationServerMetadata, ProtectedResourceMetadata};
-pub use storage::{TokenInfo, TokenStorage};
+pub use refresh::RefreshHealth;
+pub use storage::{RegisteredClient, TokenInfo, TokenStorage};
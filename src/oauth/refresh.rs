@@ -0,0 +1,128 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Background token refresh scheduling and health.
+//!
+//! [`OAuthClient::spawn_refresh_task`](super::OAuthClient::spawn_refresh_task)
+//! renews tokens before they expire. Checks are jittered so backends sharing
+//! an authorization server do not refresh in lockstep, and every outcome is
+//! recorded in a [`RefreshHealth`] that operator alerts poll
+//! ([`crate::alerts`]), the same way circuit breaker trips are detected.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use rand::RngExt;
+
+/// Nominal time between two refresh checks.
+pub const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum deviation of a check from [`REFRESH_CHECK_INTERVAL`], in percent.
+const CHECK_JITTER_PERCENT: u64 = 20;
+
+/// Delay until the next refresh check: [`REFRESH_CHECK_INTERVAL`] ± 20%.
+#[must_use]
+pub fn next_check_delay() -> Duration {
+    let base = u64::try_from(REFRESH_CHECK_INTERVAL.as_millis()).unwrap_or(u64::MAX);
+    let spread = base * CHECK_JITTER_PERCENT / 100;
+    let offset = rand::rng().random_range(0..=2 * spread);
+    Duration::from_millis(base - spread + offset)
+}
+
+/// Random extra lead time for a refresh buffer: up to 20% of `buffer_secs`,
+/// so clients configured with the same buffer refresh at different moments.
+#[must_use]
+pub fn buffer_jitter_secs(buffer_secs: u64) -> u64 {
+    rand::rng().random_range(0..=buffer_secs * CHECK_JITTER_PERCENT / 100)
+}
+
+/// Outcome history of one backend's background token refreshes.
+///
+/// Shared between the backend (read by alerting) and its OAuth client's
+/// refresh task (written).
+#[derive(Debug, Default)]
+pub struct RefreshHealth {
+    /// Number of failure streaks so far; bumps on the first failure after a
+    /// success (or after startup).
+    failure_streaks: AtomicU64,
+    /// Whether the latest attempt failed.
+    failing: AtomicBool,
+    /// Error of the latest failed attempt.
+    last_error: RwLock<Option<String>>,
+}
+
+impl RefreshHealth {
+    /// Record a successful refresh.
+    ///
+    /// Returns `true` when this ends a failure streak.
+    pub fn record_success(&self) -> bool {
+        self.failing.swap(false, Ordering::AcqRel)
+    }
+
+    /// Record a failed refresh.
+    ///
+    /// Returns `true` when this starts a new failure streak.
+    pub fn record_failure(&self, error: String) -> bool {
+        *self.last_error.write() = Some(error);
+        let started = !self.failing.swap(true, Ordering::AcqRel);
+        if started {
+            self.failure_streaks.fetch_add(1, Ordering::AcqRel);
+        }
+        started
+    }
+
+    /// Number of failure streaks recorded so far.
+    #[must_use]
+    pub fn failure_streaks(&self) -> u64 {
+        self.failure_streaks.load(Ordering::Acquire)
+    }
+
+    /// Whether the latest refresh attempt failed.
+    #[must_use]
+    pub fn is_failing(&self) -> bool {
+        self.failing.load(Ordering::Acquire)
+    }
+
+    /// Error of the latest failed attempt.
+    #[must_use]
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.read().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_delay_stays_within_jitter_bounds() {
+        for _ in 0..100 {
+            let delay = next_check_delay();
+            assert!(delay >= Duration::from_secs(48), "{delay:?}");
+            assert!(delay <= Duration::from_secs(72), "{delay:?}");
+        }
+        assert!(buffer_jitter_secs(300) <= 60);
+        assert_eq!(buffer_jitter_secs(0), 0);
+    }
+
+    #[test]
+    fn failures_count_once_per_streak() {
+        // GIVEN: a fresh health record
+        let health = RefreshHealth::default();
+
+        // WHEN: two failures in a row
+        assert!(health.record_failure("timeout".to_string()));
+        assert!(!health.record_failure("HTTP 500".to_string()));
+
+        // THEN: one streak, latest error kept
+        assert_eq!(health.failure_streaks(), 1);
+        assert!(health.is_failing());
+        assert_eq!(health.last_error().as_deref(), Some("HTTP 500"));
+
+        // AND: a success ends the streak and the next failure starts another
+        assert!(health.record_success());
+        assert!(!health.record_success());
+        assert!(health.record_failure("invalid_grant".to_string()));
+        assert_eq!(health.failure_streaks(), 2);
+    }
+}
@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! OAuth Token Storage
 //!
-//! Persists OAuth tokens to disk for reuse across gateway restarts.
+//! Persists OAuth tokens and dynamically-registered clients to disk for
+//! reuse across gateway restarts. Both are encrypted at rest (see
+//! [`super::vault`]).
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -13,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tracing::{debug, info, warn};
 
+use super::vault::TokenVault;
 use crate::{Error, Result};
 
 /// OAuth token information
@@ -75,6 +78,73 @@ impl std::fmt::Debug for TokenInfo {
     }
 }
 
+/// Client credentials issued by Dynamic Client Registration (RFC 7591).
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredClient {
+    /// Issued `client_id`.
+    pub client_id: String,
+    /// Issued `client_secret`, for confidential registrations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    /// When the secret expires (Unix timestamp; `0` or absent = never).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret_expires_at: Option<u64>,
+}
+
+impl RegisteredClient {
+    /// A public client with only an id.
+    #[must_use]
+    pub fn public(client_id: &str) -> Self {
+        Self {
+            client_id: client_id.to_string(),
+            client_secret: None,
+            client_secret_expires_at: None,
+        }
+    }
+
+    /// Whether the issued secret has expired, so the client must re-register.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.client_secret_expires_at
+            .is_some_and(|at| at != 0 && at <= now)
+    }
+}
+
+// Manual `Debug` that redacts the issued client secret (CWE-532, mirrors PR
+// #323); only its presence is surfaced.
+impl std::fmt::Debug for RegisteredClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redact_opt = |v: &Option<String>| if v.is_some() { "<redacted>" } else { "None" };
+        f.debug_struct("RegisteredClient")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &redact_opt(&self.client_secret))
+            .field("client_secret_expires_at", &self.client_secret_expires_at)
+            .finish()
+    }
+}
+
+/// On-disk client record: a bare `client_id` string (written before client
+/// secrets were persisted) or a full [`RegisteredClient`].
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredClient {
+    Id(String),
+    Registered(RegisteredClient),
+}
+
+impl From<StoredClient> for RegisteredClient {
+    fn from(stored: StoredClient) -> Self {
+        match stored {
+            StoredClient::Id(id) => Self::public(&id),
+            StoredClient::Registered(client) => client,
+        }
+    }
+}
+
 impl TokenInfo {
     /// Create token info from OAuth token response
     pub fn from_response(
@@ -143,26 +213,30 @@ impl TokenInfo {
 pub struct TokenStorage {
     /// Base directory for token storage
     base_dir: PathBuf,
+    /// Encrypts token files at rest
+    vault: TokenVault,
 }
 
 impl TokenStorage {
-    /// Create a new token storage with the given base directory
+    /// Create a new token storage with the given base directory.
+    ///
+    /// Tokens are encrypted with a key file in `base_dir`; the OS keychain is
+    /// only used by [`Self::default_location`].
     ///
     /// # Errors
     ///
     /// Returns an error if the storage directory cannot be created.
     pub fn new(base_dir: PathBuf) -> Result<Self> {
-        // Create directory if it doesn't exist
-        if !base_dir.exists() {
-            fs::create_dir_all(&base_dir)
-                .map_err(|e| Error::OAuth(format!("Failed to create token storage dir: {e}")))?;
-        }
-
-        Ok(Self { base_dir })
+        Self::create_dir(&base_dir)?;
+        let vault = TokenVault::key_file(&base_dir);
+        Ok(Self { base_dir, vault })
     }
 
     /// Create token storage in the default location (~/.mcp-gateway/oauth)
     ///
+    /// Tokens are encrypted with a key from `MCP_GATEWAY_TOKEN_PASSPHRASE`
+    /// when set, otherwise from the OS keychain.
+    ///
     /// # Errors
     ///
     /// Returns an error if the home directory cannot be determined or the
@@ -171,7 +245,19 @@ impl TokenStorage {
         let home = dirs::home_dir()
             .ok_or_else(|| Error::OAuth("Cannot determine home directory".to_string()))?;
 
-        Self::new(home.join(".mcp-gateway").join("oauth"))
+        let base_dir = home.join(".mcp-gateway").join("oauth");
+        Self::create_dir(&base_dir)?;
+        let vault = TokenVault::from_environment(&base_dir);
+        Ok(Self { base_dir, vault })
+    }
+
+    fn create_dir(base_dir: &Path) -> Result<()> {
+        // Create directory if it doesn't exist
+        if !base_dir.exists() {
+            fs::create_dir_all(base_dir)
+                .map_err(|e| Error::OAuth(format!("Failed to create token storage dir: {e}")))?;
+        }
+        Ok(())
     }
 
     /// Generate a storage key for a backend
@@ -184,40 +270,88 @@ impl TokenStorage {
         hex::encode(&hash[..8])
     }
 
-    /// Get the file path for a backend's tokens
-    fn token_path(&self, backend_name: &str, resource_url: &str) -> PathBuf {
+    /// Get the file path for a backend's tokens. Named accounts get their own
+    /// file; the default account keeps the original, account-less path.
+    fn token_path(&self, backend_name: &str, resource_url: &str, account: Option<&str>) -> PathBuf {
         let key = Self::storage_key(backend_name, resource_url);
-        self.base_dir.join(format!("{key}_tokens.json"))
+        match account {
+            None => self.base_dir.join(format!("{key}_tokens.json")),
+            Some(account) => {
+                let account_key = hex::encode(&Sha256::digest(account.as_bytes())[..8]);
+                self.base_dir
+                    .join(format!("{key}_{account_key}_tokens.json"))
+            }
+        }
+    }
+
+    /// File name of a token path, bound into its encryption.
+    fn file_name(path: &Path) -> String {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
     }
 
     /// Load tokens for a backend
     pub fn load(&self, backend_name: &str, resource_url: &str) -> Option<TokenInfo> {
-        let path = self.token_path(backend_name, resource_url);
+        self.load_account(backend_name, resource_url, None)
+    }
+
+    /// Load tokens for one of a backend's accounts (`None` = default account).
+    pub fn load_account(
+        &self,
+        backend_name: &str,
+        resource_url: &str,
+        account: Option<&str>,
+    ) -> Option<TokenInfo> {
+        let path = self.token_path(backend_name, resource_url, account);
 
         if !path.exists() {
             debug!(backend = %backend_name, "No stored tokens found");
             return None;
         }
 
-        match fs::read_to_string(&path) {
-            Ok(content) => match serde_json::from_str::<TokenInfo>(&content) {
-                Ok(token) => {
-                    if token.is_expired() {
-                        debug!(backend = %backend_name, "Stored token is expired");
-                        // Keep the token info in case we can refresh it
-                        Some(token)
-                    } else {
-                        info!(backend = %backend_name, expires_in = ?token.time_until_expiry(), "Loaded valid token");
-                        Some(token)
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!(backend = %backend_name, error = %e, "Failed to read token file");
+                return None;
+            }
+        };
+        let sealed = TokenVault::is_sealed(&content);
+        let json = if sealed {
+            match self.vault.open(&Self::file_name(&path), &content) {
+                Ok(json) => json,
+                Err(e) => {
+                    warn!(backend = %backend_name, error = %e, "Failed to decrypt stored token");
+                    return None;
+                }
+            }
+        } else {
+            content.into_bytes()
+        };
+
+        match serde_json::from_slice::<TokenInfo>(&json) {
+            Ok(token) => {
+                if !sealed {
+                    // Plaintext file from an older version: encrypt it now.
+                    match self.save_account(backend_name, resource_url, account, &token) {
+                        Ok(()) => info!(backend = %backend_name, "Encrypted plaintext token file"),
+                        Err(e) => {
+                            warn!(backend = %backend_name, error = %e, "Failed to encrypt plaintext token file");
+                        }
                     }
                 }
-                Err(e) => {
-                    warn!(backend = %backend_name, error = %e, "Failed to parse stored token");
-                    None
+                if token.is_expired() {
+                    debug!(backend = %backend_name, "Stored token is expired");
+                    // Keep the token info in case we can refresh it
+                    Some(token)
+                } else {
+                    info!(backend = %backend_name, expires_in = ?token.time_until_expiry(), "Loaded valid token");
+                    Some(token)
                 }
-            },
+            }
             Err(e) => {
-                warn!(backend = %backend_name, error = %e, "Failed to read token file");
+                warn!(backend = %backend_name, error = %e, "Failed to parse stored token");
                 None
             }
         }
@@ -229,10 +363,26 @@ impl TokenStorage {
     ///
     /// Returns an error if the token cannot be serialized or written to disk.
     pub fn save(&self, backend_name: &str, resource_url: &str, token: &TokenInfo) -> Result<()> {
-        let path = self.token_path(backend_name, resource_url);
+        self.save_account(backend_name, resource_url, None, token)
+    }
 
-        let content = serde_json::to_string_pretty(token)
+    /// Save tokens for one of a backend's accounts (`None` = default account).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token cannot be serialized or written to disk.
+    pub fn save_account(
+        &self,
+        backend_name: &str,
+        resource_url: &str,
+        account: Option<&str>,
+        token: &TokenInfo,
+    ) -> Result<()> {
+        let path = self.token_path(backend_name, resource_url, account);
+
+        let json = serde_json::to_vec(token)
             .map_err(|e| Error::OAuth(format!("Failed to serialize token: {e}")))?;
+        let content = self.vault.seal(&Self::file_name(&path), &json)?;
 
         fs::write(&path, content)
             .map_err(|e| Error::OAuth(format!("Failed to write token file: {e}")))?;
@@ -245,7 +395,7 @@ impl TokenStorage {
             let _ = fs::set_permissions(&path, perms);
         }
 
-        info!(backend = %backend_name, "Saved OAuth token");
+        info!(backend = %backend_name, account, "Saved OAuth token");
         Ok(())
     }
 
@@ -255,7 +405,21 @@ impl TokenStorage {
     ///
     /// Returns an error if the token file exists but cannot be deleted.
     pub fn delete(&self, backend_name: &str, resource_url: &str) -> Result<()> {
-        let path = self.token_path(backend_name, resource_url);
+        self.delete_account(backend_name, resource_url, None)
+    }
+
+    /// Delete tokens for one of a backend's accounts (`None` = default account).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token file exists but cannot be deleted.
+    pub fn delete_account(
+        &self,
+        backend_name: &str,
+        resource_url: &str,
+        account: Option<&str>,
+    ) -> Result<()> {
+        let path = self.token_path(backend_name, resource_url, account);
 
         if path.exists() {
             fs::remove_file(&path)
@@ -288,25 +452,100 @@ impl TokenStorage {
     /// Registration (and its browser authorize tab) on every connection.
     #[must_use]
     pub fn load_client_id(&self, backend_name: &str, resource_url: &str) -> Option<String> {
+        self.load_registered_client(backend_name, resource_url)
+            .map(|client| client.client_id)
+    }
+
+    /// Load the full dynamically-registered client record for a backend,
+    /// including any issued secret. Records that hold only a `client_id`
+    /// load as a public client. A plaintext record written before client
+    /// records were encrypted still loads, and is encrypted in place.
+    #[must_use]
+    pub fn load_registered_client(
+        &self,
+        backend_name: &str,
+        resource_url: &str,
+    ) -> Option<RegisteredClient> {
         let path = self.client_path(backend_name, resource_url);
         if !path.exists() {
             return None;
         }
-        match fs::read_to_string(&path) {
-            Ok(content) => match serde_json::from_str::<String>(&content) {
-                Ok(id) => Some(id),
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!(backend = %backend_name, error = %e, "Failed to read client_id file");
+                return None;
+            }
+        };
+        let sealed = TokenVault::is_sealed(&content);
+        let json = if sealed {
+            match self.vault.open(&Self::file_name(&path), &content) {
+                Ok(json) => json,
                 Err(e) => {
-                    warn!(backend = %backend_name, error = %e, "Failed to parse stored client_id");
-                    None
+                    warn!(backend = %backend_name, error = %e, "Failed to decrypt stored client_id");
+                    return None;
+                }
+            }
+        } else {
+            content.into_bytes()
+        };
+        match serde_json::from_slice::<StoredClient>(&json) {
+            Ok(stored) => {
+                let client = RegisteredClient::from(stored);
+                if !sealed {
+                    // Plaintext file from an older version: encrypt it now.
+                    match self.reseal_client(&path, &client) {
+                        Ok(()) => {
+                            info!(backend = %backend_name, "Encrypted plaintext client_id file");
+                        }
+                        Err(e) => {
+                            warn!(backend = %backend_name, error = %e, "Failed to encrypt plaintext client_id file");
+                        }
+                    }
                 }
-            },
+                Some(client)
+            }
             Err(e) => {
-                warn!(backend = %backend_name, error = %e, "Failed to read client_id file");
+                warn!(backend = %backend_name, error = %e, "Failed to parse stored client_id");
                 None
             }
         }
     }
 
+    /// Encrypt a client record for the file at `path`.
+    fn seal_client(&self, path: &Path, client: &RegisteredClient) -> Result<String> {
+        let json = serde_json::to_vec(client)
+            .map_err(|e| Error::OAuth(format!("Failed to serialize client_id: {e}")))?;
+        self.vault.seal(&Self::file_name(path), &json)
+    }
+
+    /// Replace the plaintext client record at `path` with its sealed form.
+    ///
+    /// The record is already authoritative, so this is a plain atomic
+    /// `rename` over it rather than the first-writer-wins link used by
+    /// [`save_registered_client`](Self::save_registered_client).
+    fn reseal_client(&self, path: &Path, client: &RegisteredClient) -> Result<()> {
+        let content = self.seal_client(path, client)?;
+        let tmp = self.create_client_tmp(&Self::file_name(path))?;
+        let tmp = Self::write_client_tmp(tmp, &content)?;
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            Error::OAuth(format!("Failed to replace client_id file: {e}"))
+        })
+    }
+
+    /// Load the registered client for a backend unless its issued secret has
+    /// expired. An expired record is as unusable as a corrupt one: adopting
+    /// it would undo the re-registration that replaces it.
+    fn load_usable_client(
+        &self,
+        backend_name: &str,
+        resource_url: &str,
+    ) -> Option<RegisteredClient> {
+        self.load_registered_client(backend_name, resource_url)
+            .filter(|client| !client.is_expired())
+    }
+
     /// Persist a dynamically-registered client id for a backend.
     ///
     /// Returns the id that is now authoritative on disk: the one passed in when
@@ -321,7 +560,7 @@ impl TokenStorage {
     /// present, so a concurrent second instance adopts the existing id instead
     /// of clobbering it (last-write-wins would churn the id across instances,
     /// the very bug this persistence exists to prevent). A corrupt/unreadable
-    /// final file is repaired under an exclusive advisory lock (see
+    /// final file, or one holding an expired secret, is repaired under an exclusive advisory lock (see
     /// [`repair_corrupt_final`](Self::repair_corrupt_final)) so two processes
     /// that both observe the corruption can never race to remove each other's
     /// freshly-written valid file.
@@ -340,9 +579,31 @@ impl TokenStorage {
         resource_url: &str,
         client_id: &str,
     ) -> Result<String> {
+        self.save_registered_client(
+            backend_name,
+            resource_url,
+            &RegisteredClient::public(client_id),
+        )
+        .map(|client| client.client_id)
+    }
+
+    /// Persist a dynamically-registered client, including its issued secret.
+    ///
+    /// Same atomic, owner-only, first-writer-wins contract as
+    /// [`save_client_id`](Self::save_client_id): the returned record is the
+    /// one that is authoritative on disk.
+    ///
+    /// # Errors
+    ///
+    /// See [`save_client_id`](Self::save_client_id).
+    pub fn save_registered_client(
+        &self,
+        backend_name: &str,
+        resource_url: &str,
+        client: &RegisteredClient,
+    ) -> Result<RegisteredClient> {
         let path = self.client_path(backend_name, resource_url);
-        let content = serde_json::to_string(client_id)
-            .map_err(|e| Error::OAuth(format!("Failed to serialize client_id: {e}")))?;
+        let content = self.seal_client(&path, client)?;
 
         let file_name = path
             .file_name()
@@ -360,26 +621,22 @@ impl TokenStorage {
         // First-writer-wins: `hard_link` fails with `AlreadyExists` when the
         // final path already exists, so a concurrent instance adopts the
         // existing id instead of clobbering it. A corrupt/unreadable existing
-        // file is unusable and gets repaired under a cross-process lock (see
+        // file, or one whose secret has expired, is unusable and gets repaired under a cross-process lock (see
         // `repair_corrupt_final` for why the repair must be serialized).
         let result = match fs::hard_link(&tmp, &path) {
             Ok(()) => {
                 info!(backend = %backend_name, "Saved registered OAuth client_id");
-                Ok(client_id.to_string())
+                Ok(client.clone())
             }
             Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
-                match self.load_client_id(backend_name, resource_url) {
+                match self.load_usable_client(backend_name, resource_url) {
                     Some(existing) => {
                         info!(backend = %backend_name, "client_id already persisted by another instance; adopting it");
                         Ok(existing)
                     }
-                    None => self.repair_corrupt_final(
-                        backend_name,
-                        resource_url,
-                        &path,
-                        &tmp,
-                        client_id,
-                    ),
+                    None => {
+                        self.repair_corrupt_final(backend_name, resource_url, &path, &tmp, client)
+                    }
                 }
             }
             Err(e) => Err(Error::OAuth(format!("Failed to persist client_id: {e}"))),
@@ -407,8 +664,8 @@ impl TokenStorage {
         resource_url: &str,
         path: &std::path::Path,
         tmp: &std::path::Path,
-        client_id: &str,
-    ) -> Result<String> {
+        client: &RegisteredClient,
+    ) -> Result<RegisteredClient> {
         let lock_path = self.client_lock_path(backend_name, resource_url);
         let _lock = crate::fs_lock::ExclusiveFileLock::acquire(&lock_path)
             .map_err(|e| Error::OAuth(format!("Failed to acquire client_id repair lock: {e}")))?;
@@ -416,25 +673,25 @@ impl TokenStorage {
         // Re-read under the lock: another process may have healed the file
         // between our caller's unlocked read (which found it corrupt) and
         // this call acquiring the lock. Never remove a final that now parses.
-        if let Some(existing) = self.load_client_id(backend_name, resource_url) {
+        if let Some(existing) = self.load_usable_client(backend_name, resource_url) {
             info!(backend = %backend_name, "client_id healed by another instance while waiting for the repair lock; adopting it");
             return Ok(existing);
         }
 
-        warn!(backend = %backend_name, "Existing client_id file is unreadable; removing and re-persisting from validated temp");
+        warn!(backend = %backend_name, "Existing client_id file is unreadable or expired; removing and re-persisting from validated temp");
         let _ = fs::remove_file(path);
 
         match fs::hard_link(tmp, path) {
             Ok(()) => {
                 info!(backend = %backend_name, "Saved registered OAuth client_id (self-healed corrupt final)");
-                Ok(client_id.to_string())
+                Ok(client.clone())
             }
             Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
                 // An unlocked fast-path writer (a fresh, non-repair save)
                 // won the link in the gap between our remove and this retry.
                 // Adopt whatever it left if valid; this is the same
                 // first-writer-wins contract as the normal case above.
-                self.load_client_id(backend_name, resource_url)
+                self.load_usable_client(backend_name, resource_url)
                     .ok_or_else(|| {
                         Error::OAuth(
                             "client_id file exists but is unreadable and could not be self-healed"
@@ -606,6 +863,171 @@ mod tests {
         assert_eq!(store.load_client_id("other", resource), None);
     }
 
+    #[test]
+    fn registered_client_secret_round_trips_and_bare_ids_still_load() {
+        // GIVEN: a confidential registration and a record holding only an id
+        let dir = tempfile::tempdir().unwrap();
+        let store = TokenStorage::new(dir.path().to_path_buf()).unwrap();
+        let resource = "https://mcp.example.test/mcp";
+        let client = RegisteredClient {
+            client_id: "dcr-id".to_string(),
+            client_secret: Some("dcr-secret".to_string()),
+            client_secret_expires_at: Some(0),
+        };
+        fs::write(
+            store.client_path("legacy", resource),
+            serde_json::to_string("legacy-id").unwrap(),
+        )
+        .unwrap();
+
+        // WHEN: persisting and reloading both
+        let persisted = store
+            .save_registered_client("confidential", resource, &client)
+            .unwrap();
+
+        // THEN: the secret survives the round trip and never shows in Debug
+        assert_eq!(persisted, client);
+        let loaded = store
+            .load_registered_client("confidential", resource)
+            .unwrap();
+        assert_eq!(loaded, client);
+        assert!(!loaded.is_expired(), "0 means the secret never expires");
+        assert!(!format!("{loaded:?}").contains("dcr-secret"));
+        // AND: the record is encrypted on disk
+        let on_disk = fs::read_to_string(store.client_path("confidential", resource)).unwrap();
+        assert!(TokenVault::is_sealed(&on_disk), "{on_disk}");
+        assert!(!on_disk.contains("dcr-secret"));
+        // AND: the id-only record loads as a public client
+        assert_eq!(
+            store.load_registered_client("legacy", resource),
+            Some(RegisteredClient::public("legacy-id"))
+        );
+    }
+
+    #[test]
+    fn plaintext_registered_client_loads_and_is_encrypted() {
+        // GIVEN: a full client record written before records were encrypted
+        let dir = tempfile::tempdir().unwrap();
+        let store = TokenStorage::new(dir.path().to_path_buf()).unwrap();
+        let resource = "https://mcp.example.test/mcp";
+        let client = RegisteredClient {
+            client_id: "plain-id".to_string(),
+            client_secret: Some("plain-secret".to_string()),
+            client_secret_expires_at: None,
+        };
+        fs::write(
+            store.client_path("crm", resource),
+            serde_json::to_string(&client).unwrap(),
+        )
+        .unwrap();
+
+        // WHEN: loading it
+        let loaded = store.load_registered_client("crm", resource);
+
+        // THEN: it loads with its secret and is encrypted from now on
+        assert_eq!(loaded, Some(client.clone()));
+        let on_disk = fs::read_to_string(store.client_path("crm", resource)).unwrap();
+        assert!(TokenVault::is_sealed(&on_disk), "{on_disk}");
+        assert_eq!(store.load_registered_client("crm", resource), Some(client));
+    }
+
+    #[test]
+    fn expired_registered_client_is_replaced_on_save() {
+        // GIVEN: a persisted registration whose secret has expired
+        let dir = tempfile::tempdir().unwrap();
+        let store = TokenStorage::new(dir.path().to_path_buf()).unwrap();
+        let resource = "https://mcp.example.test/mcp";
+        let expired = RegisteredClient {
+            client_id: "old-id".to_string(),
+            client_secret: Some("old-secret".to_string()),
+            client_secret_expires_at: Some(1),
+        };
+        store
+            .save_registered_client("crm", resource, &expired)
+            .unwrap();
+
+        // WHEN: the fresh registration is persisted
+        let fresh = RegisteredClient {
+            client_id: "new-id".to_string(),
+            client_secret: Some("new-secret".to_string()),
+            client_secret_expires_at: Some(0),
+        };
+        let persisted = store
+            .save_registered_client("crm", resource, &fresh)
+            .unwrap();
+
+        // THEN: it replaces the expired record instead of adopting it
+        assert_eq!(persisted, fresh);
+        assert_eq!(store.load_registered_client("crm", resource), Some(fresh));
+    }
+
+    #[test]
+    fn named_accounts_store_tokens_separately() {
+        // GIVEN: the default login and two named accounts for one backend
+        let dir = tempfile::tempdir().unwrap();
+        let store = TokenStorage::new(dir.path().to_path_buf()).unwrap();
+        let resource = "https://mcp.example.test/mcp";
+        let token =
+            |value: &str| TokenInfo::from_response(value.to_string(), None, None, None, None);
+
+        // WHEN: saving a token for each
+        store.save("crm", resource, &token("default")).unwrap();
+        store
+            .save_account("crm", resource, Some("alice"), &token("alice"))
+            .unwrap();
+        store
+            .save_account("crm", resource, Some("bob"), &token("bob"))
+            .unwrap();
+
+        // THEN: each account loads its own token
+        let load = |account| {
+            store
+                .load_account("crm", resource, account)
+                .map(|t| t.access_token)
+        };
+        assert_eq!(load(None).as_deref(), Some("default"));
+        assert_eq!(load(Some("alice")).as_deref(), Some("alice"));
+        assert_eq!(load(Some("bob")).as_deref(), Some("bob"));
+
+        // AND: deleting one account leaves the others
+        store
+            .delete_account("crm", resource, Some("alice"))
+            .unwrap();
+        assert_eq!(load(Some("alice")), None);
+        assert_eq!(load(None).as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn tokens_are_encrypted_at_rest_and_plaintext_files_migrate() {
+        // GIVEN: a plaintext token file written by an older version
+        let dir = tempfile::tempdir().unwrap();
+        let store = TokenStorage::new(dir.path().to_path_buf()).unwrap();
+        let resource = "https://mcp.example.test/mcp";
+        let path = store.token_path("crm", resource, None);
+        let legacy = TokenInfo::from_response(
+            "at-legacy".to_string(),
+            None,
+            Some("rt-legacy".to_string()),
+            None,
+            None,
+        );
+        fs::write(&path, serde_json::to_string(&legacy).unwrap()).unwrap();
+
+        // WHEN: loading it
+        let loaded = store.load("crm", resource).unwrap();
+
+        // THEN: the token is returned and the file is rewritten encrypted
+        assert_eq!(loaded.refresh_token.as_deref(), Some("rt-legacy"));
+        let on_disk = fs::read_to_string(&path).unwrap();
+        assert!(TokenVault::is_sealed(&on_disk), "{on_disk}");
+        assert!(!on_disk.contains("rt-legacy"));
+
+        // AND: a file copied over another backend's name does not decrypt
+        let other = store.token_path("billing", resource, None);
+        fs::copy(&path, &other).unwrap();
+        assert!(store.load("billing", resource).is_none());
+    }
+
     #[cfg(unix)]
     #[test]
     fn save_client_id_file_is_owner_only() {
@@ -772,10 +1194,16 @@ mod tests {
 
         // WHEN: we attempt to repair what we believed (from a stale read)
         // was corrupt.
-        let result = store.repair_corrupt_final(backend, resource, &path, &tmp, "our-id");
+        let result = store.repair_corrupt_final(
+            backend,
+            resource,
+            &path,
+            &tmp,
+            &RegisteredClient::public("our-id"),
+        );
 
         // THEN: we adopt the winner instead of overwriting it...
-        assert_eq!(result.unwrap(), "winner-id");
+        assert_eq!(result.unwrap().client_id, "winner-id");
         // ...and the final file was never removed/replaced.
         assert_eq!(
             store.load_client_id(backend, resource),
@@ -0,0 +1,548 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! At-rest encryption of stored OAuth tokens.
+//!
+//! Token files hold live refresh tokens, so [`TokenStorage`](super::TokenStorage)
+//! seals them with ChaCha20-Poly1305 under a 256-bit key. The key comes from,
+//! in order:
+//!
+//! 1. the `MCP_GATEWAY_TOKEN_PASSPHRASE` environment variable, stretched with
+//!    PBKDF2-HMAC-SHA256 and a random per-directory salt;
+//! 2. the OS keychain: macOS Keychain, libsecret (`secret-tool`) on Linux, or
+//!    a DPAPI-protected key file on Windows. The key is generated on first use;
+//! 3. when no keychain is reachable, a `token.key` file (`0600`) next to the
+//!    tokens. This only protects tokens copied without the key file, so a
+//!    warning is logged.
+//!
+//! Each sealed file is bound to its file name, so token files cannot be
+//! swapped between backends or accounts. Plaintext files written by older
+//! versions still load and are sealed on the next save.
+
+use std::fs;
+use std::io::Write as _;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use parking_lot::Mutex;
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{Error, Result};
+
+/// Environment variable holding the token encryption passphrase.
+pub const PASSPHRASE_ENV: &str = "MCP_GATEWAY_TOKEN_PASSPHRASE";
+
+/// Keychain service name of the generated token key.
+const KEYCHAIN_SERVICE: &str = "mcp-gateway-token-key";
+
+/// Key file used when no keychain is reachable.
+const KEY_FILE: &str = "token.key";
+
+/// Salt file for passphrase-derived keys.
+const SALT_FILE: &str = "token.salt";
+
+/// PBKDF2 iterations for passphrase-derived keys (OWASP 2023 guidance).
+const PBKDF2_ITERATIONS: NonZeroU32 = NonZeroU32::new(600_000).unwrap();
+
+/// Key length of ChaCha20-Poly1305.
+const KEY_LEN: usize = 32;
+
+/// Algorithm tag written to sealed files.
+const ALGORITHM: &str = "chacha20-poly1305";
+
+/// Where the token key comes from.
+enum KeySource {
+    /// Derived from an operator passphrase.
+    Passphrase(String),
+    /// Generated and held by the OS keychain.
+    Keychain,
+    /// Generated and kept in [`KEY_FILE`].
+    KeyFile,
+}
+
+impl KeySource {
+    /// Label recorded in sealed files, so a key mismatch is explained.
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Passphrase(_) => "passphrase",
+            Self::Keychain => "keychain",
+            Self::KeyFile => "file",
+        }
+    }
+}
+
+/// On-disk form of a sealed token file.
+#[derive(Serialize, Deserialize)]
+struct SealedFile {
+    /// AEAD algorithm ([`ALGORITHM`]).
+    sealed: String,
+    /// Source label of the key that sealed the file.
+    key: String,
+    /// Base64 nonce.
+    nonce: String,
+    /// Base64 ciphertext including the authentication tag.
+    ciphertext: String,
+}
+
+/// Seals and opens token files under a lazily resolved key.
+pub(crate) struct TokenVault {
+    /// Token storage directory (key and salt files live here).
+    dir: PathBuf,
+    /// Configured key source.
+    source: KeySource,
+    /// Resolved key and the label of the source that produced it. Cached
+    /// after the first success; failures are retried on the next use.
+    key: Mutex<Option<([u8; KEY_LEN], &'static str)>>,
+}
+
+// Manual `Debug` so the passphrase never reaches logs (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for TokenVault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenVault")
+            .field("dir", &self.dir)
+            .field("source", &self.source.label())
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl TokenVault {
+    /// Vault for the default token directory: passphrase from
+    /// [`PASSPHRASE_ENV`] when set, otherwise the OS keychain.
+    pub(crate) fn from_environment(dir: &Path) -> Self {
+        let source = match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) if !passphrase.is_empty() => KeySource::Passphrase(passphrase),
+            _ => KeySource::Keychain,
+        };
+        Self::with_source(dir, source)
+    }
+
+    /// Vault keyed by a file in `dir`, never touching the OS keychain.
+    pub(crate) fn key_file(dir: &Path) -> Self {
+        Self::with_source(dir, KeySource::KeyFile)
+    }
+
+    /// Vault keyed by `passphrase`.
+    #[cfg(test)]
+    fn passphrase(dir: &Path, passphrase: &str) -> Self {
+        Self::with_source(dir, KeySource::Passphrase(passphrase.to_string()))
+    }
+
+    fn with_source(dir: &Path, source: KeySource) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            source,
+            key: Mutex::new(None),
+        }
+    }
+
+    /// Whether `content` is a sealed token file rather than legacy plaintext.
+    pub(crate) fn is_sealed(content: &str) -> bool {
+        serde_json::from_str::<SealedFile>(content).is_ok()
+    }
+
+    /// Encrypt `plaintext` for the file named `file_name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be resolved or encryption fails.
+    pub(crate) fn seal(&self, file_name: &str, plaintext: &[u8]) -> Result<String> {
+        let (key, label) = self.key()?;
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| Error::OAuth("Failed to generate a token nonce".to_string()))?;
+        let mut in_out = plaintext.to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(file_name.as_bytes()),
+            &mut in_out,
+        )
+        .map_err(|_| Error::OAuth("Failed to encrypt token".to_string()))?;
+        serde_json::to_string_pretty(&SealedFile {
+            sealed: ALGORITHM.to_string(),
+            key: label.to_string(),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(in_out),
+        })
+        .map_err(|e| Error::OAuth(format!("Failed to serialize sealed token: {e}")))
+    }
+
+    /// Decrypt a sealed token file named `file_name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is malformed, the key cannot be resolved,
+    /// or the file was sealed under another key or for another file.
+    pub(crate) fn open(&self, file_name: &str, content: &str) -> Result<Vec<u8>> {
+        let sealed: SealedFile = serde_json::from_str(content)
+            .map_err(|e| Error::OAuth(format!("Malformed sealed token file: {e}")))?;
+        if sealed.sealed != ALGORITHM {
+            return Err(Error::OAuth(format!(
+                "Unsupported token encryption '{}'",
+                sealed.sealed
+            )));
+        }
+        let nonce: [u8; NONCE_LEN] = STANDARD
+            .decode(&sealed.nonce)
+            .ok()
+            .and_then(|n| n.try_into().ok())
+            .ok_or_else(|| Error::OAuth("Malformed sealed token nonce".to_string()))?;
+        let mut in_out = STANDARD
+            .decode(&sealed.ciphertext)
+            .map_err(|e| Error::OAuth(format!("Malformed sealed token: {e}")))?;
+        let (key, label) = self.key()?;
+        let plaintext = key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(file_name.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| {
+                Error::OAuth(format!(
+                    "Cannot decrypt token sealed with the {} key (current: {label} key)",
+                    sealed.key
+                ))
+            })?;
+        Ok(plaintext.to_vec())
+    }
+
+    /// Resolve (or reuse) the key.
+    fn key(&self) -> Result<(LessSafeKey, &'static str)> {
+        let mut cached = self.key.lock();
+        let (bytes, label) = if let Some(resolved) = *cached {
+            resolved
+        } else {
+            let resolved = self.resolve()?;
+            *cached = Some(resolved);
+            resolved
+        };
+        let key = UnboundKey::new(&CHACHA20_POLY1305, &bytes)
+            .map_err(|_| Error::OAuth("Invalid token key".to_string()))?;
+        Ok((LessSafeKey::new(key), label))
+    }
+
+    fn resolve(&self) -> Result<([u8; KEY_LEN], &'static str)> {
+        match &self.source {
+            KeySource::Passphrase(passphrase) => {
+                Ok((self.passphrase_key(passphrase)?, self.source.label()))
+            }
+            KeySource::Keychain => match self.keychain_key() {
+                Ok(key) => Ok((key, self.source.label())),
+                Err(e) => {
+                    warn!(
+                        error = %e,
+                        "OS keychain unavailable; token key kept in {KEY_FILE} next to the tokens. \
+                         Set {PASSPHRASE_ENV} to protect tokens in a copied disk image"
+                    );
+                    Ok((self.file_key()?, KeySource::KeyFile.label()))
+                }
+            },
+            KeySource::KeyFile => Ok((self.file_key()?, self.source.label())),
+        }
+    }
+
+    fn passphrase_key(&self, passphrase: &str) -> Result<[u8; KEY_LEN]> {
+        let salt = read_or_create(&self.dir.join(SALT_FILE), || random_bytes(16))?;
+        let mut key = [0u8; KEY_LEN];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            PBKDF2_ITERATIONS,
+            &salt,
+            passphrase.as_bytes(),
+            &mut key,
+        );
+        Ok(key)
+    }
+
+    fn file_key(&self) -> Result<[u8; KEY_LEN]> {
+        let key = read_or_create(&self.dir.join(KEY_FILE), || random_bytes(KEY_LEN))?;
+        key.try_into()
+            .map_err(|_| Error::OAuth(format!("{KEY_FILE} does not hold a {KEY_LEN}-byte key")))
+    }
+
+    fn keychain_key(&self) -> Result<[u8; KEY_LEN]> {
+        if let Some(encoded) = keychain_read(&self.dir) {
+            return decode_key(&encoded);
+        }
+        let stored = keychain_store(&self.dir, &STANDARD.encode(random_bytes(KEY_LEN)?));
+        // Read back rather than trusting the generated key: another gateway
+        // process may have stored its key first.
+        match keychain_read(&self.dir) {
+            Some(encoded) => {
+                info!(
+                    service = KEYCHAIN_SERVICE,
+                    "Stored token encryption key in the OS keychain"
+                );
+                decode_key(&encoded)
+            }
+            None => Err(stored.err().unwrap_or_else(|| {
+                Error::OAuth("Token key not readable after storing it".to_string())
+            })),
+        }
+    }
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; KEY_LEN]> {
+    STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|k| k.try_into().ok())
+        .ok_or_else(|| Error::OAuth("Keychain token key is malformed".to_string()))
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>> {
+    let mut bytes = vec![0u8; len];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| Error::OAuth("Failed to generate token key material".to_string()))?;
+    Ok(bytes)
+}
+
+/// Read the base64 content of `path`, creating it (`0600` on unix) with
+/// `generate()` first if missing. Concurrent creators converge on the first
+/// writer's content.
+fn read_or_create(path: &Path, generate: impl FnOnce() -> Result<Vec<u8>>) -> Result<Vec<u8>> {
+    let read = |path: &Path| -> Result<Vec<u8>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| Error::OAuth(format!("Failed to read {}: {e}", path.display())))?;
+        match STANDARD.decode(content.trim()) {
+            Ok(bytes) if !bytes.is_empty() => Ok(bytes),
+            _ => Err(Error::OAuth(format!("Malformed {}", path.display()))),
+        }
+    };
+    if path.exists() {
+        return read(path);
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        options.mode(0o600);
+    }
+    match options.open(path) {
+        Ok(mut file) => {
+            let bytes = generate()?;
+            file.write_all(STANDARD.encode(&bytes).as_bytes())
+                .map_err(|e| Error::OAuth(format!("Failed to write {}: {e}", path.display())))?;
+            Ok(bytes)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => read(path),
+        Err(e) => Err(Error::OAuth(format!(
+            "Failed to create {}: {e}",
+            path.display()
+        ))),
+    }
+}
+
+/// Run `command`, feeding `stdin`, and return its trimmed stdout.
+fn run(command: &mut Command, stdin: &str) -> Result<String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::OAuth(format!("Failed to run keychain tool: {e}")))?;
+    if let Some(mut pipe) = child.stdin.take() {
+        pipe.write_all(stdin.as_bytes())
+            .map_err(|e| Error::OAuth(format!("Failed to write to keychain tool: {e}")))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| Error::OAuth(format!("Keychain tool failed: {e}")))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(Error::OAuth(format!(
+            "Keychain tool failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Read the token key from the macOS Keychain.
+#[cfg(target_os = "macos")]
+fn keychain_read(_dir: &Path) -> Option<String> {
+    run(
+        Command::new("security").args([
+            "find-generic-password",
+            "-s",
+            KEYCHAIN_SERVICE,
+            "-a",
+            "mcp-gateway",
+            "-w",
+        ]),
+        "",
+    )
+    .ok()
+    .filter(|key| !key.is_empty())
+}
+
+/// Store the token key in the macOS Keychain. The command goes through
+/// `security -i` on stdin so the key never appears in a process listing.
+#[cfg(target_os = "macos")]
+fn keychain_store(_dir: &Path, encoded: &str) -> Result<()> {
+    run(
+        Command::new("security").arg("-i"),
+        &format!(
+            "add-generic-password -s {KEYCHAIN_SERVICE} -a mcp-gateway \
+             -l \"MCP Gateway token key\" -w {encoded}\n"
+        ),
+    )
+    .map(drop)
+}
+
+/// Read the token key from the Linux secret service.
+#[cfg(target_os = "linux")]
+fn keychain_read(_dir: &Path) -> Option<String> {
+    run(
+        Command::new("secret-tool").args(["lookup", "service", KEYCHAIN_SERVICE]),
+        "",
+    )
+    .ok()
+    .filter(|key| !key.is_empty())
+}
+
+/// Store the token key in the Linux secret service (read from stdin).
+#[cfg(target_os = "linux")]
+fn keychain_store(_dir: &Path, encoded: &str) -> Result<()> {
+    run(
+        Command::new("secret-tool").args([
+            "store",
+            "--label=MCP Gateway token key",
+            "service",
+            KEYCHAIN_SERVICE,
+        ]),
+        encoded,
+    )
+    .map(drop)
+}
+
+/// DPAPI-protected key file on Windows.
+#[cfg(windows)]
+const DPAPI_KEY_FILE: &str = "token.key.dpapi";
+
+/// Protect or unprotect base64 data with DPAPI for the current user.
+#[cfg(windows)]
+fn dpapi(operation: &str, data: &str) -> Result<String> {
+    run(
+        Command::new("powershell").args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &format!(
+                "Add-Type -AssemblyName System.Security; \
+                 $d = [Convert]::FromBase64String([Console]::In.ReadToEnd().Trim()); \
+                 [Convert]::ToBase64String([Security.Cryptography.ProtectedData]::{operation}($d, $null, 'CurrentUser'))"
+            ),
+        ]),
+        data,
+    )
+}
+
+/// Read the token key from its DPAPI-protected file.
+#[cfg(windows)]
+fn keychain_read(dir: &Path) -> Option<String> {
+    let protected = fs::read_to_string(dir.join(DPAPI_KEY_FILE)).ok()?;
+    dpapi("Unprotect", protected.trim()).ok()
+}
+
+/// Protect the token key with DPAPI and write it unless one already exists.
+#[cfg(windows)]
+fn keychain_store(dir: &Path, encoded: &str) -> Result<()> {
+    let protected = dpapi("Protect", encoded)?;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dir.join(DPAPI_KEY_FILE))
+        .and_then(|mut file| file.write_all(protected.as_bytes()))
+        .map_err(|e| Error::OAuth(format!("Failed to write {DPAPI_KEY_FILE}: {e}")))
+}
+
+/// No keychain on this platform; the key file fallback is used.
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn keychain_read(_dir: &Path) -> Option<String> {
+    None
+}
+
+/// No keychain on this platform; the key file fallback is used.
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn keychain_store(_dir: &Path, _encoded: &str) -> Result<()> {
+    Err(Error::OAuth("No OS keychain on this platform".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_tokens_open_only_under_their_key_and_name() {
+        // GIVEN: a file-keyed vault
+        let dir = tempfile::tempdir().unwrap();
+        let vault = TokenVault::key_file(dir.path());
+
+        // WHEN: sealing a token
+        let sealed = vault
+            .seal("a_tokens.json", br#"{"refresh_token":"rt-secret"}"#)
+            .unwrap();
+
+        // THEN: the secret is not on disk in the clear, and it round-trips
+        assert!(!sealed.contains("rt-secret"));
+        assert!(TokenVault::is_sealed(&sealed));
+        assert!(!TokenVault::is_sealed(r#"{"access_token":"at"}"#));
+        assert_eq!(
+            vault.open("a_tokens.json", &sealed).unwrap(),
+            br#"{"refresh_token":"rt-secret"}"#
+        );
+
+        // AND: a file moved to another name, or another key, does not open
+        assert!(vault.open("b_tokens.json", &sealed).is_err());
+        let other = tempfile::tempdir().unwrap();
+        assert!(
+            TokenVault::key_file(other.path())
+                .open("a_tokens.json", &sealed)
+                .is_err()
+        );
+
+        // AND: a second vault over the same directory reuses the key
+        assert!(
+            TokenVault::key_file(dir.path())
+                .open("a_tokens.json", &sealed)
+                .is_ok()
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join(KEY_FILE))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn passphrase_key_needs_the_same_passphrase() {
+        // GIVEN: a token sealed under a passphrase
+        let dir = tempfile::tempdir().unwrap();
+        let sealed = TokenVault::passphrase(dir.path(), "correct horse")
+            .seal("t_tokens.json", b"token")
+            .unwrap();
+
+        // THEN: no key file is written, and only the same passphrase opens it
+        assert!(!dir.path().join(KEY_FILE).exists());
+        let reopened = TokenVault::passphrase(dir.path(), "correct horse");
+        assert_eq!(reopened.open("t_tokens.json", &sealed).unwrap(), b"token");
+        let wrong = TokenVault::passphrase(dir.path(), "battery staple")
+            .open("t_tokens.json", &sealed)
+            .unwrap_err();
+        assert!(wrong.to_string().contains("passphrase key"), "{wrong}");
+    }
+}
@@ -18,8 +18,9 @@
 //! ```
 
 use std::collections::HashMap;
+use std::time::Duration;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
 use crate::transform::{parse_json_path, resolve_path_single};
@@ -63,6 +64,16 @@ pub struct PlaybookDefinition {
     /// Total timeout in seconds.
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+
+    /// Maximum calls in flight to any one server across `parallel` groups
+    /// and `for_each` steps (default 4).
+    #[serde(default)]
+    pub server_concurrency: Option<usize>,
+
+    /// Steps that only run as the `on_error: {fallback: name}` replacement
+    /// of a failed step.
+    #[serde(default)]
+    pub fallbacks: Vec<PlaybookStep>,
 }
 
 fn default_playbook_version() -> String {
@@ -87,7 +98,8 @@ pub struct PlaybookStep {
     /// Step name (used as variable prefix: `$name.path`).
     pub name: String,
 
-    /// Tool to invoke.
+    /// Tool to invoke (empty for `parallel` groups).
+    #[serde(default)]
     pub tool: String,
 
     /// Server/backend that hosts the tool.
@@ -99,14 +111,248 @@ pub struct PlaybookStep {
     pub arguments: HashMap<String, Value>,
 
     /// Condition expression (skip step if evaluates to false).
+    ///
+    /// With `for_each`, evaluated once per item; items that fail it are
+    /// skipped.
     #[serde(default)]
     pub condition: Option<String>,
+
+    /// Variable reference to an array (`$checks.failing`). The tool is
+    /// called once per element, with `$item` and `$index` bound to the
+    /// element and its position, and the step result is the list of call
+    /// results in item order.
+    #[serde(default)]
+    pub for_each: Option<String>,
+
+    /// Maximum `for_each` calls in flight (default 4).
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+
+    /// Steps run concurrently as one group. The next step starts once all
+    /// of them finished; each child's result is stored under its own name
+    /// and the group's result is an object keyed by child name.
+    #[serde(default)]
+    pub parallel: Vec<PlaybookStep>,
+
+    /// Retry policy; overrides the playbook-level `max_retries`.
+    #[serde(default)]
+    pub retry: Option<StepRetry>,
+
+    /// What to do when the step still fails after retries; overrides the
+    /// playbook-level `on_error`.
+    #[serde(default)]
+    pub on_error: Option<StepErrorPolicy>,
+}
+
+/// Per-step retry policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepRetry {
+    /// Total attempts, including the first.
+    #[serde(default = "default_retry_attempts")]
+    pub attempts: u32,
+
+    /// Delay before the second attempt, doubled before each further one.
+    #[serde(default, with = "crate::config::humantime_serde")]
+    pub backoff: Duration,
+
+    /// Only retry errors whose message contains one of these
+    /// (case-insensitive); empty retries every error.
+    #[serde(default)]
+    pub retry_on: Vec<String>,
+}
+
+const fn default_retry_attempts() -> u32 {
+    3
+}
+
+impl StepRetry {
+    /// Whether `error` is worth another attempt.
+    #[must_use]
+    pub fn retries(&self, error: &crate::Error) -> bool {
+        if self.retry_on.is_empty() {
+            return true;
+        }
+        let message = error.to_string().to_lowercase();
+        self.retry_on
+            .iter()
+            .any(|pattern| message.contains(&pattern.to_lowercase()))
+    }
+
+    /// Delay before attempt `attempt` (0-based; the first attempt has none).
+    #[must_use]
+    pub fn delay_before(&self, attempt: u32) -> Duration {
+        if attempt == 0 {
+            return Duration::ZERO;
+        }
+        self.backoff
+            .saturating_mul(2_u32.saturating_pow(attempt - 1))
+    }
+}
+
+/// Per-step error handling.
+///
+/// ```yaml
+/// on_error: continue            # or: abort
+/// on_error: {fallback: notify}  # run the `notify` entry of `fallbacks`
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepErrorPolicy {
+    /// Stop the playbook.
+    Abort,
+    /// Record the step as failed (result `null`) and go on.
+    Continue,
+    /// Run the named fallback step; its result stands in for this step's.
+    Fallback(String),
+}
+
+// `serde_yaml` reads newtype variants only from YAML tags (`!fallback x`),
+// so the `{fallback: x}` map form is parsed by hand.
+impl<'de> Deserialize<'de> for StepErrorPolicy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Name(String),
+            Fallback { fallback: String },
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Name(name) => match name.as_str() {
+                "abort" => Ok(Self::Abort),
+                "continue" => Ok(Self::Continue),
+                other => Err(serde::de::Error::unknown_variant(
+                    other,
+                    &["abort", "continue", "fallback"],
+                )),
+            },
+            Repr::Fallback { fallback } => Ok(Self::Fallback(fallback)),
+        }
+    }
 }
 
 fn default_server() -> String {
     "capabilities".to_string()
 }
 
+/// `for_each` calls in flight when `concurrency` is not set.
+pub(crate) const DEFAULT_FOR_EACH_CONCURRENCY: usize = 4;
+
+/// Calls in flight per server when `server_concurrency` is not set.
+pub(crate) const DEFAULT_SERVER_CONCURRENCY: usize = 4;
+
+impl PlaybookDefinition {
+    /// The `fallbacks` entry called `name`.
+    #[must_use]
+    pub fn fallback(&self, name: &str) -> Option<&PlaybookStep> {
+        self.fallbacks.iter().find(|step| step.name == name)
+    }
+
+    /// Effective per-server concurrency cap (at least 1).
+    #[must_use]
+    pub fn server_concurrency(&self) -> usize {
+        self.server_concurrency
+            .unwrap_or(DEFAULT_SERVER_CONCURRENCY)
+            .max(1)
+    }
+
+    /// Check the definition for mistakes that would only surface mid-run:
+    /// duplicate step names, steps without a tool, nested `parallel` groups,
+    /// groups that also use `for_each`, and unknown fallbacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::Config`] describing the first problem found.
+    pub fn validate(&self) -> crate::Result<()> {
+        let invalid = |msg: String| {
+            Err(crate::Error::Config(format!(
+                "Playbook '{}': {msg}",
+                self.name
+            )))
+        };
+        let mut names = std::collections::HashSet::new();
+        let children = self.steps.iter().flat_map(|step| &step.parallel);
+        for step in self.steps.iter().chain(children) {
+            if !names.insert(step.name.as_str()) {
+                return invalid(format!("duplicate step name '{}'", step.name));
+            }
+        }
+        let mut fallback_names = std::collections::HashSet::new();
+        for step in &self.fallbacks {
+            if !fallback_names.insert(step.name.as_str()) {
+                return invalid(format!("duplicate fallback name '{}'", step.name));
+            }
+        }
+
+        let grouped = self.steps.iter().flat_map(|step| &step.parallel);
+        for step in self.steps.iter().chain(grouped).chain(&self.fallbacks) {
+            let is_group = !step.parallel.is_empty();
+            if is_group && step.for_each.is_some() {
+                return invalid(format!(
+                    "step '{}' cannot combine parallel and for_each",
+                    step.name
+                ));
+            }
+            if is_group && step.parallel.iter().any(|c| !c.parallel.is_empty()) {
+                return invalid(format!(
+                    "step '{}': nested parallel groups are not supported",
+                    step.name
+                ));
+            }
+            if !is_group && step.tool.is_empty() {
+                return invalid(format!("step '{}' has no tool", step.name));
+            }
+            if let Some(StepErrorPolicy::Fallback(name)) = &step.on_error
+                && self.fallback(name).is_none()
+            {
+                return invalid(format!(
+                    "step '{}' names unknown fallback '{name}'",
+                    step.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PlaybookStep {
+    /// Effective `for_each` concurrency cap (at least 1).
+    #[must_use]
+    pub fn for_each_concurrency(&self) -> usize {
+        self.concurrency
+            .unwrap_or(DEFAULT_FOR_EACH_CONCURRENCY)
+            .max(1)
+    }
+
+    /// Effective retry policy: the step's own, else `max_retries` attempts
+    /// without backoff under the playbook-level `Retry` strategy.
+    #[must_use]
+    pub fn retry_policy(&self, definition: &PlaybookDefinition) -> StepRetry {
+        self.retry.clone().unwrap_or_else(|| StepRetry {
+            attempts: if definition.on_error == ErrorStrategy::Retry {
+                definition.max_retries
+            } else {
+                1
+            },
+            backoff: Duration::ZERO,
+            retry_on: Vec::new(),
+        })
+    }
+
+    /// Effective error policy: the step's own, else derived from the
+    /// playbook-level strategy.
+    #[must_use]
+    pub fn error_policy(&self, definition: &PlaybookDefinition) -> StepErrorPolicy {
+        self.on_error.clone().unwrap_or(match definition.on_error {
+            ErrorStrategy::Abort => StepErrorPolicy::Abort,
+            ErrorStrategy::Continue | ErrorStrategy::Retry => StepErrorPolicy::Continue,
+        })
+    }
+}
+
 /// Output mapping definition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaybookOutput {
@@ -152,6 +398,8 @@ pub(crate) struct PlaybookContext {
     pub(crate) inputs: Value,
     /// Results from completed steps, keyed by step name.
     pub(crate) step_results: HashMap<String, Value>,
+    /// Current `for_each` element and its index (`$item`, `$index`).
+    pub(crate) item: Option<(Value, usize)>,
 }
 
 impl PlaybookContext {
@@ -159,21 +407,25 @@ impl PlaybookContext {
         Self {
             inputs,
             step_results: HashMap::new(),
+            item: None,
         }
     }
 
     /// Resolve a variable reference like `$inputs.query` or `$search.web.results[0].title`.
+    ///
+    /// Inside a `for_each` step, `$item` and `$index` refer to the current element.
     pub(crate) fn resolve_var(&self, var_ref: &str) -> Value {
         let trimmed = var_ref.trim_start_matches('$');
         let (step_name, remainder) = trimmed.split_once('.').unwrap_or((trimmed, ""));
 
-        let source = if step_name == "inputs" {
-            &self.inputs
-        } else {
-            match self.step_results.get(step_name) {
+        let source = match (step_name, &self.item) {
+            ("inputs", _) => &self.inputs,
+            ("item", Some((item, _))) => item,
+            ("index", Some((_, index))) => return Value::from(*index),
+            _ => match self.step_results.get(step_name) {
                 Some(v) => v,
                 None => return Value::Null,
-            }
+            },
         };
 
         if remainder.is_empty() {
@@ -338,7 +590,8 @@ pub(crate) fn is_truthy(value: &Value) -> bool {
 // ============================================================================
 
 /// Result of a playbook execution.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PlaybookResult {
     /// Final output (after output mapping).
     pub output: Value,
@@ -348,6 +601,10 @@ pub struct PlaybookResult {
     pub steps_skipped: Vec<String>,
     /// Steps that failed.
     pub steps_failed: Vec<String>,
+    /// Steps that needed more than one attempt.
+    pub steps_retried: Vec<String>,
+    /// Steps that failed and were replaced by their fallback step.
+    pub steps_fallback: Vec<String>,
     /// Total execution time in milliseconds.
     pub duration_ms: u64,
 }
@@ -364,4 +621,8 @@ pub trait ToolInvoker: Send + Sync {
 }
 
 pub mod engine;
-pub use engine::PlaybookEngine;
+pub mod runs;
+pub mod watcher;
+pub use engine::{PlannedCall, PlaybookEngine, PlaybookLoadReport, PlaybookPlan};
+pub use runs::{PlaybookRun, PlaybookRunStore, RunStatus};
+pub use watcher::PlaybookWatcher;
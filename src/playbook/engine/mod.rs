@@ -6,15 +6,31 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::time::Instant;
 
+use serde::Serialize;
 use serde_json::Value;
 use tracing::{debug, warn};
 
+#[cfg(test)]
+use super::{ErrorStrategy, OutputMapping, PlaybookOutput, StepRetry, extract_var_refs, is_truthy};
 use super::{
-    ErrorStrategy, PlaybookContext, PlaybookDefinition, PlaybookResult, ToolInvoker,
-    evaluate_condition,
+    PlaybookContext, PlaybookDefinition, PlaybookResult, PlaybookRun, PlaybookStep, RunStatus,
+    StepErrorPolicy, ToolInvoker, evaluate_condition,
 };
-#[cfg(test)]
-use super::{OutputMapping, PlaybookOutput, PlaybookStep, extract_var_refs, is_truthy};
+
+mod plan;
+mod run;
+
+pub use plan::{PlannedCall, PlaybookPlan};
+use run::{StepOutcome, StepRunner};
+
+/// Outcome of loading playbook directories, returned by reloads.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PlaybookLoadReport {
+    /// Names of the playbooks loaded.
+    pub loaded: Vec<String>,
+    /// One message per file or directory that could not be loaded.
+    pub errors: Vec<String>,
+}
 
 /// Engine that loads and executes playbooks.
 pub struct PlaybookEngine {
@@ -30,18 +46,47 @@ impl PlaybookEngine {
         }
     }
 
-    /// Load playbooks from a directory (reads all `*.yaml` files).
+    /// Load playbooks from a directory (reads all `*.yaml` / `*.yml` files).
+    ///
+    /// Files that fail to parse or validate are skipped with a warning.
     ///
     /// # Errors
     ///
     /// Returns an error if the directory cannot be read.
     pub fn load_from_directory(&mut self, dir: &str) -> crate::Result<usize> {
+        let mut report = PlaybookLoadReport::default();
+        self.load_directory_into(dir, &mut report)?;
+        Ok(report.loaded.len())
+    }
+
+    /// Build an engine from every playbook file in `directories`.
+    ///
+    /// Unreadable directories, parse errors and validation errors are
+    /// collected in the report instead of failing the load, so one broken
+    /// file never unloads the others. Missing directories are not an error.
+    #[must_use]
+    pub fn load_directories(directories: &[String]) -> (Self, PlaybookLoadReport) {
+        let mut engine = Self::new();
+        let mut report = PlaybookLoadReport::default();
+        for dir in directories {
+            if let Err(e) = engine.load_directory_into(dir, &mut report) {
+                report.errors.push(e.to_string());
+            }
+        }
+        report.loaded.sort();
+        (engine, report)
+    }
+
+    fn load_directory_into(
+        &mut self,
+        dir: &str,
+        report: &mut PlaybookLoadReport,
+    ) -> crate::Result<()> {
         let path = Path::new(dir);
         if !path.is_dir() {
-            return Ok(0);
+            return Ok(());
         }
 
-        let mut count = 0;
         for entry in std::fs::read_dir(path).map_err(|e| {
             crate::Error::Config(format!("Failed to read playbooks directory '{dir}': {e}"))
         })? {
@@ -50,26 +95,33 @@ impl PlaybookEngine {
             })?;
 
             let file_path = entry.path();
-            if file_path.extension().and_then(|e| e.to_str()) == Some("yaml") {
-                match std::fs::read_to_string(&file_path) {
-                    Ok(content) => match serde_yaml::from_str::<PlaybookDefinition>(&content) {
-                        Ok(def) => {
-                            debug!(name = %def.name, path = %file_path.display(), "Loaded playbook");
-                            self.definitions.insert(def.name.clone(), def);
-                            count += 1;
-                        }
-                        Err(e) => {
-                            warn!(path = %file_path.display(), error = %e, "Failed to parse playbook");
-                        }
-                    },
-                    Err(e) => {
-                        warn!(path = %file_path.display(), error = %e, "Failed to read playbook file");
-                    }
+            if !file_path
+                .extension()
+                .is_some_and(|e| e == "yaml" || e == "yml")
+            {
+                continue;
+            }
+            let parsed = std::fs::read_to_string(&file_path)
+                .map_err(|e| format!("read failed: {e}"))
+                .and_then(|content| {
+                    serde_yaml::from_str::<PlaybookDefinition>(&content)
+                        .map_err(|e| format!("parse failed: {e}"))
+                })
+                .and_then(|def| def.validate().map(|()| def).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(def) => {
+                    debug!(name = %def.name, path = %file_path.display(), "Loaded playbook");
+                    report.loaded.push(def.name.clone());
+                    self.definitions.insert(def.name.clone(), def);
+                }
+                Err(e) => {
+                    warn!(path = %file_path.display(), error = %e, "Skipping playbook");
+                    report.errors.push(format!("{}: {e}", file_path.display()));
                 }
             }
         }
 
-        Ok(count)
+        Ok(())
     }
 
     /// Register a playbook definition directly.
@@ -119,6 +171,50 @@ impl PlaybookEngine {
         self.execute_definition(definition, inputs, invoker).await
     }
 
+    /// Dry-run a playbook by name: the tool calls it would make for
+    /// `inputs`, without invoking anything.
+    ///
+    /// `sample_results` stand in for the results of earlier steps, keyed by
+    /// step name, so conditions and arguments that reference them can be
+    /// checked too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the playbook is not found.
+    pub fn plan(
+        &self,
+        name: &str,
+        inputs: Value,
+        sample_results: HashMap<String, Value>,
+    ) -> crate::Result<PlaybookPlan> {
+        let definition = self
+            .get(name)
+            .ok_or_else(|| crate::Error::Config(format!("Playbook not found: {name}")))?;
+        Ok(plan::plan_playbook(definition, inputs, sample_results))
+    }
+
+    /// Execute or resume a checkpointed run of a registered playbook.
+    ///
+    /// Starts at `run.next_step` with the stored step results, and calls
+    /// `checkpoint` after every top-level step settles and when the run
+    /// aborts or completes. An aborted run keeps the state of its last
+    /// settled step, so resuming it re-runs the step that failed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::execute`].
+    pub async fn execute_run(
+        &self,
+        run: &mut PlaybookRun,
+        invoker: &dyn ToolInvoker,
+        checkpoint: &(dyn Fn(&PlaybookRun) + Sync),
+    ) -> crate::Result<PlaybookResult> {
+        let definition = self
+            .get(&run.playbook)
+            .ok_or_else(|| crate::Error::Config(format!("Playbook not found: {}", run.playbook)))?;
+        run_steps(definition, run, invoker, checkpoint).await
+    }
+
     /// Execute a playbook from its definition.
     async fn execute_definition(
         &self,
@@ -126,103 +222,161 @@ impl PlaybookEngine {
         inputs: Value,
         invoker: &dyn ToolInvoker,
     ) -> crate::Result<PlaybookResult> {
-        let start = Instant::now();
-        let timeout = std::time::Duration::from_secs(definition.timeout);
-        let mut ctx = PlaybookContext::new(inputs);
-
-        let mut steps_completed = Vec::new();
-        let mut steps_skipped = Vec::new();
-        let mut steps_failed = Vec::new();
-
-        for step in &definition.steps {
-            // Check timeout
-            if start.elapsed() > timeout {
-                return Err(crate::Error::Internal(format!(
-                    "Playbook '{}' exceeded timeout of {}s",
-                    definition.name, definition.timeout
-                )));
-            }
+        let mut run = PlaybookRun::new(&definition.name, inputs);
+        run_steps(definition, &mut run, invoker, &|_| {}).await
+    }
+}
 
-            // Evaluate condition
-            if let Some(ref condition) = step.condition
-                && !evaluate_condition(condition, &ctx)
-            {
-                debug!(step = %step.name, "Step skipped (condition false)");
-                steps_skipped.push(step.name.clone());
-                continue;
-            }
+/// Run the top-level steps of `definition` from `run.next_step` on.
+async fn run_steps(
+    definition: &PlaybookDefinition,
+    run: &mut PlaybookRun,
+    invoker: &dyn ToolInvoker,
+    checkpoint: &(dyn Fn(&PlaybookRun) + Sync),
+) -> crate::Result<PlaybookResult> {
+    let start = Instant::now();
+    let timeout = std::time::Duration::from_secs(definition.timeout);
+    let mut ctx = PlaybookContext::new(run.inputs.clone());
+    ctx.step_results.clone_from(&run.step_results);
+    let runner = StepRunner::new(definition, invoker);
+
+    let mut record = run.progress.clone();
+    run.status = RunStatus::Running;
+    run.error = None;
+
+    for (index, step) in definition.steps.iter().enumerate().skip(run.next_step) {
+        // Check timeout
+        let abort_error = if start.elapsed() > timeout {
+            Some(crate::Error::Internal(format!(
+                "Playbook '{}' exceeded timeout of {}s",
+                definition.name, definition.timeout
+            )))
+        } else {
+            run_step(definition, &runner, step, &mut ctx, &mut record).await
+        };
+
+        run.updated_at = chrono::Utc::now().to_rfc3339();
+        if let Some(e) = abort_error {
+            run.status = RunStatus::Failed;
+            run.error = Some(e.to_string());
+            checkpoint(run);
+            return Err(e);
+        }
+        run.next_step = index + 1;
+        run.step_results.clone_from(&ctx.step_results);
+        run.progress.clone_from(&record);
+        checkpoint(run);
+    }
 
-            // Interpolate arguments
-            let arguments = ctx.interpolate(&Value::Object(
-                step.arguments
-                    .iter()
-                    .map(|(k, v)| (k.clone(), v.clone()))
-                    .collect(),
-            ));
-
-            // Execute with retry
-            let mut last_error = None;
-            let max_attempts = if definition.on_error == ErrorStrategy::Retry {
-                definition.max_retries.max(1)
-            } else {
-                1
-            };
+    // Build output
+    record.output = build_output(definition, &ctx);
+    #[allow(clippy::cast_possible_truncation)]
+    let duration_ms = start.elapsed().as_millis() as u64;
+    record.duration_ms += duration_ms;
 
-            let mut succeeded = false;
-            for attempt in 0..max_attempts {
-                if attempt > 0 {
-                    debug!(step = %step.name, attempt, "Retrying step");
-                }
+    run.status = RunStatus::Completed;
+    run.progress.clone_from(&record);
+    run.updated_at = chrono::Utc::now().to_rfc3339();
+    checkpoint(run);
 
-                match invoker
-                    .invoke(&step.server, &step.tool, arguments.clone())
-                    .await
-                {
-                    Ok(result) => {
-                        debug!(step = %step.name, "Step completed");
-                        ctx.step_results.insert(step.name.clone(), result);
-                        steps_completed.push(step.name.clone());
-                        succeeded = true;
-                        break;
-                    }
-                    Err(e) => {
-                        warn!(step = %step.name, error = %e, "Step failed");
-                        last_error = Some(e);
-                    }
-                }
-            }
+    Ok(record)
+}
 
-            if !succeeded {
-                steps_failed.push(step.name.clone());
-                match definition.on_error {
-                    ErrorStrategy::Abort => {
-                        return Err(last_error.unwrap_or_else(|| {
-                            crate::Error::Internal(format!(
-                                "Step '{}' failed in playbook '{}'",
-                                step.name, definition.name
-                            ))
-                        }));
-                    }
-                    ErrorStrategy::Continue | ErrorStrategy::Retry => {
-                        // Already retried if Retry; continue to next step.
-                        ctx.step_results.insert(step.name.clone(), Value::Null);
-                    }
-                }
-            }
+/// Run one top-level step (or `parallel` group) and settle its outcomes.
+///
+/// Returns the error when the playbook must abort.
+async fn run_step(
+    definition: &PlaybookDefinition,
+    runner: &StepRunner<'_>,
+    step: &PlaybookStep,
+    ctx: &mut PlaybookContext,
+    record: &mut PlaybookResult,
+) -> Option<crate::Error> {
+    // Evaluate condition (per item for `for_each` steps)
+    if step.for_each.is_none()
+        && let Some(ref condition) = step.condition
+        && !evaluate_condition(condition, ctx)
+    {
+        debug!(step = %step.name, "Step skipped (condition false)");
+        record.steps_skipped.push(step.name.clone());
+        return None;
+    }
+
+    let outcomes = if step.parallel.is_empty() {
+        vec![(step, Some(runner.run(step, ctx).await))]
+    } else {
+        let outcomes = runner.run_parallel(step, ctx).await;
+        let joined = outcomes
+            .iter()
+            .filter_map(|(child, outcome)| {
+                let outcome = outcome.as_ref()?;
+                Some((child.name.clone(), outcome.result.clone()))
+            })
+            .collect();
+        ctx.step_results
+            .insert(step.name.clone(), Value::Object(joined));
+        outcomes
+    };
+
+    let mut abort_error = None;
+    for (step, outcome) in outcomes {
+        let Some(outcome) = outcome else {
+            debug!(step = %step.name, "Step skipped (condition false)");
+            record.steps_skipped.push(step.name.clone());
+            continue;
+        };
+        if let Some(e) = settle_step(definition, runner, step, outcome, ctx, record).await {
+            abort_error.get_or_insert(e);
         }
+    }
+    abort_error
+}
+
+/// Store a finished step's result and record how it went, running its
+/// fallback if it failed under `on_error: {fallback: …}`.
+///
+/// Returns the error when the playbook must abort.
+async fn settle_step(
+    definition: &PlaybookDefinition,
+    runner: &StepRunner<'_>,
+    step: &PlaybookStep,
+    mut outcome: StepOutcome,
+    ctx: &mut PlaybookContext,
+    record: &mut PlaybookResult,
+) -> Option<crate::Error> {
+    if outcome.retried {
+        record.steps_retried.push(step.name.clone());
+    }
+    let policy = step.error_policy(definition);
+    let mut used_fallback = false;
+    if let (Some(e), StepErrorPolicy::Fallback(name)) = (&outcome.error, &policy) {
+        warn!(step = %step.name, fallback = %name, error = %e, "Step failed; running fallback");
+        outcome = match definition.fallback(name) {
+            Some(fallback) => runner.run(fallback, ctx).await,
+            None => StepOutcome::failed(crate::Error::Internal(format!(
+                "Step '{}' names unknown fallback '{name}'",
+                step.name
+            ))),
+        };
+        used_fallback = true;
+    }
+    ctx.step_results.insert(step.name.clone(), outcome.result);
 
-        // Build output
-        let output = build_output(definition, &ctx);
-        #[allow(clippy::cast_possible_truncation)]
-        let duration_ms = start.elapsed().as_millis() as u64;
-
-        Ok(PlaybookResult {
-            output,
-            steps_completed,
-            steps_skipped,
-            steps_failed,
-            duration_ms,
-        })
+    match outcome.error {
+        None if used_fallback => {
+            debug!(step = %step.name, "Step replaced by fallback");
+            record.steps_fallback.push(step.name.clone());
+            None
+        }
+        None => {
+            debug!(step = %step.name, "Step completed");
+            record.steps_completed.push(step.name.clone());
+            None
+        }
+        Some(e) => {
+            record.steps_failed.push(step.name.clone());
+            (policy != StepErrorPolicy::Continue).then_some(e)
+        }
     }
 }
 
@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Dry runs: the tool calls a playbook would make, without invoking any.
+//!
+//! Steps are planned exactly as during execution (arguments interpolated,
+//! conditions evaluated, `for_each` items expanded), but no step produces a
+//! result. References to earlier steps resolve against the caller's sample
+//! results, or to `null` when none was given for that step.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::super::{PlaybookContext, PlaybookDefinition, PlaybookStep, evaluate_condition};
+use super::run::{Planned, plan_step};
+
+/// One tool call a playbook would make.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedCall {
+    /// Step making the call.
+    pub step: String,
+    /// `parallel` group the step belongs to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Server hosting the tool.
+    pub server: String,
+    /// Tool name.
+    pub tool: String,
+    /// Arguments after interpolation.
+    pub arguments: Value,
+}
+
+/// Result of a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PlaybookPlan {
+    /// Playbook name.
+    pub playbook: String,
+    /// Calls in execution order (calls of one `parallel` group run
+    /// concurrently when executed).
+    pub calls: Vec<PlannedCall>,
+    /// Steps whose condition was false.
+    pub steps_skipped: Vec<String>,
+    /// Steps that would fail before making a call, with the reason.
+    pub errors: Vec<String>,
+}
+
+/// Plan every top-level step of `definition` for `inputs`.
+pub(super) fn plan_playbook(
+    definition: &PlaybookDefinition,
+    inputs: Value,
+    sample_results: HashMap<String, Value>,
+) -> PlaybookPlan {
+    let mut ctx = PlaybookContext::new(inputs);
+    ctx.step_results = sample_results;
+    let mut plan = PlaybookPlan {
+        playbook: definition.name.clone(),
+        ..PlaybookPlan::default()
+    };
+
+    for step in &definition.steps {
+        if step.parallel.is_empty() {
+            plan_into(&mut plan, step, None, &mut ctx);
+            continue;
+        }
+        if step.for_each.is_none()
+            && let Some(ref condition) = step.condition
+            && !evaluate_condition(condition, &ctx)
+        {
+            plan.steps_skipped.push(step.name.clone());
+            continue;
+        }
+        for child in &step.parallel {
+            plan_into(&mut plan, child, Some(&step.name), &mut ctx);
+        }
+    }
+    plan
+}
+
+/// Add the calls of one (non-group) step to `plan`.
+fn plan_into(
+    plan: &mut PlaybookPlan,
+    step: &PlaybookStep,
+    group: Option<&str>,
+    ctx: &mut PlaybookContext,
+) {
+    if step.for_each.is_none()
+        && let Some(ref condition) = step.condition
+        && !evaluate_condition(condition, ctx)
+    {
+        plan.steps_skipped.push(step.name.clone());
+        return;
+    }
+    let calls = match plan_step(step, ctx) {
+        Planned::Call(arguments) => vec![arguments],
+        Planned::Each(calls) => calls,
+        Planned::Invalid(e) => {
+            plan.errors.push(e.to_string());
+            return;
+        }
+    };
+    plan.calls
+        .extend(calls.into_iter().map(|arguments| PlannedCall {
+            step: step.name.clone(),
+            group: group.map(str::to_string),
+            server: step.server.clone(),
+            tool: step.tool.clone(),
+            arguments,
+        }));
+}
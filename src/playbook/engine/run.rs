@@ -0,0 +1,257 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Step execution: single calls, `for_each` fan-out and `parallel` groups.
+//!
+//! Every step is first *planned* against the context (condition evaluated,
+//! arguments interpolated) and then executed without touching the context,
+//! so the children of a `parallel` group can run concurrently.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::future::join_all;
+use futures::stream::{self, StreamExt};
+use parking_lot::Mutex;
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+use super::super::{
+    PlaybookContext, PlaybookDefinition, PlaybookStep, ToolInvoker, evaluate_condition,
+};
+
+/// What running one step produced.
+pub(super) struct StepOutcome {
+    /// Result stored under the step name (`null` when the call failed).
+    pub(super) result: Value,
+    /// The error, if the step failed after its retries.
+    pub(super) error: Option<crate::Error>,
+    /// Whether any call needed more than one attempt.
+    pub(super) retried: bool,
+}
+
+impl StepOutcome {
+    pub(super) fn failed(error: crate::Error) -> Self {
+        Self {
+            result: Value::Null,
+            error: Some(error),
+            retried: false,
+        }
+    }
+}
+
+/// Calls a step will make, resolved before anything runs.
+pub(super) enum Planned {
+    /// One call with these arguments.
+    Call(Value),
+    /// One call per `for_each` item.
+    Each(Vec<Value>),
+    /// The step cannot run.
+    Invalid(crate::Error),
+}
+
+/// Runs the steps of one playbook execution.
+pub(super) struct StepRunner<'a> {
+    definition: &'a PlaybookDefinition,
+    invoker: &'a dyn ToolInvoker,
+    /// Per-server call slots (`server_concurrency`).
+    server_slots: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl<'a> StepRunner<'a> {
+    pub(super) fn new(definition: &'a PlaybookDefinition, invoker: &'a dyn ToolInvoker) -> Self {
+        Self {
+            definition,
+            invoker,
+            server_slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run a single (non-group) step whose condition already passed.
+    pub(super) async fn run(&self, step: &PlaybookStep, ctx: &mut PlaybookContext) -> StepOutcome {
+        let planned = plan_step(step, ctx);
+        self.execute(step, planned).await
+    }
+
+    /// Run the children of a `parallel` group concurrently and wait for all
+    /// of them.
+    ///
+    /// Children see the context as it was before the group started. Each
+    /// entry is `None` when the child's condition skipped it.
+    pub(super) async fn run_parallel(
+        &self,
+        group: &'a PlaybookStep,
+        ctx: &mut PlaybookContext,
+    ) -> Vec<(&'a PlaybookStep, Option<StepOutcome>)> {
+        let planned: Vec<(&PlaybookStep, Option<Planned>)> = group
+            .parallel
+            .iter()
+            .map(|child| {
+                let runs = child.for_each.is_some()
+                    || child
+                        .condition
+                        .as_ref()
+                        .is_none_or(|condition| evaluate_condition(condition, ctx));
+                (child, runs.then(|| plan_step(child, ctx)))
+            })
+            .collect();
+        debug!(group = %group.name, steps = planned.len(), "Running parallel group");
+
+        join_all(planned.into_iter().map(|(child, planned)| async move {
+            match planned {
+                Some(planned) => (child, Some(self.execute(child, planned).await)),
+                None => (child, None),
+            }
+        }))
+        .await
+    }
+
+    /// For `for_each` steps the result lists the call results in item order
+    /// (items skipped by the condition are left out, failed calls are
+    /// `null`) and the error is the first failure.
+    async fn execute(&self, step: &PlaybookStep, planned: Planned) -> StepOutcome {
+        match planned {
+            Planned::Invalid(e) => StepOutcome::failed(e),
+            Planned::Call(arguments) => {
+                let (outcome, attempts) = self.invoke_with_retry(step, arguments).await;
+                let retried = attempts > 1;
+                match outcome {
+                    Ok(result) => StepOutcome {
+                        result,
+                        error: None,
+                        retried,
+                    },
+                    Err(e) => StepOutcome {
+                        retried,
+                        ..StepOutcome::failed(e)
+                    },
+                }
+            }
+            Planned::Each(calls) => {
+                debug!(step = %step.name, calls = calls.len(), "Running for_each step");
+                let outcomes: Vec<(crate::Result<Value>, u32)> = stream::iter(calls)
+                    .map(|arguments| self.invoke_with_retry(step, arguments))
+                    .buffered(step.for_each_concurrency())
+                    .collect()
+                    .await;
+
+                let mut first_error = None;
+                let mut retried = false;
+                let results = outcomes
+                    .into_iter()
+                    .map(|(outcome, attempts)| {
+                        retried |= attempts > 1;
+                        outcome.unwrap_or_else(|e| {
+                            first_error.get_or_insert(e);
+                            Value::Null
+                        })
+                    })
+                    .collect();
+                StepOutcome {
+                    result: Value::Array(results),
+                    error: first_error,
+                    retried,
+                }
+            }
+        }
+    }
+
+    /// Invoke a step's tool under its retry policy; returns the outcome and
+    /// the number of attempts made.
+    ///
+    /// Each attempt holds one of the server's slots; backoff delays do not.
+    async fn invoke_with_retry(
+        &self,
+        step: &PlaybookStep,
+        arguments: Value,
+    ) -> (crate::Result<Value>, u32) {
+        let retry = step.retry_policy(self.definition);
+        let slots = Arc::clone(
+            self.server_slots
+                .lock()
+                .entry(step.server.clone())
+                .or_insert_with(|| Arc::new(Semaphore::new(self.definition.server_concurrency()))),
+        );
+
+        let max_attempts = retry.attempts.max(1);
+        let mut attempt = 0;
+        loop {
+            if attempt > 0 {
+                debug!(step = %step.name, attempt, "Retrying step");
+                tokio::time::sleep(retry.delay_before(attempt)).await;
+            }
+            let outcome = match slots.acquire().await {
+                Ok(_slot) => {
+                    self.invoker
+                        .invoke(&step.server, &step.tool, arguments.clone())
+                        .await
+                }
+                Err(e) => Err(crate::Error::Internal(e.to_string())),
+            };
+            attempt += 1;
+            match outcome {
+                Ok(result) => return (Ok(result), attempt),
+                Err(e) => {
+                    warn!(step = %step.name, attempt, error = %e, "Step failed");
+                    if attempt >= max_attempts || !retry.retries(&e) {
+                        return (Err(e), attempt);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Resolve the calls of a single (non-group) step against `ctx`: interpolate
+/// its arguments and, for `for_each` steps, evaluate the condition per item.
+///
+/// Shared by execution and dry runs, so a plan shows exactly the calls a run
+/// would make with the same context.
+pub(super) fn plan_step(step: &PlaybookStep, ctx: &mut PlaybookContext) -> Planned {
+    if !step.parallel.is_empty() {
+        return Planned::Invalid(crate::Error::Internal(format!(
+            "Step '{}': nested parallel groups are not supported",
+            step.name
+        )));
+    }
+    if step.tool.is_empty() {
+        return Planned::Invalid(crate::Error::Internal(format!(
+            "Step '{}' has no tool",
+            step.name
+        )));
+    }
+    let arguments = step_arguments(step);
+    let Some(ref source) = step.for_each else {
+        return Planned::Call(ctx.interpolate(&arguments));
+    };
+
+    let Value::Array(items) = ctx.resolve_var(source) else {
+        return Planned::Invalid(crate::Error::Internal(format!(
+            "Step '{}' for_each '{source}' did not resolve to an array",
+            step.name
+        )));
+    };
+    let mut calls = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate() {
+        ctx.item = Some((item, index));
+        if step
+            .condition
+            .as_ref()
+            .is_none_or(|condition| evaluate_condition(condition, ctx))
+        {
+            calls.push(ctx.interpolate(&arguments));
+        }
+    }
+    ctx.item = None;
+    Planned::Each(calls)
+}
+
+/// Step arguments as a JSON object, before interpolation.
+fn step_arguments(step: &PlaybookStep) -> Value {
+    Value::Object(
+        step.arguments
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+    )
+}
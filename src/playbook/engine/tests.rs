@@ -236,6 +236,8 @@ fn engine_register_and_get() {
         on_error: ErrorStrategy::Abort,
         max_retries: 1,
         timeout: 60,
+        server_concurrency: None,
+        fallbacks: Vec::new(),
     });
     assert_eq!(engine.len(), 1);
     assert!(engine.get("test").is_some());
@@ -286,11 +288,18 @@ async fn execute_simple_playbook() {
             server: "test".to_string(),
             arguments: HashMap::from([("q".to_string(), json!("hello"))]),
             condition: None,
+            for_each: None,
+            concurrency: None,
+            parallel: Vec::new(),
+            retry: None,
+            on_error: None,
         }],
         output: None,
         on_error: ErrorStrategy::Abort,
         max_retries: 1,
         timeout: 60,
+        server_concurrency: None,
+        fallbacks: Vec::new(),
     });
 
     let invoker = MockInvoker::new().respond("my_tool", json!({"result": "world"}));
@@ -315,6 +324,11 @@ async fn execute_with_variable_interpolation() {
                 server: "cap".to_string(),
                 arguments: HashMap::from([("query".to_string(), json!("$inputs.query"))]),
                 condition: None,
+                for_each: None,
+                concurrency: None,
+                parallel: Vec::new(),
+                retry: None,
+                on_error: None,
             },
             PlaybookStep {
                 name: "ground".to_string(),
@@ -322,6 +336,11 @@ async fn execute_with_variable_interpolation() {
                 server: "cap".to_string(),
                 arguments: HashMap::from([("query".to_string(), json!("$search.top_result"))]),
                 condition: None,
+                for_each: None,
+                concurrency: None,
+                parallel: Vec::new(),
+                retry: None,
+                on_error: None,
             },
         ],
         output: Some(PlaybookOutput {
@@ -346,6 +365,8 @@ async fn execute_with_variable_interpolation() {
         on_error: ErrorStrategy::Abort,
         max_retries: 1,
         timeout: 60,
+        server_concurrency: None,
+        fallbacks: Vec::new(),
     });
 
     let invoker = MockInvoker::new()
@@ -377,6 +398,11 @@ async fn execute_with_condition_skip() {
                 server: "s".to_string(),
                 arguments: HashMap::new(),
                 condition: None,
+                for_each: None,
+                concurrency: None,
+                parallel: Vec::new(),
+                retry: None,
+                on_error: None,
             },
             PlaybookStep {
                 name: "conditional".to_string(),
@@ -384,12 +410,19 @@ async fn execute_with_condition_skip() {
                 server: "s".to_string(),
                 arguments: HashMap::new(),
                 condition: Some("$inputs.deep == 'true'".to_string()),
+                for_each: None,
+                concurrency: None,
+                parallel: Vec::new(),
+                retry: None,
+                on_error: None,
             },
         ],
         output: None,
         on_error: ErrorStrategy::Abort,
         max_retries: 1,
         timeout: 60,
+        server_concurrency: None,
+        fallbacks: Vec::new(),
     });
 
     let invoker = MockInvoker::new()
@@ -428,6 +461,11 @@ async fn execute_abort_on_error() {
                 server: "s".to_string(),
                 arguments: HashMap::new(),
                 condition: None,
+                for_each: None,
+                concurrency: None,
+                parallel: Vec::new(),
+                retry: None,
+                on_error: None,
             },
             PlaybookStep {
                 name: "never_reached".to_string(),
@@ -435,12 +473,19 @@ async fn execute_abort_on_error() {
                 server: "s".to_string(),
                 arguments: HashMap::new(),
                 condition: None,
+                for_each: None,
+                concurrency: None,
+                parallel: Vec::new(),
+                retry: None,
+                on_error: None,
             },
         ],
         output: None,
         on_error: ErrorStrategy::Abort,
         max_retries: 1,
         timeout: 60,
+        server_concurrency: None,
+        fallbacks: Vec::new(),
     });
 
     let invoker = MockInvoker::new().respond("tool_a", json!({"ok": true}));
@@ -466,6 +511,11 @@ async fn execute_continue_on_error() {
                 server: "s".to_string(),
                 arguments: HashMap::new(),
                 condition: None,
+                for_each: None,
+                concurrency: None,
+                parallel: Vec::new(),
+                retry: None,
+                on_error: None,
             },
             PlaybookStep {
                 name: "after_fail".to_string(),
@@ -473,12 +523,19 @@ async fn execute_continue_on_error() {
                 server: "s".to_string(),
                 arguments: HashMap::new(),
                 condition: None,
+                for_each: None,
+                concurrency: None,
+                parallel: Vec::new(),
+                retry: None,
+                on_error: None,
             },
         ],
         output: None,
         on_error: ErrorStrategy::Continue,
         max_retries: 1,
         timeout: 60,
+        server_concurrency: None,
+        fallbacks: Vec::new(),
     });
 
     let invoker = MockInvoker::new().respond("tool_a", json!({"ok": true}));
@@ -504,6 +561,11 @@ async fn execute_output_with_fallback() {
             server: "s".to_string(),
             arguments: HashMap::new(),
             condition: None,
+            for_each: None,
+            concurrency: None,
+            parallel: Vec::new(),
+            retry: None,
+            on_error: None,
         }],
         output: Some(PlaybookOutput {
             output_type: "object".to_string(),
@@ -534,6 +596,8 @@ async fn execute_output_with_fallback() {
         on_error: ErrorStrategy::Abort,
         max_retries: 1,
         timeout: 60,
+        server_concurrency: None,
+        fallbacks: Vec::new(),
     });
 
     let invoker = MockInvoker::new().respond("tool_a", json!({"data": "found_it"}));
@@ -558,6 +622,355 @@ async fn execute_playbook_not_found() {
     assert!(err.to_string().contains("Playbook not found"));
 }
 
+// ── for_each steps ──────────────────────────────────────────────────
+
+/// Echoes its arguments back and tracks the peak number of concurrent calls.
+#[derive(Default)]
+struct EchoInvoker {
+    in_flight: std::sync::atomic::AtomicUsize,
+    peak: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl ToolInvoker for EchoInvoker {
+    async fn invoke(&self, _server: &str, tool: &str, arguments: Value) -> crate::Result<Value> {
+        use std::sync::atomic::Ordering;
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        match tool {
+            "checks" => Ok(json!({"checks": [
+                {"name": "lint", "ok": true},
+                {"name": "test", "ok": false},
+                {"name": "build", "ok": false},
+            ]})),
+            "fail" => Err(crate::Error::Internal("boom".to_string())),
+            _ => Ok(arguments),
+        }
+    }
+}
+
+fn for_each_playbook() -> PlaybookDefinition {
+    serde_yaml::from_str(
+        r#"
+name: issues
+description: Create an issue per failing check
+steps:
+  - name: ci
+    tool: checks
+  - name: issues
+    tool: create_issue
+    for_each: "$ci.checks"
+    condition: "$item.ok == 'false'"
+    concurrency: 2
+    arguments:
+      title: "$item.name failed"
+      position: "$index"
+"#,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn for_each_collects_results_in_item_order() {
+    // GIVEN: a step iterating over the checks of a previous step
+    let mut engine = PlaybookEngine::new();
+    engine.register(for_each_playbook());
+
+    // WHEN: the playbook runs
+    let result = engine
+        .execute("issues", json!({}), &EchoInvoker::default())
+        .await
+        .unwrap();
+
+    // THEN: one call per failing check, results listed in item order
+    assert_eq!(result.steps_completed, vec!["ci", "issues"]);
+    assert_eq!(
+        result.output["issues"],
+        json!([
+            {"title": "test failed", "position": 1},
+            {"title": "build failed", "position": 2},
+        ])
+    );
+}
+
+#[tokio::test]
+async fn for_each_respects_concurrency_cap() {
+    let mut def = for_each_playbook();
+    def.steps[1].condition = None;
+    def.steps[1].concurrency = Some(1);
+    let mut engine = PlaybookEngine::new();
+    engine.register(def);
+    let invoker = EchoInvoker::default();
+
+    let result = engine.execute("issues", json!({}), &invoker).await.unwrap();
+
+    assert_eq!(result.output["issues"].as_array().unwrap().len(), 3);
+    assert_eq!(invoker.peak.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn for_each_rejects_non_array_source() {
+    let mut def = for_each_playbook();
+    def.steps[1].for_each = Some("$ci.missing".to_string());
+    let mut engine = PlaybookEngine::new();
+    engine.register(def);
+
+    let err = engine
+        .execute("issues", json!({}), &EchoInvoker::default())
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.to_string().contains("did not resolve to an array"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn for_each_failures_leave_nulls_under_continue() {
+    let mut def = for_each_playbook();
+    def.steps[1].tool = "fail".to_string();
+    def.on_error = ErrorStrategy::Continue;
+    let mut engine = PlaybookEngine::new();
+    engine.register(def);
+
+    let result = engine
+        .execute("issues", json!({}), &EchoInvoker::default())
+        .await
+        .unwrap();
+
+    assert_eq!(result.steps_failed, vec!["issues"]);
+    assert_eq!(result.output["issues"], json!([null, null]));
+}
+
+// ── parallel groups ─────────────────────────────────────────────────
+
+fn parallel_playbook(server_concurrency: usize) -> PlaybookDefinition {
+    serde_yaml::from_str(&format!(
+        r#"
+name: lookups
+description: Independent lookups joined into one report
+server_concurrency: {server_concurrency}
+steps:
+  - name: lookups
+    parallel:
+      - name: github
+        tool: search
+        server: github
+        arguments: {{q: "$inputs.q"}}
+      - name: jira
+        tool: search
+        server: github
+        arguments: {{q: "$inputs.q"}}
+      - name: wiki
+        tool: search
+        server: wiki
+        condition: "$inputs.wiki"
+  - name: report
+    tool: summarize
+    server: llm
+    arguments:
+      github: "$github.q"
+      all: "$lookups"
+"#
+    ))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn parallel_group_runs_children_concurrently_and_joins() {
+    // GIVEN: a group of two lookups (the third is skipped by its condition)
+    let mut engine = PlaybookEngine::new();
+    engine.register(parallel_playbook(4));
+    let invoker = EchoInvoker::default();
+
+    // WHEN: the playbook runs
+    let result = engine
+        .execute("lookups", json!({"q": "rust"}), &invoker)
+        .await
+        .unwrap();
+
+    // THEN: the lookups overlapped and the next step saw both results
+    assert_eq!(invoker.peak.load(std::sync::atomic::Ordering::SeqCst), 2);
+    assert_eq!(result.steps_completed, vec!["github", "jira", "report"]);
+    assert_eq!(result.steps_skipped, vec!["wiki"]);
+    assert_eq!(result.output["report"]["github"], "rust");
+    assert_eq!(
+        result.output["report"]["all"],
+        json!({"github": {"q": "rust"}, "jira": {"q": "rust"}})
+    );
+}
+
+#[tokio::test]
+async fn parallel_group_respects_server_concurrency() {
+    let mut engine = PlaybookEngine::new();
+    engine.register(parallel_playbook(1));
+    let invoker = EchoInvoker::default();
+
+    let result = engine
+        .execute("lookups", json!({"q": "rust"}), &invoker)
+        .await
+        .unwrap();
+
+    // Both lookups target `github`, so they ran one at a time.
+    assert_eq!(invoker.peak.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_eq!(result.steps_failed, Vec::<String>::new());
+}
+
+#[tokio::test]
+async fn parallel_group_rejects_nested_groups() {
+    let mut def = parallel_playbook(4);
+    let nested = def.steps[0].clone();
+    def.steps[0].parallel[0].parallel = vec![nested];
+    let mut engine = PlaybookEngine::new();
+    engine.register(def);
+
+    let err = engine
+        .execute("lookups", json!({"q": "rust"}), &EchoInvoker::default())
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("nested parallel"), "{err}");
+}
+
+// ── per-step retry and error policy ────────────────────────────────
+
+/// `flaky` fails twice with a 503 before succeeding, `broken` always fails,
+/// anything else echoes its arguments.
+#[derive(Default)]
+struct FlakyInvoker {
+    flaky_calls: std::sync::atomic::AtomicU32,
+}
+
+#[async_trait::async_trait]
+impl ToolInvoker for FlakyInvoker {
+    async fn invoke(&self, _server: &str, tool: &str, arguments: Value) -> crate::Result<Value> {
+        match tool {
+            "flaky"
+                if self
+                    .flaky_calls
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+                    < 2 =>
+            {
+                Err(crate::Error::Transport(
+                    "503 Service Unavailable".to_string(),
+                ))
+            }
+            "broken" => Err(crate::Error::Protocol("invalid arguments".to_string())),
+            _ => Ok(arguments),
+        }
+    }
+}
+
+fn policy_playbook(steps: &str) -> PlaybookDefinition {
+    serde_yaml::from_str(&format!(
+        r"
+name: policy
+description: Per-step error handling
+steps:
+{steps}
+fallbacks:
+  - name: notify
+    tool: post_message
+    arguments: {{text: 'lookup failed'}}
+"
+    ))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn step_retry_recovers_flaky_step_and_is_recorded() {
+    // GIVEN: a step allowed three attempts on 503s
+    let mut engine = PlaybookEngine::new();
+    engine.register(policy_playbook(
+        "  - name: fetch\n    tool: flaky\n    retry: {attempts: 3, backoff: 1ms, retry_on: ['503']}",
+    ));
+    let invoker = FlakyInvoker::default();
+
+    // WHEN: the playbook runs
+    let result = engine.execute("policy", json!({}), &invoker).await.unwrap();
+
+    // THEN: the third attempt succeeded and the retry is reported
+    assert_eq!(result.steps_completed, vec!["fetch"]);
+    assert_eq!(result.steps_retried, vec!["fetch"]);
+    assert_eq!(
+        invoker
+            .flaky_calls
+            .load(std::sync::atomic::Ordering::SeqCst),
+        3
+    );
+}
+
+#[tokio::test]
+async fn step_retry_skips_errors_not_listed_in_retry_on() {
+    let mut engine = PlaybookEngine::new();
+    engine.register(policy_playbook(
+        "  - name: fetch\n    tool: flaky\n    retry: {attempts: 3, retry_on: [timeout]}",
+    ));
+    let invoker = FlakyInvoker::default();
+
+    let err = engine
+        .execute("policy", json!({}), &invoker)
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("503"), "{err}");
+    assert_eq!(
+        invoker
+            .flaky_calls
+            .load(std::sync::atomic::Ordering::SeqCst),
+        1
+    );
+}
+
+#[tokio::test]
+async fn step_on_error_continue_overrides_playbook_abort() {
+    let mut engine = PlaybookEngine::new();
+    engine.register(policy_playbook(
+        "  - name: optional\n    tool: broken\n    on_error: continue\n  - name: after\n    tool: echo",
+    ));
+
+    let result = engine
+        .execute("policy", json!({}), &FlakyInvoker::default())
+        .await
+        .unwrap();
+
+    assert_eq!(result.steps_failed, vec!["optional"]);
+    assert_eq!(result.steps_completed, vec!["after"]);
+    assert_eq!(result.output["optional"], Value::Null);
+}
+
+#[tokio::test]
+async fn step_on_error_fallback_stands_in_for_failed_step() {
+    let mut engine = PlaybookEngine::new();
+    engine.register(policy_playbook(
+        "  - name: lookup\n    tool: broken\n    on_error: {fallback: notify}",
+    ));
+
+    let result = engine
+        .execute("policy", json!({}), &FlakyInvoker::default())
+        .await
+        .unwrap();
+
+    assert_eq!(result.steps_fallback, vec!["lookup"]);
+    assert!(result.steps_failed.is_empty());
+    assert_eq!(result.output["lookup"], json!({"text": "lookup failed"}));
+}
+
+#[test]
+fn step_retry_backoff_doubles() {
+    let retry = StepRetry {
+        attempts: 4,
+        backoff: std::time::Duration::from_millis(100),
+        retry_on: Vec::new(),
+    };
+    assert_eq!(retry.delay_before(0), std::time::Duration::ZERO);
+    assert_eq!(retry.delay_before(1).as_millis(), 100);
+    assert_eq!(retry.delay_before(3).as_millis(), 400);
+}
+
 // ── YAML deserialization ────────────────────────────────────────────
 
 #[test]
@@ -643,6 +1056,8 @@ fn build_output_no_mapping_returns_all_results() {
         on_error: ErrorStrategy::Abort,
         max_retries: 1,
         timeout: 60,
+        server_concurrency: None,
+        fallbacks: Vec::new(),
     };
     let mut ctx = PlaybookContext::new(json!({}));
     ctx.step_results
@@ -651,3 +1066,248 @@ fn build_output_no_mapping_returns_all_results() {
     let output = build_output(&def, &ctx);
     assert_eq!(output["s1"], json!({"data": 1}));
 }
+
+// ── Durable runs ────────────────────────────────────────────────────
+
+/// Records every call; `charge` fails the first time.
+#[derive(Default)]
+struct RecordingInvoker {
+    calls: parking_lot::Mutex<Vec<String>>,
+    charged: std::sync::atomic::AtomicBool,
+}
+
+#[async_trait::async_trait]
+impl ToolInvoker for RecordingInvoker {
+    async fn invoke(&self, _server: &str, tool: &str, arguments: Value) -> crate::Result<Value> {
+        self.calls.lock().push(tool.to_string());
+        match tool {
+            "create" => Ok(json!({"id": 7})),
+            "charge" if !self.charged.swap(true, std::sync::atomic::Ordering::SeqCst) => {
+                Err(crate::Error::Transport("payment gateway down".to_string()))
+            }
+            _ => Ok(arguments),
+        }
+    }
+}
+
+fn order_playbook() -> PlaybookDefinition {
+    serde_yaml::from_str(
+        r#"
+name: order
+description: Create, charge and notify
+steps:
+  - name: create
+    server: shop
+    tool: create
+  - name: charge
+    server: shop
+    tool: charge
+    arguments: { order: "$create.id" }
+  - name: notify
+    server: mail
+    tool: notify
+"#,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn execute_run_resumes_from_failed_step() {
+    // GIVEN: a run whose second step fails once
+    let mut engine = PlaybookEngine::new();
+    engine.register(order_playbook());
+    let invoker = RecordingInvoker::default();
+    let checkpoints = parking_lot::Mutex::new(Vec::new());
+    let checkpoint = |run: &PlaybookRun| checkpoints.lock().push((run.next_step, run.status));
+    let mut run = PlaybookRun::new("order", json!({}));
+
+    // WHEN: the first attempt aborts
+    let err = engine
+        .execute_run(&mut run, &invoker, &checkpoint)
+        .await
+        .unwrap_err();
+
+    // THEN: the run is saved as failed at the step that did not finish
+    assert!(err.to_string().contains("payment gateway down"), "{err}");
+    assert_eq!(run.status, RunStatus::Failed);
+    assert_eq!(run.next_step, 1);
+    assert_eq!(run.progress.steps_completed, ["create"]);
+    assert!(run.progress.steps_failed.is_empty());
+
+    // WHEN: it is resumed
+    let result = engine
+        .execute_run(&mut run, &invoker, &checkpoint)
+        .await
+        .unwrap();
+
+    // THEN: earlier steps are not repeated and their results are reused
+    assert_eq!(
+        *invoker.calls.lock(),
+        ["create", "charge", "charge", "notify"]
+    );
+    assert_eq!(result.steps_completed, ["create", "charge", "notify"]);
+    assert_eq!(result.output["charge"], json!({"order": 7}));
+    assert_eq!(run.status, RunStatus::Completed);
+    assert_eq!(
+        *checkpoints.lock(),
+        [
+            (1, RunStatus::Running),
+            (1, RunStatus::Failed),
+            (2, RunStatus::Running),
+            (3, RunStatus::Running),
+            (3, RunStatus::Completed),
+        ]
+    );
+}
+
+// ── Directory loading and validation ────────────────────────────────
+
+#[test]
+fn validate_rejects_duplicate_step_names() {
+    let def = policy_playbook(
+        "  - name: a\n    tool: ok\n  - name: g\n    parallel:\n      - name: a\n        tool: ok\n",
+    );
+    let err = def.validate().unwrap_err().to_string();
+    assert!(err.contains("duplicate step name 'a'"), "{err}");
+}
+
+#[test]
+fn validate_rejects_unknown_fallback_and_missing_tool() {
+    let def = policy_playbook("  - name: a\n    tool: ok\n    on_error: {fallback: nope}\n");
+    let err = def.validate().unwrap_err().to_string();
+    assert!(err.contains("unknown fallback 'nope'"), "{err}");
+
+    let def = policy_playbook("  - name: a\n    server: s\n");
+    let err = def.validate().unwrap_err().to_string();
+    assert!(err.contains("has no tool"), "{err}");
+}
+
+#[test]
+fn load_directories_reports_broken_files_and_keeps_the_rest() {
+    // GIVEN: one valid playbook, one invalid one and one that does not parse
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("good.yml"),
+        "name: good\ndescription: ok\nsteps:\n  - name: a\n    tool: t\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("invalid.yaml"),
+        "name: invalid\ndescription: x\nsteps:\n  - name: a\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("broken.yaml"), "name: [").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+    // WHEN: loading the directory
+    let (engine, report) = PlaybookEngine::load_directories(&[dir.path().display().to_string()]);
+
+    // THEN: the good playbook loads and each broken file is reported
+    assert_eq!(report.loaded, ["good"]);
+    assert!(engine.get("good").is_some());
+    assert_eq!(report.errors.len(), 2, "{:?}", report.errors);
+    assert!(
+        report
+            .errors
+            .iter()
+            .any(|e| e.contains("invalid.yaml") && e.contains("has no tool"))
+    );
+    assert!(
+        report
+            .errors
+            .iter()
+            .any(|e| e.contains("broken.yaml") && e.contains("parse failed"))
+    );
+}
+
+#[test]
+fn load_directories_ignores_missing_directories() {
+    let (engine, report) = PlaybookEngine::load_directories(&["/nonexistent/playbooks".into()]);
+    assert!(engine.is_empty());
+    assert_eq!(report, PlaybookLoadReport::default());
+}
+
+// ── Dry runs ────────────────────────────────────────────────────────
+
+fn plan_playbook() -> PlaybookDefinition {
+    serde_yaml::from_str(
+        r#"
+name: triage
+description: Triage failing checks
+steps:
+  - name: checks
+    server: ci
+    tool: list_checks
+    arguments: { repo: "$inputs.repo" }
+  - name: rerun
+    server: ci
+    tool: rerun
+    for_each: $checks.failing
+    condition: "$item.flaky"
+    arguments: { check: "$item.id" }
+  - name: notify
+    parallel:
+      - name: chat
+        server: slack
+        tool: post
+        arguments: { text: "rerun $inputs.repo" }
+      - name: page
+        server: pager
+        tool: page
+        condition: "$inputs.urgent"
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn plan_lists_calls_in_order_without_invoking() {
+    // GIVEN: sample inputs and a sample result for the first step
+    let mut engine = PlaybookEngine::new();
+    engine.register(plan_playbook());
+    let samples = HashMap::from([(
+        "checks".to_string(),
+        json!({"failing": [{"id": "lint", "flaky": true}, {"id": "unit", "flaky": false}]}),
+    )]);
+
+    // WHEN: planning the playbook
+    let plan = engine
+        .plan("triage", json!({"repo": "gw", "urgent": false}), samples)
+        .unwrap();
+
+    // THEN: templating is resolved and conditions prune calls
+    let calls: Vec<_> = plan
+        .calls
+        .iter()
+        .map(|c| (c.step.as_str(), c.tool.as_str(), c.arguments.clone()))
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            ("checks", "list_checks", json!({"repo": "gw"})),
+            ("rerun", "rerun", json!({"check": "lint"})),
+            ("chat", "post", json!({"text": "rerun gw"})),
+        ]
+    );
+    assert_eq!(plan.calls[2].group.as_deref(), Some("notify"));
+    assert_eq!(plan.steps_skipped, ["page"]);
+    assert!(plan.errors.is_empty());
+}
+
+#[test]
+fn plan_reports_steps_that_cannot_resolve() {
+    // GIVEN: no sample result for the step feeding `for_each`
+    let mut engine = PlaybookEngine::new();
+    engine.register(plan_playbook());
+
+    // WHEN: planning
+    let plan = engine
+        .plan("triage", json!({"repo": "gw"}), HashMap::new())
+        .unwrap();
+
+    // THEN: the for_each step is reported instead of planned
+    assert!(plan.calls.iter().all(|c| c.step != "rerun"));
+    assert_eq!(plan.errors.len(), 1);
+    assert!(plan.errors[0].contains("did not resolve to an array"));
+    assert!(engine.plan("missing", json!({}), HashMap::new()).is_err());
+}
@@ -0,0 +1,202 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Durable playbook runs.
+//!
+//! With `playbooks.state_dir` set, every `gateway_run_playbook` call is
+//! checkpointed to one JSON file per run after each top-level step settles.
+//! A run that aborted (or was cut short by a restart) resumes with
+//! `gateway_run_playbook {resume: run_id}` from the first step that did not
+//! finish, so side effects of earlier steps are not repeated. Steps inside a
+//! `parallel` group or a `for_each` step are checkpointed together with their
+//! parent step.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use super::PlaybookResult;
+
+/// Lifecycle of a persisted run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Started and not yet finished (or the gateway stopped mid-run).
+    Running,
+    /// Aborted on a failed step; resumable.
+    Failed,
+    /// Every step settled.
+    Completed,
+}
+
+/// Checkpointed state of one playbook run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybookRun {
+    /// Run identifier handed back to the caller.
+    pub run_id: String,
+    /// Playbook name.
+    pub playbook: String,
+    /// Input arguments of the original call.
+    pub inputs: Value,
+    /// Index of the first top-level step that has not settled.
+    pub next_step: usize,
+    /// Results of settled steps, keyed by step name.
+    pub step_results: HashMap<String, Value>,
+    /// Step bookkeeping so far (output is set once completed).
+    pub progress: PlaybookResult,
+    /// Current status.
+    pub status: RunStatus,
+    /// Error that aborted the run, while [`RunStatus::Failed`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// RFC 3339 timestamp of the last checkpoint.
+    pub updated_at: String,
+}
+
+impl PlaybookRun {
+    /// A fresh run of `playbook` with a new run ID.
+    #[must_use]
+    pub fn new(playbook: &str, inputs: Value) -> Self {
+        Self {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            playbook: playbook.to_string(),
+            inputs,
+            next_step: 0,
+            step_results: HashMap::new(),
+            progress: PlaybookResult::default(),
+            status: RunStatus::Running,
+            error: None,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Directory-backed run checkpoints.
+#[derive(Debug)]
+pub struct PlaybookRunStore {
+    dir: PathBuf,
+    active: Mutex<HashSet<String>>,
+}
+
+impl PlaybookRunStore {
+    /// Open (creating if needed) the state directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created.
+    pub fn open(dir: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            active: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Directory holding the runs.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Checkpoint file of `run_id`, or `None` for IDs that are not a UUID
+    /// (they come from the caller).
+    fn path(&self, run_id: &str) -> Option<PathBuf> {
+        uuid::Uuid::parse_str(run_id)
+            .ok()
+            .map(|id| self.dir.join(format!("{id}.json")))
+    }
+
+    /// The persisted run `run_id`, if any.
+    #[must_use]
+    pub fn load(&self, run_id: &str) -> Option<PlaybookRun> {
+        let path = self.path(run_id)?;
+        let bytes = std::fs::read(&path).ok()?;
+        match serde_json::from_slice(&bytes) {
+            Ok(run) => Some(run),
+            Err(e) => {
+                debug!(path = %path.display(), error = %e, "Ignoring unreadable playbook run");
+                None
+            }
+        }
+    }
+
+    /// Write `run`, replacing its previous checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, run: &PlaybookRun) -> std::io::Result<()> {
+        let path = self.path(&run.run_id).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "run ID is not a UUID")
+        })?;
+        let bytes = serde_json::to_vec(run)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        // Write-then-rename so a crash never leaves a partial checkpoint.
+        // Runs hold tool results, so files are owner-only on unix.
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        let mut opts = std::fs::OpenOptions::new();
+        opts.create(true).write(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt as _;
+            opts.mode(0o600);
+        }
+        std::io::Write::write_all(&mut opts.open(&tmp)?, &bytes)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Mark `run_id` as executing in this process; `false` if it already is.
+    pub(crate) fn begin(&self, run_id: &str) -> bool {
+        self.active.lock().insert(run_id.to_string())
+    }
+
+    /// Clear the mark set by [`Self::begin`].
+    pub(crate) fn finish(&self, run_id: &str) {
+        self.active.lock().remove(run_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_round_trips_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PlaybookRunStore::open(&dir.path().join("runs")).unwrap();
+        let mut run = PlaybookRun::new("order", serde_json::json!({"sku": "A1"}));
+        run.next_step = 2;
+        run.status = RunStatus::Failed;
+        run.error = Some("boom".to_string());
+
+        store.save(&run).unwrap();
+
+        let loaded = store.load(&run.run_id).unwrap();
+        assert_eq!(loaded.playbook, "order");
+        assert_eq!(loaded.inputs["sku"], "A1");
+        assert_eq!(loaded.next_step, 2);
+        assert_eq!(loaded.status, RunStatus::Failed);
+        assert_eq!(loaded.error.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn load_ignores_ids_that_are_not_uuids() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PlaybookRunStore::open(dir.path()).unwrap();
+        assert!(store.load("../../etc/passwd").is_none());
+        assert!(store.load(&uuid::Uuid::new_v4().to_string()).is_none());
+    }
+
+    #[test]
+    fn begin_marks_a_run_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PlaybookRunStore::open(dir.path()).unwrap();
+        assert!(store.begin("r1"));
+        assert!(!store.begin("r1"));
+        store.finish("r1");
+        assert!(store.begin("r1"));
+    }
+}
@@ -0,0 +1,169 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Hot-reload file watcher for playbook directories.
+//!
+//! Mirrors [`crate::capability::CapabilityWatcher`]: YAML changes in the
+//! watched directories are debounced and then handed to a reload callback,
+//! which re-reads every playbook (see [`PlaybookEngine::load_directories`]).
+//!
+//! [`PlaybookEngine::load_directories`]: super::PlaybookEngine::load_directories
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::Result;
+
+/// Callback run after playbook files changed.
+pub type PlaybookReload = Arc<dyn Fn() + Send + Sync>;
+
+/// File watcher for hot-reloading playbooks.
+pub struct PlaybookWatcher {
+    /// Kept alive to prevent the OS watcher from being dropped.
+    _watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl PlaybookWatcher {
+    /// Start watching `directories`, calling `reload` 500 ms after the last
+    /// change to a YAML file (added, modified or removed).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file watcher cannot be created.
+    pub fn start(
+        directories: &[String],
+        reload: PlaybookReload,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<Self> {
+        if directories.is_empty() {
+            return Ok(Self {
+                _watcher: Mutex::new(None),
+            });
+        }
+
+        let (event_tx, event_rx) = mpsc::channel(100);
+        let watcher = Self::create_watcher(event_tx, directories)?;
+        Self::spawn_reload_task(reload, event_rx, shutdown_rx);
+
+        Ok(Self {
+            _watcher: Mutex::new(Some(watcher)),
+        })
+    }
+
+    /// Create the file system watcher.
+    fn create_watcher(
+        event_tx: mpsc::Sender<()>,
+        directories: &[String],
+    ) -> Result<RecommendedWatcher> {
+        let mut watcher = RecommendedWatcher::new(
+            move |result: std::result::Result<Event, notify::Error>| match result {
+                Ok(event) => {
+                    let is_relevant = matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) && event.paths.iter().any(|p| {
+                        p.extension()
+                            .is_some_and(|ext| ext == "yaml" || ext == "yml")
+                    });
+                    if is_relevant {
+                        debug!(paths = ?event.paths, kind = ?event.kind, "Playbook file change");
+                        let _ = event_tx.try_send(());
+                    }
+                }
+                Err(e) => error!(error = %e, "Playbook watcher error"),
+            },
+            Config::default().with_poll_interval(Duration::from_secs(2)),
+        )
+        .map_err(|e| crate::Error::ConfigWatcher(format!("Playbook watcher: {e}")))?;
+
+        for dir in directories {
+            let path = Path::new(dir);
+            if !path.exists() {
+                debug!(directory = %dir, "Playbook directory does not exist, skipping watch");
+            } else if let Err(e) = watcher.watch(path, RecursiveMode::Recursive) {
+                warn!(directory = %dir, error = %e, "Failed to watch playbook directory");
+            } else {
+                info!(directory = %dir, "Watching for playbook changes");
+            }
+        }
+
+        Ok(watcher)
+    }
+
+    /// Spawn the debounced reload task.
+    fn spawn_reload_task(
+        reload: PlaybookReload,
+        mut event_rx: mpsc::Receiver<()>,
+        mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) {
+        tokio::spawn(async move {
+            const DEBOUNCE: Duration = Duration::from_millis(500);
+            let mut last_event: Option<Instant> = None;
+            let mut interval = tokio::time::interval(Duration::from_millis(100));
+
+            loop {
+                tokio::select! {
+                    Some(()) = event_rx.recv() => last_event = Some(Instant::now()),
+                    _ = interval.tick() => {
+                        if last_event.is_some_and(|last| last.elapsed() >= DEBOUNCE) {
+                            last_event = None;
+                            reload();
+                        }
+                    }
+                    _ = shutdown_rx.recv() => {
+                        info!("Playbook watcher shutting down");
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reloads_after_yaml_change() {
+        // GIVEN: a watched playbook directory
+        let dir = tempfile::tempdir().unwrap();
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&reloads);
+        let (_tx, rx) = tokio::sync::broadcast::channel(1);
+        let _watcher = PlaybookWatcher::start(
+            &[dir.path().display().to_string()],
+            Arc::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+            rx,
+        )
+        .unwrap();
+
+        // WHEN: a playbook file is written
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        std::fs::write(dir.path().join("triage.yaml"), "name: triage\n").unwrap();
+
+        // THEN: the reload callback runs once the change settles
+        for _ in 0..50 {
+            if reloads.load(Ordering::SeqCst) > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(reloads.load(Ordering::SeqCst) > 0);
+    }
+
+    #[test]
+    fn no_directories_watches_nothing() {
+        let (_tx, rx) = tokio::sync::broadcast::channel(1);
+        assert!(PlaybookWatcher::start(&[], Arc::new(|| {}), rx).is_ok());
+    }
+}
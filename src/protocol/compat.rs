@@ -0,0 +1,375 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: MIT
+
+//! Protocol version shim between clients and backends on different versions.
+//!
+//! The gateway works on the latest message shape internally. Traffic is
+//! adapted at both edges:
+//!
+//! - **Backend edge**: [`downgrade_params`] strips request fields an older
+//!   backend does not know; [`upgrade_result`] fills in fields the latest
+//!   version requires but an older backend may omit.
+//! - **Client edge**: [`upgrade_params`] fills in request fields an older
+//!   client may omit; [`downgrade_result`] strips result fields newer than the
+//!   version the client negotiated, rewriting content blocks it cannot parse
+//!   into text.
+//!
+//! | Introduced   | Fields handled                                               |
+//! |--------------|--------------------------------------------------------------|
+//! | `2025-03-26` | tool `annotations`, `audio` content, `completions` capability |
+//! | `2025-06-18` | `title`, tool `outputSchema`, `structuredContent`, `resource_link` content, annotation `lastModified` |
+//! | `2025-11-25` | `icons`, tool `execution`, `tasks` capability, `tools/call` `task`, server `description`/`websiteUrl` |
+//!
+//! Versions the gateway does not recognise are left untouched.
+
+use serde_json::{Map, Value, json};
+
+use super::SUPPORTED_VERSIONS;
+
+const V2025_03_26: &str = "2025-03-26";
+const V2025_06_18: &str = "2025-06-18";
+const V2025_11_25: &str = "2025-11-25";
+
+/// Whether `version` predates `since` (both known versions).
+fn predates(version: &str, since: &str) -> bool {
+    let rank = |v: &str| SUPPORTED_VERSIONS.iter().position(|&s| s == v);
+    match (rank(version), rank(since)) {
+        // SUPPORTED_VERSIONS is newest first.
+        (Some(v), Some(s)) => v > s,
+        _ => false,
+    }
+}
+
+/// Strip `params` fields of `method` that `backend_version` does not know.
+pub fn downgrade_params(method: &str, params: &mut Value, backend_version: &str) {
+    if method == "tools/call"
+        && predates(backend_version, V2025_11_25)
+        && let Some(obj) = params.as_object_mut()
+    {
+        obj.remove("task");
+    }
+}
+
+/// Fill in `params` fields of `method` that the latest version expects but a
+/// `client_version` client may omit.
+pub fn upgrade_params(method: &str, params: &mut Value, client_version: &str) {
+    if method == "tools/call"
+        && predates(client_version, super::PROTOCOL_VERSION)
+        && let Some(obj) = params.as_object_mut()
+        && !obj.get("arguments").is_some_and(Value::is_object)
+    {
+        obj.insert("arguments".to_string(), json!({}));
+    }
+}
+
+/// Fill in required `result` fields of `method` that a `backend_version`
+/// backend may omit, so the rest of the gateway sees the latest shape.
+pub fn upgrade_result(method: &str, result: &mut Value, backend_version: &str) {
+    if !predates(backend_version, super::PROTOCOL_VERSION) {
+        return;
+    }
+    let Some(obj) = result.as_object_mut() else {
+        return;
+    };
+    match method {
+        "tools/list" => {
+            for tool in array_mut(obj, "tools") {
+                let Some(tool) = tool.as_object_mut() else {
+                    continue;
+                };
+                let schema = tool
+                    .entry("inputSchema")
+                    .or_insert_with(|| json!({ "type": "object" }));
+                if let Some(schema) = schema.as_object_mut() {
+                    schema.entry("type").or_insert_with(|| json!("object"));
+                }
+            }
+        }
+        "tools/call" if !obj.contains_key("task") => {
+            obj.entry("content").or_insert_with(|| json!([]));
+        }
+        "prompts/list" => {
+            obj.entry("prompts").or_insert_with(|| json!([]));
+        }
+        "prompts/get" => {
+            obj.entry("messages").or_insert_with(|| json!([]));
+        }
+        "resources/list" => {
+            obj.entry("resources").or_insert_with(|| json!([]));
+        }
+        "resources/templates/list" => {
+            obj.entry("resourceTemplates").or_insert_with(|| json!([]));
+        }
+        _ => {}
+    }
+}
+
+/// Strip `result` fields of `method` newer than `client_version`.
+pub fn downgrade_result(method: &str, result: &mut Value, client_version: &str) {
+    if !predates(client_version, super::PROTOCOL_VERSION) {
+        return;
+    }
+    let Some(obj) = result.as_object_mut() else {
+        return;
+    };
+    match method {
+        "initialize" => downgrade_initialize(obj, client_version),
+        "tools/list" => {
+            for tool in array_mut(obj, "tools") {
+                if let Some(tool) = tool.as_object_mut() {
+                    downgrade_listed(tool, client_version);
+                    if predates(client_version, V2025_11_25) {
+                        tool.remove("execution");
+                    }
+                    if predates(client_version, V2025_06_18) {
+                        tool.remove("outputSchema");
+                    }
+                    if predates(client_version, V2025_03_26) {
+                        tool.remove("annotations");
+                    }
+                }
+            }
+        }
+        "tools/call" => {
+            if predates(client_version, V2025_06_18)
+                && let Some(structured) = obj.remove("structuredContent")
+            {
+                // Older clients only read `content`; keep the data visible.
+                let content = obj.entry("content").or_insert_with(|| json!([]));
+                if let Some(blocks) = content.as_array_mut()
+                    && blocks.is_empty()
+                {
+                    blocks.push(json!({ "type": "text", "text": structured.to_string() }));
+                }
+            }
+            downgrade_content(array_mut(obj, "content"), client_version);
+        }
+        "prompts/get" => {
+            for message in array_mut(obj, "messages") {
+                if let Some(content) = message.get_mut("content") {
+                    downgrade_block(content, client_version);
+                }
+            }
+        }
+        "prompts/list" | "resources/list" | "resources/templates/list" => {
+            let key = match method {
+                "prompts/list" => "prompts",
+                "resources/list" => "resources",
+                _ => "resourceTemplates",
+            };
+            for item in array_mut(obj, key) {
+                if let Some(item) = item.as_object_mut() {
+                    downgrade_listed(item, client_version);
+                    downgrade_annotations(item, client_version);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn downgrade_initialize(obj: &mut Map<String, Value>, client_version: &str) {
+    if let Some(caps) = obj.get_mut("capabilities").and_then(Value::as_object_mut) {
+        if predates(client_version, V2025_11_25) {
+            caps.remove("tasks");
+        }
+        if predates(client_version, V2025_03_26) {
+            caps.remove("completions");
+        }
+    }
+    if let Some(info) = obj.get_mut("serverInfo").and_then(Value::as_object_mut) {
+        if predates(client_version, V2025_11_25) {
+            info.remove("icons");
+            info.remove("description");
+            info.remove("websiteUrl");
+        }
+        if predates(client_version, V2025_06_18) {
+            info.remove("title");
+        }
+    }
+}
+
+/// Fields shared by listed tools, prompts and resources.
+fn downgrade_listed(item: &mut Map<String, Value>, client_version: &str) {
+    if predates(client_version, V2025_11_25) {
+        item.remove("icons");
+    }
+    if predates(client_version, V2025_06_18) {
+        item.remove("title");
+    }
+}
+
+fn downgrade_annotations(item: &mut Map<String, Value>, client_version: &str) {
+    if predates(client_version, V2025_06_18)
+        && let Some(annotations) = item.get_mut("annotations").and_then(Value::as_object_mut)
+    {
+        annotations.remove("lastModified");
+    }
+}
+
+fn downgrade_content<'a>(blocks: impl Iterator<Item = &'a mut Value>, client_version: &str) {
+    for block in blocks {
+        downgrade_block(block, client_version);
+    }
+}
+
+/// Rewrite one content block the client cannot parse into a text block.
+fn downgrade_block(block: &mut Value, client_version: &str) {
+    let Some(obj) = block.as_object_mut() else {
+        return;
+    };
+    downgrade_annotations(obj, client_version);
+    let str_field = |key: &str| obj.get(key).and_then(Value::as_str).unwrap_or_default();
+    let replacement = match obj.get("type").and_then(Value::as_str) {
+        Some("audio") if predates(client_version, V2025_03_26) => {
+            format!("[audio content omitted: {}]", str_field("mimeType"))
+        }
+        Some("resource_link") if predates(client_version, V2025_06_18) => {
+            format!("[resource: {} <{}>]", str_field("name"), str_field("uri"))
+        }
+        _ => return,
+    };
+    *block = json!({ "type": "text", "text": replacement });
+}
+
+fn array_mut<'a>(
+    obj: &'a mut Map<String, Value>,
+    key: &str,
+) -> impl Iterator<Item = &'a mut Value> + use<'a> {
+    obj.get_mut(key)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predates_orders_known_versions_only() {
+        assert!(predates("2024-11-05", V2025_11_25));
+        assert!(!predates(V2025_11_25, V2025_11_25));
+        assert!(!predates("1999-01-01", V2025_03_26));
+    }
+
+    #[test]
+    fn downgrade_tools_list_for_2024_client() {
+        let mut result = json!({ "tools": [{
+            "name": "search",
+            "title": "Search",
+            "icons": [{ "src": "https://example.com/i.png" }],
+            "inputSchema": { "type": "object" },
+            "outputSchema": { "type": "object" },
+            "annotations": { "readOnlyHint": true },
+            "execution": { "taskSupport": "optional" }
+        }]});
+
+        downgrade_result("tools/list", &mut result, "2024-11-05");
+
+        assert_eq!(
+            result,
+            json!({ "tools": [{ "name": "search", "inputSchema": { "type": "object" } }] })
+        );
+    }
+
+    #[test]
+    fn downgrade_keeps_fields_the_client_knows() {
+        let tool = json!({
+            "name": "search",
+            "title": "Search",
+            "inputSchema": { "type": "object" },
+            "outputSchema": { "type": "object" },
+            "icons": []
+        });
+        let mut result = json!({ "tools": [tool.clone()] });
+
+        downgrade_result("tools/list", &mut result, V2025_06_18);
+
+        let mut expected = tool;
+        expected.as_object_mut().unwrap().remove("icons");
+        assert_eq!(result["tools"][0], expected);
+    }
+
+    #[test]
+    fn downgrade_tools_call_rewrites_unknown_content() {
+        let mut result = json!({
+            "content": [
+                { "type": "audio", "data": "AAAA", "mimeType": "audio/wav" },
+                { "type": "resource_link", "uri": "file:///a.txt", "name": "a.txt" },
+                { "type": "text", "text": "ok", "annotations": { "priority": 1, "lastModified": "x" } }
+            ],
+            "isError": false
+        });
+
+        downgrade_result("tools/call", &mut result, "2024-11-05");
+
+        assert_eq!(
+            result["content"],
+            json!([
+                { "type": "text", "text": "[audio content omitted: audio/wav]" },
+                { "type": "text", "text": "[resource: a.txt <file:///a.txt>]" },
+                { "type": "text", "text": "ok", "annotations": { "priority": 1 } }
+            ])
+        );
+    }
+
+    #[test]
+    fn downgrade_tools_call_surfaces_structured_content_as_text() {
+        let mut result = json!({ "content": [], "structuredContent": { "n": 1 } });
+
+        downgrade_result("tools/call", &mut result, V2025_03_26);
+
+        assert_eq!(
+            result,
+            json!({ "content": [{ "type": "text", "text": "{\"n\":1}" }] })
+        );
+    }
+
+    #[test]
+    fn downgrade_initialize_drops_newer_capabilities() {
+        let mut result = json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": { "tools": {}, "tasks": {}, "completions": {} },
+            "serverInfo": { "name": "gw", "version": "1", "title": "GW" }
+        });
+
+        downgrade_result("initialize", &mut result, "2024-11-05");
+
+        assert_eq!(result["capabilities"], json!({ "tools": {} }));
+        assert_eq!(
+            result["serverInfo"],
+            json!({ "name": "gw", "version": "1" })
+        );
+    }
+
+    #[test]
+    fn upgrade_result_synthesizes_required_fields_for_old_backend() {
+        let mut tools = json!({ "tools": [{ "name": "a" }, { "name": "b", "inputSchema": {} }] });
+        let mut call = json!({ "isError": false });
+
+        upgrade_result("tools/list", &mut tools, "2024-11-05");
+        upgrade_result("tools/call", &mut call, "2024-11-05");
+
+        assert_eq!(
+            tools["tools"][0]["inputSchema"],
+            json!({ "type": "object" })
+        );
+        assert_eq!(
+            tools["tools"][1]["inputSchema"],
+            json!({ "type": "object" })
+        );
+        assert_eq!(call["content"], json!([]));
+    }
+
+    #[test]
+    fn params_are_adapted_in_both_directions() {
+        let mut to_backend = json!({ "name": "a", "arguments": {}, "task": { "ttl": 1000 } });
+        downgrade_params("tools/call", &mut to_backend, V2025_06_18);
+        assert!(to_backend.get("task").is_none());
+
+        let mut from_client = json!({ "name": "a" });
+        upgrade_params("tools/call", &mut from_client, "2024-11-05");
+        assert_eq!(from_client["arguments"], json!({}));
+    }
+}
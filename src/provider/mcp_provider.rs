@@ -163,6 +163,7 @@ mod tests {
                     session_mode: SessionMode::Stateless,
                     token_exchange_endpoint: None,
                     token_exchange_scope: None,
+                    token_exchange_client: None,
                 }),
                 ..BackendConfig::default()
             },
@@ -0,0 +1,311 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! `ArgumentTransform` — rewrite tool-call arguments before forwarding.
+//!
+//! # Behaviour
+//!
+//! `transform_invoke` applies, in order:
+//! 1. **rename** — move a caller field to the name the backend expects
+//!    (`query` → `q`). An explicit value already present under the target
+//!    name wins.
+//! 2. **defaults** — insert values for fields the caller omitted (or sent as
+//!    `null`), e.g. `limit: 20`.
+//! 3. **coerce** — convert field values to the declared JSON type
+//!    (`"20"` → `20`). A value that cannot be converted is rejected with a
+//!    JSON-RPC invalid-params error rather than forwarded malformed.
+//!
+//! `transform_tools` and `transform_result` pass through unchanged.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::protocol::Tool;
+use crate::{Error, Result, provider::Transform};
+
+/// Argument rewriting rules for one tool (deserialized from YAML).
+///
+/// # Example (YAML)
+///
+/// ```yaml
+/// argument_transforms:
+///   search:
+///     rename: { query: q }
+///     defaults: { limit: 20 }
+///     coerce: { limit: integer }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArgumentRewriteConfig {
+    /// Caller field name → backend field name.
+    pub rename: HashMap<String, String>,
+    /// Values inserted when the field is absent or `null`.
+    pub defaults: Map<String, Value>,
+    /// Field name → target JSON type, applied after rename and defaults.
+    pub coerce: HashMap<String, CoerceType>,
+}
+
+impl ArgumentRewriteConfig {
+    /// Returns `true` when no rewriting rules are configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rename.is_empty() && self.defaults.is_empty() && self.coerce.is_empty()
+    }
+}
+
+/// Target type for argument coercion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoerceType {
+    /// JSON string; numbers and booleans are stringified.
+    String,
+    /// JSON integer; numeric strings and integral floats are accepted.
+    Integer,
+    /// JSON number; numeric strings are accepted.
+    Number,
+    /// JSON boolean; `"true"`/`"false"` (any case), `1`/`0` are accepted.
+    Boolean,
+}
+
+/// Rewrites invocation arguments according to an [`ArgumentRewriteConfig`].
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_gateway::provider::transforms::{ArgumentRewriteConfig, ArgumentTransform};
+/// use serde_json::json;
+///
+/// let config: ArgumentRewriteConfig = serde_json::from_value(json!({
+///     "rename": {"query": "q"},
+///     "defaults": {"limit": 20}
+/// }))
+/// .unwrap();
+/// let t = ArgumentTransform::new(config);
+/// let args = t.rewrite(json!({"query": "rust"})).unwrap();
+/// assert_eq!(args, json!({"q": "rust", "limit": 20}));
+/// ```
+pub struct ArgumentTransform {
+    config: ArgumentRewriteConfig,
+}
+
+impl ArgumentTransform {
+    /// Create an argument transform from its rules.
+    #[must_use]
+    pub fn new(config: ArgumentRewriteConfig) -> Self {
+        Self { config }
+    }
+
+    /// Apply rename → defaults → coerce to a tool's `arguments` object.
+    ///
+    /// Non-object arguments (including `null`) are treated as an empty
+    /// object so defaults still apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns a JSON-RPC invalid-params error (`-32602`) when a field cannot
+    /// be coerced to its configured type.
+    pub fn rewrite(&self, args: Value) -> Result<Value> {
+        let mut map = match args {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        };
+
+        for (from, to) in &self.config.rename {
+            if let Some(value) = map.remove(from)
+                && !map.contains_key(to)
+            {
+                map.insert(to.clone(), value);
+            }
+        }
+
+        for (key, value) in &self.config.defaults {
+            if map.get(key).is_none_or(Value::is_null) {
+                map.insert(key.clone(), value.clone());
+            }
+        }
+
+        for (key, target) in &self.config.coerce {
+            if let Some(value) = map.get_mut(key)
+                && !value.is_null()
+            {
+                *value = coerce(value, *target).ok_or_else(|| {
+                    Error::json_rpc(
+                        -32602,
+                        format!("Argument '{key}' cannot be coerced to {target:?}: {value}"),
+                    )
+                })?;
+            }
+        }
+
+        Ok(Value::Object(map))
+    }
+}
+
+fn coerce(value: &Value, target: CoerceType) -> Option<Value> {
+    match (target, value) {
+        (CoerceType::String, Value::String(_))
+        | (CoerceType::Boolean, Value::Bool(_))
+        | (CoerceType::Number, Value::Number(_)) => Some(value.clone()),
+        (CoerceType::String, Value::Number(n)) => Some(Value::String(n.to_string())),
+        (CoerceType::String, Value::Bool(b)) => Some(Value::String(b.to_string())),
+        (CoerceType::Integer, Value::Number(n)) => {
+            if n.is_i64() || n.is_u64() {
+                Some(value.clone())
+            } else {
+                integral_f64(n.as_f64()?)
+            }
+        }
+        (CoerceType::Integer, Value::String(s)) => {
+            let s = s.trim();
+            s.parse::<i64>()
+                .map(Value::from)
+                .ok()
+                .or_else(|| integral_f64(s.parse().ok()?))
+        }
+        (CoerceType::Number, Value::String(s)) => {
+            let s = s.trim();
+            s.parse::<i64>()
+                .map(Value::from)
+                .ok()
+                .or_else(|| serde_json::Number::from_f64(s.parse().ok()?).map(Value::Number))
+        }
+        (CoerceType::Boolean, Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (CoerceType::Boolean, Value::Number(n)) => match n.as_i64() {
+            Some(1) => Some(Value::Bool(true)),
+            Some(0) => Some(Value::Bool(false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Convert an integral float (e.g. `20.0`) to a JSON integer.
+#[allow(clippy::cast_possible_truncation, clippy::float_cmp)]
+fn integral_f64(f: f64) -> Option<Value> {
+    (f.is_finite() && f.fract() == 0.0 && f.abs() < 9.007_199_254_740_992e15)
+        .then(|| Value::from(f as i64))
+}
+
+#[async_trait]
+impl Transform for ArgumentTransform {
+    async fn transform_tools(&self, tools: Vec<Tool>) -> Result<Vec<Tool>> {
+        Ok(tools)
+    }
+
+    async fn transform_invoke(&self, tool: &str, args: Value) -> Result<Option<(String, Value)>> {
+        Ok(Some((tool.to_string(), self.rewrite(args)?)))
+    }
+
+    async fn transform_result(&self, _tool: &str, result: Value) -> Result<Value> {
+        Ok(result)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transform(config: Value) -> ArgumentTransform {
+        ArgumentTransform::new(serde_json::from_value(config).unwrap())
+    }
+
+    #[test]
+    fn rename_moves_field_to_backend_name() {
+        let t = transform(json!({"rename": {"query": "q"}}));
+        assert_eq!(
+            t.rewrite(json!({"query": "rust"})).unwrap(),
+            json!({"q": "rust"})
+        );
+    }
+
+    #[test]
+    fn rename_does_not_clobber_explicit_target() {
+        let t = transform(json!({"rename": {"query": "q"}}));
+        assert_eq!(
+            t.rewrite(json!({"query": "a", "q": "b"})).unwrap(),
+            json!({"q": "b"})
+        );
+    }
+
+    #[test]
+    fn defaults_fill_missing_and_null_fields_only() {
+        let t = transform(json!({"defaults": {"limit": 20, "sort": "new"}}));
+        assert_eq!(
+            t.rewrite(json!({"limit": 5, "sort": null})).unwrap(),
+            json!({"limit": 5, "sort": "new"})
+        );
+    }
+
+    #[test]
+    fn defaults_apply_to_missing_arguments() {
+        let t = transform(json!({"defaults": {"limit": 20}}));
+        assert_eq!(t.rewrite(Value::Null).unwrap(), json!({"limit": 20}));
+    }
+
+    #[test]
+    fn coerce_converts_supported_values() {
+        let t = transform(json!({"coerce": {
+            "limit": "integer",
+            "ratio": "number",
+            "exact": "boolean",
+            "id": "string"
+        }}));
+        assert_eq!(
+            t.rewrite(json!({"limit": "20", "ratio": "0.5", "exact": "TRUE", "id": 42}))
+                .unwrap(),
+            json!({"limit": 20, "ratio": 0.5, "exact": true, "id": "42"})
+        );
+    }
+
+    #[test]
+    fn coerce_accepts_integral_float_as_integer() {
+        let t = transform(json!({"coerce": {"limit": "integer"}}));
+        assert_eq!(
+            t.rewrite(json!({"limit": 20.0})).unwrap(),
+            json!({"limit": 20})
+        );
+    }
+
+    #[test]
+    fn coerce_rejects_unconvertible_value() {
+        let t = transform(json!({"coerce": {"limit": "integer"}}));
+        let err = t.rewrite(json!({"limit": "twenty"})).unwrap_err();
+        assert!(matches!(err, Error::JsonRpc { code: -32602, .. }));
+    }
+
+    #[test]
+    fn coerce_runs_after_rename_and_defaults() {
+        let t = transform(json!({
+            "rename": {"count": "limit"},
+            "defaults": {"page": "1"},
+            "coerce": {"limit": "integer", "page": "integer"}
+        }));
+        assert_eq!(
+            t.rewrite(json!({"count": "5"})).unwrap(),
+            json!({"limit": 5, "page": 1})
+        );
+    }
+
+    #[tokio::test]
+    async fn transform_invoke_rewrites_arguments() {
+        let t = transform(json!({"defaults": {"limit": 20}}));
+        let (tool, args) = t
+            .transform_invoke("search", json!({}))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tool, "search");
+        assert_eq!(args, json!({"limit": 20}));
+    }
+}
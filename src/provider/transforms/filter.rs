@@ -4,7 +4,8 @@
 //!
 //! Pattern matching supports:
 //! - Exact names: `"gmail_search"` matches only that tool.
-//! - Glob suffix: `"gmail_*"` matches any tool starting with `"gmail_"`.
+//! - Glob wildcards: `*` matches any run of characters anywhere in the
+//!   pattern — `"gmail_*"`, `"*_read"`, and `"*delete*"` all work.
 //!
 //! # Precedence
 //!
@@ -75,15 +76,27 @@ impl FilterTransform {
 
 /// Match a pattern against a tool name.
 ///
-/// Supports `*` as a wildcard suffix only (e.g. `"gmail_*"` matches
-/// any tool starting with `"gmail_"`).  For simplicity, `*` anywhere
-/// else in the pattern is treated as a literal character.
+/// `*` matches any (possibly empty) run of characters and may appear any
+/// number of times (e.g. `"gmail_*"`, `"*_read"`, `"*delete*"`). A pattern
+/// without `*` must match the name exactly.
 fn matches_pattern(pattern: &str, name: &str) -> bool {
-    if let Some(prefix) = pattern.strip_suffix('*') {
-        name.starts_with(prefix)
-    } else {
-        pattern == name
+    if !pattern.contains('*') {
+        return pattern == name;
     }
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut middle: Vec<&str> = parts.collect();
+    let last = middle.pop().unwrap_or_default();
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
 }
 
 #[async_trait]
@@ -145,6 +158,17 @@ mod tests {
         assert!(!matches_pattern("gmail_*", "brave_search"));
     }
 
+    #[test]
+    fn pattern_leading_and_infix_wildcards() {
+        assert!(matches_pattern("*_read", "file_read"));
+        assert!(!matches_pattern("*_read", "file_reader"));
+        assert!(matches_pattern("*delete*", "bulk_delete_all"));
+        assert!(matches_pattern("*delete*", "delete"));
+        assert!(!matches_pattern("*delete*", "remove"));
+        assert!(matches_pattern("gh_*_list", "gh_repo_list"));
+        assert!(!matches_pattern("a*a", "a"));
+    }
+
     #[test]
     fn pattern_star_only_matches_everything() {
         assert!(matches_pattern("*", "any_tool"));
@@ -13,19 +13,25 @@
 //! | [`FilterTransform`] | Allow/deny tools by exact name or glob pattern |
 //! | [`RenameTransform`] | Rename individual tools |
 //! | [`ResponseTransform`] | Project/redact response fields |
+//! | [`ArgumentTransform`] | Rename, default, and coerce call arguments |
+//! | [`OverrideTransform`] | Apply validator remediations from an overrides file |
 //!
 //! # Transform Pipeline Order
 //!
 //! Fixed order within a `TransformChain`:
 //! `namespace → filter → auth → response`
 
+pub mod arguments;
 pub mod chain;
 pub mod filter;
 pub mod namespace;
+pub mod overrides;
 pub mod rename;
 pub mod response;
 
+pub use arguments::{ArgumentRewriteConfig, ArgumentTransform, CoerceType};
 pub use filter::FilterTransform;
 pub use namespace::NamespaceTransform;
+pub use overrides::{OverrideTransform, ToolOverride, ToolOverrides};
 pub use rename::RenameTransform;
 pub use response::ResponseTransform;
@@ -51,12 +51,14 @@ impl NamespaceTransform {
     }
 
     /// Build the full prefixed name.
-    fn prefixed(&self, name: &str) -> String {
+    #[must_use]
+    pub fn prefixed(&self, name: &str) -> String {
         format!("{}{}{name}", self.prefix, self.separator)
     }
 
     /// Strip the prefix if present; return original if not.
-    fn strip(&self, name: &str) -> String {
+    #[must_use]
+    pub fn strip(&self, name: &str) -> String {
         let full_prefix = format!("{}{}", self.prefix, self.separator);
         name.strip_prefix(&full_prefix).unwrap_or(name).to_string()
     }
@@ -0,0 +1,354 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! `OverrideTransform` — apply per-tool remediations from an overrides file.
+//!
+//! `mcp-gateway validate --server <s> --fix` cannot edit third-party
+//! backends, so it writes concrete remediations to a [`ToolOverrides`] file
+//! instead: renames, rewritten descriptions, annotation hints and JSON merge
+//! patches (RFC 7396) that tighten the input schema. This transform applies
+//! the entries for one provider.
+//!
+//! # Behaviour
+//!
+//! `list_tools`: rewrites each overridden tool (schema patch, annotations,
+//! description, then rename).
+//! `transform_invoke`: maps a renamed tool back to its upstream name.
+//! `transform_result`: passes through unchanged.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::protocol::{Tool, ToolAnnotations};
+use crate::{Error, Result, provider::Transform};
+
+/// Current overrides file format version.
+pub const OVERRIDES_VERSION: u32 = 1;
+
+/// Overrides file (JSON).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolOverrides {
+    /// File format version.
+    pub version: u32,
+    /// Entries keyed by `server:tool` (the tool name as the server lists it).
+    #[serde(default)]
+    pub tools: BTreeMap<String, ToolOverride>,
+}
+
+/// Remediations for one tool. Unset fields leave the tool unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolOverride {
+    /// Name to expose instead of the upstream one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rename: Option<String>,
+    /// Replacement description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Hints merged over the tool's own annotations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+    /// JSON merge patch applied to `inputSchema`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_schema_patch: Option<Value>,
+}
+
+impl Default for ToolOverrides {
+    fn default() -> Self {
+        Self {
+            version: OVERRIDES_VERSION,
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+impl ToolOverrides {
+    /// Read an overrides file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the file cannot be read or parsed, or was
+    /// written by a newer format version.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read tool overrides {}: {e}",
+                path.display()
+            ))
+        })?;
+        let overrides: Self = serde_json::from_str(&content)
+            .map_err(|e| Error::Config(format!("Invalid tool overrides: {e}")))?;
+        if overrides.version > OVERRIDES_VERSION {
+            return Err(Error::Config(format!(
+                "Tool overrides version {} is newer than supported ({OVERRIDES_VERSION})",
+                overrides.version
+            )));
+        }
+        Ok(overrides)
+    }
+
+    /// Write the overrides as pretty-printed JSON, creating parent directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Replace every entry of `server` with `entries` (keyed by tool name).
+    pub fn replace_server(&mut self, server: &str, entries: BTreeMap<String, ToolOverride>) {
+        let prefix = format!("{server}:");
+        self.tools.retain(|key, _| !key.starts_with(&prefix));
+        self.tools.extend(
+            entries
+                .into_iter()
+                .map(|(tool, entry)| (format!("{prefix}{tool}"), entry)),
+        );
+    }
+
+    /// Number of tools with entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    /// Returns `true` when there are no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+}
+
+/// Applies the [`ToolOverrides`] entries of one provider.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_gateway::provider::transforms::{OverrideTransform, ToolOverrides};
+///
+/// let t = OverrideTransform::new("github", &ToolOverrides::default());
+/// ```
+pub struct OverrideTransform {
+    /// upstream name → override
+    overrides: HashMap<String, ToolOverride>,
+    /// exposed name → upstream name, for renamed tools
+    reverse: HashMap<String, String>,
+}
+
+impl OverrideTransform {
+    /// Select the entries of `server` from `overrides`.
+    #[must_use]
+    pub fn new(server: &str, overrides: &ToolOverrides) -> Self {
+        let prefix = format!("{server}:");
+        let overrides: HashMap<String, ToolOverride> = overrides
+            .tools
+            .iter()
+            .filter_map(|(key, entry)| {
+                Some((key.strip_prefix(&prefix)?.to_string(), entry.clone()))
+            })
+            .collect();
+        let reverse = overrides
+            .iter()
+            .filter_map(|(tool, entry)| Some((entry.rename.clone()?, tool.clone())))
+            .collect();
+        Self { overrides, reverse }
+    }
+
+    fn apply(&self, mut tool: Tool) -> Tool {
+        let Some(entry) = self.overrides.get(&tool.name) else {
+            return tool;
+        };
+        if let Some(patch) = &entry.input_schema_patch {
+            merge_patch(&mut tool.input_schema, patch);
+        }
+        if let Some(hints) = &entry.annotations {
+            let annotations = tool
+                .annotations
+                .get_or_insert_with(ToolAnnotations::default);
+            annotations.title = hints.title.clone().or_else(|| annotations.title.take());
+            annotations.read_only_hint = hints.read_only_hint.or(annotations.read_only_hint);
+            annotations.destructive_hint = hints.destructive_hint.or(annotations.destructive_hint);
+            annotations.idempotent_hint = hints.idempotent_hint.or(annotations.idempotent_hint);
+            annotations.open_world_hint = hints.open_world_hint.or(annotations.open_world_hint);
+        }
+        if let Some(description) = &entry.description {
+            tool.description = Some(description.clone());
+        }
+        if let Some(rename) = &entry.rename {
+            tool.name.clone_from(rename);
+        }
+        tool
+    }
+}
+
+/// Apply an RFC 7396 JSON merge patch to `target`.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Transform for OverrideTransform {
+    async fn transform_tools(&self, tools: Vec<Tool>) -> Result<Vec<Tool>> {
+        Ok(tools.into_iter().map(|tool| self.apply(tool)).collect())
+    }
+
+    async fn transform_invoke(&self, tool: &str, args: Value) -> Result<Option<(String, Value)>> {
+        let resolved = self
+            .reverse
+            .get(tool)
+            .cloned()
+            .unwrap_or_else(|| tool.to_string());
+        Ok(Some((resolved, args)))
+    }
+
+    async fn transform_result(&self, _tool: &str, result: Value) -> Result<Value> {
+        Ok(result)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_tool(name: &str) -> Tool {
+        Tool {
+            name: name.to_string(),
+            title: None,
+            description: Some("Delete".to_string()),
+            input_schema: json!({
+                "type": "object",
+                "additionalProperties": true,
+                "properties": {"id": {"type": "string"}}
+            }),
+            output_schema: None,
+            annotations: None,
+            role: None,
+            projection: None,
+        }
+    }
+
+    fn overrides() -> ToolOverrides {
+        let mut overrides = ToolOverrides::default();
+        overrides.replace_server(
+            "repo",
+            BTreeMap::from([(
+                "deleteRepo".to_string(),
+                ToolOverride {
+                    rename: Some("repo_delete_repo".to_string()),
+                    description: Some("Delete a repository permanently.".to_string()),
+                    annotations: Some(ToolAnnotations {
+                        destructive_hint: Some(true),
+                        ..ToolAnnotations::default()
+                    }),
+                    input_schema_patch: Some(json!({
+                        "additionalProperties": false,
+                        "required": ["id"],
+                        "properties": {"id": {"description": "Repository ID"}}
+                    })),
+                },
+            )]),
+        );
+        overrides
+    }
+
+    #[tokio::test]
+    async fn overrides_rewrite_only_their_servers_tools() {
+        // GIVEN: overrides for one tool of server "repo"
+        let t = OverrideTransform::new("repo", &overrides());
+        let tools = vec![make_tool("deleteRepo"), make_tool("other")];
+
+        // WHEN: transforming the tool list
+        let result = t.transform_tools(tools).await.unwrap();
+
+        // THEN: the tool is renamed, described, hinted and its schema tightened
+        let fixed = &result[0];
+        assert_eq!(fixed.name, "repo_delete_repo");
+        assert_eq!(
+            fixed.description.as_deref(),
+            Some("Delete a repository permanently.")
+        );
+        assert_eq!(
+            fixed.annotations.as_ref().and_then(|a| a.destructive_hint),
+            Some(true)
+        );
+        assert_eq!(
+            fixed.input_schema,
+            json!({
+                "type": "object",
+                "additionalProperties": false,
+                "required": ["id"],
+                "properties": {"id": {"type": "string", "description": "Repository ID"}}
+            })
+        );
+        assert_eq!(result[1].name, "other");
+
+        // AND: another server's transform leaves the tool alone
+        let elsewhere = OverrideTransform::new("other", &overrides());
+        let untouched = elsewhere
+            .transform_tools(vec![make_tool("deleteRepo")])
+            .await;
+        assert_eq!(untouched.unwrap()[0].name, "deleteRepo");
+    }
+
+    #[tokio::test]
+    async fn renamed_tools_invoke_their_upstream_name() {
+        let t = OverrideTransform::new("repo", &overrides());
+        let (name, _) = t
+            .transform_invoke("repo_delete_repo", json!({}))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(name, "deleteRepo");
+    }
+
+    #[test]
+    fn merge_patch_removes_null_members() {
+        let mut target = json!({"a": 1, "b": {"c": 2, "d": 3}});
+        merge_patch(&mut target, &json!({"a": null, "b": {"d": null, "e": 4}}));
+        assert_eq!(target, json!({"b": {"c": 2, "e": 4}}));
+    }
+
+    #[test]
+    fn overrides_round_trip_and_replace_per_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("overrides.json");
+        let mut written = overrides();
+        written.replace_server(
+            "other",
+            BTreeMap::from([("x".to_string(), ToolOverride::default())]),
+        );
+        written.save(&path).unwrap();
+
+        let mut loaded = ToolOverrides::load(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        loaded.replace_server("repo", BTreeMap::new());
+        assert_eq!(loaded.tools.keys().collect::<Vec<_>>(), ["other:x"]);
+    }
+}
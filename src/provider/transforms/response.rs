@@ -10,7 +10,7 @@ use serde_json::Value;
 
 use crate::protocol::Tool;
 use crate::transform::{TransformConfig, TransformPipeline};
-use crate::{Result, provider::Transform};
+use crate::{Error, Result, provider::Transform};
 
 /// Shapes tool responses using the existing transform pipeline.
 ///
@@ -30,7 +30,7 @@ use crate::{Result, provider::Transform};
 ///     project: vec!["id".to_string(), "subject".to_string()],
 ///     ..Default::default()
 /// };
-/// let t = ResponseTransform::new(&config);
+/// let t = ResponseTransform::new(&config).unwrap();
 /// ```
 pub struct ResponseTransform {
     pipeline: TransformPipeline,
@@ -38,11 +38,14 @@ pub struct ResponseTransform {
 
 impl ResponseTransform {
     /// Compile a `TransformConfig` into an executable response transform.
-    #[must_use]
-    pub fn new(config: &TransformConfig) -> Self {
-        Self {
-            pipeline: TransformPipeline::compile(config),
-        }
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when the transform's `query` is invalid.
+    pub fn new(config: &TransformConfig) -> Result<Self> {
+        let pipeline = TransformPipeline::compile(config)
+            .map_err(|e| Error::Config(format!("response transform: {e}")))?;
+        Ok(Self { pipeline })
     }
 }
 
@@ -79,7 +82,7 @@ mod tests {
     #[tokio::test]
     async fn response_transform_noop_passes_result_through() {
         // GIVEN: default (empty) transform config
-        let t = ResponseTransform::new(&TransformConfig::default());
+        let t = ResponseTransform::new(&TransformConfig::default()).unwrap();
         let val = json!({"a": 1, "b": 2});
 
         // WHEN: transforming result
@@ -96,7 +99,7 @@ mod tests {
             project: vec!["id".to_string()],
             ..Default::default()
         };
-        let t = ResponseTransform::new(&config);
+        let t = ResponseTransform::new(&config).unwrap();
         let val = json!({"id": "abc", "secret": "xyz"});
 
         // WHEN
@@ -110,7 +113,7 @@ mod tests {
     #[tokio::test]
     async fn response_transform_tool_list_unchanged() {
         // GIVEN: any transform
-        let t = ResponseTransform::new(&TransformConfig::default());
+        let t = ResponseTransform::new(&TransformConfig::default()).unwrap();
         let tools = vec![crate::protocol::Tool {
             name: "x".to_string(),
             title: None,
@@ -133,7 +136,7 @@ mod tests {
     #[tokio::test]
     async fn response_transform_invoke_passes_through() {
         // GIVEN: any transform
-        let t = ResponseTransform::new(&TransformConfig::default());
+        let t = ResponseTransform::new(&TransformConfig::default()).unwrap();
 
         // WHEN: transform_invoke called
         let result = t
@@ -157,7 +160,7 @@ mod tests {
             }],
             ..Default::default()
         };
-        let t = ResponseTransform::new(&config);
+        let t = ResponseTransform::new(&config).unwrap();
         let val = json!({"message": "Contact user@example.com for details"});
 
         // WHEN
@@ -4,19 +4,33 @@
 //!
 //! Ranks search results by combining text relevance with usage-based popularity.
 //! Synonym expansion allows semantically related words to match with a slight
-//! score discount (0.8×) relative to exact matches.
+//! score discount (0.8×) relative to exact matches; operators can add their
+//! own vocabulary through a synonyms file ([`synonyms`]). Usage can decay with a
+//! configurable half-life so past popularity fades.
+//!
+//! Usage alone rewards tools agents keep *trying*, including broken ones, so
+//! the ranker also counts negative outcomes: invocations that fail, and
+//! invocations the agent abandons by re-running the same search or switching
+//! to another result of that search. Tools with a high negative rate are
+//! down-ranked. Usage within the calling session and its routing profile is
+//! blended in as well ([`SessionContext`]), so results follow the task at
+//! hand rather than only global popularity.
 
 use std::path::Path;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 mod scoring;
+mod session;
+pub mod synonyms;
 
 use scoring::score_text_relevance;
 pub use scoring::{expand_synonyms, is_schema_field_match};
+use session::SessionAffinity;
+pub use session::SessionContext;
 
 #[cfg(test)]
 use scoring::{
@@ -312,10 +326,71 @@ pub struct RankingEvalReport {
     pub improvement_targets: Vec<RankingImprovementTarget>,
 }
 
+/// Invocations a tool needs before negative outcomes affect its rank.
+const MIN_OUTCOME_SAMPLES: u64 = 5;
+/// Score reduction for a tool whose every invocation was negative.
+const NEGATIVE_OUTCOME_PENALTY: f64 = 0.6;
+/// Result keys remembered per search for abandonment detection.
+const TRAIL_RESULTS: usize = 10;
+/// Upper bound on sessions with remembered per-session state.
+const MAX_TRACKED_SESSIONS: usize = 10_000;
+
 /// Search ranker with usage-based weighting
 pub struct SearchRanker {
-    /// Usage counts per tool (key = "server:tool")
-    usage_counts: DashMap<String, AtomicU64>,
+    /// Usage per tool (key = "server:tool")
+    usage_counts: DashMap<String, UsageRecord>,
+    /// Half-life of the usage weight; `None` ranks by lifetime counts.
+    half_life: Option<Duration>,
+    /// Last search and invocation per session (key = session id).
+    trails: DashMap<String, SearchTrail>,
+    /// Session- and profile-local usage; `None` disables personalization.
+    affinity: Option<SessionAffinity>,
+}
+
+/// Lifetime count plus an exponentially decayed usage weight.
+#[derive(Debug, Clone, Copy, Default)]
+struct UsageRecord {
+    count: u64,
+    /// Decayed weight as of `last_used`.
+    weight: f64,
+    /// Unix seconds of the most recent use.
+    last_used: u64,
+    /// Invocations that returned an error.
+    failures: u64,
+    /// Successful invocations the agent walked away from.
+    abandoned: u64,
+}
+
+/// A session's most recent search and the tool it then invoked.
+#[derive(Debug, Default)]
+struct SearchTrail {
+    query: String,
+    /// Top result keys (`server:tool`) of the search.
+    results: Vec<String>,
+    /// Tool invoked since the search and whether it succeeded.
+    invoked: Option<(String, bool)>,
+}
+
+impl UsageRecord {
+    /// Weight decayed from `last_used` to `now`.
+    #[allow(clippy::cast_precision_loss)]
+    fn weight_at(&self, now: u64, half_life: Option<Duration>) -> f64 {
+        match half_life {
+            Some(half_life) if !half_life.is_zero() => {
+                let elapsed = now.saturating_sub(self.last_used) as f64;
+                self.weight * (-elapsed / half_life.as_secs_f64()).exp2()
+            }
+            _ => self.count as f64,
+        }
+    }
+
+    /// Fraction of invocations that failed or were abandoned, once there
+    /// are enough samples to judge.
+    #[allow(clippy::cast_precision_loss)]
+    fn negative_rate(&self) -> Option<f64> {
+        (self.count >= MIN_OUTCOME_SAMPLES)
+            .then(|| ((self.failures + self.abandoned) as f64 / self.count as f64).min(1.0))
+    }
 }
 
 impl SearchRanker {
@@ -324,25 +399,156 @@ impl SearchRanker {
     pub fn new() -> Self {
         Self {
             usage_counts: DashMap::new(),
+            half_life: None,
+            trails: DashMap::new(),
+            affinity: Some(SessionAffinity::default()),
         }
     }
 
+    /// Decay usage weight with the given half-life, so a use `half_life`
+    /// ago counts half as much as one now. Lifetime counts are unaffected.
+    #[must_use]
+    pub fn with_half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = (!half_life.is_zero()).then_some(half_life);
+        self
+    }
+
+    /// Enable or disable per-session personalization (enabled by default).
+    #[must_use]
+    pub fn with_personalization(mut self, enabled: bool) -> Self {
+        self.affinity = enabled.then(SessionAffinity::default);
+        self
+    }
+
     /// Record a tool usage
     pub fn record_use(&self, server: &str, tool: &str) {
+        self.record_use_at(server, tool, now_secs());
+    }
+
+    fn record_use_at(&self, server: &str, tool: &str, now: u64) {
+        let key = format!("{server}:{tool}");
+        let mut record = self.usage_counts.entry(key).or_default();
+        record.weight = record.weight_at(now, self.half_life) + 1.0;
+        record.count += 1;
+        record.last_used = now;
+    }
+
+    /// Record a tool usage within a session and routing profile.
+    ///
+    /// Feeds personalization only; call [`Self::record_use`] for the global
+    /// count.
+    pub fn record_session_use(&self, context: SessionContext<'_>, server: &str, tool: &str) {
+        if let Some(ref affinity) = self.affinity {
+            affinity.record(context, server, tool);
+        }
+    }
+
+    /// Drop everything remembered about a session.
+    pub fn forget_session(&self, session_id: &str) {
+        self.trails.remove(session_id);
+        if let Some(ref affinity) = self.affinity {
+            affinity.forget(session_id);
+        }
+    }
+
+    /// Record the outcome of an invocation.
+    ///
+    /// Failures count against the tool directly. With a `session_id`, the
+    /// invocation also joins the session's search trail: if the previous
+    /// tool invoked from the same search succeeded but the agent moved on to
+    /// a different result, the previous tool counts as abandoned.
+    pub fn record_outcome(
+        &self,
+        session_id: Option<&str>,
+        server: &str,
+        tool: &str,
+        success: bool,
+    ) {
+        let key = format!("{server}:{tool}");
+        if !success && let Some(mut record) = self.usage_counts.get_mut(&key) {
+            record.failures += 1;
+        }
+        let Some(sid) = session_id else {
+            return;
+        };
+        let Some(mut trail) = self.trails.get_mut(sid) else {
+            return;
+        };
+        if !trail.results.contains(&key) {
+            return;
+        }
+        if let Some((previous, true)) = &trail.invoked
+            && *previous != key
+        {
+            self.mark_abandoned(previous);
+        }
+        trail.invoked = Some((key, success));
+    }
+
+    /// Record a search so later invocations can be attributed to it.
+    ///
+    /// Repeating the same query after a successful invocation marks that
+    /// invocation as abandoned: the result did not answer the agent's need.
+    pub fn record_search(&self, session_id: &str, query: &str, results: &[SearchResult]) {
+        let query = query.trim().to_lowercase();
+        if let Some(trail) = self.trails.get(session_id)
+            && trail.query == query
+            && let Some((previous, true)) = &trail.invoked
+        {
+            self.mark_abandoned(previous);
+        }
+        if self.trails.len() >= MAX_TRACKED_SESSIONS && !self.trails.contains_key(session_id) {
+            self.trails.clear();
+        }
+        self.trails.insert(
+            session_id.to_string(),
+            SearchTrail {
+                query,
+                results: results
+                    .iter()
+                    .take(TRAIL_RESULTS)
+                    .map(|r| format!("{}:{}", r.server, r.tool))
+                    .collect(),
+                invoked: None,
+            },
+        );
+    }
+
+    fn mark_abandoned(&self, key: &str) {
+        if let Some(mut record) = self.usage_counts.get_mut(key) {
+            record.abandoned += 1;
+        }
+    }
+
+    /// Fraction of a tool's invocations that failed or were abandoned, or
+    /// `None` until it has enough invocations to judge.
+    #[must_use]
+    pub fn negative_rate(&self, server: &str, tool: &str) -> Option<f64> {
         let key = format!("{server}:{tool}");
         self.usage_counts
-            .entry(key)
-            .or_insert_with(|| AtomicU64::new(0))
-            .fetch_add(1, Ordering::Relaxed);
+            .get(&key)
+            .and_then(|entry| entry.negative_rate())
     }
 
     /// Get usage count for a tool
     #[must_use]
     pub fn usage_count(&self, server: &str, tool: &str) -> u64 {
+        let key = format!("{server}:{tool}");
+        self.usage_counts.get(&key).map_or(0, |entry| entry.count)
+    }
+
+    /// Usage weight used for ranking: the decayed weight when a half-life
+    /// is configured, otherwise the lifetime count.
+    #[must_use]
+    pub fn usage_weight(&self, server: &str, tool: &str) -> f64 {
+        self.usage_weight_at(server, tool, now_secs())
+    }
+
+    fn usage_weight_at(&self, server: &str, tool: &str, now: u64) -> f64 {
         let key = format!("{server}:{tool}");
         self.usage_counts
             .get(&key)
-            .map_or(0, |entry| entry.load(Ordering::Relaxed))
+            .map_or(0.0, |entry| entry.weight_at(now, self.half_life))
     }
 
     /// Rank search results by relevance and usage.
@@ -365,10 +571,37 @@ impl SearchRanker {
     /// - 5: name contains the full query as a substring
     /// - 2: description contains the full query as a substring
     ///
-    /// Usage factor: `log2(usage_count + 1) * 0.15` (multiplicative)
+    /// Usage factor: `log2(usage_weight + 1) * 0.15` (multiplicative)
     /// - 0 uses → ×1.0, 4 uses → ×1.35, 10 uses → ×1.52, 100 uses → ×2.0
+    ///
+    /// With a half-life configured, the weight of each use halves every
+    /// half-life, so stale popularity fades instead of pinning old tools.
+    ///
+    /// Negative outcomes: once a tool has enough invocations, its score is
+    /// multiplied by `1 - 0.6 * negative_rate`, where `negative_rate` is the
+    /// fraction of invocations that failed or were abandoned. The observed
+    /// success rate also caps the `success_rate` signal.
+    #[must_use]
+    pub fn rank(&self, results: Vec<SearchResult>, query: &str) -> Vec<SearchResult> {
+        self.rank_for(results, query, SessionContext::default())
+    }
+
+    /// Rank search results for a specific session.
+    ///
+    /// Same as [`Self::rank`], with a session boost added to the usage
+    /// factor: `0.3 * log2(n + 1)` for `n` uses of the tool in this session,
+    /// `0.1 * log2(n + 1)` for `n` uses of its backend in this session, and
+    /// `0.1 * log2(n + 1)` for `n` uses of the tool by sessions of the same
+    /// routing profile. Like global usage, the boost is multiplicative, so
+    /// it reorders comparable matches without promoting irrelevant tools.
     #[must_use]
-    pub fn rank(&self, mut results: Vec<SearchResult>, query: &str) -> Vec<SearchResult> {
+    pub fn rank_for(
+        &self,
+        mut results: Vec<SearchResult>,
+        query: &str,
+        context: SessionContext<'_>,
+    ) -> Vec<SearchResult> {
+        let now = now_secs();
         let query_lower = query.to_lowercase();
         let words: Vec<&str> = query_lower.split_whitespace().collect();
 
@@ -387,19 +620,44 @@ impl SearchRanker {
                 score_text_relevance(&result.tool, &result.description, &query_lower, &words);
 
             let usage = self.usage_count(&result.server, &result.tool);
-            #[allow(clippy::cast_precision_loss)]
-            let usage_factor = if usage > 0 {
-                ((usage + 1) as f64).log2() * 0.15
+            let weight = self.usage_weight_at(&result.server, &result.tool, now);
+            let usage_factor = if weight > 0.0 {
+                (weight + 1.0).log2() * 0.15
             } else {
                 0.0
             };
 
+            let session_boost = self
+                .affinity
+                .as_ref()
+                .map_or(0.0, |a| a.boost(context, &result.server, &result.tool));
+            let negative_rate = self.negative_rate(&result.server, &result.tool);
+
             result.signals.relevance = text_relevance;
             result.signals.usage_count = usage;
             result.signals.user_feedback = usage_factor;
+            if let Some(rate) = negative_rate {
+                result.signals.success_rate = result.signals.success_rate.min(1.0 - rate);
+            }
 
-            result.score = text_relevance * (1.0 + usage_factor) * result.signals.multiplier();
+            let penalty = negative_rate.map_or(1.0, |rate| 1.0 - NEGATIVE_OUTCOME_PENALTY * rate);
+            result.score = text_relevance
+                * (1.0 + usage_factor + session_boost)
+                * result.signals.multiplier()
+                * penalty;
             result.explanation = explanation_for(result);
+            if session_boost > 0.0 {
+                result
+                    .explanation
+                    .reasons
+                    .push("session_affinity_boost".to_string());
+            }
+            if penalty < 1.0 {
+                result
+                    .explanation
+                    .reasons
+                    .push("negative_outcomes_downgraded".to_string());
+            }
         }
 
         results.retain(|result| result.exclusion.is_none());
@@ -457,10 +715,15 @@ impl SearchRanker {
             .iter()
             .map(|entry| {
                 let parts: Vec<&str> = entry.key().split(':').collect();
+                let record = entry.value();
                 UsageEntry {
                     server: parts.first().unwrap_or(&"").to_string(),
                     tool: parts.get(1).unwrap_or(&"").to_string(),
-                    count: entry.value().load(Ordering::Relaxed),
+                    count: record.count,
+                    weight: Some(record.weight),
+                    last_used: Some(record.last_used),
+                    failures: record.failures,
+                    abandoned: record.abandoned,
                 }
             })
             .collect();
@@ -471,16 +734,28 @@ impl SearchRanker {
 
     /// Load usage counts from JSON file
     ///
+    /// Files written before usage decay existed carry only counts; those
+    /// are loaded as if last used now, so decay starts from load time.
+    ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be read or JSON is invalid.
+    #[allow(clippy::cast_precision_loss)]
     pub fn load(&self, path: &Path) -> std::io::Result<()> {
         let content = std::fs::read_to_string(path)?;
         let entries: Vec<UsageEntry> = serde_json::from_str(&content)?;
+        let now = now_secs();
 
         for entry in entries {
             let key = format!("{}:{}", entry.server, entry.tool);
-            self.usage_counts.insert(key, AtomicU64::new(entry.count));
+            let record = UsageRecord {
+                count: entry.count,
+                weight: entry.weight.unwrap_or(entry.count as f64),
+                last_used: entry.last_used.unwrap_or(now),
+                failures: entry.failures,
+                abandoned: entry.abandoned,
+            };
+            self.usage_counts.insert(key, record);
         }
 
         Ok(())
@@ -489,6 +764,10 @@ impl SearchRanker {
     /// Clear all usage counts
     pub fn clear(&self) {
         self.usage_counts.clear();
+        self.trails.clear();
+        if let Some(ref affinity) = self.affinity {
+            affinity.clear();
+        }
     }
 }
 
@@ -539,6 +818,25 @@ struct UsageEntry {
     server: String,
     tool: String,
     count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    weight: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_used: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    failures: u64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    abandoned: u64,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 /// Convert a JSON search result to a `SearchResult`
@@ -1,5 +1,7 @@
 // SPDX-FileCopyrightText: 2026 Mikko Parkkola
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use super::synonyms::expand;
+
 /// Return the synonym group for a given word (all lowercase).
 ///
 /// Each word maps to the *other* members of its group. Matches against synonyms
@@ -13,6 +15,8 @@
 /// "send" | "deliver" | "publish" | "emit" => &["send", "deliver", "publish", "emit"],
 /// ```
 /// Every word in the group must map to the full group (bidirectional).
+/// Organisation-specific vocabulary belongs in a synonyms file instead
+/// (see [`super::synonyms`]).
 #[must_use]
 pub fn expand_synonyms(word: &str) -> &'static [&'static str] {
     match word {
@@ -107,17 +111,15 @@ pub fn expand_synonyms(word: &str) -> &'static [&'static str] {
 pub(super) const SYNONYM_MULTIPLIER: f64 = 0.8;
 
 /// Return `true` if `text` contains `word` as a substring, or contains any
-/// synonym of `word`. The `synonym_hit` output flag is set to `true` when a
+/// built-in or custom synonym of `word`. The `synonym_hit` output flag is set to `true` when a
 /// synonym (not the word itself) produced the match; callers can apply the
 /// `SYNONYM_MULTIPLIER` in that case.
 fn text_contains_with_synonyms(text: &str, word: &str) -> (bool, bool) {
     if text.contains(word) {
         return (true, false);
     }
-    for syn in expand_synonyms(word) {
-        if *syn != word && text.contains(*syn) {
-            return (true, true);
-        }
+    if expand(word).iter().any(|syn| text.contains(syn.as_str())) {
+        return (true, true);
     }
     (false, false)
 }
@@ -239,14 +241,12 @@ pub(super) fn score_text_relevance(
         return 2.0;
     }
     if words.len() == 1 {
-        for syn in expand_synonyms(query) {
-            if *syn != query {
-                if tool_lower.contains(syn) {
-                    return 5.0 * SYNONYM_MULTIPLIER;
-                }
-                if desc_lower.contains(syn) {
-                    return 2.0 * SYNONYM_MULTIPLIER;
-                }
+        for syn in expand(query) {
+            if tool_lower.contains(syn.as_str()) {
+                return 5.0 * SYNONYM_MULTIPLIER;
+            }
+            if desc_lower.contains(syn.as_str()) {
+                return 2.0 * SYNONYM_MULTIPLIER;
             }
         }
     }
@@ -305,9 +305,9 @@ pub(super) fn is_keyword_match_with_synonyms(desc_lower: &str, word: &str) -> bo
     if is_keyword_match(desc_lower, word) {
         return true;
     }
-    expand_synonyms(word)
+    expand(word)
         .iter()
-        .any(|syn| *syn != word && is_keyword_match(desc_lower, syn))
+        .any(|syn| is_keyword_match(desc_lower, syn))
 }
 
 fn count_keyword_matches(desc_lower: &str, words: &[&str]) -> usize {
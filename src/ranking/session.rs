@@ -0,0 +1,104 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Session-local ranking affinity.
+//!
+//! Global popularity says which tools agents use in general; what a session
+//! already used says what *this* task is about. A session that has been
+//! calling GitHub tools should see `github:search_code` ahead of
+//! `web:search` for the query "search", while a research session should see
+//! the reverse. Usage is also aggregated per routing profile so a fresh
+//! session bound to a profile (e.g. `research`) starts from what sessions of
+//! that profile typically use.
+
+use std::collections::HashMap;
+
+use dashmap::DashMap;
+
+use super::MAX_TRACKED_SESSIONS;
+
+/// Boost per `log2(uses + 1)` of the same tool in this session.
+const SESSION_TOOL_WEIGHT: f64 = 0.3;
+/// Boost per `log2(uses + 1)` of any tool on the same backend in this session.
+const SESSION_BACKEND_WEIGHT: f64 = 0.1;
+/// Boost per `log2(uses + 1)` of the tool across sessions of the profile.
+const PROFILE_TOOL_WEIGHT: f64 = 0.1;
+
+/// Where a search is being ranked for.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionContext<'a> {
+    /// Calling session, if known.
+    pub session_id: Option<&'a str>,
+    /// Active routing profile, or `None` for the gateway default (which
+    /// every session shares and so says nothing about the task).
+    pub profile: Option<&'a str>,
+}
+
+/// Tools and backends used by one session.
+#[derive(Debug, Default)]
+struct SessionUsage {
+    tools: HashMap<String, u64>,
+    backends: HashMap<String, u64>,
+}
+
+/// Per-session and per-profile usage counts (in memory only).
+#[derive(Debug, Default)]
+pub(super) struct SessionAffinity {
+    sessions: DashMap<String, SessionUsage>,
+    /// Tool usage per profile (key = profile name, inner key = "server:tool").
+    profiles: DashMap<String, HashMap<String, u64>>,
+}
+
+impl SessionAffinity {
+    pub(super) fn record(&self, context: SessionContext<'_>, server: &str, tool: &str) {
+        let key = format!("{server}:{tool}");
+        if let Some(sid) = context.session_id {
+            if self.sessions.len() >= MAX_TRACKED_SESSIONS && !self.sessions.contains_key(sid) {
+                self.sessions.clear();
+            }
+            let mut usage = self.sessions.entry(sid.to_string()).or_default();
+            *usage.tools.entry(key.clone()).or_default() += 1;
+            *usage.backends.entry(server.to_string()).or_default() += 1;
+        }
+        if let Some(profile) = context.profile {
+            *self
+                .profiles
+                .entry(profile.to_string())
+                .or_default()
+                .entry(key)
+                .or_default() += 1;
+        }
+    }
+
+    /// Additive usage factor for `server:tool` in `context` (`0.0` when the
+    /// session and profile have no history).
+    #[allow(clippy::cast_precision_loss)]
+    pub(super) fn boost(&self, context: SessionContext<'_>, server: &str, tool: &str) -> f64 {
+        let key = format!("{server}:{tool}");
+        let factor = |uses: u64, weight: f64| (uses as f64 + 1.0).log2() * weight;
+        let mut boost = 0.0;
+        if let Some(usage) = context.session_id.and_then(|sid| self.sessions.get(sid)) {
+            boost += factor(
+                usage.tools.get(&key).copied().unwrap_or(0),
+                SESSION_TOOL_WEIGHT,
+            );
+            boost += factor(
+                usage.backends.get(server).copied().unwrap_or(0),
+                SESSION_BACKEND_WEIGHT,
+            );
+        }
+        if let Some(tools) = context.profile.and_then(|p| self.profiles.get(p)) {
+            boost += factor(tools.get(&key).copied().unwrap_or(0), PROFILE_TOOL_WEIGHT);
+        }
+        boost
+    }
+
+    /// Forget a session's usage (e.g. when the session ends).
+    pub(super) fn forget(&self, session_id: &str) {
+        self.sessions.remove(session_id);
+    }
+
+    pub(super) fn clear(&self) {
+        self.sessions.clear();
+        self.profiles.clear();
+    }
+}
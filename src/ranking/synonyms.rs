@@ -0,0 +1,295 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Operator-supplied synonyms merged with the built-in table.
+//!
+//! The built-in [`expand_synonyms`] table covers generic verbs; it cannot know
+//! an organisation's vocabulary ("PR", "incident"). A YAML synonyms file adds
+//! domain terms:
+//!
+//! ```yaml
+//! # Bidirectional groups: every member expands to the others.
+//! groups:
+//!   - [incident, alert, outage]
+//! # One-way aliases: the key expands to the listed terms only.
+//! aliases:
+//!   pr: [pull request, merge request]
+//! ```
+//!
+//! The table is process-wide so every matcher (search filtering and
+//! ranking) sees the same vocabulary, and [`SynonymWatcher`] swaps it in
+//! place when the file changes.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::{Mutex, RwLock};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use super::expand_synonyms;
+use crate::{Error, Result};
+
+/// Custom synonyms currently in effect (empty until a file is loaded).
+static CUSTOM: LazyLock<RwLock<Arc<SynonymTable>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(SynonymTable::default())));
+
+#[cfg(test)]
+thread_local! {
+    /// Table shadowing [`CUSTOM`] on the current test thread, so tests never
+    /// change the vocabulary other tests rank with.
+    static SCOPED: std::cell::RefCell<Option<Arc<SynonymTable>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// On-disk synonyms file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SynonymFile {
+    groups: Vec<Vec<String>>,
+    aliases: HashMap<String, Vec<String>>,
+}
+
+/// Custom synonym lookup table (all entries lowercase).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SynonymTable {
+    entries: HashMap<String, Vec<String>>,
+}
+
+impl SynonymTable {
+    /// Parse a synonyms YAML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the YAML is malformed or has unknown keys.
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let file: SynonymFile = serde_yaml::from_str(yaml)
+            .map_err(|e| Error::Config(format!("Invalid synonyms file: {e}")))?;
+        let mut table = Self::default();
+        for group in &file.groups {
+            let members: Vec<String> = group.iter().map(|w| normalize(w)).collect();
+            for member in &members {
+                for other in &members {
+                    if other != member {
+                        table.add(member, other);
+                    }
+                }
+            }
+        }
+        for (word, expansions) in &file.aliases {
+            let word = normalize(word);
+            for expansion in expansions {
+                table.add(&word, &normalize(expansion));
+            }
+        }
+        Ok(table)
+    }
+
+    /// Read and parse a synonyms file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read synonyms file {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::from_yaml(&content)
+    }
+
+    fn add(&mut self, word: &str, synonym: &str) {
+        if word.is_empty() || synonym.is_empty() || word == synonym {
+            return;
+        }
+        let entry = self.entries.entry(word.to_string()).or_default();
+        if !entry.iter().any(|s| s == synonym) {
+            entry.push(synonym.to_string());
+        }
+    }
+
+    /// Number of words with custom synonyms.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` when the table has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Custom synonyms of `word` (lowercase), excluding the built-in ones.
+    #[must_use]
+    pub fn get(&self, word: &str) -> &[String] {
+        self.entries.get(word).map_or(&[], Vec::as_slice)
+    }
+}
+
+fn normalize(word: &str) -> String {
+    word.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Replace the process-wide custom synonym table.
+pub fn install(table: SynonymTable) {
+    *CUSTOM.write() = Arc::new(table);
+}
+
+/// The custom synonym table currently in effect.
+#[must_use]
+pub fn custom() -> Arc<SynonymTable> {
+    #[cfg(test)]
+    if let Some(table) = SCOPED.with_borrow(Clone::clone) {
+        return table;
+    }
+    Arc::clone(&CUSTOM.read())
+}
+
+/// Use `table` on the current thread until the guard is dropped, which
+/// restores the table in effect before.
+#[cfg(test)]
+pub(crate) fn scoped(table: SynonymTable) -> ScopedSynonyms {
+    ScopedSynonyms {
+        previous: SCOPED.replace(Some(Arc::new(table))),
+    }
+}
+
+/// Guard returned by [`scoped`].
+#[cfg(test)]
+pub(crate) struct ScopedSynonyms {
+    previous: Option<Arc<SynonymTable>>,
+}
+
+#[cfg(test)]
+impl Drop for ScopedSynonyms {
+    fn drop(&mut self) {
+        SCOPED.set(self.previous.take());
+    }
+}
+
+/// Built-in plus custom synonyms of `word`, excluding `word` itself.
+///
+/// Callers apply the usual synonym discount to matches found this way.
+#[must_use]
+pub fn expand(word: &str) -> Vec<String> {
+    let mut synonyms: Vec<String> = expand_synonyms(word)
+        .iter()
+        .filter(|s| **s != word)
+        .map(|s| (*s).to_string())
+        .collect();
+    let custom = custom();
+    if !custom.is_empty() {
+        let lower = word.to_lowercase();
+        for synonym in custom.get(&lower) {
+            if !synonyms.contains(synonym) {
+                synonyms.push(synonym.clone());
+            }
+        }
+    }
+    synonyms
+}
+
+/// Load `path` into the process-wide table, logging the outcome.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed; the previous
+/// table is kept in that case.
+pub fn load_file(path: &Path) -> Result<usize> {
+    let table = SynonymTable::load(path)?;
+    let words = table.len();
+    install(table);
+    info!(path = %path.display(), words, "Loaded custom synonyms");
+    Ok(words)
+}
+
+/// Reloads the synonyms file whenever it changes.
+pub struct SynonymWatcher {
+    /// Kept alive to prevent the OS watcher from being dropped.
+    _watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl SynonymWatcher {
+    /// Load `path` now and reload it on every change until shutdown.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial load fails or the watcher cannot be
+    /// created.
+    pub fn start(path: PathBuf, shutdown_rx: tokio::sync::broadcast::Receiver<()>) -> Result<Self> {
+        load_file(&path)?;
+
+        let (event_tx, event_rx) = mpsc::channel(16);
+        let file_name = path.file_name().map(ToOwned::to_owned);
+        let mut watcher = RecommendedWatcher::new(
+            move |result: std::result::Result<Event, notify::Error>| {
+                let Ok(event) = result else { return };
+                let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event
+                        .paths
+                        .iter()
+                        .any(|p| p.file_name() == file_name.as_deref());
+                if relevant {
+                    let _ = event_tx.try_send(());
+                }
+            },
+            Config::default().with_poll_interval(Duration::from_secs(2)),
+        )
+        .map_err(|e| Error::ConfigWatcher(format!("Failed to create synonyms watcher: {e}")))?;
+
+        // Watch the parent directory so editors that replace the file
+        // (write-to-temp + rename) are still picked up.
+        let dir = path
+            .parent()
+            .filter(|d| !d.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::ConfigWatcher(format!("Failed to watch synonyms file: {e}")))?;
+
+        Self::spawn_reload_task(path, event_rx, shutdown_rx);
+        Ok(Self {
+            _watcher: Mutex::new(Some(watcher)),
+        })
+    }
+
+    fn spawn_reload_task(
+        path: PathBuf,
+        mut event_rx: mpsc::Receiver<()>,
+        mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) {
+        tokio::spawn(async move {
+            const DEBOUNCE: Duration = Duration::from_millis(500);
+            let mut last_event: Option<Instant> = None;
+            let mut interval = tokio::time::interval(Duration::from_millis(100));
+
+            loop {
+                tokio::select! {
+                    Some(()) = event_rx.recv() => {
+                        last_event = Some(Instant::now());
+                    }
+                    _ = interval.tick() => {
+                        if last_event.is_some_and(|t| t.elapsed() >= DEBOUNCE) {
+                            last_event = None;
+                            if let Err(e) = load_file(&path) {
+                                warn!(error = %e, "Synonyms reload failed; keeping previous table");
+                            }
+                        }
+                    }
+                    _ = shutdown_rx.recv() => {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
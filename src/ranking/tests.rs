@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 use super::*;
 
+mod outcomes;
 mod schema;
+mod session;
+mod synonyms;
 
 #[test]
 fn test_record_and_retrieve_usage() {
@@ -98,6 +101,68 @@ fn test_save_and_load() {
     std::fs::remove_file(temp).ok();
 }
 
+#[test]
+fn usage_weight_halves_every_half_life() {
+    let ranker = SearchRanker::new().with_half_life(Duration::from_secs(100));
+    for _ in 0..8 {
+        ranker.record_use_at("s1", "t1", 1_000);
+    }
+
+    assert!((ranker.usage_weight_at("s1", "t1", 1_000) - 8.0).abs() < 1e-9);
+    assert!((ranker.usage_weight_at("s1", "t1", 1_100) - 4.0).abs() < 1e-9);
+    assert!((ranker.usage_weight_at("s1", "t1", 1_300) - 1.0).abs() < 1e-9);
+    // Lifetime counts are not decayed.
+    assert_eq!(ranker.usage_count("s1", "t1"), 8);
+}
+
+#[test]
+fn usage_weight_without_half_life_is_lifetime_count() {
+    let ranker = SearchRanker::new().with_half_life(Duration::ZERO);
+    ranker.record_use_at("s1", "t1", 0);
+    ranker.record_use_at("s1", "t1", 0);
+    assert!((ranker.usage_weight_at("s1", "t1", 1_000_000) - 2.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn decayed_ranking_prefers_recent_use_over_stale_popularity() {
+    // GIVEN: "legacy" used heavily a year ago, "current" used a few times today
+    let search_ranker = SearchRanker::new().with_half_life(Duration::from_secs(7 * 86_400));
+    let now = now_secs();
+    for _ in 0..500 {
+        search_ranker.record_use_at("old", "legacy_search", now - 365 * 86_400);
+    }
+    for _ in 0..3 {
+        search_ranker.record_use_at("new", "current_search", now);
+    }
+
+    // WHEN: both match the query equally
+    let ranked = search_ranker.rank(
+        vec![
+            SearchResult::new("old", "legacy_search", "Search issues"),
+            SearchResult::new("new", "current_search", "Search issues"),
+        ],
+        "issues",
+    );
+
+    // THEN: recent use wins
+    assert_eq!(ranked[0].tool, "current_search");
+    assert_eq!(ranked[1].signals.usage_count, 500);
+}
+
+#[test]
+fn load_accepts_count_only_usage_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("usage.json");
+    std::fs::write(&path, r#"[{"server":"s1","tool":"t1","count":6}]"#).unwrap();
+
+    let ranker = SearchRanker::new().with_half_life(Duration::from_secs(3600));
+    ranker.load(&path).unwrap();
+
+    assert_eq!(ranker.usage_count("s1", "t1"), 6);
+    let weight = ranker.usage_weight("s1", "t1");
+    assert!(weight > 5.9 && weight <= 6.0, "{weight}");
+}
+
 #[test]
 fn persisted_usage_feedback_omits_query_and_argument_payloads() {
     let ranker = SearchRanker::new();
@@ -119,8 +184,10 @@ fn persisted_usage_feedback_omits_query_and_argument_payloads() {
         keys,
         vec![
             "count".to_string(),
+            "last_used".to_string(),
             "server".to_string(),
-            "tool".to_string()
+            "tool".to_string(),
+            "weight".to_string()
         ]
     );
     assert!(!content.contains("query"));
@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use super::*;
+
+fn candidates() -> Vec<SearchResult> {
+    vec![
+        SearchResult::new("a", "broken_search", "Search issues"),
+        SearchResult::new("b", "working_search", "Search issues"),
+    ]
+}
+
+fn invoke(ranker: &SearchRanker, session: Option<&str>, server: &str, tool: &str, ok: bool) {
+    ranker.record_use(server, tool);
+    ranker.record_outcome(session, server, tool, ok);
+}
+
+#[test]
+fn negative_rate_needs_minimum_samples() {
+    let ranker = SearchRanker::new();
+    for _ in 0..MIN_OUTCOME_SAMPLES - 1 {
+        invoke(&ranker, None, "a", "broken_search", false);
+    }
+    assert_eq!(ranker.negative_rate("a", "broken_search"), None);
+
+    invoke(&ranker, None, "a", "broken_search", true);
+    let rate = ranker.negative_rate("a", "broken_search").unwrap();
+    assert!((rate - 0.8).abs() < f64::EPSILON, "{rate}");
+}
+
+#[test]
+fn failing_tool_drops_below_working_tool_despite_more_use() {
+    // GIVEN: the broken tool is tried far more often than the working one
+    let search_ranker = SearchRanker::new();
+    for _ in 0..20 {
+        invoke(&search_ranker, None, "a", "broken_search", false);
+    }
+    for _ in 0..5 {
+        invoke(&search_ranker, None, "b", "working_search", true);
+    }
+
+    // WHEN
+    let ranked = search_ranker.rank(candidates(), "issues");
+
+    // THEN: failures outweigh popularity and are explained
+    assert_eq!(ranked[0].tool, "working_search");
+    assert!(
+        ranked[1]
+            .explanation
+            .reasons
+            .contains(&"negative_outcomes_downgraded".to_string())
+    );
+    assert!(ranked[1].signals.success_rate.abs() < f64::EPSILON);
+}
+
+#[test]
+fn switching_to_another_result_abandons_previous_tool() {
+    let ranker = SearchRanker::new();
+    let results = candidates();
+
+    // GIVEN: search → broken_search succeeds → agent uses working_search instead
+    ranker.record_search("s1", "issues", &results);
+    invoke(&ranker, Some("s1"), "a", "broken_search", true);
+    invoke(&ranker, Some("s1"), "b", "working_search", true);
+
+    // THEN
+    let record = *ranker.usage_counts.get("a:broken_search").unwrap();
+    assert_eq!(record.abandoned, 1);
+    assert_eq!(
+        ranker
+            .usage_counts
+            .get("b:working_search")
+            .unwrap()
+            .abandoned,
+        0
+    );
+}
+
+#[test]
+fn repeating_the_same_search_abandons_previous_tool() {
+    let ranker = SearchRanker::new();
+    ranker.record_search("s1", "Issues", &candidates());
+    invoke(&ranker, Some("s1"), "a", "broken_search", true);
+
+    ranker.record_search("s1", "issues ", &candidates());
+    assert_eq!(
+        ranker
+            .usage_counts
+            .get("a:broken_search")
+            .unwrap()
+            .abandoned,
+        1
+    );
+
+    // A different query is a new task, not an abandonment.
+    invoke(&ranker, Some("s1"), "b", "working_search", true);
+    ranker.record_search("s1", "create issue", &candidates());
+    assert_eq!(
+        ranker
+            .usage_counts
+            .get("b:working_search")
+            .unwrap()
+            .abandoned,
+        0
+    );
+}
+
+#[test]
+fn failed_invocation_is_not_also_counted_as_abandoned() {
+    let ranker = SearchRanker::new();
+    ranker.record_search("s1", "issues", &candidates());
+    invoke(&ranker, Some("s1"), "a", "broken_search", false);
+    invoke(&ranker, Some("s1"), "b", "working_search", true);
+
+    let record = *ranker.usage_counts.get("a:broken_search").unwrap();
+    assert_eq!((record.failures, record.abandoned), (1, 0));
+}
+
+#[test]
+fn invocations_outside_the_search_results_keep_the_trail() {
+    let ranker = SearchRanker::new();
+    ranker.record_search("s1", "issues", &candidates());
+    invoke(&ranker, Some("s1"), "a", "broken_search", true);
+    invoke(&ranker, Some("s1"), "c", "unrelated", true);
+
+    assert_eq!(
+        ranker
+            .usage_counts
+            .get("a:broken_search")
+            .unwrap()
+            .abandoned,
+        0
+    );
+}
+
+#[test]
+fn outcome_counts_survive_save_and_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("usage.json");
+    let ranker = SearchRanker::new();
+    ranker.record_search("s1", "issues", &candidates());
+    invoke(&ranker, Some("s1"), "a", "broken_search", false);
+    invoke(&ranker, Some("s1"), "b", "working_search", true);
+    ranker.record_search("s1", "issues", &candidates());
+    ranker.save(&path).unwrap();
+
+    let loaded = SearchRanker::new();
+    loaded.load(&path).unwrap();
+
+    let broken = *loaded.usage_counts.get("a:broken_search").unwrap();
+    let working = *loaded.usage_counts.get("b:working_search").unwrap();
+    assert_eq!((broken.failures, working.abandoned), (1, 1));
+}
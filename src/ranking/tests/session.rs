@@ -0,0 +1,92 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use super::*;
+
+fn search_tools() -> Vec<SearchResult> {
+    vec![
+        SearchResult::new("web", "search", "Search the web"),
+        SearchResult::new("github", "search", "Search code in repositories"),
+    ]
+}
+
+fn session<'a>(id: &'a str, profile: Option<&'a str>) -> SessionContext<'a> {
+    SessionContext {
+        session_id: Some(id),
+        profile,
+    }
+}
+
+#[test]
+fn session_usage_reorders_results_per_session() {
+    // GIVEN: globally, web search is more popular
+    let ranker = SearchRanker::new();
+    for _ in 0..10 {
+        ranker.record_use("web", "search");
+    }
+    // ...but the coding session has been working on GitHub
+    ranker.record_session_use(session("coding", None), "github", "create_pr");
+    ranker.record_session_use(session("coding", None), "github", "search");
+    ranker.record_session_use(session("coding", None), "github", "search");
+
+    // WHEN
+    let coding = ranker.rank_for(search_tools(), "search", session("coding", None));
+    let research = ranker.rank_for(search_tools(), "search", session("research", None));
+
+    // THEN: each session sees its own ordering
+    assert_eq!(coding[0].server, "github");
+    assert!(
+        coding[0]
+            .explanation
+            .reasons
+            .contains(&"session_affinity_boost".to_string())
+    );
+    assert_eq!(research[0].server, "web");
+}
+
+#[test]
+fn profile_usage_carries_over_to_new_sessions() {
+    let ranker = SearchRanker::new();
+    for _ in 0..7 {
+        ranker.record_session_use(session("old", Some("coding")), "github", "search");
+    }
+
+    let boosted = ranker.rank_for(search_tools(), "search", session("new", Some("coding")));
+    let other = ranker.rank_for(search_tools(), "search", session("new", Some("research")));
+
+    assert!(boosted[0].score > other.iter().find(|r| r.server == "github").unwrap().score);
+    assert!(other.iter().all(|r| {
+        !r.explanation
+            .reasons
+            .contains(&"session_affinity_boost".to_string())
+    }));
+}
+
+#[test]
+fn personalization_can_be_disabled() {
+    let ranker = SearchRanker::new().with_personalization(false);
+    ranker.record_session_use(session("s1", Some("coding")), "github", "search");
+
+    let personal = ranker.rank_for(search_tools(), "search code", session("s1", Some("coding")));
+    let global = ranker.rank(search_tools(), "search code");
+
+    assert!((personal[0].score - global[0].score).abs() < f64::EPSILON);
+}
+
+#[test]
+fn forget_session_drops_session_usage() {
+    let ranker = SearchRanker::new();
+    ranker.record_session_use(session("s1", None), "github", "search");
+    let before = ranker.rank_for(search_tools(), "search", session("s1", None));
+
+    ranker.forget_session("s1");
+    let after = ranker.rank_for(search_tools(), "search", session("s1", None));
+
+    let score = |results: &[SearchResult]| {
+        results
+            .iter()
+            .find(|r| r.server == "github")
+            .map(|r| r.score)
+            .unwrap()
+    };
+    assert!(score(&before) > score(&after));
+}
@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use super::*;
+use crate::ranking::synonyms::{self, SynonymTable};
+
+const YAML: &str = r"
+groups:
+  - [Incident, alert, outage]
+aliases:
+  PR: [pull   request, merge request]
+";
+
+#[test]
+fn groups_are_bidirectional_and_aliases_one_way() {
+    let table = SynonymTable::from_yaml(YAML).unwrap();
+
+    assert_eq!(table.get("incident"), ["alert", "outage"]);
+    assert_eq!(table.get("outage"), ["incident", "alert"]);
+    assert_eq!(table.get("pr"), ["pull request", "merge request"]);
+    assert!(table.get("pull request").is_empty());
+    assert_eq!(table.len(), 4);
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let err = SynonymTable::from_yaml("synonyms: []").unwrap_err();
+    assert!(err.to_string().contains("Invalid synonyms file"), "{err}");
+}
+
+#[test]
+fn custom_synonyms_merge_with_builtin_table_and_drive_ranking() {
+    // GIVEN: a custom table extending the built-in monitor group, in effect
+    // on this test's thread only
+    let guard = synonyms::scoped(SynonymTable::from_yaml(YAML).unwrap());
+
+    // THEN: built-in members come first, custom members are appended
+    let expanded = synonyms::expand("alert");
+    assert!(expanded.starts_with(&["monitor".to_string()]));
+    assert!(expanded.contains(&"incident".to_string()));
+
+    // AND: jargon matches tool text through the custom alias
+    let ranked = SearchRanker::new().rank(
+        vec![
+            SearchResult::new("git", "create_review", "Open a pull request"),
+            SearchResult::new("git", "list_tags", "List tags"),
+        ],
+        "pr",
+    );
+    assert_eq!(ranked[0].tool, "create_review");
+    assert!(ranked[0].score > 0.0);
+
+    // AND: dropping the guard restores the previous table
+    drop(guard);
+    assert!(synonyms::custom().is_empty());
+}
@@ -0,0 +1,310 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Capability lockfile for reproducible installs
+//!
+//! `capabilities.lock` sits in a capabilities directory and records, per
+//! capability, the installed file, its SHA-256 and — for registry installs
+//! — the registry, version and package it came from. `cap install` pins new
+//! capabilities in it and reinstalls locked ones at exactly the locked
+//! version; with `--frozen` it refuses anything not already locked and
+//! `cap lock --frozen` fails when the directory has drifted from the lock.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use super::publish::package_checksum;
+use crate::capability::parse_capability;
+use crate::{Error, Result};
+
+/// File name of the lockfile inside a capabilities directory.
+pub const LOCKFILE_NAME: &str = "capabilities.lock";
+
+const LOCKFILE_VERSION: u32 = 1;
+
+/// Locked capabilities of one directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// Lockfile format version.
+    pub version: u32,
+    /// Locked capabilities by name.
+    #[serde(default)]
+    pub capabilities: BTreeMap<String, LockedCapability>,
+}
+
+/// One locked capability.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedCapability {
+    /// Installed file, relative to the capabilities directory.
+    pub path: String,
+    /// Lowercase hex SHA-256 of the installed file.
+    pub sha256: String,
+    /// Capability `version:`, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Registry it was installed from; unset for local files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// Package path inside that registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Publisher that signed the package, if it was signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// Base64 Ed25519 signature of the package, checked at load time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// A difference between a directory and its lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Drift {
+    /// Locked but not installed.
+    Missing(String),
+    /// Installed with different content than locked.
+    Changed {
+        /// Capability name.
+        name: String,
+        /// Locked SHA-256.
+        locked: String,
+        /// Installed SHA-256.
+        actual: String,
+    },
+    /// Installed but not locked.
+    Unlocked(String),
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(name) => write!(f, "{name}: locked but not installed"),
+            Self::Changed {
+                name,
+                locked,
+                actual,
+            } => write!(f, "{name}: sha256 {actual} does not match locked {locked}"),
+            Self::Unlocked(name) => write!(f, "{name}: installed but not in {LOCKFILE_NAME}"),
+        }
+    }
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            capabilities: BTreeMap::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// Read `<dir>/capabilities.lock`; a missing file is an empty lockfile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed, or
+    /// has a newer format version.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(LOCKFILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let lockfile: Self = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| Error::Config(format!("Invalid lockfile {}: {e}", path.display())))?;
+        if lockfile.version > LOCKFILE_VERSION {
+            return Err(Error::Config(format!(
+                "{} has format version {}; this mcp-gateway supports {LOCKFILE_VERSION}",
+                path.display(),
+                lockfile.version
+            )));
+        }
+        Ok(lockfile)
+    }
+
+    /// Write `<dir>/capabilities.lock`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(dir.join(LOCKFILE_NAME), format!("{json}\n"))?;
+        Ok(())
+    }
+
+    /// Lock every capability installed in `dir`. Entries of `previous`
+    /// whose checksum still matches keep their registry origin and
+    /// signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read or two files define the
+    /// same capability.
+    pub fn scan(dir: &Path, previous: &Self) -> Result<Self> {
+        let mut lockfile = Self::default();
+        for entry in WalkDir::new(dir)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+        {
+            let path = entry.path();
+            if !path.is_file()
+                || !matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("yaml" | "yml")
+                )
+            {
+                continue;
+            }
+            let content = std::fs::read(path)?;
+            // Non-capability YAML (suites, mock responses) is not locked.
+            let Ok(capability) = parse_capability(&String::from_utf8_lossy(&content)) else {
+                continue;
+            };
+            let relative = path
+                .strip_prefix(dir)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let sha256 = package_checksum(&content);
+            let origin = previous
+                .capabilities
+                .get(&capability.name)
+                .filter(|locked| locked.sha256 == sha256);
+            let locked = LockedCapability {
+                path: relative,
+                sha256,
+                // Keep the version as signed (e.g. without a `v` prefix).
+                version: origin.map_or(capability.version, |locked| locked.version.clone()),
+                registry: origin.and_then(|locked| locked.registry.clone()),
+                package: origin.and_then(|locked| locked.package.clone()),
+                publisher: origin.and_then(|locked| locked.publisher.clone()),
+                signature: origin.and_then(|locked| locked.signature.clone()),
+            };
+            if let Some(other) = lockfile
+                .capabilities
+                .insert(capability.name.clone(), locked)
+            {
+                return Err(Error::Config(format!(
+                    "Capability '{}' is defined by both {} and {}",
+                    capability.name, other.path, lockfile.capabilities[&capability.name].path
+                )));
+            }
+        }
+        Ok(lockfile)
+    }
+
+    /// Differences between this lockfile and `installed` (see
+    /// [`Lockfile::scan`]): locked entries first, then unlocked ones, each
+    /// by name.
+    #[must_use]
+    pub fn drift(&self, installed: &Self) -> Vec<Drift> {
+        let mut drift = Vec::new();
+        for (name, locked) in &self.capabilities {
+            match installed.capabilities.get(name) {
+                None => drift.push(Drift::Missing(name.clone())),
+                Some(actual) if actual.sha256 != locked.sha256 => drift.push(Drift::Changed {
+                    name: name.clone(),
+                    locked: locked.sha256.clone(),
+                    actual: actual.sha256.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for name in installed.capabilities.keys() {
+            if !self.capabilities.contains_key(name) {
+                drift.push(Drift::Unlocked(name.clone()));
+            }
+        }
+        drift
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn capability(name: &str, version: &str) -> String {
+        format!(
+            "name: {name}\nversion: \"{version}\"\ndescription: Test.\nproviders:\n  primary:\n    \
+             service: rest\n    config:\n      base_url: https://api.example.com\n      path: /x\n"
+        )
+    }
+
+    #[test]
+    fn scan_locks_capabilities_and_drift_detects_changes() {
+        // GIVEN: a directory with two capabilities and a non-capability file
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("finance")).unwrap();
+        std::fs::write(
+            dir.path().join("weather.yaml"),
+            capability("weather", "1.0.0"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("finance/quote.yaml"),
+            capability("quote", "2.1.0"),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("weather.test.yaml"), "cases: []\n").unwrap();
+
+        // WHEN: locking it and reading the lock back
+        let lock = Lockfile::scan(dir.path(), &Lockfile::default()).unwrap();
+        lock.save(dir.path()).unwrap();
+        let loaded = Lockfile::load(dir.path()).unwrap();
+
+        // THEN: both capabilities are locked with path, version and checksum
+        assert_eq!(loaded, lock);
+        assert_eq!(lock.capabilities.len(), 2);
+        let quote = &lock.capabilities["quote"];
+        assert_eq!(quote.path, "finance/quote.yaml");
+        assert_eq!(quote.version.as_deref(), Some("2.1.0"));
+        assert!(lock.drift(&lock).is_empty());
+
+        // AND: editing, removing and adding files is reported as drift
+        std::fs::write(
+            dir.path().join("weather.yaml"),
+            capability("weather", "1.1.0"),
+        )
+        .unwrap();
+        std::fs::remove_file(dir.path().join("finance/quote.yaml")).unwrap();
+        std::fs::write(dir.path().join("news.yaml"), capability("news", "0.1.0")).unwrap();
+        let installed = Lockfile::scan(dir.path(), &lock).unwrap();
+        let drift = lock.drift(&installed);
+        assert_eq!(drift.len(), 3, "{drift:?}");
+        assert_eq!(drift[0], Drift::Missing("quote".to_string()));
+        assert!(matches!(&drift[1], Drift::Changed { name, .. } if name == "weather"));
+        assert_eq!(drift[2], Drift::Unlocked("news".to_string()));
+    }
+
+    #[test]
+    fn scan_keeps_registry_origin_of_unchanged_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("weather.yaml"),
+            capability("weather", "1.0.0"),
+        )
+        .unwrap();
+        let mut previous = Lockfile::scan(dir.path(), &Lockfile::default()).unwrap();
+        let locked = previous.capabilities.get_mut("weather").unwrap();
+        locked.registry = Some("internal".to_string());
+        locked.package = Some("packages/weather/1.0.0.yaml".to_string());
+
+        let rescanned = Lockfile::scan(dir.path(), &previous).unwrap();
+        assert_eq!(rescanned, previous);
+
+        std::fs::write(
+            dir.path().join("weather.yaml"),
+            capability("weather", "1.0.1"),
+        )
+        .unwrap();
+        let rescanned = Lockfile::scan(dir.path(), &previous).unwrap();
+        assert_eq!(rescanned.capabilities["weather"].registry, None);
+    }
+}
@@ -4,9 +4,18 @@
 //!
 //! Provides discovery and installation of pre-built capability definitions
 //! from both local capabilities directory and remote GitHub sources.
+//!
+//! A registry directory published with `mcp-gateway cap publish` holds an
+//! `index.json` ([`RegistryIndex`]) and one package per published version
+//! under `packages/<name>/<version>.yaml`; see [`publish`].
 
+pub mod lockfile;
 pub mod marketplace;
+pub mod publish;
+pub mod remote;
 pub mod server_registry;
+pub mod trust;
+pub mod update;
 
 use std::collections::HashMap;
 use std::fs;
@@ -15,11 +24,11 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
-use crate::capability::parse_capability_file;
+use crate::capability::{SchemaDefinition, parse_capability_file};
 use crate::{Error, Result};
 
 /// Registry entry describing a capability
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RegistryEntry {
     /// Capability name
     pub name: String,
@@ -33,6 +42,41 @@ pub struct RegistryEntry {
     /// Whether this capability requires authentication
     #[serde(default)]
     pub requires_key: bool,
+    /// Published version (semver)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Lowercase hex SHA-256 of the package file at `path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Input and output schema of the published version, compared against
+    /// the next publish to enforce the semver bump
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<SchemaDefinition>,
+    /// When this version was published (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
+    /// Release notes of published versions, newest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changelog: Vec<ChangelogEntry>,
+    /// Publisher that signed this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// Base64 Ed25519 signature over name, version and `sha256`; see
+    /// [`trust`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// Release notes of one published version
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    /// Version the notes describe
+    pub version: String,
+    /// Notes, as given to `cap publish --notes`
+    pub notes: String,
+    /// When the version was published (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
 }
 
 /// Capability registry index with O(1) name lookup.
@@ -133,6 +177,41 @@ impl RegistryIndex {
     pub fn find(&self, name: &str) -> Option<&RegistryEntry> {
         self.name_index.get(name).map(|&i| &self.capabilities[i])
     }
+
+    /// Insert or replace the entry named `entry.name`, keeping entries
+    /// sorted by name so the saved index diffs cleanly.
+    pub fn upsert(&mut self, entry: RegistryEntry) {
+        let mut capabilities = std::mem::take(&mut self.capabilities);
+        capabilities.retain(|e| e.name != entry.name);
+        capabilities.push(entry);
+        capabilities.sort_by(|a, b| a.name.cmp(&b.name));
+        *self = Self::from_parts(std::mem::take(&mut self.version), capabilities);
+    }
+
+    /// Read an index file; a missing file is an empty index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new(Vec::new()));
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| Error::Config(format!("Invalid registry index {}: {e}", path.display())))
+    }
+
+    /// Write the index as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, format!("{json}\n"))?;
+        Ok(())
+    }
 }
 
 /// Capability registry manager
@@ -202,6 +281,8 @@ impl Registry {
                         path: relative_path,
                         tags,
                         requires_key,
+                        version: capability.version,
+                        ..RegistryEntry::default()
                     });
                 }
                 Err(e) => {
@@ -305,6 +386,7 @@ mod tests {
             path: "utility/test_tool.yaml".to_string(),
             tags: vec!["test".to_string()],
             requires_key: true,
+            ..RegistryEntry::default()
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -327,6 +409,7 @@ mod tests {
             path: path.to_string(),
             tags: tags.into_iter().map(String::from).collect(),
             requires_key,
+            ..RegistryEntry::default()
         }
     }
 
@@ -418,6 +501,7 @@ mod tests {
             path: "secure.yaml".to_string(),
             tags: vec![],
             requires_key: true,
+            ..RegistryEntry::default()
         };
 
         assert!(entry.requires_key);
@@ -428,6 +512,7 @@ mod tests {
             path: "open.yaml".to_string(),
             tags: vec![],
             requires_key: false,
+            ..RegistryEntry::default()
         };
 
         assert!(!entry_no_key.requires_key);
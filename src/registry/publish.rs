@@ -0,0 +1,556 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Publishing capabilities into a registry directory
+//!
+//! `mcp-gateway cap publish` copies a validated capability YAML to
+//! `packages/<name>/<version>.yaml` and records its version, SHA-256 and
+//! schema in `index.json`, so the index is generated rather than
+//! hand-edited.
+//!
+//! # Semver
+//!
+//! The capability's `version:` must be `MAJOR.MINOR.PATCH` and newer than
+//! the published one. Its schema is compared with the published schema:
+//!
+//! | Change | Examples | Required bump |
+//! |--------|----------|---------------|
+//! | Breaking | input property removed, retyped or newly required; output property removed or retyped | major (minor below 1.0) |
+//! | Compatible | optional input property or output property added | minor (patch below 1.0) |
+//! | None | descriptions, provider config | patch |
+//!
+//! Republishing an existing version is a no-op when the package is
+//! identical and an error otherwise; republishing it with a signing key
+//! signs the existing entry.
+//!
+//! # Signing
+//!
+//! With a [`SigningKey`] the entry records the publisher and an Ed25519
+//! signature over its name, version and checksum, verified by registries
+//! that trust the publisher (see [`super::trust`]).
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::trust::SigningKey;
+use super::{ChangelogEntry, RegistryEntry, RegistryIndex};
+use crate::capability::{
+    CapabilityDefinition, IssueSeverity, SchemaDefinition, compare_versions, parse_capability,
+    validate_capability, validate_capability_definition,
+};
+use crate::{Error, Result};
+
+/// File name of the index inside a registry directory.
+pub const INDEX_FILE: &str = "index.json";
+
+/// Semantic version `MAJOR.MINOR.PATCH[-PRE][+BUILD]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemVer {
+    /// Major version.
+    pub major: u64,
+    /// Minor version.
+    pub minor: u64,
+    /// Patch version.
+    pub patch: u64,
+    /// Pre-release label, e.g. `rc.1`.
+    pub pre: Option<String>,
+}
+
+impl SemVer {
+    /// Parse a version; a leading `v` is accepted and build metadata is
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error unless the version has three numeric parts.
+    pub fn parse(version: &str) -> Result<Self> {
+        let invalid = || {
+            Error::Config(format!(
+                "Version '{version}' is not semver (MAJOR.MINOR.PATCH)"
+            ))
+        };
+        let core = version.trim().trim_start_matches('v');
+        let core = core.split_once('+').map_or(core, |(core, _)| core);
+        let (core, pre) = match core.split_once('-') {
+            Some((core, pre)) if !pre.is_empty() => (core, Some(pre.to_string())),
+            Some(_) => return Err(invalid()),
+            None => (core, None),
+        };
+        let mut parts = core
+            .split('.')
+            .map(|p| p.parse::<u64>().map_err(|_| invalid()));
+        let (Some(major), Some(minor), Some(patch), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            major: major?,
+            minor: minor?,
+            patch: patch?,
+            pre,
+        })
+    }
+
+    /// Bump level from `previous` to `self`, which must be newer.
+    fn bump_from(&self, previous: &Self) -> Bump {
+        if self.major > previous.major {
+            Bump::Major
+        } else if self.minor > previous.minor {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        }
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_versions(a, b),
+            })
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for SemVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+/// How a schema changed between two published versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaChange {
+    /// No change to the interface.
+    None,
+    /// Additions existing callers are unaffected by.
+    Compatible,
+    /// Changes that can break existing callers.
+    Breaking,
+}
+
+/// Classify the change from `old` to `new`, with one reason per breaking
+/// or compatible difference.
+#[must_use]
+pub fn classify_schema_change(
+    old: &SchemaDefinition,
+    new: &SchemaDefinition,
+) -> (SchemaChange, Vec<String>) {
+    let mut change = SchemaChange::None;
+    let mut reasons = Vec::new();
+    let mut note = |level: SchemaChange, reason: String| {
+        change = change.max(level);
+        reasons.push(reason);
+    };
+
+    let (old_in, new_in) = (properties(&old.input), properties(&new.input));
+    let (old_required, new_required) = (required(&old.input), required(&new.input));
+    for (name, schema) in &old_in {
+        match new_in.iter().find(|(n, _)| n == name) {
+            None => note(
+                SchemaChange::Breaking,
+                format!("input property '{name}' removed"),
+            ),
+            Some((_, new_schema)) if schema.get("type") != new_schema.get("type") => note(
+                SchemaChange::Breaking,
+                format!("input property '{name}' changed type"),
+            ),
+            Some(_) => {}
+        }
+    }
+    for name in &new_required {
+        if !old_required.contains(name) {
+            note(
+                SchemaChange::Breaking,
+                format!("input property '{name}' is now required"),
+            );
+        }
+    }
+    for (name, _) in &new_in {
+        if !old_in.iter().any(|(n, _)| n == name) && !new_required.contains(name) {
+            note(
+                SchemaChange::Compatible,
+                format!("optional input property '{name}' added"),
+            );
+        }
+    }
+
+    let (old_out, new_out) = (properties(&old.output), properties(&new.output));
+    for (name, schema) in &old_out {
+        match new_out.iter().find(|(n, _)| n == name) {
+            None => note(
+                SchemaChange::Breaking,
+                format!("output property '{name}' removed"),
+            ),
+            Some((_, new_schema)) if schema.get("type") != new_schema.get("type") => note(
+                SchemaChange::Breaking,
+                format!("output property '{name}' changed type"),
+            ),
+            Some(_) => {}
+        }
+    }
+    for (name, _) in &new_out {
+        if !old_out.iter().any(|(n, _)| n == name) {
+            note(
+                SchemaChange::Compatible,
+                format!("output property '{name}' added"),
+            );
+        }
+    }
+    (change, reasons)
+}
+
+fn properties(schema: &Value) -> Vec<(String, Value)> {
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|props| props.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default()
+}
+
+fn required(schema: &Value) -> Vec<String> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Result of publishing one capability.
+#[derive(Debug, Clone)]
+pub struct PublishOutcome {
+    /// Capability name.
+    pub name: String,
+    /// Published version.
+    pub version: String,
+    /// Version it replaces in the index, if any.
+    pub previous: Option<String>,
+    /// SHA-256 of the package.
+    pub sha256: String,
+    /// Package file written.
+    pub package: PathBuf,
+    /// Schema change relative to `previous`.
+    pub schema_change: SchemaChange,
+    /// Why the schema change was classified as it was.
+    pub reasons: Vec<String>,
+    /// Publisher that signed the package, if it was signed.
+    pub publisher: Option<String>,
+    /// True when this exact package was already published.
+    pub unchanged: bool,
+}
+
+/// Lowercase hex SHA-256 of a package.
+#[must_use]
+pub fn package_checksum(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+/// Publish the capability at `capability_file` into the registry directory
+/// `registry_dir`, creating it if needed.
+///
+/// `notes` become this version's changelog entry; without them the schema
+/// changes, if any, are recorded instead. Entries of earlier versions are
+/// kept, newest first. With `signer` the entry is signed by its publisher.
+///
+/// # Errors
+///
+/// Returns an error if the capability does not parse or validate, has no
+/// semver `version:`, is not newer than the published version, bumps the
+/// version less than its schema change requires, reuses a published version
+/// for different content, or the files cannot be written.
+pub fn publish_capability(
+    registry_dir: &Path,
+    capability_file: &Path,
+    notes: Option<&str>,
+    signer: Option<&SigningKey>,
+) -> Result<PublishOutcome> {
+    let content = std::fs::read_to_string(capability_file)?;
+    let (capability, version) = publishable(&content)?;
+    let sha256 = package_checksum(content.as_bytes());
+    let signature = signer
+        .map(|key| key.sign(&capability.name, Some(&version.to_string()), &sha256))
+        .transpose()?;
+
+    let index_path = registry_dir.join(INDEX_FILE);
+    let mut index = RegistryIndex::load(&index_path)?;
+    let relative = format!("packages/{}/{version}.yaml", capability.name);
+    let package = registry_dir.join(&relative);
+
+    let mut outcome = PublishOutcome {
+        name: capability.name.clone(),
+        version: version.to_string(),
+        previous: None,
+        sha256: sha256.clone(),
+        package: package.clone(),
+        schema_change: SchemaChange::None,
+        reasons: Vec::new(),
+        publisher: signer.map(|key| key.publisher().to_string()),
+        unchanged: false,
+    };
+
+    let mut changelog = Vec::new();
+    if let Some(published) = index.find(&capability.name) {
+        changelog.clone_from(&published.changelog);
+        let previous = SemVer::parse(published.version.as_deref().unwrap_or("0.0.0"))?;
+        if previous == version {
+            if published.sha256.as_deref() == Some(sha256.as_str()) {
+                outcome.unchanged = signature.is_none() || published.signature == signature;
+                if outcome.unchanged {
+                    outcome.publisher.clone_from(&published.publisher);
+                } else {
+                    let mut entry = published.clone();
+                    entry.publisher.clone_from(&outcome.publisher);
+                    entry.signature = signature;
+                    index.upsert(entry);
+                    index.save(&index_path)?;
+                }
+                return Ok(outcome);
+            }
+            return Err(Error::Config(format!(
+                "{} {version} is already published with different content; bump the version",
+                capability.name
+            )));
+        }
+        if version < previous {
+            return Err(Error::Config(format!(
+                "{} {version} is older than the published {previous}",
+                capability.name
+            )));
+        }
+        // Entries indexed before publishing existed carry no schema to
+        // compare against; only the version order applies to them.
+        let (change, reasons) = published.schema.as_ref().map_or_else(
+            || (SchemaChange::None, Vec::new()),
+            |schema| classify_schema_change(schema, &capability.schema),
+        );
+        check_bump(&capability.name, &previous, &version, change, &reasons)?;
+        outcome.previous = Some(previous.to_string());
+        outcome.schema_change = change;
+        outcome.reasons = reasons;
+    }
+
+    if let Some(dir) = package.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&package, &content)?;
+    let published_at = chrono::Utc::now().to_rfc3339();
+    let notes = notes.map(str::to_string).or_else(|| {
+        (!outcome.reasons.is_empty()).then(|| format!("Schema: {}", outcome.reasons.join("; ")))
+    });
+    if let Some(notes) = notes {
+        changelog.insert(
+            0,
+            ChangelogEntry {
+                version: version.to_string(),
+                notes,
+                published_at: Some(published_at.clone()),
+            },
+        );
+    }
+    index.upsert(RegistryEntry {
+        name: capability.name,
+        description: capability.description,
+        path: relative,
+        tags: capability.metadata.tags,
+        requires_key: capability.auth.required,
+        version: Some(version.to_string()),
+        sha256: Some(sha256),
+        schema: Some(capability.schema),
+        published_at: Some(published_at),
+        changelog,
+        publisher: outcome.publisher.clone(),
+        signature,
+    });
+    index.save(&index_path)?;
+    Ok(outcome)
+}
+
+/// Parse and validate a capability for publishing, with its version.
+fn publishable(content: &str) -> Result<(CapabilityDefinition, SemVer)> {
+    let capability = parse_capability(content)?;
+    validate_capability(&capability)?;
+    if let Some(issue) = validate_capability_definition(&capability, None)
+        .into_iter()
+        .find(|i| i.severity == IssueSeverity::Error)
+    {
+        return Err(Error::Config(format!(
+            "Capability '{}' is invalid: [{}] {}",
+            capability.name, issue.code, issue.message
+        )));
+    }
+    let raw_version = capability.version.as_deref().ok_or_else(|| {
+        Error::Config(format!(
+            "Capability '{}' has no `version:`; add one (e.g. 1.0.0) to publish it",
+            capability.name
+        ))
+    })?;
+    let version = SemVer::parse(raw_version)?;
+    Ok((capability, version))
+}
+
+fn check_bump(
+    name: &str,
+    previous: &SemVer,
+    version: &SemVer,
+    change: SchemaChange,
+    reasons: &[String],
+) -> Result<()> {
+    // Below 1.0 every level shifts down one: minor bumps may break.
+    let needed = match (change, previous.major) {
+        (SchemaChange::Breaking, 0) | (SchemaChange::Compatible, 1..) => Bump::Minor,
+        (SchemaChange::Breaking, _) => Bump::Major,
+        _ => Bump::Patch,
+    };
+    let bump = version.bump_from(previous);
+    if bump >= needed {
+        return Ok(());
+    }
+    let level = match needed {
+        Bump::Major => "major",
+        Bump::Minor => "minor",
+        Bump::Patch => "patch",
+    };
+    Err(Error::Config(format!(
+        "{name} {previous} -> {version} needs a {level} version bump: {}",
+        reasons.join("; ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn capability_yaml(version: &str, input: &str) -> String {
+        format!(
+            "name: weather\nversion: \"{version}\"\ndescription: Current weather.\n\
+             schema:\n  input:\n{input}\nproviders:\n  primary:\n    service: rest\n    \
+             config:\n      base_url: https://api.example.com\n      path: /weather\n"
+        )
+    }
+
+    const CITY_ONLY: &str =
+        "    type: object\n    properties:\n      city: { type: string }\n    required: [city]";
+    const CITY_AND_UNITS: &str = "    type: object\n    properties:\n      city: { type: string }\n      units: { type: string }\n    required: [city]";
+    const UNITS_REQUIRED: &str = "    type: object\n    properties:\n      city: { type: string }\n      units: { type: string }\n    required: [city, units]";
+
+    fn publish(dir: &TempDir, version: &str, input: &str) -> Result<PublishOutcome> {
+        let file = dir.path().join("weather.yaml");
+        std::fs::write(&file, capability_yaml(version, input)).unwrap();
+        publish_capability(&dir.path().join("registry"), &file, None, None)
+    }
+
+    #[test]
+    fn publish_writes_package_and_index_entry() {
+        // GIVEN: an empty registry directory
+        let dir = TempDir::new().unwrap();
+
+        // WHEN: publishing version 1.0.0
+        let outcome = publish(&dir, "1.0.0", CITY_ONLY).unwrap();
+
+        // THEN: the package and an index entry with its checksum exist
+        let package = std::fs::read(&outcome.package).unwrap();
+        assert_eq!(package_checksum(&package), outcome.sha256);
+        let index = RegistryIndex::load(&dir.path().join("registry").join(INDEX_FILE)).unwrap();
+        let entry = index.find("weather").unwrap();
+        assert_eq!(entry.version.as_deref(), Some("1.0.0"));
+        assert_eq!(entry.path, "packages/weather/1.0.0.yaml");
+        assert_eq!(entry.sha256.as_deref(), Some(outcome.sha256.as_str()));
+        assert!(entry.schema.is_some());
+
+        // AND: publishing the same package again changes nothing
+        assert!(publish(&dir, "1.0.0", CITY_ONLY).unwrap().unchanged);
+    }
+
+    #[test]
+    fn publish_enforces_semver_against_published_schema() {
+        // GIVEN: version 1.0.0 is published
+        let dir = TempDir::new().unwrap();
+        publish(&dir, "1.0.0", CITY_ONLY).unwrap();
+
+        // WHEN/THEN: an optional input needs a minor bump
+        let err = publish(&dir, "1.0.1", CITY_AND_UNITS).unwrap_err();
+        assert!(
+            err.to_string().contains("needs a minor version bump"),
+            "{err}"
+        );
+        let outcome = publish(&dir, "1.1.0", CITY_AND_UNITS).unwrap();
+        assert_eq!(outcome.schema_change, SchemaChange::Compatible);
+        assert_eq!(outcome.previous.as_deref(), Some("1.0.0"));
+
+        // AND: making it required needs a major bump
+        let err = publish(&dir, "1.2.0", UNITS_REQUIRED).unwrap_err();
+        assert!(err.to_string().contains("'units' is now required"), "{err}");
+        publish(&dir, "2.0.0", UNITS_REQUIRED).unwrap();
+
+        // AND: going backwards or reusing a version with new content fails
+        assert!(publish(&dir, "1.5.0", UNITS_REQUIRED).is_err());
+        assert!(publish(&dir, "2.0.0", CITY_AND_UNITS).is_err());
+    }
+
+    #[test]
+    fn publish_records_changelog_newest_first() {
+        // GIVEN: 1.0.0 published with notes
+        let dir = TempDir::new().unwrap();
+        let registry = dir.path().join("registry");
+        let file = dir.path().join("weather.yaml");
+        std::fs::write(&file, capability_yaml("1.0.0", CITY_ONLY)).unwrap();
+        publish_capability(&registry, &file, Some("Initial release"), None).unwrap();
+
+        // WHEN: publishing 1.1.0 without notes
+        publish(&dir, "1.1.0", CITY_AND_UNITS).unwrap();
+
+        // THEN: the schema change stands in for notes, above the older entry
+        let index = RegistryIndex::load(&registry.join(INDEX_FILE)).unwrap();
+        let changelog = &index.find("weather").unwrap().changelog;
+        assert_eq!(changelog.len(), 2);
+        assert_eq!(changelog[0].version, "1.1.0");
+        assert!(
+            changelog[0].notes.contains("'units' added"),
+            "{changelog:?}"
+        );
+        assert_eq!(changelog[1].notes, "Initial release");
+    }
+
+    #[test]
+    fn semver_parses_and_orders_prereleases_first() {
+        let rc = SemVer::parse("v2.0.0-rc.1+build.5").unwrap();
+        assert_eq!(rc.to_string(), "2.0.0-rc.1");
+        assert!(rc < SemVer::parse("2.0.0").unwrap());
+        assert!(rc > SemVer::parse("1.9.9").unwrap());
+        assert!(SemVer::parse("1.2").is_err());
+        assert!(SemVer::parse("1.2.x").is_err());
+    }
+}
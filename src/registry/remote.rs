@@ -0,0 +1,761 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Remote capability registries
+//!
+//! Fetches `index.json` and packages from registry directories written by
+//! `mcp-gateway cap publish` and served over HTTP(S), optionally behind
+//! bearer or basic auth. Sources are queried highest `priority` first; the
+//! first one listing a capability wins.
+//!
+//! Every fetched index and package is cached under
+//! `<cache_dir>/<registry>/`, mirroring the registry layout. When a registry
+//! is unreachable (connection error, timeout or HTTP 5xx) the cached copy is
+//! used instead, so installs keep working offline. Authentication and
+//! not-found responses are reported, never masked by the cache.
+//!
+//! Every package is checked against its registry's trust policy (see
+//! [`super::trust`]) before it is written.
+
+use std::fmt::Write as _;
+use std::path::{Component, Path, PathBuf};
+
+use super::lockfile::LockedCapability;
+use super::publish::package_checksum;
+use super::trust::{PackageClaims, verify_package};
+use super::{RegistryEntry, RegistryIndex};
+use crate::config::{RegistryAuthConfig, RegistryConfig, RegistrySourceConfig};
+use crate::{Error, Result};
+
+/// Index fetched from one registry.
+#[derive(Debug, Clone)]
+pub struct RemoteIndex {
+    /// Registry name.
+    pub registry: String,
+    /// The registry's index.
+    pub index: RegistryIndex,
+    /// True when served from the local cache because the registry was
+    /// unreachable.
+    pub cached: bool,
+}
+
+/// A capability found in a remote registry.
+#[derive(Debug, Clone)]
+pub struct RemoteEntry {
+    /// Registry that lists it.
+    pub registry: String,
+    /// Index entry.
+    pub entry: RegistryEntry,
+    /// True when the index came from the local cache.
+    pub cached: bool,
+}
+
+/// Result of searching every registry.
+#[derive(Debug, Clone, Default)]
+pub struct RegistrySearch {
+    /// Matches, highest-priority registry first; each name appears once.
+    pub matches: Vec<RemoteEntry>,
+    /// Registries that could not be queried, with the reason.
+    pub unavailable: Vec<(String, String)>,
+}
+
+/// A capability installed from a remote registry.
+#[derive(Debug, Clone)]
+pub struct RemoteInstall {
+    /// Registry it came from.
+    pub registry: String,
+    /// Installed version, when the registry records one.
+    pub version: Option<String>,
+    /// Package path inside the registry.
+    pub package: String,
+    /// SHA-256 of the installed file.
+    pub sha256: String,
+    /// Publisher whose signature was verified, if any.
+    pub publisher: Option<String>,
+    /// The verified signature, recorded in the lockfile.
+    pub signature: Option<String>,
+    /// File written.
+    pub path: PathBuf,
+    /// True when the package came from the local cache.
+    pub cached: bool,
+}
+
+/// Outcome of one HTTP fetch.
+enum Fetch {
+    Ok(Vec<u8>),
+    /// Network failure or 5xx: the cache may stand in.
+    Unreachable(String),
+    /// Any other failure; reported as is.
+    Rejected(Error),
+}
+
+/// Client for the registries in [`RegistryConfig::sources`].
+pub struct RemoteRegistries {
+    sources: Vec<RegistrySourceConfig>,
+    cache_dir: PathBuf,
+    client: reqwest::Client,
+}
+
+impl RemoteRegistries {
+    /// Create a client for `config`, ordering sources by descending
+    /// priority (ties keep their config order).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be constructed.
+    pub fn new(config: &RegistryConfig) -> Result<Self> {
+        let mut sources = config.sources.clone();
+        sources.sort_by_key(|source| std::cmp::Reverse(source.priority));
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("mcp-gateway/", env!("CARGO_PKG_VERSION")))
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(|e| Error::Config(format!("failed to build HTTP client: {e}")))?;
+        Ok(Self {
+            sources,
+            cache_dir: config.cache_path(),
+            client,
+        })
+    }
+
+    /// Whether no registries are configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Names of the configured registries, in query order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sources.iter().map(|source| source.name.as_str())
+    }
+
+    /// Fetch the index of `source`, falling back to its cached copy when
+    /// the registry is unreachable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the registry rejects the request, is unreachable
+    /// without a cached index, or serves an invalid index.
+    pub async fn fetch_index(&self, source: &RegistrySourceConfig) -> Result<RemoteIndex> {
+        let (bytes, cached) = self.fetch(source, super::publish::INDEX_FILE).await?;
+        let index = serde_json::from_slice(&bytes).map_err(|e| {
+            Error::Config(format!(
+                "Registry '{}' has an invalid index: {e}",
+                source.name
+            ))
+        })?;
+        Ok(RemoteIndex {
+            registry: source.name.clone(),
+            index,
+            cached,
+        })
+    }
+
+    /// Fetch every registry's index, highest priority first, with the
+    /// registries that could not be queried and why.
+    pub async fn indexes(&self) -> (Vec<RemoteIndex>, Vec<(String, String)>) {
+        let mut indexes = Vec::with_capacity(self.sources.len());
+        let mut unavailable = Vec::new();
+        for source in &self.sources {
+            match self.fetch_index(source).await {
+                Ok(remote) => indexes.push(remote),
+                Err(e) => unavailable.push((source.name.clone(), e.to_string())),
+            }
+        }
+        (indexes, unavailable)
+    }
+
+    /// Search every registry's index for `query` (see
+    /// [`RegistryIndex::search`]).
+    pub async fn search(&self, query: &str) -> RegistrySearch {
+        let (indexes, unavailable) = self.indexes().await;
+        let mut search = RegistrySearch {
+            matches: Vec::new(),
+            unavailable,
+        };
+        for remote in &indexes {
+            for entry in remote.index.search(query) {
+                if search.matches.iter().all(|m| m.entry.name != entry.name) {
+                    search.matches.push(RemoteEntry {
+                        registry: remote.registry.clone(),
+                        entry: entry.clone(),
+                        cached: remote.cached,
+                    });
+                }
+            }
+        }
+        search
+    }
+
+    /// Install `name` from the highest-priority registry listing it into
+    /// `<dest_dir>/<name>.yaml`, verifying the package checksum when the
+    /// index records one and the signature against the registry's trust
+    /// policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no reachable registry lists `name`, the package
+    /// cannot be fetched, its checksum does not match, the trust policy
+    /// refuses it, or the file cannot be written.
+    pub async fn install(&self, name: &str, dest_dir: &Path) -> Result<RemoteInstall> {
+        let mut unavailable = Vec::new();
+        for source in &self.sources {
+            let remote = match self.fetch_index(source).await {
+                Ok(remote) => remote,
+                Err(e) => {
+                    unavailable.push(format!("{}: {e}", source.name));
+                    continue;
+                }
+            };
+            let Some(entry) = remote.index.find(name) else {
+                continue;
+            };
+            let (content, cached) = self
+                .fetch_package(source, name, &entry.path, entry.sha256.as_deref())
+                .await?;
+            let path = dest_dir.join(format!("{name}.yaml"));
+            return write_install(
+                source,
+                entry_claims(entry),
+                &entry.path,
+                &path,
+                &content,
+                cached,
+            );
+        }
+        let mut message = format!(
+            "Capability '{name}' not found in registries: {}",
+            self.names().collect::<Vec<_>>().join(", ")
+        );
+        if !unavailable.is_empty() {
+            let _ = write!(message, " (unavailable: {})", unavailable.join("; "));
+        }
+        Err(Error::Config(message))
+    }
+
+    /// Reinstall `name` exactly as `locked` records it: the same package
+    /// from the same registry, into the same file, with the same checksum.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock entry has no registry origin, that
+    /// registry is not configured, the package cannot be fetched, its
+    /// checksum differs from the locked one, or the trust policy refuses the
+    /// locked signature.
+    pub async fn install_locked(
+        &self,
+        name: &str,
+        locked: &LockedCapability,
+        dest_dir: &Path,
+    ) -> Result<RemoteInstall> {
+        let (Some(registry), Some(package)) = (&locked.registry, &locked.package) else {
+            return Err(Error::Config(format!(
+                "Capability '{name}' is locked as a local file, not a registry install"
+            )));
+        };
+        let source = self.source(registry)?;
+        let (content, cached) = self
+            .fetch_package(source, name, package, Some(&locked.sha256))
+            .await?;
+        if !is_contained(Path::new(&locked.path)) {
+            return Err(Error::Config(format!(
+                "Capability '{name}' has an unsafe locked path '{}'",
+                locked.path
+            )));
+        }
+        let path = dest_dir.join(&locked.path);
+        let claims = PackageClaims {
+            name,
+            version: locked.version.as_deref(),
+            publisher: locked.publisher.as_deref(),
+            signature: locked.signature.as_deref(),
+        };
+        write_install(source, claims, package, &path, &content, cached)
+    }
+
+    /// Install `entry`, as listed by `registry`, to `path`, verifying its
+    /// checksum and signature. Used to upgrade a capability in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `registry` is not configured, the package cannot
+    /// be fetched, its checksum does not match, the trust policy refuses it,
+    /// or the file cannot be written.
+    pub async fn install_entry(
+        &self,
+        registry: &str,
+        entry: &RegistryEntry,
+        path: &Path,
+    ) -> Result<RemoteInstall> {
+        let source = self.source(registry)?;
+        let (content, cached) = self
+            .fetch_package(source, &entry.name, &entry.path, entry.sha256.as_deref())
+            .await?;
+        write_install(
+            source,
+            entry_claims(entry),
+            &entry.path,
+            path,
+            &content,
+            cached,
+        )
+    }
+
+    fn source(&self, name: &str) -> Result<&RegistrySourceConfig> {
+        self.sources
+            .iter()
+            .find(|source| source.name == name)
+            .ok_or_else(|| Error::Config(format!("Registry '{name}' is not configured")))
+    }
+
+    async fn fetch_package(
+        &self,
+        source: &RegistrySourceConfig,
+        name: &str,
+        package: &str,
+        expected: Option<&str>,
+    ) -> Result<(Vec<u8>, bool)> {
+        let (bytes, cached) = self.fetch(source, package).await?;
+        if let Some(expected) = expected {
+            let actual = package_checksum(&bytes);
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(Error::Config(format!(
+                    "checksum mismatch for capability '{name}' from registry '{}': expected {expected}, got {actual}",
+                    source.name
+                )));
+            }
+        }
+        Ok((bytes, cached))
+    }
+
+    /// Fetch `relative` from `source`, refreshing the cache on success and
+    /// reading it when the registry is unreachable.
+    async fn fetch(
+        &self,
+        source: &RegistrySourceConfig,
+        relative: &str,
+    ) -> Result<(Vec<u8>, bool)> {
+        let cache = self.cache_file(source, relative)?;
+        let url = format!("{}/{relative}", source.url.trim_end_matches('/'));
+        match self.get(source, &url).await? {
+            Fetch::Ok(bytes) => {
+                if let Some(dir) = cache.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(&cache, &bytes)?;
+                Ok((bytes, false))
+            }
+            Fetch::Unreachable(reason) => match std::fs::read(&cache) {
+                Ok(bytes) => {
+                    tracing::warn!(
+                        registry = %source.name,
+                        "Registry unreachable ({reason}); using cached {relative}"
+                    );
+                    Ok((bytes, true))
+                }
+                Err(_) => Err(Error::Transport(format!(
+                    "Registry '{}' is unreachable ({reason}) and {relative} is not cached",
+                    source.name
+                ))),
+            },
+            Fetch::Rejected(e) => Err(e),
+        }
+    }
+
+    async fn get(&self, source: &RegistrySourceConfig, url: &str) -> Result<Fetch> {
+        let request = match &source.auth {
+            RegistryAuthConfig::None => self.client.get(url),
+            RegistryAuthConfig::Bearer { token_env } => {
+                self.client.get(url).bearer_auth(secret(source, token_env)?)
+            }
+            RegistryAuthConfig::Basic {
+                username,
+                password_env,
+            } => self
+                .client
+                .get(url)
+                .basic_auth(username, Some(secret(source, password_env)?)),
+        };
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => return Ok(Fetch::Unreachable(e.to_string())),
+        };
+        let status = response.status();
+        if status.is_server_error() {
+            return Ok(Fetch::Unreachable(format!("HTTP {status}")));
+        }
+        if !status.is_success() {
+            let hint = if matches!(status.as_u16(), 401 | 403) {
+                " (check the registry credentials)"
+            } else {
+                ""
+            };
+            return Ok(Fetch::Rejected(Error::Transport(format!(
+                "Registry '{}' returned HTTP {status} for {url}{hint}",
+                source.name
+            ))));
+        }
+        match response.bytes().await {
+            Ok(bytes) => Ok(Fetch::Ok(bytes.to_vec())),
+            Err(e) => Ok(Fetch::Unreachable(e.to_string())),
+        }
+    }
+
+    /// Cache location of `relative`; rejects paths escaping the registry.
+    fn cache_file(&self, source: &RegistrySourceConfig, relative: &str) -> Result<PathBuf> {
+        let path = Path::new(relative);
+        if !is_contained(path) {
+            return Err(Error::Config(format!(
+                "Registry '{}' lists an unsafe package path '{relative}'",
+                source.name
+            )));
+        }
+        Ok(self.cache_dir.join(&source.name).join(path))
+    }
+}
+
+/// Whether `path` is relative and stays below the directory it is joined to.
+fn is_contained(path: &Path) -> bool {
+    path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn entry_claims(entry: &RegistryEntry) -> PackageClaims<'_> {
+    PackageClaims {
+        name: &entry.name,
+        version: entry.version.as_deref(),
+        publisher: entry.publisher.as_deref(),
+        signature: entry.signature.as_deref(),
+    }
+}
+
+/// Write `content` to `path` once `source`'s trust policy accepts it.
+fn write_install(
+    source: &RegistrySourceConfig,
+    claims: PackageClaims<'_>,
+    package: &str,
+    path: &Path,
+    content: &[u8],
+    cached: bool,
+) -> Result<RemoteInstall> {
+    let sha256 = package_checksum(content);
+    let publisher = verify_package(source, claims, &sha256)?;
+    let signature = publisher.as_ref().and(claims.signature).map(str::to_string);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(RemoteInstall {
+        registry: source.name.clone(),
+        version: claims.version.map(str::to_string),
+        package: package.to_string(),
+        sha256,
+        publisher,
+        signature,
+        path: path.to_path_buf(),
+        cached,
+    })
+}
+
+fn secret(source: &RegistrySourceConfig, var: &str) -> Result<String> {
+    std::env::var(var).map_err(|_| {
+        Error::Config(format!(
+            "Registry '{}' credentials: environment variable {var} is not set",
+            source.name
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use axum::{
+        Router,
+        extract::State,
+        http::{HeaderMap, StatusCode, Uri},
+    };
+    use parking_lot::Mutex;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config::RegistryTrustConfig;
+    use crate::registry::publish::publish_capability;
+    use crate::registry::trust::{SigningKey, generate_signing_key};
+
+    const CAPABILITY: &str = "name: weather\nversion: \"1.0.0\"\ndescription: Current weather.\n\
+        providers:\n  primary:\n    service: rest\n    config:\n      \
+        base_url: https://api.example.com\n      path: /weather\n";
+
+    /// Serves `root` as a registry, records `Authorization` headers and
+    /// answers 503 once `down` is set.
+    #[derive(Clone)]
+    struct Served {
+        root: PathBuf,
+        down: Arc<Mutex<bool>>,
+        auth: Arc<Mutex<Vec<String>>>,
+    }
+
+    async fn serve_file(
+        State(served): State<Served>,
+        headers: HeaderMap,
+        uri: Uri,
+    ) -> (StatusCode, Vec<u8>) {
+        if let Some(auth) = headers.get("authorization") {
+            served.auth.lock().push(auth.to_str().unwrap().to_string());
+        }
+        if *served.down.lock() {
+            return (StatusCode::SERVICE_UNAVAILABLE, Vec::new());
+        }
+        match std::fs::read(served.root.join(uri.path().trim_start_matches('/'))) {
+            Ok(bytes) => (StatusCode::OK, bytes),
+            Err(_) => (StatusCode::NOT_FOUND, Vec::new()),
+        }
+    }
+
+    async fn serve(root: PathBuf) -> (String, Served) {
+        let served = Served {
+            root,
+            down: Arc::default(),
+            auth: Arc::default(),
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .fallback(serve_file)
+            .with_state(served.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}"), served)
+    }
+
+    fn source(
+        name: &str,
+        url: &str,
+        priority: i32,
+        auth: RegistryAuthConfig,
+    ) -> RegistrySourceConfig {
+        RegistrySourceConfig {
+            name: name.to_string(),
+            url: url.to_string(),
+            priority,
+            auth,
+            public_keys: BTreeMap::new(),
+            trust: RegistryTrustConfig::default(),
+        }
+    }
+
+    fn published_registry(dir: &TempDir, subdir: &str, description: &str) -> PathBuf {
+        signed_registry(dir, subdir, description, None)
+    }
+
+    fn signed_registry(
+        dir: &TempDir,
+        subdir: &str,
+        description: &str,
+        key: Option<&SigningKey>,
+    ) -> PathBuf {
+        let file = dir.path().join(format!("{subdir}.yaml"));
+        std::fs::write(&file, CAPABILITY.replace("Current weather.", description)).unwrap();
+        let registry = dir.path().join(subdir);
+        publish_capability(&registry, &file, None, key).unwrap();
+        registry
+    }
+
+    #[tokio::test]
+    async fn install_prefers_priority_sends_auth_and_falls_back_to_cache() {
+        // GIVEN: two registries listing `weather`, the private one behind
+        // bearer auth and with the higher priority
+        let dir = TempDir::new().unwrap();
+        let (public_url, _) = serve(published_registry(&dir, "public", "Public.")).await;
+        let (private_url, private) = serve(published_registry(&dir, "private", "Private.")).await;
+        let config = RegistryConfig {
+            sources: vec![
+                source("public", &public_url, 0, RegistryAuthConfig::None),
+                source(
+                    "private",
+                    &private_url,
+                    10,
+                    RegistryAuthConfig::Bearer {
+                        token_env: "PATH".to_string(),
+                    },
+                ),
+            ],
+            cache_dir: dir.path().join("cache").to_string_lossy().into_owned(),
+        };
+        let registries = RemoteRegistries::new(&config).unwrap();
+        let dest = dir.path().join("capabilities");
+
+        // WHEN: installing it
+        let installed = registries.install("weather", &dest).await.unwrap();
+
+        // THEN: it comes from the private registry, with the token sent
+        assert_eq!(installed.registry, "private");
+        assert_eq!(installed.version.as_deref(), Some("1.0.0"));
+        assert!(!installed.cached);
+        assert!(
+            std::fs::read_to_string(&installed.path)
+                .unwrap()
+                .contains("Private.")
+        );
+        let token = std::env::var("PATH").unwrap();
+        assert!(
+            private
+                .auth
+                .lock()
+                .iter()
+                .all(|a| *a == format!("Bearer {token}"))
+        );
+
+        // AND: with the registry down, the cached copy is installed
+        *private.down.lock() = true;
+        std::fs::remove_file(&installed.path).unwrap();
+        let offline = registries.install("weather", &dest).await.unwrap();
+        assert_eq!(offline.registry, "private");
+        assert!(offline.cached);
+        assert!(
+            std::fs::read_to_string(&offline.path)
+                .unwrap()
+                .contains("Private.")
+        );
+    }
+
+    #[tokio::test]
+    async fn search_reports_unavailable_registries_and_rejects_bad_checksums() {
+        // GIVEN: one working registry whose package was tampered with, and
+        // one unreachable registry with no cache
+        let dir = TempDir::new().unwrap();
+        let root = published_registry(&dir, "public", "Public.");
+        std::fs::write(root.join("packages/weather/1.0.0.yaml"), "tampered").unwrap();
+        let (url, _) = serve(root).await;
+        let config = RegistryConfig {
+            sources: vec![
+                source("public", &url, 0, RegistryAuthConfig::None),
+                source("gone", "http://127.0.0.1:1", 0, RegistryAuthConfig::None),
+            ],
+            cache_dir: dir.path().join("cache").to_string_lossy().into_owned(),
+        };
+        let registries = RemoteRegistries::new(&config).unwrap();
+
+        // WHEN: searching
+        let search = registries.search("weather").await;
+
+        // THEN: the match is found and the dead registry is reported
+        assert_eq!(search.matches.len(), 1);
+        assert_eq!(search.matches[0].registry, "public");
+        assert_eq!(search.unavailable.len(), 1);
+        assert_eq!(search.unavailable[0].0, "gone");
+
+        // AND: installing fails on the checksum
+        let err = registries
+            .install("weather", &dir.path().join("capabilities"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn install_locked_fetches_the_locked_version() {
+        // GIVEN: weather 1.0.0 installed and locked, then 1.1.0 published
+        let dir = TempDir::new().unwrap();
+        let root = published_registry(&dir, "public", "Public.");
+        let (url, _) = serve(root.clone()).await;
+        let config = RegistryConfig {
+            sources: vec![source("public", &url, 0, RegistryAuthConfig::None)],
+            cache_dir: dir.path().join("cache").to_string_lossy().into_owned(),
+        };
+        let registries = RemoteRegistries::new(&config).unwrap();
+        let dest = dir.path().join("capabilities");
+        let first = registries.install("weather", &dest).await.unwrap();
+        let mut locked = LockedCapability {
+            path: "weather.yaml".to_string(),
+            sha256: first.sha256.clone(),
+            version: first.version.clone(),
+            registry: Some(first.registry.clone()),
+            package: Some(first.package.clone()),
+            publisher: None,
+            signature: None,
+        };
+        let newer = dir.path().join("newer.yaml");
+        std::fs::write(&newer, CAPABILITY.replace("1.0.0", "1.1.0")).unwrap();
+        publish_capability(&root, &newer, None, None).unwrap();
+
+        // WHEN: reinstalling from the lock
+        let again = registries
+            .install_locked("weather", &locked, &dest)
+            .await
+            .unwrap();
+
+        // THEN: the locked 1.0.0 package is installed, not the latest
+        assert_eq!(again.package, "packages/weather/1.0.0.yaml");
+        assert_eq!(again.sha256, first.sha256);
+
+        // AND: a checksum that does not match the package is refused
+        locked.sha256 = "0".repeat(64);
+        let err = registries
+            .install_locked("weather", &locked, &dest)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn install_enforces_the_registry_trust_policy() {
+        // GIVEN: a registry signed by acme and one with unsigned packages,
+        // both requiring acme's signature
+        let (seed, public_key) = generate_signing_key().unwrap();
+        let acme = SigningKey::from_seed("acme", &seed).unwrap();
+        let dir = TempDir::new().unwrap();
+        let (signed_url, _) = serve(signed_registry(&dir, "signed", "Signed.", Some(&acme))).await;
+        let (unsigned_url, _) = serve(published_registry(&dir, "unsigned", "Unsigned.")).await;
+        let strict = |name: &str, url: &str| RegistrySourceConfig {
+            public_keys: BTreeMap::from([("acme".to_string(), public_key.clone())]),
+            trust: RegistryTrustConfig {
+                require_signed: true,
+                allowed_publishers: vec!["acme".to_string()],
+            },
+            ..source(name, url, 0, RegistryAuthConfig::None)
+        };
+        let cache_dir = dir.path().join("cache").to_string_lossy().into_owned();
+        let dest = dir.path().join("capabilities");
+
+        // WHEN: installing from the signed registry
+        let registries = RemoteRegistries::new(&RegistryConfig {
+            sources: vec![strict("signed", &signed_url)],
+            cache_dir: cache_dir.clone(),
+        })
+        .unwrap();
+        let installed = registries.install("weather", &dest).await.unwrap();
+
+        // THEN: the verified publisher and signature are returned
+        assert_eq!(installed.publisher.as_deref(), Some("acme"));
+        assert!(installed.signature.is_some());
+
+        // AND: the unsigned package is refused and never written
+        std::fs::remove_file(&installed.path).unwrap();
+        let registries = RemoteRegistries::new(&RegistryConfig {
+            sources: vec![strict("unsigned", &unsigned_url)],
+            cache_dir,
+        })
+        .unwrap();
+        let err = registries.install("weather", &dest).await.unwrap_err();
+        assert!(err.to_string().contains("not signed"), "{err}");
+        assert!(!dest.join("weather.yaml").exists());
+    }
+
+    #[test]
+    fn cache_paths_cannot_escape_the_registry() {
+        let registries = RemoteRegistries::new(&RegistryConfig::default()).unwrap();
+        let source = source(
+            "public",
+            "https://r.example.com",
+            0,
+            RegistryAuthConfig::None,
+        );
+        assert!(
+            registries
+                .cache_file(&source, "packages/a/1.0.0.yaml")
+                .is_ok()
+        );
+        assert!(registries.cache_file(&source, "../../etc/passwd").is_err());
+        assert!(registries.cache_file(&source, "/etc/passwd").is_err());
+    }
+}
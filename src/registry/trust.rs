@@ -0,0 +1,355 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Registry trust policy and package signatures
+//!
+//! `cap publish --publisher <name> --signing-key-env <VAR>` signs each
+//! package with the publisher's Ed25519 key. The signature covers the
+//! capability name, version and package SHA-256, so neither the index entry
+//! nor the package can be altered without invalidating it. It is stored in
+//! the index entry next to the publisher name and copied into
+//! `capabilities.lock` on install.
+//!
+//! Each registry source lists its publishers' public keys and a trust
+//! policy (see [`RegistrySourceConfig`]). The policy is enforced twice:
+//! before an installed package is written, and when the gateway loads a
+//! capabilities directory, where every capability locked to a
+//! signature-checked registry must still match its signed checksum.
+
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde::Serialize;
+
+use super::lockfile::Lockfile;
+use super::publish::package_checksum;
+use crate::config::{RegistryConfig, RegistrySourceConfig};
+use crate::{Error, Result};
+
+/// Signed content of a package.
+#[derive(Serialize)]
+struct SignedPayload<'a> {
+    name: &'a str,
+    version: Option<&'a str>,
+    sha256: &'a str,
+}
+
+fn signing_payload(name: &str, version: Option<&str>, sha256: &str) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&SignedPayload {
+        name,
+        version,
+        sha256: &sha256.to_ascii_lowercase(),
+    })?)
+}
+
+/// A publisher's Ed25519 signing key.
+pub struct SigningKey {
+    publisher: String,
+    key_pair: Ed25519KeyPair,
+}
+
+impl SigningKey {
+    /// Key of `publisher` from a base64 32-byte seed, as printed by
+    /// `cap keygen`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `seed` is not 32 bytes of base64.
+    pub fn from_seed(publisher: &str, seed: &str) -> Result<Self> {
+        let seed = STANDARD
+            .decode(seed.trim())
+            .map_err(|e| Error::Config(format!("Signing key is not valid base64: {e}")))?;
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .map_err(|_| Error::Config("Signing key must be a 32-byte Ed25519 seed".to_string()))?;
+        Ok(Self {
+            publisher: publisher.to_string(),
+            key_pair,
+        })
+    }
+
+    /// Publisher name recorded with each signature.
+    #[must_use]
+    pub fn publisher(&self) -> &str {
+        &self.publisher
+    }
+
+    /// Base64 public key, for `registry.sources[].public_keys`.
+    #[must_use]
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(self.key_pair.public_key().as_ref())
+    }
+
+    /// Base64 signature of the package `name` `version` with checksum
+    /// `sha256`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload cannot be serialized.
+    pub fn sign(&self, name: &str, version: Option<&str>, sha256: &str) -> Result<String> {
+        let payload = signing_payload(name, version, sha256)?;
+        Ok(STANDARD.encode(self.key_pair.sign(&payload).as_ref()))
+    }
+}
+
+/// Generate a signing key seed; returns `(seed, public_key)`, both base64.
+///
+/// # Errors
+///
+/// Returns an error if the system random source fails.
+pub fn generate_signing_key() -> Result<(String, String)> {
+    let mut seed = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut seed)
+        .map_err(|_| Error::Config("Failed to generate a signing key".to_string()))?;
+    let seed = STANDARD.encode(seed);
+    let public_key = SigningKey::from_seed("", &seed)?.public_key();
+    Ok((seed, public_key))
+}
+
+/// Signature claims of one package, as listed in an index entry or
+/// lockfile.
+#[derive(Debug, Clone, Copy)]
+pub struct PackageClaims<'a> {
+    /// Capability name.
+    pub name: &'a str,
+    /// Capability version.
+    pub version: Option<&'a str>,
+    /// Publisher that signed the package.
+    pub publisher: Option<&'a str>,
+    /// Base64 Ed25519 signature.
+    pub signature: Option<&'a str>,
+}
+
+/// Check a package with checksum `sha256` against the trust policy of
+/// `source`. Returns the publisher whose signature was verified, or `None`
+/// for an unverified package the policy accepts.
+///
+/// # Errors
+///
+/// Returns an error if the signature is invalid, the publisher is not
+/// allowed, or the policy requires a signature the package does not carry
+/// or whose publisher key is not configured.
+pub fn verify_package(
+    source: &RegistrySourceConfig,
+    claims: PackageClaims<'_>,
+    sha256: &str,
+) -> Result<Option<String>> {
+    let refuse = |reason: String| {
+        Err(Error::Config(format!(
+            "Untrusted capability '{}' from registry '{}': {reason}",
+            claims.name, source.name
+        )))
+    };
+    let (Some(publisher), Some(signature)) = (claims.publisher, claims.signature) else {
+        if source.trust.require_signed {
+            return refuse("the package is not signed".to_string());
+        }
+        return Ok(None);
+    };
+    if !source.allows_publisher(publisher) {
+        return refuse(format!("publisher '{publisher}' is not allowed"));
+    }
+    let Some(key) = source.public_key(publisher) else {
+        if source.trust.require_signed {
+            return refuse(format!(
+                "no public key configured for publisher '{publisher}'"
+            ));
+        }
+        return Ok(None);
+    };
+    let payload = signing_payload(claims.name, claims.version, sha256)?;
+    let verified = STANDARD.decode(signature).is_ok_and(|sig| {
+        UnparsedPublicKey::new(&ED25519, key)
+            .verify(&payload, &sig)
+            .is_ok()
+    });
+    if !verified {
+        return refuse(format!("invalid signature by '{publisher}'"));
+    }
+    Ok(Some(publisher.to_string()))
+}
+
+/// A capability refused at load time by its registry's trust policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Untrusted {
+    /// Capability name.
+    pub name: String,
+    /// Installed file.
+    pub path: PathBuf,
+    /// Why it was refused.
+    pub reason: String,
+}
+
+/// Capabilities in `dir` whose `capabilities.lock` entry names a
+/// signature-checked registry but which no longer pass its policy: the
+/// locked signature does not verify, or the installed file differs from
+/// the signed checksum. Capabilities locked to unconfigured registries and
+/// local files are not checked.
+///
+/// # Errors
+///
+/// Returns an error if the lockfile cannot be read.
+pub fn untrusted_capabilities(dir: &Path, config: &RegistryConfig) -> Result<Vec<Untrusted>> {
+    let lock = Lockfile::load(dir)?;
+    let mut untrusted = Vec::new();
+    for (name, locked) in &lock.capabilities {
+        let Some(source) = locked
+            .registry
+            .as_ref()
+            .and_then(|registry| config.sources.iter().find(|s| &s.name == registry))
+            .filter(|source| source.verifies_signatures())
+        else {
+            continue;
+        };
+        let path = dir.join(&locked.path);
+        let claims = PackageClaims {
+            name,
+            version: locked.version.as_deref(),
+            publisher: locked.publisher.as_deref(),
+            signature: locked.signature.as_deref(),
+        };
+        let reason = match std::fs::read(&path) {
+            Ok(content) if package_checksum(&content) != locked.sha256 => Some(format!(
+                "{} was modified after install from registry '{}'",
+                locked.path, source.name
+            )),
+            Ok(_) => verify_package(source, claims, &locked.sha256)
+                .err()
+                .map(|e| e.to_string()),
+            Err(_) => None,
+        };
+        if let Some(reason) = reason {
+            untrusted.push(Untrusted {
+                name: name.clone(),
+                path,
+                reason,
+            });
+        }
+    }
+    Ok(untrusted)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config::{RegistryAuthConfig, RegistryTrustConfig};
+    use crate::registry::lockfile::LockedCapability;
+
+    const SHA: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn key(publisher: &str) -> SigningKey {
+        let (seed, _) = generate_signing_key().unwrap();
+        SigningKey::from_seed(publisher, &seed).unwrap()
+    }
+
+    fn source(
+        keys: &[&SigningKey],
+        require_signed: bool,
+        allowed: &[&str],
+    ) -> RegistrySourceConfig {
+        RegistrySourceConfig {
+            name: "community".to_string(),
+            url: "https://r.example.org".to_string(),
+            priority: 0,
+            auth: RegistryAuthConfig::None,
+            public_keys: keys
+                .iter()
+                .map(|k| (k.publisher().to_string(), k.public_key()))
+                .collect::<BTreeMap<_, _>>(),
+            trust: RegistryTrustConfig {
+                require_signed,
+                allowed_publishers: allowed.iter().map(|p| (*p).to_string()).collect(),
+            },
+        }
+    }
+
+    fn claims<'a>(publisher: Option<&'a str>, signature: Option<&'a str>) -> PackageClaims<'a> {
+        PackageClaims {
+            name: "weather",
+            version: Some("1.0.0"),
+            publisher,
+            signature,
+        }
+    }
+
+    #[test]
+    fn verifies_signatures_and_enforces_the_policy() {
+        // GIVEN: a registry trusting acme, requiring signatures
+        let acme = key("acme");
+        let mallory = key("mallory");
+        let strict = source(&[&acme, &mallory], true, &["acme"]);
+        let signature = acme.sign("weather", Some("1.0.0"), SHA).unwrap();
+
+        // THEN: acme's signature verifies
+        let verified = verify_package(&strict, claims(Some("acme"), Some(&signature)), SHA);
+        assert_eq!(verified.unwrap().as_deref(), Some("acme"));
+
+        // AND: a different checksum, an unsigned package and a publisher
+        // outside the allow-list are refused
+        let other = "0".repeat(64);
+        let err =
+            verify_package(&strict, claims(Some("acme"), Some(&signature)), &other).unwrap_err();
+        assert!(err.to_string().contains("invalid signature"), "{err}");
+        assert!(verify_package(&strict, claims(None, None), SHA).is_err());
+        let forged = mallory.sign("weather", Some("1.0.0"), SHA).unwrap();
+        let err = verify_package(&strict, claims(Some("mallory"), Some(&forged)), SHA).unwrap_err();
+        assert!(err.to_string().contains("not allowed"), "{err}");
+
+        // AND: without require_signed, unsigned packages pass unverified
+        let lenient = source(&[&acme], false, &[]);
+        assert_eq!(
+            verify_package(&lenient, claims(None, None), SHA).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn load_time_check_detects_tampered_and_unsigned_installs() {
+        // GIVEN: two capabilities locked to a strict registry, one signed
+        let acme = key("acme");
+        let dir = TempDir::new().unwrap();
+        let mut lock = Lockfile::default();
+        for (name, signed) in [("weather", true), ("news", false)] {
+            let content = format!("name: {name}\n");
+            std::fs::write(dir.path().join(format!("{name}.yaml")), &content).unwrap();
+            let sha256 = package_checksum(content.as_bytes());
+            let signature = signed.then(|| acme.sign(name, None, &sha256).unwrap());
+            lock.capabilities.insert(
+                name.to_string(),
+                LockedCapability {
+                    path: format!("{name}.yaml"),
+                    sha256,
+                    version: None,
+                    registry: Some("community".to_string()),
+                    package: Some(format!("packages/{name}/1.0.0.yaml")),
+                    publisher: signed.then(|| "acme".to_string()),
+                    signature,
+                },
+            );
+        }
+        lock.save(dir.path()).unwrap();
+        let config = RegistryConfig {
+            sources: vec![source(&[&acme], true, &[])],
+            ..RegistryConfig::default()
+        };
+
+        // WHEN: checking the directory
+        let untrusted = untrusted_capabilities(dir.path(), &config).unwrap();
+
+        // THEN: only the unsigned capability is refused
+        assert_eq!(untrusted.len(), 1);
+        assert_eq!(untrusted[0].name, "news");
+
+        // AND: editing the signed one after install refuses it too
+        std::fs::write(dir.path().join("weather.yaml"), "name: weather\n# edited\n").unwrap();
+        let untrusted = untrusted_capabilities(dir.path(), &config).unwrap();
+        assert_eq!(untrusted.len(), 2);
+        assert!(untrusted[1].reason.contains("modified after install"));
+    }
+}
@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Finding newer versions of installed capabilities
+//!
+//! Backs `mcp-gateway cap outdated` and `cap update`: each installed
+//! capability with a `version:` is compared with the version its registry
+//! lists — the locked registry when `capabilities.lock` records one,
+//! otherwise the highest-priority registry listing the name.
+
+use super::lockfile::Lockfile;
+use super::publish::SemVer;
+use super::remote::RemoteIndex;
+use super::{ChangelogEntry, RegistryEntry};
+
+/// A newer published version of an installed capability.
+#[derive(Debug, Clone)]
+pub struct AvailableUpdate {
+    /// Capability name.
+    pub name: String,
+    /// Installed version.
+    pub installed: String,
+    /// Latest published version.
+    pub latest: String,
+    /// Registry listing `latest`.
+    pub registry: String,
+    /// Installed file, relative to the capabilities directory.
+    pub path: String,
+    /// Index entry of `latest`.
+    pub entry: RegistryEntry,
+    /// Changelog entries newer than `installed`, newest first.
+    pub changes: Vec<ChangelogEntry>,
+}
+
+impl AvailableUpdate {
+    /// Whether the update crosses a major version (a minor one below 1.0),
+    /// i.e. may break callers.
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        match (SemVer::parse(&self.installed), SemVer::parse(&self.latest)) {
+            (Ok(installed), Ok(latest)) => {
+                latest.major > installed.major
+                    || (installed.major == 0 && latest.minor > installed.minor)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Newer versions of the capabilities in `installed` (see
+/// [`Lockfile::scan`]) listed by `indexes`, sorted by name. Capabilities
+/// without a semver `version:` cannot be compared and are skipped.
+#[must_use]
+pub fn find_updates(installed: &Lockfile, indexes: &[RemoteIndex]) -> Vec<AvailableUpdate> {
+    let mut updates = Vec::new();
+    for (name, locked) in &installed.capabilities {
+        let Some(current) = locked.version.as_deref() else {
+            continue;
+        };
+        let Ok(current_semver) = SemVer::parse(current) else {
+            continue;
+        };
+        let listed = indexes
+            .iter()
+            .filter(|remote| {
+                locked
+                    .registry
+                    .as_ref()
+                    .is_none_or(|registry| *registry == remote.registry)
+            })
+            .find_map(|remote| remote.index.find(name).map(|entry| (remote, entry)));
+        let Some((remote, entry)) = listed else {
+            continue;
+        };
+        let Some(latest) = entry.version.as_deref() else {
+            continue;
+        };
+        if !SemVer::parse(latest).is_ok_and(|latest| latest > current_semver) {
+            continue;
+        }
+        let changes = entry
+            .changelog
+            .iter()
+            .filter(|change| SemVer::parse(&change.version).is_ok_and(|v| v > current_semver))
+            .cloned()
+            .collect();
+        updates.push(AvailableUpdate {
+            name: name.clone(),
+            installed: current.to_string(),
+            latest: latest.to_string(),
+            registry: remote.registry.clone(),
+            path: locked.path.clone(),
+            entry: entry.clone(),
+            changes,
+        });
+    }
+    updates
+}
+
+/// First line of `notes`, cut to `max` characters.
+#[must_use]
+pub fn changelog_snippet(notes: &str, max: usize) -> String {
+    let line = notes.lines().next().unwrap_or_default().trim();
+    if line.chars().count() <= max {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(max.saturating_sub(1)).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::RegistryIndex;
+    use crate::registry::lockfile::LockedCapability;
+
+    fn locked(version: &str, registry: Option<&str>) -> LockedCapability {
+        LockedCapability {
+            path: "weather.yaml".to_string(),
+            sha256: "0".repeat(64),
+            version: Some(version.to_string()),
+            registry: registry.map(str::to_string),
+            package: None,
+            publisher: None,
+            signature: None,
+        }
+    }
+
+    fn index(registry: &str, version: &str, changelog: &[(&str, &str)]) -> RemoteIndex {
+        RemoteIndex {
+            registry: registry.to_string(),
+            index: RegistryIndex::new(vec![RegistryEntry {
+                name: "weather".to_string(),
+                version: Some(version.to_string()),
+                changelog: changelog
+                    .iter()
+                    .map(|(version, notes)| ChangelogEntry {
+                        version: (*version).to_string(),
+                        notes: (*notes).to_string(),
+                        published_at: None,
+                    })
+                    .collect(),
+                ..RegistryEntry::default()
+            }]),
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn finds_newer_versions_with_unseen_changelog_entries() {
+        // GIVEN: weather 1.0.0 installed; the public registry lists 2.0.0
+        let mut installed = Lockfile::default();
+        installed
+            .capabilities
+            .insert("weather".to_string(), locked("1.0.0", None));
+        let indexes = [index(
+            "public",
+            "2.0.0",
+            &[
+                ("2.0.0", "Units required"),
+                ("1.1.0", "Adds units"),
+                ("1.0.0", "Initial"),
+            ],
+        )];
+
+        // WHEN: looking for updates
+        let updates = find_updates(&installed, &indexes);
+
+        // THEN: 2.0.0 is offered with the two newer changelog entries
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].latest, "2.0.0");
+        assert_eq!(updates[0].registry, "public");
+        assert!(updates[0].is_breaking());
+        let versions: Vec<_> = updates[0]
+            .changes
+            .iter()
+            .map(|c| c.version.as_str())
+            .collect();
+        assert_eq!(versions, ["2.0.0", "1.1.0"]);
+    }
+
+    #[test]
+    fn locked_registry_is_the_only_source_and_current_versions_are_skipped() {
+        let mut installed = Lockfile::default();
+        installed
+            .capabilities
+            .insert("weather".to_string(), locked("1.0.0", Some("internal")));
+        let indexes = [
+            index("public", "3.0.0", &[]),
+            index("internal", "1.0.0", &[]),
+        ];
+
+        assert!(find_updates(&installed, &indexes).is_empty());
+    }
+
+    #[test]
+    fn snippet_takes_the_first_line_and_truncates() {
+        assert_eq!(changelog_snippet("Adds units\nDetails", 80), "Adds units");
+        assert_eq!(changelog_snippet("abcdefghij", 5), "abcd…");
+    }
+}
@@ -24,9 +24,14 @@
 //! 3. Otherwise → allowed.
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::cluster::SharedState;
 
 // ============================================================================
 // Configuration types (deserialized from YAML)
@@ -45,6 +50,7 @@ use serde::{Deserialize, Serialize};
 ///   coding:
 ///     description: "Software development — no social or email tools"
 ///     deny_tools: ["gmail_*", "slack_*"]
+///     weight: 2
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RoutingProfileConfig {
@@ -71,6 +77,12 @@ pub struct RoutingProfileConfig {
     /// Evaluated after `allow_tools`.
     #[serde(default)]
     pub deny_tools: Option<Vec<String>>,
+
+    /// Fair-share weight of sessions bound to this profile when they contend
+    /// for a saturated backend. `None` means 1; a weight-2 session is admitted
+    /// twice as often as a weight-1 session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
 }
 
 // ============================================================================
@@ -84,6 +96,8 @@ pub struct RoutingProfile {
     pub name: String,
     /// Human-readable description (e.g. `"Web research tools only"`).
     pub description: String,
+    /// Fair-share weight for backend scheduling (at least 1).
+    pub weight: u32,
     /// Compiled backend filter.
     backend_filter: PatternFilter,
     /// Compiled tool filter.
@@ -97,6 +111,7 @@ impl RoutingProfile {
         Self {
             name: name.to_string(),
             description: config.description.clone(),
+            weight: config.weight.unwrap_or(1).max(1),
             backend_filter: PatternFilter::new(
                 config.allow_backends.as_deref(),
                 config.deny_backends.as_deref(),
@@ -116,6 +131,7 @@ impl RoutingProfile {
         Self {
             name: name.to_string(),
             description: "All tools (unrestricted)".to_string(),
+            weight: 1,
             backend_filter: PatternFilter::allow_all(),
             tool_filter: PatternFilter::allow_all(),
         }
@@ -166,6 +182,7 @@ impl RoutingProfile {
         serde_json::json!({
             "name": self.name,
             "description": self.description,
+            "weight": self.weight,
             "backend_filter": self.backend_filter.describe(),
             "tool_filter": self.tool_filter.describe(),
         })
@@ -409,11 +426,16 @@ impl Default for ProfileRegistry {
 
 /// Thread-safe store that maps session IDs to their active profile name.
 ///
-/// New sessions automatically receive the registry's default profile.
+/// New sessions automatically receive the registry's default profile. With
+/// [`with_shared`](Self::with_shared) bindings are also kept in the cluster
+/// state, so a session keeps its profile on whichever replica serves it; the
+/// shared binding wins over the local one.
 #[derive(Debug, Default)]
 pub struct SessionProfileStore {
     /// `session_id` → `profile_name`
     sessions: RwLock<HashMap<String, String>>,
+    /// Cluster state and how long a binding lives there.
+    shared: Option<(Arc<dyn SharedState>, Duration)>,
 }
 
 impl SessionProfileStore {
@@ -423,11 +445,26 @@ impl SessionProfileStore {
         Self::default()
     }
 
+    /// Also keep bindings in the cluster state, expiring `ttl` after they
+    /// were last set.
+    #[must_use]
+    pub fn with_shared(mut self, shared: Arc<dyn SharedState>, ttl: Duration) -> Self {
+        self.shared = Some((shared, ttl));
+        self
+    }
+
     /// Get the active profile name for a session.
     ///
     /// Returns `default_name` when the session has no explicit assignment.
     #[must_use]
     pub fn get_profile_name(&self, session_id: &str, default_name: &str) -> String {
+        if let Some((shared, _)) = &self.shared {
+            match shared.get(&shared_key(session_id)) {
+                Ok(Some(name)) => return name,
+                Ok(None) => {}
+                Err(e) => warn!(error = %e, "Shared session profile unavailable"),
+            }
+        }
         self.sessions
             .read()
             .get(session_id)
@@ -440,14 +477,29 @@ impl SessionProfileStore {
         self.sessions
             .write()
             .insert(session_id.to_string(), profile_name.to_string());
+        if let Some((shared, ttl)) = &self.shared
+            && let Err(e) = shared.put(&shared_key(session_id), profile_name, *ttl)
+        {
+            warn!(error = %e, "Session profile not shared with other replicas");
+        }
     }
 
     /// Remove a session (called on session teardown).
     pub fn remove_session(&self, session_id: &str) {
         self.sessions.write().remove(session_id);
+        if let Some((shared, _)) = &self.shared
+            && let Err(e) = shared.delete(&shared_key(session_id))
+        {
+            warn!(error = %e, "Shared session profile not removed");
+        }
     }
 }
 
+/// Cluster state key of a session's profile binding.
+fn shared_key(session_id: &str) -> String {
+    format!("profile:{session_id}")
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -16,6 +16,7 @@ fn profile_from(
         deny_tools: deny_tools.map(|s| s.iter().map(|x| (*x).to_string()).collect()),
         allow_backends: allow_backends.map(|s| s.iter().map(|x| (*x).to_string()).collect()),
         deny_backends: deny_backends.map(|s| s.iter().map(|x| (*x).to_string()).collect()),
+        weight: None,
     };
     RoutingProfile::from_config("test", &cfg)
 }
@@ -395,6 +396,31 @@ fn profile_summaries_empty_when_no_profiles_configured() {
     assert!(registry.profile_summaries().is_empty());
 }
 
+#[test]
+fn profile_weight_defaults_to_one_and_clamps_zero() {
+    // GIVEN: profiles with no weight, weight 0, and weight 3
+    let unset = RoutingProfile::from_config("a", &RoutingProfileConfig::default());
+    let zero = RoutingProfile::from_config(
+        "b",
+        &RoutingProfileConfig {
+            weight: Some(0),
+            ..Default::default()
+        },
+    );
+    let three = RoutingProfile::from_config(
+        "c",
+        &RoutingProfileConfig {
+            weight: Some(3),
+            ..Default::default()
+        },
+    );
+    // THEN: weights are at least 1
+    assert_eq!(unset.weight, 1);
+    assert_eq!(zero.weight, 1);
+    assert_eq!(three.weight, 3);
+    assert_eq!(RoutingProfile::allow_all("x").weight, 1);
+}
+
 // ── SessionProfileStore ──────────────────────────────────────────────
 
 #[test]
@@ -426,3 +452,20 @@ fn session_store_isolates_different_sessions() {
     assert_eq!(store.get_profile_name("s1", "default"), "research");
     assert_eq!(store.get_profile_name("s2", "default"), "coding");
 }
+
+#[test]
+fn session_store_shares_bindings_between_replicas() {
+    // GIVEN: two replicas sharing one cluster state
+    let shared: Arc<dyn crate::cluster::SharedState> = Arc::new(crate::cluster::MemoryState::new());
+    let ttl = std::time::Duration::from_secs(60);
+    let replica_a = SessionProfileStore::new().with_shared(Arc::clone(&shared), ttl);
+    let replica_b = SessionProfileStore::new().with_shared(shared, ttl);
+
+    // WHEN: the session picks a profile on replica a
+    replica_a.set_profile("s1", "coding");
+
+    // THEN: replica b routes it with that profile, until the session ends
+    assert_eq!(replica_b.get_profile_name("s1", "research"), "coding");
+    replica_a.remove_session("s1");
+    assert_eq!(replica_b.get_profile_name("s1", "research"), "research");
+}
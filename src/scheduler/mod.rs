@@ -71,6 +71,19 @@ fn default_true() -> bool {
     true
 }
 
+impl SchedulerConfig {
+    /// Check cron expressions and job name uniqueness.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigValidation`] naming the first invalid job.
+    pub fn validate(&self) -> Result<()> {
+        CronScheduler::from_config(self.clone())
+            .map(|_| ())
+            .map_err(|e| Error::ConfigValidation(format!("scheduler: {e}")))
+    }
+}
+
 /// Serialisable action definition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -259,6 +272,18 @@ pub enum ScheduleAction {
     },
 }
 
+impl ScheduleAction {
+    /// Short label for logs and audit entries (`playbook:name` or
+    /// `server:tool`).
+    #[must_use]
+    pub fn target(&self) -> String {
+        match self {
+            Self::RunPlaybook(name) => format!("playbook:{name}"),
+            Self::InvokeTool { server, tool, .. } => format!("{server}:{tool}"),
+        }
+    }
+}
+
 impl From<ActionConfig> for ScheduleAction {
     fn from(cfg: ActionConfig) -> Self {
         match cfg {
@@ -457,3 +457,34 @@ jobs:
     assert_eq!(cfg.jobs[0].name, "daily-sync");
     assert_eq!(cfg.jobs[1].name, "hourly-check");
 }
+
+#[test]
+fn scheduler_config_validate_rejects_bad_cron() {
+    let yaml = r#"
+enabled: true
+jobs:
+  - name: broken
+    cron: "61 * * * *"
+    action:
+      type: run_playbook
+      playbook: sync-data
+"#;
+    let cfg: SchedulerConfig = serde_yaml::from_str(yaml).expect("valid yaml");
+    let err = cfg.validate().unwrap_err();
+    assert!(matches!(err, Error::ConfigValidation(_)), "{err}");
+    assert!(err.to_string().contains("scheduler"), "{err}");
+}
+
+#[test]
+fn schedule_action_target_labels() {
+    assert_eq!(
+        ScheduleAction::RunPlaybook("sync".to_string()).target(),
+        "playbook:sync"
+    );
+    let tool = ScheduleAction::InvokeTool {
+        server: "monitoring".to_string(),
+        tool: "health_check".to_string(),
+        arguments: HashMap::new(),
+    };
+    assert_eq!(tool.target(), "monitoring:health_check");
+}
@@ -23,7 +23,9 @@
 //! - Agents never receive raw credential values (injection happens after the agent call)
 //! - Domain-scoped: credentials only flow to their intended backend
 //! - Header overwrite protection: injected headers overwrite any agent-supplied duplicates
-//! - Audit trail: every injection is logged with backend, tool, credential name, and timestamp
+//! - Audit trail: every injection is recorded in the secret access audit trail
+//!   ([`crate::secrets::audit`]) with backend, tool, client, credential reference, and
+//!   timestamp — never the value
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -110,6 +112,28 @@ pub enum InjectTarget {
     Query,
 }
 
+impl InjectTarget {
+    /// Name used in audit entries.
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Argument => "argument",
+            Self::Header => "header",
+            Self::Query => "query",
+        }
+    }
+}
+
+/// What the audit trail names a rule's credential by: its `{…}` reference,
+/// or the rule name when the value is a literal that must not be logged.
+fn audit_reference(rule: &CredentialRule) -> &str {
+    let value = rule.value.trim();
+    if value.starts_with('{') && value.ends_with('}') && value.matches('{').count() == 1 {
+        value
+    } else {
+        &rule.name
+    }
+}
+
 fn default_credential_type() -> CredentialType {
     CredentialType::ApiKey
 }
@@ -268,6 +292,10 @@ impl SecretInjector {
                 }
             }
 
+            crate::secrets::audit::injected(
+                audit_reference(rule),
+                &format!("{}:{}", rule.inject_as.as_str(), rule.inject_key),
+            );
             injected_names.push(rule.name.clone());
 
             // Audit log: credential injected
@@ -2,8 +2,14 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Secret resolution with keychain integration
 //!
-//! Resolves credential patterns like `{keychain.SERVICE}` and `{env.VAR}`
-//! from secure system keychains and environment variables.
+//! Resolves credential patterns like `{keychain.SERVICE}`, `{env.VAR}` and
+//! `{vault.MOUNT/PATH#FIELD}` from secure system keychains, environment
+//! variables and a Vault server ([`vault`]), `{aws-secrets.NAME#FIELD}` /
+//! `{aws-ssm.NAME}` from AWS ([`aws`]), and `{op.VAULT/ITEM/FIELD}` /
+//! `{bw.ITEM#FIELD}` from the 1Password and Bitwarden CLIs
+//! ([`password_manager`]), and `{secret.KEY}` from an encrypted secrets file
+//! ([`encrypted_file`]). Every resolution is recorded in the secret access
+//! audit trail ([`audit`]).
 
 #[cfg(any(target_os = "macos", target_os = "linux"))]
 use std::process::Command;
@@ -12,6 +18,12 @@ use dashmap::DashMap;
 
 use crate::{Error, Result};
 
+pub mod audit;
+pub mod aws;
+pub mod encrypted_file;
+pub mod password_manager;
+pub mod vault;
+
 /// Secret resolver with caching
 pub struct SecretResolver {
     /// Cached resolved secrets for the session
@@ -32,6 +44,12 @@ impl SecretResolver {
     /// Supports:
     /// - `{keychain.SERVICE}` - macOS Keychain or Linux secret-tool
     /// - `{env.VAR}` - Environment variable
+    /// - `{vault.MOUNT/PATH#FIELD}` - Vault KV v2 field (needs `vault:` config)
+    /// - `{aws-secrets.NAME}` / `{aws-secrets.NAME#FIELD}` - AWS Secrets Manager
+    /// - `{aws-ssm.NAME}` - AWS SSM Parameter Store
+    /// - `{op.VAULT/ITEM/FIELD}` - 1Password, via the `op` CLI
+    /// - `{bw.ITEM}` / `{bw.ITEM#FIELD}` - Bitwarden, via the `bw` CLI
+    /// - `{secret.KEY.PATH}` - SOPS/age secrets file (needs `secrets_file:` config)
     ///
     /// # Example
     ///
@@ -43,7 +61,8 @@ impl SecretResolver {
     ///
     /// # Errors
     ///
-    /// Returns an error if a keychain entry is not found or cannot be accessed.
+    /// Returns an error if a keychain entry or a Vault, AWS or password
+    /// manager secret is not found or cannot be accessed.
     ///
     /// # Panics
     ///
@@ -60,18 +79,33 @@ impl SecretResolver {
 
             // Check cache first
             let secret = if let Some(cached) = self.cache.get(service) {
-                cached.clone()
+                Ok(cached.clone())
             } else {
-                // Fetch from keychain
-                let secret = Self::fetch_from_keychain(service)?;
-                // Cache it
-                self.cache.insert(service.to_string(), secret.clone());
-                secret
+                // Fetch from keychain and cache it
+                let fetched = Self::fetch_from_keychain(service);
+                if let Ok(secret) = &fetched {
+                    self.cache.insert(service.to_string(), secret.clone());
+                }
+                fetched
             };
+            audit::resolved(placeholder, secret.is_ok());
+            let secret = secret?;
 
             result = result.replace(placeholder, &secret);
         }
 
+        // Find all {vault.X}, {aws-secrets.X}, {aws-ssm.X}, {op.X}, {bw.X} and
+        // {secret.X} patterns. Not cached here: the remote clients re-read
+        // after their `cache_ttl` so rotated secrets are picked up.
+        #[allow(clippy::unwrap_used)]
+        let remote_pattern =
+            regex::Regex::new(r"\{(vault|aws-secrets|aws-ssm|op|bw|secret)\.([^}]+)\}").unwrap();
+        for caps in remote_pattern.captures_iter(value) {
+            let secret = Self::fetch_remote(&caps[1], &caps[2], &caps[0]);
+            audit::resolved(&caps[0], secret.is_ok());
+            result = result.replace(&caps[0], &secret?);
+        }
+
         // Find all {env.X} patterns
         #[allow(clippy::unwrap_used)]
         let env_pattern = regex::Regex::new(r"\{env\.([^}]+)\}").unwrap();
@@ -79,13 +113,38 @@ impl SecretResolver {
             let var_name = &caps[1];
             let placeholder = &caps[0];
 
-            let value = std::env::var(var_name).unwrap_or_default();
-            result = result.replace(placeholder, &value);
+            let value = std::env::var(var_name);
+            audit::resolved(placeholder, value.is_ok());
+            result = result.replace(placeholder, &value.unwrap_or_default());
         }
 
         Ok(result)
     }
 
+    /// Fetch `reference` from the secret store named by `source`
+    fn fetch_remote(source: &str, reference: &str, placeholder: &str) -> Result<String> {
+        match source {
+            "vault" => vault::client()
+                .ok_or_else(|| {
+                    Error::Config(format!(
+                        "'{placeholder}' needs a `vault:` section in the gateway config"
+                    ))
+                })?
+                .read_blocking(reference),
+            "aws-secrets" => {
+                aws::client().read_blocking(aws::AwsSecretSource::SecretsManager, reference)
+            }
+            "aws-ssm" => {
+                aws::client().read_blocking(aws::AwsSecretSource::ParameterStore, reference)
+            }
+            "op" => password_manager::client()
+                .read(password_manager::PasswordManager::OnePassword, reference),
+            "bw" => password_manager::client()
+                .read(password_manager::PasswordManager::Bitwarden, reference),
+            _ => encrypted_file::lookup(reference),
+        }
+    }
+
     /// Fetch a secret from the system keychain
     ///
     /// # Platform Support
@@ -167,6 +226,26 @@ impl SecretResolver {
     }
 }
 
+/// Run an async secret lookup to completion from synchronous code.
+///
+/// The future runs on a helper thread with its own runtime, so this is safe
+/// to call from inside a Tokio worker (it blocks that worker meanwhile, like
+/// a keychain lookup does).
+fn block_on<T: Send>(lookup: impl Future<Output = Result<T>> + Send) -> Result<T> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(|e| Error::Config(format!("Failed to start secret lookup: {e}")))?
+                    .block_on(lookup)
+            })
+            .join()
+            .unwrap_or_else(|_| Err(Error::Config("Secret lookup panicked".to_string())))
+    })
+}
+
 impl Default for SecretResolver {
     fn default() -> Self {
         Self::new()
@@ -224,6 +303,24 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
+    #[test]
+    fn test_vault_pattern_without_vault_config() {
+        let resolver = SecretResolver::new();
+        let err = resolver
+            .resolve("Bearer {vault.secret/crm#token}")
+            .unwrap_err();
+        assert!(err.to_string().contains("vault:"), "{err}");
+    }
+
+    #[test]
+    fn test_secret_pattern_without_secrets_file() {
+        let resolver = SecretResolver::new();
+        let err = resolver
+            .resolve("Bearer {secret.crm.api_token}")
+            .unwrap_err();
+        assert!(err.to_string().contains("secrets_file:"), "{err}");
+    }
+
     #[test]
     fn test_default_impl() {
         let resolver = SecretResolver::default();
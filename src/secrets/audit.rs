@@ -0,0 +1,245 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Audit trail of secret access.
+//!
+//! Every resolution of a credential reference (`{keychain.X}`, `vault:X`,
+//! `env:X`, …) and every injection of a resolved credential into an outbound
+//! request is recorded:
+//!
+//! - as a `secret_access` event via `tracing::info!`, and
+//! - in the tamper-evident transparency log, when it is enabled.
+//!
+//! An event names the reference, the backend and tool, and the client and
+//! session of the tool call that triggered it (see [`with_accessor`]). The
+//! resolved value is never part of an event.
+//!
+//! # Entry schema
+//!
+//! ```json
+//! {
+//!   "action": "secret_resolve",        // or "secret_inject"
+//!   "reference": "vault:secret/crm#token",
+//!   "source": "vault",
+//!   "outcome": "ok",                   // or "error"
+//!   "backend": "crm",
+//!   "tool": "search_contacts",
+//!   "client": "api-key-prod",
+//!   "agent": "support-bot",
+//!   "session_id": "sess-123",
+//!   "trace_id": "gw-…",
+//!   "injected_into": "header:Authorization",   // secret_inject only
+//!   "timestamp": "2026-10-15T12:00:00Z"
+//! }
+//! ```
+
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+
+use serde_json::{Map, Value};
+use tracing::{info, warn};
+
+use crate::security::TransparencyLogger;
+
+tokio::task_local! {
+    /// The tool call on whose behalf secrets are accessed.
+    ///
+    /// Set by [`with_accessor`]; read when an event is recorded.
+    static ACCESSOR: SecretAccessor;
+}
+
+/// Transparency log the events are appended to, once [`install`] ran.
+static LOGGER: OnceLock<Arc<TransparencyLogger>> = OnceLock::new();
+
+/// Append secret access events to `logger` from now on.
+pub fn install(logger: Arc<TransparencyLogger>) {
+    let _ = LOGGER.set(logger);
+}
+
+/// The tool call that triggered a secret access.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretAccessor {
+    /// Backend or capability server called.
+    pub backend: String,
+    /// Tool called.
+    pub tool: String,
+    /// Authenticated client (API key name), when known.
+    pub client: Option<String>,
+    /// Agent identity, when known.
+    pub agent: Option<String>,
+    /// MCP session, when known.
+    pub session_id: Option<String>,
+}
+
+/// Run `future` with `accessor` attributed to every secret access in it.
+pub async fn with_accessor<F: Future>(accessor: SecretAccessor, future: F) -> F::Output {
+    ACCESSOR.scope(accessor, future).await
+}
+
+/// Record that `reference` was resolved, successfully or not.
+pub fn resolved(reference: &str, ok: bool) {
+    record(
+        LOGGER.get().map(AsRef::as_ref),
+        "secret_resolve",
+        reference,
+        ok,
+        None,
+    );
+}
+
+/// Record that the secret `reference` was injected into `target`, e.g.
+/// `header:Authorization`.
+pub fn injected(reference: &str, target: &str) {
+    record(
+        LOGGER.get().map(AsRef::as_ref),
+        "secret_inject",
+        reference,
+        true,
+        Some(target),
+    );
+}
+
+/// Emit one event and append it to `logger`.
+fn record(
+    logger: Option<&TransparencyLogger>,
+    action: &'static str,
+    reference: &str,
+    ok: bool,
+    injected_into: Option<&str>,
+) {
+    let fields = event_fields(action, reference, ok, injected_into);
+    let field = |name: &str| fields.get(name).and_then(Value::as_str).unwrap_or_default();
+    info!(
+        action,
+        reference,
+        source = field("source"),
+        outcome = field("outcome"),
+        backend = field("backend"),
+        tool = field("tool"),
+        client = field("client"),
+        "secret_access"
+    );
+    if let Some(logger) = logger
+        && let Err(e) = logger.append_event(fields)
+    {
+        warn!(action, reference, error = %e, "Failed to write secret access audit entry");
+    }
+}
+
+/// Audit fields of one event, attributed to the current accessor.
+fn event_fields(
+    action: &'static str,
+    reference: &str,
+    ok: bool,
+    injected_into: Option<&str>,
+) -> Map<String, Value> {
+    let mut fields = Map::new();
+    fields.insert("action".into(), action.into());
+    fields.insert("reference".into(), reference.into());
+    fields.insert("source".into(), source_of(reference).into());
+    fields.insert("outcome".into(), if ok { "ok" } else { "error" }.into());
+    if let Ok(accessor) = ACCESSOR.try_with(Clone::clone) {
+        fields.insert("backend".into(), accessor.backend.into());
+        fields.insert("tool".into(), accessor.tool.into());
+        for (name, value) in [
+            ("client", accessor.client),
+            ("agent", accessor.agent),
+            ("session_id", accessor.session_id),
+        ] {
+            if let Some(value) = value {
+                fields.insert(name.into(), value.into());
+            }
+        }
+    }
+    if let Some(trace_id) = crate::gateway::trace::current() {
+        fields.insert("trace_id".into(), trace_id.into());
+    }
+    if let Some(target) = injected_into {
+        fields.insert("injected_into".into(), target.into());
+    }
+    fields.insert("timestamp".into(), chrono::Utc::now().to_rfc3339().into());
+    fields
+}
+
+/// Secret store a reference reads from: `keychain` for `{keychain.X}` and
+/// `keychain:X`, `env` for a bare `VAR_NAME`.
+fn source_of(reference: &str) -> &str {
+    let reference = reference.trim_start_matches('{');
+    reference
+        .find(['.', ':'])
+        .map_or("env", |end| &reference[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::TransparencyLogConfig;
+
+    #[tokio::test]
+    async fn events_name_the_reference_and_the_triggering_call() {
+        // GIVEN: a transparency log and a tool call accessing two secrets
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let logger = TransparencyLogger::open(Arc::new(TransparencyLogConfig {
+            enabled: true,
+            path: path.to_string_lossy().into_owned(),
+            key_id: "test".to_string(),
+            shared_secret: String::new(),
+        }))
+        .unwrap();
+        let accessor = SecretAccessor {
+            backend: "crm".to_string(),
+            tool: "search".to_string(),
+            client: Some("api-key-prod".to_string()),
+            agent: None,
+            session_id: Some("sess-1".to_string()),
+        };
+
+        // WHEN: recording a resolution and an injection within the call
+        with_accessor(accessor, async {
+            record(
+                Some(&logger),
+                "secret_resolve",
+                "{vault.secret/crm#token}",
+                true,
+                None,
+            );
+            record(
+                Some(&logger),
+                "secret_inject",
+                "vault:secret/crm#token",
+                true,
+                Some("header:Authorization"),
+            );
+        })
+        .await;
+        // AND: a resolution outside any tool call fails
+        record(Some(&logger), "secret_resolve", "CRM_TOKEN", false, None);
+        drop(logger);
+
+        // THEN: each event is attributed and names only the reference
+        let entries: Vec<Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0]["action"], "secret_resolve");
+        assert_eq!(entries[0]["source"], "vault");
+        assert_eq!(entries[0]["client"], "api-key-prod");
+        assert_eq!(entries[0]["session_id"], "sess-1");
+        assert_eq!(entries[1]["injected_into"], "header:Authorization");
+        assert_eq!(entries[1]["tool"], "search");
+        assert_eq!(entries[2]["source"], "env");
+        assert_eq!(entries[2]["outcome"], "error");
+        assert!(entries[2].get("client").is_none());
+        assert!(entries[2]["entry_hash"].is_string());
+    }
+
+    #[test]
+    fn source_is_the_reference_prefix() {
+        assert_eq!(source_of("{keychain.crm}"), "keychain");
+        assert_eq!(source_of("aws-ssm:/prod/token"), "aws-ssm");
+        assert_eq!(source_of("{secret.crm.api_token}"), "secret");
+        assert_eq!(source_of("CRM_TOKEN"), "env");
+    }
+}
@@ -0,0 +1,426 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! AWS Secrets Manager and SSM Parameter Store secret sources.
+//!
+//! - `{aws-secrets.NAME}` is the `SecretString` of secret `NAME` (a name or
+//!   ARN); `{aws-secrets.NAME#FIELD}` is field `FIELD` of a JSON secret.
+//! - `{aws-ssm.NAME}` is the value of parameter `NAME`, decrypted when it is
+//!   a `SecureString`.
+//!
+//! Capabilities use the `aws-secrets:NAME#FIELD` / `aws-ssm:NAME` credential
+//! forms. Requests are signed with AWS Signature Version 4, using the same
+//! credential chain as `aws_sigv4` capabilities: environment variables, the
+//! ECS task role, then the EC2 instance profile. Values are cached for
+//! [`AwsSecretsConfig::cache_ttl`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+use chrono::Utc;
+use parking_lot::RwLock;
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{Value, json};
+use tracing::debug;
+
+use crate::capability::{AwsCredentialCache, AwsSigV4Config, sign_aws_request};
+use crate::config::AwsSecretsConfig;
+use crate::{Error, Result};
+
+/// The process-wide client.
+static CLIENT: OnceLock<Arc<AwsSecretsClient>> = OnceLock::new();
+
+/// Use `config` for AWS secret references. Must run before the first lookup;
+/// later calls keep the first configuration.
+pub fn install(config: &AwsSecretsConfig) {
+    if CLIENT
+        .set(Arc::new(AwsSecretsClient::new(config.clone())))
+        .is_err()
+    {
+        debug!("AWS secrets client already initialised; keeping the first configuration");
+    }
+}
+
+/// The process-wide client, with default settings when [`install`] did not
+/// run.
+#[must_use]
+pub fn client() -> Arc<AwsSecretsClient> {
+    Arc::clone(CLIENT.get_or_init(|| Arc::new(AwsSecretsClient::new(AwsSecretsConfig::default()))))
+}
+
+/// Which AWS service a reference reads from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AwsSecretSource {
+    /// AWS Secrets Manager (`aws-secrets`).
+    SecretsManager,
+    /// SSM Parameter Store (`aws-ssm`).
+    ParameterStore,
+}
+
+impl AwsSecretSource {
+    /// Reference prefix, e.g. `aws-secrets`.
+    #[must_use]
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::SecretsManager => "aws-secrets",
+            Self::ParameterStore => "aws-ssm",
+        }
+    }
+
+    /// Endpoint host prefix and `SigV4` signing name.
+    fn service(self) -> &'static str {
+        match self {
+            Self::SecretsManager => "secretsmanager",
+            Self::ParameterStore => "ssm",
+        }
+    }
+
+    /// `X-Amz-Target` of the read operation.
+    fn target(self) -> &'static str {
+        match self {
+            Self::SecretsManager => "secretsmanager.GetSecretValue",
+            Self::ParameterStore => "AmazonSSM.GetParameter",
+        }
+    }
+}
+
+/// A value read from AWS, with its read time.
+struct CachedValue {
+    value: String,
+    read_at: Instant,
+}
+
+/// Reads secrets from Secrets Manager and Parameter Store.
+pub struct AwsSecretsClient {
+    config: AwsSecretsConfig,
+    credentials: AwsCredentialCache,
+    cache: RwLock<HashMap<String, CachedValue>>,
+}
+
+impl std::fmt::Debug for AwsSecretsClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AwsSecretsClient")
+            .field("config", &self.config)
+            .field("cached", &self.cache.read().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl AwsSecretsClient {
+    /// Create a client; credentials are looked up on the first read.
+    #[must_use]
+    pub fn new(config: AwsSecretsConfig) -> Self {
+        Self {
+            config,
+            credentials: AwsCredentialCache::default(),
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Read `reference` (`NAME` or, for Secrets Manager, `NAME#FIELD`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no region or credentials are available, AWS
+    /// refuses the read, or the field does not exist.
+    pub async fn read(&self, source: AwsSecretSource, reference: &str) -> Result<String> {
+        let cache_key = format!("{}:{reference}", source.prefix());
+        if let Some(value) = self.cached(&cache_key) {
+            return Ok(value);
+        }
+        let (name, field) = match source {
+            AwsSecretSource::SecretsManager => match reference.rsplit_once('#') {
+                Some((name, field)) => (name, Some(field)),
+                None => (reference, None),
+            },
+            AwsSecretSource::ParameterStore => (reference, None),
+        };
+        if name.is_empty() || field.is_some_and(str::is_empty) {
+            return Err(Error::Config(format!(
+                "Invalid {} reference '{reference}'",
+                source.prefix()
+            )));
+        }
+
+        let body = match source {
+            AwsSecretSource::SecretsManager => json!({ "SecretId": name }),
+            AwsSecretSource::ParameterStore => json!({ "Name": name, "WithDecryption": true }),
+        };
+        let response = self.call(source, name, &body).await?;
+        let value = match source {
+            AwsSecretSource::SecretsManager => secret_value(&response, name, field)?,
+            AwsSecretSource::ParameterStore => response
+                .pointer("/Parameter/Value")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    Error::Config(format!("SSM parameter '{name}' response has no value"))
+                })?,
+        };
+        debug!(source = source.prefix(), name, "Read secret from AWS");
+        self.cache.write().insert(
+            cache_key,
+            CachedValue {
+                value: value.clone(),
+                read_at: Instant::now(),
+            },
+        );
+        Ok(value)
+    }
+
+    /// [`Self::read`] for synchronous callers such as
+    /// [`SecretResolver`](super::SecretResolver).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::read`].
+    pub fn read_blocking(&self, source: AwsSecretSource, reference: &str) -> Result<String> {
+        if let Some(value) = self.cached(&format!("{}:{reference}", source.prefix())) {
+            return Ok(value);
+        }
+        super::block_on(self.read(source, reference))
+    }
+
+    fn cached(&self, key: &str) -> Option<String> {
+        let cache = self.cache.read();
+        let entry = cache.get(key)?;
+        (entry.read_at.elapsed() < self.config.cache_ttl).then(|| entry.value.clone())
+    }
+
+    /// Region of `name`: the ARN's own, else the configured or environment one.
+    fn region(&self, name: &str) -> Result<String> {
+        if name.starts_with("arn:")
+            && let Some(region) = name.split(':').nth(3).filter(|r| !r.is_empty())
+        {
+            return Ok(region.to_string());
+        }
+        self.config
+            .region
+            .clone()
+            .or_else(|| std::env::var("AWS_REGION").ok())
+            .or_else(|| std::env::var("AWS_DEFAULT_REGION").ok())
+            .filter(|r| !r.is_empty())
+            .ok_or_else(|| {
+                Error::Config("No AWS region: set aws_secrets.region or AWS_REGION".to_string())
+            })
+    }
+
+    /// Send one signed JSON 1.1 request and return its response body.
+    async fn call(&self, source: AwsSecretSource, name: &str, body: &Value) -> Result<Value> {
+        let region = self.region(name)?;
+        let url = match &self.config.endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => format!("https://{}.{region}.amazonaws.com/", source.service()),
+        };
+        let http = reqwest::Client::builder()
+            .timeout(self.config.timeout)
+            .build()
+            .map_err(|e| Error::Config(format!("Failed to create AWS HTTP client: {e}")))?;
+        let credentials = self.credentials.ambient(&http).await?;
+        let mut request = http
+            .post(&url)
+            .header(CONTENT_TYPE, "application/x-amz-json-1.1")
+            .header("X-Amz-Target", source.target())
+            .body(body.to_string())
+            .build()
+            .map_err(|e| Error::Config(format!("Invalid AWS request: {e}")))?;
+        let signing = AwsSigV4Config {
+            region,
+            service: source.service().to_string(),
+            ..AwsSigV4Config::default()
+        };
+        sign_aws_request(&mut request, &signing, &credentials, Utc::now())?;
+
+        let response = http.execute(request).await.map_err(|e| {
+            Error::Config(format!(
+                "{} request for '{name}' failed: {e}",
+                source.prefix()
+            ))
+        })?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        if status.is_success() {
+            return Ok(body);
+        }
+        // `__type` is e.g. `com.amazonaws...#ResourceNotFoundException`.
+        let kind = body
+            .get("__type")
+            .and_then(Value::as_str)
+            .map(|t| t.rsplit('#').next().unwrap_or(t))
+            .unwrap_or_default();
+        let message = body
+            .get("message")
+            .or_else(|| body.get("Message"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        Err(Error::Config(format!(
+            "{} read of '{name}' returned HTTP {status}: {kind} {message}",
+            source.prefix()
+        )))
+    }
+}
+
+/// `SecretString` of a `GetSecretValue` response, or one field of it.
+fn secret_value(response: &Value, name: &str, field: Option<&str>) -> Result<String> {
+    let secret = response
+        .get("SecretString")
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            Error::Config(format!(
+                "Secret '{name}' has no SecretString (binary secrets are not supported)"
+            ))
+        })?;
+    let Some(field) = field else {
+        return Ok(secret.to_string());
+    };
+    let fields: Value = serde_json::from_str(secret).map_err(|_| {
+        Error::Config(format!(
+            "Secret '{name}' is not JSON, so it has no field '{field}'"
+        ))
+    })?;
+    match fields.get(field) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(other) => Ok(other.to_string()),
+        None => Err(Error::Config(format!(
+            "Secret '{name}' has no field '{field}'"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use axum::{
+        Json, Router, extract::State, http::HeaderMap, response::IntoResponse, routing::post,
+    };
+
+    use super::*;
+
+    async fn aws_stub(
+        State(calls): State<Arc<AtomicUsize>>,
+        headers: HeaderMap,
+        body: String,
+    ) -> axum::response::Response {
+        calls.fetch_add(1, Ordering::SeqCst);
+        let authorization = headers["authorization"].to_str().unwrap();
+        let body: Value = serde_json::from_str(&body).unwrap();
+        match headers["x-amz-target"].to_str().unwrap() {
+            "secretsmanager.GetSecretValue" => {
+                assert!(
+                    authorization.contains("/eu-west-1/secretsmanager/aws4_request"),
+                    "{authorization}"
+                );
+                if body["SecretId"] == "prod/crm" {
+                    Json(json!({"Name": "prod/crm", "SecretString": r#"{"api_key":"k-1","port":8443}"#}))
+                        .into_response()
+                } else {
+                    (
+                        axum::http::StatusCode::BAD_REQUEST,
+                        Json(json!({
+                            "__type": "ResourceNotFoundException",
+                            "message": "Secrets Manager can't find the specified secret."
+                        })),
+                    )
+                        .into_response()
+                }
+            }
+            "AmazonSSM.GetParameter" => {
+                assert!(
+                    authorization.contains("/eu-west-1/ssm/aws4_request"),
+                    "{authorization}"
+                );
+                assert_eq!(body["WithDecryption"], true);
+                Json(json!({"Parameter": {"Name": body["Name"], "Type": "SecureString", "Value": "ssm-value"}}))
+                    .into_response()
+            }
+            other => panic!("unexpected target {other}"),
+        }
+    }
+
+    async fn stub_client() -> (
+        AwsSecretsClient,
+        Arc<AtomicUsize>,
+        tokio::task::JoinHandle<()>,
+    ) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = Router::new()
+            .route("/", post(aws_stub))
+            .with_state(Arc::clone(&calls));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        let client = AwsSecretsClient {
+            credentials: AwsCredentialCache::seeded("AKIDTEST", "secret"),
+            ..AwsSecretsClient::new(AwsSecretsConfig {
+                region: Some("eu-west-1".to_string()),
+                endpoint: Some(format!("http://{addr}/")),
+                cache_ttl: Duration::from_secs(60),
+                ..AwsSecretsConfig::default()
+            })
+        };
+        (client, calls, server)
+    }
+
+    #[tokio::test]
+    async fn reads_secret_fields_and_parameters_with_caching() {
+        // GIVEN: a signed-request stub for both services
+        let (client, calls, server) = stub_client().await;
+
+        // WHEN: reading a JSON secret field twice, and a parameter
+        let secrets = AwsSecretSource::SecretsManager;
+        assert_eq!(
+            client.read(secrets, "prod/crm#api_key").await.unwrap(),
+            "k-1"
+        );
+        assert_eq!(
+            client.read(secrets, "prod/crm#api_key").await.unwrap(),
+            "k-1"
+        );
+        assert_eq!(
+            client
+                .read(AwsSecretSource::ParameterStore, "/prod/crm/token")
+                .await
+                .unwrap(),
+            "ssm-value"
+        );
+
+        // THEN: the repeated read came from the cache
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // AND: the whole secret and non-string fields are available
+        assert_eq!(client.read(secrets, "prod/crm#port").await.unwrap(), "8443");
+
+        // AND: AWS errors and missing fields are reported by name
+        let missing = client.read(secrets, "prod/none").await.unwrap_err();
+        assert!(
+            missing.to_string().contains("ResourceNotFoundException"),
+            "{missing}"
+        );
+        let no_field = client.read(secrets, "prod/crm#password").await.unwrap_err();
+        assert!(
+            no_field.to_string().contains("no field 'password'"),
+            "{no_field}"
+        );
+
+        server.abort();
+    }
+
+    #[test]
+    fn arn_references_use_their_own_region() {
+        let client = AwsSecretsClient::new(AwsSecretsConfig {
+            region: Some("eu-west-1".to_string()),
+            ..AwsSecretsConfig::default()
+        });
+        assert_eq!(
+            client
+                .region("arn:aws:secretsmanager:us-east-2:123456789012:secret:crm-AbCdEf")
+                .unwrap(),
+            "us-east-2"
+        );
+        assert_eq!(client.region("prod/crm").unwrap(), "eu-west-1");
+    }
+}
@@ -0,0 +1,310 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! SOPS- or age-encrypted secrets file, decrypted once at startup.
+//!
+//! `{secret.KEY.PATH}` (and the `secret:KEY.PATH` capability credential) is
+//! the value at the dotted `KEY.PATH` in the decrypted YAML or JSON document.
+//! The file is decrypted with the `sops` or `age` CLI, without a shell; the
+//! plaintext is only held in memory.
+
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use serde_json::Value;
+use tracing::{debug, info};
+
+use crate::config::SecretsFileConfig;
+use crate::secrets::SecretResolver;
+use crate::{Error, Result};
+
+/// First line of an ASCII-armored age file.
+const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+/// First line of a binary age file.
+const AGE_BINARY_HEADER: &str = "age-encryption.org/v1";
+
+/// The decrypted file, once [`install`] ran.
+static STORE: OnceLock<SecretsFile> = OnceLock::new();
+
+/// Decrypt the file in `config` and keep its contents for lookups. A
+/// relative path is resolved against `base_dir`, the config file's
+/// directory. Later calls keep the first file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or decrypted, or is not a
+/// YAML or JSON document.
+pub fn install(config: &SecretsFileConfig, base_dir: Option<&Path>) -> Result<()> {
+    if STORE.get().is_some() {
+        debug!("Secrets file already loaded; keeping the first one");
+        return Ok(());
+    }
+    let path = match base_dir {
+        Some(dir) if config.path.is_relative() => dir.join(&config.path),
+        _ => config.path.clone(),
+    };
+    let file = SecretsFile::decrypt(config, &path)?;
+    info!(path = %path.display(), "Loaded encrypted secrets file");
+    let _ = STORE.set(file);
+    Ok(())
+}
+
+/// Look up `path` in the installed secrets file.
+///
+/// # Errors
+///
+/// Returns an error if no secrets file is configured or `path` is missing.
+pub fn lookup(path: &str) -> Result<String> {
+    STORE
+        .get()
+        .ok_or_else(|| {
+            Error::Config(format!(
+                "'secret:{path}' needs a `secrets_file:` section in the gateway config"
+            ))
+        })?
+        .get(path)
+}
+
+/// How a secrets file is encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encryption {
+    /// SOPS: encrypted values plus a `sops:` metadata section.
+    Sops,
+    /// The whole file encrypted with age.
+    Age,
+}
+
+impl Encryption {
+    /// Recognise age files by their header; anything else goes to SOPS.
+    fn detect(content: &[u8]) -> Self {
+        if content.starts_with(AGE_ARMOR_HEADER.as_bytes())
+            || content.starts_with(AGE_BINARY_HEADER.as_bytes())
+        {
+            Self::Age
+        } else {
+            Self::Sops
+        }
+    }
+}
+
+/// A decrypted secrets document.
+pub struct SecretsFile {
+    document: Value,
+}
+
+// Holds plaintext secrets; never print them (CWE-532, mirrors PR #323).
+impl std::fmt::Debug for SecretsFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretsFile")
+            .field("document", &"<redacted>")
+            .finish()
+    }
+}
+
+impl SecretsFile {
+    /// Decrypt the file at `path` with the settings in `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or decrypted, or is not a
+    /// YAML or JSON document.
+    pub fn decrypt(config: &SecretsFileConfig, path: &Path) -> Result<Self> {
+        let content = std::fs::read(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read secrets file {}: {e}",
+                path.display()
+            ))
+        })?;
+        let key = config
+            .key
+            .as_deref()
+            .map(|key| SecretResolver::new().resolve(key))
+            .transpose()?;
+        let plaintext = match Encryption::detect(&content) {
+            Encryption::Sops => {
+                let mut command = Command::new(&config.sops_binary);
+                command.arg("--decrypt").arg(path);
+                if let Some(key) = &key {
+                    command.env("SOPS_AGE_KEY", key);
+                }
+                run(command, &config.sops_binary, None)?
+            }
+            Encryption::Age => {
+                let key = key.ok_or_else(|| {
+                    Error::Config(format!(
+                        "{} is age-encrypted; set secrets_file.key to its age identity",
+                        path.display()
+                    ))
+                })?;
+                // The identity goes through stdin, never the command line.
+                let mut command = Command::new(&config.age_binary);
+                command.args(["--decrypt", "--identity", "-"]).arg(path);
+                run(command, &config.age_binary, Some(&key))?
+            }
+        };
+        Self::parse(&plaintext, path)
+    }
+
+    /// Parse a decrypted YAML or JSON document.
+    fn parse(plaintext: &str, path: &Path) -> Result<Self> {
+        let document: Value = serde_yaml::from_str(plaintext).map_err(|e| {
+            Error::Config(format!(
+                "Decrypted secrets file {} is not YAML or JSON: {e}",
+                path.display()
+            ))
+        })?;
+        if !document.is_object() {
+            return Err(Error::Config(format!(
+                "Secrets file {} must contain a mapping of names to secrets",
+                path.display()
+            )));
+        }
+        Ok(Self { document })
+    }
+
+    /// The value at dotted `path`, e.g. `crm.api_token`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` is missing or names a nested mapping.
+    pub fn get(&self, path: &str) -> Result<String> {
+        let value = path
+            .split('.')
+            .try_fold(&self.document, |node, key| node.get(key))
+            .ok_or_else(|| Error::Config(format!("Secrets file has no entry '{path}'")))?;
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            Value::Number(_) | Value::Bool(_) => Ok(value.to_string()),
+            _ => Err(Error::Config(format!(
+                "Secrets file entry '{path}' is not a single value"
+            ))),
+        }
+    }
+}
+
+/// Run a decryption CLI, optionally writing `stdin` to it, and return its
+/// output.
+fn run(mut command: Command, binary: &Path, stdin: Option<&str>) -> Result<String> {
+    let mut child = command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Error::Config(format!(
+                "'{}' not found; it is needed to decrypt the secrets file",
+                binary.display()
+            )),
+            _ => Error::Config(format!("Failed to run '{}': {e}", binary.display())),
+        })?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())
+            .and_then(|()| pipe.write_all(b"\n"))
+            .map_err(|e| {
+                Error::Config(format!(
+                    "Failed to pass the key to '{}': {e}",
+                    binary.display()
+                ))
+            })?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| Error::Config(format!("Failed to run '{}': {e}", binary.display())))?;
+    if !output.status.success() {
+        return Err(Error::Config(format!(
+            "'{}' could not decrypt the secrets file ({}): {}",
+            binary.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| Error::Config(format!("'{}' returned non-UTF-8 output", binary.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn lookups_follow_dotted_paths() {
+        // GIVEN: a decrypted document with nested and scalar entries
+        let file = SecretsFile::parse(
+            "crm:\n  api_token: tok-1\n  port: 8443\ngithub_token: ghp_x\n",
+            Path::new("secrets.yaml"),
+        )
+        .unwrap();
+
+        // THEN: leaves resolve by dotted path, scalars as text
+        assert_eq!(file.get("crm.api_token").unwrap(), "tok-1");
+        assert_eq!(file.get("crm.port").unwrap(), "8443");
+        assert_eq!(file.get("github_token").unwrap(), "ghp_x");
+
+        // AND: missing entries and mappings are errors, without leaking values
+        assert!(file.get("crm.password").is_err());
+        let mapping = file.get("crm").unwrap_err().to_string();
+        assert!(!mapping.contains("tok-1"), "{mapping}");
+        assert_eq!(
+            format!("{file:?}"),
+            r#"SecretsFile { document: "<redacted>" }"#
+        );
+    }
+
+    #[test]
+    fn age_files_are_detected_by_header() {
+        assert_eq!(
+            Encryption::detect(b"-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n"),
+            Encryption::Age
+        );
+        assert_eq!(
+            Encryption::detect(b"age-encryption.org/v1\n-> X25519 abc\n"),
+            Encryption::Age
+        );
+        assert_eq!(
+            Encryption::detect(b"crm:\n  api_token: ENC[AES256_GCM,data:...]\nsops:\n"),
+            Encryption::Sops
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn decrypts_with_the_cli_and_passes_the_age_key_on_stdin() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // GIVEN: an age-armored file and an `age` stub that requires the key
+        // on stdin and prints the plaintext
+        let dir = tempfile::tempdir().unwrap();
+        let encrypted = dir.path().join("secrets.age");
+        std::fs::write(&encrypted, format!("{AGE_ARMOR_HEADER}\nYWdl\n")).unwrap();
+        let age = dir.path().join("age");
+        std::fs::write(
+            &age,
+            "#!/bin/sh\nread key\n[ \"$key\" = AGE-SECRET-KEY-1TEST ] || { echo 'no identity matched' >&2; exit 1; }\necho 'crm: {api_token: tok-1}'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&age, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let config = |key: &str| SecretsFileConfig {
+            path: encrypted.clone(),
+            key: Some(key.to_string()),
+            sops_binary: PathBuf::from("sops"),
+            age_binary: age.clone(),
+        };
+
+        // WHEN: decrypting with the right key
+        let file = SecretsFile::decrypt(&config("AGE-SECRET-KEY-1TEST"), &encrypted).unwrap();
+
+        // THEN: entries are available
+        assert_eq!(file.get("crm.api_token").unwrap(), "tok-1");
+
+        // AND: a wrong key surfaces the CLI's error
+        let err = SecretsFile::decrypt(&config("AGE-SECRET-KEY-1OTHER"), &encrypted).unwrap_err();
+        assert!(err.to_string().contains("no identity matched"), "{err}");
+    }
+}
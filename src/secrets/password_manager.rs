@@ -0,0 +1,413 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! 1Password and Bitwarden secret sources, read through their CLIs.
+//!
+//! - `{op.VAULT/ITEM/FIELD}` is `op read op://VAULT/ITEM/FIELD`.
+//! - `{bw.ITEM}` is the password of item `ITEM` (a name or ID) from
+//!   `bw get item`; `{bw.ITEM#FIELD}` is its `username`, `password`, `notes`,
+//!   `uri` or a custom field.
+//!
+//! Capabilities use the `op:VAULT/ITEM/FIELD` / `bw:ITEM#FIELD` credential
+//! forms. The CLIs are run without a shell; values are cached for the
+//! configured `cache_ttl`.
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use parking_lot::RwLock;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::config::{BitwardenConfig, OnePasswordConfig};
+use crate::secrets::SecretResolver;
+use crate::{Error, Result};
+
+/// The process-wide client.
+static CLIENT: OnceLock<Arc<PasswordManagerClient>> = OnceLock::new();
+
+/// Use these settings for password manager references. Must run before the
+/// first lookup; later calls keep the first configuration.
+pub fn install(onepassword: &OnePasswordConfig, bitwarden: &BitwardenConfig) {
+    let client = PasswordManagerClient::new(onepassword.clone(), bitwarden.clone());
+    if CLIENT.set(Arc::new(client)).is_err() {
+        debug!("Password manager client already initialised; keeping the first configuration");
+    }
+}
+
+/// The process-wide client, with default settings when [`install`] did not
+/// run.
+#[must_use]
+pub fn client() -> Arc<PasswordManagerClient> {
+    Arc::clone(CLIENT.get_or_init(|| {
+        Arc::new(PasswordManagerClient::new(
+            OnePasswordConfig::default(),
+            BitwardenConfig::default(),
+        ))
+    }))
+}
+
+/// [`PasswordManagerClient::read`] on the blocking thread pool, for async
+/// callers.
+///
+/// # Errors
+///
+/// Same as [`PasswordManagerClient::read`].
+pub async fn read(manager: PasswordManager, reference: &str) -> Result<String> {
+    let client = client();
+    let reference = reference.to_string();
+    tokio::task::spawn_blocking(move || client.read(manager, &reference))
+        .await
+        .map_err(|e| Error::Config(format!("Password manager lookup failed: {e}")))?
+}
+
+/// Which CLI a reference is read with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordManager {
+    /// 1Password (`op`).
+    OnePassword,
+    /// Bitwarden (`bw`).
+    Bitwarden,
+}
+
+impl PasswordManager {
+    /// Reference prefix, e.g. `op`.
+    #[must_use]
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::OnePassword => "op",
+            Self::Bitwarden => "bw",
+        }
+    }
+}
+
+/// A value read from a CLI, with its read time.
+struct CachedValue {
+    value: String,
+    read_at: Instant,
+}
+
+/// Reads secrets with the `op` and `bw` CLIs.
+pub struct PasswordManagerClient {
+    onepassword: OnePasswordConfig,
+    bitwarden: BitwardenConfig,
+    cache: RwLock<HashMap<String, CachedValue>>,
+}
+
+impl std::fmt::Debug for PasswordManagerClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PasswordManagerClient")
+            .field("onepassword", &self.onepassword)
+            .field("bitwarden", &self.bitwarden)
+            .field("cached", &self.cache.read().len())
+            .finish()
+    }
+}
+
+impl PasswordManagerClient {
+    /// Create a client; the CLIs are run on the first read.
+    #[must_use]
+    pub fn new(onepassword: OnePasswordConfig, bitwarden: BitwardenConfig) -> Self {
+        Self {
+            onepassword,
+            bitwarden,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Read `reference` with the `manager` CLI. Blocks while the CLI runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI is missing, is not signed in or unlocked,
+    /// or the item or field does not exist.
+    pub fn read(&self, manager: PasswordManager, reference: &str) -> Result<String> {
+        let cache_key = format!("{}:{reference}", manager.prefix());
+        let ttl = match manager {
+            PasswordManager::OnePassword => self.onepassword.cache_ttl,
+            PasswordManager::Bitwarden => self.bitwarden.cache_ttl,
+        };
+        if let Some(value) = self.cached(&cache_key, ttl) {
+            return Ok(value);
+        }
+        let value = match manager {
+            PasswordManager::OnePassword => self.read_onepassword(reference)?,
+            PasswordManager::Bitwarden => self.read_bitwarden(reference)?,
+        };
+        debug!(
+            manager = manager.prefix(),
+            reference, "Read secret from password manager"
+        );
+        self.cache.write().insert(
+            cache_key,
+            CachedValue {
+                value: value.clone(),
+                read_at: Instant::now(),
+            },
+        );
+        Ok(value)
+    }
+
+    fn cached(&self, key: &str, ttl: Duration) -> Option<String> {
+        let cache = self.cache.read();
+        let entry = cache.get(key)?;
+        (entry.read_at.elapsed() < ttl).then(|| entry.value.clone())
+    }
+
+    /// `op read op://REFERENCE`.
+    fn read_onepassword(&self, reference: &str) -> Result<String> {
+        if reference.split('/').filter(|part| !part.is_empty()).count() < 3 {
+            return Err(Error::Config(format!(
+                "Invalid 1Password reference 'op.{reference}'; expected VAULT/ITEM/FIELD"
+            )));
+        }
+        let config = &self.onepassword;
+        let mut command = Command::new(&config.binary);
+        if let Some(account) = &config.account {
+            command.args(["--account", account.as_str()]);
+        }
+        let target = format!("op://{reference}");
+        command.args(["read", "--no-newline", target.as_str()]);
+        if let Some(token) = &config.service_account_token {
+            command.env(
+                "OP_SERVICE_ACCOUNT_TOKEN",
+                SecretResolver::new().resolve(token)?,
+            );
+        }
+        let value = run(command, &config.binary, "onepassword.binary")?;
+        if value.is_empty() {
+            return Err(Error::Config(format!(
+                "1Password field 'op://{reference}' is empty"
+            )));
+        }
+        Ok(value)
+    }
+
+    /// `bw get item -- ITEM`, then the requested field of the item.
+    fn read_bitwarden(&self, reference: &str) -> Result<String> {
+        let (item, field) = reference
+            .rsplit_once('#')
+            .unwrap_or((reference, "password"));
+        if item.is_empty() || field.is_empty() {
+            return Err(Error::Config(format!(
+                "Invalid Bitwarden reference 'bw.{reference}'; expected ITEM or ITEM#FIELD"
+            )));
+        }
+        let config = &self.bitwarden;
+        let mut command = Command::new(&config.binary);
+        // `--` so an item name starting with `-` is never read as an option
+        command.args(["get", "item", "--nointeraction", "--", item]);
+        if let Some(session) = &config.session {
+            command.env("BW_SESSION", SecretResolver::new().resolve(session)?);
+        }
+        let output = run(command, &config.binary, "bitwarden.binary")?;
+        let item_json: Value = serde_json::from_str(&output).map_err(|e| {
+            Error::Config(format!(
+                "Bitwarden returned an unreadable item '{item}': {e}"
+            ))
+        })?;
+        bitwarden_field(&item_json, field)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| Error::Config(format!("Bitwarden item '{item}' has no field '{field}'")))
+    }
+}
+
+/// Run a CLI and return its stdout without the trailing newline.
+fn run(mut command: Command, binary: &Path, setting: &str) -> Result<String> {
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Error::Config(format!(
+                "'{}' not found. Install it or set {setting} to its path",
+                binary.display()
+            )),
+            _ => Error::Config(format!("Failed to run '{}': {e}", binary.display())),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Config(format!(
+            "'{}' failed ({}): {}",
+            binary.display(),
+            output.status,
+            stderr.trim()
+        )));
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| Error::Config(format!("'{}' returned non-UTF-8 output", binary.display())))?;
+    Ok(stdout.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// A named field of a `bw get item` document.
+fn bitwarden_field(item: &Value, field: &str) -> Option<String> {
+    let value = match field {
+        "username" | "password" => item.pointer(&format!("/login/{field}")),
+        "notes" => item.get("notes"),
+        "uri" => item.pointer("/login/uris/0/uri"),
+        custom => item
+            .get("fields")?
+            .as_array()?
+            .iter()
+            .find(|f| f.get("name").and_then(Value::as_str) == Some(custom))
+            .and_then(|f| f.get("value")),
+    };
+    value.and_then(Value::as_str).map(str::to_string)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Write an executable shell script standing in for a CLI.
+    fn stub_cli(dir: &Path, name: &str, script: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn onepassword_reads_with_service_account_token_and_caches() {
+        // GIVEN: an `op` stub that echoes its token and arguments, counting runs
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("runs");
+        let op = stub_cli(
+            dir.path(),
+            "op",
+            &format!(
+                "echo run >> {}\nprintf '%s|%s' \"$OP_SERVICE_ACCOUNT_TOKEN\" \"$*\"",
+                runs.display()
+            ),
+        );
+        let client = PasswordManagerClient::new(
+            OnePasswordConfig {
+                binary: op,
+                account: Some("team.1password.com".to_string()),
+                service_account_token: Some("ops_test".to_string()),
+                ..OnePasswordConfig::default()
+            },
+            BitwardenConfig::default(),
+        );
+
+        // WHEN: the same field is read twice
+        let first = client
+            .read(PasswordManager::OnePassword, "Dev/CRM/api key")
+            .unwrap();
+        let second = client
+            .read(PasswordManager::OnePassword, "Dev/CRM/api key")
+            .unwrap();
+
+        // THEN: `op read` got the token, account and reference, and ran once
+        assert_eq!(
+            first,
+            "ops_test|--account team.1password.com read --no-newline op://Dev/CRM/api key"
+        );
+        assert_eq!(second, first);
+        assert_eq!(std::fs::read_to_string(&runs).unwrap().lines().count(), 1);
+
+        // AND: malformed references never reach the CLI
+        assert!(
+            client
+                .read(PasswordManager::OnePassword, "Dev/CRM")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn bitwarden_reads_login_and_custom_fields() {
+        // GIVEN: a `bw` stub returning an item, and failing without a session
+        let dir = tempfile::tempdir().unwrap();
+        let bw = stub_cli(
+            dir.path(),
+            "bw",
+            r#"[ "$BW_SESSION" = "sess" ] || { echo "Vault is locked." >&2; exit 1; }
+echo '{"name":"crm","login":{"username":"svc","password":"pw-1","uris":[{"uri":"https://crm"}]},"fields":[{"name":"api_key","value":"k-1"}]}'"#,
+        );
+        let bitwarden = |session: Option<&str>| BitwardenConfig {
+            binary: bw.clone(),
+            session: session.map(str::to_string),
+            ..BitwardenConfig::default()
+        };
+        let client =
+            PasswordManagerClient::new(OnePasswordConfig::default(), bitwarden(Some("sess")));
+
+        // WHEN / THEN: the default field is the password
+        assert_eq!(
+            client.read(PasswordManager::Bitwarden, "crm").unwrap(),
+            "pw-1"
+        );
+        // AND: login and custom fields are selected with `#FIELD`
+        assert_eq!(
+            client
+                .read(PasswordManager::Bitwarden, "crm#username")
+                .unwrap(),
+            "svc"
+        );
+        assert_eq!(
+            client
+                .read(PasswordManager::Bitwarden, "crm#api_key")
+                .unwrap(),
+            "k-1"
+        );
+        let missing = client
+            .read(PasswordManager::Bitwarden, "crm#token")
+            .unwrap_err();
+        assert!(
+            missing.to_string().contains("no field 'token'"),
+            "{missing}"
+        );
+
+        // AND: a locked vault surfaces the CLI's message
+        let locked = PasswordManagerClient::new(OnePasswordConfig::default(), bitwarden(None))
+            .read(PasswordManager::Bitwarden, "crm")
+            .unwrap_err();
+        assert!(locked.to_string().contains("Vault is locked."), "{locked}");
+    }
+
+    #[test]
+    fn bitwarden_item_is_passed_after_end_of_options() {
+        // GIVEN: a `bw` stub answering with its arguments as the password
+        let dir = tempfile::tempdir().unwrap();
+        let bw = stub_cli(
+            dir.path(),
+            "bw",
+            r#"printf '{"login":{"password":"%s"}}' "$*""#,
+        );
+        let client = PasswordManagerClient::new(
+            OnePasswordConfig::default(),
+            BitwardenConfig {
+                binary: bw,
+                ..BitwardenConfig::default()
+            },
+        );
+
+        // WHEN: the item name looks like an option
+        let args = client
+            .read(PasswordManager::Bitwarden, "--session=x")
+            .unwrap();
+
+        // THEN: it follows `--`, so the CLI reads it as the item
+        assert_eq!(args, "get item --nointeraction -- --session=x");
+    }
+
+    #[test]
+    fn missing_cli_names_the_setting() {
+        let client = PasswordManagerClient::new(
+            OnePasswordConfig {
+                binary: PathBuf::from("/nonexistent/op"),
+                ..OnePasswordConfig::default()
+            },
+            BitwardenConfig::default(),
+        );
+        let err = client
+            .read(PasswordManager::OnePassword, "Dev/CRM/password")
+            .unwrap_err();
+        assert!(err.to_string().contains("onepassword.binary"), "{err}");
+    }
+}
@@ -0,0 +1,599 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Vault secret source (`HashiCorp` Vault KV v2).
+//!
+//! Resolves `{vault.MOUNT/PATH#FIELD}` references (and `vault:MOUNT/PATH#FIELD`
+//! capability credentials) from KV version 2 engines: `secret/crm#api_key`
+//! reads field `api_key` of `GET /v1/secret/data/crm`.
+//!
+//! The gateway logs in with a token, `AppRole` or a Kubernetes service
+//! account ([`VaultConfig`]). [`install`] makes the client available
+//! process-wide and starts a task that renews the login token at two thirds
+//! of its lease, logging in again when renewal is impossible. Read secrets
+//! are cached for `cache_ttl` so rotations reach the gateway without a
+//! restart.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use parking_lot::RwLock;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::{debug, info, warn};
+
+use crate::config::{VaultAuthConfig, VaultConfig};
+use crate::secrets::SecretResolver;
+use crate::{Error, Result};
+
+/// Shortest wait between two renewal attempts.
+const MIN_RENEW_DELAY: Duration = Duration::from_secs(5);
+
+/// Wait after a failed login before trying again.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Renewal check interval for tokens without a lease (root/periodic tokens).
+const UNLEASED_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// The process-wide client, set by [`install`].
+static CLIENT: OnceLock<Arc<VaultClient>> = OnceLock::new();
+
+/// Make `config` the process-wide Vault client and start token renewal.
+///
+/// Must run inside a Tokio runtime. Later calls keep the first client.
+pub fn install(config: &VaultConfig) {
+    let client = Arc::new(VaultClient::new(config.clone()));
+    if CLIENT.set(Arc::clone(&client)).is_err() {
+        warn!("Vault client already installed; keeping the first configuration");
+        return;
+    }
+    info!(address = %config.address, "Vault secret source enabled");
+    tokio::spawn(client.renewal_loop());
+}
+
+/// The process-wide client, if [`install`] ran.
+#[must_use]
+pub fn client() -> Option<Arc<VaultClient>> {
+    CLIENT.get().cloned()
+}
+
+/// A parsed `MOUNT/PATH#FIELD` reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultReference {
+    /// KV v2 engine mount.
+    pub mount: String,
+    /// Secret path inside the mount.
+    pub path: String,
+    /// Field of the secret's data.
+    pub field: String,
+}
+
+impl VaultReference {
+    /// Parse `MOUNT/PATH#FIELD`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the mount, path or field is missing.
+    pub fn parse(reference: &str) -> Result<Self> {
+        let invalid = || {
+            Error::Config(format!(
+                "Invalid Vault reference '{reference}'. Expected MOUNT/PATH#FIELD, e.g. secret/crm#api_key"
+            ))
+        };
+        let (location, field) = reference.rsplit_once('#').ok_or_else(invalid)?;
+        let (mount, path) = location
+            .trim_matches('/')
+            .split_once('/')
+            .ok_or_else(invalid)?;
+        if mount.is_empty() || path.is_empty() || field.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            mount: mount.to_string(),
+            path: path.to_string(),
+            field: field.to_string(),
+        })
+    }
+}
+
+/// Current login token.
+struct Login {
+    token: String,
+    renewable: bool,
+    /// When the token expires; `None` for tokens without a lease.
+    expires_at: Option<Instant>,
+}
+
+/// A secret read from Vault, with its read time.
+struct CachedSecret {
+    data: HashMap<String, Value>,
+    read_at: Instant,
+}
+
+/// `auth` block of a Vault login, renewal or lookup response.
+#[derive(Deserialize)]
+struct AuthBlock {
+    client_token: String,
+    #[serde(default)]
+    lease_duration: u64,
+    #[serde(default)]
+    renewable: bool,
+}
+
+/// Client for one Vault server.
+pub struct VaultClient {
+    config: VaultConfig,
+    login: RwLock<Option<Login>>,
+    cache: RwLock<HashMap<String, CachedSecret>>,
+    /// Resolves `{env.VAR}` / `{keychain.SERVICE}` in the login settings.
+    secrets: SecretResolver,
+}
+
+impl std::fmt::Debug for VaultClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultClient")
+            .field("config", &self.config)
+            .field("logged_in", &self.login.read().is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl VaultClient {
+    /// Create a client; nothing is sent to Vault until the first read.
+    #[must_use]
+    pub fn new(config: VaultConfig) -> Self {
+        Self {
+            config,
+            login: RwLock::new(None),
+            cache: RwLock::new(HashMap::new()),
+            secrets: SecretResolver::new(),
+        }
+    }
+
+    /// Read the secret `reference` (`MOUNT/PATH#FIELD`) points to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reference is malformed, login fails, or the
+    /// secret or field does not exist.
+    pub async fn read(&self, reference: &str) -> Result<String> {
+        let reference = VaultReference::parse(reference)?;
+        if let Some(value) = self.cached(&reference) {
+            return Ok(value);
+        }
+        let http = self.http_client()?;
+        let data = self.fetch_secret(&http, &reference).await?;
+        let value = field_value(&data, &reference)?;
+        self.cache.write().insert(
+            cache_key(&reference),
+            CachedSecret {
+                data,
+                read_at: Instant::now(),
+            },
+        );
+        Ok(value)
+    }
+
+    /// [`Self::read`] for synchronous callers such as [`SecretResolver`].
+    ///
+    /// Cache hits return immediately; otherwise the request runs on a helper
+    /// thread with its own runtime, so this is safe to call from async code.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::read`].
+    pub fn read_blocking(&self, reference: &str) -> Result<String> {
+        if let Some(value) = VaultReference::parse(reference)
+            .ok()
+            .and_then(|r| self.cached(&r))
+        {
+            return Ok(value);
+        }
+        super::block_on(self.read(reference))
+    }
+
+    /// Renew the login token, or log in again when it cannot be renewed.
+    ///
+    /// Returns how long to wait before the next renewal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if both renewal and a fresh login fail.
+    pub async fn renew(&self) -> Result<Duration> {
+        let http = self.http_client()?;
+        let renewable = self.login.read().as_ref().map(|l| l.renewable);
+        if renewable == Some(true) {
+            match self.renew_self(&http).await {
+                Ok(()) => return Ok(self.next_renewal()),
+                Err(e) => warn!(error = %e, "Vault token renewal failed; logging in again"),
+            }
+        }
+        // Not logged in yet, not renewable, or renewal failed.
+        self.login(&http).await?;
+        Ok(self.next_renewal())
+    }
+
+    /// Background task started by [`install`].
+    async fn renewal_loop(self: Arc<Self>) {
+        loop {
+            let delay = match self.renew().await {
+                Ok(delay) => delay,
+                Err(e) => {
+                    warn!(error = %e, "Vault login renewal failed");
+                    RETRY_DELAY
+                }
+            };
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Delay until two thirds of the current token's remaining lease.
+    fn next_renewal(&self) -> Duration {
+        match self.login.read().as_ref().and_then(|l| l.expires_at) {
+            Some(at) => (at.saturating_duration_since(Instant::now()) * 2 / 3).max(MIN_RENEW_DELAY),
+            None => UNLEASED_CHECK_INTERVAL,
+        }
+    }
+
+    fn cached(&self, reference: &VaultReference) -> Option<String> {
+        let cache = self.cache.read();
+        let entry = cache.get(&cache_key(reference))?;
+        if entry.read_at.elapsed() >= self.config.cache_ttl {
+            return None;
+        }
+        field_value(&entry.data, reference).ok()
+    }
+
+    fn http_client(&self) -> Result<reqwest::Client> {
+        reqwest::Client::builder()
+            .timeout(self.config.timeout)
+            .build()
+            .map_err(|e| Error::Config(format!("Failed to create Vault HTTP client: {e}")))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/v1/{path}", self.config.address.trim_end_matches('/'))
+    }
+
+    fn request(
+        &self,
+        http: &reqwest::Client,
+        method: reqwest::Method,
+        path: &str,
+    ) -> reqwest::RequestBuilder {
+        let request = http.request(method, self.url(path));
+        match &self.config.namespace {
+            Some(namespace) => request.header("X-Vault-Namespace", namespace),
+            None => request,
+        }
+    }
+
+    /// Current token, logging in first if needed.
+    async fn token(&self, http: &reqwest::Client) -> Result<String> {
+        let current = self.login.read().as_ref().and_then(|l| {
+            let live = l.expires_at.is_none_or(|at| at > Instant::now());
+            live.then(|| l.token.clone())
+        });
+        match current {
+            Some(token) => Ok(token),
+            None => self.login(http).await,
+        }
+    }
+
+    async fn fetch_secret(
+        &self,
+        http: &reqwest::Client,
+        reference: &VaultReference,
+    ) -> Result<HashMap<String, Value>> {
+        let token = self.token(http).await?;
+        let path = format!("{}/data/{}", reference.mount, reference.path);
+        let body = send(
+            self.request(http, reqwest::Method::GET, &path)
+                .header("X-Vault-Token", token),
+            &path,
+        )
+        .await?;
+        debug!(mount = %reference.mount, path = %reference.path, "Read secret from Vault");
+        body.pointer("/data/data")
+            .and_then(Value::as_object)
+            .map(|data| data.clone().into_iter().collect())
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "Vault path '{path}' is not a KV v2 secret (no data.data)"
+                ))
+            })
+    }
+
+    /// Log in with the configured method and store the token.
+    async fn login(&self, http: &reqwest::Client) -> Result<String> {
+        let resolve = |value: &str| self.secrets.resolve(value);
+        let auth = match &self.config.auth {
+            VaultAuthConfig::Token { token } => {
+                let token = resolve(token)?;
+                let body = send(
+                    self.request(http, reqwest::Method::GET, "auth/token/lookup-self")
+                        .header("X-Vault-Token", &token),
+                    "auth/token/lookup-self",
+                )
+                .await?;
+                // lookup-self reports the remaining `ttl` under `data`.
+                let data = body.get("data").cloned().unwrap_or_default();
+                AuthBlock {
+                    client_token: token,
+                    lease_duration: data.get("ttl").and_then(Value::as_u64).unwrap_or(0),
+                    renewable: data
+                        .get("renewable")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                }
+            }
+            VaultAuthConfig::Approle {
+                mount,
+                role_id,
+                secret_id,
+            } => {
+                let body =
+                    json!({ "role_id": resolve(role_id)?, "secret_id": resolve(secret_id)? });
+                self.login_with(http, mount, &body).await?
+            }
+            VaultAuthConfig::Kubernetes {
+                mount,
+                role,
+                jwt_path,
+            } => {
+                let jwt = std::fs::read_to_string(jwt_path).map_err(|e| {
+                    Error::Config(format!(
+                        "Failed to read Kubernetes service-account token '{}': {e}",
+                        jwt_path.display()
+                    ))
+                })?;
+                let body = json!({ "role": resolve(role)?, "jwt": jwt.trim() });
+                self.login_with(http, mount, &body).await?
+            }
+        };
+        info!(
+            lease_secs = auth.lease_duration,
+            renewable = auth.renewable,
+            "Logged in to Vault"
+        );
+        let token = auth.client_token.clone();
+        *self.login.write() = Some(login_from(auth));
+        Ok(token)
+    }
+
+    async fn login_with(
+        &self,
+        http: &reqwest::Client,
+        mount: &str,
+        body: &Value,
+    ) -> Result<AuthBlock> {
+        let path = format!("auth/{}/login", mount.trim_matches('/'));
+        let response = send(
+            self.request(http, reqwest::Method::POST, &path).json(body),
+            &path,
+        )
+        .await?;
+        parse_auth(&response, &path)
+    }
+
+    async fn renew_self(&self, http: &reqwest::Client) -> Result<()> {
+        let token = self
+            .login
+            .read()
+            .as_ref()
+            .map(|l| l.token.clone())
+            .ok_or_else(|| Error::Config("Not logged in to Vault".to_string()))?;
+        let path = "auth/token/renew-self";
+        let response = send(
+            self.request(http, reqwest::Method::POST, path)
+                .header("X-Vault-Token", token)
+                .json(&json!({})),
+            path,
+        )
+        .await?;
+        let auth = parse_auth(&response, path)?;
+        debug!(lease_secs = auth.lease_duration, "Renewed Vault token");
+        *self.login.write() = Some(login_from(auth));
+        Ok(())
+    }
+}
+
+fn login_from(auth: AuthBlock) -> Login {
+    Login {
+        token: auth.client_token,
+        renewable: auth.renewable,
+        expires_at: (auth.lease_duration > 0)
+            .then(|| Instant::now() + Duration::from_secs(auth.lease_duration)),
+    }
+}
+
+fn parse_auth(response: &Value, path: &str) -> Result<AuthBlock> {
+    response
+        .get("auth")
+        .cloned()
+        .and_then(|auth| serde_json::from_value(auth).ok())
+        .ok_or_else(|| Error::Config(format!("Vault '{path}' response has no auth block")))
+}
+
+fn cache_key(reference: &VaultReference) -> String {
+    format!("{}/{}", reference.mount, reference.path)
+}
+
+fn field_value(data: &HashMap<String, Value>, reference: &VaultReference) -> Result<String> {
+    match data.get(&reference.field) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(other) => Ok(other.to_string()),
+        None => Err(Error::Config(format!(
+            "Vault secret '{}/{}' has no field '{}'",
+            reference.mount, reference.path, reference.field
+        ))),
+    }
+}
+
+/// Send a Vault request and return its JSON body, mapping Vault's `errors`
+/// array into the error message. Secret values never appear in errors.
+async fn send(request: reqwest::RequestBuilder, path: &str) -> Result<Value> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| Error::Config(format!("Vault request '{path}' failed: {e}")))?;
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    if status.is_success() {
+        return Ok(body);
+    }
+    let errors = body
+        .get("errors")
+        .and_then(Value::as_array)
+        .map(|errors| {
+            errors
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+        .unwrap_or_default();
+    Err(Error::Config(format!(
+        "Vault '{path}' returned HTTP {status}: {errors}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{
+        Json, Router,
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::{get, post},
+    };
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Stub {
+        logins: Arc<AtomicUsize>,
+        reads: Arc<AtomicUsize>,
+        renewals: Arc<AtomicUsize>,
+    }
+
+    async fn approle_login(State(stub): State<Stub>, Json(body): Json<Value>) -> impl IntoResponse {
+        if body["role_id"] != "gw-role" || body["secret_id"] != "gw-secret" {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"errors": ["invalid role or secret ID"]})),
+            );
+        }
+        stub.logins.fetch_add(1, Ordering::SeqCst);
+        (
+            StatusCode::OK,
+            Json(
+                json!({"auth": {"client_token": "s.login", "lease_duration": 3600, "renewable": true}}),
+            ),
+        )
+    }
+
+    async fn renew_self(State(stub): State<Stub>, headers: HeaderMap) -> impl IntoResponse {
+        assert_eq!(headers["x-vault-token"], "s.login");
+        stub.renewals.fetch_add(1, Ordering::SeqCst);
+        Json(
+            json!({"auth": {"client_token": "s.login", "lease_duration": 3600, "renewable": true}}),
+        )
+    }
+
+    async fn kv_read(State(stub): State<Stub>, headers: HeaderMap) -> impl IntoResponse {
+        if headers.get("x-vault-token").is_none_or(|t| t != "s.login") {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(json!({"errors": ["permission denied"]})),
+            );
+        }
+        stub.reads.fetch_add(1, Ordering::SeqCst);
+        (
+            StatusCode::OK,
+            Json(
+                json!({"data": {"data": {"api_key": "k-123", "port": 8443}, "metadata": {"version": 3}}}),
+            ),
+        )
+    }
+
+    async fn vault_stub() -> (String, Stub, tokio::task::JoinHandle<()>) {
+        let stub = Stub::default();
+        let app = Router::new()
+            .route("/v1/auth/approle/login", post(approle_login))
+            .route("/v1/auth/token/renew-self", post(renew_self))
+            .route("/v1/secret/data/crm", get(kv_read))
+            .with_state(stub.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        (format!("http://{addr}"), stub, server)
+    }
+
+    fn approle_config(address: &str) -> VaultConfig {
+        serde_yaml::from_str(&format!(
+            "address: {address}\nauth:\n  method: approle\n  role_id: gw-role\n  secret_id: gw-secret\n"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn reference_parsing() {
+        assert_eq!(
+            VaultReference::parse("secret/team/crm#api_key").unwrap(),
+            VaultReference {
+                mount: "secret".to_string(),
+                path: "team/crm".to_string(),
+                field: "api_key".to_string(),
+            }
+        );
+        for bad in ["secret/crm", "secret#key", "/crm#key", "secret/crm#"] {
+            assert!(VaultReference::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[tokio::test]
+    async fn approle_login_reads_caches_and_renews() {
+        // GIVEN: a Vault stub with an AppRole login and a KV v2 secret
+        let (address, stub, server) = vault_stub().await;
+        let client = VaultClient::new(approle_config(&address));
+
+        // WHEN: reading two fields of the same secret
+        assert_eq!(client.read("secret/crm#api_key").await.unwrap(), "k-123");
+        assert_eq!(client.read("secret/crm#port").await.unwrap(), "8443");
+
+        // THEN: one login and one read served both
+        assert_eq!(stub.logins.load(Ordering::SeqCst), 1);
+        assert_eq!(stub.reads.load(Ordering::SeqCst), 1);
+
+        // AND: a missing field is an error naming the field, not the secret
+        let err = client.read("secret/crm#password").await.unwrap_err();
+        assert!(err.to_string().contains("no field 'password'"), "{err}");
+
+        // AND: the renewable token is renewed rather than replaced
+        let next = client.renew().await.unwrap();
+        assert_eq!(stub.renewals.load(Ordering::SeqCst), 1);
+        assert_eq!(stub.logins.load(Ordering::SeqCst), 1);
+        assert!(next > Duration::from_secs(2000), "{next:?}");
+
+        server.abort();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn blocking_read_works_inside_a_runtime() {
+        // GIVEN: a client used from synchronous code running on a Tokio worker
+        let (address, stub, server) = vault_stub().await;
+        let client = VaultClient::new(approle_config(&address));
+
+        // WHEN/THEN: the blocking read succeeds without nesting runtimes
+        assert_eq!(client.read_blocking("secret/crm#api_key").unwrap(), "k-123");
+        assert_eq!(client.read_blocking("secret/crm#api_key").unwrap(), "k-123");
+        assert_eq!(stub.reads.load(Ordering::SeqCst), 1);
+
+        server.abort();
+    }
+}
@@ -93,7 +93,7 @@ mod tests {
     use super::*;
     use crate::capability::{
         AuthConfig, CacheConfig, CapabilityDefinition, CapabilityMetadata, ProviderConfig,
-        ProvidersConfig, RestConfig, SchemaDefinition,
+        ProvidersConfig, ResilienceConfig, RestConfig, SchemaDefinition,
     };
     use crate::transform::TransformConfig;
     use std::collections::HashMap;
@@ -107,6 +107,8 @@ mod tests {
                 service: "rest".to_owned(),
                 cost_per_call: 0.0,
                 timeout: 30,
+                priority: None,
+                weight: 1,
                 config: RestConfig::default(),
             },
         );
@@ -118,6 +120,7 @@ mod tests {
             providers: ProvidersConfig {
                 named,
                 fallback: vec![],
+                ..Default::default()
             },
             auth: AuthConfig::default(),
             cache: CacheConfig::default(),
@@ -129,6 +132,10 @@ mod tests {
             response_transform: TransformConfig::default(),
             projection: None,
             visible_in_states: vec![],
+            resilience: ResilienceConfig::default(),
+            version: None,
+            deprecated: false,
+            replaced_by: None,
             webhooks: HashMap::default(),
             sha256: None,
         }
@@ -291,7 +291,7 @@ mod tests {
     use super::*;
     use crate::capability::{
         AuthConfig, CacheConfig, CapabilityDefinition, CapabilityMetadata, ProviderConfig,
-        ProvidersConfig, RestConfig, SchemaDefinition,
+        ProvidersConfig, ResilienceConfig, RestConfig, SchemaDefinition,
     };
     use crate::transform::TransformConfig;
     use serde_json::json;
@@ -325,12 +325,15 @@ mod tests {
                         service: "rest".to_owned(),
                         cost_per_call: 0.0,
                         timeout: 30,
+                        priority: None,
+                        weight: 1,
                         config: RestConfig::default(),
                     },
                 );
                 ProvidersConfig {
                     named,
                     fallback: vec![],
+                    ..Default::default()
                 }
             },
             auth: AuthConfig {
@@ -357,6 +360,10 @@ mod tests {
             response_transform: TransformConfig::default(),
             projection: None,
             visible_in_states: vec![],
+            resilience: ResilienceConfig::default(),
+            version: None,
+            deprecated: false,
+            replaced_by: None,
             webhooks: HashMap::default(),
             sha256: None,
         }
@@ -0,0 +1,429 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+//! Per-backend service-level objectives with error-budget burn alerts.
+//!
+//! The kill switch's error budget reacts once a backend is already failing.
+//! SLO tracking warns earlier: each backend's success rate and latency are
+//! compared against an objective (e.g. 99.5 % success, 99 % of calls under
+//! 800 ms), and the *burn rate* — how many times faster than sustainable
+//! the error budget is being spent — is computed over rolling windows.
+//!
+//! Alerts follow the multi-window pattern: a rule fires only when the burn
+//! rate exceeds its threshold over both a long window (the burn is
+//! significant) and a short window (it is still happening), and resolves
+//! once either drops back below. Firing and resolved transitions are logged
+//! and, when configured, sent to a webhook as a JSON `POST`.
+//!
+//! Outcomes are kept in one-minute buckets for the longest configured
+//! window, so memory per backend is bounded regardless of traffic.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::{DashMap, DashSet};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::config::{BurnRateAlertConfig, SloConfig, SloObjectiveConfig};
+
+/// Width of one outcome bucket.
+const BUCKET_SECS: u64 = 60;
+/// Timeout for alert webhook deliveries.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Service-level indicator measured against an objective.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Indicator {
+    /// Fraction of calls that succeeded.
+    SuccessRate,
+    /// Fraction of calls within the latency threshold.
+    Latency,
+}
+
+impl Indicator {
+    /// Label used in alert keys and logs.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SuccessRate => "success_rate",
+            Self::Latency => "latency",
+        }
+    }
+}
+
+/// Transition reported by [`SloTracker::evaluate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    /// The burn rate crossed the threshold in both windows.
+    Firing,
+    /// A previously firing alert dropped back below the threshold.
+    Resolved,
+}
+
+/// A burn-rate alert transition.
+#[derive(Debug, Clone, Serialize)]
+pub struct SloAlert {
+    /// Backend name.
+    pub backend: String,
+    /// Indicator whose budget is burning.
+    pub indicator: Indicator,
+    /// Alert rule name (e.g. `fast-burn`).
+    pub alert: String,
+    /// Firing or resolved.
+    pub state: AlertState,
+    /// Objective target (e.g. `0.995`).
+    pub target: f64,
+    /// Burn-rate threshold of the rule.
+    pub threshold: f64,
+    /// Burn rate over the long window.
+    pub long_burn_rate: f64,
+    /// Burn rate over the short window.
+    pub short_burn_rate: f64,
+    /// Long window in seconds.
+    pub long_window_secs: u64,
+    /// Short window in seconds.
+    pub short_window_secs: u64,
+}
+
+/// Current burn for one backend indicator, for stats surfaces.
+#[derive(Debug, Clone, Serialize)]
+pub struct SloStatus {
+    /// Backend name.
+    pub backend: String,
+    /// Indicator.
+    pub indicator: Indicator,
+    /// Objective target.
+    pub target: f64,
+    /// Burn per configured window, shortest first.
+    pub windows: Vec<WindowBurn>,
+    /// Names of alert rules currently firing.
+    pub firing: Vec<String>,
+}
+
+/// Burn over one rolling window.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowBurn {
+    /// Window length in seconds.
+    pub window_secs: u64,
+    /// Calls in the window.
+    pub events: u64,
+    /// Calls that violated the indicator.
+    pub bad_events: u64,
+    /// Error-budget burn rate (1.0 = sustainable).
+    pub burn_rate: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bucket {
+    index: u64,
+    total: u64,
+    failures: u64,
+    slow: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counts {
+    total: u64,
+    failures: u64,
+    slow: u64,
+}
+
+impl Counts {
+    fn bad(self, indicator: Indicator) -> u64 {
+        match indicator {
+            Indicator::SuccessRate => self.failures,
+            Indicator::Latency => self.slow,
+        }
+    }
+}
+
+/// Tracks per-backend outcomes against configured objectives.
+pub struct SloTracker {
+    objectives: HashMap<String, SloObjectiveConfig>,
+    alerts: Vec<BurnRateAlertConfig>,
+    min_events: u64,
+    origin: Instant,
+    retention: u64,
+    buckets: DashMap<String, Mutex<VecDeque<Bucket>>>,
+    /// Firing alerts, keyed `backend/indicator/alert`.
+    firing: DashSet<String>,
+}
+
+impl SloTracker {
+    /// Create a tracker for `config.objectives`.
+    #[must_use]
+    pub fn new(config: &SloConfig) -> Self {
+        let longest = config
+            .alerts
+            .iter()
+            .map(|a| a.long_window.as_secs())
+            .max()
+            .unwrap_or(3600);
+        Self {
+            objectives: config.objectives.clone(),
+            alerts: config.alerts.clone(),
+            min_events: config.min_events,
+            origin: Instant::now(),
+            retention: longest.div_ceil(BUCKET_SECS) + 1,
+            buckets: DashMap::new(),
+            firing: DashSet::new(),
+        }
+    }
+
+    /// Returns `true` when no objectives are configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.objectives.is_empty()
+    }
+
+    /// Record one call outcome for `backend`. Backends without an objective
+    /// are ignored.
+    pub fn record(&self, backend: &str, success: bool, latency: Duration) {
+        self.record_at(backend, success, latency, Instant::now());
+    }
+
+    fn record_at(&self, backend: &str, success: bool, latency: Duration, now: Instant) {
+        let Some(objective) = self.objectives.get(backend) else {
+            return;
+        };
+        let slow = objective
+            .latency
+            .as_ref()
+            .is_some_and(|l| latency > l.threshold);
+        let index = self.bucket_index(now);
+        let entry = self.buckets.entry(backend.to_string()).or_default();
+        let mut buckets = entry.lock();
+        if buckets.back().is_none_or(|b| b.index != index) {
+            buckets.push_back(Bucket {
+                index,
+                ..Bucket::default()
+            });
+        }
+        while buckets
+            .front()
+            .is_some_and(|b| b.index + self.retention <= index)
+        {
+            buckets.pop_front();
+        }
+        if let Some(bucket) = buckets.back_mut() {
+            bucket.total += 1;
+            bucket.failures += u64::from(!success);
+            bucket.slow += u64::from(slow);
+        }
+    }
+
+    fn bucket_index(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.origin).as_secs() / BUCKET_SECS
+    }
+
+    /// Sum outcomes in the trailing `window` (whole buckets, current included).
+    fn counts(&self, backend: &str, window: Duration, now: Instant) -> Counts {
+        let Some(entry) = self.buckets.get(backend) else {
+            return Counts::default();
+        };
+        let current = self.bucket_index(now);
+        let span = window.as_secs().div_ceil(BUCKET_SECS).max(1);
+        let mut counts = Counts::default();
+        for bucket in entry.lock().iter().rev() {
+            if bucket.index + span <= current {
+                break;
+            }
+            counts.total += bucket.total;
+            counts.failures += bucket.failures;
+            counts.slow += bucket.slow;
+        }
+        counts
+    }
+
+    /// Indicators configured for `objective`, with their targets.
+    fn indicators(objective: &SloObjectiveConfig) -> Vec<(Indicator, f64)> {
+        let mut indicators = Vec::new();
+        if let Some(target) = objective.success_rate {
+            indicators.push((Indicator::SuccessRate, target));
+        }
+        if let Some(latency) = &objective.latency {
+            indicators.push((Indicator::Latency, latency.target));
+        }
+        indicators
+    }
+
+    /// Evaluate every alert rule and return firing/resolved transitions.
+    #[must_use]
+    pub fn evaluate(&self) -> Vec<SloAlert> {
+        self.evaluate_at(Instant::now())
+    }
+
+    fn evaluate_at(&self, now: Instant) -> Vec<SloAlert> {
+        let mut transitions = Vec::new();
+        let mut backends: Vec<&String> = self.objectives.keys().collect();
+        backends.sort();
+        for backend in backends {
+            for (indicator, target) in Self::indicators(&self.objectives[backend]) {
+                for rule in &self.alerts {
+                    let long = self.counts(backend, rule.long_window, now);
+                    let short = self.counts(backend, rule.short_window, now);
+                    let long_burn = burn_rate(long.total, long.bad(indicator), target);
+                    let short_burn = burn_rate(short.total, short.bad(indicator), target);
+                    let burning = long.total >= self.min_events
+                        && long_burn >= rule.burn_rate
+                        && short_burn >= rule.burn_rate;
+
+                    let key = format!("{backend}/{}/{}", indicator.as_str(), rule.name);
+                    let state = if burning && self.firing.insert(key.clone()) {
+                        AlertState::Firing
+                    } else if !burning && self.firing.remove(&key).is_some() {
+                        AlertState::Resolved
+                    } else {
+                        continue;
+                    };
+                    transitions.push(SloAlert {
+                        backend: backend.clone(),
+                        indicator,
+                        alert: rule.name.clone(),
+                        state,
+                        target,
+                        threshold: rule.burn_rate,
+                        long_burn_rate: long_burn,
+                        short_burn_rate: short_burn,
+                        long_window_secs: rule.long_window.as_secs(),
+                        short_window_secs: rule.short_window.as_secs(),
+                    });
+                }
+            }
+        }
+        transitions
+    }
+
+    /// Current burn per backend indicator over each distinct alert window.
+    #[must_use]
+    pub fn status(&self) -> Vec<SloStatus> {
+        self.status_at(Instant::now())
+    }
+
+    fn status_at(&self, now: Instant) -> Vec<SloStatus> {
+        let mut windows: Vec<Duration> = self
+            .alerts
+            .iter()
+            .flat_map(|a| [a.short_window, a.long_window])
+            .collect();
+        windows.sort();
+        windows.dedup();
+
+        let mut backends: Vec<&String> = self.objectives.keys().collect();
+        backends.sort();
+        let mut statuses = Vec::new();
+        for backend in backends {
+            for (indicator, target) in Self::indicators(&self.objectives[backend]) {
+                let burns = windows
+                    .iter()
+                    .map(|window| {
+                        let counts = self.counts(backend, *window, now);
+                        WindowBurn {
+                            window_secs: window.as_secs(),
+                            events: counts.total,
+                            bad_events: counts.bad(indicator),
+                            burn_rate: burn_rate(counts.total, counts.bad(indicator), target),
+                        }
+                    })
+                    .collect();
+                let prefix = format!("{backend}/{}/", indicator.as_str());
+                let mut firing: Vec<String> = self
+                    .firing
+                    .iter()
+                    .filter_map(|key| key.strip_prefix(&prefix).map(str::to_string))
+                    .collect();
+                firing.sort();
+                statuses.push(SloStatus {
+                    backend: backend.clone(),
+                    indicator,
+                    target,
+                    windows: burns,
+                    firing,
+                });
+            }
+        }
+        statuses
+    }
+}
+
+/// Error-budget burn rate: observed bad fraction over allowed bad fraction.
+#[allow(clippy::cast_precision_loss)]
+fn burn_rate(total: u64, bad: u64, target: f64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (bad as f64 / total as f64) / (1.0 - target)
+}
+
+/// Log alert transitions and deliver them to the configured webhook.
+async fn notify(client: &reqwest::Client, webhook_url: Option<&str>, alerts: &[SloAlert]) {
+    for alert in alerts {
+        match alert.state {
+            AlertState::Firing => warn!(
+                backend = %alert.backend,
+                indicator = alert.indicator.as_str(),
+                alert = %alert.alert,
+                long_burn_rate = alert.long_burn_rate,
+                short_burn_rate = alert.short_burn_rate,
+                "SLO error budget burning"
+            ),
+            AlertState::Resolved => info!(
+                backend = %alert.backend,
+                indicator = alert.indicator.as_str(),
+                alert = %alert.alert,
+                "SLO burn alert resolved"
+            ),
+        }
+        let Some(url) = webhook_url else {
+            continue;
+        };
+        let body = serde_json::json!({
+            "source": "mcp-gateway",
+            "type": "slo_burn_rate",
+            "alert": alert,
+        });
+        match client.post(url).json(&body).send().await {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => warn!(status = %response.status(), "SLO alert webhook rejected event"),
+            Err(e) => warn!(error = %e, "SLO alert webhook delivery failed"),
+        }
+    }
+}
+
+/// Spawn the periodic burn-rate evaluator.
+pub fn spawn(
+    tracker: Arc<SloTracker>,
+    config: &SloConfig,
+    mut shutdown: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    let webhook_url = config.webhook_url.clone();
+    let period = config.evaluation_interval;
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let alerts = tracker.evaluate();
+                    notify(&client, webhook_url.as_deref(), &alerts).await;
+                }
+                _ = shutdown.recv() => {
+                    break;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests;
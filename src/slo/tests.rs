@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+use super::*;
+use crate::config::LatencyObjectiveConfig;
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+fn config() -> SloConfig {
+    SloConfig {
+        enabled: true,
+        objectives: HashMap::from([(
+            "github".to_string(),
+            SloObjectiveConfig {
+                success_rate: Some(0.99),
+                latency: Some(LatencyObjectiveConfig {
+                    threshold: Duration::from_millis(500),
+                    target: 0.9,
+                }),
+            },
+        )]),
+        alerts: vec![BurnRateAlertConfig {
+            name: "fast-burn".to_string(),
+            long_window: Duration::from_secs(3600),
+            short_window: Duration::from_secs(300),
+            burn_rate: 10.0,
+        }],
+        min_events: 10,
+        ..SloConfig::default()
+    }
+}
+
+fn record_many(tracker: &SloTracker, at: Instant, ok: u64, failed: u64) {
+    for _ in 0..ok {
+        tracker.record_at("github", true, Duration::from_millis(50), at);
+    }
+    for _ in 0..failed {
+        tracker.record_at("github", false, Duration::from_millis(50), at);
+    }
+}
+
+#[test]
+fn burn_rate_is_bad_fraction_over_budget() {
+    // 2 % errors against a 99 % target burns budget at 2×
+    assert!((burn_rate(100, 2, 0.99) - 2.0).abs() < 1e-9);
+    assert!(burn_rate(0, 0, 0.99).abs() < f64::EPSILON);
+}
+
+#[test]
+fn sustained_failures_fire_then_resolve() {
+    // GIVEN: 20 % errors (burn 20×) in the current minute
+    let tracker = SloTracker::new(&config());
+    let t0 = tracker.origin + MINUTE * 10;
+    record_many(&tracker, t0, 80, 20);
+
+    // WHEN/THEN: the fast-burn rule fires once
+    let alerts = tracker.evaluate_at(t0);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].state, AlertState::Firing);
+    assert_eq!(alerts[0].indicator, Indicator::SuccessRate);
+    assert!((alerts[0].long_burn_rate - 20.0).abs() < 1e-9);
+    assert!(tracker.evaluate_at(t0).is_empty(), "no duplicate firing");
+
+    // WHEN: the short window rolls past the failures with clean traffic
+    let later = t0 + MINUTE * 6;
+    record_many(&tracker, later, 100, 0);
+
+    // THEN: the alert resolves even though the long window still burns
+    let alerts = tracker.evaluate_at(later);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].state, AlertState::Resolved);
+}
+
+#[test]
+fn alerts_require_min_events() {
+    let tracker = SloTracker::new(&config());
+    let t0 = tracker.origin;
+    record_many(&tracker, t0, 0, 5);
+    assert!(tracker.evaluate_at(t0).is_empty());
+}
+
+#[test]
+fn slow_calls_burn_latency_budget() {
+    // GIVEN: a 4× rule and calls that all succeed, half over the 500 ms bar
+    let mut cfg = config();
+    cfg.alerts[0].burn_rate = 4.0;
+    let tracker = SloTracker::new(&cfg);
+    let t0 = tracker.origin;
+    for i in 0..40 {
+        let latency = if i % 2 == 0 { 900 } else { 100 };
+        tracker.record_at("github", true, Duration::from_millis(latency), t0);
+    }
+
+    // THEN: only the latency indicator fires (50 % slow / 10 % budget = 5×)
+    let alerts = tracker.evaluate_at(t0);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].indicator, Indicator::Latency);
+    assert!((alerts[0].long_burn_rate - 5.0).abs() < 1e-9);
+}
+
+#[test]
+fn untracked_backends_are_ignored() {
+    let tracker = SloTracker::new(&config());
+    tracker.record("jira", false, Duration::ZERO);
+    assert!(tracker.buckets.get("jira").is_none());
+}
+
+#[test]
+fn old_buckets_expire_from_windows() {
+    let tracker = SloTracker::new(&config());
+    let t0 = tracker.origin;
+    record_many(&tracker, t0, 0, 50);
+
+    // Two hours later the failures are outside the 1 h window.
+    let status = tracker.status_at(t0 + MINUTE * 120);
+    let success = status
+        .iter()
+        .find(|s| s.indicator == Indicator::SuccessRate)
+        .unwrap();
+    assert!(success.windows.iter().all(|w| w.events == 0));
+}
+
+#[test]
+fn status_reports_burn_per_window_and_firing_rules() {
+    let tracker = SloTracker::new(&config());
+    let t0 = tracker.origin;
+    record_many(&tracker, t0, 50, 50);
+    let _ = tracker.evaluate_at(t0);
+
+    let status = tracker.status_at(t0);
+    assert_eq!(status.len(), 2);
+    let success = &status[0];
+    assert_eq!(success.indicator, Indicator::SuccessRate);
+    assert_eq!(
+        success
+            .windows
+            .iter()
+            .map(|w| w.window_secs)
+            .collect::<Vec<_>>(),
+        vec![300, 3600]
+    );
+    assert_eq!(success.windows[0].bad_events, 50);
+    assert!((success.windows[0].burn_rate - 50.0).abs() < 1e-9);
+    assert_eq!(success.firing, vec!["fast-burn".to_string()]);
+}
@@ -2,14 +2,23 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 //! Usage statistics tracking for the gateway
 //!
-//! Tracks invocations, cache hits, tools discovered, cached token counts, and
-//! calculates token/cost savings.
+//! Tracks invocations, cache hits, tools discovered, cached token counts,
+//! latency percentiles, and error classes, and calculates token/cost savings.
+//! [`StatsHistory`] keeps a bounded in-memory time series of snapshots.
 
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use dashmap::DashMap;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
+/// Latency samples kept per backend and per tool; percentiles cover this
+/// most-recent window.
+const LATENCY_WINDOW: usize = 1024;
+
 /// Usage statistics for the gateway
 #[derive(Default)]
 pub struct UsageStats {
@@ -28,6 +37,104 @@ pub struct UsageStats {
     cached_tokens_by_server: DashMap<String, AtomicU64>,
     /// Cumulative prompt-cached tokens per conversation/session (key = session ID)
     cached_tokens_by_session: DashMap<String, AtomicU64>,
+    /// Recent dispatch latencies per backend (key = server name)
+    latency_by_server: DashMap<String, LatencyWindow>,
+    /// Recent dispatch latencies per tool (key = "server:tool")
+    latency_by_tool: DashMap<String, LatencyWindow>,
+    /// Failed invocations by error class (e.g. `timeout`, `rate_limited`)
+    errors_by_class: DashMap<String, AtomicU64>,
+    /// Cache hits by cache kind (`response`, `idempotency`)
+    cache_hits_by_kind: DashMap<String, AtomicU64>,
+    /// Cache hits by backend (key = server name)
+    cache_hits_by_server: DashMap<String, AtomicU64>,
+}
+
+/// Ring buffer of the most recent latency samples, in microseconds.
+#[derive(Default)]
+struct LatencyWindow {
+    samples: Mutex<VecDeque<u64>>,
+}
+
+impl LatencyWindow {
+    fn record(&self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        let mut samples = self.samples.lock();
+        if samples.len() == LATENCY_WINDOW {
+            samples.pop_front();
+        }
+        samples.push_back(micros);
+    }
+
+    fn summary(&self, key: &str) -> LatencySummary {
+        let mut sorted: Vec<u64> = self.samples.lock().iter().copied().collect();
+        sorted.sort_unstable();
+        LatencySummary {
+            key: key.to_string(),
+            samples: sorted.len(),
+            p50_ms: percentile_ms(&sorted, 50),
+            p95_ms: percentile_ms(&sorted, 95),
+            p99_ms: percentile_ms(&sorted, 99),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted microsecond samples, in milliseconds.
+#[allow(clippy::cast_precision_loss)]
+fn percentile_ms(sorted: &[u64], pct: usize) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1] as f64 / 1000.0
+}
+
+/// Increment a keyed counter, creating it on first use.
+fn bump(map: &DashMap<String, AtomicU64>, key: &str) {
+    map.entry(key.to_string())
+        .or_insert_with(|| AtomicU64::new(0))
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// Collect a keyed counter map into a sorted map.
+fn counts(map: &DashMap<String, AtomicU64>) -> BTreeMap<String, u64> {
+    map.iter()
+        .map(|e| (e.key().clone(), e.value().load(Ordering::Relaxed)))
+        .collect()
+}
+
+/// Add persisted counts onto a keyed counter map.
+fn merge(map: &DashMap<String, AtomicU64>, persisted: BTreeMap<String, u64>) {
+    for (key, count) in persisted {
+        map.entry(key)
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+/// On-disk form of the cumulative [`UsageStats`] counters.
+///
+/// Latency windows and per-session token counts are intentionally not
+/// persisted: percentiles describe recent behaviour and session IDs do not
+/// survive a restart.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct PersistedStats {
+    invocations: u64,
+    cache_hits: u64,
+    tools_discovered: u64,
+    tool_usage: BTreeMap<String, u64>,
+    cached_tokens_by_server: BTreeMap<String, u64>,
+    errors_by_class: BTreeMap<String, u64>,
+    cache_hits_by_kind: BTreeMap<String, u64>,
+    cache_hits_by_server: BTreeMap<String, u64>,
+}
+
+/// Summarise every latency window, sorted by key.
+fn latency_summaries(map: &DashMap<String, LatencyWindow>) -> Vec<LatencySummary> {
+    let mut summaries: Vec<LatencySummary> =
+        map.iter().map(|e| e.value().summary(e.key())).collect();
+    summaries.sort_by(|a, b| a.key.cmp(&b.key));
+    summaries
 }
 
 impl UsageStats {
@@ -52,6 +159,31 @@ impl UsageStats {
         self.cache_hits.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a cache hit served for `server` from the cache of `kind`
+    /// (`response` or `idempotency`).
+    pub fn record_cache_hit_for(&self, server: &str, kind: &str) {
+        self.record_cache_hit();
+        bump(&self.cache_hits_by_kind, kind);
+        bump(&self.cache_hits_by_server, server);
+    }
+
+    /// Record the dispatch latency of one invocation.
+    pub fn record_latency(&self, server: &str, tool: &str, latency: Duration) {
+        self.latency_by_server
+            .entry(server.to_string())
+            .or_default()
+            .record(latency);
+        self.latency_by_tool
+            .entry(format!("{server}:{tool}"))
+            .or_default()
+            .record(latency);
+    }
+
+    /// Record a failed invocation under its error class.
+    pub fn record_error(&self, class: &str) {
+        bump(&self.errors_by_class, class);
+    }
+
     /// Record tools discovered in a search
     pub fn record_search(&self, count: u64) {
         self.tools_discovered.fetch_add(count, Ordering::Relaxed);
@@ -179,9 +311,64 @@ impl UsageStats {
             top_tools,
             total_cached_tokens,
             cached_tokens_by_server,
+            latency_by_server: latency_summaries(&self.latency_by_server),
+            latency_by_tool: latency_summaries(&self.latency_by_tool),
+            errors_by_class: counts(&self.errors_by_class),
+            cache_hits_by_kind: counts(&self.cache_hits_by_kind),
+            cache_hits_by_server: counts(&self.cache_hits_by_server),
         }
     }
 
+    /// Save the cumulative counters to a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails or the file cannot be written.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let persisted = PersistedStats {
+            invocations: self.total_invocations.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            tools_discovered: self.tools_discovered.load(Ordering::Relaxed),
+            tool_usage: counts(&self.tool_usage),
+            cached_tokens_by_server: counts(&self.cached_tokens_by_server),
+            errors_by_class: counts(&self.errors_by_class),
+            cache_hits_by_kind: counts(&self.cache_hits_by_kind),
+            cache_hits_by_server: counts(&self.cache_hits_by_server),
+        };
+        let json = serde_json::to_string_pretty(&persisted)?;
+        std::fs::write(path, json)
+    }
+
+    /// Load counters from a JSON file written by [`save`](Self::save).
+    ///
+    /// Loaded counts are added to the current ones, so loading into a fresh
+    /// tracker restores the saved totals.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or JSON is invalid.
+    pub fn load(&self, path: &Path) -> std::io::Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        let persisted: PersistedStats = serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        self.total_invocations
+            .fetch_add(persisted.invocations, Ordering::Relaxed);
+        self.cache_hits
+            .fetch_add(persisted.cache_hits, Ordering::Relaxed);
+        self.tools_discovered
+            .fetch_add(persisted.tools_discovered, Ordering::Relaxed);
+        merge(&self.tool_usage, persisted.tool_usage);
+        merge(
+            &self.cached_tokens_by_server,
+            persisted.cached_tokens_by_server,
+        );
+        merge(&self.errors_by_class, persisted.errors_by_class);
+        merge(&self.cache_hits_by_kind, persisted.cache_hits_by_kind);
+        merge(&self.cache_hits_by_server, persisted.cache_hits_by_server);
+        Ok(())
+    }
+
     /// Calculate estimated cost savings
     #[allow(clippy::cast_precision_loss)]
     pub fn cost_savings(&self, total_backend_tools: usize, price_per_million: f64) -> f64 {
@@ -211,6 +398,21 @@ pub struct StatsSnapshot {
     pub total_cached_tokens: u64,
     /// Per-server prompt-cached token breakdown (sorted descending by token count)
     pub cached_tokens_by_server: Vec<CachedTokensEntry>,
+    /// Dispatch latency percentiles per backend (sorted by server)
+    #[serde(default)]
+    pub latency_by_server: Vec<LatencySummary>,
+    /// Dispatch latency percentiles per tool (sorted by `server:tool`)
+    #[serde(default)]
+    pub latency_by_tool: Vec<LatencySummary>,
+    /// Failed invocations by error class
+    #[serde(default)]
+    pub errors_by_class: BTreeMap<String, u64>,
+    /// Cache hits by cache kind (`response`, `idempotency`)
+    #[serde(default)]
+    pub cache_hits_by_kind: BTreeMap<String, u64>,
+    /// Cache hits by backend
+    #[serde(default)]
+    pub cache_hits_by_server: BTreeMap<String, u64>,
 }
 
 impl StatsSnapshot {
@@ -233,6 +435,21 @@ pub struct TopTool {
     pub count: u64,
 }
 
+/// Latency percentiles for one backend or tool over its recent window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencySummary {
+    /// Server name, or `server:tool`
+    pub key: String,
+    /// Samples in the window (at most 1024)
+    pub samples: usize,
+    /// Median latency in milliseconds
+    pub p50_ms: f64,
+    /// 95th-percentile latency in milliseconds
+    pub p95_ms: f64,
+    /// 99th-percentile latency in milliseconds
+    pub p99_ms: f64,
+}
+
 /// Per-server cached token entry in statistics snapshots
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedTokensEntry {
@@ -242,10 +459,143 @@ pub struct CachedTokensEntry {
     pub cached_tokens: u64,
 }
 
+/// One point in the [`StatsHistory`] time series.
+///
+/// Counters are cumulative; difference consecutive samples for rates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsSample {
+    /// Unix timestamp (seconds) when the sample was taken
+    pub timestamp: u64,
+    /// Total invocations
+    pub invocations: u64,
+    /// Cache hits
+    pub cache_hits: u64,
+    /// Cache hit rate (0.0-1.0)
+    pub cache_hit_rate: f64,
+    /// Failed invocations across all error classes
+    pub errors: u64,
+    /// Estimated tokens saved by using gateway
+    pub tokens_saved: u64,
+    /// Total prompt-cached tokens observed across all backends
+    pub total_cached_tokens: u64,
+    /// 95th-percentile dispatch latency per backend, in milliseconds
+    pub latency_p95_ms: BTreeMap<String, f64>,
+}
+
+impl StatsSample {
+    /// Reduce a snapshot to the fields worth charting over time.
+    #[must_use]
+    pub fn from_snapshot(snapshot: &StatsSnapshot, timestamp: u64) -> Self {
+        Self {
+            timestamp,
+            invocations: snapshot.invocations,
+            cache_hits: snapshot.cache_hits,
+            cache_hit_rate: snapshot.cache_hit_rate,
+            errors: snapshot.errors_by_class.values().sum(),
+            tokens_saved: snapshot.tokens_saved,
+            total_cached_tokens: snapshot.total_cached_tokens,
+            latency_p95_ms: snapshot
+                .latency_by_server
+                .iter()
+                .map(|l| (l.key.clone(), l.p95_ms))
+                .collect(),
+        }
+    }
+}
+
+/// Fixed-capacity in-memory time series of [`StatsSample`]s.
+///
+/// Sampled every `resolution` by the server; the oldest sample is dropped
+/// once `capacity` samples are held.
+pub struct StatsHistory {
+    resolution: Duration,
+    capacity: usize,
+    samples: Mutex<VecDeque<StatsSample>>,
+}
+
+impl StatsHistory {
+    /// Create a history holding up to `capacity` samples taken every `resolution`.
+    #[must_use]
+    pub fn new(resolution: Duration, capacity: usize) -> Self {
+        Self {
+            resolution,
+            capacity: capacity.max(1),
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Interval between samples.
+    #[must_use]
+    pub fn resolution(&self) -> Duration {
+        self.resolution
+    }
+
+    /// Time span covered by a full history.
+    #[must_use]
+    pub fn retention(&self) -> Duration {
+        self.resolution
+            .saturating_mul(u32::try_from(self.capacity).unwrap_or(u32::MAX))
+    }
+
+    /// Append a sample of `snapshot` taken at `timestamp` (Unix seconds).
+    pub fn record(&self, snapshot: &StatsSnapshot, timestamp: u64) {
+        let mut samples = self.samples.lock();
+        if samples.len() == self.capacity {
+            samples.pop_front();
+        }
+        samples.push_back(StatsSample::from_snapshot(snapshot, timestamp));
+    }
+
+    /// Samples in chronological order, optionally only those taken at or
+    /// after `since` (Unix seconds).
+    #[must_use]
+    pub fn samples(&self, since: Option<u64>) -> Vec<StatsSample> {
+        self.samples
+            .lock()
+            .iter()
+            .filter(|s| since.is_none_or(|since| s.timestamp >= since))
+            .cloned()
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn history_drops_oldest_sample_at_capacity() {
+        let stats = UsageStats::new();
+        let history = StatsHistory::new(Duration::from_secs(60), 3);
+        for ts in 0..5 {
+            stats.record_invocation("server1", "tool1");
+            history.record(&stats.snapshot(0), ts * 60);
+        }
+
+        let samples = history.samples(None);
+        let timestamps: Vec<u64> = samples.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![120, 180, 240]);
+        assert_eq!(samples[2].invocations, 5);
+        assert_eq!(history.retention(), Duration::from_secs(180));
+    }
+
+    #[test]
+    fn history_filters_samples_since_timestamp() {
+        let stats = UsageStats::new();
+        stats.record_error("timeout");
+        stats.record_error("rate_limited");
+        stats.record_latency("server1", "tool1", Duration::from_millis(20));
+        let history = StatsHistory::new(Duration::from_secs(10), 10);
+        history.record(&stats.snapshot(0), 100);
+        history.record(&stats.snapshot(0), 110);
+
+        let samples = history.samples(Some(105));
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].timestamp, 110);
+        assert_eq!(samples[0].errors, 2);
+        assert!((samples[0].latency_p95_ms["server1"] - 20.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_record_invocation() {
         let stats = UsageStats::new();
@@ -455,6 +805,63 @@ mod tests {
         assert_eq!(snap.cached_tokens_by_server[1].cached_tokens, 500);
     }
 
+    // ── latency / errors / cache breakdown ────────────────────────────
+
+    #[test]
+    fn snapshot_reports_latency_percentiles_per_server_and_tool() {
+        // GIVEN: 100 calls at 1..=100 ms on one tool
+        let stats = UsageStats::new();
+        for ms in 1..=100 {
+            stats.record_latency("srv", "slow", Duration::from_millis(ms));
+        }
+        stats.record_latency("other", "fast", Duration::from_millis(2));
+
+        // WHEN: taking a snapshot
+        let snap = stats.snapshot(10);
+
+        // THEN: nearest-rank percentiles are reported for both dimensions
+        let server = &snap.latency_by_server[1];
+        assert_eq!(server.key, "srv");
+        assert_eq!(server.samples, 100);
+        assert!((server.p50_ms - 50.0).abs() < f64::EPSILON);
+        assert!((server.p95_ms - 95.0).abs() < f64::EPSILON);
+        assert!((server.p99_ms - 99.0).abs() < f64::EPSILON);
+        assert_eq!(snap.latency_by_tool[0].key, "other:fast");
+        assert!((snap.latency_by_tool[0].p99_ms - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn latency_window_keeps_only_recent_samples() {
+        let stats = UsageStats::new();
+        for _ in 0..LATENCY_WINDOW {
+            stats.record_latency("srv", "t", Duration::from_secs(1));
+        }
+        for _ in 0..LATENCY_WINDOW {
+            stats.record_latency("srv", "t", Duration::from_millis(1));
+        }
+        let snap = stats.snapshot(10);
+        assert_eq!(snap.latency_by_server[0].samples, LATENCY_WINDOW);
+        assert!((snap.latency_by_server[0].p99_ms - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn snapshot_breaks_down_errors_and_cache_hits() {
+        let stats = UsageStats::new();
+        stats.record_error("timeout");
+        stats.record_error("timeout");
+        stats.record_error("rate_limited");
+        stats.record_cache_hit_for("a", "response");
+        stats.record_cache_hit_for("a", "idempotency");
+        stats.record_cache_hit_for("b", "response");
+
+        let snap = stats.snapshot(10);
+        assert_eq!(snap.errors_by_class["timeout"], 2);
+        assert_eq!(snap.errors_by_class["rate_limited"], 1);
+        assert_eq!(snap.cache_hits, 3);
+        assert_eq!(snap.cache_hits_by_kind["response"], 2);
+        assert_eq!(snap.cache_hits_by_server["a"], 2);
+    }
+
     #[test]
     fn snapshot_empty_cached_tokens_when_none_recorded() {
         let stats = UsageStats::new();
@@ -462,4 +869,62 @@ mod tests {
         assert_eq!(snap.total_cached_tokens, 0);
         assert!(snap.cached_tokens_by_server.is_empty());
     }
+
+    #[test]
+    fn save_and_load_round_trips_cumulative_counters() {
+        // GIVEN: stats with invocations, cache hits, errors and cached tokens
+        let stats = UsageStats::new();
+        stats.record_invocation("srv", "search");
+        stats.record_invocation("srv", "search");
+        stats.record_cache_hit_for("srv", "response");
+        stats.record_search(7);
+        stats.record_error("timeout");
+        stats.record_cached_tokens("srv", Some("session-1"), 500);
+        stats.record_latency("srv", "search", Duration::from_millis(5));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stats.json");
+
+        // WHEN: saved and loaded into a fresh tracker
+        stats.save(&path).unwrap();
+        let restored = UsageStats::new();
+        restored.load(&path).unwrap();
+
+        // THEN: counters and token savings survive; latency windows do not
+        let before = stats.snapshot(20);
+        let after = restored.snapshot(20);
+        assert_eq!(after.invocations, 2);
+        assert_eq!(after.tokens_saved, before.tokens_saved);
+        assert_eq!(after.cache_hits, 1);
+        assert_eq!(after.tools_discovered, 7);
+        assert_eq!(restored.tool_usage("srv", "search"), 2);
+        assert_eq!(after.errors_by_class["timeout"], 1);
+        assert_eq!(after.cache_hits_by_kind["response"], 1);
+        assert_eq!(restored.cached_tokens_for_server("srv"), 500);
+        assert_eq!(restored.cached_tokens_for_session("session-1"), 0);
+        assert!(after.latency_by_server.is_empty());
+    }
+
+    #[test]
+    fn load_adds_to_existing_counters() {
+        let stats = UsageStats::new();
+        stats.record_invocation("srv", "t");
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stats.json");
+        stats.save(&path).unwrap();
+
+        stats.load(&path).unwrap();
+
+        assert_eq!(stats.tool_usage("srv", "t"), 2);
+        assert_eq!(stats.snapshot(0).invocations, 2);
+    }
+
+    #[test]
+    fn load_rejects_invalid_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stats.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let err = UsageStats::new().load(&path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
 }
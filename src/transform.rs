@@ -5,14 +5,14 @@
 //! Transforms sit between the executor response and the MCP response,
 //! configured per-capability in the YAML `transform` section.
 //!
-//! Pipeline order (fixed): **project -> rename -> redact -> format**
+//! Pipeline order (fixed): **query -> project -> rename -> redact -> format**
 //!
 //! ```text
 //! Executor Response
 //!       │
 //!       ▼
 //! ┌─────────────┐
-//! │  Transform  │──▶ project ──▶ rename ──▶ redact ──▶ format
+//! │  Transform  │──▶ query ──▶ project ──▶ rename ──▶ redact ──▶ format
 //! │  Pipeline   │
 //! └─────────────┘
 //!       │
@@ -32,6 +32,13 @@ use serde_json::Value;
 /// Complete transform configuration for a capability.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TransformConfig {
+    /// JMESPath-style query that reshapes the response before any other
+    /// step, e.g. `results[*].{id: id, title: meta.title}`.
+    ///
+    /// See [`query`](crate::transform::query) for the supported syntax.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
     /// Field projection (allowlist of JSON paths to keep).
     #[serde(default)]
     pub project: Vec<String>,
@@ -56,11 +63,28 @@ impl TransformConfig {
     /// are omitted from serialised capability YAML.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.project.is_empty()
+        self.query.is_none()
+            && self.project.is_empty()
             && self.rename.is_empty()
             && self.redact.is_empty()
             && self.format.is_none()
     }
+
+    /// Check that `query`, when set, parses.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem when the query is invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        self.query
+            .as_deref()
+            .map_or(Ok(()), |expr| parse_query(expr).map(drop))
+    }
+}
+
+/// Parse a configured `query`, naming it in the error.
+fn parse_query(expr: &str) -> Result<query::Query, String> {
+    query::Query::parse(expr).map_err(|e| format!("invalid query '{expr}': {e}"))
 }
 
 /// A single redaction rule.
@@ -192,4 +216,5 @@ pub fn resolve_path_single(value: &Value, path: &[JsonPathSegment]) -> Value {
 }
 
 pub mod pipeline;
+pub mod query;
 pub use pipeline::TransformPipeline;
@@ -6,9 +6,10 @@
 use regex::Regex;
 use serde_json::Value;
 
+use super::query::Query;
 use super::{
-    FormatConfig, FormatType, JsonPath, TransformConfig, parse_json_path, resolve_path,
-    resolve_path_single,
+    FormatConfig, FormatType, JsonPath, TransformConfig, parse_json_path, parse_query,
+    resolve_path, resolve_path_single,
 };
 
 // ============================================================================
@@ -25,6 +26,7 @@ struct CompiledRedaction {
 ///
 /// Construct via [`TransformPipeline::compile`] from a [`TransformConfig`].
 pub struct TransformPipeline {
+    query: Option<Query>,
     projections: Vec<(String, JsonPath)>,
     renames: Vec<(String, String)>,
     redactions: Vec<CompiledRedaction>,
@@ -36,8 +38,15 @@ impl TransformPipeline {
     ///
     /// Invalid regex patterns in redact rules are silently skipped
     /// (logged at debug level in production).
-    #[must_use]
-    pub fn compile(config: &TransformConfig) -> Self {
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem when `query` does not parse. A
+    /// pipeline without its query would pass through data the query was
+    /// meant to drop, so there is no partial result.
+    pub fn compile(config: &TransformConfig) -> Result<Self, String> {
+        let query = config.query.as_deref().map(parse_query).transpose()?;
+
         let projections: Vec<(String, JsonPath)> = config
             .project
             .iter()
@@ -63,18 +72,20 @@ impl TransformPipeline {
             })
             .collect();
 
-        Self {
+        Ok(Self {
+            query,
             projections,
             renames,
             redactions,
             format: config.format.clone(),
-        }
+        })
     }
 
     /// Returns `true` if this pipeline has no operations to perform.
     #[must_use]
     pub fn is_noop(&self) -> bool {
-        self.projections.is_empty()
+        self.query.is_none()
+            && self.projections.is_empty()
             && self.renames.is_empty()
             && self.redactions.is_empty()
             && self.format.is_none()
@@ -82,20 +93,24 @@ impl TransformPipeline {
 
     /// Apply the full transform pipeline to a JSON value.
     ///
-    /// Pipeline order: project -> rename -> redact -> format.
+    /// Pipeline order: query -> project -> rename -> redact -> format.
     #[must_use]
     pub fn apply(&self, value: Value) -> Value {
         if self.is_noop() {
             return value;
         }
 
+        let value = match &self.query {
+            Some(query) => query.evaluate(&value),
+            None => value,
+        };
         let value = self.apply_project(value);
         let value = self.apply_rename(value);
         let value = self.apply_redact(value);
         self.apply_format(value)
     }
 
-    // ── Step 1: Projection ──────────────────────────────────────────────
+    // ── Step 1: Projection (after the optional query) ───────────────────
 
     fn apply_project(&self, value: Value) -> Value {
         if self.projections.is_empty() {
@@ -175,14 +175,14 @@ fn leaf_key_wildcard_only() {
 #[test]
 fn default_config_produces_noop_pipeline() {
     let config = TransformConfig::default();
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     assert!(pipeline.is_noop());
 }
 
 #[test]
 fn noop_pipeline_returns_input_unchanged() {
     let config = TransformConfig::default();
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"keep": "this", "nested": {"deep": true}});
     let output = pipeline.apply(input.clone());
     assert_eq!(output, input);
@@ -194,7 +194,7 @@ fn pipeline_with_projections_is_not_noop() {
         project: vec!["name".to_string()],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     assert!(!pipeline.is_noop());
 }
 
@@ -206,7 +206,7 @@ fn project_keeps_only_listed_fields() {
         project: vec!["name".to_string(), "age".to_string()],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"name": "Alice", "age": 30, "secret": "hidden"});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"name": "Alice", "age": 30}));
@@ -218,7 +218,7 @@ fn project_nested_paths() {
         project: vec!["query.original".to_string()],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"query": {"original": "rust", "altered": "Rust lang"}, "extra": 42});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"original": "rust"}));
@@ -230,7 +230,7 @@ fn project_array_wildcard() {
         project: vec!["items[].name".to_string()],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"items": [{"name": "a", "x": 1}, {"name": "b", "x": 2}]});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"name": ["a", "b"]}));
@@ -242,7 +242,7 @@ fn project_missing_field_is_omitted() {
         project: vec!["nonexistent".to_string()],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"real": "data"});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({}));
@@ -256,7 +256,7 @@ fn rename_flat_keys() {
         rename: HashMap::from([("old_name".to_string(), "new_name".to_string())]),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"old_name": "value", "other": 42});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"new_name": "value", "other": 42}));
@@ -268,7 +268,7 @@ fn rename_dotted_path_uses_leaf() {
         rename: HashMap::from([("query.original".to_string(), "query".to_string())]),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     // After projection, the key is the leaf "original"
     let input = json!({"original": "rust search"});
     let output = pipeline.apply(input);
@@ -281,7 +281,7 @@ fn rename_missing_key_is_noop() {
         rename: HashMap::from([("missing".to_string(), "new".to_string())]),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"present": "value"});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"present": "value"}));
@@ -293,7 +293,7 @@ fn rename_non_object_value_unchanged() {
         rename: HashMap::from([("x".to_string(), "y".to_string())]),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!("a string");
     let output = pipeline.apply(input.clone());
     assert_eq!(output, input);
@@ -310,7 +310,7 @@ fn redact_email_in_string() {
         }],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"msg": "Contact alice@example.com for info"});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"msg": "Contact [EMAIL] for info"}));
@@ -325,7 +325,7 @@ fn redact_ssn_pattern() {
         }],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"data": "SSN is 123-45-6789"});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"data": "SSN is [SSN]"}));
@@ -340,7 +340,7 @@ fn redact_recursive_into_arrays() {
         }],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"items": ["this is secret", {"note": "another secret"}]});
     let output = pipeline.apply(input);
     assert_eq!(
@@ -358,7 +358,7 @@ fn redact_non_string_values_unchanged() {
         }],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"num": 123, "bool": true, "null": null});
     let output = pipeline.apply(input.clone());
     assert_eq!(output, input);
@@ -379,7 +379,7 @@ fn redact_invalid_regex_skipped() {
         ],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     assert_eq!(pipeline.redactions.len(), 1); // only the valid one
     let input = json!({"msg": "this is good"});
     let output = pipeline.apply(input);
@@ -401,7 +401,7 @@ fn redact_multiple_patterns() {
         ],
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"msg": "foo and bar"});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"msg": "X and Y"}));
@@ -418,7 +418,7 @@ fn format_flat_simple() {
         }),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"a": {"b": 1, "c": 2}});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"a.b": 1, "a.c": 2}));
@@ -433,7 +433,7 @@ fn format_flat_with_array() {
         }),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"items": ["a", "b"]});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"items.0": "a", "items.1": "b"}));
@@ -448,7 +448,7 @@ fn format_flat_scalar() {
         }),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"x": 42});
     let output = pipeline.apply(input);
     assert_eq!(output, json!({"x": 42}));
@@ -465,7 +465,7 @@ fn format_nested_is_noop() {
         }),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"a": {"b": 1}});
     let output = pipeline.apply(input.clone());
     assert_eq!(output, input);
@@ -482,7 +482,7 @@ fn format_template_basic() {
         }),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"name": "World"});
     let output = pipeline.apply(input);
     assert_eq!(output, json!("Hello, World!"));
@@ -497,7 +497,7 @@ fn format_template_nested_path() {
         }),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"query": {"original": "Rust MCP"}});
     let output = pipeline.apply(input);
     assert_eq!(output, json!("Query: Rust MCP"));
@@ -512,7 +512,7 @@ fn format_template_missing_var_renders_empty() {
         }),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({});
     let output = pipeline.apply(input);
     assert_eq!(output, json!("Value: "));
@@ -527,12 +527,55 @@ fn format_template_none_returns_input() {
         }),
         ..Default::default()
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
     let input = json!({"data": true});
     let output = pipeline.apply(input.clone());
     assert_eq!(output, input);
 }
 
+// ── Query ───────────────────────────────────────────────────────────
+
+#[test]
+fn query_reshapes_before_redaction() {
+    let config = TransformConfig {
+        query: Some("results[*].{id: id, owner: meta.owner}".to_string()),
+        redact: vec![RedactRule {
+            pattern: r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b".to_string(),
+            replacement: "[EMAIL]".to_string(),
+        }],
+        ..Default::default()
+    };
+    let pipeline = TransformPipeline::compile(&config).unwrap();
+
+    let output = pipeline.apply(json!({
+        "results": [
+            {"id": 1, "meta": {"owner": "a@example.com", "etag": "x"}, "raw": "…"},
+            {"id": 2, "meta": {"owner": "b@example.com", "etag": "y"}, "raw": "…"}
+        ],
+        "paging": {"next": null}
+    }));
+
+    assert_eq!(
+        output,
+        json!([{"id": 1, "owner": "[EMAIL]"}, {"id": 2, "owner": "[EMAIL]"}])
+    );
+}
+
+#[test]
+fn invalid_query_fails_compilation() {
+    // GIVEN: a query that does not parse
+    let config = TransformConfig {
+        query: Some("items[?".to_string()),
+        ..Default::default()
+    };
+
+    // WHEN/THEN: validation and compilation both reject it, so a typo
+    // never passes the untransformed response through
+    let err = config.validate().unwrap_err();
+    assert!(err.contains("items[?"), "{err}");
+    assert!(TransformPipeline::compile(&config).is_err());
+}
+
 // ── Full pipeline integration ───────────────────────────────────────
 
 #[test]
@@ -552,8 +595,9 @@ fn full_pipeline_project_rename_redact() {
             replacement: "[EMAIL]".to_string(),
         }],
         format: None,
+        query: None,
     };
-    let pipeline = TransformPipeline::compile(&config);
+    let pipeline = TransformPipeline::compile(&config).unwrap();
 
     let input = json!({
         "query": {"original": "search query", "altered": "ignore"},
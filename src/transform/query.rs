@@ -0,0 +1,493 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: MIT
+
+//! JMESPath-style query expressions for reshaping tool responses.
+//!
+//! Implements the projection-oriented subset of [JMESPath](https://jmespath.org)
+//! that response shaping actually needs, without pulling in a full
+//! interpreter:
+//!
+//! | Syntax | Meaning |
+//! |--------|---------|
+//! | `foo.bar`, `"quoted key"` | Field access |
+//! | `items[0]`, `items[-1]` | Array index (negative counts from the end) |
+//! | `items[*].name` | List projection |
+//! | `items[].name` | Flatten one level, then project |
+//! | `obj.*.name` | Object-value projection |
+//! | `[a, b]` / `{x: a, y: b}` | Multi-select list / hash |
+//! | `expr \| expr` | Pipe (stops a projection) |
+//! | `@` | Current node |
+//!
+//! As in `JMESPath`, projections drop `null` results and a missing field
+//! evaluates to `null` rather than failing. Functions, filters, slices, and
+//! literals are not supported and are rejected at parse time.
+//!
+//! # Example
+//!
+//! ```rust
+//! use mcp_gateway::transform::query::Query;
+//! use serde_json::json;
+//!
+//! let q = Query::parse("items[*].{id: id, title: meta.title}").unwrap();
+//! let out = q.evaluate(&json!({"items": [{"id": 1, "meta": {"title": "a"}}]}));
+//! assert_eq!(out, json!([{"id": 1, "title": "a"}]));
+//! ```
+
+use serde_json::{Map, Value};
+
+/// A compiled query expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    root: Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Identity,
+    Field(String),
+    Index(i64),
+    Subexpr(Box<Node>, Box<Node>),
+    ListProject(Box<Node>, Box<Node>),
+    Flatten(Box<Node>, Box<Node>),
+    ValueProject(Box<Node>, Box<Node>),
+    MultiList(Vec<Node>),
+    MultiHash(Vec<(String, Node)>),
+    Pipe(Box<Node>, Box<Node>),
+}
+
+impl Query {
+    /// Parse a query expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns a human-readable message naming the offending position when
+    /// the expression is malformed or uses unsupported syntax.
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: expr.chars().collect(),
+            pos: 0,
+        };
+        let root = parser.parse_pipe()?;
+        parser.skip_ws();
+        if let Some(c) = parser.peek() {
+            return Err(format!("unexpected '{c}' at position {}", parser.pos));
+        }
+        Ok(Self { root })
+    }
+
+    /// Evaluate the query against a JSON value.
+    #[must_use]
+    pub fn evaluate(&self, value: &Value) -> Value {
+        eval(&self.root, value)
+    }
+}
+
+fn eval(node: &Node, value: &Value) -> Value {
+    match node {
+        Node::Identity => value.clone(),
+        Node::Field(key) => value.get(key.as_str()).cloned().unwrap_or(Value::Null),
+        Node::Index(idx) => value
+            .as_array()
+            .and_then(|arr| {
+                let len = i64::try_from(arr.len()).ok()?;
+                let resolved = if *idx < 0 { len + idx } else { *idx };
+                usize::try_from(resolved).ok().and_then(|i| arr.get(i))
+            })
+            .cloned()
+            .unwrap_or(Value::Null),
+        Node::Subexpr(lhs, rhs) | Node::Pipe(lhs, rhs) => {
+            let left = eval(lhs, value);
+            if left.is_null() {
+                Value::Null
+            } else {
+                eval(rhs, &left)
+            }
+        }
+        Node::ListProject(lhs, rhs) => match eval(lhs, value) {
+            Value::Array(items) => project(items.iter(), rhs),
+            _ => Value::Null,
+        },
+        Node::Flatten(lhs, rhs) => match eval(lhs, value) {
+            Value::Array(items) => {
+                let flat: Vec<Value> = items
+                    .into_iter()
+                    .flat_map(|item| match item {
+                        Value::Array(inner) => inner,
+                        other => vec![other],
+                    })
+                    .collect();
+                project(flat.iter(), rhs)
+            }
+            _ => Value::Null,
+        },
+        Node::ValueProject(lhs, rhs) => match eval(lhs, value) {
+            Value::Object(map) => project(map.values(), rhs),
+            _ => Value::Null,
+        },
+        Node::MultiList(items) => {
+            if value.is_null() {
+                return Value::Null;
+            }
+            Value::Array(items.iter().map(|n| eval(n, value)).collect())
+        }
+        Node::MultiHash(pairs) => {
+            if value.is_null() {
+                return Value::Null;
+            }
+            let mut out = Map::new();
+            for (key, n) in pairs {
+                out.insert(key.clone(), eval(n, value));
+            }
+            Value::Object(out)
+        }
+    }
+}
+
+fn project<'a>(items: impl Iterator<Item = &'a Value>, rhs: &Node) -> Value {
+    Value::Array(
+        items
+            .map(|item| eval(rhs, item))
+            .filter(|v| !v.is_null())
+            .collect(),
+    )
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{c}'")))
+        }
+    }
+
+    fn error(&self, msg: &str) -> String {
+        match self.peek() {
+            Some(c) => format!("{msg}, found '{c}' at position {}", self.pos),
+            None => format!("{msg}, found end of expression"),
+        }
+    }
+
+    fn parse_pipe(&mut self) -> Result<Node, String> {
+        let mut node = self.parse_chain()?;
+        while self.eat('|') {
+            let rhs = self.parse_chain()?;
+            node = Node::Pipe(Box::new(node), Box::new(rhs));
+        }
+        Ok(node)
+    }
+
+    fn parse_chain(&mut self) -> Result<Node, String> {
+        let head = self.parse_head()?;
+        self.parse_continuation(head, false)
+    }
+
+    /// Parse the first element of a chain.
+    fn parse_head(&mut self) -> Result<Node, String> {
+        self.skip_ws();
+        match self.peek() {
+            Some('@') => {
+                self.pos += 1;
+                Ok(Node::Identity)
+            }
+            Some('*') => {
+                self.pos += 1;
+                let rhs = self.parse_continuation(Node::Identity, true)?;
+                Ok(Node::ValueProject(Box::new(Node::Identity), Box::new(rhs)))
+            }
+            Some('{') => self.parse_multi_hash(),
+            Some('[') => self.parse_bracket(Node::Identity),
+            Some('"') => Ok(Node::Field(self.parse_quoted()?)),
+            Some(c) if is_ident_start(c) => Ok(Node::Field(self.parse_ident())),
+            _ => Err(self.error("expected an expression")),
+        }
+    }
+
+    /// Parse `.field`, `[...]`, and `.*` suffixes onto `node`.
+    ///
+    /// A projection (`[*]`, `[]`, `.*`) takes the rest of the chain as its
+    /// right-hand side, so `a[*].b.c` projects `.b.c` over every element. As in
+    /// `JMESPath`, a flatten (`[]`) inside a projection's right-hand side ends
+    /// that projection and applies to its collected result, which is what
+    /// makes `a[].b[]` a single flat list.
+    fn parse_continuation(&mut self, mut node: Node, in_projection: bool) -> Result<Node, String> {
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                Some('.') => {
+                    self.pos += 1;
+                    self.skip_ws();
+                    let rhs = match self.peek() {
+                        Some('*') => {
+                            self.pos += 1;
+                            let rhs = self.parse_continuation(Node::Identity, true)?;
+                            node = Node::ValueProject(Box::new(node), Box::new(rhs));
+                            continue;
+                        }
+                        Some('{') => self.parse_multi_hash()?,
+                        Some('[') => self.parse_multi_list()?,
+                        Some('"') => Node::Field(self.parse_quoted()?),
+                        Some(c) if is_ident_start(c) => Node::Field(self.parse_ident()),
+                        _ => return Err(self.error("expected a field name after '.'")),
+                    };
+                    node = Node::Subexpr(Box::new(node), Box::new(rhs));
+                }
+                Some('[') if in_projection && self.flatten_ahead() => return Ok(node),
+                Some('[') => node = self.parse_bracket(node)?,
+                _ => return Ok(node),
+            }
+        }
+    }
+
+    /// Whether the upcoming tokens are `[` `]` (a flatten).
+    fn flatten_ahead(&self) -> bool {
+        self.chars[self.pos + 1..]
+            .iter()
+            .find(|c| !c.is_whitespace())
+            .is_some_and(|&c| c == ']')
+    }
+
+    /// Parse a bracket suffix (`[n]`, `[*]`, `[]`) or, at the head of a
+    /// chain, a multi-select list.
+    fn parse_bracket(&mut self, lhs: Node) -> Result<Node, String> {
+        let start = self.pos;
+        self.expect('[')?;
+        self.skip_ws();
+        match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                self.expect(']')?;
+                let rhs = self.parse_continuation(Node::Identity, true)?;
+                Ok(Node::ListProject(Box::new(lhs), Box::new(rhs)))
+            }
+            Some(']') => {
+                self.pos += 1;
+                let rhs = self.parse_continuation(Node::Identity, true)?;
+                Ok(Node::Flatten(Box::new(lhs), Box::new(rhs)))
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let idx = self.parse_int()?;
+                self.expect(']')?;
+                Ok(Node::Subexpr(Box::new(lhs), Box::new(Node::Index(idx))))
+            }
+            _ if lhs == Node::Identity => {
+                self.pos = start;
+                self.parse_multi_list()
+            }
+            _ => Err(self.error("expected an index, '*' or ']'")),
+        }
+    }
+
+    fn parse_multi_list(&mut self) -> Result<Node, String> {
+        self.expect('[')?;
+        let mut items = vec![self.parse_pipe()?];
+        while self.eat(',') {
+            items.push(self.parse_pipe()?);
+        }
+        self.expect(']')?;
+        Ok(Node::MultiList(items))
+    }
+
+    fn parse_multi_hash(&mut self) -> Result<Node, String> {
+        self.expect('{')?;
+        let mut pairs = Vec::new();
+        loop {
+            self.skip_ws();
+            let key = match self.peek() {
+                Some('"') => self.parse_quoted()?,
+                Some(c) if is_ident_start(c) => self.parse_ident(),
+                _ => return Err(self.error("expected a key in multi-select hash")),
+            };
+            self.expect(':')?;
+            pairs.push((key, self.parse_pipe()?));
+            if !self.eat(',') {
+                break;
+            }
+        }
+        self.expect('}')?;
+        Ok(Node::MultiHash(pairs))
+    }
+
+    fn parse_ident(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c @ ('"' | '\\' | '/')) => out.push(c),
+                        _ => return Err(self.error("unsupported escape in quoted identifier")),
+                    }
+                    self.pos += 1;
+                }
+                Some(c) => {
+                    out.push(c);
+                    self.pos += 1;
+                }
+                None => return Err(self.error("unterminated quoted identifier")),
+            }
+        }
+    }
+
+    fn parse_int(&mut self) -> Result<i64, String> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse()
+            .map_err(|_| format!("invalid index '{text}' at position {start}"))
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn run(expr: &str, value: &Value) -> Value {
+        Query::parse(expr).unwrap().evaluate(value)
+    }
+
+    #[test]
+    fn field_and_nested_access() {
+        let v = json!({"a": {"b": {"c": 3}}});
+        assert_eq!(run("a.b.c", &v), json!(3));
+        assert_eq!(run("a.missing.c", &v), Value::Null);
+    }
+
+    #[test]
+    fn quoted_identifier() {
+        let v = json!({"odd key": 1});
+        assert_eq!(run("\"odd key\"", &v), json!(1));
+    }
+
+    #[test]
+    fn index_positive_and_negative() {
+        let v = json!({"items": [1, 2, 3]});
+        assert_eq!(run("items[0]", &v), json!(1));
+        assert_eq!(run("items[-1]", &v), json!(3));
+        assert_eq!(run("items[9]", &v), Value::Null);
+    }
+
+    #[test]
+    fn list_projection_drops_nulls() {
+        let v = json!({"items": [{"n": "a"}, {"x": 1}, {"n": "b"}]});
+        assert_eq!(run("items[*].n", &v), json!(["a", "b"]));
+    }
+
+    #[test]
+    fn flatten_projection() {
+        let v = json!({"pages": [{"rows": [1, 2]}, {"rows": [3]}]});
+        assert_eq!(run("pages[].rows[]", &v), json!([1, 2, 3]));
+    }
+
+    #[test]
+    fn value_projection() {
+        let v = json!({"users": {"a": {"age": 1}, "b": {"age": 2}}});
+        let out = run("users.*.age", &v);
+        let mut ages: Vec<i64> = out
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_i64().unwrap())
+            .collect();
+        ages.sort_unstable();
+        assert_eq!(ages, vec![1, 2]);
+    }
+
+    #[test]
+    fn multiselect_hash_reshapes_each_element() {
+        let v = json!({"results": [
+            {"id": 1, "meta": {"title": "one"}, "noise": true},
+            {"id": 2, "meta": {"title": "two"}, "noise": false}
+        ]});
+        assert_eq!(
+            run("results[*].{id: id, title: meta.title}", &v),
+            json!([{"id": 1, "title": "one"}, {"id": 2, "title": "two"}])
+        );
+    }
+
+    #[test]
+    fn multiselect_list() {
+        let v = json!({"a": 1, "b": 2});
+        assert_eq!(run("[a, b]", &v), json!([1, 2]));
+    }
+
+    #[test]
+    fn pipe_stops_projection() {
+        let v = json!({"items": [{"n": "a"}, {"n": "b"}]});
+        assert_eq!(run("items[*].n | [0]", &v), json!("a"));
+    }
+
+    #[test]
+    fn identity_returns_input() {
+        let v = json!({"a": 1});
+        assert_eq!(run("@", &v), v);
+    }
+
+    #[test]
+    fn rejects_unsupported_syntax() {
+        assert!(Query::parse("items[?x > 1]").is_err());
+        assert!(Query::parse("length(items)").is_err());
+        assert!(Query::parse("a.").is_err());
+        assert!(Query::parse("{a b}").is_err());
+    }
+}
@@ -13,6 +13,8 @@
 //! - `predict_next(tool, min_confidence, min_count)` returns candidates whose
 //!   observed frequency clears both thresholds, sorted by descending confidence.
 //! - Save/load follows the same pattern as [`crate::ranking::SearchRanker`].
+//! - `top_transitions` / `top_sequences` summarise the learned data for the
+//!   `mcp-gateway transitions report` command.
 //!
 //! # Thresholds
 //!
@@ -20,6 +22,7 @@
 //! - `count ≥ min_count` — prevents noise from single observations
 //! - `confidence ≥ min_confidence` — expressed as a fraction (0.0–1.0)
 
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -40,6 +43,30 @@ pub struct Prediction {
     pub confidence: f64,
 }
 
+/// An observed `from → to` transition with its count.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransitionCount {
+    /// Tool invoked first
+    pub from: String,
+    /// Tool invoked next
+    pub to: String,
+    /// Number of observations
+    pub count: u64,
+    /// Share of all transitions out of `from`, in `[0.0, 1.0]`
+    pub confidence: f64,
+}
+
+/// A multi-step tool sequence built by chaining the most common successors.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolSequence {
+    /// Tools in invocation order (at least two)
+    pub tools: Vec<String>,
+    /// Count of the rarest step — an upper bound on full-sequence runs
+    pub count: u64,
+    /// Product of the step confidences
+    pub confidence: f64,
+}
+
 // ============================================================================
 // TransitionTracker
 // ============================================================================
@@ -174,6 +201,105 @@ impl TransitionTracker {
             .sum()
     }
 
+    /// The `limit` most frequent transitions, by descending count.
+    #[must_use]
+    pub fn top_transitions(&self, limit: usize) -> Vec<TransitionCount> {
+        let mut all = self.all_transitions();
+        all.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.from.cmp(&b.from))
+                .then_with(|| a.to.cmp(&b.to))
+        });
+        all.truncate(limit);
+        all
+    }
+
+    /// The `limit` most frequent sequences of up to `max_len` tools.
+    ///
+    /// Each transition seen at least `min_count` times starts a sequence that
+    /// is extended with the most common successor of its last tool while that
+    /// step also clears `min_count` and does not revisit a tool. Sequences
+    /// contained in a longer reported sequence with the same count are dropped.
+    #[must_use]
+    pub fn top_sequences(&self, max_len: usize, min_count: u64, limit: usize) -> Vec<ToolSequence> {
+        let transitions = self.all_transitions();
+        let mut best_next: HashMap<&str, &TransitionCount> = HashMap::new();
+        for t in &transitions {
+            let slot = best_next.entry(t.from.as_str()).or_insert(t);
+            if (t.count, std::cmp::Reverse(&t.to)) > (slot.count, std::cmp::Reverse(&slot.to)) {
+                *slot = t;
+            }
+        }
+
+        let mut sequences: Vec<ToolSequence> = transitions
+            .iter()
+            .filter(|t| t.count >= min_count)
+            .map(|start| {
+                let mut seq = ToolSequence {
+                    tools: vec![start.from.clone(), start.to.clone()],
+                    count: start.count,
+                    confidence: start.confidence,
+                };
+                while seq.tools.len() < max_len {
+                    let last = seq.tools.last().map_or("", String::as_str);
+                    let Some(next) = best_next.get(last) else {
+                        break;
+                    };
+                    if next.count < min_count || seq.tools.contains(&next.to) {
+                        break;
+                    }
+                    seq.tools.push(next.to.clone());
+                    seq.count = seq.count.min(next.count);
+                    seq.confidence *= next.confidence;
+                }
+                seq
+            })
+            .collect();
+
+        sequences.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| b.tools.len().cmp(&a.tools.len()))
+                .then_with(|| a.tools.cmp(&b.tools))
+        });
+        let mut kept: Vec<ToolSequence> = Vec::new();
+        for seq in sequences {
+            let redundant = kept.iter().any(|k| {
+                k.count == seq.count && k.tools.windows(seq.tools.len()).any(|w| w == seq.tools)
+            });
+            if !redundant {
+                kept.push(seq);
+            }
+            if kept.len() == limit {
+                break;
+            }
+        }
+        kept
+    }
+
+    /// Snapshot of every transition with its confidence.
+    fn all_transitions(&self) -> Vec<TransitionCount> {
+        let mut all = Vec::new();
+        for outer in &self.transitions {
+            let counts: Vec<(String, u64)> = outer
+                .value()
+                .iter()
+                .map(|inner| (inner.key().clone(), inner.value().load(Ordering::Relaxed)))
+                .filter(|(_, count)| *count > 0)
+                .collect();
+            let total: u64 = counts.iter().map(|(_, count)| count).sum();
+            #[allow(clippy::cast_precision_loss)]
+            all.extend(counts.into_iter().map(|(to, count)| TransitionCount {
+                from: outer.key().clone(),
+                to,
+                count,
+                confidence: count as f64 / total as f64,
+            }));
+        }
+        all
+    }
+
     /// Save transition data to a JSON file.
     ///
     /// # Errors
@@ -199,7 +325,10 @@ impl TransitionTracker {
 
         let json = serde_json::to_string_pretty(&entries)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        std::fs::write(path, json)
+        // Write-then-rename so a crash mid-save never truncates the history.
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
     }
 
     /// Load transition data from a JSON file.
@@ -606,4 +735,79 @@ mod tests {
 
         assert_eq!(tracker.total_transitions(), 9);
     }
+
+    // ── top_transitions / top_sequences ─────────────────────────────────
+
+    fn record_session(tracker: &TransitionTracker, session: &str, tools: &[&str]) {
+        for tool in tools {
+            tracker.record_transition(session, tool);
+        }
+    }
+
+    #[test]
+    fn top_transitions_orders_by_count_and_truncates() {
+        // GIVEN: a→b observed three times, a→c once
+        let tracker = TransitionTracker::new();
+        for i in 0..3 {
+            record_session(&tracker, &format!("s{i}"), &["t:a", "t:b"]);
+        }
+        record_session(&tracker, "s9", &["t:a", "t:c"]);
+
+        // WHEN: asking for the single most common transition
+        let top = tracker.top_transitions(1);
+
+        // THEN: a→b wins with its share of transitions out of a
+        assert_eq!(top.len(), 1);
+        assert_eq!((top[0].from.as_str(), top[0].to.as_str()), ("t:a", "t:b"));
+        assert_eq!(top[0].count, 3);
+        assert!((top[0].confidence - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn top_sequences_chains_most_common_successors() {
+        // GIVEN: the workflow search → fetch → summarize repeated in 4 sessions
+        let tracker = TransitionTracker::new();
+        for i in 0..4 {
+            record_session(
+                &tracker,
+                &format!("s{i}"),
+                &["t:search", "t:fetch", "t:summarize"],
+            );
+        }
+
+        // WHEN: building sequences of up to 4 tools
+        let sequences = tracker.top_sequences(4, 3, 10);
+
+        // THEN: the full chain is reported once; its fetch → summarize suffix
+        // is dropped as redundant
+        assert_eq!(sequences.len(), 1);
+        assert_eq!(sequences[0].tools, ["t:search", "t:fetch", "t:summarize"]);
+        assert_eq!(sequences[0].count, 4);
+        assert!((sequences[0].confidence - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn top_sequences_respects_min_count_and_stops_at_cycles() {
+        // GIVEN: a↔b ping-pong (three times each way) plus a rare b→c step
+        let tracker = TransitionTracker::new();
+        record_session(
+            &tracker,
+            "s",
+            &["t:a", "t:b", "t:a", "t:b", "t:a", "t:b", "t:a"],
+        );
+        record_session(&tracker, "rare", &["t:b", "t:c"]);
+
+        // WHEN: building sequences that need three observations per step
+        let sequences = tracker.top_sequences(5, 3, 10);
+
+        // THEN: both directions qualify, neither loops back on itself, and
+        // the rare step is left out
+        assert_eq!(sequences.len(), 2);
+        assert!(sequences.iter().all(|s| s.tools.len() == 2));
+        assert!(
+            sequences
+                .iter()
+                .all(|s| !s.tools.contains(&"t:c".to_string()))
+        );
+    }
 }
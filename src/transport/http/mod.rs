@@ -28,7 +28,8 @@ use crate::gateway::trace;
 use crate::oauth::OAuthClient;
 use crate::protocol::{
     JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, PROTOCOL_VERSION, RequestId,
-    is_version_mismatch_error, negotiate_best_version, parse_supported_versions_from_error,
+    SUPPORTED_VERSIONS, is_version_mismatch_error, negotiate_best_version,
+    parse_supported_versions_from_error,
 };
 use crate::security::validate_url_not_ssrf;
 use crate::{Error, Result};
@@ -132,6 +133,23 @@ fn is_session_expired_response(resp: &JsonRpcResponse) -> bool {
     })
 }
 
+/// Check the `protocolVersion` a server answered `initialize` with.
+///
+/// Only a version the gateway speaks may be adopted: it is sent back in the
+/// `MCP-Protocol-Version` header of every later request, and an unknown
+/// version means the server picked a protocol the gateway cannot follow.
+fn supported_server_version(server_version: &str) -> Result<&'static str> {
+    SUPPORTED_VERSIONS
+        .iter()
+        .copied()
+        .find(|&supported| supported == server_version)
+        .ok_or_else(|| {
+            Error::Protocol(format!(
+                "Server answered initialize with unsupported protocol version {server_version:?}"
+            ))
+        })
+}
+
 /// HTTP transport for MCP servers using SSE or Streamable HTTP protocol
 pub struct HttpTransport {
     /// HTTP client
@@ -306,6 +324,16 @@ impl HttpTransport {
         self.single_tenant_hint.store(true, Ordering::Relaxed);
     }
 
+    /// Protocol version in use: the configured or negotiated one, otherwise
+    /// the gateway's latest.
+    #[must_use]
+    pub fn protocol_version(&self) -> String {
+        self.protocol_version
+            .read()
+            .clone()
+            .unwrap_or_else(|| PROTOCOL_VERSION.to_string())
+    }
+
     /// Initialize the connection
     ///
     /// For SSE mode: establishes SSE handshake to get message endpoint
@@ -334,8 +362,13 @@ impl HttpTransport {
                 let mut oauth = oauth_arc_for_task.lock().await;
                 oauth.initialize().await?;
 
-                // If we don't have a valid token, trigger authorization flow
-                if !oauth.has_valid_token() {
+                // If we don't have a valid token, renew it headlessly when the
+                // stored token allows it, and only then fall back to the
+                // interactive authorization flow.
+                if !oauth.has_valid_token()
+                    && let Err(e) = oauth.attempt_background_renewal().await
+                {
+                    debug!(url = %base_url_for_task, error = %e, "Headless token renewal failed");
                     info!(url = %base_url_for_task, "OAuth required - initiating authorization flow");
                     oauth.authorize().await?;
                 }
@@ -454,6 +487,22 @@ impl HttpTransport {
             } else {
                 return Err(Error::Protocol(format!("Initialize failed: {error:?}")));
             }
+        } else if let Some(server_version) = response
+            .result
+            .as_ref()
+            .and_then(|result| result.get("protocolVersion"))
+            .and_then(serde_json::Value::as_str)
+            && server_version != version
+        {
+            // Success — adopt the version the server answered with
+            let server_version = supported_server_version(server_version)?;
+            info!(
+                url = %self.base_url,
+                requested = %version,
+                negotiated = %server_version,
+                "Server negotiated different protocol version"
+            );
+            *self.protocol_version.write() = Some(server_version.to_string());
         }
 
         // Some Streamable HTTP backends either close the initialize request
@@ -558,12 +607,15 @@ impl HttpTransport {
             }
         }
 
-        // Ambient trace ID (send_request only; not SSE or notify).
-        if matches!(mode, HeaderMode::Request { .. })
-            && let Some(trace_id) = trace::current()
-            && let Ok(v) = trace_id.parse::<reqwest::header::HeaderValue>()
-        {
-            headers.insert("x-trace-id", v);
+        // Ambient trace ID and the caller's W3C trace context (send_request
+        // only; not SSE or notify).
+        if matches!(mode, HeaderMode::Request { .. }) {
+            if let Some(trace_id) = trace::current()
+                && let Ok(v) = trace_id.parse::<reqwest::header::HeaderValue>()
+            {
+                headers.insert("x-trace-id", v);
+            }
+            trace::inject_context(&mut headers);
         }
 
         Ok(headers)
@@ -1019,6 +1071,10 @@ impl Transport for HttpTransport {
         Ok(())
     }
 
+    fn protocol_version(&self) -> Option<String> {
+        Some(HttpTransport::protocol_version(self))
+    }
+
     fn is_connected(&self) -> bool {
         self.connected.load(Ordering::Relaxed)
     }
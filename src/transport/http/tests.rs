@@ -72,6 +72,23 @@ fn new_with_oauth_and_protocol_version() {
     assert_eq!(*t.protocol_version.read(), Some("2024-11-05".to_string()));
 }
 
+#[test]
+fn supported_server_version_accepts_only_known_versions() {
+    // GIVEN/WHEN: a server answers initialize with a version we speak
+    // THEN: it is adopted
+    assert_eq!(
+        supported_server_version("2025-06-18").unwrap(),
+        "2025-06-18"
+    );
+
+    // GIVEN/WHEN: an unknown version, or one that is not a header value
+    // THEN: the handshake fails with a protocol error
+    for answer in ["2099-01-01", "", "2025-06-18\r\nX-Injected: 1"] {
+        let err = supported_server_version(answer).unwrap_err();
+        assert!(matches!(err, Error::Protocol(_)), "{answer:?}: {err:?}");
+    }
+}
+
 // =========================================================================
 // parse_supported_versions
 // =========================================================================
@@ -447,6 +464,49 @@ async fn build_headers_send_request_with_session_and_trace() {
     );
 }
 
+/// `send_request` mode inside a caller's W3C trace: `traceparent` continues
+/// the caller's trace and `tracestate` is forwarded next to `x-trace-id`.
+#[tokio::test]
+async fn build_headers_send_request_propagates_w3c_trace_context() {
+    use crate::gateway::trace;
+
+    let t = make_transport("http://localhost");
+    let mut inbound = axum::http::HeaderMap::new();
+    inbound.insert(
+        "traceparent",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+            .parse()
+            .unwrap(),
+    );
+    inbound.insert("tracestate", "vendor=abc".parse().unwrap());
+    let context = trace::TraceContext::from_headers(&inbound).unwrap();
+
+    let map = trace::with_context(
+        context,
+        trace::with_trace_id("gw-trace-w3c".to_string(), async {
+            t.build_mcp_headers(
+                HeaderMode::Request {
+                    method: "tools/call",
+                },
+                None,
+            )
+            .await
+            .unwrap()
+        }),
+    )
+    .await;
+
+    assert!(
+        map["traceparent"]
+            .to_str()
+            .unwrap()
+            .starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"),
+        "traceparent must keep the caller's trace ID"
+    );
+    assert_eq!(map["tracestate"], "vendor=abc");
+    assert_eq!(map["x-trace-id"], "gw-trace-w3c");
+}
+
 /// `send_request` mode without a session: no mcp-session-id header at all.
 #[tokio::test]
 async fn build_headers_send_request_no_session() {
@@ -88,6 +88,13 @@ pub trait Transport: Send + Sync {
         self.notify(method, params).await
     }
 
+    /// Protocol version spoken with the backend, when the transport tracks
+    /// one (see [`crate::protocol::compat`]). Defaults to `None`: traffic is
+    /// passed through unadapted.
+    fn protocol_version(&self) -> Option<String> {
+        None
+    }
+
     /// Check if transport is connected
     fn is_connected(&self) -> bool;
 
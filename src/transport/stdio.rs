@@ -125,6 +125,16 @@ impl StdioTransport {
         })
     }
 
+    /// Protocol version in use: the configured or negotiated one, otherwise
+    /// the gateway's latest.
+    #[must_use]
+    pub fn protocol_version(&self) -> String {
+        self.protocol_version
+            .read()
+            .clone()
+            .unwrap_or_else(|| PROTOCOL_VERSION.to_string())
+    }
+
     /// Start the subprocess
     ///
     /// # Errors
@@ -476,6 +486,10 @@ impl Transport for StdioTransport {
         self.write_message(&message).await
     }
 
+    fn protocol_version(&self) -> Option<String> {
+        Some(StdioTransport::protocol_version(self))
+    }
+
     fn is_connected(&self) -> bool {
         if !self.connected.load(Ordering::Relaxed) {
             return false;
@@ -0,0 +1,190 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: MIT
+
+//! Validation baselines for CI adoption
+//!
+//! A baseline records the violations a tool surface already has, keyed by
+//! source (file path or `server:<name>`), tool and rule code. Runs with
+//! `--baseline baseline.json` suppress those known violations so CI fails
+//! only on new issues; `--update-baseline` rewrites the file from the current
+//! run. Issue text is deliberately not part of the key, so rewording a
+//! message or a changed count does not resurface a known violation.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::ValidationResult;
+use crate::{Error, Result};
+
+/// Source label used for cross-capability results.
+pub const CROSS_CAPABILITY_SOURCE: &str = "cross-capability";
+
+const BASELINE_VERSION: u32 = 1;
+
+/// One known violation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// Capability file path or `server:<name>`
+    pub source: String,
+    /// Tool the violation was reported for
+    pub tool: String,
+    /// Rule code (e.g. "AX-003")
+    pub rule: String,
+}
+
+impl BaselineEntry {
+    fn of(source: &str, result: &ValidationResult) -> Self {
+        Self {
+            source: source.to_string(),
+            tool: result.tool_name.clone(),
+            rule: result.rule_code.clone(),
+        }
+    }
+}
+
+/// The set of known violations, stored as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// File format version
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// Known violations, sorted for stable diffs
+    #[serde(default)]
+    pub violations: BTreeSet<BaselineEntry>,
+}
+
+const fn default_version() -> u32 {
+    BASELINE_VERSION
+}
+
+impl Baseline {
+    /// Record every failed result of the given sources.
+    #[must_use]
+    pub fn record<'a>(
+        sources: impl IntoIterator<Item = (&'a str, &'a [ValidationResult])>,
+    ) -> Self {
+        let violations = sources
+            .into_iter()
+            .flat_map(|(source, results)| {
+                results
+                    .iter()
+                    .filter(|r| !r.passed)
+                    .map(move |r| BaselineEntry::of(source, r))
+            })
+            .collect();
+        Self {
+            version: BASELINE_VERSION,
+            violations,
+        }
+    }
+
+    /// Load a baseline file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the file cannot be read or parsed, or has
+    /// an unsupported version.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!("failed to read baseline {}: {e}", path.display()))
+        })?;
+        let baseline: Self = serde_json::from_str(&content)
+            .map_err(|e| Error::Config(format!("baseline {}: {e}", path.display())))?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(Error::Config(format!(
+                "baseline {} has unsupported version {}",
+                path.display(),
+                baseline.version
+            )));
+        }
+        Ok(baseline)
+    }
+
+    /// Write the baseline as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::Config(format!("failed to encode baseline: {e}")))?;
+        std::fs::write(path, json + "\n")
+            .map_err(|e| Error::Config(format!("failed to write baseline {}: {e}", path.display())))
+    }
+
+    /// Whether `result` from `source` is a known violation.
+    #[must_use]
+    pub fn contains(&self, source: &str, result: &ValidationResult) -> bool {
+        !result.passed && self.violations.contains(&BaselineEntry::of(source, result))
+    }
+
+    /// Drop the known violations from `results`, returning how many were
+    /// suppressed.
+    pub fn suppress(&self, source: &str, results: &mut Vec<ValidationResult>) -> usize {
+        let before = results.len();
+        results.retain(|r| !self.contains(source, r));
+        before - results.len()
+    }
+
+    /// Baseline entries that no longer occur in `current`.
+    #[must_use]
+    pub fn stale(&self, current: &Self) -> usize {
+        self.violations.difference(&current.violations).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::Severity;
+
+    fn failed(rule: &str, tool: &str) -> ValidationResult {
+        let mut result = ValidationResult::new(rule, "rule", tool);
+        result.add_issue("issue");
+        result.with_severity(Severity::Fail)
+    }
+
+    #[test]
+    fn known_violations_are_suppressed_and_new_ones_kept() {
+        // GIVEN: a baseline recorded from yesterday's run
+        let old = vec![failed("AX-003", "get_user")];
+        let baseline = Baseline::record([("caps/user.yaml", old.as_slice())]);
+
+        // WHEN: today's run has the known issue, a new one and a pass
+        let mut today = vec![
+            failed("AX-003", "get_user"),
+            failed("AX-001", "get_user"),
+            ValidationResult::new("AX-002", "rule", "get_user"),
+        ];
+        let suppressed = baseline.suppress("caps/user.yaml", &mut today);
+
+        // THEN: only the known violation is dropped
+        assert_eq!(suppressed, 1);
+        let codes: Vec<_> = today.iter().map(|r| r.rule_code.as_str()).collect();
+        assert_eq!(codes, ["AX-001", "AX-002"]);
+    }
+
+    #[test]
+    fn baselines_are_scoped_to_their_source() {
+        let known = vec![failed("AX-003", "search")];
+        let baseline = Baseline::record([("server:a", known.as_slice())]);
+        assert!(!baseline.contains("server:b", &failed("AX-003", "search")));
+    }
+
+    #[test]
+    fn baseline_round_trips_and_counts_stale_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        let results = vec![failed("AX-003", "a"), failed("AX-005", "b")];
+        let baseline = Baseline::record([("f.yaml", results.as_slice())]);
+
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+
+        assert_eq!(loaded, baseline);
+        let fixed = Baseline::record([("f.yaml", &results[..1])]);
+        assert_eq!(loaded.stale(&fixed), 1);
+    }
+}
@@ -3,14 +3,19 @@
 
 //! CLI handler for the validate command.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
+use super::baseline::{self, Baseline};
+use super::conformance;
+use super::live::{self, LiveServer};
 use super::{
-    AgentUxValidator, ConflictDetectionRule, NamingConsistencyRule, OutputFormat, Severity,
-    ValidateConfig,
+    AgentUxValidator, ConflictDetectionRule, CustomRule, NamingConsistencyRule, OutputFormat,
+    Severity, ValidateConfig, ValidationRules,
 };
 use crate::capability::parse_capability_file;
+use crate::config::Config;
+use crate::provider::transforms::ToolOverrides;
 
 /// Collect YAML capability files from paths.
 fn collect_capability_files(paths: &[PathBuf]) -> Vec<PathBuf> {
@@ -123,22 +128,106 @@ fn format_validate_text(
     output
 }
 
-/// Run the validate command against one or more capability paths.
+/// Resolve `--server` specs (and `--all-backends`) against the gateway config.
+fn resolve_live_servers(
+    servers: &[String],
+    all_backends: bool,
+    gateway_config: Option<&Path>,
+) -> crate::Result<(Config, Vec<LiveServer>)> {
+    let gateway = Config::load(gateway_config)?;
+    let mut live = if all_backends {
+        LiveServer::all_backends(&gateway)
+    } else {
+        Vec::new()
+    };
+    for spec in servers {
+        if !live.iter().any(|server| &server.name == spec) {
+            live.push(LiveServer::resolve(spec, &gateway));
+        }
+    }
+    Ok((gateway, live))
+}
+
+/// Run the validate command against capability paths and live MCP servers.
+///
+/// Each server's `tools/list` is validated as one report labelled
+/// `server:<name>`; cross-capability checks cover files and servers together.
 #[allow(clippy::too_many_lines)]
-pub async fn run_validate_command(paths: &[PathBuf], config: &ValidateConfig) -> ExitCode {
+pub async fn run_validate_command(
+    paths: &[PathBuf],
+    servers: &[String],
+    all_backends: bool,
+    gateway_config: Option<&Path>,
+    config: &ValidateConfig,
+) -> ExitCode {
     let files = collect_capability_files(paths);
 
-    if files.is_empty() {
-        eprintln!("No YAML capability files found in the given paths.");
+    let (gateway, live_servers) = if servers.is_empty() && !all_backends {
+        (Config::default(), Vec::new())
+    } else {
+        match resolve_live_servers(servers, all_backends, gateway_config) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                eprintln!("Failed to load gateway config: {e}");
+                return ExitCode::from(2);
+            }
+        }
+    };
+
+    if files.is_empty() && live_servers.is_empty() {
+        eprintln!("No YAML capability files or MCP servers found to validate.");
+        return ExitCode::from(2);
+    }
+
+    if config.conformance.is_some() && live_servers.is_empty() {
+        eprintln!("Conformance mode needs --server or --all-backends.");
         return ExitCode::from(2);
     }
+    let samples = match config
+        .conformance
+        .as_ref()
+        .and_then(|c| c.samples_file.as_deref())
+    {
+        None => std::collections::HashMap::new(),
+        Some(path) => match conformance::load_samples(path) {
+            Ok(samples) => samples,
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::from(2);
+            }
+        },
+    };
 
-    let validator = AgentUxValidator::new();
+    let validator = match &config.rules_file {
+        None => AgentUxValidator::new(),
+        Some(path) => match CustomRule::load(path) {
+            Ok(custom) => AgentUxValidator::with_rules(ValidationRules::new().with_custom(custom)),
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::from(2);
+            }
+        },
+    };
     let mut all_tools = Vec::new();
     let mut file_reports: Vec<(String, super::ValidationReport)> = Vec::new();
-    let mut has_failures = false;
     let mut parse_errors = false;
 
+    let overrides_path = config
+        .overrides_file
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(super::fix::DEFAULT_OVERRIDES_FILE));
+    let mut overrides = if config.auto_fix && !live_servers.is_empty() && overrides_path.exists() {
+        match ToolOverrides::load(&overrides_path) {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::from(2);
+            }
+        }
+    } else {
+        ToolOverrides::default()
+    };
+
     // Phase 1: Parse and validate each file
     for file in &files {
         let cap = match parse_capability_file(file).await {
@@ -159,10 +248,6 @@ pub async fn run_validate_command(paths: &[PathBuf], config: &ValidateConfig) ->
             }
         };
 
-        if !report.failures().is_empty() {
-            has_failures = true;
-        }
-
         // Auto-fix if requested
         if config.auto_fix {
             let suggested = super::fix::CapabilityFixer::suggest_fixes(&report.results);
@@ -184,21 +269,139 @@ pub async fn run_validate_command(paths: &[PathBuf], config: &ValidateConfig) ->
         file_reports.push((file.display().to_string(), report));
     }
 
+    // Phase 1b: Pull tools/list from live servers and validate the real definitions
+    let fetched = live::fetch_all(&live_servers, &gateway).await;
+    for (server, tools) in live_servers.iter().zip(fetched) {
+        let tools = match tools {
+            Ok(tools) => tools,
+            Err(e) => {
+                eprintln!("Failed to list tools of server {}: {e}", server.name);
+                parse_errors = true;
+                continue;
+            }
+        };
+
+        let mut report = match validator.validate_tools(&tools) {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("Validation error for server {}: {e}", server.name);
+                continue;
+            }
+        };
+
+        if let Some(conformance_config) = &config.conformance {
+            let backend = server.connect(&gateway);
+            let checked =
+                conformance::check_tools(&backend, &tools, conformance_config, &samples).await;
+            let _ = backend.stop().await;
+            let mut results = report.results;
+            results.extend(checked);
+            report = super::ValidationReport::from_results(tools.len(), results);
+        }
+
+        // Auto-fix: record gateway-side remediations for the server's tools
+        if config.auto_fix {
+            let entries =
+                super::fix::OverrideFixer::suggest_overrides(&server.name, &tools, &report.results);
+            if !entries.is_empty() {
+                eprintln!(
+                    "Suggested {} override(s) for server {}",
+                    entries.len(),
+                    server.name
+                );
+            }
+            overrides.replace_server(&server.name, entries);
+        }
+
+        all_tools.extend(tools);
+        file_reports.push((format!("server:{}", server.name), report));
+    }
+
+    if config.auto_fix && !live_servers.is_empty() {
+        match overrides.save(&overrides_path) {
+            Ok(()) => eprintln!(
+                "Wrote {} tool override(s) to {}",
+                overrides.len(),
+                overrides_path.display()
+            ),
+            Err(e) => eprintln!(
+                "Failed to write overrides {}: {e}",
+                overrides_path.display()
+            ),
+        }
+    }
+
     // Phase 2: Cross-capability checks
-    let conflict_results = ConflictDetectionRule::check_conflicts(&all_tools);
-    let consistency_results = NamingConsistencyRule::check_consistency(&all_tools);
+    let mut conflict_results = ConflictDetectionRule::check_conflicts(&all_tools);
+    let mut consistency_results = NamingConsistencyRule::check_consistency(&all_tools);
+
+    // Phase 2b: Suppress violations recorded in the baseline
+    if let Some(path) = &config.baseline {
+        let current = Baseline::record(
+            file_reports
+                .iter()
+                .map(|(source, report)| (source.as_str(), report.results.as_slice()))
+                .chain([
+                    (
+                        baseline::CROSS_CAPABILITY_SOURCE,
+                        conflict_results.as_slice(),
+                    ),
+                    (
+                        baseline::CROSS_CAPABILITY_SOURCE,
+                        consistency_results.as_slice(),
+                    ),
+                ]),
+        );
+        let known = if config.update_baseline {
+            if let Err(e) = current.save(path) {
+                eprintln!("{e}");
+                return ExitCode::from(2);
+            }
+            eprintln!(
+                "Baseline updated: {} violation(s) recorded in {}",
+                current.violations.len(),
+                path.display()
+            );
+            current
+        } else {
+            match Baseline::load(path) {
+                Ok(known) => {
+                    let stale = known.stale(&current);
+                    if stale > 0 {
+                        eprintln!(
+                            "{stale} baseline violation(s) no longer occur; run with --update-baseline to drop them"
+                        );
+                    }
+                    known
+                }
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::from(2);
+                }
+            }
+        };
 
-    let has_cross_failures = conflict_results
+        let mut suppressed = 0;
+        for (source, report) in &mut file_reports {
+            let mut results = std::mem::take(&mut report.results);
+            suppressed += known.suppress(source, &mut results);
+            *report = super::ValidationReport::from_results(report.total_tools, results);
+        }
+        suppressed += known.suppress(baseline::CROSS_CAPABILITY_SOURCE, &mut conflict_results);
+        suppressed += known.suppress(baseline::CROSS_CAPABILITY_SOURCE, &mut consistency_results);
+        if suppressed > 0 {
+            eprintln!("{suppressed} known violation(s) suppressed by baseline");
+        }
+    }
+
+    let has_failures = file_reports
         .iter()
-        .any(|r| !r.passed && r.severity == Severity::Fail)
-        || consistency_results
+        .any(|(_, report)| !report.failures().is_empty())
+        || conflict_results
             .iter()
+            .chain(&consistency_results)
             .any(|r| !r.passed && r.severity == Severity::Fail);
 
-    if has_cross_failures {
-        has_failures = true;
-    }
-
     // Phase 3: Output
     match config.format {
         OutputFormat::Text => {
@@ -289,6 +492,23 @@ pub async fn run_validate_command(paths: &[PathBuf], config: &ValidateConfig) ->
                 serde_json::to_string_pretty(&sarif).unwrap_or_default()
             );
         }
+
+        OutputFormat::Junit => {
+            let mut sources: Vec<(&str, &[super::ValidationResult])> = file_reports
+                .iter()
+                .map(|(p, r)| (p.as_str(), r.results.as_slice()))
+                .collect();
+            let cross: Vec<super::ValidationResult> = conflict_results
+                .iter()
+                .chain(&consistency_results)
+                .cloned()
+                .collect();
+            if !cross.is_empty() {
+                sources.push((baseline::CROSS_CAPABILITY_SOURCE, cross.as_slice()));
+            }
+
+            print!("{}", super::junit::to_junit_multi(&sources));
+        }
     }
 
     if parse_errors {
@@ -0,0 +1,373 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: MIT
+
+//! AX-011: Response conformance testing
+//!
+//! Declared `outputSchema`s drift from what servers actually return. In
+//! conformance mode (`mcp-gateway validate --server <s> --conformance`) the
+//! validator calls selected tools with sample arguments and checks each real
+//! response against the tool's declared `outputSchema`, reporting mismatches
+//! per tool.
+//!
+//! Calling a tool can have side effects, so without an explicit `--invoke`
+//! list only tools annotated `readOnlyHint: true` that declare an
+//! `outputSchema` are called. Arguments come from a samples file
+//! (`tool_name: {args}`) when given, otherwise they are generated from the
+//! input schema (`default`, `examples`, `const`, `enum`, then a placeholder
+//! per type).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value, json};
+
+use super::{Severity, ValidationResult};
+use crate::backend::Backend;
+use crate::capability::validate_output;
+use crate::protocol::{JsonRpcResponse, Tool};
+use crate::{Error, Result};
+
+const CODE: &str = "AX-011";
+const NAME: &str = "Response Conformance";
+
+/// Which tools to call and with what arguments.
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceConfig {
+    /// Tools to call; empty means read-only tools that declare an `outputSchema`
+    pub tools: Vec<String>,
+    /// YAML/JSON file mapping tool names to sample arguments
+    pub samples_file: Option<PathBuf>,
+}
+
+/// Load sample arguments (`tool_name: {args}`) from a YAML or JSON file.
+///
+/// # Errors
+///
+/// Returns `Error::Config` if the file cannot be read or is not a mapping of
+/// tool names to argument objects.
+pub fn load_samples(path: &Path) -> Result<HashMap<String, Value>> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        Error::Config(format!(
+            "failed to read samples file {}: {e}",
+            path.display()
+        ))
+    })?;
+    serde_yaml::from_str(&content)
+        .map_err(|e| Error::Config(format!("samples file {}: {e}", path.display())))
+}
+
+/// Whether conformance mode calls `tool`.
+fn is_selected(tool: &Tool, config: &ConformanceConfig) -> bool {
+    if config.tools.is_empty() {
+        tool.output_schema.is_some()
+            && tool
+                .annotations
+                .as_ref()
+                .and_then(|a| a.read_only_hint)
+                .unwrap_or(false)
+    } else {
+        config.tools.iter().any(|name| name == &tool.name)
+    }
+}
+
+/// Call the selected tools on `backend` and check their responses.
+///
+/// Tools named in `config.tools` that the server does not list are reported
+/// as failures.
+pub async fn check_tools<S: std::hash::BuildHasher>(
+    backend: &Backend,
+    tools: &[Tool],
+    config: &ConformanceConfig,
+    samples: &HashMap<String, Value, S>,
+) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for tool in tools.iter().filter(|tool| is_selected(tool, config)) {
+        let arguments = samples
+            .get(&tool.name)
+            .cloned()
+            .unwrap_or_else(|| sample_arguments(&tool.input_schema));
+        let response = backend
+            .request(
+                "tools/call",
+                Some(json!({"name": tool.name, "arguments": arguments})),
+            )
+            .await;
+        results.push(check_response(tool, response));
+    }
+
+    for name in &config.tools {
+        if !tools.iter().any(|tool| &tool.name == name) {
+            let mut result = ValidationResult::new(CODE, NAME, name);
+            result.add_issue("Server does not list this tool");
+            results.push(result.with_score(0.0).with_severity(Severity::Fail));
+        }
+    }
+
+    results
+}
+
+/// Check one `tools/call` response against the tool's `outputSchema`.
+#[must_use]
+pub fn check_response(tool: &Tool, response: Result<JsonRpcResponse>) -> ValidationResult {
+    let mut result = ValidationResult::new(CODE, NAME, &tool.name);
+
+    let Some(schema) = &tool.output_schema else {
+        result.add_issue("Tool declares no outputSchema; its response cannot be checked");
+        result.add_suggestion("Declare an outputSchema describing the structured result");
+        return result
+            .with_score(Severity::Info.score())
+            .with_severity(Severity::Info);
+    };
+
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            result.add_issue(format!("tools/call failed: {e}"));
+            return result.with_score(0.0).with_severity(Severity::Fail);
+        }
+    };
+    if let Some(error) = response.error {
+        result.add_issue(format!(
+            "tools/call returned error {}: {}",
+            error.code, error.message
+        ));
+        result.add_suggestion("Provide working sample arguments with --samples");
+        return result
+            .with_score(Severity::Warn.score())
+            .with_severity(Severity::Warn);
+    }
+    let payload = response.result.unwrap_or(Value::Null);
+    if payload.get("isError").and_then(Value::as_bool) == Some(true) {
+        result.add_issue("Tool reported isError for the sample arguments");
+        result.add_suggestion("Provide working sample arguments with --samples");
+        return result
+            .with_score(Severity::Warn.score())
+            .with_severity(Severity::Warn);
+    }
+
+    let mut severity = Severity::Pass;
+    let target = if let Some(structured) = payload.get("structuredContent") {
+        structured.clone()
+    } else if let Some(parsed) = single_text_json(&payload) {
+        result.add_issue("Response has no structuredContent; checked the JSON text content");
+        result.add_suggestion("Return structuredContent whenever an outputSchema is declared");
+        severity = Severity::Warn;
+        parsed
+    } else {
+        result.add_issue("Response has no structuredContent for the declared outputSchema");
+        return result.with_score(0.0).with_severity(Severity::Fail);
+    };
+
+    let validation = validate_output(&target, schema);
+    for violation in &validation.violations {
+        if violation.param.is_empty() {
+            result.add_issue(violation.message.clone());
+        } else {
+            result.add_issue(format!("`{}`: {}", violation.param, violation.message));
+        }
+    }
+    if !validation.is_valid() {
+        result.add_suggestion("Update the outputSchema or the implementation so they agree");
+        severity = Severity::Fail;
+    }
+
+    result.with_score(severity.score()).with_severity(severity)
+}
+
+/// The JSON value of a result whose only content block is JSON text.
+fn single_text_json(payload: &Value) -> Option<Value> {
+    let content = payload.get("content")?.as_array()?;
+    if content.len() != 1 {
+        return None;
+    }
+    serde_json::from_str(content[0].get("text")?.as_str()?).ok()
+}
+
+/// Generate arguments satisfying the required properties of `input_schema`.
+#[must_use]
+pub fn sample_arguments(input_schema: &Value) -> Value {
+    match sample_value(input_schema) {
+        Value::Object(map) => Value::Object(map),
+        _ => Value::Object(Map::new()),
+    }
+}
+
+fn sample_value(schema: &Value) -> Value {
+    if let Some(value) = schema.get("default").or_else(|| schema.get("const")) {
+        return value.clone();
+    }
+    if let Some(value) = ["examples", "enum"]
+        .iter()
+        .find_map(|key| schema.get(*key)?.as_array()?.first())
+    {
+        return value.clone();
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => ty.as_str(),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|ty| *ty != "null")
+            .unwrap_or("null"),
+        _ if schema.get("properties").is_some() => "object",
+        _ => "null",
+    };
+    match ty {
+        "string" => Value::String("example".to_string()),
+        "integer" | "number" => schema.get("minimum").cloned().unwrap_or(json!(1)),
+        "boolean" => Value::Bool(false),
+        "array" => {
+            let min_items = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+            match schema.get("items") {
+                Some(items) if min_items > 0 => Value::Array(vec![sample_value(items)]),
+                _ => Value::Array(Vec::new()),
+            }
+        }
+        "object" => {
+            let properties = schema.get("properties");
+            let required = schema
+                .get("required")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            Value::Object(
+                required
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|name| {
+                        let property = properties.and_then(|p| p.get(name)).unwrap_or(&Value::Null);
+                        (name.to_string(), sample_value(property))
+                    })
+                    .collect(),
+            )
+        }
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{JsonRpcError, RequestId, ToolAnnotations};
+
+    fn tool(output_schema: Option<Value>) -> Tool {
+        Tool {
+            name: "weather_current".to_string(),
+            title: None,
+            description: Some("Get the current weather for a city.".to_string()),
+            input_schema: json!({
+                "type": "object",
+                "required": ["city", "units", "days"],
+                "properties": {
+                    "city": {"type": "string"},
+                    "units": {"type": "string", "enum": ["metric", "imperial"]},
+                    "days": {"type": "integer", "minimum": 1},
+                    "verbose": {"type": "boolean"}
+                }
+            }),
+            output_schema,
+            annotations: None,
+            role: None,
+            projection: None,
+        }
+    }
+
+    fn temperature_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["temperature"],
+            "properties": {"temperature": {"type": "number"}}
+        })
+    }
+
+    fn success(result: Value) -> JsonRpcResponse {
+        JsonRpcResponse::success(RequestId::Number(1), result)
+    }
+
+    #[test]
+    fn sample_arguments_cover_required_properties_only() {
+        let args = sample_arguments(&tool(None).input_schema);
+        assert_eq!(
+            args,
+            json!({"city": "example", "units": "metric", "days": 1})
+        );
+    }
+
+    #[test]
+    fn conforming_structured_content_passes() {
+        // GIVEN: a response matching the declared outputSchema
+        let response = Ok(success(
+            json!({"structuredContent": {"temperature": 21.5}, "content": []}),
+        ));
+
+        // WHEN
+        let result = check_response(&tool(Some(temperature_schema())), response);
+
+        // THEN
+        assert!(result.passed, "{:?}", result.issues);
+        assert_eq!(result.severity, Severity::Pass);
+    }
+
+    #[test]
+    fn schema_drift_is_reported_per_field() {
+        // GIVEN: the server now returns a string and an undeclared field
+        let response = Ok(success(json!({
+            "structuredContent": {"temperature": "warm", "humidity": 40}
+        })));
+
+        // WHEN
+        let result = check_response(&tool(Some(temperature_schema())), response);
+
+        // THEN: a failure naming the drifted fields
+        assert_eq!(result.severity, Severity::Fail);
+        assert!(result.issues.iter().any(|i| i.contains("temperature")));
+        assert!(result.issues.iter().any(|i| i.contains("humidity")));
+    }
+
+    #[test]
+    fn text_only_and_error_responses_are_warnings() {
+        let schema = Some(temperature_schema());
+        let text = Ok(success(
+            json!({"content": [{"type": "text", "text": "{\"temperature\": 3}"}]}),
+        ));
+        let result = check_response(&tool(schema.clone()), text);
+        assert_eq!(result.severity, Severity::Warn);
+        assert!(!result.passed);
+
+        let error = Ok(JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id: Some(RequestId::Number(1)),
+            result: None,
+            error: Some(JsonRpcError {
+                code: -32602,
+                message: "invalid city".to_string(),
+                data: None,
+            }),
+        });
+        assert_eq!(
+            check_response(&tool(schema), error).severity,
+            Severity::Warn
+        );
+    }
+
+    #[test]
+    fn only_read_only_tools_with_schemas_are_called_by_default() {
+        let mut read_only = tool(Some(temperature_schema()));
+        read_only.annotations = Some(ToolAnnotations {
+            read_only_hint: Some(true),
+            ..ToolAnnotations::default()
+        });
+        let config = ConformanceConfig::default();
+
+        assert!(is_selected(&read_only, &config));
+        assert!(!is_selected(&tool(Some(temperature_schema())), &config));
+
+        let explicit = ConformanceConfig {
+            tools: vec!["weather_current".to_string()],
+            ..ConformanceConfig::default()
+        };
+        assert!(is_selected(&tool(None), &explicit));
+    }
+}
@@ -4,8 +4,22 @@
 //! Auto-fix for validation issues
 //!
 //! Provides suggested fixes and the ability to apply them to capability YAML files.
+//! Tools served by backends the operator cannot edit get gateway-side
+//! remediations instead, written as
+//! [`ToolOverrides`](crate::provider::transforms::ToolOverrides) entries for
+//! the provider transform chain.
+
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::protocol::{Tool, ToolAnnotations};
+use crate::provider::transforms::ToolOverride;
+use crate::provider::transforms::overrides::merge_patch;
+
+/// Overrides file written by `validate --server ... --fix` when none is given.
+pub const DEFAULT_OVERRIDES_FILE: &str = "tool-overrides.json";
 
 /// Suggested fix for a validation issue
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,6 +139,183 @@ impl CapabilityFixer {
     }
 }
 
+/// Generate gateway-side remediations for live server tools
+pub struct OverrideFixer;
+
+impl OverrideFixer {
+    /// Build override entries for `tools` of `server` from validation results.
+    ///
+    /// Renames fix naming (AX-005, AX-008, AX-009), descriptions are extended
+    /// with usage and parameter context (AX-003), schema patches fill missing
+    /// property types and descriptions (AX-003, AX-007) and close overly
+    /// broad schemas (AX-014), and destructive tools get `destructiveHint`
+    /// (AX-012). Entries are keyed by the tool name as the server lists it.
+    #[must_use]
+    pub fn suggest_overrides(
+        server: &str,
+        tools: &[Tool],
+        results: &[super::ValidationResult],
+    ) -> BTreeMap<String, ToolOverride> {
+        let mut entries: BTreeMap<String, ToolOverride> = BTreeMap::new();
+
+        for tool in tools {
+            let failed: Vec<&super::ValidationResult> = results
+                .iter()
+                .filter(|r| !r.passed && r.tool_name == tool.name)
+                .collect();
+            if failed.is_empty() {
+                continue;
+            }
+            let issues = |code: &'static str| issues_of(&failed, code);
+
+            let mut entry = ToolOverride::default();
+            let mut patch = json!({});
+
+            // Naming: snake_case, then a service prefix when missing or generic
+            let mut name = tool.name.clone();
+            if issues("AX-009").any(|i| i.contains("kebab-case") || i.contains("camelCase")) {
+                name = to_snake_case(&name);
+            }
+            if let Some(prefix) = service_prefix(server)
+                && (issues("AX-005").any(|i| i.contains("No service prefix"))
+                    || issues("AX-008").any(|i| i.contains("too generic")))
+                && !name.starts_with(&format!("{prefix}_"))
+            {
+                name = format!("{prefix}_{name}");
+            }
+            if name != tool.name {
+                entry.rename = Some(name);
+            }
+
+            // Descriptions: add usage guidance and parameter context
+            if issues("AX-003").any(|i| i.starts_with("Description")) {
+                entry.description = Some(extended_description(tool));
+            }
+
+            // Schema: fill missing property types and descriptions
+            for issue in issues("AX-003").chain(issues("AX-007")) {
+                let Some(prop) = extract_property_name(issue) else {
+                    continue;
+                };
+                if issue.contains("missing description") || issue.contains("missing 'description'")
+                {
+                    merge_patch(
+                        &mut patch,
+                        &json!({"properties": {prop.as_str(): {"description": format!("The {prop} parameter")}}}),
+                    );
+                } else if issue.contains("missing 'type'") {
+                    merge_patch(
+                        &mut patch,
+                        &json!({"properties": {prop.as_str(): {"type": "string"}}}),
+                    );
+                }
+            }
+
+            // Schema: close an open schema that declares its properties
+            let declares_properties = tool
+                .input_schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .is_some_and(|p| !p.is_empty());
+            if issues("AX-014").next().is_some() && declares_properties {
+                merge_patch(&mut patch, &json!({"additionalProperties": false}));
+            }
+            if patch.as_object().is_some_and(|p| !p.is_empty()) {
+                entry.input_schema_patch = Some(patch);
+            }
+
+            // Annotations: destructive tools must say so
+            if issues("AX-012").next().is_some() {
+                entry.annotations = Some(ToolAnnotations {
+                    destructive_hint: Some(true),
+                    read_only_hint: tool
+                        .annotations
+                        .as_ref()
+                        .and_then(|a| a.read_only_hint)
+                        .map(|_| false),
+                    ..ToolAnnotations::default()
+                });
+            }
+
+            if entry.rename.is_some()
+                || entry.description.is_some()
+                || entry.input_schema_patch.is_some()
+                || entry.annotations.is_some()
+            {
+                entries.insert(tool.name.clone(), entry);
+            }
+        }
+
+        entries
+    }
+}
+
+/// Issues reported by rule `code` among `failed` results.
+fn issues_of<'a>(
+    failed: &'a [&'a super::ValidationResult],
+    code: &'static str,
+) -> impl Iterator<Item = &'a str> {
+    failed
+        .iter()
+        .filter(move |r| r.rule_code == code)
+        .flat_map(|r| r.issues.iter().map(String::as_str))
+}
+
+/// A `snake_case` service prefix for `server`, if its name is usable as one.
+fn service_prefix(server: &str) -> Option<String> {
+    let valid = !server.is_empty()
+        && server
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    valid.then(|| to_snake_case(server))
+}
+
+/// `tool`'s description followed by usage guidance and its parameters.
+fn extended_description(tool: &Tool) -> String {
+    let mut description = tool
+        .description
+        .as_deref()
+        .unwrap_or("")
+        .trim()
+        .trim_end_matches('.')
+        .to_string();
+    if description.is_empty() {
+        description = format!("Run the {} tool", tool.name.replace(['_', '-'], " "));
+    }
+    description.push_str(". Use this when you need to ");
+    description.push_str(&to_snake_case(&tool.name).replace('_', " "));
+    description.push('.');
+
+    let required: Vec<&str> = tool
+        .input_schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if let Some(props) = tool
+        .input_schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .filter(|p| !p.is_empty())
+    {
+        let params: Vec<String> = props
+            .iter()
+            .map(|(name, prop)| {
+                let ty = prop.get("type").and_then(Value::as_str).unwrap_or("any");
+                if required.contains(&name.as_str()) {
+                    format!("{name} ({ty}, required)")
+                } else {
+                    format!("{name} ({ty})")
+                }
+            })
+            .collect();
+        description.push_str(" Parameters: ");
+        description.push_str(&params.join(", "));
+        description.push('.');
+    }
+    description
+}
+
 /// Extract a property name from an issue string like "Property 'query' missing 'type'"
 fn extract_property_name(issue: &str) -> Option<String> {
     let start = issue.find('\'')?;
@@ -247,6 +438,116 @@ mod tests {
         assert!(result.unwrap().contains("name: my_tool"));
     }
 
+    fn live_tool(name: &str, input_schema: Value) -> Tool {
+        Tool {
+            name: name.to_string(),
+            title: None,
+            description: Some("Delete".to_string()),
+            input_schema,
+            output_schema: None,
+            annotations: None,
+            role: None,
+            projection: None,
+        }
+    }
+
+    fn failed(code: &str, tool: &str, issues: &[&str]) -> ValidationResult {
+        let mut result = ValidationResult::new(code, "rule", tool);
+        for issue in issues {
+            result.add_issue(*issue);
+        }
+        result.with_severity(Severity::Warn)
+    }
+
+    #[test]
+    fn suggest_overrides_emits_concrete_remediations() {
+        // GIVEN: an open, camelCase, destructive tool with thin docs
+        let tool = live_tool(
+            "deleteRepo",
+            json!({"type": "object", "additionalProperties": true,
+                   "required": ["id"], "properties": {"id": {"type": "string"}}}),
+        );
+        let results = [
+            failed(
+                "AX-009",
+                "deleteRepo",
+                &["Name 'deleteRepo' uses camelCase instead of snake_case"],
+            ),
+            failed(
+                "AX-005",
+                "deleteRepo",
+                &["No service prefix - hard to discover in large tool lists"],
+            ),
+            failed(
+                "AX-003",
+                "deleteRepo",
+                &[
+                    "Description too short - agents need rich context",
+                    "Parameter 'id' missing description",
+                ],
+            ),
+            failed(
+                "AX-012",
+                "deleteRepo",
+                &["Name contains 'delete' but the tool does not declare destructiveHint"],
+            ),
+            failed(
+                "AX-014",
+                "deleteRepo",
+                &["Input schema sets additionalProperties: true with no required fields"],
+            ),
+        ];
+
+        // WHEN
+        let entries = OverrideFixer::suggest_overrides("github", &[tool], &results);
+
+        // THEN: one entry with every remediation
+        let entry = &entries["deleteRepo"];
+        assert_eq!(entry.rename.as_deref(), Some("github_delete_repo"));
+        assert_eq!(
+            entry.description.as_deref(),
+            Some(
+                "Delete. Use this when you need to delete repo. Parameters: id (string, required)."
+            )
+        );
+        assert_eq!(
+            entry.input_schema_patch,
+            Some(json!({"additionalProperties": false,
+                        "properties": {"id": {"description": "The id parameter"}}}))
+        );
+        assert_eq!(
+            entry.annotations.as_ref().and_then(|a| a.destructive_hint),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn suggest_overrides_skips_clean_tools_and_unusable_prefixes() {
+        let tool = live_tool("search", json!({"type": "object"}));
+        let generic = [failed(
+            "AX-008",
+            "search",
+            &["Name 'search' is too generic and likely to conflict with other tools"],
+        )];
+
+        assert!(
+            OverrideFixer::suggest_overrides("github", std::slice::from_ref(&tool), &[]).is_empty()
+        );
+        assert!(
+            OverrideFixer::suggest_overrides(
+                "https://mcp.example.com/mcp",
+                std::slice::from_ref(&tool),
+                &generic
+            )
+            .is_empty()
+        );
+        let entries = OverrideFixer::suggest_overrides("my-search", &[tool], &generic);
+        assert_eq!(
+            entries["search"].rename.as_deref(),
+            Some("my_search_search")
+        );
+    }
+
     #[test]
     fn apply_fixes_returns_none_for_empty() {
         let yaml = "name: my_tool\n";
@@ -0,0 +1,132 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: MIT
+
+//! `JUnit` XML output format for CI test reports
+//!
+//! Jenkins, GitLab and most other CI systems render `JUnit` XML natively. Each
+//! source (capability file, `server:<name>` or the cross-capability checks)
+//! becomes a `<testsuite>` and each rule/tool pair a `<testcase>`, so every
+//! rule shows up directly in the pipeline's test view. Warnings are reported
+//! as failures, like other linters do, with the severity in the `type`
+//! attribute.
+
+use std::fmt::Write as _;
+
+use quick_xml::escape::escape;
+
+use super::{Severity, ValidationResult};
+
+/// Render validation results from several sources as one `JUnit` XML document.
+///
+/// Each entry in `sources` is `(source_name, results_for_that_source)`.
+#[must_use]
+pub fn to_junit_multi(sources: &[(&str, &[ValidationResult])]) -> String {
+    let (tests, failures) = sources
+        .iter()
+        .flat_map(|(_, results)| results.iter())
+        .fold((0, 0), |(tests, failures), r| {
+            (tests + 1, failures + usize::from(!r.passed))
+        });
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"mcp-gateway-validate\" tests=\"{tests}\" failures=\"{failures}\">"
+    );
+    for &(source, results) in sources {
+        write_suite(&mut xml, source, results);
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn write_suite(xml: &mut String, source: &str, results: &[ValidationResult]) {
+    let source = escape(source);
+    let failures = results.iter().filter(|r| !r.passed).count();
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{source}\" tests=\"{}\" failures=\"{failures}\">",
+        results.len()
+    );
+    for result in results {
+        let _ = write!(
+            xml,
+            "    <testcase classname=\"{source}\" name=\"{} {}\"",
+            escape(&result.rule_code),
+            escape(&result.tool_name)
+        );
+        if result.passed {
+            xml.push_str("/>\n");
+            continue;
+        }
+        let mut text = result.issues.join("\n");
+        for suggestion in &result.suggestions {
+            let _ = write!(text, "\nSuggestion: {suggestion}");
+        }
+        let _ = writeln!(
+            xml,
+            ">\n      <failure type=\"{}\" message=\"{}\">{}</failure>\n    </testcase>",
+            severity_label(result.severity),
+            escape(&result.rule_name),
+            escape(&text)
+        );
+    }
+    xml.push_str("  </testsuite>\n");
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Fail => "error",
+        Severity::Warn => "warning",
+        Severity::Info | Severity::Pass => "info",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(code: &str, tool: &str, severity: Severity) -> ValidationResult {
+        let mut result = ValidationResult::new(code, "Rule <name>", tool);
+        result.add_issue("Description uses \"quotes\" & <tags>");
+        result.add_suggestion("Rewrite it");
+        result.with_severity(severity)
+    }
+
+    #[test]
+    fn every_result_becomes_a_test_case() {
+        // GIVEN: one passing and two failing results across two sources
+        let file = vec![
+            ValidationResult::new("AX-001", "Outcomes", "get_user"),
+            failed("AX-003", "get_user", Severity::Fail),
+        ];
+        let cross = vec![failed("AX-008", "search", Severity::Warn)];
+
+        // WHEN
+        let xml = to_junit_multi(&[
+            ("caps/user.yaml", file.as_slice()),
+            ("cross-capability", cross.as_slice()),
+        ]);
+
+        // THEN: totals, one suite per source, one case per result
+        assert!(
+            xml.contains("<testsuites name=\"mcp-gateway-validate\" tests=\"3\" failures=\"2\">")
+        );
+        assert!(xml.contains("<testsuite name=\"caps/user.yaml\" tests=\"2\" failures=\"1\">"));
+        assert!(xml.contains("<testsuite name=\"cross-capability\" tests=\"1\" failures=\"1\">"));
+        assert!(xml.contains("<testcase classname=\"caps/user.yaml\" name=\"AX-001 get_user\"/>"));
+        assert!(xml.contains("<failure type=\"warning\""));
+        assert!(xml.contains("Suggestion: Rewrite it"));
+    }
+
+    #[test]
+    fn markup_in_results_is_escaped() {
+        let results = vec![failed("AX-003", "a<b>", Severity::Fail)];
+        let xml = to_junit_multi(&[("f.yaml", results.as_slice())]);
+
+        assert!(xml.contains("name=\"AX-003 a&lt;b&gt;\""));
+        assert!(xml.contains("message=\"Rule &lt;name&gt;\""));
+        assert!(xml.contains("uses &quot;quotes&quot; &amp; &lt;tags&gt;"));
+        assert!(!xml.contains("<tags>"));
+    }
+}
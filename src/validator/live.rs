@@ -0,0 +1,184 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: MIT
+
+//! Live server validation.
+//!
+//! `mcp-gateway validate --server <name|url|command>` validates the tools a
+//! running MCP server actually lists instead of static capability files.
+//! A server is reached the way the gateway reaches a backend, so configured
+//! env, headers, auth and namespaces apply to named backends.
+
+use futures::future::join_all;
+
+use crate::Result;
+use crate::backend::{Backend, runtime_plan_for_backend};
+use crate::config::{BackendConfig, Config, TransportConfig};
+use crate::protocol::Tool;
+
+/// An MCP server whose `tools/list` is validated.
+#[derive(Debug, Clone)]
+pub struct LiveServer {
+    /// Label in reports: the backend name, URL or command.
+    pub name: String,
+    /// How to reach the server.
+    pub backend: BackendConfig,
+}
+
+impl LiveServer {
+    /// Resolve `spec` as a backend configured in `gateway`, else an
+    /// `http(s)://` URL (Streamable HTTP unless it ends in `/sse`), else a
+    /// stdio command to spawn.
+    #[must_use]
+    pub fn resolve(spec: &str, gateway: &Config) -> Self {
+        if let Some(backend) = gateway.backends.get(spec) {
+            return Self {
+                name: spec.to_string(),
+                backend: backend.clone(),
+            };
+        }
+        let transport = if spec.starts_with("http://") || spec.starts_with("https://") {
+            TransportConfig::Http {
+                http_url: spec.to_string(),
+                streamable_http: !spec.ends_with("/sse"),
+                protocol_version: None,
+            }
+        } else {
+            TransportConfig::Stdio {
+                command: spec.to_string(),
+                cwd: None,
+                protocol_version: None,
+            }
+        };
+        Self {
+            name: spec.to_string(),
+            backend: BackendConfig {
+                transport,
+                ..BackendConfig::default()
+            },
+        }
+    }
+
+    /// Every enabled backend of `gateway`, sorted by name.
+    #[must_use]
+    pub fn all_backends(gateway: &Config) -> Vec<Self> {
+        let mut servers: Vec<Self> = gateway
+            .enabled_backends()
+            .map(|(name, backend)| Self {
+                name: name.clone(),
+                backend: backend.clone(),
+            })
+            .collect();
+        servers.sort_by(|a, b| a.name.cmp(&b.name));
+        servers
+    }
+
+    /// A backend handle for the server; it connects (or spawns) on first use.
+    #[must_use]
+    pub fn connect(&self, gateway: &Config) -> Backend {
+        Backend::new_with_runtime_plan(
+            &self.name,
+            self.backend.clone(),
+            &gateway.failsafe,
+            gateway.meta_mcp.cache_ttl,
+            runtime_plan_for_backend(&self.name, &self.backend, &gateway.runtime),
+        )
+    }
+
+    /// Connect to (or spawn) the server, list its tools and disconnect.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be reached or `tools/list`
+    /// fails.
+    pub async fn fetch_tools(&self, gateway: &Config) -> Result<Vec<Tool>> {
+        let backend = self.connect(gateway);
+        let tools = backend.get_tools().await;
+        let _ = backend.stop().await;
+        tools
+    }
+}
+
+/// Fetch the tools of every server concurrently, in order.
+pub async fn fetch_all(servers: &[LiveServer], gateway: &Config) -> Vec<Result<Vec<Tool>>> {
+    join_all(servers.iter().map(|server| server.fetch_tools(gateway))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, response::IntoResponse, routing::post};
+    use serde_json::{Value, json};
+
+    async fn mcp_stub(Json(request): Json<Value>) -> axum::response::Response {
+        let id = request["id"].clone();
+        match request["method"].as_str() {
+            Some("initialize") => Json(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {"tools": {}},
+                    "serverInfo": {"name": "stub", "version": "1.0.0"}
+                }
+            }))
+            .into_response(),
+            Some("tools/list") => Json(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {"tools": [{
+                    "name": "weather_current",
+                    "description": "Get the current weather for a city.",
+                    "inputSchema": {"type": "object", "properties": {}}
+                }]}
+            }))
+            .into_response(),
+            _ => axum::http::StatusCode::ACCEPTED.into_response(),
+        }
+    }
+
+    #[test]
+    fn resolve_prefers_configured_backends_then_urls_then_commands() {
+        let mut gateway = Config::default();
+        let tavily = LiveServer::resolve("https://mcp.tavily.com/mcp", &gateway).backend;
+        gateway.backends.insert("tavily".to_string(), tavily);
+
+        let named = LiveServer::resolve("tavily", &gateway);
+        assert_eq!(named.name, "tavily");
+        assert!(matches!(
+            named.backend.transport,
+            TransportConfig::Http { ref http_url, .. } if http_url == "https://mcp.tavily.com/mcp"
+        ));
+        assert!(matches!(
+            LiveServer::resolve("https://mcp.example.com/mcp", &gateway).backend.transport,
+            TransportConfig::Http { ref http_url, .. } if http_url == "https://mcp.example.com/mcp"
+        ));
+        assert!(matches!(
+            LiveServer::resolve("npx -y @acme/server", &gateway).backend.transport,
+            TransportConfig::Stdio { ref command, .. } if command == "npx -y @acme/server"
+        ));
+    }
+
+    #[tokio::test]
+    async fn fetch_tools_lists_a_running_server() {
+        // GIVEN: a streamable-HTTP MCP stub
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/mcp", post(mcp_stub))
+            .route("/mcp/", post(mcp_stub));
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        let gateway = Config::default();
+        let live = LiveServer::resolve(&format!("http://{addr}/mcp"), &gateway);
+
+        // WHEN: fetching its tools
+        let tools = live.fetch_tools(&gateway).await.unwrap();
+
+        // THEN: the real definitions come back
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "weather_current");
+
+        server.abort();
+    }
+}
@@ -10,8 +10,12 @@
 //!
 //! - <https://www.philschmid.de/mcp-best-practices>
 
+pub mod baseline;
 pub mod cli_handler;
+pub mod conformance;
 pub mod fix;
+pub mod junit;
+pub mod live;
 pub mod report;
 pub mod rules;
 pub mod rules_schema;
@@ -23,7 +27,8 @@ use crate::protocol::Tool;
 
 pub use report::{Severity, ValidationReport, ValidationResult};
 pub use rules::{
-    ConflictDetectionRule, NamingConsistencyRule, Rule, ToolPoisoningRule, ValidationRules,
+    ConflictDetectionRule, CustomRule, NamingConsistencyRule, Rule, ToolPoisoningRule,
+    ValidationRules,
 };
 
 /// Output format for validation reports
@@ -35,6 +40,8 @@ pub enum OutputFormat {
     Json,
     /// SARIF 2.1.0 for CI integration
     Sarif,
+    /// `JUnit` XML for CI test reports
+    Junit,
 }
 
 /// Minimum severity filter
@@ -56,8 +63,18 @@ pub struct ValidateConfig {
     pub min_severity: SeverityFilter,
     /// Whether to attempt auto-fixing
     pub auto_fix: bool,
+    /// Overrides file receiving auto-fixes for live servers
+    pub overrides_file: Option<std::path::PathBuf>,
     /// Whether to use colored output
     pub color: bool,
+    /// YAML file with user-defined rules merged into the built-in set
+    pub rules_file: Option<std::path::PathBuf>,
+    /// Call live server tools and check responses against `outputSchema`
+    pub conformance: Option<conformance::ConformanceConfig>,
+    /// Baseline file of known violations to suppress
+    pub baseline: Option<std::path::PathBuf>,
+    /// Rewrite the baseline from this run instead of reading it
+    pub update_baseline: bool,
 }
 
 impl SeverityFilter {
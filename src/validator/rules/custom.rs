@@ -0,0 +1,379 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: MIT
+
+//! User-defined validation rules
+//!
+//! Organisations encode their own tool conventions in a YAML rules file and
+//! `mcp-gateway validate --rules <file>` merges them into [`ValidationRules`]
+//! next to the built-in AX rules:
+//!
+//! ```yaml
+//! rules:
+//!   - code: ORG-001
+//!     name: Team prefix
+//!     severity: fail
+//!     name_pattern: "^(billing|search)_"
+//!   - code: ORG-002
+//!     name: House style
+//!     banned_words: [simply, just, easy]
+//!     max_description_length: 600
+//!     required_schema_fields: [required, properties.tenant_id]
+//! ```
+//!
+//! Every check a rule declares must hold; each violation becomes one issue
+//! on the rule's result.
+//!
+//! [`ValidationRules`]: super::ValidationRules
+
+use std::path::Path;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::super::{Severity, ValidationResult};
+use super::Rule;
+use crate::protocol::Tool;
+use crate::{Error, Result};
+
+/// A YAML rules file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRulesFile {
+    /// Rules in report order
+    #[serde(default)]
+    pub rules: Vec<CustomRuleSpec>,
+}
+
+/// One user-defined rule as written in the rules file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRuleSpec {
+    /// Rule code shown in reports (e.g. "ORG-001")
+    pub code: String,
+    /// Short rule name
+    pub name: String,
+    /// What the rule enforces
+    #[serde(default)]
+    pub description: String,
+    /// Severity of a violation
+    #[serde(default)]
+    pub severity: CustomSeverity,
+    /// Regex the tool name must match
+    #[serde(default)]
+    pub name_pattern: Option<String>,
+    /// Regex the tool description must match
+    #[serde(default)]
+    pub description_pattern: Option<String>,
+    /// Dotted paths that must exist in the input schema
+    /// (e.g. `required`, `properties.tenant_id`)
+    #[serde(default)]
+    pub required_schema_fields: Vec<String>,
+    /// Words that must not appear in the name or description
+    /// (case-insensitive, whole words)
+    #[serde(default)]
+    pub banned_words: Vec<String>,
+    /// Maximum description length in characters
+    #[serde(default)]
+    pub max_description_length: Option<usize>,
+}
+
+/// Severity of a custom rule violation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomSeverity {
+    /// Violations fail validation
+    Fail,
+    /// Violations are warnings
+    #[default]
+    Warn,
+    /// Violations are informational
+    Info,
+}
+
+impl From<CustomSeverity> for Severity {
+    fn from(severity: CustomSeverity) -> Self {
+        match severity {
+            CustomSeverity::Fail => Self::Fail,
+            CustomSeverity::Warn => Self::Warn,
+            CustomSeverity::Info => Self::Info,
+        }
+    }
+}
+
+/// A compiled user-defined rule.
+#[derive(Debug)]
+pub struct CustomRule {
+    code: String,
+    name: String,
+    description: String,
+    severity: Severity,
+    name_pattern: Option<Regex>,
+    description_pattern: Option<Regex>,
+    required_schema_fields: Vec<String>,
+    banned_words: Vec<(String, Regex)>,
+    max_description_length: Option<usize>,
+}
+
+impl CustomRule {
+    /// Compile a rule spec.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the rule has no code, declares no check,
+    /// or contains an invalid regex.
+    pub fn compile(spec: CustomRuleSpec) -> Result<Self> {
+        if spec.code.trim().is_empty() {
+            return Err(Error::Config("custom rule without a code".to_string()));
+        }
+        if spec.name_pattern.is_none()
+            && spec.description_pattern.is_none()
+            && spec.required_schema_fields.is_empty()
+            && spec.banned_words.is_empty()
+            && spec.max_description_length.is_none()
+        {
+            return Err(Error::Config(format!(
+                "custom rule {} declares no check",
+                spec.code
+            )));
+        }
+        let compile_pattern = |pattern: Option<String>, field: &str| -> Result<Option<Regex>> {
+            pattern
+                .map(|p| {
+                    Regex::new(&p).map_err(|e| {
+                        Error::Config(format!("custom rule {} {field}: {e}", spec.code))
+                    })
+                })
+                .transpose()
+        };
+        let name_pattern = compile_pattern(spec.name_pattern, "name_pattern")?;
+        let description_pattern = compile_pattern(spec.description_pattern, "description_pattern")?;
+        let banned_words = spec
+            .banned_words
+            .into_iter()
+            .map(|word| {
+                Regex::new(&format!(r"(?i)\b{}\b", regex::escape(&word)))
+                    .map(|re| (word, re))
+                    .map_err(|e| Error::Config(format!("custom rule {}: {e}", spec.code)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            code: spec.code,
+            name: spec.name,
+            description: spec.description,
+            severity: spec.severity.into(),
+            name_pattern,
+            description_pattern,
+            required_schema_fields: spec.required_schema_fields,
+            banned_words,
+            max_description_length: spec.max_description_length,
+        })
+    }
+
+    /// Load and compile every rule in a YAML rules file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the file cannot be read or parsed, a rule
+    /// is invalid, or two rules share a code.
+    pub fn load(path: &Path) -> Result<Vec<Self>> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!("failed to read rules file {}: {e}", path.display()))
+        })?;
+        Self::parse(&content)
+            .map_err(|e| Error::Config(format!("rules file {}: {e}", path.display())))
+    }
+
+    /// Parse and compile rules from YAML.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the YAML is invalid, a rule is invalid, or
+    /// two rules share a code.
+    pub fn parse(yaml: &str) -> Result<Vec<Self>> {
+        let file: CustomRulesFile =
+            serde_yaml::from_str(yaml).map_err(|e| Error::Config(e.to_string()))?;
+        let mut seen = std::collections::HashSet::new();
+        file.rules
+            .into_iter()
+            .map(|spec| {
+                if !seen.insert(spec.code.clone()) {
+                    return Err(Error::Config(format!(
+                        "duplicate custom rule code {}",
+                        spec.code
+                    )));
+                }
+                Self::compile(spec)
+            })
+            .collect()
+    }
+}
+
+/// Whether a dotted `path` resolves inside `schema`.
+fn schema_has_field(schema: &Value, path: &str) -> bool {
+    path.split('.')
+        .try_fold(schema, |value, key| value.get(key))
+        .is_some()
+}
+
+impl Rule for CustomRule {
+    fn code(&self) -> &str {
+        &self.code
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn check(&self, tool: &Tool) -> Result<ValidationResult> {
+        let mut result = ValidationResult::new(self.code(), self.name(), &tool.name);
+        let desc = tool.description.as_deref().unwrap_or("");
+
+        if let Some(pattern) = &self.name_pattern
+            && !pattern.is_match(&tool.name)
+        {
+            result.add_issue(format!(
+                "Name '{}' does not match /{}/",
+                tool.name,
+                pattern.as_str()
+            ));
+        }
+
+        if let Some(pattern) = &self.description_pattern
+            && !pattern.is_match(desc)
+        {
+            result.add_issue(format!("Description does not match /{}/", pattern.as_str()));
+        }
+
+        for field in &self.required_schema_fields {
+            if !schema_has_field(&tool.input_schema, field) {
+                result.add_issue(format!("Input schema is missing '{field}'"));
+            }
+        }
+
+        for (word, pattern) in &self.banned_words {
+            if pattern.is_match(&tool.name) || pattern.is_match(desc) {
+                result.add_issue(format!("Uses banned word '{word}'"));
+            }
+        }
+
+        if let Some(max) = self.max_description_length {
+            let len = desc.chars().count();
+            if len > max {
+                result.add_issue(format!(
+                    "Description is {len} characters, over the {max} limit"
+                ));
+            }
+        }
+
+        if result.issues.is_empty() {
+            return Ok(result);
+        }
+        if !self.description.is_empty() {
+            result.add_suggestion(self.description.clone());
+        }
+        Ok(result
+            .with_score(self.severity.score())
+            .with_severity(self.severity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool(name: &str, description: &str, input_schema: Value) -> Tool {
+        Tool {
+            name: name.to_string(),
+            title: None,
+            description: Some(description.to_string()),
+            input_schema,
+            output_schema: None,
+            annotations: None,
+            role: None,
+            projection: None,
+        }
+    }
+
+    const RULES: &str = r#"
+rules:
+  - code: ORG-001
+    name: Team prefix
+    description: Prefix tools with the owning team
+    severity: fail
+    name_pattern: "^(billing|search)_"
+  - code: ORG-002
+    name: House style
+    banned_words: [simply]
+    max_description_length: 40
+    required_schema_fields: [required, properties.tenant_id]
+"#;
+
+    #[test]
+    fn conforming_tool_passes_every_custom_rule() {
+        // GIVEN: org rules and a tool that follows them
+        let rules = CustomRule::parse(RULES).unwrap();
+        let t = tool(
+            "billing_find_invoice",
+            "Find an invoice by number.",
+            json!({"type": "object", "required": ["tenant_id"],
+                   "properties": {"tenant_id": {"type": "string"}}}),
+        );
+
+        // WHEN / THEN: both rules pass
+        for rule in &rules {
+            let result = rule.check(&t).unwrap();
+            assert!(result.passed, "{}: {:?}", rule.code(), result.issues);
+        }
+    }
+
+    #[test]
+    fn violations_are_reported_with_the_rule_severity() {
+        // GIVEN: a tool breaking every check
+        let rules = CustomRule::parse(RULES).unwrap();
+        let t = tool(
+            "get_invoice",
+            "Simply fetches an invoice, whatever the tenant may be.",
+            json!({"type": "object", "properties": {}}),
+        );
+
+        // WHEN
+        let prefix = rules[0].check(&t).unwrap();
+        let style = rules[1].check(&t).unwrap();
+
+        // THEN
+        assert_eq!(prefix.severity, Severity::Fail);
+        assert_eq!(prefix.suggestions, ["Prefix tools with the owning team"]);
+        assert_eq!(style.severity, Severity::Warn);
+        assert_eq!(style.issues.len(), 4, "{:?}", style.issues);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(CustomRule::parse("rules:\n  - {code: X-1, name: n}\n").is_err());
+        assert!(
+            CustomRule::parse("rules:\n  - {code: X-1, name: n, name_pattern: '('}\n").is_err()
+        );
+        assert!(
+            CustomRule::parse(
+                "rules:\n  - {code: X-1, name: a, banned_words: [x]}\n  - {code: X-1, name: b, banned_words: [y]}\n"
+            )
+            .is_err()
+        );
+        assert!(CustomRule::parse("rules:\n  - {code: X-1, name: n, typo: 1}\n").is_err());
+    }
+
+    #[test]
+    fn custom_rules_merge_into_the_validator() {
+        let rules =
+            super::super::ValidationRules::new().with_custom(CustomRule::parse(RULES).unwrap());
+        assert!(rules.all_rules().iter().any(|r| r.code() == "ORG-002"));
+        assert!(rules.all_rules().iter().any(|r| r.code() == "AX-001"));
+    }
+}
@@ -18,7 +18,13 @@ use crate::protocol::Tool;
 use regex::Regex;
 use std::sync::OnceLock;
 
+pub mod custom;
+pub mod security;
 pub mod tool_poisoning;
+pub use custom::CustomRule;
+pub use security::{
+    BroadSchemaRule, DestructiveHintRule, SecretDefaultRule, UnconstrainedInputRule,
+};
 pub use tool_poisoning::ToolPoisoningRule;
 
 /// Validation rule trait
@@ -72,11 +78,26 @@ impl ValidationRules {
             Box::new(ConflictDetectionRule),
             Box::new(NamingConsistencyRule),
             Box::new(ToolPoisoningRule),
+            Box::new(DestructiveHintRule),
+            Box::new(UnconstrainedInputRule),
+            Box::new(BroadSchemaRule),
+            Box::new(SecretDefaultRule),
         ];
 
         Self { rules }
     }
 
+    /// Append user-defined rules after the built-in ones
+    #[must_use]
+    pub fn with_custom(mut self, custom: Vec<CustomRule>) -> Self {
+        self.rules.extend(
+            custom
+                .into_iter()
+                .map(|rule| Box::new(rule) as Box<dyn Rule>),
+        );
+        self
+    }
+
     /// Get all rules
     #[must_use]
     pub fn all_rules(&self) -> &[Box<dyn Rule>] {
@@ -0,0 +1,458 @@
+// SPDX-FileCopyrightText: 2026 Mikko Parkkola
+// SPDX-License-Identifier: MIT
+
+//! AX-012..AX-015: Security review of tool surfaces
+//!
+//! Agent-UX is one axis of a tool definition; what the definition lets an
+//! agent do is the other. These rules flag the patterns a security review of
+//! an MCP tool surface looks for first:
+//!
+//! * **AX-012** destructive-sounding tools that do not declare
+//!   `destructiveHint` (or claim `readOnlyHint`), so clients skip confirmation
+//! * **AX-013** raw URL or shell-string parameters with no `enum`, `const` or
+//!   `pattern` constraint (SSRF / command injection surface)
+//! * **AX-014** input schemas with `additionalProperties: true` and no
+//!   required fields, which accept anything
+//! * **AX-015** defaults or examples that look like real secrets
+
+use super::super::{Severity, ValidationResult};
+use super::Rule;
+use crate::Result;
+use crate::protocol::Tool;
+use regex::Regex;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// Name tokens that suggest a tool destroys or irreversibly changes state.
+const DESTRUCTIVE_VERBS: &[&str] = &[
+    "delete",
+    "remove",
+    "drop",
+    "destroy",
+    "purge",
+    "wipe",
+    "erase",
+    "truncate",
+    "terminate",
+    "kill",
+    "revoke",
+    "overwrite",
+    "reset",
+    "uninstall",
+];
+
+/// Parameter names that usually carry a URL.
+fn url_param_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)(^|_)(url|uri|endpoint|webhook|callback|href)s?($|_)")
+            .expect("url_param_re must be a valid regex")
+    })
+}
+
+/// Parameter names that usually carry a shell command or script.
+fn shell_param_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)(^|_)(command|cmd|shell|script|exec|bash|sh|commandline)($|_)")
+            .expect("shell_param_re must be a valid regex")
+    })
+}
+
+/// Values shaped like well-known credential formats.
+fn secret_value_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"sk-[A-Za-z0-9_-]{20,}",
+            r"|gh[pousr]_[A-Za-z0-9]{30,}",
+            r"|github_pat_[A-Za-z0-9_]{30,}",
+            r"|AKIA[0-9A-Z]{16}",
+            r"|xox[abprs]-[A-Za-z0-9-]{10,}",
+            r"|AIza[0-9A-Za-z_-]{35}",
+            r"|-----BEGIN [A-Z ]*PRIVATE KEY-----",
+            r"|eyJ[A-Za-z0-9_-]{10,}\.eyJ[A-Za-z0-9_-]{10,}\.",
+        ))
+        .expect("secret_value_re must be a valid regex")
+    })
+}
+
+/// Parameter names whose values are credentials.
+fn secret_param_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)(^|_)(password|passwd|secret|token|api_?key|access_?key|private_?key|credentials?)($|_)",
+        )
+        .expect("secret_param_re must be a valid regex")
+    })
+}
+
+/// Top-level input properties of `tool`.
+fn properties(tool: &Tool) -> impl Iterator<Item = (&String, &Value)> {
+    tool.input_schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+}
+
+/// Whether a string property restricts its values to an allowlist.
+fn is_constrained(prop: &Value) -> bool {
+    ["enum", "const", "pattern"]
+        .iter()
+        .any(|key| prop.get(*key).is_some())
+}
+
+/// Finish a result: pass when there are no issues, else `severity`.
+fn finish(mut result: ValidationResult, severity: Severity) -> ValidationResult {
+    if result.issues.is_empty() {
+        return result;
+    }
+    result.passed = false;
+    result.with_score(severity.score()).with_severity(severity)
+}
+
+/// AX-012: Destructive Hint
+///
+/// Tools whose name says they delete or overwrite something must declare
+/// `destructiveHint: true` so clients can ask for confirmation.
+pub struct DestructiveHintRule;
+
+#[allow(clippy::unnecessary_literal_bound)]
+impl Rule for DestructiveHintRule {
+    fn code(&self) -> &str {
+        "AX-012"
+    }
+
+    fn name(&self) -> &str {
+        "Destructive Hint"
+    }
+
+    fn description(&self) -> &str {
+        "Destructive-sounding tools must declare destructiveHint"
+    }
+
+    fn check(&self, tool: &Tool) -> Result<ValidationResult> {
+        let mut result = ValidationResult::new(self.code(), self.name(), &tool.name);
+
+        let name_lower = tool.name.to_lowercase();
+        let Some(verb) = name_lower
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .find(|token| DESTRUCTIVE_VERBS.contains(token))
+        else {
+            return Ok(result);
+        };
+
+        let annotations = tool.annotations.as_ref();
+        if annotations.and_then(|a| a.read_only_hint) == Some(true) {
+            result.add_issue(format!(
+                "Name contains '{verb}' but the tool claims readOnlyHint: true"
+            ));
+        } else if annotations.and_then(|a| a.destructive_hint).is_none() {
+            result.add_issue(format!(
+                "Name contains '{verb}' but the tool does not declare destructiveHint"
+            ));
+        }
+        if !result.issues.is_empty() {
+            result.add_suggestion(
+                "Set annotations.destructiveHint: true (or false if the operation is reversible)",
+            );
+        }
+
+        Ok(finish(result, Severity::Warn))
+    }
+}
+
+/// AX-013: Unconstrained Injection Surface
+///
+/// Free-form URL parameters invite SSRF and free-form shell strings invite
+/// command injection; both should be restricted with `enum`, `const` or
+/// `pattern`.
+pub struct UnconstrainedInputRule;
+
+#[allow(clippy::unnecessary_literal_bound)]
+impl Rule for UnconstrainedInputRule {
+    fn code(&self) -> &str {
+        "AX-013"
+    }
+
+    fn name(&self) -> &str {
+        "Unconstrained Injection Surface"
+    }
+
+    fn description(&self) -> &str {
+        "Raw URL and shell-string parameters need enum, const or pattern constraints"
+    }
+
+    fn check(&self, tool: &Tool) -> Result<ValidationResult> {
+        let mut result = ValidationResult::new(self.code(), self.name(), &tool.name);
+        let mut has_shell = false;
+
+        for (name, prop) in properties(tool) {
+            let is_string = prop.get("type").and_then(Value::as_str) == Some("string");
+            if !is_string || is_constrained(prop) {
+                continue;
+            }
+            let format = prop.get("format").and_then(Value::as_str);
+            if shell_param_re().is_match(name) {
+                has_shell = true;
+                result.add_issue(format!(
+                    "Parameter '{name}' accepts an unconstrained shell string"
+                ));
+            } else if url_param_re().is_match(name) || matches!(format, Some("uri" | "url" | "iri"))
+            {
+                result.add_issue(format!("Parameter '{name}' accepts an unconstrained URL"));
+            }
+        }
+
+        if !result.issues.is_empty() {
+            result.add_suggestion(
+                "Restrict the value with enum/const, or a pattern allowlisting hosts or commands",
+            );
+        }
+
+        let severity = if has_shell {
+            Severity::Fail
+        } else {
+            Severity::Warn
+        };
+        Ok(finish(result, severity))
+    }
+}
+
+/// AX-014: Overly Broad Schema
+///
+/// `additionalProperties: true` with no required fields accepts any object,
+/// so the schema documents nothing and constrains nothing.
+pub struct BroadSchemaRule;
+
+#[allow(clippy::unnecessary_literal_bound)]
+impl Rule for BroadSchemaRule {
+    fn code(&self) -> &str {
+        "AX-014"
+    }
+
+    fn name(&self) -> &str {
+        "Overly Broad Schema"
+    }
+
+    fn description(&self) -> &str {
+        "Input schemas must not accept arbitrary objects"
+    }
+
+    fn check(&self, tool: &Tool) -> Result<ValidationResult> {
+        let mut result = ValidationResult::new(self.code(), self.name(), &tool.name);
+
+        let open = tool.input_schema.get("additionalProperties") == Some(&Value::Bool(true));
+        let has_required = tool
+            .input_schema
+            .get("required")
+            .and_then(Value::as_array)
+            .is_some_and(|required| !required.is_empty());
+        if open && !has_required {
+            result
+                .add_issue("Input schema sets additionalProperties: true with no required fields");
+            result.add_suggestion(
+                "Declare the accepted properties, mark the essential ones required, and set additionalProperties: false",
+            );
+        }
+
+        Ok(finish(result, Severity::Warn))
+    }
+}
+
+/// AX-015: Secret-Looking Defaults
+///
+/// A credential in a `default` or `examples` value is published to every
+/// client that lists the tool.
+pub struct SecretDefaultRule;
+
+#[allow(clippy::unnecessary_literal_bound)]
+impl Rule for SecretDefaultRule {
+    fn code(&self) -> &str {
+        "AX-015"
+    }
+
+    fn name(&self) -> &str {
+        "Secret-Looking Defaults"
+    }
+
+    fn description(&self) -> &str {
+        "Defaults and examples must not contain credentials"
+    }
+
+    fn check(&self, tool: &Tool) -> Result<ValidationResult> {
+        let mut result = ValidationResult::new(self.code(), self.name(), &tool.name);
+
+        for (name, prop) in properties(tool) {
+            let values = prop
+                .get("default")
+                .into_iter()
+                .chain(
+                    prop.get("examples")
+                        .and_then(Value::as_array)
+                        .into_iter()
+                        .flatten(),
+                )
+                .filter_map(Value::as_str);
+            for value in values {
+                if secret_value_re().is_match(value) {
+                    result.add_issue(format!(
+                        "Parameter '{name}' has a default/example shaped like a credential"
+                    ));
+                    break;
+                }
+                if secret_param_re().is_match(name) && !value.trim().is_empty() {
+                    result.add_issue(format!(
+                        "Credential parameter '{name}' has a non-empty default/example"
+                    ));
+                    break;
+                }
+            }
+        }
+
+        if !result.issues.is_empty() {
+            result.add_suggestion(
+                "Remove the value, rotate the credential if it was real, and inject secrets server-side",
+            );
+        }
+
+        Ok(finish(result, Severity::Fail))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ToolAnnotations;
+    use serde_json::json;
+
+    fn tool(name: &str, input_schema: Value) -> Tool {
+        Tool {
+            name: name.to_string(),
+            title: None,
+            description: Some("Test tool".to_string()),
+            input_schema,
+            output_schema: None,
+            annotations: None,
+            role: None,
+            projection: None,
+        }
+    }
+
+    fn annotated(name: &str, annotations: ToolAnnotations) -> Tool {
+        Tool {
+            annotations: Some(annotations),
+            ..tool(name, json!({"type": "object"}))
+        }
+    }
+
+    #[test]
+    fn destructive_names_need_a_destructive_hint() {
+        let rule = DestructiveHintRule;
+
+        let bare = rule.check(&tool("github_delete_repo", json!({}))).unwrap();
+        assert_eq!(bare.severity, Severity::Warn);
+
+        let read_only = annotated(
+            "files_purge",
+            ToolAnnotations {
+                read_only_hint: Some(true),
+                ..ToolAnnotations::default()
+            },
+        );
+        assert!(!rule.check(&read_only).unwrap().passed);
+
+        let hinted = annotated(
+            "github_delete_repo",
+            ToolAnnotations {
+                destructive_hint: Some(true),
+                ..ToolAnnotations::default()
+            },
+        );
+        assert!(rule.check(&hinted).unwrap().passed);
+        // "dropbox" is not "drop"
+        assert!(
+            rule.check(&tool("dropbox_search", json!({})))
+                .unwrap()
+                .passed
+        );
+    }
+
+    #[test]
+    fn unconstrained_urls_warn_and_shell_strings_fail() {
+        let rule = UnconstrainedInputRule;
+
+        let url = tool(
+            "http_fetch",
+            json!({"type": "object", "properties": {"target_url": {"type": "string"}}}),
+        );
+        assert_eq!(rule.check(&url).unwrap().severity, Severity::Warn);
+
+        let shell = tool(
+            "host_run",
+            json!({"type": "object", "properties": {"command": {"type": "string"}}}),
+        );
+        assert_eq!(rule.check(&shell).unwrap().severity, Severity::Fail);
+
+        let allowlisted = tool(
+            "http_fetch",
+            json!({"type": "object", "properties": {
+                "url": {"type": "string", "pattern": "^https://api\\.example\\.com/"}
+            }}),
+        );
+        assert!(rule.check(&allowlisted).unwrap().passed);
+    }
+
+    #[test]
+    fn open_schemas_without_required_fields_are_flagged() {
+        let rule = BroadSchemaRule;
+
+        let open = tool(
+            "kv_put",
+            json!({"type": "object", "additionalProperties": true}),
+        );
+        assert!(!rule.check(&open).unwrap().passed);
+
+        let anchored = tool(
+            "kv_put",
+            json!({"type": "object", "additionalProperties": true, "required": ["key"],
+                   "properties": {"key": {"type": "string"}}}),
+        );
+        assert!(rule.check(&anchored).unwrap().passed);
+    }
+
+    #[test]
+    fn secret_looking_defaults_fail() {
+        let rule = SecretDefaultRule;
+
+        let leaked = tool(
+            "llm_complete",
+            json!({"type": "object", "properties": {
+                "model": {"type": "string", "default": "gpt-4o"},
+                "auth": {"type": "string", "examples": ["sk-proj-abcdefghijklmnopqrstuvwx"]}
+            }}),
+        );
+        let result = rule.check(&leaked).unwrap();
+        assert_eq!(result.severity, Severity::Fail);
+        assert_eq!(result.issues.len(), 1);
+
+        let named = tool(
+            "db_connect",
+            json!({"type": "object", "properties": {
+                "password": {"type": "string", "default": "hunter2"}
+            }}),
+        );
+        assert!(!rule.check(&named).unwrap().passed);
+
+        let clean = tool(
+            "db_connect",
+            json!({"type": "object", "properties": {
+                "password": {"type": "string", "default": ""},
+                "host": {"type": "string", "default": "localhost"}
+            }}),
+        );
+        assert!(rule.check(&clean).unwrap().passed);
+    }
+}
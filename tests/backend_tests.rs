@@ -27,6 +27,12 @@ fn create_test_backend(name: &str, command: &str) -> Backend {
         passthrough: false,
         runtime_profile: None,
         identity_propagation: None,
+        namespace: None,
+        tools: mcp_gateway::config::BackendToolFilter::default(),
+        response_transforms: HashMap::default(),
+        argument_transforms: HashMap::default(),
+        cost: mcp_gateway::config::BackendCostConfig::default(),
+        ..BackendConfig::default()
     };
 
     let failsafe = FailsafeConfig::default();
@@ -109,6 +109,8 @@ async fn test_discovered_server_to_backend_config() {
             port: Some(3000),
             command: None,
             working_dir: None,
+            expires_at: None,
+            probe: None,
         },
     };
 
@@ -184,6 +186,8 @@ fn make_stdio_discovered(name: &str, command: &str) -> mcp_gateway::discovery::D
             port: None,
             command: Some(command.to_string()),
             working_dir: None,
+            expires_at: None,
+            probe: None,
         },
     }
 }
@@ -207,6 +211,8 @@ fn make_http_discovered(name: &str, url: &str) -> mcp_gateway::discovery::Discov
             port: None,
             command: None,
             working_dir: None,
+            expires_at: None,
+            probe: None,
         },
     }
 }
@@ -104,6 +104,7 @@ async fn test_stdio_initialize_produces_valid_response() {
         ssrf_protection: false,
         trust_configured_backends: false,
         inflight: Arc::new(tokio::sync::Semaphore::new(10_000)),
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs: Vec::new(),
@@ -117,6 +118,7 @@ async fn test_stdio_initialize_produces_valid_response() {
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     });
 
     // Call handle_initialize directly — this is what dispatch_single calls
@@ -8,7 +8,7 @@ use std::sync::Arc;
 use mcp_gateway::backend::BackendRegistry;
 use mcp_gateway::capability::{
     AuthConfig, CacheConfig, CapabilityDefinition, CapabilityMetadata, ProvidersConfig,
-    SchemaDefinition, WebhookDefinition, WebhookTransform,
+    ResilienceConfig, SchemaDefinition, WebhookDefinition, WebhookTransform,
 };
 use mcp_gateway::config::StreamingConfig;
 use mcp_gateway::config::WebhookConfig;
@@ -46,6 +46,7 @@ async fn test_webhook_registry_registration() {
                 },
             },
             notify: true,
+            schema: None,
         },
     );
 
@@ -62,6 +63,10 @@ async fn test_webhook_registry_registration() {
         response_transform: TransformConfig::default(),
         projection: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
         webhooks,
         sha256: None,
     };
@@ -94,6 +99,7 @@ async fn test_webhook_routes_creation() {
             signature_header: None,
             transform: WebhookTransform::default(),
             notify: true,
+            schema: None,
         },
     );
 
@@ -110,6 +116,10 @@ async fn test_webhook_routes_creation() {
         response_transform: TransformConfig::default(),
         projection: None,
         visible_in_states: vec![],
+        resilience: ResilienceConfig::default(),
+        version: None,
+        deprecated: false,
+        replaced_by: None,
         webhooks,
         sha256: None,
     };
@@ -91,6 +91,7 @@ fn make_app_state(cap_dir: Option<&str>, config_path: Option<std::path::PathBuf>
         ssrf_protection: false,
         trust_configured_backends: false,
         inflight,
+        drain: Arc::default(),
         agent_auth,
         gateway_key_pair,
         capability_dirs,
@@ -104,6 +105,7 @@ fn make_app_state(cap_dir: Option<&str>, config_path: Option<std::path::PathBuf>
         )),
         export_status: None,
         transparency_log: None,
+        access_log: None,
     })
 }
 
@@ -163,6 +165,7 @@ fn make_app_state_with_reload(
             ssrf_protection: false,
             trust_configured_backends: false,
             inflight,
+            drain: Arc::default(),
             agent_auth,
             gateway_key_pair,
             capability_dirs,
@@ -176,6 +179,7 @@ fn make_app_state_with_reload(
             )),
             export_status: None,
             transparency_log: None,
+            access_log: None,
         }),
         live_config,
     )